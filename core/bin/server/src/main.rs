@@ -6,15 +6,20 @@ use structopt::StructOpt;
 
 use serde::{Deserialize, Serialize};
 
+use zksync_api::core_api_client::CoreApiClient;
 use zksync_api::fee_ticker::{run_updaters, FeeTicker, TickerInfo};
+use zksync_api::utils::token_cache::TokenCache;
 use zksync_core::{genesis_init, run_core, wait_for_tasks};
 use zksync_eth_client::EthereumGateway;
+use zksync_eth_sender::SharedGasAdjusterStatus;
 use zksync_forced_exit_requests::run_forced_exit_requests_actors;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
 use zksync_witness_generator::run_prover_server;
 
 use tokio::task::JoinHandle;
-use zksync_config::configs::api::{PrivateApiConfig, PrometheusConfig, TokenConfig};
+use zksync_config::configs::api::{
+    AdminApiConfig, PrivateApiConfig, PrometheusConfig, TokenConfig,
+};
 use zksync_config::{
     configs::api::{CommonApiConfig, JsonRpcConfig, ProverApiConfig, RestApiConfig, Web3Config},
     ChainConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
@@ -132,7 +137,11 @@ async fn main() -> anyhow::Result<()> {
     let server_mode = if opt.genesis {
         ServerCommand::Genesis
     } else {
-        _vlog_guard = Some(vlog::init());
+        let api_config = CommonApiConfig::from_env();
+        _vlog_guard = Some(vlog::init_with_otlp_exporter(
+            api_config.otlp_exporter_url(),
+            "zksync_server",
+        ));
         ServerCommand::Launch
     };
 
@@ -205,13 +214,23 @@ async fn run_server(components: &ComponentsToRun) {
         let chain_config = ChainConfig::from_env();
         let fee_ticker_config = TickerConfig::from_env();
         let eth_client_config = ETHClientConfig::from_env();
-        let ticker_info = Box::new(TickerInfo::new(read_only_connection_pool.clone()));
+        // Tokens are resolved through the same cache by the fee ticker and all the API servers.
+        let token_cache = TokenCache::new(
+            read_only_connection_pool.clone(),
+            token_config.invalidate_token_cache_period(),
+        );
+        tasks.push(token_cache.spawn_refresh());
+        let ticker_info = Box::new(TickerInfo::new(
+            read_only_connection_pool.clone(),
+            token_cache.clone(),
+        ));
 
         let ticker = FeeTicker::new_with_default_validator(
             ticker_info,
             fee_ticker_config,
             chain_config.max_blocks_to_aggregate(),
             read_only_connection_pool.clone(),
+            token_cache.clone(),
         );
 
         if components.0.contains(&Component::RpcWebSocketApi) {
@@ -222,10 +241,15 @@ async fn run_server(components: &ComponentsToRun) {
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
             ));
+            tasks.push(zksync_api::api_server::tx_forwarder::run_tx_forwarder(
+                connection_pool.clone(),
+                mempool_tx_request_sender.clone(),
+            ));
             tasks.push(zksync_api::api_server::rpc_subscriptions::start_ws_server(
                 read_only_connection_pool.clone(),
                 sign_check_sender.clone(),
                 ticker.clone(),
+                token_cache.clone(),
                 &common_config,
                 &token_config,
                 &JsonRpcConfig::from_env(),
@@ -244,13 +268,18 @@ async fn run_server(components: &ComponentsToRun) {
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes.clone(),
             ));
+            tasks.push(zksync_api::api_server::tx_forwarder::run_tx_forwarder(
+                connection_pool.clone(),
+                mempool_tx_request_sender.clone(),
+            ));
             tasks.push(zksync_api::api_server::rpc_server::start_rpc_server(
                 read_only_connection_pool.clone(),
+                connection_pool.clone(),
                 sign_check_sender.clone(),
                 ticker.clone(),
                 &JsonRpcConfig::from_env(),
                 &common_config,
-                &token_config,
+                token_cache.clone(),
                 mempool_tx_request_sender,
                 ChainId(eth_client_config.chain_id),
                 eth_watch_config.confirmations_for_eth_event,
@@ -265,23 +294,44 @@ async fn run_server(components: &ComponentsToRun) {
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes,
             ));
-            let private_config = PrivateApiConfig::from_env();
+            let core_client = CoreApiClient::from_config(&PrivateApiConfig::from_env());
+            tasks.push(zksync_api::api_server::tx_forwarder::run_tx_forwarder(
+                connection_pool.clone(),
+                mempool_tx_request_sender.clone(),
+            ));
+            let rest_api_config = RestApiConfig::from_env();
+            tasks.push(zksync_api::api_server::webhooks::run_webhook_dispatcher(
+                connection_pool.clone(),
+                ticker.clone(),
+                &rest_api_config,
+            ));
             tasks.push(zksync_api::api_server::rest::start_server_thread_detached(
                 read_only_connection_pool.clone(),
                 connection_pool.clone(),
-                RestApiConfig::from_env().bind_addr(),
+                rest_api_config.bind_addr(),
                 contracts_config.contract_addr,
                 ticker,
+                token_cache,
                 sign_check_sender,
                 ChainId(eth_client_config.chain_id),
                 mempool_tx_request_sender,
-                private_config.url,
+                core_client.clone(),
+            ));
+            tasks.push(zksync_api::api_server::admin_server::start_admin_server(
+                AdminApiConfig::from_env(),
+                connection_pool.clone(),
+                core_client,
             ));
         }
     }
 
+    // The gas adjuster status is visible to the core only if both run in this process.
+    let gas_adjuster_status = SharedGasAdjusterStatus::default();
     if components.0.contains(&Component::EthSender) {
-        tasks.push(run_eth_sender(connection_pool.clone()))
+        tasks.push(run_eth_sender(
+            connection_pool.clone(),
+            gas_adjuster_status.clone(),
+        ))
     }
 
     if components.0.contains(&Component::Core) {
@@ -293,6 +343,7 @@ async fn run_server(components: &ComponentsToRun) {
                 read_only_connection_pool.clone(),
                 &ZkSyncConfig::from_env(),
                 eth_gateway.clone(),
+                gas_adjuster_status,
             )
             .await
             .unwrap(),
@@ -377,7 +428,10 @@ pub fn run_witness_generator(connection_pool: ConnectionPool) -> JoinHandle<()>
     run_prover_server(database, prover_api_config, prover_config)
 }
 
-pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
+pub fn run_eth_sender(
+    connection_pool: ConnectionPool,
+    gas_adjuster_status: SharedGasAdjusterStatus,
+) -> JoinHandle<()> {
     vlog::info!("Starting the Ethereum sender actors");
     let eth_client_config = ETHClientConfig::from_env();
     let eth_sender_config = ETHSenderConfig::from_env();
@@ -388,7 +442,12 @@ pub fn run_eth_sender(connection_pool: ConnectionPool) -> JoinHandle<()> {
         contracts.contract_addr,
     );
 
-    zksync_eth_sender::run_eth_sender(connection_pool, eth_gateway, eth_sender_config)
+    zksync_eth_sender::run_eth_sender(
+        connection_pool,
+        eth_gateway,
+        eth_sender_config,
+        gas_adjuster_status,
+    )
 }
 
 pub fn run_price_updaters(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
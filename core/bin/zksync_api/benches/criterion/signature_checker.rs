@@ -0,0 +1,55 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+use zksync_api::signature_checker::verify_batch_correctness;
+use zksync_test_account::ZkSyncAccount;
+use zksync_types::{tx::TimeRange, AccountId, Address, SignedZkSyncTx, TokenId, ZkSyncTx};
+
+const BATCH_SIZE: usize = 50;
+
+fn generate_batch(size: usize) -> Vec<SignedZkSyncTx> {
+    let account = ZkSyncAccount::rand();
+    account.set_account_id(Some(AccountId(1)));
+    (0..size)
+        .map(|_| {
+            let (transfer, _) = account.sign_transfer(
+                TokenId(0),
+                "ETH",
+                100u64.into(),
+                10u64.into(),
+                &Address::random(),
+                None,
+                true,
+                TimeRange::new(0, u64::MAX),
+            );
+            ZkSyncTx::from(transfer).into()
+        })
+        .collect()
+}
+
+/// Compares the verification of the batch signatures using a single thread
+/// and all the available threads.
+fn bench_batch_verification(c: &mut Criterion) {
+    let batch = generate_batch(BATCH_SIZE);
+    let mut threads = vec![1, rayon::current_num_threads()];
+    threads.dedup();
+
+    for threads in threads {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        c.bench_function(
+            &format!("verify_batch_correctness_{}_threads", threads),
+            |b| {
+                b.iter_batched(
+                    || batch.clone(),
+                    |mut batch| pool.install(|| verify_batch_correctness(&mut batch).unwrap()),
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+}
+
+criterion_group!(benches, bench_batch_verification);
+criterion_main!(benches);
@@ -0,0 +1,705 @@
+//! Admin API server.
+//!
+//! This file contains endpoints used by the operators of the network to manage
+//! the list of banned addresses, to see the usage of the JSON-RPC methods, to resend the stuck
+//! Ethereum operations, to monitor the gas spend of the Ethereum sender, to pause the processing
+//! of the forced exit requests, to repair the links of the withdrawals with the Ethereum
+//! transactions that completed them, to correct the token metadata, to set the maximum age
+//! of the token prices the fees are calculated with and to check whether the state keeper would
+//! accept a transaction without submitting it.
+//! Every request must be authorized with the JWT signed by the `API_ADMIN_SECRET_AUTH` secret,
+//! the subject of the token is recorded as the author of the change.
+//!
+//! The API must not be available from outside of the cluster.
+
+// Built-in uses
+use std::thread;
+
+// External uses
+use actix_web::{dev::ServiceRequest, web, App, HttpMessage, HttpResponse, HttpServer};
+use actix_web_httpauth::{
+    extractors::{
+        bearer::{BearerAuth, Config},
+        AuthenticationError,
+    },
+    middleware::HttpAuthentication,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use futures::{channel::mpsc, StreamExt};
+use jsonwebtoken::{decode, errors::Error as JwtError, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_api_types::DEFAULT_GAS_REPORT_TXS;
+use zksync_config::configs::api::AdminApiConfig;
+use zksync_storage::{
+    banned_addresses::records::BannedAddress,
+    misc::records::RpcMethodUsage,
+    tokens::records::{TokenChangeSource, TokenMetadataChange},
+    ConnectionPool,
+};
+use zksync_types::{Address, BlockNumber, TokenId, TokenLike, ZkSyncTx, H256};
+use zksync_utils::panic_notify::ThreadPanicNotify;
+
+// Local uses
+use crate::api_server::tx_simulation::simulate_tx;
+use crate::core_api_client::CoreApiClient;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayloadAuthToken {
+    /// Subject (whom auth token refers to).
+    pub sub: String,
+    /// Expiration time (as UTC timestamp).
+    pub exp: usize,
+}
+
+#[derive(Debug, Clone)]
+struct AppState {
+    connection_pool: ConnectionPool,
+    core_client: CoreApiClient,
+    secret_auth: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BannedAddressInfo {
+    pub address: Address,
+    pub reason: String,
+    pub banned_by: String,
+    pub banned_at: DateTime<Utc>,
+}
+
+impl From<BannedAddress> for BannedAddressInfo {
+    fn from(value: BannedAddress) -> Self {
+        Self {
+            address: value.address,
+            reason: value.reason,
+            banned_by: value.banned_by,
+            banned_at: value.banned_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BannedAddressChange {
+    pub address: Address,
+    pub reason: String,
+}
+
+/// Range of days to load the JSON-RPC methods usage for, both bounds are inclusive.
+/// By default, the usage for the last 30 days is returned.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RpcMethodsUsageQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMethodUsageInfo {
+    pub method: String,
+    pub day: NaiveDate,
+    /// Hash of the callers subnet, absent if the usage is not tracked per subnet.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subnet_hash: Option<String>,
+    pub calls: i64,
+}
+
+impl From<RpcMethodUsage> for RpcMethodUsageInfo {
+    fn from(value: RpcMethodUsage) -> Self {
+        Self {
+            method: value.method,
+            day: value.day,
+            subnet_hash: Some(value.subnet_hash).filter(|hash| !hash.is_empty()),
+            calls: value.calls,
+        }
+    }
+}
+
+/// Range of blocks to repair the withdrawal links of, both bounds are inclusive.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalLinksRepairQuery {
+    pub from: BlockNumber,
+    pub to: BlockNumber,
+    /// Only report the links to restore without changing anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Execute operation linked with its final Ethereum transaction by the repair.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairedWithdrawalLink {
+    pub aggregated_op_id: i64,
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    pub eth_tx_hash: H256,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalLinksRepair {
+    pub dry_run: bool,
+    /// Amount of the execute operations linked with their final transactions,
+    /// or the ones to be linked in the dry-run mode.
+    pub repaired: usize,
+    pub links: Vec<RepairedWithdrawalLink>,
+    /// Execute operations missing the final transaction none of the recorded withdrawal events
+    /// point to, they have to be investigated manually.
+    pub unresolved: Vec<i64>,
+}
+
+/// Previous symbol of the token renamed by the operator is accepted in the lookups for this
+/// number of days, same as the default validity of the aliases of the token handler.
+const RENAMED_TOKEN_ALIAS_VALIDITY_DAYS: i64 = 90;
+
+/// New values of the token metadata, the omitted values are not changed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenMetadataUpdate {
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+/// Maximum age of the token price the fees may be calculated with, `None` restores
+/// the default of the token liquidity tier.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPriceMaxAgeUpdate {
+    pub max_age_secs: Option<u64>,
+}
+
+/// Decodes the authorization token, returns its claims if the token was signed with the given secret.
+pub(crate) fn validate_auth_token(secret: &str, token: &str) -> Result<PayloadAuthToken, JwtError> {
+    let token = decode::<PayloadAuthToken>(
+        token,
+        &DecodingKey::from_secret(secret.as_ref()),
+        &Validation::default(),
+    )?;
+
+    Ok(token.claims)
+}
+
+/// Checks the authorization token and stores its claims in the request extensions,
+/// so handlers can get the actor of the request.
+async fn validator(
+    req: ServiceRequest,
+    credentials: BearerAuth,
+) -> actix_web::Result<ServiceRequest> {
+    let config = req.app_data::<Config>().cloned().unwrap_or_default();
+    let claims = {
+        let secret_auth = &req
+            .app_data::<web::Data<AppState>>()
+            .expect("failed get AppState upon receipt of the authentication token")
+            .secret_auth;
+        validate_auth_token(secret_auth, credentials.token())
+            .map_err(|_| AuthenticationError::from(config))?
+    };
+    req.extensions_mut().insert(claims);
+
+    Ok(req)
+}
+
+/// Returns the list of banned addresses.
+#[actix_web::get("/banned_addresses")]
+async fn banned_addresses(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let addresses: Vec<BannedAddressInfo> = storage
+        .banned_addresses_schema()
+        .load_banned_addresses()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .into_iter()
+        .map(BannedAddressInfo::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(addresses))
+}
+
+/// Adds the address to the banned list.
+/// API servers are notified about the change via the database trigger,
+/// so they invalidate their caches without restart.
+#[actix_web::post("/banned_addresses/ban")]
+async fn ban_address(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+    request: web::Json<BannedAddressChange>,
+) -> actix_web::Result<HttpResponse> {
+    if request.reason.trim().is_empty() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Reason must not be empty",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let changed = storage
+        .banned_addresses_schema()
+        .ban_address(request.address, &request.reason, &claims.sub)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if changed {
+        vlog::info!(
+            "Address {:?} was banned by {}: {}",
+            request.address,
+            claims.sub,
+            request.reason
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(changed))
+}
+
+/// Removes the address from the banned list.
+#[actix_web::post("/banned_addresses/unban")]
+async fn unban_address(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+    request: web::Json<BannedAddressChange>,
+) -> actix_web::Result<HttpResponse> {
+    if request.reason.trim().is_empty() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Reason must not be empty",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let changed = storage
+        .banned_addresses_schema()
+        .unban_address(request.address, &request.reason, &claims.sub)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if changed {
+        vlog::info!(
+            "Address {:?} was unbanned by {}: {}",
+            request.address,
+            claims.sub,
+            request.reason
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(changed))
+}
+
+/// Returns the daily number of calls of the JSON-RPC methods.
+/// Counters are flushed by the API servers periodically, so the latest calls may be missing.
+#[actix_web::get("/rpc_methods_usage")]
+async fn rpc_methods_usage(
+    data: web::Data<AppState>,
+    query: web::Query<RpcMethodsUsageQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let to = query.to.unwrap_or_else(|| Utc::now().naive_utc().date());
+    let from = query.from.unwrap_or(to - Duration::days(30));
+    if from > to {
+        return Err(actix_web::error::ErrorBadRequest(
+            "`from` must not be later than `to`",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let usage: Vec<RpcMethodUsageInfo> = storage
+        .misc_schema()
+        .load_rpc_methods_usage(from, to)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .into_iter()
+        .map(RpcMethodUsageInfo::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(usage))
+}
+
+/// Pauses or resumes the processing of the forced exit requests, the processor picks up
+/// the change on its next poll of the Ethereum node.
+async fn set_forced_exit_requests_paused(
+    data: &AppState,
+    actor: &str,
+    paused: bool,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let changed = storage
+        .forced_exit_requests_schema()
+        .set_processing_paused(paused, actor)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if changed {
+        vlog::info!(
+            "Processing of the forced exit requests was {} by {}",
+            if paused { "paused" } else { "resumed" },
+            actor
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(changed))
+}
+
+/// Pauses the processing of the forced exit requests. The requests are still accepted,
+/// the ones paid during the pause are processed once the processing is resumed.
+#[actix_web::post("/forced_exit_requests/pause")]
+async fn pause_forced_exit_requests(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+) -> actix_web::Result<HttpResponse> {
+    set_forced_exit_requests_paused(&data, &claims.sub, true).await
+}
+
+/// Resumes the processing of the forced exit requests.
+#[actix_web::post("/forced_exit_requests/resume")]
+async fn resume_forced_exit_requests(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+) -> actix_web::Result<HttpResponse> {
+    set_forced_exit_requests_paused(&data, &claims.sub, false).await
+}
+
+/// Converts the error of the core private API request into the response,
+/// preserving the status code returned by the core server (e.g. for the missing operation).
+fn core_api_error(err: anyhow::Error) -> actix_web::Error {
+    let status = err
+        .downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+        .and_then(|status| actix_web::http::StatusCode::from_u16(status.as_u16()).ok());
+    match status {
+        Some(status) => actix_web::error::InternalError::new(err, status).into(),
+        None => actix_web::error::ErrorInternalServerError(err),
+    }
+}
+
+/// Returns the Ethereum transactions sent for the aggregated operation
+/// with their gas prices and confirmations.
+#[actix_web::get("/eth_operations/{aggregated_op_id}")]
+async fn eth_operation_status(
+    data: web::Data<AppState>,
+    aggregated_op_id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let status = data
+        .core_client
+        .eth_operation_status(aggregated_op_id.into_inner())
+        .await
+        .map_err(core_api_error)?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Requests the Ethereum sender to resend the transaction of the stuck aggregated operation
+/// with the increased gas price. The request is refused if the operation is already confirmed.
+#[actix_web::post("/eth_operations/{aggregated_op_id}/resend")]
+async fn resend_eth_operation(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+    aggregated_op_id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let aggregated_op_id = aggregated_op_id.into_inner();
+    let status = data
+        .core_client
+        .resend_eth_operation(aggregated_op_id)
+        .await
+        .map_err(core_api_error)?;
+    vlog::info!(
+        "Resend of the aggregated operation {} was requested by {}",
+        aggregated_op_id,
+        claims.sub
+    );
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[derive(Debug, Deserialize)]
+struct GasReportQuery {
+    limit: Option<u32>,
+}
+
+/// Returns the gas price strategy of the Ethereum sender, the gas used by its latest transactions
+/// and the fees paid within the last 24 hours compared to the configured daily budget.
+#[actix_web::get("/eth_sender/gas_report")]
+async fn eth_sender_gas_report(
+    data: web::Data<AppState>,
+    query: web::Query<GasReportQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_GAS_REPORT_TXS);
+    let report = data
+        .core_client
+        .eth_sender_gas_report(limit)
+        .await
+        .map_err(core_api_error)?;
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[derive(Debug, Deserialize)]
+struct SimulateTxRequest {
+    tx: ZkSyncTx,
+}
+
+/// Executes the signed transaction against the committed state of the accounts it affects
+/// and returns the updates it would make or the reason it would fail with.
+/// The transaction is not submitted and nothing is persisted.
+#[actix_web::post("/transactions/simulate")]
+async fn simulate_transaction(
+    data: web::Data<AppState>,
+    request: web::Json<SimulateTxRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let simulation = simulate_tx(&mut storage, request.into_inner().tx)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(simulation))
+}
+
+/// Restores the missing links of the withdrawals of the blocks in the range with the Ethereum
+/// transactions that completed them. The final transaction of the execute operation is found
+/// by the withdrawal events it emitted, so the repeated requests don't change anything.
+#[actix_web::post("/withdrawals/eth_txs/repair")]
+async fn repair_withdrawal_links(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+    query: web::Query<WithdrawalLinksRepairQuery>,
+) -> actix_web::Result<HttpResponse> {
+    if query.from > query.to {
+        return Err(actix_web::error::ErrorBadRequest(
+            "`from` must not be later than `to`",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let operations = storage
+        .ethereum_schema()
+        .restore_withdrawal_eth_txs(query.from, query.to, query.dry_run)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let mut links = Vec::new();
+    let mut unresolved = Vec::new();
+    for operation in operations {
+        match operation.withdrawal_tx_hash {
+            Some(tx_hash) => links.push(RepairedWithdrawalLink {
+                aggregated_op_id: operation.aggregated_op_id,
+                from_block: BlockNumber(operation.from_block as u32),
+                to_block: BlockNumber(operation.to_block as u32),
+                eth_tx_hash: H256::from_slice(&tx_hash),
+            }),
+            None => unresolved.push(operation.aggregated_op_id),
+        }
+    }
+    if !query.dry_run && !links.is_empty() {
+        vlog::info!(
+            "Withdrawal links of {} execute operations in blocks {}-{} were repaired by {}",
+            links.len(),
+            *query.from,
+            *query.to,
+            claims.sub
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(WithdrawalLinksRepair {
+        dry_run: query.dry_run,
+        repaired: links.len(),
+        links,
+        unresolved,
+    }))
+}
+
+/// Corrects the metadata of the token. Every change is recorded in the token changelog
+/// with the author of the request as its source.
+/// Returns whether anything was changed.
+#[actix_web::post("/tokens/{token_id}/metadata")]
+async fn update_token_metadata(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+    token_id: web::Path<u32>,
+    request: web::Json<TokenMetadataUpdate>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = TokenId(token_id.into_inner());
+    let request = request.into_inner();
+    if matches!(&request.symbol, Some(symbol) if symbol.trim().is_empty()) {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Symbol must not be empty",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mut transaction = storage
+        .start_transaction()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if let Some(symbol) = &request.symbol {
+        let token_with_symbol = transaction
+            .tokens_schema()
+            .get_token(TokenLike::Symbol(symbol.clone()))
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        if matches!(token_with_symbol, Some(other) if other.id != token_id) {
+            return Err(actix_web::error::ErrorBadRequest(
+                "Symbol is already used by another token",
+            ));
+        }
+    }
+
+    let source = TokenChangeSource::Admin(claims.sub.clone());
+    let mut changes = Vec::new();
+    if let Some(decimals) = request.decimals {
+        changes.push(TokenMetadataChange::Decimals(decimals));
+    }
+    if let Some(symbol) = request.symbol {
+        changes.push(TokenMetadataChange::Symbol {
+            symbol,
+            alias_valid_until: Utc::now() + Duration::days(RENAMED_TOKEN_ALIAS_VALIDITY_DAYS),
+        });
+    }
+    let mut changed = false;
+    for change in changes {
+        if transaction
+            .tokens_schema()
+            .update_token_metadata(token_id, change.clone(), &source)
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?
+        {
+            vlog::info!(
+                "Metadata of token {} was changed by {}: {:?}",
+                token_id,
+                claims.sub,
+                change
+            );
+            changed = true;
+        }
+    }
+    transaction
+        .commit()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(changed))
+}
+
+/// Overrides the maximum age of the token price the fees may be calculated with. The API servers
+/// load the value on every fee check, so the change takes effect without restart.
+/// Returns whether anything was changed.
+#[actix_web::post("/tokens/{token_id}/price_max_age")]
+async fn update_token_price_max_age(
+    data: web::Data<AppState>,
+    claims: web::ReqData<PayloadAuthToken>,
+    token_id: web::Path<u32>,
+    request: web::Json<TokenPriceMaxAgeUpdate>,
+) -> actix_web::Result<HttpResponse> {
+    let token_id = TokenId(token_id.into_inner());
+    if request.max_age_secs == Some(0) {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Maximum price age must be positive",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let token = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(token_id))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if token.is_none() {
+        return Err(actix_web::error::ErrorNotFound("Token not found"));
+    }
+    let changed = storage
+        .tokens_schema()
+        .set_price_max_age(token_id, request.max_age_secs, &claims.sub)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if changed {
+        vlog::info!(
+            "Maximum price age of token {} was set to {:?} seconds by {}",
+            token_id,
+            request.max_age_secs,
+            claims.sub
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(changed))
+}
+
+pub fn start_admin_server(
+    config: AdminApiConfig,
+    connection_pool: ConnectionPool,
+    core_client: CoreApiClient,
+) -> JoinHandle<()> {
+    let (panic_sender, mut panic_receiver) = mpsc::channel(1);
+
+    thread::Builder::new()
+        .name("admin-api".to_string())
+        .spawn(move || {
+            let _panic_sentinel = ThreadPanicNotify(panic_sender.clone());
+            let actix_runtime = actix_rt::System::new();
+
+            actix_runtime.block_on(async move {
+                let secret_auth = config.secret_auth.clone();
+                HttpServer::new(move || {
+                    let app_state = AppState {
+                        connection_pool: connection_pool.clone(),
+                        core_client: core_client.clone(),
+                        secret_auth: secret_auth.clone(),
+                    };
+
+                    App::new()
+                        .wrap(HttpAuthentication::bearer(validator))
+                        .wrap(actix_web::middleware::Logger::default())
+                        .app_data(web::Data::new(app_state))
+                        .service(banned_addresses)
+                        .service(ban_address)
+                        .service(unban_address)
+                        .service(rpc_methods_usage)
+                        .service(eth_operation_status)
+                        .service(resend_eth_operation)
+                        .service(eth_sender_gas_report)
+                        .service(simulate_transaction)
+                        .service(pause_forced_exit_requests)
+                        .service(resume_forced_exit_requests)
+                        .service(repair_withdrawal_links)
+                        .service(update_token_metadata)
+                        .service(update_token_price_max_age)
+                })
+                .bind(&config.bind_addr())
+                .expect("failed to bind")
+                .workers(1)
+                .run()
+                .await
+            })
+        })
+        .expect("failed to start admin API server");
+    tokio::spawn(async move {
+        panic_receiver.next().await.unwrap();
+    })
+}
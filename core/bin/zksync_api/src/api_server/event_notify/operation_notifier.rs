@@ -1,6 +1,7 @@
 use crate::api_server::rpc_server::types::{
     BlockInfo, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp,
 };
+use crate::api_server::tx_info::TxInfoShape;
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use std::time::{Duration, Instant};
 use zksync_storage::ConnectionPool;
@@ -245,26 +246,25 @@ impl OperationNotifier {
         let start = Instant::now();
         let sub_id = self.tx_subs.generate_sub_id(hash, action);
 
-        let tx_receipt = self.state.get_tx_receipt(&hash).await?;
+        let tx_info = self
+            .state
+            .get_tx_info(&hash)
+            .await?
+            .map(TransactionInfoResp::from_tx_info);
 
-        if let Some(receipt) = tx_receipt {
-            let tx_info_resp = TransactionInfoResp {
-                executed: true,
-                success: Some(receipt.success),
-                fail_reason: receipt.fail_reason,
-                block: Some(BlockInfo {
-                    block_number: receipt.block_number,
-                    committed: receipt.success,
-                    verified: receipt.verified,
-                }),
-            };
+        // Transactions that are not included into a block yet are reported once they are.
+        if let Some(tx_info_resp) = tx_info.filter(|resp| resp.executed) {
+            let verified = tx_info_resp
+                .block
+                .as_ref()
+                .map_or(false, |block| block.verified);
             match action {
                 ActionType::COMMIT => {
                     self.tx_subs.respond_once(sub_id, sub, tx_info_resp)?;
                     return Ok(());
                 }
                 ActionType::VERIFY => {
-                    if receipt.verified {
+                    if verified {
                         self.tx_subs.respond_once(sub_id, sub, tx_info_resp)?;
                         return Ok(());
                     }
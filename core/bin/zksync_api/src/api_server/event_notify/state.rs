@@ -1,8 +1,8 @@
 use crate::api_server::rpc_server::types::{BlockInfo, ResponseAccountState};
+use crate::api_server::tx_info::{self, TxInfo};
 use lru_cache::LruCache;
 use std::time::{Duration, Instant};
 use zksync_storage::chain::operations::records::StoredExecutedPriorityOperation;
-use zksync_storage::chain::operations_ext::records::TxReceiptResponse;
 use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType;
@@ -13,7 +13,7 @@ use zksync_types::{AccountId, ActionType, Address};
 pub struct NotifierState {
     pub(super) cache_of_executed_priority_operations:
         LruCache<u32, StoredExecutedPriorityOperation>,
-    pub(super) cache_of_transaction_receipts: LruCache<Vec<u8>, TxReceiptResponse>,
+    pub(super) cache_of_transaction_receipts: LruCache<Vec<u8>, TxInfo>,
     pub(super) cache_of_blocks_info: LruCache<BlockNumber, BlockInfo>,
     pub(super) tokens_cache: TokenDBCache,
     pub(super) db_pool: ConnectionPool,
@@ -34,32 +34,25 @@ impl NotifierState {
         }
     }
 
-    pub async fn get_tx_receipt(
-        &mut self,
-        hash: &TxHash,
-    ) -> Result<Option<TxReceiptResponse>, anyhow::Error> {
+    pub async fn get_tx_info(&mut self, hash: &TxHash) -> Result<Option<TxInfo>, anyhow::Error> {
         let start = Instant::now();
-        let res = if let Some(tx_receipt) = self
+        let res = if let Some(tx_info) = self
             .cache_of_transaction_receipts
             .get_mut(&hash.as_ref().to_vec())
         {
-            Some(tx_receipt.clone())
+            Some(tx_info.clone())
         } else {
             let mut storage = self.db_pool.access_storage().await?;
-            let tx_receipt = storage
-                .chain()
-                .operations_ext_schema()
-                .tx_receipt(hash.as_ref())
-                .await?;
+            let tx_info = tx_info::load_tx_info(&mut storage, *hash).await?;
 
-            if let Some(tx_receipt) = tx_receipt.clone() {
-                if tx_receipt.verified {
+            if let Some(tx_info) = tx_info.clone() {
+                if tx_info.is_final() {
                     self.cache_of_transaction_receipts
-                        .insert(hash.as_ref().to_vec(), tx_receipt);
+                        .insert(hash.as_ref().to_vec(), tx_info);
                 }
             }
 
-            tx_receipt
+            tx_info
         };
 
         metrics::histogram!("api.notifier.get_tx_receipt", start.elapsed());
@@ -9,13 +9,13 @@ use num::BigUint;
 // Workspace uses
 use zksync_api_types::v02::account::{DepositingAccountBalances, DepositingFunds, OngoingDeposit};
 use zksync_storage::StorageProcessor;
-use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{Address, H256};
 use zksync_utils::remove_prefix;
 
 // Local uses
 use super::rest::v02::error::Error;
 use crate::fee_ticker::PriceError;
+use crate::utils::token_cache::TokenCache;
 
 pub fn try_parse_hash(query: &str) -> Result<H256, hex::FromHexError> {
     const HASH_SIZE: usize = 32; // 32 bytes
@@ -30,7 +30,7 @@ pub fn try_parse_hash(query: &str) -> Result<H256, hex::FromHexError> {
 
 async fn depositing_from_pending_ops(
     storage: &mut StorageProcessor<'_>,
-    tokens: &TokenDBCache,
+    tokens: &TokenCache,
     pending_ops: Vec<OngoingDeposit>,
     confirmations_for_eth_event: u64,
 ) -> Result<DepositingAccountBalances, Error> {
@@ -68,7 +68,7 @@ async fn depositing_from_pending_ops(
 
 pub async fn get_depositing(
     storage: &mut StorageProcessor<'_>,
-    tokens: &TokenDBCache,
+    tokens: &TokenCache,
     address: Address,
     confirmations_for_eth_event: u64,
 ) -> Result<DepositingAccountBalances, Error> {
@@ -5,13 +5,18 @@
 //! `mod rpc_subscriptions` - JSON rpc via WebSocket (for request reply functions and subscriptions)
 
 mod event_notify;
+pub mod admin_server;
 pub mod forced_exit_checker;
 mod helpers;
 pub mod rest;
 pub mod rpc_server;
 pub mod rpc_subscriptions;
+pub mod tx_forwarder;
+mod tx_info;
 mod tx_sender;
+mod tx_simulation;
 pub mod web3;
+pub mod webhooks;
 
 /// Amount of threads used by each server to serve requests.
 const THREADS_PER_SERVER: usize = 128;
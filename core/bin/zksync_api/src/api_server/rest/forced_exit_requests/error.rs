@@ -86,10 +86,16 @@ pub enum SumbitErrorCode {
     IncorrectTx = 104,
     TxAdd = 105,
     InappropriateFeeToken = 106,
+    TxTypeDisabled = 107,
+    InvalidFeeQuote = 108,
+    AddressBlocked = 109,
 
     Internal = 110,
     CommunicationCoreServer = 111,
     Other = 112,
+    ServerOverloaded = 113,
+    ComplianceRejected = 114,
+    QuotaExceeded = 115,
 }
 
 impl SumbitErrorCode {
@@ -98,13 +104,31 @@ impl SumbitErrorCode {
             SubmitError::AccountCloseDisabled => Self::AccountCloseDisabled,
             SubmitError::InvalidParams(_) => Self::InvalidParams,
             SubmitError::UnsupportedFastProcessing => Self::UnsupportedFastProcessing,
-            SubmitError::IncorrectTx(_) => Self::IncorrectTx,
+            SubmitError::IncorrectTx(_)
+            | SubmitError::Create2FactoryNotAllowed(_)
+            | SubmitError::Create2AddressMismatch(_)
+            | SubmitError::ReservedRecipient(_)
+            | SubmitError::AmountNotPackable { .. }
+            | SubmitError::UnknownToken(_)
+            | SubmitError::TokenDisabled(_)
+            | SubmitError::AmountBelowMinimum { .. } => Self::IncorrectTx,
             SubmitError::TxAdd(_) => Self::TxAdd,
-            SubmitError::InappropriateFeeToken => Self::InappropriateFeeToken,
+            SubmitError::InappropriateFeeToken | SubmitError::FeeTokenNotAllowedForTxType(_) => {
+                Self::InappropriateFeeToken
+            }
+            SubmitError::TxTypeDisabled(_) | SubmitError::BatchTxTypeDisabled { .. } => {
+                Self::TxTypeDisabled
+            }
+            SubmitError::InvalidFeeQuote(_) => Self::InvalidFeeQuote,
+            SubmitError::AddressBlocked => Self::AddressBlocked,
+            SubmitError::AccountLocked(_) => Self::IncorrectTx,
+            SubmitError::ComplianceRejected(_) => Self::ComplianceRejected,
+            SubmitError::ServerOverloaded { .. } => Self::ServerOverloaded,
+            SubmitError::QuotaExceeded { .. } => Self::QuotaExceeded,
             SubmitError::MempoolCommunication(_) => Self::CommunicationCoreServer,
             SubmitError::Internal(_) => Self::Internal,
-            SubmitError::Other(_) => Self::Other,
-            SubmitError::Toggle2FA(_) => Self::Other,
+            SubmitError::Other(_) | SubmitError::BatchTxCancellation => Self::Other,
+            SubmitError::Toggle2FA(_) | SubmitError::AccountLock(_) => Self::Other,
             SubmitError::PriceError(_) => Self::Other,
         }
     }
@@ -12,7 +12,7 @@ use bigdecimal::{BigDecimal, FromPrimitive};
 use chrono::{Duration, Utc};
 use num::{bigint::ToBigInt, BigUint};
 use std::time::Instant;
-use std::{convert::TryInto, ops::Add};
+use std::{convert::TryInto, ops::Add, str::FromStr};
 // Workspace uses
 pub use zksync_api_client::rest::forced_exit_requests::{
     ForcedExitRegisterRequest, ForcedExitRequestStatus,
@@ -20,13 +20,13 @@ pub use zksync_api_client::rest::forced_exit_requests::{
 
 use zksync_api_client::rest::forced_exit_requests::ConfigInfo;
 use zksync_config::ForcedExitRequestsConfig;
-use zksync_storage::ConnectionPool;
+use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     forced_exit_requests::{
         ForcedExitEligibilityResponse, ForcedExitRequest, ForcedExitRequestId,
         SaveForcedExitRequestQuery,
     },
-    Address, TokenLike,
+    AccountId, Address, TokenLike,
 };
 
 // Local uses
@@ -71,6 +71,55 @@ impl ApiForcedExitRequestsData {
     }
 }
 
+/// Resolves the target of the forced exit, which can be specified either by the address or by
+/// the account id. If both are specified, they must refer to the same account.
+async fn resolve_target(
+    storage: &mut StorageProcessor<'_>,
+    target: Option<Address>,
+    target_account_id: Option<AccountId>,
+) -> Result<Address, ApiError> {
+    let account_id = match (target, target_account_id) {
+        (Some(target), None) => return Ok(target),
+        (_, Some(account_id)) => account_id,
+        (None, None) => {
+            return Err(ApiError::bad_request(
+                "Either target or target_account_id must be specified",
+            ))
+        }
+    };
+
+    let address = storage
+        .chain()
+        .account_schema()
+        .account_address_by_id(account_id)
+        .await
+        .map_err(warn_err)
+        .map_err(ApiError::internal)?
+        .ok_or_else(|| {
+            ApiError::bad_request(format!("Account with id {} does not exist", account_id))
+        })?;
+    match target {
+        Some(target) if target != address => Err(ApiError::bad_request(format!(
+            "Account with id {} has address {:?}, not {:?}",
+            account_id, address, target
+        ))),
+        _ => Ok(address),
+    }
+}
+
+/// Parses the account given either by its id or by its address.
+fn parse_account_id_or_address(
+    account: &str,
+) -> Result<(Option<Address>, Option<AccountId>), ApiError> {
+    if let Ok(account_id) = u32::from_str(account) {
+        return Ok((None, Some(AccountId(account_id))));
+    }
+    let address = account.strip_prefix("0x").unwrap_or(account);
+    Address::from_str(address)
+        .map(|address| (Some(address), None))
+        .map_err(|_| ApiError::bad_request("Account must be specified by its id or address"))
+}
+
 async fn get_status(
     data: web::Data<ApiForcedExitRequestsData>,
 ) -> JsonResult<ForcedExitRequestStatus> {
@@ -111,8 +160,9 @@ pub async fn submit_request(
         ));
     }
 
+    let target = resolve_target(&mut storage, params.target, params.target_account_id).await?;
     data.forced_exit_checker
-        .validate_forced_exit(&mut storage, params.target)
+        .validate_forced_exit(&mut storage, target)
         .await
         .map_err(ApiError::from)?;
 
@@ -147,7 +197,7 @@ pub async fn submit_request(
 
     let saved_fe_request = fe_schema
         .store_request(SaveForcedExitRequestQuery {
-            target: params.target,
+            target,
             tokens: params.tokens.clone(),
             price_in_wei: params.price_in_wei.clone(),
             created_at,
@@ -193,12 +243,13 @@ pub async fn get_request_by_id(
 }
 
 // Checks if the account is eligible for forced_exit in terms of
-// existing enough time
+// existing enough time. The account can be specified either by its id or address.
 pub async fn check_account_eligibility(
     data: web::Data<ApiForcedExitRequestsData>,
-    account: web::Path<Address>,
+    account: web::Path<String>,
 ) -> JsonResult<ForcedExitEligibilityResponse> {
     let start = Instant::now();
+    let (target, target_account_id) = parse_account_id_or_address(&account)?;
     let mut storage = data
         .connection_pool
         .access_storage()
@@ -206,13 +257,14 @@ pub async fn check_account_eligibility(
         .map_err(warn_err)
         .map_err(ApiError::internal)?;
 
+    let target = resolve_target(&mut storage, target, target_account_id).await?;
     let eligible = data
         .forced_exit_checker
-        .check_forced_exit(&mut storage, *account)
+        .check_forced_exit(&mut storage, target)
         .await
         .map_err(ApiError::from)?;
 
-    let result = ForcedExitEligibilityResponse { eligible };
+    let result = ForcedExitEligibilityResponse { eligible, target };
 
     metrics::histogram!("api", start.elapsed(), "type" => "v01", "endpoint_name" => "check_account_eligibility");
     Ok(Json(result))
@@ -254,7 +306,7 @@ mod tests {
     use zksync_api_client::rest::client::Client;
     use zksync_config::{ForcedExitRequestsConfig, ZkSyncConfig};
     use zksync_storage::ConnectionPool;
-    use zksync_types::{Address, TokenId};
+    use zksync_types::{AccountId, Address, TokenId};
 
     use super::*;
     use crate::api_server::{
@@ -327,7 +379,8 @@ mod tests {
         assert_eq!(status, ForcedExitRequestStatus::Disabled);
 
         let register_request = ForcedExitRegisterRequest {
-            target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
+            target: Some(Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap()),
+            target_account_id: None,
             tokens: vec![TokenId(0)],
             price_in_wei: BigUint::from_str("1212").unwrap(),
         };
@@ -399,7 +452,8 @@ mod tests {
             .mul(tokens.len());
 
         let register_request = ForcedExitRegisterRequest {
-            target: Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap(),
+            target: Some(Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap()),
+            target_account_id: None,
             tokens,
             price_in_wei,
         };
@@ -442,7 +496,8 @@ mod tests {
         let target = Address::from_str("c0f97CC918C9d6fA4E9fc6be61a6a06589D199b2").unwrap();
 
         let fe_request = ForcedExitRegisterRequest {
-            target,
+            target: Some(target),
+            target_account_id: None,
             tokens: tokens.clone(),
             price_in_wei: price_in_wei.clone(),
         };
@@ -456,6 +511,97 @@ mod tests {
         server.stop().await;
         Ok(())
     }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn test_forced_exit_requests_target_account_id() -> anyhow::Result<()> {
+        let price_per_token: i64 = 1000000000000000000;
+        let server_config = get_test_config_from_forced_exit_requests(ForcedExitRequestsConfig {
+            price_per_token,
+            ..ForcedExitRequestsConfig::from_env()
+        });
+        server_config.fill_database().await?;
+
+        let (client, server) = TestServer::from_config(server_config).await?;
+
+        // Take an existing account to refer to it by id.
+        let (account_id, address) = {
+            let mut storage = server.pool.access_storage().await?;
+            let (_, accounts) = storage
+                .chain()
+                .state_schema()
+                .load_committed_state(None)
+                .await?;
+            let (account_id, account) = accounts.iter().next().unwrap();
+            let address = storage
+                .chain()
+                .account_schema()
+                .account_address_by_id(*account_id)
+                .await?
+                .unwrap();
+            assert_eq!(address, account.address);
+            (*account_id, address)
+        };
+        let unknown_account_id = AccountId(u32::MAX - 1);
+        let request = |target: Option<Address>, target_account_id: Option<AccountId>| {
+            ForcedExitRegisterRequest {
+                target,
+                target_account_id,
+                tokens: vec![TokenId(0)],
+                price_in_wei: BigUint::from_i64(price_per_token).unwrap(),
+            }
+        };
+
+        // Valid account id is resolved to the address of the account.
+        let eligibility = client
+            .check_forced_exit_eligibility(&account_id.to_string())
+            .await?;
+        assert!(eligibility.eligible);
+        assert_eq!(eligibility.target, address);
+        let eligibility = client
+            .check_forced_exit_eligibility(&format!("{:?}", address))
+            .await?;
+        assert_eq!(eligibility.target, address);
+
+        let submit_result = client
+            .submit_forced_exit_request(request(None, Some(account_id)))
+            .await?;
+        assert_eq!(submit_result.target, address);
+        let submit_result = client
+            .submit_forced_exit_request(request(Some(address), Some(account_id)))
+            .await?;
+        assert_eq!(submit_result.target, address);
+
+        // Unknown account id is rejected.
+        client
+            .check_forced_exit_eligibility(&unknown_account_id.to_string())
+            .await
+            .expect_err("Eligibility of the unknown account id is checked");
+        let err = client
+            .submit_forced_exit_request(request(None, Some(unknown_account_id)))
+            .await
+            .expect_err("Forced exit request for the unknown account id is accepted");
+        assert!(err.to_string().contains("does not exist"));
+
+        // Address must match the account id if both are specified.
+        let err = client
+            .submit_forced_exit_request(request(Some(Address::repeat_byte(0x11)), Some(account_id)))
+            .await
+            .expect_err("Forced exit request with the mismatched target is accepted");
+        assert!(err.to_string().contains("has address"));
+
+        // The target must be specified in some way.
+        client
+            .submit_forced_exit_request(request(None, None))
+            .await
+            .expect_err("Forced exit request without the target is accepted");
+
+        server.stop().await;
+        Ok(())
+    }
 }
 
 fn warn_err<T: std::fmt::Display>(err: T) -> T {
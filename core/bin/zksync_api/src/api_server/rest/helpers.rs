@@ -128,6 +128,7 @@ pub fn priority_op_to_tx_history(
         verified: false,
         created_at: current_time,
         batch_id: None,
+        block_index: None,
     }
 }
 
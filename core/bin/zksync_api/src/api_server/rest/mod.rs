@@ -2,17 +2,29 @@ use actix_cors::Cors;
 use actix_web::{web, App, HttpResponse, HttpServer};
 use futures::channel::mpsc;
 use std::net::SocketAddr;
-use zksync_storage::ConnectionPool;
+use zksync_storage::{ConnectionPool, ReplicatedPool};
 use zksync_types::{ChainId, SequentialTxId, H160};
 
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 use self::v01::api_decl::ApiV01;
+use self::v02::{
+    account_snapshots::AccountSnapshotWriter, archive::TxArchive, blocks_export::BlocksExporter,
+    client_ip::ClientIpResolver, state_root_check::StateRootChecker,
+    token_activity::TokenActivityRollup, token_holders::TokenHoldersRefresher,
+    watchdog::RequestWatchdog,
+};
+use self::warm_up::{ApiReadiness, ApiWarmUp};
 use crate::signature_checker::VerifySignatureRequest;
+use crate::utils::{
+    banned_addresses::BannedAddresses, disabled_tx_types::DisabledTxTypes,
+    pool_supervisor::PoolSupervisor, token_cache::TokenCache,
+};
 
 use super::tx_sender::TxSender;
 
 use crate::api_server::rest::network_status::SharedNetworkStatus;
+use crate::core_api_client::CoreApiClient;
 use crate::fee_ticker::FeeTicker;
 use tokio::task::JoinHandle;
 use zksync_config::ZkSyncConfig;
@@ -23,16 +35,96 @@ mod helpers;
 pub mod network_status;
 mod v01;
 pub mod v02;
+pub mod warm_up;
 
+#[allow(clippy::too_many_arguments)]
 async fn start_server(
     api_v01: ApiV01,
     fee_ticker: FeeTicker,
+    tokens: TokenCache,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     bind_to: SocketAddr,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     chain_id: ChainId,
 ) {
-    HttpServer::new(move || {
+    // Cache is shared between workers, so only one listener connection is required.
+    let disabled_tx_types = DisabledTxTypes::new(api_v01.connection_pool.clone());
+    disabled_tx_types.spawn_invalidation_listener();
+    let banned_addresses = BannedAddresses::new(api_v01.connection_pool.clone());
+    banned_addresses.spawn_invalidation_listener();
+
+    // Caches of the transaction sender are shared between workers, so they can be warmed up at once.
+    let tx_sender = TxSender::new(
+        api_v01.connection_pool.clone(),
+        sign_verifier,
+        fee_ticker,
+        &api_v01.config.api.common,
+        tokens,
+        mempool_tx_sender,
+        disabled_tx_types,
+        banned_addresses,
+        chain_id,
+    );
+    tx_sender.account_locks.spawn_invalidation_listener();
+    // Read-only queries of REST API v0.2 are served by the replicas, while writes and
+    // consistency-critical reads go to the primary database.
+    let rest_config = &api_v01.config.api.rest;
+    let configured_replicas: Vec<_> = rest_config
+        .replica_urls()
+        .into_iter()
+        .map(|url| ConnectionPool::new_with_url(url, None))
+        .collect();
+    let replicas = if configured_replicas.is_empty() {
+        vec![api_v01.connection_pool.clone()]
+    } else {
+        configured_replicas.clone()
+    };
+    let pool = ReplicatedPool::new(
+        api_v01.main_database_connection_pool.clone(),
+        replicas,
+        rest_config.replica_max_lag(),
+    );
+    // Old executed transactions are looked up in the archive, if it's configured.
+    let archive = TxArchive::from_config(rest_config);
+    // Exports and state root checks are limited across all the workers.
+    let blocks_exporter =
+        BlocksExporter::new(rest_config, api_v01.config.api.admin.secret_auth.clone());
+    let state_root_checker =
+        StateRootChecker::new(rest_config, api_v01.config.api.admin.secret_auth.clone());
+    // Requests of all the workers are watched at once, so a wedged worker is still reported.
+    let request_watchdog = RequestWatchdog::new(rest_config);
+    let client_ip = ClientIpResolver::from_config(rest_config)
+        .expect("Invalid trusted proxies in the REST API config");
+    let token_holders_refresher =
+        TokenHoldersRefresher::new(api_v01.main_database_connection_pool.clone(), rest_config);
+    let token_activity_rollup =
+        TokenActivityRollup::new(api_v01.main_database_connection_pool.clone(), rest_config);
+    let account_snapshot_writer =
+        AccountSnapshotWriter::new(api_v01.main_database_connection_pool.clone(), rest_config);
+    // Pools are shared with `TxSender` and the API scopes, so the recreated pools are used at once.
+    let pool_supervisor = configured_replicas.into_iter().enumerate().fold(
+        PoolSupervisor::new(&api_v01.config.api.common)
+            .with_pool("main", api_v01.main_database_connection_pool.clone())
+            .with_pool("read_only", api_v01.connection_pool.clone()),
+        |supervisor, (idx, replica)| supervisor.with_pool(format!("replica_{}", idx), replica),
+    );
+
+    // Pools are moved into the server factory, so the background tasks are prepared beforehand.
+    let replicas_health_checks = pool
+        .clone()
+        .run_health_checks(rest_config.replica_health_check_interval());
+    let pools_supervision = pool_supervisor.clone().run();
+
+    let readiness = ApiReadiness::new();
+    let warm_up = ApiWarmUp::new(
+        api_v01.connection_pool.clone(),
+        tx_sender.tokens.clone(),
+        tx_sender.blocks.clone(),
+        api_v01.network_status.core_client().clone(),
+        readiness.clone(),
+    );
+
+    let server = HttpServer::new(move || {
         let api_v01 = api_v01.clone();
         // This api stores forced exit requests, it's necessary to use main database connection
         let forced_exit_requests_api_scope = forced_exit_requests::api_scope(
@@ -46,18 +138,19 @@ async fn start_server(
             api_v01.config.contracts.forced_exit_addr,
         );
 
-        let api_v02_scope = {
-            let tx_sender = TxSender::new(
-                api_v01.connection_pool.clone(),
-                sign_verifier.clone(),
-                fee_ticker.clone(),
-                &api_v01.config.api.common,
-                &api_v01.config.api.token_config,
-                mempool_tx_sender.clone(),
-                chain_id,
-            );
-            v02::api_scope(tx_sender, &api_v01.config, api_v01.network_status.clone())
-        };
+        let api_v02_scope = v02::api_scope(
+            pool.clone(),
+            archive.clone(),
+            tx_sender.clone(),
+            &api_v01.config,
+            api_v01.network_status.clone(),
+            readiness.clone(),
+            blocks_exporter.clone(),
+            state_root_checker.clone(),
+            pool_supervisor.clone(),
+            request_watchdog.clone(),
+            client_ip.clone(),
+        );
         App::new()
             .wrap(
                 Cors::default()
@@ -82,9 +175,18 @@ async fn start_server(
     .shutdown_timeout(60)
     .keep_alive(10)
     .client_timeout(60000)
-    .run()
-    .await
-    .expect("REST API server has crashed");
+    .run();
+
+    // The server is already bound at this point, it reports readiness once the warm-up is completed.
+    actix_rt::spawn(warm_up.run());
+    actix_rt::spawn(replicas_health_checks);
+    actix_rt::spawn(token_holders_refresher.run());
+    actix_rt::spawn(token_activity_rollup.run());
+    actix_rt::spawn(account_snapshot_writer.run());
+    actix_rt::spawn(pools_supervision);
+    actix_rt::spawn(request_watchdog.run());
+
+    server.await.expect("REST API server has crashed");
 }
 
 /// Start HTTP REST API
@@ -96,10 +198,11 @@ pub fn start_server_thread_detached(
     listen_addr: SocketAddr,
     contract_address: H160,
     fee_ticker: FeeTicker,
+    tokens: TokenCache,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     chain_id: ChainId,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
-    core_address: String,
+    core_client: CoreApiClient,
 ) -> JoinHandle<()> {
     let (handler, panic_sender) = spawn_panic_handler();
 
@@ -111,7 +214,7 @@ pub fn start_server_thread_detached(
                 // TODO remove this config ZKS-815
                 let config = ZkSyncConfig::from_env();
 
-                let mut network_status = SharedNetworkStatus::new(core_address);
+                let mut network_status = SharedNetworkStatus::with_core_client(core_client);
                 // We want to update the network status, as soon as possible, otherwise we can catch the situation,
                 // when the node is started and receiving the request, but the status is still `null` and
                 // monitoring tools spawn the notification that our node is down, though it's just a default status
@@ -137,6 +240,7 @@ pub fn start_server_thread_detached(
                 start_server(
                     api_v01,
                     fee_ticker,
+                    tokens,
                     sign_verifier,
                     listen_addr,
                     mempool_tx_sender.clone(),
@@ -4,11 +4,13 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::{runtime::Runtime, time};
-use zksync_api_types::CoreStatus;
+use zksync_api_types::{CoreStatus, EthWatchStatus};
 use zksync_storage::ConnectionPool;
 use zksync_types::{BlockNumber, SequentialTxId};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+use crate::core_api_client::CoreApiClient;
+
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
 pub struct NetworkStatus {
     pub next_block_at_max: Option<u64>,
@@ -18,39 +20,33 @@ pub struct NetworkStatus {
     pub outstanding_txs: u32,
     pub mempool_size: u32,
     pub core_status: Option<CoreStatus>,
+    pub eth_watch_status: Option<EthWatchStatus>,
 }
 
 #[derive(Debug, Clone)]
 pub struct SharedNetworkStatus {
     status: Arc<RwLock<NetworkStatus>>,
-    core_status_address: String,
-    core_client: reqwest::Client,
+    core_client: CoreApiClient,
 }
 
 impl SharedNetworkStatus {
     pub fn new(core_address: String) -> Self {
-        let core_client = reqwest::Client::new();
-        let core_status_address = format!("{}/status", core_address);
+        Self::with_core_client(CoreApiClient::new(core_address))
+    }
+
+    pub fn with_core_client(core_client: CoreApiClient) -> Self {
         Self {
             status: Default::default(),
-            core_status_address,
             core_client,
         }
     }
 
-    pub async fn read(&self) -> NetworkStatus {
-        (*self.status.as_ref().read().await).clone()
+    pub fn core_client(&self) -> &CoreApiClient {
+        &self.core_client
     }
 
-    /// Get healthcheck status from core server.
-    async fn get_core_status(&self) -> anyhow::Result<CoreStatus> {
-        Ok(self
-            .core_client
-            .get(&self.core_status_address)
-            .send()
-            .await?
-            .json()
-            .await?)
+    pub async fn read(&self) -> NetworkStatus {
+        (*self.status.as_ref().read().await).clone()
     }
 
     /// Updates shared network status. We use last_tx_id as a checkpoint
@@ -103,7 +99,8 @@ impl SharedNetworkStatus {
 
         transaction.commit().await.unwrap_or_default();
 
-        let core_status = self.get_core_status().await.ok();
+        let core_status = self.core_client.get_status().await.ok();
+        let eth_watch_status = self.core_client.eth_watch_status().await.ok();
         let status = NetworkStatus {
             next_block_at_max: None,
             last_committed,
@@ -112,6 +109,7 @@ impl SharedNetworkStatus {
             outstanding_txs,
             mempool_size,
             core_status,
+            eth_watch_status,
         };
 
         // save status to state
@@ -10,12 +10,14 @@ use zksync_config::ZkSyncConfig;
 use zksync_storage::{
     chain::{
         block::records::StorageBlockDetails,
-        operations_ext::records::{PriorityOpReceiptResponse, TxReceiptResponse},
+        operations_ext::records::{
+            PriorityOpReceiptResponse, TransactionsHistoryItem, TxHistoryCursor, TxReceiptResponse,
+        },
     },
-    ConnectionPool, StorageProcessor,
+    ConnectionPool, QueryResult, StorageProcessor,
 };
 use zksync_types::{
-    block::ExecutedOperations, BlockNumber, PriorityOp, SequentialTxId, H160, H256,
+    block::ExecutedOperations, Address, BlockNumber, PriorityOp, SequentialTxId, H160, H256,
 };
 
 /// `ApiV01` structure contains the implementation of `/api/v0.1` endpoints set.
@@ -157,6 +159,35 @@ impl ApiV01 {
         Ok(tx_receipt)
     }
 
+    /// Loads the page of the account transactions history along with the cursor of the next page.
+    ///
+    /// The first page and the pages following the cursor are loaded using the keyset pagination,
+    /// which is fast regardless of the page depth. The rest of the pages are loaded using
+    /// the offset, their next page has no cursor.
+    pub async fn get_account_transactions_history(
+        &self,
+        address: Address,
+        offset: u64,
+        limit: u64,
+        cursor: Option<TxHistoryCursor>,
+    ) -> QueryResult<(Vec<TransactionsHistoryItem>, Option<TxHistoryCursor>)> {
+        let mut storage = self.connection_pool.access_storage().await?;
+
+        if cursor.is_none() && offset > 0 {
+            let tx_history = storage
+                .chain()
+                .operations_ext_schema()
+                .get_account_transactions_history(&address, offset, limit)
+                .await?;
+            return Ok((tx_history, None));
+        }
+        storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history_page(&address, cursor, limit)
+            .await
+    }
+
     pub async fn get_priority_op_receipt(
         &self,
         id: u32,
@@ -287,5 +318,6 @@ impl ApiV01 {
             .mempool_schema()
             .get_pending_operation_by_hash(eth_tx_hash)
             .await
+            .map(|op| op.map(|pending| pending.op))
     }
 }
@@ -89,9 +89,13 @@ impl ApiV01 {
         ok_json!(tokens)
     }
 
+    /// Loads the page of the account transactions history. The page is followed by the one
+    /// of the token returned in the `X-Next-Page-Token` header, if it's provided, so the new
+    /// transactions don't shift the pages the client iterates over.
     pub async fn tx_history(
         self_: web::Data<Self>,
         path: web::Path<(Address, u64, u64)>,
+        web::Query(query): web::Query<TxHistoryPageQuery>,
     ) -> ActixResult<HttpResponse> {
         let (address, mut offset, mut limit) = path.into_inner();
         let start = Instant::now();
@@ -99,6 +103,13 @@ impl ApiV01 {
         if limit > MAX_LIMIT {
             return Ok(HttpResponse::BadRequest().finish());
         }
+        let cursor = match &query.page_token {
+            Some(token) => match decode_page_token(token) {
+                Some(cursor) => Some(cursor),
+                None => return Ok(HttpResponse::BadRequest().finish()),
+            },
+            None => None,
+        };
 
         let tokens = self_
             .access_storage()
@@ -163,12 +174,8 @@ impl ApiV01 {
             offset = offset.saturating_sub(num_account_ongoing_deposits);
         }
 
-        let mut transactions_history = self_
-            .access_storage()
-            .await?
-            .chain()
-            .operations_ext_schema()
-            .get_account_transactions_history(&address, offset, limit)
+        let (mut transactions_history, next_cursor) = self_
+            .get_account_transactions_history(address, offset, limit, cursor)
             .await
             .map_err(|err| {
                 vlog::warn!(
@@ -186,7 +193,11 @@ impl ApiV01 {
         transactions_history.append(&mut ongoing_transactions_history);
 
         metrics::histogram!("api", start.elapsed(), "type" => "v01", "endpoint_name" => "tx_history");
-        ok_json!(transactions_history)
+        let mut response = HttpResponse::Ok();
+        if let Some(cursor) = next_cursor {
+            response.insert_header((NEXT_PAGE_TOKEN_HEADER, encode_page_token(cursor)));
+        }
+        Ok(response.json(transactions_history))
     }
 
     pub async fn tx_history_older_than(
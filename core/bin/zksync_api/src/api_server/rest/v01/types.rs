@@ -1,8 +1,13 @@
 //! Requests and responses used by the REST API.
 
 use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+use zksync_storage::chain::operations_ext::records::TxHistoryCursor;
 use zksync_types::{Account, AccountId};
 
+/// Response header with the token of the next page of the account transactions history.
+pub const NEXT_PAGE_TOKEN_HEADER: &str = "X-Next-Page-Token";
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestnetConfigResponse {
@@ -29,6 +34,25 @@ pub struct TxHistoryQuery {
     pub limit: Option<u64>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TxHistoryPageQuery {
+    /// Token from the `X-Next-Page-Token` header of the previous page.
+    pub page_token: Option<String>,
+}
+
+/// Encodes the cursor of the account transactions history page into the opaque token.
+pub fn encode_page_token(cursor: TxHistoryCursor) -> String {
+    hex::encode(cursor.sequence_number.to_be_bytes())
+}
+
+/// Decodes the page token, returns `None` if the token is malformed.
+pub fn decode_page_token(token: &str) -> Option<TxHistoryCursor> {
+    let bytes: [u8; 8] = hex::decode(token).ok()?.try_into().ok()?;
+    Some(TxHistoryCursor {
+        sequence_number: i64::from_be_bytes(bytes),
+    })
+}
+
 #[derive(Deserialize)]
 pub struct HandleBlocksQuery {
     pub max_block: Option<u32>,
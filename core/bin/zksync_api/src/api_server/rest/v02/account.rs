@@ -1,48 +1,251 @@
 //! Account part of API implementation.
 
 // Built-in uses
-use std::collections::BTreeMap;
-use std::str::FromStr;
-use std::time::Instant;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
 
 // External uses
 use actix_web::{web, Scope};
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use num::{rational::Ratio, BigUint};
+use serde_json::Value;
 
 // Workspace uses
 use zksync_api_types::v02::{
-    account::{Account, AccountAddressOrId, AccountState, IncomingAccountTxsQuery},
+    account::{
+        Account, AccountActivity, AccountActivityQuery, AccountAddressOrId, AccountByPubKeyHash,
+        AccountChecksum, AccountChecksumQuery, AccountExitPlan, AccountNonce, AccountState,
+        AccountTxsCheckpoint, AccountTxsDelta, AccountTxsDeltaQuery, BalancesQuery, EthAccountType,
+        IncomingAccountTxsQuery, PendingBalance, PendingBalances, RejectedTransaction,
+        RejectedTxsQuery, RejectionCode, RejectionSource,
+    },
     pagination::{
         parse_query, AccountTxsRequest, ApiEither, Paginated, PaginationQuery, PendingOpsRequest,
+        MAX_LIMIT,
     },
-    transaction::{Transaction, TxHashSerializeWrapper},
+    transaction::{Transaction, TxHashSerializeWrapper, TxInBlockStatus},
+    AmountsQuery,
 };
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
-use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenLike};
+use zksync_storage::{AccessIntent, ReplicatedPool, StorageProcessor};
+use zksync_types::{
+    tx::{preconditions::required_balances, TxHash},
+    AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, SerialId, Token, TokenId,
+    TokenLike, TokenPrice, ZkSyncPriorityOp, ZkSyncTx,
+};
+use zksync_utils::{big_decimal_to_ratio, BigUintSerdeWrapper};
 
 // Local uses
 use super::{
+    amounts::render_amounts,
+    checksum::account_checksum,
     error::{Error, InvalidDataError},
+    exit_plan::load_exit_plan,
     paginate_trait::Paginate,
-    response::ApiResult,
+    path_params::{PathParam, ValidPath},
+    response::{ApiResult, OrNotFound},
+};
+use crate::{
+    api_server::helpers::get_depositing,
+    api_try,
+    fee_ticker::{FeeTicker, PriceError},
+    utils::{
+        account_locks::{lock_info, AccountLocks},
+        special_accounts::{is_reserved_address, SpecialAccounts},
+        token_cache::TokenCache,
+    },
 };
-use crate::{api_server::helpers::get_depositing, api_try, fee_ticker::PriceError};
+
+/// Estimates the balances of the account after the execution of its pending operations.
+///
+/// Incoming funds are the pending transfers to the account and the pending deposits,
+/// outgoing funds are the amounts and fees of the transactions initiated by the account.
+/// A pending `ForcedExit` of the account withdraws its whole committed balance of the token.
+/// NFTs are not taken into account.
+fn estimate_pending_balances(
+    address: Address,
+    account: Option<&zksync_types::Account>,
+    mempool_txs: &[ZkSyncTx],
+    deposits: &[PriorityOp],
+) -> BTreeMap<TokenId, PendingBalance> {
+    let mut balances: BTreeMap<TokenId, PendingBalance> = BTreeMap::new();
+    let committed_balance = |token: TokenId| {
+        account
+            .map(|account| account.get_balance(token))
+            .unwrap_or_default()
+    };
+
+    if let Some(account) = account {
+        for (token, balance) in account.get_nonzero_balances() {
+            if token.0 < MIN_NFT_TOKEN_ID {
+                balances.entry(token).or_default().committed = balance.0;
+            }
+        }
+    }
+
+    for tx in mempool_txs {
+        match tx {
+            ZkSyncTx::Transfer(transfer) if transfer.to == address => {
+                if transfer.token.0 < MIN_NFT_TOKEN_ID {
+                    balances
+                        .entry(transfer.token)
+                        .or_default()
+                        .incoming_transfers += &transfer.amount;
+                }
+            }
+            ZkSyncTx::ForcedExit(forced_exit) if forced_exit.target == address => {
+                // The fee is paid by the initiator, the target loses the whole balance.
+                balances.entry(forced_exit.token).or_default().outgoing +=
+                    committed_balance(forced_exit.token);
+                continue;
+            }
+            _ => {}
+        }
+        if tx.account() != address {
+            continue;
+        }
+        for (token, amount) in required_balances(tx) {
+            if token.0 < MIN_NFT_TOKEN_ID {
+                balances.entry(token).or_default().outgoing += amount;
+            }
+        }
+    }
+
+    for op in deposits {
+        if let ZkSyncPriorityOp::Deposit(deposit) = &op.data {
+            if deposit.to == address && deposit.token.0 < MIN_NFT_TOKEN_ID {
+                balances.entry(deposit.token).or_default().incoming_deposits += &deposit.amount;
+            }
+        }
+    }
+
+    for balance in balances.values_mut() {
+        let incoming =
+            &balance.committed + &balance.incoming_transfers + &balance.incoming_deposits;
+        balance.estimated = if incoming > balance.outgoing {
+            incoming - &balance.outgoing
+        } else {
+            Default::default()
+        };
+    }
+    balances
+}
+
+/// Suggests the nonce of the next transaction of the account: the committed nonce is
+/// advanced over the pending transactions as long as their nonces follow it without gaps.
+/// Pending transactions after a gap can't be executed until it's filled.
+fn suggest_nonce(committed_nonce: Nonce, pending_nonces: &[Nonce]) -> Nonce {
+    let pending_nonces: BTreeSet<Nonce> = pending_nonces.iter().copied().collect();
+    let mut nonce = committed_nonce;
+    while pending_nonces.contains(&nonce) {
+        nonce = Nonce(*nonce + 1);
+    }
+    nonce
+}
+
+/// Parsed `BalancesQuery`.
+#[derive(Debug, Default)]
+struct BalancesFilter {
+    min_balance_usd: Option<Ratio<BigUint>>,
+    tokens: Option<Vec<TokenLike>>,
+}
+
+impl BalancesFilter {
+    fn parse(query: BalancesQuery) -> Result<Self, Error> {
+        let min_balance_usd = match query.min_balance_usd {
+            Some(value) => {
+                let value = BigDecimal::try_from(value)
+                    .ok()
+                    .and_then(|value| big_decimal_to_ratio(&value).ok())
+                    .ok_or_else(|| Error::from(InvalidDataError::InvalidMinBalanceUsd))?;
+                Some(value)
+            }
+            None => None,
+        };
+        let tokens = query.tokens.map(|tokens| {
+            tokens
+                .split(',')
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .map(TokenLike::parse)
+                .collect()
+        });
+
+        Ok(Self {
+            min_balance_usd,
+            tokens,
+        })
+    }
+}
+
+/// Removes the balances worth less than `min_balance_usd` and returns the symbols of the rest
+/// sorted by their USD value descending, ties are ordered by the symbol. Balances of the tokens
+/// without a known price can't be valued, so they are kept and listed last.
+fn filter_dust_balances(
+    balances: &mut BTreeMap<String, BigUintSerdeWrapper>,
+    tokens: &[Token],
+    prices: &HashMap<TokenId, TokenPrice>,
+    min_balance_usd: &Ratio<BigUint>,
+) -> Vec<String> {
+    let mut valued = Vec::with_capacity(tokens.len());
+    for token in tokens {
+        let balance = match balances.get(&token.symbol) {
+            Some(balance) => balance.0.clone(),
+            None => continue,
+        };
+        let value = prices.get(&token.id).map(|price| {
+            let unit = BigUint::from(10u32).pow(u32::from(token.decimals));
+            Ratio::new(balance, unit) * &price.usd_price
+        });
+        match &value {
+            Some(value) if value < min_balance_usd => {
+                balances.remove(&token.symbol);
+            }
+            _ => valued.push((value, token.symbol.clone())),
+        }
+    }
+
+    // `None` is less than any value, so the balances without a price go last.
+    valued.sort_by(|(lhs_value, lhs_symbol), (rhs_value, rhs_symbol)| {
+        rhs_value
+            .cmp(lhs_value)
+            .then_with(|| lhs_symbol.cmp(rhs_symbol))
+    });
+    valued.into_iter().map(|(_, symbol)| symbol).collect()
+}
 
 /// Shared data between `api/v02/accounts` endpoints.
 #[derive(Clone)]
 struct ApiAccountData {
-    pool: ConnectionPool,
-    tokens: TokenDBCache,
+    pool: ReplicatedPool,
+    tokens: TokenCache,
+    ticker: FeeTicker,
     confirmations_for_eth_event: u64,
+    rejected_txs_retention_period: Duration,
+    special_accounts: SpecialAccounts,
+    account_locks: AccountLocks,
 }
 
 impl ApiAccountData {
-    fn new(pool: ConnectionPool, tokens: TokenDBCache, confirmations_for_eth_event: u64) -> Self {
+    fn new(
+        pool: ReplicatedPool,
+        tokens: TokenCache,
+        ticker: FeeTicker,
+        confirmations_for_eth_event: u64,
+        rejected_txs_retention_period: Duration,
+        special_accounts: SpecialAccounts,
+        account_locks: AccountLocks,
+    ) -> Self {
         Self {
             pool,
             tokens,
+            ticker,
             confirmations_for_eth_event,
+            rejected_txs_retention_period,
+            special_accounts,
+            account_locks,
         }
     }
 
@@ -53,7 +256,11 @@ impl ApiAccountData {
         match account_address_or_id {
             AccountAddressOrId::Id(account_id) => Ok(Some(account_id)),
             AccountAddressOrId::Address(address) => {
-                let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+                let mut storage = self
+                    .pool
+                    .access_storage(AccessIntent::Read)
+                    .await
+                    .map_err(Error::storage)?;
                 let account_id = storage
                     .chain()
                     .account_schema()
@@ -71,14 +278,22 @@ impl ApiAccountData {
     ) -> Result<Address, Error> {
         match account_address_or_id {
             AccountAddressOrId::Id(account_id) => {
-                let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+                let mut storage = self
+                    .pool
+                    .access_storage(AccessIntent::Read)
+                    .await
+                    .map_err(Error::storage)?;
                 let address = storage
                     .chain()
                     .account_schema()
                     .account_address_by_id(account_id)
                     .await
-                    .map_err(Error::storage)?;
-                address.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))
+                    .map_err(Error::storage)?
+                    .ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))?;
+                if is_reserved_address(address) {
+                    return Err(Error::from(InvalidDataError::NotUserAccount));
+                }
+                Ok(address)
             }
             AccountAddressOrId::Address(address) => Ok(address),
         }
@@ -88,20 +303,18 @@ impl ApiAccountData {
         &self,
         account_address_or_id: &str,
     ) -> Result<AccountAddressOrId, Error> {
-        if let Ok(account_id) = u32::from_str(account_address_or_id) {
-            Ok(AccountAddressOrId::Id(AccountId(account_id)))
-        } else {
-            let address_str = if let Some(address_str) = account_address_or_id.strip_prefix("0x") {
-                address_str
-            } else {
-                account_address_or_id
-            };
+        let address_or_id = AccountAddressOrId::parse_path_param(account_address_or_id.trim())
+            .ok_or_else(|| Error::from(InvalidDataError::InvalidAccountIdOrAddress))?;
+        self.user_account(address_or_id)
+    }
 
-            if let Ok(address) = Address::from_str(address_str) {
-                Ok(AccountAddressOrId::Address(address))
-            } else {
-                Err(Error::from(InvalidDataError::InvalidAccountIdOrAddress))
+    /// Reserved addresses can't own an account, so there is nothing to show for them.
+    fn user_account(&self, address_or_id: AccountAddressOrId) -> Result<AccountAddressOrId, Error> {
+        match address_or_id {
+            AccountAddressOrId::Address(address) if is_reserved_address(address) => {
+                Err(Error::from(InvalidDataError::NotUserAccount))
             }
+            address_or_id => Ok(address_or_id),
         }
     }
 
@@ -110,9 +323,30 @@ impl ApiAccountData {
         account: zksync_types::Account,
         account_id: AccountId,
         last_update_in_block: BlockNumber,
+        balances_filter: &BalancesFilter,
         storage: &mut StorageProcessor<'_>,
     ) -> Result<Account, Error> {
+        let requested_tokens = match &balances_filter.tokens {
+            Some(token_likes) => {
+                let mut requested_tokens = HashSet::new();
+                for token_like in token_likes {
+                    // Unknown tokens have no balances, so they are ignored.
+                    if let Some(token) = self
+                        .tokens
+                        .get_token(storage, token_like.clone())
+                        .await
+                        .map_err(Error::storage)?
+                    {
+                        requested_tokens.insert(token.id);
+                    }
+                }
+                Some(requested_tokens)
+            }
+            None => None,
+        };
+
         let mut balances = BTreeMap::new();
+        let mut held_tokens = Vec::new();
         let mut nfts = BTreeMap::new();
         for (token_id, balance) in account.get_nonzero_balances() {
             match token_id.0 {
@@ -133,13 +367,39 @@ impl ApiAccountData {
                     );
                 }
                 _ => {
-                    let token_symbol = self
+                    if let Some(requested_tokens) = &requested_tokens {
+                        if !requested_tokens.contains(&token_id) {
+                            continue;
+                        }
+                    }
+                    let token = self
                         .tokens
-                        .token_symbol(storage, token_id)
+                        .get_token(storage, token_id)
                         .await
                         .map_err(Error::storage)?
                         .ok_or_else(|| Error::from(PriceError::token_not_found(token_id)))?;
-                    balances.insert(token_symbol, balance);
+                    balances.insert(token.symbol.clone(), balance);
+                    held_tokens.push(token);
+                }
+            }
+        }
+
+        let mut balances_order = None;
+        let mut balances_not_valued = false;
+        if let Some(min_balance_usd) = &balances_filter.min_balance_usd {
+            // Balances are still returned if the ticker is degraded, just not valued.
+            match self.ticker.get_token_prices(&held_tokens).await {
+                Ok(prices) => {
+                    balances_order = Some(filter_dust_balances(
+                        &mut balances,
+                        &held_tokens,
+                        &prices,
+                        min_balance_usd,
+                    ));
+                }
+                Err(err) => {
+                    vlog::warn!("Failed to value the balances of the account: {}", err);
+                    balances_not_valued = true;
                 }
             }
         }
@@ -149,13 +409,23 @@ impl ApiAccountData {
             .map(|(id, nft)| (*id, nft.clone().into()))
             .collect();
 
-        let account_type = storage
+        let stored_type = storage
             .chain()
             .account_schema()
             .account_type_by_id(account_id)
             .await
             .map_err(Error::storage)?
             .map(|t| t.into());
+        let account_type = EthAccountType::for_account(stored_type, &account.pub_key_hash);
+        let account_kind = self
+            .special_accounts
+            .kind(Some(account_id), account.address);
+        let lock = self
+            .account_locks
+            .get(account_id)
+            .await
+            .map_err(Error::storage)?
+            .map(lock_info);
         Ok(Account {
             account_id,
             address: account.address,
@@ -163,18 +433,31 @@ impl ApiAccountData {
             pub_key_hash: account.pub_key_hash,
             last_update_in_block,
             balances,
-            account_type,
+            account_type: Some(account_type),
+            account_kind,
             nfts,
             minted_nfts,
+            balances_order,
+            balances_not_valued,
+            lock,
         })
     }
 
     async fn account_committed_info(
         &self,
         account_id: AccountId,
+        balances_filter: &BalancesFilter,
     ) -> Result<Option<Account>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+        // Committed nonce is used to sign the next transaction, so it must not be outdated.
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let mut transaction = storage
+            .start_snapshot_read()
+            .await
+            .map_err(Error::storage)?;
         let ((last_block, _), account) = transaction
             .chain()
             .account_schema()
@@ -192,8 +475,14 @@ impl ApiAccountData {
                 .await
                 .map_err(Error::storage)?;
             Ok(Some(
-                self.api_account(account, account_id, last_block, &mut transaction)
-                    .await?,
+                self.api_account(
+                    account,
+                    account_id,
+                    last_block,
+                    balances_filter,
+                    &mut transaction,
+                )
+                .await?,
             ))
         } else {
             Ok(None)
@@ -205,9 +494,17 @@ impl ApiAccountData {
     async fn account_finalized_info(
         &self,
         account_id: AccountId,
+        balances_filter: &BalancesFilter,
     ) -> Result<Option<Account>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let mut transaction = storage
+            .start_snapshot_read()
+            .await
+            .map_err(Error::storage)?;
         let (last_block, account) = transaction
             .chain()
             .account_schema()
@@ -220,6 +517,7 @@ impl ApiAccountData {
                     account,
                     account_id,
                     BlockNumber(last_block as u32),
+                    balances_filter,
                     &mut transaction,
                 )
                 .await?,
@@ -235,9 +533,18 @@ impl ApiAccountData {
         &self,
         address: Address,
         account_id: Option<AccountId>,
+        balances_filter: &BalancesFilter,
     ) -> Result<AccountState, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+        // Committed nonce is used to sign the next transaction, so it must not be outdated.
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let mut transaction = storage
+            .start_snapshot_read()
+            .await
+            .map_err(Error::storage)?;
 
         let depositing = get_depositing(
             &mut transaction,
@@ -258,8 +565,14 @@ impl ApiAccountData {
             let finalized_block = BlockNumber(finalized_state.0 as u32);
             let finalized = if let Some(account) = finalized_state.1 {
                 Some(
-                    self.api_account(account, account_id, finalized_block, &mut transaction)
-                        .await?,
+                    self.api_account(
+                        account,
+                        account_id,
+                        finalized_block,
+                        balances_filter,
+                        &mut transaction,
+                    )
+                    .await?,
                 )
             } else {
                 None
@@ -272,8 +585,14 @@ impl ApiAccountData {
                     .await
                     .map_err(Error::storage)?;
                 Some(
-                    self.api_account(account, account_id, last_block, &mut transaction)
-                        .await?,
+                    self.api_account(
+                        account,
+                        account_id,
+                        last_block,
+                        balances_filter,
+                        &mut transaction,
+                    )
+                    .await?,
                 )
             } else {
                 None
@@ -296,8 +615,13 @@ impl ApiAccountData {
         address: Address,
         token_like: Option<TokenLike>,
         second_address: Option<Address>,
+        counterparty: Option<Address>,
     ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
         let token = if let Some(token_like) = token_like {
             Some(
                 self.tokens
@@ -316,6 +640,7 @@ impl ApiAccountData {
                 address,
                 token,
                 second_address,
+                counterparty,
             },
             limit: query.limit,
             direction: query.direction,
@@ -342,238 +667,1375 @@ impl ApiAccountData {
             limit: query.limit,
             direction: query.direction,
         };
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
         storage.paginate_checked(&new_query).await
     }
-}
 
-async fn account_committed_info(
-    data: web::Data<ApiAccountData>,
-    account_id_or_address: web::Path<String>,
-) -> ApiResult<Option<Account>> {
-    let start = Instant::now();
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
-    let res = if let Some(account_id) = account_id {
-        data.account_committed_info(account_id).await.into()
-    } else {
-        ApiResult::Ok(None)
-    };
-    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_committed_info");
-    res
-}
+    /// Returns transactions of the account that were rejected during the retention period,
+    /// newest first.
+    async fn account_rejected_txs(
+        &self,
+        address: Address,
+        limit: u32,
+    ) -> Result<Vec<RejectedTransaction>, Error> {
+        if limit > MAX_LIMIT {
+            return Err(Error::from(InvalidDataError::PaginationLimitTooBig));
+        }
+        let since = Utc::now()
+            - chrono::Duration::from_std(self.rejected_txs_retention_period)
+                .expect("Retention period is too big");
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let records = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_rejected_txs(address, since, limit)
+            .await
+            .map_err(Error::storage)?;
 
-async fn account_finalized_info(
-    data: web::Data<ApiAccountData>,
-    account_id_or_address: web::Path<String>,
-) -> ApiResult<Option<Account>> {
-    let start = Instant::now();
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
-    let res = if let Some(account_id) = account_id {
-        data.account_finalized_info(account_id).await.into()
-    } else {
-        ApiResult::Ok(None)
-    };
-    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_finalized_info");
-    res
-}
+        Ok(records
+            .into_iter()
+            .map(|record| RejectedTransaction {
+                tx_hash: TxHash::from_slice(&record.tx_hash).unwrap(),
+                source: if record.evicted {
+                    RejectionSource::Evicted
+                } else {
+                    RejectionSource::Executed
+                },
+                code: record
+                    .fail_reason
+                    .as_deref()
+                    .map(RejectionCode::from_fail_reason)
+                    .unwrap_or(RejectionCode::Other),
+                fail_reason: record.fail_reason,
+                rejected_at: record.rejected_at,
+            })
+            .collect())
+    }
 
-async fn account_full_info(
-    data: web::Data<ApiAccountData>,
-    account_id_or_address: web::Path<String>,
-) -> ApiResult<AccountState> {
-    let start = Instant::now();
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let address = api_try!(
-        data.get_address_by_address_or_id(address_or_id.clone())
+    /// Returns a page of the executed priority operations and transactions of the account,
+    /// newest first.
+    async fn account_activity(
+        &self,
+        address: Address,
+        from: Option<i64>,
+        limit: u32,
+    ) -> Result<AccountActivity, Error> {
+        if limit > MAX_LIMIT {
+            return Err(Error::from(InvalidDataError::PaginationLimitTooBig));
+        }
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
             .await
-    );
-    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
-    let res = data.account_full_info(address, account_id).await.into();
-    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_full_info");
-    res
-}
-
-async fn account_txs(
-    data: web::Data<ApiAccountData>,
-    account_id_or_address: web::Path<String>,
-    web::Query(query): web::Query<IncomingAccountTxsQuery>,
-) -> ApiResult<Paginated<Transaction, TxHashSerializeWrapper>> {
-    let start = Instant::now();
-    let pagination = api_try!(parse_query(PaginationQuery {
-        from: query.from,
-        limit: query.limit,
-        direction: query.direction
-    })
-    .map_err(Error::from));
+            .map_err(Error::storage)?;
+        let list = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_activity(address, from, limit)
+            .await
+            .map_err(Error::storage)?;
 
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+        let next_from = if list.len() == limit as usize {
+            list.last().map(|entry| entry.sequence_number)
+        } else {
+            None
+        };
+        Ok(AccountActivity { list, next_from })
+    }
 
-    let second_address = if let Some(second_account) = query.second_account {
-        let address_or_id = api_try!(data.parse_account_id_or_address(&second_account));
-        Some(api_try!(
-            data.get_address_by_address_or_id(address_or_id).await
-        ))
-    } else {
-        None
-    };
+    /// Returns the changes of the account history since the checkpoint, see `AccountTxsDelta`.
+    ///
+    /// The checkpoint always covers whole blocks, so if the transactions are limited, they are cut
+    /// at the block boundary. A single block having more transactions of the account than
+    /// the limit is returned entirely.
+    async fn account_txs_delta(
+        &self,
+        address: Address,
+        query: AccountTxsDeltaQuery,
+    ) -> Result<AccountTxsDelta, Error> {
+        if query.limit > MAX_LIMIT {
+            return Err(Error::from(InvalidDataError::PaginationLimitTooBig));
+        }
+        let checkpoint = match (query.checkpoint, query.since_tx, query.since_block) {
+            (Some(checkpoint), None, None) => Some(
+                checkpoint
+                    .parse::<AccountTxsCheckpoint>()
+                    .map_err(InvalidDataError::InvalidAccountTxsDeltaQuery)?,
+            ),
+            (None, Some(_), None) | (None, None, Some(_)) => None,
+            _ => {
+                return Err(Error::from(InvalidDataError::InvalidAccountTxsDeltaQuery(
+                    "got none or several of them".to_owned(),
+                )))
+            }
+        };
 
-    let token_like = query.token.map(|token| TokenLike::parse(&token));
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let mut transaction = storage
+            .start_snapshot_read()
+            .await
+            .map_err(Error::storage)?;
+        let last_committed = transaction
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(Error::storage)?;
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
 
-    let res = data
-        .account_txs(pagination, address, token_like, second_address)
-        .await
-        .into();
-    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_txs");
-    res
-}
+        let (from_block, after_sequence_number) = match (checkpoint, query.since_tx) {
+            (Some(checkpoint), _) => (checkpoint.block, None),
+            (None, Some(tx_hash)) => {
+                let sequence_number = transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_tx_sequence_number(tx_hash)
+                    .await
+                    .map_err(Error::storage)?
+                    .ok_or_else(|| Error::not_found("transaction", tx_hash))?;
+                (BlockNumber(0), Some(sequence_number))
+            }
+            (None, None) => (query.since_block.unwrap_or_default(), None),
+        };
 
-async fn account_pending_txs(
-    data: web::Data<ApiAccountData>,
-    account_id_or_address: web::Path<String>,
-    web::Query(query): web::Query<PaginationQuery<String>>,
-) -> ApiResult<Paginated<Transaction, SerialId>> {
-    let start = Instant::now();
-    let query = api_try!(parse_query(query).map_err(Error::from));
-    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
-    let address = api_try!(
-        data.get_address_by_address_or_id(address_or_id.clone())
+        let mut transactions = transaction
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_in_blocks(
+                address,
+                from_block,
+                last_committed,
+                after_sequence_number,
+                Some(query.limit + 1),
+            )
             .await
-    );
-    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
-    let res = data
-        .account_pending_txs(query, address, account_id)
-        .await
-        .into();
-    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_pending_txs");
-    res
-}
+            .map_err(Error::storage)?;
+        let mut block = last_committed;
+        if let Some(cut_tx) = transactions.get(query.limit as usize) {
+            let cut_block = cut_tx
+                .block_number
+                .expect("Executed transaction without the block");
+            transactions.retain(|tx| tx.block_number < Some(cut_block));
+            if transactions.is_empty() {
+                transactions = transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_account_transactions_in_blocks(
+                        address,
+                        cut_block - 1,
+                        cut_block,
+                        after_sequence_number,
+                        None,
+                    )
+                    .await
+                    .map_err(Error::storage)?;
+                block = cut_block;
+            } else {
+                block = cut_block - 1;
+            }
+        }
 
-pub fn api_scope(
-    pool: ConnectionPool,
-    tokens: TokenDBCache,
-    confirmations_for_eth_event: u64,
-) -> Scope {
-    let data = ApiAccountData::new(pool, tokens, confirmations_for_eth_event);
+        let mut status_changes = Vec::new();
+        if let Some(checkpoint) = checkpoint {
+            let to_block = checkpoint.block.min(last_finalized);
+            if to_block > checkpoint.finalized_block {
+                status_changes = transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_account_transactions_in_blocks(
+                        address,
+                        checkpoint.finalized_block,
+                        to_block,
+                        None,
+                        None,
+                    )
+                    .await
+                    .map_err(Error::storage)?;
+                status_changes.retain(|tx| tx.status == TxInBlockStatus::Finalized);
+            }
+        }
+        transaction.commit().await.map_err(Error::storage)?;
 
-    web::scope("accounts")
-        .app_data(web::Data::new(data))
-        .route(
-            "{account_id_or_address}/committed",
-            web::get().to(account_committed_info),
-        )
-        .route(
-            "{account_id_or_address}/finalized",
-            web::get().to(account_finalized_info),
-        )
-        .route("{account_id_or_address}", web::get().to(account_full_info))
-        .route(
-            "{account_id_or_address}/transactions",
-            web::get().to(account_txs),
-        )
-        .route(
-            "{account_id_or_address}/transactions/pending",
-            web::get().to(account_pending_txs),
-        )
+        Ok(AccountTxsDelta {
+            transactions,
+            status_changes,
+            checkpoint: AccountTxsCheckpoint {
+                block,
+                finalized_block: last_finalized.min(block),
+            },
+            has_more: block < last_committed,
+        })
+    }
+
+    async fn account_pending_balances(
+        &self,
+        address: Address,
+        account_id: Option<AccountId>,
+    ) -> Result<PendingBalances, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let mut transaction = storage
+            .start_snapshot_read()
+            .await
+            .map_err(Error::storage)?;
+        let account = if let Some(account_id) = account_id {
+            let (_, committed) = transaction
+                .chain()
+                .account_schema()
+                .last_committed_state_for_account(account_id)
+                .await
+                .map_err(Error::storage)?;
+            committed
+        } else {
+            None
+        };
+        let mempool_txs = transaction
+            .chain()
+            .mempool_schema()
+            .get_pending_txs_for_address(address)
+            .await
+            .map_err(Error::storage)?;
+        let deposits = transaction
+            .chain()
+            .mempool_schema()
+            .get_pending_deposits(address)
+            .await
+            .map_err(Error::storage)?;
+
+        let estimates =
+            estimate_pending_balances(address, account.as_ref(), &mempool_txs, &deposits);
+        let mut balances = BTreeMap::new();
+        for (token_id, balance) in estimates {
+            let symbol = self
+                .tokens
+                .token_symbol(&mut transaction, token_id)
+                .await
+                .map_err(Error::storage)?
+                .ok_or_else(|| Error::from(PriceError::token_not_found(token_id)))?;
+            balances.insert(symbol, balance);
+        }
+        transaction.commit().await.map_err(Error::storage)?;
+
+        Ok(PendingBalances {
+            is_estimate: true,
+            balances,
+        })
+    }
+
+    /// Returns the committed nonce of the account and the nonce suggested for its next
+    /// transaction. Unknown accounts are reported with the zero nonce.
+    async fn account_nonce(
+        &self,
+        account_address_or_id: AccountAddressOrId,
+    ) -> Result<AccountNonce, Error> {
+        // Both the committed nonce and the mempool must not be outdated.
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let (address, account_id) = match account_address_or_id {
+            AccountAddressOrId::Id(account_id) => {
+                let address = storage
+                    .chain()
+                    .account_schema()
+                    .account_address_by_id(account_id)
+                    .await
+                    .map_err(Error::storage)?;
+                (address, address.map(|_| account_id))
+            }
+            AccountAddressOrId::Address(address) => {
+                let account_id = storage
+                    .chain()
+                    .account_schema()
+                    .account_id_by_address(address)
+                    .await
+                    .map_err(Error::storage)?;
+                (Some(address), account_id)
+            }
+        };
+        let committed_nonce = match account_id {
+            Some(account_id) => storage
+                .chain()
+                .account_schema()
+                .estimate_nonce(account_id)
+                .await
+                .map_err(Error::storage)?,
+            None => None,
+        };
+        let pending_nonces = match address {
+            Some(address) => storage
+                .chain()
+                .mempool_schema()
+                .get_pending_nonces_for_address(address)
+                .await
+                .map_err(Error::storage)?,
+            None => Vec::new(),
+        };
+
+        let exists = committed_nonce.is_some();
+        let committed_nonce = committed_nonce.unwrap_or_default();
+        Ok(AccountNonce {
+            exists,
+            committed_nonce,
+            pending_txs: pending_nonces.len() as u32,
+            suggested_nonce: suggest_nonce(committed_nonce, &pending_nonces),
+        })
+    }
+
+    /// Computes the checksum of the account state at the block from the data of the replica
+    /// serving the request. The last committed block is used if the block isn't specified.
+    async fn account_checksum(
+        &self,
+        account_id: AccountId,
+        block: Option<BlockNumber>,
+    ) -> Result<Option<AccountChecksum>, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(Error::storage)?;
+        let block_number = block.unwrap_or(last_block);
+        if block_number > last_block {
+            return Err(Error::not_found("block", block_number));
+        }
+
+        let accounts = storage
+            .chain()
+            .state_schema()
+            .load_accounts_state(block_number, &[account_id])
+            .await
+            .map_err(Error::storage)?;
+        Ok(accounts.get(&account_id).map(|account| AccountChecksum {
+            account_id,
+            block_number,
+            checksum: account_checksum(account_id, block_number, account),
+        }))
+    }
+
+    async fn account_exit_plan(
+        &self,
+        account_id: AccountId,
+    ) -> Result<Option<AccountExitPlan>, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let mut transaction = storage
+            .start_snapshot_read()
+            .await
+            .map_err(Error::storage)?;
+        let plan = load_exit_plan(&mut transaction, &self.tokens, account_id).await?;
+        transaction.commit().await.map_err(Error::storage)?;
+        Ok(plan)
+    }
+
+    fn parse_pubkey_hash(&self, hash: &str) -> Result<PubKeyHash, Error> {
+        match PubKeyHash::from_hex(hash) {
+            // Zero hash means that the signing key is not set, so there is nothing to search for.
+            Ok(pubkey_hash) if pubkey_hash != PubKeyHash::zero() => Ok(pubkey_hash),
+            _ => Err(Error::from(InvalidDataError::InvalidPubKeyHash)),
+        }
+    }
+
+    /// Returns all the accounts that have the public key hash set currently or had it set before.
+    async fn accounts_by_pubkey_hash(
+        &self,
+        pubkey_hash: PubKeyHash,
+    ) -> Result<Vec<AccountByPubKeyHash>, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let owners = storage
+            .chain()
+            .account_schema()
+            .accounts_by_pubkey_hash(pubkey_hash)
+            .await
+            .map_err(Error::storage)?;
+
+        Ok(owners
+            .into_iter()
+            .map(|owner| AccountByPubKeyHash {
+                account_id: AccountId(owner.account_id as u32),
+                address: Address::from_slice(&owner.address),
+                is_current: owner.is_current,
+            })
+            .collect())
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::api_server::rest::v02::{
-        test_utils::{deserialize_response_result, TestServerConfig},
-        SharedData,
-    };
-    use num::BigUint;
-    use serde::Deserialize;
-    use zksync_api_client::rest::client::Client;
-    use zksync_api_types::v02::{
-        account::{DepositingAccountBalances, DepositingFunds},
-        pagination::{PaginationDirection, PaginationQuery},
-        transaction::{L1Transaction, TransactionData},
-        ApiVersion,
-    };
-    use zksync_storage::StorageProcessor;
-    use zksync_types::{AccountId, Address, Deposit, PriorityOp, TokenId, ZkSyncPriorityOp, H256};
+async fn account_committed_info(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(amounts): web::Query<AmountsQuery>,
+    web::Query(balances): web::Query<BalancesQuery>,
+) -> ApiResult<Value> {
+    let start = Instant::now();
+    let balances_filter = api_try!(BalancesFilter::parse(balances));
+    let account = account_id_or_address.to_string();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = match account_id {
+        Some(account_id) => {
+            data.account_committed_info(account_id, &balances_filter)
+                .await
+        }
+        None => Ok(None),
+    }
+    .or_not_found("account", account);
+    let res = render_amounts(res, amounts.amounts_in, &data.tokens, &data.pool, None)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_committed_info");
+    res
+}
+
+async fn account_finalized_info(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(amounts): web::Query<AmountsQuery>,
+    web::Query(balances): web::Query<BalancesQuery>,
+) -> ApiResult<Value> {
+    let start = Instant::now();
+    let balances_filter = api_try!(BalancesFilter::parse(balances));
+    let account = account_id_or_address.to_string();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = match account_id {
+        Some(account_id) => {
+            data.account_finalized_info(account_id, &balances_filter)
+                .await
+        }
+        None => Ok(None),
+    }
+    .or_not_found("account", account);
+    let res = render_amounts(res, amounts.amounts_in, &data.tokens, &data.pool, None)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_finalized_info");
+    res
+}
+
+async fn account_full_info(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(amounts): web::Query<AmountsQuery>,
+    web::Query(balances): web::Query<BalancesQuery>,
+) -> ApiResult<Value> {
+    let start = Instant::now();
+    let balances_filter = api_try!(BalancesFilter::parse(balances));
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let address = api_try!(
+        data.get_address_by_address_or_id(address_or_id.clone())
+            .await
+    );
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = data
+        .account_full_info(address, account_id, &balances_filter)
+        .await;
+    let res = render_amounts(res, amounts.amounts_in, &data.tokens, &data.pool, None)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_full_info");
+    res
+}
+
+async fn account_txs(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(query): web::Query<IncomingAccountTxsQuery>,
+    web::Query(amounts): web::Query<AmountsQuery>,
+) -> ApiResult<Value> {
+    let start = Instant::now();
+    let pagination = api_try!(parse_query(PaginationQuery {
+        from: query.from,
+        limit: query.limit,
+        direction: query.direction
+    })
+    .map_err(Error::from));
+
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+
+    if query.second_account.is_some() && query.counterparty.is_some() {
+        return Error::from(InvalidDataError::ConflictingAccountTxsFilters).into();
+    }
+    let second_address = if let Some(second_account) = query.second_account {
+        let address_or_id = api_try!(data.parse_account_id_or_address(&second_account));
+        Some(api_try!(
+            data.get_address_by_address_or_id(address_or_id).await
+        ))
+    } else {
+        None
+    };
+    // The counterparty may be an L1 address, e.g. the sender of a deposit or the withdrawal target.
+    let counterparty = if let Some(counterparty) = query.counterparty {
+        let address_or_id = api_try!(data.parse_account_id_or_address(&counterparty));
+        Some(api_try!(
+            data.get_address_by_address_or_id(address_or_id).await
+        ))
+    } else {
+        None
+    };
+
+    let token_like = query.token.map(|token| TokenLike::parse(&token));
+
+    let res = data
+        .account_txs(
+            pagination,
+            address,
+            token_like,
+            second_address,
+            counterparty,
+        )
+        .await;
+    let res = render_amounts(res, amounts.amounts_in, &data.tokens, &data.pool, None)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_txs");
+    res
+}
+
+async fn account_pending_txs(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<Transaction, SerialId>> {
+    let start = Instant::now();
+    let query = api_try!(parse_query(query).map_err(Error::from));
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let address = api_try!(
+        data.get_address_by_address_or_id(address_or_id.clone())
+            .await
+    );
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = data
+        .account_pending_txs(query, address, account_id)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_pending_txs");
+    res
+}
+
+async fn account_txs_delta(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(query): web::Query<AccountTxsDeltaQuery>,
+) -> ApiResult<AccountTxsDelta> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.account_txs_delta(address, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_txs_delta");
+    res
+}
+
+async fn account_pending_balances(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(amounts): web::Query<AmountsQuery>,
+) -> ApiResult<Value> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let address = api_try!(
+        data.get_address_by_address_or_id(address_or_id.clone())
+            .await
+    );
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = data.account_pending_balances(address, account_id).await;
+    let res = render_amounts(res, amounts.amounts_in, &data.tokens, &data.pool, None)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_pending_balances");
+    res
+}
+
+async fn account_nonce(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+) -> ApiResult<AccountNonce> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let res = data.account_nonce(address_or_id).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_nonce");
+    res
+}
+
+async fn account_rejected_txs(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(query): web::Query<RejectedTxsQuery>,
+) -> ApiResult<Vec<RejectedTransaction>> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.account_rejected_txs(address, query.limit).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_rejected_txs");
+    res
+}
+
+async fn account_activity(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(query): web::Query<AccountActivityQuery>,
+) -> ApiResult<AccountActivity> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data
+        .account_activity(address, query.from, query.limit)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_activity");
+    res
+}
+
+async fn accounts_by_pubkey_hash(
+    data: web::Data<ApiAccountData>,
+    pubkey_hash: web::Path<String>,
+) -> ApiResult<Vec<AccountByPubKeyHash>> {
+    let start = Instant::now();
+    let pubkey_hash = api_try!(data.parse_pubkey_hash(&pubkey_hash));
+    let res = data.accounts_by_pubkey_hash(pubkey_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "accounts_by_pubkey_hash");
+    res
+}
+
+/// Returns the checksum of the account state at the block, replicas serving the same state
+/// return the same checksum.
+async fn checksum(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+    web::Query(query): web::Query<AccountChecksumQuery>,
+) -> ApiResult<AccountChecksum> {
+    let start = Instant::now();
+    let account = account_id_or_address.to_string();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = match account_id {
+        Some(account_id) => data.account_checksum(account_id, query.block).await,
+        None => Ok(None),
+    }
+    .or_not_found("account", account)
+    .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_checksum");
+    res
+}
+
+/// Lists the exits of all the funds of the account in the exodus mode, built from
+/// the last finalized state.
+async fn exit_plan(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: ValidPath<AccountAddressOrId>,
+) -> ApiResult<AccountExitPlan> {
+    let start = Instant::now();
+    let account = account_id_or_address.to_string();
+    let address_or_id = api_try!(data.user_account(account_id_or_address.into_inner()));
+    let account_id = api_try!(data.get_id_by_address_or_id(address_or_id).await);
+    let res = match account_id {
+        Some(account_id) => data.account_exit_plan(account_id).await,
+        None => Ok(None),
+    }
+    .or_not_found("account", account)
+    .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_exit_plan");
+    res
+}
+
+pub fn api_scope(
+    pool: ReplicatedPool,
+    tokens: TokenCache,
+    ticker: FeeTicker,
+    confirmations_for_eth_event: u64,
+    rejected_txs_retention_period: Duration,
+    special_accounts: SpecialAccounts,
+    account_locks: AccountLocks,
+) -> Scope {
+    let data = ApiAccountData::new(
+        pool,
+        tokens,
+        ticker,
+        confirmations_for_eth_event,
+        rejected_txs_retention_period,
+        special_accounts,
+        account_locks,
+    );
+
+    web::scope("accounts")
+        .app_data(web::Data::new(data))
+        .route(
+            "byPubkeyHash/{pubkey_hash}",
+            web::get().to(accounts_by_pubkey_hash),
+        )
+        .route(
+            "{account_id_or_address}/committed",
+            web::get().to(account_committed_info),
+        )
+        .route(
+            "{account_id_or_address}/finalized",
+            web::get().to(account_finalized_info),
+        )
+        .route("{account_id_or_address}", web::get().to(account_full_info))
+        .route(
+            "{account_id_or_address}/transactions",
+            web::get().to(account_txs),
+        )
+        .route(
+            "{account_id_or_address}/transactions/pending",
+            web::get().to(account_pending_txs),
+        )
+        .route(
+            "{account_id_or_address}/transactions/delta",
+            web::get().to(account_txs_delta),
+        )
+        .route(
+            "{account_id_or_address}/pendingBalances",
+            web::get().to(account_pending_balances),
+        )
+        .route(
+            "{account_id_or_address}/nonce",
+            web::get().to(account_nonce),
+        )
+        .route(
+            "{account_id_or_address}/rejectedTransactions",
+            web::get().to(account_rejected_txs),
+        )
+        .route(
+            "{account_id_or_address}/activity",
+            web::get().to(account_activity),
+        )
+        .route("{account_id_or_address}/checksum", web::get().to(checksum))
+        .route("{account_id_or_address}/exitPlan", web::get().to(exit_plan))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{
+            assert_not_found, deserialize_response_result, dummy_fee_ticker, BlockTx,
+            TestServerConfig, TxLifecycle,
+        },
+        SharedData,
+    };
+    use actix_web::http::StatusCode;
+    use num::BigUint;
+    use serde::Deserialize;
+    use std::str::FromStr;
+    use zksync_api_client::rest::client::Client;
+    use zksync_api_types::{
+        error_code::ErrorCode,
+        v02::{
+            account::{AccountKind, DepositingAccountBalances, DepositingFunds},
+            pagination::{PaginationDirection, PaginationQuery},
+            transaction::{L1Transaction, TransactionData},
+            ApiVersion,
+        },
+    };
+    use zksync_storage::{ConnectionPool, StorageProcessor};
+    use zksync_types::{
+        tx::TimeRange, AccountId, Address, Deposit, ForcedExit, Nonce, PriorityOp, TokenId,
+        Transfer, ZkSyncPriorityOp, ZkSyncTx, H256,
+    };
+
+    // While the values of the PendingOpsFlattenRequest's fields are never directly
+    // used in the tests, we still need them to specify the JSON format of the `unconfirmed_ops` endpoint input in tests.
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct PendingOpsFlattenRequest {
+        pub address: Address,
+        pub account_id: Option<AccountId>,
+        pub serial_id: String,
+        pub limit: u32,
+        pub direction: PaginationDirection,
+    }
+
+    struct TestServer {
+        api_server: actix_test::TestServer,
+        pool: ConnectionPool,
+        confirmations_for_eth_event: u64,
+    }
+
+    impl TestServer {
+        async fn new() -> anyhow::Result<(Client, Self)> {
+            let cfg = TestServerConfig::default();
+            cfg.fill_database().await?;
+
+            let pool = cfg.pool.clone();
+
+            let shared_data = SharedData {
+                net: cfg.config.chain.eth.network,
+                api_version: ApiVersion::V02,
+                not_found_status: StatusCode::OK,
+            };
+            let (api_client, api_server) = cfg.start_server(
+                move |cfg: &TestServerConfig| {
+                    api_scope(
+                        cfg.replicated_pool(),
+                        cfg.token_cache(),
+                        dummy_fee_ticker(&[], None),
+                        cfg.config.eth_watch.confirmations_for_eth_event,
+                        cfg.config
+                            .chain
+                            .state_keeper
+                            .rejected_txs_retention_period(),
+                        SpecialAccounts::new(cfg.config.chain.state_keeper.fee_account_addr),
+                        AccountLocks::new(cfg.pool.clone()),
+                    )
+                },
+                Some(shared_data),
+            );
+
+            Ok((
+                api_client,
+                Self {
+                    api_server,
+                    pool,
+                    confirmations_for_eth_event: cfg.config.eth_watch.confirmations_for_eth_event,
+                },
+            ))
+        }
+
+        async fn account_id_and_tx_hash(
+            storage: &mut StorageProcessor<'_>,
+            block: BlockNumber,
+        ) -> anyhow::Result<(AccountId, TxHash)> {
+            let transactions = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(block)
+                .await?;
+
+            let tx = &transactions[1];
+            let op = tx.op.as_object().unwrap();
+
+            let id = if op.contains_key("accountId") {
+                serde_json::from_value(op["accountId"].clone()).unwrap()
+            } else {
+                serde_json::from_value(op["creatorId"].clone()).unwrap()
+            };
+            Ok((id, TxHash::from_str(&tx.tx_hash).unwrap()))
+        }
+
+        async fn stop(self) {
+            self.api_server.stop().await;
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn accounts_scope() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        // Get account information.
+        let (account_id, tx_hash) = TestServer::account_id_and_tx_hash(
+            &mut server.pool.access_storage().await?,
+            BlockNumber(1),
+        )
+        .await?;
+
+        let response = client
+            .account_info(&account_id.to_string(), "committed")
+            .await?;
+        let account_committed_info_by_id: Account = deserialize_response_result(response)?;
+
+        let address = account_committed_info_by_id.address;
+        let response = client
+            .account_info(&format!("{:?}", address), "committed")
+            .await?;
+        let account_committed_info_by_address: Account = deserialize_response_result(response)?;
+        assert_eq!(
+            account_committed_info_by_id,
+            account_committed_info_by_address
+        );
+
+        let response = client
+            .account_info(&format!("{:?}", address), "finalized")
+            .await?;
+        // The account may be not finalized yet.
+        let account_finalized_info: Option<Account> = if response.error.is_some() {
+            assert_not_found(response, "account")?;
+            None
+        } else {
+            Some(deserialize_response_result(response)?)
+        };
+
+        let unknown_address = format!("{:?}", Address::repeat_byte(0x42));
+        for info_type in ["committed", "finalized"] {
+            let response = client.account_info(&unknown_address, info_type).await?;
+            assert_not_found(response, "account")?;
+        }
+
+        {
+            let mut storage = server.pool.access_storage().await?;
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_priority_ops(
+                    &[
+                        PriorityOp {
+                            serial_id: 10,
+                            data: ZkSyncPriorityOp::Deposit(Deposit {
+                                from: Default::default(),
+                                token: TokenId(0),
+                                amount: BigUint::from(100500u64),
+                                to: address,
+                            }),
+                            deadline_block: 0,
+                            eth_hash: H256::from_slice(&[0u8; 32]),
+                            eth_block: 25,
+                            eth_block_index: Some(1),
+                        },
+                        PriorityOp {
+                            serial_id: 11,
+                            data: ZkSyncPriorityOp::Deposit(Deposit {
+                                from: Default::default(),
+                                token: TokenId(0),
+                                amount: BigUint::from(100500u64),
+                                to: address,
+                            }),
+                            deadline_block: 0,
+                            eth_hash: H256::from_slice(&[0u8; 32]),
+                            eth_block: 25,
+                            eth_block_index: Some(1),
+                        },
+                        PriorityOp {
+                            serial_id: 12,
+                            data: ZkSyncPriorityOp::Deposit(Deposit {
+                                from: Default::default(),
+                                token: TokenId(0),
+                                amount: BigUint::from(100500u64),
+                                to: address,
+                            }),
+                            deadline_block: 0,
+                            eth_hash: H256::from_slice(&[0u8; 32]),
+                            eth_block: 25,
+                            eth_block_index: Some(1),
+                        },
+                    ],
+                    false,
+                )
+                .await?;
+        }
+        let balances = vec![(
+            String::from("ETH"),
+            DepositingFunds {
+                amount: BigUint::from(301500u32),
+                expected_accept_block: 25 + server.confirmations_for_eth_event,
+            },
+        )]
+        .into_iter()
+        .collect();
+        let expected_depositing = DepositingAccountBalances { balances };
+
+        let response = client.account_full_info(&format!("{:?}", address)).await?;
+        let account_full_info: AccountState = deserialize_response_result(response)?;
+        assert_eq!(
+            account_full_info.committed,
+            Some(account_committed_info_by_id)
+        );
+        assert_eq!(account_full_info.finalized, account_finalized_info);
+        assert_eq!(account_full_info.depositing, expected_depositing);
 
-    // While the values of the PendingOpsFlattenRequest's fields are never directly
-    // used in the tests, we still need them to specify the JSON format of the `unconfirmed_ops` endpoint input in tests.
-    #[derive(Debug, Deserialize)]
-    #[allow(dead_code)]
-    struct PendingOpsFlattenRequest {
-        pub address: Address,
-        pub account_id: Option<AccountId>,
-        pub serial_id: String,
-        pub limit: u32,
-        pub direction: PaginationDirection,
+        let query = PaginationQuery {
+            from: ApiEither::from(tx_hash),
+            limit: 1,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client.account_txs(&query, &account_id.to_string()).await?;
+        let txs: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
+        assert_eq!(txs.list[0].tx_hash, tx_hash);
+
+        // There are no transactions with an unrelated counterparty.
+        let response = client
+            .account_txs_with_counterparty(
+                &query,
+                &account_id.to_string(),
+                &format!("{:?}", Address::random()),
+            )
+            .await?;
+        let txs: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
+        assert!(txs.list.is_empty());
+        assert_eq!(txs.pagination.count, 0);
+
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 2,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_pending_txs(&query, &account_id.to_string())
+            .await?;
+        let txs: Paginated<Transaction, SerialId> = deserialize_response_result(response)?;
+        assert_eq!(txs.list.len(), 2);
+        match &txs.list[0].op {
+            TransactionData::L1(tx) => match tx {
+                L1Transaction::Deposit(deposit) => {
+                    assert_eq!(deposit.id, 12);
+                }
+                _ => panic!("should return deposit"),
+            },
+            _ => panic!("account_pending_txs returned L2 tx"),
+        }
+
+        server.stop().await;
+        Ok(())
     }
 
-    struct TestServer {
-        api_server: actix_test::TestServer,
-        pool: ConnectionPool,
-        confirmations_for_eth_event: u64,
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn rejected_txs() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let (rejected_tx_hash, address) = {
+            let mut storage = server.pool.access_storage().await?;
+            let failed_tx = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(1))
+                .await?
+                .into_iter()
+                .find(|tx| !tx.success)
+                .expect("Test data should contain a failed transaction");
+            let tx: ZkSyncTx = serde_json::from_value(failed_tx.op)?;
+            let address = tx.account();
+            (TxHash::from_str(&failed_tx.tx_hash).unwrap(), address)
+        };
+
+        // Evict an expired transaction of the same account from the mempool.
+        let expired_tx = Transfer::new(
+            AccountId(0xf00d),
+            address,
+            Address::random(),
+            TokenId(0),
+            1_u64.into(),
+            1_u64.into(),
+            Nonce(100),
+            TimeRange::new(0, 1),
+            None,
+        );
+        let mut lifecycle = TxLifecycle::new(server.pool.clone()).await?;
+        let evicted_tx_hash = lifecycle
+            .submit(ZkSyncTx::Transfer(Box::new(expired_tx)))
+            .await?;
+        lifecycle
+            .evict(evicted_tx_hash, "Transaction has expired")
+            .await?;
+        assert!(
+            !server
+                .pool
+                .access_storage()
+                .await?
+                .chain()
+                .mempool_schema()
+                .contains_tx(evicted_tx_hash)
+                .await?
+        );
+
+        let query = RejectedTxsQuery { limit: MAX_LIMIT };
+        let response = client
+            .account_rejected_txs(&query, &format!("{:?}", address))
+            .await?;
+        let txs: Vec<RejectedTransaction> = deserialize_response_result(response)?;
+
+        // The eviction is the most recent rejection.
+        assert_eq!(txs[0].tx_hash, evicted_tx_hash);
+        assert_eq!(txs[0].source, RejectionSource::Evicted);
+        assert_eq!(txs[0].code, RejectionCode::Expired);
+
+        let rejected_tx = txs
+            .iter()
+            .find(|tx| tx.tx_hash == rejected_tx_hash)
+            .expect("Failed transaction should be reported");
+        assert_eq!(rejected_tx.source, RejectionSource::Executed);
+        assert_eq!(rejected_tx.fail_reason.as_deref(), Some("Unknown token"));
+        assert_eq!(rejected_tx.code, RejectionCode::Other);
+
+        assert!(txs
+            .windows(2)
+            .all(|pair| pair[0].rejected_at >= pair[1].rejected_at));
+
+        // Limit must not exceed the maximum.
+        let query = RejectedTxsQuery {
+            limit: MAX_LIMIT + 1,
+        };
+        let response = client
+            .account_rejected_txs(&query, &format!("{:?}", address))
+            .await?;
+        assert!(response.error.is_some());
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        Ok(())
     }
 
-    impl TestServer {
-        async fn new() -> anyhow::Result<(Client, Self)> {
-            let cfg = TestServerConfig::default();
-            cfg.fill_database().await?;
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_txs_delta() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+        let mut lifecycle = TxLifecycle::new(server.pool.clone()).await?;
+        let address = Address::random();
+        let account = format!("{:?}", address);
 
-            let pool = cfg.pool.clone();
+        let delta_since = |checkpoint: AccountTxsCheckpoint| AccountTxsDeltaQuery {
+            checkpoint: Some(checkpoint.to_string()),
+            limit: MAX_LIMIT,
+            ..Default::default()
+        };
+        let query = AccountTxsDeltaQuery {
+            since_block: Some(BlockNumber(0)),
+            limit: MAX_LIMIT,
+            ..Default::default()
+        };
+        let response = client.account_txs_delta(&query, &account).await?;
+        let delta: AccountTxsDelta = deserialize_response_result(response)?;
+        assert!(delta.transactions.is_empty());
+        assert!(!delta.has_more);
+        let checkpoint = delta.checkpoint;
 
-            let shared_data = SharedData {
-                net: cfg.config.chain.eth.network,
-                api_version: ApiVersion::V02,
-            };
-            let (api_client, api_server) = cfg.start_server(
-                move |cfg: &TestServerConfig| {
-                    api_scope(
-                        cfg.pool.clone(),
-                        TokenDBCache::new(
-                            cfg.config.api.token_config.invalidate_token_cache_period(),
-                        ),
-                        cfg.config.eth_watch.confirmations_for_eth_event,
-                    )
-                },
-                Some(shared_data),
+        // Nothing has changed since the checkpoint.
+        let response = client
+            .account_txs_delta(&delta_since(checkpoint), &account)
+            .await?;
+        let delta: AccountTxsDelta = deserialize_response_result(response)?;
+        assert!(delta.transactions.is_empty());
+        assert!(delta.status_changes.is_empty());
+        assert_eq!(delta.checkpoint, checkpoint);
+        assert!(!delta.has_more);
+
+        // A new transaction of the account is committed.
+        let transfer = Transfer::new(
+            AccountId(0xf00d),
+            Address::random(),
+            address,
+            TokenId(0),
+            1_u64.into(),
+            1_u64.into(),
+            Nonce(0),
+            TimeRange::default(),
+            None,
+        );
+        let tx = ZkSyncTx::Transfer(Box::new(transfer));
+        let tx_hash = tx.hash();
+        let block_number = lifecycle.include(vec![BlockTx::success(tx)]).await?;
+
+        let response = client
+            .account_txs_delta(&delta_since(checkpoint), &account)
+            .await?;
+        let delta: AccountTxsDelta = deserialize_response_result(response)?;
+        assert_eq!(delta.transactions.len(), 1);
+        assert_eq!(delta.transactions[0].tx_hash, tx_hash);
+        assert_eq!(delta.transactions[0].status, TxInBlockStatus::Committed);
+        assert!(delta.status_changes.is_empty());
+        assert_eq!(delta.checkpoint.block, block_number);
+        assert_eq!(delta.checkpoint.finalized_block, checkpoint.finalized_block);
+        let checkpoint = delta.checkpoint;
+
+        // The same transaction is reported since the previous block, but not since itself.
+        let query = AccountTxsDeltaQuery {
+            since_block: Some(block_number - 1),
+            limit: MAX_LIMIT,
+            ..Default::default()
+        };
+        let response = client.account_txs_delta(&query, &account).await?;
+        let delta: AccountTxsDelta = deserialize_response_result(response)?;
+        assert_eq!(delta.transactions.len(), 1);
+        assert_eq!(delta.checkpoint, checkpoint);
+        let query = AccountTxsDeltaQuery {
+            since_tx: Some(tx_hash),
+            limit: MAX_LIMIT,
+            ..Default::default()
+        };
+        let response = client.account_txs_delta(&query, &account).await?;
+        let delta: AccountTxsDelta = deserialize_response_result(response)?;
+        assert!(delta.transactions.is_empty());
+
+        // Only the status of the transaction changes once its block is finalized.
+        lifecycle.verify(block_number).await?;
+        lifecycle.execute(block_number).await?;
+        let response = client
+            .account_txs_delta(&delta_since(checkpoint), &account)
+            .await?;
+        let delta: AccountTxsDelta = deserialize_response_result(response)?;
+        assert!(delta.transactions.is_empty());
+        assert_eq!(delta.status_changes.len(), 1);
+        assert_eq!(delta.status_changes[0].tx_hash, tx_hash);
+        assert_eq!(delta.status_changes[0].status, TxInBlockStatus::Finalized);
+        assert_eq!(
+            delta.checkpoint,
+            AccountTxsCheckpoint {
+                block: block_number,
+                finalized_block: block_number,
+            }
+        );
+        assert!(!delta.has_more);
+
+        // Exactly one of the starting points should be provided.
+        for query in [
+            AccountTxsDeltaQuery {
+                limit: MAX_LIMIT,
+                ..Default::default()
+            },
+            AccountTxsDeltaQuery {
+                since_block: Some(BlockNumber(0)),
+                ..delta_since(checkpoint)
+            },
+            AccountTxsDeltaQuery {
+                checkpoint: Some("1:2".to_owned()),
+                limit: MAX_LIMIT,
+                ..Default::default()
+            },
+        ] {
+            let response = client.account_txs_delta(&query, &account).await?;
+            assert_eq!(
+                response.error.map(|error| error["code"].as_u64().unwrap()),
+                Some(ErrorCode::InvalidAccountTxsDeltaQuery as u64)
             );
+        }
 
-            Ok((
-                api_client,
-                Self {
-                    api_server,
-                    pool,
-                    confirmations_for_eth_event: cfg.config.eth_watch.confirmations_for_eth_event,
-                },
-            ))
+        lifecycle.finish().await?;
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_activity() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let (account_id, _) = TestServer::account_id_and_tx_hash(
+            &mut server.pool.access_storage().await?,
+            BlockNumber(1),
+        )
+        .await?;
+        let account_id = account_id.to_string();
+
+        let query = AccountActivityQuery {
+            from: None,
+            limit: MAX_LIMIT,
+        };
+        let response = client.account_activity(&query, &account_id).await?;
+        let activity: AccountActivity = deserialize_response_result(response)?;
+        assert!(!activity.list.is_empty());
+        let expected: Vec<_> = activity
+            .list
+            .iter()
+            .map(|entry| (entry.kind, entry.tx.tx_hash))
+            .collect();
+
+        // Paging with the cursor returns the same entries.
+        let mut entries = Vec::new();
+        let mut from = None;
+        loop {
+            let query = AccountActivityQuery { from, limit: 2 };
+            let response = client.account_activity(&query, &account_id).await?;
+            let page: AccountActivity = deserialize_response_result(response)?;
+            entries.extend(page.list.iter().map(|entry| (entry.kind, entry.tx.tx_hash)));
+            from = page.next_from;
+            if from.is_none() {
+                break;
+            }
         }
+        assert_eq!(entries, expected);
 
-        async fn account_id_and_tx_hash(
-            storage: &mut StorageProcessor<'_>,
-            block: BlockNumber,
-        ) -> anyhow::Result<(AccountId, TxHash)> {
-            let transactions = storage
-                .chain()
-                .block_schema()
-                .get_block_transactions(block)
-                .await?;
+        // Limit must not exceed the maximum.
+        let query = AccountActivityQuery {
+            from: None,
+            limit: MAX_LIMIT + 1,
+        };
+        let response = client.account_activity(&query, &account_id).await?;
+        assert!(response.error.is_some());
 
-            let tx = &transactions[1];
-            let op = tx.op.as_object().unwrap();
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn accounts_by_pubkey_hash() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let pubkey_hash = PubKeyHash::from_bytes(&[0xfe; 20]).unwrap();
+        let expected_accounts: Vec<_> = {
+            let mut storage = server.pool.access_storage().await?;
+            storage
+                .chain()
+                .account_schema()
+                .accounts_by_pubkey_hash(pubkey_hash)
+                .await?
+                .into_iter()
+                .map(|owner| AccountByPubKeyHash {
+                    account_id: AccountId(owner.account_id as u32),
+                    address: Address::from_slice(&owner.address),
+                    is_current: owner.is_current,
+                })
+                .collect()
+        };
+        let response = client
+            .accounts_by_pubkey_hash(&pubkey_hash.as_hex())
+            .await?;
+        let accounts: Vec<AccountByPubKeyHash> = deserialize_response_result(response)?;
+        assert_eq!(accounts, expected_accounts);
 
-            let id = if op.contains_key("accountId") {
-                serde_json::from_value(op["accountId"].clone()).unwrap()
-            } else {
-                serde_json::from_value(op["creatorId"].clone()).unwrap()
-            };
-            Ok((id, TxHash::from_str(&tx.tx_hash).unwrap()))
+        // Malformed and zero hashes are rejected.
+        for hash in [
+            "fefefefefefefefefefefefefefefefefefefefe",
+            "sync:fefe",
+            "sync:zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz",
+            "sync:0000000000000000000000000000000000000000",
+        ] {
+            let response = client.accounts_by_pubkey_hash(hash).await?;
+            let expected_error = Error::from(InvalidDataError::InvalidPubKeyHash);
+            let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+            assert_eq!(error, expected_error);
         }
 
-        async fn stop(self) {
-            self.api_server.stop().await;
-        }
+        server.stop().await;
+        Ok(())
     }
 
     #[actix_rt::test]
@@ -581,137 +2043,500 @@ mod tests {
         not(feature = "api_test"),
         ignore = "Use `zk test rust-api` command to perform this test"
     )]
-    async fn accounts_scope() -> anyhow::Result<()> {
+    async fn reserved_addresses() -> anyhow::Result<()> {
         let (client, server) = TestServer::new().await?;
 
-        // Get account information.
         let (account_id, tx_hash) = TestServer::account_id_and_tx_hash(
             &mut server.pool.access_storage().await?,
             BlockNumber(1),
         )
         .await?;
-
         let response = client
             .account_info(&account_id.to_string(), "committed")
             .await?;
-        let account_committed_info_by_id: Account = deserialize_response_result(response)?;
+        let account: Account = deserialize_response_result(response)?;
+        assert_eq!(account.account_kind, AccountKind::User);
 
-        let address = account_committed_info_by_id.address;
+        // The zero address can't own an account, every endpoint reports it the same way.
+        let zero_address = format!("{:?}", Address::zero());
+        let txs_query = PaginationQuery {
+            from: ApiEither::from(tx_hash),
+            limit: 1,
+            direction: PaginationDirection::Older,
+        };
+        let pending_txs_query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 1,
+            direction: PaginationDirection::Older,
+        };
+        let responses = vec![
+            client.account_info(&zero_address, "committed").await?,
+            client.account_info(&zero_address, "finalized").await?,
+            client.account_full_info(&zero_address).await?,
+            client.account_txs(&txs_query, &zero_address).await?,
+            client
+                .account_pending_txs(&pending_txs_query, &zero_address)
+                .await?,
+            client.account_pending_balances(&zero_address).await?,
+            client.account_nonce(&zero_address).await?,
+            client
+                .account_checksum(&AccountChecksumQuery::default(), &zero_address)
+                .await?,
+        ];
+        for response in responses {
+            let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+            assert_eq!(error, Error::from(InvalidDataError::NotUserAccount));
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Checks that the account checksums are computed from the account state at the requested block.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_checksums() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let mut storage = server.pool.access_storage().await?;
+        let (account_id, _) =
+            TestServer::account_id_and_tx_hash(&mut storage, BlockNumber(1)).await?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+        let mut expected_checksums = Vec::new();
+        for block_number in [BlockNumber(1), last_block] {
+            let accounts = storage
+                .chain()
+                .state_schema()
+                .load_accounts_state(block_number, &[account_id])
+                .await?;
+            expected_checksums.push(AccountChecksum {
+                account_id,
+                block_number,
+                checksum: account_checksum(account_id, block_number, &accounts[&account_id]),
+            });
+        }
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await?
+            .unwrap();
+        drop(storage);
+
+        // The last committed block is used by default, the account may be requested by its address.
         let response = client
-            .account_info(&format!("{:?}", address), "committed")
+            .account_checksum(&AccountChecksumQuery::default(), &account_id.to_string())
             .await?;
-        let account_committed_info_by_address: Account = deserialize_response_result(response)?;
-        assert_eq!(
-            account_committed_info_by_id,
-            account_committed_info_by_address
-        );
+        let checksum: AccountChecksum = deserialize_response_result(response)?;
+        assert_eq!(checksum, expected_checksums[1]);
+        let response = client
+            .account_checksum(&AccountChecksumQuery::default(), &format!("{:?}", address))
+            .await?;
+        let checksum: AccountChecksum = deserialize_response_result(response)?;
+        assert_eq!(checksum, expected_checksums[1]);
+
+        let query = AccountChecksumQuery {
+            block: Some(BlockNumber(1)),
+        };
+        let response = client
+            .account_checksum(&query, &account_id.to_string())
+            .await?;
+        let checksum: AccountChecksum = deserialize_response_result(response)?;
+        assert_eq!(checksum, expected_checksums[0]);
 
+        // Blocks that are not committed yet and unknown accounts are not found.
+        let query = AccountChecksumQuery {
+            block: Some(last_block + 1),
+        };
         let response = client
-            .account_info(&format!("{:?}", address), "finalized")
+            .account_checksum(&query, &account_id.to_string())
+            .await?;
+        assert_not_found(response, "block")?;
+        let unknown_address = format!("{:?}", Address::repeat_byte(0x42));
+        let response = client
+            .account_checksum(&AccountChecksumQuery::default(), &unknown_address)
             .await?;
-        let account_finalized_info: Option<Account> = deserialize_response_result(response)?;
+        assert_not_found(response, "account")?;
 
-        {
-            let mut storage = server.pool.access_storage().await?;
-            storage
-                .chain()
-                .mempool_schema()
-                .insert_priority_ops(
-                    &[
-                        PriorityOp {
-                            serial_id: 10,
-                            data: ZkSyncPriorityOp::Deposit(Deposit {
-                                from: Default::default(),
-                                token: TokenId(0),
-                                amount: BigUint::from(100500u64),
-                                to: address,
-                            }),
-                            deadline_block: 0,
-                            eth_hash: H256::from_slice(&[0u8; 32]),
-                            eth_block: 25,
-                            eth_block_index: Some(1),
-                        },
-                        PriorityOp {
-                            serial_id: 11,
-                            data: ZkSyncPriorityOp::Deposit(Deposit {
-                                from: Default::default(),
-                                token: TokenId(0),
-                                amount: BigUint::from(100500u64),
-                                to: address,
-                            }),
-                            deadline_block: 0,
-                            eth_hash: H256::from_slice(&[0u8; 32]),
-                            eth_block: 25,
-                            eth_block_index: Some(1),
-                        },
-                        PriorityOp {
-                            serial_id: 12,
-                            data: ZkSyncPriorityOp::Deposit(Deposit {
-                                from: Default::default(),
-                                token: TokenId(0),
-                                amount: BigUint::from(100500u64),
-                                to: address,
-                            }),
-                            deadline_block: 0,
-                            eth_hash: H256::from_slice(&[0u8; 32]),
-                            eth_block: 25,
-                            eth_block_index: Some(1),
-                        },
-                    ],
-                    false,
-                )
-                .await?;
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_exit_plan() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let mut storage = server.pool.access_storage().await?;
+        let (account_id, _) =
+            TestServer::account_id_and_tx_hash(&mut storage, BlockNumber(1)).await?;
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await?
+            .unwrap();
+        drop(storage);
+
+        // The account may be requested by its address.
+        let response = client.account_exit_plan(&account_id.to_string()).await?;
+        let plan: AccountExitPlan = deserialize_response_result(response)?;
+        assert_eq!(plan.account_id, account_id);
+        assert_eq!(plan.address, address);
+        assert!(!plan.exits.is_empty());
+        let response = client.account_exit_plan(&format!("{:?}", address)).await?;
+        let plan_by_address: AccountExitPlan = deserialize_response_result(response)?;
+        assert_eq!(plan_by_address, plan);
+
+        let unknown_address = format!("{:?}", Address::repeat_byte(0x42));
+        let response = client.account_exit_plan(&unknown_address).await?;
+        assert_not_found(response, "account")?;
+
+        server.stop().await;
+        Ok(())
+    }
+
+    fn pending_transfer(from: Address, to: Address, token: u32, amount: u64, fee: u64) -> ZkSyncTx {
+        ZkSyncTx::Transfer(Box::new(Transfer::new(
+            AccountId(1),
+            from,
+            to,
+            TokenId(token),
+            amount.into(),
+            fee.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        )))
+    }
+
+    fn pending_deposit(to: Address, token: u32, amount: u64) -> PriorityOp {
+        PriorityOp {
+            serial_id: 0,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(token),
+                amount: amount.into(),
+                to,
+            }),
+            deadline_block: 0,
+            eth_hash: H256::zero(),
+            eth_block: 0,
+            eth_block_index: None,
         }
-        let balances = vec![(
-            String::from("ETH"),
-            DepositingFunds {
-                amount: BigUint::from(301500u32),
-                expected_accept_block: 25 + server.confirmations_for_eth_event,
-            },
-        )]
+    }
+
+    fn pending_balance(
+        committed: u64,
+        incoming_transfers: u64,
+        incoming_deposits: u64,
+        outgoing: u64,
+        estimated: u64,
+    ) -> PendingBalance {
+        PendingBalance {
+            committed: committed.into(),
+            incoming_transfers: incoming_transfers.into(),
+            incoming_deposits: incoming_deposits.into(),
+            outgoing: outgoing.into(),
+            estimated: estimated.into(),
+        }
+    }
+
+    #[test]
+    fn pending_balances_contributions() {
+        let address = Address::random();
+        let other = Address::random();
+        let mut account = zksync_types::Account::default_with_address(&address);
+        account.set_balance(TokenId(0), 1000u64.into());
+        account.set_balance(TokenId(MIN_NFT_TOKEN_ID), 1u64.into());
+
+        // Committed balance only.
+        let balances = estimate_pending_balances(address, Some(&account), &[], &[]);
+        assert_eq!(
+            balances,
+            vec![(TokenId(0), pending_balance(1000, 0, 0, 0, 1000))]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>()
+        );
+
+        // Incoming transfer of a token the account doesn't have yet.
+        let incoming = pending_transfer(other, address, 1, 300, 10);
+        let balances = estimate_pending_balances(address, Some(&account), &[incoming], &[]);
+        assert_eq!(balances[&TokenId(1)], pending_balance(0, 300, 0, 0, 300));
+
+        // Pending deposit, the account isn't created yet.
+        let deposits = [
+            pending_deposit(address, 0, 500),
+            pending_deposit(other, 0, 700),
+        ];
+        let balances = estimate_pending_balances(address, None, &[], &deposits);
+        assert_eq!(balances[&TokenId(0)], pending_balance(0, 0, 500, 0, 500));
+
+        // Outgoing transfer with the fee.
+        let outgoing = pending_transfer(address, other, 0, 200, 5);
+        let balances = estimate_pending_balances(address, Some(&account), &[outgoing], &[]);
+        assert_eq!(balances[&TokenId(0)], pending_balance(1000, 0, 0, 205, 795));
+
+        // Forced exit of the account withdraws its whole balance, the fee is paid by the initiator.
+        let forced_exit = ZkSyncTx::ForcedExit(Box::new(ForcedExit::new(
+            AccountId(2),
+            address,
+            TokenId(0),
+            7u64.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        )));
+        let balances = estimate_pending_balances(address, Some(&account), &[forced_exit], &[]);
+        assert_eq!(balances[&TokenId(0)], pending_balance(1000, 0, 0, 1000, 0));
+
+        // Transactions of other accounts are ignored.
+        let unrelated = pending_transfer(other, Address::random(), 0, 100, 1);
+        let balances = estimate_pending_balances(address, Some(&account), &[unrelated], &[]);
+        assert_eq!(balances[&TokenId(0)], pending_balance(1000, 0, 0, 0, 1000));
+    }
+
+    #[test]
+    fn pending_balances_netting() {
+        let address = Address::random();
+        let other = Address::random();
+        let mut account = zksync_types::Account::default_with_address(&address);
+        account.set_balance(TokenId(0), 100u64.into());
+
+        let txs = [
+            pending_transfer(other, address, 0, 50, 1),
+            pending_transfer(address, other, 0, 120, 3),
+            pending_transfer(address, other, 1, 10, 1),
+        ];
+        let deposits = [pending_deposit(address, 0, 30)];
+        let balances = estimate_pending_balances(address, Some(&account), &txs, &deposits);
+        // 100 + 50 + 30 - 123
+        assert_eq!(balances[&TokenId(0)], pending_balance(100, 50, 30, 123, 57));
+        // Spending more than the account has results in zero, not in an underflow.
+        assert_eq!(balances[&TokenId(1)], pending_balance(0, 0, 0, 11, 0));
+    }
+
+    fn nonces(values: &[u32]) -> Vec<Nonce> {
+        values.iter().copied().map(Nonce).collect()
+    }
+
+    #[test]
+    fn nonce_suggestion_contiguous() {
+        // No pending transactions.
+        assert_eq!(suggest_nonce(Nonce(5), &[]), Nonce(5));
+        // Pending transactions continue the committed nonce, in any order.
+        assert_eq!(suggest_nonce(Nonce(5), &nonces(&[5, 6, 7])), Nonce(8));
+        assert_eq!(suggest_nonce(Nonce(5), &nonces(&[7, 5, 6])), Nonce(8));
+        // Duplicates (e.g. a replacement of the same nonce) are counted once.
+        assert_eq!(suggest_nonce(Nonce(0), &nonces(&[0, 0, 1])), Nonce(2));
+        // Stale transactions with the already used nonces are ignored.
+        assert_eq!(suggest_nonce(Nonce(5), &nonces(&[3, 4, 5])), Nonce(6));
+    }
+
+    #[test]
+    fn nonce_suggestion_stops_at_gap() {
+        // The run stops at the first missing nonce.
+        assert_eq!(suggest_nonce(Nonce(5), &nonces(&[5, 6, 8, 9])), Nonce(7));
+        // Pending transactions that don't start at the committed nonce don't advance it.
+        assert_eq!(suggest_nonce(Nonce(5), &nonces(&[6, 7])), Nonce(5));
+    }
+
+    /// Account holding two valuable balances, dust in several tokens and a token without a price.
+    fn dust_fixture() -> (
+        BTreeMap<String, BigUintSerdeWrapper>,
+        Vec<Token>,
+        HashMap<TokenId, TokenPrice>,
+    ) {
+        let token = |id: u32, symbol: &str, decimals: u8| {
+            Token::new(
+                TokenId(id),
+                Address::repeat_byte(id as u8),
+                symbol,
+                decimals,
+                zksync_types::TokenKind::ERC20,
+            )
+        };
+        let price = |usd_price: u64| TokenPrice {
+            usd_price: Ratio::from_integer(BigUint::from(usd_price)),
+            last_updated: Utc::now(),
+        };
+        let tokens = vec![
+            token(0, "ETH", 18),
+            token(1, "USDC", 6),
+            token(2, "DAI", 18),
+            token(3, "WBTC", 8),
+            token(4, "SPAM", 18),
+            token(5, "USDT", 6),
+        ];
+        let balances = vec![
+            // 0.5 ETH, $1000.
+            ("ETH", 500_000_000_000_000_000u64),
+            // 5 USDC, $5.
+            ("USDC", 5_000_000),
+            // 0.01 DAI, $0.01.
+            ("DAI", 10_000_000_000_000_000),
+            // 1 satoshi, $0.0003.
+            ("WBTC", 1),
+            // No price is known.
+            ("SPAM", 1_000_000),
+            // 5 USDT, $5.
+            ("USDT", 5_000_000),
+        ]
         .into_iter()
+        .map(|(symbol, balance)| (symbol.to_owned(), BigUint::from(balance).into()))
         .collect();
-        let expected_depositing = DepositingAccountBalances { balances };
+        let prices = vec![
+            (TokenId(0), price(2000)),
+            (TokenId(1), price(1)),
+            (TokenId(2), price(1)),
+            (TokenId(3), price(30000)),
+            (TokenId(5), price(1)),
+        ]
+        .into_iter()
+        .collect();
+        (balances, tokens, prices)
+    }
 
-        let response = client.account_full_info(&format!("{:?}", address)).await?;
-        let account_full_info: AccountState = deserialize_response_result(response)?;
+    #[test]
+    fn dust_balances_are_filtered() {
+        let (mut balances, tokens, prices) = dust_fixture();
+        let min_balance_usd = Ratio::from_integer(BigUint::from(1u32));
+
+        let order = filter_dust_balances(&mut balances, &tokens, &prices, &min_balance_usd);
+        // Equally valued balances are ordered by the symbol, the one without a price goes last.
+        assert_eq!(order, vec!["ETH", "USDC", "USDT", "SPAM"]);
+        let symbols: Vec<_> = balances.keys().cloned().collect();
+        assert_eq!(symbols, vec!["ETH", "SPAM", "USDC", "USDT"]);
+    }
+
+    #[test]
+    fn zero_min_balance_only_sorts() {
+        let (mut balances, tokens, prices) = dust_fixture();
+
+        let min_balance_usd = Ratio::from_integer(BigUint::from(0u32));
+        let order = filter_dust_balances(&mut balances, &tokens, &prices, &min_balance_usd);
+        assert_eq!(order, vec!["ETH", "USDC", "USDT", "DAI", "WBTC", "SPAM"]);
+        assert_eq!(balances.len(), tokens.len());
+    }
+
+    #[test]
+    fn balances_query_parsing() {
+        let filter = BalancesFilter::parse(BalancesQuery {
+            min_balance_usd: Some(0.5),
+            tokens: Some("ETH, 1,,0x0101010101010101010101010101010101010101".to_owned()),
+        })
+        .unwrap();
         assert_eq!(
-            account_full_info.committed,
-            Some(account_committed_info_by_id)
+            filter.min_balance_usd,
+            Some(Ratio::new(BigUint::from(1u32), BigUint::from(2u32)))
+        );
+        assert_eq!(
+            filter.tokens,
+            Some(vec![
+                TokenLike::Symbol("ETH".to_owned()),
+                TokenLike::Id(TokenId(1)),
+                TokenLike::Address(Address::repeat_byte(1)),
+            ])
         );
-        assert_eq!(account_full_info.finalized, account_finalized_info);
-        assert_eq!(account_full_info.depositing, expected_depositing);
 
-        let query = PaginationQuery {
-            from: ApiEither::from(tx_hash),
-            limit: 1,
-            direction: PaginationDirection::Newer,
-        };
-        let response = client.account_txs(&query, &account_id.to_string()).await?;
-        let txs: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
-        assert_eq!(txs.list[0].tx_hash, tx_hash);
+        for value in vec![-1.0, f64::NAN, f64::INFINITY] {
+            let query = BalancesQuery {
+                min_balance_usd: Some(value),
+                tokens: None,
+            };
+            assert!(BalancesFilter::parse(query).is_err());
+        }
+    }
 
-        let query = PaginationQuery {
-            from: ApiEither::from_str("latest").unwrap(),
-            limit: 2,
-            direction: PaginationDirection::Older,
-        };
-        let response = client
-            .account_pending_txs(&query, &account_id.to_string())
+    async fn account_info(
+        data: &ApiAccountData,
+        storage: &mut StorageProcessor<'_>,
+        account: zksync_types::Account,
+        account_id: AccountId,
+    ) -> Account {
+        data.api_account(
+            account,
+            account_id,
+            BlockNumber(1),
+            &BalancesFilter::default(),
+            storage,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Checks the type of the account reported by the account endpoints: keyless accounts
+    /// are reported as `NoKey`, the rest have the type set by their latest `ChangePubKey`.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_types() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let data = ApiAccountData::new(
+            cfg.replicated_pool(),
+            cfg.token_cache(),
+            dummy_fee_ticker(&[], None),
+            cfg.config.eth_watch.confirmations_for_eth_event,
+            cfg.config
+                .chain
+                .state_keeper
+                .rejected_txs_retention_period(),
+            SpecialAccounts::new(cfg.config.chain.state_keeper.fee_account_addr),
+            AccountLocks::new(cfg.pool.clone()),
+        );
+        let mut storage = cfg.pool.access_storage().await?;
+        // The changes are rolled back once the transaction is dropped.
+        let mut transaction = storage.start_transaction().await?;
+        let account_id = transaction
+            .chain()
+            .account_schema()
+            .next_free_account_id()
             .await?;
-        let txs: Paginated<Transaction, SerialId> = deserialize_response_result(response)?;
-        assert_eq!(txs.list.len(), 2);
-        match &txs.list[0].op {
-            TransactionData::L1(tx) => match tx {
-                L1Transaction::Deposit(deposit) => {
-                    assert_eq!(deposit.id, 12);
-                }
-                _ => panic!("should return deposit"),
-            },
-            _ => panic!("account_pending_txs returned L2 tx"),
-        }
+        let keyless = zksync_types::Account::default_with_address(&Address::random());
+        let mut with_key = keyless.clone();
+        with_key.pub_key_hash = PubKeyHash { data: [1; 20] };
+
+        let info = account_info(&data, &mut transaction, keyless.clone(), account_id).await;
+        assert_eq!(info.account_type, Some(EthAccountType::NoKey));
+        assert_eq!(
+            serde_json::to_value(&info)?["accountType"],
+            serde_json::json!("NoKey")
+        );
+        // Accounts which set their keys before the types were tracked are owned.
+        let info = account_info(&data, &mut transaction, with_key.clone(), account_id).await;
+        assert_eq!(info.account_type, Some(EthAccountType::Owned));
+
+        transaction
+            .chain()
+            .account_schema()
+            .set_account_type(
+                account_id,
+                zksync_storage::chain::account::records::EthAccountType::CREATE2,
+            )
+            .await?;
+        let info = account_info(&data, &mut transaction, with_key, account_id).await;
+        assert_eq!(info.account_type, Some(EthAccountType::CREATE2));
+        assert_eq!(
+            serde_json::to_value(&info)?["accountType"],
+            serde_json::json!("CREATE2")
+        );
+        // The stored type doesn't matter until the key is set.
+        let info = account_info(&data, &mut transaction, keyless, account_id).await;
+        assert_eq!(info.account_type, Some(EthAccountType::NoKey));
 
-        server.stop().await;
         Ok(())
     }
 }
@@ -0,0 +1,82 @@
+//! Snapshots of the accounts state taken every few finalized blocks.
+//!
+//! The historical state of the account (e.g. for `accounts/{id}/checksum`) is restored from its
+//! nearest snapshot and the updates made after it, instead of replaying the whole history.
+//! Snapshots contain only the accounts updated since the previous snapshot, so the accounts that
+//! weren't updated for a long time are restored from their older snapshots.
+//! Reverted blocks are removed from the snapshots by the block schema.
+
+// Built-in uses
+use std::time::Duration;
+
+// External uses
+use tokio::time;
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::BlockNumber;
+
+/// Takes the account snapshots at the finalized blocks once per interval.
+#[derive(Debug, Clone)]
+pub struct AccountSnapshotWriter {
+    pool: ConnectionPool,
+    snapshot_blocks: u32,
+    interval: Duration,
+}
+
+impl AccountSnapshotWriter {
+    /// Snapshots are stored using the main database, since the replicas are read-only.
+    pub fn new(pool: ConnectionPool, config: &RestApiConfig) -> Self {
+        Self {
+            pool,
+            snapshot_blocks: config.account_snapshot_blocks,
+            interval: config.account_snapshot_interval(),
+        }
+    }
+
+    /// Takes the snapshots at every `snapshot_blocks`-th block up to the last finalized one.
+    /// Returns the block of the last snapshot.
+    pub async fn take_snapshots(&self) -> anyhow::Result<BlockNumber> {
+        let mut storage = self.pool.access_storage().await?;
+        let mut last_snapshot = storage
+            .chain()
+            .state_schema()
+            .last_account_snapshot_block()
+            .await?;
+        if self.snapshot_blocks == 0 {
+            return Ok(last_snapshot);
+        }
+        let last_finalized = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+
+        while last_snapshot + self.snapshot_blocks <= last_finalized {
+            let snapshot_block = last_snapshot + self.snapshot_blocks;
+            storage
+                .chain()
+                .state_schema()
+                .take_account_snapshot(snapshot_block)
+                .await?;
+            last_snapshot = snapshot_block;
+        }
+        Ok(last_snapshot)
+    }
+
+    /// Takes the snapshots forever. Failed snapshots are retried on the next tick,
+    /// the historical state is restored from the older snapshots in the meantime.
+    pub async fn run(self) {
+        let mut timer = time::interval(self.interval);
+        loop {
+            timer.tick().await;
+            match self.take_snapshots().await {
+                Ok(last_snapshot) => {
+                    vlog::debug!("Account snapshots are taken up to block {}", last_snapshot)
+                }
+                Err(err) => vlog::warn!("Unable to take the account snapshots: {}", err),
+            }
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Admission control in front of the storage pool.
+//!
+//! Under load, the handlers waiting for a database connection pile up and every request gets slow.
+//! Instead, every scope admits a limited number of requests at a time, roughly matching the size
+//! of the pool, and the requests that can't be admitted within a short wait are rejected with
+//! `503 Service Unavailable`, so the clients can retry them later. Submissions are admitted within
+//! a separate budget, so they are not starved by the reads.
+
+// Built-in uses
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// External uses
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::Method,
+    HttpRequest, Responder,
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+
+// Local uses
+use super::{
+    error::{Error, StoragePoolExhausted},
+    response::ApiResult,
+};
+
+/// Retry of the rejected requests is suggested after this number of seconds.
+const RETRY_AFTER_SECS: u64 = 1;
+
+/// Limits the number of requests of the API scope handled at the same time,
+/// meant to be used with `Scope::wrap_fn`.
+#[derive(Debug, Clone)]
+pub struct StorageAdmission {
+    scope: &'static str,
+    reads: Arc<Semaphore>,
+    /// Budget of the submissions (`POST` requests), if the scope accepts them.
+    submissions: Option<Arc<Semaphore>>,
+    max_wait: Duration,
+}
+
+impl StorageAdmission {
+    pub fn new(scope: &'static str, config: &RestApiConfig) -> Self {
+        Self {
+            scope,
+            reads: Arc::new(Semaphore::new(config.storage_permits)),
+            submissions: None,
+            max_wait: config.storage_permit_wait(),
+        }
+    }
+
+    /// Admits the `POST` requests of the scope within the separate budget.
+    pub fn with_submissions(mut self, config: &RestApiConfig) -> Self {
+        self.submissions = Some(Arc::new(Semaphore::new(config.submission_storage_permits)));
+        self
+    }
+
+    /// Handles the request once it's admitted.
+    pub fn admit<S, B>(
+        &self,
+        req: ServiceRequest,
+        service: &S,
+    ) -> impl Future<Output = Result<ServiceResponse<B>, actix_web::Error>>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    {
+        let (permits, kind) = match &self.submissions {
+            Some(submissions) if req.method() == Method::POST => (submissions.clone(), "submit"),
+            _ => (self.reads.clone(), "read"),
+        };
+        let scope = self.scope;
+        let max_wait = self.max_wait;
+        let http_request = req.request().clone();
+        // The handler doesn't run until the response is awaited.
+        let response = service.call(req);
+
+        async move {
+            let start = Instant::now();
+            let permit = acquire(permits, max_wait).await;
+            metrics::histogram!(
+                "api.admission.wait",
+                start.elapsed(),
+                "scope" => scope,
+                "kind" => kind
+            );
+
+            match permit {
+                Some(_permit) => response.await,
+                None => {
+                    metrics::increment_counter!(
+                        "api.admission.rejected",
+                        "scope" => scope,
+                        "kind" => kind
+                    );
+                    Err(rejection(&http_request))
+                }
+            }
+        }
+    }
+}
+
+async fn acquire(permits: Arc<Semaphore>, max_wait: Duration) -> Option<OwnedSemaphorePermit> {
+    tokio::time::timeout(max_wait, permits.acquire_owned())
+        .await
+        .ok()
+        .map(|permit| permit.expect("Admission semaphore is never closed"))
+}
+
+/// Renders the rejection as a regular API response.
+fn rejection(req: &HttpRequest) -> actix_web::Error {
+    let error = StoragePoolExhausted {
+        retry_after_secs: RETRY_AFTER_SECS,
+    };
+    let response = ApiResult::<()>::from(Error::from(error)).respond_to(req);
+    InternalError::from_response("storage pool is exhausted", response).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{error::ErrorCode, SharedData};
+    use actix_web::{web, App, HttpResponse};
+    use futures::future::join_all;
+    use reqwest::StatusCode;
+    use zksync_api_types::v02::{ApiVersion, Response};
+    use zksync_types::network::Network;
+
+    /// Number of connections of the simulated storage pool.
+    const POOL_SIZE: usize = 2;
+    /// Time the handler holds the connection.
+    const QUERY_TIME: Duration = Duration::from_millis(50);
+    /// Number of concurrent requests in the load.
+    const LOAD_SIZE: usize = 20;
+
+    fn admission(reads: usize, submissions: usize) -> StorageAdmission {
+        StorageAdmission {
+            scope: "test",
+            reads: Arc::new(Semaphore::new(reads)),
+            submissions: Some(Arc::new(Semaphore::new(submissions))),
+            max_wait: Duration::from_millis(10),
+        }
+    }
+
+    async fn query(pool: web::Data<Semaphore>) -> HttpResponse {
+        let _connection = pool.acquire().await.unwrap();
+        actix_rt::time::sleep(QUERY_TIME).await;
+        HttpResponse::Ok().finish()
+    }
+
+    /// Starts the server with the handlers sharing the simulated storage pool.
+    fn start_server(admission: StorageAdmission) -> actix_test::TestServer {
+        let pool = Arc::new(Semaphore::new(POOL_SIZE));
+        actix_test::start(move || {
+            let admission = admission.clone();
+            let scope = web::scope("/api/v0.2/scope")
+                .app_data(web::Data::from(pool.clone()))
+                .route("/query", web::get().to(query))
+                .route("/query", web::post().to(query))
+                .wrap_fn(move |req, srv| admission.admit(req, srv));
+            App::new()
+                .app_data(web::Data::new(SharedData {
+                    net: Network::Localhost,
+                    api_version: ApiVersion::V02,
+                    not_found_status: StatusCode::OK,
+                }))
+                .service(scope)
+        })
+    }
+
+    /// Sends the concurrent requests, returns their responses and the maximum latency.
+    async fn load(
+        server: &actix_test::TestServer,
+        methods: Vec<reqwest::Method>,
+    ) -> (Vec<reqwest::Response>, Duration) {
+        let client = reqwest::Client::new();
+        let url = server.url("/api/v0.2/scope/query");
+        let start = Instant::now();
+        let responses = join_all(methods.into_iter().map(|method| {
+            let request = client.request(method, &url).send();
+            async move {
+                let response = request.await.unwrap();
+                (response, start.elapsed())
+            }
+        }))
+        .await;
+
+        let max_latency = responses.iter().map(|(_, latency)| *latency).max().unwrap();
+        let responses = responses
+            .into_iter()
+            .map(|(response, _)| response)
+            .collect();
+        (responses, max_latency)
+    }
+
+    #[actix_rt::test]
+    async fn overloaded_scope_fails_fast() {
+        let reads = vec![reqwest::Method::GET; LOAD_SIZE];
+
+        // Without the admission control all the requests wait for the pool.
+        let server = start_server(admission(LOAD_SIZE, LOAD_SIZE));
+        let (responses, unlimited_latency) = load(&server, reads.clone()).await;
+        assert!(responses
+            .iter()
+            .all(|response| response.status() == StatusCode::OK));
+        assert!(unlimited_latency >= QUERY_TIME * (LOAD_SIZE / POOL_SIZE) as u32);
+
+        // With the admission control, the requests beyond the pool size are rejected at once.
+        let server = start_server(admission(POOL_SIZE, 1));
+        let (responses, limited_latency) = load(&server, reads).await;
+        assert!(limited_latency * 2 < unlimited_latency);
+        let mut admitted = 0;
+        for response in responses {
+            if response.status() == StatusCode::OK {
+                admitted += 1;
+                continue;
+            }
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(response.headers()[reqwest::header::RETRY_AFTER], "1");
+            let response: Response = response.json().await.unwrap();
+            let code: ErrorCode =
+                serde_json::from_value(response.error.unwrap()["code"].clone()).unwrap();
+            assert_eq!(code, ErrorCode::StoragePoolExhausted);
+        }
+        assert_eq!(admitted, POOL_SIZE);
+    }
+
+    #[actix_rt::test]
+    async fn submissions_are_not_starved() {
+        let server = start_server(admission(POOL_SIZE, 1));
+        let mut methods = vec![reqwest::Method::GET; LOAD_SIZE];
+        methods.push(reqwest::Method::POST);
+
+        let (responses, _) = load(&server, methods).await;
+        let submission = responses.last().unwrap();
+        assert_eq!(submission.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,376 @@
+//! Rendering of the token amounts in the responses.
+//!
+//! Amounts are serialized as integer strings in the token base units. With `amountsIn=decimal`
+//! the amounts of transfers, withdrawals and other operations, their fees and the account balances
+//! are rendered as decimal strings adjusted by the token decimals, e.g. `1500000` of a token with
+//! 6 decimals becomes `1.5`. The conversion only moves the decimal point, so no precision is lost.
+//!
+//! Decimals are taken from the tokens cache. Amounts of the tokens unknown to the server are left
+//! in base units, and the object containing them is marked with `"amountsInUnits": true`.
+
+// Built-in uses
+use std::collections::HashMap;
+
+// External uses
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+// Workspace uses
+use zksync_api_types::v02::AmountsFormat;
+use zksync_storage::{AccessIntent, ReplicatedPool};
+use zksync_types::{TokenId, TokenLike};
+use zksync_utils::format_units;
+
+// Local uses
+use super::error::Error;
+use crate::utils::token_cache::TokenCache;
+
+/// Top-level fields of the fee responses, denominated in the token requested by the client.
+const FEE_FIELDS: [&str; 3] = ["gasFee", "zkpFee", "totalFee"];
+/// Marks the objects with amounts left in base units because the token is unknown.
+const UNITS_FLAG: &str = "amountsInUnits";
+
+/// Serializes the response and renders its amounts in the requested format.
+///
+/// `fee_token` is the token of the fee responses, which don't mention the token themselves.
+pub async fn render_amounts<R: Serialize>(
+    result: Result<R, Error>,
+    format: AmountsFormat,
+    tokens: &TokenCache,
+    pool: &ReplicatedPool,
+    fee_token: Option<TokenLike>,
+) -> Result<Value, Error> {
+    let mut value =
+        serde_json::to_value(result?).map_err(|err| Error::from(anyhow::Error::from(err)))?;
+
+    let mut decimals = HashMap::new();
+    if format == AmountsFormat::Decimal {
+        let mut requested = Vec::new();
+        collect_tokens(&value, &mut requested);
+        requested.extend(fee_token.clone());
+
+        if !requested.is_empty() {
+            let mut storage = pool
+                .access_storage(AccessIntent::Read)
+                .await
+                .map_err(Error::storage)?;
+            for token in requested {
+                if decimals.contains_key(&token) {
+                    continue;
+                }
+                let token_decimals = tokens
+                    .get_token(&mut storage, token.clone())
+                    .await
+                    .map_err(Error::storage)?
+                    .map(|token| token.decimals);
+                decimals.insert(token, token_decimals);
+            }
+        }
+    }
+
+    render(&mut value, format, &decimals, fee_token.as_ref());
+    Ok(value)
+}
+
+/// Renders the amounts of the serialized response, `decimals` has to contain every token
+/// found by `collect_tokens`, `None` stands for an unknown token.
+fn render(
+    value: &mut Value,
+    format: AmountsFormat,
+    decimals: &HashMap<TokenLike, Option<u8>>,
+    fee_token: Option<&TokenLike>,
+) {
+    if format == AmountsFormat::Units {
+        return;
+    }
+
+    if let (Some(fee_token), Value::Object(fields)) = (fee_token, &mut *value) {
+        let token_decimals = decimals.get(fee_token).copied().flatten();
+        let mut rendered = true;
+        for field in FEE_FIELDS.iter() {
+            if let Some(amount) = fields.get_mut(*field) {
+                rendered &= render_amount(amount, token_decimals);
+            }
+        }
+        if !rendered {
+            fields.insert(UNITS_FLAG.to_owned(), Value::Bool(true));
+        }
+    }
+    render_value(value, decimals);
+}
+
+fn render_value(value: &mut Value, decimals: &HashMap<TokenLike, Option<u8>>) {
+    match value {
+        Value::Object(fields) => {
+            let lookup = |token: Option<TokenLike>| {
+                token.map(|token| decimals.get(&token).copied().flatten())
+            };
+            let mut rendered = true;
+
+            let (amount_token, fee_token) = amount_tokens(fields);
+            if let (Some(token_decimals), Some(amount)) =
+                (lookup(amount_token), fields.get_mut("amount"))
+            {
+                rendered &= render_amount(amount, token_decimals);
+            }
+            if let (Some(token_decimals), Some(fee)) = (lookup(fee_token), fields.get_mut("fee")) {
+                rendered &= render_amount(fee, token_decimals);
+            }
+
+            for (field, nested) in fields.iter_mut() {
+                match (field.as_str(), nested) {
+                    // Balances are keyed by the token symbol.
+                    ("balances", Value::Object(balances)) => {
+                        for (symbol, balance) in balances.iter_mut() {
+                            let token_decimals =
+                                lookup(Some(TokenLike::Symbol(symbol.clone()))).flatten();
+                            match balance {
+                                Value::Object(parts) => {
+                                    for part in parts.values_mut() {
+                                        rendered &= render_amount(part, token_decimals);
+                                    }
+                                }
+                                _ => rendered &= render_amount(balance, token_decimals),
+                            }
+                        }
+                    }
+                    (_, nested) => render_value(nested, decimals),
+                }
+            }
+
+            if !rendered {
+                fields.insert(UNITS_FLAG.to_owned(), Value::Bool(true));
+            }
+        }
+        Value::Array(values) => {
+            for nested in values {
+                render_value(nested, decimals);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the tokens `render_value` needs the decimals of.
+fn collect_tokens(value: &Value, tokens: &mut Vec<TokenLike>) {
+    match value {
+        Value::Object(fields) => {
+            let (amount_token, fee_token) = amount_tokens(fields);
+            if fields.contains_key("amount") {
+                tokens.extend(amount_token);
+            }
+            if fields.contains_key("fee") {
+                tokens.extend(fee_token);
+            }
+
+            for (field, nested) in fields {
+                match (field.as_str(), nested) {
+                    ("balances", Value::Object(balances)) => tokens.extend(
+                        balances
+                            .keys()
+                            .map(|symbol| TokenLike::Symbol(symbol.clone())),
+                    ),
+                    (_, nested) => collect_tokens(nested, tokens),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for nested in values {
+                collect_tokens(nested, tokens);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the tokens of the `amount` and `fee` fields of the operation.
+fn amount_tokens(fields: &Map<String, Value>) -> (Option<TokenLike>, Option<TokenLike>) {
+    let token_id = |field: &str| {
+        fields
+            .get(field)
+            .and_then(Value::as_u64)
+            .map(|id| TokenLike::Id(TokenId(id as u32)))
+    };
+    let amount_token = token_id("token").or_else(|| token_id("tokenId"));
+    let fee_token = token_id("feeToken").or_else(|| amount_token.clone());
+    (amount_token, fee_token)
+}
+
+/// Renders the amount in base units as a decimal string,
+/// returns `false` if the amount was left in base units since the decimals are unknown.
+fn render_amount(amount: &mut Value, decimals: Option<u8>) -> bool {
+    let units = match amount {
+        Value::String(units) if !units.is_empty() && units.chars().all(|c| c.is_ascii_digit()) => {
+            units
+        }
+        // Not an amount in base units, e.g. `null` for the unknown fee.
+        _ => return true,
+    };
+    match decimals {
+        Some(0) => true,
+        Some(decimals) => {
+            *units = format_units(units.as_str(), decimals);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transfer() -> Value {
+        json!({
+            "txHash": "sync-tx:0000000000000000000000000000000000000000000000000000000000000001",
+            "op": {
+                "type": "Transfer",
+                "accountId": 1,
+                "token": 1,
+                "amount": "1500000",
+                "fee": "12345",
+                "nonce": 0
+            }
+        })
+    }
+
+    fn change_pubkey() -> Value {
+        json!({
+            "type": "ChangePubKey",
+            "feeToken": 0,
+            "fee": "1000000000000000",
+            "nonce": 0
+        })
+    }
+
+    fn account() -> Value {
+        json!({
+            "accountId": 1,
+            "balances": {
+                "ETH": "1000000000000000001",
+                "USDC": "1500000",
+                "MLTT": "100"
+            },
+            "depositing": {
+                "balances": {
+                    "USDC": { "amount": "25", "expectedAcceptBlock": 10 }
+                }
+            }
+        })
+    }
+
+    fn fee() -> Value {
+        json!({
+            "gasFee": "1250000",
+            "zkpFee": "0",
+            "totalFee": "1250000",
+            "quote": { "tokenId": 1, "gasFee": "1250000" }
+        })
+    }
+
+    fn decimals() -> HashMap<TokenLike, Option<u8>> {
+        vec![
+            (TokenLike::Id(TokenId(0)), Some(18)),
+            (TokenLike::Id(TokenId(1)), Some(6)),
+            (TokenLike::Symbol("ETH".to_owned()), Some(18)),
+            (TokenLike::Symbol("USDC".to_owned()), Some(6)),
+            (TokenLike::Symbol("MLTT".to_owned()), None),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn render_in(format: AmountsFormat, mut value: Value, fee_token: Option<TokenLike>) -> Value {
+        let mut tokens = Vec::new();
+        collect_tokens(&value, &mut tokens);
+        tokens.extend(fee_token.clone());
+        let decimals = decimals();
+        assert!(tokens.iter().all(|token| decimals.contains_key(token)));
+
+        render(&mut value, format, &decimals, fee_token.as_ref());
+        value
+    }
+
+    #[test]
+    fn amounts_in_decimal() {
+        let render = |value, fee_token| render_in(AmountsFormat::Decimal, value, fee_token);
+
+        assert_eq!(
+            render(transfer(), None),
+            json!({
+                "txHash": "sync-tx:0000000000000000000000000000000000000000000000000000000000000001",
+                "op": {
+                    "type": "Transfer",
+                    "accountId": 1,
+                    "token": 1,
+                    "amount": "1.5",
+                    "fee": "0.012345",
+                    "nonce": 0
+                }
+            })
+        );
+        assert_eq!(
+            render(change_pubkey(), None),
+            json!({
+                "type": "ChangePubKey",
+                "feeToken": 0,
+                "fee": "0.001",
+                "nonce": 0
+            })
+        );
+
+        // Amounts of the unknown tokens are left in base units.
+        assert_eq!(
+            render(account(), None),
+            json!({
+                "accountId": 1,
+                "balances": {
+                    "ETH": "1.000000000000000001",
+                    "USDC": "1.5",
+                    "MLTT": "100"
+                },
+                "amountsInUnits": true,
+                "depositing": {
+                    "balances": {
+                        "USDC": { "amount": "0.000025", "expectedAcceptBlock": 10 }
+                    }
+                }
+            })
+        );
+
+        // Signed quotes are returned as is.
+        assert_eq!(
+            render(fee(), Some(TokenLike::Id(TokenId(1)))),
+            json!({
+                "gasFee": "1.25",
+                "zkpFee": "0.0",
+                "totalFee": "1.25",
+                "quote": { "tokenId": 1, "gasFee": "1250000" }
+            })
+        );
+        assert_eq!(
+            render(fee(), Some(TokenLike::Symbol("MLTT".to_owned()))),
+            json!({
+                "gasFee": "1250000",
+                "zkpFee": "0",
+                "totalFee": "1250000",
+                "amountsInUnits": true,
+                "quote": { "tokenId": 1, "gasFee": "1250000" }
+            })
+        );
+    }
+
+    #[test]
+    fn amounts_in_units() {
+        let render = |value, fee_token| render_in(AmountsFormat::Units, value, fee_token);
+
+        assert_eq!(render(transfer(), None), transfer());
+        assert_eq!(render(change_pubkey(), None), change_pubkey());
+        assert_eq!(render(account(), None), account());
+        assert_eq!(
+            render(fee(), Some(TokenLike::Symbol("MLTT".to_owned()))),
+            fee()
+        );
+    }
+}
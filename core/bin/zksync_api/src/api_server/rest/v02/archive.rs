@@ -0,0 +1,182 @@
+//! Lookups of the old executed transactions moved out of the main database.
+//!
+//! The archive database has the same schema as the main one, so the same queries are used.
+//! It's only queried when the main database doesn't know about the transaction, and the
+//! queries are bounded by a short timeout, so the unknown hashes don't slow the API down.
+
+// Built-in uses
+use std::{future::Future, time::Duration};
+
+// Workspace uses
+use zksync_api_types::v02::transaction::{Receipt, TxData};
+use zksync_config::configs::api::RestApiConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::tx::TxHash;
+
+// Local uses
+use super::error::Error;
+
+/// Archive database holding the old executed transactions.
+#[derive(Debug, Clone)]
+pub struct TxArchive {
+    pool: ConnectionPool,
+    query_timeout: Duration,
+}
+
+impl TxArchive {
+    /// Connects to the archive database, returns `None` if the archive is not configured.
+    pub fn from_config(config: &RestApiConfig) -> Option<Self> {
+        let url = config.archive_url()?;
+        Some(Self::new(
+            ConnectionPool::new_with_url(url, None),
+            config.archive_query_timeout(),
+        ))
+    }
+
+    pub fn new(pool: ConnectionPool, query_timeout: Duration) -> Self {
+        Self {
+            pool,
+            query_timeout,
+        }
+    }
+
+    /// Returns the receipt of the archived transaction or priority operation.
+    pub async fn tx_receipt(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error> {
+        let receipt = self
+            .with_timeout("tx_receipt", async {
+                let mut storage = self
+                    .pool
+                    .try_access_storage()
+                    .await
+                    .map_err(Error::storage)?;
+                storage
+                    .chain()
+                    .operations_ext_schema()
+                    .tx_receipt_api_v02(tx_hash.as_ref())
+                    .await
+                    .map_err(Error::storage)
+            })
+            .await?;
+
+        Ok(receipt.map(|receipt| match receipt {
+            Receipt::L1(mut receipt) => {
+                receipt.archived = true;
+                Receipt::L1(receipt)
+            }
+            Receipt::L2(mut receipt) => {
+                receipt.archived = true;
+                Receipt::L2(receipt)
+            }
+        }))
+    }
+
+    /// Returns the data of the archived transaction or priority operation.
+    pub async fn tx_data(&self, tx_hash: TxHash) -> Result<Option<TxData>, Error> {
+        let data = self
+            .with_timeout("tx_data", async {
+                let mut storage = self
+                    .pool
+                    .try_access_storage()
+                    .await
+                    .map_err(Error::storage)?;
+                storage
+                    .chain()
+                    .operations_ext_schema()
+                    .tx_data_api_v02(tx_hash.as_ref())
+                    .await
+                    .map_err(Error::storage)
+            })
+            .await?;
+
+        Ok(data.map(|mut data| {
+            data.archived = true;
+            data
+        }))
+    }
+
+    /// Runs the query within the timeout. The query that doesn't complete in time
+    /// is considered a miss, the same as the transaction unknown to the archive.
+    async fn with_timeout<T, F>(&self, query: &'static str, future: F) -> Result<Option<T>, Error>
+    where
+        F: Future<Output = Result<Option<T>, Error>>,
+    {
+        match tokio::time::timeout(self.query_timeout, future).await {
+            Ok(result) => result,
+            Err(_) => {
+                metrics::increment_counter!("api.archive.timeout", "query" => query);
+                vlog::warn!(
+                    "Archive query `{}` did not complete within {:?}",
+                    query,
+                    self.query_timeout
+                );
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::TestServerConfig;
+    use std::str::FromStr;
+    use zksync_api_types::v02::transaction::TxInBlockStatus;
+    use zksync_types::BlockNumber;
+
+    async fn executed_tx_hash(cfg: &TestServerConfig) -> anyhow::Result<TxHash> {
+        let mut storage = cfg.pool.access_storage().await?;
+        let transactions = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions(BlockNumber(1))
+            .await?;
+        Ok(TxHash::from_str(&transactions[0].tx_hash).unwrap())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn archived_transactions_are_flagged() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let tx_hash = executed_tx_hash(&cfg).await?;
+
+        // The test database plays the role of the archive.
+        let archive = TxArchive::new(cfg.pool.clone(), Duration::from_secs(10));
+        let receipt = archive.tx_receipt(tx_hash).await?.unwrap();
+        match receipt {
+            Receipt::L2(receipt) => {
+                assert_eq!(receipt.tx_hash, tx_hash);
+                assert_eq!(receipt.status, TxInBlockStatus::Finalized);
+                assert!(receipt.archived);
+            }
+            Receipt::L1(_) => panic!("L2 transaction is expected"),
+        }
+        let data = archive.tx_data(tx_hash).await?.unwrap();
+        assert_eq!(data.tx.tx_hash, tx_hash);
+        assert!(data.archived);
+
+        let unknown_tx_hash = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.hash();
+        assert!(archive.tx_receipt(unknown_tx_hash).await?.is_none());
+        assert!(archive.tx_data(unknown_tx_hash).await?.is_none());
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn slow_archive_queries_are_misses() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let tx_hash = executed_tx_hash(&cfg).await?;
+
+        let archive = TxArchive::new(cfg.pool.clone(), Duration::from_nanos(1));
+        assert!(archive.tx_receipt(tx_hash).await?.is_none());
+        assert!(archive.tx_data(tx_hash).await?.is_none());
+        Ok(())
+    }
+}
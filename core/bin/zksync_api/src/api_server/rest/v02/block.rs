@@ -1,29 +1,45 @@
 //! Block part of API implementation.
 
 // Built-in uses
+use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 use std::time::Instant;
 
 // External uses
-use actix_web::{web, Scope};
+use actix_web::{http::header, web, Either, HttpResponse, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use num::{rational::Ratio, BigUint, Zero};
 
 // Workspace uses
 use zksync_api_types::v02::{
-    block::{BlockInfo, BlockStatus},
+    block::{
+        BlockChecksum, BlockCostBreakdown, BlockFinality, BlockFinalityStatus, BlockHeader,
+        BlockInfo, BlockL1Cost, BlockStatus, BlocksByNumbersRequest, BlocksExportQuery,
+        StateRootCheck, TxTypeFees, MAX_BLOCKS_BY_NUMBERS_LIMIT,
+    },
     pagination::{parse_query, ApiEither, BlockAndTxHash, Paginated, PaginationQuery},
     transaction::{Transaction, TxData, TxHashSerializeWrapper},
 };
 use zksync_crypto::{convert::FeConvert, Fr};
-use zksync_storage::{chain::block::records::StorageBlockDetails, ConnectionPool, QueryResult};
-use zksync_types::{tx::TxHash, BlockNumber, H256};
+use zksync_storage::{
+    chain::block::records::StorageBlockDetails, AccessIntent, QueryResult, ReplicatedPool,
+};
+use zksync_types::{tx::TxHash, BlockNumber, ExecutedOperations, TokenId, TokenLike, H256};
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 
 // Local uses
 use super::{
+    blocks_export::BlocksExporter,
+    checksum::{block_checksum, ChecksumOp},
     error::{Error, InvalidDataError},
     paginate_trait::Paginate,
-    response::ApiResult,
+    response::{ApiResult, OrNotFound},
+    state_root_check::StateRootChecker,
+};
+use crate::{
+    api_server::rest::network_status::SharedNetworkStatus, api_try,
+    utils::block_details_cache::BlockDetailsCache,
 };
-use crate::{api_try, utils::block_details_cache::BlockDetailsCache};
 
 pub fn block_info_from_details(details: StorageBlockDetails) -> BlockInfo {
     let status = if details.is_verified() {
@@ -48,18 +64,81 @@ pub fn block_info_from_details(details: StorageBlockDetails) -> BlockInfo {
     }
 }
 
+/// Number of decimal places of the ETH amounts in the cost breakdown.
+const ETH_AMOUNT_PRECISION: usize = 18;
+
+/// Returns the share of the gas used by the operation that processed the range of blocks
+/// which is attributed to the given block. The gas is split evenly, the remainder goes
+/// to the first blocks of the range, so the shares always sum up to `gas_used`.
+fn prorated_gas(
+    gas_used: u64,
+    from_block: BlockNumber,
+    to_block: BlockNumber,
+    block_number: BlockNumber,
+) -> u64 {
+    let blocks_count = u64::from(*to_block - *from_block) + 1;
+    let block_index = u64::from(*block_number - *from_block);
+    let share = gas_used / blocks_count;
+    if block_index < gas_used % blocks_count {
+        share + 1
+    } else {
+        share
+    }
+}
+
+/// Converts an amount in wei to ETH.
+fn wei_to_eth(amount: Ratio<BigUint>) -> Ratio<BigUint> {
+    amount / BigUint::from(10u32).pow(18)
+}
+
+/// Assesses the finality of the block, see `BlockFinality` for the rule of `safe_to_credit`.
+fn block_finality(
+    block_number: BlockNumber,
+    status: BlockFinalityStatus,
+    commit_l1_block: Option<u64>,
+    current_l1_block: Option<u64>,
+    required_confirmations: u64,
+) -> BlockFinality {
+    let commit_confirmations = match (status, commit_l1_block, current_l1_block) {
+        (BlockFinalityStatus::Pending, _, _) => None,
+        // The last processed L1 block may briefly go backwards (e.g. if the watcher was restarted).
+        (_, Some(commit_l1_block), Some(current_l1_block)) => {
+            Some(current_l1_block.saturating_sub(commit_l1_block))
+        }
+        _ => None,
+    };
+    BlockFinality {
+        block_number,
+        status,
+        revertible: status != BlockFinalityStatus::Finalized,
+        commit_confirmations,
+        required_confirmations,
+        safe_to_credit: status != BlockFinalityStatus::Pending,
+    }
+}
+
 /// Shared data between `api/v0.2/blocks` endpoints.
 #[derive(Debug, Clone)]
 struct ApiBlockData {
-    pool: ConnectionPool,
+    pool: ReplicatedPool,
     verified_blocks_cache: BlockDetailsCache,
+    network_status: SharedNetworkStatus,
+    /// Number of L1 confirmations the commit transactions are confirmed after.
+    required_confirmations: u64,
 }
 
 impl ApiBlockData {
-    fn new(pool: ConnectionPool, verified_blocks_cache: BlockDetailsCache) -> Self {
+    fn new(
+        pool: ReplicatedPool,
+        verified_blocks_cache: BlockDetailsCache,
+        network_status: SharedNetworkStatus,
+        required_confirmations: u64,
+    ) -> Self {
         Self {
             pool,
             verified_blocks_cache,
+            network_status,
+            required_confirmations,
         }
     }
 
@@ -69,7 +148,7 @@ impl ApiBlockData {
     async fn block_info(&self, block_number: BlockNumber) -> Result<Option<BlockInfo>, Error> {
         let details = self
             .verified_blocks_cache
-            .get(&self.pool, block_number)
+            .get(self.pool.pool_for(AccessIntent::Read), block_number)
             .await
             .map_err(Error::storage)?;
         if let Some(details) = details {
@@ -100,11 +179,49 @@ impl ApiBlockData {
         }
     }
 
+    /// Returns the headers of the requested blocks in the requested order,
+    /// `None` stands for the blocks that are not committed yet.
+    async fn blocks_by_numbers(
+        &self,
+        block_numbers: Vec<BlockNumber>,
+    ) -> Result<Vec<Option<BlockHeader>>, Error> {
+        if block_numbers.is_empty() || block_numbers.len() > MAX_BLOCKS_BY_NUMBERS_LIMIT {
+            return Err(Error::from(InvalidDataError::TooManyBlockNumbers));
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let headers: HashMap<_, _> = storage
+            .chain()
+            .block_schema()
+            .load_blocks_by_numbers(&block_numbers)
+            .await
+            .map_err(Error::storage)?
+            .into_iter()
+            .map(|(details, tx_count)| {
+                let info = block_info_from_details(details);
+                (info.block_number, BlockHeader { info, tx_count })
+            })
+            .collect();
+
+        Ok(block_numbers
+            .into_iter()
+            .map(|block_number| headers.get(&block_number).cloned())
+            .collect())
+    }
+
     async fn block_page(
         &self,
         query: PaginationQuery<ApiEither<BlockNumber>>,
     ) -> Result<Paginated<BlockInfo, BlockNumber>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
         storage.paginate_checked(&query).await
     }
 
@@ -113,7 +230,11 @@ impl ApiBlockData {
         block_number: BlockNumber,
         query: PaginationQuery<ApiEither<TxHash>>,
     ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
 
         let new_query = PaginationQuery {
             from: BlockAndTxHash {
@@ -132,7 +253,11 @@ impl ApiBlockData {
         block_number: BlockNumber,
         block_index: u64,
     ) -> Result<Option<TxData>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
         Ok(storage
             .chain()
             .operations_ext_schema()
@@ -141,8 +266,246 @@ impl ApiBlockData {
             .map_err(Error::storage)?)
     }
 
+    /// Estimates the L1 cost of the block and the fees paid by its transactions in ETH,
+    /// `None` if the block doesn't exist.
+    async fn cost_breakdown(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Option<BlockCostBreakdown>, Error> {
+        if self.block_info(block_number).await?.is_none() {
+            return Ok(None);
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let operations = storage
+            .ethereum_schema()
+            .load_block_l1_operations(block_number)
+            .await
+            .map_err(Error::storage)?;
+        let executed_ops = storage
+            .chain()
+            .block_schema()
+            .get_block_executed_ops(block_number)
+            .await
+            .map_err(Error::storage)?;
+
+        let mut estimated_l1_gas = 0u64;
+        let mut estimated_l1_cost = Ratio::from_integer(BigUint::zero());
+        let mut l1_operations = Vec::with_capacity(operations.len());
+        for operation in operations {
+            let from_block = BlockNumber(operation.from_block as u32);
+            let to_block = BlockNumber(operation.to_block as u32);
+            let gas_used = operation.gas_used.map(|gas| gas as u64);
+            let block_gas =
+                gas_used.map(|gas| prorated_gas(gas, from_block, to_block, block_number));
+            let block_cost = match (
+                block_gas,
+                big_decimal_to_ratio(&operation.last_used_gas_price),
+            ) {
+                (Some(gas), Ok(gas_price)) => {
+                    estimated_l1_gas += gas;
+                    let cost = wei_to_eth(gas_price * BigUint::from(gas));
+                    estimated_l1_cost += cost.clone();
+                    Some(cost)
+                }
+                _ => None,
+            };
+            l1_operations.push(BlockL1Cost {
+                action_type: operation.action_type,
+                from_block,
+                to_block,
+                gas_used,
+                block_gas,
+                block_cost_eth: block_cost
+                    .map(|cost| ratio_to_big_decimal(&cost, ETH_AMOUNT_PRECISION)),
+            });
+        }
+
+        let fees: Vec<_> = executed_ops
+            .into_iter()
+            .filter_map(|op| match op {
+                ExecutedOperations::Tx(tx) if tx.success => tx
+                    .signed_tx
+                    .tx
+                    .get_fee_info()
+                    .map(|(_, token, _, fee)| (tx.signed_tx.tx.variance_name(), token, fee)),
+                _ => None,
+            })
+            .collect();
+
+        // Decimals of the fee tokens, ETH is always among them to load its price.
+        let mut token_decimals = HashMap::new();
+        token_decimals.insert(TokenId(0), 18u8);
+        for (_, token, _) in &fees {
+            if let TokenLike::Id(token_id) = token {
+                if token_decimals.contains_key(token_id) {
+                    continue;
+                }
+                if let Some(token) = storage
+                    .tokens_schema()
+                    .get_token(token.clone())
+                    .await
+                    .map_err(Error::storage)?
+                {
+                    token_decimals.insert(token.id, token.decimals);
+                }
+            }
+        }
+        let token_ids: Vec<_> = token_decimals.keys().copied().collect();
+        let prices = storage
+            .tokens_schema()
+            .get_historical_ticker_prices(&token_ids)
+            .await
+            .map_err(Error::storage)?;
+        let eth_price = prices
+            .get(&TokenId(0))
+            .map(|price| price.usd_price.clone())
+            .filter(|price| !price.is_zero());
+
+        let fee_in_eth = |token: &TokenLike, fee: BigUint| -> Option<Ratio<BigUint>> {
+            let token_id = match token {
+                TokenLike::Id(token_id) => *token_id,
+                _ => return None,
+            };
+            if token_id == TokenId(0) {
+                return Some(wei_to_eth(Ratio::from_integer(fee)));
+            }
+            let decimals = token_decimals.get(&token_id)?;
+            let token_price = &prices.get(&token_id)?.usd_price;
+            let eth_price = eth_price.as_ref()?;
+            let amount = Ratio::new(fee, BigUint::from(10u32).pow(u32::from(*decimals)));
+            Some(amount * token_price / eth_price)
+        };
+
+        let mut estimated_fees = Ratio::from_integer(BigUint::zero());
+        let mut unpriced_txs = 0u32;
+        // Transaction type -> (transactions count, priced transactions count, total fee in ETH).
+        let mut type_fees: BTreeMap<String, (u32, u32, Ratio<BigUint>)> = BTreeMap::new();
+        for (tx_type, token, fee) in fees {
+            let entry = type_fees
+                .entry(tx_type)
+                .or_insert_with(|| (0, 0, Ratio::from_integer(BigUint::zero())));
+            entry.0 += 1;
+            match fee_in_eth(&token, fee) {
+                Some(fee) => {
+                    entry.1 += 1;
+                    entry.2 += fee.clone();
+                    estimated_fees += fee;
+                }
+                None => unpriced_txs += 1,
+            }
+        }
+        let tx_types = type_fees
+            .into_iter()
+            .map(|(tx_type, (count, priced_count, total_fee))| TxTypeFees {
+                tx_type,
+                count,
+                average_fee_eth: if priced_count > 0 {
+                    Some(ratio_to_big_decimal(
+                        &(total_fee / BigUint::from(priced_count)),
+                        ETH_AMOUNT_PRECISION,
+                    ))
+                } else {
+                    None
+                },
+            })
+            .collect();
+
+        Ok(Some(BlockCostBreakdown {
+            block_number,
+            l1_operations,
+            estimated_l1_gas,
+            estimated_l1_cost_eth: ratio_to_big_decimal(&estimated_l1_cost, ETH_AMOUNT_PRECISION),
+            estimated_fees_eth: ratio_to_big_decimal(&estimated_fees, ETH_AMOUNT_PRECISION),
+            unpriced_txs,
+            tx_types,
+        }))
+    }
+
+    /// Returns the finality of the block, `None` if the block doesn't exist.
+    async fn block_finality(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Option<BlockFinality>, Error> {
+        let status = match self.block_info(block_number).await? {
+            Some(info) if info.status == BlockStatus::Finalized => BlockFinalityStatus::Finalized,
+            Some(_) => BlockFinalityStatus::Committed,
+            None => BlockFinalityStatus::Pending,
+        };
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        if status == BlockFinalityStatus::Pending {
+            let last_saved_block = storage
+                .chain()
+                .block_schema()
+                .get_last_saved_block()
+                .await
+                .map_err(Error::storage)?;
+            // The genesis block is never committed.
+            if *block_number == 0 || block_number > last_saved_block {
+                return Ok(None);
+            }
+        }
+        let commit_l1_block = storage
+            .ethereum_schema()
+            .load_commit_l1_block(block_number)
+            .await
+            .map_err(Error::storage)?;
+        let current_l1_block = self
+            .network_status
+            .read()
+            .await
+            .eth_watch_status
+            .map(|status| status.last_processed_block);
+
+        Ok(Some(block_finality(
+            block_number,
+            status,
+            commit_l1_block,
+            current_l1_block,
+            self.required_confirmations,
+        )))
+    }
+
+    /// Computes the checksum of the block from the data of the replica serving the request.
+    async fn block_checksum(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<Option<BlockChecksum>, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let block = storage
+            .chain()
+            .block_schema()
+            .get_block(block_number)
+            .await
+            .map_err(Error::storage)?;
+        Ok(block.map(|block| {
+            let ops = block
+                .block_transactions
+                .iter()
+                .map(ChecksumOp::from)
+                .collect();
+            BlockChecksum {
+                block_number,
+                checksum: block_checksum(block_number, block.get_eth_encoded_root(), ops),
+            }
+        }))
+    }
+
     async fn get_last_committed_block_number(&self) -> QueryResult<BlockNumber> {
-        let mut storage = self.pool.access_storage().await?;
+        let mut storage = self.pool.access_storage(AccessIntent::Read).await?;
         storage
             .chain()
             .block_schema()
@@ -151,7 +514,7 @@ impl ApiBlockData {
     }
 
     async fn get_last_finalized_block_number(&self) -> QueryResult<BlockNumber> {
-        let mut storage = self.pool.access_storage().await?;
+        let mut storage = self.pool.access_storage(AccessIntent::Read).await?;
         storage
             .chain()
             .block_schema()
@@ -173,15 +536,29 @@ async fn block_pagination(
     res
 }
 
+async fn blocks_by_numbers(
+    data: web::Data<ApiBlockData>,
+    web::Json(body): web::Json<BlocksByNumbersRequest>,
+) -> ApiResult<Vec<Option<BlockHeader>>> {
+    let start = Instant::now();
+    let res = data.blocks_by_numbers(body.block_numbers).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "blocks_by_numbers");
+    res
+}
+
 // TODO: take `block_position` as enum.
 // Currently actix path extractor doesn't work with enums: https://github.com/actix/actix-web/issues/318 (ZKS-628)
 async fn block_by_position(
     data: web::Data<ApiBlockData>,
     block_position: web::Path<String>,
-) -> ApiResult<Option<BlockInfo>> {
+) -> ApiResult<BlockInfo> {
     let start = Instant::now();
     let block_number = api_try!(data.get_block_number_by_position(&block_position).await);
-    let res = data.block_info(block_number).await.into();
+    let res = data
+        .block_info(block_number)
+        .await
+        .or_not_found("block", block_number)
+        .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_by_position");
     res
 }
@@ -202,20 +579,119 @@ async fn block_transactions(
 async fn transaction_in_block(
     data: web::Data<ApiBlockData>,
     path: web::Path<(BlockNumber, u64)>,
-) -> ApiResult<Option<TxData>> {
+) -> ApiResult<TxData> {
     let start = Instant::now();
     let (block_number, block_index) = *path;
-    let res = api_try!(data.tx_data(block_number, block_index).await);
+    let res = data
+        .tx_data(block_number, block_index)
+        .await
+        .or_not_found("transaction", format!("{}/{}", block_number, block_index))
+        .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "transaction_in_block");
-    ApiResult::Ok(res)
+    res
+}
+
+/// Streams the blocks of the range as gzip-compressed newline-delimited JSON.
+/// Errors occurred before the export is started are reported as usual API responses.
+async fn export_blocks(
+    data: web::Data<ApiBlockData>,
+    exporter: web::Data<BlocksExporter>,
+    credentials: Option<BearerAuth>,
+    web::Query(query): web::Query<BlocksExportQuery>,
+) -> Either<HttpResponse, ApiResult<()>> {
+    let start = Instant::now();
+    let response = match exporter.start(data.pool.clone(), credentials, query) {
+        Ok(stream) => Either::Left(
+            HttpResponse::Ok()
+                .content_type("application/x-ndjson")
+                .insert_header((header::CONTENT_ENCODING, "gzip"))
+                .streaming(stream),
+        ),
+        Err(err) => Either::Right(ApiResult::Error(err)),
+    };
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "export_blocks");
+    response
+}
+
+/// Recomputes the state root of the block from the stored account updates.
+async fn state_root_check(
+    data: web::Data<ApiBlockData>,
+    checker: web::Data<StateRootChecker>,
+    credentials: Option<BearerAuth>,
+    block_number: web::Path<BlockNumber>,
+) -> ApiResult<StateRootCheck> {
+    let start = Instant::now();
+    let res = checker
+        .check(&data.pool, credentials, *block_number)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "state_root_check");
+    res
+}
+
+/// Estimates how the L1 cost of the block compares to the fees collected in it.
+async fn cost_breakdown(
+    data: web::Data<ApiBlockData>,
+    block_number: web::Path<BlockNumber>,
+) -> ApiResult<BlockCostBreakdown> {
+    let start = Instant::now();
+    let res = data
+        .cost_breakdown(*block_number)
+        .await
+        .or_not_found("block", *block_number)
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "cost_breakdown");
+    res
 }
 
-pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
-    let data = ApiBlockData::new(pool, cache);
+/// Returns whether the block can still be reverted and whether it's safe to credit the deposits.
+async fn finality(
+    data: web::Data<ApiBlockData>,
+    block_number: web::Path<BlockNumber>,
+) -> ApiResult<BlockFinality> {
+    let start = Instant::now();
+    let res = data
+        .block_finality(*block_number)
+        .await
+        .or_not_found("block", *block_number)
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_finality");
+    res
+}
+
+/// Returns the checksum of the block, replicas serving the same block return the same checksum.
+async fn checksum(
+    data: web::Data<ApiBlockData>,
+    block_number: web::Path<BlockNumber>,
+) -> ApiResult<BlockChecksum> {
+    let start = Instant::now();
+    let res = data
+        .block_checksum(*block_number)
+        .await
+        .or_not_found("block", *block_number)
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_checksum");
+    res
+}
+
+pub fn api_scope(
+    pool: ReplicatedPool,
+    cache: BlockDetailsCache,
+    exporter: BlocksExporter,
+    checker: StateRootChecker,
+    network_status: SharedNetworkStatus,
+    required_confirmations: u64,
+) -> Scope {
+    let data = ApiBlockData::new(pool, cache, network_status, required_confirmations);
 
     web::scope("blocks")
         .app_data(web::Data::new(data))
+        .app_data(web::Data::new(exporter))
+        .app_data(web::Data::new(checker))
         .route("", web::get().to(block_pagination))
+        // Must be registered before `{block_position}`, which matches any path segment.
+        .route("export", web::get().to(export_blocks))
+        .route("byNumbers", web::post().to(blocks_by_numbers))
         .route("{block_position}", web::get().to(block_by_position))
         .route(
             "{block_position}/transactions",
@@ -225,18 +701,71 @@ pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
             "{block_position}/transactions/{block_index}",
             web::get().to(transaction_in_block),
         )
+        .route(
+            "{block_number}/stateRootCheck",
+            web::get().to(state_root_check),
+        )
+        .route(
+            "{block_number}/costBreakdown",
+            web::get().to(cost_breakdown),
+        )
+        .route("{block_number}/checksum", web::get().to(checksum))
+        .route("{block_number}/finality", web::get().to(finality))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
-        test_utils::{deserialize_response_result, TestServerConfig},
+        error::ErrorCode,
+        test_utils::{
+            assert_not_found, auth_token, deserialize_response_result, BlockTx, TestServerConfig,
+            TxLifecycle, COMMITTED_BLOCKS_COUNT, SECRET_AUTH,
+        },
         SharedData,
     };
+    use actix_web::http::StatusCode;
+    use flate2::read::GzDecoder;
+    use std::io::{BufRead, BufReader};
     use zksync_api_types::v02::{
-        pagination::PaginationDirection, transaction::TransactionData, ApiVersion,
+        block::{BlocksExportLine, BlocksExportTrailer},
+        pagination::PaginationDirection,
+        transaction::TransactionData,
+        ApiVersion, Response, COMPAT_MODE_HEADER, NULL_NOT_FOUND_COMPAT_MODE,
     };
+    use zksync_storage::test_data::{gen_sample_block, BLOCK_SIZE_CHUNKS};
+
+    /// Requests the blocks export, returns the parsed lines or the error code of the response.
+    async fn export_blocks(
+        url: &str,
+        token: Option<&str>,
+        from: u32,
+        to: u32,
+    ) -> anyhow::Result<Result<Vec<BlocksExportLine>, u64>> {
+        let mut request = reqwest::Client::new()
+            .get(url)
+            .query(&[("from", from), ("to", to)]);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+
+        let is_export = response
+            .headers()
+            .get(header::CONTENT_ENCODING.as_str())
+            .map_or(false, |encoding| encoding == "gzip");
+        if !is_export {
+            let response: Response = response.json().await?;
+            return Ok(Err(response.error.unwrap()["code"].as_u64().unwrap()));
+        }
+
+        let body = response.bytes().await?;
+        let lines = BufReader::new(GzDecoder::new(body.as_ref()))
+            .lines()
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Ok(lines))
+    }
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -250,9 +779,21 @@ mod tests {
         let shared_data = SharedData {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
         };
         let (client, server) = cfg.start_server(
-            |cfg: &TestServerConfig| api_scope(cfg.pool.clone(), BlockDetailsCache::new(10)),
+            |cfg: &TestServerConfig| {
+                let exporter = BlocksExporter::new(&cfg.config.api.rest, String::new());
+                let checker = StateRootChecker::new(&cfg.config.api.rest, String::new());
+                api_scope(
+                    cfg.replicated_pool(),
+                    BlockDetailsCache::new(10),
+                    exporter,
+                    checker,
+                    SharedNetworkStatus::new(String::new()),
+                    cfg.config.eth_sender.sender.wait_confirmations,
+                )
+            },
             Some(shared_data),
         );
 
@@ -329,8 +870,7 @@ mod tests {
                     expected_tx.block_index.unwrap() as u32,
                 )
                 .await?;
-            let tx: Option<TxData> = deserialize_response_result(response)?;
-            let tx = tx.unwrap().tx;
+            let tx = deserialize_response_result::<TxData>(response)?.tx;
             assert_eq!(tx.created_at, Some(expected_tx.created_at));
             assert_eq!(*tx.block_number.unwrap(), expected_tx.block_number as u32);
             assert_eq!(tx.fail_reason, expected_tx.fail_reason);
@@ -339,6 +879,566 @@ mod tests {
             }
         }
 
+        // The block is committed, so its commit operation is taken into account.
+        let response = client.block_cost_breakdown(BlockNumber(1)).await?;
+        let breakdown: BlockCostBreakdown = deserialize_response_result(response)?;
+        assert_eq!(breakdown.block_number, BlockNumber(1));
+        assert!(breakdown
+            .l1_operations
+            .iter()
+            .any(|op| op.action_type == "CommitBlocks"));
+        let txs_count: u32 = breakdown.tx_types.iter().map(|fees| fees.count).sum();
+        assert!(breakdown.unpriced_txs <= txs_count);
+
+        let response = client
+            .block_cost_breakdown(BlockNumber(COMMITTED_BLOCKS_COUNT + 100))
+            .await?;
+        assert_not_found(response, "block")?;
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Missing blocks are reported with the `NotFound` error using the configured HTTP status,
+    /// or as `null` for the clients requesting the compatibility mode.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn missing_blocks() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let missing_block = BlockNumber(COMMITTED_BLOCKS_COUNT + 100);
+        for not_found_status in [StatusCode::OK, StatusCode::NOT_FOUND] {
+            let shared_data = SharedData {
+                net: cfg.config.chain.eth.network,
+                api_version: ApiVersion::V02,
+                not_found_status,
+            };
+            let (client, server) = cfg.start_server(
+                |cfg: &TestServerConfig| {
+                    let exporter = BlocksExporter::new(&cfg.config.api.rest, String::new());
+                    let checker = StateRootChecker::new(&cfg.config.api.rest, String::new());
+                    api_scope(
+                        cfg.replicated_pool(),
+                        BlockDetailsCache::new(10),
+                        exporter,
+                        checker,
+                        SharedNetworkStatus::new(String::new()),
+                        cfg.config.eth_sender.sender.wait_confirmations,
+                    )
+                },
+                Some(shared_data),
+            );
+
+            let response = client.block_by_position(&missing_block.to_string()).await?;
+            assert_not_found(response, "block")?;
+            let response = client.transaction_in_block(*missing_block, 0).await?;
+            assert_not_found(response, "transaction")?;
+
+            let url = server.url(&format!("/api/v0.2/blocks/{}", missing_block));
+            let response = reqwest::get(&url).await?;
+            assert_eq!(response.status(), not_found_status);
+            let response: Response = response.json().await?;
+            assert_eq!(
+                response.error.unwrap()["data"]["id"],
+                missing_block.to_string()
+            );
+
+            // Old clients get the `null` result, as before.
+            let response = reqwest::Client::new()
+                .get(&url)
+                .header(COMPAT_MODE_HEADER, NULL_NOT_FOUND_COMPAT_MODE)
+                .send()
+                .await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let response: Response = response.json().await?;
+            assert!(response.error.is_none());
+            assert_eq!(response.result, Some(serde_json::Value::Null));
+
+            server.stop().await;
+        }
+        Ok(())
+    }
+
+    /// Checks that the block checksums are computed from the stored blocks.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn block_checksums() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.replicated_pool(),
+                    BlockDetailsCache::new(10),
+                    BlocksExporter::new(&cfg.config.api.rest, String::new()),
+                    StateRootChecker::new(&cfg.config.api.rest, String::new()),
+                    SharedNetworkStatus::new(String::new()),
+                    cfg.config.eth_sender.sender.wait_confirmations,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let mut checksums = Vec::new();
+        for block_number in 1..=COMMITTED_BLOCKS_COUNT {
+            let block_number = BlockNumber(block_number);
+            let response = client.block_checksum(block_number).await?;
+            let checksum: BlockChecksum = deserialize_response_result(response)?;
+
+            let block = cfg
+                .pool
+                .access_storage()
+                .await?
+                .chain()
+                .block_schema()
+                .get_block(block_number)
+                .await?
+                .unwrap();
+            let ops = block
+                .block_transactions
+                .iter()
+                .map(ChecksumOp::from)
+                .collect();
+            assert_eq!(
+                checksum,
+                BlockChecksum {
+                    block_number,
+                    checksum: block_checksum(block_number, block.get_eth_encoded_root(), ops),
+                }
+            );
+            checksums.push(checksum.checksum);
+        }
+        checksums.sort();
+        checksums.dedup();
+        assert_eq!(checksums.len(), COMMITTED_BLOCKS_COUNT as usize);
+
+        let missing_block = BlockNumber(COMMITTED_BLOCKS_COUNT + 100);
+        let response = client.block_checksum(missing_block).await?;
+        assert_not_found(response, "block")?;
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[test]
+    fn prorated_gas_shares() {
+        // The operation processed a single block.
+        assert_eq!(
+            prorated_gas(150_000, BlockNumber(5), BlockNumber(5), BlockNumber(5)),
+            150_000
+        );
+
+        // The gas is split evenly.
+        for block in 1..=3 {
+            assert_eq!(
+                prorated_gas(300_000, BlockNumber(1), BlockNumber(3), BlockNumber(block)),
+                100_000
+            );
+        }
+
+        // The remainder goes to the first blocks of the range.
+        let shares: Vec<_> = (10..=13)
+            .map(|block| prorated_gas(102, BlockNumber(10), BlockNumber(13), BlockNumber(block)))
+            .collect();
+        assert_eq!(shares, vec![26, 26, 25, 25]);
+
+        // The shares always sum up to the gas used by the operation.
+        for gas_used in [0, 1, 7, 999_999, u64::MAX] {
+            let total: u128 = (3..=9)
+                .map(|block| {
+                    prorated_gas(gas_used, BlockNumber(3), BlockNumber(9), BlockNumber(block))
+                        as u128
+                })
+                .sum();
+            assert_eq!(total, gas_used as u128);
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocks_by_numbers_in_requested_order() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.replicated_pool(),
+                    BlockDetailsCache::new(10),
+                    BlocksExporter::new(&cfg.config.api.rest, String::new()),
+                    StateRootChecker::new(&cfg.config.api.rest, String::new()),
+                    SharedNetworkStatus::new(String::new()),
+                    cfg.config.eth_sender.sender.wait_confirmations,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let finalized_block = BlockNumber(1);
+        // Committed, but not finalized yet.
+        let committed_block = BlockNumber(COMMITTED_BLOCKS_COUNT);
+        let unknown_block = BlockNumber(COMMITTED_BLOCKS_COUNT + 100);
+        let requested = [
+            committed_block,
+            unknown_block,
+            finalized_block,
+            committed_block,
+        ];
+        let response = client.blocks_by_numbers(&requested).await?;
+        let headers: Vec<Option<BlockHeader>> = deserialize_response_result(response)?;
+        assert_eq!(headers.len(), requested.len());
+        assert!(headers[1].is_none());
+        assert_eq!(headers[0], headers[3]);
+
+        let mut storage = cfg.pool.access_storage().await?;
+        for (index, block_number, status) in vec![
+            (0, committed_block, BlockStatus::Committed),
+            (2, finalized_block, BlockStatus::Finalized),
+        ] {
+            let header = headers[index].clone().unwrap();
+            let expected_info = block_info_from_details(
+                storage
+                    .chain()
+                    .block_schema()
+                    .load_block_range_asc(block_number, 1)
+                    .await?
+                    .remove(0),
+            );
+            let expected_tx_count = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions_count(block_number)
+                .await?;
+            assert_eq!(header.info, expected_info);
+            assert_eq!(header.info.status, status);
+            assert_eq!(header.tx_count, expected_tx_count);
+        }
+        drop(storage);
+
+        // The header follows the lifecycle of the block.
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let txs = TestServerConfig::gen_zk_txs(1_u64)
+            .txs
+            .into_iter()
+            .take(2)
+            .map(|(tx, _op)| BlockTx::success(tx))
+            .collect();
+        let block_number = lifecycle.include(txs).await?;
+        let response = client.blocks_by_numbers(&[block_number]).await?;
+        let headers: Vec<Option<BlockHeader>> = deserialize_response_result(response)?;
+        let header = headers[0].clone().unwrap();
+        assert_eq!(header.info.status, BlockStatus::Committed);
+        assert!(header.info.commit_tx_hash.is_some());
+        assert_eq!(header.tx_count, 2);
+
+        lifecycle.verify(block_number).await?;
+        lifecycle.execute(block_number).await?;
+        let response = client.blocks_by_numbers(&[block_number]).await?;
+        let headers: Vec<Option<BlockHeader>> = deserialize_response_result(response)?;
+        let header = headers[0].clone().unwrap();
+        assert_eq!(header.info.status, BlockStatus::Finalized);
+        assert!(header.info.verify_tx_hash.is_some());
+        lifecycle.finish().await?;
+
+        // The number of the requested blocks is limited.
+        let too_many: Vec<_> = (1..=MAX_BLOCKS_BY_NUMBERS_LIMIT as u32 + 1)
+            .map(BlockNumber)
+            .collect();
+        for block_numbers in vec![vec![], too_many] {
+            let response = client.blocks_by_numbers(&block_numbers).await?;
+            assert_eq!(
+                response.error.unwrap()["code"],
+                ErrorCode::TooManyBlockNumbers as u64
+            );
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocks_export() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let mut rest_config = cfg.config.api.rest.clone();
+        rest_config.blocks_export_max_range = 3;
+        let exporter = BlocksExporter::new(&rest_config, SECRET_AUTH.to_owned());
+        let (_client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.replicated_pool(),
+                    BlockDetailsCache::new(10),
+                    exporter.clone(),
+                    StateRootChecker::new(&cfg.config.api.rest, String::new()),
+                    SharedNetworkStatus::new(String::new()),
+                    cfg.config.eth_sender.sender.wait_confirmations,
+                )
+            },
+            Some(shared_data),
+        );
+        let url = server.url("/api/v0.2/blocks/export");
+        let token = auth_token("indexer");
+
+        let last_block = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .get_last_committed_confirmed_block()
+            .await?;
+        assert!(*last_block >= 3);
+
+        // Exports are available only with a valid token.
+        let code = export_blocks(&url, None, 1, 2).await?.unwrap_err();
+        assert_eq!(code, ErrorCode::BlocksExportUnauthorized as u64);
+        let code = export_blocks(&url, Some("invalid"), 1, 2)
+            .await?
+            .unwrap_err();
+        assert_eq!(code, ErrorCode::BlocksExportUnauthorized as u64);
+        // Range is limited.
+        let code = export_blocks(&url, Some(&token), 1, 4).await?.unwrap_err();
+        assert_eq!(code, ErrorCode::InvalidBlocksExportRange as u64);
+        let code = export_blocks(&url, Some(&token), 2, 1).await?.unwrap_err();
+        assert_eq!(code, ErrorCode::InvalidBlocksExportRange as u64);
+
+        let mut lines = export_blocks(&url, Some(&token), 1, 3).await?.unwrap();
+        assert_eq!(lines.len(), 4);
+        match lines.pop().unwrap() {
+            BlocksExportLine::Trailer(trailer) => assert_eq!(
+                trailer,
+                BlocksExportTrailer {
+                    last_block: Some(BlockNumber(3))
+                }
+            ),
+            BlocksExportLine::Block(_) => panic!("Export must end with the trailer"),
+        }
+        for (expected_number, line) in (1..=3).zip(lines) {
+            let exported = match line {
+                BlocksExportLine::Block(exported) => exported,
+                BlocksExportLine::Trailer(_) => panic!("Block expected"),
+            };
+            let mut storage = cfg.pool.access_storage().await?;
+            let expected_info = block_info_from_details(
+                storage
+                    .chain()
+                    .block_schema()
+                    .load_block_range_asc(BlockNumber(expected_number), 1)
+                    .await?
+                    .remove(0),
+            );
+            assert_eq!(exported.block, expected_info);
+
+            let expected_txs_count = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions_count(BlockNumber(expected_number))
+                .await?;
+            assert_eq!(exported.transactions.len(), expected_txs_count as usize);
+            for tx in &exported.transactions {
+                assert_eq!(tx.block_number, Some(BlockNumber(expected_number)));
+            }
+        }
+
+        // The export is continued from the cursor, blocks that don't exist yet are skipped.
+        let lines = export_blocks(&url, Some(&token), *last_block, *last_block + 2)
+            .await?
+            .unwrap();
+        assert_eq!(lines.len(), 2);
+        match &lines[1] {
+            BlocksExportLine::Trailer(trailer) => {
+                assert_eq!(trailer.last_block, Some(last_block))
+            }
+            BlocksExportLine::Block(_) => panic!("Export must end with the trailer"),
+        }
+        let lines = export_blocks(&url, Some(&token), *last_block + 1, *last_block + 1)
+            .await?
+            .unwrap();
+        assert!(matches!(
+            lines.as_slice(),
+            [BlocksExportLine::Trailer(BlocksExportTrailer {
+                last_block: None
+            })]
+        ));
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[test]
+    fn block_finality_policy() {
+        let block_number = BlockNumber(5);
+        // The commitment is not confirmed yet, even if its transaction is already mined.
+        let finality = block_finality(
+            block_number,
+            BlockFinalityStatus::Pending,
+            Some(100),
+            Some(101),
+            3,
+        );
+        assert_eq!(
+            finality,
+            BlockFinality {
+                block_number,
+                status: BlockFinalityStatus::Pending,
+                revertible: true,
+                commit_confirmations: None,
+                required_confirmations: 3,
+                safe_to_credit: false,
+            }
+        );
+
+        // The commitment is confirmed after the required number of confirmations.
+        let finality = block_finality(
+            block_number,
+            BlockFinalityStatus::Committed,
+            Some(100),
+            Some(104),
+            3,
+        );
+        assert!(finality.revertible);
+        assert_eq!(finality.commit_confirmations, Some(4));
+        assert!(finality.safe_to_credit);
+        // The L1 block of the commitment isn't known for the blocks committed before it was recorded.
+        let finality = block_finality(
+            block_number,
+            BlockFinalityStatus::Committed,
+            None,
+            Some(104),
+            3,
+        );
+        assert_eq!(finality.commit_confirmations, None);
+        assert!(finality.safe_to_credit);
+        // The last processed L1 block may go backwards.
+        let finality = block_finality(
+            block_number,
+            BlockFinalityStatus::Committed,
+            Some(100),
+            Some(99),
+            3,
+        );
+        assert_eq!(finality.commit_confirmations, Some(0));
+
+        let finality = block_finality(
+            block_number,
+            BlockFinalityStatus::Finalized,
+            Some(100),
+            None,
+            3,
+        );
+        assert!(!finality.revertible);
+        assert_eq!(finality.commit_confirmations, None);
+        assert!(finality.safe_to_credit);
+    }
+
+    /// Checks that the finality of the block follows its lifecycle.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn block_finality_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let required_confirmations = cfg.config.eth_sender.sender.wait_confirmations;
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.replicated_pool(),
+                    BlockDetailsCache::new(10),
+                    BlocksExporter::new(&cfg.config.api.rest, String::new()),
+                    StateRootChecker::new(&cfg.config.api.rest, String::new()),
+                    SharedNetworkStatus::new(String::new()),
+                    cfg.config.eth_sender.sender.wait_confirmations,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let finalized_block = BlockNumber(1);
+        let response = client.block_finality(finalized_block).await?;
+        let finality: BlockFinality = deserialize_response_result(response)?;
+        assert_eq!(finality.status, BlockFinalityStatus::Finalized);
+        assert!(!finality.revertible);
+        assert!(finality.safe_to_credit);
+        assert_eq!(finality.required_confirmations, required_confirmations);
+
+        // The block sealed by the server, which commitment is not confirmed yet.
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let pending_block = TxLifecycle::BASE_BLOCK + 1;
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .save_full_block(gen_sample_block(
+                pending_block,
+                BLOCK_SIZE_CHUNKS,
+                Vec::new(),
+            ))
+            .await?;
+        let response = client.block_finality(pending_block).await?;
+        let finality: BlockFinality = deserialize_response_result(response)?;
+        assert_eq!(finality.status, BlockFinalityStatus::Pending);
+        assert!(finality.revertible);
+        assert_eq!(finality.commit_confirmations, None);
+        assert!(!finality.safe_to_credit);
+        lifecycle.revert(TxLifecycle::BASE_BLOCK).await?;
+
+        // The committed block can still be reverted, but is safe to credit.
+        let block_number = lifecycle.include(Vec::new()).await?;
+        let response = client.block_finality(block_number).await?;
+        let finality: BlockFinality = deserialize_response_result(response)?;
+        assert_eq!(finality.status, BlockFinalityStatus::Committed);
+        assert!(finality.revertible);
+        assert!(finality.safe_to_credit);
+
+        lifecycle.verify(block_number).await?;
+        lifecycle.execute(block_number).await?;
+        let response = client.block_finality(block_number).await?;
+        let finality: BlockFinality = deserialize_response_result(response)?;
+        assert_eq!(finality.status, BlockFinalityStatus::Finalized);
+        assert!(!finality.revertible);
+        lifecycle.finish().await?;
+
+        let response = client.block_finality(BlockNumber(u32::MAX)).await?;
+        assert_not_found(response, "block")?;
+
         server.stop().await;
         Ok(())
     }
@@ -0,0 +1,244 @@
+//! Bulk export of blocks for indexers.
+//!
+//! Indexers bootstrapping from scratch would otherwise crawl every block through the paginated
+//! endpoints. The export streams the blocks of the requested range as gzip-compressed
+//! newline-delimited JSON, one block with all of its transactions per line, followed by
+//! the trailer with the last exported block. Blocks are read from the storage in small chunks,
+//! so the memory usage doesn't depend on the size of the range.
+//!
+//! Exports are available only to the consumers the network operators issued tokens to:
+//! every request must be authorized with the JWT signed by the `API_ADMIN_SECRET_AUTH` secret,
+//! and only one export at a time is allowed per token subject.
+
+// Built-in uses
+use std::{
+    collections::HashSet,
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+// External uses
+use actix_web::web::Bytes;
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use flate2::{write::GzEncoder, Compression};
+use futures::{stream, Stream};
+
+// Workspace uses
+use zksync_api_types::v02::block::{
+    BlocksExportLine, BlocksExportQuery, BlocksExportTrailer, ExportedBlock,
+};
+use zksync_config::configs::api::RestApiConfig;
+use zksync_storage::{AccessIntent, ReplicatedPool};
+use zksync_types::BlockNumber;
+
+// Local uses
+use super::{
+    block::block_info_from_details,
+    error::{BlocksExportError, Error, InvalidDataError},
+};
+use crate::api_server::admin_server::validate_auth_token;
+
+/// Number of blocks loaded from the storage at once.
+const EXPORT_CHUNK_SIZE: u32 = 10;
+
+/// Authorizes the blocks exports and limits the number of exports running at the same time.
+#[derive(Debug, Clone)]
+pub struct BlocksExporter {
+    secret_auth: String,
+    max_range: u32,
+    max_concurrency: usize,
+    /// Subjects of the tokens with a running export.
+    active_exports: Arc<Mutex<HashSet<String>>>,
+}
+
+impl BlocksExporter {
+    pub fn new(config: &RestApiConfig, secret_auth: String) -> Self {
+        Self {
+            secret_auth,
+            max_range: config.blocks_export_max_range,
+            max_concurrency: config.blocks_export_max_concurrency,
+            active_exports: Arc::default(),
+        }
+    }
+
+    /// Checks the request and starts the export, the export is running until the returned stream is dropped.
+    pub fn start(
+        &self,
+        pool: ReplicatedPool,
+        credentials: Option<BearerAuth>,
+        query: BlocksExportQuery,
+    ) -> Result<impl Stream<Item = Result<Bytes, actix_web::Error>>, Error> {
+        let claims = credentials
+            .and_then(|credentials| {
+                validate_auth_token(&self.secret_auth, credentials.token()).ok()
+            })
+            .ok_or(BlocksExportError::Unauthorized)?;
+        if query.from > query.to || *query.to - *query.from >= self.max_range {
+            return Err(InvalidDataError::InvalidBlocksExportRange(self.max_range).into());
+        }
+        let permit = self.acquire(claims.sub)?;
+
+        let state = ExportState {
+            pool,
+            next_block: query.from,
+            remaining: *query.to - *query.from + 1,
+            last_block: None,
+            encoder: GzEncoder::new(Vec::new(), Compression::default()),
+            _permit: permit,
+        };
+        Ok(export_stream(state))
+    }
+
+    fn acquire(&self, subject: String) -> Result<ExportPermit, BlocksExportError> {
+        let mut active_exports = self.active_exports.lock().unwrap();
+        if active_exports.contains(&subject) {
+            return Err(BlocksExportError::AlreadyInProgress);
+        }
+        if active_exports.len() >= self.max_concurrency {
+            return Err(BlocksExportError::TooManyExports);
+        }
+        active_exports.insert(subject.clone());
+        metrics::gauge!("api.blocks_export.active", active_exports.len() as f64);
+
+        Ok(ExportPermit {
+            active_exports: self.active_exports.clone(),
+            subject,
+        })
+    }
+}
+
+/// Slot of the running export, released once the export is finished or the client disconnects.
+#[derive(Debug)]
+struct ExportPermit {
+    active_exports: Arc<Mutex<HashSet<String>>>,
+    subject: String,
+}
+
+impl Drop for ExportPermit {
+    fn drop(&mut self) {
+        let mut active_exports = self.active_exports.lock().unwrap();
+        active_exports.remove(&self.subject);
+        metrics::gauge!("api.blocks_export.active", active_exports.len() as f64);
+    }
+}
+
+struct ExportState {
+    pool: ReplicatedPool,
+    next_block: BlockNumber,
+    /// Number of blocks left in the requested range.
+    remaining: u32,
+    last_block: Option<BlockNumber>,
+    encoder: GzEncoder<Vec<u8>>,
+    _permit: ExportPermit,
+}
+
+impl ExportState {
+    /// Writes the next chunk of blocks, returns `false` once there are no blocks left in the range.
+    async fn write_chunk(&mut self) -> anyhow::Result<bool> {
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+
+        let limit = self.remaining.min(EXPORT_CHUNK_SIZE);
+        let mut storage = self.pool.access_storage(AccessIntent::Read).await?;
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_block_range_asc(self.next_block, limit)
+            .await?;
+        let loaded = blocks.len() as u32;
+        for details in blocks {
+            let block = block_info_from_details(details);
+            let transactions = storage
+                .chain()
+                .block_schema()
+                .load_block_transactions_api_v02(block.block_number)
+                .await?;
+
+            self.last_block = Some(block.block_number);
+            self.next_block = BlockNumber(block.block_number.saturating_add(1));
+            self.remaining -= 1;
+            self.write_line(&BlocksExportLine::Block(Box::new(ExportedBlock {
+                block,
+                transactions,
+            })))?;
+        }
+        metrics::counter!("api.blocks_export.blocks", loaded as u64);
+
+        Ok(loaded == limit)
+    }
+
+    fn write_line(&mut self, line: &BlocksExportLine) -> anyhow::Result<()> {
+        serde_json::to_writer(&mut self.encoder, line)?;
+        self.encoder.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Returns the data compressed so far.
+    fn take_output(&mut self) -> anyhow::Result<Bytes> {
+        self.encoder.flush()?;
+        Ok(Bytes::from(std::mem::take(self.encoder.get_mut())))
+    }
+
+    /// Writes the trailer and returns the rest of the compressed data.
+    fn finish(mut self) -> anyhow::Result<Bytes> {
+        let trailer = BlocksExportTrailer {
+            last_block: self.last_block,
+        };
+        self.write_line(&BlocksExportLine::Trailer(trailer))?;
+        Ok(Bytes::from(self.encoder.finish()?))
+    }
+}
+
+fn export_stream(state: ExportState) -> impl Stream<Item = Result<Bytes, actix_web::Error>> {
+    stream::unfold(Some(state), |state| async move {
+        let mut state = state?;
+        let output = match state.write_chunk().await {
+            Ok(true) => state.take_output().map(move |bytes| (bytes, Some(state))),
+            Ok(false) => state.finish().map(|bytes| (bytes, None)),
+            Err(err) => Err(err),
+        };
+
+        Some(match output {
+            Ok((bytes, state)) => (Ok(bytes), state),
+            Err(err) => {
+                // The response is already being sent, so the only option is to abort it.
+                // Clients can continue the export from the last received block.
+                vlog::warn!("Blocks export failed: {}", err);
+                (Err(actix_web::error::ErrorInternalServerError(err)), None)
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrency_limits() {
+        let exporter = BlocksExporter {
+            secret_auth: String::new(),
+            max_range: 100,
+            max_concurrency: 2,
+            active_exports: Arc::default(),
+        };
+
+        let first = exporter.acquire("first".to_owned()).unwrap();
+        // Only one export at a time is allowed per token.
+        assert!(matches!(
+            exporter.acquire("first".to_owned()),
+            Err(BlocksExportError::AlreadyInProgress)
+        ));
+        let _second = exporter.acquire("second".to_owned()).unwrap();
+        assert!(matches!(
+            exporter.acquire("third".to_owned()),
+            Err(BlocksExportError::TooManyExports)
+        ));
+
+        // Slots are released once the exports are finished.
+        drop(first);
+        exporter.acquire("third".to_owned()).unwrap();
+        exporter.acquire("first".to_owned()).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+//! Checksums of the blocks and the account states, used to cross-check the API replicas.
+//!
+//! A checksum is `keccak256` of the canonical serialization of the data, so the replicas serving
+//! the same data return the same checksums regardless of the order the rows are read in.
+//! All the integers are encoded as big-endian of a fixed width: 4 bytes for the numbers, ids, nonces
+//! and list lengths, 16 bytes for the balances. The serialization starts with the versioned domain
+//! tag, any change of it must bump the version, since the replicas running different versions
+//! are compared. The golden tests below pin the serialization.
+
+// External uses
+use num::BigUint;
+
+// Workspace uses
+use zksync_crypto::params::BALANCE_BIT_WIDTH;
+use zksync_types::{
+    tx::TxHash, Account, AccountId, BlockNumber, ExecutedOperations, TokenId, H256,
+};
+
+const BLOCK_CHECKSUM_DOMAIN: &[u8] = b"zksync-block-checksum-v1";
+const ACCOUNT_CHECKSUM_DOMAIN: &[u8] = b"zksync-account-checksum-v1";
+const BALANCE_BYTES: usize = BALANCE_BIT_WIDTH / 8;
+
+/// Operation of the block as it's included into the checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumOp {
+    pub tx_hash: TxHash,
+    /// `None` for the failed transactions.
+    pub block_index: Option<u32>,
+    pub success: bool,
+}
+
+impl From<&ExecutedOperations> for ChecksumOp {
+    fn from(op: &ExecutedOperations) -> Self {
+        match op {
+            ExecutedOperations::Tx(tx) => Self {
+                tx_hash: tx.signed_tx.tx.hash(),
+                block_index: tx.block_index,
+                success: tx.success,
+            },
+            ExecutedOperations::PriorityOp(op) => Self {
+                tx_hash: op.priority_op.tx_hash(),
+                block_index: Some(op.block_index),
+                success: true,
+            },
+        }
+    }
+}
+
+/// Checksum of the block number, its state root and the list of its operations.
+///
+/// The operations are taken in the order of execution, the failed transactions have no position
+/// in the block, so they follow the executed ones ordered by hash.
+pub fn block_checksum(
+    block_number: BlockNumber,
+    root_hash: H256,
+    mut ops: Vec<ChecksumOp>,
+) -> H256 {
+    ops.sort_by_key(|op| (op.block_index.is_none(), op.block_index, op.tx_hash));
+
+    let mut bytes = BLOCK_CHECKSUM_DOMAIN.to_vec();
+    bytes.extend_from_slice(&block_number.to_be_bytes());
+    bytes.extend_from_slice(root_hash.as_bytes());
+    bytes.extend_from_slice(&(ops.len() as u32).to_be_bytes());
+    for op in ops {
+        bytes.extend_from_slice(op.tx_hash.as_ref());
+        bytes.push(op.success as u8);
+    }
+    H256::from(tiny_keccak::keccak256(&bytes))
+}
+
+/// Checksum of the account state at the given block: its address, public key hash, nonce and
+/// the non-zero balances ordered by the token id.
+pub fn account_checksum(
+    account_id: AccountId,
+    block_number: BlockNumber,
+    account: &Account,
+) -> H256 {
+    let mut balances: Vec<(TokenId, BigUint)> = account
+        .get_nonzero_balances()
+        .into_iter()
+        .map(|(token, balance)| (token, balance.0))
+        .collect();
+    balances.sort_by_key(|(token, _)| *token);
+
+    let mut bytes = ACCOUNT_CHECKSUM_DOMAIN.to_vec();
+    bytes.extend_from_slice(&account_id.to_be_bytes());
+    bytes.extend_from_slice(&block_number.to_be_bytes());
+    bytes.extend_from_slice(account.address.as_bytes());
+    bytes.extend_from_slice(&account.pub_key_hash.data);
+    bytes.extend_from_slice(&account.nonce.to_be_bytes());
+    bytes.extend_from_slice(&(balances.len() as u32).to_be_bytes());
+    for (token, balance) in balances {
+        bytes.extend_from_slice(&token.to_be_bytes());
+        bytes.extend_from_slice(&encode_balance(&balance));
+    }
+    H256::from(tiny_keccak::keccak256(&bytes))
+}
+
+/// Encodes the balance as a fixed-width big-endian number, balances never exceed `BALANCE_BIT_WIDTH` bits.
+fn encode_balance(balance: &BigUint) -> [u8; BALANCE_BYTES] {
+    let bytes = balance.to_bytes_be();
+    assert!(
+        bytes.len() <= BALANCE_BYTES,
+        "Balance {} exceeds {} bits",
+        balance,
+        BALANCE_BIT_WIDTH
+    );
+    let mut encoded = [0u8; BALANCE_BYTES];
+    encoded[BALANCE_BYTES - bytes.len()..].copy_from_slice(&bytes);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use zksync_types::{Address, Nonce, PubKeyHash};
+
+    fn op(byte: u8, block_index: Option<u32>) -> ChecksumOp {
+        ChecksumOp {
+            tx_hash: TxHash::from_slice(&[byte; 32]).unwrap(),
+            block_index,
+            success: block_index.is_some(),
+        }
+    }
+
+    #[test]
+    fn block_checksum_golden() {
+        let root_hash = H256::repeat_byte(0xab);
+        let ops = vec![op(3, None), op(2, Some(1)), op(1, None), op(4, Some(0))];
+        let expected =
+            H256::from_str("eff66745560044e43c487c390ef476e6e02b4979a0e6d3aeecf2f3675edfed1a")
+                .unwrap();
+        assert_eq!(
+            block_checksum(BlockNumber(42), root_hash, ops.clone()),
+            expected
+        );
+
+        // The order the operations are loaded in doesn't matter.
+        let mut reversed = ops.clone();
+        reversed.reverse();
+        assert_eq!(
+            block_checksum(BlockNumber(42), root_hash, reversed),
+            expected
+        );
+        // Any difference in the block data changes the checksum.
+        assert_ne!(
+            block_checksum(BlockNumber(43), root_hash, ops.clone()),
+            expected
+        );
+        assert_ne!(
+            block_checksum(BlockNumber(42), H256::zero(), ops.clone()),
+            expected
+        );
+        assert_ne!(
+            block_checksum(BlockNumber(42), root_hash, ops[1..].to_vec()),
+            expected
+        );
+
+        let expected_empty =
+            H256::from_str("802f9215179393ec07e594346b1d595e90064db957669090d489d293296c69c8")
+                .unwrap();
+        assert_eq!(
+            block_checksum(BlockNumber(1), root_hash, Vec::new()),
+            expected_empty
+        );
+    }
+
+    #[test]
+    fn account_checksum_golden() {
+        let mut account = Account::default_with_address(&Address::repeat_byte(0x11));
+        account.pub_key_hash = PubKeyHash { data: [0x22; 20] };
+        account.nonce = Nonce(5);
+        account.set_balance(TokenId(2), BigUint::from(10u64).pow(18));
+        account.set_balance(TokenId(0), BigUint::from(7u32));
+        // Zero balances are not included.
+        account.set_balance(TokenId(1), BigUint::from(0u32));
+        // NFT.
+        account.set_balance(TokenId(65536), BigUint::from(1u32));
+
+        let expected =
+            H256::from_str("9d4023c18717e4a4a9e70d51b64d8afb97079fea55ba8cf61639f4c4bcc3ba56")
+                .unwrap();
+        assert_eq!(
+            account_checksum(AccountId(7), BlockNumber(42), &account),
+            expected
+        );
+        let expected_next_block =
+            H256::from_str("74b6e03faceb66eea75d0e5474fd4f940913a5658f5b7318a57fda8375685159")
+                .unwrap();
+        assert_eq!(
+            account_checksum(AccountId(7), BlockNumber(43), &account),
+            expected_next_block
+        );
+
+        account.nonce = Nonce(6);
+        assert_ne!(
+            account_checksum(AccountId(7), BlockNumber(42), &account),
+            expected
+        );
+    }
+
+    #[test]
+    fn balances_encoding() {
+        assert_eq!(encode_balance(&BigUint::from(0u32)), [0; 16]);
+        let mut expected = [0; 16];
+        expected[14] = 0x01;
+        expected[15] = 0x02;
+        assert_eq!(encode_balance(&BigUint::from(0x0102u32)), expected);
+        let max_balance = (BigUint::from(1u32) << BALANCE_BIT_WIDTH) - 1u32;
+        assert_eq!(encode_balance(&max_balance), [0xff; 16]);
+    }
+}
@@ -0,0 +1,532 @@
+//! Address of the client the request is made by.
+//!
+//! Behind the load balancer, the peer of every connection is the balancer itself, and the address
+//! of the client is only known from the `Forwarded` or `X-Forwarded-For` headers. Anyone can set
+//! these headers, so they are taken into account only if the peer is one of the trusted proxies.
+//! The addresses appended by the proxies are then walked from the nearest one, and the first
+//! address that doesn't belong to a trusted proxy is the client. Whatever the client put into
+//! the header itself is never reached unless every proxy in between is trusted. Only the header
+//! the proxies are configured to set is read, since they pass the other one from the client as is.
+
+// Built-in uses
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+    sync::Arc,
+};
+
+// External uses
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse},
+    http::header::HeaderMap,
+    Error, HttpMessage,
+};
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+
+/// Standard header of the proxies, RFC 7239.
+const FORWARDED_HEADER: &str = "forwarded";
+/// De-facto standard header of the proxies.
+const X_FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// Address of the client, stored in the request extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+/// Converts the IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) to IPv4, so the same client
+/// is always represented by the same address.
+fn normalize(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip) => match ip.octets() {
+            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, a, b, c, d] => {
+                IpAddr::V4(Ipv4Addr::new(a, b, c, d))
+            }
+            _ => IpAddr::V6(ip),
+        },
+        ip => ip,
+    }
+}
+
+/// Network in the CIDR notation, e.g. `10.0.0.0/8` or `fd00::/8`. An address without
+/// the prefix length is a network of the single address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, normalize(ip)) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => prefix_matches(
+                u32::from(network).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(u128::from(network), u128::from(ip), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, bits: u8, prefix_len: u8) -> bool {
+    prefix_len == 0 || (network ^ ip) >> (bits - prefix_len) == 0
+}
+
+impl FromStr for IpNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s, None),
+        };
+        let addr = normalize(
+            addr.trim()
+                .parse()
+                .map_err(|_| anyhow::anyhow!("Invalid address of the network `{}`", s))?,
+        );
+        let max_prefix_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len
+                .trim()
+                .parse()
+                .ok()
+                .filter(|prefix_len| *prefix_len <= max_prefix_len)
+                .ok_or_else(|| anyhow::anyhow!("Invalid prefix length of the network `{}`", s))?,
+            None => max_prefix_len,
+        };
+        Ok(Self { addr, prefix_len })
+    }
+}
+
+/// Parses the node of the `Forwarded` header or the `X-Forwarded-For` entry: an address,
+/// optionally quoted, with the port, IPv6 addresses with the port are in square brackets.
+/// Obfuscated identifiers and `unknown` are not addresses.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Some(bracketed) = node.strip_prefix('[') {
+        let (ip, _port) = bracketed.split_once(']')?;
+        return ip.parse::<Ipv6Addr>().ok().map(|ip| normalize(ip.into()));
+    }
+    let ip = node
+        .parse::<IpAddr>()
+        .ok()
+        .or_else(|| node.parse::<SocketAddr>().ok().map(|addr| addr.ip()))?;
+    Some(normalize(ip))
+}
+
+/// Header the trusted proxies put the client address into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForwardedHeader {
+    Forwarded,
+    XForwardedFor,
+}
+
+impl ForwardedHeader {
+    /// Returns the addresses in the order the proxies appended them, `None` for the nodes
+    /// that are not addresses.
+    fn nodes(self, headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+        let name = match self {
+            Self::Forwarded => FORWARDED_HEADER,
+            Self::XForwardedFor => X_FORWARDED_FOR_HEADER,
+        };
+        let elements = headers
+            .get_all(name)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        match self {
+            Self::Forwarded => elements
+                .map(|element| {
+                    element
+                        .split(';')
+                        .filter_map(|pair| pair.split_once('='))
+                        .find(|(name, _)| name.trim().eq_ignore_ascii_case("for"))
+                        .and_then(|(_, node)| parse_node(node))
+                })
+                .collect(),
+            Self::XForwardedFor => elements.map(parse_node).collect(),
+        }
+    }
+}
+
+impl FromStr for ForwardedHeader {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            FORWARDED_HEADER => Ok(Self::Forwarded),
+            X_FORWARDED_FOR_HEADER => Ok(Self::XForwardedFor),
+            _ => anyhow::bail!(
+                "Unsupported header of the proxies `{}`, expected `Forwarded` or `X-Forwarded-For`",
+                s
+            ),
+        }
+    }
+}
+
+/// Resolves the client addresses of the requests, meant to be used with `Scope::wrap_fn`.
+#[derive(Debug, Clone)]
+pub struct ClientIpResolver {
+    trusted_proxies: Arc<Vec<IpNetwork>>,
+    header: ForwardedHeader,
+}
+
+impl ClientIpResolver {
+    pub fn new(trusted_proxies: Vec<IpNetwork>, header: ForwardedHeader) -> Self {
+        Self {
+            trusted_proxies: Arc::new(trusted_proxies),
+            header,
+        }
+    }
+
+    pub fn from_config(config: &RestApiConfig) -> anyhow::Result<Self> {
+        let trusted_proxies = config
+            .trusted_proxies()
+            .into_iter()
+            .map(str::parse)
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self::new(
+            trusted_proxies,
+            config.trusted_proxies_header.parse()?,
+        ))
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies
+            .iter()
+            .any(|network| network.contains(ip))
+    }
+
+    /// Returns the address of the client, `None` if the peer address is unknown.
+    pub fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> Option<IpAddr> {
+        let mut client = normalize(peer?);
+        if !self.is_trusted(client) {
+            return Some(client);
+        }
+        for node in self.header.nodes(headers).into_iter().rev() {
+            match node {
+                Some(ip) => client = ip,
+                // The proxy doesn't disclose the address, nothing before it can be trusted.
+                None => break,
+            }
+            if !self.is_trusted(client) {
+                break;
+            }
+        }
+        Some(client)
+    }
+
+    /// Stores the address of the client in the request extensions and handles the request.
+    pub fn resolve<S, B>(
+        &self,
+        req: ServiceRequest,
+        service: &S,
+    ) -> impl Future<Output = Result<ServiceResponse<B>, Error>>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    {
+        let peer = req.peer_addr().map(|addr| addr.ip());
+        if let Some(ip) = self.client_ip(peer, req.headers()) {
+            req.extensions_mut().insert(ClientIp(ip));
+        }
+        service.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{
+        test::{self, TestRequest},
+        web, App, HttpRequest,
+    };
+
+    const PEER: &str = "10.0.0.1";
+    const CLIENT: &str = "203.0.113.7";
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn resolver(networks: &[&str], header: ForwardedHeader) -> ClientIpResolver {
+        ClientIpResolver::new(
+            networks
+                .iter()
+                .map(|network| network.parse().unwrap())
+                .collect(),
+            header,
+        )
+    }
+
+    fn client_ip(resolver: &ClientIpResolver, peer: &str, headers: &[(&str, &str)]) -> IpAddr {
+        let req = headers
+            .iter()
+            .fold(TestRequest::default(), |req, &header| {
+                req.append_header(header)
+            })
+            .to_http_request();
+        resolver.client_ip(Some(ip(peer)), req.headers()).unwrap()
+    }
+
+    #[test]
+    fn networks_parsing() {
+        let network: IpNetwork = "10.0.0.0/8".parse().unwrap();
+        assert!(network.contains(ip("10.255.0.1")));
+        assert!(network.contains(ip("::ffff:10.1.2.3")));
+        assert!(!network.contains(ip("11.0.0.1")));
+        assert!(!network.contains(ip("::a00:1")));
+
+        let network: IpNetwork = "fd00::/8".parse().unwrap();
+        assert!(network.contains(ip("fd12::1")));
+        assert!(!network.contains(ip("fe80::1")));
+
+        let network: IpNetwork = " 192.0.2.1 ".parse().unwrap();
+        assert!(network.contains(ip("192.0.2.1")));
+        assert!(!network.contains(ip("192.0.2.2")));
+
+        let network: IpNetwork = "0.0.0.0/0".parse().unwrap();
+        assert!(network.contains(ip("198.51.100.1")));
+        assert!(!network.contains(ip("2001:db8::1")));
+
+        // Mapped addresses are the same as IPv4 ones.
+        let network: IpNetwork = "::ffff:10.0.0.0/8".parse().unwrap();
+        assert_eq!(network, "10.0.0.0/8".parse().unwrap());
+
+        for invalid in [
+            "",
+            "10.0.0.0/33",
+            "fd00::/129",
+            "10.0.0.0/",
+            "10.0.0/8",
+            "fd00::/x",
+        ] {
+            assert!(invalid.parse::<IpNetwork>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn nodes_parsing() {
+        assert_eq!(parse_node(CLIENT), Some(ip(CLIENT)));
+        assert_eq!(parse_node(" 203.0.113.7:4711 "), Some(ip(CLIENT)));
+        assert_eq!(parse_node("\"203.0.113.7\""), Some(ip(CLIENT)));
+        assert_eq!(parse_node("2001:db8::17"), Some(ip("2001:db8::17")));
+        assert_eq!(
+            parse_node("\"[2001:db8::17]:4711\""),
+            Some(ip("2001:db8::17"))
+        );
+        assert_eq!(parse_node("[2001:db8::17]"), Some(ip("2001:db8::17")));
+        assert_eq!(parse_node("::ffff:203.0.113.7"), Some(ip(CLIENT)));
+        assert_eq!(parse_node("[::ffff:cb00:7107]:80"), Some(ip(CLIENT)));
+        assert_eq!(parse_node("unknown"), None);
+        assert_eq!(parse_node("_hidden"), None);
+        assert_eq!(parse_node(""), None);
+    }
+
+    #[test]
+    fn headers_parsing() {
+        assert_eq!(
+            "Forwarded".parse::<ForwardedHeader>().unwrap(),
+            ForwardedHeader::Forwarded
+        );
+        assert_eq!(
+            " x-forwarded-for".parse::<ForwardedHeader>().unwrap(),
+            ForwardedHeader::XForwardedFor
+        );
+        assert!("X-Real-IP".parse::<ForwardedHeader>().is_err());
+    }
+
+    #[test]
+    fn untrusted_peer() {
+        let headers = [
+            ("X-Forwarded-For", "198.51.100.1"),
+            ("Forwarded", "for=198.51.100.2"),
+        ];
+        for header in [ForwardedHeader::Forwarded, ForwardedHeader::XForwardedFor] {
+            // Nobody is trusted.
+            let resolver = resolver(&[], header);
+            assert_eq!(client_ip(&resolver, PEER, &headers), ip(PEER));
+            // The peer is not a trusted proxy.
+            let resolver = self::resolver(&["192.168.0.0/16"], header);
+            assert_eq!(client_ip(&resolver, PEER, &headers), ip(PEER));
+            assert_eq!(client_ip(&resolver, "::ffff:10.0.0.1", &[]), ip(PEER));
+            assert_eq!(resolver.client_ip(None, &HeaderMap::new()), None);
+        }
+    }
+
+    #[test]
+    fn trusted_peer() {
+        use ForwardedHeader::{Forwarded, XForwardedFor};
+
+        let trusted = ["10.0.0.0/8", "fd00::/8"];
+        let cases: &[(ForwardedHeader, &str, &[(&str, &str)], &str)] = &[
+            // No headers, the proxy itself made the request.
+            (XForwardedFor, PEER, &[], PEER),
+            (Forwarded, PEER, &[], PEER),
+            (XForwardedFor, PEER, &[("X-Forwarded-For", CLIENT)], CLIENT),
+            (
+                XForwardedFor,
+                PEER,
+                &[("X-Forwarded-For", "203.0.113.7:4711")],
+                CLIENT,
+            ),
+            (
+                XForwardedFor,
+                "fd00::1",
+                &[("X-Forwarded-For", "2001:db8::17")],
+                "2001:db8::17",
+            ),
+            (
+                XForwardedFor,
+                "::ffff:10.0.0.1",
+                &[("X-Forwarded-For", "::ffff:203.0.113.7")],
+                CLIENT,
+            ),
+            (
+                Forwarded,
+                PEER,
+                &[("Forwarded", "for=203.0.113.7;proto=https")],
+                CLIENT,
+            ),
+            (
+                Forwarded,
+                PEER,
+                &[("Forwarded", "proto=https;For=\"[2001:db8::17]:4711\"")],
+                "2001:db8::17",
+            ),
+            // The client spoofs the header, the address appended by the proxy is used.
+            (
+                XForwardedFor,
+                PEER,
+                &[("X-Forwarded-For", "1.2.3.4, 203.0.113.7")],
+                CLIENT,
+            ),
+            (
+                Forwarded,
+                PEER,
+                &[("Forwarded", "for=1.2.3.4, for=203.0.113.7")],
+                CLIENT,
+            ),
+            // Same, with the values appended as separate headers.
+            (
+                XForwardedFor,
+                PEER,
+                &[("X-Forwarded-For", "1.2.3.4"), ("X-Forwarded-For", CLIENT)],
+                CLIENT,
+            ),
+            (
+                Forwarded,
+                PEER,
+                &[
+                    ("Forwarded", "for=1.2.3.4"),
+                    ("Forwarded", "for=203.0.113.7"),
+                ],
+                CLIENT,
+            ),
+            // The client sets the header the proxies don't use.
+            (
+                XForwardedFor,
+                PEER,
+                &[("Forwarded", "for=1.2.3.4"), ("X-Forwarded-For", CLIENT)],
+                CLIENT,
+            ),
+            (XForwardedFor, PEER, &[("Forwarded", "for=1.2.3.4")], PEER),
+            (
+                Forwarded,
+                PEER,
+                &[
+                    ("Forwarded", "for=203.0.113.7"),
+                    ("X-Forwarded-For", "1.2.3.4"),
+                ],
+                CLIENT,
+            ),
+            (Forwarded, PEER, &[("X-Forwarded-For", "1.2.3.4")], PEER),
+            // Chain of the trusted proxies.
+            (
+                XForwardedFor,
+                PEER,
+                &[("X-Forwarded-For", "1.2.3.4, 203.0.113.7, 10.1.1.1")],
+                CLIENT,
+            ),
+            (
+                Forwarded,
+                PEER,
+                &[(
+                    "Forwarded",
+                    "for=1.2.3.4, for=203.0.113.7, for=\"[fd00::2]\"",
+                )],
+                CLIENT,
+            ),
+            // Only trusted proxies in the header, the farthest one is the client.
+            (
+                XForwardedFor,
+                PEER,
+                &[("X-Forwarded-For", "10.2.2.2, 10.1.1.1")],
+                "10.2.2.2",
+            ),
+            // The proxy doesn't disclose the address, the nearest known address is used.
+            (
+                XForwardedFor,
+                PEER,
+                &[("X-Forwarded-For", "1.2.3.4, unknown")],
+                PEER,
+            ),
+            (
+                Forwarded,
+                PEER,
+                &[("Forwarded", "for=1.2.3.4, for=_hidden, for=10.1.1.1")],
+                "10.1.1.1",
+            ),
+            (Forwarded, PEER, &[("Forwarded", "proto=https")], PEER),
+        ];
+        for &(header, peer, headers, expected) in cases {
+            let resolver = resolver(&trusted, header);
+            assert_eq!(
+                client_ip(&resolver, peer, headers),
+                ip(expected),
+                "header: {:?}, peer: {}, headers: {:?}",
+                header,
+                peer,
+                headers
+            );
+        }
+    }
+
+    async fn client_ip_handler(req: HttpRequest) -> web::Json<Option<IpAddr>> {
+        web::Json(req.extensions().get::<ClientIp>().map(|ip| ip.0))
+    }
+
+    #[actix_rt::test]
+    async fn client_ip_in_extensions() {
+        let resolver = resolver(&["10.0.0.0/8"], ForwardedHeader::XForwardedFor);
+        let app = test::init_service(
+            App::new().service(
+                web::scope("/api")
+                    .wrap_fn(move |req, srv| resolver.resolve(req, srv))
+                    .route("/client_ip", web::get().to(client_ip_handler)),
+            ),
+        )
+        .await;
+
+        let request = |peer: &str| {
+            TestRequest::get()
+                .uri("/api/client_ip")
+                .peer_addr(peer.parse().unwrap())
+                .insert_header(("X-Forwarded-For", CLIENT))
+                .to_request()
+        };
+        let client: Option<IpAddr> = test::read_response_json(&app, request("10.0.0.1:4711")).await;
+        assert_eq!(client, Some(ip(CLIENT)));
+        let client: Option<IpAddr> =
+            test::read_response_json(&app, request("[2001:db8::17]:4711")).await;
+        assert_eq!(client, Some(ip("2001:db8::17")));
+    }
+}
@@ -5,18 +5,28 @@
 use std::time::Instant;
 // External uses
 use actix_web::{web, Scope};
+use bigdecimal::BigDecimal;
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
 use zksync_api_types::v02::ZksyncVersion;
 use zksync_config::ZkSyncConfig;
-use zksync_types::{network::Network, Address};
+use zksync_crypto::params::{
+    self, max_account_id, max_fungible_token_id, max_token_id, BALANCE_BIT_WIDTH,
+};
+use zksync_types::{
+    event::transaction::TransactionType,
+    helpers::{max_packable_fee_amount, max_packable_token_amount},
+    network::Network,
+    AccountId, Address, TokenId,
+};
+use zksync_utils::{ratio_to_big_decimal, BigUintSerdeWrapper};
 
 // Local uses
-use super::response::ApiResult;
+use super::{error::Error, response::ApiResult};
+use crate::utils::disabled_tx_types::DisabledTxTypes;
 
-/// Shared data between `api/v0.2/config` endpoints.
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiConfigData {
     network: Network,
@@ -24,6 +34,8 @@ pub struct ApiConfigData {
     gov_contract: Address,
     deposit_confirmations: u64,
     zksync_version: ZksyncVersion,
+    /// Types of transactions that are currently accepted by the server.
+    enabled_tx_types: Vec<TransactionType>,
     // TODO: server_version (ZKS-627)
 }
 
@@ -35,25 +47,148 @@ impl ApiConfigData {
             gov_contract: config.contracts.governance_addr,
             deposit_confirmations: config.eth_watch.confirmations_for_eth_event,
             zksync_version: ZksyncVersion::ContractV4,
+            enabled_tx_types: TransactionType::L2_TYPES.to_vec(),
+        }
+    }
+}
+
+/// Floating-point format the amounts are packed into: `mantissa * exponent_base ^ exponent`.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackingParams {
+    exponent_bit_width: usize,
+    mantissa_bit_width: usize,
+    exponent_base: u32,
+    max_packable_amount: BigUintSerdeWrapper,
+}
+
+/// Minimum amount of the transfers to other accounts and of the withdrawals of the token.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MinTransferAmount {
+    token_id: TokenId,
+    /// Amount in the smallest units of the token. Transfer amounts are packed, so the smallest
+    /// accepted transfer amount is the nearest packable amount not below this one.
+    amount: BigUintSerdeWrapper,
+}
+
+/// Protocol limits the transactions are checked against, so that clients don't have to hardcode them.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiLimitsData {
+    max_batch_size: u64,
+    max_batch_authors: u64,
+    /// Amounts of transfers and swaps are packed.
+    transfer_amount_packing: PackingParams,
+    /// Withdrawal amounts are not packed and are only limited by the bit width.
+    withdrawal_amount_bit_width: usize,
+    /// Fees of all the transactions are packed.
+    fee_packing: PackingParams,
+    max_token_id: TokenId,
+    max_fungible_token_id: TokenId,
+    max_account_id: AccountId,
+    /// Sizes of the blocks in chunks, each transaction takes a fixed amount of chunks depending on its type.
+    block_chunk_sizes: Vec<usize>,
+    /// Minimum amounts of the transfers and withdrawals of the tokens, sorted by the token id.
+    min_transfer_amounts: Vec<MinTransferAmount>,
+    /// Minimum amount in USD of the transfers and withdrawals of the tokens without the explicit minimum.
+    min_transfer_amount_usd: Option<BigDecimal>,
+}
+
+impl ApiLimitsData {
+    pub fn new(config: &ZkSyncConfig) -> Self {
+        let mut min_transfer_amounts: Vec<_> = config
+            .api
+            .common
+            .min_transfer_amounts()
+            .into_iter()
+            .map(|(token_id, amount)| MinTransferAmount {
+                token_id,
+                amount: BigUintSerdeWrapper(amount),
+            })
+            .collect();
+        min_transfer_amounts.sort_by_key(|minimum| minimum.token_id);
+
+        Self {
+            max_batch_size: config.api.common.max_number_of_transactions_per_batch,
+            max_batch_authors: config.api.common.max_number_of_authors_per_batch,
+            transfer_amount_packing: PackingParams {
+                exponent_bit_width: params::AMOUNT_EXPONENT_BIT_WIDTH,
+                mantissa_bit_width: params::AMOUNT_MANTISSA_BIT_WIDTH,
+                exponent_base: 10,
+                max_packable_amount: BigUintSerdeWrapper(max_packable_token_amount()),
+            },
+            withdrawal_amount_bit_width: BALANCE_BIT_WIDTH,
+            fee_packing: PackingParams {
+                exponent_bit_width: params::FEE_EXPONENT_BIT_WIDTH,
+                mantissa_bit_width: params::FEE_MANTISSA_BIT_WIDTH,
+                exponent_base: 10,
+                max_packable_amount: BigUintSerdeWrapper(max_packable_fee_amount()),
+            },
+            max_token_id: max_token_id(),
+            max_fungible_token_id: max_fungible_token_id(),
+            max_account_id: max_account_id(),
+            block_chunk_sizes: config.chain.state_keeper.block_chunk_sizes.clone(),
+            min_transfer_amounts,
+            min_transfer_amount_usd: config
+                .api
+                .common
+                .min_transfer_amount_usd()
+                .map(|amount| ratio_to_big_decimal(&amount, 6)),
         }
     }
 }
 
+/// Shared data between `api/v0.2/config` endpoints.
+#[derive(Debug, Clone)]
+struct ApiConfigScopeData {
+    config: ApiConfigData,
+    limits: ApiLimitsData,
+    disabled_tx_types: DisabledTxTypes,
+}
+
+impl ApiConfigScopeData {
+    async fn config(&self) -> Result<ApiConfigData, Error> {
+        let enabled_tx_types = self
+            .disabled_tx_types
+            .enabled()
+            .await
+            .map_err(Error::storage)?;
+
+        Ok(ApiConfigData {
+            enabled_tx_types,
+            ..self.config.clone()
+        })
+    }
+}
+
 // Server implementation
 
-async fn config_endpoint(data: web::Data<ApiConfigData>) -> ApiResult<ApiConfigData> {
+async fn config_endpoint(data: web::Data<ApiConfigScopeData>) -> ApiResult<ApiConfigData> {
     let start = Instant::now();
-    let res = ApiResult::Ok(*data.into_inner());
+    let res = data.config().await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "config_endpoint");
     res
 }
 
-pub fn api_scope(config: &ZkSyncConfig) -> Scope {
-    let data = ApiConfigData::new(config);
+async fn limits_endpoint(data: web::Data<ApiConfigScopeData>) -> ApiResult<ApiLimitsData> {
+    let start = Instant::now();
+    let res = ApiResult::Ok(data.limits.clone());
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "limits_endpoint");
+    res
+}
+
+pub fn api_scope(config: &ZkSyncConfig, disabled_tx_types: DisabledTxTypes) -> Scope {
+    let data = ApiConfigScopeData {
+        config: ApiConfigData::new(config),
+        limits: ApiLimitsData::new(config),
+        disabled_tx_types,
+    };
 
     web::scope("config")
         .app_data(web::Data::new(data))
         .route("", web::get().to(config_endpoint))
+        .route("/limits", web::get().to(limits_endpoint))
 }
 
 #[cfg(test)]
@@ -63,7 +198,9 @@ mod tests {
         test_utils::{deserialize_response_result, TestServerConfig},
         SharedData,
     };
+    use actix_web::http::StatusCode;
     use zksync_api_types::v02::ApiVersion;
+    use zksync_types::helpers::{is_fee_amount_packable, is_token_amount_packable};
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -76,15 +213,64 @@ mod tests {
         let shared_data = SharedData {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
         };
+        let disabled_tx_types = DisabledTxTypes::new(cfg.pool.clone());
         let (client, server) = cfg.start_server(
-            |cfg: &TestServerConfig| api_scope(&cfg.config),
+            {
+                let disabled_tx_types = disabled_tx_types.clone();
+                move |cfg: &TestServerConfig| api_scope(&cfg.config, disabled_tx_types.clone())
+            },
             Some(shared_data),
         );
         let response = client.config().await?;
         let api_config: ApiConfigData = deserialize_response_result(response)?;
         assert_eq!(api_config, ApiConfigData::new(&cfg.config));
 
+        // Disable `Swap` transactions at runtime.
+        cfg.pool
+            .access_storage()
+            .await?
+            .misc_schema()
+            .set_tx_type_disabled("Swap", true)
+            .await?;
+        // Invalidation is normally performed by the database listener.
+        disabled_tx_types.invalidate().await;
+
+        let response = client.config().await?;
+        let api_config: ApiConfigData = deserialize_response_result(response)?;
+        assert!(!api_config.enabled_tx_types.contains(&TransactionType::Swap));
+        assert!(api_config
+            .enabled_tx_types
+            .contains(&TransactionType::Transfer));
+
+        // Enable `Swap` transactions back.
+        cfg.pool
+            .access_storage()
+            .await?
+            .misc_schema()
+            .set_tx_type_disabled("Swap", false)
+            .await?;
+        disabled_tx_types.invalidate().await;
+
+        let response = client.config().await?;
+        let api_config: ApiConfigData = deserialize_response_result(response)?;
+        assert_eq!(api_config, ApiConfigData::new(&cfg.config));
+
+        let response = client.config_limits().await?;
+        let limits: ApiLimitsData = deserialize_response_result(response)?;
+        assert_eq!(limits, ApiLimitsData::new(&cfg.config));
+        assert_eq!(
+            limits.block_chunk_sizes,
+            cfg.config.chain.state_keeper.block_chunk_sizes
+        );
+        assert!(is_token_amount_packable(
+            &limits.transfer_amount_packing.max_packable_amount.0
+        ));
+        assert!(is_fee_amount_packable(
+            &limits.fee_packing.max_packable_amount.0
+        ));
+
         server.stop().await;
         Ok(())
     }
@@ -3,44 +3,26 @@ use std::fmt::{Display, Formatter};
 
 // External uses
 use serde::{Deserialize, Serialize};
-use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_json::Value;
 use thiserror::Error;
 
 // Workspace uses
-use zksync_api_types::v02::pagination::{UnknownFromParameter, MAX_LIMIT};
+pub use zksync_api_types::error_code::ErrorCode;
+use zksync_api_types::v02::{
+    access_token::MAX_ACCESS_TOKEN_VALIDITY_DAYS,
+    block::MAX_BLOCKS_BY_NUMBERS_LIMIT,
+    fee::MAX_FEE_STATISTICS_BUCKETS,
+    pagination::{UnknownFromParameter, MAX_LIMIT},
+    token::{MAX_TOKEN_ACTIVITY_DAYS, MAX_TOKEN_PRICES_LIMIT},
+};
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_types::{Address, BlockNumber};
 
 // Local uses
-use crate::{api_server::tx_sender::SubmitError, fee_ticker::PriceError};
-
-#[derive(Serialize_repr, Debug, Deserialize_repr, Clone, PartialEq)]
-#[repr(u16)]
-pub enum ErrorCode {
-    UnreacheableError = 0,
-    CoreApiError = 100,
-    TokenZeroPriceError = 200,
-    InvalidCurrency = 201,
-    InvalidBlockPosition = 202,
-    InvalidAccountIdOrAddress = 203,
-    AccountNotFound = 204,
-    TransactionNotFound = 205,
-    PaginationLimitTooBig = 206,
-    QueryDeserializationError = 207,
-    InvalidNFTTokenId = 208,
-    StorageError = 300,
-    TokenNotFound = 500,
-    ExternalApiError = 501,
-    InternalError = 600,
-    AccountCloseDisabled = 601,
-    InvalidParams = 602,
-    UnsupportedFastProcessing = 603,
-    IncorrectTx = 604,
-    TxAddError = 605,
-    InappropriateFeeToken = 606,
-    CommunicationCoreServer = 607,
-    Toggle2FAError = 608,
-    Other = 60_000,
-}
+use crate::{
+    api_server::{tx_sender::SubmitError, webhooks::preferences::InvalidPreferences},
+    fee_ticker::PriceError,
+};
 
 /// Error object in a response
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -48,7 +30,16 @@ pub enum ErrorCode {
 pub struct Error {
     pub error_type: String,
     pub code: ErrorCode,
+    /// Stable name of the error code.
+    pub name: String,
     pub message: String,
+    /// Details of the error, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// Seconds after which the request can be retried, sent in the `Retry-After` header
+    /// of the `503 Service Unavailable` response.
+    #[serde(skip)]
+    pub retry_after_secs: Option<u64>,
 }
 
 /// Trait that can be used to map custom errors to the object.
@@ -60,6 +51,14 @@ pub trait ApiError: std::fmt::Display {
     fn message(&self) -> String {
         self.to_string()
     }
+
+    fn data(&self) -> Option<Value> {
+        None
+    }
+
+    fn retry_after_secs(&self) -> Option<u64> {
+        None
+    }
 }
 
 impl<T> From<T> for Error
@@ -67,10 +66,14 @@ where
     T: ApiError,
 {
     fn from(t: T) -> Error {
+        let code = t.code();
         Error {
             error_type: t.error_type(),
-            code: t.code(),
+            code,
+            name: code.name().to_owned(),
             message: t.message(),
+            data: t.data(),
+            retry_after_secs: t.retry_after_secs(),
         }
     }
 }
@@ -83,6 +86,18 @@ impl Error {
     pub fn core_api(err: impl Display) -> Error {
         Error::from(CoreApiError::new(err))
     }
+
+    pub fn not_found(resource: &'static str, id: impl ToString) -> Error {
+        Error::from(NotFoundError {
+            resource,
+            id: id.to_string(),
+        })
+    }
+
+    /// Whether the error reports the resource requested by its identifier as missing.
+    pub fn is_not_found(&self) -> bool {
+        self.code == ErrorCode::NotFound
+    }
 }
 
 #[derive(Error, Debug)]
@@ -95,7 +110,7 @@ pub enum InvalidDataError {
     InvalidAccountIdOrAddress,
     #[error("Account is not found")]
     AccountNotFound,
-    #[error("Cannot parse currency. There are only token_id, usd options")]
+    #[error("Cannot parse currency. There are only token_id, usd, eth options")]
     InvalidCurrency,
     #[error("Transaction is not found")]
     TransactionNotFound,
@@ -103,6 +118,73 @@ pub enum InvalidDataError {
     PaginationLimitTooBig,
     #[error("NFT token ID should be greater than or equal to {}", MIN_NFT_TOKEN_ID)]
     InvalidNFTTokenId,
+    #[error("Cannot parse public key hash. It should be a non-zero hash in the `sync:<40 hex characters>` format")]
+    InvalidPubKeyHash,
+    #[error(
+        "Number of requested tokens should be between 1 and {}",
+        MAX_TOKEN_PRICES_LIMIT
+    )]
+    TooManyTokens,
+    #[error(
+        "`from` should be earlier than `to`, and the range should contain less than {} buckets",
+        MAX_FEE_STATISTICS_BUCKETS
+    )]
+    InvalidFeeStatisticsRange,
+    #[error("Either the transaction hashes or the transactions of the batch should be provided")]
+    InvalidBatchHashRequest,
+    #[error("Request body should not be larger than {0} bytes")]
+    PayloadTooLarge(usize),
+    #[error("Cannot parse token address. It should be an L1 address in the `0x<40 hex characters>` format")]
+    InvalidTokenAddress,
+    #[error(
+        "`from` should not be greater than `to`, and the range should contain at most {0} blocks"
+    )]
+    InvalidBlocksExportRange(u32),
+    #[error("`from`, `limit` and `direction` should be provided unless all the tokens are requested with `all=true`")]
+    MissingPaginationParams,
+    #[error(
+        "Number of requested blocks should be between 1 and {}",
+        MAX_BLOCKS_BY_NUMBERS_LIMIT
+    )]
+    TooManyBlockNumbers,
+    #[error("`minBalanceUsd` should be a non-negative number")]
+    InvalidMinBalanceUsd,
+    #[error("Number of requested token holders should be between 1 and {0}")]
+    InvalidTokenHoldersLimit(u32),
+    #[error("Holders are tracked only for the fungible tokens, use `nft/{{id}}/owner` for NFTs")]
+    NftHoldersNotTracked,
+    #[error("The address is reserved and is not a user account")]
+    NotUserAccount,
+    #[error("Either a single transaction or the transactions of the batch should be provided")]
+    InvalidSignMessageRequest,
+    #[error("Transaction hash prefix should consist of {0} to 64 hex characters, optionally prefixed with `0x` or `sync-tx:`")]
+    InvalidTxHashPrefix(usize),
+    #[error("`secondAccount` and `counterparty` filters can't be used together")]
+    ConflictingAccountTxsFilters,
+    #[error("Exactly one of `checkpoint`, `sinceTx` and `sinceBlock` should be provided: {0}")]
+    InvalidAccountTxsDeltaQuery(String),
+    #[error("Transaction is not a successful withdrawal")]
+    NotWithdrawal,
+    #[error(
+        "Withdrawal is not verified yet, the execution can be estimated once its block is verified"
+    )]
+    WithdrawalNotVerified,
+    #[error("Cannot parse `{name}` path parameter. It should be one of: {}", .formats.join(", "))]
+    InvalidPathParameter {
+        name: String,
+        formats: &'static [&'static str],
+    },
+    #[error(
+        "Search query should be a transaction, batch or L1 operation hash, an address, an account id, a block number or a token symbol"
+    )]
+    InvalidSearchQuery,
+    #[error("Transaction is not included into a block or is rejected, so it has no public data")]
+    TxHasNoPublicData,
+    #[error(
+        "`from` should not be later than `to`, and the range should contain at most {} days",
+        MAX_TOKEN_ACTIVITY_DAYS
+    )]
+    InvalidTokenActivityRange,
 }
 
 impl ApiError for InvalidDataError {
@@ -120,6 +202,209 @@ impl ApiError for InvalidDataError {
             Self::TransactionNotFound => ErrorCode::TransactionNotFound,
             Self::PaginationLimitTooBig => ErrorCode::PaginationLimitTooBig,
             Self::InvalidNFTTokenId => ErrorCode::InvalidNFTTokenId,
+            Self::InvalidPubKeyHash => ErrorCode::InvalidPubKeyHash,
+            Self::TooManyTokens => ErrorCode::TooManyTokens,
+            Self::InvalidFeeStatisticsRange => ErrorCode::InvalidFeeStatisticsRange,
+            Self::InvalidBatchHashRequest => ErrorCode::InvalidBatchHashRequest,
+            Self::PayloadTooLarge(_) => ErrorCode::PayloadTooLarge,
+            Self::InvalidTokenAddress => ErrorCode::InvalidTokenAddress,
+            Self::InvalidBlocksExportRange(_) => ErrorCode::InvalidBlocksExportRange,
+            Self::MissingPaginationParams => ErrorCode::MissingPaginationParams,
+            Self::TooManyBlockNumbers => ErrorCode::TooManyBlockNumbers,
+            Self::InvalidMinBalanceUsd => ErrorCode::InvalidMinBalanceUsd,
+            Self::InvalidTokenHoldersLimit(_) => ErrorCode::InvalidTokenHoldersLimit,
+            Self::NftHoldersNotTracked => ErrorCode::NftHoldersNotTracked,
+            Self::NotUserAccount => ErrorCode::NotUserAccount,
+            Self::InvalidSignMessageRequest => ErrorCode::InvalidSignMessageRequest,
+            Self::InvalidTxHashPrefix(_) => ErrorCode::InvalidTxHashPrefix,
+            Self::ConflictingAccountTxsFilters => ErrorCode::ConflictingAccountTxsFilters,
+            Self::InvalidAccountTxsDeltaQuery(_) => ErrorCode::InvalidAccountTxsDeltaQuery,
+            Self::NotWithdrawal => ErrorCode::NotWithdrawal,
+            Self::WithdrawalNotVerified => ErrorCode::WithdrawalNotVerified,
+            Self::InvalidPathParameter { .. } => ErrorCode::InvalidPathParameter,
+            Self::InvalidSearchQuery => ErrorCode::InvalidSearchQuery,
+            Self::TxHasNoPublicData => ErrorCode::TxHasNoPublicData,
+            Self::InvalidTokenActivityRange => ErrorCode::InvalidTokenActivityRange,
+        }
+    }
+
+    fn data(&self) -> Option<Value> {
+        match self {
+            Self::InvalidPathParameter { name, formats } => Some(serde_json::json!({
+                "parameter": name,
+                "acceptedFormats": formats,
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// Resource requested by its identifier is not found, reported uniformly by all the scopes.
+#[derive(Error, Debug)]
+#[error("The {resource} `{id}` is not found")]
+pub struct NotFoundError {
+    pub resource: &'static str,
+    pub id: String,
+}
+
+impl ApiError for NotFoundError {
+    fn error_type(&self) -> String {
+        String::from("notFoundError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::NotFound
+    }
+
+    fn data(&self) -> Option<Value> {
+        Some(serde_json::json!({
+            "resource": self.resource,
+            "id": self.id,
+        }))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum BlocksExportError {
+    #[error("Blocks export requires a valid authorization token")]
+    Unauthorized,
+    #[error("Another blocks export is already running for this token")]
+    AlreadyInProgress,
+    #[error("Too many blocks exports are running, please try again later")]
+    TooManyExports,
+}
+
+impl ApiError for BlocksExportError {
+    fn error_type(&self) -> String {
+        String::from("blocksExportError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::Unauthorized => ErrorCode::BlocksExportUnauthorized,
+            Self::AlreadyInProgress => ErrorCode::BlocksExportInProgress,
+            Self::TooManyExports => ErrorCode::TooManyBlocksExports,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum StateRootCheckError {
+    #[error("State root check requires a valid authorization token")]
+    Unauthorized,
+    #[error("Another state root check is already running")]
+    AlreadyInProgress,
+    #[error("State root checks are too frequent, please try again in {retry_after_secs} seconds")]
+    Throttled { retry_after_secs: u64 },
+    #[error("Block {0} is not committed")]
+    BlockNotFound(BlockNumber),
+    #[error("There is no account tree snapshot before block {0}")]
+    SnapshotNotFound(BlockNumber),
+}
+
+impl ApiError for StateRootCheckError {
+    fn error_type(&self) -> String {
+        String::from("stateRootCheckError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::Unauthorized => ErrorCode::StateRootCheckUnauthorized,
+            Self::AlreadyInProgress => ErrorCode::StateRootCheckInProgress,
+            Self::Throttled { .. } => ErrorCode::StateRootCheckThrottled,
+            Self::BlockNotFound(_) => ErrorCode::StateRootCheckBlockNotFound,
+            Self::SnapshotNotFound(_) => ErrorCode::AccountTreeSnapshotNotFound,
+        }
+    }
+
+    fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            Self::Throttled { retry_after_secs } => Some(*retry_after_secs),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TxSearchError {
+    #[error("Transactions search requires a valid authorization token")]
+    Unauthorized,
+}
+
+impl ApiError for TxSearchError {
+    fn error_type(&self) -> String {
+        String::from("txSearchError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::Unauthorized => ErrorCode::TxSearchUnauthorized,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum AccessTokenError {
+    #[error("Delegation is not signed by the account owner")]
+    InvalidSignature,
+    #[error(
+        "Delegation should expire in the future, but no later than in {} days",
+        MAX_ACCESS_TOKEN_VALIDITY_DAYS
+    )]
+    InvalidExpiry,
+    #[error("Revocation timestamp is too far from the current time")]
+    InvalidRevocationTimestamp,
+    #[error("Access token is unknown or revoked")]
+    InvalidToken,
+    #[error("Access token has expired")]
+    Expired,
+    #[error("Access token grants read-only access to the endpoints of account {0:?} only")]
+    Forbidden(Address),
+}
+
+impl ApiError for AccessTokenError {
+    fn error_type(&self) -> String {
+        String::from("accessTokenError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidSignature | Self::InvalidExpiry | Self::InvalidRevocationTimestamp => {
+                ErrorCode::InvalidAccessDelegation
+            }
+            Self::InvalidToken => ErrorCode::InvalidAccessToken,
+            Self::Expired => ErrorCode::AccessTokenExpired,
+            Self::Forbidden(_) => ErrorCode::AccessTokenForbidden,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("Subscription is not signed by the account owner")]
+    InvalidSignature,
+    #[error("Subscription timestamp is too far from the current time")]
+    InvalidTimestamp,
+    #[error("Webhook URL should be an absolute `http` or `https` URL")]
+    InvalidUrl,
+    #[error("Preferences can never match an event: {0}")]
+    InvalidPreferences(#[from] InvalidPreferences),
+    #[error("Subscription is unknown or the secret doesn't match")]
+    InvalidSecret,
+}
+
+impl ApiError for WebhookError {
+    fn error_type(&self) -> String {
+        String::from("webhookError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        match self {
+            Self::InvalidSignature | Self::InvalidTimestamp | Self::InvalidUrl => {
+                ErrorCode::InvalidWebhookSubscription
+            }
+            Self::InvalidPreferences(_) => ErrorCode::InvalidWebhookPreferences,
+            Self::InvalidSecret => ErrorCode::InvalidWebhookSecret,
         }
     }
 }
@@ -149,6 +434,42 @@ impl ApiError for StorageError {
     }
 }
 
+#[derive(Error, Debug)]
+#[error("Storage is overloaded, please retry in {retry_after_secs} seconds")]
+pub struct StoragePoolExhausted {
+    pub retry_after_secs: u64,
+}
+
+impl ApiError for StoragePoolExhausted {
+    fn error_type(&self) -> String {
+        String::from("storageError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::StoragePoolExhausted
+    }
+
+    fn retry_after_secs(&self) -> Option<u64> {
+        Some(self.retry_after_secs)
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("Request is not processed within {timeout_ms} ms and is aborted")]
+pub struct RequestTimedOut {
+    pub timeout_ms: u64,
+}
+
+impl ApiError for RequestTimedOut {
+    fn error_type(&self) -> String {
+        String::from("internalError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::RequestTimedOut
+    }
+}
+
 #[derive(Debug)]
 pub struct CoreApiError(String);
 
@@ -190,18 +511,20 @@ impl ApiError for SubmitError {
     }
 
     fn code(&self) -> ErrorCode {
+        self.error_code()
+    }
+
+    fn data(&self) -> Option<Value> {
+        self.error_data()
+    }
+
+    fn retry_after_secs(&self) -> Option<u64> {
         match self {
-            Self::AccountCloseDisabled => ErrorCode::AccountCloseDisabled,
-            Self::InvalidParams(_) => ErrorCode::InvalidParams,
-            Self::UnsupportedFastProcessing => ErrorCode::UnsupportedFastProcessing,
-            Self::IncorrectTx(_) => ErrorCode::IncorrectTx,
-            Self::TxAdd(_) => ErrorCode::TxAddError,
-            Self::InappropriateFeeToken => ErrorCode::InappropriateFeeToken,
-            Self::MempoolCommunication(_) => ErrorCode::CommunicationCoreServer,
-            Self::Internal(_) => ErrorCode::InternalError,
-            Self::Toggle2FA(_) => ErrorCode::Toggle2FAError,
-            Self::Other(_) => ErrorCode::Other,
-            Self::PriceError(_) => ErrorCode::InternalError,
+            Self::ServerOverloaded { retry_after_secs }
+            | Self::QuotaExceeded {
+                retry_after_secs, ..
+            } => Some(*retry_after_secs),
+            _ => None,
         }
     }
 }
@@ -216,6 +539,7 @@ impl ApiError for PriceError {
             Self::TokenNotFound(_) => ErrorCode::TokenNotFound,
             Self::ApiError(_) => ErrorCode::ExternalApiError,
             Self::DBError(_) => ErrorCode::StorageError,
+            Self::StalePrice { .. } => ErrorCode::StaleFeeTokenPrice,
         }
     }
 }
@@ -229,3 +553,260 @@ impl ApiError for UnknownFromParameter {
         ErrorCode::QueryDeserializationError
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use chrono::Utc;
+    use zksync_types::{
+        event::transaction::TransactionType,
+        tx::error::{Create2AddressMismatch, TxAddError},
+        AccountId, TokenId,
+    };
+
+    use super::*;
+    use crate::{
+        api_server::tx_sender::screening::ScreeningVerdict,
+        fee_ticker::quote::FeeQuoteError,
+        tx_error::{AccountLockError, Toggle2FAError},
+    };
+
+    fn tx_add_errors() -> Vec<TxAddError> {
+        let errors = vec![
+            TxAddError::NonceMismatch,
+            // Errors of the specific transaction types are not exported.
+            TxAddError::IncorrectTx(
+                serde_json::from_value(serde_json::json!({ "TransferError": "WrongAmount" }))
+                    .unwrap(),
+            ),
+            TxAddError::TxFeeTooLow,
+            TxAddError::TxBatchFeeTooLow,
+            TxAddError::EIP1271SignatureVerificationFail,
+            TxAddError::MissingEthSignature,
+            TxAddError::IncorrectEthSignature,
+            TxAddError::ChangePkNotAuthorized,
+            TxAddError::Other,
+            TxAddError::DbError,
+            TxAddError::EmptyBatch,
+            TxAddError::BatchTooBig,
+            TxAddError::BatchWithdrawalsOverload,
+            TxAddError::EthSignaturesLimitExceeded,
+        ];
+        // A new variant has to be added both to the match and to the list above.
+        let variants: HashSet<_> = errors
+            .iter()
+            .map(|error| match error {
+                TxAddError::NonceMismatch => 0,
+                TxAddError::IncorrectTx(_) => 1,
+                TxAddError::TxFeeTooLow => 2,
+                TxAddError::TxBatchFeeTooLow => 3,
+                TxAddError::EIP1271SignatureVerificationFail => 4,
+                TxAddError::MissingEthSignature => 5,
+                TxAddError::IncorrectEthSignature => 6,
+                TxAddError::ChangePkNotAuthorized => 7,
+                TxAddError::Other => 8,
+                TxAddError::DbError => 9,
+                TxAddError::EmptyBatch => 10,
+                TxAddError::BatchTooBig => 11,
+                TxAddError::BatchWithdrawalsOverload => 12,
+                TxAddError::EthSignaturesLimitExceeded => 13,
+            })
+            .collect();
+        assert_eq!(variants.len(), 14);
+        errors
+    }
+
+    fn submit_errors() -> Vec<SubmitError> {
+        let mut errors = vec![
+            SubmitError::AccountCloseDisabled,
+            SubmitError::TxTypeDisabled(TransactionType::Transfer),
+            SubmitError::BatchTxTypeDisabled {
+                index: 1,
+                tx_type: TransactionType::Swap,
+            },
+            SubmitError::AddressBlocked,
+            SubmitError::AccountLocked(AccountId(1)),
+            SubmitError::UnknownToken(TokenId(100)),
+            SubmitError::TokenDisabled(TokenId(1)),
+            SubmitError::ReservedRecipient(Address::zero()),
+            SubmitError::BatchTxCancellation,
+            SubmitError::AmountNotPackable {
+                field: "fee",
+                amount: 2048_u32.into(),
+                nearest: 2047_u32.into(),
+            },
+            SubmitError::ComplianceRejected(ScreeningVerdict::Deny),
+            SubmitError::AmountBelowMinimum {
+                token: TokenId(0),
+                amount: 1_u32.into(),
+                minimum: 1000_u32.into(),
+            },
+            SubmitError::Create2FactoryNotAllowed(Address::zero()),
+            SubmitError::Create2AddressMismatch(Create2AddressMismatch {
+                factory: Address::zero(),
+                derived: Address::repeat_byte(1),
+                account: Address::repeat_byte(2),
+            }),
+            SubmitError::InvalidFeeQuote(FeeQuoteError::Expired),
+            SubmitError::InvalidParams("params".to_owned()),
+            SubmitError::UnsupportedFastProcessing,
+            SubmitError::IncorrectTx("tx".to_owned()),
+            SubmitError::InappropriateFeeToken,
+            SubmitError::FeeTokenNotAllowedForTxType(TransactionType::Withdraw),
+            SubmitError::Toggle2FA(Toggle2FAError::CREATE2),
+            SubmitError::AccountLock(AccountLockError::NotLocked),
+            SubmitError::ServerOverloaded {
+                retry_after_secs: 1,
+            },
+            SubmitError::QuotaExceeded {
+                limit: 10,
+                window_secs: 3600,
+                reset_at: Utc::now(),
+                retry_after_secs: 60,
+            },
+            SubmitError::MempoolCommunication("mempool".to_owned()),
+            SubmitError::PriceError(PriceError::token_not_found("token")),
+            SubmitError::PriceError(PriceError::StalePrice {
+                token_id: TokenId(1),
+                symbol: "DAI".to_owned(),
+                age_secs: 1800,
+                max_age_secs: 1200,
+            }),
+            SubmitError::internal(anyhow::anyhow!("internal")),
+            SubmitError::Other("other".to_owned()),
+        ];
+        errors.extend(tx_add_errors().into_iter().map(SubmitError::TxAdd));
+
+        // A new variant has to be added both to the match and to the list above.
+        let variants: HashSet<_> = errors
+            .iter()
+            .map(|error| match error {
+                SubmitError::AccountCloseDisabled => 0,
+                SubmitError::TxTypeDisabled(_) => 1,
+                SubmitError::BatchTxTypeDisabled { .. } => 2,
+                SubmitError::AddressBlocked => 3,
+                SubmitError::ComplianceRejected(_) => 4,
+                SubmitError::Create2FactoryNotAllowed(_) => 5,
+                SubmitError::Create2AddressMismatch(_) => 6,
+                SubmitError::InvalidFeeQuote(_) => 7,
+                SubmitError::InvalidParams(_) => 8,
+                SubmitError::UnsupportedFastProcessing => 9,
+                SubmitError::IncorrectTx(_) => 10,
+                SubmitError::TxAdd(_) => 11,
+                SubmitError::InappropriateFeeToken => 12,
+                SubmitError::FeeTokenNotAllowedForTxType(_) => 13,
+                SubmitError::Toggle2FA(_) => 14,
+                SubmitError::ServerOverloaded { .. } => 15,
+                SubmitError::MempoolCommunication(_) => 16,
+                SubmitError::PriceError(_) => 17,
+                SubmitError::Internal(_) => 18,
+                SubmitError::Other(_) => 19,
+                SubmitError::ReservedRecipient(_) => 20,
+                SubmitError::BatchTxCancellation => 21,
+                SubmitError::AmountNotPackable { .. } => 22,
+                SubmitError::UnknownToken(_) => 23,
+                SubmitError::TokenDisabled(_) => 24,
+                SubmitError::AmountBelowMinimum { .. } => 25,
+                SubmitError::QuotaExceeded { .. } => 26,
+                SubmitError::AccountLocked(_) => 27,
+                SubmitError::AccountLock(_) => 28,
+            })
+            .collect();
+        assert_eq!(variants.len(), 29);
+        errors
+    }
+
+    #[test]
+    fn submit_errors_are_registered() {
+        let mut codes = HashSet::new();
+        for error in submit_errors() {
+            let expected_data = error.error_data();
+            let rest_error = Error::from(error);
+            assert!(ErrorCode::ALL.contains(&rest_error.code));
+            assert_eq!(rest_error.name, rest_error.code.name());
+            assert_eq!(rest_error.data, expected_data);
+            codes.insert(rest_error.code);
+        }
+        // Reasons the transaction is not added to the mempool are distinguishable.
+        assert!(codes.contains(&ErrorCode::NonceMismatch));
+        assert!(codes.contains(&ErrorCode::TxFeeTooLow));
+        assert!(codes.contains(&ErrorCode::IncorrectEthSignature));
+    }
+
+    #[test]
+    fn rest_and_rpc_codes_match() {
+        for error in submit_errors() {
+            let code = error.error_code();
+            let rpc_error = jsonrpc_core::Error::from(error);
+            assert_eq!(rpc_error.code.code(), i64::from(code.code()));
+            assert_eq!(rpc_error.data.unwrap()["name"], code.name());
+        }
+    }
+
+    #[test]
+    fn invalid_data_errors_are_registered() {
+        let errors = vec![
+            InvalidDataError::TokenZeroPriceError,
+            InvalidDataError::InvalidBlockPosition,
+            InvalidDataError::InvalidAccountIdOrAddress,
+            InvalidDataError::AccountNotFound,
+            InvalidDataError::InvalidCurrency,
+            InvalidDataError::TransactionNotFound,
+            InvalidDataError::PaginationLimitTooBig,
+            InvalidDataError::InvalidNFTTokenId,
+            InvalidDataError::InvalidPubKeyHash,
+            InvalidDataError::TooManyTokens,
+            InvalidDataError::InvalidFeeStatisticsRange,
+            InvalidDataError::InvalidBatchHashRequest,
+            InvalidDataError::PayloadTooLarge(1),
+            InvalidDataError::InvalidTokenAddress,
+            InvalidDataError::InvalidBlocksExportRange(1),
+            InvalidDataError::MissingPaginationParams,
+            InvalidDataError::TooManyBlockNumbers,
+            InvalidDataError::InvalidMinBalanceUsd,
+            InvalidDataError::InvalidTokenHoldersLimit(1),
+            InvalidDataError::NftHoldersNotTracked,
+            InvalidDataError::NotUserAccount,
+            InvalidDataError::InvalidSignMessageRequest,
+            InvalidDataError::InvalidTxHashPrefix(8),
+            InvalidDataError::ConflictingAccountTxsFilters,
+            InvalidDataError::InvalidAccountTxsDeltaQuery(String::new()),
+            InvalidDataError::NotWithdrawal,
+            InvalidDataError::WithdrawalNotVerified,
+            InvalidDataError::InvalidPathParameter {
+                name: String::new(),
+                formats: &[],
+            },
+            InvalidDataError::InvalidSearchQuery,
+            InvalidDataError::TxHasNoPublicData,
+            InvalidDataError::InvalidTokenActivityRange,
+        ];
+        let codes: HashSet<_> = errors.iter().map(|error| error.code()).collect();
+        // Every validation error has its own code.
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| ErrorCode::ALL.contains(code)));
+
+        let error = Error::from(InvalidDataError::PayloadTooLarge(1));
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], 213);
+        assert_eq!(value["name"], "PayloadTooLarge");
+        assert!(value.get("data").is_none());
+    }
+
+    #[test]
+    fn not_found_error() {
+        let error = Error::not_found("block", 5);
+        assert!(error.is_not_found());
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["errorType"], "notFoundError");
+        assert_eq!(value["code"], 223);
+        assert_eq!(value["name"], "NotFound");
+        assert_eq!(value["message"], "The block `5` is not found");
+        assert_eq!(
+            value["data"],
+            serde_json::json!({ "resource": "block", "id": "5" })
+        );
+        assert!(!Error::from(InvalidDataError::AccountNotFound).is_not_found());
+    }
+}
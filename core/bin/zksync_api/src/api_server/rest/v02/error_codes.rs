@@ -0,0 +1,64 @@
+//! Error codes part of API implementation.
+
+// Built-in uses
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+use zksync_api_types::error_code::{ErrorCode, ErrorCodeInfo};
+
+// Local uses
+use super::response::ApiResult;
+
+// Server implementation
+
+/// Lists the error codes shared by the REST and JSON-RPC APIs, e.g. for the SDK generation.
+async fn error_codes() -> ApiResult<Vec<ErrorCodeInfo>> {
+    let start = Instant::now();
+    let res = ApiResult::Ok(ErrorCode::registry());
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "error_codes");
+    res
+}
+
+pub fn api_scope() -> Scope {
+    web::scope("errors").route("", web::get().to(error_codes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{deserialize_response_result, TestServerConfig},
+        SharedData,
+    };
+    use actix_web::http::StatusCode;
+    use zksync_api_types::v02::ApiVersion;
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn error_codes_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) =
+            cfg.start_server(|_: &TestServerConfig| api_scope(), Some(shared_data));
+
+        let response = client.error_codes().await?;
+        let codes: Vec<ErrorCodeInfo> = deserialize_response_result(response)?;
+        assert_eq!(codes, ErrorCode::registry());
+        assert!(codes
+            .iter()
+            .any(|info| info.code == 625 && info.name == "NonceMismatch"));
+
+        server.stop().await;
+        Ok(())
+    }
+}
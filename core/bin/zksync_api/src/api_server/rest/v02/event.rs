@@ -0,0 +1,193 @@
+//! Events part of API implementation.
+
+// Built-in uses
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+use zksync_api_types::v02::{
+    event::{ApiEvent, ApiEventType, EventsQuery},
+    pagination::MAX_LIMIT,
+};
+use zksync_storage::{
+    event::{records::StoredEvent, EventType},
+    AccessIntent, ReplicatedPool,
+};
+use zksync_types::{event::EventId, BlockNumber};
+
+// Local uses
+use super::{
+    error::{Error, InvalidDataError},
+    response::ApiResult,
+};
+
+fn event_type_to_storage(event_type: ApiEventType) -> EventType {
+    match event_type {
+        ApiEventType::Account => EventType::Account,
+        ApiEventType::Block => EventType::Block,
+        ApiEventType::Transaction => EventType::Transaction,
+    }
+}
+
+pub(crate) fn api_event_from_stored(event: StoredEvent) -> ApiEvent {
+    let event_type = match event.event_type {
+        EventType::Account => ApiEventType::Account,
+        EventType::Block => ApiEventType::Block,
+        EventType::Transaction => ApiEventType::Transaction,
+    };
+    ApiEvent {
+        id: EventId(event.id as u64),
+        block_number: BlockNumber(event.block_number as u32),
+        event_type,
+        data: event.event_data,
+        created_at: event.created_at,
+    }
+}
+
+/// Shared data between `api/v0.2/events` endpoints.
+#[derive(Debug, Clone)]
+struct ApiEventData {
+    pool: ReplicatedPool,
+}
+
+impl ApiEventData {
+    fn new(pool: ReplicatedPool) -> Self {
+        Self { pool }
+    }
+
+    /// Returns events with the id greater than `query.from_id` in ascending order.
+    async fn events(&self, query: EventsQuery) -> Result<Vec<ApiEvent>, Error> {
+        if query.limit > MAX_LIMIT {
+            return Err(Error::from(InvalidDataError::PaginationLimitTooBig));
+        }
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let events = storage
+            .event_schema()
+            .load_events(
+                query.from_id.unwrap_or(EventId(0)),
+                query.limit,
+                query.event_type.map(event_type_to_storage),
+            )
+            .await
+            .map_err(Error::storage)?;
+
+        Ok(events.into_iter().map(api_event_from_stored).collect())
+    }
+}
+
+// Server implementation
+
+async fn events(
+    data: web::Data<ApiEventData>,
+    web::Query(query): web::Query<EventsQuery>,
+) -> ApiResult<Vec<ApiEvent>> {
+    let start = Instant::now();
+    let res = data.events(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "events");
+    res
+}
+
+pub fn api_scope(pool: ReplicatedPool) -> Scope {
+    let data = ApiEventData::new(pool);
+
+    web::scope("events")
+        .app_data(web::Data::new(data))
+        .route("", web::get().to(events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{deserialize_response_result, TestServerConfig},
+        SharedData,
+    };
+    use actix_web::http::StatusCode;
+    use zksync_api_types::v02::ApiVersion;
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn events_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| api_scope(cfg.replicated_pool()),
+            Some(shared_data),
+        );
+
+        let expected_events: Vec<_> = {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .event_schema()
+                .load_events(EventId(0), MAX_LIMIT, None)
+                .await?
+                .into_iter()
+                .map(api_event_from_stored)
+                .collect()
+        };
+        assert!(expected_events.len() > 1);
+
+        let query = EventsQuery {
+            from_id: None,
+            limit: MAX_LIMIT,
+            event_type: None,
+        };
+        let response = client.events(&query).await?;
+        let events: Vec<ApiEvent> = deserialize_response_result(response)?;
+        assert_eq!(events, expected_events);
+        // Events are returned in ascending order of their ids.
+        assert!(events.windows(2).all(|pair| pair[0].id < pair[1].id));
+
+        // Replay starts right after the provided id.
+        let query = EventsQuery {
+            from_id: Some(expected_events[0].id),
+            limit: 1,
+            event_type: None,
+        };
+        let response = client.events(&query).await?;
+        let events: Vec<ApiEvent> = deserialize_response_result(response)?;
+        assert_eq!(events, &expected_events[1..2]);
+
+        // Filter events by type.
+        let query = EventsQuery {
+            from_id: None,
+            limit: MAX_LIMIT,
+            event_type: Some(ApiEventType::Block),
+        };
+        let response = client.events(&query).await?;
+        let events: Vec<ApiEvent> = deserialize_response_result(response)?;
+        let expected_block_events: Vec<_> = expected_events
+            .iter()
+            .filter(|event| event.event_type == ApiEventType::Block)
+            .cloned()
+            .collect();
+        assert_eq!(events, expected_block_events);
+
+        // Limit must not exceed the maximum.
+        let query = EventsQuery {
+            from_id: None,
+            limit: MAX_LIMIT + 1,
+            event_type: None,
+        };
+        let response = client.events(&query).await?;
+        assert!(response.error.is_some());
+
+        server.stop().await;
+        Ok(())
+    }
+}
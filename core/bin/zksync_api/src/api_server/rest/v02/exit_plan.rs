@@ -0,0 +1,247 @@
+//! Exits of all the funds of the account in the exodus mode.
+//!
+//! The contract only accepts the exits proven against the last executed block, so the plan is
+//! built strictly from the finalized state. Every token is exited by a separate `performExodus`
+//! call, and the exited funds are withdrawn by another call afterwards. The exit proofs are made
+//! by the `generate_exit_proof` tool from the same state.
+
+// External uses
+use num::BigUint;
+
+// Workspace uses
+use zksync_api_types::v02::account::{AccountExitPlan, NftExitParams, TokenExit};
+use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID_VAL};
+use zksync_storage::StorageProcessor;
+use zksync_types::{gas_counter::VerifyCost, AccountId, TokenId, NFT};
+
+// Local uses
+use super::error::Error;
+use crate::utils::token_cache::TokenCache;
+
+/// Gas limit of the `performExodus` call set by the exit tool, the exit proof verification
+/// takes most of it.
+const EXODUS_GAS: u64 = 1_000_000;
+
+fn exit_gas(is_nft: bool) -> u64 {
+    let withdrawal_gas = if is_nft {
+        VerifyCost::PENDING_WITHDRAW_NFT_COST
+    } else {
+        VerifyCost::PENDING_WITHDRAW_COST
+    };
+    EXODUS_GAS + withdrawal_gas
+}
+
+/// Builds the exit plan of the account from the last finalized state, `None` if the account
+/// doesn't exist in it. The storage is expected to be in the snapshot read, so the account
+/// and the last finalized block are consistent.
+pub async fn load_exit_plan(
+    storage: &mut StorageProcessor<'_>,
+    tokens: &TokenCache,
+    account_id: AccountId,
+) -> Result<Option<AccountExitPlan>, Error> {
+    // The special account holds the NFTs data and can't exit.
+    if account_id == NFT_STORAGE_ACCOUNT_ID {
+        return Ok(None);
+    }
+    // The `accounts` table is updated only once the block is executed.
+    let (_, account) = storage
+        .chain()
+        .account_schema()
+        .account_and_last_block(account_id)
+        .await
+        .map_err(Error::storage)?;
+    let account = match account {
+        Some(account) => account,
+        None => return Ok(None),
+    };
+    let block_number = storage
+        .chain()
+        .block_schema()
+        .get_last_verified_confirmed_block()
+        .await
+        .map_err(Error::storage)?;
+    // The stored info of the block is passed along with the proof.
+    let block_stored = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await
+        .map_err(Error::storage)?
+        .is_some();
+
+    let mut exits = Vec::new();
+    for (token_id, amount) in account.get_nonzero_balances() {
+        let exit = match token_id.0 {
+            // Counter of the minted NFTs, not a balance.
+            NFT_TOKEN_ID_VAL => continue,
+            MIN_NFT_TOKEN_ID..=NFT_TOKEN_ID_VAL => {
+                let nft = tokens
+                    .get_nft_by_id(storage, token_id)
+                    .await
+                    .map_err(Error::storage)?;
+                nft_exit(token_id, amount.0, nft, block_stored)
+            }
+            _ => {
+                let token = tokens
+                    .get_token(storage, token_id)
+                    .await
+                    .map_err(Error::storage)?;
+                TokenExit {
+                    token_id,
+                    token_symbol: token.as_ref().map(|token| token.symbol.clone()),
+                    token_address: token.map(|token| token.address),
+                    amount: amount.0,
+                    nft: None,
+                    proof_ready: block_stored,
+                    estimated_gas: exit_gas(false),
+                }
+            }
+        };
+        exits.push(exit);
+    }
+    exits.sort_by_key(|exit| exit.token_id);
+
+    Ok(Some(AccountExitPlan {
+        account_id,
+        address: account.address,
+        block_number,
+        total_estimated_gas: exits.iter().map(|exit| exit.estimated_gas).sum(),
+        exits,
+    }))
+}
+
+/// The NFT can't be exited without its parameters, so the proof is not ready until they are known.
+fn nft_exit(token_id: TokenId, amount: BigUint, nft: Option<NFT>, block_stored: bool) -> TokenExit {
+    TokenExit {
+        token_id,
+        token_symbol: nft.as_ref().map(|nft| nft.symbol.clone()),
+        token_address: nft.as_ref().map(|nft| nft.address),
+        amount,
+        proof_ready: block_stored && nft.is_some(),
+        nft: nft.map(|nft| NftExitParams {
+            creator_account_id: nft.creator_id,
+            creator_address: nft.creator_address,
+            serial_id: nft.serial_id,
+            content_hash: nft.content_hash,
+        }),
+        estimated_gas: exit_gas(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::TestServerConfig;
+    use zksync_types::{AccountUpdate, BlockNumber};
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn exit_plan_lists_every_token() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let tokens = cfg.token_cache();
+        let mut storage = cfg.pool.access_storage().await?;
+        // The changes are rolled back once the transaction is dropped.
+        let mut transaction = storage.start_transaction().await?;
+
+        let (last_block, accounts) = transaction
+            .chain()
+            .state_schema()
+            .load_verified_state()
+            .await?;
+        // Accounts of the first block own the NFTs minted in the following ones.
+        let (&account_id, account) = accounts
+            .iter()
+            .find(|(_, account)| {
+                account
+                    .get_nonzero_balances()
+                    .keys()
+                    .any(|token_id| (MIN_NFT_TOKEN_ID..NFT_TOKEN_ID_VAL).contains(&token_id.0))
+            })
+            .expect("No account owns an NFT");
+
+        // Fixture accounts hold ETH only, add the other tokens to the finalized state.
+        let block_number = BlockNumber(1000);
+        let updates: Vec<_> = [(TokenId(1), 100u32), (TokenId(16), 200u32)]
+            .iter()
+            .map(|&(token_id, amount)| {
+                (
+                    account_id,
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: account.nonce,
+                        new_nonce: account.nonce,
+                        balance_update: (token_id, BigUint::from(0u32), BigUint::from(amount)),
+                    },
+                )
+            })
+            .collect();
+        transaction
+            .chain()
+            .state_schema()
+            .commit_state_update(block_number, &updates, 0)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .apply_state_update(block_number)
+            .await?;
+
+        let plan = load_exit_plan(&mut transaction, &tokens, account_id)
+            .await?
+            .unwrap();
+        assert_eq!(plan.account_id, account_id);
+        assert_eq!(plan.address, account.address);
+        assert_eq!(plan.block_number, last_block);
+
+        let mut expected_balances = account.get_nonzero_balances();
+        expected_balances.remove(&TokenId(NFT_TOKEN_ID_VAL));
+        expected_balances.insert(TokenId(1), BigUint::from(100u32).into());
+        expected_balances.insert(TokenId(16), BigUint::from(200u32).into());
+        assert_eq!(plan.exits.len(), expected_balances.len());
+        for exit in &plan.exits {
+            assert_eq!(exit.amount, expected_balances[&exit.token_id].0);
+            assert!(exit.proof_ready, "{:?}", exit);
+            let is_nft = exit.token_id.0 >= MIN_NFT_TOKEN_ID;
+            assert_eq!(exit.nft.is_some(), is_nft, "{:?}", exit);
+            assert_eq!(exit.estimated_gas, exit_gas(is_nft));
+        }
+        let symbols: Vec<_> = plan
+            .exits
+            .iter()
+            .filter(|exit| exit.nft.is_none())
+            .filter_map(|exit| exit.token_symbol.as_deref())
+            .collect();
+        assert!(symbols.contains(&"PHNX"));
+        assert!(symbols.contains(&"GNT"));
+        assert_eq!(
+            plan.total_estimated_gas,
+            plan.exits
+                .iter()
+                .map(|exit| exit.estimated_gas)
+                .sum::<u64>()
+        );
+
+        // Only the finalized state is taken into account.
+        let (_, committed_accounts) = transaction
+            .chain()
+            .state_schema()
+            .load_committed_state(None)
+            .await?;
+        let committed_only = committed_accounts
+            .keys()
+            .find(|account_id| !accounts.contains_key(account_id))
+            .expect("All the accounts are finalized");
+        assert_eq!(
+            load_exit_plan(&mut transaction, &tokens, *committed_only).await?,
+            None
+        );
+        assert_eq!(
+            load_exit_plan(&mut transaction, &tokens, NFT_STORAGE_ACCOUNT_ID).await?,
+            None
+        );
+        Ok(())
+    }
+}
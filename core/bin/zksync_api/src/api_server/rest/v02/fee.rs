@@ -2,58 +2,242 @@
 
 // Built-in uses
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External uses
 use actix_web::{
     web::{self, Json},
     Scope,
 };
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use serde_json::Value;
 
 // Workspace uses
-use zksync_api_types::v02::fee::{ApiFee, BatchFeeRequest, TxFeeRequest};
+use zksync_api_types::v02::{
+    fee::{
+        AllowedFeeTokens, ApiFee, BatchFeeRequest, FeeStatistics, FeeStatisticsBucket,
+        FeeStatisticsQuery, FeeStatisticsResolution, FeeTokenRequirement, TxFeeRequest,
+        TxInBatchFeeRequest, MAX_FEE_STATISTICS_BUCKETS,
+    },
+    AmountsQuery,
+};
+use zksync_storage::{AccessIntent, ReplicatedPool};
+use zksync_types::{event::transaction::TransactionType, TokenId, TokenLike};
+use zksync_utils::{big_decimal_to_ratio, BigUintSerdeWrapper};
 
 // Local uses
-use super::{error::Error, response::ApiResult};
+use super::{
+    amounts::render_amounts,
+    error::{Error, InvalidDataError},
+    response::ApiResult,
+};
 use crate::{
-    api_server::tx_sender::{SubmitError, TxSender},
+    api_server::tx_sender::TxSender,
     api_try,
+    fee_ticker::{FeePriceInputs, PriceError},
+    utils::shared_lru_cache::SharedLruCache,
 };
 
+/// Number of the cached fee statistics responses.
+const FEE_STATISTICS_CACHE_SIZE: usize = 100;
+/// Period during which the cached fee statistics response is served.
+const FEE_STATISTICS_CACHE_TTL: Duration = Duration::from_secs(60);
+/// Period during which the cached allowed fee tokens response is served.
+const ALLOWED_TOKENS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+type FeeStatisticsKey = (
+    TransactionType,
+    TokenId,
+    FeeStatisticsResolution,
+    chrono::DateTime<Utc>,
+    chrono::DateTime<Utc>,
+);
+
 /// Shared data between `api/v0.2/fee` endpoints.
 #[derive(Clone)]
 struct ApiFeeData {
+    pool: ReplicatedPool,
     tx_sender: TxSender,
+    statistics_cache: SharedLruCache<FeeStatisticsKey, (Instant, FeeStatistics)>,
+    allowed_tokens_cache: SharedLruCache<(), (Instant, Vec<AllowedFeeTokens>)>,
 }
 
 impl ApiFeeData {
-    fn new(tx_sender: TxSender) -> Self {
-        Self { tx_sender }
+    fn new(pool: ReplicatedPool, tx_sender: TxSender) -> Self {
+        Self {
+            pool,
+            tx_sender,
+            statistics_cache: SharedLruCache::new(FEE_STATISTICS_CACHE_SIZE),
+            allowed_tokens_cache: SharedLruCache::new(1),
+        }
+    }
+
+    /// Attaches the signed quote with the prices used to calculate the fee.
+    fn with_quote(
+        &self,
+        mut fee: ApiFee,
+        transactions: Vec<TxInBatchFeeRequest>,
+        price_inputs: &FeePriceInputs,
+    ) -> ApiFee {
+        let quote = self.tx_sender.fee_quotes.quote(
+            transactions,
+            price_inputs,
+            (
+                fee.gas_fee.clone(),
+                fee.zkp_fee.clone(),
+                fee.total_fee.clone(),
+            ),
+            Utc::now().timestamp() as u64,
+        );
+        fee.quote = Some(self.tx_sender.fee_quotes.sign(quote));
+        fee
+    }
+
+    /// Aggregates the fees of the executed transactions.
+    /// The range is limited to `MAX_FEE_STATISTICS_BUCKETS` buckets, the responses are cached
+    /// for a short period since the aggregation is expensive.
+    async fn fee_statistics(&self, query: FeeStatisticsQuery) -> Result<FeeStatistics, Error> {
+        let buckets_count =
+            (query.to - query.from).num_seconds() / query.resolution.max_duration().num_seconds();
+        if query.from >= query.to || buckets_count >= MAX_FEE_STATISTICS_BUCKETS {
+            return Err(Error::from(InvalidDataError::InvalidFeeStatisticsRange));
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let token_like = TokenLike::parse(&query.token);
+        let token = self
+            .tx_sender
+            .tokens
+            .get_token(&mut storage, token_like.clone())
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(PriceError::token_not_found(token_like)))?;
+
+        let key = (
+            query.tx_type,
+            token.id,
+            query.resolution,
+            query.from,
+            query.to,
+        );
+        if let Some((cached_at, statistics)) = self.statistics_cache.get(&key) {
+            if cached_at.elapsed() < FEE_STATISTICS_CACHE_TTL {
+                return Ok(statistics);
+            }
+        }
+
+        let to_fee = |value: Option<BigDecimal>| -> Result<Option<BigUintSerdeWrapper>, Error> {
+            value
+                .map(|value| {
+                    big_decimal_to_ratio(&value)
+                        .map(|ratio| ratio.to_integer().into())
+                        .map_err(Error::storage)
+                })
+                .transpose()
+        };
+        let buckets = storage
+            .chain()
+            .stats_schema()
+            .fee_statistics(
+                &query.tx_type.to_string(),
+                token.id,
+                query.resolution.as_str(),
+                query.from,
+                query.to,
+            )
+            .await
+            .map_err(Error::storage)?
+            .into_iter()
+            .map(|bucket| {
+                Ok(FeeStatisticsBucket {
+                    from: bucket.bucket,
+                    count: bucket.count as u64,
+                    average: to_fee(bucket.average)?,
+                    median: to_fee(bucket.median)?,
+                    percentile_90: to_fee(bucket.percentile_90)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let statistics = FeeStatistics {
+            tx_type: query.tx_type,
+            token_id: token.id,
+            resolution: query.resolution,
+            buckets,
+        };
+        self.statistics_cache
+            .insert(key, (Instant::now(), statistics.clone()));
+        Ok(statistics)
+    }
+
+    /// Lists the tokens the fees of every transaction type can be paid in.
+    /// Checking the liquidity of all the tokens is expensive, so the response is cached for a short period.
+    async fn allowed_tokens(&self) -> Result<Vec<AllowedFeeTokens>, Error> {
+        if let Some((cached_at, allowed_tokens)) = self.allowed_tokens_cache.get(&()) {
+            if cached_at.elapsed() < ALLOWED_TOKENS_CACHE_TTL {
+                return Ok(allowed_tokens);
+            }
+        }
+
+        let tokens = self.tx_sender.ticker.allowed_fee_tokens().await?;
+        let allowed_tokens: Vec<_> = TransactionType::L2_TYPES
+            .iter()
+            .map(|&tx_type| {
+                let requirement = FeeTokenRequirement::for_tx_type(tx_type);
+                AllowedFeeTokens {
+                    tx_type,
+                    requirement,
+                    tokens: tokens
+                        .iter()
+                        .filter(|(_, liquidity)| *liquidity >= requirement)
+                        .map(|(token_id, _)| *token_id)
+                        .collect(),
+                }
+            })
+            .collect();
+        self.allowed_tokens_cache
+            .insert((), (Instant::now(), allowed_tokens.clone()));
+        Ok(allowed_tokens)
     }
 }
 
 async fn get_tx_fee(
     data: web::Data<ApiFeeData>,
     Json(body): Json<TxFeeRequest>,
-) -> ApiResult<ApiFee> {
+    web::Query(amounts): web::Query<AmountsQuery>,
+) -> ApiResult<Value> {
     let start = Instant::now();
-    let token_allowed = api_try!(data
+    api_try!(data
         .tx_sender
-        .ticker
-        .token_allowed_for_fees(body.token_like.clone())
+        .check_fee_token(&[body.tx_type.transaction_type()], body.token_like.clone())
         .await
         .map_err(Error::from));
-    if !token_allowed {
-        return Error::from(SubmitError::InappropriateFeeToken).into();
-    }
+    let fee_token = body.token_like.clone();
+    let transactions = vec![TxInBatchFeeRequest {
+        tx_type: body.tx_type.clone(),
+        address: body.address,
+    }];
     // TODO implement subsidies for v02 api ZKS-888
     let res = data
         .tx_sender
         .ticker
         .get_fee_from_ticker_in_wei(body.tx_type.into(), body.token_like, body.address)
         .await
-        .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+        .map(|fee| data.with_quote(fee.normal_fee.into(), transactions, &fee.price_inputs))
+        .map_err(Error::from);
+    let res = render_amounts(
+        res,
+        amounts.amounts_in,
+        &data.tx_sender.tokens,
+        &data.pool,
+        Some(fee_token),
+    )
+    .await
+    .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_tx_fee");
     res
 }
@@ -61,41 +245,71 @@ async fn get_tx_fee(
 async fn get_batch_fee(
     data: web::Data<ApiFeeData>,
     Json(body): Json<BatchFeeRequest>,
-) -> ApiResult<ApiFee> {
+    web::Query(amounts): web::Query<AmountsQuery>,
+) -> ApiResult<Value> {
     let start = Instant::now();
-    let token_allowed = api_try!(data
+    let tx_types: Vec<_> = body
+        .transactions
+        .iter()
+        .map(|tx| tx.tx_type.transaction_type())
+        .collect();
+    api_try!(data
         .tx_sender
-        .ticker
-        .token_allowed_for_fees(body.token_like.clone())
+        .check_fee_token(&tx_types, body.token_like.clone())
         .await
         .map_err(Error::from));
-    if !token_allowed {
-        return Error::from(SubmitError::InappropriateFeeToken).into();
-    }
-    let txs = body
-        .transactions
-        .into_iter()
-        .map(|tx| (tx.tx_type.into(), tx.address))
+    let fee_token = body.token_like.clone();
+    let transactions = body.transactions;
+    let txs = transactions
+        .iter()
+        .map(|tx| (tx.tx_type.clone().into(), tx.address))
         .collect();
     let res = data
         .tx_sender
         .ticker
         .get_batch_from_ticker_in_wei(body.token_like, txs)
         .await
-        .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+        .map(|fee| data.with_quote(fee.normal_fee.into(), transactions, &fee.price_inputs))
+        .map_err(Error::from);
+    let res = render_amounts(
+        res,
+        amounts.amounts_in,
+        &data.tx_sender.tokens,
+        &data.pool,
+        Some(fee_token),
+    )
+    .await
+    .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_batch_fee");
     res
 }
 
-pub fn api_scope(tx_sender: TxSender) -> Scope {
-    let data = ApiFeeData::new(tx_sender);
+async fn get_fee_statistics(
+    data: web::Data<ApiFeeData>,
+    web::Query(query): web::Query<FeeStatisticsQuery>,
+) -> ApiResult<FeeStatistics> {
+    let start = Instant::now();
+    let res = data.fee_statistics(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_fee_statistics");
+    res
+}
+
+async fn get_allowed_tokens(data: web::Data<ApiFeeData>) -> ApiResult<Vec<AllowedFeeTokens>> {
+    let start = Instant::now();
+    let res = data.allowed_tokens().await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_allowed_tokens");
+    res
+}
+
+pub fn api_scope(pool: ReplicatedPool, tx_sender: TxSender) -> Scope {
+    let data = ApiFeeData::new(pool, tx_sender);
 
     web::scope("fee")
         .app_data(web::Data::new(data))
         .route("", web::post().to(get_tx_fee))
         .route("/batch", web::post().to(get_batch_fee))
+        .route("/statistics", web::get().to(get_fee_statistics))
+        .route("/allowedTokens", web::get().to(get_allowed_tokens))
 }
 
 #[cfg(test)]
@@ -107,8 +321,12 @@ mod tests {
         },
         SharedData,
     };
-    use crate::fee_ticker::validator::cache::TokenInMemoryCache;
-    use chrono::Utc;
+    use crate::api_server::tx_sender::SubmitError;
+    use crate::fee_ticker::{quote::FeeQuoteSigner, validator::cache::TokenInMemoryCache};
+    use crate::utils::{banned_addresses::BannedAddresses, disabled_tx_types::DisabledTxTypes};
+    use actix_web::http::StatusCode;
+    use bigdecimal::BigDecimal;
+    use chrono::{TimeZone, Utc};
     use futures::channel::mpsc;
     use num::rational::Ratio;
     use num::BigUint;
@@ -118,7 +336,8 @@ mod tests {
         ApiVersion,
     };
     use zksync_types::{
-        tokens::{TokenLike, TokenMarketVolume},
+        tokens::{ChangePubKeyFeeTypeArg, TokenLike, TokenMarketVolume},
+        tx::ChangePubKeyType,
         Address, ChainId, Token, TokenId, TokenKind,
     };
 
@@ -135,6 +354,7 @@ mod tests {
         let shared_data = SharedData {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
         };
 
         let mut tokens = HashMap::new();
@@ -165,15 +385,20 @@ mod tests {
             .with_market(market);
         let (client, server) = cfg.start_server(
             move |cfg: &TestServerConfig| {
-                api_scope(TxSender::new(
-                    cfg.pool.clone(),
-                    dummy_sign_verifier(),
-                    dummy_fee_ticker(&prices, Some(cache.clone())),
-                    &cfg.config.api.common,
-                    &cfg.config.api.token_config,
-                    mempool_tx_request_sender.clone(),
-                    ChainId(cfg.config.eth_client.chain_id),
-                ))
+                api_scope(
+                    cfg.replicated_pool(),
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&prices, Some(cache.clone())),
+                        &cfg.config.api.common,
+                        cfg.token_cache(),
+                        mempool_tx_request_sender.clone(),
+                        DisabledTxTypes::new(cfg.pool.clone()),
+                        BannedAddresses::new(cfg.pool.clone()),
+                        ChainId(cfg.config.eth_client.chain_id),
+                    ),
+                )
             },
             Some(shared_data),
         );
@@ -199,6 +424,24 @@ mod tests {
         assert_eq!(api_fee.zkp_fee, BigUint::from(1u32));
         assert_eq!(api_fee.total_fee, BigUint::from(2u32));
 
+        // The fee is accompanied by the signed quote with the used prices.
+        let quote = api_fee.quote.expect("Fee quote is missing");
+        assert_eq!(quote.quote.token_id, TokenId(2));
+        assert_eq!(quote.quote.total_fee, api_fee.total_fee);
+        assert_eq!(
+            quote.quote.token_price_usd,
+            BigDecimal::from(10000).with_scale(18)
+        );
+        assert_eq!(
+            quote.quote.eth_price_usd,
+            BigDecimal::from(10).with_scale(18)
+        );
+        let quote_signer = FeeQuoteSigner::new(
+            cfg.config.api.common.fee_quote_private_key,
+            cfg.config.api.common.fee_quote_validity(),
+        );
+        assert!(quote_signer.verify(&quote, quote.quote.timestamp).is_ok());
+
         let tx = TxInBatchFeeRequest {
             tx_type: ApiTxFeeTypes::Withdraw,
             address: Address::default(),
@@ -210,6 +453,180 @@ mod tests {
         assert_eq!(api_batch_fee.gas_fee, BigUint::from(1u32));
         assert_eq!(api_batch_fee.zkp_fee, BigUint::from(1u32));
         assert_eq!(api_batch_fee.total_fee, BigUint::from(2u32));
+        let batch_quote = api_batch_fee.quote.expect("Fee quote is missing");
+        assert_eq!(batch_quote.quote.transactions.len(), 3);
+        assert!(quote_signer
+            .verify(&batch_quote, batch_quote.quote.timestamp)
+            .is_ok());
+
+        let from = Utc.ymd(2023, 3, 1).and_hms(0, 0, 0);
+        let mut query = FeeStatisticsQuery {
+            tx_type: TransactionType::Transfer,
+            token: "0".to_string(),
+            resolution: FeeStatisticsResolution::Day,
+            from,
+            to: from + chrono::Duration::days(3),
+        };
+        let response = client.get_fee_statistics(&query).await?;
+        let statistics: FeeStatistics = deserialize_response_result(response)?;
+        assert_eq!(statistics.token_id, TokenId(0));
+        assert_eq!(statistics.buckets.len(), 3);
+        assert_eq!(statistics.buckets[1].from, from + chrono::Duration::days(1));
+
+        // Empty and too long ranges are rejected.
+        for to in [
+            from,
+            from + chrono::Duration::days(MAX_FEE_STATISTICS_BUCKETS),
+        ] {
+            query.to = to;
+            let response = client.get_fee_statistics(&query).await?;
+            let expected_error = Error::from(InvalidDataError::InvalidFeeStatisticsRange);
+            let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+            assert_eq!(error, expected_error);
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn fee_token_policy() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let (mempool_tx_request_sender, _mempool_tx_request_receiver) = mpsc::channel(100);
+
+        // Token 1 isn't liquid, token 2 is highly liquid and token 3 is liquid.
+        let mut tokens = HashMap::new();
+        let mut market = HashMap::new();
+        for (id, market_volume) in [(1, None), (2, Some(400u32)), (3, Some(200u32))] {
+            let token_id = TokenId(id);
+            tokens.insert(
+                TokenLike::Id(token_id),
+                Token::new(token_id, Default::default(), "", 18, TokenKind::ERC20),
+            );
+            if let Some(market_volume) = market_volume {
+                market.insert(
+                    token_id,
+                    TokenMarketVolume {
+                        market_volume: Ratio::from_integer(BigUint::from(market_volume)),
+                        last_updated: Utc::now(),
+                    },
+                );
+            }
+        }
+        let prices = vec![
+            (TokenLike::Id(TokenId(0)), 10_u64.into()),
+            (TokenLike::Id(TokenId(1)), 10_u64.into()),
+            (TokenLike::Id(TokenId(2)), 10_u64.into()),
+            (TokenLike::Id(TokenId(3)), 10_u64.into()),
+        ];
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let tx_sender = TxSender::new(
+            cfg.pool.clone(),
+            dummy_sign_verifier(),
+            dummy_fee_ticker(&prices, Some(cache)),
+            &cfg.config.api.common,
+            cfg.token_cache(),
+            mempool_tx_request_sender,
+            DisabledTxTypes::new(cfg.pool.clone()),
+            BannedAddresses::new(cfg.pool.clone()),
+            ChainId(cfg.config.eth_client.chain_id),
+        );
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            {
+                let tx_sender = tx_sender.clone();
+                move |cfg: &TestServerConfig| api_scope(cfg.replicated_pool(), tx_sender.clone())
+            },
+            Some(shared_data),
+        );
+
+        let response = client.get_allowed_fee_tokens().await?;
+        let allowed_tokens: Vec<AllowedFeeTokens> = deserialize_response_result(response)?;
+        assert_eq!(allowed_tokens.len(), TransactionType::L2_TYPES.len());
+        let allowed_in = |tx_type: TransactionType| {
+            allowed_tokens
+                .iter()
+                .find(|allowed| allowed.tx_type == tx_type)
+                .unwrap()
+                .tokens
+                .clone()
+        };
+        assert_eq!(
+            allowed_in(TransactionType::Transfer),
+            vec![TokenId(2), TokenId(3)]
+        );
+        assert_eq!(allowed_in(TransactionType::Withdraw), vec![TokenId(2)]);
+
+        let fee_types = vec![
+            ApiTxFeeTypes::Withdraw,
+            ApiTxFeeTypes::FastWithdraw,
+            ApiTxFeeTypes::Transfer,
+            ApiTxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+                ChangePubKeyType::ECDSA,
+            )),
+            ApiTxFeeTypes::ForcedExit,
+            ApiTxFeeTypes::MintNFT,
+            ApiTxFeeTypes::WithdrawNFT,
+            ApiTxFeeTypes::FastWithdrawNFT,
+            ApiTxFeeTypes::Swap,
+        ];
+        for fee_type in fee_types {
+            let tx_type = fee_type.transaction_type();
+            for token_id in [TokenId(1), TokenId(2), TokenId(3)] {
+                let token = TokenLike::Id(token_id);
+                // Submissions are checked by the same policy.
+                let submission = tx_sender.check_fee_token(&[tx_type], token.clone()).await;
+                let response = client
+                    .get_txs_fee(fee_type.clone(), Address::default(), token)
+                    .await?;
+
+                let allowed = allowed_in(tx_type).contains(&token_id);
+                assert_eq!(submission.is_ok(), allowed, "{:?} {}", fee_type, token_id);
+                match submission {
+                    Ok(()) => assert!(response.error.is_none(), "{:?} {}", fee_type, token_id),
+                    Err(err) => {
+                        let error = serde_json::from_value::<Error>(response.error.unwrap())?;
+                        assert_eq!(error, Error::from(err));
+                    }
+                }
+            }
+        }
+
+        // The fee token of the batch must be suitable for every transaction in it.
+        let batch = vec![
+            TxInBatchFeeRequest {
+                tx_type: ApiTxFeeTypes::Transfer,
+                address: Address::default(),
+            },
+            TxInBatchFeeRequest {
+                tx_type: ApiTxFeeTypes::Withdraw,
+                address: Address::default(),
+            },
+        ];
+        let response = client
+            .get_batch_fee(batch.clone(), TokenLike::Id(TokenId(3)))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap())?;
+        assert_eq!(
+            error,
+            Error::from(SubmitError::FeeTokenNotAllowedForTxType(
+                TransactionType::Withdraw
+            ))
+        );
+        let response = client
+            .get_batch_fee(batch, TokenLike::Id(TokenId(2)))
+            .await?;
+        let _: ApiFee = deserialize_response_result(response)?;
 
         server.stop().await;
         Ok(())
@@ -0,0 +1,144 @@
+//! Forced exit requests part of API implementation.
+
+// Built-in uses
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+use num::BigUint;
+
+// Workspace uses
+use zksync_api_types::v02::forced_exit::ForcedExitParams;
+use zksync_config::ZkSyncConfig;
+use zksync_storage::{AccessIntent, ReplicatedPool};
+use zksync_types::Address;
+
+// Local uses
+use super::{error::Error, response::ApiResult};
+
+/// Shared data between `api/v0.2/forcedExit` endpoints.
+#[derive(Debug, Clone)]
+struct ApiForcedExitData {
+    pool: ReplicatedPool,
+    enabled: bool,
+    price_per_token: BigUint,
+    payment_address: Address,
+    max_tokens_per_request: u8,
+}
+
+impl ApiForcedExitData {
+    fn new(pool: ReplicatedPool, config: &ZkSyncConfig) -> Self {
+        Self {
+            pool,
+            enabled: config.forced_exit_requests.enabled,
+            price_per_token: BigUint::from(config.forced_exit_requests.price_per_token as u64),
+            payment_address: config.contracts.forced_exit_addr,
+            max_tokens_per_request: config.forced_exit_requests.max_tokens_per_request,
+        }
+    }
+
+    /// Returns the parameters the requests are processed with. The pause flag is set by the operator
+    /// at runtime, so it's read from the database on every request.
+    async fn params(&self) -> Result<ForcedExitParams, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let paused = storage
+            .forced_exit_requests_schema()
+            .is_processing_paused()
+            .await
+            .map_err(Error::storage)?;
+
+        Ok(ForcedExitParams {
+            enabled: self.enabled,
+            paused,
+            price_per_token: self.price_per_token.clone(),
+            payment_address: self.payment_address,
+            max_tokens_per_request: self.max_tokens_per_request,
+        })
+    }
+}
+
+// Server implementation
+
+async fn params(data: web::Data<ApiForcedExitData>) -> ApiResult<ForcedExitParams> {
+    let start = Instant::now();
+    let res = data.params().await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "forced_exit_params");
+    res
+}
+
+pub fn api_scope(pool: ReplicatedPool, config: &ZkSyncConfig) -> Scope {
+    let data = ApiForcedExitData::new(pool, config);
+
+    web::scope("forcedExit")
+        .app_data(web::Data::new(data))
+        .route("params", web::get().to(params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{deserialize_response_result, TestServerConfig},
+        SharedData,
+    };
+    use actix_web::http::StatusCode;
+    use zksync_api_types::v02::ApiVersion;
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn forced_exit_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| api_scope(cfg.replicated_pool(), &cfg.config),
+            Some(shared_data),
+        );
+
+        let expected = ForcedExitParams {
+            enabled: cfg.config.forced_exit_requests.enabled,
+            paused: false,
+            price_per_token: BigUint::from(cfg.config.forced_exit_requests.price_per_token as u64),
+            payment_address: cfg.config.contracts.forced_exit_addr,
+            max_tokens_per_request: cfg.config.forced_exit_requests.max_tokens_per_request,
+        };
+        let response = client.forced_exit_params().await?;
+        let params: ForcedExitParams = deserialize_response_result(response)?;
+        assert_eq!(params, expected);
+
+        // The pause flag is picked up without restart.
+        cfg.pool
+            .access_storage()
+            .await?
+            .forced_exit_requests_schema()
+            .set_processing_paused(true, "test")
+            .await?;
+        let response = client.forced_exit_params().await?;
+        let params: ForcedExitParams = deserialize_response_result(response)?;
+        assert!(params.paused);
+
+        cfg.pool
+            .access_storage()
+            .await?
+            .forced_exit_requests_schema()
+            .set_processing_paused(false, "test")
+            .await?;
+        let response = client.forced_exit_params().await?;
+        let params: ForcedExitParams = deserialize_response_result(response)?;
+        assert_eq!(params, expected);
+
+        server.stop().await;
+        Ok(())
+    }
+}
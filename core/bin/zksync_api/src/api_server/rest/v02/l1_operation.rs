@@ -0,0 +1,245 @@
+//! L1 operations part of API implementation.
+
+// Built-in uses
+use std::str::FromStr;
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+use zksync_api_types::v02::l1_operation::{
+    L1OperationBlock, L1OperationInfo, L1OperationStatus, L1OperationType,
+};
+use zksync_storage::{chain::block::records::StorageBlockSummary, AccessIntent, ReplicatedPool};
+use zksync_types::{
+    aggregated_operations::{AggregatedActionType, AggregatedOperation},
+    gas_counter::GasCounter,
+    BlockNumber, H256,
+};
+
+// Local uses
+use super::{
+    error::Error,
+    path_params::ValidPath,
+    response::{ApiResult, OrNotFound},
+};
+
+fn l1_operation_block_from_summary(summary: StorageBlockSummary) -> L1OperationBlock {
+    L1OperationBlock {
+        block_number: BlockNumber(summary.block_number as u32),
+        block_size: summary.block_size as u64,
+        transactions_count: summary.transactions_count as u64,
+        priority_operations_count: summary.priority_operations_count as u64,
+    }
+}
+
+/// Maps the stored action type of the aggregated operation to the type of the L1 operation,
+/// `None` for the operations that are not sent to L1.
+pub(super) fn l1_operation_type(action_type: &str) -> Option<L1OperationType> {
+    match AggregatedActionType::from_str(action_type) {
+        Ok(AggregatedActionType::CommitBlocks) => Some(L1OperationType::Commit),
+        Ok(AggregatedActionType::PublishProofBlocksOnchain) => Some(L1OperationType::Prove),
+        Ok(AggregatedActionType::ExecuteBlocks) => Some(L1OperationType::Execute),
+        _ => None,
+    }
+}
+
+/// Shared data between `api/v0.2/l1Operations` endpoints.
+#[derive(Debug, Clone)]
+struct ApiL1OperationData {
+    pool: ReplicatedPool,
+}
+
+impl ApiL1OperationData {
+    fn new(pool: ReplicatedPool) -> Self {
+        Self { pool }
+    }
+
+    /// Returns the aggregated operation the Ethereum transaction was sent for,
+    /// or `None` if the transaction was not sent by the zkSync operator.
+    async fn l1_operation(&self, eth_tx_hash: H256) -> Result<Option<L1OperationInfo>, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+
+        let operation = storage
+            .ethereum_schema()
+            .load_l1_operation(&eth_tx_hash)
+            .await
+            .map_err(Error::storage)?;
+        let operation = match operation {
+            Some(operation) => operation,
+            None => return Ok(None),
+        };
+
+        let op_type = match l1_operation_type(&operation.action_type) {
+            Some(op_type) => op_type,
+            None => return Ok(None),
+        };
+        let aggregated_op: AggregatedOperation =
+            serde_json::from_value(operation.arguments).map_err(Error::storage)?;
+        let gas_estimated = GasCounter::aggregated_op_gas_limit(&aggregated_op)
+            .map(|gas_limit| gas_limit.as_u64())
+            .unwrap_or_default();
+
+        let final_tx_hash = operation
+            .final_hash
+            .map(|final_hash| H256::from_slice(&final_hash));
+        let status = match final_tx_hash {
+            Some(final_tx_hash) if operation.confirmed && final_tx_hash == eth_tx_hash => {
+                L1OperationStatus::Confirmed
+            }
+            Some(_) if operation.confirmed => L1OperationStatus::Replaced,
+            _ => L1OperationStatus::Pending,
+        };
+
+        let from_block = BlockNumber(operation.from_block as u32);
+        let to_block = BlockNumber(operation.to_block as u32);
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_block_summaries(from_block, to_block)
+            .await
+            .map_err(Error::storage)?
+            .into_iter()
+            .map(l1_operation_block_from_summary)
+            .collect();
+
+        Ok(Some(L1OperationInfo {
+            aggregated_op_id: operation.aggregated_op_id as u64,
+            op_type,
+            eth_tx_hash,
+            final_tx_hash,
+            status,
+            from_block,
+            to_block,
+            gas_estimated,
+            gas_used: operation.gas_used.map(|gas_used| gas_used as u64),
+            created_at: operation.created_at,
+            blocks,
+        }))
+    }
+}
+
+// Server implementation
+
+async fn l1_operation(
+    data: web::Data<ApiL1OperationData>,
+    eth_tx_hash: ValidPath<H256>,
+) -> ApiResult<L1OperationInfo> {
+    let start = Instant::now();
+    let res = data
+        .l1_operation(*eth_tx_hash)
+        .await
+        .or_not_found("l1Operation", format!("{:#x}", *eth_tx_hash))
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "l1_operation");
+    res
+}
+
+pub fn api_scope(pool: ReplicatedPool) -> Scope {
+    let data = ApiL1OperationData::new(pool);
+
+    web::scope("l1Operations")
+        .app_data(web::Data::new(data))
+        .route("{eth_tx_hash}", web::get().to(l1_operation))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{
+            assert_not_found, deserialize_response_result, TestServerConfig, EXECUTED_BLOCKS_COUNT,
+        },
+        SharedData,
+    };
+    use actix_web::http::StatusCode;
+    use zksync_api_types::v02::ApiVersion;
+    use zksync_storage::{chain::operations::OperationsSchema, test_data::dummy_ethereum_tx_hash};
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn l1_operations_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| api_scope(cfg.replicated_pool()),
+            Some(shared_data),
+        );
+
+        // The block is committed, proven and executed.
+        let block_number = BlockNumber(EXECUTED_BLOCKS_COUNT);
+        let op_types = [
+            (AggregatedActionType::CommitBlocks, L1OperationType::Commit),
+            (
+                AggregatedActionType::PublishProofBlocksOnchain,
+                L1OperationType::Prove,
+            ),
+            (
+                AggregatedActionType::ExecuteBlocks,
+                L1OperationType::Execute,
+            ),
+        ];
+        for (action_type, op_type) in op_types {
+            let (op_id, aggregated_op, expected_txs_count) = {
+                let mut storage = cfg.pool.access_storage().await?;
+                let (op_id, aggregated_op) = OperationsSchema(&mut storage)
+                    .get_aggregated_op_that_affects_block(action_type, block_number)
+                    .await?
+                    .unwrap();
+                let txs_count = storage
+                    .chain()
+                    .block_schema()
+                    .get_block_transactions_count(block_number)
+                    .await?;
+                (op_id, aggregated_op, txs_count)
+            };
+            let eth_tx_hash = dummy_ethereum_tx_hash(op_id);
+
+            let response = client.l1_operation(eth_tx_hash).await?;
+            let operation: L1OperationInfo = deserialize_response_result(response)?;
+
+            assert_eq!(operation.aggregated_op_id, op_id as u64);
+            assert_eq!(operation.op_type, op_type);
+            assert_eq!(operation.eth_tx_hash, eth_tx_hash);
+            assert_eq!(operation.final_tx_hash, Some(eth_tx_hash));
+            assert_eq!(operation.status, L1OperationStatus::Confirmed);
+            assert_eq!(operation.from_block, block_number);
+            assert_eq!(operation.to_block, block_number);
+            assert_eq!(
+                Some(operation.gas_estimated.into()),
+                GasCounter::aggregated_op_gas_limit(&aggregated_op)
+            );
+            // Gas used is not stored for the fixture operations.
+            assert_eq!(operation.gas_used, None);
+
+            assert_eq!(operation.blocks.len(), 1);
+            let block = &operation.blocks[0];
+            assert_eq!(block.block_number, block_number);
+            assert_eq!(
+                (block.transactions_count + block.priority_operations_count) as u32,
+                expected_txs_count
+            );
+        }
+
+        // Unknown hash.
+        let response = client.l1_operation(H256::repeat_byte(0xff)).await?;
+        assert_not_found(response, "l1Operation")?;
+
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -1,65 +1,187 @@
 // External uses
 use actix_web::{
+    dev::HttpServiceFactory,
+    http::StatusCode,
     web::{self},
-    Scope,
 };
 // Workspace uses
-use crate::api_server::rest::network_status::SharedNetworkStatus;
+use crate::api_server::rest::{network_status::SharedNetworkStatus, warm_up::ApiReadiness};
 use zksync_api_types::v02::ApiVersion;
 use zksync_config::ZkSyncConfig;
+use zksync_storage::ReplicatedPool;
 use zksync_types::network::Network;
 
 // Local uses
-use crate::api_server::tx_sender::TxSender;
+use self::{
+    access_tokens::AccessTokens, admission::StorageAdmission, archive::TxArchive,
+    blocks_export::BlocksExporter, client_ip::ClientIpResolver, state_root_check::StateRootChecker,
+    watchdog::RequestWatchdog,
+};
+use crate::{
+    api_server::tx_sender::TxSender,
+    utils::{pool_supervisor::PoolSupervisor, special_accounts::SpecialAccounts},
+};
 
+mod access_tokens;
 mod account;
+pub mod account_snapshots;
+mod admission;
+mod amounts;
+pub mod archive;
 mod block;
+pub mod blocks_export;
+mod checksum;
+pub mod client_ip;
 mod config;
 pub mod error;
+mod error_codes;
+pub(crate) mod event;
+mod exit_plan;
 mod fee;
+mod forced_exit;
+mod l1_operation;
 mod paginate_impl;
 mod paginate_trait;
+mod path_params;
+mod request_span;
 mod response;
+mod search;
+pub mod state_root_check;
 mod status;
+mod storage_retry;
 #[cfg(test)]
 pub mod test_utils;
 mod token;
+pub mod token_activity;
+pub mod token_holders;
 mod transaction;
+pub mod watchdog;
+mod webhooks;
 
 #[derive(Debug, Clone, Copy)]
 pub struct SharedData {
     pub net: Network,
     pub api_version: ApiVersion,
+    /// HTTP status of the responses reporting the resources requested by their identifiers as missing.
+    pub not_found_status: StatusCode,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn api_scope(
+    pool: ReplicatedPool,
+    archive: Option<TxArchive>,
     tx_sender: TxSender,
     zk_config: &ZkSyncConfig,
     network_status: SharedNetworkStatus,
-) -> Scope {
+    readiness: ApiReadiness,
+    blocks_exporter: BlocksExporter,
+    state_root_checker: StateRootChecker,
+    pool_supervisor: PoolSupervisor,
+    request_watchdog: RequestWatchdog,
+    client_ip: ClientIpResolver,
+) -> impl HttpServiceFactory {
     let data = SharedData {
         net: zk_config.chain.eth.network,
         api_version: ApiVersion::V02,
+        not_found_status: if zk_config.api.rest.not_found_http_404 {
+            StatusCode::NOT_FOUND
+        } else {
+            StatusCode::OK
+        },
     };
+    // Every scope accessing the storage admits the requests within its own budget.
+    let rest_config = &zk_config.api.rest;
+    let accounts = StorageAdmission::new("accounts", rest_config);
+    let blocks = StorageAdmission::new("blocks", rest_config);
+    let fee = StorageAdmission::new("fee", rest_config);
+    let l1_operations = StorageAdmission::new("l1_operations", rest_config);
+    let search = StorageAdmission::new("search", rest_config);
+    let tokens = StorageAdmission::new("tokens", rest_config);
+    let transactions =
+        StorageAdmission::new("transactions", rest_config).with_submissions(rest_config);
+    // Requests with the account access tokens are checked before any handler runs.
+    let access = AccessTokens::new(pool.clone(), data);
+    let token_guard = access.clone();
+    let webhook_pool = pool.clone();
     web::scope("/api/v0.2")
         .app_data(web::Data::new(data))
-        .service(account::api_scope(
-            tx_sender.pool.clone(),
-            tx_sender.tokens.clone(),
-            zk_config.eth_watch.confirmations_for_eth_event,
-        ))
-        .service(block::api_scope(
-            tx_sender.pool.clone(),
-            tx_sender.blocks.clone(),
-        ))
-        .service(config::api_scope(zk_config))
-        .service(fee::api_scope(tx_sender.clone()))
-        .service(status::api_scope(network_status))
-        .service(token::api_scope(
+        .wrap_fn(move |req, srv| token_guard.guard(req, srv))
+        .wrap_fn(move |req, srv| request_watchdog.track(req, srv))
+        .wrap_fn(request_span::instrument_request)
+        // The client address is recorded in the request span, so it's resolved beforehand.
+        .wrap_fn(move |req, srv| client_ip.resolve(req, srv))
+        .service(access_tokens::api_scope(access))
+        .service(
+            account::api_scope(
+                pool.clone(),
+                tx_sender.tokens.clone(),
+                tx_sender.ticker.clone(),
+                zk_config.eth_watch.confirmations_for_eth_event,
+                zk_config.chain.state_keeper.rejected_txs_retention_period(),
+                SpecialAccounts::new(zk_config.chain.state_keeper.fee_account_addr),
+                tx_sender.account_locks.clone(),
+            )
+            .wrap_fn(move |req, srv| accounts.admit(req, srv)),
+        )
+        .service(
+            block::api_scope(
+                pool.clone(),
+                tx_sender.blocks.clone(),
+                blocks_exporter,
+                state_root_checker,
+                network_status.clone(),
+                zk_config.eth_sender.sender.wait_confirmations,
+            )
+            .wrap_fn(move |req, srv| blocks.admit(req, srv)),
+        )
+        .service(config::api_scope(
             zk_config,
-            tx_sender.pool.clone(),
-            tx_sender.tokens.clone(),
-            tx_sender.ticker.clone(),
+            tx_sender.disabled_tx_types.clone(),
+        ))
+        .service(error_codes::api_scope())
+        .service(event::api_scope(pool.clone()))
+        .service(
+            fee::api_scope(pool.clone(), tx_sender.clone())
+                .wrap_fn(move |req, srv| fee.admit(req, srv)),
+        )
+        .service(forced_exit::api_scope(pool.clone(), zk_config))
+        .service(
+            l1_operation::api_scope(pool.clone())
+                .wrap_fn(move |req, srv| l1_operations.admit(req, srv)),
+        )
+        .service(
+            search::api_scope(pool.clone(), tx_sender.tokens.clone())
+                .wrap_fn(move |req, srv| search.admit(req, srv)),
+        )
+        .service(status::api_scope(
+            network_status.clone(),
+            readiness,
+            tx_sender.sign_verifier_breaker.clone(),
+            pool_supervisor,
         ))
-        .service(transaction::api_scope(tx_sender))
+        .service(
+            token::api_scope(
+                zk_config,
+                pool.clone(),
+                tx_sender.tokens.clone(),
+                tx_sender.ticker.clone(),
+            )
+            .wrap_fn(move |req, srv| tokens.admit(req, srv)),
+        )
+        .service(
+            transaction::api_scope(
+                pool,
+                archive,
+                tx_sender,
+                network_status,
+                &zk_config.api.rest,
+                zk_config.api.admin.secret_auth.clone(),
+                zk_config
+                    .chain
+                    .state_keeper
+                    .max_aggregated_blocks_to_execute,
+            )
+            .wrap_fn(move |req, srv| transactions.admit(req, srv)),
+        )
+        .service(webhooks::api_scope(webhook_pool, &zk_config.api.rest))
 }
@@ -14,7 +14,7 @@ use zksync_api_types::{
     },
     Either,
 };
-use zksync_storage::StorageProcessor;
+use zksync_storage::{chain::mempool::records::PendingPriorityOp, StorageProcessor};
 use zksync_types::{BlockNumber, SerialId, Token, TokenId};
 
 // Local uses
@@ -22,30 +22,31 @@ use super::{
     block::block_info_from_details,
     error::{Error, InvalidDataError},
     paginate_trait::Paginate,
+    token::TokensRequest,
 };
 
 use zksync_api_types::v02::transaction::{L1Transaction, TransactionData, TxInBlockStatus};
 
 #[async_trait::async_trait]
-impl Paginate<ApiEither<TokenId>> for StorageProcessor<'_> {
+impl Paginate<TokensRequest> for StorageProcessor<'_> {
     type OutputObj = Token;
     type OutputId = TokenId;
 
     async fn paginate(
         &mut self,
-        query: &PaginationQuery<ApiEither<TokenId>>,
+        query: &PaginationQuery<TokensRequest>,
     ) -> Result<Paginated<Token, TokenId>, Error> {
         let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
 
-        let token_id = match query.from.inner {
+        let filter = &query.from.filter;
+        let (count, max_token_id) = transaction
+            .tokens_schema()
+            .get_filtered_count_and_max_id(filter)
+            .await
+            .map_err(Error::storage)?;
+        let token_id = match query.from.token_id.inner {
             Either::Left(token_id) => token_id,
-            Either::Right(_) => TokenId(
-                transaction
-                    .tokens_schema()
-                    .get_max_erc20_token_id()
-                    .await
-                    .map_err(Error::storage)?,
-            ),
+            Either::Right(_) => max_token_id.unwrap_or_default(),
         };
 
         let query = PaginationQuery {
@@ -56,12 +57,7 @@ impl Paginate<ApiEither<TokenId>> for StorageProcessor<'_> {
 
         let tokens = transaction
             .tokens_schema()
-            .load_token_page(&query)
-            .await
-            .map_err(Error::storage)?;
-        let count = transaction
-            .tokens_schema()
-            .get_count()
+            .load_token_page(&query, filter)
             .await
             .map_err(Error::storage)?;
         transaction.commit().await.map_err(Error::storage)?;
@@ -244,16 +240,28 @@ impl Paginate<AccountTxsRequest> for StorageProcessor<'_> {
             .await
             .map_err(Error::storage)?
             .ok_or_else(|| Error::from(InvalidDataError::TransactionNotFound))?;
-        let count = transaction
-            .chain()
-            .operations_ext_schema()
-            .get_account_transactions_count(
-                query.from.address,
-                query.from.token,
-                query.from.second_address,
-            )
-            .await
-            .map_err(Error::storage)?;
+        let count = if let Some(counterparty) = query.from.counterparty {
+            transaction
+                .chain()
+                .operations_ext_schema()
+                .get_counterparty_transactions_count(
+                    query.from.address,
+                    counterparty,
+                    query.from.token,
+                )
+                .await
+        } else {
+            transaction
+                .chain()
+                .operations_ext_schema()
+                .get_account_transactions_count(
+                    query.from.address,
+                    query.from.token,
+                    query.from.second_address,
+                )
+                .await
+        }
+        .map_err(Error::storage)?;
 
         transaction.commit().await.map_err(Error::storage)?;
 
@@ -308,7 +316,7 @@ impl Paginate<PendingOpsRequest> for StorageProcessor<'_> {
         let count = result.len() as u32;
         let txs = result
             .into_iter()
-            .map(|op| {
+            .map(|PendingPriorityOp { op, created_at }| {
                 let tx_hash = op.tx_hash();
                 let tx = L1Transaction::from_pending_op(
                     op.data.clone(),
@@ -323,7 +331,7 @@ impl Paginate<PendingOpsRequest> for StorageProcessor<'_> {
                     op: TransactionData::L1(tx),
                     status: TxInBlockStatus::Queued,
                     fail_reason: None,
-                    created_at: None,
+                    created_at: Some(created_at),
                     batch_id: None,
                 }
             })
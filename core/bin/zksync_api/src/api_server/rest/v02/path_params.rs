@@ -0,0 +1,352 @@
+//! Extraction of the identifiers from the request path.
+//!
+//! `web::Path` relies on the `FromStr` implementations, so e.g. a transaction hash without the
+//! `0x` or `sync-tx:` prefix is rejected with a bare `400 Bad Request`. The identifiers are accepted
+//! here in any of the formats they are commonly displayed with: the prefixes are optional and
+//! case-insensitive, the hex digits are accepted in any case. Malformed identifiers are reported
+//! with the regular `InvalidPathParameter` error naming the parameter and the accepted formats.
+//!
+//! Mixed-case addresses are expected to be checksummed according to EIP-55. Since the checksum
+//! only guards against typos and the address is valid either way, a wrong checksum is logged but
+//! the address is accepted.
+
+// Built-in uses
+use std::{ops::Deref, str::FromStr};
+
+// External uses
+use actix_web::{dev::Payload, error::InternalError, FromRequest, HttpRequest, Responder};
+use futures::future::{ready, Ready};
+
+// Workspace uses
+use zksync_api_types::v02::account::AccountAddressOrId;
+use zksync_types::{tx::TxHash, AccountId, Address, H256};
+
+// Local uses
+use super::{
+    error::{Error, InvalidDataError},
+    response::ApiResult,
+};
+
+const HASH_HEX_LEN: usize = 64;
+const ADDRESS_HEX_LEN: usize = 40;
+
+/// Identifier taken from the request path.
+pub trait PathParam: Sized {
+    /// Formats the identifier is accepted in, reported along with the validation error.
+    const ACCEPTED_FORMATS: &'static [&'static str];
+
+    /// Parses the trimmed path segment, returns `None` if it's malformed.
+    fn parse_path_param(value: &str) -> Option<Self>;
+}
+
+impl PathParam for TxHash {
+    const ACCEPTED_FORMATS: &'static [&'static str] = &[
+        "sync-tx:<64 hex characters>",
+        "0x<64 hex characters>",
+        "<64 hex characters>",
+    ];
+
+    fn parse_path_param(value: &str) -> Option<Self> {
+        let digits = hex_digits(value, &["sync-tx:", "0x"], HASH_HEX_LEN)?;
+        TxHash::from_slice(&hex::decode(digits).ok()?)
+    }
+}
+
+impl PathParam for H256 {
+    const ACCEPTED_FORMATS: &'static [&'static str] =
+        &["0x<64 hex characters>", "<64 hex characters>"];
+
+    fn parse_path_param(value: &str) -> Option<Self> {
+        let digits = hex_digits(value, &["0x"], HASH_HEX_LEN)?;
+        Some(H256::from_slice(&hex::decode(digits).ok()?))
+    }
+}
+
+impl PathParam for Address {
+    const ACCEPTED_FORMATS: &'static [&'static str] =
+        &["0x<40 hex characters>", "<40 hex characters>"];
+
+    fn parse_path_param(value: &str) -> Option<Self> {
+        let digits = hex_digits(value, &["0x"], ADDRESS_HEX_LEN)?;
+        if is_mixed_case(digits) && !is_checksummed(digits) {
+            vlog::warn!("Address `{}` has an invalid EIP-55 checksum", value);
+            metrics::increment_counter!("api.v02.invalid_address_checksum");
+        }
+        Some(Address::from_slice(&hex::decode(digits).ok()?))
+    }
+}
+
+impl PathParam for AccountAddressOrId {
+    const ACCEPTED_FORMATS: &'static [&'static str] = &[
+        "<account id>",
+        "0x<40 hex characters>",
+        "<40 hex characters>",
+    ];
+
+    fn parse_path_param(value: &str) -> Option<Self> {
+        if let Ok(account_id) = u32::from_str(value) {
+            return Some(Self::Id(AccountId(account_id)));
+        }
+        Address::parse_path_param(value).map(Self::Address)
+    }
+}
+
+/// Strips the first matching prefix, if any, and checks that exactly `len` hex digits are left.
+fn hex_digits<'a>(value: &'a str, prefixes: &[&str], len: usize) -> Option<&'a str> {
+    let digits = prefixes
+        .iter()
+        .find_map(|prefix| strip_prefix_ignore_case(value, prefix))
+        .unwrap_or(value);
+    if digits.len() == len && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        Some(digits)
+    } else {
+        None
+    }
+}
+
+pub(super) fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    match value.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => Some(&value[prefix.len()..]),
+        _ => None,
+    }
+}
+
+fn is_mixed_case(digits: &str) -> bool {
+    digits.chars().any(|c| c.is_ascii_lowercase()) && digits.chars().any(|c| c.is_ascii_uppercase())
+}
+
+/// Checks the EIP-55 checksum of the address hex digits: a letter is uppercase if the
+/// corresponding nibble of the `keccak256` of the lowercase digits is at least 8.
+fn is_checksummed(digits: &str) -> bool {
+    let hash = tiny_keccak::keccak256(digits.to_ascii_lowercase().as_bytes());
+    digits.chars().enumerate().all(|(i, c)| {
+        let nibble = if i % 2 == 0 {
+            hash[i / 2] >> 4
+        } else {
+            hash[i / 2] & 0x0f
+        };
+        !c.is_ascii_alphabetic() || c.is_ascii_uppercase() == (nibble >= 8)
+    })
+}
+
+/// Extractor of the identifier from the first dynamic segment of the path,
+/// e.g. `{tx_hash}` of `transactions/{tx_hash}/data`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidPath<T>(pub T);
+
+impl<T> ValidPath<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for ValidPath<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: PathParam> FromRequest for ValidPath<T> {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let (name, value) = req
+            .match_info()
+            .iter()
+            .next()
+            .expect("Path parameter is extracted from the route without dynamic segments");
+        let result = T::parse_path_param(value.trim()).map(Self).ok_or_else(|| {
+            let error = Error::from(InvalidDataError::InvalidPathParameter {
+                name: name.to_owned(),
+                formats: T::ACCEPTED_FORMATS,
+            });
+            let response = ApiResult::<()>::Error(error).respond_to(req);
+            InternalError::from_response("invalid path parameter", response).into()
+        });
+        ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{error::ErrorCode, SharedData};
+    use actix_web::{http::StatusCode, test, web, App};
+    use zksync_api_types::v02::{ApiVersion, Response};
+    use zksync_types::network::Network;
+
+    const HASH: &str = "3a6d8f1b9c2e4a7f0b5d8c1e6f9a2b4d7c0e3f6a9b1d4c7e0f2a5b8d1c4e7f0a";
+    const ADDRESS: &str = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+    const CHECKSUMMED_ADDRESS: &str = "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn tx_hashes() {
+        let expected = TxHash::from_slice(&hex::decode(HASH).unwrap()).unwrap();
+        for value in [
+            format!("sync-tx:{}", HASH),
+            format!("0x{}", HASH),
+            HASH.to_owned(),
+            format!("SYNC-TX:{}", HASH),
+            format!("0X{}", HASH.to_ascii_uppercase()),
+            format!("sync-tx:{}", HASH.to_ascii_uppercase()),
+        ] {
+            assert_eq!(
+                TxHash::parse_path_param(&value),
+                Some(expected),
+                "{}",
+                value
+            );
+        }
+
+        for value in [
+            String::new(),
+            "0x".to_owned(),
+            "sync-tx:".to_owned(),
+            "abcd".to_owned(),
+            HASH[1..].to_owned(),
+            format!("{}0", HASH),
+            format!("0x{}g", &HASH[1..]),
+            format!("0x0x{}", HASH),
+            format!("sync-tx:0x{}", HASH),
+            format!("tx:{}", HASH),
+            format!("0x{} {}", &HASH[..32], &HASH[32..63]),
+            format!("0x{}é", &HASH[1..]),
+        ] {
+            assert_eq!(TxHash::parse_path_param(&value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn eth_tx_hashes() {
+        let expected = H256::from_slice(&hex::decode(HASH).unwrap());
+        for value in [
+            format!("0x{}", HASH),
+            format!("0X{}", HASH),
+            HASH.to_ascii_uppercase(),
+        ] {
+            assert_eq!(H256::parse_path_param(&value), Some(expected), "{}", value);
+        }
+
+        for value in [
+            String::new(),
+            "0x".to_owned(),
+            format!("sync-tx:{}", HASH),
+            HASH[1..].to_owned(),
+            format!("{}0", HASH),
+            format!("0x{}z", &HASH[1..]),
+            format!("0x0x{}", HASH),
+            format!("0x{}", ADDRESS),
+            "1".to_owned(),
+            format!("-{}", &HASH[1..]),
+            format!("0x{}\n", &HASH[1..]),
+            format!("0x{}%", &HASH[1..]),
+        ] {
+            assert_eq!(H256::parse_path_param(&value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn accounts() {
+        let address = Address::from_slice(&hex::decode(ADDRESS).unwrap());
+        for value in [
+            format!("0x{}", ADDRESS),
+            ADDRESS.to_owned(),
+            format!("0X{}", ADDRESS.to_ascii_uppercase()),
+            format!("0x{}", CHECKSUMMED_ADDRESS),
+            // A wrong checksum is accepted.
+            format!("0x{}", CHECKSUMMED_ADDRESS.replace('a', "A")),
+        ] {
+            assert_eq!(
+                AccountAddressOrId::parse_path_param(&value),
+                Some(AccountAddressOrId::Address(address)),
+                "{}",
+                value
+            );
+        }
+        assert_eq!(
+            AccountAddressOrId::parse_path_param("42"),
+            Some(AccountAddressOrId::Id(AccountId(42)))
+        );
+
+        for value in [
+            String::new(),
+            "0x".to_owned(),
+            "-1".to_owned(),
+            "4294967296".to_owned(),
+            "1.5".to_owned(),
+            ADDRESS[1..].to_owned(),
+            format!("{}0", ADDRESS),
+            format!("0x{}z", &ADDRESS[1..]),
+            format!("0x0x{}", &ADDRESS[2..]),
+            format!("sync:{}", ADDRESS),
+            format!("0x{}", HASH),
+            format!("0x{} {}", &ADDRESS[..20], &ADDRESS[20..39]),
+        ] {
+            assert_eq!(
+                AccountAddressOrId::parse_path_param(&value),
+                None,
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn address_checksums() {
+        for checksummed in [
+            CHECKSUMMED_ADDRESS,
+            "fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "dbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "D1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            assert!(is_mixed_case(checksummed));
+            assert!(is_checksummed(checksummed), "{}", checksummed);
+        }
+        assert!(!is_checksummed(&CHECKSUMMED_ADDRESS.replace('a', "A")));
+        // Single-case addresses carry no checksum.
+        assert!(!is_mixed_case(ADDRESS));
+        assert!(!is_mixed_case(&ADDRESS.to_ascii_uppercase()));
+    }
+
+    #[actix_rt::test]
+    async fn malformed_path_parameter_response() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(SharedData {
+                    net: Network::Localhost,
+                    api_version: ApiVersion::V02,
+                    not_found_status: StatusCode::OK,
+                }))
+                .route(
+                    "/transactions/{tx_hash}/data",
+                    web::get().to(|tx_hash: ValidPath<TxHash>| async move {
+                        ApiResult::Ok(tx_hash.into_inner())
+                    }),
+                ),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/transactions/{}/data", HASH.to_ascii_uppercase()))
+            .to_request();
+        let response: Response = test::read_response_json(&app, request).await;
+        assert_eq!(response.result.unwrap(), format!("sync-tx:{}", HASH));
+
+        let request = test::TestRequest::get()
+            .uri("/transactions/0xabcd/data")
+            .to_request();
+        let response: Response = test::read_response_json(&app, request).await;
+        let error: Error = serde_json::from_value(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidPathParameter);
+        assert_eq!(
+            error.data.unwrap(),
+            serde_json::json!({
+                "parameter": "tx_hash",
+                "acceptedFormats": TxHash::ACCEPTED_FORMATS,
+            })
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Tracing span covering the handling of the whole request.
+
+// Built-in uses
+use std::future::Future;
+
+// External uses
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse},
+    Error, HttpMessage,
+};
+use tracing::{Instrument, Span};
+
+// Local uses
+use super::client_ip::ClientIp;
+
+/// Header with the request identifier assigned by the load balancer.
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Identifier of the request recorded in its span, stored in the request extensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestId(pub String);
+
+/// Creates the root span of the request. Neither the query nor the body are recorded,
+/// since the latter may contain signatures.
+fn request_span(req: &ServiceRequest, request_id: &str) -> Span {
+    let client_ip = req
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ip| ip.0.to_string())
+        .unwrap_or_else(|| "unknown".to_owned());
+    tracing::info_span!(
+        "api_request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.path(),
+        client_ip = %client_ip,
+    )
+}
+
+/// Handles the request within its span, meant to be used with `Scope::wrap_fn`.
+pub fn instrument_request<S, B>(
+    req: ServiceRequest,
+    service: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, Error>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+    let request_id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .map(str::to_owned)
+        .unwrap_or_else(|| format!("{:016x}", zksync_crypto::rand::random::<u64>()));
+    let span = request_span(&req, &request_id);
+    req.extensions_mut().insert(RequestId(request_id));
+    service.call(req).instrument(span)
+}
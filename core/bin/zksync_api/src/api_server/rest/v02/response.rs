@@ -3,13 +3,16 @@ use std::collections::HashMap;
 use std::convert::From;
 
 // External uses
-use actix_web::{web::Data, HttpRequest, HttpResponse, Responder};
+use actix_web::{http::header, web::Data, HttpRequest, HttpResponse, Responder};
 use chrono::Utc;
 use qstring::QString;
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
-use zksync_api_types::v02::{Request, Response, ResultStatus};
+use zksync_api_types::v02::{
+    sub_version::{ApiSubVersion, SUB_VERSION_HEADER},
+    Request, Response, ResultStatus, COMPAT_MODE_HEADER, NULL_NOT_FOUND_COMPAT_MODE,
+};
 
 // Local uses
 use super::{error::Error, SharedData};
@@ -28,6 +31,14 @@ impl<R: Serialize> Responder for ApiResult<R> {
         let data = req
             .app_data::<Data<SharedData>>()
             .expect("Wrong app data type");
+        self.into_response(req, data.get_ref())
+    }
+}
+
+impl<R: Serialize> ApiResult<R> {
+    /// Renders the response for the request, for the cases when the shared data
+    /// is not attached to the request yet (e.g. in the middleware of the API scope).
+    pub fn into_response(self, req: &HttpRequest, data: &SharedData) -> HttpResponse {
         let mut args = HashMap::new();
         for (name, value) in req.match_info().iter() {
             args.insert(name.to_string(), value.to_string());
@@ -45,7 +56,23 @@ impl<R: Serialize> Responder for ApiResult<R> {
             timestamp: Utc::now(),
         };
 
+        // Old clients expect the missing resources to be reported as `null`.
+        let null_not_found = req
+            .headers()
+            .get(COMPAT_MODE_HEADER)
+            .map_or(false, |mode| mode == NULL_NOT_FOUND_COMPAT_MODE);
+        let retry_after_secs = match &self {
+            ApiResult::Error(err) => err.retry_after_secs,
+            ApiResult::Ok(_) => None,
+        };
+        let not_found = matches!(&self, ApiResult::Error(err) if err.is_not_found());
         let response = match self {
+            ApiResult::Error(err) if err.is_not_found() && null_not_found => Response {
+                request,
+                status: ResultStatus::Success,
+                result: Some(serde_json::Value::Null),
+                error: None,
+            },
             ApiResult::Ok(res) => Response {
                 request,
                 status: ResultStatus::Success,
@@ -62,12 +89,33 @@ impl<R: Serialize> Responder for ApiResult<R> {
 
         let body = serde_json::to_string(&response).expect("Should be correct serializable");
 
-        HttpResponse::Ok()
+        // Errors are reported with `200 OK`, except for the ones the request can be retried after
+        // and the missing resources, if configured.
+        let mut builder = if let Some(retry_after_secs) = retry_after_secs {
+            let mut builder = HttpResponse::ServiceUnavailable();
+            builder.insert_header((header::RETRY_AFTER, retry_after_secs.to_string()));
+            builder
+        } else if not_found && !null_not_found {
+            HttpResponse::build(data.not_found_status)
+        } else {
+            HttpResponse::Ok()
+        };
+        builder
+            .insert_header((SUB_VERSION_HEADER, sub_version(req).to_string()))
             .content_type("application/json")
             .body(body)
     }
 }
 
+/// Negotiates the sub-version of the response shapes requested in the `X-Api-SubVersion` header.
+pub fn sub_version(req: &HttpRequest) -> ApiSubVersion {
+    ApiSubVersion::negotiate(
+        req.headers()
+            .get(SUB_VERSION_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    )
+}
+
 impl<R: Serialize> From<Error> for ApiResult<R> {
     fn from(err: Error) -> Self {
         Self::Error(err)
@@ -83,6 +131,18 @@ impl<R: Serialize> From<Result<R, Error>> for ApiResult<R> {
     }
 }
 
+/// Converts the lookup of the resource by its identifier into the result,
+/// so the handlers report the missing resources uniformly.
+pub trait OrNotFound<R> {
+    fn or_not_found(self, resource: &'static str, id: impl ToString) -> Result<R, Error>;
+}
+
+impl<R> OrNotFound<R> for Result<Option<R>, Error> {
+    fn or_not_found(self, resource: &'static str, id: impl ToString) -> Result<R, Error> {
+        self?.ok_or_else(|| Error::not_found(resource, id))
+    }
+}
+
 #[macro_export]
 macro_rules! api_try {
     ($e:expr) => {
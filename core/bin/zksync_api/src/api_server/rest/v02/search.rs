@@ -0,0 +1,568 @@
+//! Search part of API implementation.
+//!
+//! The search query is classified by its shape first, and only the entities that may have
+//! the identifier of such a shape are looked up, e.g. a token symbol never hits the database
+//! unless the token cache misses it. The lookups of the shape are done in the order of their cost
+//! and all the matches are returned, since some shapes are ambiguous: a 32-byte hash may
+//! identify a transaction, a batch or an Ethereum transaction of the operator, and a number
+//! may be both a block number and an account id.
+
+// Built-in uses
+use std::str::FromStr;
+use std::time::Instant;
+
+// External uses
+use actix_web::{web, Scope};
+
+// Workspace uses
+use zksync_api_types::v02::{
+    block::BlockStatus,
+    search::{
+        AccountSearchResult, BatchSearchResult, BlockSearchResult, L1OperationSearchResult,
+        SearchQuery, SearchResponse, SearchResult, TokenSearchResult, TxSearchResult,
+    },
+    transaction::Receipt,
+};
+use zksync_storage::{AccessIntent, ReplicatedPool, StorageProcessor};
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, TokenLike, H256};
+
+// Local uses
+use super::{
+    error::{Error, InvalidDataError},
+    l1_operation::l1_operation_type,
+    path_params::{strip_prefix_ignore_case, PathParam},
+    response::ApiResult,
+};
+use crate::utils::token_cache::TokenCache;
+
+/// Token symbols are short, the longer queries are not looked up in the tokens.
+const MAX_TOKEN_SYMBOL_LEN: usize = 16;
+
+/// Shape of the search query, defines the entities it's looked up in.
+#[derive(Debug, Clone, PartialEq)]
+enum QueryShape {
+    /// 32-byte hash, `zksync_only` is set if it has the `sync-tx:` prefix,
+    /// so it can't be a hash of the Ethereum transaction.
+    Hash {
+        hash: TxHash,
+        zksync_only: bool,
+    },
+    Address(Address),
+    /// Block number or account id.
+    Number(u32),
+    TokenSymbol(String),
+}
+
+fn classify(query: &str) -> Option<QueryShape> {
+    let query = query.trim();
+    if let Ok(number) = u32::from_str(query) {
+        return Some(QueryShape::Number(number));
+    }
+    if let Some(hash) = TxHash::parse_path_param(query) {
+        let zksync_only = strip_prefix_ignore_case(query, "sync-tx:").is_some();
+        return Some(QueryShape::Hash { hash, zksync_only });
+    }
+    if let Some(address) = Address::parse_path_param(query) {
+        return Some(QueryShape::Address(address));
+    }
+    let is_symbol = query.len() <= MAX_TOKEN_SYMBOL_LEN
+        && query.starts_with(|c: char| c.is_ascii_alphabetic())
+        && query
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    if is_symbol {
+        return Some(QueryShape::TokenSymbol(query.to_owned()));
+    }
+    None
+}
+
+/// Shared data between `api/v0.2/search` endpoints.
+#[derive(Debug, Clone)]
+struct ApiSearchData {
+    pool: ReplicatedPool,
+    tokens: TokenCache,
+}
+
+impl ApiSearchData {
+    fn new(pool: ReplicatedPool, tokens: TokenCache) -> Self {
+        Self { pool, tokens }
+    }
+
+    async fn search(&self, query: &str) -> Result<SearchResponse, Error> {
+        let shape = classify(query).ok_or(InvalidDataError::InvalidSearchQuery)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+
+        let mut results = Vec::new();
+        match shape {
+            QueryShape::Hash { hash, zksync_only } => {
+                results.extend(Self::transaction(&mut storage, hash).await?);
+                if !zksync_only {
+                    let eth_tx_hash = H256::from_slice(hash.as_ref());
+                    results.extend(Self::l1_operation(&mut storage, eth_tx_hash).await?);
+                }
+                results.extend(Self::batch(&mut storage, hash).await?);
+            }
+            QueryShape::Address(address) => {
+                results.extend(
+                    self.token(&mut storage, TokenLike::Address(address))
+                        .await?,
+                );
+                results.extend(Self::account_by_address(&mut storage, address).await?);
+            }
+            QueryShape::Number(number) => {
+                results.extend(Self::block(&mut storage, BlockNumber(number)).await?);
+                results.extend(Self::account_by_id(&mut storage, AccountId(number)).await?);
+            }
+            QueryShape::TokenSymbol(symbol) => {
+                results.extend(self.token(&mut storage, TokenLike::Symbol(symbol)).await?);
+            }
+        }
+        Ok(SearchResponse { results })
+    }
+
+    async fn transaction(
+        storage: &mut StorageProcessor<'_>,
+        hash: TxHash,
+    ) -> Result<Option<SearchResult>, Error> {
+        let receipt = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(hash.as_ref())
+            .await
+            .map_err(Error::storage)?;
+        let result = receipt.map(|receipt| match receipt {
+            Receipt::L1(receipt) => TxSearchResult {
+                id: hash,
+                priority_op_id: Some(receipt.id),
+                status: receipt.status,
+                block_number: receipt.rollup_block,
+            },
+            Receipt::L2(receipt) => TxSearchResult {
+                id: receipt.tx_hash,
+                priority_op_id: None,
+                status: receipt.status,
+                block_number: receipt.rollup_block,
+            },
+        });
+        Ok(result.map(SearchResult::Transaction))
+    }
+
+    async fn l1_operation(
+        storage: &mut StorageProcessor<'_>,
+        eth_tx_hash: H256,
+    ) -> Result<Option<SearchResult>, Error> {
+        let operation = storage
+            .ethereum_schema()
+            .load_l1_operation(&eth_tx_hash)
+            .await
+            .map_err(Error::storage)?;
+        let result = operation.and_then(|operation| {
+            Some(L1OperationSearchResult {
+                id: eth_tx_hash,
+                op_type: l1_operation_type(&operation.action_type)?,
+                confirmed: operation.confirmed,
+                from_block: BlockNumber(operation.from_block as u32),
+                to_block: BlockNumber(operation.to_block as u32),
+            })
+        });
+        Ok(result.map(SearchResult::L1Operation))
+    }
+
+    async fn batch(
+        storage: &mut StorageProcessor<'_>,
+        batch_hash: TxHash,
+    ) -> Result<Option<SearchResult>, Error> {
+        let batch = storage
+            .chain()
+            .operations_ext_schema()
+            .get_batch_info(batch_hash)
+            .await
+            .map_err(Error::storage)?;
+        let result = batch.map(|batch| BatchSearchResult {
+            id: batch.batch_hash,
+            status: batch.batch_status.last_state,
+            transactions_count: batch.transaction_hashes.len() as u64,
+        });
+        Ok(result.map(SearchResult::Batch))
+    }
+
+    async fn account_by_address(
+        storage: &mut StorageProcessor<'_>,
+        address: Address,
+    ) -> Result<Option<SearchResult>, Error> {
+        let account_id = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await
+            .map_err(Error::storage)?;
+        Ok(account_id.map(|id| SearchResult::Account(AccountSearchResult { id, address })))
+    }
+
+    async fn account_by_id(
+        storage: &mut StorageProcessor<'_>,
+        account_id: AccountId,
+    ) -> Result<Option<SearchResult>, Error> {
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await
+            .map_err(Error::storage)?;
+        Ok(address.map(|address| {
+            SearchResult::Account(AccountSearchResult {
+                id: account_id,
+                address,
+            })
+        }))
+    }
+
+    async fn block(
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> Result<Option<SearchResult>, Error> {
+        let last_committed_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(Error::storage)?;
+        if *block_number == 0 || block_number > last_committed_block {
+            return Ok(None);
+        }
+
+        let finalized = storage
+            .chain()
+            .block_schema()
+            .is_block_finalized(block_number)
+            .await
+            .map_err(Error::storage)?;
+        let status = if finalized {
+            BlockStatus::Finalized
+        } else {
+            BlockStatus::Committed
+        };
+        Ok(Some(SearchResult::Block(BlockSearchResult {
+            id: block_number,
+            status,
+        })))
+    }
+
+    async fn token(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token: TokenLike,
+    ) -> Result<Option<SearchResult>, Error> {
+        let token = self
+            .tokens
+            .get_token(storage, token)
+            .await
+            .map_err(Error::storage)?;
+        Ok(token.map(|token| {
+            SearchResult::Token(TokenSearchResult {
+                id: token.id,
+                symbol: token.symbol,
+                address: token.address,
+            })
+        }))
+    }
+}
+
+// Server implementation
+
+async fn search(
+    data: web::Data<ApiSearchData>,
+    web::Query(query): web::Query<SearchQuery>,
+) -> ApiResult<SearchResponse> {
+    let start = Instant::now();
+    let res = data.search(&query.query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "search");
+    res
+}
+
+pub fn api_scope(pool: ReplicatedPool, tokens: TokenCache) -> Scope {
+    let data = ApiSearchData::new(pool, tokens);
+
+    web::scope("search")
+        .app_data(web::Data::new(data))
+        .route("", web::get().to(search))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        error::ErrorCode,
+        test_utils::{
+            deserialize_response_result, TestServerConfig, TxLifecycle, EXECUTED_BLOCKS_COUNT,
+            VERIFIED_OP_SERIAL_ID,
+        },
+        SharedData,
+    };
+    use actix_web::http::StatusCode;
+    use zksync_api_client::rest::client::Client;
+    use zksync_api_types::v02::{
+        l1_operation::L1OperationType, transaction::TxInBlockStatus, ApiVersion,
+    };
+    use zksync_storage::{chain::operations::OperationsSchema, test_data::dummy_ethereum_tx_hash};
+    use zksync_types::{aggregated_operations::AggregatedActionType, TokenId, ZkSyncTx};
+
+    async fn search(client: &Client, query: impl AsRef<str>) -> anyhow::Result<Vec<SearchResult>> {
+        let response = client.search(query.as_ref()).await?;
+        let response: SearchResponse = deserialize_response_result(response)?;
+        Ok(response.results)
+    }
+
+    #[test]
+    fn query_shapes() {
+        let hash = TxHash::from_str(
+            "sync-tx:8e4b1b2ab6f4d2a7d5df2ec6c7a5c3e3bb35ab5e4ae8a0bd2e4ac0a5d5c4e3f2",
+        )
+        .unwrap();
+        let digits = "8e4b1b2ab6f4d2a7d5df2ec6c7a5c3e3bb35ab5e4ae8a0bd2e4ac0a5d5c4e3f2";
+        let address = Address::from_str("38a2fdc11f526ddd5a607c1f251c065f40fbf2f7").unwrap();
+
+        let cases = vec![
+            (
+                format!("sync-tx:{}", digits),
+                Some(QueryShape::Hash {
+                    hash,
+                    zksync_only: true,
+                }),
+            ),
+            (
+                format!("SYNC-TX:{}", digits.to_uppercase()),
+                Some(QueryShape::Hash {
+                    hash,
+                    zksync_only: true,
+                }),
+            ),
+            (
+                format!("0x{}", digits),
+                Some(QueryShape::Hash {
+                    hash,
+                    zksync_only: false,
+                }),
+            ),
+            (
+                digits.to_owned(),
+                Some(QueryShape::Hash {
+                    hash,
+                    zksync_only: false,
+                }),
+            ),
+            (
+                "0x38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7".to_owned(),
+                Some(QueryShape::Address(address)),
+            ),
+            (
+                " 38a2fdc11f526ddd5a607c1f251c065f40fbf2f7 ".to_owned(),
+                Some(QueryShape::Address(address)),
+            ),
+            ("0".to_owned(), Some(QueryShape::Number(0))),
+            ("4294967295".to_owned(), Some(QueryShape::Number(u32::MAX))),
+            (
+                "PHNX".to_owned(),
+                Some(QueryShape::TokenSymbol("PHNX".into())),
+            ),
+            (
+                "wBTC.e".to_owned(),
+                Some(QueryShape::TokenSymbol("wBTC.e".into())),
+            ),
+            // Too large for both the block number and the account id.
+            ("4294967296".to_owned(), None),
+            ("-1".to_owned(), None),
+            ("".to_owned(), None),
+            ("0x".to_owned(), None),
+            (format!("0x{}", &digits[1..]), None),
+            (format!("sync-tx:{}", &digits[..40]), None),
+            ("1abc".to_owned(), None),
+            ("Name with spaces".to_owned(), None),
+            ("VERYLONGTOKENSYMBOL".to_owned(), None),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(classify(&query), expected, "query: {:?}", query);
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn search_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| api_scope(cfg.replicated_pool(), cfg.token_cache()),
+            Some(shared_data),
+        );
+        // Transaction of the finalized block, in any of the hash formats.
+        let (tx_hash, account_id, address, l1_op_hash, l1_op_priority_hash) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let (tx_hash, tx) = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(1))
+                .await?
+                .into_iter()
+                .filter(|tx| tx.success)
+                .find_map(|tx| {
+                    let op = serde_json::from_value::<ZkSyncTx>(tx.op).ok()?;
+                    Some((TxHash::from_str(&tx.tx_hash).unwrap(), op))
+                })
+                .expect("Test data should contain an executed transaction");
+            let account_id = tx.account_id().unwrap();
+            let address = storage
+                .chain()
+                .account_schema()
+                .account_address_by_id(account_id)
+                .await?
+                .unwrap();
+            let (op_id, _) = OperationsSchema(&mut storage)
+                .get_aggregated_op_that_affects_block(
+                    AggregatedActionType::ExecuteBlocks,
+                    BlockNumber(EXECUTED_BLOCKS_COUNT),
+                )
+                .await?
+                .unwrap();
+            // The Ethereum hash of the fixture priority operation is also the hash of the operator
+            // transaction sent for one of the fixture aggregated operations.
+            let l1_op_priority_hash = dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64);
+            assert!(storage
+                .ethereum_schema()
+                .load_l1_operation(&l1_op_priority_hash)
+                .await?
+                .is_some());
+            (
+                tx_hash,
+                account_id,
+                address,
+                dummy_ethereum_tx_hash(op_id),
+                l1_op_priority_hash,
+            )
+        };
+        let expected_tx = SearchResult::Transaction(TxSearchResult {
+            id: tx_hash,
+            priority_op_id: None,
+            status: TxInBlockStatus::Finalized,
+            block_number: Some(BlockNumber(1)),
+        });
+        let digits = hex::encode(tx_hash.as_ref());
+        for query in [
+            tx_hash.to_string(),
+            format!("0x{}", digits),
+            digits.to_uppercase(),
+        ] {
+            assert_eq!(search(&client, query).await?, vec![expected_tx.clone()]);
+        }
+
+        // Operator transaction of the aggregated operation.
+        assert_eq!(
+            search(&client, format!("{:?}", l1_op_hash)).await?,
+            vec![SearchResult::L1Operation(L1OperationSearchResult {
+                id: l1_op_hash,
+                op_type: L1OperationType::Execute,
+                confirmed: true,
+                from_block: BlockNumber(EXECUTED_BLOCKS_COUNT),
+                to_block: BlockNumber(EXECUTED_BLOCKS_COUNT),
+            })]
+        );
+        // It's not an Ethereum transaction if queried as the zkSync one.
+        let l1_op_sync_hash = TxHash::from_slice(l1_op_hash.as_bytes()).unwrap();
+        assert_eq!(search(&client, l1_op_sync_hash.to_string()).await?, vec![]);
+
+        // Ambiguous hash, both the priority operation and the operator transaction.
+        let results = search(&client, format!("{:?}", l1_op_priority_hash)).await?;
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            &results[0],
+            SearchResult::Transaction(TxSearchResult {
+                priority_op_id: Some(id),
+                ..
+            }) if *id == VERIFIED_OP_SERIAL_ID
+        ));
+        assert!(matches!(&results[1], SearchResult::L1Operation(_)));
+
+        // Queued batch.
+        let txs: Vec<_> = TestServerConfig::gen_zk_txs(1_u64)
+            .txs
+            .into_iter()
+            .take(2)
+            .map(|(tx, _op)| tx)
+            .collect();
+        let batch_hash = TxHash::batch_hash(&txs.iter().map(ZkSyncTx::hash).collect::<Vec<_>>());
+        lifecycle.submit_batch(txs).await?;
+        assert_eq!(
+            search(&client, batch_hash.to_string()).await?,
+            vec![SearchResult::Batch(BatchSearchResult {
+                id: batch_hash,
+                status: TxInBlockStatus::Queued,
+                transactions_count: 2,
+            })]
+        );
+
+        // Account by its id and address.
+        let expected_account = SearchResult::Account(AccountSearchResult {
+            id: account_id,
+            address,
+        });
+        assert!(search(&client, account_id.to_string())
+            .await?
+            .contains(&expected_account));
+        assert_eq!(
+            search(&client, format!("{:?}", address)).await?,
+            vec![expected_account]
+        );
+
+        // Blocks.
+        assert!(search(&client, "1".to_owned())
+            .await?
+            .contains(&SearchResult::Block(BlockSearchResult {
+                id: BlockNumber(1),
+                status: BlockStatus::Finalized,
+            })));
+        assert_eq!(search(&client, "1000000".to_owned()).await?, vec![]);
+
+        // Token by its symbol and address.
+        let expected_token = SearchResult::Token(TokenSearchResult {
+            id: TokenId(1),
+            symbol: "PHNX".to_owned(),
+            address: Address::from_str("38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7").unwrap(),
+        });
+        assert_eq!(
+            search(&client, "PHNX".to_owned()).await?,
+            vec![expected_token.clone()]
+        );
+        assert_eq!(
+            search(
+                &client,
+                "0x38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7".to_owned()
+            )
+            .await?,
+            vec![expected_token]
+        );
+        assert_eq!(search(&client, "UNKNOWN".to_owned()).await?, vec![]);
+
+        // Unrecognized query.
+        let response = client.search("what is zksync").await?;
+        assert_eq!(
+            response.error.map(|error| error["code"].as_u64().unwrap()),
+            Some(ErrorCode::InvalidSearchQuery as u64)
+        );
+
+        server.stop().await;
+        lifecycle.finish().await
+    }
+}
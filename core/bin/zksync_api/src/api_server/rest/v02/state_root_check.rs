@@ -0,0 +1,380 @@
+//! Recomputation of the block state roots for the independent verification.
+//!
+//! The check takes the last account tree snapshot before the block, applies the stored account
+//! updates of the blocks since the snapshot up to the requested one, and compares the resulting
+//! root with the stored one. Only the hashes from the snapshot and the updated accounts are kept
+//! in memory, the rest of the tree is never restored.
+//!
+//! Decoding the snapshot and rehashing the tree are still expensive, so the checks are available
+//! only to the holders of the tokens signed by the `API_ADMIN_SECRET_AUTH` secret, only one check
+//! may run at a time and consecutive checks are throttled. The tree is recomputed on the blocking
+//! thread pool, and the computation is aborted once the client disconnects.
+
+// Built-in uses
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// External uses
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+
+// Workspace uses
+use zksync_api_types::v02::block::StateRootCheck;
+use zksync_config::configs::api::RestApiConfig;
+use zksync_crypto::{
+    merkle_tree::parallel_smt::SparseMerkleTreeSerializableCacheBN256, params::account_tree_depth,
+    Fr,
+};
+use zksync_storage::{AccessIntent, ReplicatedPool, StorageProcessor};
+use zksync_types::{helpers::apply_updates, AccountId, AccountMap, AccountTree, BlockNumber};
+
+// Local uses
+use super::error::{Error, StateRootCheckError};
+use crate::api_server::admin_server::validate_auth_token;
+
+/// Number of accounts inserted into the tree between the cancellation checks.
+const CANCELLATION_CHECK_INTERVAL: usize = 1000;
+
+/// Authorizes the state root checks and limits their frequency.
+#[derive(Debug, Clone)]
+pub struct StateRootChecker {
+    secret_auth: String,
+    interval: Duration,
+    state: Arc<Mutex<CheckerState>>,
+}
+
+#[derive(Debug, Default)]
+struct CheckerState {
+    running: bool,
+    last_start: Option<Instant>,
+}
+
+impl StateRootChecker {
+    pub fn new(config: &RestApiConfig, secret_auth: String) -> Self {
+        Self {
+            secret_auth,
+            interval: config.state_root_check_interval(),
+            state: Arc::default(),
+        }
+    }
+
+    /// Checks the request and recomputes the state root of the block.
+    pub async fn check(
+        &self,
+        pool: &ReplicatedPool,
+        credentials: Option<BearerAuth>,
+        block_number: BlockNumber,
+    ) -> Result<StateRootCheck, Error> {
+        credentials
+            .and_then(|credentials| {
+                validate_auth_token(&self.secret_auth, credentials.token()).ok()
+            })
+            .ok_or(StateRootCheckError::Unauthorized)?;
+        let permit = self.acquire(Instant::now())?;
+
+        let mut storage = pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        check_state_root(&mut storage, block_number, permit).await
+    }
+
+    fn acquire(&self, now: Instant) -> Result<CheckPermit, StateRootCheckError> {
+        let mut state = self.state.lock().unwrap();
+        if state.running {
+            return Err(StateRootCheckError::AlreadyInProgress);
+        }
+        if let Some(last_start) = state.last_start {
+            let elapsed = now.saturating_duration_since(last_start);
+            if elapsed < self.interval {
+                let retry_after = self.interval - elapsed;
+                return Err(StateRootCheckError::Throttled {
+                    retry_after_secs: retry_after.as_secs_f64().ceil() as u64,
+                });
+            }
+        }
+        state.running = true;
+        state.last_start = Some(now);
+
+        Ok(CheckPermit {
+            state: self.state.clone(),
+        })
+    }
+}
+
+/// Slot of the running check, released once the tree computation is finished.
+#[derive(Debug)]
+struct CheckPermit {
+    state: Arc<Mutex<CheckerState>>,
+}
+
+impl Drop for CheckPermit {
+    fn drop(&mut self) {
+        self.state.lock().unwrap().running = false;
+    }
+}
+
+/// Signals the tree computation to stop once the request is dropped.
+#[derive(Debug, Default)]
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+async fn check_state_root(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+    permit: CheckPermit,
+) -> Result<StateRootCheck, Error> {
+    let load_start = Instant::now();
+    let stored_root = storage
+        .chain()
+        .block_schema()
+        .get_block(block_number)
+        .await
+        .map_err(Error::storage)?
+        .ok_or(StateRootCheckError::BlockNotFound(block_number))?
+        .new_root_hash;
+    let (snapshot_block, snapshot) = storage
+        .chain()
+        .tree_cache_schema_bincode()
+        .get_account_tree_cache_before(block_number - 1)
+        .await
+        .map_err(Error::storage)?
+        .ok_or(StateRootCheckError::SnapshotNotFound(block_number))?;
+
+    // Accounts changed since the snapshot, the rest of the tree is the same as in the snapshot.
+    let mut updated_accounts = storage
+        .chain()
+        .state_schema()
+        .load_state_diff(snapshot_block, Some(block_number))
+        .await
+        .map_err(Error::storage)?
+        .map(|(_, updates)| updates.into_iter().map(|(id, _)| id).collect::<Vec<_>>())
+        .unwrap_or_default();
+    updated_accounts.sort_unstable();
+    updated_accounts.dedup();
+
+    // The updates of the checked block are applied explicitly, so that the stored updates
+    // are used even if the state of the block has already been verified.
+    let mut accounts = storage
+        .chain()
+        .state_schema()
+        .load_accounts_state(block_number - 1, &updated_accounts)
+        .await
+        .map_err(Error::storage)?;
+    let block_updates = storage
+        .chain()
+        .state_schema()
+        .load_state_diff_for_block(block_number)
+        .await
+        .map_err(Error::storage)?;
+    apply_updates(&mut accounts, block_updates);
+    let load_time = load_start.elapsed();
+
+    let compute_start = Instant::now();
+    let cancel = CancelOnDrop::default();
+    let cancelled = cancel.0.clone();
+    let updated_accounts_count = updated_accounts.len() as u32;
+    let recomputed_root = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        recompute_root(&snapshot, accounts, updated_accounts, &cancelled)
+    })
+    .await
+    .map_err(|err| Error::from(anyhow::Error::from(err)))?
+    .ok_or_else(|| Error::from(anyhow::anyhow!("State root check was cancelled")))?;
+    let compute_time = compute_start.elapsed();
+
+    let matches = recomputed_root == stored_root;
+    if !matches {
+        vlog::warn!(
+            "Recomputed state root of block {} doesn't match the stored one",
+            block_number
+        );
+    }
+    metrics::histogram!("api.state_root_check.load", load_time);
+    metrics::histogram!("api.state_root_check.compute", compute_time);
+
+    Ok(StateRootCheck {
+        block_number,
+        snapshot_block,
+        updated_accounts: updated_accounts_count,
+        stored_root,
+        recomputed_root,
+        matches,
+        load_time_ms: load_time.as_millis() as u64,
+        compute_time_ms: compute_time.as_millis() as u64,
+    })
+}
+
+/// Inserts the updated accounts into the tree restored from the snapshot and computes its root.
+/// Accounts absent in `accounts` are removed from the tree.
+/// Returns `None` if the computation was cancelled.
+fn recompute_root(
+    snapshot: &[u8],
+    mut accounts: AccountMap,
+    updated_accounts: Vec<AccountId>,
+    cancelled: &AtomicBool,
+) -> Option<Fr> {
+    let mut tree = AccountTree::new(account_tree_depth());
+    tree.set_internals(SparseMerkleTreeSerializableCacheBN256::decode_bincode(
+        snapshot,
+    ));
+
+    for chunk in updated_accounts.chunks(CANCELLATION_CHECK_INTERVAL) {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+        for id in chunk {
+            match accounts.remove(id) {
+                Some(account) => tree.insert(**id, account),
+                None => {
+                    tree.remove(**id);
+                }
+            }
+        }
+    }
+
+    if cancelled.load(Ordering::Relaxed) {
+        return None;
+    }
+    Some(tree.root_hash())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::TestServerConfig;
+    use zksync_storage::test_data::gen_sample_block;
+    use zksync_types::{AccountUpdate, TokenId};
+
+    #[test]
+    fn throttling() {
+        let checker = StateRootChecker {
+            secret_auth: String::new(),
+            interval: Duration::from_secs(60),
+            state: Arc::default(),
+        };
+
+        let start = Instant::now();
+        let permit = checker.acquire(start).unwrap();
+        // Only one check may run at a time.
+        assert!(matches!(
+            checker.acquire(start + Duration::from_secs(120)),
+            Err(StateRootCheckError::AlreadyInProgress)
+        ));
+
+        drop(permit);
+        assert!(matches!(
+            checker.acquire(start + Duration::from_millis(30_500)),
+            Err(StateRootCheckError::Throttled {
+                retry_after_secs: 30
+            })
+        ));
+        checker.acquire(start + Duration::from_secs(60)).unwrap();
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn corrupted_update_is_detected() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let checker = StateRootChecker::new(&cfg.config.api.rest, String::new());
+
+        // All the changes are made in the transaction that is never committed.
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut storage = storage.start_transaction().await?;
+        let snapshot_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let (_, state) = storage
+            .chain()
+            .state_schema()
+            .load_committed_state(Some(snapshot_block))
+            .await?;
+        let mut tree = AccountTree::new(account_tree_depth());
+        for (id, account) in state.iter() {
+            tree.insert(**id, account.clone());
+        }
+        tree.root_hash();
+        storage
+            .chain()
+            .tree_cache_schema_bincode()
+            .store_account_tree_cache(snapshot_block, tree.get_internals().encode_bincode())
+            .await?;
+
+        // The next block changes the balance of a single account.
+        let block_number = snapshot_block + 1;
+        let (&account_id, account) = state.iter().next().unwrap();
+        let old_balance = account.get_balance(TokenId(0));
+        let update = |new_balance: u64| {
+            vec![(
+                account_id,
+                AccountUpdate::UpdateBalance {
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                    balance_update: (TokenId(0), old_balance.clone(), new_balance.into()),
+                },
+            )]
+        };
+        let mut updated_account = account.clone();
+        updated_account.set_balance(TokenId(0), 1_000u64.into());
+        updated_account.nonce = account.nonce + 1;
+        tree.insert(*account_id, updated_account);
+
+        let mut block = gen_sample_block(block_number, 10, Vec::new());
+        block.new_root_hash = tree.root_hash();
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(block)
+            .await?;
+        storage
+            .chain()
+            .state_schema()
+            .commit_state_update(block_number, &update(1_000), 0)
+            .await?;
+
+        let permit = checker.acquire(Instant::now()).unwrap();
+        let check = check_state_root(&mut storage, block_number, permit).await;
+        let check = check.map_err(|err| anyhow::anyhow!(err.message))?;
+        assert!(check.matches);
+        assert_eq!(check.snapshot_block, snapshot_block);
+        assert_eq!(check.updated_accounts, 1);
+        assert_eq!(check.recomputed_root, check.stored_root);
+
+        // Corrupt the stored update.
+        storage
+            .chain()
+            .state_schema()
+            .remove_account_balance_updates(snapshot_block)
+            .await?;
+        storage
+            .chain()
+            .state_schema()
+            .commit_state_update(block_number, &update(1_001), 0)
+            .await?;
+
+        // Checks are throttled, so a new checker is used.
+        let checker = StateRootChecker::new(&cfg.config.api.rest, String::new());
+        let permit = checker.acquire(Instant::now()).unwrap();
+        let check = check_state_root(&mut storage, block_number, permit).await;
+        let check = check.map_err(|err| anyhow::anyhow!(err.message))?;
+        assert!(!check.matches);
+        assert_ne!(check.recomputed_root, check.stored_root);
+
+        Ok(())
+    }
+}
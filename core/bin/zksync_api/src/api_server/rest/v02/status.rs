@@ -2,26 +2,81 @@
 
 // Built-in uses
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External uses
-use actix_web::{web, Scope};
+use actix_web::{web, HttpResponse, Scope};
 
 // Workspace uses
-use zksync_api_types::v02::status::NetworkStatus;
+use zksync_api_types::{
+    v02::status::{ApiHealth, NetworkStatus},
+    PendingBlockStatus,
+};
 
 // Local uses
-use super::response::ApiResult;
-use crate::api_server::rest::network_status::SharedNetworkStatus;
+use super::{error::Error, response::ApiResult};
+use crate::{
+    api_server::rest::{network_status::SharedNetworkStatus, warm_up::ApiReadiness},
+    api_try,
+    utils::{
+        pool_supervisor::PoolSupervisor, shared_lru_cache::SharedLruCache,
+        sign_verifier_breaker::SignVerifierBreaker,
+    },
+};
+
+/// Period during which the cached pending block stats are served.
+const PENDING_BLOCK_CACHE_TTL: Duration = Duration::from_secs(2);
 
 /// Shared data between `api/v0.2/networkStatus` endpoints.
 #[derive(Debug, Clone)]
 pub struct ApiStatusData {
     status: SharedNetworkStatus,
+    readiness: ApiReadiness,
+    sign_verifier: SignVerifierBreaker,
+    pool_supervisor: PoolSupervisor,
+    pending_block_cache: SharedLruCache<(), (Instant, PendingBlockStatus)>,
 }
 
 impl ApiStatusData {
-    pub fn new(status: SharedNetworkStatus) -> Self {
-        Self { status }
+    pub fn new(
+        status: SharedNetworkStatus,
+        readiness: ApiReadiness,
+        sign_verifier: SignVerifierBreaker,
+        pool_supervisor: PoolSupervisor,
+    ) -> Self {
+        Self {
+            status,
+            readiness,
+            sign_verifier,
+            pool_supervisor,
+            pending_block_cache: SharedLruCache::new(1),
+        }
+    }
+
+    /// Queries the stats of the pending block from the core. The stats change with every miniblock,
+    /// so the response is cached for a short period only to not flood the core with the requests.
+    async fn pending_block_status(&self) -> Result<PendingBlockStatus, Error> {
+        if let Some((cached_at, status)) = self.pending_block_cache.get(&()) {
+            if cached_at.elapsed() < PENDING_BLOCK_CACHE_TTL {
+                return Ok(status);
+            }
+        }
+
+        let status = self
+            .status
+            .core_client()
+            .pending_block_status()
+            .await
+            .map_err(Error::core_api)?;
+        self.pending_block_cache
+            .insert((), (Instant::now(), status.clone()));
+        Ok(status)
+    }
+
+    fn health(&self) -> ApiHealth {
+        ApiHealth {
+            alive: true,
+            ready: self.readiness.is_ready(),
+        }
     }
 }
 
@@ -37,17 +92,50 @@ async fn get_status(data: web::Data<ApiStatusData>) -> ApiResult<NetworkStatus>
         total_transactions: status.total_transactions,
         mempool_size: status.mempool_size,
         core_status: status.core_status,
+        eth_watch_status: status.eth_watch_status,
+        sign_verifier_status: Some(data.sign_verifier.status()),
+        storage_pools: data.pool_supervisor.status(),
     };
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_status");
     Ok(network_status).into()
 }
 
-pub fn api_scope(shared_status: SharedNetworkStatus) -> Scope {
-    let data = ApiStatusData::new(shared_status);
+async fn get_pending_block(data: web::Data<ApiStatusData>) -> ApiResult<PendingBlockStatus> {
+    let start = Instant::now();
+    let status = api_try!(data.pending_block_status().await);
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_pending_block");
+    Ok(status).into()
+}
+
+// Probes respond with plain HTTP status codes, so they don't use `ApiResult`.
+
+async fn liveness(data: web::Data<ApiStatusData>) -> HttpResponse {
+    HttpResponse::Ok().json(data.health())
+}
+
+async fn readiness(data: web::Data<ApiStatusData>) -> HttpResponse {
+    let health = data.health();
+    if health.ready {
+        HttpResponse::Ok().json(health)
+    } else {
+        HttpResponse::ServiceUnavailable().json(health)
+    }
+}
+
+pub fn api_scope(
+    shared_status: SharedNetworkStatus,
+    readiness: ApiReadiness,
+    sign_verifier: SignVerifierBreaker,
+    pool_supervisor: PoolSupervisor,
+) -> Scope {
+    let data = ApiStatusData::new(shared_status, readiness, sign_verifier, pool_supervisor);
 
     web::scope("networkStatus")
         .app_data(web::Data::new(data))
         .route("", web::get().to(get_status))
+        .route("pendingBlock", web::get().to(get_pending_block))
+        .route("liveness", web::get().to(liveness))
+        .route("readiness", web::get().to(self::readiness))
 }
 
 #[cfg(test)]
@@ -57,8 +145,43 @@ mod tests {
         test_utils::{deserialize_response_result, TestServerConfig},
         SharedData,
     };
-    use zksync_api_types::v02::ApiVersion;
-    use zksync_types::SequentialTxId;
+    use crate::utils::sign_verifier_breaker::BreakerThresholds;
+    use crate::{
+        api_server::rest::warm_up::ApiWarmUp, utils::block_details_cache::BlockDetailsCache,
+    };
+    use actix_web::{http::StatusCode, App};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use zksync_api_client::rest::client::ClientError;
+    use zksync_api_types::{
+        error_code::ErrorCode,
+        v02::{status::SignVerifierStatus, ApiVersion},
+    };
+    use zksync_types::{BlockNumber, SequentialTxId, TokenId, TokenLike};
+
+    /// Starts the mock of the core private API serving the pending block stats,
+    /// the counter is the amount of the served requests.
+    fn start_core_mock(status: PendingBlockStatus) -> (actix_test::TestServer, Arc<AtomicUsize>) {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = actix_test::start({
+            let requests = requests.clone();
+            move || {
+                let requests = requests.clone();
+                let status = status.clone();
+                App::new().route(
+                    "/pending_block_status",
+                    web::get().to(move || {
+                        requests.fetch_add(1, Ordering::SeqCst);
+                        let status = status.clone();
+                        async move { HttpResponse::Ok().json(status) }
+                    }),
+                )
+            }
+        });
+        (server, requests)
+    }
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -72,12 +195,26 @@ mod tests {
         let shared_data = SharedData {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
         };
         let mut status = SharedNetworkStatus::new("0.0.0.0".to_string());
+        let sign_verifier =
+            SignVerifierBreaker::new(BreakerThresholds::from_config(&cfg.config.api.common));
+        let pool_supervisor =
+            PoolSupervisor::new(&cfg.config.api.common).with_pool("main", cfg.pool.clone());
         let (client, server) = cfg.start_server(
             {
                 let status = status.clone();
-                move |_| api_scope(status.clone())
+                let sign_verifier = sign_verifier.clone();
+                let pool_supervisor = pool_supervisor.clone();
+                move |_| {
+                    api_scope(
+                        status.clone(),
+                        ApiReadiness::new(),
+                        sign_verifier.clone(),
+                        pool_supervisor.clone(),
+                    )
+                }
             },
             Some(shared_data),
         );
@@ -106,15 +243,176 @@ mod tests {
                 total_transactions,
                 mempool_size,
                 core_status: None,
+                eth_watch_status: None,
+                sign_verifier_status: Some(SignVerifierStatus {
+                    breaker_open: false,
+                    queue_depth: 0,
+                    average_latency_ms: None,
+                }),
+                storage_pools: vec![],
             }
         };
 
         status.update(&cfg.pool, SequentialTxId(0)).await.unwrap();
         let response = client.status().await?;
-        let status: NetworkStatus = deserialize_response_result(response)?;
+        let mut status: NetworkStatus = deserialize_response_result(response)?;
 
+        // Pool sizes depend on the other tests sharing the pool, so only the pool names are compared.
+        let storage_pools = std::mem::take(&mut status.storage_pools);
         assert_eq!(expected_status, status);
+        assert_eq!(storage_pools.len(), 1);
+        assert_eq!(storage_pools[0].name, "main");
+        assert_eq!(storage_pools[0].recreations, 0);
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn probes() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
 
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let status = SharedNetworkStatus::new("http://127.0.0.1:1".to_string());
+        let readiness = ApiReadiness::new();
+        let sign_verifier =
+            SignVerifierBreaker::new(BreakerThresholds::from_config(&cfg.config.api.common));
+        let (client, server) = cfg.start_server(
+            {
+                let status = status.clone();
+                let readiness = readiness.clone();
+                let sign_verifier = sign_verifier.clone();
+                let pool_supervisor = PoolSupervisor::new(&cfg.config.api.common);
+                move |_| {
+                    api_scope(
+                        status.clone(),
+                        readiness.clone(),
+                        sign_verifier.clone(),
+                        pool_supervisor.clone(),
+                    )
+                }
+            },
+            Some(shared_data),
+        );
+
+        // The server is alive, but not ready until the warm-up is completed.
+        let health = client.liveness().await?;
+        assert!(health.alive);
+        assert!(!health.ready);
+        match client.readiness().await {
+            Err(ClientError::BadRequest { http_code, .. }) => {
+                assert_eq!(http_code, reqwest::StatusCode::SERVICE_UNAVAILABLE)
+            }
+            other => panic!("Server must not be ready before the warm-up: {:?}", other),
+        }
+
+        let tokens = cfg.token_cache();
+        ApiWarmUp::new(
+            cfg.pool.clone(),
+            tokens.clone(),
+            BlockDetailsCache::new(cfg.config.api.common.caches_size),
+            status.core_client().clone(),
+            readiness.clone(),
+        )
+        .run()
+        .await;
+
+        let health = client.readiness().await?;
+        assert!(health.alive);
+        assert!(health.ready);
+        // Token cache is filled during the warm-up.
+        assert!(tokens
+            .try_get_token_from_cache(TokenLike::Id(TokenId(0)))
+            .await
+            .is_some());
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn pending_block() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let sign_verifier =
+            SignVerifierBreaker::new(BreakerThresholds::from_config(&cfg.config.api.common));
+        let pool_supervisor = PoolSupervisor::new(&cfg.config.api.common);
+        let start_server = |status: SharedNetworkStatus| {
+            let sign_verifier = sign_verifier.clone();
+            let pool_supervisor = pool_supervisor.clone();
+            cfg.start_server(
+                move |_| {
+                    api_scope(
+                        status.clone(),
+                        ApiReadiness::new(),
+                        sign_verifier.clone(),
+                        pool_supervisor.clone(),
+                    )
+                },
+                Some(shared_data),
+            )
+        };
+
+        let expected_status = PendingBlockStatus {
+            block_number: BlockNumber(10),
+            chunks_used: 12,
+            chunks_capacity: 100,
+            txs_count: 2,
+            failed_txs_count: 1,
+            fast_processing: false,
+            opened_at: 1_600_000_000,
+            open_for_secs: 3,
+            expected_seal_at: Some(1_600_000_010),
+            updated_at: 1_600_000_003,
+        };
+        let (core, core_requests) = start_core_mock(expected_status.clone());
+        let core_address = core.url("").trim_end_matches('/').to_owned();
+        let (client, server) = start_server(SharedNetworkStatus::new(core_address));
+
+        let response = client.pending_block_status().await?;
+        let status: PendingBlockStatus = deserialize_response_result(response)?;
+        assert_eq!(status, expected_status);
+        assert_eq!(core_requests.load(Ordering::SeqCst), 1);
+
+        // The cached stats are served without querying the core.
+        let response = client.pending_block_status().await?;
+        let status: PendingBlockStatus = deserialize_response_result(response)?;
+        assert_eq!(status, expected_status);
+        assert_eq!(core_requests.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(PENDING_BLOCK_CACHE_TTL).await;
+        let response = client.pending_block_status().await?;
+        let status: PendingBlockStatus = deserialize_response_result(response)?;
+        assert_eq!(status, expected_status);
+        assert_eq!(core_requests.load(Ordering::SeqCst), 2);
+        server.stop().await;
+
+        // The core is not available.
+        core.stop().await;
+        let (client, server) =
+            start_server(SharedNetworkStatus::new("http://127.0.0.1:1".to_string()));
+        let response = client.pending_block_status().await?;
+        assert_eq!(
+            response.error.map(|error| error["code"].as_u64().unwrap()),
+            Some(ErrorCode::CoreApiError as u64)
+        );
         server.stop().await;
         Ok(())
     }
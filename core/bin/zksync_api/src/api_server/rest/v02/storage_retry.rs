@@ -0,0 +1,210 @@
+//! Retries of the read-only storage queries failed because of the transient database errors.
+//!
+//! Serialization failures and dropped connections would otherwise be reported to the clients
+//! as internal errors, although an immediate retry almost always succeeds. Such queries are
+//! retried a few times with a small jittered backoff, as long as the request is within its
+//! deadline, so a slow database doesn't make the requests even slower.
+//!
+//! Only the read-only queries may be retried: a write may be applied before the connection
+//! is dropped, so the writes are never passed to `ReadRetry::read`.
+
+// Built-in uses
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+// External uses
+use tokio::time;
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+use zksync_crypto::rand::{thread_rng, Rng};
+use zksync_storage::{is_transient_error, QueryResult};
+
+/// Retry policy of the storage reads shared by the handlers.
+#[derive(Debug, Clone, Copy)]
+pub struct StorageRetry {
+    max_retries: u32,
+    backoff: Duration,
+    deadline: Duration,
+}
+
+impl StorageRetry {
+    pub fn new(config: &RestApiConfig) -> Self {
+        Self {
+            max_retries: config.storage_read_retries,
+            backoff: config.storage_retry_backoff(),
+            deadline: config.storage_retry_deadline(),
+        }
+    }
+
+    /// Starts the retries of the request, the deadline is counted from now.
+    pub fn begin(&self) -> ReadRetry {
+        ReadRetry {
+            policy: *self,
+            deadline: Instant::now() + self.deadline,
+        }
+    }
+}
+
+/// Retries of the storage reads of a single request.
+#[derive(Debug, Clone, Copy)]
+pub struct ReadRetry {
+    policy: StorageRetry,
+    deadline: Instant,
+}
+
+impl ReadRetry {
+    /// Runs the read-only `query`, retrying it on the transient errors. The `query` acquires
+    /// the connection itself, since the connection may be the one that failed.
+    pub async fn read<T, F, Fut>(&self, query_name: &'static str, mut query: F) -> QueryResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = QueryResult<T>>,
+    {
+        let mut retries = 0;
+        loop {
+            let err = match query().await {
+                Ok(value) => return Ok(value),
+                Err(err) if is_transient_error(&err) => err,
+                Err(err) => return Err(err),
+            };
+
+            let delay = self
+                .policy
+                .backoff
+                .mul_f64(thread_rng().gen_range(0.5, 1.5));
+            if retries >= self.policy.max_retries || Instant::now() + delay > self.deadline {
+                metrics::increment_counter!("api.storage_retry.exhausted", "query" => query_name);
+                return Err(err);
+            }
+            retries += 1;
+            metrics::increment_counter!("api.storage_retry.retries", "query" => query_name);
+            vlog::debug!(
+                "Retrying the `{}` query after the error: {}",
+                query_name,
+                err
+            );
+            time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{error::Error, response::ApiResult, SharedData};
+    use actix_web::{web, App};
+    use reqwest::StatusCode;
+    use std::{
+        io,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Arc,
+        },
+    };
+    use zksync_api_types::v02::{ApiVersion, Response};
+    use zksync_types::network::Network;
+
+    fn retry(max_retries: u32, deadline: Duration) -> StorageRetry {
+        StorageRetry {
+            max_retries,
+            backoff: Duration::from_millis(10),
+            deadline,
+        }
+    }
+
+    /// Simulated query failing with the given error the given number of times first.
+    fn failing_query(
+        attempts: Arc<AtomicU32>,
+        failures: u32,
+        error: fn() -> anyhow::Error,
+    ) -> impl FnMut() -> futures::future::Ready<QueryResult<u32>> {
+        move || {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            futures::future::ready(if attempt < failures {
+                Err(error())
+            } else {
+                Ok(42)
+            })
+        }
+    }
+
+    fn connection_reset() -> anyhow::Error {
+        io::Error::new(io::ErrorKind::ConnectionReset, "connection reset").into()
+    }
+
+    fn invalid_query() -> anyhow::Error {
+        anyhow::anyhow!("column does not exist")
+    }
+
+    async fn query(
+        retry: web::Data<StorageRetry>,
+        attempts: web::Data<AtomicU32>,
+    ) -> ApiResult<u32> {
+        let attempts = attempts.into_inner();
+        retry
+            .begin()
+            .read("test", failing_query(attempts, 1, connection_reset))
+            .await
+            .map_err(Error::storage)
+            .into()
+    }
+
+    #[actix_rt::test]
+    async fn transient_error_is_retried() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let server = {
+            let attempts = attempts.clone();
+            actix_test::start(move || {
+                App::new()
+                    .app_data(web::Data::new(SharedData {
+                        net: Network::Localhost,
+                        api_version: ApiVersion::V02,
+                        not_found_status: StatusCode::OK,
+                    }))
+                    .app_data(web::Data::new(retry(2, Duration::from_secs(1))))
+                    .app_data(web::Data::from(attempts.clone()))
+                    .route("/query", web::get().to(query))
+            })
+        };
+
+        let response = reqwest::get(server.url("/query")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response: Response = response.json().await.unwrap();
+        assert_eq!(response.result, Some(serde_json::json!(42)));
+        // The first attempt failed, the only retry succeeded.
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[actix_rt::test]
+    async fn retries_are_bounded() {
+        // Other errors are not retried.
+        let attempts = Arc::new(AtomicU32::new(0));
+        let result = retry(2, Duration::from_secs(1))
+            .begin()
+            .read("test", failing_query(attempts.clone(), 1, invalid_query))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        // The query is retried at most `max_retries` times.
+        let attempts = Arc::new(AtomicU32::new(0));
+        let result = retry(2, Duration::from_secs(1))
+            .begin()
+            .read("test", failing_query(attempts.clone(), 5, connection_reset))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // The query isn't retried once the request is past its deadline.
+        let attempts = Arc::new(AtomicU32::new(0));
+        let result = retry(5, Duration::from_millis(1))
+            .begin()
+            .read("test", failing_query(attempts.clone(), 5, connection_reset))
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -3,6 +3,10 @@
 // Built-in uses
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 // External uses
 use actix_web::{web, App, Scope};
@@ -10,6 +14,7 @@ use anyhow::Error;
 use bigdecimal::{BigDecimal, Zero};
 use chrono::Utc;
 use futures::{channel::mpsc, StreamExt};
+use jsonwebtoken::{EncodingKey, Header};
 use num::{rational::Ratio, BigUint};
 use once_cell::sync::Lazy;
 use serde::de::DeserializeOwned;
@@ -22,37 +27,41 @@ use zksync_config::ZkSyncConfig;
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_storage::{
     chain::operations::records::NewExecutedPriorityOperation,
-    chain::operations::OperationsSchema,
+    ethereum::records::ETHStats,
     prover::ProverSchema,
     test_data::{
         dummy_ethereum_tx_hash, gen_acc_random_updates, gen_sample_block,
         gen_unique_aggregated_operation_with_txs, generate_nft, get_sample_aggregated_proof,
         get_sample_single_proof, BLOCK_SIZE_CHUNKS,
     },
-    ConnectionPool,
+    tokens::records::TokenChangeSource,
+    ConnectionPool, ReplicatedPool, StorageProcessor,
 };
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     helpers::{apply_updates, closest_packable_fee_amount, closest_packable_token_amount},
+    mempool::SignedTxVariant,
     operations::{ChangePubKeyOp, TransferToNewOp},
     prover::ProverJobType,
-    tx::ChangePubKeyType,
+    tx::{ChangePubKeyType, TxHash},
     AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Deposit, DepositOp,
     ExecutedOperations, ExecutedPriorityOp, ExecutedTx, FullExit, FullExitOp, MintNFTOp, Nonce,
-    PriorityOp, Token, TokenId, TokenKind, TokenLike, TokenPrice, Transfer, TransferOp, ZkSyncOp,
-    ZkSyncTx, H256, NFT,
+    PriorityOp, SignedZkSyncTx, Token, TokenId, TokenKind, TokenLike, TokenPrice, Transfer,
+    TransferOp, ZkSyncOp, ZkSyncTx, H256, NFT,
 };
 use zksync_utils::{big_decimal_to_ratio, scaled_u64_to_ratio, UnsignedRatioSerializeAsDecimal};
 
 // Local uses
+use crate::api_server::admin_server::PayloadAuthToken;
 use crate::fee_ticker::{
     tests::TestToken,
     ticker_info::BlocksInFutureAggregatedOperations,
-    validator::{cache::TokenInMemoryCache, FeeTokenValidator},
+    validator::{cache::TokenInMemoryCache, FeeTokenValidator, PriceMaxAge},
     {FeeTicker, FeeTickerInfo, GasOperationsCost, PriceError, TickerConfig},
 };
 use crate::signature_checker::{VerifiedTx, VerifySignatureRequest};
+use crate::utils::token_cache::TokenCache;
 use std::any::Any;
 
 /// Serial ID of the verified priority operation.
@@ -65,6 +74,8 @@ pub const COMMITTED_BLOCKS_COUNT: u32 = 8;
 pub const VERIFIED_BLOCKS_COUNT: u32 = 5;
 /// Number of executed blocks.
 pub const EXECUTED_BLOCKS_COUNT: u32 = 3;
+/// Secret the admin tokens are signed with in the tests.
+pub const SECRET_AUTH: &str = "sample";
 
 #[derive(Debug, Clone)]
 pub struct TestServerConfig {
@@ -129,6 +140,19 @@ impl TestServerConfig {
         self.start_server_with_scope(String::from("/api/v0.2"), scope_factory, shared_data)
     }
 
+    /// Creates a pool that routes all the queries to the test database.
+    pub fn replicated_pool(&self) -> ReplicatedPool {
+        ReplicatedPool::primary_only(self.pool.clone())
+    }
+
+    /// Creates an empty token cache backed by the test database.
+    pub fn token_cache(&self) -> TokenCache {
+        TokenCache::new(
+            self.pool.clone(),
+            self.config.api.token_config.invalidate_token_cache_period(),
+        )
+    }
+
     /// Creates several transactions and the corresponding executed operations.
     pub fn gen_zk_txs(fee: u64) -> TestTransactions {
         Self::gen_zk_txs_for_account(AccountId(0xdead), ZkSyncAccount::rand().address, fee)
@@ -373,24 +397,30 @@ impl TestServerConfig {
         // Insert PHNX token
         storage
             .tokens_schema()
-            .store_or_update_token(Token::new(
-                TokenId(1),
-                Address::from_str("38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7").unwrap(),
-                "PHNX",
-                18,
-                TokenKind::ERC20,
-            ))
+            .store_or_update_token(
+                Token::new(
+                    TokenId(1),
+                    Address::from_str("38A2fDc11f526Ddd5a607C1F251C065f40fBF2f7").unwrap(),
+                    "PHNX",
+                    18,
+                    TokenKind::ERC20,
+                ),
+                &TokenChangeSource::TokenHandler,
+            )
             .await?;
         // Insert Golem token with old symbol (from rinkeby).
         storage
             .tokens_schema()
-            .store_or_update_token(Token::new(
-                TokenId(16),
-                Address::from_str("d94e3dc39d4cad1dad634e7eb585a57a19dc7efe").unwrap(),
-                "GNT",
-                18,
-                TokenKind::ERC20,
-            ))
+            .store_or_update_token(
+                Token::new(
+                    TokenId(16),
+                    Address::from_str("d94e3dc39d4cad1dad634e7eb585a57a19dc7efe").unwrap(),
+                    "GNT",
+                    18,
+                    TokenKind::ERC20,
+                ),
+                &TokenChangeSource::TokenHandler,
+            )
             .await?;
 
         let mut accounts = AccountMap::default();
@@ -473,43 +503,14 @@ impl TestServerConfig {
 
             // Store & confirm the operation in the ethereum schema, as it's used for obtaining
             // commit/verify/execute hashes.
-            let aggregated_operation = gen_unique_aggregated_operation_with_txs(
+            confirm_aggregated_operation(
+                &mut storage,
                 block_number,
                 AggregatedActionType::CommitBlocks,
-                BLOCK_SIZE_CHUNKS,
                 txs.clone(),
-            );
-            OperationsSchema(&mut storage)
-                .store_aggregated_action(aggregated_operation)
-                .await?;
-            let (id, op) = OperationsSchema(&mut storage)
-                .get_aggregated_op_that_affects_block(
-                    AggregatedActionType::CommitBlocks,
-                    block_number,
-                )
-                .await?
-                .unwrap();
-
-            // Store the Ethereum transaction.
-            let eth_tx_hash = dummy_ethereum_tx_hash(id);
-            let response = storage
-                .ethereum_schema()
-                .save_new_eth_tx(
-                    AggregatedActionType::CommitBlocks,
-                    Some((id, op)),
-                    100,
-                    100u32.into(),
-                    Default::default(),
-                )
-                .await?;
-            storage
-                .ethereum_schema()
-                .add_hash_entry(response.id, &eth_tx_hash)
-                .await?;
-            storage
-                .ethereum_schema()
-                .confirm_eth_tx(&eth_tx_hash)
-                .await?;
+                true,
+            )
+            .await?;
 
             // Add verification for the block if required.
             if *block_number <= VERIFIED_BLOCKS_COUNT {
@@ -561,82 +562,25 @@ impl TestServerConfig {
                     )
                     .await?;
 
-                let aggregated_operation = gen_unique_aggregated_operation_with_txs(
+                confirm_aggregated_operation(
+                    &mut storage,
                     block_number,
                     AggregatedActionType::PublishProofBlocksOnchain,
-                    BLOCK_SIZE_CHUNKS,
                     txs.clone(),
-                );
-                OperationsSchema(&mut storage)
-                    .store_aggregated_action(aggregated_operation)
-                    .await?;
-                let (id, op) = OperationsSchema(&mut storage)
-                    .get_aggregated_op_that_affects_block(
-                        AggregatedActionType::PublishProofBlocksOnchain,
-                        block_number,
-                    )
-                    .await?
-                    .unwrap();
-
-                let response = storage
-                    .ethereum_schema()
-                    .save_new_eth_tx(
-                        AggregatedActionType::PublishProofBlocksOnchain,
-                        Some((id, op)),
-                        100,
-                        100u32.into(),
-                        Default::default(),
-                    )
-                    .await?;
-                let eth_tx_hash = dummy_ethereum_tx_hash(id);
-                storage
-                    .ethereum_schema()
-                    .add_hash_entry(response.id, &eth_tx_hash)
-                    .await?;
-                storage
-                    .ethereum_schema()
-                    .confirm_eth_tx(&eth_tx_hash)
-                    .await?;
+                    true,
+                )
+                .await?;
             }
 
             if *block_number <= EXECUTED_BLOCKS_COUNT {
-                let aggregated_operation = gen_unique_aggregated_operation_with_txs(
+                confirm_aggregated_operation(
+                    &mut storage,
                     block_number,
                     AggregatedActionType::ExecuteBlocks,
-                    BLOCK_SIZE_CHUNKS,
                     txs.clone(),
-                );
-                OperationsSchema(&mut storage)
-                    .store_aggregated_action(aggregated_operation)
-                    .await?;
-                let (id, op) = OperationsSchema(&mut storage)
-                    .get_aggregated_op_that_affects_block(
-                        AggregatedActionType::ExecuteBlocks,
-                        block_number,
-                    )
-                    .await?
-                    .unwrap();
-
-                // Store the Ethereum transaction.
-                let eth_tx_hash = dummy_ethereum_tx_hash(id);
-                let response = storage
-                    .ethereum_schema()
-                    .save_new_eth_tx(
-                        AggregatedActionType::ExecuteBlocks,
-                        Some((id, op)),
-                        100,
-                        100u32.into(),
-                        Default::default(),
-                    )
-                    .await?;
-                storage
-                    .ethereum_schema()
-                    .add_hash_entry(response.id, &eth_tx_hash)
-                    .await?;
-                storage
-                    .ethereum_schema()
-                    .confirm_eth_tx(&eth_tx_hash)
-                    .await?;
+                    true,
+                )
+                .await?;
                 storage
                     .chain()
                     .state_schema()
@@ -729,6 +673,425 @@ impl TestServerConfig {
     }
 }
 
+/// Stores the aggregated operation of the given type for the block and confirms
+/// the Ethereum transaction sent for it, returns the hash of the transaction.
+///
+/// Unless `linked`, only the aggregated operation is confirmed, so it's not linked
+/// with the final Ethereum transaction.
+async fn confirm_aggregated_operation(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+    action_type: AggregatedActionType,
+    txs: Vec<ExecutedOperations>,
+    linked: bool,
+) -> anyhow::Result<H256> {
+    let aggregated_operation =
+        gen_unique_aggregated_operation_with_txs(block_number, action_type, BLOCK_SIZE_CHUNKS, txs);
+    storage
+        .chain()
+        .operations_schema()
+        .store_aggregated_action(aggregated_operation)
+        .await?;
+    let (id, op) = storage
+        .chain()
+        .operations_schema()
+        .get_aggregated_op_that_affects_block(action_type, block_number)
+        .await?
+        .unwrap();
+
+    // Store the Ethereum transaction.
+    let eth_tx_hash = dummy_ethereum_tx_hash(id);
+    let response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            action_type,
+            Some((id, op)),
+            100,
+            100u32.into(),
+            Default::default(),
+        )
+        .await?;
+    storage
+        .ethereum_schema()
+        .add_hash_entry(response.id, &eth_tx_hash)
+        .await?;
+    if linked {
+        storage
+            .ethereum_schema()
+            .confirm_eth_tx(&eth_tx_hash)
+            .await?;
+    } else {
+        storage
+            .chain()
+            .operations_schema()
+            .confirm_aggregated_operations(block_number, block_number, action_type)
+            .await?;
+    }
+    Ok(eth_tx_hash)
+}
+
+/// Transaction to be included into the block by the `TxLifecycle`.
+#[derive(Debug, Clone)]
+pub struct BlockTx {
+    pub tx: ZkSyncTx,
+    /// Reason of the execution failure, `None` for the successfully executed transaction.
+    pub fail_reason: Option<String>,
+    /// Identifier of the batch the transaction was submitted within.
+    pub batch_id: Option<i64>,
+}
+
+impl BlockTx {
+    pub fn success(tx: ZkSyncTx) -> Self {
+        Self {
+            tx,
+            fail_reason: None,
+            batch_id: None,
+        }
+    }
+
+    pub fn failure(tx: ZkSyncTx, fail_reason: &str) -> Self {
+        Self {
+            tx,
+            fail_reason: Some(fail_reason.to_owned()),
+            batch_id: None,
+        }
+    }
+
+    pub fn in_batch(mut self, batch_id: i64) -> Self {
+        self.batch_id = Some(batch_id);
+        self
+    }
+}
+
+/// Drives transactions through their lifecycle in the test database, one call per transition:
+/// mempool insertion, inclusion into the committed block, verification and execution of the
+/// block. The latter also completes the withdrawals, since they are made within `executeBlocks`.
+///
+/// Blocks are created on top of the ones stored by `TestServerConfig::fill_database`.
+/// Ethereum operations must be stored in order, so the block is verified and executed as if
+/// the preceding blocks were as well, and the endpoints relying on the last executed block
+/// treat the preceding blocks as finalized until the lifecycle is finished.
+///
+/// `finish` reverts all the changes made to the blocks and the mempool. Leftovers of a failed
+/// test are reverted on creation of the next lifecycle.
+#[derive(Debug)]
+pub struct TxLifecycle {
+    pool: ConnectionPool,
+    /// Transactions that were either submitted or included into the blocks.
+    txs: HashMap<TxHash, SignedZkSyncTx>,
+    /// Executed operations of the created blocks, required for their aggregated operations.
+    blocks: HashMap<BlockNumber, Vec<ExecutedOperations>>,
+    /// Blocks returned to the mempool by `revert`.
+    reverted_blocks: Vec<BlockNumber>,
+}
+
+impl TxLifecycle {
+    /// Last block of the test data, the lifecycle blocks follow it.
+    pub const BASE_BLOCK: BlockNumber = BlockNumber(COMMITTED_BLOCKS_COUNT);
+
+    /// Creates a lifecycle on top of the test data, which is expected to be stored already.
+    pub async fn new(pool: ConnectionPool) -> anyhow::Result<Self> {
+        let mut storage = pool.access_storage().await?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let reverted_blocks = (*Self::BASE_BLOCK + 1..=*last_block)
+            .map(BlockNumber)
+            .collect();
+        Self::reset(&mut storage, reverted_blocks).await?;
+
+        Ok(Self {
+            pool,
+            txs: HashMap::new(),
+            blocks: HashMap::new(),
+            reverted_blocks: Vec::new(),
+        })
+    }
+
+    /// Inserts the transaction into the mempool.
+    pub async fn submit(&mut self, tx: ZkSyncTx) -> anyhow::Result<TxHash> {
+        let tx = SignedZkSyncTx::from(tx);
+        let tx_hash = tx.hash();
+        let mut storage = self.pool.access_storage().await?;
+        storage.chain().mempool_schema().insert_tx(&tx).await?;
+
+        self.txs.insert(tx_hash, tx);
+        Ok(tx_hash)
+    }
+
+    /// Inserts the batch of transactions into the mempool, returns the batch ID.
+    pub async fn submit_batch(&mut self, txs: Vec<ZkSyncTx>) -> anyhow::Result<i64> {
+        let txs: Vec<_> = txs.into_iter().map(SignedZkSyncTx::from).collect();
+        let mut storage = self.pool.access_storage().await?;
+        let batch_id = storage
+            .chain()
+            .mempool_schema()
+            .insert_batch(&txs, Vec::new())
+            .await?;
+
+        self.txs.extend(txs.into_iter().map(|tx| (tx.hash(), tx)));
+        Ok(batch_id)
+    }
+
+    /// Evicts the submitted transaction from the mempool with the provided reason.
+    pub async fn evict(&mut self, tx_hash: TxHash, reason: &str) -> anyhow::Result<()> {
+        let tx = self
+            .txs
+            .get(&tx_hash)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} was not submitted", tx_hash))?;
+        let mut storage = self.pool.access_storage().await?;
+        storage
+            .chain()
+            .mempool_schema()
+            .evict_txs(&[SignedTxVariant::Tx(tx)], reason)
+            .await?;
+        Ok(())
+    }
+
+    /// Includes the transactions into the next block and commits it. Included transactions
+    /// are removed from the mempool along with the rest of their batches.
+    pub async fn include(&mut self, txs: Vec<BlockTx>) -> anyhow::Result<BlockNumber> {
+        let mut storage = self.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        let block_number = transaction
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?
+            + 1;
+
+        let mut block_index = 0;
+        let mut tx_hashes = Vec::with_capacity(txs.len());
+        let mut operations = Vec::with_capacity(txs.len());
+        for BlockTx {
+            tx,
+            fail_reason,
+            batch_id,
+        } in txs
+        {
+            let signed_tx = SignedZkSyncTx::from(tx);
+            let success = fail_reason.is_none();
+            // Only the successful transactions occupy the place in the block.
+            let index = if success {
+                block_index += 1;
+                Some(block_index - 1)
+            } else {
+                None
+            };
+            tx_hashes.push(signed_tx.hash());
+            self.txs.insert(signed_tx.hash(), signed_tx.clone());
+
+            let executed_tx = ExecutedTx {
+                signed_tx,
+                success,
+                op: None,
+                fail_reason,
+                block_index: index,
+                created_at: Utc::now(),
+                batch_id,
+            };
+            operations.push(ExecutedOperations::Tx(Box::new(executed_tx)));
+        }
+
+        transaction
+            .chain()
+            .mempool_schema()
+            .remove_txs(&tx_hashes)
+            .await?;
+        transaction
+            .chain()
+            .block_schema()
+            .save_full_block(gen_sample_block(
+                block_number,
+                BLOCK_SIZE_CHUNKS,
+                operations.clone(),
+            ))
+            .await?;
+        confirm_aggregated_operation(
+            &mut transaction,
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            operations.clone(),
+            true,
+        )
+        .await?;
+        transaction.commit().await?;
+
+        self.blocks.insert(block_number, operations);
+        Ok(block_number)
+    }
+
+    /// Confirms the proof of the committed block on Ethereum.
+    pub async fn verify(&mut self, block_number: BlockNumber) -> anyhow::Result<()> {
+        self.confirm(
+            block_number,
+            AggregatedActionType::PublishProofBlocksOnchain,
+            true,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Executes the verified block on Ethereum, which finalizes its transactions
+    /// and completes its withdrawals.
+    pub async fn execute(&mut self, block_number: BlockNumber) -> anyhow::Result<()> {
+        self.confirm(block_number, AggregatedActionType::ExecuteBlocks, true)
+            .await?;
+        Ok(())
+    }
+
+    /// Executes the verified block without linking its withdrawals with the Ethereum
+    /// transaction that completed them, as if the final hash of the execute operation was lost.
+    /// Returns the hash of the transaction.
+    pub async fn execute_unlinked(&mut self, block_number: BlockNumber) -> anyhow::Result<H256> {
+        self.confirm(block_number, AggregatedActionType::ExecuteBlocks, false)
+            .await
+    }
+
+    /// Reverts the blocks following the provided one, their successful transactions
+    /// are returned to the mempool.
+    pub async fn revert(&mut self, last_block: BlockNumber) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            last_block >= Self::BASE_BLOCK,
+            "Blocks of the test data can't be reverted"
+        );
+        let mut storage = self.pool.access_storage().await?;
+        let last_saved_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let mut transaction = storage.start_transaction().await?;
+        Self::revert_blocks(&mut transaction, last_block).await?;
+        transaction.commit().await?;
+
+        self.reverted_blocks
+            .extend((*last_block + 1..=*last_saved_block).map(BlockNumber));
+        self.blocks
+            .retain(|&block_number, _| block_number <= last_block);
+        Ok(())
+    }
+
+    /// Reverts all the lifecycle blocks and removes the known transactions from the mempool.
+    pub async fn finish(self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage().await?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let mut reverted_blocks = self.reverted_blocks;
+        reverted_blocks.extend((*Self::BASE_BLOCK + 1..=*last_block).map(BlockNumber));
+        Self::reset(&mut storage, reverted_blocks).await?;
+
+        let tx_hashes: Vec<_> = self.txs.keys().copied().collect();
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_txs(&tx_hashes)
+            .await?;
+        Ok(())
+    }
+
+    async fn confirm(
+        &mut self,
+        block_number: BlockNumber,
+        action_type: AggregatedActionType,
+        linked: bool,
+    ) -> anyhow::Result<H256> {
+        let operations = self
+            .blocks
+            .get(&block_number)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Block {} is not included", block_number))?;
+
+        let mut storage = self.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        // Pretend that the preceding blocks are processed, otherwise the operation
+        // can't be stored.
+        let mut stats = transaction.ethereum_schema().load_stats().await?;
+        let previous_block = i64::from(*block_number - 1);
+        match action_type {
+            AggregatedActionType::PublishProofBlocksOnchain => {
+                stats.last_verified_block = previous_block;
+            }
+            AggregatedActionType::ExecuteBlocks => {
+                stats.last_executed_block = previous_block;
+            }
+            _ => unreachable!("Blocks are committed on inclusion"),
+        }
+        transaction.ethereum_schema().store_stats(&stats).await?;
+        let eth_tx_hash = confirm_aggregated_operation(
+            &mut transaction,
+            block_number,
+            action_type,
+            operations,
+            linked,
+        )
+        .await?;
+        transaction.commit().await?;
+        Ok(eth_tx_hash)
+    }
+
+    async fn revert_blocks(
+        storage: &mut StorageProcessor<'_>,
+        last_block: BlockNumber,
+    ) -> anyhow::Result<()> {
+        storage
+            .chain()
+            .mempool_schema()
+            .return_executed_txs_to_mempool(last_block)
+            .await?;
+        storage
+            .chain()
+            .block_schema()
+            .remove_blocks(last_block)
+            .await?;
+        storage
+            .chain()
+            .operations_schema()
+            .remove_aggregate_operations_and_bindings(last_block)
+            .await?;
+
+        let mut stats = storage.ethereum_schema().load_stats().await?;
+        let last_block = i64::from(*last_block);
+        stats.last_committed_block = last_block;
+        stats.last_verified_block = stats.last_verified_block.min(last_block);
+        stats.last_executed_block = stats.last_executed_block.min(last_block);
+        storage.ethereum_schema().store_stats(&stats).await?;
+        Ok(())
+    }
+
+    /// Reverts the database to the test data.
+    async fn reset(
+        storage: &mut StorageProcessor<'_>,
+        reverted_blocks: Vec<BlockNumber>,
+    ) -> anyhow::Result<()> {
+        let mut transaction = storage.start_transaction().await?;
+        Self::revert_blocks(&mut transaction, Self::BASE_BLOCK).await?;
+        for block_number in reverted_blocks {
+            transaction
+                .chain()
+                .mempool_schema()
+                .remove_reverted_block(block_number)
+                .await?;
+        }
+        transaction
+            .ethereum_schema()
+            .store_stats(&ETHStats {
+                last_committed_block: COMMITTED_BLOCKS_COUNT.into(),
+                last_verified_block: VERIFIED_BLOCKS_COUNT.into(),
+                last_executed_block: EXECUTED_BLOCKS_COUNT.into(),
+            })
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
 /// Creates dummy deposit priority operation.
 pub fn dummy_deposit_op(
     address: Address,
@@ -814,19 +1177,63 @@ pub fn deserialize_response_result<T: DeserializeOwned>(response: Response) -> a
     }
 }
 
+/// Checks that the response reports the requested resource as missing.
+pub fn assert_not_found(response: Response, resource: &str) -> anyhow::Result<()> {
+    let error = match response.error {
+        Some(error) if response.result.is_none() => error,
+        _ => anyhow::bail!(
+            "Response should report the missing {}: {:?}",
+            resource,
+            response
+        ),
+    };
+    let error: super::error::Error = serde_json::from_value(error)?;
+    anyhow::ensure!(error.is_not_found(), "Unexpected error: {:?}", error);
+    anyhow::ensure!(
+        error.data.as_ref().map(|data| &data["resource"]) == Some(&resource.into()),
+        "Unexpected missing resource: {:?}",
+        error
+    );
+    Ok(())
+}
+
+/// Returns the admin token signed with [`SECRET_AUTH`], valid for an hour.
+pub fn auth_token(subject: &str) -> String {
+    let claims = PayloadAuthToken {
+        sub: subject.to_owned(),
+        exp: (Utc::now() + chrono::Duration::hours(1)).timestamp() as usize,
+    };
+    jsonwebtoken::encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(SECRET_AUTH.as_ref()),
+    )
+    .unwrap()
+}
+
 pub fn dummy_sign_verifier() -> mpsc::Sender<VerifySignatureRequest> {
-    let (sender, mut receiver) = mpsc::channel::<VerifySignatureRequest>(10);
+    counting_sign_verifier().0
+}
 
-    actix_rt::spawn(async move {
-        while let Some(item) = receiver.next().await {
-            let verified = VerifiedTx::unverified(item.data.get_tx_variant());
-            item.response
-                .send(Ok(verified))
-                .expect("Unable to send response");
+/// Same as [`dummy_sign_verifier`], but also returns the number of the verification requests received.
+pub fn counting_sign_verifier() -> (mpsc::Sender<VerifySignatureRequest>, Arc<AtomicUsize>) {
+    let (sender, mut receiver) = mpsc::channel::<VerifySignatureRequest>(10);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    actix_rt::spawn({
+        let calls = calls.clone();
+        async move {
+            while let Some(item) = receiver.next().await {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let verified = VerifiedTx::unverified(item.data.get_tx_variant());
+                item.response
+                    .send(Ok(verified))
+                    .expect("Unable to send response");
+            }
         }
     });
 
-    sender
+    (sender, calls)
 }
 
 #[derive(Debug, Clone)]
@@ -923,7 +1330,12 @@ pub fn dummy_fee_ticker(
         in_memory_cache.unwrap_or_default(),
         chrono::Duration::seconds(100),
         BigDecimal::from(100),
+        BigDecimal::from(300),
         Default::default(),
+        PriceMaxAge {
+            highly_liquid: chrono::Duration::hours(1),
+            liquid: chrono::Duration::minutes(20),
+        },
     );
 
     FeeTicker::new(
@@ -932,3 +1344,75 @@ pub fn dummy_fee_ticker(
         validator,
     )
 }
+
+/// Span captured by `SpansCapture`.
+#[derive(Debug, Clone)]
+pub struct CapturedSpan {
+    id: u64,
+    /// Names of the span ancestors starting from the root, and the name of the span itself.
+    pub path: Vec<&'static str>,
+    pub fields: HashMap<String, String>,
+}
+
+impl tracing::field::Visit for CapturedSpan {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.fields
+            .insert(field.name().to_owned(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.fields
+            .insert(field.name().to_owned(), format!("{:?}", value));
+    }
+}
+
+/// Tracing layer that stores all the created spans.
+#[derive(Debug, Clone, Default)]
+pub struct SpansCapture(std::sync::Arc<std::sync::Mutex<Vec<CapturedSpan>>>);
+
+impl SpansCapture {
+    pub fn spans(&self) -> Vec<CapturedSpan> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for SpansCapture
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn new_span(
+        &self,
+        attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let mut path = Vec::new();
+        let mut current = ctx.span(id);
+        while let Some(span) = current {
+            path.push(span.name());
+            current = span.parent();
+        }
+        path.reverse();
+
+        let mut span = CapturedSpan {
+            id: id.into_u64(),
+            path,
+            fields: HashMap::new(),
+        };
+        attrs.record(&mut span);
+        self.0.lock().unwrap().push(span);
+    }
+
+    fn on_record(
+        &self,
+        id: &tracing::span::Id,
+        values: &tracing::span::Record<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let mut spans = self.0.lock().unwrap();
+        // Ids of the closed spans are reused, so the latest span with the id is the recorded one.
+        if let Some(span) = spans.iter_mut().rev().find(|span| span.id == id.into_u64()) {
+            values.record(span);
+        }
+    }
+}
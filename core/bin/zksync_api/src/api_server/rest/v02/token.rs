@@ -1,6 +1,7 @@
 //! Tokens part of API implementation.
 
 // Built-in uses
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::time::Instant;
 
@@ -11,43 +12,96 @@ use actix_web::{
 };
 use bigdecimal::{BigDecimal, Zero};
 use num::{rational::Ratio, BigUint, FromPrimitive};
+use serde::Serialize;
 
 // Workspace uses
 use zksync_api_types::v02::{
-    pagination::{parse_query, ApiEither, Paginated, PaginationQuery},
-    token::{ApiNFT, ApiToken, TokenPrice},
+    pagination::{parse_query, ApiEither, Paginated, PaginationDirection, PaginationQuery},
+    token::{
+        ApiNFT, ApiToken, TokenActivity, TokenActivityBucket, TokenActivityQuery, TokenChange,
+        TokenChangesQuery, TokenHolder, TokenHolders, TokenHoldersQuery, TokenKindFilter,
+        TokenListingStage, TokenListingStatus, TokenPrice, TokenPriceInfo, TokenPrices,
+        TokenPricesQuery, TokensQuery, MAX_TOKEN_ACTIVITY_DAYS, MAX_TOKEN_CHANGES_LIMIT,
+        MAX_TOKEN_PRICES_LIMIT,
+    },
 };
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
-use zksync_storage::{ConnectionPool, StorageProcessor};
-use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{tx::TxHash, AccountId, Token, TokenId, TokenLike};
+use zksync_storage::{
+    tokens::records::TokensFilter, AccessIntent, ReplicatedPool, StorageProcessor,
+};
+use zksync_types::{tx::TxHash, AccountId, Address, Token, TokenId, TokenKind, TokenLike};
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 
 // Local uses
 use super::{
     error::{Error, InvalidDataError},
     paginate_trait::Paginate,
-    response::ApiResult,
+    path_params::ValidPath,
+    response::{ApiResult, OrNotFound},
 };
 use crate::{
     api_try,
     fee_ticker::{FeeTicker, PriceError, TokenPriceRequestType},
+    utils::token_cache::TokenCache,
 };
 
+/// Parses the currency to show the prices in. Returns `None` for USD,
+/// otherwise the id of the token used as a currency.
+fn parse_currency(currency: &str) -> Result<Option<TokenId>, Error> {
+    if let Ok(token_id) = u32::from_str(currency) {
+        return Ok(Some(TokenId(token_id)));
+    }
+    match currency {
+        "usd" => Ok(None),
+        "eth" => Ok(Some(TokenId(0))),
+        _ => Err(Error::from(InvalidDataError::InvalidCurrency)),
+    }
+}
+
+/// Returns the latest listing stage reached by the token.
+/// Stages are passed one after another, so every stage implies the previous ones.
+fn listing_stage(
+    event_processed: bool,
+    token_stored: bool,
+    price_available: bool,
+    fee_enabled: bool,
+) -> TokenListingStage {
+    if token_stored && price_available && fee_enabled {
+        TokenListingStage::FeeEnabled
+    } else if token_stored && price_available {
+        TokenListingStage::PriceAvailable
+    } else if token_stored {
+        TokenListingStage::TokenIdAssigned
+    } else if event_processed {
+        TokenListingStage::GovernanceTxConfirmed
+    } else {
+        TokenListingStage::NotStarted
+    }
+}
+
+/// Page of the tokens listing, `token_id` is the id the page starts from.
+#[derive(Debug, Serialize)]
+pub struct TokensRequest {
+    pub token_id: ApiEither<TokenId>,
+    pub filter: TokensFilter,
+}
+
 /// Shared data between `api/v0.2/tokens` endpoints.
 #[derive(Clone)]
 struct ApiTokenData {
     min_market_volume: Ratio<BigUint>,
     fee_ticker: FeeTicker,
-    tokens: TokenDBCache,
-    pool: ConnectionPool,
+    tokens: TokenCache,
+    pool: ReplicatedPool,
+    holders_limit: u32,
 }
 
 impl ApiTokenData {
     fn new(
         config: &ZkSyncConfig,
-        pool: ConnectionPool,
-        tokens: TokenDBCache,
+        pool: ReplicatedPool,
+        tokens: TokenCache,
         fee_ticker: FeeTicker,
     ) -> Self {
         Self {
@@ -58,6 +112,7 @@ impl ApiTokenData {
             pool,
             tokens,
             fee_ticker,
+            holders_limit: config.api.rest.token_holders_limit,
         }
     }
 }
@@ -76,82 +131,313 @@ impl ApiTokenData {
         Ok(!result.is_empty())
     }
 
-    async fn token_page(
-        &self,
-        query: PaginationQuery<ApiEither<TokenId>>,
-    ) -> Result<Paginated<ApiToken, TokenId>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        let paginated_tokens: Result<Paginated<Token, TokenId>, Error> =
-            storage.paginate_checked(&query).await;
-        match paginated_tokens {
-            Ok(paginated_tokens) => {
-                let tokens_to_check: Vec<TokenId> =
-                    paginated_tokens.list.iter().map(|token| token.id).collect();
-                let tokens_enabled_for_fees = storage
-                    .tokens_schema()
-                    .filter_tokens_by_market_volume(tokens_to_check, &self.min_market_volume)
-                    .await
-                    .map_err(Error::storage)?;
-                let list = paginated_tokens
-                    .list
-                    .into_iter()
-                    .map(|token| {
-                        let eligibility = tokens_enabled_for_fees.contains(&token.id);
-                        ApiToken::from_token_and_eligibility(token, eligibility)
-                    })
-                    .collect();
-                Ok(Paginated::new(
-                    list,
-                    paginated_tokens.pagination.from,
-                    paginated_tokens.pagination.limit,
-                    paginated_tokens.pagination.direction,
-                    paginated_tokens.pagination.count,
-                ))
-            }
-            Err(err) => Err(err),
-        }
+    async fn token_page(&self, query: TokensQuery) -> Result<Paginated<ApiToken, TokenId>, Error> {
+        let kind = match query.kind {
+            TokenKindFilter::Erc20 => TokenKind::ERC20,
+            TokenKindFilter::Nft => TokenKind::NFT,
+        };
+        let filter = TokensFilter {
+            kind,
+            min_market_volume: if query.enabled_for_fees {
+                Some(self.min_market_volume.clone())
+            } else {
+                None
+            },
+            symbol_prefix: query.symbol.filter(|symbol| !symbol.is_empty()),
+        };
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let paginated_tokens = if query.all {
+            // Unpaginated listing is kept for the backward compatibility, the usage is tracked
+            // to make sure it can be removed.
+            metrics::increment_counter!("api.v02.tokens.unpaginated");
+            let tokens = storage
+                .tokens_schema()
+                .load_filtered_tokens_asc(TokenId(0), None, &filter)
+                .await
+                .map_err(Error::storage)?;
+            let count = tokens.len() as u32;
+            Paginated::new(tokens, TokenId(0), count, PaginationDirection::Newer, count)
+        } else {
+            let query = match (query.from, query.limit, query.direction) {
+                (Some(from), Some(limit), Some(direction)) => parse_query(PaginationQuery {
+                    from,
+                    limit,
+                    direction,
+                })?,
+                _ => return Err(InvalidDataError::MissingPaginationParams.into()),
+            };
+            let query = PaginationQuery {
+                from: TokensRequest {
+                    token_id: query.from,
+                    filter,
+                },
+                limit: query.limit,
+                direction: query.direction,
+            };
+            storage.paginate_checked(&query).await?
+        };
+
+        let tokens_to_check: Vec<TokenId> =
+            paginated_tokens.list.iter().map(|token| token.id).collect();
+        let mut aliases = storage
+            .tokens_schema()
+            .load_token_aliases(&tokens_to_check)
+            .await
+            .map_err(Error::storage)?;
+        let tokens_enabled_for_fees = storage
+            .tokens_schema()
+            .filter_tokens_by_market_volume(tokens_to_check, &self.min_market_volume)
+            .await
+            .map_err(Error::storage)?;
+        let list = paginated_tokens
+            .list
+            .into_iter()
+            .map(|token| {
+                let eligibility = tokens_enabled_for_fees.contains(&token.id);
+                let aliases = aliases.remove(&token.id).unwrap_or_default();
+                ApiToken::from_token_and_eligibility(token, eligibility).with_aliases(aliases)
+            })
+            .collect();
+        Ok(Paginated::new(
+            list,
+            paginated_tokens.pagination.from,
+            paginated_tokens.pagination.limit,
+            paginated_tokens.pagination.direction,
+            paginated_tokens.pagination.count,
+        ))
     }
 
-    async fn token(&self, token_like: TokenLike) -> Result<Token, Error> {
+    async fn find_token(&self, token_like: TokenLike) -> Result<Option<Token>, Error> {
         // Try to find the token in the cache first.
         if let Some(token) = self
             .tokens
             .try_get_token_from_cache(token_like.clone())
             .await
         {
-            return Ok(token);
+            return Ok(Some(token));
         }
 
         // Establish db connection and repeat the query, so the token is loaded
         // from the db.
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
 
-        let token = self
-            .tokens
+        self.tokens
             .get_token(&mut storage, token_like)
             .await
-            .map_err(Error::storage)?;
-        if let Some(token) = token {
-            Ok(token)
-        } else {
-            Err(Error::from(PriceError::token_not_found(
-                "Token not found in storage",
-            )))
-        }
+            .map_err(Error::storage)
     }
 
-    async fn api_token(&self, token_like: TokenLike) -> Result<ApiToken, Error> {
-        let token = self.token(token_like).await?;
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+    async fn token(&self, token_like: TokenLike) -> Result<Token, Error> {
+        self.find_token(token_like)
+            .await?
+            .ok_or_else(|| Error::from(PriceError::token_not_found("Token not found in storage")))
+    }
+
+    async fn api_token(&self, token_like: TokenLike) -> Result<Option<ApiToken>, Error> {
+        let token = match self.find_token(token_like).await? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
         let enabled_for_fees = self
             .is_token_enabled_for_fees(&mut storage, token.id)
             .await?;
-        Ok(ApiToken::from_token_and_eligibility(
-            token,
-            enabled_for_fees,
+        let aliases = storage
+            .tokens_schema()
+            .load_token_aliases(&[token.id])
+            .await
+            .map_err(Error::storage)?
+            .remove(&token.id)
+            .unwrap_or_default();
+        Ok(Some(
+            ApiToken::from_token_and_eligibility(token, enabled_for_fees).with_aliases(aliases),
         ))
     }
 
+    /// Loads the largest holders of the token from the last recalculation.
+    async fn token_holders(
+        &self,
+        token_like: TokenLike,
+        query: TokenHoldersQuery,
+    ) -> Result<TokenHolders, Error> {
+        let limit = query.limit.unwrap_or(self.holders_limit);
+        if limit == 0 || limit > self.holders_limit {
+            return Err(InvalidDataError::InvalidTokenHoldersLimit(self.holders_limit).into());
+        }
+        let token = self.token(token_like).await?;
+        if *token.id >= MIN_NFT_TOKEN_ID {
+            return Err(InvalidDataError::NftHoldersNotTracked.into());
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let (holders, refreshed_at) = storage
+            .tokens_schema()
+            .load_top_holders(token.id, limit)
+            .await
+            .map_err(Error::storage)?;
+        Ok(TokenHolders {
+            token_id: token.id,
+            holders: holders.into_iter().map(TokenHolder::from).collect(),
+            refreshed_at,
+        })
+    }
+
+    /// Loads the daily activity of the token rolled up from the finalized blocks.
+    /// The volumes are converted to USD using the price of the token stored during the day.
+    async fn token_activity(
+        &self,
+        token_like: TokenLike,
+        query: TokenActivityQuery,
+    ) -> Result<TokenActivity, Error> {
+        if query.from > query.to || (query.to - query.from).num_days() >= MAX_TOKEN_ACTIVITY_DAYS {
+            return Err(InvalidDataError::InvalidTokenActivityRange.into());
+        }
+        let token = self.token(token_like).await?;
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let last_block = storage
+            .chain()
+            .stats_schema()
+            .last_token_activity_block()
+            .await
+            .map_err(Error::storage)?;
+        let buckets = storage
+            .chain()
+            .stats_schema()
+            .token_activity(token.id, query.from, query.to)
+            .await
+            .map_err(Error::storage)?;
+
+        let to_amount = |volume: &BigDecimal| -> Result<BigUint, Error> {
+            big_decimal_to_ratio(volume)
+                .map(|ratio| ratio.to_integer())
+                .map_err(Error::storage)
+        };
+        let to_usd = |volume: &BigDecimal, usd_price: &Option<BigDecimal>| {
+            usd_price.as_ref().map(|price| {
+                let (amount, scale) = volume.as_bigint_and_exponent();
+                BigDecimal::new(amount, scale + i64::from(token.decimals)) * price
+            })
+        };
+        let buckets = buckets
+            .into_iter()
+            .map(|bucket| {
+                Ok(TokenActivityBucket {
+                    day: bucket.day,
+                    transfers: bucket.transfers as u64,
+                    transfer_volume: to_amount(&bucket.transfer_volume)?,
+                    transfer_volume_usd: to_usd(&bucket.transfer_volume, &bucket.usd_price),
+                    unique_senders: bucket.unique_senders as u64,
+                    withdrawals: bucket.withdrawals as u64,
+                    withdrawal_volume: to_amount(&bucket.withdrawal_volume)?,
+                    withdrawal_volume_usd: to_usd(&bucket.withdrawal_volume, &bucket.usd_price),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(TokenActivity {
+            token_id: token.id,
+            resolution: query.resolution,
+            last_block,
+            buckets,
+        })
+    }
+
+    /// Loads the token metadata changes made after the given one, larger limits are capped.
+    async fn token_changes(&self, query: TokenChangesQuery) -> Result<Vec<TokenChange>, Error> {
+        let limit = query
+            .limit
+            .unwrap_or(MAX_TOKEN_CHANGES_LIMIT)
+            .min(MAX_TOKEN_CHANGES_LIMIT);
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        storage
+            .tokens_schema()
+            .load_token_changes(query.since_id, limit)
+            .await
+            .map_err(Error::storage)
+    }
+
+    async fn token_listing_status(&self, address: Address) -> Result<TokenListingStatus, Error> {
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let mut tokens_schema = storage.tokens_schema();
+        let listing = tokens_schema
+            .get_token_listing(address)
+            .await
+            .map_err(Error::storage)?;
+        // Tokens added before the listings were tracked have no listing record.
+        let token = tokens_schema
+            .get_token(TokenLike::Address(address))
+            .await
+            .map_err(Error::storage)?;
+
+        let (price, market_volume) = if let Some(token) = &token {
+            let price = tokens_schema
+                .get_historical_ticker_price(token.id)
+                .await
+                .map_err(Error::storage)?
+                .filter(|price| !price.usd_price.is_zero());
+            let market_volume = tokens_schema
+                .get_token_market_volume(token.id)
+                .await
+                .map_err(Error::storage)?
+                .filter(|volume| volume.market_volume >= self.min_market_volume);
+            (price, market_volume)
+        } else {
+            (None, None)
+        };
+
+        let stage = listing_stage(
+            listing.is_some(),
+            token.is_some(),
+            price.is_some(),
+            market_volume.is_some(),
+        );
+        Ok(TokenListingStatus {
+            address,
+            stage,
+            token_id: token
+                .as_ref()
+                .map(|token| token.id)
+                .or_else(|| listing.as_ref().map(|listing| listing.token_id)),
+            symbol: token.map(|token| token.symbol),
+            eth_block_number: listing.as_ref().map(|listing| listing.eth_block_number),
+            governance_tx_confirmed_at: listing.as_ref().map(|listing| listing.event_processed_at),
+            token_id_assigned_at: listing.as_ref().and_then(|listing| listing.token_stored_at),
+            price_updated_at: price.map(|price| price.last_updated),
+            fee_enabled_at: market_volume.map(|volume| volume.last_updated),
+            store_error: listing.and_then(|listing| listing.store_error),
+        })
+    }
+
     async fn token_price_usd(&self, token: TokenLike) -> Result<BigDecimal, Error> {
         self.fee_ticker
             .get_token_price(token, TokenPriceRequestType::USDForOneToken)
@@ -164,8 +450,8 @@ impl ApiTokenData {
         first_token: TokenLike,
         currency: &str,
     ) -> Result<BigDecimal, Error> {
-        if let Ok(second_token_id) = u32::from_str(currency) {
-            let second_token = TokenLike::from(TokenId(second_token_id));
+        if let Some(second_token_id) = parse_currency(currency)? {
+            let second_token = TokenLike::from(second_token_id);
             let first_usd_price = self.token_price_usd(first_token).await;
             let second_usd_price = self.token_price_usd(second_token).await;
             match (first_usd_price, second_usd_price) {
@@ -180,11 +466,116 @@ impl ApiTokenData {
                 (_, Err(err)) => Err(err),
             }
         } else {
-            match currency {
-                "usd" => self.token_price_usd(first_token).await,
-                _ => Err(Error::from(InvalidDataError::InvalidCurrency)),
+            self.token_price_usd(first_token).await
+        }
+    }
+
+    /// Loads the prices of all the requested tokens at once. Tokens that are
+    /// unknown or have no price are reported as missing.
+    async fn token_prices(&self, query: TokenPricesQuery) -> Result<TokenPrices, Error> {
+        let requested: Vec<&str> = query
+            .tokens
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .collect();
+        if requested.is_empty() || requested.len() > MAX_TOKEN_PRICES_LIMIT {
+            return Err(Error::from(InvalidDataError::TooManyTokens));
+        }
+        let currency_token_id = parse_currency(&query.currency)?;
+
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let mut resolved = Vec::with_capacity(requested.len());
+        for token_like in requested {
+            let token = self
+                .tokens
+                .get_token(&mut storage, TokenLike::parse(token_like))
+                .await
+                .map_err(Error::storage)?;
+            resolved.push((token_like, token));
+        }
+        let currency_token = match currency_token_id {
+            Some(token_id) => {
+                let token = self
+                    .tokens
+                    .get_token(&mut storage, TokenLike::from(token_id))
+                    .await
+                    .map_err(Error::storage)?
+                    .ok_or_else(|| {
+                        Error::from(PriceError::token_not_found(
+                            "Currency token not found in storage",
+                        ))
+                    })?;
+                Some(token)
+            }
+            None => None,
+        };
+
+        let tokens: Vec<Token> = resolved
+            .iter()
+            .filter_map(|(_, token)| token.clone())
+            .chain(currency_token.clone())
+            .collect();
+        let usd_prices = self
+            .fee_ticker
+            .get_token_prices(&tokens)
+            .await
+            .map_err(Error::storage)?;
+        let currency_usd_price = match currency_token {
+            Some(token) => {
+                let price = usd_prices
+                    .get(&token.id)
+                    .map(|price| ratio_to_big_decimal(&price.usd_price, 100))
+                    .unwrap_or_default();
+                if price.is_zero() {
+                    return Err(Error::from(InvalidDataError::TokenZeroPriceError));
+                }
+                Some(price)
             }
+            None => None,
+        };
+
+        let mut prices = BTreeMap::new();
+        let mut missing = Vec::new();
+        for (token_like, token) in resolved {
+            let token_price = token.and_then(|token| {
+                usd_prices
+                    .get(&token.id)
+                    .map(|token_price| (token, token_price))
+            });
+            let (token, token_price) = match token_price {
+                Some(token_price) => token_price,
+                None => {
+                    missing.push(token_like.to_string());
+                    continue;
+                }
+            };
+
+            let mut price = ratio_to_big_decimal(&token_price.usd_price, 100);
+            if let Some(currency_usd_price) = &currency_usd_price {
+                price = price / currency_usd_price;
+            }
+            prices.insert(
+                token_like.to_string(),
+                TokenPriceInfo {
+                    token_id: token.id,
+                    token_symbol: token.symbol,
+                    decimals: token.decimals,
+                    price,
+                    last_updated: token_price.last_updated,
+                },
+            );
         }
+
+        Ok(TokenPrices {
+            price_in: query.currency,
+            prices,
+            missing,
+        })
     }
 }
 
@@ -192,10 +583,9 @@ impl ApiTokenData {
 
 async fn token_pagination(
     data: web::Data<ApiTokenData>,
-    web::Query(query): web::Query<PaginationQuery<String>>,
+    web::Query(query): web::Query<TokensQuery>,
 ) -> ApiResult<Paginated<ApiToken, TokenId>> {
     let start = Instant::now();
-    let query = api_try!(parse_query(query).map_err(Error::from));
     let res = data.token_page(query).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_pagination");
     res
@@ -207,7 +597,11 @@ async fn token_info(
 ) -> ApiResult<ApiToken> {
     let start = Instant::now();
     let token_like = TokenLike::parse(&token_like_string);
-    let res = data.api_token(token_like).await.into();
+    let res = data
+        .api_token(token_like)
+        .await
+        .or_not_found("token", token_like_string.as_str())
+        .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_info");
     res
 }
@@ -235,63 +629,131 @@ async fn token_price(
     })
 }
 
-async fn get_nft(
+async fn token_prices(
     data: web::Data<ApiTokenData>,
-    id: web::Path<TokenId>,
-) -> ApiResult<Option<ApiNFT>> {
+    web::Query(query): web::Query<TokenPricesQuery>,
+) -> ApiResult<TokenPrices> {
+    let start = Instant::now();
+    let res = data.token_prices(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_token_prices");
+    res
+}
+
+async fn token_listing_status(
+    data: web::Data<ApiTokenData>,
+    address: web::Path<String>,
+) -> ApiResult<TokenListingStatus> {
+    let start = Instant::now();
+    let address = api_try!(Address::from_str(address.trim_start_matches("0x"))
+        .map_err(|_| Error::from(InvalidDataError::InvalidTokenAddress)));
+    let res = data.token_listing_status(address).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_listing_status");
+    res
+}
+
+async fn token_holders(
+    data: web::Data<ApiTokenData>,
+    token_like_string: web::Path<String>,
+    web::Query(query): web::Query<TokenHoldersQuery>,
+) -> ApiResult<TokenHolders> {
+    let start = Instant::now();
+    let token_like = TokenLike::parse(&token_like_string);
+    let res = data.token_holders(token_like, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_holders");
+    res
+}
+
+async fn token_activity(
+    data: web::Data<ApiTokenData>,
+    token_like_string: web::Path<String>,
+    web::Query(query): web::Query<TokenActivityQuery>,
+) -> ApiResult<TokenActivity> {
+    let start = Instant::now();
+    let token_like = TokenLike::parse(&token_like_string);
+    let res = data.token_activity(token_like, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_activity");
+    res
+}
+
+async fn token_changes(
+    data: web::Data<ApiTokenData>,
+    web::Query(query): web::Query<TokenChangesQuery>,
+) -> ApiResult<Vec<TokenChange>> {
+    let start = Instant::now();
+    let res = data.token_changes(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_changes");
+    res
+}
+
+async fn get_nft(data: web::Data<ApiTokenData>, id: web::Path<TokenId>) -> ApiResult<ApiNFT> {
     let start = Instant::now();
     if id.0 < MIN_NFT_TOKEN_ID {
         return Error::from(InvalidDataError::InvalidNFTTokenId).into();
     }
-    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
-    let nft = api_try!(storage
+    let mut storage = api_try!(data
+        .pool
+        .access_storage(AccessIntent::Read)
+        .await
+        .map_err(Error::storage));
+    let nft = storage
         .tokens_schema()
         .get_nft_with_factories(*id)
         .await
-        .map_err(Error::storage));
+        .map_err(Error::storage)
+        .or_not_found("nft", *id);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_nft");
-    ApiResult::Ok(nft)
+    nft.into()
 }
 
 async fn get_nft_owner(
     data: web::Data<ApiTokenData>,
     id: web::Path<TokenId>,
-) -> ApiResult<Option<AccountId>> {
+) -> ApiResult<AccountId> {
     let start = Instant::now();
     if id.0 < MIN_NFT_TOKEN_ID {
         return Error::from(InvalidDataError::InvalidNFTTokenId).into();
     }
-    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
-    let owner_id = api_try!(storage
+    let mut storage = api_try!(data
+        .pool
+        .access_storage(AccessIntent::Read)
+        .await
+        .map_err(Error::storage));
+    let owner_id = storage
         .chain()
         .account_schema()
         .get_nft_owner(*id)
         .await
-        .map_err(Error::storage));
+        .map_err(Error::storage)
+        .or_not_found("nft", *id);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_nft_owner");
-    ApiResult::Ok(owner_id)
+    owner_id.into()
 }
 
 async fn get_nft_id_by_tx_hash(
     data: web::Data<ApiTokenData>,
-    tx_hash: web::Path<TxHash>,
-) -> ApiResult<Option<TokenId>> {
+    tx_hash: ValidPath<TxHash>,
+) -> ApiResult<TokenId> {
     let start = Instant::now();
-    let mut storage = api_try!(data.pool.access_storage().await.map_err(Error::storage));
-    let nft_id = api_try!(storage
+    let mut storage = api_try!(data
+        .pool
+        .access_storage(AccessIntent::Read)
+        .await
+        .map_err(Error::storage));
+    let nft_id = storage
         .chain()
         .state_schema()
         .get_nft_id_by_tx_hash(*tx_hash)
         .await
-        .map_err(Error::storage));
+        .map_err(Error::storage)
+        .or_not_found("nft", *tx_hash);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_nft_id_by_tx_hash");
-    ApiResult::Ok(nft_id)
+    nft_id.into()
 }
 
 pub fn api_scope(
     config: &ZkSyncConfig,
-    pool: ConnectionPool,
-    tokens_db: TokenDBCache,
+    pool: ReplicatedPool,
+    tokens_db: TokenCache,
     fee_ticker: FeeTicker,
 ) -> Scope {
     let data = ApiTokenData::new(config, pool, tokens_db, fee_ticker);
@@ -299,6 +761,12 @@ pub fn api_scope(
     web::scope("tokens")
         .app_data(web::Data::new(data))
         .route("", web::get().to(token_pagination))
+        .route("prices", web::get().to(token_prices))
+        .route("changes", web::get().to(token_changes))
+        .route(
+            "listingStatus/{l1_address}",
+            web::get().to(token_listing_status),
+        )
         .route("{token_like}", web::get().to(token_info))
         .route(
             "{token_like}/priceIn/{currency}",
@@ -310,17 +778,32 @@ pub fn api_scope(
             "nft_id_by_tx_hash/{tx_hash}",
             web::get().to(get_nft_id_by_tx_hash),
         )
+        .route("{token_like}/holders", web::get().to(token_holders))
+        .route("{token_like}/activity", web::get().to(token_activity))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
-        test_utils::{deserialize_response_result, dummy_fee_ticker, TestServerConfig},
+        error::ErrorCode,
+        test_utils::{
+            assert_not_found, deserialize_response_result, dummy_fee_ticker, TestServerConfig,
+        },
         SharedData,
     };
-    use zksync_api_types::v02::{pagination::PaginationDirection, ApiVersion};
-    use zksync_types::{Address, BlockNumber, ZkSyncTx};
+    use crate::api_server::rest::v02::{
+        token_activity::TokenActivityRollup, token_holders::TokenHoldersRefresher,
+    };
+    use actix_web::http::StatusCode;
+    use chrono::{NaiveDate, SubsecRound, Utc};
+    use std::collections::HashSet;
+    use zksync_api_types::v02::token::TokenActivityResolution;
+    use zksync_api_types::v02::{token::TokenMetadataField, ApiVersion};
+    use zksync_storage::tokens::records::{TokenChangeSource, TokenMetadataChange};
+    use zksync_types::{
+        BlockNumber, ExecutedOperations, TokenKind, TokenMarketVolume, ZkSyncOp, ZkSyncTx,
+    };
 
     async fn is_token_enabled_for_fees(
         storage: &mut StorageProcessor<'_>,
@@ -359,13 +842,14 @@ mod tests {
         let shared_data = SharedData {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
         };
         let (client, server) = cfg.start_server(
             move |cfg| {
                 api_scope(
                     &cfg.config,
-                    cfg.pool.clone(),
-                    TokenDBCache::new(cfg.config.api.token_config.invalidate_token_cache_period()),
+                    cfg.replicated_pool(),
+                    cfg.token_cache(),
                     fee_ticker.clone(),
                 )
             },
@@ -403,7 +887,14 @@ mod tests {
         let expected_pagination = {
             let mut storage = cfg.pool.access_storage().await?;
             let paginated_tokens: Paginated<Token, TokenId> = storage
-                .paginate_checked(&query)
+                .paginate_checked(&PaginationQuery {
+                    from: TokensRequest {
+                        token_id: ApiEither::from(TokenId(15)),
+                        filter: TokensFilter::new(TokenKind::ERC20),
+                    },
+                    limit: 2,
+                    direction: PaginationDirection::Older,
+                })
                 .await
                 .map_err(|err| anyhow::anyhow!(err.message))?;
             let mut list = Vec::new();
@@ -497,8 +988,645 @@ mod tests {
         };
 
         let response = client.nft_id_by_tx_hash(tx_hash).await?;
-        let nft_id: Option<TokenId> = deserialize_response_result(response)?;
-        assert!(nft_id.is_some());
+        let minted_nft_id: TokenId = deserialize_response_result(response)?;
+        assert!(minted_nft_id.0 >= MIN_NFT_TOKEN_ID);
+
+        // Missing NFTs and tokens are reported uniformly.
+        let missing_nft_id = TokenId(u32::MAX);
+        let response = client.nft_by_id(missing_nft_id).await?;
+        assert_not_found(response, "nft")?;
+        let response = client.nft_owner_by_id(missing_nft_id).await?;
+        assert_not_found(response, "nft")?;
+        let response = client
+            .token_by_id(&TokenLike::Symbol("UNKNOWN".to_string()))
+            .await?;
+        assert_not_found(response, "token")?;
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tokens_filters() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let prices = vec![
+            (TokenLike::Id(TokenId(1)), 10_u64.into()),
+            (Address::default().into(), 1_u64.into()),
+        ];
+        let fee_ticker = dummy_fee_ticker(&prices, None);
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg| {
+                api_scope(
+                    &cfg.config,
+                    cfg.replicated_pool(),
+                    cfg.token_cache(),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let list_tokens = |query: TokensQuery| {
+            let client = client.clone();
+            async move {
+                let response = client.tokens(&query).await?;
+                deserialize_response_result::<Paginated<ApiToken, TokenId>>(response)
+            }
+        };
+        // Walks through all the pages of the listing.
+        let list_pages = |query: TokensQuery| {
+            let list_tokens = list_tokens.clone();
+            async move {
+                let mut from = String::from("0");
+                let mut tokens = Vec::new();
+                loop {
+                    let page = list_tokens(TokensQuery {
+                        from: Some(from),
+                        limit: Some(2),
+                        direction: Some(PaginationDirection::Newer),
+                        ..query.clone()
+                    })
+                    .await?;
+                    let last_id = match page.list.last() {
+                        Some(token) => token.id,
+                        None => break,
+                    };
+                    assert!(page.list.len() <= 2);
+                    tokens.extend(page.list);
+                    from = (*last_id + 1).to_string();
+                }
+                Ok::<_, anyhow::Error>(tokens)
+            }
+        };
+
+        // Pagination parameters are required unless all the tokens are requested.
+        let response = client.tokens(&TokensQuery::default()).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::MissingPaginationParams);
+
+        let all = list_tokens(TokensQuery {
+            all: true,
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(all.pagination.count as usize, all.list.len());
+        assert!(all.list.iter().all(|token| *token.id < MIN_NFT_TOKEN_ID));
+        assert_eq!(list_pages(TokensQuery::default()).await?, all.list);
+
+        // Filters are applied to every page, and the count is the number of the matching tokens.
+        let query = TokensQuery {
+            symbol: Some(String::from("p")),
+            ..Default::default()
+        };
+        let filtered = list_pages(query.clone()).await?;
+        assert!(!filtered.is_empty());
+        assert!(filtered
+            .iter()
+            .all(|token| token.symbol.to_lowercase().starts_with('p')));
+        let latest = list_tokens(TokensQuery {
+            from: Some(String::from("latest")),
+            limit: Some(1),
+            direction: Some(PaginationDirection::Older),
+            ..query
+        })
+        .await?;
+        assert_eq!(latest.pagination.count as usize, filtered.len());
+        assert_eq!(latest.list, filtered[filtered.len() - 1..]);
+
+        let enabled_for_fees = list_pages(TokensQuery {
+            enabled_for_fees: true,
+            ..Default::default()
+        })
+        .await?;
+        assert!(enabled_for_fees.iter().any(|token| token.id == TokenId(0)));
+        assert!(enabled_for_fees.iter().all(|token| token.enabled_for_fees));
+
+        let nfts = list_pages(TokensQuery {
+            kind: TokenKindFilter::Nft,
+            ..Default::default()
+        })
+        .await?;
+        assert!(!nfts.is_empty());
+        assert!(nfts.iter().all(|token| *token.id >= MIN_NFT_TOKEN_ID));
+        // NFTs can't be used to pay fees.
+        let nfts = list_pages(TokensQuery {
+            kind: TokenKindFilter::Nft,
+            enabled_for_fees: true,
+            ..Default::default()
+        })
+        .await?;
+        assert!(nfts.is_empty());
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn token_prices_batch() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let prices = vec![
+            (TokenLike::Id(TokenId(0)), 2_000_u64.into()),
+            (TokenLike::Id(TokenId(1)), 10_u64.into()),
+        ];
+        let fee_ticker = dummy_fee_ticker(&prices, None);
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg| {
+                api_scope(
+                    &cfg.config,
+                    cfg.replicated_pool(),
+                    cfg.token_cache(),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let tokens = [
+            TokenLike::parse("ETH"),
+            TokenLike::parse("PHNX"),
+            TokenLike::parse("UNKNOWN"),
+        ];
+        let cases = [
+            ("usd", BigDecimal::from(2_000), BigDecimal::from(10)),
+            (
+                "eth",
+                BigDecimal::from(1),
+                BigDecimal::from(10) / BigDecimal::from(2_000),
+            ),
+        ];
+        for (currency, eth_price, phnx_price) in cases {
+            let response = client.token_prices(&tokens, currency).await?;
+            let token_prices: TokenPrices = deserialize_response_result(response)?;
+
+            assert_eq!(token_prices.price_in, currency);
+            assert_eq!(token_prices.missing, vec![String::from("UNKNOWN")]);
+            assert_eq!(token_prices.prices.len(), 2);
+
+            let eth = &token_prices.prices["ETH"];
+            assert_eq!(eth.token_id, TokenId(0));
+            assert_eq!(eth.price, eth_price);
+            let phnx = &token_prices.prices["PHNX"];
+            assert_eq!(phnx.token_id, TokenId(1));
+            assert_eq!(phnx.decimals, 18);
+            assert_eq!(phnx.price, phnx_price);
+        }
+
+        // Unsupported currency.
+        let response = client.token_prices(&tokens, "btc").await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error, Error::from(InvalidDataError::InvalidCurrency));
+
+        // Too many tokens.
+        let tokens = vec![TokenLike::Id(TokenId(0)); MAX_TOKEN_PRICES_LIMIT + 1];
+        let response = client.token_prices(&tokens, "usd").await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error, Error::from(InvalidDataError::TooManyTokens));
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn token_holders_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let before_refresh = Utc::now();
+        TokenHoldersRefresher::new(cfg.pool.clone(), &cfg.config.api.rest)
+            .refresh()
+            .await?;
+        let after_refresh = Utc::now();
+
+        let fee_ticker = dummy_fee_ticker(&[], None);
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg| {
+                api_scope(
+                    &cfg.config,
+                    cfg.replicated_pool(),
+                    cfg.token_cache(),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let token_like = TokenLike::Id(TokenId(0));
+        let response = client.token_holders(&token_like, None).await?;
+        let holders: TokenHolders = deserialize_response_result(response)?;
+        assert_eq!(holders.token_id, TokenId(0));
+        assert!(!holders.holders.is_empty());
+        let refreshed_at = holders.refreshed_at.unwrap();
+        assert!(refreshed_at >= before_refresh.trunc_subsecs(6) && refreshed_at <= after_refresh);
+        for (i, holder) in holders.holders.iter().enumerate() {
+            assert_eq!(holder.rank as usize, i + 1);
+        }
+        assert!(holders
+            .holders
+            .windows(2)
+            .all(|pair| pair[0].balance >= pair[1].balance));
+
+        let response = client.token_holders(&token_like, Some(1)).await?;
+        let largest: TokenHolders = deserialize_response_result(response)?;
+        assert_eq!(largest.holders, holders.holders[..1]);
+
+        // Limit exceeding the number of the tracked holders.
+        let limit = cfg.config.api.rest.token_holders_limit;
+        let response = client.token_holders(&token_like, Some(limit + 1)).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::InvalidTokenHoldersLimit);
+
+        // NFTs have a single owner, their holders are not tracked.
+        let response = client
+            .token_holders(&TokenLike::Id(TokenId(65542)), None)
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::NftHoldersNotTracked);
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn token_activity_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let last_block = TokenActivityRollup::new(cfg.pool.clone(), &cfg.config.api.rest)
+            .rollup()
+            .await?;
+        assert!(*last_block >= 1);
+
+        // Calculate the activity of the default token in the rolled up blocks, all of them
+        // have the zero timestamp.
+        let mut storage = cfg.pool.access_storage().await?;
+        let (mut transfers, mut transfer_volume, mut senders) =
+            (0, BigUint::zero(), HashSet::new());
+        let (mut withdrawals, mut withdrawal_volume) = (0, BigUint::zero());
+        for block_number in 1..=*last_block {
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(BlockNumber(block_number))
+                .await?
+                .unwrap();
+            assert_eq!(block.timestamp, 0);
+            for op in &block.block_transactions {
+                let op = match op {
+                    ExecutedOperations::Tx(tx) if tx.success => tx.op.clone().unwrap(),
+                    ExecutedOperations::Tx(_) => continue,
+                    ExecutedOperations::PriorityOp(op) => op.op.clone(),
+                };
+                match op {
+                    ZkSyncOp::Transfer(op) if op.tx.token == TokenId(0) => {
+                        transfers += 1;
+                        transfer_volume += op.tx.amount;
+                        senders.insert(op.from);
+                    }
+                    ZkSyncOp::TransferToNew(op) if op.tx.token == TokenId(0) => {
+                        transfers += 1;
+                        transfer_volume += op.tx.amount;
+                        senders.insert(op.from);
+                    }
+                    ZkSyncOp::Withdraw(op) if op.tx.token == TokenId(0) => {
+                        withdrawals += 1;
+                        withdrawal_volume += op.tx.amount;
+                    }
+                    ZkSyncOp::FullExit(op) if op.priority_op.token == TokenId(0) => {
+                        if let Some(amount) = op.withdraw_amount {
+                            withdrawals += 1;
+                            withdrawal_volume += amount.0;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        drop(storage);
+        assert!(transfers > 0);
+
+        let fee_ticker = dummy_fee_ticker(&[], None);
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg| {
+                api_scope(
+                    &cfg.config,
+                    cfg.replicated_pool(),
+                    cfg.token_cache(),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let token_like = TokenLike::Id(TokenId(0));
+        let query = TokenActivityQuery {
+            from: NaiveDate::from_ymd(1969, 12, 31),
+            to: NaiveDate::from_ymd(1970, 1, 2),
+            resolution: TokenActivityResolution::Day,
+        };
+        let response = client.token_activity(&token_like, &query).await?;
+        let activity: TokenActivity = deserialize_response_result(response)?;
+        assert_eq!(activity.token_id, TokenId(0));
+        assert_eq!(activity.last_block, last_block);
+        let days: Vec<_> = activity.buckets.iter().map(|bucket| bucket.day).collect();
+        assert_eq!(
+            days,
+            vec![
+                NaiveDate::from_ymd(1969, 12, 31),
+                NaiveDate::from_ymd(1970, 1, 1),
+                NaiveDate::from_ymd(1970, 1, 2)
+            ]
+        );
+        // Days without the activity are reported with zeros.
+        for bucket in [&activity.buckets[0], &activity.buckets[2]] {
+            assert_eq!(bucket.transfers, 0);
+            assert_eq!(bucket.transfer_volume, BigUint::zero());
+            assert_eq!(bucket.unique_senders, 0);
+            assert_eq!(bucket.withdrawals, 0);
+        }
+        let bucket = &activity.buckets[1];
+        assert_eq!(bucket.transfers, transfers);
+        assert_eq!(bucket.transfer_volume, transfer_volume);
+        assert_eq!(bucket.unique_senders, senders.len() as u64);
+        assert_eq!(bucket.withdrawals, withdrawals);
+        assert_eq!(bucket.withdrawal_volume, withdrawal_volume);
+
+        // The range should be ordered and fit into the limit.
+        for (from, to) in [
+            (
+                NaiveDate::from_ymd(1970, 1, 2),
+                NaiveDate::from_ymd(1970, 1, 1),
+            ),
+            (
+                NaiveDate::from_ymd(1970, 1, 1),
+                NaiveDate::from_ymd(1971, 1, 2),
+            ),
+        ] {
+            let query = TokenActivityQuery {
+                from,
+                to,
+                resolution: TokenActivityResolution::Day,
+            };
+            let response = client.token_activity(&token_like, &query).await?;
+            let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+            assert_eq!(error.code, ErrorCode::InvalidTokenActivityRange);
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn token_changes_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let fee_ticker = dummy_fee_ticker(&[], None);
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg| {
+                api_scope(
+                    &cfg.config,
+                    cfg.replicated_pool(),
+                    cfg.token_cache(),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let response = client.token_changes(0, None).await?;
+        let changes: Vec<TokenChange> = deserialize_response_result(response)?;
+        let last_id = changes.last().map_or(0, |change| change.id);
+
+        let token = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let token = storage
+                .tokens_schema()
+                .get_token(TokenLike::Id(TokenId(1)))
+                .await?
+                .unwrap();
+            storage
+                .tokens_schema()
+                .update_token_metadata(
+                    token.id,
+                    TokenMetadataChange::Decimals(token.decimals + 1),
+                    &TokenChangeSource::Admin("operator".to_string()),
+                )
+                .await?;
+            token
+        };
+
+        let response = client.token_changes(last_id, Some(10)).await?;
+        let delta: Vec<TokenChange> = deserialize_response_result(response)?;
+        assert_eq!(delta.len(), 1);
+        assert!(delta[0].id > last_id);
+        assert_eq!(delta[0].token_id, token.id);
+        assert_eq!(delta[0].field, TokenMetadataField::Decimals);
+        assert_eq!(delta[0].old_value, token.decimals.to_string());
+        assert_eq!(delta[0].new_value, (token.decimals + 1).to_string());
+        assert_eq!(delta[0].source, "admin:operator");
+
+        let response = client.token_changes(delta[0].id, None).await?;
+        let delta: Vec<TokenChange> = deserialize_response_result(response)?;
+        assert!(delta.is_empty());
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[test]
+    fn token_listing_stages() {
+        let cases = [
+            ((false, false, false, false), TokenListingStage::NotStarted),
+            (
+                (true, false, false, false),
+                TokenListingStage::GovernanceTxConfirmed,
+            ),
+            (
+                (true, true, false, false),
+                TokenListingStage::TokenIdAssigned,
+            ),
+            ((true, true, true, false), TokenListingStage::PriceAvailable),
+            ((true, true, true, true), TokenListingStage::FeeEnabled),
+            // Tokens added before the listings were tracked.
+            (
+                (false, true, true, false),
+                TokenListingStage::PriceAvailable,
+            ),
+            // Market volume without a price doesn't enable the token for fees.
+            (
+                (true, true, false, true),
+                TokenListingStage::TokenIdAssigned,
+            ),
+        ];
+        for ((event_processed, token_stored, price_available, fee_enabled), expected) in cases {
+            assert_eq!(
+                listing_stage(event_processed, token_stored, price_available, fee_enabled),
+                expected
+            );
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn token_listing_status_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let fee_ticker = dummy_fee_ticker(&[], None);
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg| {
+                api_scope(
+                    &cfg.config,
+                    cfg.replicated_pool(),
+                    cfg.token_cache(),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let address = Address::repeat_byte(0x5a);
+        let token_id = {
+            let mut storage = cfg.pool.access_storage().await?;
+            TokenId(storage.tokens_schema().get_max_erc20_token_id().await? + 1)
+        };
+        let client = &client;
+        let listing_status = move || async move {
+            let response = client.token_listing_status(address).await?;
+            deserialize_response_result::<TokenListingStatus>(response)
+        };
+
+        let status = listing_status().await?;
+        assert_eq!(status.address, address);
+        assert_eq!(status.stage, TokenListingStage::NotStarted);
+        assert!(status.token_id.is_none());
+        assert!(status.governance_tx_confirmed_at.is_none());
+
+        // The governance event is confirmed on L1 and processed by the server.
+        cfg.pool
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .record_token_listing(address, token_id, 10)
+            .await?;
+        let status = listing_status().await?;
+        assert_eq!(status.stage, TokenListingStage::GovernanceTxConfirmed);
+        assert_eq!(status.token_id, Some(token_id));
+        assert_eq!(status.eth_block_number, Some(10));
+        assert!(status.governance_tx_confirmed_at.is_some());
+        assert!(status.token_id_assigned_at.is_none());
+
+        // The token is stored.
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            let token = Token::new(token_id, address, "LSTD", 18, TokenKind::ERC20);
+            storage.tokens_schema().store_token(token).await?;
+            storage
+                .tokens_schema()
+                .record_token_listing_result(address, None)
+                .await?;
+        }
+        let status = listing_status().await?;
+        assert_eq!(status.stage, TokenListingStage::TokenIdAssigned);
+        assert_eq!(status.symbol.as_deref(), Some("LSTD"));
+        assert!(status.token_id_assigned_at.is_some());
+        assert!(status.price_updated_at.is_none());
+
+        // The ticker fetches the token price.
+        let now = Utc::now();
+        cfg.pool
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .update_historical_ticker_price(
+                token_id,
+                zksync_types::TokenPrice {
+                    usd_price: Ratio::from_integer(BigUint::from(2u32)),
+                    last_updated: now,
+                },
+            )
+            .await?;
+        let status = listing_status().await?;
+        assert_eq!(status.stage, TokenListingStage::PriceAvailable);
+        assert!(status.price_updated_at.is_some());
+        assert!(status.fee_enabled_at.is_none());
+
+        // The market volume becomes large enough to pay fees in the token.
+        let market_volume = BigUint::from_f64(cfg.config.ticker.liquidity_volume).unwrap() + 1u32;
+        cfg.pool
+            .access_storage()
+            .await?
+            .tokens_schema()
+            .update_token_market_volume(
+                token_id,
+                TokenMarketVolume {
+                    market_volume: Ratio::from_integer(market_volume),
+                    last_updated: now,
+                },
+            )
+            .await?;
+        let status = listing_status().await?;
+        assert_eq!(status.stage, TokenListingStage::FeeEnabled);
+        assert!(status.fee_enabled_at.is_some());
+        assert!(status.store_error.is_none());
 
         server.stop().await;
         Ok(())
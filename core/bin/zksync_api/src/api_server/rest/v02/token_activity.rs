@@ -0,0 +1,141 @@
+//! Rollup of the finalized blocks into the daily activity of the tokens.
+//!
+//! Scanning the executed operations of a date range is too expensive to be done per request,
+//! so the finalized blocks are aggregated in the background and `tokens/{token}/activity`
+//! serves the stored buckets along with the last block taken into account.
+//! Reverted blocks are removed from the activity by the block schema.
+
+// Built-in uses
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+// External uses
+use tokio::time;
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_storage::{chain::stats::TokenActivityDelta, ConnectionPool};
+use zksync_types::{BlockNumber, ExecutedOperations, TokenId, ZkSyncOp};
+use zksync_utils::biguint_to_big_decimal;
+
+/// Aggregates the activity of the tokens in the successful operations of the block.
+pub fn block_token_activity(operations: &[ExecutedOperations]) -> Vec<TokenActivityDelta> {
+    let mut deltas = BTreeMap::<TokenId, TokenActivityDelta>::new();
+    for operation in operations {
+        let op = match operation {
+            ExecutedOperations::Tx(tx) if tx.success => tx.op.as_ref(),
+            ExecutedOperations::Tx(_) => None,
+            ExecutedOperations::PriorityOp(priority_op) => Some(&priority_op.op),
+        };
+        let (token_id, transfer, withdrawal) = match op {
+            Some(ZkSyncOp::Transfer(op)) => {
+                (op.tx.token, Some((op.from, op.tx.amount.clone())), None)
+            }
+            Some(ZkSyncOp::TransferToNew(op)) => {
+                (op.tx.token, Some((op.from, op.tx.amount.clone())), None)
+            }
+            Some(ZkSyncOp::Withdraw(op)) => (op.tx.token, None, Some(op.tx.amount.clone())),
+            Some(ZkSyncOp::ForcedExit(op)) => (
+                op.tx.token,
+                None,
+                op.withdraw_amount.clone().map(|amount| amount.0),
+            ),
+            Some(ZkSyncOp::FullExit(op)) => (
+                op.priority_op.token,
+                None,
+                op.withdraw_amount.clone().map(|amount| amount.0),
+            ),
+            _ => continue,
+        };
+        // NFTs are not fungible, their volumes are meaningless.
+        if *token_id >= MIN_NFT_TOKEN_ID {
+            continue;
+        }
+
+        let delta = deltas
+            .entry(token_id)
+            .or_insert_with(|| TokenActivityDelta {
+                token_id,
+                ..Default::default()
+            });
+        if let Some((sender, amount)) = transfer {
+            delta.transfers += 1;
+            delta.transfer_volume += biguint_to_big_decimal(amount);
+            delta.senders.push(sender);
+        }
+        if let Some(amount) = withdrawal {
+            delta.withdrawals += 1;
+            delta.withdrawal_volume += biguint_to_big_decimal(amount);
+        }
+    }
+    deltas.into_values().collect()
+}
+
+/// Rolls up the newly finalized blocks into the token activity once per interval.
+#[derive(Debug, Clone)]
+pub struct TokenActivityRollup {
+    pool: ConnectionPool,
+    blocks_limit: u32,
+    interval: Duration,
+}
+
+impl TokenActivityRollup {
+    /// The activity is stored using the main database, since the replicas are read-only.
+    pub fn new(pool: ConnectionPool, config: &RestApiConfig) -> Self {
+        Self {
+            pool,
+            blocks_limit: config.token_activity_rollup_blocks,
+            interval: config.token_activity_rollup_interval(),
+        }
+    }
+
+    /// Rolls up the finalized blocks that are not rolled up yet, at most `blocks_limit` of them.
+    /// Returns the last rolled up block.
+    pub async fn rollup(&self) -> anyhow::Result<BlockNumber> {
+        let mut storage = self.pool.access_storage().await?;
+        let last_rolled_up = storage
+            .chain()
+            .stats_schema()
+            .last_token_activity_block()
+            .await?;
+        let last_finalized = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let last_block = last_finalized.min(last_rolled_up + self.blocks_limit);
+
+        for block_number in (*last_rolled_up + 1)..=*last_block {
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(BlockNumber(block_number))
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Finalized block {} is missing", block_number))?;
+            let day = block.timestamp_utc().date().naive_utc();
+            let deltas = block_token_activity(&block.block_transactions);
+            storage
+                .chain()
+                .stats_schema()
+                .store_token_activity(block.block_number, day, &deltas)
+                .await?;
+        }
+        Ok(last_rolled_up.max(last_block))
+    }
+
+    /// Rolls up the blocks forever. Failed rollups are retried on the next tick,
+    /// the activity of the blocks rolled up before is served in the meantime.
+    pub async fn run(self) {
+        let mut timer = time::interval(self.interval);
+        loop {
+            timer.tick().await;
+            match self.rollup().await {
+                Ok(last_block) => {
+                    vlog::debug!("Token activity is rolled up to block {}", last_block)
+                }
+                Err(err) => vlog::warn!("Unable to roll up the token activity: {}", err),
+            }
+        }
+    }
+}
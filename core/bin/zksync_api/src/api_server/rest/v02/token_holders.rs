@@ -0,0 +1,57 @@
+//! Periodic recalculation of the largest token holders.
+//!
+//! Ranking the balances of every token is too expensive to be done per request, so the holders
+//! are recalculated in the background and `tokens/{token}/holders` serves the stored snapshot
+//! along with the time it was taken.
+
+// Built-in uses
+use std::time::Duration;
+
+// External uses
+use tokio::time;
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+use zksync_storage::ConnectionPool;
+
+/// Recalculates the largest holders of every token once per interval.
+#[derive(Debug, Clone)]
+pub struct TokenHoldersRefresher {
+    pool: ConnectionPool,
+    limit: u32,
+    interval: Duration,
+}
+
+impl TokenHoldersRefresher {
+    /// Holders are recalculated using the main database, since the replicas are read-only.
+    pub fn new(pool: ConnectionPool, config: &RestApiConfig) -> Self {
+        Self {
+            pool,
+            limit: config.token_holders_limit,
+            interval: config.token_holders_refresh_interval(),
+        }
+    }
+
+    /// Recalculates the holders once.
+    pub async fn refresh(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage().await?;
+        let refreshed_at = storage
+            .tokens_schema()
+            .refresh_top_holders(self.limit)
+            .await?;
+        vlog::debug!("Token holders are recalculated at {}", refreshed_at);
+        Ok(())
+    }
+
+    /// Recalculates the holders forever. Failed recalculations are retried on the next tick,
+    /// the previous holders are served in the meantime.
+    pub async fn run(self) {
+        let mut timer = time::interval(self.interval);
+        loop {
+            timer.tick().await;
+            if let Err(err) = self.refresh().await {
+                vlog::warn!("Unable to recalculate the token holders: {}", err);
+            }
+        }
+    }
+}
@@ -1,173 +1,786 @@
 //! Transactions part of API implementation.
 
 // Built-in uses
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External uses
 use actix_web::{
+    error::InternalError,
+    http::StatusCode,
     web::{self, Json},
-    Scope,
+    HttpRequest, Responder, ResponseError, Scope,
 };
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 
 // Workspace uses
 use zksync_api_types::{
+    v02::account::{AccountLockInfo, LockAccount, UnlockAccount},
+    v02::sub_version::SubVersioned,
     v02::transaction::{
-        ApiTxBatch, IncomingTxBatch, L1Receipt, L1Transaction, Receipt, SubmitBatchResponse,
-        Toggle2FA, Toggle2FAResponse, Transaction, TransactionData, TxData, TxHashSerializeWrapper,
-        TxInBlockStatus,
+        ApiTxBatch, BatchHashRequest, BatchHashResponse, BatchStatus, CancelTxRequest,
+        CancelTxResponse, ConsistencyQuery, EtaRange, IncomingTxBatch, L1Transaction, L2Receipt,
+        L2Signature, L2Transaction, ReadConsistency, Receipt, SignMessageRequest,
+        SignMessageResponse, SubmitBatchResponse, SubmitQuery, SubmitTxResponse, Toggle2FA,
+        Toggle2FAResponse, TransactionData, TxData, TxDataQuery, TxDiagnosis, TxHashMatch,
+        TxHashMatchSource, TxHashSearchQuery, TxHashSearchResponse, TxHashSerializeWrapper,
+        TxInBlockStatus, TxPublicData, WithdrawalExecuteEta, WithdrawalExecution,
+        BATCH_HASH_PREIMAGE_FORMAT,
     },
-    TxWithSignature,
+    AmountsQuery, MempoolTxLookup, TxWithSignature,
+};
+use zksync_config::configs::api::RestApiConfig;
+use zksync_storage::{AccessIntent, ReplicatedPool};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType,
+    tx::{preconditions::check_tx_preconditions, TxHash},
+    Address, BlockNumber, ExecutedOperations, ZkSyncTx, H256,
 };
-use zksync_types::{tx::TxHash, EthBlockId};
 
 // Local uses
-use super::{error::Error, response::ApiResult};
-use crate::api_server::tx_sender::{SubmitError, TxSender};
+use super::{
+    amounts::render_amounts,
+    archive::TxArchive,
+    error::{Error, InvalidDataError, TxSearchError},
+    path_params::ValidPath,
+    response::{sub_version, ApiResult, OrNotFound},
+    storage_retry::{ReadRetry, StorageRetry},
+};
+use crate::api_server::{
+    admin_server::validate_auth_token,
+    rest::network_status::SharedNetworkStatus,
+    tx_info,
+    tx_sender::{SubmitError, TxSender},
+};
+use crate::utils::tx_data_cache::TxDataCache;
+
+/// Number of the recent execute operations the interval between them is averaged over.
+const EXECUTE_INTERVAL_WINDOW: u32 = 10;
+/// Header of the API key, which allows to request the debug timings of the batch submissions.
+const API_KEY_HEADER: &str = "X-Api-Key";
 
 /// Shared data between `api/v0.2/transactions` endpoints.
 #[derive(Clone)]
 struct ApiTransactionData {
+    pool: ReplicatedPool,
+    archive: Option<TxArchive>,
     tx_sender: TxSender,
+    network_status: SharedNetworkStatus,
+    /// Secret of the admin tokens, the search by the hash prefix is available to the admins only.
+    secret_auth: String,
+    search_min_prefix_len: usize,
+    search_limit: u32,
+    max_blocks_per_execute: u32,
+    tx_data_cache: TxDataCache,
+    storage_retry: StorageRetry,
 }
 
 impl ApiTransactionData {
-    fn new(tx_sender: TxSender) -> Self {
-        Self { tx_sender }
+    fn new(
+        pool: ReplicatedPool,
+        archive: Option<TxArchive>,
+        tx_sender: TxSender,
+        network_status: SharedNetworkStatus,
+        config: &RestApiConfig,
+        secret_auth: String,
+        max_blocks_per_execute: usize,
+    ) -> Self {
+        let tx_data_cache =
+            TxDataCache::new(config.tx_data_cache_size, config.tx_data_cache_max_bytes)
+                .with_disk_cache(tx_sender.disk_cache.clone());
+        Self {
+            pool,
+            archive,
+            tx_sender,
+            network_status,
+            secret_auth,
+            search_min_prefix_len: config.tx_search_min_prefix_len,
+            search_limit: config.tx_search_limit,
+            max_blocks_per_execute: max_blocks_per_execute.max(1) as u32,
+            tx_data_cache,
+            storage_retry: StorageRetry::new(config),
+        }
     }
 
-    async fn tx_status(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error> {
-        // Try to find in the DB.
-        let mut storage = self
+    /// Recently submitted transactions may be not replicated yet, so if the replica
+    /// doesn't know about the transaction, the primary database is checked as well.
+    /// The same applies to the transaction data and batches. With the strong consistency
+    /// the replica is skipped, and the core mempool is asked about the transaction
+    /// before reporting it as unknown.
+    ///
+    /// Old transactions may be moved to the archive, it's checked the last.
+    async fn tx_status(
+        &self,
+        tx_hash: TxHash,
+        consistency: ReadConsistency,
+    ) -> Result<Option<Receipt>, Error> {
+        let retry = self.storage_retry.begin();
+        if consistency == ReadConsistency::Eventual {
+            if let Some(receipt) = self
+                .load_tx_status(tx_hash, AccessIntent::Read, &retry)
+                .await?
+            {
+                return Ok(Some(receipt));
+            }
+        }
+        if let Some(receipt) = self
+            .load_tx_status(tx_hash, AccessIntent::Write, &retry)
+            .await?
+        {
+            return Ok(Some(receipt));
+        }
+        if let Some(archive) = &self.archive {
+            if let Some(receipt) = archive.tx_receipt(tx_hash).await? {
+                return Ok(Some(receipt));
+            }
+        }
+        match consistency {
+            ReadConsistency::Eventual => Ok(None),
+            ReadConsistency::Strong => self.mempool_tx_receipt(tx_hash).await,
+        }
+    }
+
+    /// The transaction data is not known to the core mempool, so the strong consistency
+    /// only skips the replica. The data of the finalized transactions never changes, so it's
+    /// served from the cache regardless of the consistency.
+    async fn tx_data(
+        &self,
+        tx_hash: TxHash,
+        query: TxDataQuery,
+        consistency: ReadConsistency,
+    ) -> Result<Option<TxData>, Error> {
+        let data = match self.tx_data_cache.get(&tx_hash) {
+            Some(data) => Some(data),
+            None => {
+                let data = self.lookup_tx_data(tx_hash, consistency).await?;
+                // The signature is added per request, so the data is cached without it.
+                if let Some(data) = &data {
+                    self.tx_data_cache.insert(tx_hash, data);
+                }
+                data
+            }
+        };
+
+        Ok(data.map(|mut data| {
+            if query.include_l2_signature() {
+                data.l2_signature = match &data.tx.op {
+                    TransactionData::L2(tx) => L2Signature::from_tx(&tx.to_zksync_tx()),
+                    TransactionData::L1(_) => None,
+                };
+            }
+            data
+        }))
+    }
+
+    async fn lookup_tx_data(
+        &self,
+        tx_hash: TxHash,
+        consistency: ReadConsistency,
+    ) -> Result<Option<TxData>, Error> {
+        let retry = self.storage_retry.begin();
+        let mut data = None;
+        if consistency == ReadConsistency::Eventual {
+            data = self
+                .load_tx_data(tx_hash, AccessIntent::Read, &retry)
+                .await?;
+        }
+        if data.is_none() {
+            data = self
+                .load_tx_data(tx_hash, AccessIntent::Write, &retry)
+                .await?;
+        }
+        if let (None, Some(archive)) = (&data, &self.archive) {
+            data = archive.tx_data(tx_hash).await?;
+        }
+        Ok(data)
+    }
+
+    /// Returns the message to be signed with the Ethereum key. It's built by the same code
+    /// that verifies the signatures of the submitted transactions and batches.
+    async fn sign_message(
+        &self,
+        request: SignMessageRequest,
+    ) -> Result<SignMessageResponse, Error> {
+        let message = match (request.tx, request.txs.is_empty()) {
+            (Some(tx), true) => self.tx_sender.tx_message_to_sign(&tx).await?.1,
+            (None, false) => Some(self.tx_sender.batch_message_to_sign(&request.txs).await?),
+            _ => return Err(Error::from(InvalidDataError::InvalidSignMessageRequest)),
+        };
+        Ok(SignMessageResponse::new(message))
+    }
+
+    /// Cancels the transaction if it's not proposed for a block yet. Unknown transactions
+    /// are reported as not found, the ones that are already processed as too late.
+    async fn cancel_tx(
+        &self,
+        tx_hash: TxHash,
+        request: CancelTxRequest,
+    ) -> Result<CancelTxResponse, Error> {
+        let result = self
             .tx_sender
+            .cancel_tx(
+                tx_hash,
+                request.signature,
+                self.network_status.core_client(),
+            )
+            .await?
+            .ok_or_else(|| Error::from(InvalidDataError::TransactionNotFound))?;
+        Ok(CancelTxResponse { tx_hash, result })
+    }
+
+    async fn get_batch(&self, batch_hash: TxHash) -> Result<Option<ApiTxBatch>, Error> {
+        let retry = self.storage_retry.begin();
+        match self
+            .load_batch(batch_hash, AccessIntent::Read, &retry)
+            .await?
+        {
+            Some(batch) => Ok(Some(batch)),
+            None => {
+                self.load_batch(batch_hash, AccessIntent::Write, &retry)
+                    .await
+            }
+        }
+    }
+
+    /// Finds the transactions by the prefix of their hash. The search is limited to the admins,
+    /// so that the hashes of the pending transactions can't be enumerated.
+    async fn search(
+        &self,
+        credentials: Option<BearerAuth>,
+        prefix: &str,
+    ) -> Result<TxHashSearchResponse, Error> {
+        credentials
+            .and_then(|credentials| {
+                validate_auth_token(&self.secret_auth, credentials.token()).ok()
+            })
+            .ok_or(TxSearchError::Unauthorized)?;
+        let prefix = parse_tx_hash_prefix(prefix, self.search_min_prefix_len)?;
+
+        // The mempool is not replicated reliably enough, so the primary database is used.
+        let mut storage = self
             .pool
-            .access_storage()
+            .access_storage(AccessIntent::Write)
             .await
             .map_err(Error::storage)?;
-
-        // 1. Try to find the already received/executed operation.
-        if let Some(receipt) = storage
+        // One extra match tells whether there are more matches than returned.
+        let mut records = storage
             .chain()
             .operations_ext_schema()
-            .tx_receipt_api_v02(tx_hash.as_ref())
+            .search_tx_hashes_by_prefix(&prefix, self.search_limit + 1)
             .await
-            .map_err(Error::storage)?
-        {
-            Ok(Some(receipt))
+            .map_err(Error::storage)?;
+        let truncated = records.len() > self.search_limit as usize;
+        records.truncate(self.search_limit as usize);
+
+        let matches = records
+            .into_iter()
+            .map(|record| TxHashMatch {
+                tx_hash: TxHash::from_slice(&record.tx_hash).unwrap(),
+                source: match record.source.as_str() {
+                    "executed" => TxHashMatchSource::Executed,
+                    "priority" => TxHashMatchSource::PriorityOp,
+                    _ => TxHashMatchSource::Mempool,
+                },
+                tx_type: record.tx_type,
+                block_number: record
+                    .block_number
+                    .map(|block_number| BlockNumber(block_number as u32)),
+                created_at: record.created_at,
+                account: record.account.map(|address| Address::from_slice(&address)),
+            })
+            .collect();
+        Ok(TxHashSearchResponse { matches, truncated })
+    }
+
+    /// Explains why the transaction is not executed. Pending transactions are checked
+    /// against the last committed state of the initiator account.
+    async fn tx_diagnosis(&self, tx_hash: TxHash) -> Result<TxDiagnosis, Error> {
+        let mut diagnosis = TxDiagnosis {
+            tx_hash,
+            status: None,
+            failed_conditions: Vec::new(),
+            note: None,
+        };
+
+        let receipt = match self.tx_status(tx_hash, ReadConsistency::Eventual).await? {
+            Some(Receipt::L2(receipt)) => receipt,
+            Some(Receipt::L1(receipt)) => {
+                diagnosis.status = Some(receipt.status);
+                diagnosis.note = Some("Priority operations can't fail to execute".to_string());
+                return Ok(diagnosis);
+            }
+            None => {
+                diagnosis.note = Some("Transaction is unknown".to_string());
+                return Ok(diagnosis);
+            }
+        };
+        diagnosis.status = Some(receipt.status);
+        match receipt.status {
+            TxInBlockStatus::Queued => {}
+            TxInBlockStatus::Committed | TxInBlockStatus::Finalized => {
+                diagnosis.note = Some("Transaction is executed".to_string());
+                return Ok(diagnosis);
+            }
+            TxInBlockStatus::Rejected => {
+                diagnosis.note = Some(format!(
+                    "Transaction is rejected: {}",
+                    receipt.fail_reason.unwrap_or_default()
+                ));
+                return Ok(diagnosis);
+            }
         }
-        // 2. Try to find the pending operation.
-        else if let Some(op) = storage
+
+        // The mempool is not replicated reliably enough, so the primary database is used.
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let tx = match storage
             .chain()
             .mempool_schema()
-            .get_pending_operation_by_hash(tx_hash.into())
+            .get_tx(tx_hash.as_ref())
             .await
-            .map_err(Error::core_api)?
+            .map_err(Error::storage)?
         {
-            Ok(Some(Receipt::L1(L1Receipt {
-                status: TxInBlockStatus::Queued,
-                eth_block: EthBlockId(op.eth_block),
-                rollup_block: None,
-                id: op.serial_id,
-            })))
-        }
-        // 3. No operation found, return nothing.
-        else {
-            Ok(None)
+            Some(tx) => tx.tx,
+            None => {
+                diagnosis.note =
+                    Some("Transaction is not forwarded to the mempool yet".to_string());
+                return Ok(diagnosis);
+            }
+        };
+        let account_id = match tx.account_id() {
+            Ok(account_id) => account_id,
+            Err(err) => {
+                diagnosis.note = Some(err.to_string());
+                return Ok(diagnosis);
+            }
+        };
+        let (_, account) = storage
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(account_id)
+            .await
+            .map_err(Error::storage)?;
+
+        let now = Utc::now().timestamp() as u64;
+        diagnosis.failed_conditions =
+            check_tx_preconditions(&tx, account_id, account.as_ref(), now);
+        if diagnosis.failed_conditions.is_empty() {
+            diagnosis.note = Some("Transaction is waiting to be included into a block".to_string());
         }
+
+        Ok(diagnosis)
     }
 
-    async fn tx_data(&self, tx_hash: TxHash) -> Result<Option<TxData>, Error> {
+    /// Estimates when the block of the verified withdrawal gets executed from its position in
+    /// the execute queue and the average interval between the recent execute operations.
+    /// Executed withdrawals are reported with the actual execution instead.
+    async fn execute_eta(&self, tx_hash: TxHash) -> Result<Option<WithdrawalExecuteEta>, Error> {
         let mut storage = self
-            .tx_sender
             .pool
-            .access_storage()
+            .access_storage(AccessIntent::Read)
+            .await
+            .map_err(Error::storage)?;
+        let mut transaction = storage
+            .start_snapshot_read()
             .await
             .map_err(Error::storage)?;
-        if let Some(data) = storage
+        let tx = match transaction
             .chain()
             .operations_ext_schema()
             .tx_data_api_v02(tx_hash.as_ref())
             .await
             .map_err(Error::storage)?
         {
-            Ok(Some(data))
-        } else if let Some(op) = storage
+            Some(data) => data.tx,
+            None => return Ok(None),
+        };
+        let is_withdrawal = matches!(
+            tx.op,
+            TransactionData::L2(
+                L2Transaction::Withdraw(_)
+                    | L2Transaction::ForcedExit(_)
+                    | L2Transaction::WithdrawNFT(_)
+            ) | TransactionData::L1(L1Transaction::FullExit(_))
+        );
+        let block_number = match (tx.status, tx.block_number) {
+            _ if !is_withdrawal => return Err(Error::from(InvalidDataError::NotWithdrawal)),
+            (TxInBlockStatus::Rejected, _) => {
+                return Err(Error::from(InvalidDataError::NotWithdrawal))
+            }
+            (TxInBlockStatus::Committed | TxInBlockStatus::Finalized, Some(block_number)) => {
+                block_number
+            }
+            _ => return Err(Error::from(InvalidDataError::WithdrawalNotVerified)),
+        };
+
+        let mut eta = WithdrawalExecuteEta {
+            tx_hash,
+            block_number,
+            execution: None,
+            execute_queue_position: None,
+            max_blocks_per_execute: self.max_blocks_per_execute,
+            execute_interval_secs: None,
+            eta: None,
+        };
+        if tx.status == TxInBlockStatus::Finalized {
+            let executed_at = transaction
+                .chain()
+                .operations_schema()
+                .get_stored_aggregated_operation(block_number, AggregatedActionType::ExecuteBlocks)
+                .await
+                .map(|operation| operation.created_at);
+            let eth_tx_hash = transaction
+                .chain()
+                .operations_schema()
+                .eth_tx_for_withdrawal(&tx_hash)
+                .await
+                .map_err(Error::storage)?;
+            transaction.commit().await.map_err(Error::storage)?;
+
+            eta.execution = Some(WithdrawalExecution {
+                executed_at,
+                eth_tx_hash,
+            });
+            return Ok(Some(eta));
+        }
+
+        let last_verified_block = transaction
             .chain()
-            .mempool_schema()
-            .get_pending_operation_by_hash(tx_hash.into())
+            .operations_schema()
+            .get_last_block_by_aggregated_action(
+                AggregatedActionType::PublishProofBlocksOnchain,
+                Some(true),
+            )
             .await
-            .map_err(Error::core_api)?
-        {
-            let tx_hash = op.tx_hash();
-            let tx = Transaction {
-                tx_hash,
-                block_index: None,
-                block_number: None,
-                op: TransactionData::L1(L1Transaction::from_pending_op(
-                    op.data,
-                    op.eth_hash,
-                    op.serial_id,
-                    tx_hash,
-                )),
-                status: TxInBlockStatus::Queued,
-                fail_reason: None,
-                created_at: None,
-                batch_id: None,
-            };
-
-            Ok(Some(TxData {
-                tx,
-                eth_signature: None,
-            }))
-        } else {
-            Ok(None)
+            .map_err(Error::storage)?;
+        if block_number > last_verified_block {
+            return Err(Error::from(InvalidDataError::WithdrawalNotVerified));
         }
+        let last_scheduled_block = transaction
+            .chain()
+            .operations_schema()
+            .get_last_affected_block_by_aggregated_action(AggregatedActionType::ExecuteBlocks)
+            .await
+            .map_err(Error::storage)?;
+        let recent_executes = transaction
+            .ethereum_schema()
+            .load_recent_operations_created_at(
+                AggregatedActionType::ExecuteBlocks,
+                EXECUTE_INTERVAL_WINDOW,
+            )
+            .await
+            .map_err(Error::storage)?;
+        transaction.commit().await.map_err(Error::storage)?;
+
+        let position = execute_queue_position(
+            block_number,
+            last_scheduled_block,
+            self.max_blocks_per_execute,
+        );
+        let interval = average_interval(&recent_executes);
+        eta.execute_queue_position = Some(position);
+        eta.execute_interval_secs = interval.map(|interval| interval.as_secs());
+        eta.eta = interval.map(|interval| eta_range(Utc::now(), position, interval));
+        Ok(Some(eta))
     }
 
-    async fn get_batch(&self, batch_hash: TxHash) -> Result<Option<ApiTxBatch>, Error> {
+    /// Serializes the operation of the transaction the same way it's done for the block commitment.
+    /// The chunk offset is counted over the preceding operations of the block, in the order they
+    /// are put into the public data of the block.
+    async fn tx_public_data(&self, tx_hash: TxHash) -> Result<Option<TxPublicData>, Error> {
         let mut storage = self
-            .tx_sender
             .pool
-            .access_storage()
+            .access_storage(AccessIntent::Read)
             .await
             .map_err(Error::storage)?;
-        storage
+        let mut transaction = storage
+            .start_snapshot_read()
+            .await
+            .map_err(Error::storage)?;
+        let tx = match transaction
             .chain()
             .operations_ext_schema()
-            .get_batch_info(batch_hash)
+            .tx_data_api_v02(tx_hash.as_ref())
+            .await
+            .map_err(Error::storage)?
+        {
+            Some(data) => data.tx,
+            None => return Ok(None),
+        };
+        let (block_number, block_index) = match (tx.status, tx.block_number, tx.block_index) {
+            (
+                TxInBlockStatus::Committed | TxInBlockStatus::Finalized,
+                Some(block_number),
+                Some(block_index),
+            ) => (block_number, block_index),
+            _ => return Err(Error::from(InvalidDataError::TxHasNoPublicData)),
+        };
+        let executed_ops = transaction
+            .chain()
+            .block_schema()
+            .get_block_executed_ops(block_number)
+            .await
+            .map_err(Error::storage)?;
+        transaction.commit().await.map_err(Error::storage)?;
+
+        let mut chunk_offset = 0;
+        for executed_op in &executed_ops {
+            // Failed transactions don't have an operation and don't take any chunks.
+            let op = match executed_op.get_executed_op() {
+                Some(op) => op,
+                None => continue,
+            };
+            let op_block_index = match executed_op {
+                ExecutedOperations::Tx(tx) => tx.block_index,
+                ExecutedOperations::PriorityOp(op) => Some(op.block_index),
+            };
+            if op_block_index == Some(block_index) {
+                return Ok(Some(TxPublicData::new(
+                    tx_hash,
+                    block_number,
+                    chunk_offset,
+                    op,
+                )));
+            }
+            chunk_offset += op.chunks();
+        }
+        Err(Error::from(InvalidDataError::TxHasNoPublicData))
+    }
+
+    async fn load_tx_status(
+        &self,
+        tx_hash: TxHash,
+        intent: AccessIntent,
+        retry: &ReadRetry,
+    ) -> Result<Option<Receipt>, Error> {
+        let mut receipt: Option<Receipt> = retry
+            .read("tx_status", || async move {
+                let mut storage = self.pool.access_storage(intent).await?;
+                tx_info::load_tx_info_as(&mut storage, tx_hash).await
+            })
+            .await
+            .map_err(Error::storage)?;
+        // Only the pending priority operations are queued L1 receipts.
+        if let Some(Receipt::L1(receipt)) = &mut receipt {
+            if receipt.status == TxInBlockStatus::Queued {
+                receipt.confirmations_remaining = self
+                    .network_status
+                    .read()
+                    .await
+                    .eth_watch_status
+                    .map(|status| status.confirmations_remaining(*receipt.eth_block));
+            }
+        }
+
+        Ok(receipt)
+    }
+
+    /// Asks the core mempool about the transaction that is not stored in the database yet.
+    async fn mempool_tx_receipt(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error> {
+        let lookup = self
+            .network_status
+            .core_client()
+            .mempool_tx_lookup(tx_hash)
+            .await
+            .map_err(Error::core_api)?;
+        let receipt = match lookup {
+            MempoolTxLookup::Pending | MempoolTxLookup::Proposed => Some(Receipt::L2(L2Receipt {
+                tx_hash,
+                rollup_block: None,
+                status: TxInBlockStatus::Queued,
+                fail_reason: None,
+                archived: false,
+            })),
+            MempoolTxLookup::Unknown => None,
+        };
+        Ok(receipt)
+    }
+
+    async fn load_tx_data(
+        &self,
+        tx_hash: TxHash,
+        intent: AccessIntent,
+        retry: &ReadRetry,
+    ) -> Result<Option<TxData>, Error> {
+        retry
+            .read("tx_data", || async move {
+                let mut storage = self.pool.access_storage(intent).await?;
+                tx_info::load_tx_data(&mut storage, tx_hash).await
+            })
+            .await
+            .map_err(Error::storage)
+    }
+
+    async fn load_batch(
+        &self,
+        batch_hash: TxHash,
+        intent: AccessIntent,
+        retry: &ReadRetry,
+    ) -> Result<Option<ApiTxBatch>, Error> {
+        retry
+            .read("get_batch", || async move {
+                let mut storage = self.pool.access_storage(intent).await?;
+                storage
+                    .chain()
+                    .operations_ext_schema()
+                    .get_batch_info(batch_hash)
+                    .await
+            })
             .await
             .map_err(Error::storage)
     }
 }
 
+/// Execute operation the block is expected to be included into, see `WithdrawalExecuteEta`.
+/// Execute operations are created for the consecutive blocks, so the blocks are queued
+/// after the last block an operation is created for.
+fn execute_queue_position(
+    block_number: BlockNumber,
+    last_scheduled_block: BlockNumber,
+    max_blocks_per_execute: u32,
+) -> u32 {
+    if block_number <= last_scheduled_block {
+        return 0;
+    }
+    let blocks_ahead = *block_number - *last_scheduled_block;
+    (blocks_ahead + max_blocks_per_execute - 1) / max_blocks_per_execute
+}
+
+/// Average interval between the consecutive times, the order of the times doesn't matter.
+fn average_interval(times: &[DateTime<Utc>]) -> Option<Duration> {
+    if times.len() < 2 {
+        return None;
+    }
+    let (newest, oldest) = (times.iter().max()?, times.iter().min()?);
+    let span = (*newest - *oldest).to_std().ok()?;
+    Some(span / (times.len() as u32 - 1))
+}
+
+/// The block is executed by the operation at the given position, the operations are expected
+/// to follow each other with the given interval starting from now.
+fn eta_range(now: DateTime<Utc>, position: u32, interval: Duration) -> EtaRange {
+    let after = |operations: u32| {
+        now + chrono::Duration::from_std(interval * operations).expect("ETA is too far")
+    };
+    EtaRange {
+        from: after(position.saturating_sub(1)),
+        to: after(position.max(1)),
+    }
+}
+
+/// Normalizes the searched hash prefix to the lowercase hex digits the hashes are stored with.
+/// The prefixes are accepted in any of the formats the hashes are displayed with.
+fn parse_tx_hash_prefix(prefix: &str, min_len: usize) -> Result<String, Error> {
+    let prefix = prefix.trim();
+    let prefix = prefix
+        .strip_prefix("0x")
+        .or_else(|| prefix.strip_prefix("sync-tx:"))
+        .unwrap_or(prefix);
+    let valid = prefix.len() >= min_len
+        && prefix.len() <= 2 * H256::len_bytes()
+        && prefix.chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(Error::from(InvalidDataError::InvalidTxHashPrefix(min_len)));
+    }
+    Ok(prefix.to_ascii_lowercase())
+}
+
 // Server implementation
 
 async fn tx_status(
+    req: HttpRequest,
     data: web::Data<ApiTransactionData>,
-    tx_hash: web::Path<TxHash>,
-) -> ApiResult<Option<Receipt>> {
+    tx_hash: ValidPath<TxHash>,
+    web::Query(consistency): web::Query<ConsistencyQuery>,
+) -> ApiResult<Value> {
     let start = Instant::now();
-    let res = data.tx_status(*tx_hash).await.into();
+    let res = data
+        .tx_status(*tx_hash, consistency.consistency)
+        .await
+        .or_not_found("transaction", *tx_hash)
+        .map(|receipt| receipt.to_sub_version(sub_version(&req)))
+        .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_status");
     res
 }
 
 async fn tx_data(
+    req: HttpRequest,
     data: web::Data<ApiTransactionData>,
-    tx_hash: web::Path<TxHash>,
-) -> ApiResult<Option<TxData>> {
+    tx_hash: ValidPath<TxHash>,
+    web::Query(query): web::Query<TxDataQuery>,
+    web::Query(consistency): web::Query<ConsistencyQuery>,
+    web::Query(amounts): web::Query<AmountsQuery>,
+) -> ApiResult<Value> {
     let start = Instant::now();
-    let res = data.tx_data(*tx_hash).await.into();
+    let res = data
+        .tx_data(*tx_hash, query, consistency.consistency)
+        .await
+        .or_not_found("transaction", *tx_hash)
+        .map(|tx_data| tx_data.to_sub_version(sub_version(&req)));
+    let res = render_amounts(
+        res,
+        amounts.amounts_in,
+        &data.tx_sender.tokens,
+        &data.pool,
+        None,
+    )
+    .await
+    .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_data");
     res
 }
 
+async fn execute_eta(
+    data: web::Data<ApiTransactionData>,
+    tx_hash: ValidPath<TxHash>,
+) -> ApiResult<WithdrawalExecuteEta> {
+    let start = Instant::now();
+    let res = data
+        .execute_eta(*tx_hash)
+        .await
+        .or_not_found("transaction", *tx_hash)
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "execute_eta");
+    res
+}
+
+async fn tx_public_data(
+    data: web::Data<ApiTransactionData>,
+    tx_hash: ValidPath<TxHash>,
+) -> ApiResult<TxPublicData> {
+    let start = Instant::now();
+    let res = data
+        .tx_public_data(*tx_hash)
+        .await
+        .or_not_found("transaction", *tx_hash)
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_public_data");
+    res
+}
+
+async fn tx_diagnosis(
+    data: web::Data<ApiTransactionData>,
+    tx_hash: ValidPath<TxHash>,
+) -> ApiResult<TxDiagnosis> {
+    let start = Instant::now();
+    let res = data.tx_diagnosis(*tx_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_diagnosis");
+    res
+}
+
 async fn submit_tx(
     data: web::Data<ApiTransactionData>,
     Json(body): Json<TxWithSignature>,
-) -> ApiResult<TxHashSerializeWrapper> {
+    web::Query(query): web::Query<SubmitQuery>,
+) -> ApiResult<SubmitTxResponse> {
     let start = Instant::now();
     let tx_hash = data
         .tx_sender
-        .submit_tx(body.tx, body.signature, None)
+        .submit_tx_with_fee_quote(body.tx, body.signature, body.fee_quote, None)
         .await;
 
     if let Err(err) = &tx_hash {
@@ -182,17 +795,43 @@ async fn submit_tx(
 
     let tx_hash = tx_hash.map_err(Error::from);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "submit_tx");
-    tx_hash.map(TxHashSerializeWrapper).into()
+    tx_hash
+        .map(|tx_hash| {
+            // The receipt is constructed locally, the submitted transaction is always queued.
+            if query.include_receipt() {
+                SubmitTxResponse::WithReceipt {
+                    tx_hash,
+                    receipt: L2Receipt {
+                        tx_hash,
+                        rollup_block: None,
+                        status: TxInBlockStatus::Queued,
+                        fail_reason: None,
+                        archived: false,
+                    },
+                }
+            } else {
+                SubmitTxResponse::TxHash(TxHashSerializeWrapper(tx_hash))
+            }
+        })
+        .into()
 }
 
 async fn submit_batch(
+    req: HttpRequest,
     data: web::Data<ApiTransactionData>,
     Json(body): Json<IncomingTxBatch>,
+    web::Query(query): web::Query<SubmitQuery>,
 ) -> ApiResult<SubmitBatchResponse> {
     let start = Instant::now();
+    // Anonymous requests never get the timings, even if they are requested.
+    let api_key = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|key| key.to_str().ok());
+    let debug_timings = query.debug_timings && data.tx_sender.debug_timings_allowed(api_key);
     let response = data
         .tx_sender
-        .submit_txs_batch(body.txs, body.signature, None)
+        .submit_txs_batch_with_timings(body.txs, body.signature, None, debug_timings)
         .await;
 
     if let Err(err) = &response {
@@ -205,11 +844,81 @@ async fn submit_batch(
         metrics::increment_counter!("rejected_txs", &labels);
     }
 
-    let response = response.map_err(Error::from);
+    let response = response.map_err(Error::from).map(|mut response| {
+        if query.include_receipt() {
+            let now = Utc::now();
+            response.batch = Some(ApiTxBatch {
+                batch_hash: response.batch_hash,
+                transaction_hashes: response.transaction_hashes.clone(),
+                created_at: now,
+                batch_status: BatchStatus {
+                    updated_at: now,
+                    last_state: TxInBlockStatus::Queued,
+                    fail_reason: None,
+                },
+            });
+        }
+        response
+    });
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "submit_batch");
     response.into()
 }
 
+/// Computes the hash of the batch the same way as `submit_batch` does,
+/// so clients can know it before submitting the batch.
+fn compute_batch_hash(request: BatchHashRequest) -> Result<BatchHashResponse, Error> {
+    let tx_hashes: Vec<TxHash> = match (request.tx_hashes.is_empty(), request.txs.is_empty()) {
+        (false, true) => request.tx_hashes,
+        (true, false) => request.txs.iter().map(ZkSyncTx::hash).collect(),
+        _ => return Err(Error::from(InvalidDataError::InvalidBatchHashRequest)),
+    };
+    Ok(BatchHashResponse {
+        batch_hash: TxHash::batch_hash(&tx_hashes),
+        preimage: format!("0x{}", hex::encode(TxHash::batch_hash_preimage(&tx_hashes))),
+        preimage_format: BATCH_HASH_PREIMAGE_FORMAT.to_string(),
+        transaction_hashes: tx_hashes.into_iter().map(TxHashSerializeWrapper).collect(),
+    })
+}
+
+async fn batch_hash(Json(body): Json<BatchHashRequest>) -> ApiResult<BatchHashResponse> {
+    let start = Instant::now();
+    let res = compute_batch_hash(body).into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "batch_hash");
+    res
+}
+
+async fn sign_message(
+    data: web::Data<ApiTransactionData>,
+    Json(body): Json<SignMessageRequest>,
+) -> ApiResult<SignMessageResponse> {
+    let start = Instant::now();
+    let res = data.sign_message(body).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "sign_message");
+    res
+}
+
+async fn cancel_tx(
+    data: web::Data<ApiTransactionData>,
+    tx_hash: ValidPath<TxHash>,
+    Json(body): Json<CancelTxRequest>,
+) -> ApiResult<CancelTxResponse> {
+    let start = Instant::now();
+    let res = data.cancel_tx(*tx_hash, body).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "cancel_tx");
+    res
+}
+
+async fn search(
+    data: web::Data<ApiTransactionData>,
+    credentials: Option<BearerAuth>,
+    web::Query(query): web::Query<TxHashSearchQuery>,
+) -> ApiResult<TxHashSearchResponse> {
+    let start = Instant::now();
+    let res = data.search(credentials, &query.prefix).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "search");
+    res
+}
+
 async fn toggle_2fa(
     data: web::Data<ApiTransactionData>,
     Json(toggle_2fa): Json<Toggle2FA>,
@@ -225,58 +934,190 @@ async fn toggle_2fa(
     response.into()
 }
 
+async fn lock_account(
+    data: web::Data<ApiTransactionData>,
+    Json(request): Json<LockAccount>,
+) -> ApiResult<AccountLockInfo> {
+    let start = Instant::now();
+    let response = data
+        .tx_sender
+        .lock_account(request)
+        .await
+        .map_err(Error::from);
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "lock_account");
+    response.into()
+}
+
+async fn unlock_account(
+    data: web::Data<ApiTransactionData>,
+    Json(request): Json<UnlockAccount>,
+) -> ApiResult<AccountLockInfo> {
+    let start = Instant::now();
+    let response = data
+        .tx_sender
+        .unlock_account(request)
+        .await
+        .map_err(Error::from);
+
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "unlock_account");
+    response.into()
+}
+
 async fn get_batch(
     data: web::Data<ApiTransactionData>,
-    batch_hash: web::Path<TxHash>,
-) -> ApiResult<Option<ApiTxBatch>> {
+    batch_hash: ValidPath<TxHash>,
+) -> ApiResult<ApiTxBatch> {
     let start = Instant::now();
-    let res = data.get_batch(*batch_hash).await.into();
+    let res = data
+        .get_batch(*batch_hash)
+        .await
+        .or_not_found("batch", *batch_hash)
+        .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_batch");
     res
 }
 
-pub fn api_scope(tx_sender: TxSender) -> Scope {
-    let data = ApiTransactionData::new(tx_sender);
+/// Creates the JSON extractor config rejecting the bodies larger than `limit` bytes.
+/// Bodies with the known length are rejected before being read, streamed ones as soon
+/// as the limit is exceeded, so an oversized body is never buffered completely.
+fn json_config(limit: usize) -> web::JsonConfig {
+    web::JsonConfig::default()
+        .limit(limit)
+        .error_handler(move |err, req: &HttpRequest| {
+            if err.status_code() != StatusCode::PAYLOAD_TOO_LARGE {
+                return err.into();
+            }
+
+            let error = Error::from(InvalidDataError::PayloadTooLarge(limit));
+            let mut response = ApiResult::<()>::Error(error).respond_to(req);
+            *response.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+            InternalError::from_response(err, response).into()
+        })
+}
+
+pub fn api_scope(
+    pool: ReplicatedPool,
+    archive: Option<TxArchive>,
+    tx_sender: TxSender,
+    network_status: SharedNetworkStatus,
+    config: &RestApiConfig,
+    secret_auth: String,
+    max_blocks_per_execute: usize,
+) -> Scope {
+    let data = ApiTransactionData::new(
+        pool,
+        archive,
+        tx_sender,
+        network_status,
+        config,
+        secret_auth,
+        max_blocks_per_execute,
+    );
 
     web::scope("transactions")
         .app_data(web::Data::new(data))
-        .route("", web::post().to(submit_tx))
+        .service(
+            web::resource("")
+                .app_data(json_config(config.max_tx_payload_size))
+                .route(web::post().to(submit_tx)),
+        )
+        .service(
+            web::resource("/signMessage")
+                .app_data(json_config(config.max_batch_payload_size))
+                .route(web::post().to(sign_message)),
+        )
+        // Registered before `{tx_hash}`, so that `search` isn't parsed as a hash.
+        .route("search", web::get().to(search))
         .route("{tx_hash}", web::get().to(tx_status))
         .route("{tx_hash}/data", web::get().to(tx_data))
-        .route("/batches", web::post().to(submit_batch))
+        .route("{tx_hash}/diagnosis", web::get().to(tx_diagnosis))
+        .route("{tx_hash}/executeEta", web::get().to(execute_eta))
+        .route("{tx_hash}/publicData", web::get().to(tx_public_data))
+        .service(
+            web::resource("{tx_hash}/cancel")
+                .app_data(json_config(config.max_tx_payload_size))
+                .route(web::post().to(cancel_tx)),
+        )
+        .service(
+            web::resource("/batches")
+                .app_data(json_config(config.max_batch_payload_size))
+                .route(web::post().to(submit_batch)),
+        )
+        .service(
+            web::resource("/batches/hash")
+                .app_data(json_config(config.max_batch_payload_size))
+                .route(web::post().to(batch_hash)),
+        )
         .route("/batches/{batch_hash}", web::get().to(get_batch))
-        .route("/toggle2FA", web::post().to(toggle_2fa))
+        .service(
+            web::resource("/toggle2FA")
+                .app_data(json_config(config.max_tx_payload_size))
+                .route(web::post().to(toggle_2fa)),
+        )
+        .service(
+            web::resource("/lockAccount")
+                .app_data(json_config(config.max_tx_payload_size))
+                .route(web::post().to(lock_account)),
+        )
+        .service(
+            web::resource("/unlockAccount")
+                .app_data(json_config(config.max_tx_payload_size))
+                .route(web::post().to(unlock_account)),
+        )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
+        error::ErrorCode,
+        request_span,
         test_utils::{
-            deserialize_response_result, dummy_fee_ticker, dummy_sign_verifier, TestServerConfig,
-            TestTransactions,
+            assert_not_found, auth_token, counting_sign_verifier, deserialize_response_result,
+            dummy_fee_ticker, dummy_sign_verifier, BlockTx, SpansCapture, TestServerConfig,
+            TestTransactions, TxLifecycle, SECRET_AUTH,
         },
         SharedData,
     };
+    use crate::api_server::tx_sender::screening::{
+        ScreeningClient, ScreeningPolicy, ScreeningVerdict, WithdrawalScreening,
+    };
     use crate::fee_ticker::validator::cache::TokenInMemoryCache;
-    use chrono::Utc;
+    use crate::signature_checker::{VerifiedTx, VerifySignatureRequest};
+    use crate::tx_error::AccountLockError;
+    use crate::utils::{banned_addresses::BannedAddresses, disabled_tx_types::DisabledTxTypes};
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App, HttpResponse};
     use futures::{channel::mpsc, StreamExt};
     use num::{rational::Ratio, BigUint};
     use std::collections::HashMap;
     use std::str::FromStr;
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+    use std::time::Duration;
     use tokio::task::JoinHandle;
+    use tracing_subscriber::layer::SubscriberExt;
+    use zksync_api_client::rest::client::Client;
     use zksync_api_types::v02::{
+        sub_version::SUB_VERSION_HEADER,
         transaction::{L2Receipt, TxHashSerializeWrapper},
-        ApiVersion,
+        ApiVersion, Response,
     };
+    use zksync_crypto::params::{CHUNK_BYTES, MIN_NFT_TOKEN_ID};
     use zksync_mempool::MempoolTransactionRequest;
+    use zksync_test_account::ZkSyncAccount;
     use zksync_types::{
+        event::transaction::TransactionType,
         tokens::{Token, TokenMarketVolume},
         tx::{
-            EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature,
-            TxEthSignatureVariant,
+            error::TxAddError, EthBatchSignData, EthBatchSignatures, PackedEthSignature, Transfer,
+            TxEthSignature, TxEthSignatureVariant, TxSignature,
         },
-        Address, BlockNumber, ChainId, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        withdrawals::{WithdrawalPendingEvent, WithdrawalType},
+        AccountId, Address, BlockNumber, ChainId, TokenId, TokenKind, TokenLike,
     };
 
     fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
@@ -309,12 +1150,15 @@ mod tests {
     async fn transactions_scope() -> anyhow::Result<()> {
         let (sender, task) = submit_txs_loopback();
 
-        let cfg = TestServerConfig::default();
+        let mut cfg = TestServerConfig::default();
+        cfg.config.api.common.debug_timings_api_keys = vec!["debug-key".to_owned()];
         cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
 
         let shared_data = SharedData {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
         };
 
         let mut tokens = HashMap::new();
@@ -344,17 +1188,45 @@ mod tests {
             (Address::default().into(), 100000_u64.into()),
         ];
 
+        // Use the test database both as the primary and as the replica.
+        let pool = ReplicatedPool::new(
+            cfg.pool.clone(),
+            vec![cfg.pool.clone()],
+            Duration::from_secs(1),
+        );
+        pool.check_replicas().await;
+        assert_eq!(pool.healthy_replicas(), 1);
+
+        let disabled_tx_types = DisabledTxTypes::new(cfg.pool.clone());
+        let banned_addresses = BannedAddresses::new(cfg.pool.clone());
         let (client, server) = cfg.start_server(
-            move |cfg: &TestServerConfig| {
-                api_scope(TxSender::new(
-                    cfg.pool.clone(),
-                    dummy_sign_verifier(),
-                    dummy_fee_ticker(&prices, Some(cache.clone())),
-                    &cfg.config.api.common,
-                    &cfg.config.api.token_config,
-                    sender.clone(),
-                    ChainId(cfg.config.eth_client.chain_id),
-                ))
+            {
+                let disabled_tx_types = disabled_tx_types.clone();
+                let banned_addresses = banned_addresses.clone();
+                move |cfg: &TestServerConfig| {
+                    api_scope(
+                        pool.clone(),
+                        None,
+                        TxSender::new(
+                            cfg.pool.clone(),
+                            dummy_sign_verifier(),
+                            dummy_fee_ticker(&prices, Some(cache.clone())),
+                            &cfg.config.api.common,
+                            cfg.token_cache(),
+                            sender.clone(),
+                            disabled_tx_types.clone(),
+                            banned_addresses.clone(),
+                            ChainId(cfg.config.eth_client.chain_id),
+                        ),
+                        SharedNetworkStatus::new("0.0.0.0".to_string()),
+                        &cfg.config.api.rest,
+                        String::new(),
+                        cfg.config
+                            .chain
+                            .state_keeper
+                            .max_aggregated_blocks_to_execute,
+                    )
+                }
             },
             Some(shared_data),
         );
@@ -366,16 +1238,62 @@ mod tests {
         let tx_hash: TxHash = deserialize_response_result(response)?;
         assert_eq!(tx.hash(), tx_hash);
 
-        let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
-        let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
-        let (good_batch, expected_tx_hashes): (Vec<_>, Vec<_>) = txs
-            .into_iter()
+        // The transaction is queued until it's forwarded to the mempool.
+        let response = client.tx_status(tx_hash).await?;
+        let tx_status: Receipt = deserialize_response_result(response)?;
+        let expected_tx_status = Receipt::L2(L2Receipt {
+            tx_hash,
+            rollup_block: None,
+            status: TxInBlockStatus::Queued,
+            fail_reason: None,
+            archived: false,
+        });
+        assert_eq!(tx_status, expected_tx_status);
+
+        // Resubmission of the same transaction succeeds.
+        let response = client
+            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let resubmitted_tx_hash: TxHash = deserialize_response_result(response)?;
+        assert_eq!(resubmitted_tx_hash, tx_hash);
+
+        // The initial receipt is embedded into the response on request.
+        let response = client
+            .submit_tx_with_query(
+                tx.clone(),
+                TxEthSignatureVariant::Single(None),
+                &SubmitQuery {
+                    include: Some("receipt".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let submit_tx_response: SubmitTxResponse = deserialize_response_result(response)?;
+        assert_eq!(
+            submit_tx_response,
+            SubmitTxResponse::WithReceipt {
+                tx_hash,
+                receipt: L2Receipt {
+                    tx_hash,
+                    rollup_block: None,
+                    status: TxInBlockStatus::Queued,
+                    fail_reason: None,
+                    archived: false,
+                },
+            }
+        );
+
+        let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
+        let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
+        let (good_batch, expected_tx_hashes): (Vec<_>, Vec<_>) = txs
+            .into_iter()
             .map(|(tx, _op)| {
                 let tx_hash = tx.hash();
                 (
                     TxWithSignature {
                         tx,
                         signature: TxEthSignatureVariant::Single(None),
+                        fee_quote: None,
                     },
                     tx_hash,
                 )
@@ -388,6 +1306,8 @@ mod tests {
                 .map(TxHashSerializeWrapper)
                 .collect(),
             batch_hash: expected_batch_hash,
+            batch: None,
+            timings: None,
         };
 
         let txs = good_batch
@@ -412,22 +1332,280 @@ mod tests {
         let submit_batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
         assert_eq!(submit_batch_response, expected_response);
 
-        {
-            let mut storage = cfg.pool.access_storage().await?;
-            let txs: Vec<_> = good_batch
-                .into_iter()
-                .map(|tx| SignedZkSyncTx {
-                    tx: tx.tx,
-                    eth_sign_data: None,
-                    created_at: Utc::now(),
-                })
+        let response = client
+            .submit_batch_with_query(
+                good_batch.clone(),
+                Some(batch_signature.clone()),
+                &SubmitQuery {
+                    include: Some("receipt".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        let batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
+        let batch = batch_response
+            .batch
+            .expect("Batch is requested to be included");
+        assert_eq!(batch.batch_hash, expected_response.batch_hash);
+        assert_eq!(
+            batch.transaction_hashes,
+            expected_response.transaction_hashes
+        );
+        assert_eq!(batch.batch_status.last_state, TxInBlockStatus::Queued);
+        assert_eq!(batch_response.timings, None);
+
+        // The stage timings are reported only to the requests made with the allowed API key.
+        let url = server.url("/api/v0.2/transactions/batches?debug_timings=true");
+        let body = IncomingTxBatch {
+            txs: good_batch.clone(),
+            signature: Some(batch_signature.clone()),
+        };
+        for api_key in [None, Some("unknown-key"), Some("debug-key")] {
+            let mut request = reqwest::Client::new().post(&url).json(&body);
+            if let Some(api_key) = api_key {
+                request = request.header(API_KEY_HEADER, api_key);
+            }
+            let response: Response = request.send().await?.json().await?;
+            let batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
+            assert_eq!(batch_response.batch_hash, expected_response.batch_hash);
+
+            if api_key == Some("debug-key") {
+                let timings = batch_response
+                    .timings
+                    .expect("Timings are requested with the allowed key");
+                let stages_sum = timings.parse_us
+                    + timings.token_checks_us
+                    + timings.fee_aggregation_us
+                    + timings.signature_verification_us
+                    + timings.core_forward_us;
+                assert!(stages_sum <= timings.total_us, "{:?}", timings);
+                assert!(timings.total_us - stages_sum <= 1_000, "{:?}", timings);
+            } else {
+                assert_eq!(batch_response.timings, None, "api key: {:?}", api_key);
+            }
+        }
+
+        // The batch hash computed by the API matches the hash of the submitted batch,
+        // both for the transactions and for their hashes.
+        let requests = [
+            BatchHashRequest {
+                txs: good_batch.iter().map(|tx| tx.tx.clone()).collect(),
+                ..Default::default()
+            },
+            BatchHashRequest {
+                tx_hashes: submit_batch_response
+                    .transaction_hashes
+                    .iter()
+                    .map(|tx_hash| tx_hash.0)
+                    .collect(),
+                ..Default::default()
+            },
+        ];
+        for request in &requests {
+            let response = client.batch_hash(request).await?;
+            let batch_hash_response: BatchHashResponse = deserialize_response_result(response)?;
+            assert_eq!(
+                batch_hash_response.batch_hash,
+                submit_batch_response.batch_hash
+            );
+            assert_eq!(
+                batch_hash_response.transaction_hashes,
+                submit_batch_response.transaction_hashes
+            );
+            // The preimage is the concatenation of the transaction hashes.
+            let preimage = hex::decode(&batch_hash_response.preimage[2..])?;
+            let expected_preimage: Vec<u8> = submit_batch_response
+                .transaction_hashes
+                .iter()
+                .flat_map(|tx_hash| tx_hash.0.as_ref().to_vec())
                 .collect();
-            storage
-                .chain()
-                .mempool_schema()
-                .insert_batch(&txs, Vec::new())
-                .await?;
+            assert_eq!(preimage, expected_preimage);
+        }
+
+        let response = client.batch_hash(&BatchHashRequest::default()).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(InvalidDataError::InvalidBatchHashRequest)
+        );
+
+        // The messages to sign are the same the submitted transactions are verified against,
+        // so the transactions signed with them are accepted.
+        let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
+        let eth_private_key = acc
+            .try_get_eth_private_key()
+            .expect("Should have ETH private key");
+        let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
+        let txs: Vec<ZkSyncTx> = txs.into_iter().map(|(tx, _op)| tx).collect();
+
+        let transfer = txs[1].clone();
+        let request = SignMessageRequest {
+            tx: Some(transfer.clone()),
+            ..Default::default()
+        };
+        let response = client.sign_message(&request).await?;
+        let sign_message: SignMessageResponse = deserialize_response_result(response)?;
+        let message = sign_message
+            .message
+            .expect("Transfer should be signed with the Ethereum key");
+        assert_eq!(
+            Some(message.clone()),
+            transfer.get_ethereum_sign_message(eth.clone())
+        );
+        assert_eq!(
+            sign_message.message_bytes,
+            Some(format!("0x{}", hex::encode(&message)))
+        );
+        let eth_sig = PackedEthSignature::sign(eth_private_key, message.as_bytes()).unwrap();
+        assert_eq!(
+            eth_sig.signature_recover_signer_from_raw_message(message.as_bytes())?,
+            acc.address
+        );
+        let response = client
+            .submit_tx(
+                transfer.clone(),
+                TxEthSignatureVariant::Single(Some(TxEthSignature::EthereumSignature(eth_sig))),
+            )
+            .await?;
+        let tx_hash: TxHash = deserialize_response_result(response)?;
+        assert_eq!(tx_hash, transfer.hash());
+
+        // The batch is signed by another account, so its transactions are not submitted yet.
+        let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
+        let eth_private_key = acc
+            .try_get_eth_private_key()
+            .expect("Should have ETH private key");
+        let txs: Vec<ZkSyncTx> = txs.into_iter().map(|(tx, _op)| tx).collect();
+        let request = SignMessageRequest {
+            txs: txs.clone(),
+            ..Default::default()
         };
+        let response = client.sign_message(&request).await?;
+        let sign_message: SignMessageResponse = deserialize_response_result(response)?;
+        let message = sign_message
+            .message
+            .expect("Batch should be signed with the Ethereum key");
+        let expected_message = EthBatchSignData::get_batch_sign_message(
+            txs.iter()
+                .map(|tx| (tx.clone(), eth.clone(), tx.account()))
+                .collect(),
+        );
+        assert_eq!(message.as_bytes(), expected_message.as_slice());
+        let eth_sig = PackedEthSignature::sign(eth_private_key, message.as_bytes()).unwrap();
+        let batch = txs
+            .iter()
+            .map(|tx| TxWithSignature {
+                tx: tx.clone(),
+                signature: TxEthSignatureVariant::Single(None),
+                fee_quote: None,
+            })
+            .collect();
+        let response = client
+            .submit_batch(
+                batch,
+                Some(EthBatchSignatures::Single(
+                    TxEthSignature::EthereumSignature(eth_sig),
+                )),
+            )
+            .await?;
+        let submit_batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
+        assert_eq!(
+            submit_batch_response.batch_hash,
+            TxHash::batch_hash(&txs.iter().map(ZkSyncTx::hash).collect::<Vec<_>>())
+        );
+
+        // Either a single transaction or a batch must be provided.
+        for request in &[
+            SignMessageRequest::default(),
+            SignMessageRequest {
+                tx: Some(transfer),
+                txs,
+            },
+        ] {
+            let response = client.sign_message(request).await?;
+            let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+            assert_eq!(
+                error,
+                Error::from(InvalidDataError::InvalidSignMessageRequest)
+            );
+        }
+
+        // Disable `Transfer` transactions at runtime, both single transactions
+        // and batches containing them must be rejected.
+        cfg.pool
+            .access_storage()
+            .await?
+            .misc_schema()
+            .set_tx_type_disabled("Transfer", true)
+            .await?;
+        // Invalidation is normally performed by the database listener.
+        disabled_tx_types.invalidate().await;
+
+        let response = client
+            .submit_tx(
+                good_batch[1].tx.clone(),
+                TxEthSignatureVariant::Single(None),
+            )
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxTypeDisabled(TransactionType::Transfer))
+        );
+
+        let response = client.submit_batch(good_batch.clone(), None).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::BatchTxTypeDisabled {
+                index: 1,
+                tx_type: TransactionType::Transfer,
+            })
+        );
+
+        cfg.pool
+            .access_storage()
+            .await?
+            .misc_schema()
+            .set_tx_type_disabled("Transfer", false)
+            .await?;
+        disabled_tx_types.invalidate().await;
+
+        // Ban the recipient of the transfer, the transaction must be rejected
+        // without revealing which address is banned.
+        let banned_address = good_batch[1].tx.addresses()[1];
+        cfg.pool
+            .access_storage()
+            .await?
+            .banned_addresses_schema()
+            .ban_address(banned_address, "test", "test")
+            .await?;
+        banned_addresses.invalidate().await;
+
+        let response = client
+            .submit_tx(
+                good_batch[1].tx.clone(),
+                TxEthSignatureVariant::Single(None),
+            )
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error, Error::from(SubmitError::AddressBlocked));
+
+        let response = client.submit_batch(good_batch.clone(), None).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error, Error::from(SubmitError::AddressBlocked));
+
+        cfg.pool
+            .access_storage()
+            .await?
+            .banned_addresses_schema()
+            .unban_address(banned_address, "test", "test")
+            .await?;
+        banned_addresses.invalidate().await;
+
+        lifecycle
+            .submit_batch(good_batch.into_iter().map(|tx| tx.tx).collect())
+            .await?;
 
         let response = client.get_batch(submit_batch_response.batch_hash).await?;
         let batch: ApiTxBatch = deserialize_response_result(response)?;
@@ -456,30 +1634,17 @@ mod tests {
             rollup_block: Some(BlockNumber(1)),
             status: TxInBlockStatus::Finalized,
             fail_reason: None,
+            archived: false,
         });
         assert_eq!(tx_status, expected_tx_status);
 
         let response = client.tx_data(tx_hash).await?;
-        let tx_data: Option<TxData> = deserialize_response_result(response)?;
-        assert_eq!(tx_data.unwrap().tx.tx_hash, tx_hash);
-
-        let pending_tx_hash = {
-            let mut storage = cfg.pool.access_storage().await?;
-
-            let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
-            let tx_hash = tx.hash();
-            storage
-                .chain()
-                .mempool_schema()
-                .insert_tx(&SignedZkSyncTx {
-                    tx,
-                    eth_sign_data: None,
-                    created_at: Utc::now(),
-                })
-                .await?;
+        let tx_data: TxData = deserialize_response_result(response)?;
+        assert_eq!(tx_data.tx.tx_hash, tx_hash);
 
-            tx_hash
-        };
+        let pending_tx_hash = lifecycle
+            .submit(TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone())
+            .await?;
         let response = client.tx_status(pending_tx_hash).await?;
         let tx_status: Receipt = deserialize_response_result(response)?;
         let expected_tx_status = Receipt::L2(L2Receipt {
@@ -487,20 +1652,1523 @@ mod tests {
             rollup_block: None,
             status: TxInBlockStatus::Queued,
             fail_reason: None,
+            archived: false,
         });
         assert_eq!(tx_status, expected_tx_status);
 
         let response = client.tx_data(pending_tx_hash).await?;
-        let tx_data: Option<TxData> = deserialize_response_result(response)?;
-        assert_eq!(tx_data.unwrap().tx.tx_hash, pending_tx_hash);
+        let tx_data: TxData = deserialize_response_result(response)?;
+        assert_eq!(tx_data.tx.tx_hash, pending_tx_hash);
+        assert!(tx_data.l2_signature.is_none());
+
+        let query = TxDataQuery {
+            include: Some("l2Signature".to_string()),
+        };
+        let response = client.tx_data_with_query(pending_tx_hash, &query).await?;
+        let tx_data: TxData = deserialize_response_result(response)?;
+        assert!(tx_data.l2_signature.is_some());
+
+        // The pending transaction is checked against the current state.
+        let response = client.tx_diagnosis(pending_tx_hash).await?;
+        let diagnosis: TxDiagnosis = deserialize_response_result(response)?;
+        assert_eq!(diagnosis.status, Some(TxInBlockStatus::Queued));
+        assert!(diagnosis.note.is_none() || diagnosis.failed_conditions.is_empty());
+
+        let response = client.tx_diagnosis(tx_hash).await?;
+        let diagnosis: TxDiagnosis = deserialize_response_result(response)?;
+        assert_eq!(diagnosis.status, Some(TxInBlockStatus::Finalized));
+        assert!(diagnosis.failed_conditions.is_empty());
+        assert_eq!(diagnosis.note.as_deref(), Some("Transaction is executed"));
 
         let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
         let response = client.tx_data(tx.hash()).await?;
-        let tx_data: Option<TxData> = deserialize_response_result(response)?;
-        assert!(tx_data.is_none());
+        assert_not_found(response, "transaction")?;
+        let response = client.tx_status(tx.hash()).await?;
+        assert_not_found(response, "transaction")?;
+
+        let response = client.tx_diagnosis(tx.hash()).await?;
+        let diagnosis: TxDiagnosis = deserialize_response_result(response)?;
+        assert_eq!(diagnosis.status, None);
+        assert_eq!(diagnosis.note.as_deref(), Some("Transaction is unknown"));
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the oversized submissions are rejected with the structured error
+    /// and that the streamed bodies are not read completely.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn oversized_payloads_rejection() -> anyhow::Result<()> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+        const CHUNKS_COUNT: usize = 1600;
+
+        let (sender, task) = submit_txs_loopback();
+
+        let mut cfg = TestServerConfig::default();
+        cfg.config.api.rest.max_tx_payload_size = 1024;
+        cfg.config.api.rest.max_batch_payload_size = 4096;
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (_client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.replicated_pool(),
+                    None,
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&[], None),
+                        &cfg.config.api.common,
+                        cfg.token_cache(),
+                        sender.clone(),
+                        DisabledTxTypes::new(cfg.pool.clone()),
+                        BannedAddresses::new(cfg.pool.clone()),
+                        ChainId(cfg.config.eth_client.chain_id),
+                    ),
+                    SharedNetworkStatus::new("0.0.0.0".to_string()),
+                    &cfg.config.api.rest,
+                    String::new(),
+                    cfg.config
+                        .chain
+                        .state_keeper
+                        .max_aggregated_blocks_to_execute,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let assert_rejected = |status: StatusCode, body: &[u8], limit: usize| {
+            assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+            let response: zksync_api_types::v02::Response = serde_json::from_slice(body).unwrap();
+            let error: Error = serde_json::from_value(response.error.unwrap()).unwrap();
+            assert_eq!(error.code, ErrorCode::PayloadTooLarge);
+            assert_eq!(
+                error.message,
+                InvalidDataError::PayloadTooLarge(limit).to_string()
+            );
+        };
+
+        // Bodies with the known length are rejected without being read.
+        for (path, limit) in [
+            ("/api/v0.2/transactions", 1024),
+            ("/api/v0.2/transactions/batches", 4096),
+        ] {
+            let mut response = server
+                .post(path)
+                .content_type("application/json")
+                .send_body(vec![b' '; limit + 1])
+                .await
+                .unwrap();
+            let body = response.body().await.unwrap();
+            assert_rejected(response.status(), &body, limit);
+        }
+
+        // Streamed body of 100 MB is rejected as soon as the limit is exceeded.
+        let produced = Arc::new(AtomicUsize::new(0));
+        let body = futures::stream::iter(0..CHUNKS_COUNT).map({
+            let produced = produced.clone();
+            move |_| {
+                produced.fetch_add(CHUNK_SIZE, Ordering::SeqCst);
+                Ok::<_, std::io::Error>(web::Bytes::from(vec![b' '; CHUNK_SIZE]))
+            }
+        });
+        let response = server
+            .post("/api/v0.2/transactions/batches")
+            .content_type("application/json")
+            .send_stream(body)
+            .await;
+        // The server may close the connection before the client notices the response.
+        if let Ok(mut response) = response {
+            let body = response.body().await.unwrap();
+            assert_rejected(response.status(), &body, 4096);
+        }
+        let produced = produced.load(Ordering::SeqCst);
+        assert!(
+            produced < CHUNK_SIZE * CHUNKS_COUNT / 10,
+            "Oversized body was read almost completely: {} bytes",
+            produced
+        );
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[test]
+    fn tx_hash_prefix_parsing() {
+        let min_len = 8;
+        for (prefix, expected) in [
+            ("0xABCD1234", "abcd1234"),
+            ("sync-tx:abcd12345", "abcd12345"),
+            (" abcd1234 ", "abcd1234"),
+        ] {
+            assert_eq!(parse_tx_hash_prefix(prefix, min_len).unwrap(), expected);
+        }
+        let full_hash = "ab".repeat(32);
+        assert_eq!(
+            parse_tx_hash_prefix(&full_hash, min_len).unwrap(),
+            full_hash
+        );
+
+        // Too short, too long and non-hex prefixes are rejected.
+        for prefix in ["", "0xabcd123", "abcd", &"ab".repeat(33), "0xabcd123g"] {
+            let error = parse_tx_hash_prefix(prefix, min_len).unwrap_err();
+            assert_eq!(error.code, ErrorCode::InvalidTxHashPrefix);
+        }
+    }
+
+    /// Searches the transactions by the hash prefix, returns the error code if the search fails.
+    async fn search_txs(
+        server: &actix_test::TestServer,
+        token: Option<&str>,
+        prefix: &str,
+    ) -> anyhow::Result<Result<TxHashSearchResponse, ErrorCode>> {
+        let mut request = server.get(format!("/api/v0.2/transactions/search?prefix={}", prefix));
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let response: zksync_api_types::v02::Response = request
+            .send()
+            .await
+            .map_err(|err| anyhow::anyhow!("Search request failed: {}", err))?
+            .json()
+            .await
+            .map_err(|err| anyhow::anyhow!("Malformed search response: {}", err))?;
+        if let Some(error) = response.error {
+            let error: Error = serde_json::from_value(error)?;
+            return Ok(Err(error.code));
+        }
+        Ok(Ok(deserialize_response_result(response)?))
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn search_by_hash_prefix() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let mut cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        // Single hex digit is allowed, so that several transactions share the prefix.
+        cfg.config.api.rest.tx_search_min_prefix_len = 1;
+        cfg.config.api.rest.tx_search_limit = 2;
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (_client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.replicated_pool(),
+                    None,
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&[], None),
+                        &cfg.config.api.common,
+                        cfg.token_cache(),
+                        sender.clone(),
+                        DisabledTxTypes::new(cfg.pool.clone()),
+                        BannedAddresses::new(cfg.pool.clone()),
+                        ChainId(cfg.config.eth_client.chain_id),
+                    ),
+                    SharedNetworkStatus::new("0.0.0.0".to_string()),
+                    &cfg.config.api.rest,
+                    SECRET_AUTH.to_owned(),
+                    cfg.config
+                        .chain
+                        .state_keeper
+                        .max_aggregated_blocks_to_execute,
+                )
+            },
+            Some(shared_data),
+        );
+        let token = auth_token("support");
+
+        // Pick the first digit shared by the most of the stored hashes.
+        let stored = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .operations_ext_schema()
+            .search_tx_hashes_by_prefix("", 10_000)
+            .await?;
+        let mut hashes_by_digit: HashMap<String, Vec<Vec<u8>>> = HashMap::new();
+        for record in stored {
+            hashes_by_digit
+                .entry(hex::encode(&record.tx_hash)[..1].to_owned())
+                .or_default()
+                .push(record.tx_hash);
+        }
+        let (digit, hashes) = hashes_by_digit
+            .into_iter()
+            .max_by_key(|(_, hashes)| hashes.len())
+            .unwrap();
+        assert!(hashes.len() > 2, "Test data should contain more hashes");
+
+        // The search is available to the admins only.
+        assert_eq!(
+            search_txs(&server, None, &digit).await?.unwrap_err(),
+            ErrorCode::TxSearchUnauthorized
+        );
+        assert_eq!(
+            search_txs(&server, Some("invalid"), &digit)
+                .await?
+                .unwrap_err(),
+            ErrorCode::TxSearchUnauthorized
+        );
+        // Prefixes shorter than the minimum are rejected.
+        assert_eq!(
+            search_txs(&server, Some(&token), "0x").await?.unwrap_err(),
+            ErrorCode::InvalidTxHashPrefix
+        );
+
+        // Matches are ordered by the hash and limited.
+        let found = search_txs(&server, Some(&token), &digit).await?.unwrap();
+        assert!(found.truncated);
+        assert_eq!(
+            found
+                .matches
+                .iter()
+                .map(|m| m.tx_hash.as_ref().to_vec())
+                .collect::<Vec<_>>(),
+            hashes[..2].to_vec()
+        );
+
+        // The full hash is matched exactly.
+        let full_hash = format!("0x{}", hex::encode(&hashes[0]));
+        let found = search_txs(&server, Some(&token), &full_hash)
+            .await?
+            .unwrap();
+        assert!(!found.truncated);
+        assert_eq!(found.matches.len(), 1);
+        assert_eq!(found.matches[0].tx_hash.as_ref(), hashes[0].as_slice());
+        assert!(found.matches[0].tx_type.is_some());
 
         server.stop().await;
         task.abort();
         Ok(())
     }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn submit_tx_spans() -> anyhow::Result<()> {
+        let (sender, _task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(TokenId(0)),
+            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+        );
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+        let tx_sender = TxSender::new(
+            cfg.pool.clone(),
+            dummy_sign_verifier(),
+            dummy_fee_ticker(&prices, Some(cache)),
+            &cfg.config.api.common,
+            cfg.token_cache(),
+            sender,
+            DisabledTxTypes::new(cfg.pool.clone()),
+            BannedAddresses::new(cfg.pool.clone()),
+            ChainId(cfg.config.eth_client.chain_id),
+        );
+
+        // The service is run within the test, so the spans are captured by the thread-local subscriber.
+        let capture = SpansCapture::default();
+        let _subscriber_guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(shared_data)).service(
+                web::scope("/api/v0.2")
+                    .wrap_fn(request_span::instrument_request)
+                    .service(api_scope(
+                        cfg.replicated_pool(),
+                        None,
+                        tx_sender,
+                        SharedNetworkStatus::new("0.0.0.0".to_string()),
+                        &cfg.config.api.rest,
+                        String::new(),
+                        cfg.config
+                            .chain
+                            .state_keeper
+                            .max_aggregated_blocks_to_execute,
+                    )),
+            ),
+        )
+        .await;
+
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        let request = test::TestRequest::post()
+            .uri("/api/v0.2/transactions")
+            .insert_header(("x-request-id", "submit-tx-spans"))
+            .set_json(&TxWithSignature {
+                tx: tx.clone(),
+                signature: TxEthSignatureVariant::Single(None),
+                fee_quote: None,
+            })
+            .to_request();
+        let response: zksync_api_types::v02::Response =
+            test::read_response_json(&app, request).await;
+        let tx_hash: TxHash = deserialize_response_result(response)?;
+        assert_eq!(tx_hash, tx.hash());
+
+        let spans = capture.spans();
+        let root_span = spans
+            .iter()
+            .find(|span| span.path == ["api_request"])
+            .expect("Request span is not created");
+        assert_eq!(root_span.fields["request_id"], "submit-tx-spans");
+        assert_eq!(root_span.fields["path"], "/api/v0.2/transactions");
+
+        let submit_span = spans
+            .iter()
+            .find(|span| span.path == ["api_request", "submit_tx"])
+            .expect("Submission span is not created");
+        assert_eq!(submit_span.fields["tx_hash"], tx_hash.to_string());
+
+        for stage in ["parse", "fee_check", "verify_signature", "forward"] {
+            assert!(
+                spans
+                    .iter()
+                    .any(|span| span.path == ["api_request", "submit_tx", stage]),
+                "Span of the `{}` stage is not created",
+                stage
+            );
+        }
+        assert!(spans.iter().any(|span| span.path
+            == [
+                "api_request",
+                "submit_tx",
+                "forward",
+                "sql.chain.mempool.insert_outbox_tx"
+            ]));
+
+        // Neither signatures nor request bodies are recorded.
+        for span in &spans {
+            assert!(span
+                .fields
+                .keys()
+                .all(|field| !field.contains("signature") && field != "body"));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the transactions with unknown or disabled tokens are rejected before
+    /// their signatures are verified.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn bad_tokens_are_rejected_before_signature_verification() -> anyhow::Result<()> {
+        let (sender, _task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(TokenId(0)),
+            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+        );
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+        let mut config = cfg.config.api.common.clone();
+        config.disabled_tokens = vec![TokenId(1)];
+        let (sign_verifier, verifications) = counting_sign_verifier();
+        let tx_sender = TxSender::new(
+            cfg.pool.clone(),
+            sign_verifier,
+            dummy_fee_ticker(&prices, Some(cache)),
+            &config,
+            cfg.token_cache(),
+            sender,
+            DisabledTxTypes::new(cfg.pool.clone()),
+            BannedAddresses::new(cfg.pool.clone()),
+            ChainId(cfg.config.eth_client.chain_id),
+        );
+
+        let good_tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        let with_fee_token = |token: TokenId| {
+            let mut tx = good_tx.clone();
+            match &mut tx {
+                ZkSyncTx::ChangePubKey(change_pub_key) => change_pub_key.fee_token = token,
+                _ => panic!("Change pubkey transaction is expected"),
+            }
+            tx
+        };
+        let unknown_token = TokenId(MIN_NFT_TOKEN_ID - 1);
+
+        let cases = vec![
+            (unknown_token, SubmitError::UnknownToken(unknown_token)),
+            (
+                TokenId(u32::MAX),
+                SubmitError::UnknownToken(TokenId(u32::MAX)),
+            ),
+            (TokenId(1), SubmitError::TokenDisabled(TokenId(1))),
+        ];
+        for (token, expected_error) in cases {
+            let error = tx_sender
+                .submit_tx(
+                    with_fee_token(token),
+                    TxEthSignatureVariant::Single(None),
+                    None,
+                )
+                .await
+                .unwrap_err();
+            assert_eq!(error.to_string(), expected_error.to_string());
+            assert_eq!(error.error_code(), expected_error.error_code());
+        }
+
+        // The tokens of the whole batch are checked before any of its signatures.
+        let batch = vec![good_tx.clone(), with_fee_token(unknown_token)]
+            .into_iter()
+            .map(|tx| TxWithSignature {
+                tx,
+                signature: TxEthSignatureVariant::Single(None),
+                fee_quote: None,
+            })
+            .collect();
+        let error = tx_sender
+            .submit_txs_batch(batch, None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.error_code(), ErrorCode::UnknownToken);
+        assert_eq!(error.error_data().unwrap()["tokenId"], unknown_token.0);
+        assert_eq!(verifications.load(Ordering::SeqCst), 0);
+
+        // The signature of the transaction with the valid tokens is verified.
+        tx_sender
+            .submit_tx(good_tx, TxEthSignatureVariant::Single(None), None)
+            .await?;
+        assert!(verifications.load(Ordering::SeqCst) > 0);
+
+        Ok(())
+    }
+
+    /// Mock of the screening service allowing every address, counts the requests it has received.
+    #[derive(Debug, Default)]
+    struct CountingScreeningClient(AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl ScreeningClient for CountingScreeningClient {
+        async fn screen(&self, _address: Address) -> anyhow::Result<ScreeningVerdict> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(ScreeningVerdict::Allow)
+        }
+    }
+
+    /// Starts a signature verifier that accepts the requests only if `valid` is set.
+    fn toggled_sign_verifier(valid: Arc<AtomicBool>) -> mpsc::Sender<VerifySignatureRequest> {
+        let (sender, mut receiver) = mpsc::channel::<VerifySignatureRequest>(10);
+        actix_rt::spawn(async move {
+            while let Some(item) = receiver.next().await {
+                let response = if valid.load(Ordering::SeqCst) {
+                    Ok(VerifiedTx::unverified(item.data.get_tx_variant()))
+                } else {
+                    Err(TxAddError::IncorrectEthSignature)
+                };
+                item.response.send(response).unwrap_or_default();
+            }
+        });
+        sender
+    }
+
+    /// Checks that the withdrawals are screened only after their signatures are verified.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn incorrectly_signed_withdrawal_is_not_screened() -> anyhow::Result<()> {
+        let (sender, _task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(TokenId(0)),
+            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+        );
+        let mut market = HashMap::new();
+        market.insert(
+            TokenId(0),
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+        let valid_signatures = Arc::new(AtomicBool::new(false));
+        let mut tx_sender = TxSender::new(
+            cfg.pool.clone(),
+            toggled_sign_verifier(valid_signatures.clone()),
+            dummy_fee_ticker(&prices, Some(cache)),
+            &cfg.config.api.common,
+            cfg.token_cache(),
+            sender,
+            DisabledTxTypes::new(cfg.pool.clone()),
+            BannedAddresses::new(cfg.pool.clone()),
+            ChainId(cfg.config.eth_client.chain_id),
+        );
+        let screening_client = Arc::new(CountingScreeningClient::default());
+        let policy = ScreeningPolicy {
+            threshold_usd: 0.into(),
+            timeout: Duration::from_secs(1),
+            fail_closed: true,
+            cache_ttl: Duration::from_secs(60),
+        };
+        tx_sender.screening = Some(WithdrawalScreening::new(screening_client.clone(), policy));
+
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(0xf00d)));
+        let withdraw = account
+            .sign_withdraw(
+                TokenId(0),
+                "ETH",
+                10_u64.into(),
+                1_000_000_000_000_000_000_u64.into(),
+                &account.address,
+                None,
+                false,
+                Default::default(),
+            )
+            .0;
+        let withdraw = ZkSyncTx::Withdraw(Box::new(withdraw));
+        let expected_error = SubmitError::TxAdd(TxAddError::IncorrectEthSignature);
+
+        let error = tx_sender
+            .submit_tx(withdraw.clone(), TxEthSignatureVariant::Single(None), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), expected_error.to_string());
+        let batch = vec![TxWithSignature {
+            tx: withdraw.clone(),
+            signature: TxEthSignatureVariant::Single(None),
+            fee_quote: None,
+        }];
+        let error = tx_sender
+            .submit_txs_batch(batch, None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), expected_error.to_string());
+        assert_eq!(screening_client.0.load(Ordering::SeqCst), 0);
+
+        // The correctly signed withdrawal is screened.
+        valid_signatures.store(true, Ordering::SeqCst);
+        tx_sender
+            .submit_tx(withdraw, TxEthSignatureVariant::Single(None), None)
+            .await?;
+        assert_eq!(screening_client.0.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    /// Starts the transactions scope with the test data for the lifecycle tests.
+    fn start_lifecycle_server(
+        cfg: &TestServerConfig,
+    ) -> (Client, actix_test::TestServer, JoinHandle<()>) {
+        start_lifecycle_server_with_core(cfg, "0.0.0.0".to_string())
+    }
+
+    /// Same as `start_lifecycle_server`, but with the provided address of the core private API.
+    fn start_lifecycle_server_with_core(
+        cfg: &TestServerConfig,
+        core_address: String,
+    ) -> (Client, actix_test::TestServer, JoinHandle<()>) {
+        let (sender, task) = submit_txs_loopback();
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.replicated_pool(),
+                    None,
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&[], None),
+                        &cfg.config.api.common,
+                        cfg.token_cache(),
+                        sender.clone(),
+                        DisabledTxTypes::new(cfg.pool.clone()),
+                        BannedAddresses::new(cfg.pool.clone()),
+                        ChainId(cfg.config.eth_client.chain_id),
+                    ),
+                    SharedNetworkStatus::new(core_address.clone()),
+                    &cfg.config.api.rest,
+                    String::new(),
+                    cfg.config
+                        .chain
+                        .state_keeper
+                        .max_aggregated_blocks_to_execute,
+                )
+            },
+            Some(shared_data),
+        );
+        (client, server, task)
+    }
+
+    /// Starts the mock of the core private API, the mempool knows only the provided transaction.
+    fn start_core_mock(mempool_tx: TxHash) -> actix_test::TestServer {
+        actix_test::start(move || {
+            App::new().route(
+                "/mempool/tx/{tx_hash}",
+                web::get().to(move |tx_hash: web::Path<TxHash>| async move {
+                    let lookup = if *tx_hash == mempool_tx {
+                        MempoolTxLookup::Pending
+                    } else {
+                        MempoolTxLookup::Unknown
+                    };
+                    HttpResponse::Ok().json(lookup)
+                }),
+            )
+        })
+    }
+
+    async fn l2_receipt(client: &Client, tx_hash: TxHash) -> anyhow::Result<Option<L2Receipt>> {
+        l2_receipt_with_consistency(client, tx_hash, ReadConsistency::Eventual).await
+    }
+
+    async fn l2_receipt_with_consistency(
+        client: &Client,
+        tx_hash: TxHash,
+        consistency: ReadConsistency,
+    ) -> anyhow::Result<Option<L2Receipt>> {
+        let response = client
+            .tx_status_with_consistency(tx_hash, consistency)
+            .await?;
+        if response.error.is_some() {
+            assert_not_found(response, "transaction")?;
+            return Ok(None);
+        }
+        match deserialize_response_result(response)? {
+            Receipt::L2(receipt) => Ok(Some(receipt)),
+            Receipt::L1(_) => panic!("Unexpected receipt of the priority operation"),
+        }
+    }
+
+    /// The transaction accepted by the mempool may be not visible in the databases the API reads
+    /// right after the submission, here it's never stored by the loopback mempool.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn strong_consistency_receipt() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+        let tx_hash = tx.hash();
+        let core = start_core_mock(tx_hash);
+        let core_address = core.url("").trim_end_matches('/').to_owned();
+        let (client, server, task) = start_lifecycle_server_with_core(&cfg, core_address);
+
+        // Only the strong consistency consults the mempool.
+        assert_eq!(l2_receipt(&client, tx_hash).await?, None);
+        let expected_receipt = L2Receipt {
+            tx_hash,
+            rollup_block: None,
+            status: TxInBlockStatus::Queued,
+            fail_reason: None,
+            archived: false,
+        };
+        assert_eq!(
+            l2_receipt_with_consistency(&client, tx_hash, ReadConsistency::Strong).await?,
+            Some(expected_receipt)
+        );
+        // The data is not known to the mempool.
+        let response = client
+            .tx_data_with_consistency(tx_hash, ReadConsistency::Strong)
+            .await?;
+        assert_not_found(response, "transaction")?;
+
+        // Transactions unknown to the mempool are not found either way.
+        let unknown_tx_hash = TxHash::from_slice(&[0xaa; 32]).unwrap();
+        assert_eq!(
+            l2_receipt_with_consistency(&client, unknown_tx_hash, ReadConsistency::Strong).await?,
+            None
+        );
+
+        // Once stored, the transaction is read from the database.
+        let block_number = lifecycle.include(vec![BlockTx::success(tx)]).await?;
+        let receipt = l2_receipt_with_consistency(&client, tx_hash, ReadConsistency::Strong)
+            .await?
+            .unwrap();
+        assert_eq!(receipt.status, TxInBlockStatus::Committed);
+        assert_eq!(receipt.rollup_block, Some(block_number));
+        let response = client
+            .tx_data_with_consistency(tx_hash, ReadConsistency::Strong)
+            .await?;
+        let data: TxData = deserialize_response_result(response)?;
+        assert_eq!(data.tx.tx_hash, tx_hash);
+        assert_eq!(data.tx.block_number, Some(block_number));
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        core.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tx_rejected_at_execution() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (client, server, task) = start_lifecycle_server(&cfg);
+
+        let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+        let tx_hash = lifecycle.submit(tx.clone()).await?;
+        let receipt = l2_receipt(&client, tx_hash).await?.unwrap();
+        assert_eq!(receipt.status, TxInBlockStatus::Queued);
+
+        let block_number = lifecycle
+            .include(vec![BlockTx::failure(tx, "Nonce mismatch")])
+            .await?;
+        let expected_receipt = || L2Receipt {
+            tx_hash,
+            rollup_block: Some(block_number),
+            status: TxInBlockStatus::Rejected,
+            fail_reason: Some("Nonce mismatch".to_string()),
+            archived: false,
+        };
+        assert_eq!(
+            l2_receipt(&client, tx_hash).await?,
+            Some(expected_receipt())
+        );
+
+        let response = client.tx_diagnosis(tx_hash).await?;
+        let diagnosis: TxDiagnosis = deserialize_response_result(response)?;
+        assert_eq!(diagnosis.status, Some(TxInBlockStatus::Rejected));
+
+        // The rejection is final, finalization of the block doesn't affect it.
+        lifecycle.verify(block_number).await?;
+        lifecycle.execute(block_number).await?;
+        assert_eq!(
+            l2_receipt(&client, tx_hash).await?,
+            Some(expected_receipt())
+        );
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn batch_partially_included() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (client, server, task) = start_lifecycle_server(&cfg);
+
+        let batch: Vec<_> = TestServerConfig::gen_zk_txs(1_u64)
+            .txs
+            .into_iter()
+            .take(2)
+            .map(|(tx, _op)| tx)
+            .collect();
+        let tx_hashes: Vec<_> = batch.iter().map(|tx| tx.hash()).collect();
+        let batch_hash = TxHash::batch_hash(&tx_hashes);
+        let batch_id = lifecycle.submit_batch(batch.clone()).await?;
+
+        let response = client.get_batch(batch_hash).await?;
+        let api_batch: ApiTxBatch = deserialize_response_result(response)?;
+        assert_eq!(api_batch.transaction_hashes.len(), 2);
+        assert_eq!(api_batch.batch_status.last_state, TxInBlockStatus::Queued);
+
+        // Only the first transaction makes it into the block,
+        // the rest of the batch is evicted from the mempool.
+        let block_number = lifecycle
+            .include(vec![BlockTx::success(batch[0].clone()).in_batch(batch_id)])
+            .await?;
+        let receipt = l2_receipt(&client, tx_hashes[0]).await?.unwrap();
+        assert_eq!(receipt.status, TxInBlockStatus::Committed);
+        assert_eq!(receipt.rollup_block, Some(block_number));
+        assert!(l2_receipt(&client, tx_hashes[1]).await?.is_none());
+
+        let response = client.get_batch(batch_hash).await?;
+        let api_batch: ApiTxBatch = deserialize_response_result(response)?;
+        assert_eq!(
+            api_batch.transaction_hashes,
+            vec![TxHashSerializeWrapper(tx_hashes[0])]
+        );
+        assert_eq!(
+            api_batch.batch_status.last_state,
+            TxInBlockStatus::Committed
+        );
+
+        lifecycle.verify(block_number).await?;
+        lifecycle.execute(block_number).await?;
+        let receipt = l2_receipt(&client, tx_hashes[0]).await?.unwrap();
+        assert_eq!(receipt.status, TxInBlockStatus::Finalized);
+        let response = client.get_batch(batch_hash).await?;
+        let api_batch: ApiTxBatch = deserialize_response_result(response)?;
+        assert_eq!(
+            api_batch.batch_status.last_state,
+            TxInBlockStatus::Finalized
+        );
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn block_reverted_after_commit() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (client, server, task) = start_lifecycle_server(&cfg);
+
+        let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+        let tx_hash = lifecycle.submit(tx.clone()).await?;
+        let block_number = lifecycle
+            .include(vec![BlockTx::success(tx.clone())])
+            .await?;
+        let receipt = l2_receipt(&client, tx_hash).await?.unwrap();
+        assert_eq!(receipt.status, TxInBlockStatus::Committed);
+        assert_eq!(receipt.rollup_block, Some(block_number));
+
+        // The transaction is returned to the mempool along with the reverted block.
+        lifecycle.revert(TxLifecycle::BASE_BLOCK).await?;
+        let expected_receipt = L2Receipt {
+            tx_hash,
+            rollup_block: None,
+            status: TxInBlockStatus::Queued,
+            fail_reason: None,
+            archived: false,
+        };
+        assert_eq!(l2_receipt(&client, tx_hash).await?, Some(expected_receipt));
+
+        let response = client.tx_data(tx_hash).await?;
+        let tx_data: TxData = deserialize_response_result(response)?;
+        assert!(tx_data.tx.block_number.is_none());
+
+        // Once executed again, the transaction belongs to the new block.
+        let new_block_number = lifecycle.include(vec![BlockTx::success(tx)]).await?;
+        assert_eq!(new_block_number, block_number);
+        let receipt = l2_receipt(&client, tx_hash).await?.unwrap();
+        assert_eq!(receipt.status, TxInBlockStatus::Committed);
+        assert_eq!(receipt.rollup_block, Some(new_block_number));
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn finalized_tx_data_is_cached() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (client, server, task) = start_lifecycle_server(&cfg);
+
+        // Neither the queued nor the committed transactions are cached.
+        let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+        let tx_hash = lifecycle.submit(tx.clone()).await?;
+        let tx_data: TxData = deserialize_response_result(client.tx_data(tx_hash).await?)?;
+        assert_eq!(tx_data.tx.status, TxInBlockStatus::Queued);
+
+        let block_number = lifecycle
+            .include(vec![BlockTx::success(tx.clone())])
+            .await?;
+        let tx_data: TxData = deserialize_response_result(client.tx_data(tx_hash).await?)?;
+        assert_eq!(tx_data.tx.status, TxInBlockStatus::Committed);
+
+        lifecycle.verify(block_number).await?;
+        lifecycle.execute(block_number).await?;
+        let tx_data: TxData = deserialize_response_result(client.tx_data(tx_hash).await?)?;
+        assert_eq!(tx_data.tx.status, TxInBlockStatus::Finalized);
+        let finalized = serde_json::to_value(&tx_data)?;
+
+        // Finalized transactions never change, rewrite the stored one anyway to make sure
+        // it's not loaded again.
+        lifecycle.revert(TxLifecycle::BASE_BLOCK).await?;
+        lifecycle
+            .include(vec![BlockTx::failure(tx, "Nonce mismatch")])
+            .await?;
+        let receipt = l2_receipt(&client, tx_hash).await?.unwrap();
+        assert_eq!(receipt.status, TxInBlockStatus::Rejected);
+
+        for consistency in [ReadConsistency::Eventual, ReadConsistency::Strong] {
+            let response = client
+                .tx_data_with_consistency(tx_hash, consistency)
+                .await?;
+            let tx_data: TxData = deserialize_response_result(response)?;
+            assert_eq!(serde_json::to_value(&tx_data)?, finalized);
+        }
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the withdrawal link restored by the repair reaches the clients, i.e. the
+    /// finalized withdrawal without the hash of its Ethereum transaction is not cached.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn repaired_withdrawal_link() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (client, server, task) = start_lifecycle_server(&cfg);
+
+        let withdrawal_eth_tx = |tx_data: TxData| match tx_data.tx.op {
+            TransactionData::L2(L2Transaction::Withdraw(withdraw)) => withdraw.eth_tx_hash,
+            _ => panic!("Should be a withdrawal"),
+        };
+
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(0xf00d)));
+        let withdraw = account
+            .sign_withdraw(
+                TokenId(0),
+                "ETH",
+                10_u64.into(),
+                0_u64.into(),
+                &account.address,
+                None,
+                false,
+                Default::default(),
+            )
+            .0;
+        let withdraw = ZkSyncTx::Withdraw(Box::new(withdraw));
+        let tx_hash = withdraw.hash();
+        let block_number = lifecycle.include(vec![BlockTx::success(withdraw)]).await?;
+        lifecycle.verify(block_number).await?;
+        let eth_tx_hash = lifecycle.execute_unlinked(block_number).await?;
+
+        let tx_data: TxData = deserialize_response_result(client.tx_data(tx_hash).await?)?;
+        assert_eq!(tx_data.tx.status, TxInBlockStatus::Finalized);
+        assert_eq!(withdrawal_eth_tx(tx_data), None);
+
+        let mut storage = cfg.pool.access_storage().await?;
+        storage
+            .withdrawals_schema()
+            .save_pending_withdrawals(&[WithdrawalPendingEvent {
+                block_number: 10,
+                tx_hash: eth_tx_hash,
+                token_id: TokenId(0),
+                recipient: account.address,
+                amount: 10_u64.into(),
+                withdrawal_type: WithdrawalType::Withdrawal,
+                log_index: 0,
+            }])
+            .await?;
+        let repaired = storage
+            .ethereum_schema()
+            .restore_withdrawal_eth_txs(block_number, block_number, false)
+            .await?;
+        assert_eq!(repaired.len(), 1);
+        drop(storage);
+
+        for consistency in [ReadConsistency::Eventual, ReadConsistency::Strong] {
+            let response = client
+                .tx_data_with_consistency(tx_hash, consistency)
+                .await?;
+            let tx_data: TxData = deserialize_response_result(response)?;
+            assert_eq!(withdrawal_eth_tx(tx_data), Some(eth_tx_hash));
+        }
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Returns the negotiated sub-version along with the result of the response.
+    async fn get_with_sub_version(
+        url: String,
+        sub_version: Option<&str>,
+    ) -> anyhow::Result<(String, Value)> {
+        let mut request = reqwest::Client::new().get(&url);
+        if let Some(sub_version) = sub_version {
+            request = request.header(SUB_VERSION_HEADER, sub_version);
+        }
+        let response = request.send().await?;
+        let negotiated = response.headers()[SUB_VERSION_HEADER].to_str()?.to_owned();
+        let response: Response = response.json().await?;
+        Ok((negotiated, response.result.unwrap()))
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn sub_version_negotiation() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (_client, server, task) = start_lifecycle_server(&cfg);
+
+        let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+        let tx_hash = lifecycle.submit(tx.clone()).await?;
+        lifecycle.include(vec![BlockTx::success(tx)]).await?;
+
+        let status_path = format!("/api/v0.2/transactions/{}", tx_hash.to_string());
+        let data_path = format!("/api/v0.2/transactions/{}/data", tx_hash.to_string());
+
+        // Clients not requesting the sub-version and the ones ahead of the server get the latest one.
+        for requested in [None, Some("2"), Some("7")] {
+            let (negotiated, receipt) =
+                get_with_sub_version(server.url(&status_path), requested).await?;
+            assert_eq!(negotiated, "2");
+            assert_eq!(receipt["status"], "committed");
+            let (negotiated, data) =
+                get_with_sub_version(server.url(&data_path), requested).await?;
+            assert_eq!(negotiated, "2");
+            assert!(data["tx"].get("blockIndex").is_some());
+            assert!(data.get("l2Signature").is_some());
+        }
+
+        let (negotiated, receipt) =
+            get_with_sub_version(server.url(&status_path), Some("1")).await?;
+        assert_eq!(negotiated, "1");
+        assert_eq!(receipt["status"], "committed");
+        let (negotiated, data) = get_with_sub_version(server.url(&data_path), Some("1")).await?;
+        assert_eq!(negotiated, "1");
+        assert_eq!(data["tx"]["status"], "committed");
+        assert!(data["tx"].get("blockIndex").is_none());
+        assert!(data.get("l2Signature").is_none());
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[test]
+    fn execute_eta_estimation() {
+        // Blocks covered by the created execute operations don't wait for the next one.
+        assert_eq!(execute_queue_position(BlockNumber(5), BlockNumber(7), 3), 0);
+        assert_eq!(execute_queue_position(BlockNumber(7), BlockNumber(7), 3), 0);
+        assert_eq!(execute_queue_position(BlockNumber(8), BlockNumber(7), 3), 1);
+        assert_eq!(
+            execute_queue_position(BlockNumber(10), BlockNumber(7), 3),
+            1
+        );
+        assert_eq!(
+            execute_queue_position(BlockNumber(11), BlockNumber(7), 3),
+            2
+        );
+        assert_eq!(
+            execute_queue_position(BlockNumber(11), BlockNumber(7), 1),
+            4
+        );
+
+        let now = Utc::now();
+        let minutes_ago = |minutes| now - chrono::Duration::minutes(minutes);
+        assert_eq!(average_interval(&[]), None);
+        assert_eq!(average_interval(&[now]), None);
+        assert_eq!(
+            average_interval(&[now, minutes_ago(30), minutes_ago(10)]),
+            Some(Duration::from_secs(15 * 60))
+        );
+
+        let interval = Duration::from_secs(60);
+        let expected = EtaRange {
+            from: now + chrono::Duration::minutes(2),
+            to: now + chrono::Duration::minutes(3),
+        };
+        assert_eq!(eta_range(now, 3, interval), expected);
+        // The operation covering the block is already created, it's expected within the interval.
+        let expected = EtaRange {
+            from: now,
+            to: now + chrono::Duration::minutes(1),
+        };
+        assert_eq!(eta_range(now, 0, interval), expected);
+        assert_eq!(eta_range(now, 1, interval), expected);
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn withdrawal_execute_eta() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (client, server, task) = start_lifecycle_server(&cfg);
+
+        let error_code = |response: zksync_api_types::v02::Response| {
+            response.error.map(|error| error["code"].as_u64().unwrap())
+        };
+
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(0xf00d)));
+        let withdraw = account
+            .sign_withdraw(
+                TokenId(0),
+                "ETH",
+                10_u64.into(),
+                0_u64.into(),
+                &account.address,
+                None,
+                false,
+                Default::default(),
+            )
+            .0;
+        let withdraw = ZkSyncTx::Withdraw(Box::new(withdraw));
+        let tx_hash = lifecycle.submit(withdraw.clone()).await?;
+        let response = client.withdrawal_execute_eta(tx_hash).await?;
+        assert_eq!(
+            error_code(response),
+            Some(ErrorCode::WithdrawalNotVerified as u64)
+        );
+
+        let transfer = TestServerConfig::gen_zk_txs(1_u64).txs[1].0.clone();
+        let block_number = lifecycle
+            .include(vec![
+                BlockTx::success(withdraw),
+                BlockTx::success(transfer.clone()),
+            ])
+            .await?;
+        let response = client.withdrawal_execute_eta(tx_hash).await?;
+        assert_eq!(
+            error_code(response),
+            Some(ErrorCode::WithdrawalNotVerified as u64)
+        );
+        let response = client.withdrawal_execute_eta(transfer.hash()).await?;
+        assert_eq!(error_code(response), Some(ErrorCode::NotWithdrawal as u64));
+
+        lifecycle.verify(block_number).await?;
+        let response = client.withdrawal_execute_eta(tx_hash).await?;
+        let eta: WithdrawalExecuteEta = deserialize_response_result(response)?;
+        assert_eq!(eta.tx_hash, tx_hash);
+        assert_eq!(eta.block_number, block_number);
+        assert!(eta.execution.is_none());
+        assert!(eta.execute_queue_position.unwrap() >= 1);
+        assert_eq!(
+            eta.max_blocks_per_execute as usize,
+            cfg.config
+                .chain
+                .state_keeper
+                .max_aggregated_blocks_to_execute
+        );
+
+        lifecycle.execute(block_number).await?;
+        let response = client.withdrawal_execute_eta(tx_hash).await?;
+        let eta: WithdrawalExecuteEta = deserialize_response_result(response)?;
+        assert!(eta.execution.is_some());
+        assert!(eta.execute_queue_position.is_none());
+        assert!(eta.eta.is_none());
+
+        let response = client.withdrawal_execute_eta(TxHash::default()).await?;
+        assert_not_found(response, "transaction")?;
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tx_public_data() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let (client, server, task) = start_lifecycle_server(&cfg);
+
+        let error_code = |response: zksync_api_types::v02::Response| {
+            response.error.map(|error| error["code"].as_u64().unwrap())
+        };
+
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut op_codes = Vec::new();
+        // The first block contains the L2 transactions, the second one the priority operation.
+        for block_number in [BlockNumber(1), BlockNumber(2)] {
+            let block = storage
+                .chain()
+                .block_schema()
+                .get_block(block_number)
+                .await?
+                .unwrap();
+            let block_public_data = block.get_eth_public_data();
+            for executed_op in &block.block_transactions {
+                let tx_hash = match executed_op {
+                    ExecutedOperations::Tx(tx) if tx.success => tx.signed_tx.tx.hash(),
+                    ExecutedOperations::Tx(tx) => {
+                        let response = client.tx_public_data(tx.signed_tx.tx.hash()).await?;
+                        assert_eq!(
+                            error_code(response),
+                            Some(ErrorCode::TxHasNoPublicData as u64)
+                        );
+                        continue;
+                    }
+                    // The fixture stores the priority operations by their Ethereum hashes.
+                    ExecutedOperations::PriorityOp(op) => {
+                        TxHash::from_slice(op.priority_op.eth_hash.as_bytes()).unwrap()
+                    }
+                };
+                let op = executed_op.get_executed_op().unwrap();
+                let response = client.tx_public_data(tx_hash).await?;
+                let public_data: TxPublicData = deserialize_response_result(response)?;
+                assert_eq!(
+                    public_data,
+                    TxPublicData::new(tx_hash, block_number, public_data.chunk_offset, op)
+                );
+                assert_eq!(public_data.op_code, op.public_data()[0]);
+
+                // The operation is located in the public data of the block by the offset.
+                let start = public_data.chunk_offset * CHUNK_BYTES;
+                let end = start + public_data.chunks * CHUNK_BYTES;
+                assert_eq!(block_public_data[start..end], op.public_data()[..]);
+                op_codes.push(public_data.op_code);
+            }
+        }
+        op_codes.dedup();
+        assert!(op_codes.len() > 1, "{:?}", op_codes);
+
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(0xf00d)));
+        let transfer = account
+            .sign_transfer(
+                TokenId(0),
+                "ETH",
+                10_u64.into(),
+                0_u64.into(),
+                &account.address,
+                None,
+                false,
+                Default::default(),
+            )
+            .0;
+        let tx_hash = lifecycle
+            .submit(ZkSyncTx::Transfer(Box::new(transfer)))
+            .await?;
+        let response = client.tx_public_data(tx_hash).await?;
+        assert_eq!(
+            error_code(response),
+            Some(ErrorCode::TxHasNoPublicData as u64)
+        );
+
+        let response = client.tx_public_data(TxHash::default()).await?;
+        assert_not_found(response, "transaction")?;
+
+        lifecycle.finish().await?;
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the locked accounts can't spend their funds until the unlock
+    /// requested by the owner takes effect after the cool-down.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn locked_account() -> anyhow::Result<()> {
+        let (sender, _task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(TokenId(0)),
+            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+        );
+        let cache = TokenInMemoryCache::new().with_tokens(tokens);
+        let prices = vec![(TokenLike::Id(TokenId(0)), 10500_u64.into())];
+        let mut config = cfg.config.api.common.clone();
+        config.account_unlock_cooldown_secs = 3600;
+        let mut tx_sender = TxSender::new(
+            cfg.pool.clone(),
+            dummy_sign_verifier(),
+            dummy_fee_ticker(&prices, Some(cache)),
+            &config,
+            cfg.token_cache(),
+            sender,
+            DisabledTxTypes::new(cfg.pool.clone()),
+            BannedAddresses::new(cfg.pool.clone()),
+            ChainId(cfg.config.eth_client.chain_id),
+        );
+
+        let (_, accounts) = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .state_schema()
+            .load_committed_state(None)
+            .await?;
+        let account_id = *accounts.keys().max().unwrap();
+        let owner = ZkSyncAccount::rand();
+        let eth_signature = |message: String| {
+            let eth_private_key = owner
+                .try_get_eth_private_key()
+                .expect("Should have ETH private key");
+            TxEthSignature::EthereumSignature(
+                PackedEthSignature::sign(eth_private_key, message.as_bytes()).unwrap(),
+            )
+        };
+        let transfer = ZkSyncTx::from(Transfer::new(
+            account_id,
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            TokenId(0),
+            1_000_000u64.into(),
+            0u32.into(),
+            Default::default(),
+            Default::default(),
+            None,
+        ));
+        async fn submit_transfer(
+            tx_sender: &TxSender,
+            tx: &ZkSyncTx,
+        ) -> Result<TxHash, SubmitError> {
+            tx_sender
+                .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None), None)
+                .await
+        }
+
+        let mut lock = LockAccount {
+            account_id,
+            timestamp: Utc::now() - chrono::Duration::seconds(10),
+            signature: eth_signature(String::new()),
+        };
+        lock.signature = eth_signature(lock.get_ethereum_sign_message());
+        let lock_info = tx_sender.lock_account(lock.clone()).await?;
+        assert_eq!(lock_info.unlocks_at, None);
+        let error = submit_transfer(&tx_sender, &transfer).await.unwrap_err();
+        assert_eq!(error.error_code(), ErrorCode::AccountLocked);
+        assert_eq!(error.error_data().unwrap()["accountId"], account_id.0);
+        // The same request can't be replayed.
+        let error = tx_sender.lock_account(lock).await.unwrap_err();
+        assert_eq!(error.error_code(), ErrorCode::AccountLockError);
+
+        // The signing key of the account is unknown, so only the Ethereum signature is accepted.
+        let mut unlock = UnlockAccount {
+            account_id,
+            timestamp: Utc::now() - chrono::Duration::seconds(5),
+            eth_signature: None,
+            signature: Some(TxSignature::default()),
+        };
+        let error = tx_sender.unlock_account(unlock.clone()).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            SubmitError::AccountLock(AccountLockError::IncorrectSignature).to_string()
+        );
+        unlock.signature = None;
+        unlock.eth_signature = Some(eth_signature(unlock.get_sign_message()));
+        let lock_info = tx_sender.unlock_account(unlock).await?;
+        assert!(lock_info.unlocks_at.unwrap() > Utc::now() + chrono::Duration::minutes(59));
+        // The account stays locked during the cool-down.
+        let error = submit_transfer(&tx_sender, &transfer).await.unwrap_err();
+        assert_eq!(error.error_code(), ErrorCode::AccountLocked);
+
+        // Locking the account again cancels the scheduled unlock.
+        let mut lock = LockAccount {
+            account_id,
+            timestamp: Utc::now() - chrono::Duration::seconds(2),
+            signature: eth_signature(String::new()),
+        };
+        lock.signature = eth_signature(lock.get_ethereum_sign_message());
+        let lock_info = tx_sender.lock_account(lock).await?;
+        assert_eq!(lock_info.unlocks_at, None);
+
+        tx_sender.account_unlock_cooldown = Duration::from_secs(0);
+        let mut unlock = UnlockAccount {
+            account_id,
+            timestamp: Utc::now(),
+            eth_signature: None,
+            signature: None,
+        };
+        let error = tx_sender.unlock_account(unlock.clone()).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            SubmitError::AccountLock(AccountLockError::MissingSignature).to_string()
+        );
+        unlock.eth_signature = Some(eth_signature(unlock.get_sign_message()));
+        tx_sender.unlock_account(unlock).await?;
+        if let Err(error) = submit_transfer(&tx_sender, &transfer).await {
+            assert_ne!(error.error_code(), ErrorCode::AccountLocked);
+        }
+        Ok(())
+    }
 }
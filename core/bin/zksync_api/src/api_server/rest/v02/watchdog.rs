@@ -0,0 +1,390 @@
+//! Watchdog of the requests in flight.
+//!
+//! Occasionally a worker gets wedged with all its requests stuck, and afterwards it's unclear what
+//! they were waiting for. The watchdog tracks the requests in flight across all the workers and logs
+//! the ones running for longer than the threshold, along with the time spent polling the handler
+//! (computing) and the time spent waiting, mostly for the storage. This is the same split as the
+//! `busy` and `idle` times of the tracing spans. The requests exceeding the hard timeout, if it's
+//! enabled, are aborted with `504 Gateway Timeout`, so the connection isn't held forever.
+
+// Built-in uses
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// External uses
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse},
+    error::InternalError,
+    http::StatusCode,
+    HttpMessage, HttpRequest, Responder,
+};
+use futures::future::poll_fn;
+use tokio::time;
+
+// Workspace uses
+use zksync_config::configs::api::RestApiConfig;
+
+// Local uses
+use super::{
+    error::{Error, RequestTimedOut},
+    request_span::RequestId,
+    response::ApiResult,
+};
+
+const API_PREFIX: &str = "/api/v0.2/";
+/// Scopes the requests in flight are counted for, the requests to other paths are counted as `other`.
+const SCOPES: &[&str] = &[
+    "accessTokens",
+    "accounts",
+    "blocks",
+    "config",
+    "errors",
+    "events",
+    "fee",
+    "l1Operations",
+    "networkStatus",
+    "search",
+    "tokens",
+    "transactions",
+];
+
+/// Returns the scope of the API the path belongs to.
+fn scope_of(path: &str) -> &'static str {
+    let segment = path
+        .strip_prefix(API_PREFIX)
+        .and_then(|path| path.split('/').next())
+        .unwrap_or_default();
+    SCOPES
+        .iter()
+        .copied()
+        .find(|scope| *scope == segment)
+        .unwrap_or("other")
+}
+
+/// Request running for longer than the threshold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlowRequest {
+    pub request_id: String,
+    /// Method and path of the request.
+    pub endpoint: String,
+    pub elapsed: Duration,
+    /// Time spent polling the handler.
+    pub busy: Duration,
+    /// Time spent waiting, e.g. for the storage or for the other services.
+    pub idle: Duration,
+}
+
+#[derive(Debug)]
+struct InFlightRequest {
+    request_id: String,
+    endpoint: String,
+    scope: &'static str,
+    started_at: Instant,
+    busy_nanos: Arc<AtomicU64>,
+    /// Slow requests are logged once.
+    reported: bool,
+}
+
+impl InFlightRequest {
+    fn report(&self, now: Instant) -> SlowRequest {
+        let elapsed = now.duration_since(self.started_at);
+        let busy = Duration::from_nanos(self.busy_nanos.load(Ordering::Relaxed));
+        SlowRequest {
+            request_id: self.request_id.clone(),
+            endpoint: self.endpoint.clone(),
+            elapsed,
+            busy,
+            idle: elapsed.checked_sub(busy).unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct InFlightRequests {
+    requests: HashMap<u64, InFlightRequest>,
+    per_scope: HashMap<&'static str, usize>,
+    next_id: u64,
+}
+
+impl InFlightRequests {
+    fn insert(&mut self, request: InFlightRequest) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        let in_flight = self.per_scope.entry(request.scope).or_default();
+        *in_flight += 1;
+        metrics::gauge!("api.v02.in_flight_requests", *in_flight as f64, "scope" => request.scope);
+        self.requests.insert(id, request);
+        id
+    }
+
+    fn remove(&mut self, id: u64) -> Option<InFlightRequest> {
+        let request = self.requests.remove(&id)?;
+        let in_flight = self.per_scope.entry(request.scope).or_default();
+        *in_flight -= 1;
+        metrics::gauge!("api.v02.in_flight_requests", *in_flight as f64, "scope" => request.scope);
+        Some(request)
+    }
+}
+
+/// Stops tracking the request once it's completed or dropped, e.g. after the client disconnects.
+struct TrackedRequest {
+    requests: Arc<Mutex<InFlightRequests>>,
+    id: u64,
+}
+
+impl Drop for TrackedRequest {
+    fn drop(&mut self) {
+        self.requests.lock().unwrap().remove(self.id);
+    }
+}
+
+/// Tracks the requests in flight, meant to be used with `Scope::wrap_fn`. The requests are tracked
+/// across all the workers, so the watchdog keeps reporting them even if a worker is wedged.
+#[derive(Debug, Clone)]
+pub struct RequestWatchdog {
+    requests: Arc<Mutex<InFlightRequests>>,
+    slow_threshold: Duration,
+    check_interval: Duration,
+    hard_timeout: Option<Duration>,
+}
+
+impl RequestWatchdog {
+    pub fn new(config: &RestApiConfig) -> Self {
+        Self {
+            requests: Arc::default(),
+            slow_threshold: config.slow_request_threshold(),
+            check_interval: config.slow_request_check_interval(),
+            hard_timeout: config.request_hard_timeout(),
+        }
+    }
+
+    /// Handles the request while it's tracked, aborts it after the hard timeout.
+    pub fn track<S, B>(
+        &self,
+        req: ServiceRequest,
+        service: &S,
+    ) -> impl Future<Output = Result<ServiceResponse<B>, actix_web::Error>>
+    where
+        S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    {
+        let scope = scope_of(req.path());
+        let busy_nanos = Arc::new(AtomicU64::new(0));
+        let id = self.requests.lock().unwrap().insert(InFlightRequest {
+            request_id: req
+                .extensions()
+                .get::<RequestId>()
+                .map(|request_id| request_id.0.clone())
+                .unwrap_or_default(),
+            endpoint: format!("{} {}", req.method(), req.path()),
+            scope,
+            started_at: Instant::now(),
+            busy_nanos: busy_nanos.clone(),
+            reported: false,
+        });
+        let tracked = TrackedRequest {
+            requests: self.requests.clone(),
+            id,
+        };
+        let hard_timeout = self.hard_timeout;
+        let http_request = req.request().clone();
+
+        // The handler doesn't run until the response is polled, so the polling time is the time it's busy.
+        let mut handler = Box::pin(service.call(req));
+        let response = poll_fn(move |cx| {
+            let start = Instant::now();
+            let poll = handler.as_mut().poll(cx);
+            busy_nanos.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+            poll
+        });
+
+        async move {
+            let hard_timeout = match hard_timeout {
+                Some(hard_timeout) => hard_timeout,
+                None => return response.await,
+            };
+            match time::timeout(hard_timeout, response).await {
+                Ok(response) => response,
+                Err(_) => {
+                    let requests = tracked.requests.lock().unwrap();
+                    let request = &requests.requests[&tracked.id];
+                    let report = request.report(Instant::now());
+                    drop(requests);
+                    vlog::warn!(
+                        "Request {} to `{}` is aborted after {:?}: {:?} busy, {:?} idle",
+                        report.request_id,
+                        report.endpoint,
+                        report.elapsed,
+                        report.busy,
+                        report.idle
+                    );
+                    metrics::increment_counter!("api.v02.aborted_requests", "scope" => scope);
+                    Err(abort(&http_request, hard_timeout))
+                }
+            }
+        }
+    }
+
+    /// Returns the requests that have become slow since the previous check.
+    pub fn check(&self) -> Vec<SlowRequest> {
+        let now = Instant::now();
+        let mut requests = self.requests.lock().unwrap();
+        requests
+            .requests
+            .values_mut()
+            .filter(|request| {
+                !request.reported && now.duration_since(request.started_at) >= self.slow_threshold
+            })
+            .map(|request| {
+                request.reported = true;
+                request.report(now)
+            })
+            .collect()
+    }
+
+    /// Logs the slow requests forever, should be spawned as a separate task.
+    pub async fn run(self) {
+        let mut timer = time::interval(self.check_interval);
+        loop {
+            timer.tick().await;
+            for request in self.check() {
+                vlog::warn!(
+                    "Request {} to `{}` is in flight for {:?}: {:?} busy, {:?} idle",
+                    request.request_id,
+                    request.endpoint,
+                    request.elapsed,
+                    request.busy,
+                    request.idle
+                );
+                metrics::increment_counter!("api.v02.slow_requests");
+            }
+        }
+    }
+}
+
+/// Renders the abort as a regular API response with `504 Gateway Timeout`.
+fn abort(req: &HttpRequest, hard_timeout: Duration) -> actix_web::Error {
+    let error = RequestTimedOut {
+        timeout_ms: hard_timeout.as_millis() as u64,
+    };
+    let mut response = ApiResult::<()>::from(Error::from(error)).respond_to(req);
+    *response.status_mut() = StatusCode::GATEWAY_TIMEOUT;
+    InternalError::from_response("request timed out", response).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{error::ErrorCode, request_span, SharedData};
+    use actix_web::{web, App, HttpResponse};
+    use zksync_api_types::v02::{ApiVersion, Response};
+    use zksync_types::network::Network;
+
+    /// Time the handler computes before waiting.
+    const BUSY_TIME: Duration = Duration::from_millis(50);
+    const SLOW_THRESHOLD: Duration = Duration::from_millis(100);
+
+    fn watchdog(hard_timeout: Option<Duration>) -> RequestWatchdog {
+        RequestWatchdog {
+            requests: Arc::default(),
+            slow_threshold: SLOW_THRESHOLD,
+            check_interval: Duration::from_millis(10),
+            hard_timeout,
+        }
+    }
+
+    /// Blocks the worker for `BUSY_TIME`, then waits for the storage.
+    async fn slow_query() -> HttpResponse {
+        std::thread::sleep(BUSY_TIME);
+        actix_rt::time::sleep(SLOW_THRESHOLD * 3).await;
+        HttpResponse::Ok().finish()
+    }
+
+    fn start_server(watchdog: RequestWatchdog) -> actix_test::TestServer {
+        actix_test::start(move || {
+            let watchdog = watchdog.clone();
+            let scope = web::scope("/api/v0.2")
+                .wrap_fn(move |req, srv| watchdog.track(req, srv))
+                .wrap_fn(request_span::instrument_request)
+                .route("/blocks/slow", web::get().to(slow_query));
+            App::new()
+                .app_data(web::Data::new(SharedData {
+                    net: Network::Localhost,
+                    api_version: ApiVersion::V02,
+                    not_found_status: StatusCode::OK,
+                }))
+                .service(scope)
+        })
+    }
+
+    fn in_flight(watchdog: &RequestWatchdog, scope: &str) -> usize {
+        let requests = watchdog.requests.lock().unwrap();
+        requests.per_scope.get(scope).copied().unwrap_or_default()
+    }
+
+    #[test]
+    fn scopes() {
+        assert_eq!(scope_of("/api/v0.2/accounts/0x01/transactions"), "accounts");
+        assert_eq!(scope_of("/api/v0.2/blocks"), "blocks");
+        assert_eq!(scope_of("/api/v0.2/unknown"), "other");
+        assert_eq!(scope_of("/favicon.ico"), "other");
+    }
+
+    #[actix_rt::test]
+    async fn slow_requests_are_reported() {
+        let watchdog = watchdog(None);
+        let server = start_server(watchdog.clone());
+        let request = reqwest::Client::new()
+            .get(server.url("/api/v0.2/blocks/slow"))
+            .header("x-request-id", "slow-request")
+            .send();
+        let request = actix_rt::spawn(request);
+
+        actix_rt::time::sleep(SLOW_THRESHOLD / 2).await;
+        assert_eq!(in_flight(&watchdog, "blocks"), 1);
+        assert!(watchdog.check().is_empty());
+
+        actix_rt::time::sleep(SLOW_THRESHOLD).await;
+        let reports = watchdog.check();
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.request_id, "slow-request");
+        assert_eq!(report.endpoint, "GET /api/v0.2/blocks/slow");
+        assert!(report.elapsed >= SLOW_THRESHOLD);
+        assert!(report.busy >= BUSY_TIME);
+        assert!(report.idle >= SLOW_THRESHOLD - BUSY_TIME);
+        assert_eq!(report.busy + report.idle, report.elapsed);
+        // The request is reported once.
+        assert!(watchdog.check().is_empty());
+
+        let response = request.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(in_flight(&watchdog, "blocks"), 0);
+    }
+
+    #[actix_rt::test]
+    async fn requests_are_aborted_after_hard_timeout() {
+        let watchdog = watchdog(Some(SLOW_THRESHOLD));
+        let server = start_server(watchdog.clone());
+        let start = Instant::now();
+        let response = reqwest::Client::new()
+            .get(server.url("/api/v0.2/blocks/slow"))
+            .send()
+            .await
+            .unwrap();
+        assert!(start.elapsed() < SLOW_THRESHOLD * 3);
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let response: Response = response.json().await.unwrap();
+        let code: ErrorCode =
+            serde_json::from_value(response.error.unwrap()["code"].clone()).unwrap();
+        assert_eq!(code, ErrorCode::RequestTimedOut);
+        // The aborted request is no longer tracked.
+        assert_eq!(in_flight(&watchdog, "blocks"), 0);
+    }
+}
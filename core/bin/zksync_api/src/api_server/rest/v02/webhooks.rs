@@ -0,0 +1,739 @@
+//! Webhook subscriptions part of API implementation.
+//!
+//! The account owner subscribes to the transaction events of the account with a signed request
+//! and receives the subscription secret. The secret authenticates the requests managing
+//! the subscription and signs the events posted to the subscription URL. The logged delivery
+//! attempts can be inspected, and the event can be sent again with the body of its first attempt.
+
+// Built-in uses
+use std::{
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+
+// External uses
+use actix_web::{web, Scope};
+use actix_web_httpauth::extractors::bearer::BearerAuth;
+use chrono::Utc;
+use reqwest::Url;
+
+// Workspace uses
+use zksync_api_types::v02::webhook::{
+    CreatedWebhookSubscription, SignedWebhookSubscription, WebhookDeliveriesQuery, WebhookDelivery,
+    WebhookPreferences, WebhookSubscription, MAX_WEBHOOK_DELIVERIES_LIMIT, WEBHOOK_SECRET_PREFIX,
+};
+use zksync_config::configs::api::RestApiConfig;
+use zksync_crypto::rand::{OsRng, Rng};
+use zksync_storage::{webhooks::records, AccessIntent, ReplicatedPool};
+use zksync_types::event::EventId;
+
+// Local uses
+use super::{
+    error::{Error, WebhookError},
+    response::ApiResult,
+};
+use crate::api_server::webhooks::{post_event, preferences};
+
+/// Subscriptions signed earlier or later than this number of seconds from the current time are rejected.
+const SUBSCRIPTION_TIMESTAMP_TOLERANCE_SECS: i64 = 600;
+
+fn generate_secret() -> String {
+    let mut rng = OsRng::new().expect("Failed to access the OS random number generator");
+    let bytes: [u8; 32] = rng.gen();
+    format!("{}{}", WEBHOOK_SECRET_PREFIX, hex::encode(bytes))
+}
+
+fn validate_url(url: &str) -> Result<(), WebhookError> {
+    let url = Url::parse(url).map_err(|_| WebhookError::InvalidUrl)?;
+    if matches!(url.scheme(), "http" | "https") && url.host().is_some() {
+        Ok(())
+    } else {
+        Err(WebhookError::InvalidUrl)
+    }
+}
+
+fn api_subscription(
+    subscription: records::WebhookSubscription,
+) -> Result<WebhookSubscription, Error> {
+    Ok(WebhookSubscription {
+        id: subscription.id,
+        address: subscription.address,
+        url: subscription.url,
+        preferences: serde_json::from_value(subscription.preferences).map_err(Error::storage)?,
+        created_at: subscription.created_at,
+    })
+}
+
+fn api_delivery(delivery: records::WebhookDelivery) -> WebhookDelivery {
+    WebhookDelivery {
+        event_id: delivery.event_id,
+        created_at: delivery.created_at,
+        response_code: delivery.response_code,
+        latency_ms: delivery.latency.as_millis() as u64,
+        body_hash: format!("0x{}", hex::encode(delivery.body_hash)),
+        replay: delivery.replay,
+    }
+}
+
+/// Data for the webhooks endpoints.
+struct ApiWebhooksData {
+    pool: ReplicatedPool,
+    client: reqwest::Client,
+    delivery_timeout: Duration,
+}
+
+impl ApiWebhooksData {
+    fn new(pool: ReplicatedPool, config: &RestApiConfig) -> Self {
+        Self {
+            pool,
+            client: reqwest::Client::new(),
+            delivery_timeout: config.webhook_delivery_timeout(),
+        }
+    }
+
+    /// Stores the subscription signed by the account owner and returns its secret.
+    async fn subscribe(
+        &self,
+        signed: SignedWebhookSubscription,
+    ) -> Result<CreatedWebhookSubscription, Error> {
+        let request = signed.subscription;
+        let signer = signed
+            .signature
+            .signature_recover_signer_from_raw_message(request.message().as_bytes())
+            .map_err(|_| WebhookError::InvalidSignature)?;
+        if signer != request.address {
+            return Err(WebhookError::InvalidSignature.into());
+        }
+        let is_recent = i64::try_from(request.timestamp).map_or(false, |timestamp| {
+            (Utc::now().timestamp() - timestamp).abs() <= SUBSCRIPTION_TIMESTAMP_TOLERANCE_SECS
+        });
+        if !is_recent {
+            return Err(WebhookError::InvalidTimestamp.into());
+        }
+        validate_url(&request.url)?;
+        preferences::validate(&request.preferences).map_err(WebhookError::from)?;
+
+        let secret = generate_secret();
+        let preferences = serde_json::to_value(&request.preferences).map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let subscription = storage
+            .webhooks_schema()
+            .create_subscription(request.address, &request.url, &secret, &preferences)
+            .await
+            .map_err(Error::storage)?;
+
+        Ok(CreatedWebhookSubscription {
+            subscription: api_subscription(subscription)?,
+            secret,
+        })
+    }
+
+    /// Loads the subscription the secret belongs to. Unknown subscriptions are reported
+    /// the same way as the wrong secrets, so the subscriptions can't be enumerated.
+    async fn authenticate(
+        &self,
+        id: i64,
+        credentials: Option<BearerAuth>,
+    ) -> Result<records::WebhookSubscription, Error> {
+        let secret = credentials.ok_or(WebhookError::InvalidSecret)?;
+        // Updates should be seen at once, so the subscription is not read from the replicas.
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let subscription = storage
+            .webhooks_schema()
+            .load_subscription(id)
+            .await
+            .map_err(Error::storage)?
+            .ok_or(WebhookError::InvalidSecret)?;
+        // Hashes are compared, so the comparison time doesn't tell how much of the secret matches.
+        if tiny_keccak::keccak256(secret.token().as_bytes())
+            != tiny_keccak::keccak256(subscription.secret.as_bytes())
+        {
+            return Err(WebhookError::InvalidSecret.into());
+        }
+        Ok(subscription)
+    }
+
+    async fn subscription(
+        &self,
+        id: i64,
+        credentials: Option<BearerAuth>,
+    ) -> Result<WebhookSubscription, Error> {
+        api_subscription(self.authenticate(id, credentials).await?)
+    }
+
+    /// Replaces the preferences, they apply to the events not delivered yet.
+    async fn update_preferences(
+        &self,
+        id: i64,
+        credentials: Option<BearerAuth>,
+        preferences: WebhookPreferences,
+    ) -> Result<WebhookSubscription, Error> {
+        let mut subscription = self.authenticate(id, credentials).await?;
+        preferences::validate(&preferences).map_err(WebhookError::from)?;
+
+        subscription.preferences = serde_json::to_value(&preferences).map_err(Error::storage)?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let updated = storage
+            .webhooks_schema()
+            .update_preferences(id, &subscription.preferences)
+            .await
+            .map_err(Error::storage)?;
+        if !updated {
+            return Err(WebhookError::InvalidSecret.into());
+        }
+        api_subscription(subscription)
+    }
+
+    async fn unsubscribe(&self, id: i64, credentials: Option<BearerAuth>) -> Result<(), Error> {
+        self.authenticate(id, credentials).await?;
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        storage
+            .webhooks_schema()
+            .remove_subscription(id)
+            .await
+            .map_err(Error::storage)?;
+        Ok(())
+    }
+
+    async fn deliveries(
+        &self,
+        id: i64,
+        credentials: Option<BearerAuth>,
+        query: WebhookDeliveriesQuery,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        self.authenticate(id, credentials).await?;
+        let limit = query
+            .limit
+            .unwrap_or(MAX_WEBHOOK_DELIVERIES_LIMIT)
+            .min(MAX_WEBHOOK_DELIVERIES_LIMIT);
+        // The attempts are logged by the dispatchers right away, so they're not read from the replicas.
+        let mut storage = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?;
+        let deliveries = storage
+            .webhooks_schema()
+            .load_deliveries(id, query.from.unwrap_or(EventId(0)), limit)
+            .await
+            .map_err(Error::storage)?;
+        Ok(deliveries.into_iter().map(api_delivery).collect())
+    }
+
+    /// Sends the event again with the body of its first delivery attempt, marked as a replay.
+    /// The attempt is logged and returned whether it succeeds or not, the subscription cursor
+    /// is not affected.
+    async fn replay(
+        &self,
+        id: i64,
+        event_id: EventId,
+        credentials: Option<BearerAuth>,
+    ) -> Result<WebhookDelivery, Error> {
+        let subscription = self.authenticate(id, credentials).await?;
+        let payload = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?
+            .webhooks_schema()
+            .load_delivered_payload(id, event_id)
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::not_found("webhook delivery", event_id))?;
+
+        // The connection is not held while the subscriber responds.
+        let attempt = post_event(
+            &self.client,
+            &subscription,
+            event_id,
+            payload,
+            true,
+            self.delivery_timeout,
+        )
+        .await;
+        let delivery = self
+            .pool
+            .access_storage(AccessIntent::Write)
+            .await
+            .map_err(Error::storage)?
+            .webhooks_schema()
+            .store_delivery(attempt.as_new_delivery())
+            .await
+            .map_err(Error::storage)?;
+        metrics::increment_counter!("api.webhooks.replayed_events");
+        Ok(api_delivery(delivery))
+    }
+}
+
+// Server implementation
+
+async fn subscribe(
+    data: web::Data<ApiWebhooksData>,
+    web::Json(body): web::Json<SignedWebhookSubscription>,
+) -> ApiResult<CreatedWebhookSubscription> {
+    let start = Instant::now();
+    let res = data.subscribe(body).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "subscribe_webhook");
+    res
+}
+
+async fn get_subscription(
+    data: web::Data<ApiWebhooksData>,
+    id: web::Path<i64>,
+    credentials: Option<BearerAuth>,
+) -> ApiResult<WebhookSubscription> {
+    let start = Instant::now();
+    let res = data.subscription(*id, credentials).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_webhook");
+    res
+}
+
+async fn update_preferences(
+    data: web::Data<ApiWebhooksData>,
+    id: web::Path<i64>,
+    credentials: Option<BearerAuth>,
+    web::Json(body): web::Json<WebhookPreferences>,
+) -> ApiResult<WebhookSubscription> {
+    let start = Instant::now();
+    let res = data.update_preferences(*id, credentials, body).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "update_webhook_preferences");
+    res
+}
+
+async fn unsubscribe(
+    data: web::Data<ApiWebhooksData>,
+    id: web::Path<i64>,
+    credentials: Option<BearerAuth>,
+) -> ApiResult<()> {
+    let start = Instant::now();
+    let res = data.unsubscribe(*id, credentials).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "unsubscribe_webhook");
+    res
+}
+
+async fn deliveries(
+    data: web::Data<ApiWebhooksData>,
+    id: web::Path<i64>,
+    credentials: Option<BearerAuth>,
+    web::Query(query): web::Query<WebhookDeliveriesQuery>,
+) -> ApiResult<Vec<WebhookDelivery>> {
+    let start = Instant::now();
+    let res = data.deliveries(*id, credentials, query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "webhook_deliveries");
+    res
+}
+
+async fn replay(
+    data: web::Data<ApiWebhooksData>,
+    path: web::Path<(i64, EventId)>,
+    credentials: Option<BearerAuth>,
+) -> ApiResult<WebhookDelivery> {
+    let start = Instant::now();
+    let (id, event_id) = path.into_inner();
+    let res = data.replay(id, event_id, credentials).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "replay_webhook_delivery");
+    res
+}
+
+pub fn api_scope(pool: ReplicatedPool, config: &RestApiConfig) -> Scope {
+    let data = ApiWebhooksData::new(pool, config);
+
+    web::scope("webhooks")
+        .app_data(web::Data::new(data))
+        .route("", web::post().to(subscribe))
+        .route("{id}", web::get().to(get_subscription))
+        .route("{id}", web::delete().to(unsubscribe))
+        .route("{id}/preferences", web::put().to(update_preferences))
+        .route("{id}/deliveries", web::get().to(deliveries))
+        .route("{id}/deliveries/{event_id}/replay", web::post().to(replay))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::{
+        rest::v02::{
+            test_utils::{
+                assert_not_found, deserialize_response_result, dummy_deposit_op, dummy_fee_ticker,
+                TestServerConfig,
+            },
+            SharedData,
+        },
+        webhooks::WebhookDispatcher,
+    };
+    use actix_web::{http::StatusCode, web::Bytes, App, HttpRequest, HttpResponse};
+    use std::sync::Mutex;
+    use zksync_api_client::rest::client::Client;
+    use zksync_api_types::{
+        error_code::ErrorCode,
+        v02::{
+            webhook::{
+                webhook_signature, MinAmount, WebhookPayload, WebhookSubscriptionRequest,
+                WEBHOOK_EVENT_ID_HEADER, WEBHOOK_REPLAY_HEADER, WEBHOOK_SIGNATURE_HEADER,
+                WEBHOOK_TIMESTAMP_HEADER,
+            },
+            ApiVersion, Response,
+        },
+    };
+    use zksync_types::{
+        tx::PackedEthSignature, AccountId, Address, BlockNumber, ExecutedOperations, TokenId, H256,
+    };
+
+    fn start_server(cfg: &TestServerConfig) -> (Client, actix_test::TestServer) {
+        let cfg = cfg.clone();
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+            not_found_status: StatusCode::OK,
+        };
+        let server = actix_test::start(move || {
+            App::new().app_data(web::Data::new(shared_data)).service(
+                web::scope("/api/v0.2")
+                    .service(api_scope(cfg.replicated_pool(), &cfg.config.api.rest)),
+            )
+        });
+
+        let client = Client::new(server.url("").trim_end_matches('/').to_owned());
+        (client, server)
+    }
+
+    fn sign_subscription(
+        private_key: &H256,
+        subscription: WebhookSubscriptionRequest,
+    ) -> SignedWebhookSubscription {
+        let signature =
+            PackedEthSignature::sign(private_key, subscription.message().as_bytes()).unwrap();
+        SignedWebhookSubscription {
+            subscription,
+            signature,
+        }
+    }
+
+    /// Makes the request with the secret, returns the response.
+    async fn send_with_secret(
+        builder: reqwest::RequestBuilder,
+        secret: &str,
+    ) -> anyhow::Result<Response> {
+        Ok(builder.bearer_auth(secret).send().await?.json().await?)
+    }
+
+    fn error_code(response: &Response) -> Option<u64> {
+        response
+            .error
+            .as_ref()
+            .map(|error| error["code"].as_u64().unwrap())
+    }
+
+    #[test]
+    fn url_validation() {
+        assert!(validate_url("https://example.com/hooks?account=1").is_ok());
+        assert!(validate_url("http://127.0.0.1:8080").is_ok());
+        assert!(validate_url("ftp://example.com").is_err());
+        assert!(validate_url("example.com/hooks").is_err());
+        assert!(validate_url("").is_err());
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn webhooks_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let (client, server) = start_server(&cfg);
+        let http = reqwest::Client::new();
+
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key)?;
+        let request = WebhookSubscriptionRequest {
+            address,
+            url: "https://example.com/hooks".to_owned(),
+            preferences: WebhookPreferences::default(),
+            timestamp: Utc::now().timestamp() as u64,
+        };
+
+        // The subscription must be signed by the account owner, be recent and have a valid URL.
+        let invalid_requests = vec![
+            WebhookSubscriptionRequest {
+                address: Address::repeat_byte(0x42),
+                ..request.clone()
+            },
+            WebhookSubscriptionRequest {
+                timestamp: request.timestamp - 3600,
+                ..request.clone()
+            },
+            WebhookSubscriptionRequest {
+                url: "ftp://example.com".to_owned(),
+                ..request.clone()
+            },
+        ];
+        for invalid in invalid_requests {
+            let response = client
+                .subscribe_webhook(&sign_subscription(&private_key, invalid))
+                .await?;
+            assert_eq!(
+                error_code(&response),
+                Some(ErrorCode::InvalidWebhookSubscription as u64)
+            );
+        }
+
+        // Preferences that can never match are rejected.
+        let nft_usd = WebhookSubscriptionRequest {
+            preferences: WebhookPreferences {
+                tokens: Some(vec![TokenId(u32::MAX - 1)]),
+                min_amount: Some(MinAmount::Usd(10.into())),
+                ..Default::default()
+            },
+            ..request.clone()
+        };
+        let response = client
+            .subscribe_webhook(&sign_subscription(&private_key, nft_usd))
+            .await?;
+        assert_eq!(
+            error_code(&response),
+            Some(ErrorCode::InvalidWebhookPreferences as u64)
+        );
+
+        let response = client
+            .subscribe_webhook(&sign_subscription(&private_key, request))
+            .await?;
+        let created: CreatedWebhookSubscription = deserialize_response_result(response)?;
+        assert_eq!(created.subscription.address, address);
+        assert!(created.secret.starts_with(WEBHOOK_SECRET_PREFIX));
+        let url = server.url(&format!("/api/v0.2/webhooks/{}", created.subscription.id));
+
+        // The subscription is managed with its secret only.
+        let wrong_secret = format!("{}{}", WEBHOOK_SECRET_PREFIX, "00".repeat(32));
+        let response = send_with_secret(http.get(&url), &wrong_secret).await?;
+        assert_eq!(
+            error_code(&response),
+            Some(ErrorCode::InvalidWebhookSecret as u64)
+        );
+        let response = send_with_secret(http.get(&url), &created.secret).await?;
+        let subscription: WebhookSubscription = deserialize_response_result(response)?;
+        assert_eq!(subscription, created.subscription);
+
+        let preferences = WebhookPreferences {
+            tokens: Some(vec![TokenId(0)]),
+            min_amount: Some(MinAmount::Usd(10.into())),
+            ..Default::default()
+        };
+        let preferences_url = format!("{}/preferences", url);
+        let response =
+            send_with_secret(http.put(&preferences_url).json(&preferences), &wrong_secret).await?;
+        assert_eq!(
+            error_code(&response),
+            Some(ErrorCode::InvalidWebhookSecret as u64)
+        );
+        let response = send_with_secret(
+            http.put(&preferences_url).json(&preferences),
+            &created.secret,
+        )
+        .await?;
+        let subscription: WebhookSubscription = deserialize_response_result(response)?;
+        assert_eq!(subscription.preferences, preferences);
+
+        // The amount in the token units needs the token to be selected.
+        let without_token = WebhookPreferences {
+            min_amount: Some(MinAmount::Token(1000u32.into())),
+            ..Default::default()
+        };
+        let response = send_with_secret(
+            http.put(&preferences_url).json(&without_token),
+            &created.secret,
+        )
+        .await?;
+        assert_eq!(
+            error_code(&response),
+            Some(ErrorCode::InvalidWebhookPreferences as u64)
+        );
+
+        let response = send_with_secret(http.delete(&url), &created.secret).await?;
+        assert_eq!(error_code(&response), None);
+        let response = send_with_secret(http.get(&url), &created.secret).await?;
+        assert_eq!(
+            error_code(&response),
+            Some(ErrorCode::InvalidWebhookSecret as u64)
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Request received by the subscriber: the headers and the body.
+    type ReceivedRequest = (Vec<(String, String)>, Bytes);
+
+    /// Receives the events, fails the ones delivered by the dispatcher and accepts the replays.
+    async fn receive(
+        req: HttpRequest,
+        body: Bytes,
+        received: web::Data<Mutex<Vec<ReceivedRequest>>>,
+    ) -> HttpResponse {
+        let headers = req
+            .headers()
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_owned()))
+            .collect();
+        received.lock().unwrap().push((headers, body));
+
+        let is_replay = req
+            .headers()
+            .get(WEBHOOK_REPLAY_HEADER)
+            .map_or(false, |value| value == "true");
+        if is_replay {
+            HttpResponse::Ok().finish()
+        } else {
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> &'a str {
+        headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+            .unwrap()
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn replay_failed_delivery() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let (_client, server) = start_server(&cfg);
+        let http = reqwest::Client::new();
+
+        let received = web::Data::new(Mutex::new(Vec::<ReceivedRequest>::new()));
+        let subscriber = {
+            let received = received.clone();
+            actix_test::start(move || {
+                App::new()
+                    .app_data(received.clone())
+                    .route("/hooks", web::post().to(receive))
+            })
+        };
+
+        let address = Address::random();
+        let secret = generate_secret();
+        let (subscription, event_id) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let subscription = storage
+                .webhooks_schema()
+                .create_subscription(
+                    address,
+                    &subscriber.url("/hooks"),
+                    &secret,
+                    &serde_json::to_value(WebhookPreferences::default())?,
+                )
+                .await?;
+            let deposit = dummy_deposit_op(address, AccountId(0xbeef), 1, 0);
+            storage
+                .event_schema()
+                .store_executed_transaction_event(
+                    BlockNumber(1),
+                    &[ExecutedOperations::PriorityOp(Box::new(deposit))],
+                )
+                .await?;
+            let event_id = storage
+                .event_schema()
+                .get_last_event_id()
+                .await?
+                .expect("Deposit event should be stored");
+            (subscription, event_id)
+        };
+
+        // The subscriber fails the delivery, so it's retried later.
+        let dispatcher = WebhookDispatcher::new(
+            cfg.pool.clone(),
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.rest,
+        );
+        dispatcher.dispatch(subscription.clone()).await?;
+        let (headers, body) = received.lock().unwrap()[0].clone();
+        assert_eq!(header(&headers, WEBHOOK_REPLAY_HEADER), "false");
+        assert_eq!(
+            header(&headers, WEBHOOK_EVENT_ID_HEADER),
+            event_id.to_string()
+        );
+        let payload: WebhookPayload = serde_json::from_slice(&body)?;
+        assert_eq!(payload.event.id, event_id);
+
+        let url = server.url(&format!("/api/v0.2/webhooks/{}", subscription.id));
+        let deliveries_url = format!("{}/deliveries?from={}", url, event_id);
+        let response = send_with_secret(http.get(&deliveries_url), &secret).await?;
+        let deliveries: Vec<WebhookDelivery> = deserialize_response_result(response)?;
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].event_id, event_id);
+        assert_eq!(deliveries[0].response_code, Some(500));
+        assert!(!deliveries[0].replay);
+
+        // The replay is authenticated with the subscription secret.
+        let replay_url = format!("{}/deliveries/{}/replay", url, event_id);
+        let wrong_secret = format!("{}{}", WEBHOOK_SECRET_PREFIX, "00".repeat(32));
+        let response = send_with_secret(http.post(&replay_url), &wrong_secret).await?;
+        assert_eq!(
+            error_code(&response),
+            Some(ErrorCode::InvalidWebhookSecret as u64)
+        );
+        let response = send_with_secret(http.get(&deliveries_url), &wrong_secret).await?;
+        assert_eq!(
+            error_code(&response),
+            Some(ErrorCode::InvalidWebhookSecret as u64)
+        );
+
+        // The replay sends the same body, marked as a replay under the signature.
+        let response = send_with_secret(http.post(&replay_url), &secret).await?;
+        let replayed: WebhookDelivery = deserialize_response_result(response)?;
+        assert_eq!(replayed.event_id, event_id);
+        assert_eq!(replayed.response_code, Some(200));
+        assert!(replayed.replay);
+        assert_eq!(replayed.body_hash, deliveries[0].body_hash);
+
+        let (replay_headers, replay_body) = received.lock().unwrap()[1].clone();
+        assert_eq!(replay_body, body);
+        assert_eq!(header(&replay_headers, WEBHOOK_REPLAY_HEADER), "true");
+        let timestamp: i64 = header(&replay_headers, WEBHOOK_TIMESTAMP_HEADER).parse()?;
+        assert_eq!(
+            header(&replay_headers, WEBHOOK_SIGNATURE_HEADER),
+            webhook_signature(&secret, timestamp, true, &replay_body)
+        );
+
+        let response = send_with_secret(http.get(&deliveries_url), &secret).await?;
+        let deliveries: Vec<WebhookDelivery> = deserialize_response_result(response)?;
+        assert_eq!(deliveries.len(), 2);
+        assert_eq!(deliveries[1], replayed);
+
+        // Only the delivered events can be replayed.
+        let unknown_url = format!("{}/deliveries/{}/replay", url, *event_id + 1);
+        let response = send_with_secret(http.post(&unknown_url), &secret).await?;
+        assert_not_found(response, "webhook delivery")?;
+
+        cfg.pool
+            .access_storage()
+            .await?
+            .webhooks_schema()
+            .remove_subscription(subscription.id)
+            .await?;
+        subscriber.stop().await;
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+//! Warm-up routine of the REST API server.
+//!
+//! Right after the start all the caches of the API server are cold, so the first
+//! requests may take too long. The warm-up routine fills the most important caches
+//! and only then the server is reported as ready to serve requests.
+
+// Built-in uses
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+
+// External uses
+use futures::future::try_join_all;
+
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::H256;
+
+// Local uses
+use crate::{
+    core_api_client::CoreApiClient,
+    utils::{block_details_cache::BlockDetailsCache, token_cache::TokenCache},
+};
+
+/// Amount of connections to be opened in the storage pool during the warm-up.
+const PRIMED_CONNECTIONS: usize = 2;
+/// Delay before the next warm-up attempt if the previous one has failed.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Readiness of the API server to serve requests.
+///
+/// Unlike liveness, the server becomes ready only after the warm-up is completed.
+#[derive(Debug, Clone, Default)]
+pub struct ApiReadiness(Arc<AtomicBool>);
+
+impl ApiReadiness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    fn set_ready(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Warms up the caches used by the API server.
+#[derive(Debug, Clone)]
+pub struct ApiWarmUp {
+    pool: ConnectionPool,
+    tokens: TokenCache,
+    blocks: BlockDetailsCache,
+    core_client: CoreApiClient,
+    readiness: ApiReadiness,
+}
+
+impl ApiWarmUp {
+    pub fn new(
+        pool: ConnectionPool,
+        tokens: TokenCache,
+        blocks: BlockDetailsCache,
+        core_client: CoreApiClient,
+        readiness: ApiReadiness,
+    ) -> Self {
+        Self {
+            pool,
+            tokens,
+            blocks,
+            core_client,
+            readiness,
+        }
+    }
+
+    /// Performs the warm-up until it succeeds and marks the server as ready.
+    pub async fn run(mut self) {
+        let start = Instant::now();
+        while let Err(err) = self.warm_up().await {
+            vlog::warn!("API warm-up failed, retrying: {}", err);
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+        self.readiness.set_ready();
+
+        vlog::info!("API warm-up completed in {:?}", start.elapsed());
+        metrics::histogram!("api.warm_up", start.elapsed());
+    }
+
+    async fn warm_up(&mut self) -> anyhow::Result<()> {
+        // Open several connections, they are returned to the pool and reused afterwards.
+        let connections =
+            try_join_all((0..PRIMED_CONNECTIONS).map(|_| self.pool.access_storage())).await?;
+        drop(connections);
+
+        let mut storage = self.pool.access_storage().await?;
+        self.tokens.reload(&mut storage).await?;
+
+        // Load the last finalized block, so the finality of the recent operations
+        // is resolved from the cache.
+        let last_finalized = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        if *last_finalized > 0 {
+            self.blocks.get(&self.pool, last_finalized).await?;
+        }
+
+        // Perform the same query as the pending operations requests do.
+        storage
+            .chain()
+            .mempool_schema()
+            .get_pending_operation_by_hash(H256::zero())
+            .await?;
+
+        // Core server may be temporarily unavailable, it should not block the API server.
+        if let Err(err) = self.core_client.get_status().await {
+            vlog::warn!("Unable to reach the core API during the warm-up: {}", err);
+        }
+
+        Ok(())
+    }
+}
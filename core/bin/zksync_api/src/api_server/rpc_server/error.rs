@@ -1,112 +1,77 @@
 // External uses
-use jsonrpc_core::ErrorCode;
-use zksync_types::tx::error::TxAddError;
+use serde_json::Value;
 // Workspace uses
+use zksync_api_types::error_code::ErrorCode;
 // Local uses
 use crate::api_server::tx_sender::SubmitError;
 
-#[derive(Debug, Clone, Copy)]
-pub enum RpcErrorCodes {
-    NonceMismatch = 101,
-    IncorrectTx = 103,
-    FeeTooLow = 104,
-    InappropriateFeeToken = 105,
-
-    MissingEthSignature = 200,
-    EIP1271SignatureVerificationFail = 201,
-    IncorrectEthSignature = 202,
-    ChangePkNotAuthorized = 203,
-
-    Other = 300,
-    AccountCloseDisabled = 301,
-    OperationsLimitReached = 302,
-    UnsupportedFastProcessing = 303,
-    Toggle2FA = 304,
-}
-
-impl From<TxAddError> for RpcErrorCodes {
-    fn from(error: TxAddError) -> Self {
-        match error {
-            TxAddError::NonceMismatch => Self::NonceMismatch,
-            TxAddError::IncorrectTx(_) => Self::IncorrectTx,
-            TxAddError::TxFeeTooLow => Self::FeeTooLow,
-            TxAddError::TxBatchFeeTooLow => Self::FeeTooLow,
-            TxAddError::MissingEthSignature => Self::MissingEthSignature,
-            TxAddError::EIP1271SignatureVerificationFail => Self::EIP1271SignatureVerificationFail,
-            TxAddError::IncorrectEthSignature => Self::IncorrectEthSignature,
-            TxAddError::ChangePkNotAuthorized => Self::ChangePkNotAuthorized,
-            TxAddError::Other => Self::Other,
-            TxAddError::DbError => Self::Other,
-            TxAddError::EmptyBatch => Self::Other,
-            TxAddError::BatchTooBig => Self::Other,
-            TxAddError::BatchWithdrawalsOverload => Self::Other,
-            TxAddError::EthSignaturesLimitExceeded => Self::Other,
-        }
+/// Creates the JSON-RPC error with the code from the registry shared with the REST API.
+/// The name of the code is sent in the `data` object along with the details of the error.
+pub fn rpc_error(
+    code: ErrorCode,
+    message: impl Into<String>,
+    data: Option<Value>,
+) -> jsonrpc_core::Error {
+    let mut details = serde_json::json!({ "name": code.name() });
+    if let Some(data) = data {
+        details["data"] = data;
     }
-}
-
-impl From<RpcErrorCodes> for ErrorCode {
-    fn from(val: RpcErrorCodes) -> Self {
-        (val as i64).into()
+    jsonrpc_core::Error {
+        code: i64::from(code.code()).into(),
+        message: message.into(),
+        data: Some(details),
     }
 }
 
 impl From<SubmitError> for jsonrpc_core::Error {
     fn from(inner: SubmitError) -> Self {
-        match inner {
-            SubmitError::AccountCloseDisabled => Self {
-                code: RpcErrorCodes::AccountCloseDisabled.into(),
-                message: "Account close tx is disabled.".to_string(),
-                data: None,
-            },
+        let code = inner.error_code();
+        let data = inner.error_data();
+        let message = match inner {
+            SubmitError::InvalidParams(message)
+            | SubmitError::IncorrectTx(message)
+            | SubmitError::Other(message) => message,
+            SubmitError::TxAdd(error) => error.to_string(),
+            SubmitError::Toggle2FA(error) => error.to_string(),
+            SubmitError::AccountLock(error) => error.to_string(),
+            SubmitError::PriceError(error) => error.to_string(),
+            SubmitError::Internal(error) => error.to_string(),
+            SubmitError::MempoolCommunication(_) => "Error communicating core server".to_string(),
+            _ => inner.to_string(),
+        };
+        rpc_error(code, message, data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::tx::error::TxAddError;
+
+    #[test]
+    fn submit_error_conversion() {
+        let error = jsonrpc_core::Error::from(SubmitError::TxAdd(TxAddError::NonceMismatch));
+        assert_eq!(error.code.code(), ErrorCode::NonceMismatch as i64);
+        assert_eq!(error.message, "Tx nonce is too low.");
+        assert_eq!(
+            error.data,
+            Some(serde_json::json!({ "name": "NonceMismatch" }))
+        );
+
+        let error = jsonrpc_core::Error::from(SubmitError::ServerOverloaded {
+            retry_after_secs: 3,
+        });
+        assert_eq!(error.code.code(), ErrorCode::ServerOverloaded as i64);
+        assert_eq!(
+            error.data,
+            Some(serde_json::json!({
+                "name": "ServerOverloaded",
+                "data": { "retryAfter": 3 },
+            }))
+        );
 
-            SubmitError::InvalidParams(msg) => Self::invalid_params(msg),
-            SubmitError::UnsupportedFastProcessing => Self {
-                code: RpcErrorCodes::UnsupportedFastProcessing.into(),
-                message: "Fast processing available only for 'withdraw' operation type."
-                    .to_string(),
-                data: None,
-            },
-            SubmitError::IncorrectTx(message) => Self {
-                code: RpcErrorCodes::IncorrectTx.into(),
-                message,
-                data: None,
-            },
-            SubmitError::TxAdd(inner) => Self {
-                code: RpcErrorCodes::from(inner).into(),
-                message: inner.to_string(),
-                data: None,
-            },
-            SubmitError::Toggle2FA(inner) => Self {
-                code: RpcErrorCodes::Toggle2FA.into(),
-                message: inner.to_string(),
-                data: None,
-            },
-            SubmitError::InappropriateFeeToken => Self {
-                code: RpcErrorCodes::InappropriateFeeToken.into(),
-                message: inner.to_string(),
-                data: None,
-            },
-            SubmitError::MempoolCommunication(reason) => Self {
-                code: RpcErrorCodes::Other.into(),
-                message: "Error communicating core server".to_string(),
-                data: Some(reason.into()),
-            },
-            SubmitError::Internal(msg) => Self {
-                code: ErrorCode::InternalError,
-                message: msg.to_string(),
-                data: None,
-            },
-            SubmitError::Other(message) => Self {
-                code: ErrorCode::InternalError,
-                message,
-                data: None,
-            },
-            SubmitError::PriceError(error) => Self {
-                code: ErrorCode::InternalError,
-                message: error.to_string(),
-                data: None,
-            },
-        }
+        let error = jsonrpc_core::Error::from(SubmitError::InvalidParams("bad".to_owned()));
+        assert_eq!(error.code.code(), ErrorCode::InvalidParams as i64);
+        assert_eq!(error.message, "bad");
     }
 }
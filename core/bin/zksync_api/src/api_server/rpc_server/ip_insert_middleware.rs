@@ -8,7 +8,8 @@ use jsonrpc_http_server::{RequestMiddleware, RequestMiddlewareAction};
 
 use super::types::RequestMetadata;
 
-const CLOUDFLARE_CONNECTING_IP_HEADER: &str = "CF-Connecting-IP";
+pub(super) const CLOUDFLARE_CONNECTING_IP_HEADER: &str = "CF-Connecting-IP";
+const API_KEY_HEADER: &str = "X-Api-Key";
 const METADATA_PARAM_NAME: &str = "extracted_request_metadata";
 
 /// Unfortunately, the JSON-RPC library does not natively support retrieving any information about the HTTP request,
@@ -17,7 +18,7 @@ const METADATA_PARAM_NAME: &str = "extracted_request_metadata";
 /// header of HTTP request. This header IP inserted by Cloudflare and users can never set it by themselves.
 ///
 /// IpInsertMiddleWare is the middleware that gets the value of the `CF-Connecting-IP` header of the HTTP request and appends it as the last
-/// parameter of the JSON-RPC call.
+/// parameter of the JSON-RPC call. The API key from the `X-Api-Key` header, if any, is passed along with the IP.
 pub struct IpInsertMiddleWare;
 
 /// Structure that is used to describe the minimum and the maximum number
@@ -44,6 +45,7 @@ impl MethodWithIpDescription {
 fn get_call_with_ip_if_needed(
     mut call: jsonrpc_core::MethodCall,
     ip: Option<String>,
+    api_key: Option<String>,
 ) -> jsonrpc_core::MethodCall {
     // Methods, which should have the information about the ip appended to them
     let methods_with_ip: HashMap<&'static str, MethodWithIpDescription> = HashMap::from_iter([
@@ -64,7 +66,7 @@ fn get_call_with_ip_if_needed(
     };
 
     let metadata = ip.map(|ip| {
-        let metadata = RequestMetadata { ip };
+        let metadata = RequestMetadata { ip, api_key };
         serde_json::to_value(metadata).unwrap()
     });
 
@@ -111,7 +113,11 @@ fn get_call_with_ip_if_needed(
 /// Given the HTTP body of the JSON-RPC request and the IP of the user, inserts the information about it
 /// in the call (if needed) and returns the bytes of the new body.
 /// If the IP supplied is None, the method makes sure that the user could not pass the IP
-async fn insert_ip_if_needed(body: hyper::Body, ip: Option<String>) -> hyper::Result<Vec<u8>> {
+async fn insert_ip_if_needed(
+    body: hyper::Body,
+    ip: Option<String>,
+    api_key: Option<String>,
+) -> hyper::Result<Vec<u8>> {
     let body_stream: Vec<_> = body.collect().await;
 
     let mut body_bytes = vec![];
@@ -123,7 +129,7 @@ async fn insert_ip_if_needed(body: hyper::Body, ip: Option<String>) -> hyper::Re
         serde_json::from_slice(&body_bytes);
 
     if let Ok(call) = call {
-        let new_call = get_call_with_ip_if_needed(call, ip);
+        let new_call = get_call_with_ip_if_needed(call, ip, api_key);
         let new_body_bytes = serde_json::to_vec(&new_call);
         if let Ok(s) = new_body_bytes {
             body_bytes = s;
@@ -141,9 +147,14 @@ impl RequestMiddleware for IpInsertMiddleWare {
             .headers
             .get(CLOUDFLARE_CONNECTING_IP_HEADER)
             .map(|ip| ip.to_str().map(|s| s.to_owned()));
+        let api_key = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .and_then(|key| key.to_str().ok())
+            .map(|key| key.to_owned());
 
         let proceed = move |ip: Option<String>| {
-            let body_bytes = insert_ip_if_needed(body, ip).into_stream();
+            let body_bytes = insert_ip_if_needed(body, ip, api_key).into_stream();
             let body = hyper::Body::wrap_stream(body_bytes);
             RequestMiddlewareAction::Proceed {
                 should_continue_on_invalid_cors: false,
@@ -190,7 +201,7 @@ mod tests {
         ip: Option<String>,
     ) {
         let call = get_method_call(method, params);
-        let processed_call = get_call_with_ip_if_needed(call, ip);
+        let processed_call = get_call_with_ip_if_needed(call, ip, None);
 
         assert_eq!(processed_call.params, expected_result_params);
     }
@@ -223,6 +234,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn insert_api_key_test() {
+        let params = Params::Array(vec![
+            Value::String("serialized_transfer".to_owned()),
+            Value::String("some_signature".to_owned()),
+        ]);
+        let call = get_method_call("tx_submit".to_owned(), params);
+        let processed_call =
+            get_call_with_ip_if_needed(call, Some(IP.to_owned()), Some("key".to_owned()));
+        assert_eq!(
+            processed_call.params,
+            Params::Array(vec![
+                Value::String("serialized_transfer".to_owned()),
+                Value::String("some_signature".to_owned()),
+                Value::Null,
+                json!({ "ip": IP, "api_key": "key" }),
+            ])
+        );
+    }
+
     #[test]
     fn prevent_user_from_overriding_metadata() {
         let params = Params::Array(vec![
@@ -3,16 +3,15 @@ use std::time::Instant;
 
 // External uses
 use futures::channel::mpsc;
-use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
+use jsonrpc_core::{Error, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
 use tokio::task::JoinHandle;
 
 // Workspace uses
-use zksync_config::configs::api::{CommonApiConfig, JsonRpcConfig, TokenConfig};
+use zksync_config::configs::api::{CommonApiConfig, JsonRpcConfig};
 use zksync_storage::{
     chain::{
         block::records::StorageBlockDetails, operations::records::StoredExecutedPriorityOperation,
-        operations_ext::records::TxReceiptResponse,
     },
     ConnectionPool, StorageProcessor,
 };
@@ -27,18 +26,26 @@ mod ip_insert_middleware;
 mod rpc_impl;
 mod rpc_trait;
 pub mod types;
+mod usage;
 
 pub use self::rpc_trait::Rpc;
 use self::types::*;
-use super::tx_sender::TxSender;
+use super::{
+    tx_info::{self, TxInfo},
+    tx_sender::TxSender,
+};
 use crate::fee_ticker::FeeTicker;
+use crate::utils::{
+    banned_addresses::BannedAddresses, disabled_tx_types::DisabledTxTypes, token_cache::TokenCache,
+};
 use ip_insert_middleware::IpInsertMiddleWare;
+use usage::{CallerMeta, MethodsUsage, MethodsUsageMiddleware};
 use zksync_mempool::MempoolTransactionRequest;
 
 #[derive(Clone)]
 pub struct RpcApp {
     cache_of_executed_priority_operations: AsyncLruCache<u32, StoredExecutedPriorityOperation>,
-    cache_of_transaction_receipts: AsyncLruCache<Vec<u8>, TxReceiptResponse>,
+    cache_of_transaction_receipts: AsyncLruCache<Vec<u8>, TxInfo>,
     cache_of_complete_withdrawal_tx_hashes: AsyncLruCache<TxHash, String>,
 
     pub confirmations_for_eth_event: u64,
@@ -53,22 +60,30 @@ impl RpcApp {
         sign_verify_request_sender: mpsc::Sender<VerifySignatureRequest>,
         ticker: FeeTicker,
         config: &CommonApiConfig,
-        token_config: &TokenConfig,
+        tokens: TokenCache,
         confirmations_for_eth_event: u64,
         chain_id: ChainId,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     ) -> Self {
         let api_requests_caches_size = config.caches_size;
 
+        let disabled_tx_types = DisabledTxTypes::new(connection_pool.clone());
+        disabled_tx_types.spawn_invalidation_listener();
+        let banned_addresses = BannedAddresses::new(connection_pool.clone());
+        banned_addresses.spawn_invalidation_listener();
+
         let tx_sender = TxSender::new(
             connection_pool,
             sign_verify_request_sender,
             ticker,
             config,
-            token_config,
+            tokens,
             mempool_tx_sender,
+            disabled_tx_types,
+            banned_addresses,
             chain_id,
         );
+        tx_sender.account_locks.spawn_invalidation_listener();
 
         RpcApp {
             cache_of_executed_priority_operations: AsyncLruCache::new(api_requests_caches_size),
@@ -144,20 +159,17 @@ impl RpcApp {
         Ok(res)
     }
 
-    async fn get_tx_receipt(&self, tx_hash: TxHash) -> Result<Option<TxReceiptResponse>> {
+    async fn get_tx_info(&self, tx_hash: TxHash) -> Result<Option<TxInfo>> {
         let start = Instant::now();
-        let res = if let Some(tx_receipt) = self
+        let res = if let Some(tx_info) = self
             .cache_of_transaction_receipts
             .get(&tx_hash.as_ref().to_vec())
             .await
         {
-            Some(tx_receipt)
+            Some(tx_info)
         } else {
             let mut storage = self.access_storage().await?;
-            let tx_receipt = storage
-                .chain()
-                .operations_ext_schema()
-                .tx_receipt(tx_hash.as_ref())
+            let tx_info = tx_info::load_tx_info(&mut storage, tx_hash)
                 .await
                 .map_err(|err| {
                     vlog::warn!(
@@ -168,15 +180,15 @@ impl RpcApp {
                     Error::internal_error()
                 })?;
 
-            if let Some(tx_receipt) = tx_receipt.clone() {
-                if tx_receipt.verified {
+            if let Some(tx_info) = tx_info.clone() {
+                if tx_info.is_final() {
                     self.cache_of_transaction_receipts
-                        .insert(tx_hash.as_ref().to_vec(), tx_receipt)
+                        .insert(tx_hash.as_ref().to_vec(), tx_info)
                         .await;
                 }
             }
 
-            tx_receipt
+            tx_info
         };
 
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_tx_receipt");
@@ -262,11 +274,12 @@ impl RpcApp {
 #[must_use]
 pub fn start_rpc_server(
     connection_pool: ConnectionPool,
+    usage_connection_pool: ConnectionPool,
     sign_verify_request_sender: mpsc::Sender<VerifySignatureRequest>,
     ticker: FeeTicker,
     config: &JsonRpcConfig,
     common_api_config: &CommonApiConfig,
-    token_config: &TokenConfig,
+    tokens: TokenCache,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     chain_id: ChainId,
     confirmations_for_eth_event: u64,
@@ -277,19 +290,24 @@ pub fn start_rpc_server(
         sign_verify_request_sender,
         ticker,
         common_api_config,
-        token_config,
+        tokens,
         confirmations_for_eth_event,
         chain_id,
         mempool_tx_sender,
     );
 
+    let methods_usage = MethodsUsage::new(config.track_usage_per_subnet);
+    methods_usage.spawn_flusher(usage_connection_pool, config.usage_flush_interval());
+    let usage_middleware =
+        MethodsUsageMiddleware::new(methods_usage, config.deprecated_methods.clone());
+
     let (handler, panic_sender) = spawn_panic_handler();
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
-        let mut io = IoHandler::new();
+        let mut io = MetaIoHandler::with_middleware(usage_middleware);
         rpc_app.extend(&mut io);
 
-        let server = ServerBuilder::new(io)
+        let server = ServerBuilder::with_meta_extractor(io, CallerMeta::from_request)
             .threads(super::THREADS_PER_SERVER)
             .request_middleware(IpInsertMiddleWare {})
             .start_http(&addr)
@@ -5,7 +5,9 @@ use bigdecimal::BigDecimal;
 use jsonrpc_core::{Error, Result};
 // Workspace uses
 use zksync_api_types::{
+    error_code::ErrorCode,
     v02::{
+        account::EthAccountType,
         fee::ApiTxFeeTypes,
         token::ApiNFT,
         transaction::{Toggle2FA, Toggle2FAResponse},
@@ -20,7 +22,8 @@ use zksync_types::{
 // Local uses
 use crate::{
     api_server::{
-        helpers::get_depositing, rpc_server::error::RpcErrorCodes, tx_sender::SubmitError,
+        helpers::get_depositing, rpc_server::error::rpc_error, tx_info::TxInfoShape,
+        tx_sender::SubmitError,
     },
     fee_ticker::TokenPriceRequestType,
 };
@@ -52,7 +55,7 @@ impl RpcApp {
             Error::internal_error()
         })?;
         let account_type = if let Some(account_id) = account_state.account_id {
-            storage
+            let stored_type = storage
                 .chain()
                 .account_schema()
                 .account_type_by_id(account_id)
@@ -67,7 +70,11 @@ impl RpcApp {
                     );
                     Error::internal_error()
                 })?
-                .map(|t| t.into())
+                .map(|t| t.into());
+            Some(EthAccountType::for_account(
+                stored_type,
+                &account_state.committed.pub_key_hash,
+            ))
         } else {
             None
         };
@@ -113,27 +120,11 @@ impl RpcApp {
 
     pub async fn _impl_tx_info(self, tx_hash: TxHash) -> Result<TransactionInfoResp> {
         let start = Instant::now();
-        let stored_receipt = self.get_tx_receipt(tx_hash).await?;
+        let tx_info = self.get_tx_info(tx_hash).await?;
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "tx_info");
-        Ok(if let Some(stored_receipt) = stored_receipt {
-            TransactionInfoResp {
-                executed: true,
-                success: Some(stored_receipt.success),
-                fail_reason: stored_receipt.fail_reason,
-                block: Some(BlockInfo {
-                    block_number: stored_receipt.block_number,
-                    committed: true,
-                    verified: stored_receipt.verified,
-                }),
-            }
-        } else {
-            TransactionInfoResp {
-                executed: false,
-                success: None,
-                fail_reason: None,
-                block: None,
-            }
-        })
+        Ok(tx_info
+            .map(TransactionInfoResp::from_tx_info)
+            .unwrap_or_else(TransactionInfoResp::unknown))
     }
 
     #[allow(deprecated)]
@@ -280,15 +271,9 @@ impl RpcApp {
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<Fee> {
         let start = Instant::now();
-        let token_allowed = self
-            .tx_sender
-            .ticker
-            .token_allowed_for_fees(token.clone())
-            .await
-            .map_err(SubmitError::Internal)?;
-        if !token_allowed {
-            return Err(SubmitError::InappropriateFeeToken.into());
-        }
+        self.tx_sender
+            .check_fee_token(&[tx_type.transaction_type()], token.clone())
+            .await?;
 
         let result = self
             .tx_sender
@@ -326,22 +311,20 @@ impl RpcApp {
     ) -> Result<TotalFee> {
         let start = Instant::now();
         if tx_types.len() != addresses.len() {
-            return Err(Error {
-                code: RpcErrorCodes::IncorrectTx.into(),
-                message: "Number of tx_types must be equal to the number of addresses".to_string(),
-                data: None,
-            });
+            return Err(rpc_error(
+                ErrorCode::IncorrectTx,
+                "Number of tx_types must be equal to the number of addresses",
+                None,
+            ));
         }
 
-        let token_allowed = self
-            .tx_sender
-            .ticker
-            .token_allowed_for_fees(token.clone())
-            .await
-            .map_err(|_| Error::internal_error())?;
-        if !token_allowed {
-            return Err(SubmitError::InappropriateFeeToken.into());
-        }
+        let fee_tx_types: Vec<_> = tx_types
+            .iter()
+            .map(ApiTxFeeTypes::transaction_type)
+            .collect();
+        self.tx_sender
+            .check_fee_token(&fee_tx_types, token.clone())
+            .await?;
 
         let transactions: Vec<(TxFeeTypes, Address)> = (tx_types
             .iter()
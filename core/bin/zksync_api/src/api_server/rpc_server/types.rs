@@ -11,10 +11,11 @@ use zksync_api_types::v02::{
 };
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
 use zksync_storage::StorageProcessor;
-use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{Account, AccountId, Address, Nonce, PubKeyHash, TokenId};
 use zksync_utils::BigUintSerdeWrapper;
 
+use crate::utils::token_cache::TokenCache;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseAccountState {
@@ -28,7 +29,7 @@ pub struct ResponseAccountState {
 impl ResponseAccountState {
     pub async fn try_restore(
         storage: &mut StorageProcessor<'_>,
-        tokens: &TokenDBCache,
+        tokens: &TokenCache,
         account: Account,
     ) -> Result<Self> {
         let mut balances = HashMap::new();
@@ -131,4 +132,7 @@ pub struct ContractAddressResp {
 pub struct RequestMetadata {
     /// The ip of the call origin
     pub ip: String,
+    /// The API key passed in the `X-Api-Key` header of the call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
 }
@@ -0,0 +1,328 @@
+//! Tracking of the JSON-RPC methods usage.
+//!
+//! Before removing the legacy methods we need to know who still calls them, so every call is counted
+//! per method and day (and optionally per caller subnet). Counters are kept in memory and are periodically
+//! flushed to the database with a single query, so tracking doesn't slow down the calls themselves.
+
+// Built-in uses
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+// External uses
+use chrono::{NaiveDate, Utc};
+use futures::{future::Either, FutureExt};
+use jsonrpc_core::{
+    middleware::NoopFuture, BoxFuture, Call, ErrorCode, Metadata, Middleware, Output,
+};
+use serde_json::Value;
+use tokio::{task::JoinHandle, time};
+
+// Workspace uses
+use zksync_storage::{misc::records::RpcMethodUsage, ConnectionPool};
+
+// Local uses
+use super::ip_insert_middleware::CLOUDFLARE_CONNECTING_IP_HEADER;
+
+/// Name of the field added to the results of the deprecated methods.
+const DEPRECATED_FIELD: &str = "deprecated";
+
+/// Metadata of the JSON-RPC call retrieved from the HTTP request.
+#[derive(Debug, Clone, Default)]
+pub struct CallerMeta {
+    /// IP of the caller, if the request came through Cloudflare.
+    pub ip: Option<String>,
+}
+
+impl Metadata for CallerMeta {}
+
+impl CallerMeta {
+    pub fn from_request(request: &hyper::Request<hyper::Body>) -> Self {
+        let ip = request
+            .headers()
+            .get(CLOUDFLARE_CONNECTING_IP_HEADER)
+            .and_then(|ip| ip.to_str().ok())
+            .map(str::to_owned);
+        Self { ip }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct UsageKey {
+    method: String,
+    day: NaiveDate,
+    subnet_hash: String,
+}
+
+/// In-memory counters of the JSON-RPC methods calls.
+#[derive(Debug, Clone)]
+pub struct MethodsUsage {
+    counters: Arc<Mutex<HashMap<UsageKey, i64>>>,
+    track_per_subnet: bool,
+}
+
+impl MethodsUsage {
+    pub fn new(track_per_subnet: bool) -> Self {
+        Self {
+            counters: Arc::default(),
+            track_per_subnet,
+        }
+    }
+
+    /// Counts a single call of the method.
+    pub fn record(&self, method: &str, ip: Option<&str>) {
+        let subnet_hash = if self.track_per_subnet {
+            ip.and_then(subnet_hash).unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let key = UsageKey {
+            method: method.to_owned(),
+            day: Utc::now().naive_utc().date(),
+            subnet_hash,
+        };
+        *self.counters.lock().unwrap().entry(key).or_default() += 1;
+    }
+
+    /// Takes all the collected counters, the following calls are counted from zero.
+    pub fn take(&self) -> Vec<RpcMethodUsage> {
+        let counters = std::mem::take(&mut *self.counters.lock().unwrap());
+        counters
+            .into_iter()
+            .map(|(key, calls)| RpcMethodUsage {
+                method: key.method,
+                day: key.day,
+                subnet_hash: key.subnet_hash,
+                calls,
+            })
+            .collect()
+    }
+
+    /// Returns the counters that were not stored back, so they are flushed next time.
+    fn restore(&self, usage: Vec<RpcMethodUsage>) {
+        let mut counters = self.counters.lock().unwrap();
+        for record in usage {
+            let key = UsageKey {
+                method: record.method,
+                day: record.day,
+                subnet_hash: record.subnet_hash,
+            };
+            *counters.entry(key).or_default() += record.calls;
+        }
+    }
+
+    /// Spawns a task that periodically writes the collected counters to the database.
+    pub fn spawn_flusher(&self, pool: ConnectionPool, interval: Duration) -> JoinHandle<()> {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let mut timer = time::interval(interval);
+            loop {
+                timer.tick().await;
+                let usage = this.take();
+                if usage.is_empty() {
+                    continue;
+                }
+                if let Err(err) = Self::store(&pool, &usage).await {
+                    vlog::warn!("Failed to store the JSON-RPC methods usage: {}", err);
+                    this.restore(usage);
+                }
+            }
+        })
+    }
+
+    async fn store(pool: &ConnectionPool, usage: &[RpcMethodUsage]) -> anyhow::Result<()> {
+        pool.access_storage()
+            .await?
+            .misc_schema()
+            .record_rpc_methods_usage(usage)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Returns the hash of the /24 (IPv4) or /48 (IPv6) subnet of the address,
+/// so the callers can be told apart without storing their IPs.
+fn subnet_hash(ip: &str) -> Option<String> {
+    let subnet = match ip.trim().parse::<IpAddr>().ok()? {
+        IpAddr::V4(ip) => ip.octets()[..3].to_vec(),
+        IpAddr::V6(ip) => ip.octets()[..6].to_vec(),
+    };
+    Some(hex::encode(&tiny_keccak::keccak256(&subnet)[..8]))
+}
+
+/// Adds the `deprecated` field to the successful result if it's an object.
+/// Other results are left as is, since adding a field would change their type.
+fn mark_deprecated(output: Output) -> Output {
+    match output {
+        Output::Success(mut success) => {
+            if let Value::Object(fields) = &mut success.result {
+                fields.insert(DEPRECATED_FIELD.to_owned(), Value::Bool(true));
+            }
+            Output::Success(success)
+        }
+        failure => failure,
+    }
+}
+
+/// Middleware that counts the calls of the methods and marks the responses
+/// of the methods slated for removal.
+#[derive(Debug)]
+pub struct MethodsUsageMiddleware {
+    usage: MethodsUsage,
+    deprecated_methods: Arc<HashSet<String>>,
+}
+
+impl MethodsUsageMiddleware {
+    pub fn new(usage: MethodsUsage, deprecated_methods: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            usage,
+            deprecated_methods: Arc::new(deprecated_methods.into_iter().collect()),
+        }
+    }
+}
+
+impl Middleware<CallerMeta> for MethodsUsageMiddleware {
+    type Future = NoopFuture;
+    type CallFuture = BoxFuture<Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: CallerMeta, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, CallerMeta) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let method = match &call {
+            Call::MethodCall(call) => call.method.clone(),
+            Call::Notification(notification) => notification.method.clone(),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let deprecated = self.deprecated_methods.contains(&method);
+        let usage = self.usage.clone();
+        let ip = meta.ip.clone();
+
+        Either::Left(Box::pin(next(call, meta).map(move |output| {
+            // Calls of unknown methods are not counted, otherwise anyone could flood the table.
+            let unknown_method = matches!(
+                &output,
+                Some(Output::Failure(failure)) if failure.error.code == ErrorCode::MethodNotFound
+            );
+            if !unknown_method {
+                usage.record(&method, ip.as_deref());
+            }
+
+            if deprecated {
+                output.map(mark_deprecated)
+            } else {
+                output
+            }
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::{MetaIoHandler, Params};
+    use serde_json::json;
+
+    const IP: &str = "100.100.100.100";
+
+    fn calls(usage: &[RpcMethodUsage], method: &str) -> Vec<(String, i64)> {
+        let mut calls: Vec<_> = usage
+            .iter()
+            .filter(|record| record.method == method)
+            .map(|record| (record.subnet_hash.clone(), record.calls))
+            .collect();
+        calls.sort();
+        calls
+    }
+
+    #[test]
+    fn usage_counting() {
+        let usage = MethodsUsage::new(false);
+        usage.record("tx_info", Some(IP));
+        usage.record("tx_info", Some("100.100.100.1"));
+        usage.record("tx_info", None);
+        usage.record("tokens", None);
+
+        let collected = usage.take();
+        assert_eq!(calls(&collected, "tx_info"), vec![(String::new(), 3)]);
+        assert_eq!(calls(&collected, "tokens"), vec![(String::new(), 1)]);
+        assert!(usage.take().is_empty());
+
+        // Counters that failed to be stored are flushed next time.
+        usage.record("tokens", None);
+        usage.restore(collected);
+        assert_eq!(calls(&usage.take(), "tokens"), vec![(String::new(), 2)]);
+    }
+
+    #[test]
+    fn usage_counting_per_subnet() {
+        let usage = MethodsUsage::new(true);
+        usage.record("tx_info", Some(IP));
+        usage.record("tx_info", Some("100.100.100.1"));
+        usage.record("tx_info", Some("100.100.101.1"));
+        usage.record("tx_info", Some("not an ip"));
+        usage.record("tx_info", None);
+
+        let subnet = subnet_hash(IP).unwrap();
+        let other_subnet = subnet_hash("100.100.101.1").unwrap();
+        assert_ne!(subnet, other_subnet);
+        assert_eq!(subnet_hash("100.100.100.1"), Some(subnet.clone()));
+
+        let mut expected = vec![(String::new(), 2), (subnet, 2), (other_subnet, 1)];
+        expected.sort();
+        assert_eq!(calls(&usage.take(), "tx_info"), expected);
+    }
+
+    #[tokio::test]
+    async fn deprecated_methods_marking() {
+        let usage = MethodsUsage::new(false);
+        let middleware = MethodsUsageMiddleware::new(
+            usage.clone(),
+            vec!["old_method".to_owned(), "old_scalar_method".to_owned()],
+        );
+        let mut io = MetaIoHandler::with_middleware(middleware);
+        io.add_method("old_method", |_: Params| async {
+            Ok(json!({ "value": 1 }))
+        });
+        io.add_method("old_scalar_method", |_: Params| async { Ok(json!(1)) });
+        io.add_method("new_method", |_: Params| async {
+            Ok(json!({ "value": 1 }))
+        });
+
+        let call = |method: &str| {
+            json!({ "jsonrpc": "2.0", "method": method, "params": [], "id": 1 }).to_string()
+        };
+        let result = |response: Option<String>| {
+            let response: Value = serde_json::from_str(&response.unwrap()).unwrap();
+            response["result"].clone()
+        };
+
+        let response = io
+            .handle_request(&call("old_method"), CallerMeta::default())
+            .await;
+        assert_eq!(result(response), json!({ "value": 1, "deprecated": true }));
+
+        let response = io
+            .handle_request(&call("old_scalar_method"), CallerMeta::default())
+            .await;
+        assert_eq!(result(response), json!(1));
+
+        let response = io
+            .handle_request(&call("new_method"), CallerMeta::default())
+            .await;
+        assert_eq!(result(response), json!({ "value": 1 }));
+
+        io.handle_request(&call("unknown_method"), CallerMeta::default())
+            .await;
+
+        let collected = usage.take();
+        assert_eq!(calls(&collected, "old_method"), vec![(String::new(), 1)]);
+        assert_eq!(calls(&collected, "new_method"), vec![(String::new(), 1)]);
+        assert!(calls(&collected, "unknown_method").is_empty());
+    }
+}
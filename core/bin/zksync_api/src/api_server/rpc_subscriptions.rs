@@ -22,6 +22,7 @@ use crate::{
     api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
     api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
     signature_checker::VerifySignatureRequest,
+    utils::token_cache::TokenCache,
 };
 
 #[rpc]
@@ -182,6 +183,7 @@ pub fn start_ws_server(
     db_pool: ConnectionPool,
     sign_verify_request_sender: mpsc::Sender<VerifySignatureRequest>,
     ticker: FeeTicker,
+    tokens: TokenCache,
     common_config: &CommonApiConfig,
     token_config: &TokenConfig,
     config: &JsonRpcConfig,
@@ -207,7 +209,7 @@ pub fn start_ws_server(
         sign_verify_request_sender,
         ticker,
         common_config,
-        token_config,
+        tokens,
         confirmations_for_eth_event,
         chain_id,
         mempool_tx_sender,
@@ -0,0 +1,507 @@
+//! Forwarding of the submitted transactions to the mempool.
+//!
+//! `TxSender` persists validated transactions and batches into the submission outbox and
+//! responds to the client right away. The forwarder delivers the outbox submissions to the
+//! mempool, retrying the failed deliveries. The outbox is stored in the database, so the
+//! forwarding resumes after the restart, while the repeated deliveries are ignored by the mempool.
+//!
+//! Since the client doesn't wait for the mempool, the mempool rejections (e.g. a nonce mismatch)
+//! are not returned by the submission endpoints. Such transactions are reported as rejected
+//! along with the reason by the transaction and batch status endpoints instead.
+
+// Built-in uses
+use std::time::{Duration, Instant};
+
+// External uses
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use tokio::{task::JoinHandle, time};
+
+// Workspace uses
+use zksync_mempool::MempoolTransactionRequest;
+use zksync_storage::ConnectionPool;
+use zksync_types::{mempool::SignedTxVariant, tx::error::TxAddError};
+
+/// Interval between the checks of the outbox.
+const FORWARDING_INTERVAL: Duration = Duration::from_millis(100);
+/// Maximum amount of transactions forwarded per iteration, batches are forwarded as a whole.
+const MAX_TXS_PER_ITERATION: u32 = 100;
+/// Interval between the removals of the delivered transactions from the outbox.
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Delivered transactions are kept in the outbox for this amount of hours,
+/// so the rejected ones can be reported to the users.
+const DELIVERED_TXS_RETENTION_HOURS: i64 = 24;
+
+/// Outcome of the transaction or batch delivery to the mempool.
+#[derive(Debug)]
+enum Delivery {
+    /// Submission is added to the mempool (or was added there before).
+    Accepted,
+    /// Submission is refused by the mempool and won't be retried.
+    Rejected(TxAddError),
+    /// Delivery has failed and should be retried later.
+    Failed(String),
+}
+
+/// Delivers the transactions from the submission outbox to the mempool.
+#[derive(Debug)]
+pub struct TxForwarder {
+    pool: ConnectionPool,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    last_cleanup: Option<Instant>,
+}
+
+impl TxForwarder {
+    pub fn new(
+        pool: ConnectionPool,
+        mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    ) -> Self {
+        Self {
+            pool,
+            mempool_tx_sender,
+            last_cleanup: None,
+        }
+    }
+
+    pub async fn run(mut self) {
+        let mut timer = time::interval(FORWARDING_INTERVAL);
+        loop {
+            timer.tick().await;
+
+            if let Err(err) = self.forward_pending().await {
+                vlog::warn!("Failed to forward the submitted transactions: {}", err);
+            }
+            if let Err(err) = self.remove_delivered().await {
+                vlog::warn!("Failed to clean up the submission outbox: {}", err);
+            }
+        }
+    }
+
+    /// Forwards the oldest undelivered transactions and batches to the mempool.
+    /// Returns the number of submissions processed by the mempool.
+    ///
+    /// Forwarding stops at the first failed delivery, the remaining submissions
+    /// are retried during the next iteration. Submissions rejected by the mempool are
+    /// not retried, the rejection reason is reported to the clients by the outbox.
+    pub async fn forward_pending(&mut self) -> anyhow::Result<usize> {
+        let mut storage = self.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+        let submissions = transaction
+            .chain()
+            .mempool_schema()
+            .lock_undelivered_outbox_submissions(MAX_TXS_PER_ITERATION)
+            .await?;
+
+        let mut processed = 0;
+        for submission in submissions {
+            let tx_hashes = submission.hashes();
+            let mut mempool_schema = transaction.chain().mempool_schema();
+            match self.deliver(submission).await {
+                Delivery::Accepted => {
+                    for tx_hash in tx_hashes {
+                        mempool_schema
+                            .mark_outbox_tx_delivered(tx_hash, None)
+                            .await?;
+                    }
+                }
+                Delivery::Rejected(err) => {
+                    metrics::increment_counter!("api.tx_forwarder.rejected");
+                    let reason = err.to_string();
+                    for tx_hash in tx_hashes {
+                        mempool_schema
+                            .mark_outbox_tx_delivered(tx_hash, Some(&reason))
+                            .await?;
+                    }
+                }
+                Delivery::Failed(err) => {
+                    vlog::warn!(
+                        "Failed to forward the transaction {}: {}",
+                        tx_hashes[0].to_string(),
+                        err
+                    );
+                    metrics::increment_counter!("api.tx_forwarder.failed");
+                    for tx_hash in tx_hashes {
+                        mempool_schema
+                            .record_outbox_tx_failure(tx_hash, &err)
+                            .await?;
+                    }
+                    break;
+                }
+            }
+            processed += 1;
+        }
+        transaction.commit().await?;
+
+        metrics::counter!("api.tx_forwarder.processed", processed as u64);
+        Ok(processed)
+    }
+
+    async fn deliver(&mut self, submission: SignedTxVariant) -> Delivery {
+        let (sender, receiver) = oneshot::channel();
+        let request = match submission {
+            SignedTxVariant::Tx(tx) => MempoolTransactionRequest::NewTx(Box::new(tx), sender),
+            SignedTxVariant::Batch(batch) => {
+                MempoolTransactionRequest::NewTxsBatch(batch.txs, batch.eth_signatures, sender)
+            }
+        };
+        if let Err(err) = self.mempool_tx_sender.send(request).await {
+            return Delivery::Failed(err.to_string());
+        }
+
+        match receiver.await {
+            Ok(Ok(())) => Delivery::Accepted,
+            // Database errors are temporary, the transaction itself may be correct.
+            Ok(Err(TxAddError::DbError)) => Delivery::Failed(TxAddError::DbError.to_string()),
+            Ok(Err(err)) => Delivery::Rejected(err),
+            Err(err) => Delivery::Failed(err.to_string()),
+        }
+    }
+
+    async fn remove_delivered(&mut self) -> anyhow::Result<()> {
+        if let Some(last_cleanup) = self.last_cleanup {
+            if last_cleanup.elapsed() < CLEANUP_INTERVAL {
+                return Ok(());
+            }
+        }
+
+        let threshold = chrono::Utc::now() - chrono::Duration::hours(DELIVERED_TXS_RETENTION_HOURS);
+        let removed = self
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .remove_delivered_outbox_txs_older_than(threshold)
+            .await?;
+        self.last_cleanup = Some(Instant::now());
+
+        vlog::debug!("Removed {} delivered transactions from the outbox", removed);
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn run_tx_forwarder(
+    pool: ConnectionPool,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+) -> JoinHandle<()> {
+    tokio::spawn(TxForwarder::new(pool, mempool_tx_sender).run())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use futures::StreamExt;
+    use zksync_api_types::v02::transaction::{L2Receipt, Receipt, TxInBlockStatus};
+    use zksync_mempool::run_mempool_tx_handler;
+    use zksync_storage::chain::mempool::CANCELLED_TX_REASON;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        helpers::{closest_packable_fee_amount, closest_packable_token_amount},
+        tx::TxHash,
+        AccountId, SignedZkSyncTx, TokenId, ZkSyncTx,
+    };
+
+    /// Creates transfers of an account that is not stored in the database,
+    /// so the mempool accepts them regardless of the nonce.
+    fn outbox_txs(count: usize) -> Vec<SignedZkSyncTx> {
+        let from = ZkSyncAccount::rand();
+        from.set_account_id(Some(AccountId(0xbeef)));
+        let to = ZkSyncAccount::rand();
+
+        (0..count)
+            .map(|_| {
+                let (tx, _) = from.sign_transfer(
+                    TokenId(0),
+                    "ETH",
+                    closest_packable_token_amount(&10_u64.into()),
+                    closest_packable_fee_amount(&100_u64.into()),
+                    &to.address,
+                    None,
+                    true,
+                    Default::default(),
+                );
+                SignedZkSyncTx {
+                    tx: ZkSyncTx::Transfer(Box::new(tx)),
+                    eth_sign_data: None,
+                    created_at: Utc::now(),
+                }
+            })
+            .collect()
+    }
+
+    async fn outbox_receipt(pool: &ConnectionPool, tx_hash: TxHash) -> Option<Receipt> {
+        pool.access_storage()
+            .await
+            .unwrap()
+            .chain()
+            .mempool_schema()
+            .get_outbox_tx_receipt(tx_hash)
+            .await
+            .unwrap()
+    }
+
+    /// Checks that the forwarding resumes after the failure and the transactions
+    /// that already reached the mempool are not duplicated.
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn forwarding_resumes_after_failure() -> anyhow::Result<()> {
+        let pool = ConnectionPool::new(Some(4));
+        let txs = outbox_txs(3);
+        {
+            let mut storage = pool.access_storage().await?;
+            for tx in &txs {
+                assert!(
+                    storage
+                        .chain()
+                        .mempool_schema()
+                        .insert_outbox_tx(tx)
+                        .await?
+                );
+            }
+            // Repeated submission doesn't create a new entry.
+            assert!(
+                !storage
+                    .chain()
+                    .mempool_schema()
+                    .insert_outbox_tx(&txs[0])
+                    .await?
+            );
+            // The first transaction has reached the mempool, but the forwarder
+            // has crashed before marking it as delivered.
+            storage.chain().mempool_schema().insert_tx(&txs[0]).await?;
+        }
+
+        // Mempool is unavailable, nothing is delivered.
+        let (mempool_sender, mempool_receiver) = mpsc::channel(10);
+        drop(mempool_receiver);
+        let mut forwarder = TxForwarder::new(pool.clone(), mempool_sender);
+        assert_eq!(forwarder.forward_pending().await?, 0);
+        for tx in &txs {
+            let receipt = outbox_receipt(&pool, tx.hash()).await;
+            assert_eq!(
+                receipt,
+                Some(Receipt::L2(L2Receipt {
+                    tx_hash: tx.hash(),
+                    rollup_block: None,
+                    status: TxInBlockStatus::Queued,
+                    fail_reason: None,
+                    archived: false,
+                }))
+            );
+        }
+
+        // Restarted forwarder delivers all the transactions. The outbox may contain
+        // transactions of other tests, so the forwarding is repeated until it's empty.
+        let (mempool_sender, mempool_receiver) = mpsc::channel(10);
+        let mempool_task = run_mempool_tx_handler(pool.clone(), mempool_receiver, vec![100]);
+        let mut forwarder = TxForwarder::new(pool.clone(), mempool_sender);
+        while forwarder.forward_pending().await? > 0 {}
+
+        let mut storage = pool.access_storage().await?;
+        for tx in &txs {
+            assert!(
+                storage
+                    .chain()
+                    .mempool_schema()
+                    .contains_tx(tx.hash())
+                    .await?
+            );
+            // Delivered transactions are reported by the mempool itself.
+            assert_eq!(outbox_receipt(&pool, tx.hash()).await, None);
+        }
+        // The transaction delivered before the crash is not duplicated.
+        let mempool_txs = storage.chain().mempool_schema().load_txs(&[]).await?;
+        let copies = mempool_txs
+            .iter()
+            .flat_map(|tx| tx.hashes())
+            .filter(|tx_hash| *tx_hash == txs[0].hash())
+            .count();
+        assert_eq!(copies, 1);
+
+        mempool_task.abort();
+        Ok(())
+    }
+
+    async fn cancel_outbox_tx(pool: &ConnectionPool, tx_hash: TxHash) -> bool {
+        pool.access_storage()
+            .await
+            .unwrap()
+            .chain()
+            .mempool_schema()
+            .cancel_outbox_tx(tx_hash)
+            .await
+            .unwrap()
+    }
+
+    /// Checks that the transaction cancelled before the forwarding is not delivered,
+    /// while the cancellation racing with the delivery waits for it and fails.
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn cancellation_races_with_forwarding() -> anyhow::Result<()> {
+        let pool = ConnectionPool::new(Some(4));
+        let txs = outbox_txs(2);
+        let (cancelled, delivered) = (txs[0].hash(), txs[1].hash());
+        {
+            let mut storage = pool.access_storage().await?;
+            for tx in &txs {
+                storage
+                    .chain()
+                    .mempool_schema()
+                    .insert_outbox_tx(tx)
+                    .await?;
+            }
+        }
+
+        // Transaction is cancelled before the forwarder has picked it up.
+        assert!(cancel_outbox_tx(&pool, cancelled).await);
+        assert!(!cancel_outbox_tx(&pool, cancelled).await);
+        assert_eq!(
+            outbox_receipt(&pool, cancelled).await,
+            Some(Receipt::L2(L2Receipt {
+                tx_hash: cancelled,
+                rollup_block: None,
+                status: TxInBlockStatus::Rejected,
+                fail_reason: Some(CANCELLED_TX_REASON.to_string()),
+                archived: false,
+            }))
+        );
+
+        // The mempool is emulated, so the delivery can be paused. The outbox may contain
+        // transactions of other tests, all of them are accepted.
+        let (mempool_sender, mut mempool_receiver) = mpsc::channel(10);
+        let mut forwarder = TxForwarder::new(pool.clone(), mempool_sender);
+        let forwarding = tokio::spawn(async move { forwarder.forward_pending().await });
+
+        let mut forwarded = Vec::new();
+        let response = match mempool_receiver.next().await {
+            Some(MempoolTransactionRequest::NewTx(tx, response)) => {
+                forwarded.push(tx.hash());
+                response
+            }
+            Some(MempoolTransactionRequest::NewTxsBatch(txs, _, response)) => {
+                forwarded.extend(txs.iter().map(|tx| tx.hash()));
+                response
+            }
+            _ => panic!("Forwarder has sent an unexpected request"),
+        };
+
+        // The forwarder holds the lock of the outbox transactions, so the cancellation waits
+        // for the delivery to finish and fails afterwards.
+        let cancellation_pool = pool.clone();
+        let mut cancellation =
+            tokio::spawn(async move { cancel_outbox_tx(&cancellation_pool, delivered).await });
+        assert!(time::timeout(Duration::from_millis(200), &mut cancellation)
+            .await
+            .is_err());
+
+        response.send(Ok(())).unwrap();
+        while let Some(request) = mempool_receiver.next().await {
+            match request {
+                MempoolTransactionRequest::NewTx(tx, response) => {
+                    forwarded.push(tx.hash());
+                    response.send(Ok(())).unwrap();
+                }
+                MempoolTransactionRequest::NewTxsBatch(txs, _, response) => {
+                    forwarded.extend(txs.iter().map(|tx| tx.hash()));
+                    response.send(Ok(())).unwrap();
+                }
+                _ => panic!("Forwarder has sent an unexpected request"),
+            }
+        }
+        forwarding.await??;
+
+        assert!(!cancellation.await?);
+        assert!(forwarded.contains(&delivered));
+        assert!(!forwarded.contains(&cancelled));
+        assert_eq!(outbox_receipt(&pool, delivered).await, None);
+        Ok(())
+    }
+
+    /// Checks that the batch is forwarded as a whole, while the mempool rejection is reported
+    /// to the client by the status of the batch and its transactions.
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn rejected_batch_is_reported() -> anyhow::Result<()> {
+        let pool = ConnectionPool::new(Some(4));
+        let txs = outbox_txs(3);
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        let batch_hash = TxHash::batch_hash(&tx_hashes);
+        pool.access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .insert_outbox_batch(&txs, &[])
+            .await?;
+
+        // The mempool is emulated, so it can reject the batch. The outbox may contain
+        // submissions of other tests, all of them are accepted.
+        let (mempool_sender, mut mempool_receiver) = mpsc::channel(10);
+        let mut forwarder = TxForwarder::new(pool.clone(), mempool_sender);
+        let forwarding = tokio::spawn(async move { forwarder.forward_pending().await });
+        let mut forwarded_batch = None;
+        while let Some(request) = mempool_receiver.next().await {
+            match request {
+                MempoolTransactionRequest::NewTxsBatch(txs, _, response) => {
+                    let hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+                    if hashes == tx_hashes {
+                        forwarded_batch = Some(hashes);
+                        response.send(Err(TxAddError::NonceMismatch)).unwrap();
+                    } else {
+                        response.send(Ok(())).unwrap();
+                    }
+                }
+                MempoolTransactionRequest::NewTx(_, response) => response.send(Ok(())).unwrap(),
+                _ => panic!("Forwarder has sent an unexpected request"),
+            }
+        }
+        forwarding.await??;
+        assert_eq!(forwarded_batch, Some(tx_hashes.clone()));
+
+        // The client has received the batch hash right away, the rejection is reported
+        // by the status endpoints.
+        let mut storage = pool.access_storage().await?;
+        let batch_info = storage
+            .chain()
+            .operations_ext_schema()
+            .get_batch_info(batch_hash)
+            .await?
+            .expect("Rejected batch is not found");
+        assert_eq!(
+            batch_info.batch_status.last_state,
+            TxInBlockStatus::Rejected
+        );
+        assert_eq!(
+            batch_info.batch_status.fail_reason,
+            Some(TxAddError::NonceMismatch.to_string())
+        );
+        for tx_hash in tx_hashes {
+            let tx_info = crate::api_server::tx_info::load_tx_info(&mut storage, tx_hash)
+                .await?
+                .expect("Rejected transaction is not found");
+            assert_eq!(
+                tx_info.receipt,
+                Receipt::L2(L2Receipt {
+                    tx_hash,
+                    rollup_block: None,
+                    status: TxInBlockStatus::Rejected,
+                    fail_reason: Some(TxAddError::NonceMismatch.to_string()),
+                    archived: false,
+                })
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,438 @@
+//! Status and data of the transactions shared by the REST, JSON RPC and WebSocket servers,
+//! so all of them report the same state of the same transaction.
+//!
+//! The database is queried once per request and the found information is converted into
+//! the shape of the particular API via the `TxInfoShape` trait.
+
+// Built-in uses
+
+// External uses
+
+// Workspace uses
+use zksync_api_types::v02::transaction::{
+    L1Receipt, L1Transaction, Receipt, Transaction, TransactionData, TxData, TxInBlockStatus,
+};
+use zksync_storage::{chain::mempool::records::PendingPriorityOp, QueryResult, StorageProcessor};
+use zksync_types::{aggregated_operations::AggregatedActionType, tx::TxHash, EthBlockId};
+
+// Local uses
+use super::rpc_server::types::{BlockInfo, TransactionInfoResp};
+
+/// Receipt of the transaction along with the finality of its block. The latter is not
+/// a part of the receipt of the transaction rejected within the block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxInfo {
+    pub receipt: Receipt,
+    /// Whether the block the transaction is included into is executed on Ethereum.
+    pub block_finalized: bool,
+}
+
+impl TxInfo {
+    /// The information can't change anymore, so it can be cached.
+    pub fn is_final(&self) -> bool {
+        self.block_finalized
+    }
+}
+
+/// Shape the transaction information is reported in by the particular API.
+pub trait TxInfoShape: Sized {
+    fn from_tx_info(info: TxInfo) -> Self;
+}
+
+impl TxInfoShape for Receipt {
+    fn from_tx_info(info: TxInfo) -> Self {
+        info.receipt
+    }
+}
+
+impl TxInfoShape for TransactionInfoResp {
+    fn from_tx_info(info: TxInfo) -> Self {
+        let (status, rollup_block, fail_reason) = match info.receipt {
+            Receipt::L1(receipt) => (receipt.status, receipt.rollup_block, None),
+            Receipt::L2(receipt) => (receipt.status, receipt.rollup_block, receipt.fail_reason),
+        };
+        let success = match status {
+            TxInBlockStatus::Queued => None,
+            TxInBlockStatus::Committed | TxInBlockStatus::Finalized => Some(true),
+            TxInBlockStatus::Rejected => Some(false),
+        };
+        // Transactions rejected by the mempool are not included into any block.
+        let block = rollup_block.map(|block_number| BlockInfo {
+            block_number: i64::from(*block_number),
+            committed: true,
+            verified: info.block_finalized,
+        });
+        Self {
+            executed: block.is_some(),
+            success,
+            fail_reason,
+            block,
+        }
+    }
+}
+
+impl TransactionInfoResp {
+    /// Response for the transaction the server knows nothing about.
+    pub fn unknown() -> Self {
+        Self {
+            executed: false,
+            success: None,
+            fail_reason: None,
+            block: None,
+        }
+    }
+}
+
+/// Looks for the transaction or the priority operation by its hash: the executed ones first,
+/// then the pending priority operations and the transactions not forwarded to the mempool yet
+/// (or rejected by it). All the queries observe the same state, otherwise the operation may
+/// get executed between them and the transaction would be reported as unknown.
+///
+/// Amount of confirmations remaining for the pending priority operations is not known to
+/// the database and is left unset.
+pub async fn load_tx_info(
+    storage: &mut StorageProcessor<'_>,
+    tx_hash: TxHash,
+) -> QueryResult<Option<TxInfo>> {
+    let mut transaction = storage.start_snapshot_read().await?;
+
+    let info = if let Some(receipt) = transaction
+        .chain()
+        .operations_ext_schema()
+        .tx_receipt_api_v02(tx_hash.as_ref())
+        .await?
+    {
+        let (status, rollup_block) = match &receipt {
+            Receipt::L1(receipt) => (receipt.status, receipt.rollup_block),
+            Receipt::L2(receipt) => (receipt.status, receipt.rollup_block),
+        };
+        let block_finalized = match (status, rollup_block) {
+            (TxInBlockStatus::Finalized, _) => true,
+            (TxInBlockStatus::Rejected, Some(block_number)) => transaction
+                .chain()
+                .operations_schema()
+                .get_stored_aggregated_operation(block_number, AggregatedActionType::ExecuteBlocks)
+                .await
+                .map(|operation| operation.confirmed)
+                .unwrap_or_default(),
+            _ => false,
+        };
+        Some(TxInfo {
+            receipt,
+            block_finalized,
+        })
+    } else if let Some(PendingPriorityOp { op, created_at }) = transaction
+        .chain()
+        .mempool_schema()
+        .get_pending_operation_by_hash(tx_hash.into())
+        .await?
+    {
+        Some(TxInfo {
+            receipt: Receipt::L1(L1Receipt {
+                status: TxInBlockStatus::Queued,
+                eth_block: EthBlockId(op.eth_block),
+                rollup_block: None,
+                id: op.serial_id,
+                confirmations_remaining: None,
+                created_at: Some(created_at),
+                archived: false,
+            }),
+            block_finalized: false,
+        })
+    } else {
+        transaction
+            .chain()
+            .mempool_schema()
+            .get_outbox_tx_receipt(tx_hash)
+            .await?
+            .map(|receipt| TxInfo {
+                receipt,
+                block_finalized: false,
+            })
+    };
+    transaction.commit().await?;
+
+    Ok(info)
+}
+
+/// Loads the transaction in the requested shape, `None` if it's unknown.
+pub async fn load_tx_info_as<T: TxInfoShape>(
+    storage: &mut StorageProcessor<'_>,
+    tx_hash: TxHash,
+) -> QueryResult<Option<T>> {
+    Ok(load_tx_info(storage, tx_hash).await?.map(T::from_tx_info))
+}
+
+/// Loads the data of the executed transaction or the pending priority operation,
+/// the counterpart of `load_tx_info`.
+pub async fn load_tx_data(
+    storage: &mut StorageProcessor<'_>,
+    tx_hash: TxHash,
+) -> QueryResult<Option<TxData>> {
+    let mut transaction = storage.start_snapshot_read().await?;
+
+    let data = if let Some(data) = transaction
+        .chain()
+        .operations_ext_schema()
+        .tx_data_api_v02(tx_hash.as_ref())
+        .await?
+    {
+        Some(data)
+    } else if let Some(PendingPriorityOp { op, created_at }) = transaction
+        .chain()
+        .mempool_schema()
+        .get_pending_operation_by_hash(tx_hash.into())
+        .await?
+    {
+        let tx_hash = op.tx_hash();
+        let tx = Transaction {
+            tx_hash,
+            block_index: None,
+            block_number: None,
+            op: TransactionData::L1(L1Transaction::from_pending_op(
+                op.data,
+                op.eth_hash,
+                op.serial_id,
+                tx_hash,
+            )),
+            status: TxInBlockStatus::Queued,
+            fail_reason: None,
+            created_at: Some(created_at),
+            batch_id: None,
+        };
+
+        Some(TxData {
+            tx,
+            eth_signature: None,
+            l2_signature: None,
+            archived: false,
+        })
+    } else {
+        None
+    };
+    transaction.commit().await?;
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::{BlockTx, TestServerConfig, TxLifecycle};
+    use chrono::Utc;
+    use zksync_api_types::v02::transaction::L2Receipt;
+    use zksync_types::BlockNumber;
+
+    /// Checks that the JSON RPC and WebSocket response tells the same as the REST receipt.
+    fn assert_consistent(info: TxInfo) {
+        let receipt = Receipt::from_tx_info(info.clone());
+        let resp = TransactionInfoResp::from_tx_info(info.clone());
+        let (status, rollup_block, fail_reason) = match &receipt {
+            Receipt::L1(receipt) => (receipt.status, receipt.rollup_block, None),
+            Receipt::L2(receipt) => (
+                receipt.status,
+                receipt.rollup_block,
+                receipt.fail_reason.clone(),
+            ),
+        };
+
+        assert_eq!(resp.executed, rollup_block.is_some(), "{:?}", info);
+        assert_eq!(
+            resp.block.as_ref().map(|block| block.block_number),
+            rollup_block.map(|block_number| i64::from(*block_number)),
+            "{:?}",
+            info
+        );
+        assert_eq!(resp.fail_reason, fail_reason, "{:?}", info);
+        match status {
+            TxInBlockStatus::Queued => assert_eq!(resp.success, None, "{:?}", info),
+            TxInBlockStatus::Committed => {
+                assert_eq!(resp.success, Some(true), "{:?}", info);
+                assert!(!resp.block.unwrap().verified, "{:?}", info);
+            }
+            TxInBlockStatus::Finalized => {
+                assert_eq!(resp.success, Some(true), "{:?}", info);
+                assert!(resp.block.unwrap().verified, "{:?}", info);
+            }
+            TxInBlockStatus::Rejected => {
+                assert_eq!(resp.success, Some(false), "{:?}", info);
+                if let Some(block) = resp.block {
+                    assert_eq!(block.verified, info.block_finalized, "{:?}", info);
+                }
+            }
+        }
+    }
+
+    fn l2_receipt(
+        tx_hash: TxHash,
+        status: TxInBlockStatus,
+        rollup_block: Option<BlockNumber>,
+        fail_reason: Option<&str>,
+    ) -> Receipt {
+        Receipt::L2(L2Receipt {
+            tx_hash,
+            rollup_block,
+            status,
+            fail_reason: fail_reason.map(str::to_owned),
+            archived: false,
+        })
+    }
+
+    fn l2_info(
+        status: TxInBlockStatus,
+        rollup_block: Option<u32>,
+        fail_reason: Option<&str>,
+        block_finalized: bool,
+    ) -> TxInfo {
+        TxInfo {
+            receipt: l2_receipt(
+                TxHash::from_slice(&[1; 32]).unwrap(),
+                status,
+                rollup_block.map(BlockNumber),
+                fail_reason,
+            ),
+            block_finalized,
+        }
+    }
+
+    fn l1_info(status: TxInBlockStatus, rollup_block: Option<u32>) -> TxInfo {
+        TxInfo {
+            receipt: Receipt::L1(L1Receipt {
+                status,
+                eth_block: EthBlockId(10),
+                rollup_block: rollup_block.map(BlockNumber),
+                id: 1,
+                confirmations_remaining: None,
+                created_at: Some(Utc::now()),
+                archived: false,
+            }),
+            block_finalized: status == TxInBlockStatus::Finalized,
+        }
+    }
+
+    #[test]
+    fn shapes_are_consistent() {
+        let fixtures = vec![
+            l2_info(TxInBlockStatus::Queued, None, None, false),
+            l2_info(TxInBlockStatus::Committed, Some(2), None, false),
+            l2_info(TxInBlockStatus::Finalized, Some(2), None, true),
+            l2_info(
+                TxInBlockStatus::Rejected,
+                Some(2),
+                Some("Nonce mismatch"),
+                false,
+            ),
+            l2_info(
+                TxInBlockStatus::Rejected,
+                Some(2),
+                Some("Nonce mismatch"),
+                true,
+            ),
+            // Rejected by the mempool.
+            l2_info(TxInBlockStatus::Rejected, None, Some("Expired"), false),
+            l1_info(TxInBlockStatus::Queued, None),
+            l1_info(TxInBlockStatus::Committed, Some(3)),
+            l1_info(TxInBlockStatus::Finalized, Some(3)),
+        ];
+        for info in fixtures {
+            assert_consistent(info);
+        }
+
+        let resp = TransactionInfoResp::from_tx_info(l2_info(
+            TxInBlockStatus::Rejected,
+            Some(2),
+            Some("Nonce mismatch"),
+            true,
+        ));
+        let block = resp.block.unwrap();
+        assert!(block.committed);
+        assert!(block.verified);
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn lookup_follows_lifecycle() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut lifecycle = TxLifecycle::new(cfg.pool.clone()).await?;
+        let load = |tx_hash: TxHash| {
+            let pool = cfg.pool.clone();
+            async move {
+                let mut storage = pool.access_storage().await?;
+                let info = load_tx_info(&mut storage, tx_hash).await?;
+                if let Some(info) = &info {
+                    assert_consistent(info.clone());
+                }
+                Ok::<_, anyhow::Error>(info)
+            }
+        };
+
+        let mut txs = TestServerConfig::gen_zk_txs(1_u64).txs.into_iter();
+        let tx = txs.next().unwrap().0;
+        let failed_tx = txs.next().unwrap().0;
+        assert_eq!(load(tx.hash()).await?, None);
+
+        let tx_hash = lifecycle.submit(tx.clone()).await?;
+        let info = load(tx_hash).await?.unwrap();
+        assert_eq!(
+            info.receipt,
+            l2_receipt(tx_hash, TxInBlockStatus::Queued, None, None)
+        );
+        assert!(!TransactionInfoResp::from_tx_info(info).executed);
+
+        let block_number = lifecycle
+            .include(vec![
+                BlockTx::success(tx),
+                BlockTx::failure(failed_tx.clone(), "Nonce mismatch"),
+            ])
+            .await?;
+        let info = load(tx_hash).await?.unwrap();
+        assert_eq!(
+            info.receipt,
+            l2_receipt(
+                tx_hash,
+                TxInBlockStatus::Committed,
+                Some(block_number),
+                None
+            )
+        );
+        assert!(!info.is_final());
+        let info = load(failed_tx.hash()).await?.unwrap();
+        assert_eq!(
+            info.receipt,
+            l2_receipt(
+                failed_tx.hash(),
+                TxInBlockStatus::Rejected,
+                Some(block_number),
+                Some("Nonce mismatch")
+            )
+        );
+        assert!(!info.block_finalized);
+
+        lifecycle.verify(block_number).await?;
+        lifecycle.execute(block_number).await?;
+        let info = load(tx_hash).await?.unwrap();
+        assert_eq!(
+            info.receipt,
+            l2_receipt(
+                tx_hash,
+                TxInBlockStatus::Finalized,
+                Some(block_number),
+                None
+            )
+        );
+        assert!(info.is_final());
+        // The finality of the block is reported for the rejected transactions as well.
+        let info = load(failed_tx.hash()).await?.unwrap();
+        assert!(info.block_finalized);
+        let resp = TransactionInfoResp::from_tx_info(info);
+        assert_eq!(resp.success, Some(false));
+        assert!(resp.block.unwrap().verified);
+
+        lifecycle.finish().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+//! Quota of the transactions submitted by the recently created accounts.
+//!
+//! Freshly created accounts are a cheap way to spam the network, so the accounts created less
+//! than the configured number of blocks ago may only submit a limited number of transactions
+//! per sliding window. The submissions are tracked in memory by every API server, requests
+//! with one of the exempt API keys are not limited.
+
+// Built-in uses
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+// External uses
+use chrono::{DateTime, Utc};
+use lru_cache::LruCache;
+
+// Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{AccountId, BlockNumber};
+
+// Local uses
+use super::SubmitError;
+use crate::utils::shared_lru_cache::SharedLruCache;
+
+/// Maximum number of the accounts the submissions are tracked for, the accounts that
+/// haven't submitted anything for the longest time are forgotten first.
+const TRACKED_ACCOUNTS: usize = 100_000;
+
+#[derive(Debug, Clone)]
+pub struct QuotaPolicy {
+    /// Accounts created at least this number of blocks ago are not limited.
+    pub max_account_age_blocks: u32,
+    /// Maximum number of the transactions submitted within the window.
+    pub max_txs: u32,
+    pub window: Duration,
+    pub exempt_api_keys: HashSet<String>,
+}
+
+impl QuotaPolicy {
+    /// Returns `None` if the quota is disabled.
+    pub fn from_config(config: &CommonApiConfig) -> Option<Self> {
+        if config.new_account_max_age_blocks == 0 {
+            return None;
+        }
+        Some(Self {
+            max_account_age_blocks: config.new_account_max_age_blocks,
+            max_txs: config.new_account_tx_quota,
+            window: config.new_account_tx_quota_window(),
+            exempt_api_keys: config.tx_quota_exempt_api_keys.iter().cloned().collect(),
+        })
+    }
+}
+
+/// Transactions quota of the recently created accounts.
+#[derive(Debug, Clone)]
+pub struct AccountTxQuota {
+    policy: QuotaPolicy,
+    /// The account is never moved to another block once it's created.
+    creation_blocks: SharedLruCache<AccountId, BlockNumber>,
+    /// Times of the submissions within the window, the oldest first.
+    submissions: Arc<Mutex<LruCache<AccountId, VecDeque<DateTime<Utc>>>>>,
+}
+
+impl AccountTxQuota {
+    /// Returns `None` if the quota is disabled.
+    pub fn from_config(config: &CommonApiConfig) -> Option<Self> {
+        QuotaPolicy::from_config(config).map(Self::new)
+    }
+
+    pub fn new(policy: QuotaPolicy) -> Self {
+        Self {
+            policy,
+            creation_blocks: SharedLruCache::new(TRACKED_ACCOUNTS),
+            submissions: Arc::new(Mutex::new(LruCache::new(TRACKED_ACCOUNTS))),
+        }
+    }
+
+    /// Charges the transactions submitted by the `initiators` (one entry per transaction)
+    /// to their quotas. If any of the recently created initiators is out of quota, none
+    /// of the transactions is charged.
+    pub async fn charge(
+        &self,
+        pool: &ConnectionPool,
+        initiators: &[AccountId],
+        api_key: Option<&str>,
+    ) -> Result<(), SubmitError> {
+        if self.is_exempt(api_key) {
+            return Ok(());
+        }
+
+        let mut storage = pool.access_storage().await.map_err(SubmitError::internal)?;
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(SubmitError::internal)?;
+        let mut ages = HashMap::new();
+        for &account_id in initiators {
+            if !ages.contains_key(&account_id) {
+                let age = self
+                    .account_age(&mut storage, account_id, last_block)
+                    .await?;
+                ages.insert(account_id, age);
+            }
+        }
+        drop(storage);
+
+        let txs: Vec<_> = initiators
+            .iter()
+            .map(|account_id| (*account_id, ages[account_id]))
+            .collect();
+        self.charge_at(&txs, Utc::now())
+    }
+
+    fn is_exempt(&self, api_key: Option<&str>) -> bool {
+        api_key.map_or(false, |key| self.policy.exempt_api_keys.contains(key))
+    }
+
+    /// Returns the age of the account in blocks, accounts that are not created yet are 0 blocks old.
+    async fn account_age(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        account_id: AccountId,
+        last_block: BlockNumber,
+    ) -> Result<u32, SubmitError> {
+        let creation_block = match self.creation_blocks.get(&account_id) {
+            Some(block_number) => Some(block_number),
+            None => {
+                let block_number = storage
+                    .chain()
+                    .account_schema()
+                    .account_creation_block(account_id)
+                    .await
+                    .map_err(SubmitError::internal)?;
+                if let Some(block_number) = block_number {
+                    self.creation_blocks.insert(account_id, block_number);
+                }
+                block_number
+            }
+        };
+        Ok(creation_block.map_or(0, |block_number| last_block.saturating_sub(*block_number)))
+    }
+
+    /// Charges the transactions given as the initiators along with their ages in blocks.
+    fn charge_at(&self, txs: &[(AccountId, u32)], now: DateTime<Utc>) -> Result<(), SubmitError> {
+        let mut counts = HashMap::<AccountId, u32>::new();
+        for &(account_id, age) in txs {
+            if age < self.policy.max_account_age_blocks {
+                *counts.entry(account_id).or_default() += 1;
+            }
+        }
+        if counts.is_empty() {
+            return Ok(());
+        }
+
+        let window =
+            chrono::Duration::from_std(self.policy.window).expect("Quota window is too long");
+        let no_submissions = VecDeque::new();
+        let mut submissions = self.submissions.lock().unwrap();
+        for (&account_id, &count) in &counts {
+            let previous = match submissions.get_mut(&account_id) {
+                Some(previous) => {
+                    while matches!(previous.front(), Some(time) if *time + window <= now) {
+                        previous.pop_front();
+                    }
+                    &*previous
+                }
+                None => &no_submissions,
+            };
+
+            let submitted = previous.len() as u32;
+            if submitted + count > self.policy.max_txs {
+                // The transactions fit once enough of the previous submissions leave the window,
+                // batches larger than the quota never fit.
+                let reset_at = if count > self.policy.max_txs {
+                    now + window
+                } else {
+                    let excess = submitted + count - self.policy.max_txs;
+                    previous[excess as usize - 1] + window
+                };
+                metrics::increment_counter!("api.tx_quota.exceeded");
+                return Err(SubmitError::QuotaExceeded {
+                    limit: self.policy.max_txs,
+                    window_secs: self.policy.window.as_secs(),
+                    reset_at,
+                    retry_after_secs: (reset_at - now).num_seconds().max(1) as u64,
+                });
+            }
+        }
+
+        for (account_id, count) in counts {
+            if submissions.get_mut(&account_id).is_none() {
+                submissions.insert(account_id, VecDeque::new());
+            }
+            let previous = submissions.get_mut(&account_id).unwrap();
+            previous.extend(std::iter::repeat(now).take(count as usize));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAX_AGE: u32 = 10;
+
+    fn quota() -> AccountTxQuota {
+        AccountTxQuota::new(QuotaPolicy {
+            max_account_age_blocks: MAX_AGE,
+            max_txs: 3,
+            window: Duration::from_secs(3600),
+            exempt_api_keys: vec!["key".to_owned()].into_iter().collect(),
+        })
+    }
+
+    fn assert_exceeded(result: Result<(), SubmitError>, expected_reset_at: DateTime<Utc>) {
+        match result {
+            Err(SubmitError::QuotaExceeded {
+                limit, reset_at, ..
+            }) => {
+                assert_eq!(limit, 3);
+                assert_eq!(reset_at, expected_reset_at);
+            }
+            other => panic!("Unexpected quota result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn quota_across_account_age() {
+        let quota = quota();
+        let account = AccountId(1);
+        let start = Utc::now();
+        let minutes = chrono::Duration::minutes;
+
+        for minute in 0..3 {
+            quota
+                .charge_at(&[(account, 0)], start + minutes(minute))
+                .unwrap();
+        }
+        // The quota is reset once the first submission leaves the window.
+        let now = start + minutes(10);
+        let result = quota.charge_at(&[(account, MAX_AGE - 1)], now);
+        assert_exceeded(result, start + minutes(60));
+        match quota.charge_at(&[(account, MAX_AGE - 1)], now) {
+            Err(SubmitError::QuotaExceeded {
+                retry_after_secs, ..
+            }) => assert_eq!(retry_after_secs, 50 * 60),
+            other => panic!("Unexpected quota result: {:?}", other),
+        }
+
+        // Once the account is old enough, it's not limited anymore.
+        for _ in 0..5 {
+            quota.charge_at(&[(account, MAX_AGE)], now).unwrap();
+        }
+        quota
+            .charge_at(&[(account, 0)], start + minutes(60))
+            .unwrap();
+        let result = quota.charge_at(&[(account, 0)], start + minutes(60));
+        assert_exceeded(result, start + minutes(61));
+    }
+
+    #[test]
+    fn batches_are_charged_atomically() {
+        let quota = quota();
+        let (first, second) = (AccountId(1), AccountId(2));
+        let now = Utc::now();
+
+        quota.charge_at(&[(first, 0), (first, 0)], now).unwrap();
+        // The second account is not charged since the first one is out of quota.
+        let result = quota.charge_at(&[(second, 0), (first, 0), (first, 0)], now);
+        assert_exceeded(result, now + chrono::Duration::hours(1));
+        quota
+            .charge_at(&[(second, 0), (second, 0), (second, 0), (first, 0)], now)
+            .unwrap();
+
+        // Batches larger than the quota never fit.
+        let batch = vec![(AccountId(3), 0); 4];
+        let result = quota.charge_at(&batch, now);
+        assert_exceeded(result, now + chrono::Duration::hours(1));
+        // Old accounts in the batch are not charged.
+        quota
+            .charge_at(&[(AccountId(3), 0), (AccountId(4), MAX_AGE)], now)
+            .unwrap();
+    }
+
+    #[test]
+    fn exempt_api_keys() {
+        let quota = quota();
+        assert!(quota.is_exempt(Some("key")));
+        assert!(!quota.is_exempt(Some("other")));
+        assert!(!quota.is_exempt(None));
+    }
+}
@@ -0,0 +1,119 @@
+//! Durations of the batch submission stages, reported to the integrators on request
+//! to find out which stage makes the submission of their batches slow.
+
+// Built-in uses
+use std::time::{Duration, Instant};
+
+// External uses
+
+// Workspace uses
+use zksync_api_types::v02::transaction::SubmitBatchTimings;
+
+// Local uses
+
+/// Stages of the batch submission in the order they are performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BatchStage {
+    Parse,
+    TokenChecks,
+    FeeAggregation,
+    SignatureVerification,
+    CoreForward,
+}
+
+#[derive(Debug)]
+struct TimerState {
+    started_at: Instant,
+    stage_started_at: Instant,
+    stages: [Duration; 5],
+}
+
+/// Measures the durations of the batch submission stages. A disabled timer doesn't even read
+/// the clock, so the submissions without the debug timings are not slowed down.
+#[derive(Debug)]
+pub(super) struct BatchStageTimer(Option<TimerState>);
+
+impl BatchStageTimer {
+    pub fn new(enabled: bool) -> Self {
+        Self(enabled.then(|| {
+            let now = Instant::now();
+            TimerState {
+                started_at: now,
+                stage_started_at: now,
+                stages: Default::default(),
+            }
+        }))
+    }
+
+    /// Finishes the stage, which is started once the previous one is finished.
+    pub fn finish(&mut self, stage: BatchStage) {
+        if let Some(state) = &mut self.0 {
+            let now = Instant::now();
+            state.stages[stage as usize] += now - state.stage_started_at;
+            state.stage_started_at = now;
+        }
+    }
+
+    /// Returns the measured durations, `None` if the timer is disabled.
+    pub fn into_timings(self) -> Option<SubmitBatchTimings> {
+        self.0.map(|state| {
+            let micros = |stage: BatchStage| state.stages[stage as usize].as_micros() as u64;
+            SubmitBatchTimings {
+                parse_us: micros(BatchStage::Parse),
+                token_checks_us: micros(BatchStage::TokenChecks),
+                fee_aggregation_us: micros(BatchStage::FeeAggregation),
+                signature_verification_us: micros(BatchStage::SignatureVerification),
+                core_forward_us: micros(BatchStage::CoreForward),
+                total_us: state.started_at.elapsed().as_micros() as u64,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_sum_up_to_total() {
+        let stages = [
+            BatchStage::Parse,
+            BatchStage::TokenChecks,
+            BatchStage::FeeAggregation,
+            BatchStage::SignatureVerification,
+            BatchStage::CoreForward,
+        ];
+        let mut timer = BatchStageTimer::new(true);
+        for stage in stages.iter() {
+            std::thread::sleep(Duration::from_millis(5));
+            timer.finish(*stage);
+        }
+        let timings = timer.into_timings().unwrap();
+
+        let stages_sum = timings.parse_us
+            + timings.token_checks_us
+            + timings.fee_aggregation_us
+            + timings.signature_verification_us
+            + timings.core_forward_us;
+        for stage_us in [
+            timings.parse_us,
+            timings.token_checks_us,
+            timings.fee_aggregation_us,
+            timings.signature_verification_us,
+            timings.core_forward_us,
+        ] {
+            assert!(stage_us >= 5_000, "{:?}", timings);
+        }
+        // Only the rounding and the time after the last stage are not attributed to the stages.
+        assert!(stages_sum <= timings.total_us, "{:?}", timings);
+        assert!(timings.total_us - stages_sum <= 1_000, "{:?}", timings);
+    }
+
+    #[test]
+    fn disabled_timer_reports_nothing() {
+        let mut timer = BatchStageTimer::new(false);
+        timer.finish(BatchStage::Parse);
+        timer.finish(BatchStage::CoreForward);
+        assert_eq!(timer.into_timings(), None);
+    }
+}
@@ -0,0 +1,2749 @@
+//! Helper module to submit transactions into the zkSync Network.
+
+pub mod account_quota;
+mod batch_timings;
+pub mod screening;
+
+// Built-in uses
+use std::iter::FromIterator;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+    time::Duration as StdDuration,
+};
+
+// External uses
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
+use futures::{
+    channel::{mpsc, oneshot},
+    prelude::*,
+};
+use itertools::izip;
+use num::rational::Ratio;
+use num::{bigint::ToBigInt, BigUint, Zero};
+use thiserror::Error;
+use tracing::Instrument;
+
+// Workspace uses
+use zksync_api_types::{
+    error_code::ErrorCode,
+    v02::{
+        account::{AccountLockInfo, LockAccount, UnlockAccount},
+        fee::{FeeTokenRequirement, SignedFeeQuote},
+        transaction::{
+            CancelTxRequest, SubmitBatchResponse, Toggle2FA, Toggle2FAResponse,
+            TxHashSerializeWrapper,
+        },
+    },
+    TxCancellation, TxWithSignature,
+};
+use zksync_crypto::params::{max_fungible_token_id, max_token_id};
+use zksync_storage::misc::records::Subsidy;
+use zksync_storage::{chain::account::records::EthAccountType, ConnectionPool};
+use zksync_types::{
+    event::transaction::TransactionType,
+    helpers::{
+        closest_greater_or_eq_packable_token_amount, nearest_packable_fee_amount,
+        nearest_packable_token_amount,
+    },
+    tx::{
+        ChangePubKeyEthAuthData, EthBatchSignData, EthBatchSignatures, EthSignData, Order,
+        SignedZkSyncTx, TxEthSignature, TxEthSignatureVariant, TxHash, TxSignature,
+    },
+    AccountId, Address, ChainId, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H160,
+};
+use zksync_utils::{
+    big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_scaled_u64, scaled_big_decimal_to_ratio,
+};
+
+// Local uses
+use crate::{
+    api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
+    core_api_client::CoreApiClient,
+    fee_ticker::{
+        quote::{FeeQuoteError, FeeQuoteSigner},
+        ResponseBatchFee, ResponseFee, TokenPriceRequestType,
+    },
+    signature_checker::{
+        AccountLockRequest, BatchRequest, OrderRequest, RequestData, Toggle2FARequest,
+        TxCancellationRequest, TxRequest, VerifiedTx, VerifySignatureRequest,
+    },
+    tx_error::{AccountLockError, Toggle2FAError},
+    utils::{
+        account_locks::{lock_info, AccountLocks},
+        banned_addresses::BannedAddresses,
+        block_details_cache::BlockDetailsCache,
+        disabled_tx_types::DisabledTxTypes,
+        disk_cache::DiskCache,
+        sign_verifier_breaker::{BreakerThresholds, SignVerifierBreaker},
+        special_accounts::is_reserved_recipient,
+        token_cache::TokenCache,
+    },
+};
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_mempool::MempoolTransactionRequest;
+use zksync_types::tx::{
+    error::{Create2AddressMismatch, TxAddError},
+    preconditions::{check_time_range, TxPreconditionFailure},
+};
+
+use self::account_quota::AccountTxQuota;
+use self::batch_timings::{BatchStage, BatchStageTimer};
+use self::screening::{ScreeningVerdict, WithdrawalScreening};
+use super::rpc_server::types::RequestMetadata;
+use crate::fee_ticker::{FeeTicker, PriceError};
+
+const VALIDNESS_INTERVAL_MINUTES: i64 = 40;
+
+#[derive(Clone)]
+pub struct TxSender {
+    pub mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    pub sign_verify_requests: mpsc::Sender<VerifySignatureRequest>,
+    /// Rejects new submissions while the signature verifier is overloaded.
+    pub sign_verifier_breaker: SignVerifierBreaker,
+    pub ticker: FeeTicker,
+
+    pub pool: ConnectionPool,
+    pub tokens: TokenCache,
+
+    pub forced_exit_checker: ForcedExitChecker,
+    pub blocks: BlockDetailsCache,
+    /// Persistent cache of the finalized data, shared by the caches of the servers using the sender.
+    pub disk_cache: Option<DiskCache>,
+    /// Types of transactions that are temporarily not accepted.
+    pub disabled_tx_types: DisabledTxTypes,
+    /// Addresses that are not allowed to submit transactions or receive funds.
+    pub banned_addresses: BannedAddresses,
+    /// Accounts which owners have frozen the outgoing transactions.
+    pub account_locks: AccountLocks,
+    /// Time between the unlock request of the account owner and the actual unlock.
+    pub account_unlock_cooldown: StdDuration,
+    /// List of account IDs that do not have to pay fees for operations.
+    pub fee_free_accounts: HashSet<AccountId>,
+    pub enforce_pubkey_change_fee: bool,
+    // Limit the number of both transactions and Ethereum signatures per batch.
+    pub max_number_of_transactions_per_batch: usize,
+    pub max_number_of_authors_per_batch: usize,
+
+    pub current_subsidy_type: String,
+    pub max_subsidy_usd: Ratio<BigUint>,
+    pub subsidized_ips: HashSet<String>,
+    pub chain_id: ChainId,
+    /// Signer of the fee quotes, quoted fees are accepted until the quote expires.
+    pub fee_quotes: FeeQuoteSigner,
+    /// Factories the CREATE2 accounts are allowed to be deployed by, any factory is allowed if empty.
+    pub create2_factory_allowlist: HashSet<Address>,
+    /// Screening of the withdrawal destinations, disabled if the screening service is not configured.
+    pub screening: Option<WithdrawalScreening>,
+    /// Tokens that are temporarily not accepted in the transactions.
+    pub disabled_tokens: HashSet<TokenId>,
+    /// Minimum amounts of the transfers and withdrawals per token.
+    pub min_transfer_amounts: HashMap<TokenId, BigUint>,
+    /// Minimum amount in USD for the tokens without the explicit minimum.
+    pub min_transfer_amount_usd: Option<Ratio<BigUint>>,
+    /// Transactions quota of the recently created accounts, disabled if not configured.
+    pub account_tx_quota: Option<AccountTxQuota>,
+    /// API keys allowed to request the durations of the batch submission stages.
+    pub debug_timings_api_keys: HashSet<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum SubmitError {
+    #[error("Account close tx is disabled.")]
+    AccountCloseDisabled,
+    #[error("Transactions of type {0} are temporarily disabled.")]
+    TxTypeDisabled(TransactionType),
+    #[error(
+        "Transaction #{index} in the batch has type {tx_type}, which is temporarily disabled."
+    )]
+    BatchTxTypeDisabled {
+        index: usize,
+        tx_type: TransactionType,
+    },
+    #[error("Transaction involves an address that is not allowed to use the network.")]
+    AddressBlocked,
+    #[error("Account {0} is locked by its owner, only ChangePubKey transactions are accepted.")]
+    AccountLocked(AccountId),
+    #[error("Token {0} is not found.")]
+    UnknownToken(TokenId),
+    #[error("Token {0} is temporarily not accepted.")]
+    TokenDisabled(TokenId),
+    #[error("Funds can't be sent to the reserved address {0:?}.")]
+    ReservedRecipient(Address),
+    #[error("Transactions of a batch can't be cancelled individually.")]
+    BatchTxCancellation,
+    #[error(
+        "Specified {field} {amount} is not packable, the nearest packable {field} is {nearest}."
+    )]
+    AmountNotPackable {
+        field: &'static str,
+        amount: BigUint,
+        nearest: BigUint,
+    },
+    #[error("Withdrawal is rejected by the compliance screening: the destination is {0}.")]
+    ComplianceRejected(ScreeningVerdict),
+    #[error("Amount {amount} of token {token} is below the minimum {minimum}.")]
+    AmountBelowMinimum {
+        token: TokenId,
+        amount: BigUint,
+        minimum: BigUint,
+    },
+    #[error("CREATE2 factory {0:?} is not allowed.")]
+    Create2FactoryNotAllowed(Address),
+    #[error("Invalid CREATE2 auth data: {0}.")]
+    Create2AddressMismatch(#[from] Create2AddressMismatch),
+    #[error("Invalid fee quote: {0}.")]
+    InvalidFeeQuote(#[from] FeeQuoteError),
+    #[error("Invalid params: {0}.")]
+    InvalidParams(String),
+    #[error("Fast processing available only for 'withdraw' operation type.")]
+    UnsupportedFastProcessing,
+    #[error("Incorrect transaction: {0}.")]
+    IncorrectTx(String),
+    #[error("Transaction adding error: {0}.")]
+    TxAdd(#[from] TxAddError),
+    #[error("Chosen token is not suitable for paying fees.")]
+    InappropriateFeeToken,
+    #[error("Chosen token is not suitable for paying fees of {0} transactions.")]
+    FeeTokenNotAllowedForTxType(TransactionType),
+    // Not all TxAddErrors would apply to Toggle2FA, but
+    // it is helpful to re-use IncorrectEthSignature and DbError
+    #[error("Failed to toggle 2FA: {0}.")]
+    Toggle2FA(#[from] Toggle2FAError),
+    #[error("Failed to lock or unlock the account: {0}.")]
+    AccountLock(#[from] AccountLockError),
+
+    #[error("Server is overloaded, please retry in {retry_after_secs} seconds.")]
+    ServerOverloaded { retry_after_secs: u64 },
+    #[error(
+        "Recently created accounts may submit at most {limit} transactions per {window_secs} seconds, please retry after {reset_at}."
+    )]
+    QuotaExceeded {
+        limit: u32,
+        window_secs: u64,
+        reset_at: DateTime<Utc>,
+        retry_after_secs: u64,
+    },
+
+    #[error("Communication error with the mempool: {0}.")]
+    MempoolCommunication(String),
+    #[error("Price error {0}")]
+    PriceError(#[from] PriceError),
+    #[error("Internal error.")]
+    Internal(#[from] anyhow::Error),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl SubmitError {
+    pub fn internal(inner: impl Into<anyhow::Error>) -> Self {
+        Self::Internal(inner.into())
+    }
+
+    pub fn other(msg: impl Display) -> Self {
+        Self::Other(msg.to_string())
+    }
+
+    pub fn mempool_communication(msg: impl Display) -> Self {
+        Self::MempoolCommunication(msg.to_string())
+    }
+
+    pub fn invalid_params(msg: impl Display) -> Self {
+        Self::InvalidParams(msg.to_string())
+    }
+
+    pub fn server_overloaded(retry_after: StdDuration) -> Self {
+        // Retry is suggested in whole seconds, and never immediately.
+        let retry_after_secs = (retry_after.as_millis() as u64 + 999) / 1000;
+        Self::ServerOverloaded {
+            retry_after_secs: retry_after_secs.max(1),
+        }
+    }
+
+    /// Returns the code of the error, the same for the REST and JSON-RPC APIs.
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::AccountCloseDisabled => ErrorCode::AccountCloseDisabled,
+            Self::TxTypeDisabled(_) | Self::BatchTxTypeDisabled { .. } => ErrorCode::TxTypeDisabled,
+            Self::InvalidFeeQuote(_) => ErrorCode::InvalidFeeQuote,
+            Self::AddressBlocked => ErrorCode::AddressBlocked,
+            Self::AccountLocked(_) => ErrorCode::AccountLocked,
+            Self::UnknownToken(_) => ErrorCode::UnknownToken,
+            Self::TokenDisabled(_) => ErrorCode::TokenDisabled,
+            Self::ReservedRecipient(_) => ErrorCode::ReservedRecipient,
+            Self::BatchTxCancellation => ErrorCode::BatchTxCancellation,
+            Self::AmountNotPackable { .. } => ErrorCode::AmountNotPackable,
+            Self::ComplianceRejected(_) => ErrorCode::ComplianceRejected,
+            Self::AmountBelowMinimum { .. } => ErrorCode::AmountBelowMinimum,
+            Self::Create2FactoryNotAllowed(_) => ErrorCode::Create2FactoryNotAllowed,
+            Self::Create2AddressMismatch(_) => ErrorCode::Create2AddressMismatch,
+            Self::ServerOverloaded { .. } => ErrorCode::ServerOverloaded,
+            Self::QuotaExceeded { .. } => ErrorCode::TxQuotaExceeded,
+            Self::InvalidParams(_) => ErrorCode::InvalidParams,
+            Self::UnsupportedFastProcessing => ErrorCode::UnsupportedFastProcessing,
+            Self::IncorrectTx(_) => ErrorCode::IncorrectTx,
+            Self::TxAdd(error) => tx_add_error_code(error),
+            Self::InappropriateFeeToken => ErrorCode::InappropriateFeeToken,
+            Self::FeeTokenNotAllowedForTxType(_) => ErrorCode::FeeTokenNotAllowedForTxType,
+            Self::MempoolCommunication(_) => ErrorCode::CommunicationCoreServer,
+            Self::Internal(_) => ErrorCode::InternalError,
+            Self::Toggle2FA(_) => ErrorCode::Toggle2FAError,
+            Self::AccountLock(_) => ErrorCode::AccountLockError,
+            Self::Other(_) => ErrorCode::Other,
+            Self::PriceError(PriceError::StalePrice { .. }) => ErrorCode::StaleFeeTokenPrice,
+            Self::PriceError(_) => ErrorCode::InternalError,
+        }
+    }
+
+    /// Returns the details of the error sent along with the message, if any.
+    pub fn error_data(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::ServerOverloaded { retry_after_secs } => {
+                Some(serde_json::json!({ "retryAfter": retry_after_secs }))
+            }
+            Self::QuotaExceeded {
+                limit,
+                reset_at,
+                retry_after_secs,
+                ..
+            } => Some(serde_json::json!({
+                "limit": limit,
+                "resetAt": reset_at.timestamp(),
+                "retryAfter": retry_after_secs,
+            })),
+            Self::MempoolCommunication(reason) => Some(reason.clone().into()),
+            Self::AmountNotPackable { field, nearest, .. } => Some(serde_json::json!({
+                "field": field,
+                "nearest": nearest.to_string(),
+            })),
+            Self::UnknownToken(token_id) | Self::TokenDisabled(token_id) => {
+                Some(serde_json::json!({ "tokenId": token_id }))
+            }
+            Self::AccountLocked(account_id) => Some(serde_json::json!({ "accountId": account_id })),
+            Self::AmountBelowMinimum { token, minimum, .. } => Some(serde_json::json!({
+                "tokenId": token,
+                "minimum": minimum.to_string(),
+            })),
+            Self::PriceError(PriceError::StalePrice {
+                token_id,
+                age_secs,
+                max_age_secs,
+                ..
+            }) => Some(serde_json::json!({
+                "tokenId": token_id,
+                "priceAge": age_secs,
+                "maxPriceAge": max_age_secs,
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the code of the reason the transaction was not added to the mempool.
+pub fn tx_add_error_code(error: &TxAddError) -> ErrorCode {
+    match error {
+        TxAddError::NonceMismatch => ErrorCode::NonceMismatch,
+        TxAddError::IncorrectTx(_) => ErrorCode::IncorrectTx,
+        TxAddError::TxFeeTooLow => ErrorCode::TxFeeTooLow,
+        TxAddError::TxBatchFeeTooLow => ErrorCode::TxBatchFeeTooLow,
+        TxAddError::EIP1271SignatureVerificationFail => ErrorCode::EIP1271SignatureVerificationFail,
+        TxAddError::MissingEthSignature => ErrorCode::MissingEthSignature,
+        TxAddError::IncorrectEthSignature => ErrorCode::IncorrectEthSignature,
+        TxAddError::ChangePkNotAuthorized => ErrorCode::ChangePkNotAuthorized,
+        TxAddError::Other => ErrorCode::TxAddError,
+        TxAddError::DbError => ErrorCode::StorageError,
+        TxAddError::EmptyBatch => ErrorCode::EmptyBatch,
+        TxAddError::BatchTooBig => ErrorCode::BatchTooBig,
+        TxAddError::BatchWithdrawalsOverload => ErrorCode::BatchWithdrawalsOverload,
+        TxAddError::EthSignaturesLimitExceeded => ErrorCode::EthSignaturesLimitExceeded,
+    }
+}
+
+#[macro_export]
+macro_rules! internal_error {
+    ($err:tt, $input:tt) => {{
+        vlog::warn!("Internal Server error: {}, input: {:?}", $err, $input);
+        SubmitError::internal($err)
+    }};
+
+    ($err:tt) => {{
+        internal_error!($err, "N/A")
+    }};
+}
+
+impl TxSender {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        connection_pool: ConnectionPool,
+        sign_verify_request_sender: mpsc::Sender<VerifySignatureRequest>,
+        ticker: FeeTicker,
+        config: &CommonApiConfig,
+        tokens: TokenCache,
+        mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+        disabled_tx_types: DisabledTxTypes,
+        banned_addresses: BannedAddresses,
+        chain_id: ChainId,
+    ) -> Self {
+        let max_number_of_transactions_per_batch =
+            config.max_number_of_transactions_per_batch as usize;
+        let max_number_of_authors_per_batch = config.max_number_of_authors_per_batch as usize;
+        let disk_cache = config
+            .disk_cache_dir()
+            .and_then(|dir| DiskCache::open(dir, config.disk_cache_max_bytes));
+        let account_locks = AccountLocks::new(connection_pool.clone());
+
+        Self {
+            mempool_tx_sender,
+            pool: connection_pool,
+            sign_verify_requests: sign_verify_request_sender,
+            sign_verifier_breaker: SignVerifierBreaker::new(BreakerThresholds::from_config(config)),
+            ticker,
+            tokens,
+            forced_exit_checker: ForcedExitChecker::new(
+                config.forced_exit_minimum_account_age_secs,
+            ),
+            enforce_pubkey_change_fee: config.enforce_pubkey_change_fee,
+            blocks: BlockDetailsCache::new(config.caches_size).with_disk_cache(disk_cache.clone()),
+            disk_cache,
+            disabled_tx_types,
+            banned_addresses,
+            account_locks,
+            account_unlock_cooldown: config.account_unlock_cooldown(),
+
+            fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
+            max_number_of_transactions_per_batch,
+            max_number_of_authors_per_batch,
+            current_subsidy_type: config.subsidy_name.clone(),
+            max_subsidy_usd: config.max_subsidy_usd(),
+            subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
+            chain_id,
+            fee_quotes: FeeQuoteSigner::new(
+                config.fee_quote_private_key,
+                config.fee_quote_validity(),
+            ),
+            create2_factory_allowlist: config.create2_factory_allowlist.iter().copied().collect(),
+            screening: WithdrawalScreening::from_config(config),
+            disabled_tokens: config.disabled_tokens.iter().copied().collect(),
+            min_transfer_amounts: config.min_transfer_amounts(),
+            min_transfer_amount_usd: config.min_transfer_amount_usd(),
+            account_tx_quota: AccountTxQuota::from_config(config),
+            debug_timings_api_keys: config.debug_timings_api_keys.iter().cloned().collect(),
+        }
+    }
+
+    /// If `ForcedExit` has Ethereum siganture (e.g. it's a part of a batch), an actual signer
+    /// is initiator, not the target, thus, this function will perform a database query to acquire
+    /// the corresponding address.
+    async fn get_tx_sender(&self, tx: &ZkSyncTx) -> Result<Address, anyhow::Error> {
+        match tx {
+            ZkSyncTx::ForcedExit(tx) => self.get_address_by_id(tx.initiator_account_id).await,
+            _ => Ok(tx.account()),
+        }
+    }
+    async fn get_address_by_id(&self, id: AccountId) -> Result<Address, anyhow::Error> {
+        self.pool
+            .access_storage()
+            .await?
+            .chain()
+            .account_schema()
+            .account_address_by_id(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Order signer account id not found in db"))
+    }
+
+    /// Returns the type of the transaction if transactions of this type are temporarily
+    /// not accepted.
+    async fn get_disabled_tx_type(
+        &self,
+        tx: &ZkSyncTx,
+    ) -> Result<Option<TransactionType>, SubmitError> {
+        let tx_type = match TransactionType::from_l2_tx(tx) {
+            Some(tx_type) => tx_type,
+            None => return Ok(None),
+        };
+        let is_disabled = self
+            .disabled_tx_types
+            .is_disabled(tx_type)
+            .await
+            .map_err(SubmitError::Internal)?;
+
+        Ok(Some(tx_type).filter(|_| is_disabled))
+    }
+
+    /// Checks that the fees of the transactions of the given types can be paid in the token.
+    ///
+    /// Both the fee endpoints and the submission rely on this check, so that the fee is never
+    /// quoted in the token the transaction is rejected for.
+    pub async fn check_fee_token(
+        &self,
+        tx_types: &[TransactionType],
+        token: TokenLike,
+    ) -> Result<(), SubmitError> {
+        let requirement = match FeeTokenRequirement::for_tx_types(tx_types.iter().copied()) {
+            Some(requirement) => requirement,
+            None => return Ok(()),
+        };
+        let liquid = self
+            .ticker
+            .token_allowed_for_fees(token.clone(), FeeTokenRequirement::Liquid)
+            .await?;
+        if !liquid {
+            return Err(SubmitError::InappropriateFeeToken);
+        }
+
+        if requirement > FeeTokenRequirement::Liquid {
+            let allowed = self
+                .ticker
+                .token_allowed_for_fees(token.clone(), requirement)
+                .await?;
+            if !allowed {
+                let tx_type = tx_types
+                    .iter()
+                    .copied()
+                    .find(|&tx_type| FeeTokenRequirement::for_tx_type(tx_type) == requirement)
+                    .expect("Requirement is taken from one of the transaction types");
+                return Err(SubmitError::FeeTokenNotAllowedForTxType(tx_type));
+            }
+        }
+        // The fee can't be calculated with an outdated price, the tokens with the thinner
+        // markets require the more recent prices.
+        self.ticker.check_fee_token_price(token).await?;
+        Ok(())
+    }
+
+    /// Checks that the tokens of the transaction exist and are accepted. The tokens are resolved
+    /// through the token cache, so the transactions with bogus tokens are rejected before any
+    /// signature work is done.
+    async fn check_tx_tokens(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        let fungible_tokens = fungible_tokens(tx);
+        for token_id in tx.tokens() {
+            if token_id > max_token_id() {
+                return Err(SubmitError::UnknownToken(token_id));
+            }
+            if self.disabled_tokens.contains(&token_id) {
+                return Err(SubmitError::TokenDisabled(token_id));
+            }
+            let token = self
+                .tokens
+                .resolve_token(token_id)
+                .await
+                .map_err(SubmitError::Internal)?
+                .ok_or(SubmitError::UnknownToken(token_id))?;
+            if token.is_nft && fungible_tokens.contains(&token_id) {
+                return Err(SubmitError::IncorrectTx(format!(
+                    "NFT {} can't be used in the {} transaction",
+                    token_id,
+                    tx.variance_name()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that none of the accounts the transaction moves funds from or to is banned.
+    /// The error doesn't reveal which of the addresses is banned.
+    async fn check_banned_addresses(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        let banned = self
+            .banned_addresses
+            .get()
+            .await
+            .map_err(SubmitError::Internal)?;
+        if banned.is_empty() {
+            return Ok(());
+        }
+
+        let mut addresses = tx.addresses();
+        let account_ids = match tx {
+            ZkSyncTx::ForcedExit(tx) => vec![tx.initiator_account_id],
+            ZkSyncTx::Swap(tx) => vec![tx.orders.0.account_id, tx.orders.1.account_id],
+            _ => vec![],
+        };
+        if !account_ids.is_empty() {
+            let mut storage = self
+                .pool
+                .access_storage()
+                .await
+                .map_err(SubmitError::internal)?;
+            for account_id in account_ids {
+                // Unknown accounts are rejected by the further checks.
+                if let Some(address) = storage
+                    .chain()
+                    .account_schema()
+                    .account_address_by_id(account_id)
+                    .await
+                    .map_err(SubmitError::Internal)?
+                {
+                    addresses.push(address);
+                }
+            }
+        }
+
+        if addresses.iter().any(|address| banned.contains(address)) {
+            return Err(SubmitError::AddressBlocked);
+        }
+        Ok(())
+    }
+
+    /// Checks that none of the accounts the transaction spends the funds of is locked by its owner.
+    async fn check_account_locks(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        let locked = self
+            .account_locks
+            .find_locked(&spending_accounts(tx))
+            .await
+            .map_err(SubmitError::Internal)?;
+        match locked {
+            Some(account_id) => Err(SubmitError::AccountLocked(account_id)),
+            None => Ok(()),
+        }
+    }
+
+    /// Screens the destination of the withdrawal or the forced exit, if the screening is enabled.
+    async fn check_withdrawal_screening(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        let screening = match &self.screening {
+            Some(screening) => screening,
+            None => return Ok(()),
+        };
+        let (destination, token, amount) = match tx {
+            ZkSyncTx::Withdraw(tx) => (tx.to, tx.token, tx.amount.clone()),
+            ZkSyncTx::ForcedExit(tx) => {
+                // The whole balance of the target account is withdrawn.
+                let balance = self
+                    .pool
+                    .access_storage()
+                    .await
+                    .map_err(SubmitError::internal)?
+                    .chain()
+                    .account_schema()
+                    .account_state_by_address(tx.target)
+                    .await
+                    .map_err(SubmitError::internal)?
+                    .committed
+                    .map(|(_, account)| account.get_balance(tx.token))
+                    .unwrap_or_default();
+                (tx.target, tx.token, balance)
+            }
+            _ => return Ok(()),
+        };
+
+        // The value of the tokens without a price is unknown.
+        let amount_usd = self
+            .ticker
+            .get_token_price(TokenLike::Id(token), TokenPriceRequestType::USDForOneWei)
+            .await
+            .ok()
+            .map(|price| BigDecimal::from(amount.to_bigint().unwrap()) * price);
+        screening.check(destination, amount_usd).await
+    }
+
+    /// Returns the minimum amount of the transfers and withdrawals of the token, if any. The explicit
+    /// minimum of the token takes precedence over the one in USD, tokens without a price have no minimum in USD.
+    async fn min_transfer_amount(&self, token: TokenId) -> Result<Option<BigUint>, SubmitError> {
+        // NFTs are always transferred as a whole.
+        if token > max_fungible_token_id() {
+            return Ok(None);
+        }
+        if let Some(minimum) = self.min_transfer_amounts.get(&token) {
+            return Ok(Some(minimum.clone()));
+        }
+        let min_amount_usd = match &self.min_transfer_amount_usd {
+            Some(min_amount_usd) => min_amount_usd,
+            None => return Ok(None),
+        };
+        let price = match self
+            .ticker
+            .get_token_price(TokenLike::Id(token), TokenPriceRequestType::USDForOneWei)
+            .await
+        {
+            Ok(price) => big_decimal_to_ratio(&price).map_err(SubmitError::Internal)?,
+            Err(_) => return Ok(None),
+        };
+        Ok(usd_to_token_amount(min_amount_usd, &price))
+    }
+
+    /// Rejects the dust transfers and withdrawals, see `check_min_amount`.
+    async fn check_min_transfer_amount(&self, tx: &ZkSyncTx) -> Result<(), SubmitError> {
+        let token = match limited_amount(tx) {
+            Some((token, _)) => token,
+            None => return Ok(()),
+        };
+        match self.min_transfer_amount(token).await? {
+            Some(minimum) => check_min_amount(tx, &minimum),
+            None => Ok(()),
+        }
+    }
+
+    /// Charges the verified transactions to the quotas of their initiators, if the quota is enabled.
+    async fn charge_tx_quota<'a>(
+        &self,
+        txs: impl Iterator<Item = &'a ZkSyncTx>,
+        api_key: Option<&str>,
+    ) -> Result<(), SubmitError> {
+        let quota = match &self.account_tx_quota {
+            Some(quota) => quota,
+            None => return Ok(()),
+        };
+        let initiators: Vec<_> = txs.filter_map(|tx| tx.account_id().ok()).collect();
+        quota.charge(&self.pool, &initiators, api_key).await
+    }
+
+    async fn get_tx_sender_type(&self, tx: &ZkSyncTx) -> Result<EthAccountType, SubmitError> {
+        self.get_sender_type(tx.account_id().or(Err(SubmitError::AccountCloseDisabled))?)
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))
+    }
+
+    async fn get_sender_type(&self, id: AccountId) -> Result<EthAccountType, anyhow::Error> {
+        Ok(self
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .account_schema()
+            .account_type_by_id(id)
+            .await?
+            .unwrap_or(EthAccountType::Owned))
+    }
+
+    pub async fn toggle_2fa(
+        &self,
+        toggle_2fa: Toggle2FA,
+    ) -> Result<Toggle2FAResponse, SubmitError> {
+        let account_id = toggle_2fa.account_id;
+        let current_type = self
+            .get_sender_type(toggle_2fa.account_id)
+            .await
+            .map_err(|_| SubmitError::Toggle2FA(Toggle2FAError::DbError))?;
+
+        if matches!(current_type, EthAccountType::CREATE2) {
+            return Err(SubmitError::Toggle2FA(Toggle2FAError::CREATE2));
+        }
+
+        // When 2FA is being enabled, supplied PubKeyHash is not used, so such a request
+        // is not valid.
+        if toggle_2fa.enable && toggle_2fa.pub_key_hash.is_some() {
+            return Err(SubmitError::Toggle2FA(Toggle2FAError::UnusedPubKeyHash));
+        }
+
+        let new_type = if toggle_2fa.enable {
+            EthAccountType::Owned
+        } else {
+            EthAccountType::No2FA(toggle_2fa.pub_key_hash)
+        };
+
+        self.verify_toggle_2fa_request_eth_signature(toggle_2fa)
+            .await?;
+
+        self.pool
+            .access_storage()
+            .await
+            .map_err(|_| SubmitError::Toggle2FA(Toggle2FAError::DbError))?
+            .chain()
+            .account_schema()
+            .set_account_type(account_id, new_type)
+            .await
+            .map_err(|_| SubmitError::Toggle2FA(Toggle2FAError::DbError))?;
+
+        Ok(Toggle2FAResponse { success: true })
+    }
+
+    async fn verify_toggle_2fa_request_eth_signature(
+        &self,
+        toggle_2fa: Toggle2FA,
+    ) -> Result<(), SubmitError> {
+        check_request_timestamp(toggle_2fa.timestamp)?;
+
+        let message = toggle_2fa.get_ethereum_sign_message().into_bytes();
+
+        let signature = toggle_2fa.signature;
+        let signer = self
+            .get_address_by_id(toggle_2fa.account_id)
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+
+        let eth_sign_data = EthSignData { signature, message };
+        let (sender, receiever) = oneshot::channel();
+
+        let request = VerifySignatureRequest {
+            data: RequestData::Toggle2FA(Toggle2FARequest {
+                sign_data: eth_sign_data,
+                sender: signer,
+            }),
+            response: sender,
+        };
+
+        send_verify_request_and_recv(
+            request,
+            self.sign_verify_requests.clone(),
+            &self.sign_verifier_breaker,
+            receiever,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Locks the outgoing transactions of the account on request of its owner,
+    /// the pending unlock of the account is cancelled.
+    pub async fn lock_account(&self, request: LockAccount) -> Result<AccountLockInfo, SubmitError> {
+        check_request_timestamp(request.timestamp)?;
+        let owner = self.account_owner(request.account_id).await?;
+        let message = request.get_ethereum_sign_message().into_bytes();
+        self.verify_account_lock_eth_signature(owner, request.signature, message)
+            .await?;
+
+        let lock = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .account_locks_schema()
+            .lock_account(request.account_id, request.timestamp)
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .ok_or(AccountLockError::OutdatedRequest)?;
+        self.account_locks.invalidate().await;
+
+        Ok(lock_info(lock))
+    }
+
+    /// Schedules the unlock of the account after the cool-down on request of its owner.
+    /// The request is signed either with the Ethereum key or with the current signing key
+    /// of the account, the latter is checked if both signatures are provided.
+    /// Repeated requests don't postpone the scheduled unlock.
+    pub async fn unlock_account(
+        &self,
+        request: UnlockAccount,
+    ) -> Result<AccountLockInfo, SubmitError> {
+        check_request_timestamp(request.timestamp)?;
+        let lock = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .account_locks_schema()
+            .account_lock(request.account_id)
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .ok_or(AccountLockError::NotLocked)?;
+        if lock.unlocks_at.is_some() {
+            return Ok(lock_info(lock));
+        }
+
+        let message = request.get_sign_message().into_bytes();
+        match (request.signature, request.eth_signature) {
+            (Some(signature), _) => {
+                self.verify_signing_key_signature(request.account_id, signature, message)
+                    .await?
+            }
+            (None, Some(eth_signature)) => {
+                let owner = self.account_owner(request.account_id).await?;
+                self.verify_account_lock_eth_signature(owner, eth_signature, message)
+                    .await?
+            }
+            (None, None) => return Err(AccountLockError::MissingSignature.into()),
+        }
+
+        let unlocks_at = Utc::now()
+            + Duration::from_std(self.account_unlock_cooldown).map_err(SubmitError::internal)?;
+        let lock = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .account_locks_schema()
+            .schedule_unlock(request.account_id, request.timestamp, unlocks_at)
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .ok_or(AccountLockError::OutdatedRequest)?;
+        self.account_locks.invalidate().await;
+
+        Ok(lock_info(lock))
+    }
+
+    async fn account_owner(&self, account_id: AccountId) -> Result<Address, SubmitError> {
+        let owner = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .ok_or(AccountLockError::AccountNotFound)?;
+        Ok(owner)
+    }
+
+    async fn verify_account_lock_eth_signature(
+        &self,
+        owner: Address,
+        signature: TxEthSignature,
+        message: Vec<u8>,
+    ) -> Result<(), SubmitError> {
+        let eth_sign_data = EthSignData { signature, message };
+        let (sender, receiver) = oneshot::channel();
+
+        let request = VerifySignatureRequest {
+            data: RequestData::AccountLock(AccountLockRequest {
+                sign_data: eth_sign_data,
+                sender: owner,
+            }),
+            response: sender,
+        };
+
+        send_verify_request_and_recv(
+            request,
+            self.sign_verify_requests.clone(),
+            &self.sign_verifier_breaker,
+            receiver,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Checks that the message is signed with the signing key of the latest committed state
+    /// of the account, so the key set by the latest `ChangePubKey` is required.
+    async fn verify_signing_key_signature(
+        &self,
+        account_id: AccountId,
+        signature: TxSignature,
+        message: Vec<u8>,
+    ) -> Result<(), SubmitError> {
+        let (_, account) = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| AccountLockError::DbError)?
+            .chain()
+            .account_schema()
+            .last_committed_state_for_account(account_id)
+            .await
+            .map_err(|_| AccountLockError::DbError)?;
+        let pub_key_hash = account
+            .ok_or(AccountLockError::AccountNotFound)?
+            .pub_key_hash;
+
+        let is_signed = tokio::task::spawn_blocking(move || {
+            is_signed_by_key(&signature, &message, &pub_key_hash)
+        })
+        .await
+        .map_err(SubmitError::internal)?;
+        if !is_signed {
+            return Err(AccountLockError::IncorrectSignature.into());
+        }
+        Ok(())
+    }
+
+    /// Cancels the transaction that is not proposed for a block yet on request of its sender.
+    /// Transactions that are not forwarded to the mempool yet are cancelled right away,
+    /// the rest are removed from the mempool by the core server, which knows whether
+    /// the transaction is already proposed. Returns `None` if the transaction is unknown.
+    pub async fn cancel_tx(
+        &self,
+        tx_hash: TxHash,
+        signature: TxEthSignature,
+        core_client: &CoreApiClient,
+    ) -> Result<Option<TxCancellation>, SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?;
+        let outbox_tx = storage
+            .chain()
+            .mempool_schema()
+            .get_undelivered_outbox_tx(tx_hash)
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?;
+        let tx = match outbox_tx {
+            Some((_, Some(_batch_hash))) => return Err(SubmitError::BatchTxCancellation),
+            Some((tx, None)) => tx.tx,
+            None => match storage
+                .chain()
+                .mempool_schema()
+                .get_tx_with_batch_id(tx_hash)
+                .await
+                .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?
+            {
+                Some((_, Some(_batch_id))) => return Err(SubmitError::BatchTxCancellation),
+                Some((tx, None)) => tx.tx,
+                // The transaction is either executed or unknown.
+                None => {
+                    let is_known = storage
+                        .chain()
+                        .mempool_schema()
+                        .is_tx_known(tx_hash)
+                        .await
+                        .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?;
+                    return Ok(is_known.then(|| TxCancellation::TooLate));
+                }
+            },
+        };
+        drop(storage);
+
+        self.verify_tx_cancellation_eth_signature(&tx, signature)
+            .await?;
+
+        // The transaction may be forwarded to the mempool in the meantime,
+        // then it's up to the mempool to cancel it.
+        let cancelled = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?
+            .chain()
+            .mempool_schema()
+            .cancel_outbox_tx(tx_hash)
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?;
+        if cancelled {
+            return Ok(Some(TxCancellation::Cancelled));
+        }
+        core_client
+            .cancel_tx(tx_hash)
+            .await
+            .map(Some)
+            .map_err(SubmitError::mempool_communication)
+    }
+
+    async fn verify_tx_cancellation_eth_signature(
+        &self,
+        tx: &ZkSyncTx,
+        signature: TxEthSignature,
+    ) -> Result<(), SubmitError> {
+        let signer = self
+            .get_tx_sender(tx)
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+        let message = CancelTxRequest::get_ethereum_sign_message(tx.hash()).into_bytes();
+
+        let eth_sign_data = EthSignData { signature, message };
+        let (sender, receiver) = oneshot::channel();
+
+        let request = VerifySignatureRequest {
+            data: RequestData::TxCancellation(TxCancellationRequest {
+                sign_data: eth_sign_data,
+                sender: signer,
+            }),
+            response: sender,
+        };
+
+        send_verify_request_and_recv(
+            request,
+            self.sign_verify_requests.clone(),
+            &self.sign_verifier_breaker,
+            receiver,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn verify_order_eth_signature(
+        &self,
+        order: &Order,
+        signature: Option<TxEthSignature>,
+    ) -> Result<(), SubmitError> {
+        let signer_type = self
+            .get_sender_type(order.account_id)
+            .await
+            .map_err(|_| SubmitError::TxAdd(TxAddError::DbError))?;
+        if matches!(signer_type, EthAccountType::CREATE2) {
+            return if signature.is_some() {
+                Err(SubmitError::IncorrectTx(
+                    "Eth signature from CREATE2 account not expected".to_string(),
+                ))
+            } else {
+                Ok(())
+            };
+        }
+
+        if matches!(signer_type, EthAccountType::No2FA(None)) {
+            // We don't verify signatures for accounts with no 2FA
+            return Ok(());
+        }
+        if let EthAccountType::No2FA(Some(unchecked_hash)) = signer_type {
+            let order_pub_key_hash = PubKeyHash::from_pubkey(&order.signature.pub_key.0);
+            // We don't scheck the signature only if the order was signed with the same
+            // is the same as unchecked PubKey
+            if order_pub_key_hash == unchecked_hash {
+                return Ok(());
+            }
+        }
+
+        let signature = signature.ok_or(SubmitError::TxAdd(TxAddError::MissingEthSignature))?;
+        let signer = self
+            .get_address_by_id(order.account_id)
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+
+        let token_sell = self.token_info_from_id(order.token_sell).await?;
+        let token_buy = self.token_info_from_id(order.token_buy).await?;
+        let message = order
+            .get_ethereum_sign_message(&token_sell.symbol, &token_buy.symbol, token_sell.decimals)
+            .into_bytes();
+        let eth_sign_data = EthSignData { signature, message };
+        let (sender, receiever) = oneshot::channel();
+
+        let request = VerifySignatureRequest {
+            data: RequestData::Order(OrderRequest {
+                order: Box::new(order.clone()),
+                sign_data: eth_sign_data,
+                sender: signer,
+            }),
+            response: sender,
+        };
+
+        send_verify_request_and_recv(
+            request,
+            self.sign_verify_requests.clone(),
+            &self.sign_verifier_breaker,
+            receiever,
+        )
+        .await?;
+        Ok(())
+    }
+
+    // This method is left for RPC API
+    #[deprecated(note = "Use the submit_tx function instead")]
+    pub async fn submit_tx_with_separate_fp(
+        &self,
+        mut tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        fast_processing: Option<bool>,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<TxHash, SubmitError> {
+        let fast_processing = fast_processing.unwrap_or(false);
+        if fast_processing && !tx.is_withdraw() {
+            return Err(SubmitError::UnsupportedFastProcessing);
+        }
+
+        if let ZkSyncTx::Withdraw(withdraw) = &mut tx {
+            if withdraw.fast {
+                // We set `fast` field ourselves, so we have to check that user did not set it themselves.
+                return Err(SubmitError::IncorrectTx(
+                    "'fast' field of Withdraw transaction must not be set manually.".to_string(),
+                ));
+            }
+
+            withdraw.fast = fast_processing;
+        }
+
+        let result = self
+            .submit_tx(tx, signature, extracted_request_metadata)
+            .await;
+
+        if let Err(err) = &result {
+            let err_label = match err {
+                SubmitError::IncorrectTx(err) => err.clone(),
+                SubmitError::TxAdd(err) => err.to_string(),
+                _ => "other".to_string(),
+            };
+            let labels = vec![("stage", "api".to_string()), ("error", err_label)];
+            metrics::increment_counter!("rejected_txs", &labels);
+        }
+
+        result
+    }
+
+    pub async fn can_subsidize(
+        &self,
+        new_subsidy_usd: Ratio<BigUint>,
+    ) -> Result<bool, anyhow::Error> {
+        let subsidized_already = self
+            .pool
+            .access_storage()
+            .await?
+            .misc_schema()
+            .get_total_used_subsidy_for_type(&self.current_subsidy_type)
+            .await?;
+        let subsidized_already_usd = scaled_big_decimal_to_ratio(subsidized_already)?;
+
+        let result = if self.max_subsidy_usd > subsidized_already_usd {
+            &self.max_subsidy_usd - &subsidized_already_usd >= new_subsidy_usd
+        } else {
+            false
+        };
+
+        Ok(result)
+    }
+
+    pub async fn should_subsidize_cpk(
+        &self,
+        normal_fee: &BigUint,
+        subsidized_fee: &BigUint,
+        subsidy_size_usd: &Ratio<BigUint>,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<bool, SubmitError> {
+        let should_subsidize_ip = if let Some(meta) = extracted_request_metadata {
+            self.subsidized_ips.contains(&meta.ip)
+        } else {
+            false
+        };
+
+        let result = should_subsidize_ip
+            && subsidized_fee < normal_fee
+            && self
+                .can_subsidize(subsidy_size_usd.clone())
+                .await
+                .map_err(SubmitError::Internal)?;
+
+        Ok(result)
+    }
+
+    pub async fn store_subsidy_data(
+        &self,
+        hash: TxHash,
+        normal_fee: BigUint,
+        subsidized_fee: BigUint,
+        token_id: TokenId,
+    ) -> Result<(), anyhow::Error> {
+        let token_price_in_usd = self
+            .ticker
+            .get_token_price(TokenLike::Id(token_id), TokenPriceRequestType::USDForOneWei)
+            .await?;
+
+        let full_cost_usd = big_decimal_to_ratio(&token_price_in_usd)? * &normal_fee;
+        let subsidized_cost_usd = big_decimal_to_ratio(&token_price_in_usd)? * &subsidized_fee;
+        if full_cost_usd < subsidized_cost_usd {
+            return Err(anyhow::Error::msg(
+                "Trying to subsidize transaction which should not be subsidized",
+            ));
+        }
+
+        let subsidy = Subsidy {
+            usd_amount_scaled: ratio_to_scaled_u64(&full_cost_usd - &subsidized_cost_usd),
+            full_cost_usd_scaled: ratio_to_scaled_u64(full_cost_usd),
+            token_id,
+            token_amount: biguint_to_big_decimal(subsidized_fee),
+            full_cost_token: biguint_to_big_decimal(normal_fee),
+            subsidy_type: self.current_subsidy_type.clone(),
+            tx_hash: hash,
+        };
+
+        self.pool
+            .access_storage()
+            .await?
+            .misc_schema()
+            .store_subsidy(subsidy)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn submit_tx(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<TxHash, SubmitError> {
+        self.submit_tx_with_fee_quote(tx, signature, None, extracted_request_metadata)
+            .await
+    }
+
+    /// Submits the transaction. If the signed fee quote is provided and is still valid,
+    /// the quoted fee is accepted even if the prices have changed since the quote was issued.
+    ///
+    /// The validated transaction is persisted into the submission outbox and its hash
+    /// is returned right away, the delivery to the mempool is performed by `TxForwarder`.
+    pub async fn submit_tx_with_fee_quote(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        fee_quote: Option<SignedFeeQuote>,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<TxHash, SubmitError> {
+        // Signatures are never recorded, the hash is recorded once the transaction is finalized.
+        let span = tracing::info_span!(
+            "submit_tx",
+            tx_type = %tx.variance_name(),
+            tx_hash = tracing::field::Empty,
+        );
+        self.submit_tx_inner(tx, signature, fee_quote, extracted_request_metadata)
+            .instrument(span)
+            .await
+    }
+
+    /// Performs the stages of the transaction submission, each stage is covered by its own span.
+    async fn submit_tx_inner(
+        &self,
+        mut tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        fee_quote: Option<SignedFeeQuote>,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<TxHash, SubmitError> {
+        let labels = vec![
+            ("stage", "api".to_string()),
+            ("name", tx.variance_name()),
+            ("token", tx.token_id().to_string()),
+        ];
+        // The initial state of processing tx
+        metrics::increment_counter!("process_tx_count", &labels);
+        let api_key = extracted_request_metadata
+            .as_ref()
+            .and_then(|meta| meta.api_key.clone());
+
+        let (token, msg_to_sign) = async {
+            if tx.is_close() {
+                return Err(SubmitError::AccountCloseDisabled);
+            }
+
+            if let Some(tx_type) = self.get_disabled_tx_type(&tx).await? {
+                return Err(SubmitError::TxTypeDisabled(tx_type));
+            }
+            self.check_tx_tokens(&tx).await?;
+            self.check_banned_addresses(&tx).await?;
+            self.check_account_locks(&tx).await?;
+            check_recipient(&tx)?;
+            check_packable_amounts(&tx)?;
+            self.check_min_transfer_amount(&tx).await?;
+            check_expiration(&tx)?;
+            check_create2_auth_data(&tx, &self.create2_factory_allowlist)?;
+
+            if let ZkSyncTx::ForcedExit(forced_exit) = &tx {
+                self.check_forced_exit(forced_exit).await?;
+            }
+            if let ZkSyncTx::ChangePubKey(change_pub_key) = &mut tx {
+                change_pub_key.chain_id = Some(self.chain_id)
+            };
+
+            self.tx_message_to_sign(&tx).await
+        }
+        .instrument(tracing::info_span!("parse"))
+        .await?;
+        tracing::Span::current().record("tx_hash", &tracing::field::display(tx.hash().to_string()));
+
+        let is_whitelisted_initiator = tx
+            .account_id()
+            .map(|account_id| self.fee_free_accounts.contains(&account_id))
+            .unwrap_or(false);
+
+        let tx_fee_info = if !is_whitelisted_initiator {
+            tx.get_fee_info()
+        } else {
+            None
+        };
+
+        let sign_verify_channel = self.sign_verify_requests.clone();
+        let sign_verifier_breaker = &self.sign_verifier_breaker;
+
+        let fee_data_for_subsidy = async {
+            let mut fee_data_for_subsidy: Option<ResponseFee> = None;
+
+            if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
+                let should_enforce_fee = !matches!(tx_type, TxFeeTypes::ChangePubKey { .. })
+                    || self.enforce_pubkey_change_fee;
+
+                let tx_types: Vec<_> = TransactionType::from_l2_tx(&tx).into_iter().collect();
+                self.check_fee_token(&tx_types, token.clone()).await?;
+
+                if let Some(fee_quote) = &fee_quote {
+                    let now = Utc::now().timestamp() as u64;
+                    self.fee_quotes.check_tx_fee(
+                        fee_quote,
+                        now,
+                        tx_type,
+                        tx.token_id(),
+                        address,
+                        &provided_fee,
+                    )?;
+                }
+
+                let required_fee_data = self
+                    .ticker
+                    .get_fee_from_ticker_in_wei(tx_type, token.clone(), address)
+                    .await?;
+
+                let required_fee_data = if self
+                    .should_subsidize_cpk(
+                        &required_fee_data.normal_fee.total_fee,
+                        &required_fee_data.subsidized_fee.total_fee,
+                        &required_fee_data.subsidy_size_usd,
+                        extracted_request_metadata,
+                    )
+                    .await?
+                {
+                    fee_data_for_subsidy = Some(required_fee_data.clone());
+                    required_fee_data.subsidized_fee
+                } else {
+                    required_fee_data.normal_fee
+                };
+
+                // Converting `BitUint` to `BigInt` is safe.
+                let required_fee: BigDecimal =
+                    required_fee_data.total_fee.to_bigint().unwrap().into();
+                let provided_fee: BigDecimal = provided_fee.to_bigint().unwrap().into();
+                // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
+                let scaled_provided_fee = scale_user_fee_up(provided_fee);
+                // The fee covered by a valid quote is accepted regardless of the current prices.
+                if required_fee >= scaled_provided_fee && should_enforce_fee && fee_quote.is_none()
+                {
+                    return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+                }
+            }
+            Ok(fee_data_for_subsidy)
+        }
+        .instrument(tracing::info_span!("fee_check"))
+        .await?;
+
+        let verified_tx = async {
+            let tx_sender = self
+                .get_tx_sender(&tx)
+                .await
+                .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+
+            let verified_tx = verify_tx_info_message_signature(
+                &tx,
+                tx_sender,
+                token.clone(),
+                self.get_tx_sender_type(&tx).await?,
+                signature.tx_signature().clone(),
+                msg_to_sign,
+                sign_verify_channel,
+                sign_verifier_breaker,
+            )
+            .await?
+            .unwrap_tx();
+
+            if let ZkSyncTx::Swap(tx) = &tx {
+                if signature.is_single() {
+                    return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+                }
+                let signatures = signature.orders_signatures();
+                self.verify_order_eth_signature(&tx.orders.0, signatures.0.clone())
+                    .await?;
+                self.verify_order_eth_signature(&tx.orders.1, signatures.1.clone())
+                    .await?;
+            }
+            Ok(verified_tx)
+        }
+        .instrument(tracing::info_span!("verify_signature"))
+        .await?;
+
+        // Screening is the only check calling the external service, so it's done once
+        // the signature and the fee are verified.
+        self.check_withdrawal_screening(&tx).await?;
+
+        // Only the transactions signed by the account are charged, so that nobody else can exhaust its quota.
+        self.charge_tx_quota(std::iter::once(&tx), api_key.as_deref())
+            .await?;
+
+        // The transaction is delivered to the mempool by the `TxForwarder`, so the response
+        // doesn't depend on the mempool availability and resubmissions don't create duplicates.
+        async {
+            self.pool
+                .access_storage()
+                .await
+                .map_err(SubmitError::internal)?
+                .chain()
+                .mempool_schema()
+                .insert_outbox_tx(&verified_tx)
+                .await
+                .map_err(|err| {
+                    vlog::error!("Failed to persist the submitted transaction: {}", err);
+                    SubmitError::TxAdd(TxAddError::DbError)
+                })
+        }
+        .instrument(tracing::info_span!("forward"))
+        .await?;
+
+        // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
+        if let Some(fee_data_for_subsidy) = fee_data_for_subsidy {
+            // The following two bad scenarios are possible when applying subsidy for the tx:
+            // - The subsidy is stored, but the tx is then rejected by the state keeper
+            // - The tx is accepted by the state keeper, but the the `store_subsidy_data` returns an error for some reason
+            //
+            // Trying to omit these scenarios unfortunately leads to large code restructure
+            // which is not worth it for subsidies (we prefer stability here)
+            self.store_subsidy_data(
+                tx.hash(),
+                fee_data_for_subsidy.normal_fee.total_fee,
+                fee_data_for_subsidy.subsidized_fee.total_fee,
+                token.id,
+            )
+            .await
+            .map_err(|e| {
+                metrics::increment_counter!("tx_sender.submit_tx.store_subsidy_data_fail");
+                SubmitError::Other(format!(
+                    "Failed to store the subsidy to database. Reason: {}",
+                    e
+                ))
+            })?;
+        }
+
+        // if everything is OK, return the transactions hashes.
+        Ok(tx.hash())
+    }
+
+    /// Submits the batch. Like the single transactions, the validated batch is persisted into
+    /// the submission outbox and is delivered to the mempool by `TxForwarder`.
+    pub async fn submit_txs_batch(
+        &self,
+        txs: Vec<TxWithSignature>,
+        eth_signatures: Option<EthBatchSignatures>,
+        extracted_request_metadata: Option<RequestMetadata>,
+    ) -> Result<SubmitBatchResponse, SubmitError> {
+        self.submit_txs_batch_with_timings(txs, eth_signatures, extracted_request_metadata, false)
+            .await
+    }
+
+    /// Returns whether the API key is allowed to request the durations of the submission stages.
+    pub fn debug_timings_allowed(&self, api_key: Option<&str>) -> bool {
+        api_key.map_or(false, |key| self.debug_timings_api_keys.contains(key))
+    }
+
+    /// Submits the batch, reporting the durations of the submission stages in the response
+    /// if `debug_timings` is set.
+    pub async fn submit_txs_batch_with_timings(
+        &self,
+        txs: Vec<TxWithSignature>,
+        eth_signatures: Option<EthBatchSignatures>,
+        extracted_request_metadata: Option<RequestMetadata>,
+        debug_timings: bool,
+    ) -> Result<SubmitBatchResponse, SubmitError> {
+        let mut timer = BatchStageTimer::new(debug_timings);
+        // Bring the received signatures into a vector for simplified work.
+        let eth_signatures = EthBatchSignatures::api_arg_to_vec(eth_signatures);
+        let api_key = extracted_request_metadata
+            .as_ref()
+            .and_then(|meta| meta.api_key.clone());
+
+        if txs.is_empty() {
+            return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
+        }
+        // Even though this is going to be checked on the Mempool part,
+        // we don't want to verify huge batches as long as this operation
+        // is expensive.
+        if txs.len() > self.max_number_of_transactions_per_batch {
+            return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
+        }
+
+        for tx in &txs {
+            let labels = vec![
+                ("stage", "api".to_string()),
+                ("name", tx.tx.variance_name()),
+                ("token", tx.tx.token_id().to_string()),
+            ];
+            metrics::increment_counter!("process_tx_count", &labels);
+        }
+
+        // Same check but in terms of signatures.
+        if eth_signatures.len() > self.max_number_of_authors_per_batch {
+            return Err(SubmitError::TxAdd(TxAddError::EthSignaturesLimitExceeded));
+        }
+
+        if txs.iter().any(|tx| tx.tx.is_close()) {
+            return Err(SubmitError::AccountCloseDisabled);
+        }
+
+        for (index, tx) in txs.iter().enumerate() {
+            if let Some(tx_type) = self.get_disabled_tx_type(&tx.tx).await? {
+                return Err(SubmitError::BatchTxTypeDisabled { index, tx_type });
+            }
+        }
+        timer.finish(BatchStage::Parse);
+        // Tokens of all the transactions are checked before anything else is done with the batch.
+        for tx in &txs {
+            self.check_tx_tokens(&tx.tx).await?;
+        }
+        for tx in &txs {
+            self.check_banned_addresses(&tx.tx).await?;
+            self.check_account_locks(&tx.tx).await?;
+            check_recipient(&tx.tx)?;
+            check_packable_amounts(&tx.tx)?;
+            self.check_min_transfer_amount(&tx.tx).await?;
+            check_expiration(&tx.tx)?;
+            check_create2_auth_data(&tx.tx, &self.create2_factory_allowlist)?;
+        }
+        timer.finish(BatchStage::TokenChecks);
+
+        // Checking fees data
+        let mut provided_total_usd_fee = BigDecimal::from(0);
+        let mut transaction_types = vec![];
+
+        let eth_token = TokenLike::Id(TokenId(0));
+
+        let mut token_fees = HashMap::<Address, BigUint>::new();
+        let mut token_fees_ids = vec![];
+        let batch_tx_types: Vec<_> = txs
+            .iter()
+            .filter_map(|tx| TransactionType::from_l2_tx(&tx.tx))
+            .collect();
+
+        for tx in &txs {
+            let tx_fee_info = tx.tx.get_fee_info();
+
+            if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
+                // Save the transaction type before moving on to the next one, otherwise
+                // the total fee won't get affected by it.
+                transaction_types.push((tx_type, address));
+
+                // In batches, transactions with non-popular token are allowed to be included, but should not
+                // used to pay fees. Fees must be covered by some more common token, which is suitable
+                // for paying fees of every transaction in the batch.
+                if provided_fee == BigUint::zero() {
+                    continue;
+                }
+                self.check_fee_token(&batch_tx_types, token.clone()).await?;
+
+                let token_price_in_usd = self
+                    .ticker
+                    .get_token_price(token.clone(), TokenPriceRequestType::USDForOneWei)
+                    .await?;
+
+                let token_data = self.token_info_from_id(token).await?;
+                token_fees_ids.push(token_data.id);
+                let mut token_fee = token_fees.remove(&token_data.address).unwrap_or_default();
+                token_fee += &provided_fee;
+                token_fees.insert(token_data.address, token_fee);
+
+                provided_total_usd_fee +=
+                    BigDecimal::from(provided_fee.clone().to_bigint().unwrap())
+                        * &token_price_in_usd;
+            }
+        }
+
+        let mut fee_data_for_subsidy: Option<ResponseBatchFee> = None;
+
+        // Only one token in batch
+        if token_fees.len() == 1 {
+            let (batch_token, fee_paid) = token_fees.into_iter().next().unwrap();
+            let batch_token_fee = self
+                .ticker
+                .get_batch_from_ticker_in_wei(batch_token.into(), transaction_types.clone())
+                .await?;
+
+            let required_fee = if self
+                .should_subsidize_cpk(
+                    &batch_token_fee.normal_fee.total_fee,
+                    &batch_token_fee.subsidized_fee.total_fee,
+                    &batch_token_fee.subsidy_size_usd,
+                    extracted_request_metadata,
+                )
+                .await?
+            {
+                fee_data_for_subsidy = Some(batch_token_fee.clone());
+                batch_token_fee.subsidized_fee.total_fee
+            } else {
+                batch_token_fee.normal_fee.total_fee
+            };
+
+            let user_provided_fee =
+                scale_user_fee_up(BigDecimal::from(fee_paid.to_bigint().unwrap()));
+            let required_normal_fee = BigDecimal::from(required_fee.to_bigint().unwrap());
+
+            // Not enough fee
+            if required_normal_fee > user_provided_fee {
+                vlog::info!(
+                    "User provided batch fee in token is too low, required: {}, provided (scaled): {}",
+                    required_normal_fee.to_string(),
+                    user_provided_fee.to_string(),
+                );
+                return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
+            }
+        } else {
+            // Calculate required fee for ethereum token
+            let required_eth_fee = self
+                .ticker
+                .get_batch_from_ticker_in_wei(eth_token.clone(), transaction_types)
+                .await?;
+
+            let required_fee = if self
+                .should_subsidize_cpk(
+                    &required_eth_fee.normal_fee.total_fee,
+                    &required_eth_fee.subsidized_fee.total_fee,
+                    &required_eth_fee.subsidy_size_usd,
+                    extracted_request_metadata,
+                )
+                .await?
+            {
+                fee_data_for_subsidy = Some(required_eth_fee.clone());
+                required_eth_fee.subsidized_fee.total_fee
+            } else {
+                required_eth_fee.normal_fee.total_fee
+            };
+
+            let eth_price_in_usd = self
+                .ticker
+                .get_token_price(eth_token, TokenPriceRequestType::USDForOneWei)
+                .await?;
+
+            let required_total_usd_fee =
+                BigDecimal::from(required_fee.to_bigint().unwrap()) * &eth_price_in_usd;
+
+            // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
+            let scaled_provided_fee_in_usd = scale_user_fee_up(provided_total_usd_fee.clone());
+            if required_total_usd_fee > scaled_provided_fee_in_usd {
+                vlog::info!(
+                    "User provided batch fee is too low, required: {}, provided: {} (scaled: {}); difference {}",
+                    &required_total_usd_fee,
+                    provided_total_usd_fee.to_string(),
+                    scaled_provided_fee_in_usd.to_string(),
+                    (&required_total_usd_fee - &scaled_provided_fee_in_usd).to_string(),
+                );
+                return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
+            }
+        }
+        timer.finish(BatchStage::FeeAggregation);
+
+        for tx in txs.iter() {
+            if let ZkSyncTx::Swap(swap) = &tx.tx {
+                if tx.signature.is_single() {
+                    return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+                }
+                let signatures = tx.signature.orders_signatures();
+                self.verify_order_eth_signature(&swap.orders.0, signatures.0.clone())
+                    .await?;
+                self.verify_order_eth_signature(&swap.orders.1, signatures.1.clone())
+                    .await?;
+            }
+        }
+
+        let mut verified_txs = Vec::with_capacity(txs.len());
+        let mut verified_signatures = Vec::new();
+
+        let mut messages_to_sign = Vec::with_capacity(txs.len());
+        let mut tx_senders = Vec::with_capacity(txs.len());
+        let mut tx_sender_types = Vec::with_capacity(txs.len());
+        let mut tokens = Vec::with_capacity(txs.len());
+        for tx in txs.iter().map(|tx| &tx.tx) {
+            // Resolve the token and save it for constructing the batch message.
+            let (token, message) = self.tx_message_to_sign(tx).await?;
+            tokens.push(token);
+            messages_to_sign.push(message);
+            tx_senders.push(self.batch_tx_sender(tx).await?);
+            tx_sender_types.push(self.get_tx_sender_type(tx).await?);
+        }
+
+        let batch_sign_data = if !eth_signatures.is_empty() {
+            // User provided at least one signature for the whole batch.
+            // In this case each sender cannot be CREATE2.
+            if tx_sender_types
+                .iter()
+                .any(|_type| matches!(_type, EthAccountType::CREATE2))
+            {
+                return Err(SubmitError::IncorrectTx(
+                    "Eth signature from CREATE2 account not expected".to_string(),
+                ));
+            }
+            // Create batch signature data.
+            Some(EthBatchSignData {
+                signatures: eth_signatures,
+                message: batch_sign_message(txs.iter().map(|tx| &tx.tx), &tokens, &tx_senders),
+            })
+        } else {
+            None
+        };
+        let (verified_batch, sign_data) = verify_txs_batch_signature(
+            txs,
+            tx_senders,
+            tokens,
+            tx_sender_types,
+            batch_sign_data,
+            messages_to_sign,
+            self.sign_verify_requests.clone(),
+            &self.sign_verifier_breaker,
+        )
+        .await?
+        .unwrap_batch();
+        if let Some(sign_data) = sign_data {
+            verified_signatures.extend(sign_data.signatures.into_iter());
+        }
+        verified_txs.extend(verified_batch.into_iter());
+        timer.finish(BatchStage::SignatureVerification);
+
+        // Same as for the single transactions, only the valid withdrawals are screened.
+        for tx in &verified_txs {
+            self.check_withdrawal_screening(&tx.tx).await?;
+        }
+
+        self.charge_tx_quota(verified_txs.iter().map(|tx| &tx.tx), api_key.as_deref())
+            .await?;
+
+        let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
+
+        // The batch is delivered to the mempool by the `TxForwarder` the same way as the single
+        // transactions, so the mempool rejections are reported by the batch status instead.
+        self.pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?
+            .chain()
+            .mempool_schema()
+            .insert_outbox_batch(&verified_txs, &verified_signatures)
+            .await
+            .map_err(|err| {
+                vlog::error!("Failed to persist the submitted batch: {}", err);
+                SubmitError::TxAdd(TxAddError::DbError)
+            })?;
+
+        let batch_hash = TxHash::batch_hash(&tx_hashes);
+
+        // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
+        if let Some(fee_data) = fee_data_for_subsidy {
+            let subsidy_token_id = if token_fees_ids.len() == 1 {
+                token_fees_ids[0]
+            } else {
+                // When there are more than token to pay the fee with,
+                // we get the price of the batch in ETH and then convert it to USD.
+                // Since the `subsidies` table contains the token_id field and the only fee which is fetched from the fee_ticker is
+                // in ETH, then we can consider ETH as the token_id of the subsidy. Even though formally this may not be the case.
+                TokenId(0)
+            };
+
+            // The following two bad scenarios are possible when applying subsidy for the tx:
+            // - The subsidy is stored, but the tx is then rejected by the state keeper
+            // - The tx is accepted by the state keeper, but the the `store_subsidy_data` returns an error for some reason
+            //
+            // Trying to omit these scenarios unfortunately leads to large code restructure
+            // which is not worth it for subsidies (we prefer stability here)
+            self.store_subsidy_data(
+                batch_hash,
+                fee_data.normal_fee.total_fee,
+                fee_data.subsidized_fee.total_fee,
+                subsidy_token_id,
+            )
+            .await
+            .map_err(|e| {
+                metrics::increment_counter!("tx_sender.submit_txs_batch.store_subsidy_data_fail");
+
+                SubmitError::Other(format!(
+                    "Failed to store the subsidy to database. Reason: {}",
+                    e
+                ))
+            })?;
+        }
+
+        timer.finish(BatchStage::CoreForward);
+
+        Ok(SubmitBatchResponse {
+            transaction_hashes: tx_hashes.into_iter().map(TxHashSerializeWrapper).collect(),
+            batch_hash,
+            batch: None,
+            timings: timer.into_timings(),
+        })
+    }
+
+    /// For forced exits, we must check that target account exists for more
+    /// than 24 hours in order to give new account owners give an opportunity
+    /// to set the signing key. While `ForcedExit` operation doesn't do anything
+    /// bad to the account, it's more user-friendly to only allow this operation
+    /// after we're somewhat sure that zkSync account is not owned by anybody.
+    async fn check_forced_exit(
+        &self,
+        forced_exit: &zksync_types::ForcedExit,
+    ) -> Result<(), SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+
+        self.forced_exit_checker
+            .validate_forced_exit(&mut storage, forced_exit.target)
+            .await
+    }
+
+    /// Resolves the token of the transaction and returns it along with the message the user has to sign
+    /// to send the transaction. If the transaction doesn't need a message signature, the message is `None`.
+    /// Submitted transactions are verified against the same message.
+    pub(crate) async fn tx_message_to_sign(
+        &self,
+        tx: &ZkSyncTx,
+    ) -> Result<(Token, Option<Vec<u8>>), SubmitError> {
+        let token = self.token_info_from_id(tx.token_id()).await?;
+        let message = tx
+            .get_ethereum_sign_message(token.clone())
+            .map(String::into_bytes);
+        Ok((token, message))
+    }
+
+    /// Returns the message the senders have to sign to sign the batch as a whole.
+    /// Submitted batches with the batch signatures are verified against the same message.
+    pub(crate) async fn batch_message_to_sign(
+        &self,
+        txs: &[ZkSyncTx],
+    ) -> Result<Vec<u8>, SubmitError> {
+        if txs.is_empty() {
+            return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
+        }
+        let mut tokens = Vec::with_capacity(txs.len());
+        let mut senders = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let (token, _) = self.tx_message_to_sign(tx).await?;
+            tokens.push(token);
+            senders.push(self.batch_tx_sender(tx).await?);
+        }
+        Ok(batch_sign_message(txs, &tokens, &senders))
+    }
+
+    /// Returns the address signing the transaction as a part of the batch.
+    async fn batch_tx_sender(&self, tx: &ZkSyncTx) -> Result<Address, SubmitError> {
+        self.get_tx_sender(tx)
+            .await
+            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))
+    }
+
+    /// Resolves the token from the database.
+    pub(crate) async fn token_info_from_id(
+        &self,
+        token_id: impl Into<TokenLike>,
+    ) -> Result<Token, SubmitError> {
+        let token_id = token_id.into();
+        // Try to find the token in the cache first.
+        if let Some(token) = self.tokens.try_get_token_from_cache(token_id.clone()).await {
+            return Ok(token);
+        }
+
+        // Establish db connection and repeat the query, so the token is loaded
+        // from the db.
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+
+        self.tokens
+            .get_token(&mut storage, token_id)
+            .await
+            .map_err(SubmitError::internal)?
+            // TODO Make error more clean
+            .ok_or_else(|| SubmitError::other("Token not found in the DB"))
+    }
+}
+
+/// Builds the message of the batch from its transactions along with their tokens and senders.
+fn batch_sign_message<'a>(
+    txs: impl IntoIterator<Item = &'a ZkSyncTx>,
+    tokens: &[Token],
+    senders: &[Address],
+) -> Vec<u8> {
+    let txs = izip!(txs, tokens, senders)
+        .map(|(tx, token, sender)| (tx.clone(), token.clone(), *sender))
+        .collect();
+    EthBatchSignData::get_batch_sign_message(txs)
+}
+
+/// Returns the tokens of the transaction that can't be NFTs: the fee tokens and the tokens
+/// of the fungible withdrawals. Fees can't be paid in NFTs.
+fn fungible_tokens(tx: &ZkSyncTx) -> Vec<TokenId> {
+    match tx {
+        ZkSyncTx::Transfer(transfer) if !transfer.fee.is_zero() => vec![transfer.token],
+        ZkSyncTx::Withdraw(withdraw) => vec![withdraw.token],
+        ZkSyncTx::ForcedExit(forced_exit) => vec![forced_exit.token],
+        ZkSyncTx::ChangePubKey(change_pub_key) => vec![change_pub_key.fee_token],
+        ZkSyncTx::MintNFT(mint_nft) => vec![mint_nft.fee_token],
+        ZkSyncTx::WithdrawNFT(withdraw_nft) => vec![withdraw_nft.fee_token],
+        ZkSyncTx::Swap(swap) => vec![swap.fee_token],
+        _ => Vec::new(),
+    }
+}
+
+/// Rejects the transaction sending funds to the reserved address, e.g. a transfer to the NFT storage account.
+/// Such transactions would either fail in the block or lose the funds.
+fn check_recipient(tx: &ZkSyncTx) -> Result<(), SubmitError> {
+    let recipient = match tx {
+        ZkSyncTx::Transfer(transfer) => transfer.to,
+        ZkSyncTx::MintNFT(mint_nft) => mint_nft.recipient,
+        ZkSyncTx::ForcedExit(forced_exit) => forced_exit.target,
+        _ => return Ok(()),
+    };
+    if is_reserved_recipient(recipient) {
+        return Err(SubmitError::ReservedRecipient(recipient));
+    }
+    Ok(())
+}
+
+/// Returns the accounts the transaction spends the funds of. `ChangePubKey` only rotates the signing key,
+/// so it's accepted from the locked accounts: the owner may replace the compromised key.
+fn spending_accounts(tx: &ZkSyncTx) -> Vec<AccountId> {
+    match tx {
+        ZkSyncTx::ChangePubKey(_) => Vec::new(),
+        ZkSyncTx::Swap(swap) => vec![
+            swap.submitter_id,
+            swap.orders.0.account_id,
+            swap.orders.1.account_id,
+        ],
+        _ => tx.account_id().into_iter().collect(),
+    }
+}
+
+/// Checks that the request to change the account settings is signed recently enough,
+/// so the leaked signatures can't be used long after.
+fn check_request_timestamp(request_time: DateTime<Utc>) -> Result<(), SubmitError> {
+    let current_time = Utc::now();
+    let validness_interval = Duration::minutes(VALIDNESS_INTERVAL_MINUTES);
+
+    if current_time - validness_interval > request_time
+        || current_time + validness_interval < request_time
+    {
+        return Err(SubmitError::InvalidParams(format!(
+            "Timestamp differs by more than {} minutes",
+            VALIDNESS_INTERVAL_MINUTES
+        )));
+    }
+    Ok(())
+}
+
+/// Returns `true` if the message is signed with the key of the given hash.
+/// Accounts without the signing key can't sign anything.
+fn is_signed_by_key(signature: &TxSignature, message: &[u8], pub_key_hash: &PubKeyHash) -> bool {
+    if *pub_key_hash == PubKeyHash::default() {
+        return false;
+    }
+    signature.verify_musig(message).map_or(false, |pub_key| {
+        PubKeyHash::from_pubkey(&pub_key) == *pub_key_hash
+    })
+}
+
+/// Rejects the transaction with the amount or fee that can't be packed into the pubdata, naming the nearest
+/// packable value, so the client doesn't have to reimplement the packing rules. Withdrawal amounts are not packed.
+fn check_packable_amounts(tx: &ZkSyncTx) -> Result<(), SubmitError> {
+    let amounts = match tx {
+        ZkSyncTx::Transfer(transfer) => vec![&transfer.amount],
+        ZkSyncTx::Swap(swap) => vec![&swap.amounts.0, &swap.amounts.1],
+        _ => Vec::new(),
+    };
+    for amount in amounts {
+        let nearest = nearest_packable_token_amount(amount);
+        if nearest != *amount {
+            return Err(SubmitError::AmountNotPackable {
+                field: "amount",
+                amount: amount.clone(),
+                nearest,
+            });
+        }
+    }
+
+    if let Some((.., fee)) = tx.get_fee_info() {
+        let nearest = nearest_packable_fee_amount(&fee);
+        if nearest != fee {
+            return Err(SubmitError::AmountNotPackable {
+                field: "fee",
+                amount: fee,
+                nearest,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Returns the token and the amount of the transaction the minimum amount applies to: the transfers to other
+/// accounts and the withdrawals. Transfers to self only pay fees, forced exits and swaps are not limited.
+fn limited_amount(tx: &ZkSyncTx) -> Option<(TokenId, &BigUint)> {
+    match tx {
+        ZkSyncTx::Transfer(transfer) if transfer.from != transfer.to => {
+            Some((transfer.token, &transfer.amount))
+        }
+        ZkSyncTx::Withdraw(withdraw) => Some((withdraw.token, &withdraw.amount)),
+        _ => None,
+    }
+}
+
+/// Rejects the transfer or withdrawal with the amount below the minimum. Transfer amounts are packed, so
+/// the minimum is rounded up to the packable amount: the error names the smallest amount that is accepted.
+fn check_min_amount(tx: &ZkSyncTx, minimum: &BigUint) -> Result<(), SubmitError> {
+    let (token, amount) = match limited_amount(tx) {
+        Some(limited) => limited,
+        None => return Ok(()),
+    };
+    let minimum = match tx {
+        ZkSyncTx::Transfer(_) => closest_greater_or_eq_packable_token_amount(minimum),
+        _ => minimum.clone(),
+    };
+    if *amount < minimum {
+        return Err(SubmitError::AmountBelowMinimum {
+            token,
+            amount: amount.clone(),
+            minimum,
+        });
+    }
+    Ok(())
+}
+
+/// Converts the amount in USD into the amount of the token, rounding up, so that any smaller amount
+/// is worth less than the given one. Returns `None` for the tokens priced at zero.
+fn usd_to_token_amount(
+    amount_usd: &Ratio<BigUint>,
+    price_per_wei: &Ratio<BigUint>,
+) -> Option<BigUint> {
+    if price_per_wei.is_zero() {
+        return None;
+    }
+    Some((amount_usd / price_per_wei).ceil().to_integer())
+}
+
+/// Rejects the transaction that has already expired, since it would be rejected by the state keeper anyway.
+fn check_expiration(tx: &ZkSyncTx) -> Result<(), SubmitError> {
+    let now = Utc::now().timestamp() as u64;
+    match check_time_range(&tx.time_range(), now) {
+        Some(failure @ TxPreconditionFailure::Expired { .. }) => {
+            Err(SubmitError::IncorrectTx(failure.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks the CREATE2 auth data of the `ChangePubKey`: the factory must be allowed and the account
+/// address must be the one derived from the factory, the salt with the new public key hash and the code hash.
+/// Such accounts have no Ethereum signature to check, so this is the only proof of the account ownership.
+fn check_create2_auth_data(
+    tx: &ZkSyncTx,
+    allowed_factories: &HashSet<Address>,
+) -> Result<(), SubmitError> {
+    let change_pub_key = match tx {
+        ZkSyncTx::ChangePubKey(change_pub_key) => change_pub_key,
+        _ => return Ok(()),
+    };
+    let create2_data = match &change_pub_key.eth_auth_data {
+        Some(ChangePubKeyEthAuthData::CREATE2(create2_data)) => create2_data,
+        _ => return Ok(()),
+    };
+
+    if !allowed_factories.is_empty() && !allowed_factories.contains(&create2_data.creator_address) {
+        return Err(SubmitError::Create2FactoryNotAllowed(
+            create2_data.creator_address,
+        ));
+    }
+    create2_data.check_address(&change_pub_key.new_pk_hash, change_pub_key.account)?;
+    Ok(())
+}
+
+/// Send a request for the signature verification and wait for the response.
+/// The request is rejected right away if the verifier is overloaded.
+async fn send_verify_request_and_recv(
+    request: VerifySignatureRequest,
+    mut req_channel: mpsc::Sender<VerifySignatureRequest>,
+    breaker: &SignVerifierBreaker,
+    receiver: oneshot::Receiver<Result<VerifiedTx, TxAddError>>,
+) -> Result<VerifiedTx, SubmitError> {
+    // The permit is held until the response is received, so the request is counted as in flight.
+    let _permit = breaker
+        .try_acquire()
+        .map_err(SubmitError::server_overloaded)?;
+    // Send the check request.
+    req_channel
+        .send(request)
+        .await
+        .map_err(SubmitError::internal)?;
+    // Wait for the check result.
+    receiver
+        .await
+        .map_err(|err| internal_error!(err))?
+        .map_err(SubmitError::TxAdd)
+}
+
+/// Send a request for Ethereum signature verification and wait for the response.
+/// If `msg_to_sign` is not `None`, then the signature must be present.
+async fn verify_tx_info_message_signature(
+    tx: &ZkSyncTx,
+    tx_sender: Address,
+    token: Token,
+    account_type: EthAccountType,
+    signature: Option<TxEthSignature>,
+    msg_to_sign: Option<Vec<u8>>,
+    req_channel: mpsc::Sender<VerifySignatureRequest>,
+    breaker: &SignVerifierBreaker,
+) -> Result<VerifiedTx, SubmitError> {
+    if matches!(
+        (account_type, signature.clone(), msg_to_sign.clone()),
+        (EthAccountType::CREATE2, Some(_), Some(_))
+    ) {
+        return Err(SubmitError::IncorrectTx(
+            "Eth signature from CREATE2 account not expected".to_string(),
+        ));
+    }
+
+    let should_check_eth_signature = match (account_type, tx) {
+        (EthAccountType::CREATE2, _) => false,
+        (EthAccountType::No2FA(_), ZkSyncTx::ChangePubKey(_)) => true,
+        (EthAccountType::No2FA(hash), _) => {
+            if let Some(not_checked_hash) = hash {
+                let tx_pub_key_hash = PubKeyHash::from_pubkey(&tx.signature().pub_key.0);
+
+                tx_pub_key_hash != not_checked_hash
+            } else {
+                false
+            }
+        }
+
+        _ => true,
+    };
+
+    let eth_sign_data = match (msg_to_sign, should_check_eth_signature) {
+        (Some(message), true) => {
+            let signature = signature.ok_or(SubmitError::TxAdd(TxAddError::MissingEthSignature))?;
+            Some(EthSignData { signature, message })
+        }
+        _ => None,
+    };
+
+    let (sender, receiever) = oneshot::channel();
+
+    let request = VerifySignatureRequest {
+        data: RequestData::Tx(TxRequest {
+            tx: SignedZkSyncTx {
+                tx: tx.clone(),
+                eth_sign_data,
+                created_at: Utc::now(),
+            },
+            sender: tx_sender,
+            token,
+        }),
+        response: sender,
+    };
+
+    send_verify_request_and_recv(request, req_channel, breaker, receiever).await
+}
+
+/// Send a request for Ethereum signature verification and wait for the response.
+/// Unlike in case of `verify_tx_info_message_signature`, we do not require
+/// every transaction from the batch to be signed. The signature must be obtained
+/// through signing a human-readable message with accordance to zkSync protocol.
+async fn verify_txs_batch_signature(
+    batch: Vec<TxWithSignature>,
+    senders: Vec<Address>,
+    tokens: Vec<Token>,
+    sender_types: Vec<EthAccountType>,
+    batch_sign_data: Option<EthBatchSignData>,
+    msgs_to_sign: Vec<Option<Vec<u8>>>,
+    req_channel: mpsc::Sender<VerifySignatureRequest>,
+    breaker: &SignVerifierBreaker,
+) -> Result<VerifiedTx, SubmitError> {
+    // This hashset holds addresses that have performed a CREATE2 ChangePubKey
+    // within this batch, so that we don't check ETH signatures on their transactions
+    // from this batch. We save the account type to the db later.
+    let mut create2_senders = HashSet::<H160>::new();
+    let mut txs = Vec::with_capacity(batch.len());
+    for (tx, message, sender, mut sender_type) in
+        izip!(batch, msgs_to_sign, senders.iter(), sender_types)
+    {
+        if create2_senders.contains(sender) {
+            sender_type = EthAccountType::CREATE2;
+        }
+        if let ZkSyncTx::ChangePubKey(tx) = &tx.tx {
+            if let Some(auth_data) = &tx.eth_auth_data {
+                if auth_data.is_create2() {
+                    create2_senders.insert(*sender);
+                }
+            }
+        }
+        // If we have more signatures provided than required,
+        // we will verify those too.
+        let eth_sign_data = if let Some(message) = message {
+            match sender_type {
+                EthAccountType::CREATE2 => {
+                    if tx.signature.exists() {
+                        return Err(SubmitError::IncorrectTx(
+                            "Eth signature from CREATE2 account not expected".to_string(),
+                        ));
+                    }
+                    None
+                }
+                EthAccountType::Owned => {
+                    if batch_sign_data.is_none() && !tx.signature.exists() {
+                        return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+                    }
+                    tx.signature
+                        .tx_signature()
+                        .clone()
+                        .map(|signature| EthSignData { signature, message })
+                }
+                EthAccountType::No2FA(Some(unchecked_hash)) => {
+                    let tx_pub_key_hash = PubKeyHash::from_pubkey(&tx.tx.signature().pub_key.0);
+                    if tx_pub_key_hash != unchecked_hash {
+                        if batch_sign_data.is_none() && !tx.signature.exists() {
+                            return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+                        }
+
+                        tx.signature
+                            .tx_signature()
+                            .clone()
+                            .map(|signature| EthSignData { signature, message })
+                    } else {
+                        None
+                    }
+                }
+                EthAccountType::No2FA(None) => None,
+            }
+        } else {
+            None
+        };
+
+        txs.push(SignedZkSyncTx {
+            tx: tx.tx,
+            eth_sign_data,
+            created_at: Utc::now(),
+        });
+    }
+
+    let (sender, receiver) = oneshot::channel();
+
+    let request = VerifySignatureRequest {
+        data: RequestData::Batch(BatchRequest {
+            txs,
+            batch_sign_data,
+            senders,
+            tokens,
+        }),
+        response: sender,
+    };
+
+    send_verify_request_and_recv(request, req_channel, breaker, receiver).await
+}
+
+/// Scales the fee provided by user up to check whether the provided fee is enough to cover our expenses for
+/// maintaining the protocol.
+///
+/// We calculate both `provided_fee * 1.05` and `provided_fee + 1 cent` and choose the maximum.
+/// This is required since the price may change between signing the transaction and sending it to the server.
+fn scale_user_fee_up(provided_total_usd_fee: BigDecimal) -> BigDecimal {
+    let one_cent = BigDecimal::from_str("0.01").unwrap();
+
+    // This formula is needed when the fee is really small.
+    //
+    // We don't compare it with any of the following scaled numbers, because
+    // a) Scaling by two (100%) is always greater than scaling by 5%.
+    // b) It is intended as a smaller substitute for 1 cent scaling when
+    // scaling by 1 cent means scaling more than 2x.
+    if provided_total_usd_fee < one_cent {
+        let scaled_by_two_provided_fee_in_usd = provided_total_usd_fee * BigDecimal::from(2u32);
+
+        return scaled_by_two_provided_fee_in_usd;
+    }
+
+    // Scale by 5%.
+    let scaled_percent_provided_fee_in_usd =
+        provided_total_usd_fee.clone() * BigDecimal::from(105u32) / BigDecimal::from(100u32);
+
+    // Scale by 1 cent.
+    let scaled_one_cent_provided_fee_in_usd = provided_total_usd_fee + one_cent;
+
+    // Choose the maximum of these two values.
+    std::cmp::max(
+        scaled_percent_provided_fee_in_usd,
+        scaled_one_cent_provided_fee_in_usd,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use zksync_crypto::params::{AMOUNT_MANTISSA_BIT_WIDTH, NFT_STORAGE_ACCOUNT_ADDRESS};
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        tx::{ChangePubKey, ChangePubKeyCREATE2Data, ForcedExit, Transfer, Withdraw},
+        Nonce, TokenKind, H256,
+    };
+
+    /// Starts a signature verifier that accepts every request after the delay.
+    fn slow_sign_verifier(delay: StdDuration) -> mpsc::Sender<VerifySignatureRequest> {
+        let (sender, mut receiver) = mpsc::channel::<VerifySignatureRequest>(10);
+        tokio::spawn(async move {
+            while let Some(item) = receiver.next().await {
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let verified = VerifiedTx::unverified(item.data.get_tx_variant());
+                    item.response.send(Ok(verified)).unwrap_or_default();
+                });
+            }
+        });
+        sender
+    }
+
+    async fn verify_transfer(
+        channel: mpsc::Sender<VerifySignatureRequest>,
+        breaker: &SignVerifierBreaker,
+    ) -> Result<VerifiedTx, SubmitError> {
+        let transfer = Transfer::new(
+            AccountId(0),
+            Address::zero(),
+            Address::zero(),
+            TokenId(0),
+            1u32.into(),
+            1u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        verify_tx_info_message_signature(
+            &ZkSyncTx::from(transfer),
+            Address::zero(),
+            Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20),
+            EthAccountType::No2FA(None),
+            None,
+            None,
+            channel,
+            breaker,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn overloaded_sign_verifier() {
+        let channel = slow_sign_verifier(StdDuration::from_millis(200));
+        let breaker = SignVerifierBreaker::new(BreakerThresholds {
+            max_queue_depth: 2,
+            max_latency: StdDuration::from_secs(60),
+            cooldown: StdDuration::from_millis(100),
+        });
+
+        let first = verify_transfer(channel.clone(), &breaker);
+        let second = verify_transfer(channel.clone(), &breaker);
+        let rejected = async {
+            // Let the first requests be enqueued.
+            tokio::time::sleep(StdDuration::from_millis(50)).await;
+            let started_at = Instant::now();
+            let result = verify_transfer(channel.clone(), &breaker).await;
+            (result, started_at.elapsed())
+        };
+        let (first, second, (rejected, elapsed)) = tokio::join!(first, second, rejected);
+
+        // Already enqueued requests are processed, while the new one is rejected without waiting.
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        assert!(matches!(
+            rejected,
+            Err(SubmitError::ServerOverloaded {
+                retry_after_secs: 1
+            })
+        ));
+        assert!(elapsed < StdDuration::from_millis(100));
+
+        // The queue is drained and the cooldown has passed, so the requests are accepted again.
+        assert!(breaker.status().queue_depth == 0);
+        assert!(verify_transfer(channel, &breaker).await.is_ok());
+    }
+
+    /// CREATE2 `ChangePubKey` for the account deployed by the factory `0x11..11`
+    /// with the salt argument `0x22..22` and the code hash `0x33..33`.
+    fn create2_change_pubkey() -> ChangePubKey {
+        let mut change_pub_key = ChangePubKey::new(
+            AccountId(1),
+            "32ab0551fee069c7d5c361f5a9965e2aa24bb0cd".parse().unwrap(),
+            PubKeyHash { data: [0x44; 20] },
+            TokenId(0),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+            None,
+            None,
+        );
+        change_pub_key.eth_auth_data =
+            Some(ChangePubKeyEthAuthData::CREATE2(ChangePubKeyCREATE2Data {
+                creator_address: Address::repeat_byte(0x11),
+                salt_arg: H256::repeat_byte(0x22),
+                code_hash: H256::repeat_byte(0x33),
+            }));
+        change_pub_key
+    }
+
+    #[test]
+    fn create2_auth_data() {
+        let factory = Address::repeat_byte(0x11);
+        let any_factory = HashSet::new();
+        let allowed_factory = HashSet::from_iter(vec![factory]);
+        let other_factory = HashSet::from_iter(vec![Address::repeat_byte(0x12)]);
+
+        let tx = ZkSyncTx::from(create2_change_pubkey());
+        check_create2_auth_data(&tx, &any_factory).unwrap();
+        check_create2_auth_data(&tx, &allowed_factory).unwrap();
+        assert!(matches!(
+            check_create2_auth_data(&tx, &other_factory),
+            Err(SubmitError::Create2FactoryNotAllowed(address)) if address == factory
+        ));
+
+        // The salt depends on the public key hash, so the data is valid only for the key it was created for.
+        let mut change_pub_key = create2_change_pubkey();
+        change_pub_key.new_pk_hash = PubKeyHash { data: [0x45; 20] };
+        let error = check_create2_auth_data(&ZkSyncTx::from(change_pub_key), &allowed_factory);
+        assert!(matches!(
+            error,
+            Err(SubmitError::Create2AddressMismatch(Create2AddressMismatch { factory: address, .. }))
+                if address == factory
+        ));
+
+        // The account can't claim the address deployed with another code.
+        let mut change_pub_key = create2_change_pubkey();
+        if let Some(ChangePubKeyEthAuthData::CREATE2(create2_data)) =
+            &mut change_pub_key.eth_auth_data
+        {
+            create2_data.code_hash = H256::repeat_byte(0x34);
+        }
+        let error = check_create2_auth_data(&ZkSyncTx::from(change_pub_key), &any_factory);
+        assert!(matches!(error, Err(SubmitError::Create2AddressMismatch(_))));
+
+        // Other auth data types are not affected by the allow-list.
+        let mut change_pub_key = create2_change_pubkey();
+        change_pub_key.eth_auth_data = Some(ChangePubKeyEthAuthData::Onchain);
+        check_create2_auth_data(&ZkSyncTx::from(change_pub_key), &other_factory).unwrap();
+    }
+
+    #[test]
+    fn reserved_recipients() {
+        let transfer_to = |to: Address| {
+            ZkSyncTx::from(Transfer::new(
+                AccountId(1),
+                Address::repeat_byte(0x01),
+                to,
+                TokenId(0),
+                1u32.into(),
+                1u32.into(),
+                Nonce(0),
+                Default::default(),
+                None,
+            ))
+        };
+        check_recipient(&transfer_to(Address::repeat_byte(0x02))).unwrap();
+        for recipient in [Address::zero(), *NFT_STORAGE_ACCOUNT_ADDRESS].iter() {
+            assert!(matches!(
+                check_recipient(&transfer_to(*recipient)),
+                Err(SubmitError::ReservedRecipient(address)) if address == *recipient
+            ));
+        }
+
+        let forced_exit = ForcedExit::new(
+            AccountId(1),
+            *NFT_STORAGE_ACCOUNT_ADDRESS,
+            TokenId(0),
+            1u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        assert!(matches!(
+            check_recipient(&ZkSyncTx::from(forced_exit)),
+            Err(SubmitError::ReservedRecipient(_))
+        ));
+        // Transactions without a recipient on L2 are not affected.
+        check_recipient(&ZkSyncTx::from(create2_change_pubkey())).unwrap();
+    }
+
+    #[test]
+    fn unpackable_amounts() {
+        let transfer = |amount: u64, fee: u64| {
+            ZkSyncTx::from(Transfer::new(
+                AccountId(1),
+                Address::repeat_byte(0x01),
+                Address::repeat_byte(0x02),
+                TokenId(0),
+                amount.into(),
+                fee.into(),
+                Nonce(0),
+                Default::default(),
+                None,
+            ))
+        };
+        check_packable_amounts(&transfer(1_000_000, 2047)).unwrap();
+
+        let max_mantissa = (1u64 << AMOUNT_MANTISSA_BIT_WIDTH) - 1;
+        let error = check_packable_amounts(&transfer(max_mantissa + 1, 2047));
+        assert!(matches!(
+            error,
+            Err(SubmitError::AmountNotPackable { field: "amount", nearest, .. })
+                if nearest == BigUint::from(max_mantissa)
+        ));
+
+        let error = check_packable_amounts(&transfer(1_000_000, 2049));
+        assert!(matches!(
+            error,
+            Err(SubmitError::AmountNotPackable { field: "fee", nearest, .. })
+                if nearest == BigUint::from(2050u32)
+        ));
+
+        // Withdrawal amounts are not packed.
+        let withdraw = Withdraw::new(
+            AccountId(1),
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            TokenId(0),
+            (max_mantissa + 1).into(),
+            2047u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        check_packable_amounts(&ZkSyncTx::from(withdraw)).unwrap();
+    }
+
+    fn transfer_to(to: Address, amount: u64) -> ZkSyncTx {
+        ZkSyncTx::from(Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(0x01),
+            to,
+            TokenId(0),
+            amount.into(),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ))
+    }
+
+    fn withdraw(amount: u64) -> ZkSyncTx {
+        ZkSyncTx::from(Withdraw::new(
+            AccountId(1),
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x01),
+            TokenId(0),
+            amount.into(),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ))
+    }
+
+    fn assert_below_minimum(result: Result<(), SubmitError>, expected_minimum: u64) {
+        assert!(matches!(
+            result,
+            Err(SubmitError::AmountBelowMinimum { token: TokenId(0), minimum, .. })
+                if minimum == BigUint::from(expected_minimum)
+        ));
+    }
+
+    #[test]
+    fn min_amounts() {
+        let other = Address::repeat_byte(0x02);
+        // Packable minimum.
+        let minimum = BigUint::from(1_000_000u32);
+        check_min_amount(&transfer_to(other, 1_000_000), &minimum).unwrap();
+        assert_below_minimum(
+            check_min_amount(&transfer_to(other, 999_999), &minimum),
+            1_000_000,
+        );
+        check_min_amount(&withdraw(1_000_000), &minimum).unwrap();
+        assert_below_minimum(check_min_amount(&withdraw(999_999), &minimum), 1_000_000);
+
+        // The minimum that can't be packed is rounded up for the transfers, but not for the withdrawals.
+        let max_mantissa = (1u64 << AMOUNT_MANTISSA_BIT_WIDTH) - 1;
+        let minimum = BigUint::from(max_mantissa + 2);
+        let packable_minimum = (max_mantissa + 2 + 9) / 10 * 10;
+        check_min_amount(&transfer_to(other, packable_minimum), &minimum).unwrap();
+        assert_below_minimum(
+            check_min_amount(&transfer_to(other, max_mantissa), &minimum),
+            packable_minimum,
+        );
+        check_min_amount(&withdraw(max_mantissa + 2), &minimum).unwrap();
+        assert_below_minimum(
+            check_min_amount(&withdraw(max_mantissa + 1), &minimum),
+            max_mantissa + 2,
+        );
+
+        // Transfers to self only pay fees, other transactions are not limited.
+        check_min_amount(&transfer_to(Address::repeat_byte(0x01), 0), &minimum).unwrap();
+        check_min_amount(&ZkSyncTx::from(create2_change_pubkey()), &minimum).unwrap();
+    }
+
+    #[test]
+    fn min_amounts_in_usd() {
+        let other = Address::repeat_byte(0x02);
+        // $0.01 at $3000 per 10^18 wei.
+        let min_amount_usd = Ratio::new(BigUint::from(1u32), BigUint::from(100u32));
+        let price = Ratio::new(BigUint::from(3000u32), BigUint::from(10u64.pow(18)));
+        let minimum = usd_to_token_amount(&min_amount_usd, &price).unwrap();
+        assert_eq!(minimum, BigUint::from(3_333_333_333_334u64));
+        // Rounding up keeps the minimum worth at least the minimum in USD.
+        assert!(Ratio::from(minimum.clone()) * &price >= min_amount_usd);
+        assert!(Ratio::from(&minimum - 1u32) * &price < min_amount_usd);
+
+        check_min_amount(&withdraw(3_333_333_333_334), &minimum).unwrap();
+        assert_below_minimum(
+            check_min_amount(&withdraw(3_333_333_333_333), &minimum),
+            3_333_333_333_334,
+        );
+        // The nearest packable transfer amount not below the minimum and the packable one just below it.
+        check_min_amount(&transfer_to(other, 3_333_333_333_400), &minimum).unwrap();
+        assert_below_minimum(
+            check_min_amount(&transfer_to(other, 3_333_333_333_300), &minimum),
+            3_333_333_333_400,
+        );
+
+        // The tokens priced at zero have no minimum.
+        assert_eq!(
+            usd_to_token_amount(&min_amount_usd, &Ratio::from(BigUint::zero())),
+            None
+        );
+    }
+
+    #[test]
+    fn locked_account_transactions() {
+        // Transactions spending the funds of the account are locked, `ChangePubKey` is not.
+        assert_eq!(
+            spending_accounts(&transfer_to(Address::repeat_byte(0x02), 1)),
+            vec![AccountId(1)]
+        );
+        assert_eq!(spending_accounts(&withdraw(1)), vec![AccountId(1)]);
+        assert!(spending_accounts(&ZkSyncTx::from(create2_change_pubkey())).is_empty());
+    }
+
+    #[test]
+    fn unlock_signature_after_key_rotation() {
+        let old_key = ZkSyncAccount::rand();
+        let new_key = ZkSyncAccount::rand();
+        let message = b"Unlock the account";
+        let signed_with =
+            |account: &ZkSyncAccount| TxSignature::sign_musig(&account.private_key, message);
+
+        assert!(is_signed_by_key(
+            &signed_with(&old_key),
+            message,
+            &old_key.pubkey_hash
+        ));
+        // Once the key is rotated with `ChangePubKey`, only the new key may unlock the account.
+        assert!(!is_signed_by_key(
+            &signed_with(&old_key),
+            message,
+            &new_key.pubkey_hash
+        ));
+        assert!(is_signed_by_key(
+            &signed_with(&new_key),
+            message,
+            &new_key.pubkey_hash
+        ));
+        assert!(!is_signed_by_key(
+            &signed_with(&new_key),
+            b"Other message",
+            &new_key.pubkey_hash
+        ));
+        // Accounts without the signing key can be unlocked only with the Ethereum key.
+        assert!(!is_signed_by_key(
+            &signed_with(&new_key),
+            message,
+            &PubKeyHash::default()
+        ));
+    }
+
+    #[test]
+    fn test_scaling_user_fee_by_two() {
+        let provided_fee = BigDecimal::from_str("0.005").unwrap();
+        let provided_fee_scaled_by_two = BigDecimal::from_str("0.01").unwrap();
+
+        let scaled_fee = scale_user_fee_up(provided_fee);
+
+        assert_eq!(provided_fee_scaled_by_two, scaled_fee);
+    }
+
+    #[test]
+    fn test_scaling_user_fee_by_one_cent() {
+        let provided_fee = BigDecimal::from_str("0.015").unwrap();
+        let provided_fee_scaled_by_cent = BigDecimal::from_str("0.025").unwrap();
+
+        let scaled_fee = scale_user_fee_up(provided_fee);
+
+        assert_eq!(provided_fee_scaled_by_cent, scaled_fee);
+    }
+
+    #[test]
+    fn test_scaling_user_fee_by_5_percent() {
+        let provided_fee = BigDecimal::from_str("0.30").unwrap();
+        let provided_fee_scaled_by_five_percent = BigDecimal::from_str("0.315").unwrap();
+
+        let scaled_fee = scale_user_fee_up(provided_fee);
+
+        assert_eq!(provided_fee_scaled_by_five_percent, scaled_fee);
+    }
+}
@@ -0,0 +1,334 @@
+//! Optional screening of the withdrawal destinations by an external compliance service.
+//!
+//! Withdrawals and forced exits worth at least the configured threshold are only accepted
+//! once the service allows their destination address. The verdicts are cached per address,
+//! and the service is given a bounded amount of time to respond: if it fails, the withdrawal
+//! is either accepted or rejected depending on the configured policy.
+
+// Built-in uses
+use std::{
+    fmt::{self, Debug, Display},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// External uses
+use async_trait::async_trait;
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+
+// Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_types::Address;
+
+// Local uses
+use super::SubmitError;
+use crate::utils::shared_lru_cache::SharedLruCache;
+
+/// Verdict of the screening service on the destination address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScreeningVerdict {
+    /// Withdrawals to the address are allowed.
+    Allow,
+    /// The address is under review, withdrawals to it are not accepted for now.
+    Hold,
+    /// Withdrawals to the address are not allowed.
+    Deny,
+}
+
+impl Display for ScreeningVerdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match self {
+            Self::Allow => "allowed",
+            Self::Hold => "held for review",
+            Self::Deny => "denied",
+        };
+        f.write_str(verdict)
+    }
+}
+
+/// Service deciding whether the funds can be withdrawn to the address.
+#[async_trait]
+pub trait ScreeningClient: Debug + Send + Sync {
+    async fn screen(&self, address: Address) -> anyhow::Result<ScreeningVerdict>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScreeningRequest {
+    pub address: Address,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScreeningResponse {
+    pub verdict: ScreeningVerdict,
+}
+
+/// Screening service accepting `ScreeningRequest` as the JSON body of the `POST` request
+/// and responding with `ScreeningResponse`.
+#[derive(Debug, Clone)]
+pub struct HttpScreeningClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HttpScreeningClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[async_trait]
+impl ScreeningClient for HttpScreeningClient {
+    async fn screen(&self, address: Address) -> anyhow::Result<ScreeningVerdict> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&ScreeningRequest { address })
+            .send()
+            .await?
+            .error_for_status()?;
+        let response: ScreeningResponse = response.json().await?;
+        Ok(response.verdict)
+    }
+}
+
+/// Maximum number of addresses the verdicts are cached for.
+const VERDICTS_CACHE_SIZE: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct ScreeningPolicy {
+    /// Withdrawals worth less than this amount of USD are not screened.
+    pub threshold_usd: BigDecimal,
+    /// Maximum time to wait for the verdict.
+    pub timeout: Duration,
+    /// Whether the withdrawals are rejected if the service fails to provide the verdict.
+    pub fail_closed: bool,
+    /// Verdicts are cached per address for this amount of time.
+    pub cache_ttl: Duration,
+}
+
+impl ScreeningPolicy {
+    pub fn from_config(config: &CommonApiConfig) -> Self {
+        Self {
+            threshold_usd: BigDecimal::from(config.screening_threshold_usd),
+            timeout: config.screening_timeout(),
+            fail_closed: config.screening_fail_closed,
+            cache_ttl: config.screening_cache_ttl(),
+        }
+    }
+}
+
+/// Screening of the withdrawal destinations performed before the withdrawal is accepted.
+#[derive(Debug, Clone)]
+pub struct WithdrawalScreening {
+    client: Arc<dyn ScreeningClient>,
+    policy: ScreeningPolicy,
+    cache: SharedLruCache<Address, (Instant, ScreeningVerdict)>,
+}
+
+impl WithdrawalScreening {
+    /// Creates the screening by the HTTP service, returns `None` if the screening is disabled.
+    pub fn from_config(config: &CommonApiConfig) -> Option<Self> {
+        let client = HttpScreeningClient::new(config.screening_url()?.to_owned());
+        Some(Self::new(
+            Arc::new(client),
+            ScreeningPolicy::from_config(config),
+        ))
+    }
+
+    pub fn new(client: Arc<dyn ScreeningClient>, policy: ScreeningPolicy) -> Self {
+        Self {
+            client,
+            policy,
+            cache: SharedLruCache::new(VERDICTS_CACHE_SIZE),
+        }
+    }
+
+    /// Checks that the funds can be withdrawn to the address.
+    ///
+    /// Withdrawals worth less than the threshold are not screened, withdrawals
+    /// of unknown value (e.g. of the tokens without a price) are always screened.
+    pub async fn check(
+        &self,
+        destination: Address,
+        amount_usd: Option<BigDecimal>,
+    ) -> Result<(), SubmitError> {
+        if let Some(amount_usd) = amount_usd {
+            if amount_usd < self.policy.threshold_usd {
+                return Ok(());
+            }
+        }
+
+        match self.verdict(destination).await {
+            Some(ScreeningVerdict::Allow) => Ok(()),
+            Some(verdict) => Err(SubmitError::ComplianceRejected(verdict)),
+            None if self.policy.fail_closed => {
+                Err(SubmitError::ComplianceRejected(ScreeningVerdict::Hold))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the cached or the newly requested verdict, `None` if the service has failed.
+    async fn verdict(&self, address: Address) -> Option<ScreeningVerdict> {
+        if let Some((screened_at, verdict)) = self.cache.get(&address) {
+            if screened_at.elapsed() < self.policy.cache_ttl {
+                return Some(verdict);
+            }
+        }
+
+        let start = Instant::now();
+        let result = tokio::time::timeout(self.policy.timeout, self.client.screen(address)).await;
+        metrics::histogram!("api.screening.request", start.elapsed());
+        match result {
+            Ok(Ok(verdict)) => {
+                metrics::increment_counter!("api.screening.verdict", "verdict" => verdict.to_string());
+                self.cache.insert(address, (Instant::now(), verdict));
+                Some(verdict)
+            }
+            Ok(Err(err)) => {
+                metrics::increment_counter!("api.screening.failure", "reason" => "error");
+                vlog::warn!("Screening of the address {:?} failed: {}", address, err);
+                None
+            }
+            Err(_) => {
+                metrics::increment_counter!("api.screening.failure", "reason" => "timeout");
+                vlog::warn!(
+                    "Screening of the address {:?} did not complete within {:?}",
+                    address,
+                    self.policy.timeout
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // The mock service decides on the address by its first byte.
+    const ALLOWED: u8 = 1;
+    const HELD: u8 = 2;
+    const DENIED: u8 = 3;
+    const SLOW: u8 = 4;
+    const BROKEN: u8 = 5;
+
+    /// Mock of the screening service, counts the requests it has received.
+    async fn screen(
+        requests: web::Data<AtomicUsize>,
+        web::Json(request): web::Json<ScreeningRequest>,
+    ) -> HttpResponse {
+        requests.fetch_add(1, Ordering::SeqCst);
+        let verdict = match request.address[0] {
+            ALLOWED => ScreeningVerdict::Allow,
+            HELD => ScreeningVerdict::Hold,
+            DENIED => ScreeningVerdict::Deny,
+            SLOW => {
+                actix_rt::time::sleep(Duration::from_secs(1)).await;
+                ScreeningVerdict::Allow
+            }
+            _ => return HttpResponse::InternalServerError().finish(),
+        };
+        HttpResponse::Ok().json(ScreeningResponse { verdict })
+    }
+
+    fn start_server(requests: Arc<AtomicUsize>) -> actix_test::TestServer {
+        actix_test::start(move || {
+            App::new()
+                .app_data(web::Data::from(requests.clone()))
+                .route("/screen", web::post().to(screen))
+        })
+    }
+
+    fn screening(server: &actix_test::TestServer, fail_closed: bool) -> WithdrawalScreening {
+        let client = HttpScreeningClient::new(server.url("/screen"));
+        let policy = ScreeningPolicy {
+            threshold_usd: BigDecimal::from(1000),
+            timeout: Duration::from_millis(100),
+            fail_closed,
+            cache_ttl: Duration::from_secs(60),
+        };
+        WithdrawalScreening::new(Arc::new(client), policy)
+    }
+
+    async fn check(
+        screening: &WithdrawalScreening,
+        kind: u8,
+        amount_usd: Option<u64>,
+    ) -> Result<(), SubmitError> {
+        screening
+            .check(Address::repeat_byte(kind), amount_usd.map(BigDecimal::from))
+            .await
+    }
+
+    fn assert_rejected(result: Result<(), SubmitError>, expected: ScreeningVerdict) {
+        match result {
+            Err(SubmitError::ComplianceRejected(verdict)) => assert_eq!(verdict, expected),
+            other => panic!("Unexpected screening result: {:?}", other),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn screening_verdicts() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = start_server(requests.clone());
+        let screening = screening(&server, false);
+
+        check(&screening, ALLOWED, Some(5000)).await.unwrap();
+        let result = check(&screening, HELD, Some(5000)).await;
+        assert_rejected(result, ScreeningVerdict::Hold);
+        let result = check(&screening, DENIED, Some(5000)).await;
+        assert_rejected(result, ScreeningVerdict::Deny);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Verdicts are cached per address.
+        check(&screening, ALLOWED, Some(5000)).await.unwrap();
+        let result = check(&screening, DENIED, Some(5000)).await;
+        assert_rejected(result, ScreeningVerdict::Deny);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Withdrawals below the threshold are not screened, the ones of unknown value are.
+        check(&screening, DENIED, Some(999)).await.unwrap();
+        let result = check(&screening, HELD, None).await;
+        assert_rejected(result, ScreeningVerdict::Hold);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        check(&screening, BROKEN, None).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+
+        server.stop().await;
+    }
+
+    #[actix_rt::test]
+    async fn screening_failure_policy() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = start_server(requests.clone());
+
+        // Fail-open: withdrawals are accepted if the service fails or is too slow.
+        let screening_open = screening(&server, false);
+        check(&screening_open, BROKEN, Some(5000)).await.unwrap();
+        check(&screening_open, SLOW, Some(5000)).await.unwrap();
+
+        // Fail-closed: such withdrawals are held.
+        let screening_closed = screening(&server, true);
+        for kind in vec![BROKEN, SLOW] {
+            let result = check(&screening_closed, kind, Some(5000)).await;
+            assert_rejected(result, ScreeningVerdict::Hold);
+        }
+        check(&screening_closed, ALLOWED, Some(5000)).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 5);
+
+        // Failures are not cached.
+        check(&screening_open, BROKEN, Some(5000)).await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 6);
+
+        server.stop().await;
+    }
+}
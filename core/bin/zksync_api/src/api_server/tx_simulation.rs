@@ -0,0 +1,278 @@
+//! Execution of the transactions against the committed state without persisting anything.
+//!
+//! Before whitelisting unusual transactions, operators check whether the state keeper would
+//! accept them. The committed state of the accounts affected by the transaction is loaded and
+//! the transaction is executed by the same `ZkSyncState` logic the state keeper uses, so the
+//! result is either the updates the transaction would make or the exact reason of its failure.
+//! Neither the mempool nor the stored state is touched.
+
+// Built-in uses
+use std::collections::HashMap;
+
+// External uses
+use chrono::Utc;
+
+// Workspace uses
+use zksync_api_types::TxSimulation;
+use zksync_crypto::params::NFT_STORAGE_ACCOUNT_ID;
+use zksync_state::state::{OpSuccess, ZkSyncState};
+use zksync_storage::{QueryResult, StorageProcessor};
+use zksync_types::{AccountId, Address, ZkSyncTx};
+
+/// Returns the identifiers and the addresses of the accounts the transaction may affect.
+fn affected_accounts(tx: &ZkSyncTx) -> (Vec<AccountId>, Vec<Address>) {
+    let mut account_ids = Vec::new();
+    let mut addresses = vec![tx.account()];
+    if let Ok(account_id) = tx.account_id() {
+        account_ids.push(account_id);
+    }
+    match tx {
+        ZkSyncTx::Transfer(tx) => addresses.push(tx.to),
+        ZkSyncTx::ForcedExit(tx) => {
+            account_ids.push(tx.initiator_account_id);
+            addresses.push(tx.target);
+        }
+        ZkSyncTx::MintNFT(tx) => {
+            account_ids.extend([tx.creator_id, NFT_STORAGE_ACCOUNT_ID]);
+            addresses.push(tx.recipient);
+        }
+        ZkSyncTx::Swap(tx) => {
+            account_ids.extend([
+                tx.submitter_id,
+                tx.orders.0.account_id,
+                tx.orders.1.account_id,
+            ]);
+            addresses.extend([
+                tx.submitter_address,
+                tx.orders.0.recipient_address,
+                tx.orders.1.recipient_address,
+            ]);
+        }
+        ZkSyncTx::Withdraw(_)
+        | ZkSyncTx::WithdrawNFT(_)
+        | ZkSyncTx::ChangePubKey(_)
+        | ZkSyncTx::Close(_) => {}
+    }
+    (account_ids, addresses)
+}
+
+/// Executes the transaction against the state of the affected accounts at the last committed
+/// block. The transaction is checked the same way as on submission first, e.g. the amounts
+/// must be packable.
+pub async fn simulate_tx(
+    storage: &mut StorageProcessor<'_>,
+    mut tx: ZkSyncTx,
+) -> QueryResult<TxSimulation> {
+    let mut transaction = storage.start_snapshot_read().await?;
+    let block_number = transaction
+        .chain()
+        .block_schema()
+        .get_last_saved_block()
+        .await?;
+    let failure = |fail_reason: String| TxSimulation {
+        block_number,
+        success: false,
+        fail_reason: Some(fail_reason),
+        updates: Vec::new(),
+        chunks: None,
+    };
+    if let Err(err) = tx.check_correctness() {
+        transaction.commit().await?;
+        return Ok(failure(err.to_string()));
+    }
+
+    let (mut account_ids, addresses) = affected_accounts(&tx);
+    let mut nfts = HashMap::new();
+    let mut creator_address = None;
+    if let ZkSyncTx::WithdrawNFT(tx) = &tx {
+        let nft = transaction
+            .chain()
+            .state_schema()
+            .get_mint_nft_update(tx.token)
+            .await?;
+        if let Some(nft) = nft {
+            creator_address = Some(nft.creator_address);
+            nfts.insert(nft.id, nft);
+        }
+    }
+    for address in addresses.into_iter().chain(creator_address) {
+        let account_id = transaction
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await?;
+        account_ids.extend(account_id);
+    }
+    account_ids.sort_unstable();
+    account_ids.dedup();
+
+    let accounts = transaction
+        .chain()
+        .state_schema()
+        .load_accounts_state(block_number, &account_ids)
+        .await?;
+    let next_free_id = transaction
+        .chain()
+        .account_schema()
+        .next_free_account_id()
+        .await?;
+    transaction.commit().await?;
+
+    let mut state = ZkSyncState::from_partial_acc_map(accounts, nfts, next_free_id);
+    let simulation = match state.execute_tx(tx, Utc::now().timestamp() as u64) {
+        Ok(OpSuccess {
+            updates,
+            executed_op,
+            ..
+        }) => TxSimulation {
+            block_number,
+            success: true,
+            fail_reason: None,
+            updates,
+            chunks: Some(executed_op.chunks()),
+        },
+        Err(err) => failure(err.to_string()),
+    };
+    Ok(simulation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::TestServerConfig;
+    use num::BigUint;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{tx::TimeRange, AccountUpdate, Nonce, PubKeyHash, TokenId};
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn simulate_transfers() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        // The changes are rolled back once the transaction is dropped.
+        let mut transaction = storage.start_transaction().await?;
+
+        let block_number = transaction
+            .chain()
+            .block_schema()
+            .get_last_saved_block()
+            .await?;
+        let account_id = transaction
+            .chain()
+            .account_schema()
+            .next_free_account_id()
+            .await?;
+        let sender = ZkSyncAccount::rand();
+        sender.set_account_id(Some(account_id));
+        let balance = BigUint::from(1_000_000u32);
+        let updates = [
+            AccountUpdate::Create {
+                address: sender.address,
+                nonce: Nonce(0),
+            },
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash: PubKeyHash::default(),
+                new_pub_key_hash: sender.pubkey_hash,
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(1),
+            },
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(1),
+                new_nonce: Nonce(1),
+                balance_update: (TokenId(0), BigUint::from(0u32), balance.clone()),
+            },
+        ];
+        let updates: Vec<_> = updates
+            .iter()
+            .map(|update| (account_id, update.clone()))
+            .collect();
+        transaction
+            .chain()
+            .state_schema()
+            .commit_state_update(block_number, &updates, 10_000)
+            .await?;
+        let recipient = ZkSyncAccount::rand().address;
+        let transfer = |amount: u64, fee: u64, nonce: u32| {
+            let (transfer, _) = sender.sign_transfer(
+                TokenId(0),
+                "ETH",
+                BigUint::from(amount),
+                BigUint::from(fee),
+                &recipient,
+                Some(Nonce(nonce)),
+                false,
+                TimeRange::default(),
+            );
+            ZkSyncTx::from(transfer)
+        };
+
+        // The recipient doesn't exist yet, so it gets the next free account id.
+        let simulation = simulate_tx(&mut transaction, transfer(1_000, 10, 1)).await?;
+        assert!(simulation.success, "{:?}", simulation);
+        assert_eq!(simulation.block_number, block_number);
+        assert_eq!(simulation.fail_reason, None);
+        assert!(simulation.chunks.is_some());
+        assert!(simulation.updates.contains(&(
+            AccountId(*account_id + 1),
+            AccountUpdate::Create {
+                address: recipient,
+                nonce: Nonce(0),
+            }
+        )));
+        assert!(simulation.updates.contains(&(
+            account_id,
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(1),
+                new_nonce: Nonce(2),
+                balance_update: (
+                    TokenId(0),
+                    balance.clone(),
+                    &balance - BigUint::from(1_010u32)
+                ),
+            }
+        )));
+
+        // Failures are reported with the reason the state keeper would reject the transaction.
+        for (tx, fail_reason) in [
+            (transfer(1_000, 10, 2), "Nonce mismatch"),
+            (transfer(2_000_000, 10, 1), "Not enough balance"),
+            (transfer(1_000, 2_049, 1), "Specified fee is not packable"),
+        ] {
+            let simulation = simulate_tx(&mut transaction, tx).await?;
+            assert!(!simulation.success);
+            assert_eq!(simulation.fail_reason.as_deref(), Some(fail_reason));
+            assert!(simulation.updates.is_empty());
+            assert_eq!(simulation.chunks, None);
+        }
+
+        // Nothing is persisted by the simulations.
+        let accounts = transaction
+            .chain()
+            .state_schema()
+            .load_accounts_state(block_number, &[account_id])
+            .await?;
+        assert_eq!(accounts[&account_id].nonce, Nonce(1));
+        assert_eq!(accounts[&account_id].get_balance(TokenId(0)), balance);
+        assert_eq!(
+            transaction
+                .chain()
+                .account_schema()
+                .next_free_account_id()
+                .await?,
+            AccountId(*account_id + 1)
+        );
+        assert_eq!(
+            transaction
+                .chain()
+                .block_schema()
+                .get_last_saved_block()
+                .await?,
+            block_number
+        );
+        Ok(())
+    }
+}
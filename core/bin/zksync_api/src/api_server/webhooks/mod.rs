@@ -0,0 +1,364 @@
+//! Delivery of the transaction events to the webhook subscriptions.
+//!
+//! Every subscription has its own cursor in the events journal. The dispatcher claims the due
+//! subscription and posts the transaction events of its account accepted by its preferences one
+//! by one, in the order of the journal, moving the cursor past the delivered and filtered out
+//! events. The failed delivery is retried with the exponential backoff, and the event is skipped
+//! after `webhook_max_attempts` failures, so a broken endpoint doesn't stall the subscription.
+//!
+//! The events are delivered at least once: if the dispatcher fails before moving the cursor,
+//! the event is delivered again, so the subscribers deduplicate them by `X-Webhook-Event-Id`.
+//!
+//! Every attempt is logged with the sent body for `webhook_deliveries_retention_period`, so the
+//! subscriber can inspect the attempts and have the event sent again with the same body.
+
+// Built-in uses
+use std::time::{Duration, Instant};
+
+// External uses
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use reqwest::header::CONTENT_TYPE;
+use tokio::{task::JoinHandle, time};
+
+// Workspace uses
+use zksync_api_types::v02::webhook::{
+    webhook_signature, WebhookPayload, WebhookPreferences, WEBHOOK_EVENT_ID_HEADER,
+    WEBHOOK_REPLAY_HEADER, WEBHOOK_SIGNATURE_HEADER, WEBHOOK_TIMESTAMP_HEADER,
+};
+use zksync_config::configs::api::RestApiConfig;
+use zksync_storage::{
+    event::{records::StoredEvent, EventType},
+    webhooks::records::{NewWebhookDelivery, WebhookSubscription},
+    ConnectionPool,
+};
+use zksync_types::{
+    event::{transaction::TransactionEvent, EventId},
+    AccountId, Address, TokenLike,
+};
+
+// Local uses
+use self::preferences::EventFacts;
+use crate::{
+    api_server::rest::v02::event::api_event_from_stored,
+    fee_ticker::{FeeTicker, TokenPriceRequestType},
+};
+
+pub mod preferences;
+
+/// Maximum number of the events of a single subscription dispatched at once.
+const MAX_EVENTS_PER_CLAIM: u32 = 50;
+/// The subscription is claimed for the longest possible dispatch plus this margin, after which
+/// another dispatcher takes it over.
+const CLAIM_MARGIN: Duration = Duration::from_secs(60);
+
+/// Posts the transaction events to the webhook subscriptions.
+#[derive(Debug)]
+pub struct WebhookDispatcher {
+    pool: ConnectionPool,
+    ticker: FeeTicker,
+    client: reqwest::Client,
+    interval: Duration,
+    delivery_timeout: Duration,
+    retry_delay: Duration,
+    max_attempts: u32,
+    deliveries_retention_period: Duration,
+    deliveries_pruning_interval: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(pool: ConnectionPool, ticker: FeeTicker, config: &RestApiConfig) -> Self {
+        Self {
+            pool,
+            ticker,
+            client: reqwest::Client::new(),
+            interval: config.webhook_dispatch_interval(),
+            delivery_timeout: config.webhook_delivery_timeout(),
+            retry_delay: config.webhook_retry_delay(),
+            max_attempts: config.webhook_max_attempts.max(1),
+            deliveries_retention_period: config.webhook_deliveries_retention_period(),
+            deliveries_pruning_interval: config.webhook_deliveries_pruning_interval(),
+        }
+    }
+
+    pub async fn run(self) {
+        let mut timer = time::interval(self.interval);
+        let mut last_pruned_at: Option<Instant> = None;
+        loop {
+            timer.tick().await;
+
+            if last_pruned_at.map_or(true, |at| at.elapsed() >= self.deliveries_pruning_interval) {
+                self.prune_deliveries().await;
+                last_pruned_at = Some(Instant::now());
+            }
+            if let Err(err) = self.dispatch_due().await {
+                vlog::warn!("Failed to dispatch the webhook events: {}", err);
+            }
+        }
+    }
+
+    /// Removes the delivery attempts older than the retention period. Errors are only logged,
+    /// since pruning doesn't affect the deliveries.
+    async fn prune_deliveries(&self) {
+        let result: anyhow::Result<u64> = async {
+            let timestamp =
+                Utc::now() - chrono::Duration::from_std(self.deliveries_retention_period)?;
+            Ok(self
+                .pool
+                .access_storage()
+                .await?
+                .webhooks_schema()
+                .remove_deliveries_older_than(timestamp)
+                .await?)
+        }
+        .await;
+        match result {
+            Ok(removed) => vlog::info!("Removed {} outdated webhook deliveries", removed),
+            Err(err) => vlog::warn!("Failed to remove outdated webhook deliveries: {}", err),
+        }
+    }
+
+    /// Dispatches the events of all the due subscriptions.
+    async fn dispatch_due(&self) -> anyhow::Result<()> {
+        let claim_duration = self.delivery_timeout * MAX_EVENTS_PER_CLAIM + CLAIM_MARGIN;
+        loop {
+            let claimed_until = Utc::now() + chrono::Duration::from_std(claim_duration)?;
+            let subscription = self
+                .pool
+                .access_storage()
+                .await?
+                .webhooks_schema()
+                .claim_due_subscription(claimed_until)
+                .await?;
+            match subscription {
+                // The subscription failed to be dispatched stays claimed, so it's retried later.
+                Some(subscription) => self.dispatch(subscription).await?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    /// Dispatches the events of the claimed subscription and releases it.
+    pub(crate) async fn dispatch(&self, subscription: WebhookSubscription) -> anyhow::Result<()> {
+        let preferences: WebhookPreferences =
+            serde_json::from_value(subscription.preferences.clone())?;
+        let (account_id, events) = {
+            let mut storage = self.pool.access_storage().await?;
+            let account_id = storage
+                .chain()
+                .account_schema()
+                .account_id_by_address(subscription.address)
+                .await?;
+            let events = storage
+                .event_schema()
+                .load_events(
+                    subscription.last_event_id,
+                    MAX_EVENTS_PER_CLAIM,
+                    Some(EventType::Transaction),
+                )
+                .await?;
+            (account_id, events)
+        };
+
+        let mut last_event_id = subscription.last_event_id;
+        let mut failed_attempts = subscription.failed_attempts;
+        let mut next_attempt_at = Utc::now() + chrono::Duration::from_std(self.interval)?;
+        for event in events {
+            let event_id = EventId(event.id as u64);
+            if self
+                .accepts(&preferences, &event, account_id, subscription.address)
+                .await
+            {
+                if let Some(err) = self.deliver(&subscription, event).await? {
+                    failed_attempts += 1;
+                    if failed_attempts < self.max_attempts {
+                        vlog::debug!(
+                            "Failed to deliver event {} to webhook subscription {}: {}",
+                            event_id,
+                            subscription.id,
+                            err
+                        );
+                        let delay = self
+                            .retry_delay
+                            .saturating_mul(2u32.saturating_pow(failed_attempts - 1));
+                        next_attempt_at = Utc::now() + chrono::Duration::from_std(delay)?;
+                        break;
+                    }
+                    metrics::increment_counter!("api.webhooks.skipped_events");
+                    vlog::warn!(
+                        "Event {} is skipped after {} failed deliveries to webhook subscription {}: {}",
+                        event_id,
+                        failed_attempts,
+                        subscription.id,
+                        err
+                    );
+                }
+            }
+            last_event_id = event_id;
+            failed_attempts = 0;
+        }
+
+        self.pool
+            .access_storage()
+            .await?
+            .webhooks_schema()
+            .release_subscription(
+                subscription.id,
+                last_event_id,
+                failed_attempts,
+                next_attempt_at,
+            )
+            .await
+    }
+
+    /// Checks whether the event involves the account and is accepted by the preferences.
+    async fn accepts(
+        &self,
+        preferences: &WebhookPreferences,
+        event: &StoredEvent,
+        account_id: Option<AccountId>,
+        address: Address,
+    ) -> bool {
+        let tx_event: TransactionEvent = match serde_json::from_value(event.event_data.clone()) {
+            Ok(tx_event) => tx_event,
+            Err(_) => return false,
+        };
+        let facts = match EventFacts::new(&tx_event, account_id, address) {
+            Some(facts) => facts,
+            None => return false,
+        };
+        let usd_price = if preferences::needs_usd_price(preferences) {
+            self.token_price(facts.token.into()).await
+        } else {
+            None
+        };
+        preferences::accepts(preferences, &facts, usd_price.as_ref())
+    }
+
+    /// Returns the USD price of the smallest unit of the token, if it's known.
+    async fn token_price(&self, token: TokenLike) -> Option<BigDecimal> {
+        self.ticker
+            .get_token_price(token, TokenPriceRequestType::USDForOneWei)
+            .await
+            .ok()
+    }
+
+    /// Posts the event to the subscriber and logs the attempt. Returns the reason of the failure,
+    /// if the delivery failed.
+    async fn deliver(
+        &self,
+        subscription: &WebhookSubscription,
+        event: StoredEvent,
+    ) -> anyhow::Result<Option<String>> {
+        let event_id = EventId(event.id as u64);
+        let payload = WebhookPayload {
+            subscription_id: subscription.id,
+            event: api_event_from_stored(event),
+        };
+        let body = serde_json::to_vec(&payload)?;
+        let attempt = post_event(
+            &self.client,
+            subscription,
+            event_id,
+            body,
+            false,
+            self.delivery_timeout,
+        )
+        .await;
+        self.pool
+            .access_storage()
+            .await?
+            .webhooks_schema()
+            .store_delivery(attempt.as_new_delivery())
+            .await?;
+
+        if attempt.error.is_none() {
+            metrics::increment_counter!("api.webhooks.delivered_events");
+        }
+        Ok(attempt.error)
+    }
+}
+
+/// Attempt to post the event to the subscriber.
+#[derive(Debug)]
+pub(crate) struct DeliveryAttempt {
+    subscription_id: i64,
+    event_id: EventId,
+    body: Vec<u8>,
+    body_hash: [u8; 32],
+    response_code: Option<u16>,
+    latency: Duration,
+    replay: bool,
+    /// Reason of the failure, `None` if the subscriber responded with a success status.
+    pub error: Option<String>,
+}
+
+impl DeliveryAttempt {
+    /// Returns the attempt to be logged.
+    pub fn as_new_delivery(&self) -> NewWebhookDelivery<'_> {
+        NewWebhookDelivery {
+            subscription_id: self.subscription_id,
+            event_id: self.event_id,
+            payload: &self.body,
+            body_hash: &self.body_hash,
+            response_code: self.response_code,
+            latency: self.latency,
+            replay: self.replay,
+        }
+    }
+}
+
+/// Posts the body of the event to the subscription URL, signed with the subscription secret.
+/// `replay` tells whether the event is sent again on the subscriber's request.
+pub(crate) async fn post_event(
+    client: &reqwest::Client,
+    subscription: &WebhookSubscription,
+    event_id: EventId,
+    body: Vec<u8>,
+    replay: bool,
+    timeout: Duration,
+) -> DeliveryAttempt {
+    let timestamp = Utc::now().timestamp();
+    let signature = webhook_signature(&subscription.secret, timestamp, replay, &body);
+    let start = Instant::now();
+    let response = client
+        .post(&subscription.url)
+        .timeout(timeout)
+        .header(CONTENT_TYPE, "application/json")
+        .header(WEBHOOK_SIGNATURE_HEADER, signature)
+        .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string())
+        .header(WEBHOOK_EVENT_ID_HEADER, event_id.to_string())
+        .header(WEBHOOK_REPLAY_HEADER, replay.to_string())
+        .body(body.clone())
+        .send()
+        .await;
+    let latency = start.elapsed();
+
+    let response_code = response
+        .as_ref()
+        .ok()
+        .map(|response| response.status().as_u16());
+    let error = response
+        .and_then(|response| response.error_for_status())
+        .err()
+        .map(|err| err.to_string());
+    DeliveryAttempt {
+        subscription_id: subscription.id,
+        event_id,
+        body_hash: tiny_keccak::keccak256(&body),
+        body,
+        response_code,
+        latency,
+        replay,
+        error,
+    }
+}
+
+#[must_use]
+pub fn run_webhook_dispatcher(
+    pool: ConnectionPool,
+    ticker: FeeTicker,
+    config: &RestApiConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(WebhookDispatcher::new(pool, ticker, config).run())
+}
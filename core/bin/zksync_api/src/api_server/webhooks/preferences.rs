@@ -0,0 +1,451 @@
+//! Preferences of the webhook subscriptions.
+//!
+//! Preferences are validated once they are set, so the subscriber is told about the filters
+//! that can never match instead of silently receiving nothing. The dispatcher evaluates them
+//! against the facts extracted from every transaction event of the subscribed account.
+
+// External uses
+use bigdecimal::BigDecimal;
+use num::BigUint;
+use serde_json::Value;
+use thiserror::Error;
+
+// Workspace uses
+use zksync_api_types::v02::webhook::{MinAmount, TxDirection, WebhookPreferences};
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_types::{
+    event::transaction::{TransactionEvent, TransactionStatus, TransactionType},
+    AccountId, Address, TokenId,
+};
+use zksync_utils::biguint_to_big_decimal;
+
+/// Types of the transactions moving an amount of the token, the other ones never match
+/// the `minAmount` filter.
+const TYPES_WITH_AMOUNT: [TransactionType; 3] = [
+    TransactionType::Transfer,
+    TransactionType::Withdraw,
+    TransactionType::Deposit,
+];
+
+/// Types of the transactions the account can receive the funds or the NFT with.
+const RECEIVABLE_TYPES: [TransactionType; 3] = [
+    TransactionType::Transfer,
+    TransactionType::Deposit,
+    TransactionType::MintNFT,
+];
+
+const NFT_TYPES: [TransactionType; 2] = [TransactionType::MintNFT, TransactionType::WithdrawNFT];
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidPreferences {
+    #[error("`{0}` filter should not be empty, otherwise no events are delivered")]
+    EmptyFilter(&'static str),
+    #[error("`minAmount` in token units requires exactly one token in the `tokens` filter")]
+    TokenAmountWithoutToken,
+    #[error("`minAmount` in USD doesn't apply to the NFTs")]
+    UsdAmountForNft,
+    #[error("`minAmount` should not be negative")]
+    NegativeUsdAmount,
+    #[error("None of the selected transaction types has an amount to compare with `minAmount`")]
+    NoTypesWithAmount,
+    #[error("None of the selected transaction types can be received by the account")]
+    NoReceivableTypes,
+}
+
+fn is_nft(token: TokenId) -> bool {
+    token.0 >= MIN_NFT_TOKEN_ID
+}
+
+/// Rejects the preferences that can never match an event.
+pub fn validate(preferences: &WebhookPreferences) -> Result<(), InvalidPreferences> {
+    let types = preferences.tx_types.as_deref();
+    if types.map_or(false, <[_]>::is_empty) {
+        return Err(InvalidPreferences::EmptyFilter("txTypes"));
+    }
+    if preferences.statuses.as_ref().map_or(false, Vec::is_empty) {
+        return Err(InvalidPreferences::EmptyFilter("statuses"));
+    }
+    if preferences.tokens.as_ref().map_or(false, Vec::is_empty) {
+        return Err(InvalidPreferences::EmptyFilter("tokens"));
+    }
+    let has_any_type = |allowed: &[TransactionType]| {
+        types.map_or(true, |types| types.iter().any(|ty| allowed.contains(ty)))
+    };
+
+    if preferences.direction == Some(TxDirection::Received) && !has_any_type(&RECEIVABLE_TYPES) {
+        return Err(InvalidPreferences::NoReceivableTypes);
+    }
+    match &preferences.min_amount {
+        None => {}
+        Some(MinAmount::Token(_)) => {
+            if preferences
+                .tokens
+                .as_ref()
+                .map_or(true, |tokens| tokens.len() != 1)
+            {
+                return Err(InvalidPreferences::TokenAmountWithoutToken);
+            }
+        }
+        Some(MinAmount::Usd(amount)) => {
+            let nft_tokens = preferences
+                .tokens
+                .as_ref()
+                .map_or(false, |tokens| tokens.iter().copied().any(is_nft));
+            let nft_types_only =
+                types.map_or(false, |types| types.iter().all(|ty| NFT_TYPES.contains(ty)));
+            if nft_tokens || nft_types_only {
+                return Err(InvalidPreferences::UsdAmountForNft);
+            }
+            if *amount < BigDecimal::from(0) {
+                return Err(InvalidPreferences::NegativeUsdAmount);
+            }
+        }
+    }
+    if preferences.min_amount.is_some() && !has_any_type(&TYPES_WITH_AMOUNT) {
+        return Err(InvalidPreferences::NoTypesWithAmount);
+    }
+    Ok(())
+}
+
+/// Whether the USD price of the event token is required to evaluate the preferences.
+pub fn needs_usd_price(preferences: &WebhookPreferences) -> bool {
+    matches!(preferences.min_amount, Some(MinAmount::Usd(_)))
+}
+
+/// Facts about the transaction event the preferences are evaluated against.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventFacts {
+    pub tx_type: TransactionType,
+    pub status: TransactionStatus,
+    pub direction: TxDirection,
+    pub token: TokenId,
+    /// Amount in the smallest units of the token, `None` for the transactions without an amount.
+    pub amount: Option<BigUint>,
+}
+
+fn parse_address(value: &Value) -> Option<Address> {
+    serde_json::from_value(value.clone()).ok()
+}
+
+fn parse_amount(value: &Value) -> Option<BigUint> {
+    value.as_str()?.parse().ok()
+}
+
+impl EventFacts {
+    /// Extracts the facts about the event relative to the account, which may have no ID yet.
+    /// Returns `None` if the account neither initiated the transaction nor receives anything with it.
+    pub fn new(
+        event: &TransactionEvent,
+        account_id: Option<AccountId>,
+        address: Address,
+    ) -> Option<Self> {
+        let tx = &event.tx;
+        let tx_type: TransactionType = serde_json::from_value(tx["type"].clone()).ok()?;
+        // The deposit event belongs to the account receiving the funds.
+        let initiated = tx_type != TransactionType::Deposit && account_id == Some(event.account_id);
+        let recipient = match tx_type {
+            TransactionType::Transfer => parse_address(&tx["to"]),
+            TransactionType::Deposit => parse_address(&tx["priority_op"]["to"]),
+            TransactionType::MintNFT => parse_address(&tx["recipient"]),
+            _ => None,
+        };
+        // The transfers to oneself are reported as sent.
+        let direction = if initiated {
+            TxDirection::Sent
+        } else if recipient == Some(address) {
+            TxDirection::Received
+        } else {
+            return None;
+        };
+        let amount = match tx_type {
+            TransactionType::Transfer | TransactionType::Withdraw => parse_amount(&tx["amount"]),
+            TransactionType::Deposit => parse_amount(&tx["priority_op"]["amount"]),
+            _ => None,
+        };
+
+        Some(Self {
+            tx_type,
+            status: event.status,
+            direction,
+            token: event.token_id,
+            amount,
+        })
+    }
+}
+
+/// Checks whether the preferences accept the event. `usd_price` is the price of the smallest
+/// unit of the event token, the events of the tokens without a known price don't match the USD
+/// amount filter.
+pub fn accepts(
+    preferences: &WebhookPreferences,
+    facts: &EventFacts,
+    usd_price: Option<&BigDecimal>,
+) -> bool {
+    if let Some(types) = &preferences.tx_types {
+        if !types.contains(&facts.tx_type) {
+            return false;
+        }
+    }
+    if let Some(statuses) = &preferences.statuses {
+        if !statuses.contains(&facts.status) {
+            return false;
+        }
+    }
+    if let Some(tokens) = &preferences.tokens {
+        if !tokens.contains(&facts.token) {
+            return false;
+        }
+    }
+    if let Some(direction) = preferences.direction {
+        if facts.direction != direction {
+            return false;
+        }
+    }
+    match (&preferences.min_amount, &facts.amount) {
+        (None, _) => true,
+        (Some(_), None) => false,
+        (Some(MinAmount::Token(min_amount)), Some(amount)) => amount >= min_amount,
+        (Some(MinAmount::Usd(min_amount)), Some(amount)) => match usd_price {
+            Some(price) if !is_nft(facts.token) => {
+                biguint_to_big_decimal(amount.clone()) * price >= *min_amount
+            }
+            _ => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ACCOUNT_ID: AccountId = AccountId(7);
+
+    fn address() -> Address {
+        Address::repeat_byte(0x07)
+    }
+
+    fn other_address() -> Address {
+        Address::repeat_byte(0x08)
+    }
+
+    fn tx_event(account_id: AccountId, status: &str, tx: Value) -> TransactionEvent {
+        serde_json::from_value(json!({
+            "tx_hash": "sync-tx:00",
+            "account_id": account_id,
+            "token_id": 1,
+            "block_number": 1,
+            "tx": tx,
+            "status": status,
+            "created_at": "2023-11-15T12:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    fn transfer(from_account: AccountId, to: Address, amount: u64) -> TransactionEvent {
+        tx_event(
+            from_account,
+            "committed",
+            json!({
+                "type": "Transfer",
+                "accountId": from_account,
+                "from": other_address(),
+                "to": to,
+                "token": 1,
+                "amount": amount.to_string(),
+            }),
+        )
+    }
+
+    fn facts(event: &TransactionEvent) -> Option<EventFacts> {
+        EventFacts::new(event, Some(ACCOUNT_ID), address())
+    }
+
+    #[test]
+    fn event_facts() {
+        let sent = facts(&transfer(ACCOUNT_ID, other_address(), 100)).unwrap();
+        assert_eq!(sent.tx_type, TransactionType::Transfer);
+        assert_eq!(sent.status, TransactionStatus::Committed);
+        assert_eq!(sent.direction, TxDirection::Sent);
+        assert_eq!(sent.token, TokenId(1));
+        assert_eq!(sent.amount, Some(BigUint::from(100u32)));
+
+        let received = facts(&transfer(AccountId(8), address(), 100)).unwrap();
+        assert_eq!(received.direction, TxDirection::Received);
+        // Transactions of other accounts are not reported.
+        assert_eq!(facts(&transfer(AccountId(8), other_address(), 100)), None);
+        // Self-transfers are sent.
+        let own = facts(&transfer(ACCOUNT_ID, address(), 100)).unwrap();
+        assert_eq!(own.direction, TxDirection::Sent);
+
+        // Deposits are received even by the accounts without an ID yet.
+        let deposit = tx_event(
+            AccountId(9),
+            "finalized",
+            json!({
+                "type": "Deposit",
+                "account_id": 9,
+                "priority_op": {
+                    "from": other_address(),
+                    "token": 1,
+                    "amount": "500",
+                    "to": address(),
+                },
+            }),
+        );
+        let deposit = EventFacts::new(&deposit, None, address()).unwrap();
+        assert_eq!(deposit.direction, TxDirection::Received);
+        assert_eq!(deposit.status, TransactionStatus::Finalized);
+        assert_eq!(deposit.amount, Some(BigUint::from(500u32)));
+
+        let change_pubkey = tx_event(ACCOUNT_ID, "rejected", json!({ "type": "ChangePubKey" }));
+        let change_pubkey = facts(&change_pubkey).unwrap();
+        assert_eq!(change_pubkey.direction, TxDirection::Sent);
+        assert_eq!(change_pubkey.amount, None);
+    }
+
+    #[test]
+    fn preferences_matching() {
+        let event = facts(&transfer(ACCOUNT_ID, other_address(), 100)).unwrap();
+        assert!(accepts(&WebhookPreferences::default(), &event, None));
+
+        let mut preferences = WebhookPreferences {
+            tx_types: Some(vec![TransactionType::Transfer, TransactionType::Withdraw]),
+            statuses: Some(vec![TransactionStatus::Committed]),
+            tokens: Some(vec![TokenId(1)]),
+            direction: Some(TxDirection::Sent),
+            min_amount: Some(MinAmount::Token(BigUint::from(100u32))),
+        };
+        assert!(accepts(&preferences, &event, None));
+
+        // Every filter should match.
+        let mismatches = [
+            WebhookPreferences {
+                tx_types: Some(vec![TransactionType::Withdraw]),
+                ..preferences.clone()
+            },
+            WebhookPreferences {
+                statuses: Some(vec![TransactionStatus::Finalized]),
+                ..preferences.clone()
+            },
+            WebhookPreferences {
+                tokens: Some(vec![TokenId(2)]),
+                ..preferences.clone()
+            },
+            WebhookPreferences {
+                direction: Some(TxDirection::Received),
+                ..preferences.clone()
+            },
+            WebhookPreferences {
+                min_amount: Some(MinAmount::Token(BigUint::from(101u32))),
+                ..preferences.clone()
+            },
+        ];
+        for preferences in &mismatches {
+            assert!(!accepts(preferences, &event, None), "{:?}", preferences);
+        }
+
+        // The USD amount is compared at the price of the smallest token unit.
+        preferences.min_amount = Some(MinAmount::Usd("1.5".parse().unwrap()));
+        let price: BigDecimal = "0.015".parse().unwrap();
+        assert!(accepts(&preferences, &event, Some(&price)));
+        let price: BigDecimal = "0.01".parse().unwrap();
+        assert!(!accepts(&preferences, &event, Some(&price)));
+        // The amount can't be compared without the price.
+        assert!(!accepts(&preferences, &event, None));
+
+        // Transactions without an amount don't match the amount filters.
+        let change_pubkey = tx_event(ACCOUNT_ID, "committed", json!({ "type": "ChangePubKey" }));
+        let change_pubkey = facts(&change_pubkey).unwrap();
+        let preferences = WebhookPreferences {
+            min_amount: Some(MinAmount::Usd(BigDecimal::from(0))),
+            ..Default::default()
+        };
+        assert!(!accepts(&preferences, &change_pubkey, Some(&price)));
+    }
+
+    #[test]
+    fn preferences_validation() {
+        let valid = [
+            WebhookPreferences::default(),
+            WebhookPreferences {
+                tokens: Some(vec![TokenId(1)]),
+                min_amount: Some(MinAmount::Token(BigUint::from(1u32))),
+                ..Default::default()
+            },
+            WebhookPreferences {
+                tx_types: Some(vec![TransactionType::Withdraw, TransactionType::MintNFT]),
+                min_amount: Some(MinAmount::Usd(BigDecimal::from(10))),
+                ..Default::default()
+            },
+            WebhookPreferences {
+                tx_types: Some(vec![TransactionType::Deposit]),
+                direction: Some(TxDirection::Received),
+                ..Default::default()
+            },
+        ];
+        for preferences in &valid {
+            assert_eq!(validate(preferences), Ok(()), "{:?}", preferences);
+        }
+
+        let invalid = [
+            (
+                WebhookPreferences {
+                    statuses: Some(vec![]),
+                    ..Default::default()
+                },
+                InvalidPreferences::EmptyFilter("statuses"),
+            ),
+            (
+                WebhookPreferences {
+                    tokens: Some(vec![TokenId(1), TokenId(2)]),
+                    min_amount: Some(MinAmount::Token(BigUint::from(1u32))),
+                    ..Default::default()
+                },
+                InvalidPreferences::TokenAmountWithoutToken,
+            ),
+            (
+                WebhookPreferences {
+                    tx_types: Some(vec![TransactionType::MintNFT, TransactionType::WithdrawNFT]),
+                    min_amount: Some(MinAmount::Usd(BigDecimal::from(10))),
+                    ..Default::default()
+                },
+                InvalidPreferences::UsdAmountForNft,
+            ),
+            (
+                WebhookPreferences {
+                    tokens: Some(vec![TokenId(MIN_NFT_TOKEN_ID)]),
+                    min_amount: Some(MinAmount::Usd(BigDecimal::from(10))),
+                    ..Default::default()
+                },
+                InvalidPreferences::UsdAmountForNft,
+            ),
+            (
+                WebhookPreferences {
+                    min_amount: Some(MinAmount::Usd(BigDecimal::from(-1))),
+                    ..Default::default()
+                },
+                InvalidPreferences::NegativeUsdAmount,
+            ),
+            (
+                WebhookPreferences {
+                    tx_types: Some(vec![TransactionType::ChangePubKey]),
+                    min_amount: Some(MinAmount::Usd(BigDecimal::from(10))),
+                    ..Default::default()
+                },
+                InvalidPreferences::NoTypesWithAmount,
+            ),
+            (
+                WebhookPreferences {
+                    tx_types: Some(vec![TransactionType::Withdraw]),
+                    direction: Some(TxDirection::Received),
+                    ..Default::default()
+                },
+                InvalidPreferences::NoReceivableTypes,
+            ),
+        ];
+        for (preferences, err) in &invalid {
+            assert_eq!(validate(preferences), Err(*err), "{:?}", preferences);
+        }
+    }
+}
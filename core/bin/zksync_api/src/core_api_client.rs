@@ -0,0 +1,396 @@
+//! Client for the zkSync core private API.
+//!
+//! Core private API is available only from inside of the cluster. In addition, the requests
+//! are signed with the shared secret if it's configured, so the core is able to reject the requests
+//! not made by the API servers.
+
+// External uses
+use reqwest::{header::CONTENT_TYPE, Method, Url};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::Instrument;
+// Workspace uses
+use zksync_api_types::{
+    core_auth::CoreAuthSigner, CoreStatus, EthOperationStatus, EthSenderGasReport, EthWatchStatus,
+    MempoolTxLookup, PendingBlockStatus, PriorityOpLookupQuery, TxCancellation,
+    MAX_UNCONFIRMED_OPS_BATCH_SIZE,
+};
+use zksync_config::configs::api::PrivateApiConfig;
+use zksync_types::{tx::TxHash, PriorityOp};
+
+/// `CoreApiClient` is capable of interacting with a private zkSync core API.
+#[derive(Debug, Clone)]
+pub struct CoreApiClient {
+    client: reqwest::Client,
+    addr: String,
+    signer: Option<CoreAuthSigner>,
+}
+
+impl CoreApiClient {
+    pub fn new(addr: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            addr,
+            signer: None,
+        }
+    }
+
+    /// Creates the client of the configured core, the requests are signed if the secret is set.
+    pub fn from_config(config: &PrivateApiConfig) -> Self {
+        let client = Self::new(config.url.clone());
+        if config.auth_secret.is_empty() {
+            client
+        } else {
+            client.with_signer(CoreAuthSigner::new(&config.auth_secret))
+        }
+    }
+
+    pub fn with_signer(mut self, signer: CoreAuthSigner) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Queries the healthcheck status of the core server.
+    pub async fn get_status(&self) -> anyhow::Result<CoreStatus> {
+        self.get("status").await
+    }
+
+    /// Queries the last Ethereum block processed by the Ethereum watcher.
+    pub async fn eth_watch_status(&self) -> anyhow::Result<EthWatchStatus> {
+        self.get("eth_watch_status").await
+    }
+
+    /// Queries the stats of the block currently filled by the state keeper.
+    pub async fn pending_block_status(&self) -> anyhow::Result<PendingBlockStatus> {
+        self.get("pending_block_status").await
+    }
+
+    /// Queries the Ethereum transactions sent for the aggregated operation.
+    pub async fn eth_operation_status(
+        &self,
+        aggregated_op_id: i64,
+    ) -> anyhow::Result<EthOperationStatus> {
+        self.get(&format!("eth_operation/{}", aggregated_op_id))
+            .await
+    }
+
+    /// Requests the Ethereum sender to resend the transaction of the aggregated operation
+    /// with the increased gas price.
+    pub async fn resend_eth_operation(
+        &self,
+        aggregated_op_id: i64,
+    ) -> anyhow::Result<EthOperationStatus> {
+        self.post(&format!("eth_operation/{}/resend", aggregated_op_id))
+            .await
+    }
+
+    /// Queries the gas price strategy of the Ethereum sender along with the gas used by
+    /// the `limit` latest sent transactions and the fees paid within the last 24 hours.
+    pub async fn eth_sender_gas_report(&self, limit: u32) -> anyhow::Result<EthSenderGasReport> {
+        self.get(&format!("eth_sender/gas_report?limit={}", limit))
+            .await
+    }
+
+    /// Requests the mempool to remove the transaction unless it's already proposed for a block.
+    /// The request is expected to be verified beforehand.
+    pub async fn cancel_tx(&self, tx_hash: TxHash) -> anyhow::Result<TxCancellation> {
+        self.post(&format!("mempool/cancel_tx/{}", tx_hash.to_string()))
+            .await
+    }
+
+    /// Looks the transaction up in the mempool, including the transactions recently proposed
+    /// for a block.
+    pub async fn mempool_tx_lookup(&self, tx_hash: TxHash) -> anyhow::Result<MempoolTxLookup> {
+        self.get(&format!("mempool/tx/{}", tx_hash.to_string()))
+            .await
+    }
+
+    /// Looks up the priority operations received by the Ethereum watcher, but not confirmed yet.
+    /// The results are in the order of the queries, the long lists are split into several requests.
+    pub async fn get_unconfirmed_ops_batch(
+        &self,
+        queries: &[PriorityOpLookupQuery],
+    ) -> anyhow::Result<Vec<Option<PriorityOp>>> {
+        let mut ops = Vec::with_capacity(queries.len());
+        for chunk in queries.chunks(MAX_UNCONFIRMED_OPS_BATCH_SIZE) {
+            let chunk_ops: Vec<Option<PriorityOp>> =
+                self.post_json("unconfirmed_ops_batch", chunk).await?;
+            anyhow::ensure!(
+                chunk_ops.len() == chunk.len(),
+                "Core returned {} results for {} queries",
+                chunk_ops.len(),
+                chunk.len()
+            );
+            ops.extend(chunk_ops);
+        }
+        Ok(ops)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, method: &str) -> anyhow::Result<T> {
+        self.request(Method::GET, method, None).await
+    }
+
+    async fn post<T: DeserializeOwned>(&self, method: &str) -> anyhow::Result<T> {
+        self.request(Method::POST, method, None).await
+    }
+
+    async fn post_json<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        method: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        let body = serde_json::to_vec(body)?;
+        self.request(Method::POST, method, Some(body)).await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        http_method: Method,
+        method: &str,
+        body: Option<Vec<u8>>,
+    ) -> anyhow::Result<T> {
+        let endpoint = format!("{}/{}", self.addr, method);
+        async {
+            let mut request = self.client.request(http_method.clone(), &endpoint);
+            if let Some(signer) = &self.signer {
+                // The core verifies the path it receives, so it doesn't include the address.
+                let url = Url::parse(&endpoint)?;
+                let path = match url.query() {
+                    Some(query) => format!("{}?{}", url.path(), query),
+                    None => url.path().to_owned(),
+                };
+                let headers = signer.sign(
+                    http_method.as_str(),
+                    &path,
+                    body.as_deref().unwrap_or_default(),
+                );
+                for (name, value) in headers.pairs() {
+                    request = request.header(name, value);
+                }
+            }
+            if let Some(body) = body {
+                request = request.header(CONTENT_TYPE, "application/json").body(body);
+            }
+            let response = request.send().await?;
+            Ok(response.error_for_status()?.json().await?)
+        }
+        .instrument(tracing::info_span!("core_api_client", method))
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse};
+    use reqwest::StatusCode;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use std::time::Duration;
+    use zksync_api_types::core_auth::{CoreAuthHeaders, CoreAuthVerifier};
+    use zksync_core::private_api::verify_request;
+    use zksync_types::{Deposit, TokenId, ZkSyncPriorityOp, H256};
+
+    const SECRET: &str = "secret";
+    const PREVIOUS_SECRET: &str = "previous_secret";
+    const MAX_BODY_SIZE: usize = 16 * 1024;
+
+    fn priority_op(serial_id: u64) -> PriorityOp {
+        PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(0),
+                amount: Default::default(),
+                to: Default::default(),
+            }),
+            deadline_block: 0,
+            eth_hash: H256::from_low_u64_be(serial_id + 1),
+            eth_block: 10,
+            eth_block_index: Some(serial_id),
+        }
+    }
+
+    /// Starts the mock of the core private API knowing the provided unconfirmed operations,
+    /// the served requests are counted. Requests are verified the same way the core does it.
+    fn start_core_mock(ops: Vec<PriorityOp>, requests: Arc<AtomicUsize>) -> actix_test::TestServer {
+        let verifier = Some(Arc::new(CoreAuthVerifier::new(
+            vec![SECRET.to_owned(), PREVIOUS_SECRET.to_owned()],
+            Duration::from_secs(30),
+            MAX_BODY_SIZE,
+        )));
+        actix_test::start(move || {
+            let ops = ops.clone();
+            let requests = requests.clone();
+            let verifier = verifier.clone();
+            App::new()
+                .wrap_fn(move |req, srv| verify_request(&verifier, req, srv))
+                .route(
+                    "/unconfirmed_ops_batch",
+                    web::post().to(
+                        move |web::Json(queries): web::Json<Vec<PriorityOpLookupQuery>>| {
+                            requests.fetch_add(1, Ordering::SeqCst);
+                            let found: Vec<_> = queries
+                                .iter()
+                                .map(|query| {
+                                    ops.iter()
+                                        .find(|op| match query {
+                                            PriorityOpLookupQuery::BySyncHash(hash) => {
+                                                op.tx_hash() == *hash
+                                            }
+                                            PriorityOpLookupQuery::ByEthHash(hash) => {
+                                                op.eth_hash == *hash
+                                            }
+                                            PriorityOpLookupQuery::ByAnyHash(hash) => {
+                                                op.tx_hash() == *hash
+                                                    || op.eth_hash.as_bytes() == hash.as_ref()
+                                            }
+                                        })
+                                        .cloned()
+                                })
+                                .collect();
+                            let too_long = queries.len() > MAX_UNCONFIRMED_OPS_BATCH_SIZE;
+                            async move {
+                                if too_long {
+                                    HttpResponse::BadRequest().finish()
+                                } else {
+                                    HttpResponse::Ok().json(found)
+                                }
+                            }
+                        },
+                    ),
+                )
+        })
+    }
+
+    #[actix_rt::test]
+    async fn unconfirmed_ops_batch() -> anyhow::Result<()> {
+        let ops: Vec<_> = (0..3).map(priority_op).collect();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = start_core_mock(ops.clone(), requests.clone());
+        let client = CoreApiClient::new(server.url("").trim_end_matches('/').to_owned())
+            .with_signer(CoreAuthSigner::new(SECRET));
+
+        // Known operations are mixed with the unknown ones, and the list is longer than
+        // a single request allows.
+        let queries: Vec<_> = (0..MAX_UNCONFIRMED_OPS_BATCH_SIZE as u64 + 10)
+            .map(|i| match i % 4 {
+                0 => PriorityOpLookupQuery::BySyncHash(ops[0].tx_hash()),
+                1 => PriorityOpLookupQuery::ByEthHash(ops[1].eth_hash),
+                2 => PriorityOpLookupQuery::ByAnyHash(
+                    TxHash::from_slice(ops[2].eth_hash.as_bytes()).unwrap(),
+                ),
+                _ => PriorityOpLookupQuery::ByEthHash(H256::from_low_u64_be(1000 + i)),
+            })
+            .collect();
+        let serial_ids: Vec<_> = client
+            .get_unconfirmed_ops_batch(&queries)
+            .await?
+            .into_iter()
+            .map(|op| op.map(|op| op.serial_id))
+            .collect();
+        let expected: Vec<_> = (0..queries.len())
+            .map(|i| match i % 4 {
+                3 => None,
+                serial_id => Some(serial_id as u64),
+            })
+            .collect();
+        assert_eq!(serial_ids, expected);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Nothing is requested for the empty list.
+        assert!(client.get_unconfirmed_ops_batch(&[]).await?.is_empty());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Sends the lookup request to the core mock with the given authentication headers.
+    async fn send_lookup(
+        server: &actix_test::TestServer,
+        body: &[u8],
+        headers: &CoreAuthHeaders,
+    ) -> anyhow::Result<StatusCode> {
+        let mut request = reqwest::Client::new()
+            .post(server.url("/unconfirmed_ops_batch"))
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_vec());
+        for (name, value) in headers.pairs() {
+            request = request.header(name, value);
+        }
+        Ok(request.send().await?.status())
+    }
+
+    #[actix_rt::test]
+    async fn authenticated_requests() -> anyhow::Result<()> {
+        let ops = vec![priority_op(0)];
+        let requests = Arc::new(AtomicUsize::new(0));
+        let server = start_core_mock(ops.clone(), requests.clone());
+        let address = server.url("").trim_end_matches('/').to_owned();
+        let queries = [PriorityOpLookupQuery::ByEthHash(ops[0].eth_hash)];
+
+        // Requests signed with any of the accepted secrets are served.
+        for secret in [SECRET, PREVIOUS_SECRET] {
+            let client =
+                CoreApiClient::new(address.clone()).with_signer(CoreAuthSigner::new(secret));
+            let found = client.get_unconfirmed_ops_batch(&queries).await?;
+            assert_eq!(found[0].as_ref().map(|op| op.serial_id), Some(0));
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Unsigned requests and requests signed with an unknown secret are rejected.
+        let unsigned = CoreApiClient::new(address.clone());
+        let unknown = CoreApiClient::new(address).with_signer(CoreAuthSigner::new("unknown"));
+        for client in [unsigned, unknown] {
+            let err = client
+                .get_unconfirmed_ops_batch(&queries)
+                .await
+                .unwrap_err();
+            let status = err
+                .downcast_ref::<reqwest::Error>()
+                .and_then(|err| err.status());
+            assert_eq!(status, Some(StatusCode::UNAUTHORIZED));
+        }
+
+        // The body is covered by the signature.
+        let signer = CoreAuthSigner::new(SECRET);
+        let body = serde_json::to_vec(&queries)?;
+        let headers = signer.sign("POST", "/unconfirmed_ops_batch", &body);
+        let tampered = serde_json::to_vec(&[PriorityOpLookupQuery::ByEthHash(H256::zero())])?;
+        assert_eq!(
+            send_lookup(&server, &tampered, &headers).await?,
+            StatusCode::UNAUTHORIZED
+        );
+
+        // The verified request can't be replayed.
+        assert_eq!(send_lookup(&server, &body, &headers).await?, StatusCode::OK);
+        assert_eq!(
+            send_lookup(&server, &body, &headers).await?,
+            StatusCode::UNAUTHORIZED
+        );
+        // The handler runs only for the verified requests.
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Bodies over the limit are rejected even if signed, but only after the headers are checked.
+        let oversized = vec![b' '; MAX_BODY_SIZE + 1];
+        let headers = signer.sign("POST", "/unconfirmed_ops_batch", &oversized);
+        assert_eq!(
+            send_lookup(&server, &oversized, &headers).await?,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        let expired = CoreAuthHeaders {
+            timestamp: headers.timestamp - 60,
+            ..headers
+        };
+        assert_eq!(
+            send_lookup(&server, &oversized, &expired).await?,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        server.stop().await;
+        Ok(())
+    }
+}
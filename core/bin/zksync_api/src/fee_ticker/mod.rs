@@ -12,6 +12,7 @@ use std::time::Duration;
 
 // External deps
 use bigdecimal::BigDecimal;
+use chrono::Utc;
 use num::{
     rational::Ratio,
     traits::{Inv, Pow},
@@ -24,29 +25,33 @@ use tokio::time::Instant;
 
 // Workspace deps
 
+use zksync_api_types::v02::fee::FeeTokenRequirement;
 use zksync_config::configs::ticker::TokenPriceSource;
 use zksync_storage::ConnectionPool;
-use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
     gas_counter::GasCounter, tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, Address,
     BatchFee, ChangePubKeyOp, Fee, MintNFTOp, OutputFeeType, SwapOp, Token, TokenId, TokenLike,
-    TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp, WithdrawOp,
+    TokenPrice, TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp, WithdrawOp,
 };
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 
 // Local deps
 use crate::fee_ticker::constants::AMORTIZED_COST_PER_CHUNK;
 pub use crate::fee_ticker::ticker_info::{FeeTickerInfo, TickerInfo};
-use crate::fee_ticker::validator::FeeTokenValidator;
+use crate::fee_ticker::validator::{FeeTokenValidator, PriceMaxAge};
 use crate::fee_ticker::{
     ticker_api::{
-        coingecko::CoinGeckoAPI, coinmarkercap::CoinMarketCapAPI, FeeTickerAPI, TickerApi,
-        CONNECTION_TIMEOUT,
+        coingecko::CoinGeckoAPI,
+        coinmarkercap::CoinMarketCapAPI,
+        multi_source::{MultiSourcePriceAPI, PriceSource},
+        FeeTickerAPI, TickerApi, TokenPriceAPI, CONNECTION_TIMEOUT,
     },
     validator::{watcher::UniswapTokenWatcher, MarketUpdater},
 };
+use crate::utils::token_cache::TokenCache;
 
 mod constants;
+pub mod quote;
 mod ticker_api;
 pub(crate) mod ticker_info;
 pub mod validator;
@@ -158,11 +163,24 @@ pub enum TokenPriceRequestType {
     USDForOneToken,
 }
 
+/// Prices used to calculate the fee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeePriceInputs {
+    /// Token the fee is paid in.
+    pub token_id: TokenId,
+    /// Price of one whole token (not the minimal unit) in USD.
+    pub token_price_usd: Ratio<BigUint>,
+    /// Price of one ETH in USD.
+    pub eth_price_usd: Ratio<BigUint>,
+    pub gas_price_wei: BigUint,
+}
+
 #[derive(Debug, Clone)]
 pub struct ResponseFee {
     pub normal_fee: Fee,
     pub subsidized_fee: Fee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    pub price_inputs: FeePriceInputs,
 }
 
 #[derive(Debug, Clone)]
@@ -170,6 +188,7 @@ pub struct ResponseBatchFee {
     pub normal_fee: BatchFee,
     pub subsidized_fee: BatchFee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    pub price_inputs: FeePriceInputs,
 }
 
 #[derive(Debug, Error)]
@@ -180,6 +199,15 @@ pub enum PriceError {
     ApiError(String),
     #[error("Database error: {0}")]
     DBError(String),
+    #[error(
+        "Price of token {symbol} was updated {age_secs} seconds ago, fees can't be calculated with prices older than {max_age_secs} seconds"
+    )]
+    StalePrice {
+        token_id: TokenId,
+        symbol: String,
+        age_secs: i64,
+        max_age_secs: i64,
+    },
 }
 
 impl PriceError {
@@ -215,7 +243,10 @@ pub fn run_updaters(
     db_pool: ConnectionPool,
     config: &zksync_config::TickerConfig,
 ) -> Vec<JoinHandle<()>> {
-    let cache = (db_pool.clone(), TokenDBCache::new(TOKEN_INVALIDATE_CACHE));
+    let cache = (
+        db_pool.clone(),
+        TokenCache::new(db_pool.clone(), TOKEN_INVALIDATE_CACHE),
+    );
 
     let watcher = UniswapTokenWatcher::new(config.uniswap_url.clone());
 
@@ -228,26 +259,36 @@ pub fn run_updaters(
         .connect_timeout(CONNECTION_TIMEOUT)
         .build()
         .expect("Failed to build reqwest::Client");
-    let (price_source, base_url) = config.price_source();
-    let price_updater = match price_source {
-        TokenPriceSource::CoinMarketCap => {
-            let token_price_api =
-                CoinMarketCapAPI::new(client, base_url.parse().expect("Correct CoinMarketCap url"));
-
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
-            tokio::spawn(ticker_api.keep_price_updated())
-        }
-
-        TokenPriceSource::CoinGecko => tokio::spawn(async move {
-            let token_price_api =
-                CoinGeckoAPI::new(client, base_url.parse().expect("Correct CoinGecko url"))
+    let price_sources = config.price_sources();
+    let price_source_timeout = config.price_source_timeout();
+    let price_quorum = config.price_quorum;
+    let price_updater = tokio::spawn(async move {
+        let mut sources = Vec::new();
+        for (price_source, base_url) in price_sources {
+            let token_price_api: Box<dyn TokenPriceAPI + Send + Sync> = match price_source {
+                TokenPriceSource::CoinMarketCap => Box::new(CoinMarketCapAPI::new(
+                    client.clone(),
+                    base_url.parse().expect("Correct CoinMarketCap url"),
+                )),
+                TokenPriceSource::CoinGecko => Box::new(
+                    CoinGeckoAPI::new(
+                        client.clone(),
+                        base_url.parse().expect("Correct CoinGecko url"),
+                    )
                     .await
-                    .expect("failed to init CoinGecko client");
-            let ticker_api = TickerApi::new(db_pool, token_price_api);
+                    .expect("failed to init CoinGecko client"),
+                ),
+            };
+            sources.push(PriceSource::new(
+                format!("{:?}", price_source),
+                token_price_api,
+            ));
+        }
+        let token_price_api = MultiSourcePriceAPI::new(sources, price_source_timeout, price_quorum);
+        let ticker_api = TickerApi::new(db_pool, token_price_api);
 
-            ticker_api.keep_price_updated().await;
-        }),
-    };
+        ticker_api.keep_price_updated().await;
+    });
     tasks.push(price_updater);
     tasks
 }
@@ -270,8 +311,9 @@ impl FeeTicker {
         config: zksync_config::TickerConfig,
         max_blocks_to_aggregate: u32,
         connection_pool: ConnectionPool,
+        tokens: TokenCache,
     ) -> Self {
-        let cache = (connection_pool, TokenDBCache::new(TOKEN_INVALIDATE_CACHE));
+        let cache = (connection_pool, tokens);
         let ticker_config = TickerConfig {
             zkp_cost_chunk_usd: Ratio::from_integer(BigUint::from(10u32).pow(3u32)).inv(),
             gas_cost_tx: GasOperationsCost::from_constants(config.fast_processing_coeff),
@@ -283,11 +325,14 @@ impl FeeTicker {
             max_blocks_to_aggregate,
             subsidy_cpk_price_usd: config.subsidy_cpk_price_usd(),
         };
+        let price_max_age = PriceMaxAge::from_config(&config);
         let validator = FeeTokenValidator::new(
             cache,
             chrono::Duration::seconds(config.available_liquidity_seconds as i64),
             BigDecimal::try_from(config.liquidity_volume).expect("Valid f64 for decimal"),
+            BigDecimal::try_from(config.strict_liquidity_volume).expect("Valid f64 for decimal"),
             HashSet::from_iter(config.unconditionally_valid_tokens),
+            price_max_age,
         );
         Self::new(info, ticker_config, validator)
     }
@@ -329,6 +374,18 @@ impl FeeTicker {
         res
     }
 
+    /// Returns the last USD prices of one token unit for each of the given tokens.
+    /// Tokens without a known price are omitted from the result.
+    pub async fn get_token_prices(
+        &self,
+        tokens: &[Token],
+    ) -> Result<HashMap<TokenId, TokenPrice>, PriceError> {
+        let start = Instant::now();
+        let res = self.info.get_last_token_prices(tokens).await;
+        metrics::histogram!("ticker.get_token_prices", start.elapsed());
+        res
+    }
+
     pub async fn get_fee_from_ticker_in_wei(
         &self,
         tx_type: TxFeeTypes,
@@ -339,10 +396,12 @@ impl FeeTicker {
         let zkp_cost_chunk = self.config.zkp_cost_chunk_usd.clone();
         let token = self.info.get_token(token).await?;
 
-        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        let price_inputs = self.price_inputs(&token).await?;
+        let gas_price_wei = price_inputs.gas_price_wei.clone();
         let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
-        let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let wei_price_usd = Self::wei_price_from_eth_price(&price_inputs.eth_price_usd);
+        let token_usd_risk =
+            self.token_usd_risk_from_price(&token, &price_inputs.token_price_usd)?;
 
         let (fee_type, gas_tx_amount, op_chunks) = self.gas_tx_amount(tx_type, recipient).await?;
 
@@ -401,6 +460,7 @@ impl FeeTicker {
                 normal_fee,
                 subsidized_fee,
                 subsidy_size_usd,
+                price_inputs,
             });
         }
 
@@ -409,6 +469,7 @@ impl FeeTicker {
             normal_fee: normal_fee.clone(),
             subsidized_fee: normal_fee,
             subsidy_size_usd: Ratio::from(BigUint::from(0u32)),
+            price_inputs,
         })
     }
 
@@ -422,10 +483,12 @@ impl FeeTicker {
 
         let token = self.info.get_token(token).await?;
 
-        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        let price_inputs = self.price_inputs(&token).await?;
+        let gas_price_wei = price_inputs.gas_price_wei.clone();
         let scale_gas_price = Self::risk_gas_price_estimate(gas_price_wei.clone());
-        let wei_price_usd = self.wei_price_usd().await?;
-        let token_usd_risk = self.token_usd_risk(&token).await?;
+        let wei_price_usd = Self::wei_price_from_eth_price(&price_inputs.eth_price_usd);
+        let token_usd_risk =
+            self.token_usd_risk_from_price(&token, &price_inputs.token_price_usd)?;
 
         let mut total_normal_gas_tx_amount = Ratio::from(BigUint::zero());
         let mut total_op_chunks = Ratio::from(BigUint::zero());
@@ -522,23 +585,85 @@ impl FeeTicker {
             normal_fee,
             subsidized_fee,
             subsidy_size_usd,
+            price_inputs,
         })
     }
 
-    pub async fn wei_price_usd(&self) -> anyhow::Result<Ratio<BigUint>> {
-        let start = Instant::now();
-        let res = self
+    /// Loads the prices used in the fee calculation, so that the same values
+    /// are used for every part of the fee and can be reported to the user.
+    async fn price_inputs(&self, token: &Token) -> anyhow::Result<FeePriceInputs> {
+        let eth_price_usd = self
             .info
             .get_last_token_price(TokenLike::Id(TokenId(0)))
             .await?
-            .usd_price
-            / BigUint::from(10u32).pow(18u32);
-        metrics::histogram!("ticker.wei_price_usd", start.elapsed());
-        Ok(res)
+            .usd_price;
+        let token_price = self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await?;
+        self.check_price_freshness(token, &token_price).await?;
+        let token_price_usd = token_price.usd_price;
+        let gas_price_wei = self.info.get_gas_price_wei().await?;
+
+        Ok(FeePriceInputs {
+            token_id: token.id,
+            token_price_usd,
+            eth_price_usd,
+            gas_price_wei,
+        })
     }
 
-    pub async fn token_usd_risk(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
-        let start = Instant::now();
+    /// Checks that the price of the token is recent enough to calculate the fee in it.
+    async fn check_price_freshness(
+        &self,
+        token: &Token,
+        price: &TokenPrice,
+    ) -> Result<(), PriceError> {
+        let max_age = match self
+            .validator
+            .price_max_age(token)
+            .await
+            .map_err(PriceError::db_error)?
+        {
+            Some(max_age) => max_age,
+            // The token is rejected by the liquidity check before the fee is calculated.
+            None => return Ok(()),
+        };
+        let age = Utc::now() - price.last_updated;
+        if age > max_age {
+            return Err(PriceError::StalePrice {
+                token_id: token.id,
+                symbol: token.symbol.clone(),
+                age_secs: age.num_seconds(),
+                max_age_secs: max_age.num_seconds(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that the price of the fee token is recent enough to calculate the fee in it.
+    pub async fn check_fee_token_price(&self, token: TokenLike) -> Result<(), PriceError> {
+        let token = self
+            .info
+            .get_token(token)
+            .await
+            .map_err(PriceError::db_error)?;
+        let price = self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await?;
+        self.check_price_freshness(&token, &price).await
+    }
+
+    fn wei_price_from_eth_price(eth_price_usd: &Ratio<BigUint>) -> Ratio<BigUint> {
+        eth_price_usd / BigUint::from(10u32).pow(18u32)
+    }
+
+    fn token_usd_risk_from_price(
+        &self,
+        token: &Token,
+        token_price_usd: &Ratio<BigUint>,
+    ) -> anyhow::Result<Ratio<BigUint>> {
         let token_risk_factor = self
             .config
             .tokens_risk_factors
@@ -546,17 +671,35 @@ impl FeeTicker {
             .cloned()
             .unwrap_or_else(|| Ratio::from_integer(1u32.into()));
 
+        let token_price_usd = token_price_usd / BigUint::from(10u32).pow(u32::from(token.decimals));
+        // TODO Check tokens fee allowance by non-zero price (ZKS-580)
+        token_risk_factor
+            .checked_div(&token_price_usd)
+            .ok_or_else(|| anyhow::format_err!("Token is not acceptable for fee"))
+    }
+
+    pub async fn wei_price_usd(&self) -> anyhow::Result<Ratio<BigUint>> {
+        let start = Instant::now();
+        let eth_price_usd = self
+            .info
+            .get_last_token_price(TokenLike::Id(TokenId(0)))
+            .await?
+            .usd_price;
+        let res = Self::wei_price_from_eth_price(&eth_price_usd);
+        metrics::histogram!("ticker.wei_price_usd", start.elapsed());
+        Ok(res)
+    }
+
+    pub async fn token_usd_risk(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
+        let start = Instant::now();
         let token_price_usd = self
             .info
             .get_last_token_price(TokenLike::Id(token.id))
             .await?
-            .usd_price
-            / BigUint::from(10u32).pow(u32::from(token.decimals));
-        // TODO Check tokens fee allowance by non-zero price (ZKS-580)
+            .usd_price;
+        let res = self.token_usd_risk_from_price(token, &token_price_usd);
         metrics::histogram!("ticker.token_usd_risk", start.elapsed());
-        token_risk_factor
-            .checked_div(&token_price_usd)
-            .ok_or_else(|| anyhow::format_err!("Token is not acceptable for fee"))
+        res
     }
 
     /// Returns `true` if account does not yet exist in the zkSync network.
@@ -645,8 +788,17 @@ impl FeeTicker {
         ))
     }
 
-    pub async fn token_allowed_for_fees(&self, token: TokenLike) -> anyhow::Result<bool> {
-        self.validator.token_allowed(token).await
+    pub async fn token_allowed_for_fees(
+        &self,
+        token: TokenLike,
+        requirement: FeeTokenRequirement,
+    ) -> anyhow::Result<bool> {
+        self.validator.token_allowed(token, requirement).await
+    }
+
+    /// Returns the tokens that can be used to pay fees along with the strictest requirement they meet.
+    pub async fn allowed_fee_tokens(&self) -> anyhow::Result<Vec<(TokenId, FeeTokenRequirement)>> {
+        self.validator.allowed_tokens().await
     }
 }
 
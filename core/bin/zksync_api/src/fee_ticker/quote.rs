@@ -0,0 +1,335 @@
+//! Signed fee quotes.
+//!
+//! Fee responses contain the prices used in the calculation, signed by the operator.
+//! The signed quote may be attached to the transaction submission, in that case the
+//! quoted fee is accepted until the quote expires, even if the prices have changed.
+
+// Built-in deps
+use std::time::Duration;
+
+// External deps
+use num::BigUint;
+use thiserror::Error;
+
+// Workspace deps
+use zksync_api_types::v02::fee::{FeeQuote, SignedFeeQuote, TxInBatchFeeRequest};
+use zksync_types::{tx::PackedEthSignature, Address, TokenId, TxFeeTypes, H256};
+use zksync_utils::ratio_to_big_decimal;
+
+// Local deps
+use crate::fee_ticker::FeePriceInputs;
+
+/// Precision of the USD prices in the quote.
+const PRICE_PRECISION: usize = 18;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum FeeQuoteError {
+    #[error("Fee quote has expired")]
+    Expired,
+    #[error("Fee quote is not valid yet")]
+    NotYetValid,
+    #[error("Fee quote is not signed by the operator")]
+    InvalidSignature,
+    #[error("Fee quote does not match the transaction")]
+    Mismatch,
+    #[error("Transaction fee is lower than the quoted one")]
+    FeeTooLow,
+}
+
+/// Creates and verifies the fee quotes signed with the operator key.
+#[derive(Debug, Clone)]
+pub struct FeeQuoteSigner {
+    private_key: H256,
+    address: Address,
+    validity: Duration,
+}
+
+impl FeeQuoteSigner {
+    pub fn new(private_key: H256, validity: Duration) -> Self {
+        let address = PackedEthSignature::address_from_private_key(&private_key)
+            .expect("Invalid fee quote private key");
+        Self {
+            private_key,
+            address,
+            validity,
+        }
+    }
+
+    /// Address of the key used to sign the quotes.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Creates a quote valid for the configured period starting from `now` (unix timestamp in seconds).
+    pub fn quote(
+        &self,
+        transactions: Vec<TxInBatchFeeRequest>,
+        price_inputs: &FeePriceInputs,
+        fee: (BigUint, BigUint, BigUint),
+        now: u64,
+    ) -> FeeQuote {
+        let (gas_fee, zkp_fee, total_fee) = fee;
+        FeeQuote {
+            transactions,
+            token_id: price_inputs.token_id,
+            token_price_usd: ratio_to_big_decimal(&price_inputs.token_price_usd, PRICE_PRECISION),
+            eth_price_usd: ratio_to_big_decimal(&price_inputs.eth_price_usd, PRICE_PRECISION),
+            gas_price_wei: price_inputs.gas_price_wei.clone(),
+            gas_fee,
+            zkp_fee,
+            total_fee,
+            timestamp: now,
+            valid_until: now + self.validity.as_secs(),
+        }
+    }
+
+    pub fn sign(&self, quote: FeeQuote) -> SignedFeeQuote {
+        let signature = PackedEthSignature::sign(&self.private_key, quote.message().as_bytes())
+            .expect("Failed to sign the fee quote");
+        SignedFeeQuote { quote, signature }
+    }
+
+    /// Checks that the quote is signed by the operator and is valid at `now`.
+    pub fn verify(&self, signed: &SignedFeeQuote, now: u64) -> Result<(), FeeQuoteError> {
+        let signer = signed
+            .signature
+            .signature_recover_signer_from_raw_message(signed.quote.message().as_bytes())
+            .map_err(|_| FeeQuoteError::InvalidSignature)?;
+        if signer != self.address {
+            return Err(FeeQuoteError::InvalidSignature);
+        }
+
+        if now < signed.quote.timestamp {
+            return Err(FeeQuoteError::NotYetValid);
+        }
+        if now > signed.quote.valid_until {
+            return Err(FeeQuoteError::Expired);
+        }
+        Ok(())
+    }
+
+    /// Checks that the quote is valid and was issued for the transaction with the provided fee.
+    pub fn check_tx_fee(
+        &self,
+        signed: &SignedFeeQuote,
+        now: u64,
+        tx_type: TxFeeTypes,
+        token_id: TokenId,
+        address: Address,
+        provided_fee: &BigUint,
+    ) -> Result<(), FeeQuoteError> {
+        self.verify(signed, now)?;
+
+        let quote = &signed.quote;
+        let matches = match quote.transactions.as_slice() {
+            [quoted_tx] => {
+                TxFeeTypes::from(quoted_tx.tx_type.clone()) == tx_type
+                    && quoted_tx.address == address
+                    && quote.token_id == token_id
+            }
+            _ => false,
+        };
+        if !matches {
+            return Err(FeeQuoteError::Mismatch);
+        }
+
+        if provided_fee < &quote.total_fee {
+            return Err(FeeQuoteError::FeeTooLow);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::rational::Ratio;
+    use zksync_api_types::v02::fee::ApiTxFeeTypes;
+
+    const NOW: u64 = 1_600_000_000;
+    const VALIDITY: Duration = Duration::from_secs(60);
+
+    fn signer() -> FeeQuoteSigner {
+        FeeQuoteSigner::new(H256::from_low_u64_be(0xdead), VALIDITY)
+    }
+
+    fn recipient() -> Address {
+        Address::from_low_u64_be(1)
+    }
+
+    fn price_inputs() -> FeePriceInputs {
+        FeePriceInputs {
+            token_id: TokenId(1),
+            token_price_usd: Ratio::new(BigUint::from(3u32), BigUint::from(2u32)),
+            eth_price_usd: Ratio::from_integer(BigUint::from(2000u32)),
+            gas_price_wei: BigUint::from(1_000_000_000u64),
+        }
+    }
+
+    fn signed_quote(signer: &FeeQuoteSigner) -> SignedFeeQuote {
+        let transactions = vec![TxInBatchFeeRequest {
+            tx_type: ApiTxFeeTypes::Transfer,
+            address: recipient(),
+        }];
+        let fee = (
+            BigUint::from(60u32),
+            BigUint::from(40u32),
+            BigUint::from(100u32),
+        );
+        let quote = signer.quote(transactions, &price_inputs(), fee, NOW);
+        signer.sign(quote)
+    }
+
+    fn check(
+        signer: &FeeQuoteSigner,
+        quote: &SignedFeeQuote,
+        now: u64,
+        fee: u32,
+    ) -> Result<(), FeeQuoteError> {
+        signer.check_tx_fee(
+            quote,
+            now,
+            TxFeeTypes::Transfer,
+            TokenId(1),
+            recipient(),
+            &BigUint::from(fee),
+        )
+    }
+
+    #[test]
+    fn quote_contains_inputs() {
+        let signer = signer();
+        let quote = signed_quote(&signer).quote;
+
+        assert_eq!(quote.token_price_usd.to_string(), "1.500000000000000000");
+        assert_eq!(quote.eth_price_usd.to_string(), "2000.000000000000000000");
+        assert_eq!(quote.gas_price_wei, BigUint::from(1_000_000_000u64));
+        assert_eq!(quote.timestamp, NOW);
+        assert_eq!(quote.valid_until, NOW + VALIDITY.as_secs());
+    }
+
+    #[test]
+    fn valid_quote() {
+        let signer = signer();
+        let quote = signed_quote(&signer);
+
+        assert_eq!(signer.verify(&quote, NOW), Ok(()));
+        assert_eq!(signer.verify(&quote, NOW + VALIDITY.as_secs()), Ok(()));
+        assert_eq!(check(&signer, &quote, NOW + 1, 100), Ok(()));
+        // Paying more than quoted is fine.
+        assert_eq!(check(&signer, &quote, NOW + 1, 150), Ok(()));
+    }
+
+    #[test]
+    fn quote_expiry() {
+        let signer = signer();
+        let quote = signed_quote(&signer);
+
+        assert_eq!(
+            signer.verify(&quote, NOW + VALIDITY.as_secs() + 1),
+            Err(FeeQuoteError::Expired)
+        );
+        assert_eq!(
+            signer.verify(&quote, NOW - 1),
+            Err(FeeQuoteError::NotYetValid)
+        );
+
+        // Prolonging the quote invalidates the signature.
+        let mut prolonged = quote;
+        prolonged.quote.valid_until += 3600;
+        assert_eq!(
+            signer.verify(&prolonged, NOW + VALIDITY.as_secs() + 1),
+            Err(FeeQuoteError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn tampered_quote() {
+        let signer = signer();
+        let quote = signed_quote(&signer);
+
+        let mut tampered = quote.clone();
+        tampered.quote.total_fee = BigUint::from(1u32);
+        assert_eq!(
+            signer.verify(&tampered, NOW),
+            Err(FeeQuoteError::InvalidSignature)
+        );
+
+        let mut tampered = quote.clone();
+        tampered.quote.token_price_usd = "150".parse().unwrap();
+        assert_eq!(
+            signer.verify(&tampered, NOW),
+            Err(FeeQuoteError::InvalidSignature)
+        );
+
+        let mut tampered = quote.clone();
+        tampered.quote.transactions[0].address = Address::from_low_u64_be(2);
+        assert_eq!(
+            signer.verify(&tampered, NOW),
+            Err(FeeQuoteError::InvalidSignature)
+        );
+
+        let mut tampered = quote.clone();
+        tampered.quote.token_id = TokenId(2);
+        assert_eq!(
+            signer.verify(&tampered, NOW),
+            Err(FeeQuoteError::InvalidSignature)
+        );
+
+        // Quote signed by another key is rejected.
+        let other_signer = FeeQuoteSigner::new(H256::from_low_u64_be(0xbeef), VALIDITY);
+        let foreign_quote = signed_quote(&other_signer);
+        assert_eq!(
+            signer.verify(&foreign_quote, NOW),
+            Err(FeeQuoteError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn quote_tx_mismatch() {
+        let signer = signer();
+        let quote = signed_quote(&signer);
+
+        assert_eq!(
+            check(&signer, &quote, NOW, 99),
+            Err(FeeQuoteError::FeeTooLow)
+        );
+
+        let check_tx = |tx_type, token_id, address| {
+            signer.check_tx_fee(
+                &quote,
+                NOW,
+                tx_type,
+                token_id,
+                address,
+                &BigUint::from(100u32),
+            )
+        };
+        assert_eq!(
+            check_tx(TxFeeTypes::Withdraw, TokenId(1), recipient()),
+            Err(FeeQuoteError::Mismatch)
+        );
+        assert_eq!(
+            check_tx(TxFeeTypes::Transfer, TokenId(2), recipient()),
+            Err(FeeQuoteError::Mismatch)
+        );
+        assert_eq!(
+            check_tx(
+                TxFeeTypes::Transfer,
+                TokenId(1),
+                Address::from_low_u64_be(2)
+            ),
+            Err(FeeQuoteError::Mismatch)
+        );
+
+        // Quotes for batches are not accepted for single transactions.
+        let batch_quote = signer.sign(FeeQuote {
+            transactions: vec![quote.quote.transactions[0].clone(); 2],
+            ..quote.quote.clone()
+        });
+        assert_eq!(
+            check(&signer, &batch_quote, NOW, 100),
+            Err(FeeQuoteError::Mismatch)
+        );
+    }
+}
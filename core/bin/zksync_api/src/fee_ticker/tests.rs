@@ -5,14 +5,14 @@ use bigdecimal::BigDecimal;
 use chrono::Utc;
 use futures::executor::block_on;
 use std::str::FromStr;
-use zksync_types::{Address, Token, TokenId, TokenKind, TokenPrice};
+use zksync_types::{tokens::TokenMarketVolume, Address, Token, TokenId, TokenKind, TokenPrice};
 use zksync_utils::{
     ratio_to_big_decimal, ratio_to_scaled_u64, scaled_u64_to_ratio, UnsignedRatioSerializeAsDecimal,
 };
 
 use crate::fee_ticker::{
     ticker_api::TokenPriceAPI,
-    validator::{cache::TokenInMemoryCache, FeeTokenValidator},
+    validator::{cache::TokenInMemoryCache, FeeTokenValidator, PriceMaxAge},
 };
 
 use super::*;
@@ -126,10 +126,19 @@ impl FeeTickerAPI for MockApiProvider {
     }
 }
 
+pub fn test_price_max_age() -> PriceMaxAge {
+    PriceMaxAge {
+        highly_liquid: chrono::Duration::hours(1),
+        liquid: chrono::Duration::minutes(20),
+    }
+}
+
 #[derive(Clone)]
 struct MockTickerInfo {
     pub future_blocks: BlocksInFutureAggregatedOperations,
     pub remaining_chunks: Option<usize>,
+    /// Time passed since the prices of all the tokens were updated.
+    pub price_age: chrono::Duration,
 }
 
 impl Default for MockTickerInfo {
@@ -141,6 +150,7 @@ impl Default for MockTickerInfo {
                 blocks_to_execute: 0,
             },
             remaining_chunks: None,
+            price_age: chrono::Duration::zero(),
         }
     }
 }
@@ -167,7 +177,7 @@ impl FeeTickerInfo for MockTickerInfo {
             if TokenLike::Id(test_token.id) == token {
                 let token_price = TokenPrice {
                     usd_price: test_token.price_usd,
-                    last_updated: Utc::now(),
+                    last_updated: Utc::now() - self.price_age,
                 };
                 return Ok(token_price);
             }
@@ -331,7 +341,9 @@ fn test_ticker_subsidy() {
         TokenInMemoryCache::new(),
         chrono::Duration::seconds(100),
         BigDecimal::from(100),
+        BigDecimal::from(500),
         Default::default(),
+        test_price_max_age(),
     );
 
     let config = get_test_ticker_config();
@@ -453,7 +465,9 @@ fn test_ticker_formula() {
         TokenInMemoryCache::new(),
         chrono::Duration::seconds(100),
         BigDecimal::from(100),
+        BigDecimal::from(500),
         Default::default(),
+        test_price_max_age(),
     );
 
     let config = get_test_ticker_config();
@@ -688,7 +702,9 @@ fn test_zero_price_token_fee() {
         TokenInMemoryCache::new(),
         chrono::Duration::seconds(100),
         BigDecimal::from(100),
+        BigDecimal::from(500),
         Default::default(),
+        test_price_max_age(),
     );
 
     let config = get_test_ticker_config();
@@ -711,3 +727,82 @@ fn test_zero_price_token_fee() {
     ))
     .unwrap_err();
 }
+
+#[test]
+fn test_stale_fee_token_prices() {
+    let hex = TestToken::hex();
+    let cheap = TestToken::cheap();
+    // Both tokens are suitable for paying fees, but the market of the cheap one is thinner.
+    let market = vec![(hex.id, 600u32), (cheap.id, 200u32)]
+        .into_iter()
+        .map(|(token_id, volume)| {
+            let volume = TokenMarketVolume {
+                market_volume: Ratio::from_integer(volume.into()),
+                last_updated: Utc::now(),
+            };
+            (token_id, volume)
+        })
+        .collect();
+    let cache = TokenInMemoryCache::new().with_market(market);
+    let ticker = |cache: TokenInMemoryCache| {
+        let validator = FeeTokenValidator::new(
+            cache,
+            chrono::Duration::seconds(100),
+            BigDecimal::from(100),
+            BigDecimal::from(500),
+            Default::default(),
+            test_price_max_age(),
+        );
+        // The prices of all the tokens were updated 30 minutes ago.
+        let info = MockTickerInfo {
+            price_age: chrono::Duration::minutes(30),
+            ..Default::default()
+        };
+        FeeTicker::new(Box::new(info), get_test_ticker_config(), validator)
+    };
+    let fee = |ticker: &FeeTicker, token_id: TokenId| {
+        block_on(ticker.get_fee_from_ticker_in_wei(
+            TxFeeTypes::Transfer,
+            token_id.into(),
+            Address::default(),
+        ))
+    };
+    let assert_stale =
+        |result: Result<(), PriceError>, token_id: TokenId, max_age_secs: i64| match result {
+            Err(PriceError::StalePrice {
+                token_id: stale_token_id,
+                age_secs,
+                max_age_secs: stale_max_age_secs,
+                ..
+            }) => {
+                assert_eq!(stale_token_id, token_id);
+                assert!(age_secs >= 30 * 60, "{}", age_secs);
+                assert_eq!(stale_max_age_secs, max_age_secs);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        };
+
+    // The price of the highly liquid token is still acceptable.
+    let default_ticker = ticker(cache.clone());
+    block_on(default_ticker.check_fee_token_price(hex.id.into())).unwrap();
+    assert!(fee(&default_ticker, hex.id).is_ok());
+    assert_stale(
+        block_on(default_ticker.check_fee_token_price(cheap.id.into())),
+        cheap.id,
+        20 * 60,
+    );
+    let err = fee(&default_ticker, cheap.id).unwrap_err();
+    assert_stale(Err(err.downcast().unwrap()), cheap.id, 20 * 60);
+
+    // The operator overrides the defaults of the liquidity tiers.
+    let overridden = vec![(hex.id, 10 * 60), (cheap.id, 60 * 60)];
+    let overridden_ticker = ticker(cache.with_price_max_age(overridden.into_iter().collect()));
+    assert_stale(
+        block_on(overridden_ticker.check_fee_token_price(hex.id.into())),
+        hex.id,
+        10 * 60,
+    );
+    assert!(fee(&overridden_ticker, hex.id).is_err());
+    block_on(overridden_ticker.check_fee_token_price(cheap.id.into())).unwrap();
+    assert!(fee(&overridden_ticker, cheap.id).is_ok());
+}
@@ -0,0 +1,468 @@
+//! Token prices from several APIs.
+//!
+//! The APIs are queried concurrently, each one within its own timeout. The first valid price
+//! is used unless the quorum is configured, then the median of the first `quorum` valid prices
+//! is taken. The APIs failed several times in a row are queried only if none of the healthy ones
+//! returned the price, so a dead API doesn't slow down every update.
+
+// Built-in deps
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+// External deps
+use async_trait::async_trait;
+use futures::{stream::FuturesUnordered, StreamExt};
+use num::{BigUint, Zero};
+// Workspace deps
+use zksync_types::{Token, TokenPrice};
+// Local deps
+use super::{PriceError, TokenPriceAPI};
+
+/// The API is considered unhealthy after this number of consecutive failures.
+const UNHEALTHY_AFTER_FAILURES: u32 = 3;
+
+/// One of the APIs the prices are queried from.
+pub struct PriceSource {
+    name: String,
+    api: Box<dyn TokenPriceAPI + Send + Sync>,
+}
+
+impl PriceSource {
+    pub fn new(name: impl Into<String>, api: Box<dyn TokenPriceAPI + Send + Sync>) -> Self {
+        Self {
+            name: name.into(),
+            api,
+        }
+    }
+}
+
+impl fmt::Debug for PriceSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriceSource")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct SourceHealth {
+    consecutive_failures: u32,
+    last_latency: Duration,
+}
+
+impl SourceHealth {
+    fn is_healthy(&self) -> bool {
+        self.consecutive_failures < UNHEALTHY_AFTER_FAILURES
+    }
+}
+
+#[derive(Debug)]
+pub struct MultiSourcePriceAPI {
+    sources: Vec<PriceSource>,
+    timeout: Duration,
+    /// Number of the valid prices to take the median of, the first valid price is used if 0.
+    quorum: usize,
+    /// Health of the sources, in the same order as `sources`.
+    health: Mutex<Vec<SourceHealth>>,
+}
+
+impl MultiSourcePriceAPI {
+    pub fn new(sources: Vec<PriceSource>, timeout: Duration, quorum: usize) -> Self {
+        assert!(!sources.is_empty(), "At least one price source is required");
+        assert!(
+            quorum <= sources.len(),
+            "Price quorum exceeds the number of the price sources"
+        );
+        let health = Mutex::new(vec![SourceHealth::default(); sources.len()]);
+        Self {
+            sources,
+            timeout,
+            quorum,
+            health,
+        }
+    }
+
+    /// Returns the indices of the healthy sources and of the unhealthy ones, the ones with the
+    /// fewer failures and the faster responses go first. If all the sources are unhealthy,
+    /// they are all returned as the healthy ones.
+    fn ordered_sources(&self) -> (Vec<usize>, Vec<usize>) {
+        let health = self.health.lock().unwrap().clone();
+        let mut indices: Vec<usize> = (0..self.sources.len()).collect();
+        indices.sort_by_key(|&idx| (health[idx].consecutive_failures, health[idx].last_latency));
+
+        let (healthy, unhealthy): (Vec<_>, Vec<_>) = indices
+            .into_iter()
+            .partition(|&idx| health[idx].is_healthy());
+        if healthy.is_empty() {
+            (unhealthy, Vec::new())
+        } else {
+            (healthy, unhealthy)
+        }
+    }
+
+    fn record_response(&self, idx: usize, latency: Duration, success: bool) {
+        let name = self.sources[idx].name.clone();
+        metrics::histogram!("ticker.price_source.request", latency, "source" => name.clone());
+        if !success {
+            metrics::increment_counter!("ticker.price_source.failure", "source" => name);
+        }
+
+        let mut health = self.health.lock().unwrap();
+        let health = &mut health[idx];
+        health.last_latency = latency;
+        if success {
+            health.consecutive_failures = 0;
+        } else {
+            health.consecutive_failures += 1;
+        }
+    }
+
+    async fn query_source(
+        &self,
+        idx: usize,
+        token: &Token,
+    ) -> (usize, Duration, Result<TokenPrice, PriceError>) {
+        let source = &self.sources[idx];
+        let start = Instant::now();
+        let result = match tokio::time::timeout(self.timeout, source.api.get_price(token)).await {
+            Ok(Ok(price)) if price.usd_price.is_zero() => Err(PriceError::api_error(format!(
+                "{} returned zero price",
+                source.name
+            ))),
+            Ok(result) => result,
+            Err(_) => Err(PriceError::api_error(format!(
+                "{} didn't respond in {:?}",
+                source.name, self.timeout
+            ))),
+        };
+        (idx, start.elapsed(), result)
+    }
+
+    /// Queries the sources concurrently until the price is selected. The valid prices and
+    /// the errors are accumulated, so the next round continues where this one ended.
+    async fn query_round(
+        &self,
+        indices: &[usize],
+        token: &Token,
+        prices: &mut Vec<TokenPrice>,
+        errors: &mut Vec<PriceError>,
+    ) -> Option<TokenPrice> {
+        let mut requests: FuturesUnordered<_> = indices
+            .iter()
+            .map(|&idx| self.query_source(idx, token))
+            .collect();
+        while let Some((idx, latency, result)) = requests.next().await {
+            // Sources not listing the token are not penalized, the API itself is fine.
+            let responded = !matches!(&result, Err(PriceError::ApiError(_)));
+            self.record_response(idx, latency, responded);
+            match result {
+                Ok(price) => {
+                    prices.push(price);
+                    if prices.len() >= self.quorum {
+                        return Some(median_price(prices));
+                    }
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl TokenPriceAPI for MultiSourcePriceAPI {
+    async fn get_price(&self, token: &Token) -> Result<TokenPrice, PriceError> {
+        let (healthy, unhealthy) = self.ordered_sources();
+        let mut prices = Vec::new();
+        let mut errors = Vec::new();
+        for indices in [healthy, unhealthy] {
+            if indices.is_empty() {
+                continue;
+            }
+            if let Some(price) = self
+                .query_round(&indices, token, &mut prices, &mut errors)
+                .await
+            {
+                return Ok(price);
+            }
+        }
+
+        // The token is reported as missing only if none of the sources knows it,
+        // otherwise the stored price must be kept.
+        if prices.is_empty()
+            && errors
+                .iter()
+                .all(|err| matches!(err, PriceError::TokenNotFound(_)))
+        {
+            return Err(PriceError::token_not_found(format!(
+                "Token '{}' is not listed on any price source",
+                token.symbol
+            )));
+        }
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        Err(PriceError::api_error(format!(
+            "Got {} of {} required prices for token '{}': {}",
+            prices.len(),
+            self.quorum.max(1),
+            token.symbol,
+            errors.join("; ")
+        )))
+    }
+}
+
+/// Returns the median of the prices, the average of the two middle ones for the even number
+/// of prices. The oldest update time of the prices is reported.
+fn median_price(prices: &[TokenPrice]) -> TokenPrice {
+    let mut usd_prices: Vec<_> = prices.iter().map(|price| &price.usd_price).collect();
+    usd_prices.sort();
+    let middle = usd_prices.len() / 2;
+    let usd_price = if usd_prices.len() % 2 == 0 {
+        (usd_prices[middle - 1] + usd_prices[middle]) / BigUint::from(2u32)
+    } else {
+        usd_prices[middle].clone()
+    };
+    let last_updated = prices
+        .iter()
+        .map(|price| price.last_updated)
+        .min()
+        .expect("At least one price is required");
+
+    TokenPrice {
+        usd_price,
+        last_updated,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use num::rational::Ratio;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use zksync_types::{TokenId, TokenKind};
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    #[derive(Debug, Clone, Copy)]
+    enum MockResponse {
+        Price(u32),
+        NotFound,
+        Failure,
+    }
+
+    /// Price source responding with the given delay.
+    #[derive(Debug, Clone)]
+    struct MockPriceAPI {
+        delay: Duration,
+        response: MockResponse,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl MockPriceAPI {
+        fn new(delay_ms: u64, response: MockResponse) -> Self {
+            Self {
+                delay: Duration::from_millis(delay_ms),
+                response,
+                calls: Arc::default(),
+            }
+        }
+
+        fn source(&self) -> PriceSource {
+            PriceSource::new(format!("{:?}", self.response), Box::new(self.clone()))
+        }
+    }
+
+    #[async_trait]
+    impl TokenPriceAPI for MockPriceAPI {
+        async fn get_price(&self, _token: &Token) -> Result<TokenPrice, PriceError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            match self.response {
+                MockResponse::Price(price) => Ok(TokenPrice {
+                    usd_price: Ratio::from_integer(price.into()),
+                    last_updated: Utc::now(),
+                }),
+                MockResponse::NotFound => Err(PriceError::token_not_found("Unknown token")),
+                MockResponse::Failure => Err(PriceError::api_error("Service unavailable")),
+            }
+        }
+    }
+
+    fn token() -> Token {
+        Token::new(TokenId(1), Default::default(), "DAI", 18, TokenKind::ERC20)
+    }
+
+    fn price_api(mocks: &[MockPriceAPI], quorum: usize) -> MultiSourcePriceAPI {
+        let sources = mocks.iter().map(MockPriceAPI::source).collect();
+        MultiSourcePriceAPI::new(sources, TIMEOUT, quorum)
+    }
+
+    async fn usd_price(api: &MultiSourcePriceAPI) -> Result<Ratio<BigUint>, PriceError> {
+        api.get_price(&token()).await.map(|price| price.usd_price)
+    }
+
+    /// Checks that the price of the fastest valid source is used.
+    #[tokio::test]
+    async fn fastest_source_is_selected() {
+        let api = price_api(
+            &[
+                MockPriceAPI::new(100, MockResponse::Price(1)),
+                MockPriceAPI::new(10, MockResponse::Price(2)),
+                MockPriceAPI::new(0, MockResponse::Failure),
+                MockPriceAPI::new(0, MockResponse::Price(0)),
+            ],
+            0,
+        );
+        assert_eq!(
+            usd_price(&api).await.unwrap(),
+            Ratio::from_integer(2u32.into())
+        );
+
+        // Slow sources are cut by the timeout.
+        let api = price_api(
+            &[
+                MockPriceAPI::new(1000, MockResponse::Price(1)),
+                MockPriceAPI::new(0, MockResponse::Failure),
+            ],
+            0,
+        );
+        let start = Instant::now();
+        assert!(matches!(
+            usd_price(&api).await,
+            Err(PriceError::ApiError(_))
+        ));
+        assert!(start.elapsed() < Duration::from_millis(1000));
+    }
+
+    /// Checks that the token is reported as not found only if none of the sources knows it.
+    #[tokio::test]
+    async fn all_sources_failed() {
+        let api = price_api(
+            &[
+                MockPriceAPI::new(0, MockResponse::NotFound),
+                MockPriceAPI::new(0, MockResponse::NotFound),
+            ],
+            0,
+        );
+        assert!(matches!(
+            usd_price(&api).await,
+            Err(PriceError::TokenNotFound(_))
+        ));
+        assert!(api
+            .health
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|health| health.consecutive_failures == 0));
+
+        let api = price_api(
+            &[
+                MockPriceAPI::new(0, MockResponse::NotFound),
+                MockPriceAPI::new(1000, MockResponse::Price(1)),
+            ],
+            0,
+        );
+        assert!(matches!(
+            usd_price(&api).await,
+            Err(PriceError::ApiError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn quorum_median() {
+        let api = price_api(
+            &[
+                MockPriceAPI::new(0, MockResponse::Price(1)),
+                MockPriceAPI::new(10, MockResponse::Price(5)),
+                MockPriceAPI::new(20, MockResponse::Price(3)),
+                MockPriceAPI::new(1000, MockResponse::Price(100)),
+            ],
+            3,
+        );
+        assert_eq!(
+            usd_price(&api).await.unwrap(),
+            Ratio::from_integer(3u32.into())
+        );
+
+        // The average of the two middle prices is taken for the even quorum.
+        let api = price_api(
+            &[
+                MockPriceAPI::new(0, MockResponse::Price(1)),
+                MockPriceAPI::new(10, MockResponse::Price(4)),
+                MockPriceAPI::new(0, MockResponse::Failure),
+            ],
+            2,
+        );
+        assert_eq!(
+            usd_price(&api).await.unwrap(),
+            Ratio::new(5u32.into(), 2u32.into())
+        );
+
+        // The quorum is not reached.
+        let api = price_api(
+            &[
+                MockPriceAPI::new(0, MockResponse::Price(1)),
+                MockPriceAPI::new(0, MockResponse::NotFound),
+                MockPriceAPI::new(1000, MockResponse::Price(1)),
+            ],
+            2,
+        );
+        assert!(matches!(
+            usd_price(&api).await,
+            Err(PriceError::ApiError(_))
+        ));
+    }
+
+    /// Checks that the unhealthy sources are queried only if the healthy ones failed.
+    #[tokio::test]
+    async fn unhealthy_sources_are_queried_last() {
+        let failing = MockPriceAPI::new(0, MockResponse::Failure);
+        let working = MockPriceAPI::new(10, MockResponse::Price(1));
+        let api = price_api(&[failing.clone(), working.clone()], 0);
+
+        for _ in 0..UNHEALTHY_AFTER_FAILURES {
+            usd_price(&api).await.unwrap();
+        }
+        let failed_calls = failing.calls.load(Ordering::SeqCst);
+        assert_eq!(failed_calls, UNHEALTHY_AFTER_FAILURES as usize);
+        usd_price(&api).await.unwrap();
+        assert_eq!(failing.calls.load(Ordering::SeqCst), failed_calls);
+        assert_eq!(
+            working.calls.load(Ordering::SeqCst),
+            UNHEALTHY_AFTER_FAILURES as usize + 1
+        );
+
+        // If all the healthy sources fail, the unhealthy ones are tried too.
+        let api = price_api(
+            &[
+                MockPriceAPI::new(0, MockResponse::Price(1)),
+                MockPriceAPI::new(0, MockResponse::Failure),
+            ],
+            0,
+        );
+        api.health.lock().unwrap()[0].consecutive_failures = UNHEALTHY_AFTER_FAILURES;
+        assert_eq!(
+            usd_price(&api).await.unwrap(),
+            Ratio::from_integer(1u32.into())
+        );
+        assert!(api.health.lock().unwrap()[0].is_healthy());
+    }
+
+    #[test]
+    fn median_of_prices() {
+        let price = |usd_price: u32, secs_ago: i64| TokenPrice {
+            usd_price: Ratio::from_integer(usd_price.into()),
+            last_updated: Utc::now() - chrono::Duration::seconds(secs_ago),
+        };
+        let prices = [price(7, 10), price(1, 30), price(3, 20)];
+        let median = median_price(&prices);
+        assert_eq!(median.usd_price, Ratio::from_integer(3u32.into()));
+        assert_eq!(median.last_updated, prices[1].last_updated);
+
+        let median = median_price(&prices[..2]);
+        assert_eq!(median.usd_price, Ratio::from_integer(4u32.into()));
+    }
+}
@@ -4,6 +4,7 @@
 
 #[cfg(test)]
 use std::any::Any;
+use std::collections::HashMap;
 
 use std::time::Instant;
 // External deps
@@ -14,11 +15,11 @@ use num::rational::Ratio;
 use num::BigUint;
 // Workspace deps
 use zksync_storage::ConnectionPool;
-use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType;
 use zksync_types::{Address, Token, TokenId, TokenLike, TokenPrice};
 // Local deps
 use crate::fee_ticker::PriceError;
+use crate::utils::token_cache::TokenCache;
 
 pub trait FeeTickerClone {
     fn clone_box(&self) -> Box<dyn FeeTickerInfo>;
@@ -55,6 +56,25 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
     /// Get last price for token from ticker info
     async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError>;
 
+    /// Get last prices for several tokens from ticker info.
+    /// Tokens without a known price are omitted from the result.
+    async fn get_last_token_prices(
+        &self,
+        tokens: &[Token],
+    ) -> Result<HashMap<TokenId, TokenPrice>, PriceError> {
+        let mut prices = HashMap::with_capacity(tokens.len());
+        for token in tokens {
+            match self.get_last_token_price(TokenLike::Id(token.id)).await {
+                Ok(price) => {
+                    prices.insert(token.id, price);
+                }
+                Err(PriceError::TokenNotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(prices)
+    }
+
     /// Get current gas price in ETH
     async fn get_gas_price_wei(&self) -> Result<BigUint, anyhow::Error>;
 
@@ -68,15 +88,12 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
 #[derive(Clone)]
 pub struct TickerInfo {
     db: ConnectionPool,
-    token_db_cache: TokenDBCache,
+    tokens: TokenCache,
 }
 
 impl TickerInfo {
-    pub fn new(db: ConnectionPool) -> Self {
-        Self {
-            db,
-            token_db_cache: Default::default(),
-        }
+    pub fn new(db: ConnectionPool, tokens: TokenCache) -> Self {
+        Self { db, tokens }
     }
 }
 
@@ -166,31 +183,12 @@ impl FeeTickerInfo for TickerInfo {
     async fn get_last_token_price(&self, token: TokenLike) -> Result<TokenPrice, PriceError> {
         let start = Instant::now();
 
-        let token = {
-            // Try to find the token in the cache first.
-            if let Some(token) = self
-                .token_db_cache
-                .try_get_token_from_cache(token.clone())
-                .await
-            {
-                token
-            } else {
-                // Establish db connection and repeat the query, so the token is loaded
-                // from the db.
-                let mut storage = self
-                    .db
-                    .access_storage()
-                    .await
-                    .map_err(PriceError::db_error)?;
-                self.token_db_cache
-                    .get_token(&mut storage, token.clone())
-                    .await
-                    .map_err(PriceError::db_error)?
-                    .ok_or_else(|| {
-                        PriceError::token_not_found(format!("Token not found: {:?}", token))
-                    })?
-            }
-        };
+        let token = self
+            .tokens
+            .resolve_token(token.clone())
+            .await
+            .map_err(PriceError::db_error)?
+            .ok_or_else(|| PriceError::token_not_found(format!("Token not found: {:?}", token)))?;
 
         // TODO: remove hardcode for Matter Labs Trial Token (ZKS-63).
         if token.symbol == "MLTT" {
@@ -214,6 +212,39 @@ impl FeeTickerInfo for TickerInfo {
         Err(PriceError::db_error("No price stored in database"))
     }
 
+    /// Get last prices from ticker using a single database query
+    async fn get_last_token_prices(
+        &self,
+        tokens: &[Token],
+    ) -> Result<HashMap<TokenId, TokenPrice>, PriceError> {
+        let start = Instant::now();
+
+        let token_ids: Vec<TokenId> = tokens.iter().map(|token| token.id).collect();
+        let mut prices = self
+            .db
+            .access_storage()
+            .await
+            .map_err(PriceError::db_error)?
+            .tokens_schema()
+            .get_historical_ticker_prices(&token_ids)
+            .await
+            .map_err(PriceError::db_error)?;
+
+        // TODO: remove hardcode for Matter Labs Trial Token (ZKS-63).
+        for token in tokens.iter().filter(|token| token.symbol == "MLTT") {
+            prices.insert(
+                token.id,
+                TokenPrice {
+                    usd_price: Ratio::from_integer(1u32.into()),
+                    last_updated: Utc::now(),
+                },
+            );
+        }
+
+        metrics::histogram!("ticker_info.get_last_token_prices", start.elapsed());
+        Ok(prices)
+    }
+
     /// Get current gas price in ETH
     async fn get_gas_price_wei(&self) -> Result<BigUint, anyhow::Error> {
         let start = Instant::now();
@@ -237,21 +268,9 @@ impl FeeTickerInfo for TickerInfo {
 
     async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error> {
         let start = Instant::now();
-        // Try to find the token in the cache first.
-        if let Some(token) = self
-            .token_db_cache
-            .try_get_token_from_cache(token.clone())
-            .await
-        {
-            return Ok(token);
-        }
-
-        // Establish db connection and repeat the query, so the token is loaded
-        // from the db.
-        let mut storage = self.db.access_storage().await?;
         let result = self
-            .token_db_cache
-            .get_token(&mut storage, token.clone())
+            .tokens
+            .resolve_token(token.clone())
             .await?
             .ok_or_else(|| format_err!("Token not found: {:?}", token));
         metrics::histogram!("ticker_info.get_token", start.elapsed());
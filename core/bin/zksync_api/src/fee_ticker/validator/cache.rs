@@ -7,6 +7,8 @@ use zksync_token_db_cache::TokenDBCache;
 use zksync_storage::ConnectionPool;
 use zksync_types::{tokens::TokenMarketVolume, Token, TokenId, TokenLike};
 
+use crate::utils::token_cache::TokenCache;
+
 #[derive(Debug, Clone)]
 pub(crate) enum TokenCacheWrapper {
     DB(TokenInDBCache),
@@ -16,7 +18,7 @@ pub(crate) enum TokenCacheWrapper {
 
 #[derive(Debug, Clone)]
 pub(crate) struct TokenInDBCache {
-    inner: TokenDBCache,
+    inner: TokenCache,
     pool: ConnectionPool,
 }
 
@@ -25,10 +27,11 @@ pub(crate) struct TokenInDBCache {
 pub struct TokenInMemoryCache {
     tokens: Arc<Mutex<HashMap<TokenLike, Token>>>,
     market: Arc<Mutex<HashMap<TokenId, TokenMarketVolume>>>,
+    price_max_age: Arc<Mutex<HashMap<TokenId, u64>>>,
 }
 
 impl TokenInDBCache {
-    pub fn new(pool: ConnectionPool, inner: TokenDBCache) -> Self {
+    pub fn new(pool: ConnectionPool, inner: TokenCache) -> Self {
         Self { inner, pool }
     }
 }
@@ -52,6 +55,13 @@ impl TokenInMemoryCache {
             ..self
         }
     }
+
+    pub fn with_price_max_age(self, price_max_age: HashMap<TokenId, u64>) -> Self {
+        Self {
+            price_max_age: Arc::new(Mutex::new(price_max_age)),
+            ..self
+        }
+    }
 }
 
 #[cfg(test)]
@@ -61,8 +71,8 @@ impl From<TokenInMemoryCache> for TokenCacheWrapper {
     }
 }
 
-impl From<(ConnectionPool, TokenDBCache)> for TokenCacheWrapper {
-    fn from(value: (ConnectionPool, TokenDBCache)) -> Self {
+impl From<(ConnectionPool, TokenCache)> for TokenCacheWrapper {
+    fn from(value: (ConnectionPool, TokenCache)) -> Self {
         Self::DB(TokenInDBCache::new(value.0, value.1))
     }
 }
@@ -70,23 +80,7 @@ impl From<(ConnectionPool, TokenDBCache)> for TokenCacheWrapper {
 impl TokenCacheWrapper {
     pub async fn get_token(&self, token_like: TokenLike) -> anyhow::Result<Option<Token>> {
         match self {
-            Self::DB(cache) => {
-                // Try to find the token in the cache first.
-                if let Some(token) = cache
-                    .inner
-                    .try_get_token_from_cache(token_like.clone())
-                    .await
-                {
-                    return Ok(Some(token));
-                }
-
-                // Establish db connection and repeat the query, so the token is loaded
-                // from the db.
-                cache
-                    .inner
-                    .get_token(&mut cache.pool.access_storage().await?, token_like)
-                    .await
-            }
+            Self::DB(cache) => cache.inner.resolve_token(token_like).await,
             #[cfg(test)]
             Self::Memory(cache) => Ok(cache.tokens.lock().await.get(&token_like).cloned()),
         }
@@ -130,6 +124,19 @@ impl TokenCacheWrapper {
             }
         }
     }
+
+    /// Returns the maximum age of the token price set by the operator, in seconds.
+    pub async fn get_price_max_age(&self, token_id: TokenId) -> anyhow::Result<Option<u64>> {
+        match self {
+            Self::DB(cache) => {
+                let mut storage = cache.pool.access_storage().await?;
+                storage.tokens_schema().get_price_max_age(token_id).await
+            }
+            #[cfg(test)]
+            Self::Memory(cache) => Ok(cache.price_max_age.lock().await.get(&token_id).copied()),
+        }
+    }
+
     pub async fn get_all_tokens(&self) -> anyhow::Result<Vec<Token>> {
         match self {
             Self::DB(cache) => {
@@ -14,9 +14,10 @@ use bigdecimal::BigDecimal;
 use chrono::Utc;
 
 // Workspace uses
+use zksync_api_types::v02::fee::FeeTokenRequirement;
 use zksync_types::{
     tokens::{Token, TokenLike, TokenMarketVolume},
-    Address,
+    Address, TokenId, TokenKind,
 };
 
 // Local uses
@@ -92,6 +93,31 @@ impl<W: TokenWatcher> MarketUpdater<W> {
     }
 }
 
+/// Maximum age of the token price to calculate the fees with, by the liquidity tier of the token.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceMaxAge {
+    pub highly_liquid: chrono::Duration,
+    pub liquid: chrono::Duration,
+}
+
+impl PriceMaxAge {
+    pub fn from_config(config: &zksync_config::TickerConfig) -> Self {
+        Self {
+            highly_liquid: chrono::Duration::seconds(
+                config.highly_liquid_max_price_age_secs as i64,
+            ),
+            liquid: chrono::Duration::seconds(config.liquid_max_price_age_secs as i64),
+        }
+    }
+
+    fn for_tier(&self, tier: FeeTokenRequirement) -> chrono::Duration {
+        match tier {
+            FeeTokenRequirement::HighlyLiquid => self.highly_liquid,
+            FeeTokenRequirement::Liquid => self.liquid,
+        }
+    }
+}
+
 /// Fee token validator decides whether certain ERC20 token is suitable for paying fees.
 #[derive(Debug, Clone)]
 pub struct FeeTokenValidator {
@@ -100,6 +126,8 @@ pub struct FeeTokenValidator {
     tokens_cache: TokenCacheWrapper,
     available_time: chrono::Duration,
     liquidity_volume: BigDecimal,
+    strict_liquidity_volume: BigDecimal,
+    price_max_age: PriceMaxAge,
 }
 
 impl FeeTokenValidator {
@@ -107,47 +135,107 @@ impl FeeTokenValidator {
         cache: impl Into<TokenCacheWrapper>,
         available_time: chrono::Duration,
         liquidity_volume: BigDecimal,
+        strict_liquidity_volume: BigDecimal,
         unconditionally_valid: HashSet<Address>,
+        price_max_age: PriceMaxAge,
     ) -> Self {
         Self {
             unconditionally_valid,
             tokens_cache: cache.into(),
             available_time,
             liquidity_volume,
+            strict_liquidity_volume,
+            price_max_age,
         }
     }
 
-    /// Returns `true` if token can be used to pay fees.
-    pub(crate) async fn token_allowed(&self, token: TokenLike) -> anyhow::Result<bool> {
+    /// Returns `true` if token meets the requirement and can be used to pay fees.
+    pub(crate) async fn token_allowed(
+        &self,
+        token: TokenLike,
+        requirement: FeeTokenRequirement,
+    ) -> anyhow::Result<bool> {
         let token = self.resolve_token(token).await?;
         if let Some(token) = token {
-            if self.unconditionally_valid.contains(&token.address) {
-                return Ok(true);
-            }
-            self.check_token(token).await
+            Ok(self.token_liquidity(token).await? >= Some(requirement))
         } else {
             // Unknown tokens aren't suitable for our needs, obviously.
             Ok(false)
         }
     }
 
+    /// Returns the tokens that can be used to pay fees along with the strictest requirement
+    /// they meet, sorted by the token ID.
+    pub(crate) async fn allowed_tokens(
+        &self,
+    ) -> anyhow::Result<Vec<(TokenId, FeeTokenRequirement)>> {
+        let mut tokens = self.tokens_cache.get_all_tokens().await?;
+        tokens.sort_unstable_by_key(|token| token.id);
+        tokens.dedup_by_key(|token| token.id);
+
+        let mut allowed = Vec::new();
+        for token in tokens {
+            if token.kind == TokenKind::NFT {
+                continue;
+            }
+            let id = token.id;
+            if let Some(requirement) = self.token_liquidity(token).await? {
+                allowed.push((id, requirement));
+            }
+        }
+        Ok(allowed)
+    }
+
+    /// Returns the strictest requirement the token meets, `None` if it can't be used to pay fees.
+    async fn token_liquidity(&self, token: Token) -> anyhow::Result<Option<FeeTokenRequirement>> {
+        if self.unconditionally_valid.contains(&token.address) {
+            return Ok(Some(FeeTokenRequirement::HighlyLiquid));
+        }
+        self.check_token(token).await
+    }
+
+    /// Returns the maximum age of the token price to calculate the fees with, `None` if the token
+    /// can't be used to pay fees. The default is chosen by the liquidity tier the token meets
+    /// and may be overridden by the operator.
+    pub(crate) async fn price_max_age(
+        &self,
+        token: &Token,
+    ) -> anyhow::Result<Option<chrono::Duration>> {
+        let tier = match self.token_liquidity(token.clone()).await? {
+            Some(tier) => tier,
+            None => return Ok(None),
+        };
+        let max_age = match self.tokens_cache.get_price_max_age(token.id).await? {
+            Some(max_age_secs) => chrono::Duration::seconds(max_age_secs as i64),
+            None => self.price_max_age.for_tier(tier),
+        };
+        Ok(Some(max_age))
+    }
+
     async fn resolve_token(&self, token: TokenLike) -> anyhow::Result<Option<Token>> {
         self.tokens_cache.get_token(token).await
     }
 
-    async fn check_token(&self, token: Token) -> anyhow::Result<bool> {
+    async fn check_token(&self, token: Token) -> anyhow::Result<Option<FeeTokenRequirement>> {
         let start = Instant::now();
         let volume = match self.get_token_market_volume(&token).await? {
             Some(volume) => volume,
-            None => return Ok(false),
+            None => return Ok(None),
         };
 
         if Utc::now() - volume.last_updated > self.available_time {
             vlog::warn!("Token market amount for {} is not relevant", &token.symbol)
         }
-        let allowed = ratio_to_big_decimal(&volume.market_volume, 2) >= self.liquidity_volume;
+        let market_volume = ratio_to_big_decimal(&volume.market_volume, 2);
+        let requirement = if market_volume >= self.strict_liquidity_volume {
+            Some(FeeTokenRequirement::HighlyLiquid)
+        } else if market_volume >= self.liquidity_volume {
+            Some(FeeTokenRequirement::Liquid)
+        } else {
+            None
+        };
         metrics::histogram!("ticker.validator.check_token", start.elapsed());
-        Ok(allowed)
+        Ok(requirement)
     }
 
     async fn get_token_market_volume(
@@ -198,7 +286,7 @@ mod tests {
         let phnx_token = Token::new(TokenId(2), phnx_token_address, "PHNX", 18, TokenKind::ERC20);
 
         let eth_address = Address::from_str("0000000000000000000000000000000000000000").unwrap();
-        let eth_token = Token::new(TokenId(2), eth_address, "ETH", 18, TokenKind::ERC20);
+        let eth_token = Token::new(TokenId(0), eth_address, "ETH", 18, TokenKind::ERC20);
         let all_tokens = vec![dai_token.clone(), phnx_token.clone()];
 
         let mut market = HashMap::new();
@@ -220,7 +308,7 @@ mod tests {
         let mut tokens = HashMap::new();
         tokens.insert(TokenLike::Address(dai_token_address), dai_token.clone());
         tokens.insert(TokenLike::Address(phnx_token_address), phnx_token.clone());
-        tokens.insert(TokenLike::Address(eth_address), eth_token);
+        tokens.insert(TokenLike::Address(eth_address), eth_token.clone());
         let mut amounts = HashMap::new();
         amounts.insert(dai_token_address, BigDecimal::from(200));
         amounts.insert(phnx_token_address, BigDecimal::from(10));
@@ -239,7 +327,12 @@ mod tests {
             cache.clone(),
             chrono::Duration::seconds(100),
             BigDecimal::from(100),
+            BigDecimal::from(500),
             unconditionally_valid,
+            PriceMaxAge {
+                highly_liquid: chrono::Duration::seconds(3600),
+                liquid: chrono::Duration::seconds(900),
+            },
         );
 
         let mut updater = MarketUpdater::new(cache, watcher);
@@ -268,20 +361,42 @@ mod tests {
             big_decimal_to_ratio(&BigDecimal::from(10)).unwrap()
         );
 
-        let dai_allowed = validator
-            .token_allowed(TokenLike::Address(dai_token_address))
-            .await
-            .unwrap();
-        let phnx_allowed = validator
-            .token_allowed(TokenLike::Address(phnx_token_address))
-            .await
-            .unwrap();
-        let eth_allowed = validator
-            .token_allowed(TokenLike::Address(eth_address))
-            .await
-            .unwrap();
-        assert!(dai_allowed);
-        assert!(!phnx_allowed);
-        assert!(eth_allowed);
+        let allowed = |address, requirement| {
+            let validator = validator.clone();
+            async move {
+                validator
+                    .token_allowed(TokenLike::Address(address), requirement)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert!(allowed(dai_token_address, FeeTokenRequirement::Liquid).await);
+        assert!(!allowed(dai_token_address, FeeTokenRequirement::HighlyLiquid).await);
+        assert!(!allowed(phnx_token_address, FeeTokenRequirement::Liquid).await);
+        assert!(allowed(eth_address, FeeTokenRequirement::HighlyLiquid).await);
+
+        let allowed_tokens = validator.allowed_tokens().await.unwrap();
+        assert_eq!(
+            allowed_tokens,
+            vec![
+                (eth_token.id, FeeTokenRequirement::HighlyLiquid),
+                (dai_token.id, FeeTokenRequirement::Liquid),
+            ]
+        );
+
+        // The maximum age of the price follows the liquidity tier the token meets.
+        let price_max_age = |token| {
+            let validator = validator.clone();
+            async move { validator.price_max_age(&token).await.unwrap() }
+        };
+        assert_eq!(
+            price_max_age(eth_token.clone()).await,
+            Some(chrono::Duration::seconds(3600))
+        );
+        assert_eq!(
+            price_max_age(dai_token.clone()).await,
+            Some(chrono::Duration::seconds(900))
+        );
+        assert_eq!(price_max_age(phnx_token).await, None);
     }
 }
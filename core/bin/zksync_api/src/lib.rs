@@ -2,6 +2,7 @@
 #![allow(clippy::derive_partial_eq_without_eq, clippy::needless_question_mark)]
 
 pub mod api_server;
+pub mod core_api_client;
 pub mod eth_checker;
 pub mod fee_ticker;
 pub mod signature_checker;
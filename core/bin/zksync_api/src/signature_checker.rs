@@ -3,6 +3,12 @@
 //! Main routine of this module operates a multithreaded event loop,
 //! which is used to spawn concurrent tasks to efficiently check the
 //! transactions signatures.
+//!
+//! CPU-bound parts of the check (zkSync signatures verification and Ethereum
+//! signers recovery) are performed on the blocking thread pool, so the async
+//! runtime is not stalled by large batches. Transactions of the batch are
+//! verified in parallel, but the error of the first failing transaction is
+//! always reported, so the result doesn't depend on the order of execution.
 
 // Built-in uses
 use std::collections::HashSet;
@@ -11,8 +17,9 @@ use std::time::Instant;
 // External uses
 use futures::{
     channel::{mpsc, oneshot},
-    StreamExt,
+    future, StreamExt,
 };
+use rayon::prelude::*;
 use tokio::task::JoinHandle;
 
 // Workspace uses
@@ -33,6 +40,8 @@ pub enum TxVariant {
     Batch(Vec<SignedZkSyncTx>, Option<EthBatchSignData>),
     Order(Box<Order>),
     Toggle2FA,
+    TxCancellation,
+    AccountLock,
 }
 
 /// Wrapper on a `TxVariant` which guarantees that (a batch of)
@@ -53,7 +62,11 @@ impl VerifiedTx {
     ) -> Result<Self, TxAddError> {
         verify_eth_signature(&request_data, eth_checker).await?;
         let mut tx_variant = request_data.get_tx_variant();
-        verify_tx_correctness(&mut tx_variant)?;
+        let tx_variant = tokio::task::spawn_blocking(move || {
+            verify_tx_correctness(&mut tx_variant).map(|_| tx_variant)
+        })
+        .await
+        .expect("Transaction correctness check panicked")?;
 
         Ok(Self(tx_variant))
     }
@@ -71,6 +84,10 @@ impl VerifiedTx {
             TxVariant::Batch(_, _) => panic!("called `unwrap_tx` on a `Batch` value"),
             TxVariant::Order(_) => panic!("called `unwrap_tx` on an `Order` value"),
             TxVariant::Toggle2FA => panic!("called `unwrap_tx` on an `Toggle2FA` value"),
+            TxVariant::TxCancellation => {
+                panic!("called `unwrap_tx` on a `TxCancellation` value")
+            }
+            TxVariant::AccountLock => panic!("called `unwrap_tx` on an `AccountLock` value"),
         }
     }
 
@@ -81,6 +98,10 @@ impl VerifiedTx {
             TxVariant::Tx(_) => panic!("called `unwrap_batch` on a `Tx` value"),
             TxVariant::Order(_) => panic!("called `unwrap_batch` on an `Order` value"),
             TxVariant::Toggle2FA => panic!("called `unwrap_batch` on an `Toggle2FA` value"),
+            TxVariant::TxCancellation => {
+                panic!("called `unwrap_batch` on a `TxCancellation` value")
+            }
+            TxVariant::AccountLock => panic!("called `unwrap_batch` on an `AccountLock` value"),
         }
     }
 }
@@ -112,12 +133,18 @@ async fn verify_eth_signature(
                 verify_eth_signature_txs_batch(txs, accounts, batch_sign_data, eth_checker).await?;
             }
             // In case there're signatures provided for some of transactions
-            // we still verify them.
-            for ((tx, &account), token) in
-                txs.iter().zip(accounts.iter()).zip(tokens.iter().cloned())
-            {
-                verify_eth_signature_single_tx(tx, account, token, eth_checker).await?;
-            }
+            // we still verify them. Checks are performed concurrently, but the
+            // error of the first failing transaction is reported.
+            let results = future::join_all(
+                txs.iter()
+                    .zip(accounts.iter())
+                    .zip(tokens.iter().cloned())
+                    .map(|((tx, &account), token)| {
+                        verify_eth_signature_single_tx(tx, account, token, eth_checker)
+                    }),
+            )
+            .await;
+            results.into_iter().collect::<Result<(), _>>()?;
         }
         RequestData::Order(request) => {
             let signature_correct = verify_ethereum_signature(
@@ -131,11 +158,13 @@ async fn verify_eth_signature(
                 return Err(TxAddError::IncorrectEthSignature);
             }
         }
-        RequestData::Toggle2FA(request) => {
+        RequestData::Toggle2FA(Toggle2FARequest { sign_data, sender })
+        | RequestData::TxCancellation(TxCancellationRequest { sign_data, sender })
+        | RequestData::AccountLock(AccountLockRequest { sign_data, sender }) => {
             let signature_correct = verify_ethereum_signature(
-                &request.sign_data.signature,
-                &request.sign_data.message,
-                request.sender,
+                &sign_data.signature,
+                &sign_data.message,
+                *sender,
                 eth_checker,
             )
             .await;
@@ -158,7 +187,13 @@ async fn verify_ethereum_signature(
 ) -> bool {
     let signer_account = match eth_signature {
         TxEthSignature::EthereumSignature(packed_signature) => {
-            packed_signature.signature_recover_signer_from_raw_message(message)
+            let packed_signature = packed_signature.clone();
+            let message = message.to_vec();
+            tokio::task::spawn_blocking(move || {
+                packed_signature.signature_recover_signer_from_raw_message(&message)
+            })
+            .await
+            .expect("Ethereum signer recovery panicked")
         }
         TxEthSignature::EIP1271Signature(signature) => {
             return eth_checker
@@ -301,18 +336,30 @@ fn verify_tx_correctness(tx: &mut TxVariant) -> Result<(), TxAddError> {
             tx.tx.check_correctness()?;
         }
         TxVariant::Batch(batch, _) => {
-            for tx in batch.iter_mut() {
-                tx.tx.check_correctness()?;
-            }
+            verify_batch_correctness(batch)?;
         }
         TxVariant::Order(order) => order
             .check_correctness()
             .map_err(|err| TxAddError::IncorrectTx(TransactionError::OrderError(err)))?,
-        TxVariant::Toggle2FA => {} // There is no data to check correctness of
+        // There is no data to check correctness of
+        TxVariant::Toggle2FA | TxVariant::TxCancellation | TxVariant::AccountLock => {}
     }
     Ok(())
 }
 
+/// Verifies the correctness of the batch transactions in parallel using the current `rayon` pool.
+/// The error of the transaction with the lowest index is returned, so the result is the same
+/// as for the sequential check regardless of the amount of threads.
+pub fn verify_batch_correctness(batch: &mut [SignedZkSyncTx]) -> Result<(), TxAddError> {
+    let results: Vec<_> = batch
+        .par_iter_mut()
+        .map(|tx| tx.tx.check_correctness())
+        .collect();
+    results.into_iter().collect::<Result<(), _>>()?;
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct TxRequest {
     pub tx: SignedZkSyncTx,
@@ -346,6 +393,20 @@ pub struct Toggle2FARequest {
     pub sender: Address,
 }
 
+/// Request of the transaction sender to cancel the transaction.
+#[derive(Debug)]
+pub struct TxCancellationRequest {
+    pub sign_data: EthSignData,
+    pub sender: Address,
+}
+
+/// Request of the account owner to lock or unlock the account.
+#[derive(Debug)]
+pub struct AccountLockRequest {
+    pub sign_data: EthSignData,
+    pub sender: Address,
+}
+
 /// Request for the signature check.
 #[derive(Debug)]
 pub struct VerifySignatureRequest {
@@ -360,6 +421,8 @@ pub enum RequestData {
     Batch(BatchRequest),
     Order(OrderRequest),
     Toggle2FA(Toggle2FARequest),
+    TxCancellation(TxCancellationRequest),
+    AccountLock(AccountLockRequest),
 }
 
 impl RequestData {
@@ -371,6 +434,8 @@ impl RequestData {
             }
             RequestData::Order(request) => TxVariant::Order(request.order.clone()),
             RequestData::Toggle2FA(_) => TxVariant::Toggle2FA,
+            RequestData::TxCancellation(_) => TxVariant::TxCancellation,
+            RequestData::AccountLock(_) => TxVariant::AccountLock,
         }
     }
 }
@@ -400,3 +465,93 @@ pub fn start_sign_checker(
     }
     tokio::spawn(checker_routine(input, eth_checker))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        tx::{
+            error::{WRONG_SIGNATURE, WRONG_TO_ADDRESS},
+            TimeRange,
+        },
+        AccountId, TokenId,
+    };
+
+    fn signed_transfers(count: usize) -> Vec<SignedZkSyncTx> {
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(1)));
+        (0..count)
+            .map(|_| {
+                let (transfer, _) = account.sign_transfer(
+                    TokenId(0),
+                    "ETH",
+                    100u64.into(),
+                    10u64.into(),
+                    &Address::random(),
+                    None,
+                    true,
+                    TimeRange::new(0, u64::MAX),
+                );
+                ZkSyncTx::from(transfer).into()
+            })
+            .collect()
+    }
+
+    fn corrupt_signature(tx: &mut SignedZkSyncTx) {
+        match &mut tx.tx {
+            ZkSyncTx::Transfer(transfer) => transfer.amount += 1u64,
+            _ => unreachable!(),
+        }
+    }
+
+    fn corrupt_recipient(tx: &mut SignedZkSyncTx) {
+        match &mut tx.tx {
+            ZkSyncTx::Transfer(transfer) => transfer.to = Address::zero(),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Checks the batch sequentially, the way it was done before the parallel verification.
+    fn verify_batch_sequentially(batch: &mut [SignedZkSyncTx]) -> Result<(), TxAddError> {
+        for tx in batch.iter_mut() {
+            tx.tx.check_correctness()?;
+        }
+        Ok(())
+    }
+
+    fn check_batch(batch: &[SignedZkSyncTx]) -> Result<(), String> {
+        let expected = verify_batch_sequentially(&mut batch.to_vec()).map_err(|e| e.to_string());
+        for threads in &[1, 2, 8] {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(*threads)
+                .build()
+                .unwrap();
+            let result = pool
+                .install(|| verify_batch_correctness(&mut batch.to_vec()))
+                .map_err(|e| e.to_string());
+            assert_eq!(result, expected, "threads: {}", threads);
+        }
+        expected
+    }
+
+    /// Checks that the parallel verification of the batch makes the same decision
+    /// and reports the error of the same transaction as the sequential one.
+    #[test]
+    fn parallel_batch_verification() {
+        let batch = signed_transfers(20);
+        assert_eq!(check_batch(&batch), Ok(()));
+
+        let mut bad_batch = batch.clone();
+        corrupt_signature(&mut bad_batch[3]);
+        corrupt_recipient(&mut bad_batch[15]);
+        let error = check_batch(&bad_batch).unwrap_err();
+        assert!(error.ends_with(WRONG_SIGNATURE), "{}", error);
+
+        let mut bad_batch = batch;
+        corrupt_recipient(&mut bad_batch[3]);
+        corrupt_signature(&mut bad_batch[15]);
+        let error = check_batch(&bad_batch).unwrap_err();
+        assert!(error.ends_with(WRONG_TO_ADDRESS), "{}", error);
+    }
+}
@@ -15,3 +15,24 @@ pub enum Toggle2FAError {
     #[error("Request to enable 2FA should not have PubKeyHash field set")]
     UnusedPubKeyHash,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Error, PartialEq)]
+pub enum AccountLockError {
+    #[error("Database unavailable")]
+    DbError,
+
+    #[error("Account is not found")]
+    AccountNotFound,
+
+    #[error("Account is not locked")]
+    NotLocked,
+
+    #[error("Request is signed before the latest lock or unlock request of the account")]
+    OutdatedRequest,
+
+    #[error("Request to unlock the account should be signed either with the Ethereum key or with the signing key of the account")]
+    MissingSignature,
+
+    #[error("Signature doesn't match the current signing key of the account")]
+    IncorrectSignature,
+}
@@ -0,0 +1,95 @@
+// Built-in uses
+use std::collections::HashMap;
+
+// External uses
+use chrono::Utc;
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_api_types::v02::account::AccountLockInfo;
+use zksync_storage::{account_locks::records::AccountLock, ConnectionPool};
+use zksync_types::AccountId;
+
+// Local uses
+use super::listener_cache::ListenerCache;
+
+/// PostgreSQL channel that gets notified on every change of the account locks.
+pub const ACCOUNT_LOCKS_CHANNEL: &str = "account_locks_channel";
+
+/// Cached locks of the accounts which owners have frozen the outgoing transactions.
+///
+/// The locks are stored in the database and changed on request of the owners, every change
+/// invalidates the cache. Locks with the scheduled unlock expire without any change of
+/// the database, so the expiration is checked on every access.
+#[derive(Debug, Clone)]
+pub struct AccountLocks(ListenerCache<HashMap<AccountId, AccountLock>>);
+
+impl AccountLocks {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self(ListenerCache::new(
+            "Account locks",
+            ACCOUNT_LOCKS_CHANNEL,
+            pool,
+            load,
+        ))
+    }
+
+    /// Returns the active lock of the account, if any.
+    pub async fn get(&self, account_id: AccountId) -> anyhow::Result<Option<AccountLock>> {
+        let locks = self.0.get().await?;
+        Ok(locks
+            .get(&account_id)
+            .filter(|lock| lock.is_active_at(Utc::now()))
+            .cloned())
+    }
+
+    /// Returns the first of the accounts that is locked, if any.
+    pub async fn find_locked(
+        &self,
+        account_ids: &[AccountId],
+    ) -> anyhow::Result<Option<AccountId>> {
+        let locks = self.0.get().await?;
+        if locks.is_empty() {
+            return Ok(None);
+        }
+        let now = Utc::now();
+        Ok(account_ids.iter().copied().find(|account_id| {
+            locks
+                .get(account_id)
+                .map_or(false, |lock| lock.is_active_at(now))
+        }))
+    }
+
+    /// Forces the locks to be reloaded from the database on the next access.
+    pub async fn invalidate(&self) {
+        self.0.invalidate().await;
+    }
+
+    /// Spawns a task that invalidates the cache every time the account locks
+    /// are changed in the database.
+    pub fn spawn_invalidation_listener(&self) -> JoinHandle<()> {
+        self.0.spawn_invalidation_listener()
+    }
+}
+
+async fn load(pool: ConnectionPool) -> anyhow::Result<HashMap<AccountId, AccountLock>> {
+    let locks = pool
+        .access_storage()
+        .await?
+        .account_locks_schema()
+        .load_account_locks()
+        .await?;
+
+    Ok(locks
+        .into_iter()
+        .map(|lock| (lock.account_id, lock))
+        .collect())
+}
+
+/// Converts the stored lock into the one reported by the API.
+pub fn lock_info(lock: AccountLock) -> AccountLockInfo {
+    AccountLockInfo {
+        locked_at: lock.locked_at,
+        unlocks_at: lock.unlocks_at,
+    }
+}
@@ -0,0 +1,60 @@
+// Built-in uses
+use std::{collections::HashSet, sync::Arc};
+
+// External uses
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::Address;
+
+// Local uses
+use super::listener_cache::ListenerCache;
+
+/// PostgreSQL channel that gets notified on every change of the banned addresses.
+pub const BANNED_ADDRESSES_CHANNEL: &str = "banned_addresses_channel";
+
+/// Cached set of the addresses which are not allowed to submit transactions or receive funds.
+///
+/// The list is stored in the database and managed via the admin API, every change
+/// invalidates the cache, so the new list is used without restarting the server.
+#[derive(Debug, Clone)]
+pub struct BannedAddresses(ListenerCache<HashSet<Address>>);
+
+impl BannedAddresses {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self(ListenerCache::new(
+            "Banned addresses",
+            BANNED_ADDRESSES_CHANNEL,
+            pool,
+            load,
+        ))
+    }
+
+    /// Returns the set of currently banned addresses.
+    pub async fn get(&self) -> anyhow::Result<Arc<HashSet<Address>>> {
+        self.0.get().await
+    }
+
+    /// Forces the list to be reloaded from the database on the next access.
+    pub async fn invalidate(&self) {
+        self.0.invalidate().await;
+    }
+
+    /// Spawns a task that invalidates the cache every time the list of
+    /// banned addresses is changed in the database.
+    pub fn spawn_invalidation_listener(&self) -> JoinHandle<()> {
+        self.0.spawn_invalidation_listener()
+    }
+}
+
+async fn load(pool: ConnectionPool) -> anyhow::Result<HashSet<Address>> {
+    let addresses = pool
+        .access_storage()
+        .await?
+        .banned_addresses_schema()
+        .load_banned_addresses()
+        .await?;
+
+    Ok(addresses.into_iter().map(|banned| banned.address).collect())
+}
@@ -7,14 +7,29 @@ use zksync_storage::{chain::block::records::StorageBlockDetails, ConnectionPool,
 use zksync_types::BlockNumber;
 
 // Local uses
-use super::shared_lru_cache::AsyncLruCache;
+use super::{disk_cache::DiskCache, shared_lru_cache::AsyncLruCache};
+
+const DISK_CACHE_NAMESPACE: &str = "blocks";
 
 #[derive(Clone, Debug)]
-pub struct BlockDetailsCache(AsyncLruCache<BlockNumber, StorageBlockDetails>);
+pub struct BlockDetailsCache {
+    cache: AsyncLruCache<BlockNumber, StorageBlockDetails>,
+    /// Verified blocks are written through to the disk cache if it's configured,
+    /// so they are not loaded from the database again after the restart.
+    disk_cache: Option<DiskCache>,
+}
 
 impl BlockDetailsCache {
     pub fn new(capacity: usize) -> Self {
-        Self(AsyncLruCache::new(capacity))
+        Self {
+            cache: AsyncLruCache::new(capacity),
+            disk_cache: None,
+        }
+    }
+
+    pub fn with_disk_cache(mut self, disk_cache: Option<DiskCache>) -> Self {
+        self.disk_cache = disk_cache;
+        self
     }
 
     pub async fn get<'a>(
@@ -22,9 +37,16 @@ impl BlockDetailsCache {
         pool: &ConnectionPool,
         block_number: BlockNumber,
     ) -> QueryResult<Option<StorageBlockDetails>> {
-        if let Some(block) = self.0.get(&block_number).await {
+        if let Some(block) = self.cache.get(&block_number).await {
             return Ok(Some(block));
         }
+        if let Some(disk_cache) = &self.disk_cache {
+            let key = block_number.to_string();
+            if let Some(block) = disk_cache.get::<StorageBlockDetails>(DISK_CACHE_NAMESPACE, &key) {
+                self.cache.insert(block_number, block.clone()).await;
+                return Ok(Some(block));
+            }
+        }
 
         let mut storage = pool.access_storage().await?;
         let blocks = storage
@@ -41,7 +63,10 @@ impl BlockDetailsCache {
 
             // It makes sense to store in cache only fully verified blocks.
             if block.is_verified() {
-                self.0.insert(block_number, block.clone()).await;
+                if let Some(disk_cache) = &self.disk_cache {
+                    disk_cache.insert(DISK_CACHE_NAMESPACE, &block_number.to_string(), &block);
+                }
+                self.cache.insert(block_number, block.clone()).await;
             }
             Ok(Some(block))
         } else {
@@ -0,0 +1,84 @@
+// Built-in uses
+use std::{collections::HashSet, str::FromStr};
+
+// External uses
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::event::transaction::TransactionType;
+
+// Local uses
+use super::listener_cache::ListenerCache;
+
+/// PostgreSQL channel that gets notified on every change of the disabled transaction types.
+pub const DISABLED_TX_TYPES_CHANNEL: &str = "disabled_tx_types_channel";
+
+/// Cached list of the transaction types which are temporarily not accepted by the API.
+///
+/// The list is stored in the database, so it can be changed at runtime without
+/// restarting the server.
+#[derive(Debug, Clone)]
+pub struct DisabledTxTypes(ListenerCache<HashSet<TransactionType>>);
+
+impl DisabledTxTypes {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self(ListenerCache::new(
+            "Disabled transaction types",
+            DISABLED_TX_TYPES_CHANNEL,
+            pool,
+            load,
+        ))
+    }
+
+    /// Returns the set of currently disabled transaction types.
+    pub async fn get(&self) -> anyhow::Result<HashSet<TransactionType>> {
+        Ok(self.0.get().await?.as_ref().clone())
+    }
+
+    /// Checks whether transactions of the given type are currently disabled.
+    pub async fn is_disabled(&self, tx_type: TransactionType) -> anyhow::Result<bool> {
+        Ok(self.0.get().await?.contains(&tx_type))
+    }
+
+    /// Returns the list of L2 transaction types that are currently accepted by the API.
+    pub async fn enabled(&self) -> anyhow::Result<Vec<TransactionType>> {
+        let disabled = self.0.get().await?;
+        Ok(TransactionType::L2_TYPES
+            .iter()
+            .copied()
+            .filter(|tx_type| !disabled.contains(tx_type))
+            .collect())
+    }
+
+    /// Forces the list to be reloaded from the database on the next access.
+    pub async fn invalidate(&self) {
+        self.0.invalidate().await;
+    }
+
+    /// Spawns a task that invalidates the cache every time the list of
+    /// disabled transaction types is changed in the database.
+    pub fn spawn_invalidation_listener(&self) -> JoinHandle<()> {
+        self.0.spawn_invalidation_listener()
+    }
+}
+
+async fn load(pool: ConnectionPool) -> anyhow::Result<HashSet<TransactionType>> {
+    let tx_types = pool
+        .access_storage()
+        .await?
+        .misc_schema()
+        .load_disabled_tx_types()
+        .await?;
+
+    Ok(tx_types
+        .iter()
+        .filter_map(|tx_type| match TransactionType::from_str(tx_type) {
+            Ok(tx_type) => Some(tx_type),
+            Err(err) => {
+                vlog::warn!("Skipping disabled transaction type: {}", err);
+                None
+            }
+        })
+        .collect())
+}
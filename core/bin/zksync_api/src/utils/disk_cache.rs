@@ -0,0 +1,363 @@
+//! Persistent cache of the data that never changes, e.g. the finalized blocks and transactions,
+//! so the restarted API servers don't query the database for all of them again.
+//!
+//! Every value is stored in a separate JSON file named after its key, along with the cache format
+//! version and the key itself. Files that can't be read for any reason are removed, so the value
+//! is loaded from the database and rewritten by the caller. The total size of the files is bounded,
+//! the least recently used ones are removed first. The bound is kept by every cache instance
+//! separately, the instances sharing the directory only see the files of each other after a restart.
+
+// Built-in uses
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::SystemTime,
+};
+
+// External uses
+use lru_cache::LruCache;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+// Workspace uses
+
+// Local uses
+
+/// Version of the stored files, has to be bumped once the format of any cached type is changed
+/// or the stored values turn out to be wrong. Version 1 could contain the withdrawals without
+/// the hash of the Ethereum transaction completing them.
+const FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize)]
+struct Envelope<'a, T> {
+    version: u32,
+    key: &'a str,
+    value: &'a T,
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredEnvelope {
+    version: u32,
+    key: String,
+    value: serde_json::Value,
+}
+
+#[derive(Debug)]
+struct Index {
+    /// Sizes of the stored files keyed by their paths relative to the cache directory.
+    files: LruCache<String, u64>,
+    bytes: u64,
+}
+
+#[derive(Debug)]
+struct Inner {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<Index>,
+    /// Suffix of the temporary files, so the concurrent writes of the same value don't clash.
+    next_tmp_id: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiskCache(Arc<Inner>);
+
+impl DiskCache {
+    /// Opens the cache in the directory, creating it if needed. The files left by the previous
+    /// runs are indexed in the order of their modification. Returns `None` if the directory
+    /// is not accessible, so the API works without the cache.
+    pub fn open(dir: impl Into<PathBuf>, max_bytes: u64) -> Option<Self> {
+        let dir = dir.into();
+        match Self::open_inner(dir.clone(), max_bytes) {
+            Ok(cache) => Some(cache),
+            Err(err) => {
+                vlog::warn!("Disk cache in {} is disabled: {}", dir.display(), err);
+                None
+            }
+        }
+    }
+
+    fn open_inner(dir: PathBuf, max_bytes: u64) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let mut files = Vec::new();
+        for namespace in fs::read_dir(&dir)? {
+            let namespace = namespace?;
+            if !namespace.file_type()?.is_dir() {
+                continue;
+            }
+            for file in fs::read_dir(namespace.path())? {
+                let file = file?;
+                let path = file.path();
+                let metadata = file.metadata()?;
+                if path.extension().map_or(true, |ext| ext != "json") {
+                    // Leftovers of the interrupted writes.
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                let relative = format!(
+                    "{}/{}",
+                    namespace.file_name().to_string_lossy(),
+                    file.file_name().to_string_lossy()
+                );
+                files.push((modified, relative, metadata.len()));
+            }
+        }
+        files.sort();
+
+        let cache = Self(Arc::new(Inner {
+            dir,
+            max_bytes,
+            index: Mutex::new(Index {
+                files: LruCache::new(usize::MAX),
+                bytes: 0,
+            }),
+            next_tmp_id: AtomicU64::new(0),
+        }));
+        {
+            let mut index = cache.0.index.lock().unwrap();
+            for (_, relative, size) in files {
+                index.files.insert(relative, size);
+                index.bytes += size;
+            }
+            cache.evict(&mut index);
+        }
+        Ok(cache)
+    }
+
+    fn relative_path(namespace: &str, key: &str) -> String {
+        format!("{}/{}.json", namespace, key)
+    }
+
+    fn full_path(&self, relative: &str) -> PathBuf {
+        self.0.dir.join(relative)
+    }
+
+    /// Loads the value stored under the key, `None` if it's missing or can't be read.
+    pub fn get<T: DeserializeOwned>(&self, namespace: &'static str, key: &str) -> Option<T> {
+        let relative = Self::relative_path(namespace, key);
+        let path = self.full_path(&relative);
+        let value = match fs::read(&path) {
+            Ok(contents) => {
+                let value = Self::decode(&contents, key);
+                if value.is_none() {
+                    metrics::increment_counter!("api.disk_cache.invalid", "cache" => namespace);
+                    self.remove(&relative, &path);
+                }
+                value
+            }
+            Err(_) => None,
+        };
+
+        if value.is_some() {
+            let mut index = self.0.index.lock().unwrap();
+            if index.files.get_mut(&relative).is_none() {
+                // Stored by another instance since this one was opened.
+                let size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+                index.files.insert(relative, size);
+                index.bytes += size;
+            }
+            metrics::increment_counter!("api.disk_cache.hits", "cache" => namespace);
+        } else {
+            metrics::increment_counter!("api.disk_cache.misses", "cache" => namespace);
+        }
+        value
+    }
+
+    fn decode<T: DeserializeOwned>(contents: &[u8], key: &str) -> Option<T> {
+        let stored: StoredEnvelope = serde_json::from_slice(contents).ok()?;
+        if stored.version != FORMAT_VERSION || stored.key != key {
+            return None;
+        }
+        serde_json::from_value(stored.value).ok()
+    }
+
+    /// Stores the value under the key, replacing the previous one. Values larger than the whole
+    /// cache are not stored, and failures to write are only logged.
+    pub fn insert<T: Serialize>(&self, namespace: &'static str, key: &str, value: &T) {
+        let relative = Self::relative_path(namespace, key);
+        let path = self.full_path(&relative);
+        let contents = match serde_json::to_vec(&Envelope {
+            version: FORMAT_VERSION,
+            key,
+            value,
+        }) {
+            Ok(contents) => contents,
+            Err(_) => return,
+        };
+        let size = contents.len() as u64;
+        if size > self.0.max_bytes {
+            return;
+        }
+
+        if let Err(err) = self.write_file(&path, &contents) {
+            vlog::warn!("Failed to write {} to disk cache: {}", relative, err);
+            return;
+        }
+        let mut index = self.0.index.lock().unwrap();
+        if let Some(old_size) = index.files.insert(relative, size) {
+            index.bytes -= old_size;
+        }
+        index.bytes += size;
+        self.evict(&mut index);
+        metrics::gauge!("api.disk_cache.bytes", index.bytes as f64);
+    }
+
+    /// Writes the file via the temporary one, so the readers never see the partially written file.
+    fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_id = self.0.next_tmp_id.fetch_add(1, Ordering::Relaxed);
+        let tmp_path = path.with_extension(format!("tmp{}-{}", std::process::id(), tmp_id));
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, path).map_err(|err| {
+            let _ = fs::remove_file(&tmp_path);
+            err
+        })
+    }
+
+    fn remove(&self, relative: &str, path: &Path) {
+        let _ = fs::remove_file(path);
+        let mut index = self.0.index.lock().unwrap();
+        if let Some(size) = index.files.remove(relative) {
+            index.bytes -= size;
+        }
+    }
+
+    /// Removes the least recently used files until the total size fits the bound.
+    fn evict(&self, index: &mut Index) {
+        let mut evicted = 0u64;
+        while index.bytes > self.0.max_bytes {
+            match index.files.remove_lru() {
+                Some((relative, size)) => {
+                    let _ = fs::remove_file(self.full_path(&relative));
+                    index.bytes -= size;
+                    evicted += 1;
+                }
+                None => break,
+            }
+        }
+        metrics::counter!("api.disk_cache.evictions", evicted);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Temporary directory removed once the test is over.
+    pub(crate) struct TestDir(pub PathBuf);
+
+    impl TestDir {
+        pub fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "zksync_disk_cache_{}_{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&dir);
+            Self(dir)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn entry_size(key: &str, value: &str) -> u64 {
+        serde_json::to_vec(&Envelope {
+            version: FORMAT_VERSION,
+            key,
+            value: &value,
+        })
+        .unwrap()
+        .len() as u64
+    }
+
+    #[test]
+    fn values_survive_reopening() {
+        let dir = TestDir::new("reopen");
+        let cache = DiskCache::open(&dir.0, 1 << 20).unwrap();
+        assert_eq!(cache.get::<String>("blocks", "1"), None);
+        cache.insert("blocks", "1", &"first".to_string());
+        cache.insert("tx_data", "1", &"other".to_string());
+        drop(cache);
+
+        let cache = DiskCache::open(&dir.0, 1 << 20).unwrap();
+        assert_eq!(
+            cache.get::<String>("blocks", "1"),
+            Some("first".to_string())
+        );
+        assert_eq!(
+            cache.get::<String>("tx_data", "1"),
+            Some("other".to_string())
+        );
+        // Values of another type are treated as corrupted.
+        assert_eq!(cache.get::<u64>("blocks", "1"), None);
+        assert_eq!(cache.get::<String>("blocks", "1"), None);
+    }
+
+    #[test]
+    fn invalid_files_are_removed() {
+        let dir = TestDir::new("invalid");
+        let cache = DiskCache::open(&dir.0, 1 << 20).unwrap();
+        for key in &["1", "2", "3"] {
+            cache.insert("blocks", key, &key.to_string());
+        }
+
+        let path = |key: &str| dir.0.join(DiskCache::relative_path("blocks", key));
+        fs::write(path("1"), b"{\"version\": 1, \"key\": \"1\", \"val").unwrap();
+        let outdated =
+            serde_json::json!({ "version": FORMAT_VERSION + 1, "key": "2", "value": "2" });
+        fs::write(path("2"), serde_json::to_vec(&outdated).unwrap()).unwrap();
+        // The file stored under another key.
+        fs::copy(path("3"), path("4")).unwrap();
+
+        let cache = DiskCache::open(&dir.0, 1 << 20).unwrap();
+        for key in &["1", "2", "4"] {
+            assert_eq!(cache.get::<String>("blocks", key), None, "key: {}", key);
+            assert!(!path(key).exists());
+        }
+        assert_eq!(cache.get::<String>("blocks", "3"), Some("3".to_string()));
+
+        // The removed values are rewritten.
+        cache.insert("blocks", "1", &"1".to_string());
+        assert_eq!(cache.get::<String>("blocks", "1"), Some("1".to_string()));
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let dir = TestDir::new("bounded");
+        let size = entry_size("1", "value");
+        let cache = DiskCache::open(&dir.0, size * 2).unwrap();
+        cache.insert("blocks", "1", &"value");
+        cache.insert("blocks", "2", &"value");
+        // The recently used entry is kept.
+        assert!(cache.get::<String>("blocks", "1").is_some());
+        cache.insert("blocks", "3", &"value");
+        assert!(cache.get::<String>("blocks", "1").is_some());
+        assert!(cache.get::<String>("blocks", "2").is_none());
+        assert!(cache.get::<String>("blocks", "3").is_some());
+
+        // The bound is applied to the files of the previous runs too.
+        drop(cache);
+        let cache = DiskCache::open(&dir.0, size).unwrap();
+        assert_eq!(
+            ["1", "3"]
+                .iter()
+                .filter(|key| cache.get::<String>("blocks", key).is_some())
+                .count(),
+            1
+        );
+
+        // Values exceeding the whole cache are not stored.
+        let cache = DiskCache::open(&dir.0, size - 1).unwrap();
+        cache.insert("blocks", "4", &"value");
+        assert!(cache.get::<String>("blocks", "4").is_none());
+    }
+}
@@ -0,0 +1,137 @@
+// Built-in uses
+use std::{
+    fmt,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// External uses
+use futures::future::BoxFuture;
+use tokio::{sync::RwLock, task::JoinHandle, time};
+
+// Workspace uses
+use zksync_storage::{listener::StorageListener, ConnectionPool};
+
+// Local uses
+
+/// The data is reloaded from the database after this period even if no
+/// notification was received, e.g. because the listener connection was lost.
+const CACHE_LIFETIME: Duration = Duration::from_secs(30);
+/// Delay before reconnecting to the database after the listener failure.
+const LISTENER_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+type Loader<T> = dyn Fn(ConnectionPool) -> BoxFuture<'static, anyhow::Result<T>> + Send + Sync;
+
+struct Cached<T> {
+    value: Option<Arc<T>>,
+    updated_at: Option<Instant>,
+}
+
+/// Data loaded from the database and cached until it is changed there.
+///
+/// Every change of the data is expected to notify the PostgreSQL `channel`, so the cache
+/// is invalidated by the listener spawned with `spawn_invalidation_listener` and the new
+/// data is used without restarting the server.
+pub struct ListenerCache<T> {
+    name: &'static str,
+    channel: &'static str,
+    pool: ConnectionPool,
+    loader: Arc<Loader<T>>,
+    cache: Arc<RwLock<Cached<T>>>,
+}
+
+impl<T> Clone for ListenerCache<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name,
+            channel: self.channel,
+            pool: self.pool.clone(),
+            loader: self.loader.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for ListenerCache<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ListenerCache")
+            .field("name", &self.name)
+            .field("channel", &self.channel)
+            .finish()
+    }
+}
+
+impl<T: Send + Sync + 'static> ListenerCache<T> {
+    /// Creates the cache of the data loaded by the `loader`. The `name` of the data
+    /// is used in the logs only.
+    pub fn new<F, Fut>(
+        name: &'static str,
+        channel: &'static str,
+        pool: ConnectionPool,
+        loader: F,
+    ) -> Self
+    where
+        F: Fn(ConnectionPool) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = anyhow::Result<T>> + Send + 'static,
+    {
+        Self {
+            name,
+            channel,
+            pool,
+            loader: Arc::new(move |pool| Box::pin(loader(pool))),
+            cache: Arc::new(RwLock::new(Cached {
+                value: None,
+                updated_at: None,
+            })),
+        }
+    }
+
+    /// Returns the cached data, reloading it if the cache is invalidated or expired.
+    pub async fn get(&self) -> anyhow::Result<Arc<T>> {
+        {
+            let cache = self.cache.read().await;
+            if let (Some(value), Some(updated_at)) = (&cache.value, cache.updated_at) {
+                if updated_at.elapsed() < CACHE_LIFETIME {
+                    return Ok(value.clone());
+                }
+            }
+        }
+
+        let value = Arc::new((self.loader)(self.pool.clone()).await?);
+        *self.cache.write().await = Cached {
+            value: Some(value.clone()),
+            updated_at: Some(Instant::now()),
+        };
+        Ok(value)
+    }
+
+    /// Forces the data to be reloaded from the database on the next access.
+    pub async fn invalidate(&self) {
+        self.cache.write().await.updated_at = None;
+    }
+
+    /// Spawns a task that invalidates the cache every time the channel is notified.
+    pub fn spawn_invalidation_listener(&self) -> JoinHandle<()> {
+        let this = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = this.listen_for_updates().await {
+                    vlog::warn!("{} listener failed: {}", this.name, err);
+                }
+                // Notifications may have been missed while the connection was lost.
+                this.invalidate().await;
+                time::sleep(LISTENER_RECONNECT_DELAY).await;
+            }
+        })
+    }
+
+    async fn listen_for_updates(&self) -> anyhow::Result<()> {
+        let mut listener = StorageListener::connect().await?;
+        listener.listen(self.channel).await?;
+        loop {
+            listener.recv().await?;
+            self.invalidate().await;
+        }
+    }
+}
@@ -1,2 +1,12 @@
+pub mod account_locks;
+pub mod banned_addresses;
 pub mod block_details_cache;
+pub mod disk_cache;
+pub mod disabled_tx_types;
+pub mod listener_cache;
+pub mod pool_supervisor;
 pub mod shared_lru_cache;
+pub mod sign_verifier_breaker;
+pub mod special_accounts;
+pub mod token_cache;
+pub mod tx_data_cache;
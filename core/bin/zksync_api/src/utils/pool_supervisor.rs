@@ -0,0 +1,260 @@
+//! Supervision of the storage connection pools.
+//!
+//! After the database failover the pool may keep the connections to the old server: they are
+//! either closed or hang until the TCP timeout, so the requests using them fail. The supervisor
+//! periodically checks the idle connections, dropping the broken ones, and recreates the whole
+//! pool once most of the connections are broken. All the clones of the pool (e.g. the ones used
+//! by `TxSender` and the API scopes) switch to the new pool at once.
+
+// Built-in uses
+use std::time::Duration;
+
+// External uses
+use tokio::time;
+
+// Workspace uses
+use zksync_api_types::v02::status::StoragePoolStatus;
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_storage::{ConnectionPool, IdleConnectionsCheck};
+
+#[derive(Debug, Clone)]
+struct SupervisedPool {
+    name: String,
+    pool: ConnectionPool,
+}
+
+/// Checks the idle connections of the pools and recreates the pools with too many broken connections.
+#[derive(Debug, Clone)]
+pub struct PoolSupervisor {
+    pools: Vec<SupervisedPool>,
+    interval: Duration,
+    check_timeout: Duration,
+    /// Share of the broken connections the pool is recreated at.
+    recreate_failure_ratio: f64,
+}
+
+impl PoolSupervisor {
+    pub fn new(config: &CommonApiConfig) -> Self {
+        Self {
+            pools: Vec::new(),
+            interval: config.pool_check_interval(),
+            check_timeout: config.pool_check_timeout(),
+            recreate_failure_ratio: config.pool_recreate_failure_percent as f64 / 100.0,
+        }
+    }
+
+    /// Adds the pool to be supervised.
+    pub fn with_pool(mut self, name: impl Into<String>, pool: ConnectionPool) -> Self {
+        self.pools.push(SupervisedPool {
+            name: name.into(),
+            pool,
+        });
+        self
+    }
+
+    /// Returns the current state of the supervised pools.
+    pub fn status(&self) -> Vec<StoragePoolStatus> {
+        self.pools
+            .iter()
+            .map(|supervised| {
+                let stats = supervised.pool.stats();
+                StoragePoolStatus {
+                    name: supervised.name.clone(),
+                    max_size: stats.max_size as u64,
+                    size: stats.size as u64,
+                    idle: stats.idle as u64,
+                    errors: stats.errors,
+                    recreations: stats.recreations,
+                }
+            })
+            .collect()
+    }
+
+    /// Checks every pool once, returns the outcome of the checks in the order the pools were added.
+    pub async fn check_pools(&self) -> Vec<IdleConnectionsCheck> {
+        let mut checks = Vec::with_capacity(self.pools.len());
+        for supervised in &self.pools {
+            let check = supervised
+                .pool
+                .check_idle_connections(self.check_timeout)
+                .await;
+            if check.broken > 0 {
+                vlog::warn!(
+                    "{} of {} idle connections of the {} pool are broken",
+                    check.broken,
+                    check.healthy + check.broken,
+                    supervised.name
+                );
+            }
+            if check.broken > 0 && check.failure_ratio() >= self.recreate_failure_ratio {
+                vlog::warn!("Recreating the {} connection pool", supervised.name);
+                supervised.pool.recreate();
+            }
+            self.report_metrics(supervised, check);
+            checks.push(check);
+        }
+        checks
+    }
+
+    fn report_metrics(&self, supervised: &SupervisedPool, check: IdleConnectionsCheck) {
+        let stats = supervised.pool.stats();
+        let name = supervised.name.clone();
+        metrics::gauge!("api.storage_pool.size", stats.size as f64, "pool" => name.clone());
+        metrics::gauge!("api.storage_pool.idle", stats.idle as f64, "pool" => name.clone());
+        metrics::gauge!("api.storage_pool.errors", stats.errors as f64, "pool" => name.clone());
+        metrics::gauge!(
+            "api.storage_pool.recreations",
+            stats.recreations as f64,
+            "pool" => name.clone()
+        );
+        metrics::counter!("api.storage_pool.broken", check.broken as u64, "pool" => name);
+    }
+
+    /// Checks the pools forever, should be spawned as a separate task.
+    pub async fn run(self) {
+        if self.pools.is_empty() {
+            return;
+        }
+
+        let mut timer = time::interval(self.interval);
+        loop {
+            timer.tick().await;
+            self.check_pools().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    };
+    use tokio::{
+        io::copy_bidirectional,
+        net::{TcpListener, TcpStream},
+        task::JoinHandle,
+    };
+    use zksync_storage::get_database_url;
+
+    /// TCP proxy in front of the database, emulates the failover by severing the proxied connections.
+    struct DatabaseProxy {
+        url: String,
+        connections: Arc<Mutex<Vec<JoinHandle<()>>>>,
+        listener_task: JoinHandle<()>,
+    }
+
+    impl DatabaseProxy {
+        async fn start() -> Self {
+            let mut url = reqwest::Url::parse(&get_database_url()).unwrap();
+            let upstream = format!("{}:{}", url.host_str().unwrap(), url.port().unwrap_or(5432));
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address: SocketAddr = listener.local_addr().unwrap();
+            url.set_host(Some("127.0.0.1")).unwrap();
+            url.set_port(Some(address.port())).unwrap();
+
+            let connections = Arc::new(Mutex::new(Vec::new()));
+            let listener_task = tokio::spawn({
+                let connections = connections.clone();
+                async move {
+                    while let Ok((mut inbound, _)) = listener.accept().await {
+                        let upstream = upstream.clone();
+                        let connection = tokio::spawn(async move {
+                            let mut outbound = TcpStream::connect(upstream).await.unwrap();
+                            copy_bidirectional(&mut inbound, &mut outbound).await.ok();
+                        });
+                        connections.lock().unwrap().push(connection);
+                    }
+                }
+            });
+
+            Self {
+                url: url.to_string(),
+                connections,
+                listener_task,
+            }
+        }
+
+        /// Closes all the proxied connections, new connections are still accepted.
+        fn sever_connections(&self) {
+            for connection in self.connections.lock().unwrap().drain(..) {
+                connection.abort();
+            }
+        }
+    }
+
+    impl Drop for DatabaseProxy {
+        fn drop(&mut self) {
+            self.sever_connections();
+            self.listener_task.abort();
+        }
+    }
+
+    /// Checks that the broken connections are detected after the failover and the pool is recreated,
+    /// so the clones of the pool acquire working connections afterwards.
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn pool_is_recreated_after_failover() -> anyhow::Result<()> {
+        let proxy = DatabaseProxy::start().await;
+        let pool = ConnectionPool::new_with_url(proxy.url.clone(), Some(4));
+        // The clone is used by the requests, e.g. by the `TxSender`.
+        let requests_pool = pool.clone();
+        let mut config = zksync_config::ZkSyncConfig::from_env().api.common;
+        config.pool_recreate_failure_percent = 50;
+        config.pool_check_timeout_ms = 500;
+        let supervisor = PoolSupervisor::new(&config).with_pool("main", pool);
+
+        // Open several connections, they are returned to the pool as idle.
+        {
+            let mut connections = Vec::new();
+            for _ in 0..3 {
+                connections.push(requests_pool.access_storage().await?);
+            }
+        }
+        let checks = supervisor.check_pools().await;
+        assert_eq!(
+            checks,
+            vec![IdleConnectionsCheck {
+                healthy: 3,
+                broken: 0
+            }]
+        );
+        assert_eq!(supervisor.status()[0].recreations, 0);
+
+        // An in-flight request holding a connection during the failover.
+        let in_flight = requests_pool.access_storage().await?;
+        proxy.sever_connections();
+
+        // All the idle connections are broken, the pool is recreated.
+        let checks = supervisor.check_pools().await;
+        assert_eq!(checks[0].healthy, 0);
+        assert!(checks[0].broken >= 3);
+        let status = supervisor.status();
+        assert_eq!(status[0].recreations, 1);
+        assert!(status[0].errors >= checks[0].broken as u64);
+        drop(in_flight);
+
+        // The clone acquires the connections from the new pool.
+        let mut storage = requests_pool.access_storage().await?;
+        storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+        drop(storage);
+        let checks = supervisor.check_pools().await;
+        assert_eq!(
+            checks,
+            vec![IdleConnectionsCheck {
+                healthy: 1,
+                broken: 0
+            }]
+        );
+        Ok(())
+    }
+}
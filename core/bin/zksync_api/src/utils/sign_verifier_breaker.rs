@@ -0,0 +1,232 @@
+//! Circuit breaker protecting the signature verifier from the overload.
+//!
+//! Every submitted transaction is verified by the signature checker, which handles requests
+//! concurrently but has limited throughput (e.g. Ethereum signatures of the CREATE2 accounts
+//! require calls to the Web3 node). If the verifier backs up, new submissions queue behind it and
+//! time out en masse. Instead, once the verifier is overloaded, new submissions are rejected right
+//! away, while the requests that are already enqueued are processed as usual.
+
+// Built-in uses
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// Workspace uses
+use zksync_api_types::v02::status::SignVerifierStatus;
+use zksync_config::configs::api::CommonApiConfig;
+
+/// Weight of the latest measurement in the average verification latency.
+const LATENCY_SMOOTHING_FACTOR: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerThresholds {
+    /// The breaker opens once this number of requests is in flight.
+    pub max_queue_depth: u64,
+    /// The breaker opens once the average latency of the requests exceeds this value.
+    pub max_latency: Duration,
+    /// Minimal time the breaker stays open for.
+    pub cooldown: Duration,
+}
+
+impl BreakerThresholds {
+    pub fn from_config(config: &CommonApiConfig) -> Self {
+        Self {
+            max_queue_depth: config.sign_verifier_max_queue_depth,
+            max_latency: config.sign_verifier_max_latency(),
+            cooldown: config.sign_verifier_breaker_cooldown(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    queue_depth: u64,
+    average_latency: Option<Duration>,
+    opened_at: Option<Instant>,
+}
+
+/// Tracks the depth and the latency of the signature verification queue and rejects
+/// new requests while the verifier is overloaded.
+///
+/// The breaker opens once either threshold is exceeded. To avoid flapping, it closes only
+/// after the cooldown and once both values drop below half of the thresholds.
+#[derive(Debug, Clone)]
+pub struct SignVerifierBreaker {
+    thresholds: BreakerThresholds,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+/// Permission to send a request to the signature verifier.
+/// The request is considered completed once the permit is dropped.
+#[derive(Debug)]
+pub struct VerificationPermit {
+    breaker: SignVerifierBreaker,
+    started_at: Instant,
+}
+
+impl Drop for VerificationPermit {
+    fn drop(&mut self) {
+        self.breaker.complete(self.started_at.elapsed());
+    }
+}
+
+impl SignVerifierBreaker {
+    pub fn new(thresholds: BreakerThresholds) -> Self {
+        Self {
+            thresholds,
+            state: Arc::default(),
+        }
+    }
+
+    /// Returns the permit for a new verification request, or the time after which
+    /// the request can be retried if the verifier is overloaded.
+    pub fn try_acquire(&self) -> Result<VerificationPermit, Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        self.update(&mut state, now);
+        if let Some(opened_at) = state.opened_at {
+            metrics::increment_counter!("api.sign_verifier.rejected_requests");
+            return Err(self
+                .thresholds
+                .cooldown
+                .saturating_sub(now.duration_since(opened_at)));
+        }
+
+        state.queue_depth += 1;
+        self.report(&state);
+        Ok(VerificationPermit {
+            breaker: self.clone(),
+            started_at: now,
+        })
+    }
+
+    pub fn status(&self) -> SignVerifierStatus {
+        let mut state = self.state.lock().unwrap();
+        self.update(&mut state, Instant::now());
+        SignVerifierStatus {
+            breaker_open: state.opened_at.is_some(),
+            queue_depth: state.queue_depth,
+            average_latency_ms: state
+                .average_latency
+                .map(|latency| latency.as_millis() as u64),
+        }
+    }
+
+    fn complete(&self, latency: Duration) {
+        metrics::histogram!("api.sign_verifier.latency", latency);
+        let mut state = self.state.lock().unwrap();
+        state.queue_depth -= 1;
+        state.average_latency = Some(match state.average_latency {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_SMOOTHING_FACTOR)
+                    + latency.mul_f64(LATENCY_SMOOTHING_FACTOR)
+            }
+            None => latency,
+        });
+        self.update(&mut state, Instant::now());
+        self.report(&state);
+    }
+
+    fn update(&self, state: &mut BreakerState, now: Instant) {
+        let thresholds = &self.thresholds;
+        let latency = state.average_latency.unwrap_or_default();
+        match state.opened_at {
+            None => {
+                if state.queue_depth >= thresholds.max_queue_depth
+                    || latency > thresholds.max_latency
+                {
+                    vlog::warn!(
+                        "Signature verifier is overloaded (queue depth: {}, average latency: {:?}), \
+                         new submissions are rejected",
+                        state.queue_depth,
+                        latency
+                    );
+                    state.opened_at = Some(now);
+                }
+            }
+            Some(opened_at) => {
+                // Once the queue is drained, there is nothing left to measure the latency with,
+                // so the breaker closes and the next requests show whether the verifier recovered.
+                let recovered = state.queue_depth <= thresholds.max_queue_depth / 2
+                    && (state.queue_depth == 0 || latency <= thresholds.max_latency / 2);
+                if recovered && now.duration_since(opened_at) >= thresholds.cooldown {
+                    vlog::info!("Signature verifier has recovered, submissions are accepted");
+                    state.opened_at = None;
+                    state.average_latency = None;
+                }
+            }
+        }
+    }
+
+    fn report(&self, state: &BreakerState) {
+        metrics::gauge!("api.sign_verifier.queue_depth", state.queue_depth as f64);
+        let open = if state.opened_at.is_some() { 1.0 } else { 0.0 };
+        metrics::gauge!("api.sign_verifier.breaker_open", open);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COOLDOWN: Duration = Duration::from_millis(50);
+
+    fn breaker() -> SignVerifierBreaker {
+        SignVerifierBreaker::new(BreakerThresholds {
+            max_queue_depth: 4,
+            max_latency: Duration::from_secs(60),
+            cooldown: COOLDOWN,
+        })
+    }
+
+    #[test]
+    fn opens_on_queue_depth() {
+        let breaker = breaker();
+        let mut permits: Vec<_> = (0..4).map(|_| breaker.try_acquire().unwrap()).collect();
+        let retry_after = breaker.try_acquire().unwrap_err();
+        assert!(retry_after <= COOLDOWN);
+        assert!(breaker.status().breaker_open);
+
+        // Hysteresis: the breaker stays open until the queue is below half of the limit,
+        // even after the cooldown.
+        std::thread::sleep(COOLDOWN);
+        permits.pop();
+        assert!(breaker.try_acquire().is_err());
+        permits.pop();
+        let status = breaker.status();
+        assert!(!status.breaker_open);
+        assert_eq!(status.queue_depth, 2);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn stays_open_for_cooldown() {
+        let breaker = breaker();
+        let permits: Vec<_> = (0..4).map(|_| breaker.try_acquire().unwrap()).collect();
+        assert!(breaker.try_acquire().is_err());
+        drop(permits);
+        assert!(breaker.try_acquire().is_err());
+
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn opens_on_latency() {
+        let breaker = SignVerifierBreaker::new(BreakerThresholds {
+            max_queue_depth: 100,
+            max_latency: Duration::from_millis(10),
+            cooldown: COOLDOWN,
+        });
+        let slow = breaker.try_acquire().unwrap();
+        let _pending = breaker.try_acquire().unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        drop(slow);
+        assert!(breaker.try_acquire().is_err());
+
+        // The pending request is still slow, so the latency stays above the limit.
+        std::thread::sleep(COOLDOWN);
+        assert!(breaker.try_acquire().is_err());
+    }
+}
@@ -0,0 +1,89 @@
+//! Accounts that don't belong to the users.
+//!
+//! The operator collects the fees on its own account, and the contents of the minted NFTs are
+//! kept on the NFT storage account no one controls. The zero address can't own an account at all.
+
+// Workspace uses
+use zksync_api_types::v02::account::AccountKind;
+use zksync_crypto::params::{NFT_STORAGE_ACCOUNT_ADDRESS, NFT_STORAGE_ACCOUNT_ID};
+use zksync_types::{AccountId, Address};
+
+/// Tells the accounts of the users from the special ones.
+#[derive(Debug, Clone, Copy)]
+pub struct SpecialAccounts {
+    fee_account: Address,
+}
+
+impl SpecialAccounts {
+    pub fn new(fee_account: Address) -> Self {
+        Self { fee_account }
+    }
+
+    /// Returns the role of the account, `account_id` is `None` if the account doesn't exist yet.
+    pub fn kind(&self, account_id: Option<AccountId>, address: Address) -> AccountKind {
+        if is_reserved_address(address) {
+            AccountKind::Reserved
+        } else if account_id == Some(NFT_STORAGE_ACCOUNT_ID)
+            || address == *NFT_STORAGE_ACCOUNT_ADDRESS
+        {
+            AccountKind::NftStorage
+        } else if address == self.fee_account {
+            AccountKind::FeeCollector
+        } else {
+            AccountKind::User
+        }
+    }
+}
+
+/// Returns whether the address can't own an account.
+pub fn is_reserved_address(address: Address) -> bool {
+    address == Address::zero()
+}
+
+/// Returns whether the funds sent to the address can't be used by anyone. Transactions
+/// sending funds to such addresses are either failed in the block or lose the funds.
+pub fn is_reserved_recipient(address: Address) -> bool {
+    is_reserved_address(address) || address == *NFT_STORAGE_ACCOUNT_ADDRESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn account_kinds() {
+        let fee_account = Address::repeat_byte(0xfe);
+        let user = Address::repeat_byte(0x01);
+        let special_accounts = SpecialAccounts::new(fee_account);
+
+        assert_eq!(
+            special_accounts.kind(Some(AccountId(1)), user),
+            AccountKind::User
+        );
+        assert_eq!(special_accounts.kind(None, user), AccountKind::User);
+        assert_eq!(
+            special_accounts.kind(Some(AccountId(0)), fee_account),
+            AccountKind::FeeCollector
+        );
+        assert_eq!(
+            special_accounts.kind(Some(NFT_STORAGE_ACCOUNT_ID), *NFT_STORAGE_ACCOUNT_ADDRESS),
+            AccountKind::NftStorage
+        );
+        assert_eq!(
+            special_accounts.kind(None, *NFT_STORAGE_ACCOUNT_ADDRESS),
+            AccountKind::NftStorage
+        );
+        assert_eq!(
+            special_accounts.kind(None, Address::zero()),
+            AccountKind::Reserved
+        );
+    }
+
+    #[test]
+    fn reserved_recipients() {
+        assert!(is_reserved_recipient(Address::zero()));
+        assert!(is_reserved_recipient(*NFT_STORAGE_ACCOUNT_ADDRESS));
+        assert!(!is_reserved_recipient(Address::repeat_byte(0x01)));
+        assert!(!is_reserved_address(*NFT_STORAGE_ACCOUNT_ADDRESS));
+    }
+}
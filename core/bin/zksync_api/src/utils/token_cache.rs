@@ -0,0 +1,297 @@
+//! Token cache shared by all the components of the API server.
+//!
+//! The fee ticker, the transaction sender and the API scopes resolve tokens through the same
+//! cache, so they always agree on the token data. The cache is reloaded from the database
+//! periodically, and a token missing from it is loaded on demand, so the newly added tokens
+//! become visible to all the consumers at once.
+
+// Built-in uses
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+// External uses
+use tokio::{sync::RwLock, task::JoinHandle, time};
+
+// Workspace uses
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{Token, TokenId, TokenLike, NFT};
+
+// Local uses
+
+#[derive(Debug, Default)]
+struct CachedTokens {
+    /// Incremented on every change of the cached tokens.
+    version: u64,
+    /// Tokens by their lowercase id, address, symbol and previous symbols.
+    tokens: HashMap<TokenLike, Token>,
+}
+
+impl CachedTokens {
+    fn insert(&mut self, token_like: TokenLike, token: Token) {
+        self.tokens.insert(token_like.to_lowercase(), token);
+    }
+
+    fn insert_token(&mut self, token: Token) {
+        self.insert(TokenLike::Symbol(token.symbol.clone()), token.clone());
+        self.insert(TokenLike::Address(token.address), token.clone());
+        self.insert(TokenLike::Id(token.id), token);
+    }
+}
+
+/// Cache of the tokens known to the server.
+///
+/// Cloned instances share the same data, so a single cache is created at the start
+/// of the server and passed to all the components resolving tokens.
+#[derive(Debug, Clone)]
+pub struct TokenCache {
+    pool: ConnectionPool,
+    tokens: Arc<RwLock<CachedTokens>>,
+    nfts: Arc<RwLock<HashMap<TokenId, NFT>>>,
+    refresh_interval: Duration,
+}
+
+impl TokenCache {
+    pub fn new(pool: ConnectionPool, refresh_interval: Duration) -> Self {
+        Self {
+            pool,
+            tokens: Arc::default(),
+            nfts: Arc::default(),
+            refresh_interval,
+        }
+    }
+
+    /// Returns the version of the cached tokens, it's changed every time a token is added
+    /// to the cache or the tokens are changed in the database.
+    pub async fn version(&self) -> u64 {
+        self.tokens.read().await.version
+    }
+
+    /// Version of `get_token` that only attempts to find the token in the cache.
+    /// This method should be used in places that don't require the DB connection itself,
+    /// so taking a connection from the pool is avoided.
+    pub async fn try_get_token_from_cache(
+        &self,
+        token_query: impl Into<TokenLike>,
+    ) -> Option<Token> {
+        let token_query = token_query.into().to_lowercase();
+        self.tokens.read().await.tokens.get(&token_query).cloned()
+    }
+
+    /// Performs case-insensitive token search, the token missing from the cache
+    /// is loaded from the database.
+    pub async fn get_token(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_query: impl Into<TokenLike>,
+    ) -> anyhow::Result<Option<Token>> {
+        let token_query = token_query.into();
+        if let Some(token) = self.try_get_token_from_cache(token_query.clone()).await {
+            return Ok(Some(token));
+        }
+
+        metrics::increment_counter!("api.token_cache.miss");
+        let token = storage
+            .tokens_schema()
+            .get_token(token_query.clone())
+            .await?;
+        if let Some(token) = &token {
+            let mut cache = self.tokens.write().await;
+            // The token may be requested by the previous symbol, it's cached under it as well.
+            cache.insert(token_query, token.clone());
+            cache.insert_token(token.clone());
+            cache.version += 1;
+        }
+        Ok(token)
+    }
+
+    /// Same as `get_token`, but the database connection is only taken from the pool
+    /// if the token is missing from the cache.
+    pub async fn resolve_token(
+        &self,
+        token_query: impl Into<TokenLike>,
+    ) -> anyhow::Result<Option<Token>> {
+        let token_query = token_query.into();
+        if let Some(token) = self.try_get_token_from_cache(token_query.clone()).await {
+            return Ok(Some(token));
+        }
+
+        let mut storage = self.pool.access_storage().await?;
+        self.get_token(&mut storage, token_query).await
+    }
+
+    pub async fn token_symbol(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<String>> {
+        let token = self.get_token(storage, token_id).await?;
+        Ok(token.map(|token| token.symbol))
+    }
+
+    pub async fn get_nft_by_id(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<NFT>> {
+        if let Some(nft) = self.nfts.read().await.get(&token_id) {
+            return Ok(Some(nft.clone()));
+        }
+        // It's safe to get from `mint_nft_updates` because the availability of token in balance is regulated
+        // by the balance of this token.
+        if let Some(token) = storage
+            .chain()
+            .state_schema()
+            .get_mint_nft_update(token_id)
+            .await?
+        {
+            self.nfts.write().await.insert(token_id, token.clone());
+            return Ok(Some(token));
+        }
+        Ok(None)
+    }
+
+    /// Reloads all the tokens from the database.
+    pub async fn reload(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let tokens = storage.tokens_schema().load_tokens().await?;
+        let token_ids: Vec<TokenId> = tokens.keys().copied().collect();
+        let aliases = storage
+            .tokens_schema()
+            .load_token_aliases(&token_ids)
+            .await?;
+
+        let mut reloaded = CachedTokens::default();
+        // Renamed tokens are accessible by their previous symbols as well. Aliases are
+        // inserted first, so they can't shadow the current symbol of another token.
+        for token in tokens.values() {
+            for alias in aliases.get(&token.id).into_iter().flatten() {
+                reloaded.insert(TokenLike::Symbol(alias.clone()), token.clone());
+            }
+        }
+        for token in tokens.into_values() {
+            reloaded.insert_token(token);
+        }
+
+        let mut cache = self.tokens.write().await;
+        if cache.tokens != reloaded.tokens {
+            reloaded.version = cache.version + 1;
+            *cache = reloaded;
+        }
+        metrics::histogram!("api.token_cache.reload", start.elapsed());
+        Ok(())
+    }
+
+    /// Spawns the task reloading the tokens once per refresh interval.
+    #[must_use]
+    pub fn spawn_refresh(&self) -> JoinHandle<()> {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            let mut timer = time::interval(cache.refresh_interval);
+            loop {
+                timer.tick().await;
+                let result = match cache.pool.access_storage().await {
+                    Ok(mut storage) => cache.reload(&mut storage).await,
+                    Err(err) => Err(err.into()),
+                };
+                if let Err(err) = result {
+                    vlog::warn!("Failed to refresh the token cache: {}", err);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api_server::rest::v02::test_utils::TestServerConfig,
+        fee_ticker::{validator::cache::TokenCacheWrapper, FeeTickerInfo, TickerInfo},
+    };
+    use zksync_types::{Address, TokenKind};
+
+    const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+    async fn store_token(
+        cfg: &TestServerConfig,
+        address: Address,
+        symbol: &str,
+    ) -> anyhow::Result<Token> {
+        let mut storage = cfg.pool.access_storage().await?;
+        let max_token_id = storage.tokens_schema().get_max_erc20_token_id().await?;
+        let token = Token::new(
+            TokenId(max_token_id + 1),
+            address,
+            symbol,
+            18,
+            TokenKind::ERC20,
+        );
+        storage.tokens_schema().store_token(token.clone()).await?;
+        Ok(token)
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn token_cache_consumers_agree() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        // Consumers get their clones of the same cache, as they do in the server.
+        let cache = TokenCache::new(cfg.pool.clone(), REFRESH_INTERVAL);
+        let ticker_info = TickerInfo::new(cfg.pool.clone(), cache.clone());
+        let validator_tokens = TokenCacheWrapper::from((cfg.pool.clone(), cache.clone()));
+        let scope_tokens = cache.clone();
+
+        // The token added to the storage is observed by all the consumers after the refresh.
+        let token = store_token(&cfg, Address::repeat_byte(0x7c), "TCA").await?;
+        let refresh = cache.spawn_refresh();
+        tokio::time::sleep(REFRESH_INTERVAL * 2).await;
+        let version = cache.version().await;
+        assert_eq!(
+            scope_tokens.try_get_token_from_cache(token.id).await,
+            Some(token.clone())
+        );
+        assert_eq!(
+            validator_tokens.get_token(TokenLike::Id(token.id)).await?,
+            Some(token.clone())
+        );
+        assert_eq!(ticker_info.get_token(TokenLike::parse("tca")).await?, token);
+        assert_eq!(scope_tokens.version().await, version);
+        refresh.abort();
+
+        // The token missing from the cache is loaded by the first consumer requesting it,
+        // and the others observe it without reloading.
+        let token = store_token(&cfg, Address::repeat_byte(0x7d), "TCB").await?;
+        assert!(scope_tokens
+            .try_get_token_from_cache(token.id)
+            .await
+            .is_none());
+        assert_eq!(
+            ticker_info
+                .get_token(TokenLike::Address(token.address))
+                .await?,
+            token
+        );
+        assert_eq!(cache.version().await, version + 1);
+        assert_eq!(
+            scope_tokens.try_get_token_from_cache("TCB").await,
+            Some(token.clone())
+        );
+        assert_eq!(
+            validator_tokens.get_token(TokenLike::Id(token.id)).await?,
+            Some(token)
+        );
+        assert_eq!(scope_tokens.version().await, version + 1);
+
+        // Reloading the unchanged tokens doesn't change the version.
+        cache.reload(&mut cfg.pool.access_storage().await?).await?;
+        assert_eq!(cache.version().await, version + 1);
+        Ok(())
+    }
+}
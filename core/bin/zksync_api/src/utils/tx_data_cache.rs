@@ -0,0 +1,352 @@
+// Built-in uses
+use std::sync::{Arc, Mutex};
+
+// External uses
+use lru_cache::LruCache;
+
+// Workspace uses
+use zksync_api_types::v02::transaction::{L2Transaction, TransactionData, TxData, TxInBlockStatus};
+use zksync_types::tx::TxHash;
+
+// Local uses
+use super::disk_cache::DiskCache;
+
+const DISK_CACHE_NAMESPACE: &str = "tx_data";
+
+#[derive(Debug)]
+struct Entries {
+    /// Cached data along with its serialized size.
+    cache: LruCache<TxHash, (TxData, usize)>,
+    max_entries: usize,
+    bytes: usize,
+    max_bytes: usize,
+}
+
+/// Whether the data of the transaction can't change anymore. The transaction should be finalized,
+/// and the withdrawals should have the hash of the Ethereum transaction completing them, which is
+/// filled in only after the block is executed.
+fn is_complete(data: &TxData) -> bool {
+    if data.tx.status != TxInBlockStatus::Finalized {
+        return false;
+    }
+    match &data.tx.op {
+        TransactionData::L2(L2Transaction::Withdraw(withdraw)) => withdraw.eth_tx_hash.is_some(),
+        TransactionData::L2(L2Transaction::ForcedExit(forced_exit)) => {
+            forced_exit.eth_tx_hash.is_some()
+        }
+        TransactionData::L2(L2Transaction::WithdrawNFT(withdraw)) => withdraw.eth_tx_hash.is_some(),
+        _ => true,
+    }
+}
+
+/// Cache of the data of the finalized transactions, which never changes once the block is executed.
+///
+/// The cache is bounded both by the number of the transactions and by the total size of their data
+/// serialized as JSON, the least recently used transactions are evicted first. The data of the
+/// transactions that are not finalized yet is never cached, as well as the data of the withdrawals
+/// which are not completed on L1 yet.
+///
+/// If the disk cache is configured, the data is written through to it and the transactions missing
+/// in memory are looked up there, so they are not loaded from the database again after the restart.
+#[derive(Clone, Debug)]
+pub struct TxDataCache {
+    entries: Arc<Mutex<Entries>>,
+    disk_cache: Option<DiskCache>,
+}
+
+impl TxDataCache {
+    pub fn new(max_entries: usize, max_bytes: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(Entries {
+                cache: LruCache::new(max_entries.max(1)),
+                max_entries,
+                bytes: 0,
+                max_bytes,
+            })),
+            disk_cache: None,
+        }
+    }
+
+    pub fn with_disk_cache(mut self, disk_cache: Option<DiskCache>) -> Self {
+        self.disk_cache = disk_cache;
+        self
+    }
+
+    pub fn get(&self, tx_hash: &TxHash) -> Option<TxData> {
+        if let Some(data) = self.get_from_memory(tx_hash) {
+            return Some(data);
+        }
+        let disk_cache = self.disk_cache.as_ref()?;
+        let data: TxData = disk_cache
+            .get(DISK_CACHE_NAMESPACE, &tx_hash.to_string())
+            .filter(is_complete)?;
+        self.insert_into_memory(*tx_hash, &data);
+        Some(data)
+    }
+
+    fn get_from_memory(&self, tx_hash: &TxHash) -> Option<TxData> {
+        let data = self
+            .entries
+            .lock()
+            .unwrap()
+            .cache
+            .get_mut(tx_hash)
+            .map(|(data, _)| data.clone());
+        if data.is_some() {
+            metrics::increment_counter!("api.v02.tx_data_cache.hits");
+        } else {
+            metrics::increment_counter!("api.v02.tx_data_cache.misses");
+        }
+        data
+    }
+
+    /// Caches the data if it can't change anymore, otherwise does nothing. The same condition
+    /// applies to the disk cache, so the incomplete data doesn't survive the restart.
+    pub fn insert(&self, tx_hash: TxHash, data: &TxData) {
+        if !is_complete(data) {
+            return;
+        }
+        if let Some(disk_cache) = &self.disk_cache {
+            disk_cache.insert(DISK_CACHE_NAMESPACE, &tx_hash.to_string(), data);
+        }
+        self.insert_into_memory(tx_hash, data);
+    }
+
+    fn insert_into_memory(&self, tx_hash: TxHash, data: &TxData) {
+        let size = match serde_json::to_vec(data) {
+            Ok(serialized) => serialized.len(),
+            Err(_) => return,
+        };
+
+        let mut guard = self.entries.lock().unwrap();
+        let entries = &mut *guard;
+        if size > entries.max_bytes || entries.max_entries == 0 {
+            return;
+        }
+        if let Some((_, old_size)) = entries.cache.remove(&tx_hash) {
+            entries.bytes -= old_size;
+        }
+        let mut evicted = 0u64;
+        while entries.cache.len() >= entries.max_entries || entries.bytes + size > entries.max_bytes
+        {
+            match entries.cache.remove_lru() {
+                Some((_, (_, evicted_size))) => {
+                    entries.bytes -= evicted_size;
+                    evicted += 1;
+                }
+                None => break,
+            }
+        }
+        entries.cache.insert(tx_hash, (data.clone(), size));
+        entries.bytes += size;
+
+        metrics::counter!("api.v02.tx_data_cache.evictions", evicted);
+        metrics::gauge!("api.v02.tx_data_cache.bytes", entries.bytes as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::disk_cache::tests::TestDir;
+    use zksync_api_types::v02::transaction::{
+        ApiDeposit, L1Transaction, Transaction, WithdrawData,
+    };
+    use zksync_types::{tx::Withdraw, AccountId, Address, BlockNumber, Nonce, TokenId, H256};
+
+    fn tx_data(byte: u8, status: TxInBlockStatus) -> (TxHash, TxData) {
+        let tx_hash = TxHash::from_slice(&[byte; 32]).unwrap();
+        let data = TxData {
+            tx: Transaction {
+                tx_hash,
+                block_index: Some(0),
+                block_number: Some(BlockNumber(1)),
+                op: TransactionData::L1(L1Transaction::Deposit(ApiDeposit {
+                    from: Address::repeat_byte(byte),
+                    token_id: TokenId(0),
+                    amount: 1_000_000u32.into(),
+                    to: Address::repeat_byte(byte),
+                    account_id: Some(AccountId(byte as u32)),
+                    eth_hash: H256::repeat_byte(byte),
+                    id: byte as u64,
+                    tx_hash,
+                })),
+                status,
+                fail_reason: None,
+                created_at: None,
+                batch_id: None,
+            },
+            eth_signature: None,
+            l2_signature: None,
+            archived: false,
+        };
+        (tx_hash, data)
+    }
+
+    #[test]
+    fn only_finalized_data_is_cached() {
+        let cache = TxDataCache::new(10, 1 << 20);
+        for (byte, status) in [
+            (1, TxInBlockStatus::Queued),
+            (2, TxInBlockStatus::Committed),
+            (3, TxInBlockStatus::Rejected),
+        ] {
+            let (tx_hash, data) = tx_data(byte, status);
+            cache.insert(tx_hash, &data);
+            assert!(cache.get(&tx_hash).is_none());
+        }
+
+        let (tx_hash, data) = tx_data(4, TxInBlockStatus::Finalized);
+        cache.insert(tx_hash, &data);
+        let cached = cache.get(&tx_hash).unwrap();
+        assert_eq!(
+            serde_json::to_value(cached).unwrap(),
+            serde_json::to_value(data).unwrap()
+        );
+    }
+
+    fn withdrawal_data(eth_tx_hash: Option<H256>) -> (TxHash, TxData) {
+        let withdraw = Withdraw::new(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            1_000_000u32.into(),
+            0u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        );
+        let (tx_hash, mut data) = tx_data(5, TxInBlockStatus::Finalized);
+        data.tx.op = TransactionData::L2(L2Transaction::Withdraw(Box::new(WithdrawData {
+            tx: withdraw,
+            eth_tx_hash,
+        })));
+        (tx_hash, data)
+    }
+
+    /// Looks the data up the same way the API does: from the cache, falling back to the storage.
+    fn lookup(cache: &TxDataCache, tx_hash: TxHash, stored: &TxData) -> TxData {
+        cache.get(&tx_hash).unwrap_or_else(|| {
+            cache.insert(tx_hash, stored);
+            stored.clone()
+        })
+    }
+
+    fn withdrawal_eth_tx_hash(data: &TxData) -> Option<H256> {
+        match &data.tx.op {
+            TransactionData::L2(L2Transaction::Withdraw(withdraw)) => withdraw.eth_tx_hash,
+            _ => panic!("Unexpected transaction: {:?}", data.tx.op),
+        }
+    }
+
+    /// Checks that the finalized withdrawal is not cached until its L1 transaction hash is set.
+    #[test]
+    fn incomplete_withdrawal_is_not_cached() {
+        let cache = TxDataCache::new(10, 1 << 20);
+        let (tx_hash, stored) = withdrawal_data(None);
+        let data = lookup(&cache, tx_hash, &stored);
+        assert_eq!(withdrawal_eth_tx_hash(&data), None);
+        assert!(cache.get(&tx_hash).is_none());
+
+        // The withdrawal is completed on L1.
+        let eth_tx_hash = H256::repeat_byte(0x11);
+        let (_, stored) = withdrawal_data(Some(eth_tx_hash));
+        let data = lookup(&cache, tx_hash, &stored);
+        assert_eq!(withdrawal_eth_tx_hash(&data), Some(eth_tx_hash));
+        let cached = cache.get(&tx_hash).unwrap();
+        assert_eq!(withdrawal_eth_tx_hash(&cached), Some(eth_tx_hash));
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let (_, data) = tx_data(0, TxInBlockStatus::Finalized);
+        let size = serde_json::to_vec(&data).unwrap().len();
+
+        // Bounded by the number of entries.
+        let cache = TxDataCache::new(2, size * 10);
+        let hashes: Vec<_> = (1..=3)
+            .map(|byte| {
+                let (tx_hash, data) = tx_data(byte, TxInBlockStatus::Finalized);
+                cache.insert(tx_hash, &data);
+                tx_hash
+            })
+            .collect();
+        assert!(cache.get(&hashes[0]).is_none());
+        assert!(cache.get(&hashes[1]).is_some());
+        assert!(cache.get(&hashes[2]).is_some());
+
+        // Bounded by the size, the recently used entry is kept.
+        let cache = TxDataCache::new(10, size * 2);
+        let (first_hash, first) = tx_data(1, TxInBlockStatus::Finalized);
+        let (second_hash, second) = tx_data(2, TxInBlockStatus::Finalized);
+        let (third_hash, third) = tx_data(3, TxInBlockStatus::Finalized);
+        cache.insert(first_hash, &first);
+        cache.insert(second_hash, &second);
+        assert!(cache.get(&first_hash).is_some());
+        cache.insert(third_hash, &third);
+        assert!(cache.get(&first_hash).is_some());
+        assert!(cache.get(&second_hash).is_none());
+        assert!(cache.get(&third_hash).is_some());
+
+        // Entries exceeding the limit on their own are not cached, as well as any with the cache disabled.
+        let cache = TxDataCache::new(10, size - 1);
+        cache.insert(first_hash, &first);
+        assert!(cache.get(&first_hash).is_none());
+        let cache = TxDataCache::new(0, size * 10);
+        cache.insert(first_hash, &first);
+        assert!(cache.get(&first_hash).is_none());
+    }
+
+    /// Checks that the data is served from the disk cache once the cache is recreated.
+    #[test]
+    fn data_survives_restart() {
+        let dir = TestDir::new("tx_data_restart");
+        let disk_cache = || DiskCache::open(&dir.0, 1 << 20);
+        let (tx_hash, data) = tx_data(1, TxInBlockStatus::Finalized);
+        let (pending_hash, pending) = tx_data(2, TxInBlockStatus::Committed);
+
+        let cache = TxDataCache::new(10, 1 << 20).with_disk_cache(disk_cache());
+        cache.insert(tx_hash, &data);
+        cache.insert(pending_hash, &pending);
+        drop(cache);
+
+        let cache = TxDataCache::new(10, 1 << 20).with_disk_cache(disk_cache());
+        let cached = cache.get(&tx_hash).unwrap();
+        assert_eq!(
+            serde_json::to_value(cached).unwrap(),
+            serde_json::to_value(&data).unwrap()
+        );
+        assert!(cache.get(&pending_hash).is_none());
+        // The data is kept in memory after it's loaded from the disk.
+        assert!(cache.get_from_memory(&tx_hash).is_some());
+
+        // The cache works without the disk cache as before.
+        let cache = TxDataCache::new(10, 1 << 20);
+        assert!(cache.get(&tx_hash).is_none());
+    }
+
+    /// Checks that the withdrawal without the L1 transaction hash is not written to the disk,
+    /// so it's loaded with the hash from the storage after the restart.
+    #[test]
+    fn incomplete_withdrawal_is_not_persisted() {
+        let dir = TestDir::new("tx_data_incomplete_withdrawal");
+        let disk_cache = || DiskCache::open(&dir.0, 1 << 20);
+        let (tx_hash, stored) = withdrawal_data(None);
+
+        let cache = TxDataCache::new(10, 1 << 20).with_disk_cache(disk_cache());
+        lookup(&cache, tx_hash, &stored);
+        drop(cache);
+
+        let cache = TxDataCache::new(10, 1 << 20).with_disk_cache(disk_cache());
+        assert!(cache.get(&tx_hash).is_none());
+        let eth_tx_hash = H256::repeat_byte(0x11);
+        let (_, stored) = withdrawal_data(Some(eth_tx_hash));
+        lookup(&cache, tx_hash, &stored);
+        drop(cache);
+
+        let cache = TxDataCache::new(10, 1 << 20).with_disk_cache(disk_cache());
+        let cached = cache.get(&tx_hash).unwrap();
+        assert_eq!(withdrawal_eth_tx_hash(&cached), Some(eth_tx_hash));
+    }
+}
@@ -21,11 +21,12 @@ use itertools::Itertools;
 use tokio::{task::JoinHandle, time};
 use web3::types::BlockNumber;
 
+use zksync_api_types::{EthWatchStatus, PriorityOpLookupQuery};
 use zksync_config::{ContractsConfig, ETHWatchConfig};
 use zksync_crypto::params::PRIORITY_EXPIRATION;
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId, H256};
 
 // Local deps
 use self::{client::EthClient, eth_state::ETHState, received_ops::sift_outdated_ops};
@@ -67,6 +68,13 @@ pub enum EthWatchRequest {
         last_eth_block: Option<u64>,
         resp: oneshot::Sender<Vec<RegisterNFTFactoryEvent>>,
     },
+    GetStatus {
+        resp: oneshot::Sender<EthWatchStatus>,
+    },
+    GetUnconfirmedOps {
+        queries: Vec<PriorityOpLookupQuery>,
+        resp: oneshot::Sender<Vec<Option<PriorityOp>>>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -321,6 +329,39 @@ impl<W: EthClient> EthWatch<W> {
         new_tokens
     }
 
+    fn status(&self) -> EthWatchStatus {
+        EthWatchStatus {
+            last_processed_block: self.eth_state.last_ethereum_block(),
+            confirmations_for_event: self.number_of_confirmations_for_event,
+        }
+    }
+
+    /// Looks the operations up in the unconfirmed queue, the results are in the order of the queries.
+    /// The queue is indexed once for all the queries.
+    fn find_unconfirmed_ops(&self, queries: &[PriorityOpLookupQuery]) -> Vec<Option<PriorityOp>> {
+        let unconfirmed_queue = self.eth_state.unconfirmed_queue();
+        let mut by_sync_hash = HashMap::with_capacity(unconfirmed_queue.len());
+        let mut by_eth_hash = HashMap::with_capacity(unconfirmed_queue.len());
+        for op in unconfirmed_queue {
+            by_sync_hash.insert(op.tx_hash(), op);
+            by_eth_hash.insert(op.eth_hash, op);
+        }
+
+        queries
+            .iter()
+            .map(|query| {
+                let op = match query {
+                    PriorityOpLookupQuery::BySyncHash(tx_hash) => by_sync_hash.get(tx_hash),
+                    PriorityOpLookupQuery::ByEthHash(eth_hash) => by_eth_hash.get(eth_hash),
+                    PriorityOpLookupQuery::ByAnyHash(hash) => by_sync_hash
+                        .get(hash)
+                        .or_else(|| by_eth_hash.get(&H256::from_slice(hash.as_ref()))),
+                };
+                op.map(|op| (*op).clone())
+            })
+            .collect()
+    }
+
     async fn poll_eth_node(&mut self) -> anyhow::Result<()> {
         let start = Instant::now();
         let last_block_number = self.client.block_number().await?;
@@ -436,6 +477,12 @@ impl<W: EthClient> EthWatch<W> {
                     resp.send(self.get_register_factory_event(last_eth_block))
                         .ok();
                 }
+                EthWatchRequest::GetStatus { resp } => {
+                    resp.send(self.status()).ok();
+                }
+                EthWatchRequest::GetUnconfirmedOps { queries, resp } => {
+                    resp.send(self.find_unconfirmed_ops(&queries)).ok();
+                }
             }
         }
     }
@@ -5,9 +5,10 @@ use std::sync::Arc;
 
 use web3::types::{Address, BlockNumber};
 
+use zksync_api_types::PriorityOpLookupQuery;
 use zksync_types::{
-    AccountId, Deposit, FullExit, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent,
-    SerialId, TokenId, ZkSyncPriorityOp, H256,
+    tx::TxHash, AccountId, Deposit, FullExit, NewTokenEvent, Nonce, PriorityOp,
+    RegisterNFTFactoryEvent, SerialId, TokenId, ZkSyncPriorityOp, H256,
 };
 
 use futures::channel::mpsc;
@@ -547,3 +548,84 @@ async fn test_serial_id_gaps() {
     assert_eq!(watcher.eth_state.last_ethereum_block_backup(), 0);
     assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
 }
+
+/// Checks that the watcher reports the last processed block and that the remaining
+/// confirmations are never negative, even if the block number goes backwards.
+#[tokio::test]
+async fn test_eth_watch_status() {
+    let (sender, receiver) = mpsc::channel(10);
+    let mut client = FakeEthClient::new();
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+    client.set_last_block_number(10).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.poll_eth_node().await.unwrap();
+
+    let status = watcher.status();
+    assert_eq!(status.last_processed_block, 10);
+    assert_eq!(status.confirmations_for_event, 1);
+    assert_eq!(status.confirmations_remaining(10), 1);
+    assert_eq!(status.confirmations_remaining(9), 0);
+
+    // The watcher may be restored from an older block after restart.
+    watcher.restore_state_from_eth(5).await.unwrap();
+    let status = watcher.status();
+    assert_eq!(status.last_processed_block, 5);
+    assert_eq!(status.confirmations_remaining(2), 0);
+    assert_eq!(status.confirmations_remaining(10), 6);
+}
+
+/// Checks that the unconfirmed operations are found by any of their hashes,
+/// and the results follow the order of the queries.
+#[tokio::test]
+async fn test_find_unconfirmed_ops() {
+    let (sender, receiver) = mpsc::channel(10);
+    let mut client = FakeEthClient::new();
+    let data = Arc::new(RwLock::new(HashMap::new()));
+    tokio::spawn(fake_mempool(receiver, data.clone()));
+
+    let priority_ops: Vec<_> = (0..3)
+        .map(|serial_id| PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Default::default(),
+                token: TokenId(0),
+                amount: Default::default(),
+                to: [serial_id as u8 + 1; 20].into(),
+            }),
+            deadline_block: 0,
+            eth_hash: [serial_id as u8 + 1; 32].into(),
+            eth_block: if serial_id == 0 { 3 } else { 4 },
+            eth_block_index: Some(serial_id),
+        })
+        .collect();
+    client.add_operations(&priority_ops).await;
+
+    let mut watcher = create_watcher(client, sender);
+    watcher.poll_eth_node().await.unwrap();
+    // The first operation is confirmed already, the rest are not.
+    assert_eq!(watcher.eth_state.unconfirmed_queue().len(), 2);
+
+    let any_hash = |hash: H256| TxHash::from_slice(hash.as_bytes()).unwrap();
+    let queries = vec![
+        PriorityOpLookupQuery::ByEthHash(priority_ops[2].eth_hash),
+        PriorityOpLookupQuery::BySyncHash(priority_ops[0].tx_hash()),
+        PriorityOpLookupQuery::BySyncHash(priority_ops[1].tx_hash()),
+        PriorityOpLookupQuery::ByAnyHash(any_hash(priority_ops[1].eth_hash)),
+        PriorityOpLookupQuery::ByAnyHash(priority_ops[2].tx_hash()),
+        PriorityOpLookupQuery::ByEthHash(H256::repeat_byte(0xff)),
+        // Hashes of the different kind don't match.
+        PriorityOpLookupQuery::BySyncHash(any_hash(priority_ops[1].eth_hash)),
+    ];
+    let serial_ids: Vec<_> = watcher
+        .find_unconfirmed_ops(&queries)
+        .into_iter()
+        .map(|op| op.map(|op| op.serial_id))
+        .collect();
+    assert_eq!(
+        serial_ids,
+        vec![Some(2), None, Some(1), Some(1), Some(2), None, None]
+    );
+    assert!(watcher.find_unconfirmed_ops(&[]).is_empty());
+}
@@ -3,13 +3,16 @@ use crate::state_keeper::ZkSyncStateInitParams;
 use crate::{
     committer::run_committer,
     eth_watch::start_eth_watch,
-    state_keeper::{start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper},
+    state_keeper::{
+        start_root_hash_calculator, start_state_keeper, SharedPendingBlockStatus, ZkSyncStateKeeper,
+    },
     token_handler::run_token_handler,
 };
 use futures::{channel::mpsc, future};
 use tokio::task::JoinHandle;
 use zksync_config::{ChainConfig, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
+use zksync_eth_sender::SharedGasAdjusterStatus;
 use zksync_mempool::{run_mempool_block_handler, run_mempool_tx_handler};
 use zksync_storage::ConnectionPool;
 use zksync_types::{tokens::get_genesis_token_list, Token, TokenId, TokenKind};
@@ -25,7 +28,7 @@ pub mod token_handler;
 pub mod tx_event_emitter;
 
 mod genesis;
-mod private_api;
+pub mod private_api;
 
 /// Waits for any of the tokio tasks to be finished.
 /// Since the main tokio tasks are used as actors which should live as long
@@ -92,10 +95,13 @@ pub async fn run_core(
     read_only_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     eth_gateway: EthereumGateway,
+    gas_adjuster_status: SharedGasAdjusterStatus,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
     let (proposed_blocks_sender, proposed_blocks_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
     let (eth_watch_req_sender, eth_watch_req_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+    let (token_handler_req_sender, token_handler_req_receiver) =
+        mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
     let (mempool_tx_request_sender, mempool_tx_request_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
     let (mempool_block_request_sender, mempool_block_request_receiver) =
@@ -110,11 +116,20 @@ pub async fn run_core(
         config.chain.state_keeper.block_chunk_sizes.clone(),
     );
 
+    // Stats of the pending block are published by the state keeper once it's started.
+    let pending_block_status = SharedPendingBlockStatus::default();
+
     // Run health check api for core
     let private_api_task = private_api::start_private_core_api(
         connection_pool.clone(),
         read_only_connection_pool,
         eth_gateway.clone(),
+        eth_watch_req_sender.clone(),
+        token_handler_req_sender,
+        mempool_block_request_sender.clone(),
+        pending_block_status.clone(),
+        gas_adjuster_status,
+        config.eth_sender.sender.daily_fee_budget(),
         config.api.private.clone(),
     );
 
@@ -132,6 +147,20 @@ pub async fn run_core(
     // Insert pending withdrawals into database (if required)
     let mut storage_processor = connection_pool.access_storage().await?;
 
+    // Restore the zkSync hashes of the priority operations stored without them, otherwise
+    // such operations can't be found by their zkSync hash.
+    let restored_hashes = storage_processor
+        .chain()
+        .operations_schema()
+        .restore_priority_op_tx_hashes()
+        .await?;
+    if restored_hashes > 0 {
+        vlog::info!(
+            "Restored zkSync hashes of {} executed priority operations",
+            restored_hashes
+        );
+    }
+
     // Start state keeper and root hash calculator.
     let state_keeper_init = ZkSyncStateInitParams::restore_from_db(
         &mut storage_processor,
@@ -150,6 +179,7 @@ pub async fn run_core(
         config.chain.state_keeper.fast_block_miniblock_iterations as usize,
         processed_tx_events_sender,
     );
+    state_keeper.share_pending_block_status(pending_block_status);
 
     // Execute reverted blocks before start
     state_keeper.execute_reverted_blocks().await;
@@ -172,6 +202,7 @@ pub async fn run_core(
         connection_pool.clone(),
         mempool_block_request_receiver,
         config.chain.state_keeper.block_chunk_sizes.clone(),
+        config.chain.state_keeper.rejected_txs_retention_period(),
     );
 
     // Start token handler.
@@ -180,6 +211,7 @@ pub async fn run_core(
         eth_gateway.clone(),
         &config.token_handler,
         eth_watch_req_sender.clone(),
+        token_handler_req_receiver,
     );
 
     // Start token handler.
@@ -5,22 +5,50 @@
 //! available from outside of the cluster.
 //!
 //! All the incoming data is assumed to be correct and not double-checked
-//! for correctness.
+//! for correctness. If the authentication is enabled, the requests not signed
+//! by the API servers are rejected.
 
+use std::future::Future;
+use std::sync::Arc;
 use std::thread;
 use std::time::{Duration, Instant};
 
-use actix_web::{web, App, HttpResponse, HttpServer};
-use futures::{channel::mpsc, StreamExt};
+use actix_web::{
+    dev::{Payload, Service, ServiceRequest, ServiceResponse},
+    error::PayloadError,
+    http::header,
+    web::{self, Bytes, BytesMut},
+    App, HttpMessage, HttpResponse, HttpServer,
+};
+use futures::{
+    channel::{mpsc, oneshot},
+    future::Either,
+    FutureExt, SinkExt, Stream, StreamExt,
+};
+use serde::Deserialize;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use zksync_api_types::CoreStatus;
+use zksync_api_types::{
+    core_auth::{CoreAuthError, CoreAuthHeaders, CoreAuthVerifier},
+    CoreStatus, DailySpend, EthOperationStatus, EthSenderGasReport, EthTxAttempt, EthWatchStatus,
+    MempoolTxLookup, PendingBlockStatus, PriorityOpLookupQuery, SentEthTxGas, TxCancellation,
+    DEFAULT_GAS_REPORT_TXS, MAX_GAS_REPORT_TXS, MAX_UNCONFIRMED_OPS_BATCH_SIZE,
+};
 
 use zksync_config::configs::api::PrivateApiConfig;
 use zksync_eth_client::EthereumGateway;
-use zksync_storage::ConnectionPool;
+use zksync_eth_sender::SharedGasAdjusterStatus;
+use zksync_mempool::{CancelTxRequest, IsTxProposedRequest, MempoolBlocksRequest};
+use zksync_storage::{ethereum::records::StorageEthOperationGas, ConnectionPool};
+use zksync_types::{
+    event::transaction::TransactionType, tx::TxHash, PriorityOp, TokenId, H256, U256,
+};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
+use crate::eth_watch::EthWatchRequest;
+use crate::state_keeper::SharedPendingBlockStatus;
+use crate::token_handler::{RefetchedTokenMetadata, TokenHandlerRequest};
+
 const STATUS_INVALIDATION_PERIOD: Duration = Duration::from_secs(60);
 
 #[derive(Debug)]
@@ -28,6 +56,12 @@ struct AppState {
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    eth_watch_req_sender: mpsc::Sender<EthWatchRequest>,
+    token_handler_req_sender: mpsc::Sender<TokenHandlerRequest>,
+    mempool_block_req_sender: mpsc::Sender<MempoolBlocksRequest>,
+    pending_block_status: SharedPendingBlockStatus,
+    gas_adjuster_status: SharedGasAdjusterStatus,
+    daily_fee_budget: Option<U256>,
     status_cache: RwLock<Option<(CoreStatus, Instant)>>,
 }
 
@@ -63,13 +97,491 @@ async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Returns the last Ethereum block processed by the Ethereum watcher
+/// and the amount of confirmations required for the priority operations.
+#[actix_web::get("/eth_watch_status")]
+async fn eth_watch_status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    data.eth_watch_req_sender
+        .clone()
+        .send(EthWatchRequest::GetStatus { resp: sender })
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let status: EthWatchStatus = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Looks up the priority operations that are received by the Ethereum watcher, but are not
+/// confirmed yet. The results are in the order of the queries, `null` for the unknown operations.
+#[actix_web::post("/unconfirmed_ops_batch")]
+async fn unconfirmed_ops_batch(
+    data: web::Data<AppState>,
+    web::Json(queries): web::Json<Vec<PriorityOpLookupQuery>>,
+) -> actix_web::Result<HttpResponse> {
+    if queries.len() > MAX_UNCONFIRMED_OPS_BATCH_SIZE {
+        return Err(actix_web::error::ErrorBadRequest(format!(
+            "At most {} operations can be looked up at once",
+            MAX_UNCONFIRMED_OPS_BATCH_SIZE
+        )));
+    }
+
+    let (sender, receiver) = oneshot::channel();
+    data.eth_watch_req_sender
+        .clone()
+        .send(EthWatchRequest::GetUnconfirmedOps {
+            queries,
+            resp: sender,
+        })
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let ops: Vec<Option<PriorityOp>> = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(ops))
+}
+
+/// Returns the stats of the block currently filled by the state keeper.
+/// The stats are updated after every miniblock iteration, so they may be slightly stale.
+#[actix_web::get("/pending_block_status")]
+async fn pending_block_status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let status: Option<PendingBlockStatus> = data.pending_block_status.read().unwrap().clone();
+    let status = status.ok_or_else(|| {
+        actix_web::error::ErrorServiceUnavailable("State keeper is not started yet")
+    })?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+#[derive(Debug, Deserialize)]
+struct ToggleTxTypeRequest {
+    tx_type: TransactionType,
+    disabled: bool,
+}
+
+/// Enables or disables the submission of transactions of the given type.
+/// API servers are notified about the change via the database trigger,
+/// so they invalidate their caches without restart.
+#[actix_web::post("/toggle_tx_type")]
+async fn toggle_tx_type(
+    data: web::Data<AppState>,
+    request: web::Json<ToggleTxTypeRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    storage
+        .misc_schema()
+        .set_tx_type_disabled(&request.tx_type.to_string(), request.disabled)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Debug, Deserialize)]
+struct RefetchTokenMetadataRequest {
+    token_id: TokenId,
+}
+
+/// Queries the symbol and decimals of the ERC20 token from its contract again
+/// and updates the token, unless it's in the trusted token list.
+#[actix_web::post("/refetch_token_metadata")]
+async fn refetch_token_metadata(
+    data: web::Data<AppState>,
+    request: web::Json<RefetchTokenMetadataRequest>,
+) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    data.token_handler_req_sender
+        .clone()
+        .send(TokenHandlerRequest::RefetchMetadata {
+            token_id: request.token_id,
+            resp: sender,
+        })
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let refetched: Option<RefetchedTokenMetadata> = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match refetched {
+        Some(refetched) => Ok(HttpResponse::Ok().json(refetched)),
+        None => Err(actix_web::error::ErrorNotFound("ERC20 token not found")),
+    }
+}
+
+/// Collects the state of the Ethereum operation sent for the aggregated operation,
+/// querying the Ethereum node for every transaction sent for it.
+async fn load_eth_operation_status(
+    data: &AppState,
+    aggregated_op_id: i64,
+) -> actix_web::Result<EthOperationStatus> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mut schema = storage.ethereum_schema();
+    let operation = schema
+        .load_eth_operation_by_aggregated_op(aggregated_op_id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .ok_or_else(|| actix_web::error::ErrorNotFound("ETH operation not found"))?;
+    let tx_hashes = schema
+        .load_eth_tx_hashes(operation.id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let resend_requested = schema
+        .is_eth_tx_resend_requested(operation.id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    drop(storage);
+
+    let mut attempts = Vec::with_capacity(tx_hashes.len());
+    for tx_hash in tx_hashes {
+        let tx_hash = H256::from_slice(&tx_hash.tx_hash);
+        let gas_price = data
+            .eth_client
+            .get_tx(tx_hash)
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?
+            .and_then(|tx| tx.gas_price);
+        let status = data
+            .eth_client
+            .get_tx_status(tx_hash, None)
+            .await
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        attempts.push(EthTxAttempt {
+            tx_hash,
+            gas_price,
+            confirmations: status.as_ref().map(|status| status.confirmations),
+            success: status.map(|status| status.success),
+        });
+    }
+
+    Ok(EthOperationStatus {
+        aggregated_op_id,
+        eth_op_id: operation.id,
+        nonce: operation.nonce,
+        confirmed: operation.confirmed,
+        final_hash: operation.final_hash.map(|hash| H256::from_slice(&hash)),
+        last_deadline_block: operation.last_deadline_block as u64,
+        resend_requested,
+        attempts,
+    })
+}
+
+/// Returns the Ethereum transactions sent for the aggregated operation
+/// with their gas prices and confirmations.
+#[actix_web::get("/eth_operation/{aggregated_op_id}")]
+async fn eth_operation_status(
+    data: web::Data<AppState>,
+    aggregated_op_id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let status = load_eth_operation_status(&data, aggregated_op_id.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(status))
+}
+
+/// Requests the Ethereum sender to resend the transaction of the stuck aggregated operation
+/// with the increased gas price. Repeated requests are no-op until the sender picks the request up,
+/// the request for the confirmed operation is refused.
+#[actix_web::post("/eth_operation/{aggregated_op_id}/resend")]
+async fn resend_eth_operation(
+    data: web::Data<AppState>,
+    aggregated_op_id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let aggregated_op_id = aggregated_op_id.into_inner();
+    let status = load_eth_operation_status(&data, aggregated_op_id).await?;
+    if status.confirmed {
+        return Err(actix_web::error::ErrorConflict(
+            "ETH operation is already confirmed",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    storage
+        .ethereum_schema()
+        .request_eth_tx_resend(status.eth_op_id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(EthOperationStatus {
+        resend_requested: true,
+        ..status
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct GasReportQuery {
+    limit: Option<u32>,
+}
+
+fn sent_eth_tx_gas(operation: StorageEthOperationGas) -> SentEthTxGas {
+    let gas_price = U256::from_dec_str(&operation.last_used_gas_price.to_string())
+        .expect("Stored gas price is not an integer");
+    let gas_used = operation.gas_used.map(|gas_used| gas_used as u64);
+    SentEthTxGas {
+        eth_op_id: operation.id,
+        op_type: operation.op_type,
+        nonce: operation.nonce,
+        confirmed: operation.confirmed,
+        final_hash: operation.final_hash.map(|hash| H256::from_slice(&hash)),
+        gas_price,
+        gas_used,
+        fee: gas_used.map(|gas_used| gas_price.saturating_mul(gas_used.into())),
+        created_at: operation
+            .created_at
+            .map(|created_at| created_at.timestamp() as u64),
+    }
+}
+
+/// Returns the gas price strategy of the Ethereum sender along with the gas used by the latest
+/// sent transactions and the fees paid within the last 24 hours. The state of the gas adjuster
+/// is known only if the Ethereum sender runs in the same process as the core.
+#[actix_web::get("/eth_sender/gas_report")]
+async fn eth_sender_gas_report(
+    data: web::Data<AppState>,
+    query: web::Query<GasReportQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_GAS_REPORT_TXS)
+        .min(MAX_GAS_REPORT_TXS);
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mut schema = storage.ethereum_schema();
+    let stored_gas_price_limit = schema
+        .load_gas_price_limit()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let stored_average_gas_price = schema
+        .load_average_gas_price()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let operations = schema
+        .load_recent_operations_gas(limit)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let spent = schema
+        .load_spent_fees_since(chrono::Utc::now() - chrono::Duration::days(1))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    drop(storage);
+
+    let spent = U256::from_dec_str(&spent.to_string())
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let report = EthSenderGasReport {
+        gas_adjuster: data.gas_adjuster_status.read().unwrap().clone(),
+        stored_gas_price_limit,
+        stored_average_gas_price,
+        recent_txs: operations.into_iter().map(sent_eth_tx_gas).collect(),
+        daily_spend: DailySpend::new(spent, data.daily_fee_budget),
+    };
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// Removes the transaction from the mempool on request of its submitter, unless it's already
+/// proposed for a block. The request is expected to be verified by the API server.
+#[actix_web::post("/mempool/cancel_tx/{tx_hash}")]
+async fn cancel_tx(
+    data: web::Data<AppState>,
+    tx_hash: web::Path<TxHash>,
+) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    data.mempool_block_req_sender
+        .clone()
+        .send(MempoolBlocksRequest::CancelTx(CancelTxRequest {
+            tx_hash: tx_hash.into_inner(),
+            response_sender: sender,
+        }))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let cancelled = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let cancellation = if cancelled {
+        TxCancellation::Cancelled
+    } else {
+        TxCancellation::TooLate
+    };
+    Ok(HttpResponse::Ok().json(cancellation))
+}
+
+/// Looks the transaction up in the mempool. Used by the API servers to find the recently
+/// submitted transactions that are not visible in their databases yet.
+#[actix_web::get("/mempool/tx/{tx_hash}")]
+async fn mempool_tx_lookup(
+    data: web::Data<AppState>,
+    tx_hash: web::Path<TxHash>,
+) -> actix_web::Result<HttpResponse> {
+    let tx_hash = tx_hash.into_inner();
+    let (sender, receiver) = oneshot::channel();
+    data.mempool_block_req_sender
+        .clone()
+        .send(MempoolBlocksRequest::IsTxProposed(IsTxProposedRequest {
+            tx_hash,
+            response_sender: sender,
+        }))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let proposed = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if proposed {
+        return Ok(HttpResponse::Ok().json(MempoolTxLookup::Proposed));
+    }
+
+    let pending = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?
+        .chain()
+        .mempool_schema()
+        .contains_tx(tx_hash)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let lookup = if pending {
+        MempoolTxLookup::Pending
+    } else {
+        MempoolTxLookup::Unknown
+    };
+    Ok(HttpResponse::Ok().json(lookup))
+}
+
+/// Rejects the requests not signed with the secrets known to the verifier, meant to be used
+/// with `App::wrap_fn`. The requests are passed through if the verifier is not set.
+///
+/// The signature covers the body, so the body is read beforehand and is handed to the handler
+/// only once the request is verified. The headers and the timestamp are checked before the body
+/// is read, and the body is read only up to the size limit of the verifier.
+pub fn verify_request<S, B>(
+    verifier: &Option<Arc<CoreAuthVerifier>>,
+    mut req: ServiceRequest,
+    service: &S,
+) -> impl Future<Output = Result<ServiceResponse<B>, actix_web::Error>>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+{
+    let verifier = match verifier {
+        Some(verifier) => verifier.clone(),
+        None => return Either::Left(service.call(req)),
+    };
+    let method = req.method().to_string();
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or_else(|| req.path())
+        .to_owned();
+    let headers = CoreAuthHeaders::parse(|name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    });
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    let mut payload = req.take_payload();
+    let (body_sender, body_receiver) = oneshot::channel::<Bytes>();
+    let verified_body = body_receiver
+        .into_stream()
+        .map(|body| body.map_err(|_| PayloadError::Incomplete(None)));
+    req.set_payload(Payload::Stream(Box::pin(verified_body)));
+    // The handler doesn't run until the response is awaited.
+    let response = service.call(req);
+
+    Either::Right(async move {
+        let reject = |err: CoreAuthError| {
+            metrics::increment_counter!("private_api.rejected_requests");
+            vlog::warn!("Request {} {} is rejected: {}", method, path, err);
+            match err {
+                CoreAuthError::BodyTooLarge => actix_web::error::ErrorPayloadTooLarge(err),
+                _ => actix_web::error::ErrorUnauthorized(err),
+            }
+        };
+
+        let headers = headers
+            .and_then(|headers| verifier.check_headers(&headers).map(|_| headers))
+            .map_err(reject)?;
+        let max_body_size = verifier.max_body_size();
+        if content_length.map_or(false, |length| length > max_body_size) {
+            return Err(reject(CoreAuthError::BodyTooLarge));
+        }
+        let body = read_body(&mut payload, max_body_size)
+            .await?
+            .ok_or_else(|| reject(CoreAuthError::BodyTooLarge))?;
+
+        verifier
+            .verify(&method, &path, &headers, &body)
+            .map_err(reject)?;
+        // The receiver is dropped only along with the request.
+        body_sender.send(body).ok();
+        response.await
+    })
+}
+
+/// Reads the body of the request, returns `None` as soon as it turns out to be larger than
+/// `max_body_size` bytes, without reading the rest of it.
+async fn read_body<P>(payload: &mut P, max_body_size: usize) -> Result<Option<Bytes>, PayloadError>
+where
+    P: Stream<Item = Result<Bytes, PayloadError>> + Unpin,
+{
+    let mut body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > max_body_size {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(body.freeze()))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn start_private_core_api(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
+    eth_watch_req_sender: mpsc::Sender<EthWatchRequest>,
+    token_handler_req_sender: mpsc::Sender<TokenHandlerRequest>,
+    mempool_block_req_sender: mpsc::Sender<MempoolBlocksRequest>,
+    pending_block_status: SharedPendingBlockStatus,
+    gas_adjuster_status: SharedGasAdjusterStatus,
+    daily_fee_budget: Option<U256>,
     config: PrivateApiConfig,
 ) -> JoinHandle<()> {
     let (panic_sender, mut panic_receiver) = mpsc::channel(1);
+    let verifier = config.auth_enabled.then(|| {
+        let secrets = config.auth_secrets();
+        assert!(
+            !secrets.is_empty(),
+            "Authentication of the private API is enabled, but the secret is not set"
+        );
+        Arc::new(CoreAuthVerifier::new(
+            secrets,
+            config.auth_max_clock_skew(),
+            config.auth_max_body_size,
+        ))
+    });
 
     thread::Builder::new()
         .name("core-private-api".to_string())
@@ -84,16 +596,35 @@ pub fn start_private_core_api(
                         connection_pool: connection_pool.clone(),
                         read_only_connection_pool: read_only_connection_pool.clone(),
                         eth_client: eth_client.clone(),
+                        eth_watch_req_sender: eth_watch_req_sender.clone(),
+                        token_handler_req_sender: token_handler_req_sender.clone(),
+                        mempool_block_req_sender: mempool_block_req_sender.clone(),
+                        pending_block_status: pending_block_status.clone(),
+                        gas_adjuster_status: gas_adjuster_status.clone(),
+                        daily_fee_budget,
                         status_cache: Default::default(),
                     };
 
+                    let verifier = verifier.clone();
+
                     // By calling `register_data` instead of `data` we're avoiding double
                     // `Arc` wrapping of the object.
                     App::new()
+                        .wrap_fn(move |req, srv| verify_request(&verifier, req, srv))
                         .wrap(actix_web::middleware::Logger::default())
                         .app_data(web::Data::new(app_state))
                         .app_data(web::JsonConfig::default().limit(2usize.pow(32)))
                         .service(status)
+                        .service(eth_watch_status)
+                        .service(unconfirmed_ops_batch)
+                        .service(pending_block_status)
+                        .service(toggle_tx_type)
+                        .service(refetch_token_metadata)
+                        .service(eth_operation_status)
+                        .service(resend_eth_operation)
+                        .service(eth_sender_gas_report)
+                        .service(cancel_tx)
+                        .service(mempool_tx_lookup)
                 })
                 .bind(&config.bind_addr())
                 .expect("failed to bind")
@@ -107,3 +638,63 @@ pub fn start_private_core_api(
         panic_receiver.next().await.unwrap();
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_storage::BigDecimal;
+
+    #[test]
+    fn sent_eth_tx_fee() {
+        let operation = StorageEthOperationGas {
+            id: 1,
+            op_type: "CommitBlocks".to_string(),
+            nonce: 10,
+            confirmed: true,
+            final_hash: Some(H256::repeat_byte(0x11).as_bytes().to_vec()),
+            last_used_gas_price: BigDecimal::from(150_000_000_000u64),
+            gas_used: Some(200_000),
+            created_at: Some(chrono::Utc::now()),
+        };
+        let tx = sent_eth_tx_gas(operation.clone());
+        assert_eq!(tx.gas_price, U256::from(150_000_000_000u64));
+        assert_eq!(tx.gas_used, Some(200_000));
+        assert_eq!(tx.fee, Some(U256::from(30_000_000_000_000_000u64)));
+        assert_eq!(tx.final_hash, Some(H256::repeat_byte(0x11)));
+
+        // The fee is unknown until the transaction is confirmed.
+        let tx = sent_eth_tx_gas(StorageEthOperationGas {
+            confirmed: false,
+            final_hash: None,
+            gas_used: None,
+            created_at: None,
+            ..operation
+        });
+        assert_eq!(tx.fee, None);
+        assert_eq!(tx.created_at, None);
+    }
+
+    #[tokio::test]
+    async fn body_size_limit() {
+        let chunks = || {
+            futures::stream::iter(
+                vec![Bytes::from_static(b"1234"), Bytes::from_static(b"5678")]
+                    .into_iter()
+                    .map(Ok::<_, PayloadError>),
+            )
+        };
+        let body = read_body(&mut chunks(), 8).await.unwrap();
+        assert_eq!(body, Some(Bytes::from_static(b"12345678")));
+
+        // The rest of the body isn't read once the limit is exceeded.
+        let mut payload = chunks();
+        assert_eq!(read_body(&mut payload, 6).await.unwrap(), None);
+        assert!(payload.next().await.is_none());
+        let mut payload = chunks();
+        assert_eq!(read_body(&mut payload, 2).await.unwrap(), None);
+        assert_eq!(
+            payload.next().await.unwrap().unwrap(),
+            Bytes::from_static(b"5678")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 // External uses
@@ -10,6 +11,7 @@ use futures::{
 use tokio::task::JoinHandle;
 use tokio::time;
 // Workspace uses
+use zksync_api_types::PendingBlockStatus;
 use zksync_mempool::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
 use zksync_state::state::{OpSuccess, ZkSyncState};
 use zksync_types::tx::TxHash;
@@ -51,6 +53,9 @@ mod utils;
 #[cfg(test)]
 mod tests;
 
+/// Stats of the pending block, published by the state keeper after every miniblock iteration.
+pub type SharedPendingBlockStatus = Arc<RwLock<Option<PendingBlockStatus>>>;
+
 /// Responsible for tx processing and block forming.
 pub struct ZkSyncStateKeeper {
     /// Current plasma state
@@ -71,6 +76,8 @@ pub struct ZkSyncStateKeeper {
     /// Queue of reverted blocks
     /// They will be executed before the start of the StateKeeper
     reverted_blocks: VecDeque<IncompleteBlock>,
+
+    pending_block_status: SharedPendingBlockStatus,
 }
 
 impl ZkSyncStateKeeper {
@@ -159,6 +166,7 @@ impl ZkSyncStateKeeper {
 
             root_hash_queue,
             reverted_blocks: initial_state.reverted_blocks.clone(),
+            pending_block_status: Default::default(),
         };
         keeper.initialize(initial_state.pending_block);
 
@@ -275,8 +283,14 @@ impl ZkSyncStateKeeper {
         }
     }
 
+    /// Makes the state keeper publish the stats of the pending block into the shared status.
+    pub fn share_pending_block_status(&mut self, pending_block_status: SharedPendingBlockStatus) {
+        self.pending_block_status = pending_block_status;
+    }
+
     // Generate and execute new miniblock every miniblock_interval
     async fn run(mut self, miniblock_interval: Duration) {
+        self.publish_pending_block_status(miniblock_interval);
         let mut timer = time::interval(miniblock_interval);
         loop {
             let start = Instant::now();
@@ -297,6 +311,49 @@ impl ZkSyncStateKeeper {
             metrics::histogram!("miniblock_size", proposed_block.size() as f64);
 
             self.execute_proposed_block(proposed_block).await;
+            self.publish_pending_block_status(miniblock_interval);
+        }
+    }
+
+    /// Returns the stats of the pending block. The block is sealed after the limited amount of the
+    /// miniblock iterations, the iterations are counted once the first operation is included.
+    fn pending_block_status(&self, miniblock_interval: Duration) -> PendingBlockStatus {
+        let now = system_time_timestamp();
+        let block = &self.pending_block;
+        let expected_seal_at = if block.success_operations.is_empty() {
+            None
+        } else {
+            let iterations_left = self
+                .max_miniblock_iterations()
+                .saturating_sub(block.pending_block_iteration);
+            Some(now + (miniblock_interval * iterations_left as u32).as_secs())
+        };
+
+        PendingBlockStatus {
+            block_number: block.number,
+            chunks_used: (self.config.max_block_size() - block.chunks_left) as u64,
+            chunks_capacity: self.config.max_block_size() as u64,
+            txs_count: block.success_operations.len() as u64,
+            failed_txs_count: block.failed_txs.len() as u64,
+            fast_processing: block.fast_processing_required,
+            opened_at: block.timestamp,
+            open_for_secs: now.saturating_sub(block.timestamp),
+            expected_seal_at,
+            updated_at: now,
+        }
+    }
+
+    fn publish_pending_block_status(&self, miniblock_interval: Duration) {
+        let status = self.pending_block_status(miniblock_interval);
+        *self.pending_block_status.write().unwrap() = Some(status);
+    }
+
+    fn max_miniblock_iterations(&self) -> usize {
+        // If pending block contains withdrawals we seal it faster
+        if self.pending_block.fast_processing_required {
+            self.config.fast_miniblock_iterations
+        } else {
+            self.config.max_miniblock_iterations
         }
     }
 
@@ -472,12 +529,7 @@ impl ZkSyncStateKeeper {
         // Iteration is complete, increment it in the pending block.
         self.pending_block.increment_iteration();
 
-        // If pending block contains withdrawals we seal it faster
-        let max_miniblock_iterations = if self.pending_block.fast_processing_required {
-            self.config.fast_miniblock_iterations
-        } else {
-            self.config.max_miniblock_iterations
-        };
+        let max_miniblock_iterations = self.max_miniblock_iterations();
 
         // Check whether we should seal this block and start processing the next one, or we just need
         // to persist the pending block.
@@ -1,5 +1,6 @@
 use chrono::Utc;
 use num::BigUint;
+use std::time::Duration;
 use zksync_types::tx::TimeRange;
 use zksync_types::{AccountId, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx};
 
@@ -254,3 +255,39 @@ async fn correctly_restore_pending_block_timestamp() {
         "Tx with the same valid_until as for previous transaction should've been processed"
     );
 }
+
+/// Checks that the stats of the pending block reflect the included operations and the seal deadline.
+#[tokio::test]
+async fn pending_block_status() {
+    let mut tester = StateKeeperTester::new(20, 3, 3);
+    let miniblock_interval = Duration::from_secs(10);
+
+    let status = tester.state_keeper.pending_block_status(miniblock_interval);
+    assert_eq!(
+        status.block_number,
+        tester.state_keeper.pending_block.number
+    );
+    assert_eq!(status.chunks_used, 0);
+    assert_eq!(status.chunks_capacity, 20);
+    assert_eq!(status.txs_count, 0);
+    // The empty block is not sealed until the first operation is included.
+    assert_eq!(status.expected_seal_at, None);
+
+    let deposit = create_deposit(TokenId(0), 12u32);
+    assert!(tester
+        .state_keeper
+        .apply_priority_op(&deposit)
+        .is_included());
+    tester.state_keeper.pending_block.increment_iteration();
+
+    let status = tester.state_keeper.pending_block_status(miniblock_interval);
+    assert_eq!(
+        status.chunks_used,
+        20 - tester.state_keeper.pending_block.chunks_left as u64
+    );
+    assert_eq!(status.txs_count, 1);
+    assert_eq!(status.failed_txs_count, 0);
+    assert!(!status.fast_processing);
+    // Two of three iterations are left.
+    assert_eq!(status.expected_seal_at, Some(status.updated_at + 20));
+}
@@ -3,38 +3,153 @@
 //!
 //! To set the name and the decimals parameter for the token, a match is searched for with the
 //! token list (which is taken from the environment). If the token address is not found in the
-//! trusted token list, then the default values are used (name = "ERC20-{id}", decimals = 18).
+//! trusted token list, then the `symbol()` and `decimals()` are queried from the token contract.
+//! The default values (name = "ERC20-{id}", decimals = 18) are used for the values the contract
+//! failed to return, the failure is recorded in the database as the unknown metadata.
+//! Tokens stored with the default values before are periodically reconciled with their contracts,
+//! the metadata of a single token can also be queried again via the private API.
+//!
+//! If the symbol of a known token in the token list differs from the stored one, the token is renamed.
+//! The previous symbol remains an alias of the token for the configured period.
+//!
+//! The progress of adding every token is recorded in the database, so it can be reported via API.
 
 // Built-in deps
 use std::collections::HashMap;
+use std::time::Instant;
 // External uses
+use chrono::Utc;
 use futures::{
     channel::{mpsc, oneshot},
     SinkExt,
 };
+use serde::Serialize;
 use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_config::TokenHandlerConfig;
 use zksync_notifier::Notifier;
-use zksync_storage::{tokens::StoreTokenError, ConnectionPool, StorageProcessor};
+use zksync_storage::{
+    tokens::{
+        records::{TokenChangeSource, TokenMetadata, TokenMetadataChange},
+        StoreTokenError,
+    },
+    ConnectionPool, StorageProcessor,
+};
 use zksync_types::{
     tokens::{NewTokenEvent, Token, TokenInfo},
-    Address, TokenId, TokenKind, U256,
+    Address, TokenId, TokenKind, TokenLike, H256, U256,
 };
 // Local uses
 use crate::eth_watch::EthWatchRequest;
 use web3::contract::Options;
-use zksync_contracts::erc20_contract;
+use zksync_contracts::{erc20_bytes32_metadata_contract, erc20_contract, erc20_metadata_contract};
 use zksync_eth_client::EthereumGateway;
 
+/// Maximum number of tokens queried from L1 in a single reconciliation pass.
+const METADATA_RECONCILIATION_BATCH_SIZE: u32 = 100;
+/// Maximum length of the symbol accepted from the token contract.
+const MAX_SYMBOL_LENGTH: usize = 32;
+
+#[derive(Debug)]
+pub enum TokenHandlerRequest {
+    /// Queries the metadata of the token from its contract again and updates the stored token.
+    /// `None` is returned if the token doesn't exist.
+    RefetchMetadata {
+        token_id: TokenId,
+        resp: oneshot::Sender<anyhow::Result<Option<RefetchedTokenMetadata>>>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RefetchedTokenMetadata {
+    /// Token after the update.
+    pub token: Token,
+    /// Symbol returned by the contract, `None` if the call failed.
+    pub symbol: Option<String>,
+    /// Decimals returned by the contract, `None` if the call failed.
+    pub decimals: Option<u8>,
+}
+
+/// Queries the `symbol()` and `decimals()` of the token contract.
+/// Symbols returned as `bytes32` by the older tokens are supported as well.
+async fn fetch_token_metadata(eth_client: &EthereumGateway, address: Address) -> TokenMetadata {
+    let symbol = match eth_client
+        .call_contract_function::<String, _, _, _>(
+            "symbol",
+            (),
+            None,
+            Options::default(),
+            None,
+            address,
+            erc20_metadata_contract(),
+        )
+        .await
+    {
+        Ok(symbol) => parse_symbol(&symbol),
+        Err(_) => eth_client
+            .call_contract_function::<H256, _, _, _>(
+                "symbol",
+                (),
+                None,
+                Options::default(),
+                None,
+                address,
+                erc20_bytes32_metadata_contract(),
+            )
+            .await
+            .ok()
+            .and_then(parse_bytes32_symbol),
+    };
+    let decimals = eth_client
+        .call_contract_function::<U256, _, _, _>(
+            "decimals",
+            (),
+            None,
+            Options::default(),
+            None,
+            address,
+            erc20_metadata_contract(),
+        )
+        .await
+        .ok()
+        .filter(|decimals| *decimals <= U256::from(u8::MAX))
+        .map(|decimals| decimals.as_u32() as u8);
+
+    TokenMetadata { symbol, decimals }
+}
+
+/// Returns `None` for the symbols that can't be used in the requests.
+fn parse_symbol(symbol: &str) -> Option<String> {
+    let symbol = symbol.trim();
+    let is_valid = !symbol.is_empty()
+        && symbol.len() <= MAX_SYMBOL_LENGTH
+        && symbol.chars().all(|c| c.is_ascii_graphic());
+    is_valid.then(|| symbol.to_string())
+}
+
+/// The `bytes32` symbol is a string padded with zeros.
+fn parse_bytes32_symbol(symbol: H256) -> Option<String> {
+    let bytes = symbol.as_bytes();
+    let len = bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |pos| pos + 1);
+    std::str::from_utf8(&bytes[..len])
+        .ok()
+        .and_then(parse_symbol)
+}
+
 struct TokenHandler {
     connection_pool: ConnectionPool,
     poll_interval: std::time::Duration,
     eth_watcher_req: mpsc::Sender<EthWatchRequest>,
     eth_client: EthereumGateway,
     token_list: HashMap<Address, TokenInfo>,
+    symbol_alias_validity: chrono::Duration,
+    metadata_reconciliation_interval: std::time::Duration,
     last_eth_block: Option<u64>,
     notifier: Option<Notifier>,
+    requests: mpsc::Receiver<TokenHandlerRequest>,
 }
 
 impl TokenHandler {
@@ -43,8 +158,11 @@ impl TokenHandler {
         eth_watcher_req: mpsc::Sender<EthWatchRequest>,
         eth_client: EthereumGateway,
         config: TokenHandlerConfig,
+        requests: mpsc::Receiver<TokenHandlerRequest>,
     ) -> Self {
         let poll_interval = config.poll_interval();
+        let symbol_alias_validity = chrono::Duration::from_std(config.symbol_alias_validity())
+            .expect("Symbol alias validity period is too big");
         let token_list = config
             .token_list()
             .into_iter()
@@ -58,10 +176,13 @@ impl TokenHandler {
             connection_pool,
             eth_client,
             token_list,
+            symbol_alias_validity,
+            metadata_reconciliation_interval: config.metadata_reconciliation_interval(),
             poll_interval,
             notifier,
             last_eth_block: None, // TODO: Maybe load last viewed Ethereum block number for TokenHandler from DB (ZKS-518).
             eth_watcher_req,
+            requests,
         }
     }
 
@@ -99,6 +220,19 @@ impl TokenHandler {
         storage: &mut StorageProcessor<'_>,
         tokens: Vec<NewTokenEvent>,
     ) -> anyhow::Result<Vec<Token>> {
+        // Events are recorded outside of the transaction, so they are visible
+        // even if the token could not be stored.
+        for token_event in &tokens {
+            storage
+                .tokens_schema()
+                .record_token_listing(
+                    token_event.address,
+                    token_event.id,
+                    token_event.eth_block_number,
+                )
+                .await?;
+        }
+
         let mut transaction = storage.start_transaction().await?;
         let mut token_schema = transaction.tokens_schema();
 
@@ -110,11 +244,13 @@ impl TokenHandler {
                 continue;
             }
 
-            // Find a token in the list of trusted tokens
+            // Find a token in the list of trusted tokens, otherwise query the contract
             // or use default values (name = "ERC20-{id}", decimals = 18).
             let default_symbol = format!("ERC20-{}", token_event.id);
             let default_decimals = 18;
 
+            let mut store_error = None;
+
             let is_erc20 = self.is_contract_erc20(token_event.address).await;
             let token_kind = if is_erc20 {
                 TokenKind::ERC20
@@ -122,59 +258,41 @@ impl TokenHandler {
                 TokenKind::None
             };
 
-            let token_from_list = {
-                let token_info = self.token_list.get(&token_event.address).cloned();
-
-                if let Some(token_info) = token_info {
-                    Some(Token::new(
-                        token_event.id,
-                        token_info.address,
-                        &token_info.symbol,
-                        token_info.decimals,
-                        token_kind,
-                    ))
-                } else {
-                    None
+            let mut metadata = None;
+            let (symbol, decimals) = match self.token_list.get(&token_event.address) {
+                Some(token_info) => (token_info.symbol.clone(), token_info.decimals),
+                None if is_erc20 => {
+                    let token_metadata =
+                        fetch_token_metadata(&self.eth_client, token_event.address).await;
+                    let symbol = token_metadata
+                        .symbol
+                        .clone()
+                        .unwrap_or_else(|| default_symbol.clone());
+                    let decimals = token_metadata.decimals.unwrap_or(default_decimals);
+                    metadata = Some(token_metadata);
+                    (symbol, decimals)
                 }
+                None => (default_symbol.clone(), default_decimals),
             };
 
-            let token = match token_from_list {
-                Some(token_from_list) => {
-                    let try_insert_token = token_schema.store_token(token_from_list.clone()).await;
-
-                    match try_insert_token {
-                        Ok(..) => token_from_list,
-                        Err(StoreTokenError::TokenAlreadyExistsError(..)) => {
-                            // If a token with such parameters already exists in the database
-                            // then try insert token with other symbol.
-                            let token = Token::new(
-                                token_from_list.id,
-                                token_from_list.address,
-                                &default_symbol,
-                                token_from_list.decimals,
-                                token_kind,
-                            );
-                            let try_insert_token = token_schema.store_token(token.clone()).await;
-                            match try_insert_token {
-                                Ok(..) => (),
-                                Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
-                                Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
-                                    vlog::warn!("failed to store token in database: {}", err)
-                                }
-                            }
-
-                            token
-                        }
-                        Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
-                    }
-                }
-                None => {
-                    // Token with default parameters.
+            let token = Token::new(
+                token_event.id,
+                token_event.address,
+                &symbol,
+                decimals,
+                token_kind,
+            );
+            let try_insert_token = token_schema.store_token(token.clone()).await;
+            let token = match try_insert_token {
+                Ok(..) => token,
+                Err(StoreTokenError::TokenAlreadyExistsError(..)) if symbol != default_symbol => {
+                    // If a token with such parameters already exists in the database
+                    // then try insert token with other symbol.
                     let token = Token::new(
                         token_event.id,
                         token_event.address,
                         &default_symbol,
-                        default_decimals,
+                        decimals,
                         token_kind,
                     );
                     let try_insert_token = token_schema.store_token(token.clone()).await;
@@ -182,14 +300,29 @@ impl TokenHandler {
                         Ok(..) => (),
                         Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
                         Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
-                            vlog::warn!("failed to store token in database: {}", err)
+                            vlog::warn!("failed to store token in database: {}", err);
+                            store_error = Some(err);
                         }
                     }
 
                     token
                 }
+                Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
+                    vlog::warn!("failed to store token in database: {}", err);
+                    store_error = Some(err);
+                    token
+                }
+                Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
             };
 
+            if let Some(metadata) = metadata {
+                token_schema
+                    .store_token_metadata(token.id, &metadata)
+                    .await?;
+            }
+            token_schema
+                .record_token_listing_result(token_event.address, store_error.as_deref())
+                .await?;
             new_tokens.push(token);
         }
 
@@ -197,8 +330,205 @@ impl TokenHandler {
         Ok(new_tokens)
     }
 
+    /// Renames the stored tokens whose symbols in the token list have changed.
+    async fn update_renamed_tokens(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let tokens = storage.tokens_schema().load_tokens().await?;
+        let valid_until = Utc::now() + self.symbol_alias_validity;
+
+        for token in tokens.values() {
+            let new_symbol = match self.token_list.get(&token.address) {
+                Some(token_info) if token_info.symbol != token.symbol => &token_info.symbol,
+                _ => continue,
+            };
+            // The symbol may be taken by another token, e.g. if the token was stored
+            // with the default symbol because of the conflict.
+            let token_with_symbol = storage
+                .tokens_schema()
+                .get_token(TokenLike::Symbol(new_symbol.clone()))
+                .await?;
+            if matches!(token_with_symbol, Some(other) if other.id != token.id) {
+                vlog::warn!(
+                    "Failed to rename token {} to {}: symbol is already used",
+                    token.id,
+                    new_symbol
+                );
+                continue;
+            }
+
+            if storage
+                .tokens_schema()
+                .update_token_metadata(
+                    token.id,
+                    TokenMetadataChange::Symbol {
+                        symbol: new_symbol.clone(),
+                        alias_valid_until: valid_until,
+                    },
+                    &TokenChangeSource::TokenHandler,
+                )
+                .await?
+            {
+                vlog::info!(
+                    "Token {} was renamed from {} to {}",
+                    token.id,
+                    token.symbol,
+                    new_symbol
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Stores the metadata queried from the token contract and updates the token,
+    /// unless it's in the token list. Unknown values are not changed.
+    async fn apply_token_metadata(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token: Token,
+        metadata: &TokenMetadata,
+    ) -> anyhow::Result<Token> {
+        let mut transaction = storage.start_transaction().await?;
+        transaction
+            .tokens_schema()
+            .store_token_metadata(token.id, metadata)
+            .await?;
+        if self.token_list.contains_key(&token.address) {
+            transaction.commit().await?;
+            return Ok(token);
+        }
+
+        if let Some(decimals) = metadata
+            .decimals
+            .filter(|decimals| *decimals != token.decimals)
+        {
+            transaction
+                .tokens_schema()
+                .update_token_metadata(
+                    token.id,
+                    TokenMetadataChange::Decimals(decimals),
+                    &TokenChangeSource::TokenHandler,
+                )
+                .await?;
+            vlog::info!(
+                "Decimals of token {} were changed from {} to {}",
+                token.id,
+                token.decimals,
+                decimals
+            );
+        }
+        if let Some(symbol) = metadata
+            .symbol
+            .as_ref()
+            .filter(|symbol| **symbol != token.symbol)
+        {
+            let token_with_symbol = transaction
+                .tokens_schema()
+                .get_token(TokenLike::Symbol(symbol.clone()))
+                .await?;
+            if matches!(token_with_symbol, Some(other) if other.id != token.id) {
+                vlog::warn!(
+                    "Failed to rename token {} to {}: symbol is already used",
+                    token.id,
+                    symbol
+                );
+            } else if transaction
+                .tokens_schema()
+                .update_token_metadata(
+                    token.id,
+                    TokenMetadataChange::Symbol {
+                        symbol: symbol.clone(),
+                        alias_valid_until: Utc::now() + self.symbol_alias_validity,
+                    },
+                    &TokenChangeSource::TokenHandler,
+                )
+                .await?
+            {
+                vlog::info!(
+                    "Token {} was renamed from {} to {}",
+                    token.id,
+                    token.symbol,
+                    symbol
+                );
+            }
+        }
+
+        let token = transaction
+            .tokens_schema()
+            .get_token(TokenLike::Id(token.id))
+            .await?
+            .unwrap_or(token);
+        transaction.commit().await?;
+        Ok(token)
+    }
+
+    /// Queries the metadata of the tokens stored with the placeholder symbol.
+    async fn reconcile_token_metadata(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let tokens = storage
+            .tokens_schema()
+            .load_placeholder_tokens(METADATA_RECONCILIATION_BATCH_SIZE)
+            .await?;
+        for token in tokens {
+            let metadata = fetch_token_metadata(&self.eth_client, token.address).await;
+            self.apply_token_metadata(storage, token, &metadata).await?;
+        }
+        Ok(())
+    }
+
+    async fn refetch_token_metadata(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        token_id: TokenId,
+    ) -> anyhow::Result<Option<RefetchedTokenMetadata>> {
+        let token = storage
+            .tokens_schema()
+            .get_token(TokenLike::Id(token_id))
+            .await?;
+        let token = match token {
+            Some(token) if token.kind == TokenKind::ERC20 => token,
+            _ => return Ok(None),
+        };
+
+        let metadata = fetch_token_metadata(&self.eth_client, token.address).await;
+        let token = self.apply_token_metadata(storage, token, &metadata).await?;
+        Ok(Some(RefetchedTokenMetadata {
+            token,
+            symbol: metadata.symbol,
+            decimals: metadata.decimals,
+        }))
+    }
+
+    async fn handle_request(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        request: TokenHandlerRequest,
+    ) {
+        match request {
+            TokenHandlerRequest::RefetchMetadata { token_id, resp } => {
+                let result = self.refetch_token_metadata(storage, token_id).await;
+                resp.send(result).unwrap_or_default();
+            }
+        }
+    }
+
     async fn run(&mut self) {
+        {
+            let mut storage = self
+                .connection_pool
+                .access_storage()
+                .await
+                .expect("db connection failed for token handler");
+            self.update_renamed_tokens(&mut storage)
+                .await
+                .expect("failed to rename tokens");
+        }
+
         let mut timer = tokio::time::interval(self.poll_interval);
+        let mut last_reconciliation: Option<Instant> = None;
         loop {
             timer.tick().await;
 
@@ -233,6 +563,22 @@ impl TokenHandler {
                         });
                 }
             }
+
+            if last_reconciliation.map_or(true, |last| {
+                last.elapsed() >= self.metadata_reconciliation_interval
+            }) {
+                self.reconcile_token_metadata(&mut storage)
+                    .await
+                    .unwrap_or_else(|err| {
+                        vlog::error!("Failed to reconcile the token metadata: {}", err);
+                    });
+                last_reconciliation = Some(Instant::now());
+            }
+
+            // Requests are handled between the polls, so they don't race with the new tokens.
+            while let Ok(Some(request)) = self.requests.try_next() {
+                self.handle_request(&mut storage, request).await;
+            }
         }
     }
 }
@@ -243,12 +589,107 @@ pub fn run_token_handler(
     eth_client: EthereumGateway,
     config: &TokenHandlerConfig,
     eth_watcher_req: mpsc::Sender<EthWatchRequest>,
+    requests: mpsc::Receiver<TokenHandlerRequest>,
 ) -> JoinHandle<()> {
     let config = config.clone();
     tokio::spawn(async move {
-        let mut token_handler =
-            TokenHandler::new(db_pool, eth_watcher_req, eth_client, config.clone());
+        let mut token_handler = TokenHandler::new(
+            db_pool,
+            eth_watcher_req,
+            eth_client,
+            config.clone(),
+            requests,
+        );
 
         token_handler.run().await
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethabi::Token as AbiToken;
+    use zksync_eth_client::clients::mock::MockEthereum;
+
+    fn bytes32(value: &str) -> AbiToken {
+        let mut bytes = [0u8; 32];
+        bytes[..value.len()].copy_from_slice(value.as_bytes());
+        AbiToken::FixedBytes(bytes.to_vec())
+    }
+
+    #[tokio::test]
+    async fn fetch_metadata() {
+        let string_token = Address::repeat_byte(1);
+        let bytes32_token = Address::repeat_byte(2);
+        let reverting_token = Address::repeat_byte(3);
+        let invalid_token = Address::repeat_byte(4);
+
+        let mut eth_client = MockEthereum::default();
+        eth_client
+            .add_contract_call(
+                string_token,
+                "symbol",
+                vec![AbiToken::String("USDC".to_string())],
+            )
+            .await;
+        eth_client
+            .add_contract_call(string_token, "decimals", vec![AbiToken::Uint(6.into())])
+            .await;
+        // Output of the `bytes32` symbol can't be decoded as a string.
+        eth_client
+            .add_contract_call(bytes32_token, "symbol", vec![bytes32("MKR")])
+            .await;
+        eth_client
+            .add_contract_call(bytes32_token, "decimals", vec![AbiToken::Uint(18.into())])
+            .await;
+        eth_client
+            .add_contract_call(
+                invalid_token,
+                "symbol",
+                vec![AbiToken::String(" ".to_string())],
+            )
+            .await;
+        eth_client
+            .add_contract_call(invalid_token, "decimals", vec![AbiToken::Uint(256.into())])
+            .await;
+        let eth_client = EthereumGateway::Mock(eth_client);
+
+        assert_eq!(
+            fetch_token_metadata(&eth_client, string_token).await,
+            TokenMetadata {
+                symbol: Some("USDC".to_string()),
+                decimals: Some(6),
+            }
+        );
+        assert_eq!(
+            fetch_token_metadata(&eth_client, bytes32_token).await,
+            TokenMetadata {
+                symbol: Some("MKR".to_string()),
+                decimals: Some(18),
+            }
+        );
+        // Values the contract failed to return are unknown.
+        assert_eq!(
+            fetch_token_metadata(&eth_client, reverting_token).await,
+            TokenMetadata::default()
+        );
+        assert_eq!(
+            fetch_token_metadata(&eth_client, invalid_token).await,
+            TokenMetadata::default()
+        );
+    }
+
+    #[test]
+    fn bytes32_symbol() {
+        let parse = |value: &str| {
+            let mut bytes = [0u8; 32];
+            bytes[..value.len()].copy_from_slice(value.as_bytes());
+            parse_bytes32_symbol(H256::from(bytes))
+        };
+
+        assert_eq!(parse("MKR"), Some("MKR".to_string()));
+        assert_eq!(parse("SAI "), Some("SAI".to_string()));
+        assert_eq!(parse(""), None);
+        assert_eq!(parse_bytes32_symbol(H256::repeat_byte(0xff)), None);
+    }
+}
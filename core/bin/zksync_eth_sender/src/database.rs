@@ -75,12 +75,15 @@ pub(super) trait DatabaseInterface {
         new_gas_value: U256,
     ) -> anyhow::Result<()>;
 
-    /// Marks an operation as completed in the database.
+    /// Marks an operation as completed in the database, along with the Ethereum block
+    /// its transaction was included in.
     async fn confirm_operation(
         &self,
         connection: &mut StorageProcessor<'_>,
         hash: &H256,
         op: &ETHOperation,
+        gas_used: Option<U256>,
+        block_number: u64,
     ) -> anyhow::Result<()>;
 
     /// Loads the stored Ethereum operations stats.
@@ -105,6 +108,13 @@ pub(super) trait DatabaseInterface {
         connection: &mut StorageProcessor<'_>,
         op: &ETHOperation,
     ) -> anyhow::Result<bool>;
+
+    /// Removes the pending requests to resend the operations transactions and returns
+    /// the IDs of the requested operations.
+    async fn take_resend_requests(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<EthOpId>>;
 }
 
 /// The actual database wrapper.
@@ -251,11 +261,25 @@ impl DatabaseInterface for Database {
         Ok(confirmed)
     }
 
+    async fn take_resend_requests(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<EthOpId>> {
+        let eth_op_ids = connection
+            .ethereum_schema()
+            .take_eth_tx_resend_requests()
+            .await?;
+
+        Ok(eth_op_ids)
+    }
+
     async fn confirm_operation(
         &self,
         connection: &mut StorageProcessor<'_>,
         hash: &H256,
         op: &ETHOperation,
+        gas_used: Option<U256>,
+        block_number: u64,
     ) -> anyhow::Result<()> {
         let mut transaction = connection.start_transaction().await?;
 
@@ -313,6 +337,16 @@ impl DatabaseInterface for Database {
         }
 
         transaction.ethereum_schema().confirm_eth_tx(hash).await?;
+        if let Some(gas_used) = gas_used {
+            transaction
+                .ethereum_schema()
+                .save_gas_used(hash, gas_used)
+                .await?;
+        }
+        transaction
+            .ethereum_schema()
+            .save_l1_block(hash, block_number)
+            .await?;
         transaction.commit().await?;
 
         Ok(())
@@ -1,6 +1,11 @@
 // Built-in deps
-use std::{collections::VecDeque, marker::PhantomData, time::Instant};
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
 // External deps
+use zksync_api_types::GasAdjusterStatus;
 use zksync_basic_types::U256;
 use zksync_eth_client::EthereumGateway;
 // Local deps
@@ -28,6 +33,8 @@ pub(super) struct GasAdjuster<DB: DatabaseInterface> {
     last_price_renewal: Instant,
     /// Timestamp of the last sample added to the `statistics`.
     last_sample_added: Instant,
+    /// Gas price suggested for the last sent transaction.
+    last_gas_price: Option<U256>,
     _db: PhantomData<DB>,
 }
 
@@ -45,6 +52,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
             statistics: GasStatistics::new(gas_price_limit),
             last_price_renewal: Instant::now(),
             last_sample_added: Instant::now(),
+            last_gas_price: None,
 
             _db: PhantomData,
         }
@@ -89,6 +97,7 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
 
         // Report used price to be gathered by the statistics module.
         self.statistics.add_sample(price);
+        self.last_gas_price = Some(price);
 
         Ok(price)
     }
@@ -165,6 +174,21 @@ impl<DB: DatabaseInterface> GasAdjuster<DB> {
     pub fn get_current_max_price(&self) -> U256 {
         self.statistics.get_limit()
     }
+
+    /// Returns the snapshot of the adjuster to be published for the operators.
+    pub fn status(&self) -> GasAdjusterStatus {
+        let updated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Current time is before the unix epoch")
+            .as_secs();
+        GasAdjusterStatus {
+            last_gas_price: self.last_gas_price,
+            average_gas_price: self.statistics.get_average_price(),
+            gas_price_limit: self.get_current_max_price(),
+            samples_count: self.statistics.samples_count() as u64,
+            updated_at,
+        }
+    }
 }
 
 /// Helper structure responsible for collecting the data about recent transactions,
@@ -232,4 +256,8 @@ impl GasStatistics {
     pub fn get_limit(&self) -> U256 {
         self.current_max_price
     }
+
+    pub fn samples_count(&self) -> usize {
+        self.samples.len()
+    }
 }
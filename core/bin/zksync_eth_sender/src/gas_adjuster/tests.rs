@@ -378,3 +378,39 @@ async fn gas_price_limit_preservation() {
         assert_eq!(new_limit, price_limit.into());
     }
 }
+
+/// Checks that the published status reflects the gathered statistics and the last used price.
+#[tokio::test]
+async fn adjuster_status() {
+    const PRICE_LIMIT: i64 = 1000;
+
+    let (mut ethereum, db) = eth_and_db_clients().await;
+    db.update_gas_price_limit(PRICE_LIMIT).await.unwrap();
+    let mut gas_adjuster: GasAdjuster<MockDatabase> = GasAdjuster::new(&db).await;
+
+    let status = gas_adjuster.status();
+    assert_eq!(status.last_gas_price, None);
+    assert_eq!(status.average_gas_price, None);
+    assert_eq!(status.gas_price_limit, PRICE_LIMIT.into());
+    assert_eq!(status.samples_count, 0);
+
+    // The price above the limit is capped.
+    ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_gas_price((PRICE_LIMIT * 2).into())
+        .await
+        .unwrap();
+    for _ in 0..GasStatistics::GAS_PRICE_SAMPLES_AMOUNT {
+        gas_adjuster.get_gas_price(&ethereum, None).await.unwrap();
+    }
+
+    let status = gas_adjuster.status();
+    assert_eq!(status.last_gas_price, Some(PRICE_LIMIT.into()));
+    assert_eq!(status.average_gas_price, Some(PRICE_LIMIT.into()));
+    assert_eq!(
+        status.samples_count,
+        GasStatistics::GAS_PRICE_SAMPLES_AMOUNT as u64
+    );
+    assert!(status.updated_at > 0);
+}
@@ -6,6 +6,7 @@
 
 // Built-in deps
 use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 // External uses
 use anyhow::format_err;
@@ -15,10 +16,11 @@ use web3::{
     types::{TransactionReceipt, H256, U256},
 };
 // Workspace uses
+use zksync_api_types::GasAdjusterStatus;
 use zksync_config::ETHSenderConfig;
 use zksync_eth_client::{EthereumGateway, SignedCallResult};
 use zksync_storage::ConnectionPool;
-use zksync_types::ethereum::ETHOperation;
+use zksync_types::ethereum::{ETHOperation, EthOpId};
 // Local uses
 use self::{
     database::{Database, DatabaseInterface},
@@ -44,6 +46,9 @@ const RATE_LIMIT_BACKOFF_PERIOD: Duration = Duration::from_secs(30);
 /// Rate limit error will contain this response code
 const RATE_LIMIT_HTTP_CODE: &str = "429";
 
+/// Status of the gas adjuster shared with the core private API, `None` until the sender is started.
+pub type SharedGasAdjusterStatus = Arc<RwLock<Option<GasAdjusterStatus>>>;
+
 /// `TxCheckMode` enum determines the policy on the obtaining the tx status.
 /// The latest sent transaction can be pending (we're still waiting for it),
 /// but if there is more than one tx for some Ethereum operation, it means that we
@@ -120,6 +125,8 @@ struct ETHSender<DB: DatabaseInterface> {
     tx_queue: TxQueue,
     /// Utility for managing the gas price for transactions.
     gas_adjuster: GasAdjuster<DB>,
+    /// Status of the gas adjuster published after every iteration.
+    gas_adjuster_status: SharedGasAdjusterStatus,
     /// Settings for the `ETHSender`.
     options: ETHSenderConfig,
 }
@@ -179,15 +186,26 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             ethereum,
             tx_queue,
             gas_adjuster,
+            gas_adjuster_status: Default::default(),
             options,
         }
     }
 
+    /// Makes the sender publish the status of its gas adjuster into the shared status.
+    pub fn share_gas_adjuster_status(&mut self, gas_adjuster_status: SharedGasAdjusterStatus) {
+        self.gas_adjuster_status = gas_adjuster_status;
+    }
+
+    fn publish_gas_adjuster_status(&self) {
+        *self.gas_adjuster_status.write().unwrap() = Some(self.gas_adjuster.status());
+    }
+
     /// Main routine of `ETHSender`.
     pub async fn run(mut self) {
         // `eth_sender` must perform some of the activities only once per block change.
         // Having `0` as an initial value is to ensure that on the first iteration we will run all the activities.
         let mut last_used_block = 0;
+        self.publish_gas_adjuster_status();
         loop {
             // We perform a loading routine every X seconds.
             tokio::time::sleep(self.options.sender.tx_poll_period()).await;
@@ -204,6 +222,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                 self.gas_adjuster
                     .keep_updated(&self.ethereum, &self.db)
                     .await;
+                self.publish_gas_adjuster_status();
             }
         }
     }
@@ -230,6 +249,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .remove_unprocessed_operations(&mut transaction, operations_id)
             .await?;
 
+        let resend_requests = self.db.take_resend_requests(&mut transaction).await?;
+
         transaction.commit().await?;
         drop(connection);
 
@@ -237,10 +258,35 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             self.add_operation_to_queue(operation.clone())?;
         }
 
+        for eth_op_id in resend_requests {
+            self.mark_operation_for_resend(eth_op_id);
+        }
+
         metrics::histogram!("eth_sender.load_new_operations", start.elapsed());
         Ok(())
     }
 
+    /// Resets the deadline of the ongoing operation, so the operation is considered stuck
+    /// and a supplement transaction with the increased gas price is sent for it on the next block.
+    /// Requests for the operations that are not ongoing (e.g. already confirmed) are ignored.
+    fn mark_operation_for_resend(&mut self, eth_op_id: EthOpId) {
+        match self.ongoing_ops.iter_mut().find(|op| op.id == eth_op_id) {
+            Some(op) => {
+                vlog::info!(
+                    "Resend of the ETH operation <id {}> is requested",
+                    eth_op_id
+                );
+                op.last_deadline_block = 0;
+            }
+            None => {
+                vlog::warn!(
+                    "Resend of the ETH operation <id {}> is requested, but it's not ongoing",
+                    eth_op_id
+                );
+            }
+        }
+    }
+
     /// This method does two main things:
     ///
     /// 1. Pops all the available transactions from the `TxQueue` and sends them.
@@ -473,7 +519,10 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                     // Transaction is pending, nothing to do yet.
                     return Ok(OperationCommitment::Pending);
                 }
-                TxCheckOutcome::Committed => {
+                TxCheckOutcome::Committed {
+                    gas_used,
+                    block_number,
+                } => {
                     let mut connection = self.db.acquire_connection().await?;
                     let mut transaction = connection.start_transaction().await?;
 
@@ -507,7 +556,7 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
                         op.id, op.op_type, tx_hash, self.zksync_operation_description(op),
                     );
                     self.db
-                        .confirm_operation(&mut transaction, tx_hash, op)
+                        .confirm_operation(&mut transaction, tx_hash, op, gas_used, block_number)
                         .await?;
                     transaction.commit().await?;
                     return Ok(OperationCommitment::Committed);
@@ -603,7 +652,10 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             Some(status) if status.success => {
                 // Check if transaction has enough confirmations.
                 if status.confirmations >= self.options.sender.wait_confirmations {
-                    TxCheckOutcome::Committed
+                    TxCheckOutcome::Committed {
+                        gas_used: status.gas_used,
+                        block_number: current_block.saturating_sub(status.confirmations),
+                    }
                 } else {
                     TxCheckOutcome::Pending
                 }
@@ -680,20 +732,8 @@ impl<DB: DatabaseInterface> ETHSender<DB> {
             .op
             .as_ref()
             .expect("Operation not found - can't compute gas limit");
-        match op {
-            AggregatedOperation::CommitBlocks(commit) => {
-                GasCounter::commit_gas_limit_aggregated(&commit.blocks)
-            }
-            AggregatedOperation::ExecuteBlocks(execute) => {
-                GasCounter::execute_gas_limit_aggregated(&execute.blocks)
-            }
-            AggregatedOperation::PublishProofBlocksOnchain(_) => {
-                U256::from(GasCounter::BASE_PROOF_BLOCKS_TX_COST)
-            }
-            AggregatedOperation::CreateProofBlocks(_) => {
-                panic!("Can't compute gas limit for CreateProofBlocks")
-            }
-        }
+        GasCounter::aggregated_op_gas_limit(op)
+            .expect("Can't compute gas limit for CreateProofBlocks")
     }
 
     /// Creates a new transaction for the existing Ethereum operation.
@@ -802,11 +842,13 @@ pub fn run_eth_sender(
     pool: ConnectionPool,
     eth_gateway: EthereumGateway,
     options: ETHSenderConfig,
+    gas_adjuster_status: SharedGasAdjusterStatus,
 ) -> JoinHandle<()> {
     let db = Database::new(pool);
 
     tokio::spawn(async move {
-        let eth_sender = ETHSender::new(options, db, eth_gateway).await;
+        let mut eth_sender = ETHSender::new(options, db, eth_gateway).await;
+        eth_sender.share_gas_adjuster_status(gas_adjuster_status);
 
         eth_sender.run().await
     })
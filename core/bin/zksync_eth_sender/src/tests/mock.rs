@@ -26,6 +26,7 @@ pub(crate) struct MockDatabase {
     aggregated_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     unprocessed_operations: RwLock<Vec<(i64, AggregatedOperation)>>,
     eth_parameters: RwLock<ETHParams>,
+    resend_requests: RwLock<Vec<EthOpId>>,
 }
 
 impl MockDatabase {
@@ -41,6 +42,7 @@ impl MockDatabase {
             aggregated_operations: RwLock::new(aggregated_operations),
             unprocessed_operations: RwLock::new(unprocessed_operations),
             eth_parameters: RwLock::new(eth_parameters),
+            resend_requests: Default::default(),
         }
     }
 
@@ -68,6 +70,19 @@ impl MockDatabase {
         Ok(())
     }
 
+    /// Simulates the resend request submitted via the core private API.
+    pub async fn request_resend(&self, eth_op_id: EthOpId) {
+        let mut resend_requests = self.resend_requests.write().await;
+        if !resend_requests.contains(&eth_op_id) {
+            resend_requests.push(eth_op_id);
+        }
+    }
+
+    /// Returns the resend requests not taken by the `ETHSender` yet.
+    pub async fn pending_resend_requests(&self) -> Vec<EthOpId> {
+        self.resend_requests.read().await.clone()
+    }
+
     /// Ensures that the provided transaction is stored in the database and not confirmed yet.
     pub async fn assert_stored(&self, tx: &ETHOperation) {
         let eth_operations = self.eth_operations.read().await;
@@ -282,6 +297,8 @@ impl DatabaseInterface for MockDatabase {
         _connection: &mut StorageProcessor<'_>,
         hash: &H256,
         _op: &ETHOperation,
+        _gas_used: Option<U256>,
+        _block_number: u64,
     ) -> anyhow::Result<()> {
         let mut eth_operations = self.eth_operations.write().await;
         let mut op_idx: Option<i64> = None;
@@ -355,6 +372,15 @@ impl DatabaseInterface for MockDatabase {
 
         Ok(confirmed)
     }
+
+    async fn take_resend_requests(
+        &self,
+        _connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<EthOpId>> {
+        let resend_requests = std::mem::take(&mut *self.resend_requests.write().await);
+
+        Ok(resend_requests)
+    }
 }
 
 /// Creates a default `ETHParams` for use by mock `ETHSender` .
@@ -440,6 +466,7 @@ async fn build_eth_sender(
             wait_confirmations: super::WAIT_CONFIRMATIONS,
             tx_poll_period: 0,
             is_enabled: true,
+            daily_fee_budget_gwei: 0,
             operator_commit_eth_addr: Default::default(),
             operator_private_key: Default::default(),
         },
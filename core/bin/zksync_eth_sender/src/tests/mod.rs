@@ -77,6 +77,7 @@ async fn transaction_state() {
     let committed_response = ExecutedTxStatus {
         confirmations: WAIT_CONFIRMATIONS,
         success: true,
+        gas_used: Some(100_000u64.into()),
         receipt: None,
     };
     eth_sender
@@ -90,6 +91,7 @@ async fn transaction_state() {
     let pending_response = ExecutedTxStatus {
         confirmations: WAIT_CONFIRMATIONS - 1,
         success: true,
+        gas_used: Some(100_000u64.into()),
         receipt: None,
     };
     eth_sender
@@ -103,6 +105,7 @@ async fn transaction_state() {
     let failed_response = ExecutedTxStatus {
         confirmations: WAIT_CONFIRMATIONS,
         success: false,
+        gas_used: Some(100_000u64.into()),
         receipt: Some(Default::default()),
     };
     eth_sender
@@ -116,6 +119,7 @@ async fn transaction_state() {
     let pending_failed_response = ExecutedTxStatus {
         confirmations: WAIT_CONFIRMATIONS - 1,
         success: false,
+        gas_used: Some(100_000u64.into()),
         receipt: Some(Default::default()),
     };
     eth_sender
@@ -139,7 +143,10 @@ async fn transaction_state() {
             )
             .await
             .unwrap(),
-        TxCheckOutcome::Committed
+        TxCheckOutcome::Committed {
+            gas_used: Some(100_000u64.into()),
+            block_number: current_block,
+        }
     );
 
     // Pending operation (no enough confirmations).
@@ -410,6 +417,90 @@ async fn stuck_transaction() {
     eth_sender.db.assert_confirmed(&stuck_tx).await;
 }
 
+/// Check that the resend request makes `ETHSender` send a supplement transaction for the operation
+/// before its deadline, and that the requests for the confirmed operations are ignored.
+#[tokio::test]
+async fn resend_request() {
+    let mut eth_sender = default_eth_sender().await;
+
+    // Workflow for the test is similar to `stuck_transaction`.
+    let aggregated_operation = test_data::commit_blocks_operation(0);
+    eth_sender
+        .db
+        .send_aggregated_operation(aggregated_operation.clone())
+        .await
+        .unwrap();
+
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    let eth_op_id = 0;
+    let nonce = 0;
+    let current_block = eth_sender
+        .ethereum
+        .get_mock()
+        .unwrap()
+        .block_number()
+        .await
+        .unwrap()
+        .as_u64();
+    let deadline_block = eth_sender.get_deadline_block(current_block);
+    let mut op = create_signed_tx(
+        eth_op_id,
+        &eth_sender,
+        aggregated_operation.clone(),
+        deadline_block,
+        nonce,
+    )
+    .await;
+
+    // Request the resend and skip a single block, which is far from the deadline.
+    eth_sender.db.request_resend(eth_op_id).await;
+    eth_sender.load_new_operations().await.unwrap();
+    assert!(eth_sender.db.pending_resend_requests().await.is_empty());
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .set_block_number(U64::from(current_block + 1))
+        .await
+        .unwrap();
+    eth_sender.proceed_next_operations(0).await;
+
+    // Check that the supplement transaction is sent.
+    let expected_sent_tx = eth_sender
+        .create_supplement_tx(eth_sender.get_deadline_block(current_block + 1), &mut op)
+        .await
+        .unwrap();
+    eth_sender.db.assert_stored(&op).await;
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .assert_sent(expected_sent_tx.hash.as_bytes())
+        .await;
+
+    // Confirm the operation.
+    eth_sender
+        .ethereum
+        .get_mut_mock()
+        .unwrap()
+        .add_successfull_execution(op.used_tx_hashes[1], WAIT_CONFIRMATIONS)
+        .await;
+    eth_sender.proceed_next_operations(0).await;
+    op.confirmed = true;
+    op.final_hash = Some(op.used_tx_hashes[1]);
+    eth_sender.db.assert_confirmed(&op).await;
+
+    // The request for the confirmed operation is taken, but nothing is sent.
+    eth_sender.db.request_resend(eth_op_id).await;
+    eth_sender.load_new_operations().await.unwrap();
+    eth_sender.proceed_next_operations(0).await;
+    assert!(eth_sender.db.pending_resend_requests().await.is_empty());
+    assert!(eth_sender.ongoing_ops.is_empty());
+    eth_sender.db.assert_confirmed(&op).await;
+}
+
 /// This test verifies that with multiple operations received all-together,
 /// their order is respected and no processing of the next operation is started until
 /// the previous one is committed.
@@ -5,7 +5,7 @@
 
 // Built-in deps
 // External uses
-use zksync_basic_types::TransactionReceipt;
+use zksync_basic_types::{TransactionReceipt, U256};
 // Workspace uses
 use zksync_storage::ethereum::records::ETHStats as StorageETHStats;
 
@@ -36,8 +36,11 @@ impl From<StorageETHStats> for ETHStats {
 /// The result of the check for the Ethereum transaction commitment.
 #[derive(Debug, PartialEq)]
 pub enum TxCheckOutcome {
-    /// Transaction was committed and confirmed.
-    Committed,
+    /// Transaction was committed and confirmed in the Ethereum block with the given number.
+    Committed {
+        gas_used: Option<U256>,
+        block_number: u64,
+    },
     /// Transaction is pending yet.
     Pending,
     /// Transaction is considered stuck, a replacement should be made.
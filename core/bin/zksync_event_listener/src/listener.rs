@@ -1,7 +1,8 @@
 // Built-in uses
-use std::{convert::TryFrom, fmt::Display, sync::Arc};
+use std::{convert::TryFrom, fmt::Display, future::Future, sync::Arc, time::Duration};
 // External uses
 use actix::prelude::*;
+use chrono::Utc;
 use futures_util::{future::Either, stream::StreamExt};
 // Workspace uses
 use zksync_config::ZkSyncConfig;
@@ -23,6 +24,10 @@ pub struct EventListener {
     listener: Option<StorageListener>,
     /// The id of the last processed event.
     last_processed_event_id: EventId,
+    /// Events older than this period are removed from the database.
+    events_retention_period: Duration,
+    /// How often outdated events are removed.
+    events_pruning_interval: Duration,
 }
 
 type NotifyResult = anyhow::Result<NewStorageEvent>;
@@ -118,6 +123,10 @@ impl Actor for EventListener {
             .into_stream()
             .map(|item| item.and_then(NewStorageEvent::try_from));
         Self::add_stream(stream, ctx);
+        // Periodically remove outdated events.
+        ctx.run_interval(self.events_pruning_interval, |act, ctx| {
+            act.prune_events().into_actor(act).spawn(ctx);
+        });
     }
 
     fn stopped(&mut self, _ctx: &mut Self::Context) {
@@ -153,9 +162,34 @@ impl EventListener {
             server_monitor,
             listener: Some(listener),
             last_processed_event_id,
+            events_retention_period: config.event_listener.events_retention_period(),
+            events_pruning_interval: config.event_listener.events_pruning_interval(),
         })
     }
 
+    /// Returns the future removing the events which are older than the retention period.
+    /// Errors are only logged, since pruning doesn't affect streaming of the new events.
+    fn prune_events(&self) -> impl Future<Output = ()> {
+        let pool = self.db_pool.clone();
+        let retention_period = self.events_retention_period;
+        async move {
+            let result: anyhow::Result<u64> = async {
+                let timestamp = Utc::now() - chrono::Duration::from_std(retention_period)?;
+                Ok(pool
+                    .access_storage()
+                    .await?
+                    .event_schema()
+                    .remove_events_older_than(timestamp)
+                    .await?)
+            }
+            .await;
+            match result {
+                Ok(removed) => vlog::info!("Removed {} outdated events", removed),
+                Err(err) => vlog::warn!("Failed to remove outdated events: {}", err),
+            }
+        }
+    }
+
     /// Returns the future that can be spawned on the actor's context
     /// in order to initiate the shutdown of the event server.
     ///
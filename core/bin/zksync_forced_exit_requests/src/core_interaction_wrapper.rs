@@ -43,6 +43,7 @@ pub trait CoreInteractionWrapper {
         deleting_threshold: chrono::Duration,
     ) -> anyhow::Result<()>;
     async fn check_forced_exit_request(&self, request: &ForcedExitRequest) -> anyhow::Result<bool>;
+    async fn is_processing_paused(&self) -> anyhow::Result<bool>;
 }
 
 #[derive(Clone)]
@@ -203,4 +204,14 @@ impl CoreInteractionWrapper for MempoolCoreInteractionWrapper {
             Ok(false)
         }
     }
+
+    async fn is_processing_paused(&self) -> anyhow::Result<bool> {
+        let mut storage = self.connection_pool.access_storage().await?;
+        let paused = storage
+            .forced_exit_requests_schema()
+            .is_processing_paused()
+            .await?;
+
+        Ok(paused)
+    }
 }
@@ -136,6 +136,8 @@ where
     forced_exit_sender: Sender,
 
     mode: WatcherMode,
+    /// Whether the processing was paused by the operator as of the last poll.
+    paused: bool,
     db_cleanup_interval: chrono::Duration,
     last_db_cleanup_time: DateTime<Utc>,
 }
@@ -197,6 +199,7 @@ where
 
             last_viewed_block: 0,
             mode: WatcherMode::Working,
+            paused: false,
             db_cleanup_interval,
             // Zero timestamp, has never deleted anything
             last_db_cleanup_time: Utc.timestamp(0, 0),
@@ -274,6 +277,32 @@ where
         }
     }
 
+    /// Checks whether the processing was paused by the operator. The new events are not
+    /// processed while paused, so they are picked up once the processing is resumed.
+    async fn processing_allowed(&mut self) -> bool {
+        let paused = match self.core_interaction_wrapper.is_processing_paused().await {
+            Ok(paused) => paused,
+            Err(err) => {
+                vlog::error!("Failed to check whether the processing is paused: {}", err);
+                return false;
+            }
+        };
+        if paused != self.paused {
+            if paused {
+                vlog::info!("Processing of the forced exit requests is paused");
+            } else {
+                vlog::info!("Processing of the forced exit requests is resumed");
+            }
+            self.paused = paused;
+        }
+        metrics::gauge!(
+            "forced_exit_requests.eth_watcher.paused",
+            if paused { 1.0 } else { 0.0 }
+        );
+
+        !paused
+    }
+
     pub async fn delete_expired(&mut self) -> anyhow::Result<()> {
         let expiration_time = chrono::Duration::milliseconds(
             self.config
@@ -292,6 +321,9 @@ where
             // Polling is currently disabled, skip it.
             return;
         }
+        if !self.processing_allowed().await {
+            return;
+        }
 
         let last_block = match self.eth_client.block_number().await {
             Ok(block) => block,
@@ -730,4 +762,51 @@ mod test {
             BigUint::from_str("1000000002").unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn test_watcher_paused() {
+        // Checks that no requests are processed while the processing is paused
+        // and the events received during the pause are processed after resuming
+
+        let mut watcher = get_test_forced_exit_contract_watcher();
+
+        let wait_confirmations = 5;
+        watcher.config.wait_confirmations = wait_confirmations;
+        watcher.eth_client.events = vec![FundsReceivedEvent {
+            amount: BigUint::from_str("1000000001").unwrap(),
+            block_number: TEST_FIRST_CURRENT_BLOCK - 2 * wait_confirmations,
+        }];
+
+        watcher
+            .restore_state_from_eth(100)
+            .await
+            .expect("Failed to restore state from eth");
+        watcher.eth_client.current_block_number = TEST_FIRST_CURRENT_BLOCK;
+
+        watcher.core_interaction_wrapper.processing_paused = true;
+        watcher.poll().await;
+        watcher.poll().await;
+
+        assert!(watcher
+            .forced_exit_sender
+            .processed_requests
+            .lock()
+            .unwrap()
+            .is_empty());
+        assert_eq!(watcher.last_viewed_block, 100 - wait_confirmations);
+
+        watcher.core_interaction_wrapper.processing_paused = false;
+        watcher.poll().await;
+
+        let processed_requests = watcher
+            .forced_exit_sender
+            .processed_requests
+            .lock()
+            .unwrap();
+        assert_eq!(processed_requests.len(), 1);
+        assert_eq!(
+            processed_requests[0].0,
+            BigUint::from_str("1000000001").unwrap()
+        );
+    }
 }
@@ -18,6 +18,7 @@ pub struct MockCoreInteractionWrapper {
     pub sent_txs: Mutex<Vec<SignedZkSyncTx>>,
     // It is easier when keeping track of the deleted txs
     pub deleted_requests: Mutex<Vec<ForcedExitRequest>>,
+    pub processing_paused: bool,
 }
 
 impl Default for MockCoreInteractionWrapper {
@@ -36,6 +37,7 @@ impl Default for MockCoreInteractionWrapper {
             }),
             sent_txs: Mutex::new(vec![]),
             deleted_requests: Mutex::new(vec![]),
+            processing_paused: false,
         }
     }
 }
@@ -168,6 +170,10 @@ impl CoreInteractionWrapper for MockCoreInteractionWrapper {
         // For tests it is better to just return true all the time
         Ok(true)
     }
+
+    async fn is_processing_paused(&self) -> anyhow::Result<bool> {
+        Ok(self.processing_paused)
+    }
 }
 
 pub fn add_request(requests: &Mutex<Vec<ForcedExitRequest>>, new_request: ForcedExitRequest) {
@@ -119,8 +119,13 @@ impl ClientRequestBuilder {
         if status.is_success() {
             Ok(response.json().await.map_err(ClientError::Parse)?)
         } else {
+            // REST API v0.2 may report the missing resources with `404 Not Found`,
+            // the `NotFound` error is returned in the response body in this case.
             if status == StatusCode::NOT_FOUND {
-                return Err(ClientError::NotFound(self.url));
+                return response
+                    .json()
+                    .await
+                    .map_err(|_| ClientError::NotFound(self.url));
             }
 
             Err(ClientError::BadRequest {
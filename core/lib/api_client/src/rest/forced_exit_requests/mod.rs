@@ -6,7 +6,10 @@
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
-use zksync_types::{forced_exit_requests::ForcedExitRequest, Address, TokenId};
+use zksync_types::{
+    forced_exit_requests::{ForcedExitEligibilityResponse, ForcedExitRequest},
+    AccountId, Address, TokenId,
+};
 use zksync_utils::BigUintSerdeAsRadix10Str;
 
 use num::BigUint;
@@ -33,9 +36,15 @@ pub enum ForcedExitRequestStatus {
     Disabled,
 }
 
+/// Request to register the forced exit. The target can be specified either by the address
+/// or by the account id, if both are specified they must refer to the same account.
+/// The resolved address is returned in the `target` field of the stored request.
 #[derive(Deserialize, Serialize)]
 pub struct ForcedExitRegisterRequest {
-    pub target: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_account_id: Option<AccountId>,
     pub tokens: Vec<TokenId>,
     // Even though the price is constant, we still need to specify it,
     // since the price might change (with config)
@@ -61,4 +70,17 @@ impl Client {
             .send()
             .await
     }
+
+    /// Checks whether the account given by its address or id is eligible for the forced exit.
+    pub async fn check_forced_exit_eligibility(
+        &self,
+        account_id_or_address: &str,
+    ) -> ClientResult<ForcedExitEligibilityResponse> {
+        self.get_with_scope(
+            FORCED_EXIT_REQUESTS_SCOPE,
+            &format!("checks/eligibility/{}", account_id_or_address),
+        )
+        .send()
+        .await
+    }
 }
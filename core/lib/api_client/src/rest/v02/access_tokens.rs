@@ -0,0 +1,27 @@
+use crate::rest::client::{Client, Result};
+use zksync_api_types::v02::{
+    access_token::{SignedAccessDelegation, SignedAccessRevocation},
+    Response,
+};
+
+impl Client {
+    pub async fn issue_access_token(
+        &self,
+        delegation: &SignedAccessDelegation,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "accessTokens")
+            .body(delegation)
+            .send()
+            .await
+    }
+
+    pub async fn revoke_access_tokens(
+        &self,
+        revocation: &SignedAccessRevocation,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "accessTokens/revoke")
+            .body(revocation)
+            .send()
+            .await
+    }
+}
@@ -1,6 +1,7 @@
 use crate::rest::client::{Client, Result};
 
 use zksync_api_types::v02::{
+    account::{AccountActivityQuery, AccountChecksumQuery, AccountTxsDeltaQuery, RejectedTxsQuery},
     pagination::{ApiEither, PaginationQuery},
     Response,
 };
@@ -43,6 +44,22 @@ impl Client {
         .await
     }
 
+    pub async fn account_txs_with_counterparty(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<TxHash>>,
+        account_id_or_address: &str,
+        counterparty: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/transactions", account_id_or_address),
+        )
+        .query(&pagination_query)
+        .query(&[("counterparty", counterparty)])
+        .send()
+        .await
+    }
+
     pub async fn account_pending_txs(
         &self,
         pagination_query: &PaginationQuery<ApiEither<SerialId>>,
@@ -56,4 +73,96 @@ impl Client {
         .send()
         .await
     }
+
+    pub async fn account_txs_delta(
+        &self,
+        query: &AccountTxsDeltaQuery,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/transactions/delta", account_id_or_address),
+        )
+        .query(query)
+        .send()
+        .await
+    }
+
+    pub async fn account_pending_balances(&self, account_id_or_address: &str) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/pendingBalances", account_id_or_address),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn account_nonce(&self, account_id_or_address: &str) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/nonce", account_id_or_address),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn account_rejected_txs(
+        &self,
+        query: &RejectedTxsQuery,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/rejectedTransactions", account_id_or_address),
+        )
+        .query(query)
+        .send()
+        .await
+    }
+
+    pub async fn account_activity(
+        &self,
+        query: &AccountActivityQuery,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/activity", account_id_or_address),
+        )
+        .query(query)
+        .send()
+        .await
+    }
+
+    pub async fn accounts_by_pubkey_hash(&self, pubkey_hash: &str) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/byPubkeyHash/{}", pubkey_hash),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn account_checksum(
+        &self,
+        query: &AccountChecksumQuery,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/checksum", account_id_or_address),
+        )
+        .query(query)
+        .send()
+        .await
+    }
+
+    pub async fn account_exit_plan(&self, account_id_or_address: &str) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/exitPlan", account_id_or_address),
+        )
+        .send()
+        .await
+    }
 }
@@ -1,6 +1,7 @@
 use crate::rest::client::{Client, Result};
 
 use zksync_api_types::v02::{
+    block::BlocksByNumbersRequest,
     pagination::{ApiEither, PaginationQuery},
     Response,
 };
@@ -45,4 +46,40 @@ impl Client {
             .send()
             .await
     }
+
+    pub async fn blocks_by_numbers(&self, block_numbers: &[BlockNumber]) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "blocks/byNumbers")
+            .body(&BlocksByNumbersRequest {
+                block_numbers: block_numbers.to_vec(),
+            })
+            .send()
+            .await
+    }
+
+    pub async fn block_cost_breakdown(&self, block_number: BlockNumber) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/costBreakdown", block_number),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn block_checksum(&self, block_number: BlockNumber) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/checksum", block_number),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn block_finality(&self, block_number: BlockNumber) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/finality", block_number),
+        )
+        .send()
+        .await
+    }
 }
@@ -7,4 +7,10 @@ impl Client {
             .send()
             .await
     }
+
+    pub async fn config_limits(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "config/limits")
+            .send()
+            .await
+    }
 }
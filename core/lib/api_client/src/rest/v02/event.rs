@@ -0,0 +1,11 @@
+use crate::rest::client::{Client, Result};
+use zksync_api_types::v02::{event::EventsQuery, Response};
+
+impl Client {
+    pub async fn events(&self, query: &EventsQuery) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "events")
+            .query(query)
+            .send()
+            .await
+    }
+}
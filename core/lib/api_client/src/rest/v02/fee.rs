@@ -1,7 +1,7 @@
 // Local uses
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::{
-    fee::{ApiTxFeeTypes, BatchFeeRequest, TxFeeRequest, TxInBatchFeeRequest},
+    fee::{ApiTxFeeTypes, BatchFeeRequest, FeeStatisticsQuery, TxFeeRequest, TxInBatchFeeRequest},
     Response,
 };
 use zksync_types::{Address, TokenLike};
@@ -36,4 +36,17 @@ impl Client {
             .send()
             .await
     }
+
+    pub async fn get_fee_statistics(&self, query: &FeeStatisticsQuery) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "fee/statistics")
+            .query(query)
+            .send()
+            .await
+    }
+
+    pub async fn get_allowed_fee_tokens(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "fee/allowedTokens")
+            .send()
+            .await
+    }
 }
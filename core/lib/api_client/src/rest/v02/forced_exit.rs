@@ -0,0 +1,11 @@
+// Local uses
+use crate::rest::client::{Client, Result};
+use zksync_api_types::v02::Response;
+
+impl Client {
+    pub async fn forced_exit_params(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "forcedExit/params")
+            .send()
+            .await
+    }
+}
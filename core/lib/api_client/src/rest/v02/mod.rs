@@ -1,9 +1,16 @@
+pub mod access_tokens;
 pub mod account;
 pub mod block;
 pub mod config;
+pub mod error_codes;
+pub mod event;
 pub mod fee;
+pub mod forced_exit;
+pub mod l1_operation;
+pub mod search;
 pub mod status;
 pub mod token;
 pub mod transaction;
+pub mod webhooks;
 
 const API_V02_SCOPE: &str = "/api/v0.2/";
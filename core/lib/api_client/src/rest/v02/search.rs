@@ -0,0 +1,13 @@
+use crate::rest::client::{Client, Result};
+use zksync_api_types::v02::{search::SearchQuery, Response};
+
+impl Client {
+    pub async fn search(&self, query: &str) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "search")
+            .query(&SearchQuery {
+                query: query.to_owned(),
+            })
+            .send()
+            .await
+    }
+}
@@ -1,5 +1,5 @@
 use crate::rest::client::{Client, Result};
-use zksync_api_types::v02::Response;
+use zksync_api_types::v02::{status::ApiHealth, Response};
 
 impl Client {
     pub async fn status(&self) -> Result<Response> {
@@ -7,4 +7,24 @@ impl Client {
             .send()
             .await
     }
+
+    /// Returns the stats of the block currently filled by the server, the stats may be a few seconds stale.
+    pub async fn pending_block_status(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "networkStatus/pendingBlock")
+            .send()
+            .await
+    }
+
+    pub async fn liveness(&self) -> Result<ApiHealth> {
+        self.get_with_scope(super::API_V02_SCOPE, "networkStatus/liveness")
+            .send()
+            .await
+    }
+
+    /// Returns an error with `503 Service Unavailable` code until the server is ready.
+    pub async fn readiness(&self) -> Result<ApiHealth> {
+        self.get_with_scope(super::API_V02_SCOPE, "networkStatus/readiness")
+            .send()
+            .await
+    }
 }
@@ -1,9 +1,12 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::{
     pagination::{ApiEither, PaginationQuery},
+    token::{
+        TokenActivityQuery, TokenChangesQuery, TokenHoldersQuery, TokenPricesQuery, TokensQuery,
+    },
     Response,
 };
-use zksync_types::{tx::TxHash, TokenId, TokenLike};
+use zksync_types::{tx::TxHash, Address, TokenId, TokenLike};
 
 impl Client {
     pub async fn token_pagination(
@@ -16,6 +19,13 @@ impl Client {
             .await
     }
 
+    pub async fn tokens(&self, query: &TokensQuery) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "tokens")
+            .query(query)
+            .send()
+            .await
+    }
+
     pub async fn token_by_id(&self, token: &TokenLike) -> Result<Response> {
         self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/{}", token))
             .send()
@@ -31,6 +41,52 @@ impl Client {
         .await
     }
 
+    pub async fn token_prices(&self, tokens: &[TokenLike], currency: &str) -> Result<Response> {
+        let tokens: Vec<String> = tokens.iter().map(ToString::to_string).collect();
+        let query = TokenPricesQuery {
+            tokens: tokens.join(","),
+            currency: currency.to_string(),
+        };
+        self.get_with_scope(super::API_V02_SCOPE, "tokens/prices")
+            .query(&query)
+            .send()
+            .await
+    }
+
+    pub async fn token_listing_status(&self, address: Address) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("tokens/listingStatus/{:?}", address),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn token_holders(&self, token: &TokenLike, limit: Option<u32>) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/{}/holders", token))
+            .query(&TokenHoldersQuery { limit })
+            .send()
+            .await
+    }
+
+    pub async fn token_activity(
+        &self,
+        token: &TokenLike,
+        query: &TokenActivityQuery,
+    ) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/{}/activity", token))
+            .query(query)
+            .send()
+            .await
+    }
+
+    pub async fn token_changes(&self, since_id: u64, limit: Option<u32>) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "tokens/changes")
+            .query(&TokenChangesQuery { since_id, limit })
+            .send()
+            .await
+    }
+
     pub async fn nft_by_id(&self, id: TokenId) -> Result<Response> {
         self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/nft/{}", id))
             .send()
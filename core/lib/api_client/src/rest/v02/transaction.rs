@@ -1,6 +1,13 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::{
-    v02::{transaction::IncomingTxBatch, Response},
+    v02::{
+        fee::SignedFeeQuote,
+        transaction::{
+            BatchHashRequest, CancelTxRequest, ConsistencyQuery, IncomingTxBatch, ReadConsistency,
+            SignMessageRequest, SubmitQuery, TxDataQuery,
+        },
+        Response,
+    },
     TxWithSignature,
 };
 use zksync_types::tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash, ZkSyncTx};
@@ -12,7 +19,45 @@ impl Client {
         signature: TxEthSignatureVariant,
     ) -> Result<Response> {
         self.post_with_scope(super::API_V02_SCOPE, "transactions")
-            .body(&TxWithSignature { tx, signature })
+            .body(&TxWithSignature {
+                tx,
+                signature,
+                fee_quote: None,
+            })
+            .send()
+            .await
+    }
+
+    pub async fn submit_tx_with_query(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        query: &SubmitQuery,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions")
+            .query(query)
+            .body(&TxWithSignature {
+                tx,
+                signature,
+                fee_quote: None,
+            })
+            .send()
+            .await
+    }
+
+    /// Submits the transaction along with the signed fee quote obtained from the fee endpoints.
+    pub async fn submit_tx_with_fee_quote(
+        &self,
+        tx: ZkSyncTx,
+        signature: TxEthSignatureVariant,
+        fee_quote: SignedFeeQuote,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions")
+            .body(&TxWithSignature {
+                tx,
+                signature,
+                fee_quote: Some(fee_quote),
+            })
             .send()
             .await
     }
@@ -28,6 +73,43 @@ impl Client {
             .await
     }
 
+    pub async fn submit_batch_with_query(
+        &self,
+        txs: Vec<TxWithSignature>,
+        signature: Option<EthBatchSignatures>,
+        query: &SubmitQuery,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/batches")
+            .query(query)
+            .body(&IncomingTxBatch { txs, signature })
+            .send()
+            .await
+    }
+
+    pub async fn batch_hash(&self, request: &BatchHashRequest) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/batches/hash")
+            .body(request)
+            .send()
+            .await
+    }
+
+    pub async fn sign_message(&self, request: &SignMessageRequest) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/signMessage")
+            .body(request)
+            .send()
+            .await
+    }
+
+    pub async fn cancel_tx(&self, tx_hash: TxHash, request: &CancelTxRequest) -> Result<Response> {
+        self.post_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/cancel", tx_hash.to_string()),
+        )
+        .body(request)
+        .send()
+        .await
+    }
+
     pub async fn tx_status(&self, tx_hash: TxHash) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -37,6 +119,20 @@ impl Client {
         .await
     }
 
+    pub async fn tx_status_with_consistency(
+        &self,
+        tx_hash: TxHash,
+        consistency: ReadConsistency,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}", tx_hash.to_string()),
+        )
+        .query(&ConsistencyQuery { consistency })
+        .send()
+        .await
+    }
+
     pub async fn tx_data(&self, tx_hash: TxHash) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -46,6 +142,61 @@ impl Client {
         .await
     }
 
+    pub async fn tx_data_with_query(
+        &self,
+        tx_hash: TxHash,
+        query: &TxDataQuery,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/data", tx_hash.to_string()),
+        )
+        .query(query)
+        .send()
+        .await
+    }
+
+    pub async fn tx_data_with_consistency(
+        &self,
+        tx_hash: TxHash,
+        consistency: ReadConsistency,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/data", tx_hash.to_string()),
+        )
+        .query(&ConsistencyQuery { consistency })
+        .send()
+        .await
+    }
+
+    pub async fn tx_diagnosis(&self, tx_hash: TxHash) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/diagnosis", tx_hash.to_string()),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn withdrawal_execute_eta(&self, tx_hash: TxHash) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/executeEta", tx_hash.to_string()),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn tx_public_data(&self, tx_hash: TxHash) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/publicData", tx_hash.to_string()),
+        )
+        .send()
+        .await
+    }
+
     pub async fn get_batch(&self, batch_hash: TxHash) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -0,0 +1,14 @@
+use crate::rest::client::{Client, Result};
+use zksync_api_types::v02::{webhook::SignedWebhookSubscription, Response};
+
+impl Client {
+    pub async fn subscribe_webhook(
+        &self,
+        subscription: &SignedWebhookSubscription,
+    ) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "webhooks")
+            .body(subscription)
+            .send()
+            .await
+    }
+}
@@ -0,0 +1,406 @@
+//! Authentication of the requests to the core private API.
+//!
+//! Requests are signed with a secret shared by the API servers and the core: the signature is
+//! HMAC-SHA256 of the method, the path with the query, the timestamp, the nonce and the SHA256
+//! of the body. The core rejects the requests signed too far from its own clock, and the nonces
+//! already seen within that window, so the intercepted requests can't be replayed.
+//!
+//! The headers and the timestamp are checked before the body is read, and the bodies larger than
+//! the configured limit are rejected without being read to the end, so the unsigned requests can't
+//! make the core buffer arbitrary amounts of data.
+//!
+//! Two secrets are accepted at once to rotate the secret without downtime: the core accepts both
+//! the new and the previous secret until all the API servers sign with the new one.
+
+// Built-in uses
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+// External uses
+use chrono::Utc;
+use hmac::{Hmac, Mac, NewMac};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+// Workspace uses
+use zksync_crypto::rand::{OsRng, Rng};
+
+/// Header with the hex-encoded HMAC of the request.
+pub const SIGNATURE_HEADER: &str = "X-Core-Signature";
+/// Header with the unix timestamp (in seconds) the request was signed at.
+pub const TIMESTAMP_HEADER: &str = "X-Core-Timestamp";
+/// Header with the random hex-encoded value unique for every request.
+pub const NONCE_HEADER: &str = "X-Core-Nonce";
+
+type HmacSha256 = Hmac<Sha256>;
+/// Size of the HMAC-SHA256 signature in bytes.
+const SIGNATURE_SIZE: usize = 32;
+
+/// Authentication headers of the signed request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreAuthHeaders {
+    pub signature: String,
+    pub timestamp: i64,
+    pub nonce: String,
+}
+
+impl CoreAuthHeaders {
+    /// Returns the headers as name-value pairs to be added to the request.
+    pub fn pairs(&self) -> [(&'static str, String); 3] {
+        [
+            (SIGNATURE_HEADER, self.signature.clone()),
+            (TIMESTAMP_HEADER, self.timestamp.to_string()),
+            (NONCE_HEADER, self.nonce.clone()),
+        ]
+    }
+
+    /// Parses the headers of the request, `header` returns the value of the header by its name.
+    pub fn parse<'a>(header: impl Fn(&str) -> Option<&'a str>) -> Result<Self, CoreAuthError> {
+        let signature = header(SIGNATURE_HEADER).ok_or(CoreAuthError::MissingHeaders)?;
+        let timestamp = header(TIMESTAMP_HEADER).ok_or(CoreAuthError::MissingHeaders)?;
+        let nonce = header(NONCE_HEADER).ok_or(CoreAuthError::MissingHeaders)?;
+        Ok(Self {
+            signature: signature.to_owned(),
+            timestamp: timestamp
+                .parse()
+                .map_err(|_| CoreAuthError::MissingHeaders)?,
+            nonce: nonce.to_owned(),
+        })
+    }
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum CoreAuthError {
+    #[error("Request is not signed")]
+    MissingHeaders,
+    #[error("Request signature is invalid")]
+    InvalidSignature,
+    #[error("Request is signed too far from the current time")]
+    Expired,
+    #[error("Request is already received")]
+    Replayed,
+    #[error("Request body is too large")]
+    BodyTooLarge,
+}
+
+fn new_mac(secret: &[u8]) -> HmacSha256 {
+    HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length")
+}
+
+/// Feeds the signed parts of the request into the MAC, the parts are separated by newlines
+/// and the body is hashed so the message is of the bounded size.
+fn update_mac(
+    mac: &mut HmacSha256,
+    method: &str,
+    path: &str,
+    timestamp: i64,
+    nonce: &str,
+    body: &[u8],
+) {
+    let body_hash = hex::encode(Sha256::digest(body));
+    let message = format!(
+        "{}\n{}\n{}\n{}\n{}",
+        method.to_ascii_uppercase(),
+        path,
+        timestamp,
+        nonce,
+        body_hash
+    );
+    mac.update(message.as_bytes());
+}
+
+/// Signs the requests of the API servers to the core.
+#[derive(Clone)]
+pub struct CoreAuthSigner {
+    secret: Vec<u8>,
+}
+
+impl fmt::Debug for CoreAuthSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoreAuthSigner").finish()
+    }
+}
+
+impl CoreAuthSigner {
+    pub fn new(secret: &str) -> Self {
+        Self {
+            secret: secret.as_bytes().to_vec(),
+        }
+    }
+
+    /// Signs the request at the current time with a random nonce.
+    pub fn sign(&self, method: &str, path: &str, body: &[u8]) -> CoreAuthHeaders {
+        let mut rng = OsRng::new().expect("Failed to access the OS random number generator");
+        let nonce: [u8; 16] = rng.gen();
+        self.sign_with(
+            method,
+            path,
+            body,
+            Utc::now().timestamp(),
+            hex::encode(nonce),
+        )
+    }
+
+    pub fn sign_with(
+        &self,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        timestamp: i64,
+        nonce: String,
+    ) -> CoreAuthHeaders {
+        let mut mac = new_mac(&self.secret);
+        update_mac(&mut mac, method, path, timestamp, &nonce, body);
+        CoreAuthHeaders {
+            signature: hex::encode(mac.finalize().into_bytes()),
+            timestamp,
+            nonce,
+        }
+    }
+}
+
+/// Verifies the requests to the core, shared by all the workers of the core private API.
+pub struct CoreAuthVerifier {
+    secrets: Vec<Vec<u8>>,
+    max_clock_skew: Duration,
+    max_body_size: usize,
+    /// Nonces of the received requests along with their timestamps. Nonces older than
+    /// the clock skew are forgotten, since such requests are rejected anyway.
+    nonces: Mutex<HashMap<String, i64>>,
+}
+
+impl fmt::Debug for CoreAuthVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoreAuthVerifier")
+            .field("max_clock_skew", &self.max_clock_skew)
+            .field("max_body_size", &self.max_body_size)
+            .finish()
+    }
+}
+
+impl CoreAuthVerifier {
+    /// Requests signed with any of the `secrets` are accepted, the bodies of at most
+    /// `max_body_size` bytes are read.
+    pub fn new(secrets: Vec<String>, max_clock_skew: Duration, max_body_size: usize) -> Self {
+        Self {
+            secrets: secrets
+                .into_iter()
+                .map(|secret| secret.into_bytes())
+                .collect(),
+            max_clock_skew,
+            max_body_size,
+            nonces: Default::default(),
+        }
+    }
+
+    /// Maximum size (in bytes) of the body of the request to be verified.
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_size
+    }
+
+    /// Checks the headers of the request at the current time, before its body is read.
+    pub fn check_headers(&self, headers: &CoreAuthHeaders) -> Result<(), CoreAuthError> {
+        self.check_headers_at(headers, Utc::now().timestamp())
+    }
+
+    /// Rejects the requests that would be rejected regardless of their body: the ones with
+    /// the malformed signature or signed too far from the current time.
+    pub fn check_headers_at(
+        &self,
+        headers: &CoreAuthHeaders,
+        now: i64,
+    ) -> Result<(), CoreAuthError> {
+        match hex::decode(&headers.signature) {
+            Ok(signature) if signature.len() == SIGNATURE_SIZE => {}
+            _ => return Err(CoreAuthError::InvalidSignature),
+        }
+        if (now - headers.timestamp).abs() > self.max_clock_skew.as_secs() as i64 {
+            return Err(CoreAuthError::Expired);
+        }
+        Ok(())
+    }
+
+    /// Verifies the request at the current time.
+    pub fn verify(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &CoreAuthHeaders,
+        body: &[u8],
+    ) -> Result<(), CoreAuthError> {
+        self.verify_at(method, path, headers, body, Utc::now().timestamp())
+    }
+
+    pub fn verify_at(
+        &self,
+        method: &str,
+        path: &str,
+        headers: &CoreAuthHeaders,
+        body: &[u8],
+        now: i64,
+    ) -> Result<(), CoreAuthError> {
+        let signature =
+            hex::decode(&headers.signature).map_err(|_| CoreAuthError::InvalidSignature)?;
+        let signed_with_known_secret = self.secrets.iter().any(|secret| {
+            let mut mac = new_mac(secret);
+            update_mac(
+                &mut mac,
+                method,
+                path,
+                headers.timestamp,
+                &headers.nonce,
+                body,
+            );
+            // The comparison is performed in constant time.
+            mac.verify(&signature).is_ok()
+        });
+        if !signed_with_known_secret {
+            return Err(CoreAuthError::InvalidSignature);
+        }
+
+        let max_clock_skew = self.max_clock_skew.as_secs() as i64;
+        if (now - headers.timestamp).abs() > max_clock_skew {
+            return Err(CoreAuthError::Expired);
+        }
+        let mut nonces = self.nonces.lock().unwrap();
+        nonces.retain(|_, timestamp| (now - *timestamp).abs() <= max_clock_skew);
+        if nonces
+            .insert(headers.nonce.clone(), headers.timestamp)
+            .is_some()
+        {
+            return Err(CoreAuthError::Replayed);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_600_000_000;
+
+    fn verifier() -> CoreAuthVerifier {
+        CoreAuthVerifier::new(
+            vec!["new".to_owned(), "previous".to_owned()],
+            Duration::from_secs(30),
+            1024,
+        )
+    }
+
+    #[test]
+    fn signed_requests() {
+        let verifier = verifier();
+        let signer = CoreAuthSigner::new("new");
+        let body = br#"[{"bySyncHash":"0x00"}]"#;
+        let headers = signer.sign_with("post", "/unconfirmed_ops_batch", body, NOW, "1".into());
+        verifier
+            .verify_at("POST", "/unconfirmed_ops_batch", &headers, body, NOW + 10)
+            .unwrap();
+
+        // Any part of the request is covered by the signature.
+        let mut forged = headers.clone();
+        forged.timestamp += 1;
+        for (method, path, headers, body) in [
+            ("GET", "/unconfirmed_ops_batch", &headers, &body[..]),
+            ("POST", "/status", &headers, &body[..]),
+            ("POST", "/unconfirmed_ops_batch", &headers, &b"[]"[..]),
+            ("POST", "/unconfirmed_ops_batch", &forged, &body[..]),
+        ] {
+            assert_eq!(
+                verifier.verify_at(method, path, headers, body, NOW),
+                Err(CoreAuthError::InvalidSignature)
+            );
+        }
+
+        // The previous secret is still accepted, unknown secrets are not.
+        let headers =
+            CoreAuthSigner::new("previous").sign_with("GET", "/status", b"", NOW, "2".into());
+        verifier
+            .verify_at("GET", "/status", &headers, b"", NOW)
+            .unwrap();
+        let headers =
+            CoreAuthSigner::new("unknown").sign_with("GET", "/status", b"", NOW, "3".into());
+        assert_eq!(
+            verifier.verify_at("GET", "/status", &headers, b"", NOW),
+            Err(CoreAuthError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn replayed_requests() {
+        let verifier = verifier();
+        let signer = CoreAuthSigner::new("new");
+
+        let headers = signer.sign_with("GET", "/status", b"", NOW, "1".into());
+        verifier
+            .verify_at("GET", "/status", &headers, b"", NOW)
+            .unwrap();
+        assert_eq!(
+            verifier.verify_at("GET", "/status", &headers, b"", NOW + 1),
+            Err(CoreAuthError::Replayed)
+        );
+        // Once the nonce is forgotten, the request is rejected because of its timestamp.
+        assert_eq!(
+            verifier.verify_at("GET", "/status", &headers, b"", NOW + 31),
+            Err(CoreAuthError::Expired)
+        );
+
+        let headers = signer.sign_with("GET", "/status", b"", NOW + 31, "2".into());
+        assert_eq!(
+            verifier.verify_at("GET", "/status", &headers, b"", NOW),
+            Err(CoreAuthError::Expired)
+        );
+        let headers = signer.sign("GET", "/status", b"");
+        assert_ne!(headers.nonce, signer.sign("GET", "/status", b"").nonce);
+        verifier.verify("GET", "/status", &headers, b"").unwrap();
+    }
+
+    #[test]
+    fn headers_checked_before_body() {
+        let verifier = verifier();
+        let signer = CoreAuthSigner::new("new");
+
+        // The check doesn't depend on the body and doesn't consume the nonce.
+        let headers = signer.sign_with("POST", "/status", b"body", NOW, "1".into());
+        verifier.check_headers_at(&headers, NOW + 10).unwrap();
+        verifier
+            .verify_at("POST", "/status", &headers, b"body", NOW + 10)
+            .unwrap();
+
+        assert_eq!(
+            verifier.check_headers_at(&headers, NOW + 31),
+            Err(CoreAuthError::Expired)
+        );
+        let mut malformed = headers.clone();
+        malformed.signature = "zz".repeat(32);
+        assert_eq!(
+            verifier.check_headers_at(&malformed, NOW),
+            Err(CoreAuthError::InvalidSignature)
+        );
+        malformed.signature = "00".to_owned();
+        assert_eq!(
+            verifier.check_headers_at(&malformed, NOW),
+            Err(CoreAuthError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn parse_headers() {
+        let headers = CoreAuthSigner::new("new").sign_with("GET", "/status", b"", NOW, "1".into());
+        let pairs = headers.pairs();
+        let find = |name: &str| {
+            pairs
+                .iter()
+                .find(|(header, _)| *header == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(CoreAuthHeaders::parse(find), Ok(headers));
+        assert_eq!(
+            CoreAuthHeaders::parse(|_| None),
+            Err(CoreAuthError::MissingHeaders)
+        );
+    }
+}
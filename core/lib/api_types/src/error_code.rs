@@ -0,0 +1,294 @@
+//! Registry of the error codes shared by the REST and JSON-RPC APIs.
+//!
+//! Codes and names are stable: once assigned, a code is never reused for another error,
+//! so that clients can branch on them regardless of the API they use.
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// Description of the error code, as listed by the `errors` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorCodeInfo {
+    pub code: u16,
+    pub name: String,
+    pub description: String,
+}
+
+macro_rules! error_codes {
+    ($(#[doc = $description:literal] $name:ident = $code:literal,)+) => {
+        #[derive(Serialize_repr, Debug, Deserialize_repr, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(u16)]
+        pub enum ErrorCode {
+            $(#[doc = $description] $name = $code,)+
+        }
+
+        impl ErrorCode {
+            /// All the registered error codes in the ascending order.
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$name,)+];
+
+            /// Stable name of the error.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => stringify!($name),)+
+                }
+            }
+
+            pub fn description(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => $description.trim(),)+
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// The error is not expected to happen.
+    UnreacheableError = 0,
+    /// The core server failed to process the request.
+    CoreApiError = 100,
+    /// The price cannot be shown in the token with the zero price.
+    TokenZeroPriceError = 200,
+    /// The currency is not a token ID, `usd` or `eth`.
+    InvalidCurrency = 201,
+    /// The block position is not a number, `lastCommitted` or `lastFinalized`.
+    InvalidBlockPosition = 202,
+    /// The account ID or address cannot be parsed.
+    InvalidAccountIdOrAddress = 203,
+    /// The account is not found.
+    AccountNotFound = 204,
+    /// The transaction is not found.
+    TransactionNotFound = 205,
+    /// The pagination limit is too big.
+    PaginationLimitTooBig = 206,
+    /// The query parameters cannot be deserialized.
+    QueryDeserializationError = 207,
+    /// The NFT token ID is below the minimal one.
+    InvalidNFTTokenId = 208,
+    /// The public key hash cannot be parsed.
+    InvalidPubKeyHash = 209,
+    /// Too many or no tokens are requested.
+    TooManyTokens = 210,
+    /// The range of the fee statistics is invalid.
+    InvalidFeeStatisticsRange = 211,
+    /// Neither the transaction hashes nor the transactions of the batch are provided.
+    InvalidBatchHashRequest = 212,
+    /// The request body is too large.
+    PayloadTooLarge = 213,
+    /// The token address cannot be parsed.
+    InvalidTokenAddress = 214,
+    /// The range of the blocks export is invalid.
+    InvalidBlocksExportRange = 215,
+    /// The pagination parameters are missing.
+    MissingPaginationParams = 216,
+    /// Too many or no blocks are requested.
+    TooManyBlockNumbers = 217,
+    /// The minimal USD balance is negative or cannot be parsed.
+    InvalidMinBalanceUsd = 218,
+    /// The number of the requested token holders is out of range.
+    InvalidTokenHoldersLimit = 219,
+    /// The holders of NFTs are not tracked.
+    NftHoldersNotTracked = 220,
+    /// The address is reserved and doesn't belong to a user account.
+    NotUserAccount = 221,
+    /// Neither a single transaction nor the transactions of the batch are provided for signing, or both are.
+    InvalidSignMessageRequest = 222,
+    /// The resource requested by its identifier is not found.
+    NotFound = 223,
+    /// The searched transaction hash prefix is not a hex string or is too short.
+    InvalidTxHashPrefix = 224,
+    /// The `secondAccount` and `counterparty` filters of the account transactions are used together.
+    ConflictingAccountTxsFilters = 225,
+    /// Neither the checkpoint nor the transaction or the block to get the account history changes since
+    /// is provided, more than one of them is provided, or the checkpoint is malformed.
+    InvalidAccountTxsDeltaQuery = 226,
+    /// The execution ETA is requested for a transaction that is not a successful withdrawal.
+    NotWithdrawal = 227,
+    /// The execution ETA is requested for a withdrawal which block is not verified yet.
+    WithdrawalNotVerified = 228,
+    /// The identifier in the request path is malformed.
+    InvalidPathParameter = 229,
+    /// The search query doesn't look like any identifier known to the API.
+    InvalidSearchQuery = 230,
+    /// The public data is requested for a transaction that is not included into a block or is rejected.
+    TxHasNoPublicData = 231,
+    /// The requested token activity range is empty or too long.
+    InvalidTokenActivityRange = 232,
+    /// The database query failed.
+    StorageError = 300,
+    /// The database is overloaded, the request can be retried later.
+    StoragePoolExhausted = 301,
+    /// The token is not found.
+    TokenNotFound = 500,
+    /// The external price API failed.
+    ExternalApiError = 501,
+    /// The server failed to process the request.
+    InternalError = 600,
+    /// Account close transactions are disabled.
+    AccountCloseDisabled = 601,
+    /// The request parameters are invalid.
+    InvalidParams = 602,
+    /// Fast processing is requested for a transaction other than withdrawal.
+    UnsupportedFastProcessing = 603,
+    /// The transaction is incorrect.
+    IncorrectTx = 604,
+    /// The transaction cannot be added to the mempool.
+    TxAddError = 605,
+    /// The token is not suitable for paying fees.
+    InappropriateFeeToken = 606,
+    /// The server failed to communicate with the mempool.
+    CommunicationCoreServer = 607,
+    /// 2FA cannot be toggled.
+    Toggle2FAError = 608,
+    /// Transactions of the type are temporarily disabled.
+    TxTypeDisabled = 609,
+    /// The fee quote is expired, not yet valid, not signed by the operator or doesn't match the transaction.
+    InvalidFeeQuote = 610,
+    /// The transaction involves an address that is not allowed to use the network.
+    AddressBlocked = 611,
+    /// The server is overloaded, the request can be retried later.
+    ServerOverloaded = 612,
+    /// The blocks export requires a valid authorization token.
+    BlocksExportUnauthorized = 613,
+    /// Another blocks export is running for the token.
+    BlocksExportInProgress = 614,
+    /// Too many blocks exports are running.
+    TooManyBlocksExports = 615,
+    /// The CREATE2 factory is not allowed.
+    Create2FactoryNotAllowed = 616,
+    /// The account address doesn't match the one derived for the CREATE2 factory.
+    Create2AddressMismatch = 617,
+    /// The state root check requires a valid authorization token.
+    StateRootCheckUnauthorized = 618,
+    /// Another state root check is running.
+    StateRootCheckInProgress = 619,
+    /// State root checks are too frequent, the request can be retried later.
+    StateRootCheckThrottled = 620,
+    /// The block of the state root check is not committed.
+    StateRootCheckBlockNotFound = 621,
+    /// There is no account tree snapshot before the block.
+    AccountTreeSnapshotNotFound = 622,
+    /// The token is not suitable for paying fees of the transaction type.
+    FeeTokenNotAllowedForTxType = 623,
+    /// The withdrawal destination is rejected by the compliance screening.
+    ComplianceRejected = 624,
+    /// The transaction nonce doesn't match the account nonce.
+    NonceMismatch = 625,
+    /// The transaction fee is too low.
+    TxFeeTooLow = 626,
+    /// The total fee of the batch is too low.
+    TxBatchFeeTooLow = 627,
+    /// The Ethereum signature is required but missing.
+    MissingEthSignature = 628,
+    /// The EIP-1271 signature cannot be verified.
+    EIP1271SignatureVerificationFail = 629,
+    /// The Ethereum signature is incorrect.
+    IncorrectEthSignature = 630,
+    /// The `ChangePubKey` transaction is not authorized onchain.
+    ChangePkNotAuthorized = 631,
+    /// The batch is empty.
+    EmptyBatch = 632,
+    /// The batch doesn't fit into any of the supported block sizes.
+    BatchTooBig = 633,
+    /// The batch has too many withdrawals.
+    BatchWithdrawalsOverload = 634,
+    /// Too many Ethereum signatures are provided.
+    EthSignaturesLimitExceeded = 635,
+    /// The access delegation is not signed by the account owner or its expiry is invalid.
+    InvalidAccessDelegation = 636,
+    /// The access token is unknown or revoked.
+    InvalidAccessToken = 637,
+    /// The access token has expired.
+    AccessTokenExpired = 638,
+    /// The access token doesn't grant access to the requested endpoint.
+    AccessTokenForbidden = 639,
+    /// The transaction sends funds to a reserved address, e.g. the zero address or the NFT storage account.
+    ReservedRecipient = 640,
+    /// A transaction of a batch is requested to be cancelled, batches are executed atomically.
+    BatchTxCancellation = 641,
+    /// The transaction amount or fee can't be packed into the pubdata format.
+    AmountNotPackable = 642,
+    /// The authorization token for the transactions search is missing or invalid.
+    TxSearchUnauthorized = 643,
+    /// The transaction refers to a token that doesn't exist.
+    UnknownToken = 644,
+    /// The transaction refers to a token that is temporarily not accepted.
+    TokenDisabled = 645,
+    /// The amount of the transfer or withdrawal is below the minimum for the token.
+    AmountBelowMinimum = 646,
+    /// The request took longer than the hard timeout and was aborted.
+    RequestTimedOut = 647,
+    /// The recently created account has submitted too many transactions, the submission can be retried later.
+    TxQuotaExceeded = 648,
+    /// The price of the fee token is older than allowed for its liquidity, the fee can't be calculated in it.
+    StaleFeeTokenPrice = 649,
+    /// The account is locked by its owner, only `ChangePubKey` transactions are accepted from it.
+    AccountLocked = 650,
+    /// The account can't be locked or unlocked.
+    AccountLockError = 651,
+    /// The webhook subscription is not signed by the account owner, is not recent or has an invalid URL.
+    InvalidWebhookSubscription = 652,
+    /// The webhook preferences can never match an event, e.g. the USD amount filter for the NFTs.
+    InvalidWebhookPreferences = 653,
+    /// The webhook subscription is unknown or its secret doesn't match.
+    InvalidWebhookSecret = 654,
+    /// Unclassified error.
+    Other = 60_000,
+}
+
+impl ErrorCode {
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    pub fn info(self) -> ErrorCodeInfo {
+        ErrorCodeInfo {
+            code: self.code(),
+            name: self.name().to_owned(),
+            description: self.description().to_owned(),
+        }
+    }
+
+    /// Returns the descriptions of all the registered error codes.
+    pub fn registry() -> Vec<ErrorCodeInfo> {
+        Self::ALL.iter().map(|code| code.info()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn error_codes_are_unique() {
+        let codes: HashSet<_> = ErrorCode::ALL.iter().map(|code| code.code()).collect();
+        assert_eq!(codes.len(), ErrorCode::ALL.len());
+        let names: HashSet<_> = ErrorCode::ALL.iter().map(|code| code.name()).collect();
+        assert_eq!(names.len(), ErrorCode::ALL.len());
+
+        let mut sorted = ErrorCode::ALL.to_vec();
+        sorted.sort_by_key(|code| code.code());
+        assert_eq!(sorted, ErrorCode::ALL);
+    }
+
+    #[test]
+    fn error_codes_serialization() {
+        for &code in ErrorCode::ALL {
+            let value = serde_json::to_value(code).unwrap();
+            assert_eq!(value, serde_json::json!(code.code()));
+            let deserialized: ErrorCode = serde_json::from_value(value).unwrap();
+            assert_eq!(deserialized, code);
+            assert!(!code.description().is_empty());
+        }
+
+        let info = ErrorCode::NonceMismatch.info();
+        assert_eq!(info.code, 625);
+        assert_eq!(info.name, "NonceMismatch");
+        assert_eq!(
+            info.description,
+            "The transaction nonce doesn't match the account nonce."
+        );
+    }
+}
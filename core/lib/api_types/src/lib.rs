@@ -3,9 +3,11 @@ pub use either::Either;
 use serde::{Deserialize, Serialize};
 use zksync_types::{
     tx::{TxEthSignatureVariant, TxHash},
-    ZkSyncTx, H256,
+    AccountUpdates, BlockNumber, ZkSyncTx, H256, U256,
 };
 
+pub mod core_auth;
+pub mod error_code;
 pub mod v02;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,10 +16,18 @@ pub struct TxWithSignature {
     pub tx: ZkSyncTx,
     #[serde(default)]
     pub signature: TxEthSignatureVariant,
+    /// Signed fee quote, the quoted fee is accepted until the quote expires.
+    /// Not taken into account for transactions in batches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_quote: Option<v02::fee::SignedFeeQuote>,
 }
 
+/// Maximum number of the lookups in a single request of the unconfirmed priority operations
+/// to the core private API.
+pub const MAX_UNCONFIRMED_OPS_BATCH_SIZE: usize = 100;
+
 /// Combined identifier of the priority operations for the lookup.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PriorityOpLookupQuery {
     /// Query priority operation using zkSync hash, which is calculated based on the priority operation metadata.
     BySyncHash(TxHash),
@@ -36,3 +46,222 @@ pub struct CoreStatus {
     pub replica_database_available: bool,
     pub web3_available: bool,
 }
+
+/// Status of the Ethereum watcher running inside of the core server.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthWatchStatus {
+    /// The last Ethereum block processed by the watcher.
+    pub last_processed_block: u64,
+    /// Amount of confirmations required for the priority operation to be accepted.
+    pub confirmations_for_event: u64,
+}
+
+/// Stats of the block currently filled by the state keeper.
+/// The stats are taken after every miniblock iteration, so they are advisory and may be stale,
+/// all the timestamps are unix timestamps in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingBlockStatus {
+    pub block_number: BlockNumber,
+    pub chunks_used: u64,
+    /// Chunks of the largest block, the block is sealed once there is no room for the next operation.
+    pub chunks_capacity: u64,
+    /// Amount of the operations included into the block.
+    pub txs_count: u64,
+    /// Amount of the rejected transactions of the block.
+    pub failed_txs_count: u64,
+    /// Whether the block is sealed faster than usual, e.g. because of the fast withdrawals.
+    pub fast_processing: bool,
+    /// The time the block was opened at, the empty block is reopened on every miniblock iteration.
+    pub opened_at: u64,
+    pub open_for_secs: u64,
+    /// The time the block is expected to be sealed at unless it's filled earlier,
+    /// `None` for the empty block since it's not sealed until the first operation is included.
+    pub expected_seal_at: Option<u64>,
+    /// The time the stats were taken at.
+    pub updated_at: u64,
+}
+
+/// Ethereum transaction sent for the aggregated operation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthTxAttempt {
+    pub tx_hash: H256,
+    /// Gas price of the transaction, `None` if the transaction is not known to the Ethereum node.
+    pub gas_price: Option<U256>,
+    /// Confirmations of the transaction, `None` if the transaction is not executed yet.
+    pub confirmations: Option<u64>,
+    /// Whether the executed transaction succeeded, `None` if the transaction is not executed yet.
+    pub success: Option<bool>,
+}
+
+/// Status of the Ethereum operation sent by the Ethereum sender for the aggregated operation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthOperationStatus {
+    pub aggregated_op_id: i64,
+    pub eth_op_id: i64,
+    pub nonce: i64,
+    pub confirmed: bool,
+    pub final_hash: Option<H256>,
+    pub last_deadline_block: u64,
+    /// Whether the resend of the operation was requested, but not yet picked up by the Ethereum sender.
+    pub resend_requested: bool,
+    /// Sent transactions in the order they were sent.
+    pub attempts: Vec<EthTxAttempt>,
+}
+
+/// Number of the recent Ethereum transactions in the gas report of the Ethereum sender
+/// if the limit is not specified.
+pub const DEFAULT_GAS_REPORT_TXS: u32 = 20;
+/// Maximum number of the recent Ethereum transactions in the gas report of the Ethereum sender.
+pub const MAX_GAS_REPORT_TXS: u32 = 100;
+
+/// State of the gas adjuster of the Ethereum sender, all the prices are in wei.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GasAdjusterStatus {
+    /// Gas price of the last sent transaction, `None` if nothing was sent since the start.
+    pub last_gas_price: Option<U256>,
+    /// Average of the recent gas price samples, `None` until enough samples are collected.
+    pub average_gas_price: Option<U256>,
+    /// Cap of the gas price, transactions are never sent with a higher price.
+    pub gas_price_limit: U256,
+    pub samples_count: u64,
+    /// The time the status was published at, unix timestamp in seconds.
+    pub updated_at: u64,
+}
+
+/// Ethereum transaction sent by the Ethereum sender along with the gas it used.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SentEthTxGas {
+    pub eth_op_id: i64,
+    pub op_type: String,
+    pub nonce: i64,
+    pub confirmed: bool,
+    pub final_hash: Option<H256>,
+    /// Gas price of the last transaction sent for the operation.
+    pub gas_price: U256,
+    /// Gas used by the confirmed transaction, `None` until it's confirmed.
+    pub gas_used: Option<u64>,
+    /// Fee paid for the confirmed transaction in wei.
+    pub fee: Option<U256>,
+    /// Unix timestamp in seconds, `None` for the operations created before it was recorded.
+    pub created_at: Option<u64>,
+}
+
+/// Fees paid by the Ethereum sender within the last 24 hours compared to the configured budget.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySpend {
+    /// Fees paid for the confirmed transactions in wei.
+    pub spent: U256,
+    /// `None` if the budget is not configured.
+    pub budget: Option<U256>,
+    /// Spent part of the budget in percents, rounded down. May exceed 100.
+    pub budget_used_percent: Option<u64>,
+    pub budget_exceeded: bool,
+}
+
+impl DailySpend {
+    pub fn new(spent: U256, budget: Option<U256>) -> Self {
+        let budget_used_percent = budget
+            .filter(|budget| !budget.is_zero())
+            .map(|budget| (spent.saturating_mul(100.into()) / budget).low_u64());
+        Self {
+            spent,
+            budget,
+            budget_used_percent,
+            budget_exceeded: budget.map_or(false, |budget| spent > budget),
+        }
+    }
+}
+
+/// Gas price strategy and the recent gas spend of the Ethereum sender.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EthSenderGasReport {
+    /// `None` if the Ethereum sender is not running in the process of the core server.
+    pub gas_adjuster: Option<GasAdjusterStatus>,
+    /// Gas price cap stored by the gas adjuster on its last update.
+    pub stored_gas_price_limit: U256,
+    /// Average gas price stored by the gas adjuster on its last update.
+    pub stored_average_gas_price: Option<U256>,
+    /// The latest sent transactions, newest first.
+    pub recent_txs: Vec<SentEthTxGas>,
+    pub daily_spend: DailySpend,
+}
+
+/// Outcome of the request to cancel a transaction that is not executed yet.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TxCancellation {
+    /// The transaction is removed and won't be executed.
+    Cancelled,
+    /// The transaction is already proposed for a block, so it can't be cancelled anymore.
+    TooLate,
+}
+
+/// Whereabouts of the transaction in the core mempool.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MempoolTxLookup {
+    /// The transaction is waiting in the mempool.
+    Pending,
+    /// The transaction is recently proposed for a block, it may be not stored with the block yet.
+    Proposed,
+    /// The mempool doesn't know the transaction.
+    Unknown,
+}
+
+/// Outcome of the transaction executed against the committed state of the accounts it affects,
+/// without persisting anything.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSimulation {
+    /// Last committed block, the state of the accounts is taken at.
+    pub block_number: BlockNumber,
+    /// Whether the state keeper would execute the transaction successfully.
+    pub success: bool,
+    /// Reason of the failure, the same as the one stored for the failed transaction.
+    pub fail_reason: Option<String>,
+    /// Updates of the accounts the transaction would make, empty if it fails.
+    pub updates: AccountUpdates,
+    /// Chunks of the block the transaction would take.
+    pub chunks: Option<usize>,
+}
+
+impl EthWatchStatus {
+    /// Returns the amount of Ethereum blocks left until the event emitted in the
+    /// block `eth_block` gets enough confirmations.
+    ///
+    /// The last processed block may briefly go backwards (e.g. if the watcher
+    /// was restarted), thus the result is never negative.
+    pub fn confirmations_remaining(&self, eth_block: u64) -> u64 {
+        eth_block
+            .saturating_add(self.confirmations_for_event)
+            .saturating_sub(self.last_processed_block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_spend() {
+        let spend = DailySpend::new(250.into(), Some(1000.into()));
+        assert_eq!(spend.budget_used_percent, Some(25));
+        assert!(!spend.budget_exceeded);
+
+        let spend = DailySpend::new(1001.into(), Some(1000.into()));
+        assert_eq!(spend.budget_used_percent, Some(100));
+        assert!(spend.budget_exceeded);
+
+        let spend = DailySpend::new(1.into(), None);
+        assert_eq!(spend.budget_used_percent, None);
+        assert!(!spend.budget_exceeded);
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_types::{tx::PackedEthSignature, Address};
+
+/// Prefix of the access tokens, which are sent as `Authorization: Bearer <token>`.
+pub const ACCESS_TOKEN_PREFIX: &str = "zkat_";
+
+/// Maximum validity period of the access tokens.
+pub const MAX_ACCESS_TOKEN_VALIDITY_DAYS: i64 = 90;
+
+/// Access granted to the holder of the token.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AccessScope {
+    /// Read-only access to the account endpoints: state, balances and history.
+    Read,
+}
+
+impl AccessScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+        }
+    }
+}
+
+/// Delegation of the access to the account data, signed by the account owner.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessDelegation {
+    pub address: Address,
+    pub scope: AccessScope,
+    /// Unix timestamp in seconds after which the issued token is no longer accepted.
+    pub expires_at: u64,
+}
+
+impl AccessDelegation {
+    /// Returns the message which is signed by the account owner.
+    pub fn message(&self) -> String {
+        format!(
+            "zkSync account access delegation\n\
+            Account: {:?}\n\
+            Scope: {}\n\
+            Expires at: {}",
+            self.address,
+            self.scope.as_str(),
+            self.expires_at
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAccessDelegation {
+    pub delegation: AccessDelegation,
+    pub signature: PackedEthSignature,
+}
+
+/// Token issued for the delegation. The token itself is returned only once.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedAccessToken {
+    pub id: i64,
+    pub token: String,
+    pub address: Address,
+    pub scope: AccessScope,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Revocation of all the tokens issued for the account, signed by the account owner.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessRevocation {
+    pub address: Address,
+    /// Unix timestamp in seconds, the revocation is accepted only if it's recent.
+    pub timestamp: u64,
+}
+
+impl AccessRevocation {
+    /// Returns the message which is signed by the account owner.
+    pub fn message(&self) -> String {
+        format!(
+            "zkSync account access revocation\n\
+            Account: {:?}\n\
+            Timestamp: {}",
+            self.address, self.timestamp
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAccessRevocation {
+    pub revocation: AccessRevocation,
+    pub signature: PackedEthSignature,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RevokedAccessTokens {
+    pub revoked: u64,
+}
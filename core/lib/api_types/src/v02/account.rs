@@ -1,15 +1,22 @@
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
 
+use chrono::serde::ts_milliseconds;
+use chrono::{DateTime, Utc};
 use num::{BigUint, ToPrimitive};
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use zksync_types::{
+    tx::{TxEthSignature, TxHash, TxSignature},
     AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, TokenId, ZkSyncPriorityOp,
+    H256,
 };
 use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
 
 use super::pagination::PaginationDirection;
 use super::token::NFT;
+use super::transaction::Transaction;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 #[serde(rename_all = "camelCase")]
@@ -31,6 +38,103 @@ pub struct Account {
     pub nfts: BTreeMap<TokenId, NFT>,
     pub minted_nfts: BTreeMap<TokenId, NFT>,
     pub account_type: Option<EthAccountType>,
+    /// Role of the account in the network, the older servers don't report it.
+    #[serde(default)]
+    pub account_kind: AccountKind,
+    /// Symbols of the `balances` sorted by the USD value of the balance descending,
+    /// only present if the balances were valued with `minBalanceUsd`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balances_order: Option<Vec<String>>,
+    /// Set if `minBalanceUsd` was requested, but the token prices are unavailable,
+    /// so the balances are neither filtered by value nor sorted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub balances_not_valued: bool,
+    /// Lock of the outgoing transactions set by the owner, present if the account is locked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock: Option<AccountLockInfo>,
+}
+
+/// Lock of the outgoing transactions of the account, only `ChangePubKey` is accepted
+/// from the locked account.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountLockInfo {
+    pub locked_at: DateTime<Utc>,
+    /// Time the account is unlocked at, set once the owner has requested the unlock.
+    pub unlocks_at: Option<DateTime<Utc>>,
+}
+
+/// Request of the account owner to lock the outgoing transactions of the account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LockAccount {
+    pub account_id: AccountId,
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+    /// Signature of the [LockAccount::get_ethereum_sign_message] by the account owner.
+    pub signature: TxEthSignature,
+}
+
+impl LockAccount {
+    pub fn get_ethereum_sign_message(&self) -> String {
+        format!(
+            "By signing this message, you are locking your zkSync account.\n\
+            Only ChangePubKey transactions are accepted from the locked account.\n\
+            Account ID: {}\n\
+            Timestamp: {}",
+            self.account_id,
+            self.timestamp.timestamp_millis()
+        )
+    }
+}
+
+/// Request of the account owner to unlock the account, the account is unlocked once
+/// the cool-down is over. The request has to be signed either with the Ethereum key
+/// of the owner or with the current signing key of the account.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockAccount {
+    pub account_id: AccountId,
+    #[serde(with = "ts_milliseconds")]
+    pub timestamp: DateTime<Utc>,
+    #[serde(default)]
+    pub eth_signature: Option<TxEthSignature>,
+    #[serde(default)]
+    pub signature: Option<TxSignature>,
+}
+
+impl UnlockAccount {
+    /// Returns the message signed by either of the keys.
+    pub fn get_sign_message(&self) -> String {
+        format!(
+            "By signing this message, you are unlocking your zkSync account.\n\
+            Transactions from the account are accepted again once the cool-down is over.\n\
+            Account ID: {}\n\
+            Timestamp: {}",
+            self.account_id,
+            self.timestamp.timestamp_millis()
+        )
+    }
+}
+
+/// Role of the account in the network.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum AccountKind {
+    /// Account controlled by a user.
+    User,
+    /// Account of the operator collecting the fees.
+    FeeCollector,
+    /// Account holding the contents of the minted NFTs, it isn't controlled by anyone.
+    NftStorage,
+    /// Address that can't own an account, e.g. the zero address.
+    Reserved,
+}
+
+impl Default for AccountKind {
+    fn default() -> Self {
+        Self::User
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -40,11 +144,35 @@ pub enum AccountAddressOrId {
     Id(AccountId),
 }
 
+impl fmt::Display for AccountAddressOrId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(address) => write!(f, "{:?}", address),
+            Self::Id(account_id) => write!(f, "{}", account_id),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum EthAccountType {
     Owned,
     CREATE2,
     No2FA(Option<PubKeyHash>),
+    /// The signing key of the account has never been set.
+    NoKey,
+}
+
+impl EthAccountType {
+    /// Returns the type of the account with the given signing key,
+    /// `stored_type` is the type set by its latest `ChangePubKey`.
+    pub fn for_account(stored_type: Option<Self>, pub_key_hash: &PubKeyHash) -> Self {
+        match stored_type {
+            Some(Self::No2FA(hash)) => Self::No2FA(hash),
+            _ if *pub_key_hash == PubKeyHash::default() => Self::NoKey,
+            Some(account_type) => account_type,
+            None => Self::Owned,
+        }
+    }
 }
 
 /// Information about ongoing deposits for certain recipient address.
@@ -114,4 +242,419 @@ pub struct IncomingAccountTxsQuery {
     pub direction: PaginationDirection,
     pub token: Option<String>,
     pub second_account: Option<String>,
+    pub counterparty: Option<String>,
+}
+
+/// Filters of the balances in the account state responses.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BalancesQuery {
+    /// Balances worth less than the given amount of USD are omitted, the rest are sorted
+    /// by their USD value descending. Balances of the tokens without a known price are kept.
+    pub min_balance_usd: Option<f64>,
+    /// Comma-separated token IDs, addresses or symbols to return the balances of.
+    pub tokens: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedTxsQuery {
+    pub limit: u32,
+}
+
+/// Normalized reason of the transaction rejection.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum RejectionCode {
+    NonceMismatch,
+    NotEnoughBalance,
+    AccountLocked,
+    AccountNotFound,
+    UnsupportedToken,
+    IncorrectSignature,
+    InvalidTimestamp,
+    Expired,
+    Other,
+}
+
+impl RejectionCode {
+    /// Maps the fail reason reported by the state (or by the mempool for the
+    /// evicted transactions) to the normalized code.
+    pub fn from_fail_reason(fail_reason: &str) -> Self {
+        let fail_reason = fail_reason.to_lowercase();
+        if fail_reason.contains("nonce mismatch") {
+            Self::NonceMismatch
+        } else if fail_reason.contains("not enough") {
+            Self::NotEnoughBalance
+        } else if fail_reason.contains("is locked") {
+            Self::AccountLocked
+        } else if fail_reason.contains("does not exist")
+            || fail_reason.contains("account not found")
+        {
+            Self::AccountNotFound
+        } else if fail_reason.contains("is not supported") {
+            Self::UnsupportedToken
+        } else if fail_reason.contains("signature is incorrect") {
+            Self::IncorrectSignature
+        } else if fail_reason.contains("invalid timestamp") {
+            Self::InvalidTimestamp
+        } else if fail_reason.contains("expired") {
+            Self::Expired
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Where the transaction was rejected.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum RejectionSource {
+    /// The transaction was included into a block, but its execution failed.
+    Executed,
+    /// The transaction was removed from the mempool without being executed.
+    Evicted,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedTransaction {
+    pub tx_hash: TxHash,
+    pub source: RejectionSource,
+    pub code: RejectionCode,
+    pub fail_reason: Option<String>,
+    pub rejected_at: DateTime<Utc>,
+}
+
+/// Account that has (or had) the requested public key hash set.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountByPubKeyHash {
+    pub account_id: AccountId,
+    pub address: Address,
+    /// Whether the public key hash is the current one for the account.
+    /// `false` means that it was set at some point, but then changed.
+    pub is_current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountActivityQuery {
+    /// `nextFrom` value of the previous page, the newest entries are returned if not set.
+    pub from: Option<i64>,
+    pub limit: u32,
+}
+
+/// Layer the entry of the account activity comes from.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityKind {
+    /// Priority operation initiated on Ethereum (e.g. a deposit or a full exit).
+    L1,
+    /// Transaction submitted to zkSync.
+    L2,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountActivityEntry {
+    pub kind: ActivityKind,
+    /// Position of the entry in the sequence shared by the priority operations and transactions.
+    pub sequence_number: i64,
+    pub tx: Transaction,
+}
+
+/// Executed priority operations and transactions of the account, newest first.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountActivity {
+    pub list: Vec<AccountActivityEntry>,
+    /// Value of `from` to request the next page with, `None` if there are no older entries.
+    pub next_from: Option<i64>,
+}
+
+/// Query of the account history changes. The changes are requested either since the checkpoint
+/// returned by the previous request, or, on the first request, since the transaction or the block
+/// the client already has the history up to.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTxsDeltaQuery {
+    pub checkpoint: Option<String>,
+    pub since_tx: Option<TxHash>,
+    pub since_block: Option<BlockNumber>,
+    pub limit: u32,
+}
+
+/// Position the client has synced the account history up to: the last committed block it has
+/// the transactions of and the last finalized block at that time. Serialized as
+/// `<block>:<finalized block>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountTxsCheckpoint {
+    pub block: BlockNumber,
+    pub finalized_block: BlockNumber,
+}
+
+impl fmt::Display for AccountTxsCheckpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.block, self.finalized_block)
+    }
+}
+
+impl FromStr for AccountTxsCheckpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_block = |block: &str| {
+            u32::from_str(block)
+                .map(BlockNumber)
+                .map_err(|_| format!("Invalid block number `{}` in the checkpoint", block))
+        };
+        let (block, finalized_block) = s.split_once(':').ok_or_else(|| {
+            "Checkpoint should be in the `<block>:<finalized block>` format".to_owned()
+        })?;
+        let checkpoint = Self {
+            block: parse_block(block)?,
+            finalized_block: parse_block(finalized_block)?,
+        };
+        if checkpoint.finalized_block > checkpoint.block {
+            return Err(
+                "Finalized block of the checkpoint can't be greater than its block".to_owned(),
+            );
+        }
+        Ok(checkpoint)
+    }
+}
+
+impl Serialize for AccountTxsCheckpoint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountTxsCheckpoint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+/// Changes of the account history since the checkpoint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTxsDelta {
+    /// Transactions and priority operations executed after the checkpoint, oldest first.
+    pub transactions: Vec<Transaction>,
+    /// Transactions that were committed as of the checkpoint and are finalized since, oldest first.
+    /// Executed transactions can't change their status otherwise. Not reported if the changes
+    /// are requested without the checkpoint, since the finalized block the client has seen
+    /// is not known then.
+    pub status_changes: Vec<Transaction>,
+    /// Checkpoint to request the next changes with.
+    pub checkpoint: AccountTxsCheckpoint,
+    /// Whether there are more transactions after the checkpoint, i.e. the transactions
+    /// were limited and the next changes should be requested right away.
+    pub has_more: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejection_code_from_fail_reason() {
+        let cases = [
+            ("Nonce mismatch", RejectionCode::NonceMismatch),
+            (
+                "Batch execution failed, since tx #2 of batch failed with a reason: Not enough balance",
+                RejectionCode::NotEnoughBalance,
+            ),
+            ("Account is locked", RejectionCode::AccountLocked),
+            ("From account does not exist", RejectionCode::AccountNotFound),
+            ("Token id is not supported", RejectionCode::UnsupportedToken),
+            ("Transfer signature is incorrect", RejectionCode::IncorrectSignature),
+            (
+                "The transaction can't be executed in the block because of an invalid timestamp",
+                RejectionCode::InvalidTimestamp,
+            ),
+            ("Transaction has expired", RejectionCode::Expired),
+            ("Self-swap is not allowed", RejectionCode::Other),
+        ];
+        for (fail_reason, expected_code) in cases.iter() {
+            assert_eq!(
+                RejectionCode::from_fail_reason(fail_reason),
+                *expected_code,
+                "{}",
+                fail_reason
+            );
+        }
+    }
+
+    #[test]
+    fn account_type_for_account() {
+        let no_key = PubKeyHash::default();
+        let key = PubKeyHash { data: [1; 20] };
+
+        assert_eq!(
+            EthAccountType::for_account(Some(EthAccountType::Owned), &key),
+            EthAccountType::Owned
+        );
+        assert_eq!(
+            EthAccountType::for_account(Some(EthAccountType::CREATE2), &key),
+            EthAccountType::CREATE2
+        );
+        // Accounts which set their keys before the types were tracked are owned.
+        assert_eq!(
+            EthAccountType::for_account(None, &key),
+            EthAccountType::Owned
+        );
+        assert_eq!(
+            EthAccountType::for_account(None, &no_key),
+            EthAccountType::NoKey
+        );
+        assert_eq!(
+            EthAccountType::for_account(Some(EthAccountType::Owned), &no_key),
+            EthAccountType::NoKey
+        );
+        assert_eq!(
+            EthAccountType::for_account(Some(EthAccountType::No2FA(None)), &key),
+            EthAccountType::No2FA(None)
+        );
+    }
+
+    #[test]
+    fn txs_checkpoint_format() {
+        let checkpoint = AccountTxsCheckpoint {
+            block: BlockNumber(120),
+            finalized_block: BlockNumber(117),
+        };
+        assert_eq!(checkpoint.to_string(), "120:117");
+        assert_eq!("120:117".parse::<AccountTxsCheckpoint>(), Ok(checkpoint));
+        assert_eq!(
+            serde_json::to_value(checkpoint).unwrap(),
+            serde_json::json!("120:117")
+        );
+        assert_eq!(
+            serde_json::from_value::<AccountTxsCheckpoint>(serde_json::json!("120:117")).unwrap(),
+            checkpoint
+        );
+
+        for invalid in ["", "120", "120:", "x:117", "120:117:1", "117:120"] {
+            assert!(
+                invalid.parse::<AccountTxsCheckpoint>().is_err(),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+}
+
+/// Balances the account is expected to have once its pending operations are executed.
+///
+/// The values are estimates: pending transactions may fail or never be executed,
+/// and deposits awaiting confirmations may be reverted on Ethereum.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingBalances {
+    /// Always `true`, the field exists to make the nature of the values explicit to clients.
+    pub is_estimate: bool,
+    pub balances: BTreeMap<String, PendingBalance>,
+}
+
+/// Contributions to the estimated balance of a single token.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingBalance {
+    /// Balance in the last committed state.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub committed: BigUint,
+    /// Sum of the pending transfers to the account.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub incoming_transfers: BigUint,
+    /// Sum of the pending deposits to the account.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub incoming_deposits: BigUint,
+    /// Amounts and fees of the pending transactions taking funds from the account.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub outgoing: BigUint,
+    /// `committed + incoming_transfers + incoming_deposits - outgoing`, but not less than zero.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub estimated: BigUint,
+}
+
+/// Nonce of the account along with the nonce suggested for its next transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountNonce {
+    /// `false` if the account doesn't exist yet, the nonce is zero in this case.
+    pub exists: bool,
+    /// Nonce of the account in the last committed state.
+    pub committed_nonce: Nonce,
+    /// Number of the transactions of the account waiting in the mempool.
+    pub pending_txs: u32,
+    /// Committed nonce increased by the number of the pending transactions that follow it
+    /// without gaps. The suggestion is advisory: the concurrently submitted transactions
+    /// may take the same nonce, and the pending transactions may fail.
+    pub suggested_nonce: Nonce,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountChecksumQuery {
+    /// Block the state of the account is taken at, the last committed block by default.
+    pub block: Option<BlockNumber>,
+}
+
+/// Checksum of the account state used to cross-check the API replicas: the replicas serving
+/// the same state return the same checksum.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountChecksum {
+    pub account_id: AccountId,
+    pub block_number: BlockNumber,
+    /// `keccak256` of the address, public key hash, nonce and non-zero balances of the account.
+    pub checksum: H256,
+}
+
+/// Exits of all the funds of the account from the last finalized state, used in the exodus mode.
+/// Every exit is a separate `performExodus` call of the contract.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountExitPlan {
+    pub account_id: AccountId,
+    pub address: Address,
+    /// Last finalized block. The amounts are taken from its state and the exit proofs
+    /// are made against it.
+    pub block_number: BlockNumber,
+    pub exits: Vec<TokenExit>,
+    /// Gas of all the exits and the withdrawals of the exited funds.
+    pub total_estimated_gas: u64,
+}
+
+/// Parameters of the `performExodus` call for a single token, except for the stored block
+/// info and the exit proof.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenExit {
+    pub token_id: TokenId,
+    /// `None` if the token is unknown to the server, the exit is possible anyway.
+    pub token_symbol: Option<String>,
+    /// Address the exited funds are withdrawn by, `None` if the token is unknown to the server.
+    pub token_address: Option<Address>,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    /// Set for the NFTs only, their exits require the parameters of the token.
+    pub nft: Option<NftExitParams>,
+    /// Whether the exit proof can be produced from the stored state right now.
+    pub proof_ready: bool,
+    /// Gas of the exit and the withdrawal of the exited funds.
+    pub estimated_gas: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NftExitParams {
+    pub creator_account_id: AccountId,
+    pub creator_address: Address,
+    pub serial_id: u32,
+    pub content_hash: H256,
 }
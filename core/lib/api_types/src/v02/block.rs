@@ -1,8 +1,11 @@
+use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use zksync_crypto::{serialization::FrSerde, Fr};
 use zksync_types::{BlockNumber, H256};
 
+use super::transaction::Transaction;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "camelCase")]
 pub enum BlockStatus {
@@ -23,3 +26,159 @@ pub struct BlockInfo {
     pub finalized_at: Option<DateTime<Utc>>,
     pub status: BlockStatus,
 }
+
+/// Maximum number of blocks that can be requested in a single `blocks/byNumbers` call.
+pub const MAX_BLOCKS_BY_NUMBERS_LIMIT: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlocksByNumbersRequest {
+    pub block_numbers: Vec<BlockNumber>,
+}
+
+/// Block information along with the number of L1 and L2 transactions in the block.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHeader {
+    #[serde(flatten)]
+    pub info: BlockInfo,
+    pub tx_count: u32,
+}
+
+/// Range of blocks to export, both bounds are inclusive.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct BlocksExportQuery {
+    pub from: BlockNumber,
+    pub to: BlockNumber,
+}
+
+/// Block along with all of its transactions.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedBlock {
+    pub block: BlockInfo,
+    pub transactions: Vec<Transaction>,
+}
+
+/// The last line of the blocks export.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlocksExportTrailer {
+    /// The last exported block, `None` if there are no blocks in the requested range.
+    /// The export can be continued from the next block.
+    pub last_block: Option<BlockNumber>,
+}
+
+/// Line of the blocks export, which is a stream of newline-delimited JSON objects:
+/// blocks in the ascending order followed by the trailer.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlocksExportLine {
+    Block(Box<ExportedBlock>),
+    Trailer(BlocksExportTrailer),
+}
+
+/// Result of the recomputation of the block state root.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StateRootCheck {
+    pub block_number: BlockNumber,
+    /// Block of the account tree snapshot the stored account updates were applied to.
+    pub snapshot_block: BlockNumber,
+    /// Number of accounts updated since the snapshot.
+    pub updated_accounts: u32,
+    #[serde(with = "FrSerde")]
+    pub stored_root: Fr,
+    #[serde(with = "FrSerde")]
+    pub recomputed_root: Fr,
+    pub matches: bool,
+    /// Time spent loading the snapshot and the updates from the database.
+    pub load_time_ms: u64,
+    /// Time spent recomputing the root.
+    pub compute_time_ms: u64,
+}
+
+/// Checksum of the block used to cross-check the API replicas: the replicas serving the same
+/// block return the same checksum.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockChecksum {
+    pub block_number: BlockNumber,
+    /// `keccak256` of the block number, the state root and the hashes of the block operations.
+    pub checksum: H256,
+}
+
+/// Gas spent on the L1 operation that processed the block.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockL1Cost {
+    pub action_type: String,
+    /// Range of blocks processed by the operation.
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    /// Gas used by the whole operation, `None` if it wasn't recorded.
+    pub gas_used: Option<u64>,
+    /// Share of the gas attributed to the block, the gas is split evenly between
+    /// the blocks of the range.
+    pub block_gas: Option<u64>,
+    pub block_cost_eth: Option<BigDecimal>,
+}
+
+/// Fees paid by the successful transactions of the same type.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TxTypeFees {
+    pub tx_type: String,
+    pub count: u32,
+    /// Average fee converted to ETH, `None` if none of the transactions could be priced.
+    pub average_fee_eth: Option<BigDecimal>,
+}
+
+/// Estimated L1 cost of the block compared to the fees collected in it.
+///
+/// All the values are estimates: the gas of the operations processing several blocks is prorated,
+/// and the fees paid in tokens are converted to ETH at the last known ticker prices.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCostBreakdown {
+    pub block_number: BlockNumber,
+    pub l1_operations: Vec<BlockL1Cost>,
+    /// Gas attributed to the block by the operations with the recorded gas.
+    pub estimated_l1_gas: u64,
+    pub estimated_l1_cost_eth: BigDecimal,
+    pub estimated_fees_eth: BigDecimal,
+    /// Number of transactions whose fees couldn't be converted to ETH.
+    pub unpriced_txs: u32,
+    pub tx_types: Vec<TxTypeFees>,
+}
+
+/// Stage of the block on its way to the finality.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockFinalityStatus {
+    /// The block is sealed by the server, but its commitment is not confirmed on L1 yet.
+    Pending,
+    Committed,
+    Finalized,
+}
+
+/// Finality of the block and the policy it's assessed by.
+///
+/// The block is `safeToCredit` once its commitment is confirmed, i.e. its status is either
+/// `committed` or `finalized`. The server confirms the commit transaction only after it has
+/// `requiredConfirmations` L1 confirmations, so the commitment can't be dropped by an L1 reorg.
+/// The operator may still revert the `revertible` committed blocks in emergencies, clients that
+/// can't accept this risk should wait for the `finalized` status.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockFinality {
+    pub block_number: BlockNumber,
+    pub status: BlockFinalityStatus,
+    /// Whether the block can still be reverted, i.e. it's not finalized yet.
+    pub revertible: bool,
+    /// Number of L1 blocks mined on top of the block of the commit transaction, `None` if
+    /// the commitment is not confirmed yet or the block of its transaction is unknown.
+    pub commit_confirmations: Option<u64>,
+    /// Number of L1 confirmations the commit transactions are required to have.
+    pub required_confirmations: u64,
+    pub safe_to_credit: bool,
+}
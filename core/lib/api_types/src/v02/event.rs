@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zksync_types::{event::EventId, BlockNumber};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiEventType {
+    Account,
+    Block,
+    Transaction,
+}
+
+/// Query for replaying the events which happened in the zkSync network.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct EventsQuery {
+    /// Only events with the id greater than this one are returned.
+    pub from_id: Option<EventId>,
+    pub limit: u32,
+    #[serde(rename = "type")]
+    pub event_type: Option<ApiEventType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiEvent {
+    pub id: EventId,
+    pub block_number: BlockNumber,
+    #[serde(rename = "type")]
+    pub event_type: ApiEventType,
+    pub data: Value,
+    pub created_at: DateTime<Utc>,
+}
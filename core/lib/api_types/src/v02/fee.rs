@@ -1,7 +1,15 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
 use num::BigUint;
 use serde::{Deserialize, Serialize};
-use zksync_types::{tokens::ChangePubKeyFeeTypeArg, Address, BatchFee, Fee, TokenLike, TxFeeTypes};
-use zksync_utils::BigUintSerdeAsRadix10Str;
+use zksync_types::{
+    event::transaction::TransactionType, tokens::ChangePubKeyFeeTypeArg, tx::PackedEthSignature,
+    Address, BatchFee, Fee, TokenId, TokenLike, TxFeeTypes,
+};
+use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
+
+/// Maximum number of buckets that can be requested from the fee statistics.
+pub const MAX_FEE_STATISTICS_BUCKETS: i64 = 1000;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +20,9 @@ pub struct ApiFee {
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     pub total_fee: BigUint,
+    /// Inputs of the fee calculation signed by the operator.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<SignedFeeQuote>,
 }
 
 impl From<Fee> for ApiFee {
@@ -20,6 +31,7 @@ impl From<Fee> for ApiFee {
             gas_fee: fee.gas_fee,
             zkp_fee: fee.zkp_fee,
             total_fee: fee.total_fee,
+            quote: None,
         }
     }
 }
@@ -30,11 +42,88 @@ impl From<BatchFee> for ApiFee {
             gas_fee: fee.gas_fee,
             zkp_fee: fee.zkp_fee,
             total_fee: fee.total_fee,
+            quote: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Inputs and outputs of the fee calculation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeQuote {
+    /// Transactions the fee is quoted for.
+    pub transactions: Vec<TxInBatchFeeRequest>,
+    /// Token the fee is paid in.
+    pub token_id: TokenId,
+    /// Price of one token in USD.
+    pub token_price_usd: BigDecimal,
+    /// Price of one ETH in USD.
+    pub eth_price_usd: BigDecimal,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_price_wei: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub gas_fee: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub zkp_fee: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub total_fee: BigUint,
+    /// Unix timestamp (in seconds) of the quote creation.
+    pub timestamp: u64,
+    /// Unix timestamp (in seconds) until which the quoted fee is accepted.
+    pub valid_until: u64,
+}
+
+impl FeeQuote {
+    /// Returns the canonical encoding of the quote, which is signed by the operator.
+    pub fn message(&self) -> String {
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|tx| {
+                format!(
+                    "{} to {:?}",
+                    serde_json::to_string(&tx.tx_type).unwrap(),
+                    tx.address
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "zkSync fee quote\n\
+            Transactions: {}\n\
+            Token: {}\n\
+            Token price (USD): {}\n\
+            ETH price (USD): {}\n\
+            Gas price (wei): {}\n\
+            Gas fee: {}\n\
+            ZKP fee: {}\n\
+            Total fee: {}\n\
+            Timestamp: {}\n\
+            Valid until: {}",
+            transactions,
+            self.token_id,
+            self.token_price_usd,
+            self.eth_price_usd,
+            self.gas_price_wei,
+            self.gas_fee,
+            self.zkp_fee,
+            self.total_fee,
+            self.timestamp,
+            self.valid_until,
+        )
+    }
+}
+
+/// Fee quote along with the operator signature of its canonical encoding.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedFeeQuote {
+    pub quote: FeeQuote,
+    pub signature: PackedEthSignature,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum ApiTxFeeTypes {
     /// Fee for the `Withdraw` transaction.
     Withdraw,
@@ -71,6 +160,65 @@ impl From<ApiTxFeeTypes> for TxFeeTypes {
     }
 }
 
+impl ApiTxFeeTypes {
+    /// Returns the type of the transactions the fee is paid for.
+    pub fn transaction_type(&self) -> TransactionType {
+        match self {
+            Self::Withdraw | Self::FastWithdraw => TransactionType::Withdraw,
+            Self::Transfer => TransactionType::Transfer,
+            Self::ChangePubKey(_) => TransactionType::ChangePubKey,
+            Self::ForcedExit => TransactionType::ForcedExit,
+            Self::MintNFT => TransactionType::MintNFT,
+            Self::WithdrawNFT | Self::FastWithdrawNFT => TransactionType::WithdrawNFT,
+            Self::Swap => TransactionType::Swap,
+        }
+    }
+}
+
+/// Liquidity the token must have to be used for paying fees.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeTokenRequirement {
+    /// Market volume of the token is at least `FEE_TICKER_LIQUIDITY_VOLUME`.
+    Liquid,
+    /// Market volume of the token is at least `FEE_TICKER_STRICT_LIQUIDITY_VOLUME`.
+    HighlyLiquid,
+}
+
+impl FeeTokenRequirement {
+    /// Returns the requirement to the fee token of the transaction type.
+    ///
+    /// Fees of the transactions executed on L1 are swapped by the operator to pay for the gas,
+    /// so they are accepted only in the highly liquid tokens.
+    pub fn for_tx_type(tx_type: TransactionType) -> Self {
+        match tx_type {
+            TransactionType::Withdraw
+            | TransactionType::WithdrawNFT
+            | TransactionType::ForcedExit
+            | TransactionType::ChangePubKey => Self::HighlyLiquid,
+            TransactionType::Transfer
+            | TransactionType::MintNFT
+            | TransactionType::Swap
+            | TransactionType::FullExit
+            | TransactionType::Deposit => Self::Liquid,
+        }
+    }
+
+    /// Returns the strictest requirement of the transaction types, `None` if there are none.
+    pub fn for_tx_types(tx_types: impl IntoIterator<Item = TransactionType>) -> Option<Self> {
+        tx_types.into_iter().map(Self::for_tx_type).max()
+    }
+}
+
+/// Tokens the fees of the transactions of a single type can be paid in.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowedFeeTokens {
+    pub tx_type: TransactionType,
+    pub requirement: FeeTokenRequirement,
+    pub tokens: Vec<TokenId>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TxFeeRequest {
@@ -79,7 +227,7 @@ pub struct TxFeeRequest {
     pub token_like: TokenLike,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TxInBatchFeeRequest {
     pub tx_type: ApiTxFeeTypes,
@@ -92,3 +240,72 @@ pub struct BatchFeeRequest {
     pub transactions: Vec<TxInBatchFeeRequest>,
     pub token_like: TokenLike,
 }
+
+/// Size of the time buckets of the fee statistics.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum FeeStatisticsResolution {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl FeeStatisticsResolution {
+    /// Returns the precision name accepted by the Postgres `date_trunc`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    /// Returns the longest possible duration of a single bucket.
+    pub fn max_duration(&self) -> Duration {
+        match self {
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::weeks(1),
+            Self::Month => Duration::days(31),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeStatisticsQuery {
+    #[serde(rename = "type")]
+    pub tx_type: TransactionType,
+    pub token: String,
+    pub resolution: FeeStatisticsResolution,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+/// Fees paid by the successful transactions of the requested type in the requested token.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeStatistics {
+    pub tx_type: TransactionType,
+    pub token_id: TokenId,
+    pub resolution: FeeStatisticsResolution,
+    pub buckets: Vec<FeeStatisticsBucket>,
+}
+
+/// Fees paid during a single time bucket.
+/// All the statistics are `null` if there were no transactions in the bucket.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeStatisticsBucket {
+    /// Start of the bucket.
+    pub from: DateTime<Utc>,
+    /// Number of transactions in the bucket.
+    pub count: u64,
+    /// Average fee, rounded to the closest integer.
+    pub average: Option<BigUintSerdeWrapper>,
+    pub median: Option<BigUintSerdeWrapper>,
+    /// Fee not exceeded by 90% of the transactions.
+    pub percentile_90: Option<BigUintSerdeWrapper>,
+}
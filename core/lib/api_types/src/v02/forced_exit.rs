@@ -0,0 +1,21 @@
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use zksync_types::Address;
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+/// Parameters of the forced exit requests.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ForcedExitParams {
+    /// Whether the forced exit requests are enabled in the server config.
+    pub enabled: bool,
+    /// Whether the processing of the requests is paused by the operator. The requests paid
+    /// during the pause are processed once the processing is resumed.
+    pub paused: bool,
+    /// Price of the request per token in wei.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub price_per_token: BigUint,
+    /// Address of the contract the requests are paid to.
+    pub payment_address: Address,
+    pub max_tokens_per_request: u8,
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use zksync_types::{BlockNumber, H256};
+
+/// Type of the aggregated operation sent to L1.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum L1OperationType {
+    Commit,
+    Prove,
+    Execute,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum L1OperationStatus {
+    /// Operation is not confirmed on L1 yet.
+    Pending,
+    /// Operation is confirmed by the transaction with the requested hash.
+    Confirmed,
+    /// Operation is confirmed by another transaction, which replaced the requested one.
+    Replaced,
+}
+
+/// Rollup block covered by the aggregated operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct L1OperationBlock {
+    pub block_number: BlockNumber,
+    pub block_size: u64,
+    pub transactions_count: u64,
+    pub priority_operations_count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct L1OperationInfo {
+    pub aggregated_op_id: u64,
+    pub op_type: L1OperationType,
+    pub eth_tx_hash: H256,
+    /// Hash of the transaction that confirmed the operation.
+    pub final_tx_hash: Option<H256>,
+    pub status: L1OperationStatus,
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    /// Gas limit estimated for the operation.
+    pub gas_estimated: u64,
+    /// Gas actually used, known only for the confirmed operations.
+    pub gas_used: Option<u64>,
+    pub created_at: DateTime<Utc>,
+    pub blocks: Vec<L1OperationBlock>,
+}
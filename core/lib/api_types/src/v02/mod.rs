@@ -4,13 +4,20 @@ use serde_json::Value;
 use std::collections::HashMap;
 use zksync_types::network::Network;
 
+pub mod access_token;
 pub mod account;
 pub mod block;
+pub mod event;
 pub mod fee;
+pub mod forced_exit;
+pub mod l1_operation;
 pub mod pagination;
+pub mod search;
 pub mod status;
+pub mod sub_version;
 pub mod token;
 pub mod transaction;
+pub mod webhook;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -49,3 +56,33 @@ pub struct Response {
     pub error: Option<Value>,
     pub result: Option<Value>,
 }
+
+/// Header selecting the compatibility mode of the responses, used by the old clients during migrations.
+pub const COMPAT_MODE_HEADER: &str = "x-zksync-api-compat";
+/// Compatibility mode reporting the resources requested by their identifiers and not found
+/// as the successful `null` results instead of the `NotFound` errors.
+pub const NULL_NOT_FOUND_COMPAT_MODE: &str = "null-not-found";
+
+/// Format of the token amounts in the responses.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AmountsFormat {
+    /// Integer strings in the token base units.
+    Units,
+    /// Decimal strings adjusted by the token decimals, e.g. `1.5` instead of `1500000`
+    /// for a token with 6 decimals.
+    Decimal,
+}
+
+impl Default for AmountsFormat {
+    fn default() -> Self {
+        Self::Units
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct AmountsQuery {
+    #[serde(default)]
+    pub amounts_in: AmountsFormat,
+}
@@ -138,4 +138,6 @@ pub struct AccountTxsRequest {
     pub tx_hash: ApiEither<TxHash>,
     pub token: Option<TokenId>,
     pub second_address: Option<Address>,
+    /// Only the transactions sent to or received from this address are returned.
+    pub counterparty: Option<Address>,
 }
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenId, H256};
+use zksync_utils::ZeroPrefixHexSerde;
+
+use super::{block::BlockStatus, l1_operation::L1OperationType, transaction::TxInBlockStatus};
+
+/// Query of the `search` endpoint.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    /// Identifier of any kind, e.g. a transaction hash, an address or a token symbol.
+    pub query: String,
+}
+
+/// Entities the search query resolves to, the list has more than one item if the query
+/// is ambiguous, e.g. the number is both a block number and an account id.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SearchResult {
+    Transaction(TxSearchResult),
+    Batch(BatchSearchResult),
+    L1Operation(L1OperationSearchResult),
+    Account(AccountSearchResult),
+    Block(BlockSearchResult),
+    Token(TokenSearchResult),
+}
+
+/// L2 transaction or priority operation found by its zkSync or Ethereum hash.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSearchResult {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub id: TxHash,
+    /// Serial id of the priority operation, `None` for the L2 transactions.
+    pub priority_op_id: Option<SerialId>,
+    pub status: TxInBlockStatus,
+    pub block_number: Option<BlockNumber>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSearchResult {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub id: TxHash,
+    pub status: TxInBlockStatus,
+    pub transactions_count: u64,
+}
+
+/// Ethereum transaction sent by the operator for the aggregated operation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct L1OperationSearchResult {
+    pub id: H256,
+    pub op_type: L1OperationType,
+    pub confirmed: bool,
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSearchResult {
+    pub id: AccountId,
+    pub address: Address,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockSearchResult {
+    pub id: BlockNumber,
+    pub status: BlockStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenSearchResult {
+    pub id: TokenId,
+    pub symbol: String,
+    pub address: Address,
+}
@@ -1,4 +1,4 @@
-use crate::CoreStatus;
+use crate::{CoreStatus, EthWatchStatus};
 use serde::{Deserialize, Serialize};
 use zksync_types::BlockNumber;
 
@@ -10,4 +10,47 @@ pub struct NetworkStatus {
     pub total_transactions: u32,
     pub mempool_size: u32,
     pub core_status: Option<CoreStatus>,
+    pub eth_watch_status: Option<EthWatchStatus>,
+    /// Load of the signature verifier of the server that handled the request.
+    #[serde(default)]
+    pub sign_verifier_status: Option<SignVerifierStatus>,
+    /// Storage connection pools of the server that handled the request.
+    #[serde(default)]
+    pub storage_pools: Vec<StoragePoolStatus>,
+}
+
+/// Load of the signature verifier. New submissions are rejected while the breaker is open.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct SignVerifierStatus {
+    pub breaker_open: bool,
+    /// Number of the signature verification requests in flight.
+    pub queue_depth: u64,
+    /// Average latency of the recent requests, `None` if there were no requests since the recovery.
+    pub average_latency_ms: Option<u64>,
+}
+
+/// State of the storage connection pool.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StoragePoolStatus {
+    pub name: String,
+    pub max_size: u64,
+    /// Number of the open connections, both idle and in use.
+    pub size: u64,
+    pub idle: u64,
+    /// Number of the failed connection attempts and broken connections since the start.
+    pub errors: u64,
+    /// Number of times the pool was recreated after the connections were found broken.
+    pub recreations: u64,
+}
+
+/// Health of the API server reported by the liveness and readiness probes.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiHealth {
+    /// The server is running and responds to requests.
+    pub alive: bool,
+    /// The server has completed the warm-up and is ready to serve requests.
+    pub ready: bool,
 }
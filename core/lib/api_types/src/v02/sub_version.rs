@@ -0,0 +1,308 @@
+//! Sub-versions of the API v0.2 response shapes.
+//!
+//! Strict clients may break on the enum variants and the fields they don't know, so they can
+//! request the sub-version they were built against with the `X-Api-SubVersion` header. The newer
+//! values are then mapped to the closest ones known to that sub-version, the mapping of every
+//! type is kept in its `SubVersioned` implementation below.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::transaction::{
+    L1Receipt, L2Receipt, L2Transaction, Receipt, Transaction, TransactionData, TxData,
+    TxInBlockStatus,
+};
+
+/// Header with the requested sub-version, the negotiated one is reported in the response header
+/// of the same name.
+pub const SUB_VERSION_HEADER: &str = "x-api-subversion";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiSubVersion {
+    /// Shapes of the initial v0.2 release.
+    V1 = 1,
+    /// Adds `confirmationsRemaining`, `createdAt` and `archived` to the receipts, `blockIndex`
+    /// to the transactions, `l2Signature` and `archived` to the transaction data and
+    /// `decodedOrders` to the swaps.
+    V2 = 2,
+}
+
+impl ApiSubVersion {
+    pub const LATEST: Self = Self::V2;
+
+    /// Negotiates the sub-version requested by the client: sub-versions newer than the latest
+    /// one are served as the latest, missing and malformed requests get the latest sub-version too.
+    pub fn negotiate(requested: Option<&str>) -> Self {
+        match requested.and_then(|value| value.trim().parse::<u32>().ok()) {
+            Some(1) => Self::V1,
+            _ => Self::LATEST,
+        }
+    }
+}
+
+impl Default for ApiSubVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl fmt::Display for ApiSubVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", *self as u32)
+    }
+}
+
+/// Response types with the shape depending on the sub-version.
+pub trait SubVersioned: Serialize {
+    /// Serializes the value in the shape known to the clients of the sub-version.
+    fn to_sub_version(&self, sub_version: ApiSubVersion) -> Value;
+}
+
+impl TxInBlockStatus {
+    /// Returns the closest status known to the sub-version. Statuses added in the later
+    /// sub-versions have to be downgraded here.
+    pub fn for_sub_version(self, _sub_version: ApiSubVersion) -> Self {
+        match self {
+            Self::Queued | Self::Committed | Self::Finalized | Self::Rejected => self,
+        }
+    }
+}
+
+impl SubVersioned for L1Receipt {
+    fn to_sub_version(&self, sub_version: ApiSubVersion) -> Value {
+        let mut value = serialize(self);
+        value["status"] = serialize(&self.status.for_sub_version(sub_version));
+        if sub_version < ApiSubVersion::V2 {
+            remove_fields(
+                &mut value,
+                &["confirmationsRemaining", "createdAt", "archived"],
+            );
+        }
+        value
+    }
+}
+
+impl SubVersioned for L2Receipt {
+    fn to_sub_version(&self, sub_version: ApiSubVersion) -> Value {
+        let mut value = serialize(self);
+        value["status"] = serialize(&self.status.for_sub_version(sub_version));
+        if sub_version < ApiSubVersion::V2 {
+            remove_fields(&mut value, &["archived"]);
+        }
+        value
+    }
+}
+
+impl SubVersioned for Receipt {
+    fn to_sub_version(&self, sub_version: ApiSubVersion) -> Value {
+        match self {
+            Receipt::L1(receipt) => receipt.to_sub_version(sub_version),
+            Receipt::L2(receipt) => receipt.to_sub_version(sub_version),
+        }
+    }
+}
+
+impl SubVersioned for TransactionData {
+    fn to_sub_version(&self, sub_version: ApiSubVersion) -> Value {
+        let mut value = serialize(self);
+        if sub_version < ApiSubVersion::V2 {
+            if let TransactionData::L2(L2Transaction::Swap(_)) = self {
+                remove_fields(&mut value, &["decodedOrders"]);
+            }
+        }
+        value
+    }
+}
+
+impl SubVersioned for Transaction {
+    fn to_sub_version(&self, sub_version: ApiSubVersion) -> Value {
+        let mut value = serialize(self);
+        value["op"] = self.op.to_sub_version(sub_version);
+        value["status"] = serialize(&self.status.for_sub_version(sub_version));
+        if sub_version < ApiSubVersion::V2 {
+            remove_fields(&mut value, &["blockIndex"]);
+        }
+        value
+    }
+}
+
+impl SubVersioned for TxData {
+    fn to_sub_version(&self, sub_version: ApiSubVersion) -> Value {
+        let mut value = serialize(self);
+        value["tx"] = self.tx.to_sub_version(sub_version);
+        if sub_version < ApiSubVersion::V2 {
+            remove_fields(&mut value, &["l2Signature", "archived"]);
+        }
+        value
+    }
+}
+
+fn serialize<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("API types are serializable")
+}
+
+fn remove_fields(value: &mut Value, fields: &[&str]) {
+    if let Value::Object(object) = value {
+        for field in fields {
+            object.remove(*field);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use num::BigUint;
+    use serde_json::json;
+    use zksync_types::{
+        tx::{Order, Swap, TimeRange, TxHash},
+        AccountId, Address, BlockNumber, EthBlockId, Nonce, TokenId, H256,
+    };
+
+    use crate::v02::transaction::{ApiDeposit, L1Transaction, L2Signature, SwapData};
+
+    fn order(account_id: u32, token_sell: u32, token_buy: u32) -> Order {
+        Order {
+            account_id: AccountId(account_id),
+            recipient_address: Address::repeat_byte(account_id as u8),
+            nonce: Nonce(0),
+            token_buy: TokenId(token_buy),
+            token_sell: TokenId(token_sell),
+            price: (BigUint::from(1u32), BigUint::from(2u32)),
+            amount: BigUint::from(100u32),
+            time_range: TimeRange::default(),
+            signature: Default::default(),
+        }
+    }
+
+    fn swap_tx_data() -> TxData {
+        let swap = Swap::new(
+            AccountId(3),
+            Address::repeat_byte(3),
+            Nonce(0),
+            (order(1, 0, 1), order(2, 1, 0)),
+            (BigUint::from(100u32), BigUint::from(200u32)),
+            BigUint::from(10u32),
+            TokenId(0),
+            None,
+        );
+        TxData {
+            tx: Transaction {
+                tx_hash: TxHash::from_slice(&[1; 32]).unwrap(),
+                block_index: Some(2),
+                block_number: Some(BlockNumber(1)),
+                op: TransactionData::L2(L2Transaction::Swap(Box::new(SwapData::from(swap)))),
+                status: TxInBlockStatus::Finalized,
+                fail_reason: None,
+                created_at: Some(Utc::now()),
+                batch_id: None,
+            },
+            eth_signature: None,
+            l2_signature: Some(L2Signature {
+                pub_key: "00".to_owned(),
+                signature: "00".to_owned(),
+                message: "00".to_owned(),
+            }),
+            archived: true,
+        }
+    }
+
+    #[test]
+    fn sub_version_negotiation() {
+        assert_eq!(ApiSubVersion::negotiate(None), ApiSubVersion::LATEST);
+        assert_eq!(ApiSubVersion::negotiate(Some("1")), ApiSubVersion::V1);
+        assert_eq!(ApiSubVersion::negotiate(Some(" 2 ")), ApiSubVersion::V2);
+        assert_eq!(ApiSubVersion::negotiate(Some("42")), ApiSubVersion::LATEST);
+        assert_eq!(ApiSubVersion::negotiate(Some("0")), ApiSubVersion::LATEST);
+        assert_eq!(ApiSubVersion::negotiate(Some("v1")), ApiSubVersion::LATEST);
+        assert_eq!(ApiSubVersion::V1.to_string(), "1");
+        assert_eq!(ApiSubVersion::LATEST.to_string(), "2");
+    }
+
+    #[test]
+    fn receipt_sub_versions() {
+        let l1_receipt = Receipt::L1(L1Receipt {
+            status: TxInBlockStatus::Queued,
+            eth_block: EthBlockId(5),
+            rollup_block: None,
+            id: 7,
+            confirmations_remaining: Some(3),
+            created_at: Some(Utc::now()),
+            archived: true,
+        });
+        let l2_receipt = Receipt::L2(L2Receipt {
+            tx_hash: TxHash::from_slice(&[1; 32]).unwrap(),
+            rollup_block: Some(BlockNumber(1)),
+            status: TxInBlockStatus::Rejected,
+            fail_reason: Some("Nonce mismatch".to_owned()),
+            archived: true,
+        });
+
+        // The latest sub-version is the plain serialization.
+        for receipt in [&l1_receipt, &l2_receipt] {
+            assert_eq!(
+                receipt.to_sub_version(ApiSubVersion::LATEST),
+                serde_json::to_value(receipt).unwrap()
+            );
+        }
+
+        assert_eq!(
+            l1_receipt.to_sub_version(ApiSubVersion::V1),
+            json!({
+                "status": "queued",
+                "ethBlock": 5,
+                "rollupBlock": null,
+                "id": 7,
+            })
+        );
+        assert_eq!(
+            l2_receipt.to_sub_version(ApiSubVersion::V1),
+            json!({
+                "txHash": format!("0x{}", "01".repeat(32)),
+                "rollupBlock": 1,
+                "status": "rejected",
+                "failReason": "Nonce mismatch",
+            })
+        );
+    }
+
+    #[test]
+    fn transaction_data_sub_versions() {
+        let swap = swap_tx_data();
+        let latest = swap.to_sub_version(ApiSubVersion::LATEST);
+        assert_eq!(latest, serde_json::to_value(&swap).unwrap());
+
+        let legacy = swap.to_sub_version(ApiSubVersion::V1);
+        let mut expected = latest;
+        let expected_object = expected.as_object_mut().unwrap();
+        expected_object.remove("l2Signature");
+        expected_object.remove("archived");
+        let tx = expected_object["tx"].as_object_mut().unwrap();
+        tx.remove("blockIndex");
+        tx["op"].as_object_mut().unwrap().remove("decodedOrders");
+        assert_eq!(legacy, expected);
+        assert_eq!(legacy["tx"]["op"]["type"], "Swap");
+        assert_eq!(legacy["tx"]["status"], "finalized");
+
+        // Operations without the newer fields are not changed apart from the transaction fields.
+        let deposit = TransactionData::L1(L1Transaction::Deposit(ApiDeposit {
+            from: Address::repeat_byte(1),
+            token_id: TokenId(0),
+            amount: BigUint::from(1u32),
+            to: Address::repeat_byte(1),
+            account_id: Some(AccountId(1)),
+            eth_hash: H256::repeat_byte(1),
+            id: 1,
+            tx_hash: TxHash::from_slice(&[1; 32]).unwrap(),
+        }));
+        for sub_version in [ApiSubVersion::V1, ApiSubVersion::V2] {
+            assert_eq!(
+                deposit.to_sub_version(sub_version),
+                serde_json::to_value(&deposit).unwrap()
+            );
+        }
+    }
+}
@@ -1,6 +1,13 @@
+use std::collections::BTreeMap;
+
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, NaiveDate, Utc};
+use num::BigUint;
 use serde::{Deserialize, Serialize};
-use zksync_types::{AccountId, Address, Token, TokenId, H256};
+use zksync_types::{AccountId, Address, BlockNumber, Token, TokenId, H256};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use super::pagination::PaginationDirection;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -10,6 +17,44 @@ pub struct ApiToken {
     pub symbol: String,
     pub decimals: u8,
     pub enabled_for_fees: bool,
+    /// Previous symbols of the renamed token, they are still accepted in the requests.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// Kind of the tokens listed by the `tokens` endpoint.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenKindFilter {
+    Erc20,
+    Nft,
+}
+
+impl Default for TokenKindFilter {
+    fn default() -> Self {
+        Self::Erc20
+    }
+}
+
+/// Query of the `tokens` endpoint.
+///
+/// The pagination parameters may be omitted only if all the matching tokens are requested
+/// with `all=true`, which is kept for backward compatibility and will be removed in the next release.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokensQuery {
+    pub from: Option<String>,
+    pub limit: Option<u32>,
+    pub direction: Option<PaginationDirection>,
+    #[serde(default)]
+    pub kind: TokenKindFilter,
+    /// Only the tokens that can be used to pay fees are listed if set.
+    #[serde(default, alias = "enabled_for_fees")]
+    pub enabled_for_fees: bool,
+    /// Case-insensitive prefix of the token symbol.
+    pub symbol: Option<String>,
+    #[serde(default)]
+    pub all: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -22,6 +67,188 @@ pub struct TokenPrice {
     pub price: BigDecimal,
 }
 
+/// Maximum number of tokens that can be requested in a single `tokens/prices` call.
+pub const MAX_TOKEN_PRICES_LIMIT: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPricesQuery {
+    /// Comma-separated list of token ids, symbols or addresses.
+    pub tokens: String,
+    /// Either `usd`, `eth` or a token id.
+    pub currency: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPriceInfo {
+    pub token_id: TokenId,
+    pub token_symbol: String,
+    pub decimals: u8,
+    pub price: BigDecimal,
+    pub last_updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPrices {
+    pub price_in: String,
+    /// Prices keyed by the token as it was specified in the request.
+    pub prices: BTreeMap<String, TokenPriceInfo>,
+    /// Requested tokens that are unknown or have no price.
+    pub missing: Vec<String>,
+}
+
+/// Stage of the token listing via the governance contract.
+///
+/// Governance transactions that are not confirmed yet are not visible to the server,
+/// so the first stage that can be reported is the confirmed one.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenListingStage {
+    NotStarted,
+    GovernanceTxConfirmed,
+    TokenIdAssigned,
+    PriceAvailable,
+    FeeEnabled,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListingStatus {
+    pub address: Address,
+    pub stage: TokenListingStage,
+    pub token_id: Option<TokenId>,
+    pub symbol: Option<String>,
+    pub eth_block_number: Option<u64>,
+    pub governance_tx_confirmed_at: Option<DateTime<Utc>>,
+    pub token_id_assigned_at: Option<DateTime<Utc>>,
+    pub price_updated_at: Option<DateTime<Utc>>,
+    pub fee_enabled_at: Option<DateTime<Utc>>,
+    /// Error that occurred while the token was being stored, if any.
+    pub store_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenHoldersQuery {
+    /// Number of the largest holders to return, all the tracked ones by default.
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenHolder {
+    /// Position of the holder, starting from 1 for the largest balance.
+    pub rank: u32,
+    pub account_id: AccountId,
+    pub address: Address,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub balance: BigUint,
+}
+
+/// Largest holders of the token, as of the last recalculation.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenHolders {
+    pub token_id: TokenId,
+    pub holders: Vec<TokenHolder>,
+    /// Time of the last recalculation, `None` if the holders weren't calculated yet.
+    pub refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// Maximum number of changes returned by a single `tokens/changes` call.
+pub const MAX_TOKEN_CHANGES_LIMIT: u32 = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenChangesQuery {
+    /// Only the changes with the greater ids are returned, all the changes by default.
+    #[serde(default, alias = "since_id")]
+    pub since_id: u64,
+    /// Number of the changes to return, `MAX_TOKEN_CHANGES_LIMIT` by default.
+    pub limit: Option<u32>,
+}
+
+/// Token metadata field changed by the `TokenChange`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenMetadataField {
+    Symbol,
+    Decimals,
+}
+
+/// Change of the token metadata. Ids of the changes increase in the order the changes were
+/// made, so the clients can poll the new ones by passing the last seen id as `sinceId`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenChange {
+    pub id: u64,
+    pub token_id: TokenId,
+    pub field: TokenMetadataField,
+    pub old_value: String,
+    pub new_value: String,
+    /// Either `tokenHandler` or `admin:{name}` for the changes made by the operators.
+    pub source: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Maximum number of days returned by a single `tokens/{token}/activity` call.
+pub const MAX_TOKEN_ACTIVITY_DAYS: i64 = 366;
+
+/// Size of the buckets of the token activity, only the daily buckets are supported.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenActivityResolution {
+    Day,
+}
+
+impl Default for TokenActivityResolution {
+    fn default() -> Self {
+        Self::Day
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenActivityQuery {
+    /// First day of the range, in UTC.
+    pub from: NaiveDate,
+    /// Last day of the range, inclusive.
+    pub to: NaiveDate,
+    #[serde(default)]
+    pub resolution: TokenActivityResolution,
+}
+
+/// Activity of the token in the finalized blocks.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenActivity {
+    pub token_id: TokenId,
+    pub resolution: TokenActivityResolution,
+    /// Last block taken into account, the activity of the later blocks is not counted yet.
+    pub last_block: BlockNumber,
+    pub buckets: Vec<TokenActivityBucket>,
+}
+
+/// Activity of the token during a single bucket. Volumes are in the base units of the token,
+/// and in USD if the price of the token during the bucket is known.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenActivityBucket {
+    pub day: NaiveDate,
+    pub transfers: u64,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub transfer_volume: BigUint,
+    pub transfer_volume_usd: Option<BigDecimal>,
+    /// Number of the distinct accounts that sent the token.
+    pub unique_senders: u64,
+    pub withdrawals: u64,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub withdrawal_volume: BigUint,
+    pub withdrawal_volume_usd: Option<BigDecimal>,
+}
+
 impl ApiToken {
     pub fn from_token_and_eligibility(token: Token, eligibility: bool) -> Self {
         ApiToken {
@@ -30,8 +257,14 @@ impl ApiToken {
             symbol: token.symbol,
             decimals: token.decimals,
             enabled_for_fees: eligibility,
+            aliases: Vec::new(),
         }
     }
+
+    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
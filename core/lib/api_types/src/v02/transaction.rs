@@ -1,17 +1,17 @@
-use crate::{v02::block::BlockStatus, TxWithSignature};
+use crate::{v02::block::BlockStatus, TxCancellation, TxWithSignature};
 use chrono::serde::ts_milliseconds;
 use chrono::{DateTime, Utc};
-use num::BigUint;
+use num::{BigUint, Zero};
 use serde::{Deserialize, Serialize};
 use zksync_types::{
     tx::{
-        ChangePubKey, Close, EthBatchSignatures, ForcedExit, MintNFT, Swap, Transfer,
-        TxEthSignature, TxHash, Withdraw, WithdrawNFT,
+        preconditions::TxPreconditionFailure, ChangePubKey, Close, EthBatchSignatures, ForcedExit,
+        MintNFT, Order, Swap, Transfer, TxEthSignature, TxHash, Withdraw, WithdrawNFT,
     },
     AccountId, Address, BlockNumber, EthBlockId, PubKeyHash, SerialId, TokenId, ZkSyncOp,
-    ZkSyncPriorityOp, H256,
+    ZkSyncPriorityOp, ZkSyncTx, H256,
 };
-use zksync_utils::{BigUintSerdeAsRadix10Str, ZeroPrefixHexSerde};
+use zksync_utils::{BigUintPairSerdeAsRadix10Str, BigUintSerdeAsRadix10Str, ZeroPrefixHexSerde};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -38,23 +38,128 @@ impl From<BlockStatus> for TxInBlockStatus {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TxData {
     pub tx: Transaction,
     pub eth_signature: Option<String>,
+    /// Only set if requested with `include=l2Signature`, always `null` for priority operations.
+    #[serde(default)]
+    pub l2_signature: Option<L2Signature>,
+    /// Set if the transaction was served from the archive database.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxDataQuery {
+    /// Comma-separated list of the optional fields, only `l2Signature` is supported.
+    pub include: Option<String>,
+}
+
+impl TxDataQuery {
+    pub fn include_l2_signature(&self) -> bool {
+        self.include
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .any(|field| field.trim() == "l2Signature")
+    }
+}
+
+/// Consistency of the transaction lookup.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadConsistency {
+    /// The transaction may be read from a replica, which can lag behind the primary database.
+    Eventual,
+    /// The transaction is read from the primary database, and the core mempool is consulted
+    /// before reporting the status of the transaction as unknown. Intended for the lookups
+    /// right after the submission.
+    Strong,
+}
+
+impl Default for ReadConsistency {
+    fn default() -> Self {
+        Self::Eventual
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsistencyQuery {
+    #[serde(default)]
+    pub consistency: ReadConsistency,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitQuery {
+    /// Comma-separated list of the optional fields, only `receipt` is supported.
+    pub include: Option<String>,
+    /// Whether to report the durations of the batch submission stages, only honored
+    /// for the requests made with an API key allowed to see them.
+    #[serde(default, alias = "debug_timings")]
+    pub debug_timings: bool,
+}
+
+impl SubmitQuery {
+    pub fn include_receipt(&self) -> bool {
+        self.include
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .any(|field| field.trim() == "receipt")
+    }
+}
+
+/// zkSync signature that authorized the L2 transaction along with the signed message.
+/// All the fields are hex-encoded without the `0x` prefix, the same way as in the
+/// transaction signature. The signature is checked against the Rescue hash of the message.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct L2Signature {
+    /// Packed public key of the signer, 32 bytes.
+    pub pub_key: String,
+    /// Packed signature, 64 bytes.
+    pub signature: String,
+    /// Message that was signed.
+    pub message: String,
+}
+
+impl L2Signature {
+    /// Returns `None` if the signature of the transaction is not valid.
+    pub fn from_tx(tx: &ZkSyncTx) -> Option<Self> {
+        let message = tx.signed_message()?;
+        let signature = tx.signature();
+        Some(Self {
+            pub_key: hex::encode(signature.pub_key.serialize_packed().ok()?),
+            signature: hex::encode(signature.signature.serialize_packed().ok()?),
+            message: hex::encode(message),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct L1Receipt {
     pub status: TxInBlockStatus,
     pub eth_block: EthBlockId,
     pub rollup_block: Option<BlockNumber>,
     pub id: SerialId,
+    /// Amount of Ethereum blocks left until the operation is accepted by the
+    /// network. Only set for operations that are not yet processed.
+    pub confirmations_remaining: Option<u64>,
+    /// Time when the operation was first noticed by the server.
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Set if the operation was served from the archive database.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct L2Receipt {
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
@@ -62,20 +167,112 @@ pub struct L2Receipt {
     pub rollup_block: Option<BlockNumber>,
     pub status: TxInBlockStatus,
     pub fail_reason: Option<String>,
+    /// Set if the transaction was served from the archive database.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+}
+
+/// Explanation of why the L2 transaction is not executed.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxDiagnosis {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    /// Status of the transaction, `None` if the transaction is unknown.
+    pub status: Option<TxInBlockStatus>,
+    /// Conditions that currently prevent the pending transaction from being executed.
+    pub failed_conditions: Vec<TxPreconditionFailure>,
+    /// Explanation for the transactions that can't be checked against the current state.
+    pub note: Option<String>,
+}
+
+/// Expected execution of the verified withdrawal on Ethereum, the funds are withdrawn once
+/// the block of the withdrawal is executed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalExecuteEta {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    pub block_number: BlockNumber,
+    /// Set if the block is already executed, the estimate is not provided then.
+    pub execution: Option<WithdrawalExecution>,
+    /// Execute operation the block is expected to be included into: `0` if the operation
+    /// is already sent to Ethereum, `1` for the next operation and so on.
+    pub execute_queue_position: Option<u32>,
+    /// Maximum number of blocks executed by a single operation.
+    pub max_blocks_per_execute: u32,
+    /// Average time between the recent execute operations sent to Ethereum,
+    /// `None` if there are too few of them.
+    pub execute_interval_secs: Option<u64>,
+    /// Range of the expected execution time, `None` if the interval is unknown.
+    pub eta: Option<EtaRange>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct EtaRange {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalExecution {
+    /// Time the execute operation was created at, `None` if it's unknown.
+    pub executed_at: Option<DateTime<Utc>>,
+    /// Ethereum transaction the funds were withdrawn in.
+    pub eth_tx_hash: Option<H256>,
 }
 
+/// Bytes the transaction contributes to the public data of its block, i.e. to the calldata
+/// of the commit operation on Ethereum.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPublicData {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    pub block_number: BlockNumber,
+    pub op_code: u8,
+    pub chunks: usize,
+    /// Number of the chunks preceding the operation in the public data of the block.
+    pub chunk_offset: usize,
+    /// Public data of the operation, `0x`-prefixed hex.
+    pub public_data: String,
+}
+
+impl TxPublicData {
+    pub fn new(
+        tx_hash: TxHash,
+        block_number: BlockNumber,
+        chunk_offset: usize,
+        op: &ZkSyncOp,
+    ) -> Self {
+        let public_data = op.public_data();
+        Self {
+            tx_hash,
+            block_number,
+            op_code: public_data[0],
+            chunks: op.chunks(),
+            chunk_offset,
+            public_data: format!("0x{}", hex::encode(public_data)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum Receipt {
     L1(L1Receipt),
     L2(L2Receipt),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
     pub tx_hash: TxHash,
+    /// Position of the operation in the block, operations of the block are executed in
+    /// this order. `None` for the pending and the failed transactions.
     pub block_index: Option<u32>,
     pub block_number: Option<BlockNumber>,
     pub op: TransactionData,
@@ -85,7 +282,7 @@ pub struct Transaction {
     pub batch_id: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum TransactionData {
     L1(L1Transaction),
@@ -102,7 +299,7 @@ pub enum L2Transaction {
     ChangePubKey(Box<ChangePubKey>),
     ForcedExit(Box<ForcedExitData>),
     MintNFT(Box<MintNFT>),
-    Swap(Box<Swap>),
+    Swap(Box<SwapData>),
     WithdrawNFT(Box<WithdrawNFTData>),
 }
 
@@ -122,6 +319,80 @@ pub struct WithdrawData {
     pub eth_tx_hash: Option<H256>,
 }
 
+/// Decoded details of the order executed by the swap transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapOrderData {
+    /// Account that signed the order.
+    pub account_id: AccountId,
+    pub recipient: Address,
+    pub token_sell: TokenId,
+    pub token_buy: TokenId,
+    #[serde(with = "BigUintPairSerdeAsRadix10Str")]
+    pub ratio: (BigUint, BigUint),
+    /// Amount to sell specified in the order, zero for limit orders.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub amount: BigUint,
+    /// Amount of `token_sell` sold by the swap.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub sold_amount: BigUint,
+    /// Amount of `token_buy` bought by the swap.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub bought_amount: BigUint,
+    /// Whether the whole order amount was swapped. Limit orders are always
+    /// considered to be filled partially.
+    pub is_full_fill: bool,
+}
+
+impl L2Transaction {
+    pub fn to_zksync_tx(&self) -> ZkSyncTx {
+        match self {
+            L2Transaction::Transfer(tx) => ZkSyncTx::Transfer(tx.clone()),
+            L2Transaction::Withdraw(data) => ZkSyncTx::Withdraw(Box::new(data.tx.clone())),
+            L2Transaction::Close(tx) => ZkSyncTx::Close(tx.clone()),
+            L2Transaction::ChangePubKey(tx) => ZkSyncTx::ChangePubKey(tx.clone()),
+            L2Transaction::ForcedExit(data) => ZkSyncTx::ForcedExit(Box::new(data.tx.clone())),
+            L2Transaction::MintNFT(tx) => ZkSyncTx::MintNFT(tx.clone()),
+            L2Transaction::Swap(data) => ZkSyncTx::Swap(Box::new(data.tx.clone())),
+            L2Transaction::WithdrawNFT(data) => ZkSyncTx::WithdrawNFT(Box::new(data.tx.clone())),
+        }
+    }
+}
+
+impl SwapOrderData {
+    fn new(order: &Order, sold_amount: &BigUint, bought_amount: &BigUint) -> Self {
+        Self {
+            account_id: order.account_id,
+            recipient: order.recipient_address,
+            token_sell: order.token_sell,
+            token_buy: order.token_buy,
+            ratio: order.price.clone(),
+            amount: order.amount.clone(),
+            sold_amount: sold_amount.clone(),
+            bought_amount: bought_amount.clone(),
+            is_full_fill: !order.amount.is_zero() && &order.amount == sold_amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapData {
+    #[serde(flatten)]
+    pub tx: Swap,
+    pub decoded_orders: (SwapOrderData, SwapOrderData),
+}
+
+impl From<Swap> for SwapData {
+    fn from(tx: Swap) -> Self {
+        let decoded_orders = (
+            SwapOrderData::new(&tx.orders.0, &tx.amounts.0, &tx.amounts.1),
+            SwapOrderData::new(&tx.orders.1, &tx.amounts.1, &tx.amounts.0),
+        );
+        Self { tx, decoded_orders }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WithdrawNFTData {
@@ -130,7 +401,7 @@ pub struct WithdrawNFTData {
     pub eth_tx_hash: Option<H256>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "type")]
 pub enum L1Transaction {
     Deposit(ApiDeposit),
@@ -194,7 +465,7 @@ impl L1Transaction {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiDeposit {
     pub from: Address,
@@ -209,7 +480,7 @@ pub struct ApiDeposit {
     pub tx_hash: TxHash,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiFullExit {
     pub account_id: AccountId,
@@ -225,12 +496,122 @@ pub struct TxHashSerializeWrapper(
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")] pub TxHash,
 );
 
+/// Response of the transaction submission.
+///
+/// Unless the receipt is requested, it's serialized as the bare transaction hash,
+/// so the clients that don't request it are not affected.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum SubmitTxResponse {
+    TxHash(TxHashSerializeWrapper),
+    #[serde(rename_all = "camelCase")]
+    WithReceipt {
+        #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+        tx_hash: TxHash,
+        /// Initial receipt of the transaction constructed right after the submission.
+        receipt: L2Receipt,
+    },
+}
+
+impl SubmitTxResponse {
+    pub fn tx_hash(&self) -> TxHash {
+        match self {
+            Self::TxHash(tx_hash) => tx_hash.0,
+            Self::WithReceipt { tx_hash, .. } => *tx_hash,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct SubmitBatchResponse {
     pub transaction_hashes: Vec<TxHashSerializeWrapper>,
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
     pub batch_hash: TxHash,
+    /// Initial state of the batch constructed right after the submission,
+    /// only present if requested with `include=receipt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch: Option<ApiTxBatch>,
+    /// Durations of the submission stages, only present if requested with `debugTimings=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timings: Option<SubmitBatchTimings>,
+}
+
+/// Durations of the batch submission stages in microseconds. The stages follow each other,
+/// so their sum is the total duration of the submission up to the rounding.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitBatchTimings {
+    /// Checks of the batch size and of the disabled transaction types.
+    pub parse_us: u64,
+    /// Checks of the tokens, addresses and amounts of the transactions.
+    pub token_checks_us: u64,
+    /// Calculation of the fee required for the batch and its comparison with the provided one.
+    pub fee_aggregation_us: u64,
+    /// Verification of the Ethereum and zkSync signatures.
+    pub signature_verification_us: u64,
+    /// Forwarding of the batch to the mempool, including the transactions quota and subsidies.
+    pub core_forward_us: u64,
+    pub total_us: u64,
+}
+
+/// Batch to compute the hash for, identified either by the hashes of its transactions
+/// or by the transactions themselves.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchHashRequest {
+    #[serde(default)]
+    pub tx_hashes: Vec<TxHash>,
+    #[serde(default)]
+    pub txs: Vec<ZkSyncTx>,
+}
+
+/// Format of the batch hash preimage reported by the `transactions/batches/hash` endpoint.
+pub const BATCH_HASH_PREIMAGE_FORMAT: &str =
+    "sha256(tx_hash_1 || tx_hash_2 || ... || tx_hash_n), where tx_hash_i are the raw 32-byte \
+     hashes of the batch transactions in the order of the batch";
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchHashResponse {
+    pub transaction_hashes: Vec<TxHashSerializeWrapper>,
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub batch_hash: TxHash,
+    /// Data hashed to get the batch hash, `0x`-prefixed hex.
+    pub preimage: String,
+    /// Description of the preimage, see [BATCH_HASH_PREIMAGE_FORMAT].
+    pub preimage_format: String,
+}
+
+/// Request of the messages to be signed with the Ethereum key, either for a single transaction
+/// or for a batch. Signatures of the transactions are not checked.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SignMessageRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx: Option<ZkSyncTx>,
+    #[serde(default)]
+    pub txs: Vec<ZkSyncTx>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignMessageResponse {
+    /// Message to be signed, `None` if the transaction doesn't require the Ethereum signature.
+    pub message: Option<String>,
+    /// Exact bytes to be signed, `0x`-prefixed hex.
+    pub message_bytes: Option<String>,
+}
+
+impl SignMessageResponse {
+    pub fn new(message: Option<Vec<u8>>) -> Self {
+        Self {
+            message_bytes: message
+                .as_ref()
+                .map(|message| format!("0x{}", hex::encode(message))),
+            message: message.map(|message| String::from_utf8_lossy(&message).into_owned()),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -248,6 +629,9 @@ pub struct ApiTxBatch {
 pub struct BatchStatus {
     pub updated_at: DateTime<Utc>,
     pub last_state: TxInBlockStatus,
+    /// Reason of the rejection, set for the batches evicted from the mempool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_reason: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -296,3 +680,218 @@ impl Toggle2FA {
 pub struct Toggle2FAResponse {
     pub success: bool,
 }
+
+/// Request of the transaction sender to cancel the transaction that is not executed yet.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelTxRequest {
+    /// Signature of the [CancelTxRequest::get_ethereum_sign_message] by the transaction sender.
+    pub signature: TxEthSignature,
+}
+
+impl CancelTxRequest {
+    /// Returns the message the transaction sender has to sign to cancel the transaction.
+    pub fn get_ethereum_sign_message(tx_hash: TxHash) -> String {
+        format!(
+            "Cancel zkSync transaction.\n\
+            By signing this message, you are asking the zkSync Server not to execute the transaction.\n\
+            Transaction hash: {}",
+            tx_hash.to_string()
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelTxResponse {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    pub result: TxCancellation,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TxHashSearchQuery {
+    /// Hex prefix of the transaction hash, optionally prefixed with `0x`.
+    pub prefix: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum TxHashMatchSource {
+    /// Executed L2 transaction, either successful or failed.
+    Executed,
+    /// Executed priority operation.
+    PriorityOp,
+    /// Transaction waiting for the execution in the mempool.
+    Mempool,
+}
+
+/// Transaction found by the prefix of its hash, along with the data required to tell it
+/// apart from the other matches.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TxHashMatch {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    pub source: TxHashMatchSource,
+    /// Type of the transaction or the priority operation, e.g. `Transfer` or `Deposit`.
+    pub tx_type: Option<String>,
+    /// `None` for the mempool transactions.
+    pub block_number: Option<BlockNumber>,
+    pub created_at: DateTime<Utc>,
+    /// Address of the account initiating the transaction.
+    pub account: Option<Address>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TxHashSearchResponse {
+    /// Matches ordered by the hash.
+    pub matches: Vec<TxHashMatch>,
+    /// Whether there are more matches than returned, a longer prefix should be used then.
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_crypto::{
+        franklin_crypto::eddsa::PrivateKey,
+        rand::{Rng, SeedableRng, XorShiftRng},
+        Engine,
+    };
+    use zksync_types::{
+        tx::{PackedPublicKey, PackedSignature, TimeRange, TxSignature},
+        Nonce,
+    };
+
+    fn order(account_id: u32, token_sell: u32, token_buy: u32, amount: u64) -> Order {
+        Order {
+            account_id: AccountId(account_id),
+            recipient_address: Address::repeat_byte(account_id as u8),
+            nonce: Nonce(0),
+            token_buy: TokenId(token_buy),
+            token_sell: TokenId(token_sell),
+            price: (BigUint::from(1u32), BigUint::from(2u32)),
+            amount: BigUint::from(amount),
+            time_range: TimeRange::default(),
+            signature: Default::default(),
+        }
+    }
+
+    #[test]
+    fn swap_data_serialization() {
+        // The first order is filled completely, the second one is a limit order.
+        let swap = Swap::new(
+            AccountId(3),
+            Address::repeat_byte(3),
+            Nonce(0),
+            (order(1, 0, 1, 100), order(2, 1, 0, 0)),
+            (BigUint::from(100u32), BigUint::from(200u32)),
+            BigUint::from(10u32),
+            TokenId(0),
+            None,
+        );
+        let tx = L2Transaction::Swap(Box::new(SwapData::from(swap)));
+
+        let json = serde_json::to_value(&tx).unwrap();
+        assert_eq!(json["type"], "Swap");
+        assert_eq!(json["fee"], "10");
+        assert_eq!(json["feeToken"], 0);
+        let orders = &json["decodedOrders"];
+        assert_eq!(orders[0]["accountId"], 1);
+        assert_eq!(orders[0]["ratio"], serde_json::json!(["1", "2"]));
+        assert_eq!(orders[0]["soldAmount"], "100");
+        assert_eq!(orders[0]["boughtAmount"], "200");
+        assert_eq!(orders[0]["isFullFill"], true);
+        assert_eq!(orders[1]["accountId"], 2);
+        assert_eq!(orders[1]["amount"], "0");
+        assert_eq!(orders[1]["soldAmount"], "200");
+        assert_eq!(orders[1]["boughtAmount"], "100");
+        assert_eq!(orders[1]["isFullFill"], false);
+
+        let deserialized: L2Transaction = serde_json::from_value(json).unwrap();
+        match (deserialized, tx) {
+            (L2Transaction::Swap(deserialized), L2Transaction::Swap(expected)) => {
+                assert_eq!(deserialized.decoded_orders, expected.decoded_orders);
+                assert_eq!(deserialized.tx.amounts, expected.tx.amounts);
+            }
+            _ => panic!("swap transaction expected"),
+        }
+    }
+
+    #[test]
+    fn l1_receipt_without_created_at() {
+        // Receipts returned by the older servers don't have the creation time.
+        let json = serde_json::json!({
+            "status": "queued",
+            "ethBlock": 10,
+            "rollupBlock": null,
+            "id": 1,
+            "confirmationsRemaining": 2,
+        });
+        let receipt: Receipt = serde_json::from_value(json).unwrap();
+        match receipt {
+            Receipt::L1(receipt) => {
+                assert_eq!(receipt.id, 1);
+                assert_eq!(receipt.created_at, None);
+            }
+            Receipt::L2(_) => panic!("L1 receipt expected"),
+        }
+    }
+
+    /// Restores the signature from the API representation and checks it against the message.
+    fn verify_l2_signature(signature: &L2Signature) -> Option<PubKeyHash> {
+        let message = hex::decode(&signature.message).unwrap();
+        let signature = TxSignature {
+            pub_key: PackedPublicKey::deserialize_packed(&hex::decode(&signature.pub_key).unwrap())
+                .unwrap(),
+            signature: PackedSignature::deserialize_packed(
+                &hex::decode(&signature.signature).unwrap(),
+            )
+            .unwrap(),
+        };
+        signature
+            .verify_musig(&message)
+            .map(|pub_key| PubKeyHash::from_pubkey(&pub_key))
+    }
+
+    #[test]
+    fn l2_signature_verification() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let private_key: PrivateKey<Engine> = PrivateKey(rng.gen());
+        let pub_key_hash = PubKeyHash::from_privkey(&private_key);
+
+        let transfer = Transfer::new_signed(
+            AccountId(1),
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            TokenId(0),
+            BigUint::from(100u32),
+            BigUint::from(10u32),
+            Nonce(0),
+            TimeRange::default(),
+            &private_key,
+        )
+        .unwrap();
+        let tx = L2Transaction::Transfer(Box::new(transfer.clone())).to_zksync_tx();
+        let signature = L2Signature::from_tx(&tx).unwrap();
+        assert_eq!(signature.message, hex::encode(transfer.get_bytes()));
+        assert_eq!(verify_l2_signature(&signature), Some(pub_key_hash));
+
+        // Legacy transactions are signed without the transaction version.
+        let mut legacy_transfer = transfer.clone();
+        legacy_transfer.signature =
+            TxSignature::sign_musig(&private_key, &legacy_transfer.get_old_bytes());
+        let signature = L2Signature::from_tx(&ZkSyncTx::from(legacy_transfer.clone())).unwrap();
+        assert_eq!(
+            signature.message,
+            hex::encode(legacy_transfer.get_old_bytes())
+        );
+        assert_eq!(verify_l2_signature(&signature), Some(pub_key_hash));
+
+        let mut unsigned_transfer = transfer;
+        unsigned_transfer.signature = TxSignature::default();
+        assert!(L2Signature::from_tx(&ZkSyncTx::from(unsigned_transfer)).is_none());
+    }
+}
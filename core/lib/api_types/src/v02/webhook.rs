@@ -0,0 +1,217 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac, NewMac};
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use zksync_types::{
+    event::{
+        transaction::{TransactionStatus, TransactionType},
+        EventId,
+    },
+    tx::PackedEthSignature,
+    Address, TokenId,
+};
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use super::event::ApiEvent;
+
+/// Prefix of the subscription secrets, which are sent as `Authorization: Bearer <secret>`.
+pub const WEBHOOK_SECRET_PREFIX: &str = "whsec_";
+
+/// Header with the hex-encoded signature of the delivery, see [`webhook_signature`].
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+/// Header with the unix timestamp (in seconds) the delivery was signed at.
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+/// Header with the ID of the delivered event, the same event may be delivered more than once.
+pub const WEBHOOK_EVENT_ID_HEADER: &str = "X-Webhook-Event-Id";
+/// Header telling whether the event is sent again on the subscriber's request (`true`) or
+/// delivered by the dispatcher (`false`), it's covered by the signature.
+pub const WEBHOOK_REPLAY_HEADER: &str = "X-Webhook-Replay";
+
+/// Maximum number of the delivery attempts returned at once.
+pub const MAX_WEBHOOK_DELIVERIES_LIMIT: u32 = 100;
+
+/// Direction of the transaction relative to the subscribed account.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TxDirection {
+    /// The transaction is initiated by the account, e.g. an outgoing transfer or a withdrawal.
+    Sent,
+    /// The account receives the funds, e.g. an incoming transfer or a deposit.
+    Received,
+}
+
+/// Minimum amount of the transaction.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MinAmount {
+    /// Amount in the smallest units of the token (e.g. wei) selected by the `tokens` filter.
+    Token(#[serde(with = "BigUintSerdeAsRadix10Str")] BigUint),
+    /// Amount in USD at the current price of the token.
+    Usd(BigDecimal),
+}
+
+/// Preferences of the subscription: only the events matching all the set filters are delivered,
+/// the filters that are not set match any event.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WebhookPreferences {
+    pub tx_types: Option<Vec<TransactionType>>,
+    pub statuses: Option<Vec<TransactionStatus>>,
+    pub tokens: Option<Vec<TokenId>>,
+    pub direction: Option<TxDirection>,
+    /// Transactions without an amount (e.g. `ChangePubKey`) don't match this filter.
+    pub min_amount: Option<MinAmount>,
+}
+
+/// Subscription to the transaction events of the account, signed by the account owner.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscriptionRequest {
+    pub address: Address,
+    /// URL the events are posted to.
+    pub url: String,
+    #[serde(default)]
+    pub preferences: WebhookPreferences,
+    /// Unix timestamp in seconds, the subscription is accepted only if it's recent.
+    pub timestamp: u64,
+}
+
+impl WebhookSubscriptionRequest {
+    /// Returns the message which is signed by the account owner.
+    pub fn message(&self) -> String {
+        format!(
+            "zkSync webhook subscription\n\
+            Account: {:?}\n\
+            URL: {}\n\
+            Timestamp: {}",
+            self.address, self.url, self.timestamp
+        )
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedWebhookSubscription {
+    pub subscription: WebhookSubscriptionRequest,
+    pub signature: PackedEthSignature,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    pub id: i64,
+    pub address: Address,
+    pub url: String,
+    pub preferences: WebhookPreferences,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Created subscription. The secret is returned only once, it authenticates the requests
+/// to manage the subscription and signs the deliveries.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedWebhookSubscription {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    pub secret: String,
+}
+
+/// Body of the request posted to the subscription URL.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookPayload {
+    pub subscription_id: i64,
+    pub event: ApiEvent,
+}
+
+/// Query for the delivery attempts of the subscription.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookDeliveriesQuery {
+    /// Only the attempts to deliver this event and the following ones are returned.
+    pub from: Option<EventId>,
+    /// Defaults to [`MAX_WEBHOOK_DELIVERIES_LIMIT`].
+    pub limit: Option<u32>,
+}
+
+/// Attempt to deliver the event to the subscription.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub event_id: EventId,
+    pub created_at: DateTime<Utc>,
+    /// HTTP status of the response, `None` if there was no response (e.g. the request timed out).
+    pub response_code: Option<u16>,
+    pub latency_ms: u64,
+    /// Keccak-256 hash of the request body.
+    pub body_hash: String,
+    pub replay: bool,
+}
+
+/// Returns the hex-encoded HMAC-SHA256 of the delivery signed with the subscription secret.
+/// The signed message is the timestamp, the value of the [`WEBHOOK_REPLAY_HEADER`] and the body
+/// of the request separated by newlines.
+pub fn webhook_signature(secret: &str, timestamp: i64, replay: bool, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}\n{}\n", timestamp, replay).as_bytes());
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn preferences_serialization() {
+        let preferences: WebhookPreferences = serde_json::from_value(json!({
+            "txTypes": ["Withdraw"],
+            "statuses": ["finalized"],
+            "tokens": [1],
+            "direction": "sent",
+            "minAmount": { "token": "1000000" },
+        }))
+        .unwrap();
+        assert_eq!(
+            preferences,
+            WebhookPreferences {
+                tx_types: Some(vec![TransactionType::Withdraw]),
+                statuses: Some(vec![TransactionStatus::Finalized]),
+                tokens: Some(vec![TokenId(1)]),
+                direction: Some(TxDirection::Sent),
+                min_amount: Some(MinAmount::Token(BigUint::from(1_000_000u32))),
+            }
+        );
+
+        let preferences: WebhookPreferences =
+            serde_json::from_value(json!({ "minAmount": { "usd": "10.5" } })).unwrap();
+        assert_eq!(
+            preferences.min_amount,
+            Some(MinAmount::Usd("10.5".parse().unwrap()))
+        );
+        assert!(serde_json::from_value::<WebhookPreferences>(json!({ "minUsd": 10 })).is_err());
+    }
+
+    #[test]
+    fn replay_is_signed() {
+        let body = br#"{"subscriptionId":1}"#;
+        let signature = webhook_signature("whsec_00", 1_700_000_000, false, body);
+        assert_eq!(signature.len(), 64);
+        assert_eq!(
+            signature,
+            webhook_signature("whsec_00", 1_700_000_000, false, body)
+        );
+        // The replay marker can't be changed without breaking the signature.
+        assert_ne!(
+            signature,
+            webhook_signature("whsec_00", 1_700_000_000, true, body)
+        );
+        assert_ne!(
+            signature,
+            webhook_signature("whsec_01", 1_700_000_000, false, body)
+        );
+    }
+}
@@ -2,11 +2,13 @@ use num::{rational::Ratio, BigUint};
 /// External uses
 use serde::Deserialize;
 /// Built-in uses
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::str::FromStr;
 use std::time::Duration;
 use zksync_utils::scaled_u64_to_ratio;
 // Workspace uses
-use zksync_types::AccountId;
+use zksync_types::{AccountId, Address, TokenId, H256};
 // Local uses
 use crate::envy_load;
 
@@ -53,6 +55,94 @@ impl CommonApiConfig {
         scaled_u64_to_ratio(self.max_subsidy_usd_scaled)
     }
 
+    /// Parses `self.min_transfer_amounts` into the minimum amounts per token.
+    pub fn min_transfer_amounts(&self) -> HashMap<TokenId, BigUint> {
+        self.min_transfer_amounts
+            .iter()
+            .map(|entry| {
+                let mut parts = entry.splitn(2, ':');
+                let token = parts.next().and_then(|token| TokenId::from_str(token).ok());
+                let amount = parts
+                    .next()
+                    .and_then(|amount| BigUint::from_str(amount).ok());
+                match (token, amount) {
+                    (Some(token), Some(amount)) => (token, amount),
+                    _ => panic!(
+                        "Invalid minimum transfer amount `{}`, expected `token_id:amount`",
+                        entry
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Minimum transfer amount in USD for the tokens without the explicit minimum, `None` if disabled.
+    pub fn min_transfer_amount_usd(&self) -> Option<Ratio<BigUint>> {
+        Some(self.min_transfer_amount_usd_scaled)
+            .filter(|amount| *amount != 0)
+            .map(scaled_u64_to_ratio)
+    }
+
+    /// Converts `self.fee_quote_validity_secs` into `Duration`.
+    pub fn fee_quote_validity(&self) -> Duration {
+        Duration::from_secs(self.fee_quote_validity_secs)
+    }
+
+    /// Returns the endpoint of the OpenTelemetry collector, if the spans export is enabled.
+    pub fn otlp_exporter_url(&self) -> Option<&str> {
+        Some(self.otlp_exporter_url.as_str()).filter(|url| !url.is_empty() && *url != "unset")
+    }
+
+    /// Converts `self.sign_verifier_max_latency_ms` into `Duration`.
+    pub fn sign_verifier_max_latency(&self) -> Duration {
+        Duration::from_millis(self.sign_verifier_max_latency_ms)
+    }
+
+    /// Converts `self.sign_verifier_breaker_cooldown_ms` into `Duration`.
+    pub fn sign_verifier_breaker_cooldown(&self) -> Duration {
+        Duration::from_millis(self.sign_verifier_breaker_cooldown_ms)
+    }
+
+    /// Returns the URL of the screening service, if the screening is enabled.
+    pub fn screening_url(&self) -> Option<&str> {
+        Some(self.screening_url.as_str()).filter(|url| !url.is_empty())
+    }
+
+    /// Converts `self.screening_timeout_ms` into `Duration`.
+    pub fn screening_timeout(&self) -> Duration {
+        Duration::from_millis(self.screening_timeout_ms)
+    }
+
+    /// Converts `self.screening_cache_ttl_secs` into `Duration`.
+    pub fn screening_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.screening_cache_ttl_secs)
+    }
+
+    /// Converts `self.new_account_tx_quota_window_secs` into `Duration`.
+    pub fn new_account_tx_quota_window(&self) -> Duration {
+        Duration::from_secs(self.new_account_tx_quota_window_secs)
+    }
+
+    /// Returns the directory of the persistent cache, if the cache is enabled.
+    pub fn disk_cache_dir(&self) -> Option<&str> {
+        Some(self.disk_cache_dir.as_str()).filter(|dir| !dir.is_empty())
+    }
+
+    /// Converts `self.account_unlock_cooldown_secs` into `Duration`.
+    pub fn account_unlock_cooldown(&self) -> Duration {
+        Duration::from_secs(self.account_unlock_cooldown_secs)
+    }
+
+    /// Converts `self.pool_check_interval_ms` into `Duration`.
+    pub fn pool_check_interval(&self) -> Duration {
+        Duration::from_millis(self.pool_check_interval_ms)
+    }
+
+    /// Converts `self.pool_check_timeout_ms` into `Duration`.
+    pub fn pool_check_timeout(&self) -> Duration {
+        Duration::from_millis(self.pool_check_timeout_ms)
+    }
+
     pub fn from_env() -> Self {
         envy_load!("common", "API_COMMON_")
     }
@@ -122,6 +212,73 @@ pub struct CommonApiConfig {
 
     /// The name of current subsidy. It is needed to conveniently fetch historical data regarding subsidies for different partners
     pub subsidy_name: String,
+
+    /// Private key used to sign the fee quotes.
+    pub fee_quote_private_key: H256,
+    /// Fee quotes are honored for this amount of seconds after they are issued.
+    pub fee_quote_validity_secs: u64,
+    /// Endpoint of the OpenTelemetry collector the tracing spans are exported to.
+    /// Spans are not exported if set to "unset".
+    pub otlp_exporter_url: String,
+
+    /// Submissions are rejected once this number of signature verification requests is in flight.
+    pub sign_verifier_max_queue_depth: u64,
+    /// Submissions are rejected once the average signature verification latency exceeds this value.
+    pub sign_verifier_max_latency_ms: u64,
+    /// Minimal time submissions are rejected for once the signature verifier is overloaded.
+    pub sign_verifier_breaker_cooldown_ms: u64,
+
+    /// Factories the CREATE2 accounts are allowed to be deployed by, any factory is allowed if empty.
+    pub create2_factory_allowlist: Vec<Address>,
+
+    /// URL of the compliance service screening the destinations of the withdrawals and forced exits.
+    /// The screening is disabled if not set.
+    pub screening_url: String,
+    /// Withdrawals worth less than this amount of USD are not screened.
+    pub screening_threshold_usd: u64,
+    /// Maximum time (in ms) to wait for the screening verdict.
+    pub screening_timeout_ms: u64,
+    /// Whether the withdrawals are rejected if the screening service doesn't provide the verdict.
+    pub screening_fail_closed: bool,
+    /// Screening verdicts are cached per address for this amount of seconds.
+    pub screening_cache_ttl_secs: u64,
+
+    /// Interval (in ms) between the checks of the idle storage connections.
+    pub pool_check_interval_ms: u64,
+    /// Maximum time (in ms) the check of a single connection may take.
+    pub pool_check_timeout_ms: u64,
+    /// The pool is recreated once this percentage of the checked connections is broken.
+    pub pool_recreate_failure_percent: u64,
+
+    /// Tokens that are temporarily not accepted in the submitted transactions.
+    pub disabled_tokens: Vec<TokenId>,
+
+    /// Minimum amounts of the transfers to other accounts and of the withdrawals, as `token_id:amount`
+    /// entries with the amounts in the smallest units of the token.
+    pub min_transfer_amounts: Vec<String>,
+    /// Minimum amount of the transfers and withdrawals in USD for the tokens without the explicit minimum,
+    /// scaled by SUBSIDY_USD_AMOUNTS_SCALE. Disabled if zero.
+    pub min_transfer_amount_usd_scaled: u64,
+
+    /// Accounts created less than this number of blocks ago may submit at most `new_account_tx_quota`
+    /// transactions per `new_account_tx_quota_window_secs`. The quota is disabled if zero.
+    pub new_account_max_age_blocks: u32,
+    pub new_account_tx_quota: u32,
+    pub new_account_tx_quota_window_secs: u64,
+    /// API keys exempt from the quota of the new accounts, passed in the `X-Api-Key` header.
+    pub tx_quota_exempt_api_keys: Vec<String>,
+    /// API keys allowed to request the durations of the batch submission stages
+    /// with `debug_timings=true`, passed in the `X-Api-Key` header.
+    pub debug_timings_api_keys: Vec<String>,
+
+    /// Directory of the persistent cache of the finalized blocks and transactions data,
+    /// which is kept across the restarts. The cache is disabled if not set.
+    pub disk_cache_dir: String,
+    /// Maximum total size (in bytes) of the files in the persistent cache.
+    pub disk_cache_max_bytes: u64,
+
+    /// Locked accounts are unlocked this amount of seconds after the owner requests it.
+    pub account_unlock_cooldown_secs: u64,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -178,12 +335,38 @@ pub struct PrivateApiConfig {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// Whether the requests not signed with the `auth_secret` or the `auth_previous_secret` are rejected.
+    pub auth_enabled: bool,
+    /// Secret the API servers sign the requests to the private API with. Requests are signed
+    /// if the secret is set, even if the authentication is not enabled.
+    pub auth_secret: String,
+    /// Secret still accepted during the rotation of the `auth_secret`, ignored if empty.
+    pub auth_previous_secret: String,
+    /// Maximum difference (in seconds) between the time the request is signed at and the time it's received.
+    pub auth_max_clock_skew_secs: u64,
+    /// Maximum size (in bytes) of the body of the request, larger bodies are rejected before
+    /// being read to the end. Applied only if the authentication is enabled.
+    pub auth_max_body_size: usize,
 }
 
 impl PrivateApiConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    /// Returns the secrets the signed requests are accepted with.
+    pub fn auth_secrets(&self) -> Vec<String> {
+        [&self.auth_secret, &self.auth_previous_secret]
+            .iter()
+            .filter(|secret| !secret.is_empty())
+            .map(|secret| secret.to_string())
+            .collect()
+    }
+
+    /// Converts `self.auth_max_clock_skew_secs` into `Duration`.
+    pub fn auth_max_clock_skew(&self) -> Duration {
+        Duration::from_secs(self.auth_max_clock_skew_secs)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -192,12 +375,212 @@ pub struct RestApiConfig {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// URLs of the database replicas used for the read-only queries.
+    /// If not set, the replica from `DATABASE_REPLICA_URL` is used.
+    pub replica_urls: Vec<String>,
+    /// Replicas lagging behind the primary database for longer than this value (in ms)
+    /// are not used until they catch up.
+    pub replica_max_lag_ms: u64,
+    /// Interval (in ms) between the health checks of the replicas.
+    pub replica_health_check_interval_ms: u64,
+    /// Maximum size (in bytes) of the body of a single transaction submission.
+    pub max_tx_payload_size: usize,
+    /// Maximum size (in bytes) of the body of a batch submission.
+    pub max_batch_payload_size: usize,
+    /// Maximum number of blocks in a single blocks export request.
+    pub blocks_export_max_range: u32,
+    /// Maximum number of blocks exports running at the same time.
+    pub blocks_export_max_concurrency: usize,
+    /// Minimum interval (in seconds) between the starts of the state root checks.
+    pub state_root_check_interval_secs: u64,
+    /// Maximum number of requests accessing the storage at the same time, per API scope.
+    pub storage_permits: usize,
+    /// Maximum number of submissions accessing the storage at the same time,
+    /// admitted separately from the rest of the requests.
+    pub submission_storage_permits: usize,
+    /// Maximum time (in ms) a request waits to be admitted before being rejected.
+    pub storage_permit_wait_ms: u64,
+    /// Maximum number of retries of the read-only queries failed because of the transient
+    /// database errors (e.g. a dropped connection or a serialization failure), `0` disables them.
+    pub storage_read_retries: u32,
+    /// Base delay (in ms) before a retry, the actual delay is jittered.
+    pub storage_retry_backoff_ms: u64,
+    /// Maximum time (in ms) since the start of the request the queries are retried within.
+    pub storage_retry_deadline_ms: u64,
+    /// URL of the archive database holding the old executed transactions.
+    /// If not set, the transactions are only looked up in the main database.
+    pub archive_url: String,
+    /// Maximum time (in ms) of a single query to the archive database.
+    pub archive_query_timeout_ms: u64,
+    /// Number of the largest holders of each token served by the API.
+    pub token_holders_limit: u32,
+    /// Interval (in seconds) between the recalculations of the largest token holders.
+    pub token_holders_refresh_interval_secs: u64,
+    /// Interval (in seconds) between the rollups of the finalized blocks into the token activity.
+    pub token_activity_rollup_interval_secs: u64,
+    /// Maximum number of the blocks rolled up into the token activity at once.
+    pub token_activity_rollup_blocks: u32,
+    /// Number of the finalized blocks between the account snapshots used to restore
+    /// the historical state of the accounts, `0` disables the snapshots.
+    pub account_snapshot_blocks: u32,
+    /// Interval (in seconds) between the checks for the finalized blocks to take snapshots at.
+    pub account_snapshot_interval_secs: u64,
+    /// Whether the resources requested by their identifiers and not found are reported with
+    /// `404 Not Found` instead of `200 OK`. The body of the response is the same.
+    pub not_found_http_404: bool,
+    /// Minimum number of hex digits in the prefix of the transactions hashes searched by the admin.
+    pub tx_search_min_prefix_len: usize,
+    /// Maximum number of the transactions returned by a single search by the hash prefix.
+    pub tx_search_limit: u32,
+    /// Time (in ms) after which the request in flight is logged as slow.
+    pub slow_request_threshold_ms: u64,
+    /// Interval (in ms) between the checks of the requests in flight.
+    pub slow_request_check_interval_ms: u64,
+    /// Time (in ms) after which the request is aborted with `504 Gateway Timeout`. Disabled if zero.
+    pub request_hard_timeout_ms: u64,
+    /// Maximum number of the finalized transactions data cached in memory, disabled if zero.
+    pub tx_data_cache_size: usize,
+    /// Maximum total size (in bytes) of the cached transactions data, serialized as JSON.
+    pub tx_data_cache_max_bytes: usize,
+    /// Networks of the proxies (e.g. the load balancer) in the CIDR notation. The client address
+    /// is taken from the `trusted_proxies_header` only if the request comes through these proxies.
+    pub trusted_proxies: Vec<String>,
+    /// Header the trusted proxies put the client address into, either `Forwarded` or `X-Forwarded-For`.
+    pub trusted_proxies_header: String,
+    /// Interval (in ms) between the checks of the webhook subscriptions for the new events.
+    pub webhook_dispatch_interval_ms: u64,
+    /// Time (in ms) the subscriber is given to respond to the webhook delivery.
+    pub webhook_delivery_timeout_ms: u64,
+    /// Delay (in ms) before retrying the failed webhook delivery, doubled after every failure.
+    pub webhook_retry_delay_ms: u64,
+    /// Number of the failed attempts to deliver the event after which the event is skipped.
+    pub webhook_max_attempts: u32,
+    /// Webhook delivery attempts older than this period are removed from the database.
+    /// Value in seconds.
+    pub webhook_deliveries_retention_period: u64,
+    /// How often outdated webhook delivery attempts are removed. Value in seconds.
+    pub webhook_deliveries_pruning_interval: u64,
 }
 
 impl RestApiConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    /// Returns the non-empty networks of the trusted proxies.
+    pub fn trusted_proxies(&self) -> Vec<&str> {
+        self.trusted_proxies
+            .iter()
+            .map(|network| network.trim())
+            .filter(|network| !network.is_empty())
+            .collect()
+    }
+
+    /// Returns the non-empty replica URLs.
+    pub fn replica_urls(&self) -> Vec<String> {
+        self.replica_urls
+            .iter()
+            .filter(|url| !url.is_empty())
+            .cloned()
+            .collect()
+    }
+
+    /// Converts `self.replica_max_lag_ms` into `Duration`.
+    pub fn replica_max_lag(&self) -> Duration {
+        Duration::from_millis(self.replica_max_lag_ms)
+    }
+
+    /// Converts `self.replica_health_check_interval_ms` into `Duration`.
+    pub fn replica_health_check_interval(&self) -> Duration {
+        Duration::from_millis(self.replica_health_check_interval_ms)
+    }
+
+    /// Returns the archive database URL, if the archive is configured.
+    pub fn archive_url(&self) -> Option<&str> {
+        Some(self.archive_url.as_str()).filter(|url| !url.is_empty())
+    }
+
+    /// Converts `self.archive_query_timeout_ms` into `Duration`.
+    pub fn archive_query_timeout(&self) -> Duration {
+        Duration::from_millis(self.archive_query_timeout_ms)
+    }
+
+    /// Converts `self.storage_permit_wait_ms` into `Duration`.
+    pub fn storage_permit_wait(&self) -> Duration {
+        Duration::from_millis(self.storage_permit_wait_ms)
+    }
+
+    /// Converts `self.storage_retry_backoff_ms` into `Duration`.
+    pub fn storage_retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.storage_retry_backoff_ms)
+    }
+
+    /// Converts `self.storage_retry_deadline_ms` into `Duration`.
+    pub fn storage_retry_deadline(&self) -> Duration {
+        Duration::from_millis(self.storage_retry_deadline_ms)
+    }
+
+    /// Converts `self.state_root_check_interval_secs` into `Duration`.
+    pub fn state_root_check_interval(&self) -> Duration {
+        Duration::from_secs(self.state_root_check_interval_secs)
+    }
+
+    /// Converts `self.token_holders_refresh_interval_secs` into `Duration`.
+    pub fn token_holders_refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.token_holders_refresh_interval_secs)
+    }
+
+    /// Converts `self.token_activity_rollup_interval_secs` into `Duration`.
+    pub fn token_activity_rollup_interval(&self) -> Duration {
+        Duration::from_secs(self.token_activity_rollup_interval_secs)
+    }
+
+    /// Converts `self.account_snapshot_interval_secs` into `Duration`.
+    pub fn account_snapshot_interval(&self) -> Duration {
+        Duration::from_secs(self.account_snapshot_interval_secs)
+    }
+
+    /// Converts `self.slow_request_threshold_ms` into `Duration`.
+    pub fn slow_request_threshold(&self) -> Duration {
+        Duration::from_millis(self.slow_request_threshold_ms)
+    }
+
+    /// Converts `self.slow_request_check_interval_ms` into `Duration`.
+    pub fn slow_request_check_interval(&self) -> Duration {
+        Duration::from_millis(self.slow_request_check_interval_ms)
+    }
+
+    /// Returns the time after which the request is aborted, if the hard timeout is enabled.
+    pub fn request_hard_timeout(&self) -> Option<Duration> {
+        Some(self.request_hard_timeout_ms)
+            .filter(|timeout| *timeout != 0)
+            .map(Duration::from_millis)
+    }
+
+    /// Converts `self.webhook_dispatch_interval_ms` into `Duration`.
+    pub fn webhook_dispatch_interval(&self) -> Duration {
+        Duration::from_millis(self.webhook_dispatch_interval_ms)
+    }
+
+    /// Converts `self.webhook_delivery_timeout_ms` into `Duration`.
+    pub fn webhook_delivery_timeout(&self) -> Duration {
+        Duration::from_millis(self.webhook_delivery_timeout_ms)
+    }
+
+    /// Converts `self.webhook_retry_delay_ms` into `Duration`.
+    pub fn webhook_retry_delay(&self) -> Duration {
+        Duration::from_millis(self.webhook_retry_delay_ms)
+    }
+
+    /// Converts `self.webhook_deliveries_retention_period` into `Duration`.
+    pub fn webhook_deliveries_retention_period(&self) -> Duration {
+        Duration::from_secs(self.webhook_deliveries_retention_period)
+    }
+
+    /// Converts `self.webhook_deliveries_pruning_interval` into `Duration`.
+    pub fn webhook_deliveries_pruning_interval(&self) -> Duration {
+        Duration::from_secs(self.webhook_deliveries_pruning_interval)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -210,6 +593,13 @@ pub struct JsonRpcConfig {
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
     pub ws_url: String,
+    /// Methods that are going to be removed. Their responses are marked with
+    /// the `deprecated` field.
+    pub deprecated_methods: Vec<String>,
+    /// Whether the usage of the methods is tracked per caller subnet (stored hashed).
+    pub track_usage_per_subnet: bool,
+    /// Interval of flushing the collected methods usage to the database.
+    pub usage_flush_interval_ms: u64,
 }
 
 impl JsonRpcConfig {
@@ -220,6 +610,11 @@ impl JsonRpcConfig {
     pub fn ws_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.ws_port)
     }
+
+    /// Converts `self.usage_flush_interval_ms` into `Duration`.
+    pub fn usage_flush_interval(&self) -> Duration {
+        Duration::from_millis(self.usage_flush_interval_ms)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -248,7 +643,7 @@ pub struct PrometheusConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::configs::test_utils::set_env;
+    use crate::configs::test_utils::{addr, hash, set_env};
     use std::net::IpAddr;
 
     fn expected_config() -> ApiConfig {
@@ -263,6 +658,34 @@ mod tests {
                 subsidized_ips: vec!["127.0.0.1".to_owned()],
                 max_subsidy_usd_scaled: 20000,
                 subsidy_name: String::from("PartnerName"),
+                fee_quote_private_key: hash(
+                    "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
+                ),
+                fee_quote_validity_secs: 60,
+                otlp_exporter_url: "http://127.0.0.1:4317".into(),
+                sign_verifier_max_queue_depth: 1000,
+                sign_verifier_max_latency_ms: 5000,
+                sign_verifier_breaker_cooldown_ms: 10000,
+                create2_factory_allowlist: vec![addr("7ebc4f4a2d9b5a1c1c2e2b1d1b5a08e5c3d1f2a0")],
+                screening_url: "http://127.0.0.1:4050/screen".into(),
+                screening_threshold_usd: 10000,
+                screening_timeout_ms: 1000,
+                screening_fail_closed: false,
+                screening_cache_ttl_secs: 3600,
+                pool_check_interval_ms: 10000,
+                pool_check_timeout_ms: 1000,
+                pool_recreate_failure_percent: 50,
+                disabled_tokens: vec![TokenId(17), TokenId(42)],
+                min_transfer_amounts: vec!["0:1000000000000".to_owned(), "1:10000".to_owned()],
+                min_transfer_amount_usd_scaled: 10000,
+                new_account_max_age_blocks: 100,
+                new_account_tx_quota: 50,
+                new_account_tx_quota_window_secs: 3600,
+                tx_quota_exempt_api_keys: vec!["key-1".to_owned(), "key-2".to_owned()],
+                debug_timings_api_keys: vec!["key-3".to_owned()],
+                disk_cache_dir: "/var/cache/zksync-api".into(),
+                disk_cache_max_bytes: 1073741824,
+                account_unlock_cooldown_secs: 86400,
             },
             admin: AdminApiConfig {
                 port: 8080,
@@ -272,12 +695,56 @@ mod tests {
             rest: RestApiConfig {
                 port: 3001,
                 url: "http://127.0.0.1:3001".into(),
+                replica_urls: vec![
+                    "postgres://postgres@replica-1/plasma".into(),
+                    "postgres://postgres@replica-2/plasma".into(),
+                ],
+                replica_max_lag_ms: 5000,
+                replica_health_check_interval_ms: 1000,
+                max_tx_payload_size: 65536,
+                max_batch_payload_size: 2097152,
+                blocks_export_max_range: 10000,
+                blocks_export_max_concurrency: 4,
+                state_root_check_interval_secs: 60,
+                storage_permits: 10,
+                submission_storage_permits: 4,
+                storage_permit_wait_ms: 100,
+                storage_read_retries: 2,
+                storage_retry_backoff_ms: 20,
+                storage_retry_deadline_ms: 500,
+                archive_url: "postgres://postgres@archive/plasma".into(),
+                archive_query_timeout_ms: 500,
+                token_holders_limit: 100,
+                token_holders_refresh_interval_secs: 600,
+                token_activity_rollup_interval_secs: 60,
+                token_activity_rollup_blocks: 100,
+                account_snapshot_blocks: 1000,
+                account_snapshot_interval_secs: 60,
+                not_found_http_404: false,
+                tx_search_min_prefix_len: 8,
+                tx_search_limit: 20,
+                slow_request_threshold_ms: 5000,
+                slow_request_check_interval_ms: 1000,
+                request_hard_timeout_ms: 30000,
+                tx_data_cache_size: 10000,
+                tx_data_cache_max_bytes: 16777216,
+                trusted_proxies: vec!["10.0.0.0/8".into(), "fd00::/8".into()],
+                trusted_proxies_header: "X-Forwarded-For".into(),
+                webhook_dispatch_interval_ms: 1000,
+                webhook_delivery_timeout_ms: 5000,
+                webhook_retry_delay_ms: 10000,
+                webhook_max_attempts: 5,
+                webhook_deliveries_retention_period: 604800,
+                webhook_deliveries_pruning_interval: 3600,
             },
             json_rpc: JsonRpcConfig {
                 http_port: 3030,
                 http_url: "http://127.0.0.1:3030".into(),
                 ws_port: 3031,
                 ws_url: "ws://127.0.0.1:3031".into(),
+                deprecated_methods: vec!["get_tx_fee".into(), "get_txs_batch_fee_in_wei".into()],
+                track_usage_per_subnet: true,
+                usage_flush_interval_ms: 60000,
             },
             web3: Web3Config {
                 port: 3002,
@@ -288,6 +755,11 @@ mod tests {
             private: PrivateApiConfig {
                 port: 8090,
                 url: "http://127.0.0.1:8090".into(),
+                auth_enabled: true,
+                auth_secret: "new_secret".into(),
+                auth_previous_secret: "previous_secret".into(),
+                auth_max_clock_skew_secs: 30,
+                auth_max_body_size: 1048576,
             },
             prover: ProverApiConfig {
                 port: 8088,
@@ -313,22 +785,94 @@ API_COMMON_MAX_SUBSIDY_USD_SCALED=20000
 API_COMMON_SUBSIDY_NAME=PartnerName
 API_COMMON_MAX_NUMBER_OF_TRANSACTIONS_PER_BATCH=200
 API_COMMON_MAX_NUMBER_OF_AUTHORS_PER_BATCH=10
+API_COMMON_FEE_QUOTE_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
+API_COMMON_FEE_QUOTE_VALIDITY_SECS="60"
+API_COMMON_OTLP_EXPORTER_URL="http://127.0.0.1:4317"
+API_COMMON_SIGN_VERIFIER_MAX_QUEUE_DEPTH="1000"
+API_COMMON_SIGN_VERIFIER_MAX_LATENCY_MS="5000"
+API_COMMON_SIGN_VERIFIER_BREAKER_COOLDOWN_MS="10000"
+API_COMMON_CREATE2_FACTORY_ALLOWLIST="0x7ebc4f4a2d9b5a1c1c2e2b1d1b5a08e5c3d1f2a0"
+API_COMMON_SCREENING_URL="http://127.0.0.1:4050/screen"
+API_COMMON_SCREENING_THRESHOLD_USD="10000"
+API_COMMON_SCREENING_TIMEOUT_MS="1000"
+API_COMMON_SCREENING_FAIL_CLOSED="false"
+API_COMMON_SCREENING_CACHE_TTL_SECS="3600"
+API_COMMON_POOL_CHECK_INTERVAL_MS="10000"
+API_COMMON_POOL_CHECK_TIMEOUT_MS="1000"
+API_COMMON_POOL_RECREATE_FAILURE_PERCENT="50"
+API_COMMON_DISABLED_TOKENS=17,42
+API_COMMON_MIN_TRANSFER_AMOUNTS="0:1000000000000,1:10000"
+API_COMMON_MIN_TRANSFER_AMOUNT_USD_SCALED="10000"
+API_COMMON_NEW_ACCOUNT_MAX_AGE_BLOCKS="100"
+API_COMMON_NEW_ACCOUNT_TX_QUOTA="50"
+API_COMMON_NEW_ACCOUNT_TX_QUOTA_WINDOW_SECS="3600"
+API_COMMON_TX_QUOTA_EXEMPT_API_KEYS="key-1,key-2"
+API_COMMON_DEBUG_TIMINGS_API_KEYS="key-3"
+API_COMMON_DISK_CACHE_DIR="/var/cache/zksync-api"
+API_COMMON_DISK_CACHE_MAX_BYTES="1073741824"
+API_COMMON_ACCOUNT_UNLOCK_COOLDOWN_SECS="86400"
 API_TOKEN_INVALIDATE_TOKEN_CACHE_PERIOD_SEC="10"
 API_ADMIN_PORT="8080"
 API_ADMIN_URL="http://127.0.0.1:8080"
 API_ADMIN_SECRET_AUTH="sample"
 API_REST_PORT="3001"
 API_REST_URL="http://127.0.0.1:3001"
+API_REST_REPLICA_URLS="postgres://postgres@replica-1/plasma,postgres://postgres@replica-2/plasma"
+API_REST_REPLICA_MAX_LAG_MS="5000"
+API_REST_REPLICA_HEALTH_CHECK_INTERVAL_MS="1000"
+API_REST_MAX_TX_PAYLOAD_SIZE="65536"
+API_REST_MAX_BATCH_PAYLOAD_SIZE="2097152"
+API_REST_BLOCKS_EXPORT_MAX_RANGE="10000"
+API_REST_BLOCKS_EXPORT_MAX_CONCURRENCY="4"
+API_REST_STATE_ROOT_CHECK_INTERVAL_SECS="60"
+API_REST_STORAGE_PERMITS="10"
+API_REST_SUBMISSION_STORAGE_PERMITS="4"
+API_REST_STORAGE_PERMIT_WAIT_MS="100"
+API_REST_STORAGE_READ_RETRIES="2"
+API_REST_STORAGE_RETRY_BACKOFF_MS="20"
+API_REST_STORAGE_RETRY_DEADLINE_MS="500"
+API_REST_ARCHIVE_URL="postgres://postgres@archive/plasma"
+API_REST_ARCHIVE_QUERY_TIMEOUT_MS="500"
+API_REST_TOKEN_HOLDERS_LIMIT="100"
+API_REST_TOKEN_HOLDERS_REFRESH_INTERVAL_SECS="600"
+API_REST_TOKEN_ACTIVITY_ROLLUP_INTERVAL_SECS="60"
+API_REST_TOKEN_ACTIVITY_ROLLUP_BLOCKS="100"
+API_REST_ACCOUNT_SNAPSHOT_BLOCKS="1000"
+API_REST_ACCOUNT_SNAPSHOT_INTERVAL_SECS="60"
+API_REST_NOT_FOUND_HTTP_404="false"
+API_REST_TX_SEARCH_MIN_PREFIX_LEN="8"
+API_REST_TX_SEARCH_LIMIT="20"
+API_REST_SLOW_REQUEST_THRESHOLD_MS="5000"
+API_REST_SLOW_REQUEST_CHECK_INTERVAL_MS="1000"
+API_REST_REQUEST_HARD_TIMEOUT_MS="30000"
+API_REST_TX_DATA_CACHE_SIZE="10000"
+API_REST_TX_DATA_CACHE_MAX_BYTES="16777216"
+API_REST_TRUSTED_PROXIES="10.0.0.0/8,fd00::/8"
+API_REST_TRUSTED_PROXIES_HEADER="X-Forwarded-For"
+API_REST_WEBHOOK_DISPATCH_INTERVAL_MS="1000"
+API_REST_WEBHOOK_DELIVERY_TIMEOUT_MS="5000"
+API_REST_WEBHOOK_RETRY_DELAY_MS="10000"
+API_REST_WEBHOOK_MAX_ATTEMPTS="5"
+API_REST_WEBHOOK_DELIVERIES_RETENTION_PERIOD="604800"
+API_REST_WEBHOOK_DELIVERIES_PRUNING_INTERVAL="3600"
 API_JSON_RPC_HTTP_PORT="3030"
 API_JSON_RPC_HTTP_URL="http://127.0.0.1:3030"
 API_JSON_RPC_WS_PORT="3031"
 API_JSON_RPC_WS_URL="ws://127.0.0.1:3031"
+API_JSON_RPC_DEPRECATED_METHODS="get_tx_fee,get_txs_batch_fee_in_wei"
+API_JSON_RPC_TRACK_USAGE_PER_SUBNET="true"
+API_JSON_RPC_USAGE_FLUSH_INTERVAL_MS="60000"
 API_WEB3_PORT="3002"
 API_WEB3_URL="http://127.0.0.1:3002"
 API_WEB3_CHAIN_ID="240"
 API_WEB3_MAX_BLOCK_RANGE="10"
 API_PRIVATE_PORT="8090"
 API_PRIVATE_URL="http://127.0.0.1:8090"
+API_PRIVATE_AUTH_ENABLED="true"
+API_PRIVATE_AUTH_SECRET="new_secret"
+API_PRIVATE_AUTH_PREVIOUS_SECRET="previous_secret"
+API_PRIVATE_AUTH_MAX_CLOCK_SKEW_SECS="30"
+API_PRIVATE_AUTH_MAX_BODY_SIZE="1048576"
 API_PROVER_PORT="8088"
 API_PROVER_URL="http://127.0.0.1:8088"
 API_PROVER_SECRET_AUTH="sample"
@@ -370,5 +914,41 @@ API_PROMETHEUS_PORT="3312"
             config.web3.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.web3.port)
         );
+        assert_eq!(config.common.fee_quote_validity(), Duration::from_secs(60));
+        assert_eq!(
+            config.common.min_transfer_amounts(),
+            vec![
+                (TokenId(0), BigUint::from(1_000_000_000_000u64)),
+                (TokenId(1), BigUint::from(10_000u32)),
+            ]
+            .into_iter()
+            .collect()
+        );
+        assert_eq!(
+            config.common.min_transfer_amount_usd(),
+            Some(Ratio::new(BigUint::from(1u32), BigUint::from(100u32)))
+        );
+        assert_eq!(
+            config.common.disk_cache_dir(),
+            Some("/var/cache/zksync-api")
+        );
+        assert_eq!(config.rest.replica_urls(), config.rest.replica_urls);
+        assert_eq!(config.rest.replica_max_lag(), Duration::from_secs(5));
+        assert_eq!(
+            config.rest.archive_url(),
+            Some("postgres://postgres@archive/plasma")
+        );
+        assert_eq!(
+            config.rest.archive_query_timeout(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(
+            config.rest.replica_health_check_interval(),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            config.rest.request_hard_timeout(),
+            Some(Duration::from_secs(30))
+        );
     }
 }
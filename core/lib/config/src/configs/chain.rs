@@ -94,6 +94,8 @@ pub struct StateKeeper {
     pub block_prove_deadline: u64,
     pub block_execute_deadline: u64,
     pub max_aggregated_tx_gas: usize,
+    /// Time (in seconds) during which rejected and evicted transactions are reported by the API.
+    pub rejected_txs_retention_period: u64,
 }
 
 impl StateKeeper {
@@ -113,6 +115,11 @@ impl StateKeeper {
     pub fn block_execute_deadline(&self) -> Duration {
         Duration::from_secs(self.block_execute_deadline)
     }
+
+    /// Converts `self.rejected_txs_retention_period` into `Duration`.
+    pub fn rejected_txs_retention_period(&self) -> Duration {
+        Duration::from_secs(self.rejected_txs_retention_period)
+    }
 }
 
 #[cfg(test)]
@@ -147,6 +154,7 @@ mod tests {
                 block_prove_deadline: 3_000,
                 block_execute_deadline: 4_000,
                 max_aggregated_tx_gas: 4_000_000,
+                rejected_txs_retention_period: 86_400,
             },
         }
     }
@@ -175,6 +183,7 @@ CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE="300"
 CHAIN_STATE_KEEPER_BLOCK_PROVE_DEADLINE="3000"
 CHAIN_STATE_KEEPER_BLOCK_EXECUTE_DEADLINE="4000"
 CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
+CHAIN_STATE_KEEPER_REJECTED_TXS_RETENTION_PERIOD="86400"
         "#;
         set_env(config);
 
@@ -191,5 +200,9 @@ CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
             config.state_keeper.miniblock_iteration_interval(),
             Duration::from_millis(config.state_keeper.miniblock_iteration_interval)
         );
+        assert_eq!(
+            config.state_keeper.rejected_txs_retention_period(),
+            Duration::from_secs(config.state_keeper.rejected_txs_retention_period)
+        );
     }
 }
@@ -3,7 +3,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Workspace uses
-use zksync_types::{Address, H256};
+use zksync_types::{Address, H256, U256};
 // Local uses
 use crate::envy_load;
 
@@ -44,6 +44,9 @@ pub struct Sender {
     pub max_txs_in_flight: u64,
     /// Whether sender should interact with L1 or not.
     pub is_enabled: bool,
+    /// Budget for the fees paid by the sender within a day in gwei, 0 if not set.
+    /// Exceeding the budget is only reported, the transactions are sent anyway.
+    pub daily_fee_budget_gwei: u64,
 }
 
 impl Sender {
@@ -51,6 +54,13 @@ impl Sender {
     pub fn tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.tx_poll_period)
     }
+
+    /// Returns the daily fee budget in wei, `None` if the budget is not set.
+    pub fn daily_fee_budget(&self) -> Option<U256> {
+        Some(self.daily_fee_budget_gwei)
+            .filter(|budget| *budget != 0)
+            .map(|budget| U256::from(budget) * U256::exp10(9))
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -90,6 +100,7 @@ mod tests {
                 tx_poll_period: 3,
                 max_txs_in_flight: 3,
                 is_enabled: true,
+                daily_fee_budget_gwei: 5000000000,
                 operator_private_key: hash(
                     "27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be",
                 ),
@@ -112,6 +123,7 @@ ETH_SENDER_SENDER_EXPECTED_WAIT_TIME_BLOCK="30"
 ETH_SENDER_SENDER_TX_POLL_PERIOD="3"
 ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
 ETH_SENDER_SENDER_IS_ENABLED="true"
+ETH_SENDER_SENDER_DAILY_FEE_BUDGET_GWEI="5000000000"
 ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
 ETH_SENDER_SENDER_OPERATOR_COMMIT_ETH_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
 ETH_SENDER_GAS_PRICE_LIMIT_DEFAULT="400000000000"
@@ -135,6 +147,13 @@ ETH_SENDER_GAS_PRICE_LIMIT_SCALE_FACTOR="1"
             Duration::from_secs(config.sender.tx_poll_period)
         );
 
+        assert_eq!(config.sender.daily_fee_budget(), Some(U256::exp10(18) * 5));
+        let no_budget = Sender {
+            daily_fee_budget_gwei: 0,
+            ..config.sender.clone()
+        };
+        assert_eq!(no_budget.daily_fee_budget(), None);
+
         assert_eq!(
             config.gas_price_limit.update_interval(),
             Duration::from_secs(config.gas_price_limit.update_interval)
@@ -1,5 +1,5 @@
 // Built-in uses
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 
 // External uses
 use serde::Deserialize;
@@ -17,6 +17,11 @@ pub struct EventListenerConfig {
     /// PostgreSQL channel name to listen on. Must be equal to the one
     /// hardcoded into database migrations.
     pub channel_name: String,
+    /// Events older than this period are removed from the database.
+    /// Value in seconds.
+    pub events_retention_period: u64,
+    /// How often outdated events are removed. Value in seconds.
+    pub events_pruning_interval: u64,
 }
 
 impl EventListenerConfig {
@@ -27,6 +32,16 @@ impl EventListenerConfig {
     pub fn ws_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.ws_port)
     }
+
+    /// Converts `self.events_retention_period` into `Duration`.
+    pub fn events_retention_period(&self) -> Duration {
+        Duration::from_secs(self.events_retention_period)
+    }
+
+    /// Converts `self.events_pruning_interval` into `Duration`.
+    pub fn events_pruning_interval(&self) -> Duration {
+        Duration::from_secs(self.events_pruning_interval)
+    }
 }
 
 #[cfg(test)]
@@ -40,6 +55,8 @@ mod tests {
             ws_port: 65535,
             ws_url: "ws://localhost:12345".into(),
             channel_name: "zksync_event_channel".into(),
+            events_retention_period: 604800,
+            events_pruning_interval: 3600,
         }
     }
 
@@ -49,6 +66,8 @@ mod tests {
 EVENT_LISTENER_WS_URL="ws://localhost:12345"
 EVENT_LISTENER_WS_PORT="65535"
 EVENT_LISTENER_CHANNEL_NAME="zksync_event_channel"
+EVENT_LISTENER_EVENTS_RETENTION_PERIOD="604800"
+EVENT_LISTENER_EVENTS_PRUNING_INTERVAL="3600"
         "#;
         set_env(config);
 
@@ -1,5 +1,7 @@
-use num::{rational::Ratio, BigUint};
+// Built-in uses
+use std::time::Duration;
 // External uses
+use num::{rational::Ratio, BigUint};
 use serde::Deserialize;
 // Workspace uses
 use zksync_types::Address;
@@ -18,6 +20,14 @@ pub enum TokenPriceSource {
 pub struct TickerConfig {
     /// Indicator of the API to be used for getting token prices.
     pub token_price_source: TokenPriceSource,
+    /// APIs queried for the token prices along with the `token_price_source`,
+    /// so the prices are updated while some of them are slow or unavailable.
+    pub fallback_price_sources: Vec<TokenPriceSource>,
+    /// The limit of time to wait for the price from a single API.
+    pub price_source_timeout_ms: u64,
+    /// Number of the valid prices from different APIs required to take the median of them.
+    /// If 0, the first valid price is used.
+    pub price_quorum: usize,
     /// URL of CoinMarketCap API. Can be set to the mock server for local development.
     pub coinmarketcap_base_url: String,
     /// URL of CoinGecko API. Can be set to the mock server for local development.
@@ -30,8 +40,17 @@ pub struct TickerConfig {
     pub uniswap_url: String,
     /// The volume of tokens to confirm their liquidity
     pub liquidity_volume: f64,
+    /// The volume of tokens to confirm their liquidity for paying fees of the withdrawals,
+    /// forced exits and `ChangePubKey` transactions.
+    pub strict_liquidity_volume: f64,
     /// Time when liquidity check results are valid
     pub available_liquidity_seconds: u64,
+    /// Maximum age of the price of the tokens meeting the `strict_liquidity_volume`
+    /// to calculate the fees in them, unless another value is set for the token.
+    pub highly_liquid_max_price_age_secs: u64,
+    /// Maximum age of the price of the other tokens suitable for paying fees. Their markets
+    /// are thinner, so their prices go stale faster.
+    pub liquid_max_price_age_secs: u64,
     /// List of the tokens that are unconditionally acceptable for paying fee in.
     pub unconditionally_valid_tokens: Vec<Address>,
     ///
@@ -59,6 +78,38 @@ impl TickerConfig {
         };
         (self.token_price_source, url)
     }
+
+    /// Returns the API URLs of the main price source followed by the fallback ones.
+    pub fn price_sources(&self) -> Vec<(TokenPriceSource, String)> {
+        let mut sources = vec![self.token_price_source];
+        for source in &self.fallback_price_sources {
+            if !sources.contains(source) {
+                sources.push(*source);
+            }
+        }
+        sources
+            .into_iter()
+            .map(|source| {
+                let url = match source {
+                    TokenPriceSource::CoinGecko => self.coingecko_base_url.clone(),
+                    TokenPriceSource::CoinMarketCap => self.coinmarketcap_base_url.clone(),
+                };
+                (source, url)
+            })
+            .collect()
+    }
+
+    pub fn price_source_timeout(&self) -> Duration {
+        Duration::from_millis(self.price_source_timeout_ms)
+    }
+
+    pub fn highly_liquid_max_price_age(&self) -> Duration {
+        Duration::from_secs(self.highly_liquid_max_price_age_secs)
+    }
+
+    pub fn liquid_max_price_age(&self) -> Duration {
+        Duration::from_secs(self.liquid_max_price_age_secs)
+    }
 }
 
 #[cfg(test)]
@@ -69,13 +120,19 @@ mod tests {
     fn expected_config() -> TickerConfig {
         TickerConfig {
             token_price_source: TokenPriceSource::CoinGecko,
+            fallback_price_sources: vec![TokenPriceSource::CoinMarketCap],
+            price_source_timeout_ms: 700,
+            price_quorum: 0,
             coinmarketcap_base_url: "http://127.0.0.1:9876".into(),
             coingecko_base_url: "http://127.0.0.1:9876".into(),
             scale_fee_percent: 100,
             fast_processing_coeff: 10.0f64,
             uniswap_url: "http://127.0.0.1:9975/graphql".to_string(),
             liquidity_volume: 100.0,
+            strict_liquidity_volume: 500.0,
             available_liquidity_seconds: 1000,
+            highly_liquid_max_price_age_secs: 3600,
+            liquid_max_price_age_secs: 900,
             unconditionally_valid_tokens: vec![addr("0000000000000000000000000000000000000000")],
             token_market_update_time: 120,
             number_of_ticker_actors: 4,
@@ -87,14 +144,20 @@ mod tests {
     fn from_env() {
         let config = r#"
 FEE_TICKER_TOKEN_PRICE_SOURCE="CoinGecko"
+FEE_TICKER_FALLBACK_PRICE_SOURCES="CoinMarketCap"
+FEE_TICKER_PRICE_SOURCE_TIMEOUT_MS=700
+FEE_TICKER_PRICE_QUORUM=0
 FEE_TICKER_COINMARKETCAP_BASE_URL="http://127.0.0.1:9876"
 FEE_TICKER_COINGECKO_BASE_URL="http://127.0.0.1:9876"
 FEE_TICKER_FAST_PROCESSING_COEFF="10"
 FEE_TICKER_UNISWAP_URL=http://127.0.0.1:9975/graphql
 FEE_TICKER_AVAILABLE_LIQUIDITY_SECONDS=1000
+FEE_TICKER_HIGHLY_LIQUID_MAX_PRICE_AGE_SECS=3600
+FEE_TICKER_LIQUID_MAX_PRICE_AGE_SECS=900
 FEE_TICKER_TOKEN_MARKET_UPDATE_TIME=120
 FEE_TICKER_UNCONDITIONALLY_VALID_TOKENS="0x0000000000000000000000000000000000000000"
 FEE_TICKER_LIQUIDITY_VOLUME=100
+FEE_TICKER_STRICT_LIQUIDITY_VOLUME=500
 FEE_TICKER_NUMBER_OF_TICKER_ACTORS="4"
 FEE_TICKER_SUBSIDIZED_TOKENS_LIMITS=156
 FEE_TICKER_SCALE_FEE_PERCENT=100
@@ -128,5 +191,25 @@ FEE_TICKER_SUBSIDY_CPK_PRICE_USD_SCALED=100
             config.price_source(),
             (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into())
         );
+        // The main source is not repeated among the fallback ones.
+        assert_eq!(
+            config.price_sources(),
+            vec![(TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into())]
+        );
+
+        config.token_price_source = TokenPriceSource::CoinGecko;
+        assert_eq!(
+            config.price_sources(),
+            vec![
+                (TokenPriceSource::CoinGecko, COINGECKO_URL.into()),
+                (TokenPriceSource::CoinMarketCap, COINMARKETCAP_URL.into())
+            ]
+        );
+        assert_eq!(config.price_source_timeout(), Duration::from_millis(700));
+        assert_eq!(
+            config.highly_liquid_max_price_age(),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(config.liquid_max_price_age(), Duration::from_secs(900));
     }
 }
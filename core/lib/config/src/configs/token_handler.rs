@@ -17,6 +17,11 @@ pub struct TokenHandlerConfig {
     pub poll_interval: u64,
     /// Link to MatterMost channel for token list notification.
     pub webhook_url: String,
+    /// The number of days the previous symbol of a renamed token is still accepted in requests.
+    pub symbol_alias_validity_days: u64,
+    /// The number of seconds between the passes querying the metadata of the tokens
+    /// stored with the placeholder symbol and decimals.
+    pub metadata_reconciliation_interval: u64,
 }
 
 impl TokenHandlerConfig {
@@ -33,6 +38,16 @@ impl TokenHandlerConfig {
         Duration::from_secs(self.poll_interval)
     }
 
+    /// Converts self.symbol_alias_validity_days into Duration.
+    pub fn symbol_alias_validity(&self) -> Duration {
+        Duration::from_secs(self.symbol_alias_validity_days * 24 * 60 * 60)
+    }
+
+    /// Converts self.metadata_reconciliation_interval into Duration.
+    pub fn metadata_reconciliation_interval(&self) -> Duration {
+        Duration::from_secs(self.metadata_reconciliation_interval)
+    }
+
     pub fn token_list(&self) -> Vec<TokenInfo> {
         let token_list_name = self.token_list_file();
         let path = format!("./etc/token-lists/{}.json", token_list_name);
@@ -52,6 +67,8 @@ mod tests {
             token_list_name: "localhost".to_string(),
             poll_interval: 1,
             webhook_url: "http://127.0.0.1".to_string(),
+            symbol_alias_validity_days: 90,
+            metadata_reconciliation_interval: 3600,
         }
     }
 
@@ -61,6 +78,8 @@ mod tests {
 TOKEN_HANDLER_POLL_INTERVAL=1
 TOKEN_HANDLER_WEBHOOK_URL="http://127.0.0.1"
 TOKEN_HANDLER_TOKEN_LIST_NAME="localhost"
+TOKEN_HANDLER_SYMBOL_ALIAS_VALIDITY_DAYS=90
+TOKEN_HANDLER_METADATA_RECONCILIATION_INTERVAL=3600
         "#;
         set_env(config);
 
@@ -20,6 +20,16 @@ const UPGRADE_GATEKEEPER_CONTRACT_FILE: &str =
 const FORCED_EXIT_CONTRACT_FILE: &str =
     "contracts/artifacts/cache/solpp-generated-contracts/ForcedExit.sol/ForcedExit.json";
 
+/// Optional metadata methods of the ERC20 standard, not included into `IERC20`.
+const ERC20_METADATA_ABI: &str = r#"[
+    {"inputs": [], "name": "symbol", "outputs": [{"name": "", "type": "string"}], "stateMutability": "view", "type": "function"},
+    {"inputs": [], "name": "decimals", "outputs": [{"name": "", "type": "uint8"}], "stateMutability": "view", "type": "function"}
+]"#;
+/// Some of the older tokens (e.g. MKR) return the symbol as `bytes32`.
+const ERC20_BYTES32_METADATA_ABI: &str = r#"[
+    {"inputs": [], "name": "symbol", "outputs": [{"name": "", "type": "bytes32"}], "stateMutability": "view", "type": "function"}
+]"#;
+
 fn read_file_to_json_value(path: &str) -> io::Result<serde_json::Value> {
     let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
     let path = std::path::Path::new(&zksync_home).join(path);
@@ -91,6 +101,14 @@ pub fn erc20_contract() -> Contract {
     Contract::load(abi_string.as_bytes()).expect("erc20 contract abi")
 }
 
+pub fn erc20_metadata_contract() -> Contract {
+    Contract::load(ERC20_METADATA_ABI.as_bytes()).expect("erc20 metadata abi")
+}
+
+pub fn erc20_bytes32_metadata_contract() -> Contract {
+    Contract::load(ERC20_BYTES32_METADATA_ABI.as_bytes()).expect("erc20 bytes32 metadata abi")
+}
+
 pub fn eip1271_contract() -> Contract {
     let abi_string = read_file_to_json_value(IEIP1271_CONTRACT_FILE)
         .expect("couldn't read IEIP1271_CONTRACT_FILE")
@@ -270,7 +270,7 @@ impl FloatConversions {
         // 2) with that minimal exponent minus 1
         let mut exponent: usize = 0;
         let mut exponent_temp: u128 = 1;
-        while integer > max_mantissa * exponent_temp {
+        while integer > max_mantissa.saturating_mul(exponent_temp) {
             exponent_temp *= exponent_base;
             exponent += 1;
         }
@@ -279,7 +279,7 @@ impl FloatConversions {
         } else {
             let mantissa = integer / exponent_temp;
             let variant1 = mantissa * exponent_temp;
-            let variant2 = max_mantissa * exponent_temp / exponent_base;
+            let variant2 = max_mantissa * (exponent_temp / exponent_base);
             let diff1 = integer - variant1;
             let diff2 = integer - variant2;
             if diff1 < diff2 {
@@ -340,7 +340,7 @@ impl FloatConversions {
         // then mantissa is calculated as integer divided by exponent_base ^ exponent and rounded up
         let mut exponent: usize = 0;
         let mut exponent_temp: u128 = 1;
-        while integer > max_mantissa * exponent_temp {
+        while integer > max_mantissa.saturating_mul(exponent_temp) {
             exponent_temp *= exponent_base;
             exponent += 1;
         }
@@ -435,6 +435,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
             Some(TransactionReceipt {
                 block_number: Some(tx_block_number),
                 status: Some(status),
+                gas_used,
                 ..
             }) => {
                 let current_block = match current_block {
@@ -454,6 +455,7 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
                 Ok(Some(ExecutedTxStatus {
                     confirmations,
                     success,
+                    gas_used,
                     receipt,
                 }))
             }
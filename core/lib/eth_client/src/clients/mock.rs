@@ -22,6 +22,7 @@ struct MockEthereumInner {
     gas_price: U256,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
+    contract_calls: Arc<RwLock<HashMap<(Address, String), Vec<ethabi::Token>>>>,
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -37,6 +38,7 @@ impl Default for MockEthereumInner {
             gas_price: 100.into(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
+            contract_calls: Default::default(),
         }
     }
 }
@@ -82,6 +84,7 @@ impl MockEthereum {
         let status = ExecutedTxStatus {
             confirmations,
             success: true,
+            gas_used: None,
             receipt: None,
         };
         self.inner.tx_statuses.write().await.insert(tx_hash, status);
@@ -94,10 +97,27 @@ impl MockEthereum {
         let status = ExecutedTxStatus {
             confirmations,
             success: false,
+            gas_used: None,
             receipt: Some(Default::default()),
         };
         self.inner.tx_statuses.write().await.insert(*hash, status);
     }
+
+    /// Sets the output of the contract function, calls of the functions
+    /// without the output fail as if the call was reverted.
+    pub async fn add_contract_call(
+        &mut self,
+        address: Address,
+        func: &str,
+        output: Vec<ethabi::Token>,
+    ) {
+        self.inner
+            .contract_calls
+            .write()
+            .await
+            .insert((address, func.to_owned()), output);
+    }
+
     pub async fn get_tx_status(
         &self,
         hash: H256,
@@ -241,12 +261,12 @@ impl MockEthereum {
     #[allow(clippy::too_many_arguments)]
     pub async fn call_contract_function<R, A, B, P>(
         &self,
-        _func: &str,
+        func: &str,
         _params: P,
         _from: A,
         _options: Options,
         _block: B,
-        _token_address: Address,
+        token_address: Address,
         _erc20_abi: ethabi::Contract,
     ) -> Result<R, anyhow::Error>
     where
@@ -255,7 +275,16 @@ impl MockEthereum {
         B: Into<Option<BlockId>>,
         P: Tokenize,
     {
-        todo!()
+        let output = self
+            .inner
+            .contract_calls
+            .read()
+            .await
+            .get(&(token_address, func.to_owned()))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Call of {} reverted", func))?;
+        // Output of the unexpected type fails to decode, just like the real one.
+        Ok(R::from_tokens(output)?)
     }
 
     pub fn create_contract(
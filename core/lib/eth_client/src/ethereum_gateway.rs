@@ -28,6 +28,8 @@ pub struct ExecutedTxStatus {
     pub confirmations: u64,
     /// Whether transaction was executed successfully or failed.
     pub success: bool,
+    /// Amount of gas used by the transaction.
+    pub gas_used: Option<U256>,
     /// Receipt for a transaction. Will be set to `Some` only if the transaction
     /// failed during execution.
     pub receipt: Option<TransactionReceipt>,
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
 use futures::channel::{mpsc, oneshot};
 use futures::StreamExt;
 
@@ -9,6 +12,13 @@ use zksync_types::{
 
 use crate::state::MempoolState;
 
+/// How often records about evicted transactions are checked for being outdated.
+const EVICTED_TXS_PRUNING_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Proposed transactions are considered to be included into a block for this period, so they aren't
+/// cancelled while the block is being sealed and stored. Transactions of the pending block are reported
+/// by the state keeper with every request, so they're retained until the block is sealed.
+const PROPOSED_TXS_RETENTION: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Clone, Debug, Default)]
 pub struct ProposedBlock {
     pub priority_ops: Vec<PriorityOp>,
@@ -37,16 +47,60 @@ pub struct GetBlockRequest {
     pub response_sender: oneshot::Sender<ProposedBlock>,
 }
 
+#[derive(Debug)]
+pub struct CancelTxRequest {
+    pub tx_hash: TxHash,
+    /// Receives `true` if the transaction is removed from the mempool, and `false` if it's
+    /// already proposed for a block, is a part of a batch or is not in the mempool anymore.
+    pub response_sender: oneshot::Sender<Result<bool, TxAddError>>,
+}
+
+#[derive(Debug)]
+pub struct IsTxProposedRequest {
+    pub tx_hash: TxHash,
+    /// Receives `true` if the transaction is recently proposed for a block.
+    pub response_sender: oneshot::Sender<bool>,
+}
+
 #[derive(Debug)]
 pub enum MempoolBlocksRequest {
     /// Get transactions from the mempool.
     GetBlock(GetBlockRequest),
+    /// Remove the transaction from the mempool unless it's already proposed for a block.
+    /// Requests are processed one by one, so the transaction is never removed while being proposed.
+    CancelTx(CancelTxRequest),
+    /// Check whether the transaction is recently proposed for a block, it may be not stored
+    /// with the block yet.
+    IsTxProposed(IsTxProposedRequest),
+}
+
+/// Transactions recently proposed for the blocks along with the time they were last proposed.
+#[derive(Debug, Default)]
+pub(crate) struct ProposedTxs(HashMap<TxHash, Instant>);
+
+impl ProposedTxs {
+    fn record(&mut self, tx_hashes: impl IntoIterator<Item = TxHash>, now: Instant) {
+        for tx_hash in tx_hashes {
+            self.0.insert(tx_hash, now);
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        self.0
+            .retain(|_, proposed_at| now.duration_since(*proposed_at) < PROPOSED_TXS_RETENTION);
+    }
+
+    fn contains(&self, tx_hash: &TxHash) -> bool {
+        self.0.contains_key(tx_hash)
+    }
 }
 
 pub(crate) struct MempoolBlocksHandler {
     pub mempool_state: MempoolState,
     pub requests: mpsc::Receiver<MempoolBlocksRequest>,
     pub max_block_size_chunks: usize,
+    pub rejected_txs_retention_period: Duration,
+    pub proposed_txs: ProposedTxs,
 }
 
 impl MempoolBlocksHandler {
@@ -56,12 +110,12 @@ impl MempoolBlocksHandler {
         block_timestamp: u64,
         executed_txs: &[TxHash],
     ) -> Result<ProposedBlock, TxAddError> {
-        let start = std::time::Instant::now();
+        let start = Instant::now();
         // Try to exhaust the reverted transactions queue. Most of the time it
         // will be empty unless the server is restarted after reverting blocks.
         let mut tx_queue = self
             .mempool_state
-            .get_transaction_queue(executed_txs)
+            .get_transaction_queue(executed_txs, block_timestamp)
             .await?;
 
         let (txs, priority_ops, chunks_left) = tx_queue
@@ -107,13 +161,30 @@ impl MempoolBlocksHandler {
         Ok(ProposedBlock { priority_ops, txs })
     }
 
+    async fn cancel_tx(&mut self, tx_hash: TxHash) -> Result<bool, TxAddError> {
+        if self.proposed_txs.contains(&tx_hash) {
+            return Ok(false);
+        }
+        self.mempool_state.cancel_tx(tx_hash).await
+    }
+
     pub async fn run(mut self) {
         vlog::info!("Block mempool handler is running");
         // We have to clean garbage from mempool before running the block generator.
         // Remove any possible duplicates of already executed transactions
         // from the database.
         self.mempool_state.collect_garbage().await;
+        self.mempool_state
+            .remove_outdated_evicted_txs(self.rejected_txs_retention_period)
+            .await;
+        let mut last_pruning = Instant::now();
         while let Some(request) = self.requests.next().await {
+            if last_pruning.elapsed() >= EVICTED_TXS_PRUNING_INTERVAL {
+                self.mempool_state
+                    .remove_outdated_evicted_txs(self.rejected_txs_retention_period)
+                    .await;
+                last_pruning = Instant::now();
+            }
             match request {
                 MempoolBlocksRequest::GetBlock(block) => {
                     // Generate proposed block.
@@ -126,13 +197,61 @@ impl MempoolBlocksHandler {
                         .await
                         .expect("Unable to propose the new miniblock");
 
+                    let now = Instant::now();
+                    self.proposed_txs.record(block.executed_txs, now);
+                    self.proposed_txs.record(
+                        proposed_block.txs.iter().flat_map(SignedTxVariant::hashes),
+                        now,
+                    );
+                    self.proposed_txs.prune(now);
+
                     // Send the proposed block to the request initiator.
                     block
                         .response_sender
                         .send(proposed_block)
                         .expect("Mempool request receiver dropped");
                 }
+                MempoolBlocksRequest::CancelTx(request) => {
+                    let cancelled = self.cancel_tx(request.tx_hash).await;
+                    request.response_sender.send(cancelled).unwrap_or_default();
+                }
+                MempoolBlocksRequest::IsTxProposed(request) => {
+                    let proposed = self.proposed_txs.contains(&request.tx_hash);
+                    request.response_sender.send(proposed).unwrap_or_default();
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks that the proposed transactions are retained while the state keeper reports them
+    /// as executed in the pending block, and for a while after the block is sealed.
+    #[test]
+    fn proposed_txs_retention() {
+        let tx_hashes: Vec<_> = (0..3u8)
+            .map(|byte| TxHash::from_slice(&[byte; 32]).unwrap())
+            .collect();
+        let start = Instant::now();
+        let mut proposed_txs = ProposedTxs::default();
+        proposed_txs.record(tx_hashes[..2].to_vec(), start);
+        assert!(proposed_txs.contains(&tx_hashes[0]));
+        assert!(proposed_txs.contains(&tx_hashes[1]));
+        assert!(!proposed_txs.contains(&tx_hashes[2]));
+
+        // The first transaction is still in the pending block, the second one is in the sealed block.
+        let later = start + PROPOSED_TXS_RETENTION / 2;
+        proposed_txs.record(vec![tx_hashes[0]], later);
+        proposed_txs.prune(later);
+        assert!(proposed_txs.contains(&tx_hashes[0]));
+        assert!(proposed_txs.contains(&tx_hashes[1]));
+
+        let expired = start + PROPOSED_TXS_RETENTION;
+        proposed_txs.prune(expired);
+        assert!(proposed_txs.contains(&tx_hashes[0]));
+        assert!(!proposed_txs.contains(&tx_hashes[1]));
+    }
+}
@@ -10,6 +10,9 @@
 //! Communication channel with other actors:
 //! Mempool does not push information to other actors, only accepts requests. (see `MempoolRequest`)
 
+// Built-in uses
+use std::time::Duration;
+
 // External uses
 use futures::channel::mpsc;
 
@@ -20,7 +23,9 @@ use zksync_storage::ConnectionPool;
 
 // Local uses
 use crate::block_handler::MempoolBlocksHandler;
-pub use crate::block_handler::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
+pub use crate::block_handler::{
+    CancelTxRequest, GetBlockRequest, IsTxProposedRequest, MempoolBlocksRequest, ProposedBlock,
+};
 use crate::mempool_transactions_queue::MempoolTransactionsQueue;
 use crate::state::MempoolState;
 pub use crate::transactions_handler::MempoolTransactionRequest;
@@ -58,6 +63,7 @@ pub fn run_mempool_block_handler(
     db_pool: ConnectionPool,
     block_requests: mpsc::Receiver<MempoolBlocksRequest>,
     block_chunk_sizes: Vec<usize>,
+    rejected_txs_retention_period: Duration,
 ) -> JoinHandle<()> {
     let mempool_state = MempoolState::new(db_pool);
     let max_block_size_chunks = *block_chunk_sizes
@@ -69,6 +75,8 @@ pub fn run_mempool_block_handler(
         mempool_state,
         requests: block_requests,
         max_block_size_chunks,
+        rejected_txs_retention_period,
+        proposed_txs: Default::default(),
     };
 
     tokio::spawn(blocks_handler.run())
@@ -1,5 +1,7 @@
-use std::collections::HashSet;
-use std::time::Instant;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
 
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
@@ -10,6 +12,9 @@ use zksync_types::{
 
 use crate::MempoolTransactionsQueue;
 
+/// Reason recorded for the transactions evicted from the mempool because of the `valid_until` field.
+pub(crate) const EXPIRED_TX_REASON: &str = "Transaction has expired";
+
 #[derive(Debug, Clone)]
 pub(crate) struct MempoolState {
     db_pool: ConnectionPool,
@@ -114,9 +119,43 @@ impl MempoolState {
         Self { db_pool }
     }
 
+    /// Removes records about evicted transactions which are older than the retention period.
+    pub async fn remove_outdated_evicted_txs(&self, retention_period: Duration) {
+        let mut storage = self.db_pool.access_storage().await.expect("Db error");
+        let oldest_timestamp = Utc::now()
+            - chrono::Duration::from_std(retention_period).expect("Retention period is too big");
+        let removed = storage
+            .chain()
+            .mempool_schema()
+            .remove_evicted_txs_older_than(oldest_timestamp)
+            .await
+            .expect("Db error");
+        if removed > 0 {
+            vlog::debug!("Removed {} outdated evicted transactions", removed);
+        }
+    }
+
+    /// Removes the transaction from the mempool on request of its submitter.
+    /// Returns `false` if the transaction is not in the mempool or is a part of a batch.
+    pub async fn cancel_tx(&self, tx_hash: TxHash) -> Result<bool, TxAddError> {
+        self.db_pool
+            .access_storage()
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .chain()
+            .mempool_schema()
+            .cancel_tx(tx_hash)
+            .await
+            .map_err(|_| TxAddError::DbError)
+    }
+
+    /// Loads the transactions queue from the database.
+    /// Transactions that can't be executed anymore because of the `valid_until` field
+    /// are evicted from the mempool instead of being passed to the queue.
     pub async fn get_transaction_queue(
         &self,
         executed_txs: &[TxHash],
+        block_timestamp: u64,
     ) -> Result<MempoolTransactionsQueue, TxAddError> {
         let mut storage = self
             .db_pool
@@ -144,6 +183,30 @@ impl MempoolState {
             .await
             .map_err(|_| TxAddError::DbError)?;
 
+        // A batch is executed atomically, so it's evicted entirely if any of its transactions is expired.
+        let (expired_txs, mempool_txs): (Vec<_>, VecDeque<_>) =
+            mempool_txs.into_iter().partition(|tx| {
+                tx.get_transactions()
+                    .iter()
+                    .any(|tx| tx.tx.time_range().valid_until < block_timestamp)
+            });
+        if !expired_txs.is_empty() {
+            vlog::debug!(
+                "Evicting {} expired transactions from the mempool",
+                expired_txs.len()
+            );
+            transaction
+                .chain()
+                .mempool_schema()
+                .evict_txs(&expired_txs, EXPIRED_TX_REASON)
+                .await
+                .map_err(|_| TxAddError::DbError)?;
+        }
+        transaction
+            .commit()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+
         let transactions_queue = MempoolTransactionsQueue::new(priority_ops, mempool_txs);
 
         Ok(transactions_queue)
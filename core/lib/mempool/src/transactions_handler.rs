@@ -50,6 +50,18 @@ impl MempoolTransactionsHandler {
             TxAddError::DbError
         })?;
 
+        // The same transaction may be submitted again, e.g. when the API server retries
+        // the forwarding after a failure. Such submissions are accepted without any changes.
+        let is_known = storage
+            .chain()
+            .mempool_schema()
+            .is_tx_known(tx.hash())
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        if is_known {
+            return Ok(());
+        }
+
         let nonce = storage
             .chain()
             .account_schema()
@@ -149,6 +161,18 @@ impl MempoolTransactionsHandler {
             TxAddError::DbError
         })?;
 
+        // Batches are repeated the same way as the single transactions. The batch is stored
+        // and executed all at once, so it's enough to check its first transaction.
+        let is_known = storage
+            .chain()
+            .mempool_schema()
+            .is_tx_known(txs.first().ok_or(TxAddError::EmptyBatch)?.hash())
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        if is_known {
+            return Ok(());
+        }
+
         for tx in txs.iter() {
             // Correctness should be checked by `signature_checker`, thus
             // `tx.check_correctness()` is not invoked here.
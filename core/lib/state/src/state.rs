@@ -91,6 +91,21 @@ impl ZkSyncState {
         empty
     }
 
+    /// Creates the state containing only a part of the accounts, e.g. the accounts affected
+    /// by a transaction to execute it without the whole state. `next_free_id` is the identifier
+    /// of the next created account, since it can't be derived from the part of the accounts.
+    /// The root hash of such a state is meaningless.
+    pub fn from_partial_acc_map(
+        accounts: AccountMap,
+        nfts: HashMap<TokenId, NFT>,
+        next_free_id: AccountId,
+    ) -> Self {
+        let mut state = Self::from_acc_map(accounts);
+        state.nfts = nfts;
+        state.next_free_id = std::cmp::max(state.next_free_id, next_free_id);
+        state
+    }
+
     pub fn new(
         balance_tree: AccountTree,
         account_id_by_address: HashMap<Address, AccountId>,
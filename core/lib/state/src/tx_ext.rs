@@ -1,5 +1,5 @@
 use crate::error::OpError;
-use zksync_types::ZkSyncTx;
+use zksync_types::{tx::preconditions::check_time_range, ZkSyncTx};
 
 pub(super) trait TxCheck {
     fn check_timestamp(&self, block_timestamp: u64) -> Result<(), OpError>;
@@ -7,7 +7,7 @@ pub(super) trait TxCheck {
 
 impl TxCheck for ZkSyncTx {
     fn check_timestamp(&self, block_timestamp: u64) -> Result<(), OpError> {
-        if !self.time_range().is_valid(block_timestamp) {
+        if check_time_range(&self.time_range(), block_timestamp).is_some() {
             return Err(OpError::TimestampError);
         }
         Ok(())
@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS notify_disabled_tx_types_listener ON disabled_tx_types;
+DROP FUNCTION IF EXISTS notify_disabled_tx_types_channel();
+DROP TABLE IF EXISTS disabled_tx_types;
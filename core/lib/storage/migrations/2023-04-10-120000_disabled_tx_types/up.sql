@@ -0,0 +1,18 @@
+-- Types of L2 transactions that are temporarily not accepted by the API.
+CREATE TABLE disabled_tx_types (
+    tx_type TEXT PRIMARY KEY,
+    disabled_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE OR REPLACE FUNCTION notify_disabled_tx_types_channel() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM (
+        SELECT pg_notify('disabled_tx_types_channel', TG_OP)
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_disabled_tx_types_listener
+AFTER INSERT OR DELETE ON disabled_tx_types
+FOR EACH ROW EXECUTE PROCEDURE notify_disabled_tx_types_channel();
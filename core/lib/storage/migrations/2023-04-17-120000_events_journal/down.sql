@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS events_block_number_event_type_idx;
+DROP INDEX IF EXISTS events_created_at_idx;
+
+ALTER TABLE events DROP COLUMN IF EXISTS created_at;
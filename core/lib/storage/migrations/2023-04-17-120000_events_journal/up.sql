@@ -0,0 +1,6 @@
+-- Events are replayed by the API consumers, so the creation time is required
+-- to prune outdated events.
+ALTER TABLE events ADD COLUMN created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS events_created_at_idx ON events (created_at);
+CREATE INDEX IF NOT EXISTS events_block_number_event_type_idx ON events (block_number, event_type);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS mempool_evicted_txs_evicted_at_idx;
+DROP INDEX IF EXISTS mempool_evicted_txs_address_evicted_at_idx;
+DROP TABLE IF EXISTS mempool_evicted_txs;
@@ -0,0 +1,14 @@
+-- Transactions removed from the mempool without being executed (e.g. expired ones)
+-- are kept here for some time, so the users can find out what happened to them.
+CREATE TABLE IF NOT EXISTS mempool_evicted_txs
+(
+    id          BIGSERIAL PRIMARY KEY,
+    tx_hash     BYTEA NOT NULL,
+    address     BYTEA NOT NULL,
+    tx          JSONB NOT NULL,
+    fail_reason TEXT NOT NULL,
+    evicted_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS mempool_evicted_txs_address_evicted_at_idx ON mempool_evicted_txs (address, evicted_at DESC);
+CREATE INDEX IF NOT EXISTS mempool_evicted_txs_evicted_at_idx ON mempool_evicted_txs (evicted_at);
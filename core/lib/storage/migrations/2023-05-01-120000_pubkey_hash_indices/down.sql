@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS account_pubkey_updates_new_pubkey_hash_idx;
+DROP INDEX IF EXISTS accounts_pubkey_hash_idx;
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS accounts_pubkey_hash_idx ON "accounts" USING hash (pubkey_hash);
+CREATE INDEX IF NOT EXISTS account_pubkey_updates_new_pubkey_hash_idx ON "account_pubkey_updates" USING hash (new_pubkey_hash);
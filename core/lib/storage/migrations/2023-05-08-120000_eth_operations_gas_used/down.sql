@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS eth_tx_hashes_tx_hash_idx;
+DROP TABLE IF EXISTS eth_operations_gas_used;
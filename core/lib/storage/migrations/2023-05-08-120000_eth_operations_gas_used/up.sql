@@ -0,0 +1,7 @@
+-- Gas actually used by the confirmed Ethereum operations.
+CREATE TABLE IF NOT EXISTS eth_operations_gas_used (
+    eth_op_id BIGINT PRIMARY KEY REFERENCES eth_operations (id) ON DELETE CASCADE,
+    gas_used BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS eth_tx_hashes_tx_hash_idx ON eth_tx_hashes USING hash (tx_hash);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS tx_submission_outbox_delivered_at_idx;
+DROP INDEX IF EXISTS tx_submission_outbox_undelivered_idx;
+DROP TABLE IF EXISTS tx_submission_outbox;
@@ -0,0 +1,18 @@
+-- Validated transactions accepted by the API server, but not yet forwarded to the mempool.
+-- Entries are identified by the transaction hash, so resubmissions of the same transaction
+-- don't produce duplicates.
+CREATE TABLE IF NOT EXISTS tx_submission_outbox
+(
+    tx_hash          BYTEA PRIMARY KEY,
+    tx               JSONB NOT NULL,
+    eth_sign_data    JSONB,
+    created_at       TIMESTAMP WITH TIME ZONE NOT NULL,
+    attempts         INT NOT NULL DEFAULT 0,
+    last_error       TEXT,
+    delivered_at     TIMESTAMP WITH TIME ZONE,
+    rejection_reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS tx_submission_outbox_undelivered_idx ON tx_submission_outbox (created_at)
+    WHERE delivered_at IS NULL;
+CREATE INDEX IF NOT EXISTS tx_submission_outbox_delivered_at_idx ON tx_submission_outbox (delivered_at);
@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS notify_banned_addresses_listener ON banned_addresses;
+DROP FUNCTION IF EXISTS notify_banned_addresses_channel();
+DROP TABLE IF EXISTS banned_addresses_history;
+DROP TABLE IF EXISTS banned_addresses;
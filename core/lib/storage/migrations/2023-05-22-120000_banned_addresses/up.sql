@@ -0,0 +1,31 @@
+-- Addresses that are not allowed to submit transactions or receive funds.
+CREATE TABLE banned_addresses (
+    address BYTEA PRIMARY KEY,
+    reason TEXT NOT NULL,
+    banned_by TEXT NOT NULL,
+    banned_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+-- Audit log of the changes of the banned addresses list.
+CREATE TABLE banned_addresses_history (
+    id BIGSERIAL PRIMARY KEY,
+    address BYTEA NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('ban', 'unban')),
+    reason TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+CREATE INDEX banned_addresses_history_address_idx ON banned_addresses_history (address);
+
+CREATE OR REPLACE FUNCTION notify_banned_addresses_channel() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM (
+        SELECT pg_notify('banned_addresses_channel', TG_OP)
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_banned_addresses_listener
+AFTER INSERT OR DELETE ON banned_addresses
+FOR EACH ROW EXECUTE PROCEDURE notify_banned_addresses_channel();
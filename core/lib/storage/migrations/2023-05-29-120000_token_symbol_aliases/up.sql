@@ -0,0 +1,8 @@
+-- Previous symbols of the renamed tokens, they are accepted in the token lookups until `valid_until`.
+CREATE TABLE token_symbol_aliases (
+    token_id INTEGER NOT NULL REFERENCES tokens(id) ON DELETE CASCADE,
+    symbol TEXT NOT NULL,
+    valid_until TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (token_id, symbol)
+);
+CREATE INDEX token_symbol_aliases_symbol_idx ON token_symbol_aliases (lower(symbol));
@@ -0,0 +1,2 @@
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS recipient_address;
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS account_address;
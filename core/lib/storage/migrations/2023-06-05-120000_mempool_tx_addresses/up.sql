@@ -0,0 +1,15 @@
+-- Account that initiates the transaction and the L2 recipient of the funds (only set for transfers),
+-- used to find the pending transactions of the account.
+ALTER TABLE mempool_txs ADD COLUMN account_address BYTEA;
+ALTER TABLE mempool_txs ADD COLUMN recipient_address BYTEA;
+
+UPDATE mempool_txs SET
+    account_address = decode(substring(COALESCE(
+        tx->>'from', tx->>'account', tx->>'target', tx->>'submitterAddress', tx->>'creatorAddress'
+    ) FROM 3), 'hex'),
+    recipient_address = CASE
+        WHEN tx->>'type' = 'Transfer' THEN decode(substring(tx->>'to' FROM 3), 'hex')
+    END;
+
+CREATE INDEX mempool_txs_account_address_idx ON mempool_txs (account_address);
+CREATE INDEX mempool_txs_recipient_address_idx ON mempool_txs (recipient_address);
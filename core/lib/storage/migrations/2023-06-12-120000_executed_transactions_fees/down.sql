@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS store_executed_transaction_fee_tr ON executed_transactions;
+DROP FUNCTION IF EXISTS store_executed_transaction_fee;
+DROP TABLE IF EXISTS executed_transactions_fees;
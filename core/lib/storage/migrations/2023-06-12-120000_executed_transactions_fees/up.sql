@@ -0,0 +1,46 @@
+-- Fees paid by the successfully executed transactions, extracted from the transaction JSON,
+-- so the fee statistics don't have to parse every stored transaction.
+CREATE TABLE executed_transactions_fees (
+    tx_hash BYTEA PRIMARY KEY REFERENCES executed_transactions (tx_hash) ON DELETE CASCADE,
+    tx_type TEXT NOT NULL,
+    fee_token INT NOT NULL,
+    fee NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX executed_transactions_fees_type_token_created_at_idx
+    ON executed_transactions_fees (tx_type, fee_token, created_at);
+
+-- `Close` has no fee, NFT withdrawals and the transactions with the fee in a separate token
+-- store it in the `feeToken` field.
+CREATE OR REPLACE FUNCTION store_executed_transaction_fee() RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.success AND NEW.tx->>'fee' IS NOT NULL THEN
+        INSERT INTO executed_transactions_fees (tx_hash, tx_type, fee_token, fee, created_at)
+        VALUES (
+            NEW.tx_hash,
+            NEW.tx->>'type',
+            COALESCE(NEW.tx->>'feeToken', NEW.tx->>'token')::INT,
+            (NEW.tx->>'fee')::NUMERIC,
+            NEW.created_at
+        )
+        ON CONFLICT (tx_hash) DO UPDATE
+        SET tx_type = EXCLUDED.tx_type, fee_token = EXCLUDED.fee_token,
+            fee = EXCLUDED.fee, created_at = EXCLUDED.created_at;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER store_executed_transaction_fee_tr AFTER INSERT OR UPDATE ON executed_transactions
+    FOR EACH ROW EXECUTE FUNCTION store_executed_transaction_fee();
+
+INSERT INTO executed_transactions_fees (tx_hash, tx_type, fee_token, fee, created_at)
+SELECT
+    tx_hash,
+    tx->>'type',
+    COALESCE(tx->>'feeToken', tx->>'token')::INT,
+    (tx->>'fee')::NUMERIC,
+    created_at
+FROM executed_transactions
+WHERE success = true AND tx->>'fee' IS NOT NULL;
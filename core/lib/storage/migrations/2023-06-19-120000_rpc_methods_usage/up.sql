@@ -0,0 +1,10 @@
+-- Daily number of calls of the JSON-RPC methods. `subnet_hash` is the hash of the
+-- caller's subnet, empty string is used when the per-subnet tracking is disabled.
+CREATE TABLE rpc_methods_usage (
+    method TEXT NOT NULL,
+    day DATE NOT NULL,
+    subnet_hash TEXT NOT NULL DEFAULT '',
+    calls BIGINT NOT NULL,
+    PRIMARY KEY (method, day, subnet_hash)
+);
+CREATE INDEX rpc_methods_usage_day_idx ON rpc_methods_usage (day);
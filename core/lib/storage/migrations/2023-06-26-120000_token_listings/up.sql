@@ -0,0 +1,11 @@
+-- Progress of adding the tokens to the network, filled by the token handler.
+-- The row is created once the governance event adding the token is confirmed,
+-- `token_stored_at` is set once the token is stored in the `tokens` table.
+CREATE TABLE token_listings (
+    address BYTEA PRIMARY KEY,
+    token_id INTEGER NOT NULL,
+    eth_block_number BIGINT NOT NULL,
+    event_processed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    token_stored_at TIMESTAMP WITH TIME ZONE,
+    store_error TEXT
+);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS mempool_evicted_txs_batch_id_idx;
+ALTER TABLE mempool_evicted_txs DROP COLUMN IF EXISTS batch_id;
@@ -0,0 +1,5 @@
+-- Batches are evicted from the mempool as a whole, so the batch of the evicted
+-- transaction is kept to report the status of the batch.
+ALTER TABLE mempool_evicted_txs ADD COLUMN batch_id BIGINT;
+
+CREATE INDEX IF NOT EXISTS mempool_evicted_txs_batch_id_idx ON mempool_evicted_txs (batch_id);
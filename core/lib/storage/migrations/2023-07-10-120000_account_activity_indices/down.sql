@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS executed_transactions_from_account_seq_idx;
+DROP INDEX IF EXISTS executed_transactions_to_account_seq_idx;
+DROP INDEX IF EXISTS executed_priority_operations_from_account_seq_idx;
+DROP INDEX IF EXISTS executed_priority_operations_to_account_seq_idx;
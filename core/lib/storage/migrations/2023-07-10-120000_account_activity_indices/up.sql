@@ -0,0 +1,6 @@
+-- Indices for the account activity feed, which pages through the executed operations
+-- of the account by the sequence number.
+CREATE INDEX IF NOT EXISTS executed_transactions_from_account_seq_idx ON executed_transactions (from_account, sequence_number);
+CREATE INDEX IF NOT EXISTS executed_transactions_to_account_seq_idx ON executed_transactions (to_account, sequence_number);
+CREATE INDEX IF NOT EXISTS executed_priority_operations_from_account_seq_idx ON executed_priority_operations (from_account, sequence_number);
+CREATE INDEX IF NOT EXISTS executed_priority_operations_to_account_seq_idx ON executed_priority_operations (to_account, sequence_number);
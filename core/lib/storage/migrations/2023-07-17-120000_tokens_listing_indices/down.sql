@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS tokens_kind_id_idx;
+DROP INDEX IF EXISTS tokens_lower_symbol_idx;
@@ -0,0 +1,4 @@
+-- Indices for the tokens listing, which pages through the tokens of the given kind by id
+-- and may be filtered by the symbol prefix.
+CREATE INDEX IF NOT EXISTS tokens_kind_id_idx ON tokens (kind, id);
+CREATE INDEX IF NOT EXISTS tokens_lower_symbol_idx ON tokens (lower(symbol) text_pattern_ops);
@@ -0,0 +1,9 @@
+-- Metadata of the tokens queried from their contracts by the token handler.
+-- `NULL` symbol or decimals mean that the contract failed to return the value,
+-- so the placeholder values of the token are kept.
+CREATE TABLE token_metadata (
+    token_id INTEGER PRIMARY KEY,
+    symbol TEXT,
+    decimals SMALLINT,
+    fetched_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
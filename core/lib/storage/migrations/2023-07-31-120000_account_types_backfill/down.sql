@@ -0,0 +1 @@
+-- Backfilled types can't be told apart from the ones stored by the server, so they are kept.
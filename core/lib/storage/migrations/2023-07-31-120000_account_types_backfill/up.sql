@@ -0,0 +1,10 @@
+-- Accounts which set their keys before the account types were tracked don't have
+-- the type stored, so it's taken from the latest executed `ChangePubKey` of the account.
+INSERT INTO eth_account_types (account_id, account_type)
+SELECT DISTINCT ON ((tx->>'accountId')::BIGINT)
+    (tx->>'accountId')::BIGINT,
+    (CASE WHEN tx->'ethAuthData'->>'type' = 'CREATE2' THEN 'CREATE2' ELSE 'Owned' END)::eth_account_type
+FROM executed_transactions
+WHERE tx->>'type' = 'ChangePubKey' AND success
+ORDER BY (tx->>'accountId')::BIGINT, block_number DESC, sequence_number DESC NULLS LAST
+ON CONFLICT (account_id) DO NOTHING;
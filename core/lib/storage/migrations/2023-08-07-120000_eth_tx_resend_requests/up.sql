@@ -0,0 +1,7 @@
+-- Requests to resend the transaction of the Ethereum operation with a higher gas price.
+-- Requests are submitted via the core private API and taken by the Ethereum sender,
+-- so there is at most one pending request per operation.
+CREATE TABLE eth_tx_resend_requests (
+    eth_op_id BIGINT PRIMARY KEY REFERENCES eth_operations (id),
+    requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,13 @@
+-- Tokens granting third parties read-only access to the history of a single account.
+-- Tokens are issued for the delegation signed by the account owner, only the hashes
+-- of the tokens are stored.
+CREATE TABLE account_access_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    token_hash BYTEA NOT NULL UNIQUE,
+    address BYTEA NOT NULL,
+    scope TEXT NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX account_access_tokens_address_idx ON account_access_tokens (address);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS executed_transactions_batch_id_idx;
+DROP TABLE IF EXISTS tx_batch_summaries;
@@ -0,0 +1,48 @@
+-- Summary of the batches executed in blocks, so that the batch info is loaded
+-- without resolving the statuses of the batch members.
+CREATE TABLE IF NOT EXISTS tx_batch_summaries (
+    batch_id BIGINT PRIMARY KEY,
+    batch_hash BYTEA NOT NULL,
+    member_count BIGINT NOT NULL,
+    -- Block and status of the first member of the batch, they are the same for all the members.
+    block_number BIGINT NOT NULL,
+    success BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- Creation time of the aggregated operations committing and executing the block.
+    committed_at TIMESTAMP WITH TIME ZONE,
+    finalized_at TIMESTAMP WITH TIME ZONE
+);
+CREATE INDEX IF NOT EXISTS tx_batch_summaries_batch_hash_idx ON tx_batch_summaries (batch_hash);
+CREATE INDEX IF NOT EXISTS tx_batch_summaries_block_number_idx ON tx_batch_summaries (block_number);
+CREATE INDEX IF NOT EXISTS executed_transactions_batch_id_idx ON executed_transactions (batch_id);
+
+-- Backfill the summaries of the batches executed before the table was created.
+INSERT INTO tx_batch_summaries (
+    batch_id, batch_hash, member_count, block_number, success, created_at, committed_at, finalized_at
+)
+SELECT
+    members.batch_id,
+    txs_batches_hashes.batch_hash,
+    members.member_count,
+    first_tx.block_number,
+    first_tx.success,
+    first_tx.created_at,
+    (
+        SELECT created_at FROM aggregate_operations
+        WHERE action_type = 'CommitBlocks' AND first_tx.block_number BETWEEN from_block AND to_block
+        ORDER BY id LIMIT 1
+    ),
+    (
+        SELECT created_at FROM aggregate_operations
+        WHERE action_type = 'ExecuteBlocks' AND first_tx.block_number BETWEEN from_block AND to_block
+        ORDER BY id LIMIT 1
+    )
+FROM (
+    SELECT batch_id, count(*) AS member_count, min(sequence_number) AS first_sequence_number
+    FROM executed_transactions
+    WHERE batch_id IS NOT NULL
+    GROUP BY batch_id
+) AS members
+INNER JOIN txs_batches_hashes ON txs_batches_hashes.batch_id = members.batch_id
+INNER JOIN executed_transactions AS first_tx ON first_tx.sequence_number = members.first_sequence_number
+ON CONFLICT (batch_id) DO NOTHING;
@@ -0,0 +1,12 @@
+-- Largest holders of every ERC20 token, recalculated periodically from the `balances` table,
+-- so that they are not computed on every request.
+CREATE TABLE IF NOT EXISTS token_top_holders (
+    token_id INTEGER NOT NULL,
+    rank INTEGER NOT NULL,
+    account_id BIGINT NOT NULL,
+    address BYTEA NOT NULL,
+    balance NUMERIC NOT NULL,
+    -- Time of the recalculation, the same for all the rows.
+    refreshed_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (token_id, rank)
+);
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS mempool_txs_tx_hash_prefix_idx;
@@ -0,0 +1,4 @@
+-- The hash index on `tx_hash` supports only the exact matches, the btree one is used
+-- to search the mempool transactions by the hash prefix with `LIKE 'prefix%'`.
+CREATE INDEX IF NOT EXISTS mempool_txs_tx_hash_prefix_idx
+    ON mempool_txs (tx_hash text_pattern_ops);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS executed_transactions_from_to_account_seq_idx;
+DROP INDEX IF EXISTS executed_priority_operations_from_to_account_seq_idx;
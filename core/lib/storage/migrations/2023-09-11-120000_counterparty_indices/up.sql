@@ -0,0 +1,4 @@
+-- Indices for the account transactions filtered by the counterparty, which pages through
+-- the operations between the two accounts by the sequence number.
+CREATE INDEX IF NOT EXISTS executed_transactions_from_to_account_seq_idx ON executed_transactions (from_account, to_account, sequence_number);
+CREATE INDEX IF NOT EXISTS executed_priority_operations_from_to_account_seq_idx ON executed_priority_operations (from_account, to_account, sequence_number);
@@ -0,0 +1,8 @@
+-- Whether the processing of the forced exit requests is paused by the operator.
+-- The table has at most one row, no row means the processing is not paused.
+CREATE TABLE forced_exit_requests_pause (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    paused BOOLEAN NOT NULL,
+    changed_by TEXT NOT NULL,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1 @@
+-- Backfilled hashes can't be told apart from the ones stored by the server, so they are kept.
@@ -0,0 +1,9 @@
+-- Priority operations executed before the zkSync hashes were tracked are stored without them,
+-- so they can be found by the Ethereum hash only. The hash is computed the same way as
+-- `PriorityOp::tx_hash`: SHA-256 of the Ethereum hash followed by the big-endian Ethereum
+-- block, index of the transaction in the block and serial id of the operation.
+UPDATE executed_priority_operations
+SET tx_hash = sha256(
+    eth_hash || int8send(eth_block) || int8send(COALESCE(eth_block_index, 0)) || int8send(priority_op_serialid)
+)
+WHERE tx_hash = ''::bytea;
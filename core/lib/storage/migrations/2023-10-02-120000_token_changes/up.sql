@@ -0,0 +1,10 @@
+-- Changelog of the token metadata, the changes are numbered in the order they were committed.
+CREATE TABLE token_changes (
+    id BIGSERIAL PRIMARY KEY,
+    token_id INTEGER NOT NULL REFERENCES tokens(id) ON DELETE CASCADE,
+    field TEXT NOT NULL CHECK (field IN ('symbol', 'decimals')),
+    old_value TEXT NOT NULL,
+    new_value TEXT NOT NULL,
+    source TEXT NOT NULL,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
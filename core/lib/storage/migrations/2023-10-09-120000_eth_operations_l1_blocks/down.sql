@@ -0,0 +1 @@
+DROP TABLE IF EXISTS eth_operations_l1_blocks;
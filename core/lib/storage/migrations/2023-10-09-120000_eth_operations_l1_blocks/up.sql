@@ -0,0 +1,5 @@
+-- Ethereum blocks the confirmed Ethereum operations were included in.
+CREATE TABLE IF NOT EXISTS eth_operations_l1_blocks (
+    eth_op_id BIGINT PRIMARY KEY REFERENCES eth_operations (id) ON DELETE CASCADE,
+    block_number BIGINT NOT NULL
+);
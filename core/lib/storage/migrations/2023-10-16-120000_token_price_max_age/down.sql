@@ -0,0 +1 @@
+DROP TABLE IF EXISTS token_price_max_age;
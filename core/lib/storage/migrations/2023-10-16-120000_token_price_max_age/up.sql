@@ -0,0 +1,8 @@
+-- Maximum age of the token price the fees may be calculated with, set by the operators
+-- to override the default of the token liquidity tier.
+CREATE TABLE token_price_max_age (
+    token_id INTEGER NOT NULL PRIMARY KEY REFERENCES tokens(id) ON DELETE CASCADE,
+    max_age_secs BIGINT NOT NULL CHECK (max_age_secs > 0),
+    changed_by TEXT NOT NULL,
+    changed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
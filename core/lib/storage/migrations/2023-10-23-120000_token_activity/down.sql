@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS token_activity_blocks;
+DROP TABLE IF EXISTS token_activity_senders;
+DROP TABLE IF EXISTS token_activity_daily;
@@ -0,0 +1,26 @@
+-- Daily activity of the tokens, rolled up from the finalized blocks by the API server.
+-- The USD price is the ticker price of the token stored during the day, if any.
+CREATE TABLE token_activity_daily (
+    token_id INTEGER NOT NULL,
+    day DATE NOT NULL,
+    transfers BIGINT NOT NULL DEFAULT 0,
+    transfer_volume NUMERIC NOT NULL DEFAULT 0,
+    unique_senders BIGINT NOT NULL DEFAULT 0,
+    withdrawals BIGINT NOT NULL DEFAULT 0,
+    withdrawal_volume NUMERIC NOT NULL DEFAULT 0,
+    usd_price NUMERIC,
+    PRIMARY KEY (token_id, day)
+);
+-- Accounts that sent the token during the day, required to count the unique senders incrementally.
+CREATE TABLE token_activity_senders (
+    token_id INTEGER NOT NULL,
+    day DATE NOT NULL,
+    account_id BIGINT NOT NULL,
+    PRIMARY KEY (token_id, day, account_id)
+);
+-- Blocks already rolled up, along with the day their activity is attributed to.
+CREATE TABLE token_activity_blocks (
+    block_number BIGINT NOT NULL PRIMARY KEY,
+    day DATE NOT NULL
+);
+CREATE INDEX token_activity_blocks_day_idx ON token_activity_blocks (day);
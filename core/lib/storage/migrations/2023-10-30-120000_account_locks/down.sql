@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS notify_account_locks_listener ON account_locks;
+DROP FUNCTION IF EXISTS notify_account_locks_channel();
+DROP TABLE IF EXISTS account_locks;
@@ -0,0 +1,23 @@
+-- Accounts whose owners have frozen the outgoing transactions.
+-- The lock stays active until `unlocks_at`, which is set once the owner requests the unlock.
+CREATE TABLE account_locks (
+    account_id BIGINT PRIMARY KEY,
+    locked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    unlocks_at TIMESTAMP WITH TIME ZONE,
+    -- Timestamp of the latest accepted lock or unlock request signed by the owner,
+    -- the requests signed earlier are rejected so they can't be replayed.
+    signed_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE OR REPLACE FUNCTION notify_account_locks_channel() RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM (
+        SELECT pg_notify('account_locks_channel', TG_OP)
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notify_account_locks_listener
+AFTER INSERT OR UPDATE OR DELETE ON account_locks
+FOR EACH ROW EXECUTE PROCEDURE notify_account_locks_channel();
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS account_creates_account_id_idx;
+DROP TABLE IF EXISTS account_snapshot_blocks;
+DROP TABLE IF EXISTS account_balance_snapshots;
+DROP TABLE IF EXISTS account_snapshots;
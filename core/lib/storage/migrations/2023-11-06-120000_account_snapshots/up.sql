@@ -0,0 +1,25 @@
+-- Snapshots of the accounts state taken every few finalized blocks by the API server.
+-- A snapshot contains only the accounts updated since the previous one, so the state of
+-- the account at any block is restored from its nearest snapshot and the updates after it.
+CREATE TABLE account_snapshots (
+    account_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    nonce BIGINT NOT NULL,
+    address BYTEA NOT NULL,
+    pubkey_hash BYTEA NOT NULL,
+    PRIMARY KEY (account_id, block_number)
+);
+-- Non-zero balances of the accounts in the snapshots.
+CREATE TABLE account_balance_snapshots (
+    account_id BIGINT NOT NULL,
+    block_number BIGINT NOT NULL,
+    coin_id INTEGER NOT NULL,
+    balance NUMERIC NOT NULL,
+    PRIMARY KEY (account_id, block_number, coin_id)
+);
+-- Blocks the snapshots are taken at, including the ones without updated accounts.
+CREATE TABLE account_snapshot_blocks (
+    block_number BIGINT NOT NULL PRIMARY KEY
+);
+-- Updates after the snapshot are loaded per account.
+CREATE INDEX IF NOT EXISTS account_creates_account_id_idx ON account_creates (account_id);
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS tx_submission_outbox_batch_hash_idx;
+ALTER TABLE tx_submission_outbox
+    DROP COLUMN IF EXISTS batch_position,
+    DROP COLUMN IF EXISTS batch_hash;
+DROP TABLE IF EXISTS tx_submission_outbox_batches;
@@ -0,0 +1,17 @@
+-- Batches accepted by the API server, but not yet forwarded to the mempool. The transactions
+-- of the batch are stored in `tx_submission_outbox` along with the batch hash and are
+-- forwarded to the mempool all at once.
+CREATE TABLE IF NOT EXISTS tx_submission_outbox_batches
+(
+    batch_hash     BYTEA PRIMARY KEY,
+    eth_signatures JSONB NOT NULL,
+    tx_count       INT NOT NULL,
+    created_at     TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+ALTER TABLE tx_submission_outbox
+    ADD COLUMN IF NOT EXISTS batch_hash BYTEA
+        REFERENCES tx_submission_outbox_batches (batch_hash) ON DELETE CASCADE,
+    ADD COLUMN IF NOT EXISTS batch_position INT;
+
+CREATE INDEX IF NOT EXISTS tx_submission_outbox_batch_hash_idx ON tx_submission_outbox (batch_hash);
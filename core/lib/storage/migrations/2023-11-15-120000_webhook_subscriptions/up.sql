@@ -0,0 +1,18 @@
+-- Subscriptions delivering the transaction events of an account to the URL of the subscriber.
+CREATE TABLE webhook_subscriptions (
+    id BIGSERIAL PRIMARY KEY,
+    address BYTEA NOT NULL,
+    url TEXT NOT NULL,
+    -- Authenticates the requests managing the subscription and signs the deliveries,
+    -- so unlike the access tokens it's stored as is.
+    secret TEXT NOT NULL,
+    preferences JSONB NOT NULL,
+    -- Events up to this one are either delivered, filtered out or given up on.
+    last_event_id BIGINT NOT NULL,
+    -- Failed attempts to deliver the event following `last_event_id`.
+    failed_attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now(),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX webhook_subscriptions_address_idx ON webhook_subscriptions (address);
@@ -0,0 +1,17 @@
+-- Attempts to deliver the events to the webhook subscriptions, kept for the configured retention period.
+CREATE TABLE webhook_deliveries (
+    id BIGSERIAL PRIMARY KEY,
+    subscription_id BIGINT NOT NULL REFERENCES webhook_subscriptions (id) ON DELETE CASCADE,
+    event_id BIGINT NOT NULL,
+    -- Exact body of the request, replays of the event send it again.
+    payload BYTEA NOT NULL,
+    body_hash BYTEA NOT NULL,
+    -- HTTP status of the response, NULL if there was no response (e.g. the request timed out).
+    response_code INT,
+    latency_ms BIGINT NOT NULL,
+    replay BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE INDEX webhook_deliveries_subscription_event_idx ON webhook_deliveries (subscription_id, event_id);
+CREATE INDEX webhook_deliveries_created_at_idx ON webhook_deliveries (created_at);
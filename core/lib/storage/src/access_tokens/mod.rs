@@ -0,0 +1,100 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+use self::records::{AccessToken, StorageAccessToken};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Schema for the tokens granting third parties access to the data of a single account.
+///
+/// Tokens are looked up by their hashes, the tokens themselves are never stored.
+#[derive(Debug)]
+pub struct AccessTokensSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> AccessTokensSchema<'a, 'c> {
+    /// Stores the token issued for the account, returns its ID.
+    pub async fn store_token(
+        &mut self,
+        token_hash: &[u8],
+        address: Address,
+        scope: &str,
+        expires_at: DateTime<Utc>,
+    ) -> QueryResult<i64> {
+        let start = Instant::now();
+        let id = sqlx::query!(
+            r#"
+            INSERT INTO account_access_tokens (token_hash, address, scope, expires_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            token_hash,
+            address.as_bytes(),
+            scope,
+            expires_at
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .id;
+
+        metrics::histogram!("sql.access_tokens.store_token", start.elapsed());
+        Ok(id)
+    }
+
+    /// Loads the token by its hash, including the expired and revoked ones.
+    pub async fn load_token(&mut self, token_hash: &[u8]) -> QueryResult<Option<AccessToken>> {
+        let start = Instant::now();
+        let token = sqlx::query_as!(
+            StorageAccessToken,
+            "SELECT * FROM account_access_tokens WHERE token_hash = $1",
+            token_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AccessToken::from);
+
+        metrics::histogram!("sql.access_tokens.load_token", start.elapsed());
+        Ok(token)
+    }
+
+    /// Revokes the token. Returns `false` if the token is unknown or already revoked.
+    pub async fn revoke_token(&mut self, token_hash: &[u8]) -> QueryResult<bool> {
+        let start = Instant::now();
+        let revoked = sqlx::query!(
+            r#"
+            UPDATE account_access_tokens SET revoked_at = now()
+            WHERE token_hash = $1 AND revoked_at IS NULL
+            "#,
+            token_hash
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.access_tokens.revoke_token", start.elapsed());
+        Ok(revoked)
+    }
+
+    /// Revokes all the tokens issued for the account, returns the number of revoked tokens.
+    pub async fn revoke_account_tokens(&mut self, address: Address) -> QueryResult<u64> {
+        let start = Instant::now();
+        let revoked = sqlx::query!(
+            r#"
+            UPDATE account_access_tokens SET revoked_at = now()
+            WHERE address = $1 AND revoked_at IS NULL
+            "#,
+            address.as_bytes()
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.access_tokens.revoke_account_tokens", start.elapsed());
+        Ok(revoked)
+    }
+}
@@ -0,0 +1,42 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageAccessToken {
+    pub id: i64,
+    pub token_hash: Vec<u8>,
+    pub address: Vec<u8>,
+    pub scope: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// Token granting access to the data of a single account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessToken {
+    pub id: i64,
+    /// Account the access is granted to.
+    pub address: Address,
+    pub scope: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<StorageAccessToken> for AccessToken {
+    fn from(value: StorageAccessToken) -> Self {
+        Self {
+            id: value.id,
+            address: Address::from_slice(&value.address),
+            scope: value.scope,
+            expires_at: value.expires_at,
+            created_at: value.created_at,
+            revoked_at: value.revoked_at,
+        }
+    }
+}
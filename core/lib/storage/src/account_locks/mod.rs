@@ -0,0 +1,130 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_types::AccountId;
+// Local imports
+use self::records::{AccountLock, StorageAccountLock};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Schema for the locks of the outgoing transactions set by the account owners.
+///
+/// Every request of the owner is signed at some time, and only the requests signed
+/// after the latest accepted one change the lock, so the old requests can't be replayed.
+#[derive(Debug)]
+pub struct AccountLocksSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> AccountLocksSchema<'a, 'c> {
+    /// Locks the account, cancelling the pending unlock if there is one.
+    /// Returns `None` if the request is signed before the latest accepted one,
+    /// in this case nothing is changed.
+    pub async fn lock_account(
+        &mut self,
+        account_id: AccountId,
+        signed_at: DateTime<Utc>,
+    ) -> QueryResult<Option<AccountLock>> {
+        let start = Instant::now();
+        // The time of the active lock is kept, the expired one is replaced.
+        let lock = sqlx::query_as!(
+            StorageAccountLock,
+            r#"
+            INSERT INTO account_locks (account_id, signed_at)
+            VALUES ($1, $2)
+            ON CONFLICT (account_id) DO UPDATE
+            SET locked_at = CASE
+                    WHEN account_locks.unlocks_at IS NULL OR account_locks.unlocks_at > now()
+                    THEN account_locks.locked_at
+                    ELSE now()
+                END,
+                unlocks_at = NULL,
+                signed_at = $2
+            WHERE account_locks.signed_at < $2
+            RETURNING *
+            "#,
+            i64::from(*account_id),
+            signed_at
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AccountLock::from);
+
+        metrics::histogram!("sql.account_locks.lock_account", start.elapsed());
+        Ok(lock)
+    }
+
+    /// Schedules the unlock of the locked account at `unlocks_at`.
+    /// Returns `None` if the account is not locked, its unlock is already scheduled or
+    /// the request is signed before the latest accepted one, in this case nothing is changed.
+    pub async fn schedule_unlock(
+        &mut self,
+        account_id: AccountId,
+        signed_at: DateTime<Utc>,
+        unlocks_at: DateTime<Utc>,
+    ) -> QueryResult<Option<AccountLock>> {
+        let start = Instant::now();
+        let lock = sqlx::query_as!(
+            StorageAccountLock,
+            r#"
+            UPDATE account_locks
+            SET unlocks_at = $3, signed_at = $2
+            WHERE account_id = $1 AND unlocks_at IS NULL AND signed_at < $2
+            RETURNING *
+            "#,
+            i64::from(*account_id),
+            signed_at,
+            unlocks_at
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AccountLock::from);
+
+        metrics::histogram!("sql.account_locks.schedule_unlock", start.elapsed());
+        Ok(lock)
+    }
+
+    /// Loads the active lock of the account, if any.
+    pub async fn account_lock(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Option<AccountLock>> {
+        let start = Instant::now();
+        let lock = sqlx::query_as!(
+            StorageAccountLock,
+            r#"
+            SELECT * FROM account_locks
+            WHERE account_id = $1 AND (unlocks_at IS NULL OR unlocks_at > now())
+            "#,
+            i64::from(*account_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(AccountLock::from);
+
+        metrics::histogram!("sql.account_locks.account_lock", start.elapsed());
+        Ok(lock)
+    }
+
+    /// Loads the active locks of all the accounts.
+    pub async fn load_account_locks(&mut self) -> QueryResult<Vec<AccountLock>> {
+        let start = Instant::now();
+        let locks = sqlx::query_as!(
+            StorageAccountLock,
+            r#"
+            SELECT * FROM account_locks
+            WHERE unlocks_at IS NULL OR unlocks_at > now()
+            ORDER BY account_id
+            "#
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(AccountLock::from)
+        .collect();
+
+        metrics::histogram!("sql.account_locks.load_account_locks", start.elapsed());
+        Ok(locks)
+    }
+}
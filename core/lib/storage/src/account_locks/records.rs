@@ -0,0 +1,43 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::AccountId;
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageAccountLock {
+    pub account_id: i64,
+    pub locked_at: DateTime<Utc>,
+    pub unlocks_at: Option<DateTime<Utc>>,
+    pub signed_at: DateTime<Utc>,
+}
+
+/// Account whose outgoing transactions are frozen by its owner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountLock {
+    pub account_id: AccountId,
+    pub locked_at: DateTime<Utc>,
+    /// Time the lock expires at, set once the owner requests the unlock.
+    pub unlocks_at: Option<DateTime<Utc>>,
+    /// Timestamp of the latest accepted request of the owner.
+    pub signed_at: DateTime<Utc>,
+}
+
+impl AccountLock {
+    /// Returns `true` if the account is still locked at the given time.
+    pub fn is_active_at(&self, time: DateTime<Utc>) -> bool {
+        self.unlocks_at.map_or(true, |unlocks_at| unlocks_at > time)
+    }
+}
+
+impl From<StorageAccountLock> for AccountLock {
+    fn from(value: StorageAccountLock) -> Self {
+        Self {
+            account_id: AccountId(value.account_id as u32),
+            locked_at: value.locked_at,
+            unlocks_at: value.unlocks_at,
+            signed_at: value.signed_at,
+        }
+    }
+}
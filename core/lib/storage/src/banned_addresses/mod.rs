@@ -0,0 +1,121 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+use self::records::{BannedAddress, StorageBannedAddress};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Schema for the list of addresses that are not allowed to use the network.
+///
+/// Every change of the list is recorded in the history together with
+/// the reason and the administrator that has made it.
+#[derive(Debug)]
+pub struct BannedAddressesSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> BannedAddressesSchema<'a, 'c> {
+    /// Adds the address to the banned list.
+    /// Returns `false` if the address was already banned, in this case nothing is changed.
+    pub async fn ban_address(
+        &mut self,
+        address: Address,
+        reason: &str,
+        actor: &str,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO banned_addresses (address, reason, banned_by)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (address) DO NOTHING
+            "#,
+            address.as_bytes(),
+            reason,
+            actor
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected()
+            > 0;
+        if inserted {
+            sqlx::query!(
+                r#"
+                INSERT INTO banned_addresses_history (address, action, reason, actor)
+                VALUES ($1, 'ban', $2, $3)
+                "#,
+                address.as_bytes(),
+                reason,
+                actor
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.banned_addresses.ban_address", start.elapsed());
+        Ok(inserted)
+    }
+
+    /// Removes the address from the banned list.
+    /// Returns `false` if the address was not banned, in this case nothing is changed.
+    pub async fn unban_address(
+        &mut self,
+        address: Address,
+        reason: &str,
+        actor: &str,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let removed = sqlx::query!(
+            "DELETE FROM banned_addresses WHERE address = $1",
+            address.as_bytes()
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected()
+            > 0;
+        if removed {
+            sqlx::query!(
+                r#"
+                INSERT INTO banned_addresses_history (address, action, reason, actor)
+                VALUES ($1, 'unban', $2, $3)
+                "#,
+                address.as_bytes(),
+                reason,
+                actor
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.banned_addresses.unban_address", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Loads all the currently banned addresses.
+    pub async fn load_banned_addresses(&mut self) -> QueryResult<Vec<BannedAddress>> {
+        let start = Instant::now();
+        let addresses = sqlx::query_as!(
+            StorageBannedAddress,
+            "SELECT * FROM banned_addresses ORDER BY banned_at, address"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(BannedAddress::from)
+        .collect();
+
+        metrics::histogram!(
+            "sql.banned_addresses.load_banned_addresses",
+            start.elapsed()
+        );
+        Ok(addresses)
+    }
+}
@@ -0,0 +1,36 @@
+// External imports
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageBannedAddress {
+    pub address: Vec<u8>,
+    pub reason: String,
+    pub banned_by: String,
+    pub banned_at: DateTime<Utc>,
+}
+
+/// Address that is not allowed to submit transactions or receive funds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BannedAddress {
+    pub address: Address,
+    /// Reason the address was banned for.
+    pub reason: String,
+    /// Identifier of the administrator that has banned the address.
+    pub banned_by: String,
+    pub banned_at: DateTime<Utc>,
+}
+
+impl From<StorageBannedAddress> for BannedAddress {
+    fn from(value: StorageBannedAddress) -> Self {
+        Self {
+            address: Address::from_slice(&value.address),
+            reason: value.reason,
+            banned_by: value.banned_by,
+            banned_at: value.banned_at,
+        }
+    }
+}
@@ -112,6 +112,7 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
     }
 
     /// Fetches account type from the database
+    #[tracing::instrument(name = "sql.chain.account.account_type_by_id", skip(self))]
     pub async fn account_type_by_id(
         &mut self,
         account_id: AccountId,
@@ -430,6 +431,65 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(account_id)
     }
 
+    /// Returns the identifier the next created account gets, based on the committed accounts.
+    pub async fn next_free_account_id(&mut self) -> QueryResult<AccountId> {
+        let start = Instant::now();
+        // The NFT storage account has the largest identifier, but it's not taken into account.
+        let last_account_id = sqlx::query!(
+            "SELECT MAX(account_id) FROM account_creates WHERE account_id != $1",
+            i64::from(*NFT_STORAGE_ACCOUNT_ID)
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .max;
+
+        let next_account_id = last_account_id.map_or(0, |account_id| account_id as u32 + 1);
+        metrics::histogram!("sql.chain.account.next_free_account_id", start.elapsed());
+        Ok(AccountId(next_account_id))
+    }
+
+    /// Returns all the accounts that have ever used the provided public key hash,
+    /// including the accounts which have already changed it.
+    pub async fn accounts_by_pubkey_hash(
+        &mut self,
+        pubkey_hash: PubKeyHash,
+    ) -> QueryResult<Vec<StoragePubKeyHashOwner>> {
+        let start = Instant::now();
+        let result = sqlx::query_as!(
+            StoragePubKeyHashOwner,
+            r#"
+                SELECT
+                    matched.account_id as "account_id!",
+                    (SELECT address FROM account_creates
+                        WHERE account_id = matched.account_id AND is_create = true
+                        ORDER BY block_number DESC
+                        LIMIT 1
+                    ) as "address!",
+                    COALESCE(
+                        (SELECT new_pubkey_hash FROM account_pubkey_updates
+                            WHERE account_id = matched.account_id
+                            ORDER BY block_number DESC, update_order_id DESC
+                            LIMIT 1
+                        ),
+                        (SELECT pubkey_hash FROM accounts WHERE id = matched.account_id)
+                    ) = $1 as "is_current!"
+                FROM (
+                    SELECT account_id FROM account_pubkey_updates WHERE new_pubkey_hash = $1
+                    UNION
+                    SELECT id FROM accounts WHERE pubkey_hash = $1
+                ) as matched
+                ORDER BY matched.account_id
+            "#,
+            &pubkey_hash.data[..]
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.account.accounts_by_pubkey_hash", start.elapsed());
+        Ok(result)
+    }
+
+    #[tracing::instrument(name = "sql.chain.account.account_address_by_id", skip(self))]
     pub async fn account_address_by_id(
         &mut self,
         account_id: AccountId,
@@ -448,6 +508,30 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(address)
     }
 
+    /// Returns the number of the block the account was created in, `None` if the account
+    /// is not created yet.
+    pub async fn account_creation_block(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Option<BlockNumber>> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+                SELECT block_number FROM account_creates
+                WHERE account_id = $1 AND is_create = true
+                ORDER BY block_number DESC
+                LIMIT 1
+            "#,
+            i64::from(*account_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        let block_number = result.map(|record| BlockNumber(record.block_number as u32));
+        metrics::histogram!("sql.chain.account.account_creation_block", start.elapsed());
+        Ok(block_number)
+    }
+
     /// Obtains the last committed block that affects the account.
     pub async fn last_committed_block_with_update_for_acc(
         &mut self,
@@ -13,6 +13,15 @@ pub(crate) struct StorageAccount {
     pub pubkey_hash: Vec<u8>,
 }
 
+/// Account which has ever used the requested public key hash.
+#[derive(Debug, FromRow, Clone, PartialEq)]
+pub struct StoragePubKeyHashOwner {
+    pub account_id: i64,
+    pub address: Vec<u8>,
+    /// Whether the public key hash is the current one for the account.
+    pub is_current: bool,
+}
+
 #[derive(Debug, FromRow)]
 pub(crate) struct StorageAccountCreation {
     pub account_id: i64,
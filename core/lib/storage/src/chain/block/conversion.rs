@@ -17,7 +17,7 @@ use zksync_types::{
 };
 // Local imports
 use crate::chain::operations::records::StoredAggregatedOperation;
-use crate::utils::affected_accounts;
+use crate::utils::{affected_accounts, decode_eth_sign_data, EthSignTokens};
 use crate::{
     chain::{
         block::records::TransactionItem,
@@ -25,6 +25,7 @@ use crate::{
             NewExecutedPriorityOperation, NewExecutedTransaction, StoredExecutedPriorityOperation,
             StoredExecutedTransaction,
         },
+        operations_ext::records::StorageTxData,
     },
     QueryResult, StorageProcessor,
 };
@@ -36,7 +37,7 @@ impl StoredExecutedTransaction {
             serde_json::from_value(self.operation).expect("Unparsable ZkSyncOp in db");
         let eth_sign_data = self
             .eth_sign_data
-            .map(|value| serde_json::from_value(value).expect("Unparsable EthSignData"));
+            .map(|value| decode_eth_sign_data(&tx, value).expect("Unparsable EthSignData"));
         ExecutedTx {
             signed_tx: SignedZkSyncTx {
                 tx,
@@ -132,6 +133,7 @@ impl NewExecutedTransaction {
     pub(crate) async fn prepare_stored_tx(
         exec_tx: ExecutedTx,
         block: BlockNumber,
+        eth_sign_tokens: &EthSignTokens,
         storage: &mut StorageProcessor<'_>,
     ) -> QueryResult<Self> {
         fn cut_prefix(input: &str) -> String {
@@ -179,9 +181,7 @@ impl NewExecutedTransaction {
         let to_account: Option<Vec<u8>> =
             to_account_hex.map(|value| hex::decode(cut_prefix(&value)).unwrap());
 
-        let eth_sign_data = exec_tx.signed_tx.eth_sign_data.as_ref().map(|sign_data| {
-            serde_json::to_value(sign_data).expect("Failed to encode EthSignData")
-        });
+        let eth_sign_data = eth_sign_tokens.eth_sign_data_to_store(&exec_tx.signed_tx);
 
         let affected_accounts = affected_accounts(&exec_tx.signed_tx.tx, storage)
             .await?
@@ -250,7 +250,7 @@ impl TransactionItem {
                 L1Transaction::from_executed_op(operation, eth_hash, id, tx_hash).unwrap(),
             )
         } else {
-            TransactionData::L2(serde_json::from_value(item.op).unwrap())
+            StorageTxData::tx_data_from_zksync_tx(serde_json::from_value(item.op).unwrap(), None)
         };
 
         Transaction {
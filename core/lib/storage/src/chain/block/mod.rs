@@ -19,7 +19,7 @@ use zksync_types::{
 // Local imports
 use self::records::{
     BlockTransactionItem, StorageBlock, StorageBlockDetails, StorageBlockMetadata,
-    StoragePendingBlock, StorageRootHash, TransactionItem,
+    StorageBlockSummary, StoragePendingBlock, StorageRootHash, TransactionItem,
 };
 use crate::{
     chain::operations::{
@@ -30,6 +30,7 @@ use crate::{
         OperationsSchema,
     },
     chain::{account::records::EthAccountType, block::records::StorageIncompleteBlock},
+    utils::EthSignTokens,
     QueryResult, StorageProcessor,
 };
 
@@ -53,6 +54,15 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
 
+        let eth_sign_tokens = EthSignTokens::load(
+            &mut transaction,
+            operations.iter().filter_map(|op| match op {
+                ExecutedOperations::Tx(tx) => Some(&tx.signed_tx),
+                ExecutedOperations::PriorityOp(_) => None,
+            }),
+        )
+        .await?;
+
         for block_tx in operations.into_iter() {
             match block_tx {
                 ExecutedOperations::Tx(tx) => {
@@ -92,6 +102,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                     let new_tx = NewExecutedTransaction::prepare_stored_tx(
                         *tx,
                         block_number,
+                        &eth_sign_tokens,
                         &mut transaction,
                     )
                     .await?;
@@ -494,6 +505,90 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(details)
     }
 
+    /// Loads the block headers along with the number of L1 and L2 operations in every block
+    /// for the given block numbers. Blocks that are not committed yet are skipped.
+    /// The order of the returned headers is not specified.
+    pub async fn load_blocks_by_numbers(
+        &mut self,
+        block_numbers: &[BlockNumber],
+    ) -> QueryResult<Vec<(StorageBlockDetails, u32)>> {
+        let start = Instant::now();
+        let block_numbers: Vec<i64> = block_numbers
+            .iter()
+            .map(|block_number| i64::from(**block_number))
+            .collect();
+        // Same as `load_block_range_desc`, but the blocks are selected by the numbers and
+        // the transactions of every block are counted.
+        let records = sqlx::query!(
+            r#"
+            WITH aggr_comm AS (
+                SELECT 
+                    aggregate_operations.created_at, 
+                    eth_operations.final_hash, 
+                    commit_aggregated_blocks_binding.block_number 
+                FROM aggregate_operations
+                    INNER JOIN commit_aggregated_blocks_binding ON aggregate_operations.id = commit_aggregated_blocks_binding.op_id
+                    INNER JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                    INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                WHERE aggregate_operations.confirmed = true 
+            ),
+            aggr_exec as (
+                 SELECT 
+                    aggregate_operations.created_at, 
+                    eth_operations.final_hash, 
+                    execute_aggregated_blocks_binding.block_number 
+                FROM aggregate_operations
+                    INNER JOIN execute_aggregated_blocks_binding ON aggregate_operations.id = execute_aggregated_blocks_binding.op_id
+                    INNER JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                    INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                WHERE aggregate_operations.confirmed = true 
+            )
+            SELECT
+                blocks.number AS "block_number!",
+                blocks.root_hash AS "new_state_root!",
+                blocks.block_size AS "block_size!",
+                committed.final_hash AS "commit_tx_hash?",
+                verified.final_hash AS "verify_tx_hash?",
+                committed.created_at AS "committed_at!",
+                verified.created_at AS "verified_at?",
+                (
+                    SELECT count(*) FROM executed_transactions
+                    WHERE executed_transactions.block_number = blocks.number
+                ) + (
+                    SELECT count(*) FROM executed_priority_operations
+                    WHERE executed_priority_operations.block_number = blocks.number
+                ) AS "tx_count!"
+            FROM blocks
+                     INNER JOIN aggr_comm committed ON blocks.number = committed.block_number
+                     LEFT JOIN aggr_exec verified ON blocks.number = verified.block_number
+            WHERE
+                blocks.number = ANY($1)
+            "#,
+            &block_numbers
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let headers = records
+            .into_iter()
+            .map(|record| {
+                let details = StorageBlockDetails {
+                    block_number: record.block_number,
+                    new_state_root: record.new_state_root,
+                    block_size: record.block_size,
+                    commit_tx_hash: record.commit_tx_hash,
+                    verify_tx_hash: record.verify_tx_hash,
+                    committed_at: record.committed_at,
+                    verified_at: record.verified_at,
+                };
+                (details, record.tx_count as u32)
+            })
+            .collect();
+
+        metrics::histogram!("sql.chain.block.load_blocks_by_numbers", start.elapsed());
+        Ok(headers)
+    }
+
     /// Performs a database search with an uncertain query, which can be either of:
     /// - Hash of commit/verify Ethereum transaction for the block.
     /// - The state root hash of the block.
@@ -1183,7 +1278,11 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(())
     }
 
-    /// Retrieves both L1 and L2 operations stored in the block for the given pagination query
+    /// Retrieves both L1 and L2 operations stored in the block for the given pagination query.
+    ///
+    /// Newer pages are listed in the order of execution, i.e. by the block index, and older
+    /// pages in the reverse order. Sequence numbers are used for ordering instead of
+    /// the block indices since the latter are not set for the failed transactions.
     pub async fn get_block_transactions_page(
         &mut self,
         query: &PaginationQuery<BlockAndTxHash>,
@@ -1360,6 +1459,89 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(block_txs)
     }
 
+    /// Retrieves all L1 and L2 operations stored in the block in the order of execution.
+    pub async fn load_block_transactions_api_v02(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Vec<Transaction>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let raw_txs = sqlx::query_as!(
+            TransactionItem,
+            r#"
+                WITH transactions AS (
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        tx as op,
+                        block_number,
+                        created_at,
+                        success,
+                        fail_reason,
+                        Null::bytea as eth_hash,
+                        Null::bigint as priority_op_serialid,
+                        block_index,
+                        batch_id
+                    FROM executed_transactions
+                    WHERE block_number = $1
+                ), priority_ops AS (
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        operation as op,
+                        block_number,
+                        created_at,
+                        true as success,
+                        Null as fail_reason,
+                        eth_hash,
+                        priority_op_serialid,
+                        block_index,
+                        Null::bigint as batch_id
+                    FROM executed_priority_operations
+                    WHERE block_number = $1
+                ), everything AS (
+                    SELECT * FROM transactions
+                    UNION ALL
+                    SELECT * FROM priority_ops
+                )
+                SELECT
+                    sequence_number,
+                    tx_hash as "tx_hash!",
+                    block_number as "block_number!",
+                    block_index as "block_index?",
+                    op as "op!",
+                    created_at as "created_at!",
+                    success as "success!",
+                    fail_reason as "fail_reason?",
+                    eth_hash as "eth_hash?",
+                    priority_op_serialid as "priority_op_serialid?",
+                    batch_id as "batch_id?"
+                FROM everything
+                ORDER BY sequence_number ASC
+            "#,
+            i64::from(*block_number),
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let is_block_finalized = transaction
+            .chain()
+            .block_schema()
+            .is_block_finalized(block_number)
+            .await?;
+        let txs = raw_txs
+            .into_iter()
+            .map(|tx| TransactionItem::transaction_from_item(tx, is_block_finalized))
+            .collect();
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.block.load_block_transactions_api_v02",
+            start.elapsed()
+        );
+        Ok(txs)
+    }
+
     /// Returns count of both L1 and L2 operations stored in the block
     pub async fn get_block_transactions_count(
         &mut self,
@@ -1391,6 +1573,39 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok((tx_count + priority_op_count) as u32)
     }
 
+    /// Returns the size and the amount of L1 and L2 operations of every block in the range.
+    pub async fn load_block_summaries(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+    ) -> QueryResult<Vec<StorageBlockSummary>> {
+        let start = Instant::now();
+        let summaries = sqlx::query_as!(
+            StorageBlockSummary,
+            r#"SELECT
+                blocks.number AS block_number,
+                blocks.block_size,
+                (
+                    SELECT count(*) FROM executed_transactions
+                    WHERE executed_transactions.block_number = blocks.number
+                ) AS "transactions_count!",
+                (
+                    SELECT count(*) FROM executed_priority_operations
+                    WHERE executed_priority_operations.block_number = blocks.number
+                ) AS "priority_operations_count!"
+            FROM blocks
+            WHERE blocks.number BETWEEN $1 AND $2
+            ORDER BY blocks.number"#,
+            i64::from(*from_block),
+            i64::from(*to_block)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.load_block_summaries", start.elapsed());
+        Ok(summaries)
+    }
+
     // Removes blocks with number greater than `last_block`
     pub async fn remove_blocks(&mut self, last_block: BlockNumber) -> QueryResult<()> {
         let start = Instant::now();
@@ -1437,6 +1652,17 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         )
         .execute(transaction.conn())
         .await?;
+        // Days of the removed blocks are rolled up again once the blocks are finalized.
+        transaction
+            .chain()
+            .stats_schema()
+            .revert_token_activity(last_block)
+            .await?;
+        transaction
+            .chain()
+            .state_schema()
+            .remove_account_snapshots(last_block)
+            .await?;
         transaction.commit().await?;
         metrics::histogram!("sql.chain.block.remove_blocks", start.elapsed());
         Ok(())
@@ -50,6 +50,15 @@ pub(crate) struct StoragePendingBlock {
     pub timestamp: Option<i64>,
 }
 
+/// Size of the block and the amount of operations included into it.
+#[derive(Debug, FromRow, PartialEq, Clone)]
+pub struct StorageBlockSummary {
+    pub block_number: i64,
+    pub block_size: i64,
+    pub transactions_count: i64,
+    pub priority_operations_count: i64,
+}
+
 // This struct is a copy of `BlockDetails` from the `zksync_types` crate
 // with the only difference that it implements `FromRow` trait. To get rid
 // of this, we should either wait for the implementation of `sqlx::flatten`
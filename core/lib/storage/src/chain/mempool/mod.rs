@@ -1,22 +1,26 @@
 // Built-in deps
-use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
+use std::{collections::VecDeque, convert::TryFrom, iter, str::FromStr, time::Instant};
 // External imports
+use chrono::{DateTime, Utc};
 use itertools::Itertools;
 // Workspace imports
 use zksync_api_types::v02::pagination::PaginationDirection;
 use zksync_api_types::v02::transaction::{
-    ApiTxBatch, BatchStatus, TxHashSerializeWrapper, TxInBlockStatus,
+    ApiTxBatch, BatchStatus, L2Receipt, Receipt, TxHashSerializeWrapper, TxInBlockStatus,
 };
 use zksync_types::{
     block::IncompleteBlock,
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
-    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx,
-    PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, H256,
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx, Nonce,
+    PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
 // Local imports
-use self::records::{MempoolPriorityOp, MempoolTx, QueuedBatchTx, RevertedBlock};
-use crate::{QueryResult, StorageProcessor};
+use self::records::{
+    EvictedBatchTx, MempoolPriorityOp, MempoolTx, OutboxTx, PendingPriorityOp, QueuedBatchTx,
+    RevertedBlock,
+};
+use crate::{utils::EthSignTokens, QueryResult, StorageProcessor};
 
 use crate::chain::operations::records::{
     StoredExecutedPriorityOperation, StoredExecutedTransaction,
@@ -24,6 +28,23 @@ use crate::chain::operations::records::{
 
 pub mod records;
 
+/// Reason recorded for the batch members that were removed along with another transaction of the batch.
+pub const BATCH_MEMBER_REMOVED_REASON: &str =
+    "Another transaction of the batch was removed from the mempool";
+
+/// Reason recorded for the transactions cancelled by their submitters.
+pub const CANCELLED_TX_REASON: &str = "Transaction is cancelled by the submitter";
+
+/// Returns the addresses stored along with the mempool transaction, so the pending
+/// transactions of the account can be found: the account of the transaction and
+/// the L2 recipient of the funds.
+fn tx_addresses(tx: &ZkSyncTx) -> (Vec<u8>, Option<Vec<u8>>) {
+    (
+        tx.account().as_bytes().to_vec(),
+        tx.l2_recipient().map(|address| address.as_bytes().to_vec()),
+    )
+}
+
 /// Schema for persisting transactions awaiting for the execution.
 ///
 /// This schema holds the transactions that are received by the `mempool` module, but not yet have
@@ -155,6 +176,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         let mut transaction = self.0.start_transaction().await?;
         let tx_hashes: Vec<TxHash> = txs.iter().map(|tx| tx.tx.hash()).collect();
+        let eth_sign_tokens = EthSignTokens::load(&mut transaction, txs).await?;
 
         // The first transaction of the batch would be inserted manually
         // batch_id of the inserted transaction would be the id of this batch
@@ -165,18 +187,18 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             let tx_hash = hex::encode(tx_hashes[0].as_ref());
             let tx = serde_json::to_value(&first_tx_data.tx)
                 .expect("Unserializable TX provided to the database");
-            let eth_sign_data = first_tx_data
-                .eth_sign_data
-                .as_ref()
-                .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+            let eth_sign_data = eth_sign_tokens.eth_sign_data_to_store(&first_tx_data);
+            let (account_address, recipient_address) = tx_addresses(&first_tx_data.tx);
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data)
-                VALUES ($1, $2, $3, $4)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, account_address, recipient_address)
+                VALUES ($1, $2, $3, $4, $5, $6)",
                 tx_hash,
                 tx,
                 first_tx_data.created_at,
                 eth_sign_data,
+                account_address,
+                recipient_address,
             )
             .execute(transaction.conn())
             .await?;
@@ -199,19 +221,19 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             let tx_hash = hex::encode(tx_hash.as_ref());
             let tx = serde_json::to_value(&tx_data.tx)
                 .expect("Unserializable TX provided to the database");
-            let eth_sign_data = tx_data
-                .eth_sign_data
-                .as_ref()
-                .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+            let eth_sign_data = eth_sign_tokens.eth_sign_data_to_store(tx_data);
+            let (account_address, recipient_address) = tx_addresses(&tx_data.tx);
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-                VALUES ($1, $2, $3, $4, $5)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, account_address, recipient_address)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)",
                 tx_hash,
                 tx,
                 tx_data.created_at,
                 eth_sign_data,
-                batch_id
+                batch_id,
+                account_address,
+                recipient_address,
             )
             .execute(transaction.conn())
             .await?;
@@ -251,19 +273,21 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         let tx = serde_json::to_value(&tx_data.tx)?;
         let batch_id = 0; // Special case: batch_id == 0 <==> transaction is not a part of some batch
 
-        let eth_sign_data = tx_data
-            .eth_sign_data
-            .as_ref()
-            .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+        let eth_sign_data = EthSignTokens::load(self.0, iter::once(tx_data))
+            .await?
+            .eth_sign_data_to_store(tx_data);
+        let (account_address, recipient_address) = tx_addresses(&tx_data.tx);
 
         sqlx::query!(
-            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-            VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, account_address, recipient_address)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)",
             tx_hash,
             tx,
             tx_data.created_at,
             eth_sign_data,
             batch_id,
+            account_address,
+            recipient_address,
         )
         .execute(self.0.conn())
         .await?;
@@ -272,6 +296,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes the executed transaction from the mempool.
+    /// Batches are executed atomically, so unlike `remove_txs` this method doesn't touch
+    /// other members of the batch: they are removed one by one once stored as executed.
     pub async fn remove_tx(&mut self, tx: &[u8]) -> QueryResult<()> {
         let start = Instant::now();
         let tx_hash = hex::encode(tx);
@@ -288,22 +315,526 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Removes transactions from the mempool.
+    /// Batches can't be executed partially, so if any of the transactions belongs to a batch,
+    /// the rest of the batch is removed as well and recorded as evicted.
     pub async fn remove_txs(&mut self, txs: &[TxHash]) -> QueryResult<()> {
         let start = Instant::now();
         let tx_hashes: Vec<_> = txs.iter().map(hex::encode).collect();
 
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "INSERT INTO mempool_evicted_txs (tx_hash, address, tx, fail_reason, batch_id)
+            SELECT decode(tx_hash, 'hex'), account_address, tx, $2, batch_id
+                FROM mempool_txs
+                WHERE batch_id IN (
+                    SELECT batch_id FROM mempool_txs
+                    WHERE tx_hash = ANY($1) AND batch_id != 0
+                ) AND tx_hash != ALL($1) AND account_address IS NOT NULL",
+            &tx_hashes,
+            BATCH_MEMBER_REMOVED_REASON
+        )
+        .execute(transaction.conn())
+        .await?;
         sqlx::query!(
             "DELETE FROM mempool_txs
-            WHERE tx_hash = ANY($1)",
+            WHERE tx_hash = ANY($1) OR batch_id IN (
+                SELECT batch_id FROM mempool_txs
+                WHERE tx_hash = ANY($1) AND batch_id != 0
+            )",
             &tx_hashes
         )
-        .execute(self.0.conn())
+        .execute(transaction.conn())
         .await?;
+        transaction.commit().await?;
 
         metrics::histogram!("sql.chain.mempool.remove_txs", start.elapsed());
         Ok(())
     }
 
+    /// Removes transactions that won't ever be executed from the mempool.
+    /// Unlike `remove_txs`, the evicted transactions are recorded along with the
+    /// provided reason, so they can be reported to the users later.
+    pub async fn evict_txs(&mut self, txs: &[SignedTxVariant], reason: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut tx_hashes = Vec::new();
+        let mut addresses = Vec::new();
+        let mut tx_values = Vec::new();
+        for tx in txs.iter().flat_map(|variant| variant.get_transactions()) {
+            tx_hashes.push(tx.hash());
+            addresses.push(tx.account().as_bytes().to_vec());
+            tx_values.push(serde_json::to_value(&tx.tx)?);
+        }
+        let raw_tx_hashes: Vec<_> = tx_hashes
+            .iter()
+            .map(|tx_hash| tx_hash.as_ref().to_vec())
+            .collect();
+
+        let mut transaction = self.0.start_transaction().await?;
+        sqlx::query!(
+            "INSERT INTO mempool_evicted_txs (tx_hash, address, tx, fail_reason, batch_id)
+            SELECT u.tx_hash, u.address, u.tx, $4, (
+                SELECT NULLIF(batch_id, 0) FROM mempool_txs
+                WHERE mempool_txs.tx_hash = encode(u.tx_hash, 'hex')
+            )
+                FROM UNNEST ($1::bytea[], $2::bytea[], $3::jsonb[])
+                AS u(tx_hash, address, tx)",
+            &raw_tx_hashes,
+            &addresses,
+            &tx_values,
+            reason
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction
+            .chain()
+            .mempool_schema()
+            .remove_txs(&tx_hashes)
+            .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.mempool.evict_txs", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes records about evicted transactions created before the provided moment.
+    /// Returns the number of removed records.
+    pub async fn remove_evicted_txs_older_than(
+        &mut self,
+        timestamp: DateTime<Utc>,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM mempool_evicted_txs WHERE evicted_at < $1",
+            timestamp
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!(
+            "sql.chain.mempool.remove_evicted_txs_older_than",
+            start.elapsed()
+        );
+        Ok(removed)
+    }
+
+    /// Persists the validated transaction into the submission outbox, from where it is
+    /// forwarded to the mempool. Returns `false` if the transaction is already in the outbox.
+    #[tracing::instrument(
+        name = "sql.chain.mempool.insert_outbox_tx",
+        skip(self, tx_data),
+        fields(tx_hash = %tx_data.tx.hash().to_string())
+    )]
+    pub async fn insert_outbox_tx(&mut self, tx_data: &SignedZkSyncTx) -> QueryResult<bool> {
+        let start = Instant::now();
+        let tx_hash = tx_data.tx.hash().as_ref().to_vec();
+        let tx = serde_json::to_value(&tx_data.tx)?;
+        let eth_sign_data = EthSignTokens::load(self.0, iter::once(tx_data))
+            .await?
+            .eth_sign_data_to_store(tx_data);
+
+        let inserted = sqlx::query!(
+            "INSERT INTO tx_submission_outbox (tx_hash, tx, eth_sign_data, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (tx_hash) DO NOTHING",
+            tx_hash,
+            tx,
+            eth_sign_data,
+            tx_data.created_at,
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        metrics::histogram!("sql.chain.mempool.insert_outbox_tx", start.elapsed());
+        Ok(inserted)
+    }
+
+    /// Persists the validated batch into the submission outbox, from where it is forwarded
+    /// to the mempool as a whole. Returns `false` if the batch is already in the outbox.
+    ///
+    /// Transactions of the batch must not be submitted on their own, such batches are refused.
+    pub async fn insert_outbox_batch(
+        &mut self,
+        txs: &[SignedZkSyncTx],
+        eth_signatures: &[TxEthSignature],
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        if txs.is_empty() {
+            anyhow::bail!("Cannot insert an empty batch");
+        }
+
+        let mut transaction = self.0.start_transaction().await?;
+        let tx_hashes: Vec<TxHash> = txs.iter().map(|tx| tx.tx.hash()).collect();
+        let batch_hash = TxHash::batch_hash(&tx_hashes);
+        let inserted = sqlx::query!(
+            "INSERT INTO tx_submission_outbox_batches (batch_hash, eth_signatures, tx_count, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (batch_hash) DO NOTHING",
+            batch_hash.as_ref(),
+            serde_json::to_value(eth_signatures)?,
+            txs.len() as i32,
+            txs[0].created_at,
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected()
+            == 1;
+        if !inserted {
+            return Ok(false);
+        }
+
+        let eth_sign_tokens = EthSignTokens::load(&mut transaction, txs).await?;
+        for (position, (tx_data, tx_hash)) in txs.iter().zip(&tx_hashes).enumerate() {
+            let tx = serde_json::to_value(&tx_data.tx)?;
+            let eth_sign_data = eth_sign_tokens.eth_sign_data_to_store(tx_data);
+            let inserted = sqlx::query!(
+                "INSERT INTO tx_submission_outbox (tx_hash, tx, eth_sign_data, created_at, batch_hash, batch_position)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (tx_hash) DO NOTHING",
+                tx_hash.as_ref(),
+                tx,
+                eth_sign_data,
+                tx_data.created_at,
+                batch_hash.as_ref(),
+                position as i32,
+            )
+            .execute(transaction.conn())
+            .await?
+            .rows_affected()
+                == 1;
+            if !inserted {
+                anyhow::bail!(
+                    "Transaction {} of the batch is already submitted",
+                    tx_hash.to_string()
+                );
+            }
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.mempool.insert_outbox_batch", start.elapsed());
+        Ok(true)
+    }
+
+    /// Loads the oldest submissions that are not forwarded to the mempool yet and locks them,
+    /// so the concurrent forwarders skip them. Batches are loaded as a whole, along with the
+    /// transactions past the `limit`, batches partially locked by another forwarder are skipped.
+    /// Locks are held until the end of the database transaction, so the method is expected
+    /// to be called inside of one.
+    pub async fn lock_undelivered_outbox_submissions(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<SignedTxVariant>> {
+        let start = Instant::now();
+        let records = sqlx::query_as!(
+            OutboxTx,
+            "SELECT tx, eth_sign_data, created_at, batch_hash FROM tx_submission_outbox
+            WHERE delivered_at IS NULL
+            ORDER BY created_at, batch_position
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED",
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut submissions = Vec::with_capacity(records.len());
+        let mut loaded_batches = Vec::new();
+        for record in records {
+            let batch_hash = match record.batch_hash.clone() {
+                Some(batch_hash) => batch_hash,
+                None => {
+                    submissions.push(SignedTxVariant::Tx(SignedZkSyncTx::try_from(record)?));
+                    continue;
+                }
+            };
+            if loaded_batches.contains(&batch_hash) {
+                continue;
+            }
+            if let Some(batch) = self.lock_undelivered_outbox_batch(&batch_hash).await? {
+                submissions.push(batch);
+            }
+            loaded_batches.push(batch_hash);
+        }
+
+        metrics::histogram!(
+            "sql.chain.mempool.lock_undelivered_outbox_submissions",
+            start.elapsed()
+        );
+        Ok(submissions)
+    }
+
+    /// Locks all the transactions of the undelivered outbox batch, `None` if some of them
+    /// are locked by another forwarder.
+    async fn lock_undelivered_outbox_batch(
+        &mut self,
+        batch_hash: &[u8],
+    ) -> QueryResult<Option<SignedTxVariant>> {
+        let batch = sqlx::query!(
+            "SELECT eth_signatures, tx_count FROM tx_submission_outbox_batches
+            WHERE batch_hash = $1",
+            batch_hash
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+        let txs = sqlx::query_as!(
+            OutboxTx,
+            "SELECT tx, eth_sign_data, created_at, batch_hash FROM tx_submission_outbox
+            WHERE batch_hash = $1 AND delivered_at IS NULL
+            ORDER BY batch_position
+            FOR UPDATE SKIP LOCKED",
+            batch_hash
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+        if txs.len() != batch.tx_count as usize {
+            return Ok(None);
+        }
+
+        let txs = txs
+            .into_iter()
+            .map(SignedZkSyncTx::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let eth_signatures = serde_json::from_value(batch.eth_signatures)?;
+        // Batch identifier is assigned by the mempool.
+        Ok(Some(SignedTxVariant::batch(txs, 0, eth_signatures)))
+    }
+
+    /// Marks the outbox transaction as processed by the mempool. Transactions rejected
+    /// by the mempool are marked as delivered too, along with the rejection reason.
+    pub async fn mark_outbox_tx_delivered(
+        &mut self,
+        tx_hash: TxHash,
+        rejection_reason: Option<&str>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE tx_submission_outbox
+            SET delivered_at = now(), rejection_reason = $2
+            WHERE tx_hash = $1",
+            tx_hash.as_ref(),
+            rejection_reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.mark_outbox_tx_delivered",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Records a failed attempt to forward the outbox transaction, it will be retried later.
+    pub async fn record_outbox_tx_failure(
+        &mut self,
+        tx_hash: TxHash,
+        error: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE tx_submission_outbox
+            SET attempts = attempts + 1, last_error = $2
+            WHERE tx_hash = $1",
+            tx_hash.as_ref(),
+            error
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.record_outbox_tx_failure",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Returns the receipt of the outbox transaction: queued ones are not forwarded
+    /// to the mempool yet, rejected ones were refused by the mempool. Successfully
+    /// delivered transactions are not reported, since they can be found in the mempool
+    /// or among the executed ones.
+    pub async fn get_outbox_tx_receipt(&mut self, tx_hash: TxHash) -> QueryResult<Option<Receipt>> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            "SELECT delivered_at, rejection_reason FROM tx_submission_outbox
+            WHERE tx_hash = $1",
+            tx_hash.as_ref()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        let receipt = record.and_then(|record| {
+            let status = match (record.delivered_at, &record.rejection_reason) {
+                (None, _) => TxInBlockStatus::Queued,
+                (Some(_), Some(_)) => TxInBlockStatus::Rejected,
+                (Some(_), None) => return None,
+            };
+            Some(Receipt::L2(L2Receipt {
+                tx_hash,
+                rollup_block: None,
+                status,
+                fail_reason: record.rejection_reason,
+                archived: false,
+            }))
+        });
+
+        metrics::histogram!("sql.chain.mempool.get_outbox_tx_receipt", start.elapsed());
+        Ok(receipt)
+    }
+
+    /// Returns the info of the outbox batch, reported the same way as the outbox transactions
+    /// in `get_outbox_tx_receipt`.
+    pub async fn get_outbox_batch_info(
+        &mut self,
+        batch_hash: TxHash,
+    ) -> QueryResult<Option<ApiTxBatch>> {
+        let start = Instant::now();
+        let records = sqlx::query!(
+            "SELECT tx_hash, tx_submission_outbox.created_at, delivered_at, rejection_reason
+            FROM tx_submission_outbox
+            WHERE batch_hash = $1
+            ORDER BY batch_position",
+            batch_hash.as_ref()
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let info = records.first().and_then(|record| {
+            let (last_state, updated_at) = match (record.delivered_at, &record.rejection_reason) {
+                (None, _) => (TxInBlockStatus::Queued, record.created_at),
+                (Some(delivered_at), Some(_)) => (TxInBlockStatus::Rejected, delivered_at),
+                (Some(_), None) => return None,
+            };
+            Some(ApiTxBatch {
+                batch_hash,
+                transaction_hashes: records
+                    .iter()
+                    .map(|record| {
+                        TxHashSerializeWrapper(TxHash::from_slice(&record.tx_hash).unwrap())
+                    })
+                    .collect(),
+                created_at: record.created_at,
+                batch_status: BatchStatus {
+                    updated_at,
+                    last_state,
+                    fail_reason: record.rejection_reason.clone(),
+                },
+            })
+        });
+
+        metrics::histogram!("sql.chain.mempool.get_outbox_batch_info", start.elapsed());
+        Ok(info)
+    }
+
+    /// Loads the outbox transaction that is not forwarded to the mempool yet,
+    /// along with the hash of the batch it is submitted in.
+    pub async fn get_undelivered_outbox_tx(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<(SignedZkSyncTx, Option<TxHash>)>> {
+        let start = Instant::now();
+        let record = sqlx::query_as!(
+            OutboxTx,
+            "SELECT tx, eth_sign_data, created_at, batch_hash FROM tx_submission_outbox
+            WHERE tx_hash = $1 AND delivered_at IS NULL",
+            tx_hash.as_ref()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+        let tx = match record {
+            Some(record) => {
+                let batch_hash = record.batch_hash.as_deref().and_then(TxHash::from_slice);
+                Some((SignedZkSyncTx::try_from(record)?, batch_hash))
+            }
+            None => None,
+        };
+
+        metrics::histogram!(
+            "sql.chain.mempool.get_undelivered_outbox_tx",
+            start.elapsed()
+        );
+        Ok(tx)
+    }
+
+    /// Cancels the outbox transaction that is not forwarded to the mempool yet, so it's reported
+    /// as rejected with [CANCELLED_TX_REASON]. Returns `false` if the transaction is already delivered.
+    /// Transactions of the batches can't be cancelled and are never affected.
+    ///
+    /// The forwarder keeps the transactions locked until they're marked as delivered, so the
+    /// cancellation of the transaction being forwarded waits for the forwarding to complete.
+    pub async fn cancel_outbox_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let cancelled = sqlx::query!(
+            "UPDATE tx_submission_outbox
+            SET delivered_at = now(), rejection_reason = $2
+            WHERE tx_hash = $1 AND delivered_at IS NULL AND batch_hash IS NULL",
+            tx_hash.as_ref(),
+            CANCELLED_TX_REASON
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        metrics::histogram!("sql.chain.mempool.cancel_outbox_tx", start.elapsed());
+        Ok(cancelled)
+    }
+
+    /// Removes outbox transactions delivered before the provided moment, along with
+    /// their batches. Returns the number of removed transactions.
+    pub async fn remove_delivered_outbox_txs_older_than(
+        &mut self,
+        timestamp: DateTime<Utc>,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let removed = sqlx::query!(
+            "DELETE FROM tx_submission_outbox WHERE delivered_at < $1",
+            timestamp
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected();
+        // Transactions of the batch are delivered all at once, so no batch is left partially removed.
+        sqlx::query!(
+            "DELETE FROM tx_submission_outbox_batches
+            WHERE NOT EXISTS (
+                SELECT 1 FROM tx_submission_outbox
+                WHERE tx_submission_outbox.batch_hash = tx_submission_outbox_batches.batch_hash
+            )"
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.remove_delivered_outbox_txs_older_than",
+            start.elapsed()
+        );
+        Ok(removed)
+    }
+
+    /// Checks if the transaction with the given hash is either in the mempool or already executed.
+    /// Used to ignore the repeated submissions of the same transaction.
+    pub async fn is_tx_known(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let known = sqlx::query!(
+            r#"SELECT
+                EXISTS(SELECT 1 FROM mempool_txs WHERE tx_hash = $1)
+                OR EXISTS(SELECT 1 FROM executed_transactions WHERE tx_hash = $2) AS "known!""#,
+            hex::encode(tx_hash.as_ref()),
+            tx_hash.as_ref()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .known;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "is_tx_known");
+        Ok(known)
+    }
+
     /// Checks if the memory pool contains transaction with the given hash.
     pub async fn contains_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
         let start = Instant::now();
@@ -338,6 +869,117 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .map_err(anyhow::Error::from)
     }
 
+    /// Returns the mempool transaction along with the ID of its batch,
+    /// `None` for the transactions submitted on their own.
+    pub async fn get_tx_with_batch_id(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<(SignedZkSyncTx, Option<i64>)>> {
+        let start = Instant::now();
+
+        let mempool_tx = self.get_mempool_tx(tx_hash.as_ref()).await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_tx_with_batch_id");
+        mempool_tx
+            .map(|mempool_tx| {
+                // Special case: batch_id == 0 <==> transaction is not a part of some batch
+                let batch_id = Some(mempool_tx.batch_id).filter(|&batch_id| batch_id != 0);
+                SignedZkSyncTx::try_from(mempool_tx).map(|tx| (tx, batch_id))
+            })
+            .transpose()
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Removes the transaction submitted on its own from the mempool and records it as evicted
+    /// with [CANCELLED_TX_REASON]. Batches are executed atomically, so their members are never
+    /// removed. Returns `false` if the transaction is not removed.
+    pub async fn cancel_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let removed = sqlx::query!(
+            "DELETE FROM mempool_txs
+            WHERE tx_hash = $1 AND batch_id = 0
+            RETURNING tx",
+            hex::encode(tx_hash.as_ref())
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+
+        let cancelled = match removed {
+            Some(removed) => {
+                let tx: ZkSyncTx = serde_json::from_value(removed.tx.clone())?;
+                sqlx::query!(
+                    "INSERT INTO mempool_evicted_txs (tx_hash, address, tx, fail_reason)
+                    VALUES ($1, $2, $3, $4)",
+                    tx_hash.as_ref(),
+                    tx.account().as_bytes(),
+                    removed.tx,
+                    CANCELLED_TX_REASON
+                )
+                .execute(transaction.conn())
+                .await?;
+                true
+            }
+            None => false,
+        };
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.mempool.cancel_tx", start.elapsed());
+        Ok(cancelled)
+    }
+
+    /// Returns the transactions in the mempool that are initiated by the account
+    /// or transfer the funds to it, in the order they were added.
+    pub async fn get_pending_txs_for_address(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Vec<ZkSyncTx>> {
+        let start = Instant::now();
+        let txs = sqlx::query!(
+            "SELECT tx FROM mempool_txs
+            WHERE account_address = $1 OR recipient_address = $1
+            ORDER BY id",
+            address.as_bytes()
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| serde_json::from_value(record.tx))
+        .collect::<Result<Vec<ZkSyncTx>, _>>()?;
+
+        metrics::histogram!(
+            "sql.chain.mempool.get_pending_txs_for_address",
+            start.elapsed()
+        );
+        Ok(txs)
+    }
+
+    /// Returns the nonces of the transactions in the mempool initiated by the account.
+    /// Forced exits are not included, since their nonces belong to the initiators
+    /// rather than to the target accounts.
+    pub async fn get_pending_nonces_for_address(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Vec<Nonce>> {
+        let start = Instant::now();
+        let nonces = sqlx::query!(
+            r#"SELECT (tx->>'nonce')::bigint as "nonce!" FROM mempool_txs
+            WHERE account_address = $1 AND tx->>'type' <> 'ForcedExit'"#,
+            address.as_bytes()
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| Nonce(record.nonce as u32))
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain.mempool.get_pending_nonces_for_address",
+            start.elapsed()
+        );
+        Ok(nonces)
+    }
+
     /// Returns mempool transaction as it is stored in the database.
     async fn get_mempool_tx(&mut self, tx_hash: &[u8]) -> QueryResult<Option<MempoolTx>> {
         let start = Instant::now();
@@ -529,7 +1171,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         start_serial_id: SerialId,
         limit: u32,
         direction: PaginationDirection,
-    ) -> QueryResult<Vec<PriorityOp>> {
+    ) -> QueryResult<Vec<PendingPriorityOp>> {
         let query = "SELECT serial_id,data,deadline_block,eth_hash,tx_hash,eth_block,eth_block_index,created_at FROM mempool_priority_operations WHERE l2_address = $1";
         let query = match direction {
             PaginationDirection::Newer => {
@@ -551,10 +1193,13 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(ops.into_iter().map(|op| op.into()).collect())
     }
 
+    /// Loads the pending priority operation by the hash of its Ethereum transaction.
+    /// The creation time of the operation is the moment the Ethereum watcher first noticed it,
+    /// it's preserved once the operation gets confirmed.
     pub async fn get_pending_operation_by_hash(
         &mut self,
         tx_hash: H256,
-    ) -> QueryResult<Option<PriorityOp>> {
+    ) -> QueryResult<Option<PendingPriorityOp>> {
         let op = sqlx::query_as!(
             MempoolPriorityOp,
             r#"
@@ -646,6 +1291,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 batch_status: BatchStatus {
                     updated_at: created_at,
                     last_state: TxInBlockStatus::Queued,
+                    fail_reason: None,
                 },
             })
         } else {
@@ -656,6 +1302,54 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(result)
     }
 
+    /// Get info about batch that was evicted from the mempool.
+    pub async fn get_evicted_batch_info(
+        &mut self,
+        batch_hash: TxHash,
+    ) -> QueryResult<Option<ApiTxBatch>> {
+        let start = Instant::now();
+
+        let batch_data = sqlx::query_as!(
+            EvictedBatchTx,
+            r#"
+                SELECT tx_hash, fail_reason, evicted_at
+                FROM mempool_evicted_txs
+                INNER JOIN txs_batches_hashes
+                ON txs_batches_hashes.batch_id = mempool_evicted_txs.batch_id
+                WHERE batch_hash = $1
+                ORDER BY id ASC
+            "#,
+            batch_hash.as_ref()
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+        let result = if !batch_data.is_empty() {
+            let evicted_at = batch_data[0].evicted_at;
+            // Explicitly evicted transactions are recorded before the rest of their batch,
+            // so the original reason of the eviction is reported.
+            let fail_reason = batch_data[0].fail_reason.clone();
+            let transaction_hashes: Vec<TxHashSerializeWrapper> = batch_data
+                .iter()
+                .map(|tx| TxHashSerializeWrapper(TxHash::from_slice(&tx.tx_hash).unwrap()))
+                .collect();
+            Some(ApiTxBatch {
+                batch_hash,
+                transaction_hashes,
+                created_at: evicted_at,
+                batch_status: BatchStatus {
+                    updated_at: evicted_at,
+                    last_state: TxInBlockStatus::Rejected,
+                    fail_reason: Some(fail_reason),
+                },
+            })
+        } else {
+            None
+        };
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_evicted_batch_info");
+        Ok(result)
+    }
+
     pub async fn get_reverted_blocks(
         &mut self,
         available_block_sizes: &[usize],
@@ -878,6 +1572,11 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             block_number = block_number + 1;
         }
 
+        let eth_sign_tokens = EthSignTokens::load(
+            &mut transaction,
+            reverted_txs.iter().map(|(tx, _, _)| &tx.signed_tx),
+        )
+        .await?;
         for (reverted_tx, block_number, next_priority_op_serial_id) in reverted_txs {
             let ExecutedTx {
                 signed_tx,
@@ -894,20 +1593,16 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             let from_account = signed_tx.from_account().as_bytes().to_vec();
             let to_account = signed_tx.to_account().map(|a| a.as_bytes().to_vec());
             let primary_account_address = signed_tx.account().as_bytes().to_vec();
-
-            let SignedZkSyncTx {
-                tx, eth_sign_data, ..
-            } = signed_tx;
+            let eth_sign_data = eth_sign_tokens.eth_sign_data_to_store(&signed_tx);
+            let tx = signed_tx.tx;
 
             let tx_hash_bytes = tx.hash().as_ref().to_vec();
             let tx_hash = hex::encode(&tx_hash_bytes);
+            let (account_address, recipient_address) = tx_addresses(&tx);
             let tx_value =
                 serde_json::to_value(tx).expect("Failed to serialize reverted transaction");
             let operation =
                 serde_json::to_value(op).expect("Failed to serialize reverted transaction");
-            let eth_sign_data = eth_sign_data.as_ref().map(|sign_data| {
-                serde_json::to_value(sign_data).expect("Failed to serialize Ethereum sign data")
-            });
 
             sqlx::query!(
                 r#"INSERT INTO mempool_reverted_txs_meta (
@@ -931,14 +1626,16 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .await?;
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id, reverted)
-                VALUES ($1, $2, $3, $4, $5, $6, true)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id, reverted, account_address, recipient_address)
+                VALUES ($1, $2, $3, $4, $5, $6, true, $7, $8)",
                 tx_hash,
                 tx_value,
                 created_at,
                 eth_sign_data,
                 batch_id.unwrap_or(0i64),
                 next_priority_op_serial_id as i64,
+                account_address,
+                recipient_address,
             )
             .execute(transaction.conn())
             .await?;
@@ -1040,6 +1737,13 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         )
         .execute(transaction.conn())
         .await?;
+        sqlx::query!(
+            r"DELETE FROM tx_batch_summaries
+            WHERE block_number > $1",
+            *last_block_number as i64
+        )
+        .execute(transaction.conn())
+        .await?;
         transaction.commit().await?;
 
         metrics::histogram!(
@@ -9,6 +9,7 @@ use sqlx::FromRow;
 use zksync_types::{PriorityOp, SignedZkSyncTx, H256};
 
 // Local imports
+use crate::utils::decode_eth_sign_data;
 
 #[derive(Debug, FromRow)]
 pub(crate) struct RevertedBlock {
@@ -33,18 +34,24 @@ pub(crate) struct MempoolTx {
     pub next_priority_op_serial_id: Option<i64>,
     #[allow(dead_code)]
     pub reverted: bool,
+    #[allow(dead_code)]
+    pub account_address: Option<Vec<u8>>,
+    #[allow(dead_code)]
+    pub recipient_address: Option<Vec<u8>>,
 }
 
 impl TryFrom<MempoolTx> for SignedZkSyncTx {
     type Error = serde_json::Error;
 
     fn try_from(value: MempoolTx) -> Result<Self, Self::Error> {
+        let tx = serde_json::from_value(value.tx)?;
+        let eth_sign_data = value
+            .eth_sign_data
+            .map(|value| decode_eth_sign_data(&tx, value))
+            .transpose()?;
         Ok(Self {
-            tx: serde_json::from_value(value.tx)?,
-            eth_sign_data: value
-                .eth_sign_data
-                .map(serde_json::from_value)
-                .transpose()?,
+            tx,
+            eth_sign_data,
             created_at: value.created_at,
         })
     }
@@ -56,6 +63,13 @@ pub(crate) struct QueuedBatchTx {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, FromRow, PartialEq)]
+pub(crate) struct EvictedBatchTx {
+    pub tx_hash: Vec<u8>,
+    pub fail_reason: String,
+    pub evicted_at: DateTime<Utc>,
+}
+
 #[derive(Debug, FromRow)]
 pub(crate) struct MempoolPriorityOp {
     pub serial_id: i64,
@@ -63,13 +77,30 @@ pub(crate) struct MempoolPriorityOp {
     pub tx_hash: String,
     pub eth_hash: Vec<u8>,
     pub data: serde_json::Value,
-    #[allow(dead_code)]
     pub created_at: DateTime<Utc>,
     pub eth_block: i64,
     pub eth_block_index: Option<i32>,
     pub deadline_block: i64,
 }
 
+/// Priority operation waiting in the mempool along with the time
+/// it was first observed by the Ethereum watcher.
+#[derive(Debug, Clone)]
+pub struct PendingPriorityOp {
+    pub op: PriorityOp,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<MempoolPriorityOp> for PendingPriorityOp {
+    fn from(value: MempoolPriorityOp) -> Self {
+        let created_at = value.created_at;
+        Self {
+            op: value.into(),
+            created_at,
+        }
+    }
+}
+
 impl From<MempoolPriorityOp> for PriorityOp {
     fn from(value: MempoolPriorityOp) -> Self {
         Self {
@@ -82,3 +113,29 @@ impl From<MempoolPriorityOp> for PriorityOp {
         }
     }
 }
+
+#[derive(Debug, FromRow)]
+pub(crate) struct OutboxTx {
+    pub tx: serde_json::Value,
+    pub eth_sign_data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    /// Hash of the batch the transaction is submitted in, if any.
+    pub batch_hash: Option<Vec<u8>>,
+}
+
+impl TryFrom<OutboxTx> for SignedZkSyncTx {
+    type Error = serde_json::Error;
+
+    fn try_from(value: OutboxTx) -> Result<Self, Self::Error> {
+        let tx = serde_json::from_value(value.tx)?;
+        let eth_sign_data = value
+            .eth_sign_data
+            .map(|value| decode_eth_sign_data(&tx, value))
+            .transpose()?;
+        Ok(Self {
+            tx,
+            eth_sign_data,
+            created_at: value.created_at,
+        })
+    }
+}
@@ -258,6 +258,12 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             )
             .execute(transaction.conn())
             .await?;
+
+            if let Some(batch_id) = operation.batch_id {
+                OperationsSchema(&mut transaction)
+                    .store_batch_summary(batch_id)
+                    .await?;
+            }
         }
         transaction.commit().await?;
         metrics::histogram!("sql.chain.operations.store_executed_tx", start.elapsed());
@@ -269,6 +275,80 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Creates or refreshes the summary of the executed batch, so that the batch info
+    /// can be loaded without resolving the statuses of its members.
+    pub(crate) async fn store_batch_summary(&mut self, batch_id: i64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO tx_batch_summaries (
+                batch_id, batch_hash, member_count, block_number, success, created_at, committed_at, finalized_at
+            )
+            SELECT
+                txs_batches_hashes.batch_id,
+                txs_batches_hashes.batch_hash,
+                members.member_count,
+                first_tx.block_number,
+                first_tx.success,
+                first_tx.created_at,
+                (
+                    SELECT created_at FROM aggregate_operations
+                    WHERE action_type = 'CommitBlocks' AND first_tx.block_number BETWEEN from_block AND to_block
+                    ORDER BY id LIMIT 1
+                ),
+                (
+                    SELECT created_at FROM aggregate_operations
+                    WHERE action_type = 'ExecuteBlocks' AND first_tx.block_number BETWEEN from_block AND to_block
+                    ORDER BY id LIMIT 1
+                )
+            FROM (
+                SELECT count(*) AS member_count, min(sequence_number) AS first_sequence_number
+                FROM executed_transactions
+                WHERE batch_id = $1
+            ) AS members
+            INNER JOIN executed_transactions AS first_tx ON first_tx.sequence_number = members.first_sequence_number
+            INNER JOIN txs_batches_hashes ON txs_batches_hashes.batch_id = $1
+            ON CONFLICT (batch_id)
+            DO UPDATE
+            SET member_count = EXCLUDED.member_count, block_number = EXCLUDED.block_number,
+                success = EXCLUDED.success, created_at = EXCLUDED.created_at,
+                committed_at = EXCLUDED.committed_at, finalized_at = EXCLUDED.finalized_at
+            "#,
+            batch_id
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.operations.store_batch_summary", start.elapsed());
+        Ok(())
+    }
+
+    /// Recalculates the commit and finalization times of the batches executed in blocks
+    /// with number greater than `last_block` after their aggregated operations were removed.
+    async fn reset_batch_summaries_statuses(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE tx_batch_summaries
+            SET
+                committed_at = (
+                    SELECT created_at FROM aggregate_operations
+                    WHERE action_type = 'CommitBlocks' AND tx_batch_summaries.block_number BETWEEN from_block AND to_block
+                    ORDER BY id LIMIT 1
+                ),
+                finalized_at = (
+                    SELECT created_at FROM aggregate_operations
+                    WHERE action_type = 'ExecuteBlocks' AND tx_batch_summaries.block_number BETWEEN from_block AND to_block
+                    ORDER BY id LIMIT 1
+                )
+            WHERE block_number > $1
+            "#,
+            *last_block as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+        Ok(())
+    }
+
     /// Removes all rejected transactions with an age greater than `max_age` from the database.
     pub async fn remove_rejected_transactions(&mut self, max_age: Duration) -> QueryResult<()> {
         let start = Instant::now();
@@ -300,6 +380,19 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .execute(transaction.conn())
         .await?;
+        // Rejected batches are removed together with their last member.
+        sqlx::query!(
+            r#"
+            DELETE FROM tx_batch_summaries
+            WHERE success = false AND created_at < $1 AND NOT EXISTS (
+                SELECT 1 FROM executed_transactions
+                WHERE executed_transactions.batch_id = tx_batch_summaries.batch_id
+            )
+            "#,
+            offset
+        )
+        .execute(transaction.conn())
+        .await?;
 
         transaction.commit().await?;
 
@@ -317,9 +410,20 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
     #[doc = "hidden"]
     pub async fn store_executed_priority_op(
         &mut self,
-        operation: NewExecutedPriorityOperation,
+        mut operation: NewExecutedPriorityOperation,
     ) -> QueryResult<()> {
         let start = Instant::now();
+        // Operations are looked up by both hashes, so the zkSync hash is never left empty.
+        if operation.tx_hash.is_empty() {
+            operation.tx_hash = PriorityOp::compute_tx_hash(
+                &operation.eth_hash,
+                operation.eth_block as u64,
+                operation.eth_block_index.map(|index| index as u64),
+                operation.priority_op_serialid as u64,
+            )
+            .as_ref()
+            .to_vec();
+        }
         let mut transaction = self.0.start_transaction().await?;
 
         MempoolSchema(&mut transaction)
@@ -331,7 +435,7 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
                 priority_op_serialid, deadline_block, eth_hash, eth_block, created_at, eth_block_index, tx_hash)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
             ON CONFLICT (priority_op_serialid)
-            DO NOTHING
+            DO UPDATE SET tx_hash = EXCLUDED.tx_hash WHERE executed_priority_operations.tx_hash = ''::bytea
             RETURNING sequence_number
             ",
             operation.block_number,
@@ -379,6 +483,44 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Restores the zkSync hashes of the executed priority operations stored without them,
+    /// the hashes are recomputed from the Ethereum transactions the operations were emitted by.
+    /// Returns the number of the restored hashes.
+    pub async fn restore_priority_op_tx_hashes(&mut self) -> QueryResult<usize> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let operations = sqlx::query!(
+            "SELECT priority_op_serialid, eth_hash, eth_block, eth_block_index
+            FROM executed_priority_operations WHERE tx_hash = ''::bytea"
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        for operation in &operations {
+            let tx_hash = PriorityOp::compute_tx_hash(
+                &operation.eth_hash,
+                operation.eth_block as u64,
+                operation.eth_block_index.map(|index| index as u64),
+                operation.priority_op_serialid as u64,
+            );
+            sqlx::query!(
+                "UPDATE executed_priority_operations SET tx_hash = $2
+                WHERE priority_op_serialid = $1 AND tx_hash = ''::bytea",
+                operation.priority_op_serialid,
+                tx_hash.as_ref()
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.restore_priority_op_tx_hashes",
+            start.elapsed()
+        );
+        Ok(operations.len())
+    }
+
     /// Returns the highest serial id of the executed priority ops
     pub async fn get_max_priority_op_serial_id(&mut self) -> QueryResult<Option<SerialId>> {
         let start = Instant::now();
@@ -536,6 +678,17 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
                 "#, 
                 id
             ).execute(transaction.conn()).await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE tx_batch_summaries
+                SET committed_at = aggregate_operations.created_at
+                FROM aggregate_operations
+                WHERE aggregate_operations.id = $1 AND tx_batch_summaries.committed_at IS NULL
+                    AND tx_batch_summaries.block_number BETWEEN aggregate_operations.from_block AND aggregate_operations.to_block
+                "#,
+                id
+            ).execute(transaction.conn()).await?;
         }
 
         if operation.is_execute() {
@@ -550,6 +703,17 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
                 "#, 
                 id
             ).execute(transaction.conn()).await?;
+
+            sqlx::query!(
+                r#"
+                UPDATE tx_batch_summaries
+                SET finalized_at = aggregate_operations.created_at
+                FROM aggregate_operations
+                WHERE aggregate_operations.id = $1 AND tx_batch_summaries.finalized_at IS NULL
+                    AND tx_batch_summaries.block_number BETWEEN aggregate_operations.from_block AND aggregate_operations.to_block
+                "#,
+                id
+            ).execute(transaction.conn()).await?;
         }
 
         if !operation.is_create_proof() {
@@ -737,6 +901,9 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .execute(transaction.conn())
         .await?;
+        OperationsSchema(&mut transaction)
+            .reset_batch_summaries_statuses(last_block)
+            .await?;
         transaction.commit().await?;
 
         metrics::histogram!(
@@ -2,15 +2,13 @@
 // External imports
 // Workspace imports
 use zksync_api_types::v02::transaction::{
-    ForcedExitData, L1Receipt, L1Transaction, L2Receipt, L2Transaction, Receipt, Transaction,
-    TransactionData, TxData, TxInBlockStatus, WithdrawData, WithdrawNFTData,
-};
-use zksync_types::{
-    tx::{EthSignData, TxHash},
-    BlockNumber, EthBlockId, ZkSyncOp, ZkSyncTx, H256,
+    ForcedExitData, L1Receipt, L1Transaction, L2Receipt, L2Transaction, Receipt, SwapData,
+    Transaction, TransactionData, TxData, TxInBlockStatus, WithdrawData, WithdrawNFTData,
 };
+use zksync_types::{tx::TxHash, BlockNumber, EthBlockId, ZkSyncOp, ZkSyncTx, H256};
 // Local imports
 use super::records::{StorageTxData, StorageTxReceipt};
+use crate::utils::decode_eth_signature;
 
 impl StorageTxReceipt {
     pub(super) fn receipt_from_storage_receipt(
@@ -35,6 +33,9 @@ impl StorageTxReceipt {
                         .block_number
                         .map(|number| BlockNumber(number as u32)),
                     id: receipt.priority_op_serialid.unwrap() as u64,
+                    confirmations_remaining: None,
+                    created_at: receipt.created_at,
+                    archived: false,
                 })
             } else {
                 Receipt::L2(L2Receipt {
@@ -44,6 +45,7 @@ impl StorageTxReceipt {
                         .block_number
                         .map(|number| BlockNumber(number as u32)),
                     fail_reason: receipt.fail_reason,
+                    archived: false,
                 })
             }
         } else {
@@ -52,13 +54,14 @@ impl StorageTxReceipt {
                 tx_hash: TxHash::from_slice(&receipt.tx_hash).unwrap(),
                 rollup_block: None,
                 fail_reason: None,
+                archived: false,
             })
         }
     }
 }
 
 impl StorageTxData {
-    pub(super) fn tx_data_from_zksync_tx(
+    pub(crate) fn tx_data_from_zksync_tx(
         tx: ZkSyncTx,
         complete_withdrawals_tx_hash: Option<H256>,
     ) -> TransactionData {
@@ -79,7 +82,7 @@ impl StorageTxData {
                 tx: *tx,
                 eth_tx_hash: complete_withdrawals_tx_hash,
             })),
-            ZkSyncTx::Swap(tx) => L2Transaction::Swap(tx),
+            ZkSyncTx::Swap(tx) => L2Transaction::Swap(Box::new(SwapData::from(*tx))),
         };
         TransactionData::L2(tx)
     }
@@ -143,9 +146,15 @@ impl StorageTxData {
             }
         };
         let eth_signature = data.eth_sign_data.map(|eth_sign_data| {
-            let eth_sign_data: EthSignData = serde_json::from_value(eth_sign_data).unwrap();
-            eth_sign_data.signature.to_string()
+            decode_eth_signature(eth_sign_data)
+                .expect("Unparsable EthSignData")
+                .to_string()
         });
-        TxData { tx, eth_signature }
+        TxData {
+            tx,
+            eth_signature,
+            l2_signature: None,
+            archived: false,
+        }
     }
 }
@@ -7,6 +7,7 @@ use chrono::{DateTime, Utc};
 // Workspace imports
 use zksync_api_types::{
     v02::{
+        account::{AccountActivityEntry, ActivityKind},
         pagination::{AccountTxsRequest, PaginationDirection, PaginationQuery},
         transaction::{
             ApiTxBatch, BatchStatus, Receipt, Transaction, TxData, TxHashSerializeWrapper,
@@ -23,8 +24,9 @@ use zksync_types::{
 
 // Local imports
 use self::records::{
-    AccountCreatedAt, InBlockBatchTx, PriorityOpReceiptResponse, StorageTxData, StorageTxReceipt,
-    TransactionsHistoryItem, TxByHashResponse, TxReceiptResponse, Web3TxData, Web3TxReceipt,
+    AccountCreatedAt, PriorityOpReceiptResponse, RejectedTxRecord, StorageTxData, StorageTxReceipt,
+    StoredTxBatchSummary, TransactionsHistoryItem, TxByHashResponse, TxHashPrefixMatch,
+    TxHistoryCursor, TxReceiptResponse, Web3TxData, Web3TxReceipt,
 };
 use crate::chain::operations_ext::records::SequenceNumberRecord;
 use crate::{
@@ -103,7 +105,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         success,
                         fail_reason,
                         Null::bigint as eth_block,
-                        Null::bigint as priority_op_serialid
+                        Null::bigint as priority_op_serialid,
+                        created_at
                     FROM executed_transactions
                     WHERE tx_hash = $1
                 ), priority_op AS (
@@ -113,7 +116,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         true as success,
                         Null as fail_reason,
                         eth_block,
-                        priority_op_serialid
+                        priority_op_serialid,
+                        created_at
                     FROM executed_priority_operations
                     WHERE tx_hash = $1 OR eth_hash = $1
                 ), mempool_tx AS (
@@ -123,7 +127,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         Null::boolean as success,
                         Null as fail_reason,
                         Null::bigint as eth_block,
-                        Null::bigint as priority_op_serialid
+                        Null::bigint as priority_op_serialid,
+                        created_at
                     FROM mempool_txs
                     WHERE tx_hash = $2
                 ),
@@ -140,7 +145,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     success as "success?",
                     fail_reason as "fail_reason?",
                     eth_block as "eth_block?",
-                    priority_op_serialid as "priority_op_serialid?"
+                    priority_op_serialid as "priority_op_serialid?",
+                    created_at as "created_at?"
                 FROM everything
             "#,
             hash,
@@ -665,8 +671,59 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(first_history_entry.map(|entry| entry.created_at))
     }
 
+    /// Replaces the token IDs in the transactions history entries with the token symbols.
+    async fn set_history_token_symbols(
+        &mut self,
+        tx_history: &mut [TransactionsHistoryItem],
+    ) -> QueryResult<()> {
+        if tx_history.is_empty() {
+            return Ok(());
+        }
+
+        let tokens = self.0.tokens_schema().load_tokens().await?;
+        for tx_item in tx_history {
+            let tx_info = match tx_item.tx["type"].as_str().unwrap_or("NONE") {
+                "NONE" => {
+                    vlog::warn!("Tx history item type not found, tx: {:?}", tx_item);
+                    continue;
+                }
+                "Deposit" | "FullExit" => tx_item.tx.get_mut("priority_op"),
+                _ => Some(&mut tx_item.tx),
+            };
+
+            let tx_info = if let Some(tx_info) = tx_info {
+                tx_info
+            } else {
+                vlog::warn!("tx_info not found for tx: {:?}", tx_item);
+                continue;
+            };
+
+            if let Some(tok_val) = tx_info.get_mut("token") {
+                if let Some(token_id) = tok_val.as_u64() {
+                    if token_id < params::MIN_NFT_TOKEN_ID as u64 {
+                        let token_id = TokenId(token_id as u32);
+                        let token_symbol = tokens
+                            .get(&token_id)
+                            .map(|t| t.symbol.clone())
+                            .unwrap_or_else(|| "UNKNOWN".to_string());
+                        *tok_val =
+                            serde_json::to_value(token_symbol).expect("json string to value");
+                    } else {
+                        *tok_val = serde_json::to_value(token_id).expect("json string to value");
+                    }
+                };
+            };
+        }
+        Ok(())
+    }
+
     /// Loads the range of the transactions applied to the account starting
     /// from the block with number $(offset) up to $(offset + limit).
+    ///
+    /// Deep pages are slow to load for the accounts with a long history,
+    /// [`get_account_transactions_history_page`] should be used to iterate over the history.
+    ///
+    /// [`get_account_transactions_history_page`]: OperationsExtSchema::get_account_transactions_history_page()
     pub async fn get_account_transactions_history(
         &mut self,
         address: &Address,
@@ -716,6 +773,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         success,
                         fail_reason,
                         block_number,
+                        block_index,
                         created_at,
                         executed_transactions.sequence_number,
                         batch_id
@@ -732,6 +790,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         true as success,
                         null as fail_reason,
                         block_number,
+                        block_index,
                         created_at,
                         executed_priority_operations.sequence_number,
                         Null::bigint as batch_id
@@ -751,7 +810,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 true as "commited!",
                 coalesce(verified.confirmed, false) as "verified!",
                 created_at as "created_at!",
-                batch_id as "batch_id?"
+                batch_id as "batch_id?",
+                block_index as "block_index?"
             FROM transactions
             LEFT JOIN aggr_exec verified ON transactions.block_number = verified.block_number
             ORDER BY transactions.block_number DESC, sequence_number DESC
@@ -760,43 +820,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         ).fetch_all(transaction.conn())
         .await?;
 
-        if !tx_history.is_empty() {
-            let tokens = transaction.tokens_schema().load_tokens().await?;
-            for tx_item in &mut tx_history {
-                let tx_info = match tx_item.tx["type"].as_str().unwrap_or("NONE") {
-                    "NONE" => {
-                        vlog::warn!("Tx history item type not found, tx: {:?}", tx_item);
-                        continue;
-                    }
-                    "Deposit" | "FullExit" => tx_item.tx.get_mut("priority_op"),
-                    _ => Some(&mut tx_item.tx),
-                };
-
-                let tx_info = if let Some(tx_info) = tx_info {
-                    tx_info
-                } else {
-                    vlog::warn!("tx_info not found for tx: {:?}", tx_item);
-                    continue;
-                };
-
-                if let Some(tok_val) = tx_info.get_mut("token") {
-                    if let Some(token_id) = tok_val.as_u64() {
-                        if token_id < params::MIN_NFT_TOKEN_ID as u64 {
-                            let token_id = TokenId(token_id as u32);
-                            let token_symbol = tokens
-                                .get(&token_id)
-                                .map(|t| t.symbol.clone())
-                                .unwrap_or_else(|| "UNKNOWN".to_string());
-                            *tok_val =
-                                serde_json::to_value(token_symbol).expect("json string to value");
-                        } else {
-                            *tok_val =
-                                serde_json::to_value(token_id).expect("json string to value");
-                        }
-                    };
-                };
-            }
-        }
+        transaction
+            .chain()
+            .operations_ext_schema()
+            .set_history_token_symbols(&mut tx_history)
+            .await?;
 
         transaction.commit().await?;
         metrics::histogram!(
@@ -806,6 +834,135 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(tx_history)
     }
 
+    /// Loads the page of the transactions applied to the account, newest first.
+    ///
+    /// Unlike `get_account_transactions_history`, this method uses the keyset pagination:
+    /// the page starts right after the `cursor` (or from the newest transaction if it's not set),
+    /// so the time to load the page doesn't depend on its depth, and the transactions applied
+    /// to the account in the meantime don't shift the following pages. Returns the page along
+    /// with the cursor of the next page, `None` if the page is the last one.
+    pub async fn get_account_transactions_history_page(
+        &mut self,
+        address: &Address,
+        cursor: Option<TxHistoryCursor>,
+        limit: u64,
+    ) -> QueryResult<(Vec<TransactionsHistoryItem>, Option<TxHistoryCursor>)> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let sequence_numbers: Vec<i64> = sqlx::query!(
+            r#"
+            SELECT DISTINCT sequence_number as "sequence_number!" FROM tx_filters
+            WHERE address = $1 AND sequence_number < $2
+            ORDER BY sequence_number DESC
+            LIMIT $3
+            "#,
+            address.as_bytes(),
+            cursor.map_or(i64::MAX, |cursor| cursor.sequence_number),
+            limit as i64
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|record| record.sequence_number)
+        .collect();
+
+        // The page that is not full is the last one.
+        let next_cursor = sequence_numbers
+            .last()
+            .filter(|_| sequence_numbers.len() as u64 == limit)
+            .map(|&sequence_number| TxHistoryCursor { sequence_number });
+
+        // The same query as in `get_account_transactions_history`, except that
+        // the page of sequence numbers is already loaded.
+        let mut tx_history = sqlx::query_as!(
+            TransactionsHistoryItem,
+            r#"
+            WITH aggr_exec AS (
+                SELECT
+                    aggregate_operations.confirmed,
+                    execute_aggregated_blocks_binding.block_number
+                FROM aggregate_operations
+                    INNER JOIN execute_aggregated_blocks_binding ON aggregate_operations.id = execute_aggregated_blocks_binding.op_id
+                WHERE aggregate_operations.confirmed = true
+            ), tx_hashes AS (
+                SELECT u.sequence_number
+                FROM UNNEST ($1::bigint[]) AS u(sequence_number)
+            ), transactions AS (
+                SELECT
+                    *
+                FROM (
+                    SELECT
+                        concat_ws(',', block_number, block_index) AS tx_id,
+                        tx,
+                        'sync-tx:' || encode(executed_transactions.tx_hash, 'hex') AS hash,
+                        null as pq_id,
+                        null as eth_block,
+                        success,
+                        fail_reason,
+                        block_number,
+                        block_index,
+                        created_at,
+                        executed_transactions.sequence_number,
+                        batch_id
+                    FROM executed_transactions
+                    INNER JOIN tx_hashes
+                        ON tx_hashes.sequence_number = executed_transactions.sequence_number
+                    UNION ALL
+                    SELECT
+                        concat_ws(',', block_number, block_index) AS tx_id,
+                        operation as tx,
+                        '0x' || encode(eth_hash, 'hex') as hash,
+                        priority_op_serialid as pq_id,
+                        eth_block,
+                        true as success,
+                        null as fail_reason,
+                        block_number,
+                        block_index,
+                        created_at,
+                        executed_priority_operations.sequence_number,
+                        Null::bigint as batch_id
+                    FROM executed_priority_operations
+                    INNER JOIN tx_hashes
+                        ON tx_hashes.sequence_number = executed_priority_operations.sequence_number
+                    ) t
+            )
+            SELECT
+                tx_id as "tx_id!",
+                hash as "hash?",
+                eth_block as "eth_block?",
+                pq_id as "pq_id?",
+                tx as "tx!",
+                success as "success?",
+                fail_reason as "fail_reason?",
+                true as "commited!",
+                coalesce(verified.confirmed, false) as "verified!",
+                created_at as "created_at!",
+                batch_id as "batch_id?",
+                block_index as "block_index?"
+            FROM transactions
+            LEFT JOIN aggr_exec verified ON transactions.block_number = verified.block_number
+            ORDER BY transactions.block_number DESC, sequence_number DESC
+            "#,
+            &sequence_numbers
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        transaction
+            .chain()
+            .operations_ext_schema()
+            .set_history_token_symbols(&mut tx_history)
+            .await?;
+
+        transaction.commit().await?;
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_transactions_history_page",
+            start.elapsed()
+        );
+        Ok((tx_history, next_cursor))
+    }
+
     async fn get_closest_sequence_number(
         &mut self,
         block_number: i64,
@@ -948,6 +1105,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         success,
                         fail_reason,
                         block_number,
+                        block_index,
                         created_at,
                         executed_transactions.sequence_number,
                         batch_id
@@ -964,6 +1122,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         true as success,
                         null as fail_reason,
                         block_number,
+                        block_index,
                         created_at,
                         executed_priority_operations.sequence_number,
                         Null::bigint as batch_id
@@ -984,7 +1143,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 true as commited,
                 coalesce(verified.confirmed, false) as verified,
                 created_at ,
-                batch_id 
+                batch_id,
+                block_index
             FROM transactions
             LEFT JOIN aggr_comm committed ON
                 committed.block_number = transactions.block_number AND committed.confirmed = true
@@ -1002,43 +1162,11 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .fetch_all(transaction.conn())
             .await?;
 
-        if !tx_history.is_empty() {
-            let tokens = transaction.tokens_schema().load_tokens().await?;
-            for tx_item in &mut tx_history {
-                let tx_info = match tx_item.tx["type"].as_str().unwrap_or("NONE") {
-                    "NONE" => {
-                        vlog::warn!("Tx history item type not found, tx: {:?}", tx_item);
-                        continue;
-                    }
-                    "Deposit" | "FullExit" => tx_item.tx.get_mut("priority_op"),
-                    _ => Some(&mut tx_item.tx),
-                };
-
-                let tx_info = if let Some(tx_info) = tx_info {
-                    tx_info
-                } else {
-                    vlog::warn!("tx_info not found for tx: {:?}", tx_item);
-                    continue;
-                };
-
-                if let Some(tok_val) = tx_info.get_mut("token") {
-                    if let Some(token_id) = tok_val.as_u64() {
-                        if token_id < params::MIN_NFT_TOKEN_ID as u64 {
-                            let token_id = TokenId(token_id as u32);
-                            let token_symbol = tokens
-                                .get(&token_id)
-                                .map(|t| t.symbol.clone())
-                                .unwrap_or_else(|| "UNKNOWN".to_string());
-                            *tok_val =
-                                serde_json::to_value(token_symbol).expect("json string to value");
-                        } else {
-                            *tok_val =
-                                serde_json::to_value(token_id).expect("json string to value");
-                        }
-                    };
-                };
-            }
-        }
+        transaction
+            .chain()
+            .operations_ext_schema()
+            .set_history_token_symbols(&mut tx_history)
+            .await?;
 
         transaction.commit().await?;
         metrics::histogram!(
@@ -1076,7 +1204,20 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .await?;
 
         let txs = if let Some(id_from) = sequence_number {
-            let raw_txs = if let Some(address) = query.from.second_address {
+            let raw_txs = if let Some(counterparty) = query.from.counterparty {
+                transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_transactions_with_counterparty(
+                        query.from.address,
+                        counterparty,
+                        query.from.token,
+                        i64::from(query.limit),
+                        id_from,
+                        query.direction,
+                    )
+                    .await?
+            } else if let Some(address) = query.from.second_address {
                 // It's impossible to have priority operations for two accounts
                 transaction
                     .chain()
@@ -1163,6 +1304,110 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(txs)
     }
 
+    /// Loads the transactions and priority operations applied to the account in the blocks
+    /// `(from_block, to_block]`, oldest first. Only the operations with the sequence number
+    /// greater than `after_sequence_number` are loaded, if it's set.
+    pub async fn get_account_transactions_in_blocks(
+        &mut self,
+        address: Address,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        after_sequence_number: Option<i64>,
+        limit: Option<u32>,
+    ) -> QueryResult<Vec<Transaction>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        // Sequence numbers are shared by the transactions and the priority operations,
+        // so the operation is found by the sequence number alone.
+        let items = sqlx::query_as!(
+            TransactionItem,
+            r#"
+            WITH account_ops AS (
+                SELECT DISTINCT sequence_number FROM tx_filters
+                WHERE address = $1 AND sequence_number > $4
+            )
+            SELECT
+                sequence_number as "sequence_number?",
+                tx_hash as "tx_hash!",
+                op as "op!",
+                block_number as "block_number!",
+                created_at as "created_at!",
+                success as "success!",
+                fail_reason as "fail_reason?",
+                eth_hash as "eth_hash?",
+                priority_op_serialid as "priority_op_serialid?",
+                block_index as "block_index?",
+                batch_id as "batch_id?"
+            FROM (
+                SELECT
+                    executed_transactions.sequence_number,
+                    tx_hash,
+                    tx as op,
+                    block_number,
+                    created_at,
+                    success,
+                    fail_reason,
+                    Null::bytea as eth_hash,
+                    Null::bigint as priority_op_serialid,
+                    block_index,
+                    batch_id
+                FROM account_ops
+                INNER JOIN executed_transactions
+                    ON executed_transactions.sequence_number = account_ops.sequence_number
+                WHERE block_number > $2 AND block_number <= $3
+                UNION ALL
+                SELECT
+                    executed_priority_operations.sequence_number,
+                    tx_hash,
+                    operation as op,
+                    block_number,
+                    created_at,
+                    true as success,
+                    Null as fail_reason,
+                    eth_hash,
+                    priority_op_serialid,
+                    block_index,
+                    Null::bigint as batch_id
+                FROM account_ops
+                INNER JOIN executed_priority_operations
+                    ON executed_priority_operations.sequence_number = account_ops.sequence_number
+                WHERE block_number > $2 AND block_number <= $3
+            ) t
+            ORDER BY sequence_number
+            LIMIT $5
+            "#,
+            address.as_bytes(),
+            i64::from(*from_block),
+            i64::from(*to_block),
+            after_sequence_number.unwrap_or(-1),
+            limit.map(i64::from)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        transaction.commit().await?;
+
+        let txs = items
+            .into_iter()
+            .map(|item| {
+                let is_finalized = item.block_number as u32 <= *last_finalized;
+                TransactionItem::transaction_from_item(item, is_finalized)
+            })
+            .collect();
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_transactions_in_blocks",
+            start.elapsed()
+        );
+        Ok(txs)
+    }
+
     async fn get_executed_transactions_for_two_accounts(
         &mut self,
         address: Address,
@@ -1230,6 +1475,91 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .await?)
     }
 
+    /// Loads the operations sent by the account to the counterparty or received from it.
+    ///
+    /// Unlike the `tx_filters` based lookups the sides of the operation are taken into account:
+    /// the counterparty is the recipient of the sent transactions, the sender of the received ones,
+    /// the L1 sender of the deposits and the target of the withdrawals. Every side of every table is
+    /// paged through separately using the `(from_account, to_account, sequence_number)` indices,
+    /// so at most `limit` rows per side are read regardless of the number of the account operations.
+    async fn get_transactions_with_counterparty(
+        &mut self,
+        address: Address,
+        counterparty: Address,
+        token: Option<TokenId>,
+        limit: i64,
+        id_from: i64,
+        direction: PaginationDirection,
+    ) -> QueryResult<Vec<TransactionItem>> {
+        let (sequence_number_filter, order) = match direction {
+            PaginationDirection::Newer => ("sequence_number >= $4", "ASC"),
+            PaginationDirection::Older => ("sequence_number <= $4", "DESC"),
+        };
+        let token_filter = |table: &str| {
+            if token.is_some() {
+                format!(
+                    "AND EXISTS (
+                        SELECT 1 FROM tx_filters
+                        WHERE tx_filters.tx_hash = {}.tx_hash
+                            AND tx_filters.address = $1 AND tx_filters.token = $3
+                    )",
+                    table
+                )
+            } else {
+                String::new()
+            }
+        };
+        // The operations received from the counterparty are skipped if it's the account itself,
+        // otherwise the operations with the same sender and recipient would be returned twice.
+        let page = |columns: &str, table: &str, from: &str, to: &str| {
+            format!(
+                "(
+                    SELECT {columns} FROM {table}
+                    WHERE from_account = {from} AND to_account = {to} AND ({from} = $1 OR $1 <> $2)
+                        AND {sequence_number_filter} {token_filter}
+                    ORDER BY sequence_number {order}
+                    LIMIT $5
+                )",
+                columns = columns,
+                table = table,
+                from = from,
+                to = to,
+                sequence_number_filter = sequence_number_filter,
+                token_filter = token_filter(table),
+                order = order,
+            )
+        };
+        let tx_columns = "tx_hash, sequence_number, tx as op, block_number, created_at, success, \
+            fail_reason, Null::bytea as eth_hash, Null::bigint as priority_op_serialid, \
+            block_index, batch_id";
+        let priority_op_columns = "tx_hash, sequence_number, operation as op, block_number, \
+            created_at, true as success, Null::text as fail_reason, eth_hash, priority_op_serialid, \
+            block_index, Null::bigint as batch_id";
+
+        let pages = [
+            ("executed_transactions", tx_columns),
+            ("executed_priority_operations", priority_op_columns),
+        ]
+        .iter()
+        .flat_map(|(table, columns)| {
+            vec![
+                page(columns, table, "$1", "$2"),
+                page(columns, table, "$2", "$1"),
+            ]
+        })
+        .join(" UNION ALL ");
+        let query = format!("{} ORDER BY sequence_number {} LIMIT $5", pages, order);
+
+        Ok(sqlx::query_as(&query)
+            .bind(address.as_bytes())
+            .bind(counterparty.as_bytes())
+            .bind(token.unwrap_or_default().0 as i32)
+            .bind(id_from)
+            .bind(limit)
+            .fetch_all(self.0.conn())
+            .await?)
+    }
+
     async fn get_tx_seq_numbers_for_account(
         &mut self,
         address: Address,
@@ -1333,6 +1663,208 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         .await?)
     }
 
+    /// Returns transactions of the account that were rejected after the provided moment,
+    /// newest first. Both failed executed transactions and transactions evicted from
+    /// the mempool are taken into account.
+    pub async fn get_account_rejected_txs(
+        &mut self,
+        address: Address,
+        since: DateTime<Utc>,
+        limit: u32,
+    ) -> QueryResult<Vec<RejectedTxRecord>> {
+        let start = Instant::now();
+        let records = sqlx::query_as!(
+            RejectedTxRecord,
+            r#"
+            SELECT tx_hash as "tx_hash!", fail_reason, created_at as "rejected_at!", false as "evicted!"
+                FROM executed_transactions
+                WHERE from_account = $1 AND success = false AND created_at >= $2
+            UNION ALL
+            SELECT tx_hash, fail_reason, evicted_at, true
+                FROM mempool_evicted_txs
+                WHERE address = $1 AND evicted_at >= $2
+            ORDER BY 3 DESC
+            LIMIT $3
+            "#,
+            address.as_bytes(),
+            since,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_rejected_txs",
+            start.elapsed()
+        );
+        Ok(records)
+    }
+
+    /// Returns the executed transactions, executed priority operations and mempool transactions
+    /// whose hashes start with `prefix`, ordered by the hash. `prefix` is a lowercase hex string
+    /// without `0x`, an odd number of digits is allowed.
+    ///
+    /// Hashes of the executed operations are compared with the range of the hashes having
+    /// the prefix, so the btree indices on `tx_hash` are used for the search.
+    pub async fn search_tx_hashes_by_prefix(
+        &mut self,
+        prefix: &str,
+        limit: u32,
+    ) -> QueryResult<Vec<TxHashPrefixMatch>> {
+        let start = Instant::now();
+        let hash_hex_len = 2 * std::mem::size_of::<H256>();
+        anyhow::ensure!(
+            prefix.len() <= hash_hex_len,
+            "Hash prefix is longer than the hash"
+        );
+        let lower_bound = hex::decode(format!("{:0<width$}", prefix, width = hash_hex_len))?;
+        let upper_bound = hex::decode(format!("{:f<width$}", prefix, width = hash_hex_len))?;
+        let mempool_pattern = format!("{}%", prefix);
+
+        let records = sqlx::query_as!(
+            TxHashPrefixMatch,
+            r#"
+            (
+                SELECT
+                    tx_hash as "tx_hash!",
+                    'executed' as "source!",
+                    tx->>'type' as tx_type,
+                    block_number as "block_number?",
+                    created_at as "created_at!",
+                    from_account as "account?"
+                FROM executed_transactions
+                WHERE tx_hash BETWEEN $1 AND $2
+                ORDER BY tx_hash
+                LIMIT $4
+            )
+            UNION ALL
+            (
+                SELECT tx_hash, 'priority', operation->>'type', block_number, created_at, from_account
+                FROM executed_priority_operations
+                WHERE tx_hash BETWEEN $1 AND $2
+                ORDER BY tx_hash
+                LIMIT $4
+            )
+            UNION ALL
+            (
+                SELECT decode(tx_hash, 'hex'), 'mempool', tx->>'type', NULL::bigint, created_at, account_address
+                FROM mempool_txs
+                WHERE tx_hash LIKE $3
+                ORDER BY tx_hash
+                LIMIT $4
+            )
+            ORDER BY 1
+            LIMIT $4
+            "#,
+            lower_bound,
+            upper_bound,
+            mempool_pattern,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.search_tx_hashes_by_prefix",
+            start.elapsed()
+        );
+        Ok(records)
+    }
+
+    /// Returns executed priority operations and transactions of the account, newest first.
+    /// Both are merged by the sequence number, which is shared between them, so the entries
+    /// with sequence number less than `before` make up a stable page regardless of the new ones.
+    pub async fn get_account_activity(
+        &mut self,
+        address: Address,
+        before: Option<i64>,
+        limit: u32,
+    ) -> QueryResult<Vec<AccountActivityEntry>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let items = sqlx::query_as!(
+            TransactionItem,
+            r#"
+            (
+                SELECT
+                    sequence_number,
+                    tx_hash as "tx_hash!",
+                    tx as "op!",
+                    block_number as "block_number!",
+                    created_at as "created_at!",
+                    success as "success!",
+                    fail_reason,
+                    Null::bytea as eth_hash,
+                    Null::bigint as priority_op_serialid,
+                    block_index,
+                    batch_id
+                FROM executed_transactions
+                WHERE (from_account = $1 OR to_account = $1) AND sequence_number < $2
+                ORDER BY sequence_number DESC
+                LIMIT $3
+            )
+            UNION ALL
+            (
+                SELECT
+                    sequence_number,
+                    tx_hash,
+                    operation,
+                    block_number,
+                    created_at,
+                    true,
+                    Null::text,
+                    eth_hash,
+                    priority_op_serialid,
+                    block_index,
+                    Null::bigint
+                FROM executed_priority_operations
+                WHERE (from_account = $1 OR to_account = $1) AND sequence_number < $2
+                ORDER BY sequence_number DESC
+                LIMIT $3
+            )
+            ORDER BY sequence_number DESC
+            LIMIT $3
+            "#,
+            address.as_bytes(),
+            before.unwrap_or(i64::MAX),
+            i64::from(limit)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        transaction.commit().await?;
+
+        let entries = items
+            .into_iter()
+            .map(|item| {
+                let kind = if item.eth_hash.is_some() {
+                    ActivityKind::L1
+                } else {
+                    ActivityKind::L2
+                };
+                let sequence_number = item.sequence_number.unwrap_or_default();
+                let is_finalized = item.block_number as u32 <= *last_finalized;
+                AccountActivityEntry {
+                    kind,
+                    sequence_number,
+                    tx: TransactionItem::transaction_from_item(item, is_finalized),
+                }
+            })
+            .collect();
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_activity",
+            start.elapsed()
+        );
+        Ok(entries)
+    }
+
     pub async fn get_account_last_tx_hash(
         &mut self,
         address: Address,
@@ -1536,6 +2068,49 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(count as u32)
     }
 
+    /// Returns the number of the operations between the account and the counterparty,
+    /// see `get_transactions_with_counterparty` for the matching rules.
+    pub async fn get_counterparty_transactions_count(
+        &mut self,
+        address: Address,
+        counterparty: Address,
+        token: Option<TokenId>,
+    ) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+            WITH operations AS (
+                SELECT tx_hash FROM executed_transactions
+                WHERE from_account = $1 AND to_account = $2
+                    OR from_account = $2 AND to_account = $1
+                UNION ALL
+                SELECT tx_hash FROM executed_priority_operations
+                WHERE from_account = $1 AND to_account = $2
+                    OR from_account = $2 AND to_account = $1
+            )
+            SELECT COUNT(*) as "count!" FROM operations
+            WHERE $3::boolean OR EXISTS (
+                SELECT 1 FROM tx_filters
+                WHERE tx_filters.tx_hash = operations.tx_hash
+                    AND tx_filters.address = $1 AND tx_filters.token = $4
+            )
+            "#,
+            address.as_bytes(),
+            counterparty.as_bytes(),
+            token.is_none(),
+            token.unwrap_or_default().0 as i32
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_counterparty_transactions_count",
+            start.elapsed()
+        );
+        Ok(count as u32)
+    }
+
     /// Returns `created_at` for `block_number` fields for transaction with given hash.
     pub async fn get_tx_sequence_number_for_block(
         &mut self,
@@ -1619,57 +2194,52 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
 
-        let batch_data: Vec<InBlockBatchTx> = sqlx::query_as!(
-            InBlockBatchTx,
-            r#"
-                SELECT tx_hash, created_at, success, block_number
-                FROM executed_transactions
-                INNER JOIN txs_batches_hashes
-                ON txs_batches_hashes.batch_id = COALESCE(executed_transactions.batch_id, 0)
-                WHERE batch_hash = $1
-                ORDER BY sequence_number ASC
-            "#,
+        let summary = sqlx::query_as!(
+            StoredTxBatchSummary,
+            "SELECT * FROM tx_batch_summaries WHERE batch_hash = $1 ORDER BY batch_id ASC LIMIT 1",
             batch_hash.as_ref()
         )
-        .fetch_all(transaction.conn())
+        .fetch_optional(transaction.conn())
         .await?;
-        let result = if !batch_data.is_empty() {
-            let created_at = batch_data[0].created_at;
-            let transaction_hashes: Vec<TxHashSerializeWrapper> = batch_data
+        let result = if let Some(summary) = summary {
+            let tx_hashes = sqlx::query_scalar!(
+                r#"
+                    SELECT array_agg(tx_hash ORDER BY sequence_number ASC)
+                    FROM executed_transactions
+                    INNER JOIN tx_batch_summaries
+                    ON tx_batch_summaries.batch_id = executed_transactions.batch_id
+                    WHERE tx_batch_summaries.batch_hash = $1
+                "#,
+                batch_hash.as_ref()
+            )
+            .fetch_one(transaction.conn())
+            .await?
+            .unwrap_or_default();
+            let transaction_hashes: Vec<TxHashSerializeWrapper> = tx_hashes
                 .iter()
-                .map(|tx| TxHashSerializeWrapper(TxHash::from_slice(&tx.tx_hash).unwrap()))
+                .map(|tx_hash| TxHashSerializeWrapper(TxHash::from_slice(tx_hash).unwrap()))
                 .collect();
-            let block_number = BlockNumber(batch_data[0].block_number as u32);
-            let batch_status = if batch_data[0].success {
-                if let Some(op) = transaction
-                    .chain()
-                    .operations_schema()
-                    .get_stored_aggregated_operation(
-                        block_number,
-                        AggregatedActionType::ExecuteBlocks,
-                    )
-                    .await
-                {
-                    BatchStatus {
-                        updated_at: op.created_at,
-                        last_state: TxInBlockStatus::Finalized,
-                    }
-                } else {
-                    BatchStatus {
-                        updated_at: created_at,
-                        last_state: TxInBlockStatus::Committed,
-                    }
-                }
-            } else {
-                BatchStatus {
-                    updated_at: created_at,
+            let batch_status = match (summary.success, summary.finalized_at) {
+                (true, Some(finalized_at)) => BatchStatus {
+                    updated_at: finalized_at,
+                    last_state: TxInBlockStatus::Finalized,
+                    fail_reason: None,
+                },
+                (true, None) => BatchStatus {
+                    updated_at: summary.created_at,
+                    last_state: TxInBlockStatus::Committed,
+                    fail_reason: None,
+                },
+                (false, _) => BatchStatus {
+                    updated_at: summary.created_at,
                     last_state: TxInBlockStatus::Rejected,
-                }
+                    fail_reason: None,
+                },
             };
             Some(ApiTxBatch {
                 batch_hash,
                 transaction_hashes,
-                created_at,
+                created_at: summary.created_at,
                 batch_status,
             })
         } else {
@@ -1692,11 +2262,26 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .await?
         {
             Some(batch_info)
+        } else if let Some(batch_info) = transaction
+            .chain()
+            .mempool_schema()
+            .get_queued_batch_info(batch_hash)
+            .await?
+        {
+            Some(batch_info)
+        } else if let Some(batch_info) = transaction
+            .chain()
+            .mempool_schema()
+            .get_evicted_batch_info(batch_hash)
+            .await?
+        {
+            Some(batch_info)
         } else {
+            // The batch may not be forwarded to the mempool yet or be rejected by it.
             transaction
                 .chain()
                 .mempool_schema()
-                .get_queued_batch_info(batch_hash)
+                .get_outbox_batch_info(batch_hash)
                 .await?
         };
         transaction.commit().await?;
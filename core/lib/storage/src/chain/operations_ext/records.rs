@@ -34,6 +34,19 @@ pub struct TransactionsHistoryItem {
     pub verified: bool,
     pub created_at: DateTime<Utc>,
     pub batch_id: Option<i64>,
+    /// Position of the operation in the block, absent for the failed transactions.
+    pub block_index: Option<i32>,
+}
+
+/// Position of the account transactions history page, used for the keyset pagination.
+///
+/// Transactions are ordered by the block number and the position in the block, which is
+/// the same order as the one of the sequence numbers. Sequence numbers are used instead
+/// of the block indices since the latter are not set for the failed transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxHistoryCursor {
+    /// Sequence number of the last entry of the page, the next page starts right after it.
+    pub sequence_number: i64,
 }
 
 /// Stored information resulted from executing the transaction.
@@ -144,11 +157,15 @@ pub struct AccountOpReceiptResponse {
 }
 
 #[derive(Debug, FromRow, PartialEq)]
-pub struct InBlockBatchTx {
-    pub tx_hash: Vec<u8>,
-    pub created_at: DateTime<Utc>,
-    pub success: bool,
+pub struct StoredTxBatchSummary {
+    pub batch_id: i64,
+    pub batch_hash: Vec<u8>,
+    pub member_count: i64,
     pub block_number: i64,
+    pub success: bool,
+    pub created_at: DateTime<Utc>,
+    pub committed_at: Option<DateTime<Utc>>,
+    pub finalized_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, FromRow, PartialEq)]
@@ -159,6 +176,7 @@ pub struct StorageTxReceipt {
     pub fail_reason: Option<String>,
     pub eth_block: Option<i64>,
     pub priority_op_serialid: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, FromRow, PartialEq)]
@@ -204,3 +222,30 @@ pub struct SequenceNumberRecord {
     pub sequence_number: i64,
     pub is_priority: bool,
 }
+
+/// A single entry from the raw response of the [`get_account_rejected_txs`] query.
+///
+/// [`get_account_rejected_txs`]: super::OperationsExtSchema::get_account_rejected_txs()
+#[derive(Debug, FromRow, Clone, PartialEq)]
+pub struct RejectedTxRecord {
+    pub tx_hash: Vec<u8>,
+    pub fail_reason: Option<String>,
+    pub rejected_at: DateTime<Utc>,
+    /// Whether the transaction was evicted from the mempool rather than executed.
+    pub evicted: bool,
+}
+
+/// A single entry from the raw response of the [`search_tx_hashes_by_prefix`] query.
+///
+/// [`search_tx_hashes_by_prefix`]: super::OperationsExtSchema::search_tx_hashes_by_prefix()
+#[derive(Debug, FromRow, Clone, PartialEq)]
+pub struct TxHashPrefixMatch {
+    pub tx_hash: Vec<u8>,
+    /// One of `executed`, `priority` or `mempool`.
+    pub source: String,
+    pub tx_type: Option<String>,
+    /// Not set for the mempool transactions.
+    pub block_number: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub account: Option<Vec<u8>>,
+}
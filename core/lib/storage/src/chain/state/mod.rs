@@ -1,5 +1,9 @@
 // Built-in deps
-use std::{cmp, collections::HashMap, time::Instant};
+use std::{
+    cmp,
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
 // External imports
 use num::BigInt;
 use sqlx::types::BigDecimal;
@@ -529,6 +533,356 @@ impl<'a, 'c> StateSchema<'a, 'c> {
         Ok((last_block, account_map))
     }
 
+    /// Loads the state of the given accounts at the given block.
+    ///
+    /// Unlike `load_committed_state`, only the given accounts are loaded and only their updates
+    /// are applied, so the result doesn't grow with the size of the state. The state at the blocks
+    /// after the last verified one is restored from the verified state, the state at the older
+    /// blocks is restored from the nearest account snapshots (see `take_account_snapshot`).
+    /// Accounts that don't exist at the given block are absent in the result.
+    pub async fn load_accounts_state(
+        &mut self,
+        block: BlockNumber,
+        account_ids: &[AccountId],
+    ) -> QueryResult<AccountMap> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let verified_block = BlockSchema(&mut transaction)
+            .get_last_verified_confirmed_block()
+            .await?;
+        if block < verified_block {
+            let account_map = StateSchema(&mut transaction)
+                .load_accounts_state_from_snapshots(block, account_ids)
+                .await?;
+            transaction.commit().await?;
+            metrics::histogram!("sql.chain.state.load_accounts_state", start.elapsed());
+            return Ok(account_map);
+        }
+
+        let stored_account_ids: Vec<_> = account_ids.iter().map(|id| **id as i64).collect();
+        let accounts = sqlx::query_as!(
+            StorageAccount,
+            "SELECT * FROM accounts WHERE id = ANY($1)",
+            &stored_account_ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let balances = sqlx::query_as!(
+            StorageBalance,
+            "SELECT * FROM balances WHERE account_id = ANY($1)",
+            &stored_account_ids
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut balances_for_id: HashMap<AccountId, Vec<StorageBalance>> = HashMap::new();
+        for balance in balances {
+            balances_for_id
+                .entry(AccountId(balance.account_id as u32))
+                .or_default()
+                .push(balance);
+        }
+        let mut account_map = AccountMap::default();
+        for stored_account in &accounts {
+            let id = AccountId(stored_account.id as u32);
+            let balances = balances_for_id.remove(&id).unwrap_or_default();
+            let (id, account) = restore_account(stored_account, balances);
+            account_map.insert(id, account);
+        }
+
+        let state_diff = StateSchema(&mut transaction)
+            .load_state_diff(verified_block, Some(block))
+            .await?;
+        if let Some((_, mut updates)) = state_diff {
+            let account_ids: HashSet<_> = account_ids.iter().collect();
+            updates.retain(|(id, _)| account_ids.contains(id));
+            apply_updates(&mut account_map, updates);
+        }
+
+        transaction.commit().await?;
+        metrics::histogram!("sql.chain.state.load_accounts_state", start.elapsed());
+        Ok(account_map)
+    }
+
+    /// Restores the state of the given accounts at the given block from their nearest snapshots
+    /// taken at or before the block, applying only the updates of every account made after its
+    /// snapshot. Accounts without such snapshots are restored from all of their updates.
+    /// Minted NFTs are not restored, same as in the verified state.
+    async fn load_accounts_state_from_snapshots(
+        &mut self,
+        block: BlockNumber,
+        account_ids: &[AccountId],
+    ) -> QueryResult<AccountMap> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let stored_account_ids: Vec<_> = account_ids.iter().map(|id| **id as i64).collect();
+        let snapshots = sqlx::query_as!(
+            StorageAccount,
+            r#"
+            SELECT DISTINCT ON (account_id)
+                account_id AS id, block_number AS last_block, nonce, address, pubkey_hash
+            FROM account_snapshots
+            WHERE account_id = ANY($1) AND block_number <= $2
+            ORDER BY account_id, block_number DESC
+            "#,
+            &stored_account_ids,
+            i64::from(*block)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let (snapshot_ids, snapshot_blocks): (Vec<_>, Vec<_>) = snapshots
+            .iter()
+            .map(|snapshot| (snapshot.id, snapshot.last_block))
+            .unzip();
+        let balances = sqlx::query_as!(
+            StorageBalance,
+            r#"
+            SELECT balances.account_id, balances.coin_id, balances.balance
+            FROM account_balance_snapshots balances
+            INNER JOIN UNNEST($1::bigint[], $2::bigint[]) AS snapshots (account_id, block_number)
+                ON balances.account_id = snapshots.account_id
+                AND balances.block_number = snapshots.block_number
+            "#,
+            &snapshot_ids,
+            &snapshot_blocks
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut balances_for_id: HashMap<AccountId, Vec<StorageBalance>> = HashMap::new();
+        for balance in balances {
+            balances_for_id
+                .entry(AccountId(balance.account_id as u32))
+                .or_default()
+                .push(balance);
+        }
+        let mut account_map = AccountMap::default();
+        // Updates of the account are replayed starting from the block after its snapshot.
+        let mut replay_from: HashMap<i64, i64> =
+            stored_account_ids.iter().map(|id| (*id, 0)).collect();
+        for snapshot in &snapshots {
+            let id = AccountId(snapshot.id as u32);
+            let balances = balances_for_id.remove(&id).unwrap_or_default();
+            let (id, account) = restore_account(snapshot, balances);
+            account_map.insert(id, account);
+            replay_from.insert(snapshot.id, snapshot.last_block);
+        }
+
+        let first_block = replay_from.values().copied().min().unwrap_or_default();
+        let is_replayed = |account_id: i64, block_number: i64| {
+            replay_from
+                .get(&account_id)
+                .map_or(false, |from| block_number > *from)
+        };
+        let account_balance_diff = sqlx::query_as!(
+            StorageAccountUpdate,
+            r#"
+            SELECT * FROM account_balance_updates
+            WHERE account_id = ANY($1) AND block_number > $2 AND block_number <= $3
+            "#,
+            &stored_account_ids,
+            first_block,
+            i64::from(*block)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let account_creation_diff = sqlx::query_as!(
+            StorageAccountCreation,
+            r#"
+            SELECT * FROM account_creates
+            WHERE account_id = ANY($1) AND block_number > $2 AND block_number <= $3
+            "#,
+            &stored_account_ids,
+            first_block,
+            i64::from(*block)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let account_pubkey_diff = sqlx::query_as!(
+            StorageAccountPubkeyUpdate,
+            r#"
+            SELECT * FROM account_pubkey_updates
+            WHERE account_id = ANY($1) AND block_number > $2 AND block_number <= $3
+            "#,
+            &stored_account_ids,
+            first_block,
+            i64::from(*block)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut account_diff = Vec::new();
+        account_diff.extend(
+            account_balance_diff
+                .into_iter()
+                .filter(|diff| is_replayed(diff.account_id, diff.block_number))
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_creation_diff
+                .into_iter()
+                .filter(|diff| is_replayed(diff.account_id, diff.block_number))
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.extend(
+            account_pubkey_diff
+                .into_iter()
+                .filter(|diff| is_replayed(diff.account_id, diff.block_number))
+                .map(StorageAccountDiff::from),
+        );
+        account_diff.sort_by(StorageAccountDiff::cmp_order);
+        apply_updates(
+            &mut account_map,
+            account_diff.into_iter().map(|diff| diff.into()).collect(),
+        );
+
+        transaction.commit().await?;
+        metrics::histogram!(
+            "sql.chain.state.load_accounts_state_from_snapshots",
+            start.elapsed()
+        );
+        Ok(account_map)
+    }
+
+    /// Returns the last block the account snapshot is taken at, `0` if there are none.
+    pub async fn last_account_snapshot_block(&mut self) -> QueryResult<BlockNumber> {
+        let start = Instant::now();
+        let last_block = sqlx::query!("SELECT MAX(block_number) FROM account_snapshot_blocks")
+            .fetch_one(self.0.conn())
+            .await?
+            .max
+            .unwrap_or(0);
+
+        metrics::histogram!(
+            "sql.chain.state.last_account_snapshot_block",
+            start.elapsed()
+        );
+        Ok(BlockNumber(last_block as u32))
+    }
+
+    /// Takes the snapshot of the accounts updated after the last snapshot at the given block.
+    /// Snapshots are expected to be taken in order and only at the finalized blocks.
+    pub async fn take_account_snapshot(&mut self, block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let last_snapshot_block = StateSchema(&mut transaction)
+            .last_account_snapshot_block()
+            .await?;
+        let updated_accounts = sqlx::query!(
+            r#"
+            SELECT account_id FROM account_balance_updates
+            WHERE block_number > $1 AND block_number <= $2
+            UNION
+            SELECT account_id FROM account_creates
+            WHERE block_number > $1 AND block_number <= $2
+            UNION
+            SELECT account_id FROM account_pubkey_updates
+            WHERE block_number > $1 AND block_number <= $2
+            "#,
+            i64::from(*last_snapshot_block),
+            i64::from(*block)
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .filter_map(|record| record.account_id)
+        .map(|account_id| AccountId(account_id as u32))
+        .collect::<Vec<_>>();
+        let accounts = StateSchema(&mut transaction)
+            .load_accounts_state(block, &updated_accounts)
+            .await?;
+
+        let mut account_ids = Vec::with_capacity(accounts.len());
+        let mut nonces = Vec::with_capacity(accounts.len());
+        let mut addresses = Vec::with_capacity(accounts.len());
+        let mut pubkey_hashes = Vec::with_capacity(accounts.len());
+        let mut balance_account_ids = Vec::new();
+        let mut coin_ids = Vec::new();
+        let mut balances = Vec::new();
+        for (account_id, account) in &accounts {
+            account_ids.push(i64::from(**account_id));
+            nonces.push(i64::from(*account.nonce));
+            addresses.push(account.address.as_bytes().to_vec());
+            pubkey_hashes.push(account.pub_key_hash.data.to_vec());
+            for (token_id, balance) in account.get_nonzero_balances() {
+                balance_account_ids.push(i64::from(**account_id));
+                coin_ids.push(*token_id as i32);
+                balances.push(BigDecimal::from(BigInt::from(balance.0)));
+            }
+        }
+        sqlx::query!(
+            r#"
+            INSERT INTO account_snapshots (account_id, block_number, nonce, address, pubkey_hash)
+            SELECT snapshots.account_id, $1, snapshots.nonce, snapshots.address, snapshots.pubkey_hash
+            FROM UNNEST ($2::bigint[], $3::bigint[], $4::bytea[], $5::bytea[])
+                AS snapshots (account_id, nonce, address, pubkey_hash)
+            "#,
+            i64::from(*block),
+            &account_ids,
+            &nonces,
+            &addresses,
+            &pubkey_hashes
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO account_balance_snapshots (account_id, block_number, coin_id, balance)
+            SELECT balances.account_id, $1, balances.coin_id, balances.balance
+            FROM UNNEST ($2::bigint[], $3::integer[], $4::numeric[])
+                AS balances (account_id, coin_id, balance)
+            "#,
+            i64::from(*block),
+            &balance_account_ids,
+            &coin_ids,
+            &balances
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "INSERT INTO account_snapshot_blocks (block_number) VALUES ($1)",
+            i64::from(*block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.state.take_account_snapshot", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the account snapshots taken at the blocks with number greater than `last_block`.
+    pub async fn remove_account_snapshots(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!(
+            "DELETE FROM account_balance_snapshots WHERE block_number > $1",
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM account_snapshots WHERE block_number > $1",
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM account_snapshot_blocks WHERE block_number > $1",
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.state.remove_account_snapshots", start.elapsed());
+        Ok(())
+    }
+
     /// Returns the list of updates, and the block number such that if we apply
     /// these updates to the state of the block #(from_block), we will obtain state of the block
     /// #(returned block number).
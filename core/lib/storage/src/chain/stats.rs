@@ -2,12 +2,49 @@ use std::cmp::max;
 // Built-in deps
 use std::time::Instant;
 // External imports
-
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::types::BigDecimal;
 // Workspace imports
-use zksync_types::{BlockNumber, SequentialTxId};
+use zksync_types::{AccountId, BlockNumber, SequentialTxId, TokenId};
 // Local imports
 use crate::{QueryResult, StorageProcessor};
 
+/// Fees paid by the executed transactions during a single time bucket.
+/// Statistics are `None` if there were no transactions in the bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeStatisticsBucket {
+    pub bucket: DateTime<Utc>,
+    pub count: i64,
+    pub average: Option<BigDecimal>,
+    pub median: Option<BigDecimal>,
+    pub percentile_90: Option<BigDecimal>,
+}
+
+/// Activity of a single token in a single block, added to the daily activity of the token.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenActivityDelta {
+    pub token_id: TokenId,
+    pub transfers: i64,
+    pub transfer_volume: BigDecimal,
+    /// Senders of the transfers, duplicates are allowed.
+    pub senders: Vec<AccountId>,
+    pub withdrawals: i64,
+    pub withdrawal_volume: BigDecimal,
+}
+
+/// Activity of the token during a single day. The counters are zero if there was no activity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenActivityBucket {
+    pub day: NaiveDate,
+    pub transfers: i64,
+    pub transfer_volume: BigDecimal,
+    pub unique_senders: i64,
+    pub withdrawals: i64,
+    pub withdrawal_volume: BigDecimal,
+    /// Ticker price of the token in USD stored during the day, if any.
+    pub usd_price: Option<BigDecimal>,
+}
+
 /// Auxiliary schema encapsulating the stats counting logic for the storage tables.
 #[derive(Debug)]
 pub struct StatsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
@@ -61,4 +98,249 @@ impl<'a, 'c> StatsSchema<'a, 'c> {
             ) as u64),
         ))
     }
+
+    /// Returns the statistics of the fees paid by the successful transactions of the given type
+    /// in the given token, grouped by `resolution` (any precision accepted by the Postgres `date_trunc`).
+    ///
+    /// Only the transactions created in the `[from, to)` range are taken into account,
+    /// the first bucket starts at `from` truncated to the resolution in UTC.
+    pub async fn fee_statistics(
+        &mut self,
+        tx_type: &str,
+        fee_token: TokenId,
+        resolution: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> QueryResult<Vec<FeeStatisticsBucket>> {
+        let start = Instant::now();
+        let buckets = sqlx::query_as!(
+            FeeStatisticsBucket,
+            r#"
+                SELECT
+                    buckets.bucket AS "bucket!",
+                    COUNT(fees.fee) AS "count!",
+                    ROUND(AVG(fees.fee)) AS average,
+                    PERCENTILE_DISC(0.5) WITHIN GROUP (ORDER BY fees.fee) AS median,
+                    PERCENTILE_DISC(0.9) WITHIN GROUP (ORDER BY fees.fee) AS percentile_90
+                FROM generate_series(
+                    date_trunc($1, $4::timestamptz AT TIME ZONE 'UTC') AT TIME ZONE 'UTC',
+                    $5::timestamptz - interval '1 microsecond',
+                    ('1 ' || $1)::interval
+                ) AS buckets (bucket)
+                LEFT JOIN executed_transactions_fees fees
+                    ON fees.tx_type = $2 AND fees.fee_token = $3
+                    AND fees.created_at >= GREATEST(buckets.bucket, $4)
+                    AND fees.created_at < LEAST(buckets.bucket + ('1 ' || $1)::interval, $5)
+                GROUP BY buckets.bucket
+                ORDER BY buckets.bucket
+            "#,
+            resolution,
+            tx_type,
+            *fee_token as i32,
+            from,
+            to
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.stats.fee_statistics", start.elapsed());
+        Ok(buckets)
+    }
+
+    /// Returns the last block rolled up into the daily activity of the tokens,
+    /// `0` if there are none.
+    pub async fn last_token_activity_block(&mut self) -> QueryResult<BlockNumber> {
+        let start = Instant::now();
+        let last_block = sqlx::query!("SELECT MAX(block_number) FROM token_activity_blocks")
+            .fetch_one(self.0.conn())
+            .await?
+            .max
+            .unwrap_or(0);
+
+        metrics::histogram!("sql.chain.stats.last_token_activity_block", start.elapsed());
+        Ok(BlockNumber(last_block as u32))
+    }
+
+    /// Adds the activity of the block to the daily activity of the tokens. The block is marked
+    /// as rolled up even if there was no activity in it, blocks are expected to be added in order.
+    pub async fn store_token_activity(
+        &mut self,
+        block_number: BlockNumber,
+        day: NaiveDate,
+        deltas: &[TokenActivityDelta],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        if !deltas.is_empty() {
+            let token_ids: Vec<_> = deltas.iter().map(|delta| *delta.token_id as i32).collect();
+            let transfers: Vec<_> = deltas.iter().map(|delta| delta.transfers).collect();
+            let transfer_volumes: Vec<_> = deltas
+                .iter()
+                .map(|delta| delta.transfer_volume.clone())
+                .collect();
+            let withdrawals: Vec<_> = deltas.iter().map(|delta| delta.withdrawals).collect();
+            let withdrawal_volumes: Vec<_> = deltas
+                .iter()
+                .map(|delta| delta.withdrawal_volume.clone())
+                .collect();
+            sqlx::query!(
+                r#"
+                INSERT INTO token_activity_daily (
+                    token_id, day, transfers, transfer_volume, withdrawals, withdrawal_volume, usd_price
+                )
+                SELECT
+                    deltas.token_id, $1::date, deltas.transfers, deltas.transfer_volume,
+                    deltas.withdrawals, deltas.withdrawal_volume, ticker_price.usd_price
+                FROM UNNEST ($2::integer[], $3::bigint[], $4::numeric[], $5::bigint[], $6::numeric[])
+                    AS deltas (token_id, transfers, transfer_volume, withdrawals, withdrawal_volume)
+                LEFT JOIN ticker_price ON ticker_price.token_id = deltas.token_id
+                    AND (ticker_price.last_updated AT TIME ZONE 'UTC')::date = $1::date
+                ON CONFLICT (token_id, day)
+                DO UPDATE SET
+                    transfers = token_activity_daily.transfers + EXCLUDED.transfers,
+                    transfer_volume = token_activity_daily.transfer_volume + EXCLUDED.transfer_volume,
+                    withdrawals = token_activity_daily.withdrawals + EXCLUDED.withdrawals,
+                    withdrawal_volume = token_activity_daily.withdrawal_volume + EXCLUDED.withdrawal_volume,
+                    usd_price = COALESCE(EXCLUDED.usd_price, token_activity_daily.usd_price)
+                "#,
+                day,
+                &token_ids,
+                &transfers,
+                &transfer_volumes,
+                &withdrawals,
+                &withdrawal_volumes
+            )
+            .execute(transaction.conn())
+            .await?;
+
+            let (sender_tokens, senders): (Vec<_>, Vec<_>) = deltas
+                .iter()
+                .flat_map(|delta| {
+                    delta
+                        .senders
+                        .iter()
+                        .map(move |sender| (*delta.token_id as i32, i64::from(**sender)))
+                })
+                .unzip();
+            sqlx::query!(
+                r#"
+                INSERT INTO token_activity_senders (token_id, day, account_id)
+                SELECT senders.token_id, $1::date, senders.account_id
+                FROM UNNEST ($2::integer[], $3::bigint[]) AS senders (token_id, account_id)
+                ON CONFLICT DO NOTHING
+                "#,
+                day,
+                &sender_tokens,
+                &senders
+            )
+            .execute(transaction.conn())
+            .await?;
+            sqlx::query!(
+                r#"
+                UPDATE token_activity_daily
+                SET unique_senders = (
+                    SELECT COUNT(*) FROM token_activity_senders
+                    WHERE token_activity_senders.token_id = token_activity_daily.token_id
+                        AND token_activity_senders.day = token_activity_daily.day
+                )
+                WHERE day = $1 AND token_id = ANY($2)
+                "#,
+                day,
+                &token_ids
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+
+        sqlx::query!(
+            "INSERT INTO token_activity_blocks (block_number, day) VALUES ($1, $2)",
+            i64::from(*block_number),
+            day
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.stats.store_token_activity", start.elapsed());
+        Ok(())
+    }
+
+    /// Removes the daily activity of the days affected by the blocks with number greater than
+    /// `last_block`. All the blocks of these days are removed from the rolled up ones,
+    /// so the days are recalculated from the remaining blocks once they are rolled up again.
+    pub async fn revert_token_activity(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM token_activity_daily WHERE day IN (
+                SELECT day FROM token_activity_blocks WHERE block_number > $1
+            )
+            "#,
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM token_activity_senders WHERE day IN (
+                SELECT day FROM token_activity_blocks WHERE block_number > $1
+            )
+            "#,
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM token_activity_blocks WHERE day IN (
+                SELECT day FROM token_activity_blocks WHERE block_number > $1
+            )
+            "#,
+            i64::from(*last_block)
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.stats.revert_token_activity", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the daily activity of the token for every day in the `[from, to]` range.
+    pub async fn token_activity(
+        &mut self,
+        token_id: TokenId,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> QueryResult<Vec<TokenActivityBucket>> {
+        let start = Instant::now();
+        let buckets = sqlx::query_as!(
+            TokenActivityBucket,
+            r#"
+            SELECT
+                series.day::date AS "day!",
+                COALESCE(activity.transfers, 0) AS "transfers!",
+                COALESCE(activity.transfer_volume, 0) AS "transfer_volume!",
+                COALESCE(activity.unique_senders, 0) AS "unique_senders!",
+                COALESCE(activity.withdrawals, 0) AS "withdrawals!",
+                COALESCE(activity.withdrawal_volume, 0) AS "withdrawal_volume!",
+                activity.usd_price AS "usd_price?"
+            FROM generate_series($2::date, $3::date, interval '1 day') AS series (day)
+            LEFT JOIN token_activity_daily activity
+                ON activity.token_id = $1 AND activity.day = series.day::date
+            ORDER BY series.day
+            "#,
+            *token_id as i32,
+            from,
+            to
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.stats.token_activity", start.elapsed());
+        Ok(buckets)
+    }
 }
@@ -143,6 +143,45 @@ impl<'a, 'c> TreeCacheSchemaBincode<'a, 'c> {
         }))
     }
 
+    /// Gets the latest stored account tree cache for a block not greater than `block`.
+    /// Returns `None` if there are no such caches or they are encoded in JSON.
+    /// Returns the block number and associated cache otherwise.
+    pub async fn get_account_tree_cache_before(
+        &mut self,
+        block: BlockNumber,
+    ) -> QueryResult<Option<(BlockNumber, Vec<u8>)>> {
+        let start = Instant::now();
+
+        let cache_block = sqlx::query!(
+            r#"
+                SELECT MAX(block) FROM (
+                    SELECT MAX(block) as block FROM account_tree_cache
+                    WHERE block <= $1 AND tree_cache_binary IS NOT NULL
+                    UNION ALL
+                    SELECT MAX(block) as block FROM account_tree_cache_new WHERE block <= $1
+                ) AS max_block
+            "#,
+            *block as i64
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .max;
+
+        let account_tree_cache = if let Some(cache_block) = cache_block {
+            let cache_block = BlockNumber(cache_block as u32);
+            self.get_account_tree_cache_block(cache_block)
+                .await?
+                .map(|cache| (cache_block, cache))
+        } else {
+            None
+        };
+        metrics::histogram!(
+            "sql.chain.tree_cache.bincode.get_account_tree_cache_before",
+            start.elapsed()
+        );
+        Ok(account_tree_cache)
+    }
+
     // Removes account tree cache for blocks with number greater than `last_block`
     pub async fn remove_new_account_tree_cache(
         &mut self,
@@ -1,8 +1,14 @@
 // Built-in deps
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock,
+};
 use std::{fmt, time::Duration, time::Instant};
 // External imports
 use async_trait::async_trait;
-use deadpool::managed::{Manager, PoolConfig, RecycleResult, Timeouts};
+use deadpool::managed::{
+    Manager, Object, PoolConfig, PoolError, RecycleError, RecycleResult, TimeoutType, Timeouts,
+};
 use deadpool::Runtime;
 use sqlx::{Connection, Error as SqlxError, PgConnection};
 use tokio::time;
@@ -12,26 +18,44 @@ use crate::{get_database_replica_url, get_database_url, StorageProcessor};
 use zksync_utils::parse_env;
 
 pub mod holder;
+pub mod replicas;
 
 type Pool = deadpool::managed::Pool<DbPool>;
 
 pub type PooledConnection = deadpool::managed::Object<DbPool>;
 
 pub const DB_CONNECTION_RETRIES: u32 = 3;
+/// Maximum time the check of the idle connection before handing it out may take. Connections
+/// to the failed database may hang instead of failing, such connections are treated as broken.
+const RECYCLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Default)]
+struct PoolCounters {
+    /// Failed connection attempts and broken connections.
+    errors: AtomicU64,
+    /// Idle connections found broken before being handed out.
+    recycle_failures: AtomicU64,
+    recreations: AtomicU64,
+}
 
 #[derive(Clone)]
 pub struct DbPool {
     url: String,
+    counters: Arc<PoolCounters>,
 }
 
 impl DbPool {
-    fn create(url: impl Into<String>, max_size: usize) -> Pool {
+    fn create(url: impl Into<String>, max_size: usize, counters: Arc<PoolCounters>) -> Pool {
         let pool_config = PoolConfig {
             max_size,
             timeouts: Timeouts::wait_millis(20_000), // wait 20 seconds before returning error
             runtime: Runtime::Tokio1,
         };
-        Pool::from_config(DbPool { url: url.into() }, pool_config)
+        let manager = DbPool {
+            url: url.into(),
+            counters,
+        };
+        Pool::from_config(manager, pool_config)
     }
 }
 
@@ -40,21 +64,90 @@ impl Manager for DbPool {
     type Type = PgConnection;
     type Error = SqlxError;
     async fn create(&self) -> Result<PgConnection, SqlxError> {
-        PgConnection::connect(&self.url).await
+        let connection = PgConnection::connect(&self.url).await;
+        if connection.is_err() {
+            self.counters.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        connection
     }
     async fn recycle(&self, obj: &mut PgConnection) -> RecycleResult<SqlxError> {
-        Ok(obj.ping().await?)
+        let result = match time::timeout(RECYCLE_TIMEOUT, obj.ping()).await {
+            Ok(result) => result.map_err(RecycleError::Backend),
+            Err(_) => Err(RecycleError::Message("ping has timed out".to_string())),
+        };
+        if result.is_err() {
+            self.counters.errors.fetch_add(1, Ordering::Relaxed);
+            self.counters
+                .recycle_failures
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+/// Snapshot of the connection pool state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStats {
+    pub max_size: usize,
+    /// Number of the connections currently open, both idle and in use.
+    pub size: usize,
+    pub idle: usize,
+    /// Number of the failed connection attempts and broken connections since the start.
+    pub errors: u64,
+    /// Number of times the pool was recreated.
+    pub recreations: u64,
+}
+
+/// Outcome of the idle connections check, see `ConnectionPool::check_idle_connections`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IdleConnectionsCheck {
+    pub healthy: usize,
+    pub broken: usize,
+}
+
+impl IdleConnectionsCheck {
+    /// Returns the share of the broken connections, `0.0` if no connections were checked.
+    pub fn failure_ratio(&self) -> f64 {
+        match self.healthy + self.broken {
+            0 => 0.0,
+            checked => self.broken as f64 / checked as f64,
+        }
     }
 }
 
+/// Checks whether the query failed because of a transient database error, so the same
+/// read-only query may succeed once retried: the connection is dropped or can't be acquired
+/// in time, or the transaction is aborted because of a serialization failure or a deadlock.
+pub fn is_transient_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if cause.is::<std::io::Error>() {
+            return true;
+        }
+        match cause.downcast_ref::<SqlxError>() {
+            Some(SqlxError::Io(_)) | Some(SqlxError::PoolTimedOut) => true,
+            Some(SqlxError::Database(err)) => err.code().map_or(false, |code| {
+                // `serialization_failure`, `deadlock_detected` and the connection exceptions.
+                code == "40001" || code == "40P01" || code.starts_with("08")
+            }),
+            _ => false,
+        }
+    })
+}
+
 /// `ConnectionPool` is a wrapper over a `diesel`s `Pool`, encapsulating
 /// the fixed size pool of connection to the database.
 ///
 /// The size of the pool and the database URL are configured via environment
 /// variables `DATABASE_POOL_SIZE` and `DATABASE_URL` respectively.
+///
+/// The underlying pool is shared by all the clones, so once it's recreated (e.g. after
+/// the database failover), every clone acquires the connections from the new pool.
 #[derive(Clone)]
 pub struct ConnectionPool {
-    pool: Pool,
+    pool: Arc<RwLock<Pool>>,
+    url: String,
+    max_size: usize,
+    counters: Arc<PoolCounters>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -71,9 +164,7 @@ impl ConnectionPool {
         let database_url = get_database_url();
         let max_size = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
 
-        let pool = DbPool::create(database_url, max_size as usize);
-
-        Self { pool }
+        Self::from_parts(database_url, max_size as usize)
     }
 
     /// Establishes a pool of the connections to the replica of database and
@@ -84,10 +175,105 @@ impl ConnectionPool {
         let database_url = get_database_replica_url();
         let max_size = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
 
-        let pool = DbPool::create(database_url, max_size as usize);
+        Self::from_parts(database_url, max_size as usize)
+    }
+
+    /// Establishes a pool of the connections to the database with the given URL and
+    /// creates a new `ConnectionPool` object.
+    /// pool_max_size - number of connections in pool,
+    /// if not set env variable "DATABASE_POOL_SIZE" is going to be used.
+    pub fn new_with_url(database_url: impl Into<String>, pool_max_size: Option<u32>) -> Self {
+        let max_size = pool_max_size.unwrap_or_else(|| parse_env("DATABASE_POOL_SIZE"));
+
+        Self::from_parts(database_url.into(), max_size as usize)
+    }
+
+    fn from_parts(url: String, max_size: usize) -> Self {
+        let counters = Arc::<PoolCounters>::default();
+        let pool = DbPool::create(url.clone(), max_size, counters.clone());
+        Self {
+            pool: Arc::new(RwLock::new(pool)),
+            url,
+            max_size,
+            counters,
+        }
+    }
+
+    /// Returns the current pool. Connections acquired from it stay valid even if the pool
+    /// is recreated in the meantime.
+    fn pool(&self) -> Pool {
+        self.pool
+            .read()
+            .expect("connection pool lock is poisoned")
+            .clone()
+    }
 
-        Self { pool }
+    /// Returns the current state of the pool.
+    pub fn stats(&self) -> PoolStats {
+        let status = self.pool().status();
+        PoolStats {
+            max_size: status.max_size,
+            size: status.size,
+            idle: status.available.max(0) as usize,
+            errors: self.counters.errors.load(Ordering::Relaxed),
+            recreations: self.counters.recreations.load(Ordering::Relaxed),
+        }
     }
+
+    /// Runs a trivial query on every idle connection, the broken connections are removed from the pool.
+    ///
+    /// The pool pings the idle connections before handing them out and replaces the broken ones
+    /// with the new connections, such replacements are reported as broken connections as well.
+    pub async fn check_idle_connections(&self, timeout: Duration) -> IdleConnectionsCheck {
+        let pool = self.pool();
+        let idle = pool.status().available.max(0) as usize;
+        let recycle_failures = self.counters.recycle_failures.load(Ordering::Relaxed);
+        let timeouts = Timeouts {
+            wait: Some(Duration::from_secs(0)),
+            create: Some(timeout),
+            recycle: None,
+        };
+
+        let mut check = IdleConnectionsCheck::default();
+        // Healthy connections are held until the check is completed, so each of them is checked once.
+        let mut healthy = Vec::with_capacity(idle);
+        for _ in 0..idle {
+            let mut connection = match pool.timeout_get(&timeouts).await {
+                Ok(connection) => connection,
+                // The rest of the connections are taken by the requests.
+                Err(PoolError::Timeout(TimeoutType::Wait)) => break,
+                // Broken connections and failed connection attempts are counted by the manager.
+                Err(_) => continue,
+            };
+            let query = sqlx::query("SELECT 1").execute(&mut *connection);
+            match time::timeout(timeout, query).await {
+                Ok(Ok(_)) => {
+                    check.healthy += 1;
+                    healthy.push(connection);
+                }
+                _ => {
+                    check.broken += 1;
+                    self.counters.errors.fetch_add(1, Ordering::Relaxed);
+                    // The connection is closed instead of being returned to the pool.
+                    drop(Object::take(connection));
+                }
+            }
+        }
+
+        let recycle_failures =
+            self.counters.recycle_failures.load(Ordering::Relaxed) - recycle_failures;
+        check.broken += recycle_failures as usize;
+        check
+    }
+
+    /// Replaces the pool with a new one. Connections acquired from the old pool are closed
+    /// once released, while the new ones are acquired from the new pool.
+    pub fn recreate(&self) {
+        let pool = DbPool::create(self.url.clone(), self.max_size, self.counters.clone());
+        *self.pool.write().expect("connection pool lock is poisoned") = pool;
+        self.counters.recreations.fetch_add(1, Ordering::Relaxed);
+    }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -104,13 +290,28 @@ impl ConnectionPool {
         Ok(StorageProcessor::from_pool(connection))
     }
 
+    /// Creates a `StorageProcessor` without retrying if the connection cannot be obtained.
+    ///
+    /// Unlike `access_storage`, this method never panics, so it should be used when
+    /// the database is allowed to be unavailable (e.g. for replicas that have a fallback).
+    pub async fn try_access_storage(&self) -> Result<StorageProcessor<'_>, SqlxError> {
+        let start = Instant::now();
+        let connection = self.pool().get().await.map_err(|err| match err {
+            PoolError::Backend(err) => err,
+            _ => SqlxError::PoolTimedOut,
+        })?;
+        metrics::histogram!("sql.connection_acquire", start.elapsed());
+
+        Ok(StorageProcessor::from_pool(connection))
+    }
+
     async fn get_pooled_connection(&self) -> PooledConnection {
         let mut retry_count = 0;
 
         let mut one_second = time::interval(Duration::from_secs(1));
 
         while retry_count < DB_CONNECTION_RETRIES {
-            let connection = self.pool.get().await;
+            let connection = self.pool().get().await;
 
             match connection {
                 Ok(connection) => return connection,
@@ -123,6 +324,6 @@ impl ConnectionPool {
         }
 
         // Attempting to get the pooled connection for the last time
-        self.pool.get().await.unwrap()
+        self.pool().get().await.unwrap()
     }
 }
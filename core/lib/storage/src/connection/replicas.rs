@@ -0,0 +1,184 @@
+//! Routing of the storage queries between the primary database and its read replicas.
+//!
+//! Read-only queries are served by the healthy replica with the lowest latency, while
+//! writes (and reads that must observe the latest writes) are always served by the primary.
+//! If there are no healthy replicas, all the queries fall back to the primary database.
+
+// Built-in deps
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
+use std::time::{Duration, Instant};
+// External imports
+use sqlx::Error as SqlxError;
+use tokio::time;
+// Local imports
+use super::ConnectionPool;
+use crate::StorageProcessor;
+
+/// Maximum time the health check of a single replica may take.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Intent of the storage access, determines the database used to serve the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessIntent {
+    /// Read-only queries that tolerate a slightly outdated state.
+    Read,
+    /// Writes and consistency-critical reads (e.g. mempool checks right after the submission).
+    Write,
+}
+
+#[derive(Debug)]
+struct Replica {
+    pool: ConnectionPool,
+    healthy: AtomicBool,
+    /// Duration of the last successful health check in microseconds.
+    latency_us: AtomicU64,
+}
+
+/// Pool of the connections to the primary database and its read replicas.
+///
+/// Replicas are considered unhealthy until the first health check is performed,
+/// see `check_replicas` and `run_health_checks`.
+#[derive(Debug, Clone)]
+pub struct ReplicatedPool {
+    primary: ConnectionPool,
+    replicas: Arc<Vec<Replica>>,
+    max_lag: Duration,
+}
+
+impl ReplicatedPool {
+    pub fn new(primary: ConnectionPool, replicas: Vec<ConnectionPool>, max_lag: Duration) -> Self {
+        let replicas = replicas
+            .into_iter()
+            .map(|pool| Replica {
+                pool,
+                healthy: AtomicBool::new(false),
+                latency_us: AtomicU64::new(u64::MAX),
+            })
+            .collect();
+
+        Self {
+            primary,
+            replicas: Arc::new(replicas),
+            max_lag,
+        }
+    }
+
+    /// Creates a pool without replicas, all the queries are served by the primary database.
+    pub fn primary_only(primary: ConnectionPool) -> Self {
+        Self::new(primary, Vec::new(), Duration::default())
+    }
+
+    pub fn primary(&self) -> &ConnectionPool {
+        &self.primary
+    }
+
+    pub fn replicas(&self) -> impl Iterator<Item = &ConnectionPool> {
+        self.replicas.iter().map(|replica| &replica.pool)
+    }
+
+    /// Returns the amount of replicas that can currently serve the read-only queries.
+    pub fn healthy_replicas(&self) -> usize {
+        self.replicas
+            .iter()
+            .filter(|replica| replica.healthy.load(Ordering::SeqCst))
+            .count()
+    }
+
+    /// Returns the pool chosen according to the intent, for the cases when the pool itself
+    /// is required. Unlike `access_storage`, it doesn't fall back to the primary database
+    /// if the replica becomes unavailable before the next health check.
+    pub fn pool_for(&self, intent: AccessIntent) -> &ConnectionPool {
+        match intent {
+            AccessIntent::Read => self
+                .fastest_replica()
+                .map(|replica| &replica.pool)
+                .unwrap_or(&self.primary),
+            AccessIntent::Write => &self.primary,
+        }
+    }
+
+    /// Creates a `StorageProcessor` for the database chosen according to the intent.
+    ///
+    /// Read-only queries fall back to the primary database if there are no healthy replicas
+    /// or the connection to the chosen replica cannot be established.
+    pub async fn access_storage(
+        &self,
+        intent: AccessIntent,
+    ) -> Result<StorageProcessor<'_>, SqlxError> {
+        if intent == AccessIntent::Read {
+            if let Some(replica) = self.fastest_replica() {
+                match replica.pool.try_access_storage().await {
+                    Ok(storage) => {
+                        metrics::increment_counter!("sql.replicas.access", "database" => "replica");
+                        return Ok(storage);
+                    }
+                    Err(err) => {
+                        vlog::warn!("Failed to access the replica, using the primary: {}", err);
+                        replica.healthy.store(false, Ordering::SeqCst);
+                    }
+                }
+            }
+        }
+
+        metrics::increment_counter!("sql.replicas.access", "database" => "primary");
+        self.primary.access_storage().await
+    }
+
+    /// Checks the availability and the replication lag of every replica.
+    pub async fn check_replicas(&self) {
+        for (idx, replica) in self.replicas.iter().enumerate() {
+            let start = Instant::now();
+            let check = time::timeout(HEALTH_CHECK_TIMEOUT, async {
+                let mut storage = replica.pool.try_access_storage().await?;
+                storage.misc_schema().replication_lag().await
+            })
+            .await;
+            let latency = start.elapsed();
+
+            let healthy = match check {
+                Ok(Ok(lag)) if lag <= self.max_lag => true,
+                Ok(Ok(lag)) => {
+                    vlog::warn!("Replica #{} lags behind the primary for {:?}", idx, lag);
+                    false
+                }
+                Ok(Err(err)) => {
+                    vlog::warn!("Health check of the replica #{} failed: {}", idx, err);
+                    false
+                }
+                Err(_) => {
+                    vlog::warn!("Health check of the replica #{} has timed out", idx);
+                    false
+                }
+            };
+            replica.healthy.store(healthy, Ordering::SeqCst);
+            replica
+                .latency_us
+                .store(latency.as_micros() as u64, Ordering::SeqCst);
+        }
+
+        metrics::gauge!("sql.replicas.healthy", self.healthy_replicas() as f64);
+    }
+
+    /// Periodically checks the replicas, should be spawned as a separate task.
+    pub async fn run_health_checks(self, interval: Duration) {
+        if self.replicas.is_empty() {
+            return;
+        }
+
+        let mut timer = time::interval(interval);
+        loop {
+            timer.tick().await;
+            self.check_replicas().await;
+        }
+    }
+
+    fn fastest_replica(&self) -> Option<&Replica> {
+        self.replicas
+            .iter()
+            .filter(|replica| replica.healthy.load(Ordering::SeqCst))
+            .min_by_key(|replica| replica.latency_us.load(Ordering::SeqCst))
+    }
+}
@@ -2,7 +2,7 @@
 use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
 // External imports
 use anyhow::format_err;
-use num::{BigInt, BigUint};
+use num::{bigint::ToBigInt, BigInt, BigUint};
 use sqlx::types::BigDecimal;
 // Workspace imports
 use zksync_types::{
@@ -14,7 +14,10 @@ use zksync_types::{
     BlockNumber, H256, U256,
 };
 // Local imports
-use self::records::{ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageETHOperation};
+use self::records::{
+    ETHOperationData, ETHParams, ETHStats, ETHTxHash, StorageBlockL1Operation, StorageETHOperation,
+    StorageEthOperationGas, StorageL1Operation, StorageUnlinkedExecuteOperation,
+};
 use crate::{chain::operations::records::StoredAggregatedOperation, QueryResult, StorageProcessor};
 use chrono::{DateTime, Utc};
 
@@ -477,6 +480,26 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(params.into())
     }
 
+    /// Overwrites the stored Ethereum operations stats.
+    /// Stats are normally updated along with the saved operations, so this method is only
+    /// meant for restoring them, e.g. after the operations were stored out of order in tests.
+    pub async fn store_stats(&mut self, stats: &ETHStats) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "UPDATE eth_parameters
+            SET last_committed_block = $1, last_verified_block = $2, last_executed_block = $3
+            WHERE id = true",
+            stats.last_committed_block,
+            stats.last_verified_block,
+            stats.last_executed_block
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.store_stats", start.elapsed());
+        Ok(())
+    }
+
     async fn load_eth_params(&mut self) -> QueryResult<ETHParams> {
         let start = Instant::now();
         let params = sqlx::query_as!(ETHParams, "SELECT * FROM eth_parameters WHERE id = true",)
@@ -580,6 +603,302 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Stores the gas used by the Ethereum transaction. The transaction should be confirmed
+    /// via `confirm_eth_tx` within the same database transaction.
+    pub async fn save_gas_used(&mut self, hash: &H256, gas_used: U256) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_operations_gas_used (eth_op_id, gas_used)
+                SELECT eth_op_id, $2 FROM eth_tx_hashes WHERE tx_hash = $1
+            ON CONFLICT (eth_op_id) DO UPDATE SET gas_used = $2",
+            hash.as_bytes(),
+            gas_used.as_u64() as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.save_gas_used", start.elapsed());
+        Ok(())
+    }
+
+    /// Stores the number of the Ethereum block the transaction was included in. The transaction
+    /// should be confirmed via `confirm_eth_tx` within the same database transaction.
+    pub async fn save_l1_block(&mut self, hash: &H256, block_number: u64) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "INSERT INTO eth_operations_l1_blocks (eth_op_id, block_number)
+                SELECT eth_op_id, $2 FROM eth_tx_hashes WHERE tx_hash = $1
+            ON CONFLICT (eth_op_id) DO UPDATE SET block_number = $2",
+            hash.as_bytes(),
+            block_number as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.save_l1_block", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the number of the Ethereum block the confirmed commit transaction of the block
+    /// was included in, `None` if the commitment is not confirmed or the block was not recorded.
+    pub async fn load_commit_l1_block(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<u64>> {
+        let start = Instant::now();
+        let l1_block = sqlx::query!(
+            "SELECT eth_operations_l1_blocks.block_number
+            FROM aggregate_operations
+            INNER JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.op_id = aggregate_operations.id
+            INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            INNER JOIN eth_operations_l1_blocks ON eth_operations_l1_blocks.eth_op_id = eth_operations.id
+            WHERE aggregate_operations.action_type = 'CommitBlocks'
+                AND aggregate_operations.from_block <= $1 AND aggregate_operations.to_block >= $1
+                AND eth_operations.confirmed = true
+            LIMIT 1",
+            i64::from(*block_number)
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.block_number as u64);
+
+        metrics::histogram!("sql.ethereum.load_commit_l1_block", start.elapsed());
+        Ok(l1_block)
+    }
+
+    /// Loads the aggregated operation the Ethereum transaction with the given hash was sent for.
+    /// The hash may belong to any of the transactions sent for the operation, not only the final one.
+    pub async fn load_l1_operation(
+        &mut self,
+        hash: &H256,
+    ) -> QueryResult<Option<StorageL1Operation>> {
+        let start = Instant::now();
+        let operation = sqlx::query_as!(
+            StorageL1Operation,
+            r#"SELECT
+                eth_operations.id AS eth_op_id,
+                eth_operations.confirmed,
+                eth_operations.final_hash,
+                eth_operations_gas_used.gas_used AS "gas_used?",
+                aggregate_operations.id AS aggregated_op_id,
+                aggregate_operations.action_type,
+                aggregate_operations.arguments,
+                aggregate_operations.from_block,
+                aggregate_operations.to_block,
+                aggregate_operations.created_at
+            FROM eth_tx_hashes
+            INNER JOIN eth_operations ON eth_operations.id = eth_tx_hashes.eth_op_id
+            INNER JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.eth_op_id = eth_operations.id
+            INNER JOIN aggregate_operations ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+            LEFT JOIN eth_operations_gas_used ON eth_operations_gas_used.eth_op_id = eth_operations.id
+            WHERE eth_tx_hashes.tx_hash = $1
+            LIMIT 1"#,
+            hash.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_l1_operation", start.elapsed());
+        Ok(operation)
+    }
+
+    /// Loads the confirmed Ethereum operations that processed the given block,
+    /// including the ones sent for the ranges of blocks.
+    pub async fn load_block_l1_operations(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Vec<StorageBlockL1Operation>> {
+        let start = Instant::now();
+        let operations = sqlx::query_as!(
+            StorageBlockL1Operation,
+            r#"SELECT
+                aggregate_operations.action_type,
+                aggregate_operations.from_block,
+                aggregate_operations.to_block,
+                eth_operations_gas_used.gas_used AS "gas_used?",
+                eth_operations.last_used_gas_price
+            FROM aggregate_operations
+            INNER JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.op_id = aggregate_operations.id
+            INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            LEFT JOIN eth_operations_gas_used ON eth_operations_gas_used.eth_op_id = eth_operations.id
+            WHERE aggregate_operations.from_block <= $1 AND aggregate_operations.to_block >= $1
+                AND eth_operations.confirmed = true
+            ORDER BY aggregate_operations.id"#,
+            i64::from(*block_number)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_block_l1_operations", start.elapsed());
+        Ok(operations)
+    }
+
+    /// Loads the Ethereum operation sent for the aggregated operation with the given ID.
+    pub async fn load_eth_operation_by_aggregated_op(
+        &mut self,
+        aggregated_op_id: i64,
+    ) -> QueryResult<Option<StorageETHOperation>> {
+        let start = Instant::now();
+        let operation = sqlx::query_as!(
+            StorageETHOperation,
+            "SELECT eth_operations.* FROM eth_operations
+                INNER JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.eth_op_id = eth_operations.id
+            WHERE eth_aggregated_ops_binding.op_id = $1",
+            aggregated_op_id
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.ethereum.load_eth_operation_by_aggregated_op",
+            start.elapsed()
+        );
+        Ok(operation)
+    }
+
+    /// Loads the hashes of the transactions sent for the Ethereum operation,
+    /// the latest sent transaction is the last one in the list.
+    pub async fn load_eth_tx_hashes(&mut self, eth_op_id: i64) -> QueryResult<Vec<ETHTxHash>> {
+        let start = Instant::now();
+        let hashes = sqlx::query_as!(
+            ETHTxHash,
+            "SELECT * FROM eth_tx_hashes
+                WHERE eth_op_id = $1
+                ORDER BY id ASC",
+            eth_op_id
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_eth_tx_hashes", start.elapsed());
+        Ok(hashes)
+    }
+
+    /// Loads the creation times of the latest Ethereum operations of the given type, newest first.
+    /// Used to estimate how often the Ethereum sender sends the operations.
+    pub async fn load_recent_operations_created_at(
+        &mut self,
+        op_type: AggregatedActionType,
+        limit: u32,
+    ) -> QueryResult<Vec<DateTime<Utc>>> {
+        let start = Instant::now();
+        let created_at = sqlx::query!(
+            r#"SELECT created_at as "created_at!" FROM eth_operations
+                WHERE op_type = $1 AND created_at IS NOT NULL
+                ORDER BY id DESC
+                LIMIT $2"#,
+            op_type.to_string(),
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|record| record.created_at)
+        .collect();
+
+        metrics::histogram!(
+            "sql.ethereum.load_recent_operations_created_at",
+            start.elapsed()
+        );
+        Ok(created_at)
+    }
+
+    /// Loads the latest Ethereum operations along with the gas used by the confirmed ones, newest first.
+    pub async fn load_recent_operations_gas(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<StorageEthOperationGas>> {
+        let start = Instant::now();
+        let operations = sqlx::query_as!(
+            StorageEthOperationGas,
+            r#"SELECT
+                eth_operations.id,
+                eth_operations.op_type,
+                eth_operations.nonce,
+                eth_operations.confirmed,
+                eth_operations.final_hash,
+                eth_operations.last_used_gas_price,
+                eth_operations_gas_used.gas_used AS "gas_used?",
+                eth_operations.created_at
+            FROM eth_operations
+            LEFT JOIN eth_operations_gas_used ON eth_operations_gas_used.eth_op_id = eth_operations.id
+            ORDER BY eth_operations.id DESC
+            LIMIT $1"#,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.ethereum.load_recent_operations_gas", start.elapsed());
+        Ok(operations)
+    }
+
+    /// Loads the fees (in wei) paid for the confirmed Ethereum operations created since the given time.
+    /// The fee is estimated with the gas price of the last sent transaction of the operation.
+    pub async fn load_spent_fees_since(&mut self, since: DateTime<Utc>) -> QueryResult<BigUint> {
+        let start = Instant::now();
+        let spent = sqlx::query!(
+            r#"SELECT COALESCE(SUM(eth_operations_gas_used.gas_used * eth_operations.last_used_gas_price), 0) AS "spent!"
+            FROM eth_operations
+            INNER JOIN eth_operations_gas_used ON eth_operations_gas_used.eth_op_id = eth_operations.id
+            WHERE eth_operations.confirmed = true AND eth_operations.created_at >= $1"#,
+            since
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .spent;
+
+        metrics::histogram!("sql.ethereum.load_spent_fees_since", start.elapsed());
+        Ok(spent.to_bigint().unwrap().to_biguint().unwrap())
+    }
+
+    /// Requests the Ethereum sender to resend the transaction of the operation with a higher gas price.
+    /// Returns `false` if there is already a pending request for the operation.
+    pub async fn request_eth_tx_resend(&mut self, eth_op_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let inserted = sqlx::query!(
+            "INSERT INTO eth_tx_resend_requests (eth_op_id) VALUES ($1)
+            ON CONFLICT (eth_op_id) DO NOTHING",
+            eth_op_id
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.ethereum.request_eth_tx_resend", start.elapsed());
+        Ok(inserted)
+    }
+
+    /// Checks whether there is a pending resend request for the Ethereum operation.
+    pub async fn is_eth_tx_resend_requested(&mut self, eth_op_id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let requested = sqlx::query!(
+            r#"SELECT EXISTS(SELECT 1 FROM eth_tx_resend_requests WHERE eth_op_id = $1) AS "requested!""#,
+            eth_op_id
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .requested;
+
+        metrics::histogram!("sql.ethereum.is_eth_tx_resend_requested", start.elapsed());
+        Ok(requested)
+    }
+
+    /// Removes all the pending resend requests and returns the IDs of the requested Ethereum operations.
+    pub async fn take_eth_tx_resend_requests(&mut self) -> QueryResult<Vec<i64>> {
+        let start = Instant::now();
+        let eth_op_ids = sqlx::query!("DELETE FROM eth_tx_resend_requests RETURNING eth_op_id")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|record| record.eth_op_id)
+            .collect();
+
+        metrics::histogram!("sql.ethereum.take_eth_tx_resend_requests", start.elapsed());
+        Ok(eth_op_ids)
+    }
+
     /// Obtains the next nonce to use and updates the corresponding entry in the database
     /// for the next invocation.
     ///
@@ -698,6 +1017,69 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(final_hash)
     }
 
+    /// Restores the missing final hashes of the execute operations of the blocks in the range,
+    /// which link the withdrawals of the blocks with the Ethereum transaction that completed them.
+    /// The final transaction is the one of the sent transactions that emitted the recorded
+    /// withdrawal events.
+    ///
+    /// Returns the execute operations missing the final hash, the ones with the transaction found
+    /// are restored unless `dry_run` is set. Restored operations are not returned again.
+    pub async fn restore_withdrawal_eth_txs(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        dry_run: bool,
+    ) -> QueryResult<Vec<StorageUnlinkedExecuteOperation>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let operations = sqlx::query_as!(
+            StorageUnlinkedExecuteOperation,
+            r#"SELECT
+                aggregate_operations.id AS aggregated_op_id,
+                eth_operations.id AS eth_op_id,
+                aggregate_operations.from_block,
+                aggregate_operations.to_block,
+                (
+                    SELECT eth_tx_hashes.tx_hash FROM eth_tx_hashes
+                    WHERE eth_tx_hashes.eth_op_id = eth_operations.id
+                        AND EXISTS (SELECT 1 FROM withdrawals WHERE withdrawals.tx_hash = eth_tx_hashes.tx_hash)
+                    ORDER BY eth_tx_hashes.id DESC
+                    LIMIT 1
+                ) AS "withdrawal_tx_hash?"
+            FROM aggregate_operations
+            INNER JOIN eth_aggregated_ops_binding ON eth_aggregated_ops_binding.op_id = aggregate_operations.id
+            INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            WHERE aggregate_operations.action_type = $1
+                AND aggregate_operations.from_block <= $3 AND aggregate_operations.to_block >= $2
+                AND eth_operations.final_hash IS NULL
+            ORDER BY aggregate_operations.id"#,
+            AggregatedActionType::ExecuteBlocks.to_string(),
+            i64::from(*from_block),
+            i64::from(*to_block)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        if !dry_run {
+            for operation in &operations {
+                if let Some(tx_hash) = &operation.withdrawal_tx_hash {
+                    sqlx::query!(
+                        "UPDATE eth_operations SET confirmed = true, final_hash = $2
+                        WHERE id = $1 AND final_hash IS NULL",
+                        operation.eth_op_id,
+                        tx_hash
+                    )
+                    .execute(transaction.conn())
+                    .await?;
+                }
+            }
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.ethereum.restore_withdrawal_eth_txs", start.elapsed());
+        Ok(operations)
+    }
+
     // Updates eth_parameters with given nonce and last block.
     // It updates last_verified_block only if it is greater than given last block.
     pub async fn update_eth_parameters(&mut self, last_block: BlockNumber) -> QueryResult<()> {
@@ -32,6 +32,55 @@ pub struct ETHOperationData {
     pub created_at: Option<DateTime<Utc>>,
 }
 
+/// Confirmed Ethereum operation along with the range of blocks it processed.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageBlockL1Operation {
+    pub action_type: String,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub gas_used: Option<i64>,
+    pub last_used_gas_price: BigDecimal,
+}
+
+/// Ethereum operation along with the gas used by its confirmed transaction.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageEthOperationGas {
+    pub id: i64,
+    pub op_type: String,
+    pub nonce: i64,
+    pub confirmed: bool,
+    pub final_hash: Option<Vec<u8>>,
+    pub last_used_gas_price: BigDecimal,
+    pub gas_used: Option<i64>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Aggregated operation along with the Ethereum operation sent for it.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageL1Operation {
+    pub eth_op_id: i64,
+    pub confirmed: bool,
+    pub final_hash: Option<Vec<u8>>,
+    pub gas_used: Option<i64>,
+    pub aggregated_op_id: i64,
+    pub action_type: String,
+    pub arguments: serde_json::Value,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Execute operation missing the hash of its final Ethereum transaction.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageUnlinkedExecuteOperation {
+    pub aggregated_op_id: i64,
+    pub eth_op_id: i64,
+    pub from_block: i64,
+    pub to_block: i64,
+    /// Sent transaction that emitted the recorded withdrawal events, `None` if there are no such events.
+    pub withdrawal_tx_hash: Option<Vec<u8>>,
+}
+
 #[derive(Debug, Clone, FromRow, PartialEq)]
 pub struct ETHTxHash {
     pub id: i64,
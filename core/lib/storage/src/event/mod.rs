@@ -1,6 +1,7 @@
 // Built-in uses
 use std::{slice, time::Instant};
 // External uses
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 // Workspace uses
 use zksync_types::{
@@ -29,8 +30,9 @@ pub use records::{get_event_type, EventType};
 /// On every insert into this table a special PostgreSQL channel gets notified
 /// about it.
 ///
-/// Note, that all events should be created solely by other `storage` methods
-/// and they are persisted in the database forever.
+/// Note, that all events should be created solely by other `storage` methods.
+/// Events are kept in the database until they're pruned, so the consumers
+/// are able to replay the events they've missed.
 #[derive(Debug)]
 pub struct EventSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
@@ -39,6 +41,9 @@ impl<'a, 'c> EventSchema<'a, 'c> {
     /// This method is private since the type safety is only guaranteed
     /// by the correctness of `event_type` parameter.
     /// Events are expected to have the same type and belong to the same block.
+    ///
+    /// An event is not stored if the identical event was already stored for the block
+    /// since it was last reverted, so repeated processing doesn't produce duplicates.
     async fn store_event_data(
         &mut self,
         block_number: BlockNumber,
@@ -54,10 +59,21 @@ impl<'a, 'c> EventSchema<'a, 'c> {
         // sequences are always incremented ignoring
         // the fact whether the transaction is committed or reverted.
         sqlx::query!(
-            "INSERT INTO events (block_number, event_type, event_data)
+            r#"
+            INSERT INTO events (block_number, event_type, event_data)
             SELECT $1, $2, u.event_data
                 FROM UNNEST ($3::jsonb[])
-                AS u(event_data)",
+                AS u(event_data)
+            WHERE NOT EXISTS (
+                SELECT 1 FROM events
+                WHERE block_number = $1 AND event_type = $2 AND event_data = u.event_data
+                AND id > COALESCE((
+                    SELECT MAX(id) FROM events
+                    WHERE block_number = $1 AND event_type = 'Block'
+                    AND event_data->>'status' = 'reverted'
+                ), 0)
+            )
+            "#,
             i64::from(*block_number),
             event_type as EventType,
             event_data,
@@ -81,7 +97,8 @@ impl<'a, 'c> EventSchema<'a, 'c> {
                 id,
                 block_number,
                 event_type as "event_type!: EventType",
-                event_data
+                event_data,
+                created_at
             FROM events WHERE id > $1
             ORDER BY id ASC
             "#,
@@ -94,6 +111,53 @@ impl<'a, 'c> EventSchema<'a, 'c> {
         Ok(events)
     }
 
+    /// Load at most `limit` events with the `id` greater than `from` in ascending order.
+    /// If `event_type` is provided, only events of this type are returned.
+    pub async fn load_events(
+        &mut self,
+        from: EventId,
+        limit: u32,
+        event_type: Option<EventType>,
+    ) -> QueryResult<Vec<StoredEvent>> {
+        let start = Instant::now();
+        let events = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT
+                id,
+                block_number,
+                event_type as "event_type!: EventType",
+                event_data,
+                created_at
+            FROM events
+            WHERE id > $1 AND ($3::event_type IS NULL OR event_type = $3)
+            ORDER BY id ASC
+            LIMIT $2
+            "#,
+            *from as i64,
+            i64::from(limit),
+            event_type as Option<EventType>,
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.event.load_events", start.elapsed());
+        Ok(events)
+    }
+
+    /// Removes all events created before the given moment.
+    /// Returns the number of removed events.
+    pub async fn remove_events_older_than(&mut self, timestamp: DateTime<Utc>) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!("DELETE FROM events WHERE created_at < $1", timestamp)
+            .execute(self.0.conn())
+            .await?
+            .rows_affected();
+
+        metrics::histogram!("sql.event.remove_events_older_than", start.elapsed());
+        Ok(removed)
+    }
+
     /// Load the id of the latest event in the database.
     /// Returns `None` if the `events` table is empty.
     pub async fn get_last_event_id(&mut self) -> QueryResult<Option<EventId>> {
@@ -1,6 +1,7 @@
 // Built-in uses
 use std::convert::TryFrom;
 // External uses
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::value::Value;
 use sqlx::FromRow;
@@ -11,7 +12,7 @@ use zksync_types::{
 };
 // Local uses
 
-#[derive(sqlx::Type, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(sqlx::Type, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[sqlx(type_name = "event_type")]
 #[serde(rename_all = "snake_case")]
 pub enum EventType {
@@ -26,6 +27,7 @@ pub struct StoredEvent {
     pub block_number: i64,
     pub event_type: EventType,
     pub event_data: Value,
+    pub created_at: DateTime<Utc>,
 }
 
 impl TryFrom<StoredEvent> for ZkSyncEvent {
@@ -217,4 +217,53 @@ impl<'a, 'c> ForcedExitRequestsSchema<'a, 'c> {
 
         Ok(())
     }
+
+    /// Returns whether the processing of the requests is paused by the operator.
+    pub async fn is_processing_paused(&mut self) -> QueryResult<bool> {
+        let start = Instant::now();
+
+        let paused = sqlx::query!("SELECT paused FROM forced_exit_requests_pause")
+            .fetch_optional(self.0.conn())
+            .await?
+            .map_or(false, |record| record.paused);
+
+        metrics::histogram!(
+            "sql.forced_exit_requests.is_processing_paused",
+            start.elapsed()
+        );
+        Ok(paused)
+    }
+
+    /// Pauses or resumes the processing of the requests, returns `false` if the processing
+    /// was already in the requested state.
+    pub async fn set_processing_paused(&mut self, paused: bool, actor: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let changed = ForcedExitRequestsSchema(&mut transaction)
+            .is_processing_paused()
+            .await?
+            != paused;
+        if changed {
+            sqlx::query!(
+                r#"
+                INSERT INTO forced_exit_requests_pause (paused, changed_by)
+                VALUES ($1, $2)
+                ON CONFLICT (id) DO UPDATE
+                SET paused = $1, changed_by = $2, changed_at = now()
+                "#,
+                paused,
+                actor
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.forced_exit_requests.set_processing_paused",
+            start.elapsed()
+        );
+        Ok(changed)
+    }
 }
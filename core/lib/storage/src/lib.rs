@@ -77,6 +77,7 @@
 
 // Built-in deps
 use std::env;
+use std::time::Instant;
 // External imports
 use sqlx::{postgres::Postgres, Connection, PgConnection, Transaction};
 // Workspace imports
@@ -88,6 +89,9 @@ use crate::connection::{holder::ConnectionHolder, PooledConnection};
 #[cfg(test)]
 mod tests;
 
+pub mod access_tokens;
+pub mod account_locks;
+pub mod banned_addresses;
 pub mod chain;
 pub mod config;
 pub mod connection;
@@ -102,11 +106,16 @@ pub mod prover;
 pub mod test_data;
 pub mod tokens;
 pub mod utils;
+pub mod webhooks;
 pub mod withdrawals;
 
 use forced_exit_requests::ForcedExitRequestsSchema;
 
-pub use crate::connection::ConnectionPool;
+pub use crate::connection::{
+    is_transient_error,
+    replicas::{AccessIntent, ReplicatedPool},
+    ConnectionPool, IdleConnectionsCheck, PoolStats,
+};
 pub use sqlx::types::BigDecimal;
 pub type QueryResult<T, E = anyhow::Error> = Result<T, E>;
 
@@ -132,6 +141,8 @@ pub fn get_database_url() -> String {
 pub struct StorageProcessor<'a> {
     conn: ConnectionHolder<'a>,
     in_transaction: bool,
+    /// Start time of the snapshot read transaction, used to track the connection hold time.
+    snapshot_started_at: Option<Instant>,
 }
 
 #[derive(sqlx::Type, Debug, Clone, PartialEq, Eq)]
@@ -158,6 +169,7 @@ impl<'a> StorageProcessor<'a> {
         Ok(StorageProcessor {
             conn: ConnectionHolder::Direct(connection),
             in_transaction: false,
+            snapshot_started_at: None,
         })
     }
 
@@ -172,6 +184,27 @@ impl<'a> StorageProcessor<'a> {
         Ok(processor)
     }
 
+    /// Starts a read-only transaction with the `REPEATABLE READ` isolation level, so all
+    /// the queries made within it observe the same snapshot of the database. For example,
+    /// a block can't get finalized between two queries serving the same API request.
+    ///
+    /// If the `StorageProcessor` is already within a transaction, the nested transaction
+    /// is started instead and the isolation level of the outer one is kept.
+    pub async fn start_snapshot_read<'c: 'b, 'b>(
+        &'c mut self,
+    ) -> Result<StorageProcessor<'b>, anyhow::Error> {
+        let is_nested = self.in_transaction;
+        let mut processor = self.start_transaction().await?;
+        if !is_nested {
+            sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+                .execute(processor.conn())
+                .await?;
+            processor.snapshot_started_at = Some(Instant::now());
+        }
+
+        Ok(processor)
+    }
+
     /// Checks if the `StorageProcessor` is currently within database transaction.
     pub fn in_transaction(&self) -> bool {
         self.in_transaction
@@ -181,12 +214,16 @@ impl<'a> StorageProcessor<'a> {
         StorageProcessor {
             conn: ConnectionHolder::Transaction(conn),
             in_transaction: true,
+            snapshot_started_at: None,
         }
     }
 
     pub async fn commit(self) -> QueryResult<()> {
         if let ConnectionHolder::Transaction(transaction) = self.conn {
             transaction.commit().await?;
+            if let Some(started_at) = self.snapshot_started_at {
+                metrics::histogram!("sql.snapshot_read", started_at.elapsed());
+            }
             Ok(())
         } else {
             panic!("StorageProcessor::commit can only be invoked after calling StorageProcessor::begin_transaction");
@@ -200,6 +237,7 @@ impl<'a> StorageProcessor<'a> {
         Self {
             conn: ConnectionHolder::Pooled(conn),
             in_transaction: false,
+            snapshot_started_at: None,
         }
     }
 
@@ -249,6 +287,26 @@ impl<'a> StorageProcessor<'a> {
         misc::MiscSchema(self)
     }
 
+    /// Gains access to the `AccessTokens` schema.
+    pub fn access_tokens_schema(&mut self) -> access_tokens::AccessTokensSchema<'_, 'a> {
+        access_tokens::AccessTokensSchema(self)
+    }
+
+    /// Gains access to the `AccountLocks` schema.
+    pub fn account_locks_schema(&mut self) -> account_locks::AccountLocksSchema<'_, 'a> {
+        account_locks::AccountLocksSchema(self)
+    }
+
+    /// Gains access to the `BannedAddresses` schema.
+    pub fn banned_addresses_schema(&mut self) -> banned_addresses::BannedAddressesSchema<'_, 'a> {
+        banned_addresses::BannedAddressesSchema(self)
+    }
+
+    /// Gains access to the `Webhooks` schema.
+    pub fn webhooks_schema(&mut self) -> webhooks::WebhooksSchema<'_, 'a> {
+        webhooks::WebhooksSchema(self)
+    }
+
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
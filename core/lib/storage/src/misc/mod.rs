@@ -1,10 +1,11 @@
 // Built-in deps
-use std::time::Instant;
+use std::time::{Duration, Instant};
 // External imports
+use chrono::NaiveDate;
 use sqlx::types::BigDecimal;
 // Local imports
 
-use self::records::Subsidy;
+use self::records::{RpcMethodUsage, Subsidy};
 use crate::{QueryResult, StorageProcessor};
 use num::ToPrimitive;
 
@@ -64,4 +65,119 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
         metrics::histogram!("sql.token.get_total_used_subsidy_for_type", start.elapsed());
         Ok(sum)
     }
+
+    /// Loads the types of transactions that are currently not accepted by the API.
+    pub async fn load_disabled_tx_types(&mut self) -> QueryResult<Vec<String>> {
+        let start = Instant::now();
+        let tx_types = sqlx::query!("SELECT tx_type FROM disabled_tx_types ORDER BY tx_type")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|record| record.tx_type)
+            .collect();
+
+        metrics::histogram!("sql.misc.load_disabled_tx_types", start.elapsed());
+        Ok(tx_types)
+    }
+
+    /// Enables or disables the submission of transactions of the given type.
+    /// Every change notifies the `disabled_tx_types_channel` PostgreSQL channel,
+    /// so the API servers can invalidate their caches.
+    pub async fn set_tx_type_disabled(&mut self, tx_type: &str, disabled: bool) -> QueryResult<()> {
+        let start = Instant::now();
+        if disabled {
+            sqlx::query!(
+                "INSERT INTO disabled_tx_types (tx_type) VALUES ($1) ON CONFLICT (tx_type) DO NOTHING",
+                tx_type
+            )
+            .execute(self.0.conn())
+            .await?;
+        } else {
+            sqlx::query!("DELETE FROM disabled_tx_types WHERE tx_type = $1", tx_type)
+                .execute(self.0.conn())
+                .await?;
+        }
+
+        metrics::histogram!("sql.misc.set_tx_type_disabled", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns how far the database lags behind the primary one if it's a replica.
+    /// For the primary database (or the replica that has replayed all the received changes)
+    /// the lag is zero.
+    pub async fn replication_lag(&mut self) -> QueryResult<Duration> {
+        let start = Instant::now();
+        let lag = sqlx::query!(
+            r#"
+            SELECT CASE
+                WHEN NOT pg_is_in_recovery() OR pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+                ELSE COALESCE(EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()), 0)
+            END::FLOAT8 AS "lag_secs!"
+            "#
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .lag_secs;
+
+        metrics::histogram!("sql.misc.replication_lag", start.elapsed());
+        Ok(Duration::from_secs_f64(lag.max(0.0)))
+    }
+
+    /// Adds the collected calls to the daily usage of the JSON-RPC methods.
+    /// All the records are written with a single query.
+    pub async fn record_rpc_methods_usage(&mut self, usage: &[RpcMethodUsage]) -> QueryResult<()> {
+        let start = Instant::now();
+        if usage.is_empty() {
+            return Ok(());
+        }
+
+        let methods: Vec<_> = usage.iter().map(|record| record.method.clone()).collect();
+        let days: Vec<_> = usage.iter().map(|record| record.day).collect();
+        let subnet_hashes: Vec<_> = usage
+            .iter()
+            .map(|record| record.subnet_hash.clone())
+            .collect();
+        let calls: Vec<_> = usage.iter().map(|record| record.calls).collect();
+        sqlx::query!(
+            r#"
+            INSERT INTO rpc_methods_usage (method, day, subnet_hash, calls)
+            SELECT * FROM UNNEST ($1::text[], $2::date[], $3::text[], $4::bigint[])
+            ON CONFLICT (method, day, subnet_hash)
+            DO UPDATE SET calls = rpc_methods_usage.calls + EXCLUDED.calls
+            "#,
+            &methods,
+            &days,
+            &subnet_hashes,
+            &calls
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.misc.record_rpc_methods_usage", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the daily usage of the JSON-RPC methods for the days in the `[from, to]` range.
+    pub async fn load_rpc_methods_usage(
+        &mut self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> QueryResult<Vec<RpcMethodUsage>> {
+        let start = Instant::now();
+        let usage = sqlx::query_as!(
+            RpcMethodUsage,
+            r#"
+            SELECT method, day, subnet_hash, calls FROM rpc_methods_usage
+            WHERE day BETWEEN $1 AND $2
+            ORDER BY day, method, subnet_hash
+            "#,
+            from,
+            to
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.misc.load_rpc_methods_usage", start.elapsed());
+        Ok(usage)
+    }
 }
@@ -1,5 +1,6 @@
 // External imports
-use sqlx::types::BigDecimal;
+use chrono::NaiveDate;
+use sqlx::{types::BigDecimal, FromRow};
 // Workspace imports
 // Local imports
 use zksync_types::{tx::TxHash, TokenId};
@@ -13,3 +14,13 @@ pub struct Subsidy {
     pub full_cost_token: BigDecimal,
     pub subsidy_type: String,
 }
+
+/// Number of calls of the JSON-RPC method during the day.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct RpcMethodUsage {
+    pub method: String,
+    pub day: NaiveDate,
+    /// Hash of the callers subnet, empty if the calls are not tracked per subnet.
+    pub subnet_hash: String,
+    pub calls: i64,
+}
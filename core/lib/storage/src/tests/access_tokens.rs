@@ -0,0 +1,72 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+use crate::tests::db_test;
+use crate::{access_tokens::AccessTokensSchema, QueryResult, StorageProcessor};
+
+/// Checks that the tokens are loaded by their hashes and can be revoked one by one or per account.
+#[db_test]
+async fn store_and_revoke_access_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let account = Address::repeat_byte(0x01);
+    let another_account = Address::repeat_byte(0x02);
+    let expires_at = Utc::now() + Duration::days(1);
+
+    let first_id = AccessTokensSchema(&mut storage)
+        .store_token(&[1; 32], account, "read", expires_at)
+        .await?;
+    let second_id = AccessTokensSchema(&mut storage)
+        .store_token(&[2; 32], account, "read", expires_at)
+        .await?;
+    AccessTokensSchema(&mut storage)
+        .store_token(&[3; 32], another_account, "read", expires_at)
+        .await?;
+    assert_ne!(first_id, second_id);
+
+    let token = AccessTokensSchema(&mut storage)
+        .load_token(&[1; 32])
+        .await?
+        .expect("Token should be stored");
+    assert_eq!(token.id, first_id);
+    assert_eq!(token.address, account);
+    assert_eq!(token.scope, "read");
+    assert_eq!(token.expires_at.timestamp(), expires_at.timestamp());
+    assert_eq!(token.revoked_at, None);
+    assert_eq!(
+        AccessTokensSchema(&mut storage)
+            .load_token(&[4; 32])
+            .await?,
+        None
+    );
+
+    // The token can be revoked only once.
+    assert!(
+        AccessTokensSchema(&mut storage)
+            .revoke_token(&[1; 32])
+            .await?
+    );
+    assert!(
+        !AccessTokensSchema(&mut storage)
+            .revoke_token(&[1; 32])
+            .await?
+    );
+    let token = AccessTokensSchema(&mut storage)
+        .load_token(&[1; 32])
+        .await?
+        .unwrap();
+    assert!(token.revoked_at.is_some());
+
+    // Only the active tokens of the account are revoked.
+    let revoked = AccessTokensSchema(&mut storage)
+        .revoke_account_tokens(account)
+        .await?;
+    assert_eq!(revoked, 1);
+    let token = AccessTokensSchema(&mut storage)
+        .load_token(&[3; 32])
+        .await?
+        .unwrap();
+    assert_eq!(token.revoked_at, None);
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_types::AccountId;
+// Local imports
+use crate::tests::db_test;
+use crate::{account_locks::AccountLocksSchema, QueryResult, StorageProcessor};
+
+/// Checks that the accounts can be locked and unlocked after the cool-down,
+/// and the requests signed before the latest accepted one are ignored.
+#[db_test]
+async fn lock_and_unlock_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let account_id = AccountId(1);
+    let now = Utc::now();
+
+    assert!(AccountLocksSchema(&mut storage)
+        .account_lock(account_id)
+        .await?
+        .is_none());
+    // The account which is not locked can't be unlocked.
+    assert!(AccountLocksSchema(&mut storage)
+        .schedule_unlock(account_id, now, now)
+        .await?
+        .is_none());
+
+    let lock = AccountLocksSchema(&mut storage)
+        .lock_account(account_id, now - Duration::minutes(2))
+        .await?
+        .expect("Account is not locked");
+    assert_eq!(lock.account_id, account_id);
+    assert_eq!(lock.unlocks_at, None);
+    assert!(AccountLocksSchema(&mut storage)
+        .lock_account(account_id, now - Duration::minutes(3))
+        .await?
+        .is_none());
+
+    // The account stays locked during the cool-down, and the unlock can't be postponed.
+    let unlocks_at = now + Duration::hours(1);
+    let unlocking = AccountLocksSchema(&mut storage)
+        .schedule_unlock(account_id, now - Duration::minutes(1), unlocks_at)
+        .await?
+        .expect("Unlock is not scheduled");
+    assert_eq!(unlocking.locked_at, lock.locked_at);
+    assert!(unlocking.is_active_at(now));
+    assert!(!unlocking.is_active_at(unlocks_at));
+    assert!(AccountLocksSchema(&mut storage)
+        .schedule_unlock(account_id, now, now + Duration::hours(2))
+        .await?
+        .is_none());
+    assert_eq!(
+        AccountLocksSchema(&mut storage)
+            .load_account_locks()
+            .await?,
+        vec![unlocking]
+    );
+
+    // Locking the account again cancels the pending unlock.
+    let relocked = AccountLocksSchema(&mut storage)
+        .lock_account(account_id, now)
+        .await?
+        .expect("Account is not locked");
+    assert_eq!(relocked.unlocks_at, None);
+    assert_eq!(relocked.locked_at, lock.locked_at);
+
+    // Once the cool-down is over, the account is unlocked.
+    AccountLocksSchema(&mut storage)
+        .schedule_unlock(account_id, now + Duration::minutes(1), now)
+        .await?
+        .expect("Unlock is not scheduled");
+    assert!(AccountLocksSchema(&mut storage)
+        .account_lock(account_id)
+        .await?
+        .is_none());
+    assert!(AccountLocksSchema(&mut storage)
+        .load_account_locks()
+        .await?
+        .is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+// Workspace imports
+use zksync_types::Address;
+// Local imports
+use crate::tests::db_test;
+use crate::{banned_addresses::BannedAddressesSchema, QueryResult, StorageProcessor};
+
+/// Checks that addresses can be banned and unbanned, and the repeated changes are ignored.
+#[db_test]
+async fn ban_and_unban_addresses(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let first = Address::repeat_byte(0x01);
+    let second = Address::repeat_byte(0x02);
+
+    assert!(BannedAddressesSchema(&mut storage)
+        .load_banned_addresses()
+        .await?
+        .is_empty());
+
+    assert!(
+        BannedAddressesSchema(&mut storage)
+            .ban_address(first, "sanctioned", "admin")
+            .await?
+    );
+    assert!(
+        BannedAddressesSchema(&mut storage)
+            .ban_address(second, "phishing", "another_admin")
+            .await?
+    );
+    // Banning the same address twice doesn't change the initial record.
+    assert!(
+        !BannedAddressesSchema(&mut storage)
+            .ban_address(first, "duplicate", "another_admin")
+            .await?
+    );
+
+    let banned = BannedAddressesSchema(&mut storage)
+        .load_banned_addresses()
+        .await?;
+    assert_eq!(banned.len(), 2);
+    assert_eq!(banned[0].address, first);
+    assert_eq!(banned[0].reason, "sanctioned");
+    assert_eq!(banned[0].banned_by, "admin");
+    assert_eq!(banned[1].address, second);
+
+    assert!(
+        BannedAddressesSchema(&mut storage)
+            .unban_address(first, "delisted", "admin")
+            .await?
+    );
+    assert!(
+        !BannedAddressesSchema(&mut storage)
+            .unban_address(first, "delisted", "admin")
+            .await?
+    );
+    let banned = BannedAddressesSchema(&mut storage)
+        .load_banned_addresses()
+        .await?;
+    assert_eq!(banned.len(), 1);
+    assert_eq!(banned[0].address, second);
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
 // External imports
 use num::{BigUint, Zero};
 // Workspace imports
+use zksync_api_types::v02::account::EthAccountType as ApiEthAccountType;
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID};
 use zksync_types::{
     aggregated_operations::AggregatedActionType, helpers::apply_updates, AccountId, AccountMap,
-    AccountUpdate, Address, BlockNumber, Nonce, Token, TokenId, TokenKind,
+    AccountUpdate, Address, BlockNumber, Nonce, PubKeyHash, Token, TokenId, TokenKind, H256,
 };
 // Local imports
 use super::block::apply_random_updates;
@@ -17,6 +18,7 @@ use crate::{
         block::BlockSchema,
         state::StateSchema,
     },
+    tokens::records::TokenChangeSource,
     QueryResult, StorageProcessor,
 };
 
@@ -41,6 +43,235 @@ async fn eth_account_type(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Stores the executed `ChangePubKey` of the account with the given type of the authorization.
+async fn insert_change_pub_key(
+    storage: &mut StorageProcessor<'_>,
+    account_id: u32,
+    block_number: i64,
+    auth_type: &str,
+    success: bool,
+) -> QueryResult<()> {
+    let tx = serde_json::json!({
+        "type": "ChangePubKey",
+        "accountId": account_id,
+        "ethAuthData": { "type": auth_type },
+    });
+    let address = Address::random();
+    sqlx::query(
+        r#"
+        INSERT INTO executed_transactions (
+            block_number, block_index, tx, operation, tx_hash, from_account, success,
+            primary_account_address, nonce, created_at
+        )
+        VALUES ($1, 0, $2, '{}'::jsonb, $3, $4, $5, $4, 0, now())
+        "#,
+    )
+    .bind(block_number)
+    .bind(tx)
+    .bind(H256::random().as_bytes())
+    .bind(address.as_bytes())
+    .bind(success)
+    .execute(storage.conn())
+    .await?;
+    Ok(())
+}
+
+async fn stored_account_type(
+    storage: &mut StorageProcessor<'_>,
+    account_id: AccountId,
+) -> QueryResult<Option<ApiEthAccountType>> {
+    Ok(AccountSchema(storage)
+        .account_type_by_id(account_id)
+        .await?
+        .map(ApiEthAccountType::from))
+}
+
+/// Checks that the account types backfill takes the type from the latest successful
+/// `ChangePubKey` of the account and keeps the types that are already stored.
+#[db_test]
+async fn account_types_backfill(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const BACKFILL: &str =
+        include_str!("../../../migrations/2023-07-31-120000_account_types_backfill/up.sql");
+    let (create2, owned, failed, keyless, stored) = (
+        AccountId(1_000_001),
+        AccountId(1_000_002),
+        AccountId(1_000_003),
+        AccountId(1_000_004),
+        AccountId(1_000_005),
+    );
+
+    insert_change_pub_key(&mut storage, *create2, 1, "Onchain", true).await?;
+    insert_change_pub_key(&mut storage, *create2, 2, "CREATE2", true).await?;
+    insert_change_pub_key(&mut storage, *owned, 1, "CREATE2", true).await?;
+    insert_change_pub_key(&mut storage, *owned, 2, "ECDSA", true).await?;
+    insert_change_pub_key(&mut storage, *failed, 1, "CREATE2", false).await?;
+    insert_change_pub_key(&mut storage, *stored, 1, "CREATE2", true).await?;
+    AccountSchema(&mut storage)
+        .set_account_type(stored, EthAccountType::Owned)
+        .await?;
+
+    sqlx::query(BACKFILL).execute(storage.conn()).await?;
+
+    assert_eq!(
+        stored_account_type(&mut storage, create2).await?,
+        Some(ApiEthAccountType::CREATE2)
+    );
+    assert_eq!(
+        stored_account_type(&mut storage, owned).await?,
+        Some(ApiEthAccountType::Owned)
+    );
+    assert_eq!(
+        stored_account_type(&mut storage, stored).await?,
+        Some(ApiEthAccountType::Owned)
+    );
+    // Accounts without a successful `ChangePubKey` have no key, so no type is stored.
+    for &account_id in &[failed, keyless] {
+        let stored_type = stored_account_type(&mut storage, account_id).await?;
+        assert_eq!(stored_type, None);
+        assert_eq!(
+            ApiEthAccountType::for_account(stored_type, &PubKeyHash::default()),
+            ApiEthAccountType::NoKey
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that accounts can be found by both current and historical public key hashes.
+#[db_test]
+async fn accounts_by_pubkey_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let _lock = ACCOUNT_MUTEX.lock().await;
+
+    let first_hash = PubKeyHash::from_bytes(&[1u8; 20]).unwrap();
+    let second_hash = PubKeyHash::from_bytes(&[2u8; 20]).unwrap();
+    let unused_hash = PubKeyHash::from_bytes(&[3u8; 20]).unwrap();
+    let (first_address, second_address) = (Address::random(), Address::random());
+
+    // The first account sets the key in the first block and changes it in the second one,
+    // the second account reuses the first key of the first account.
+    let first_block_updates = vec![
+        (
+            AccountId(1),
+            AccountUpdate::Create {
+                address: first_address,
+                nonce: Nonce(0),
+            },
+        ),
+        (
+            AccountId(2),
+            AccountUpdate::Create {
+                address: second_address,
+                nonce: Nonce(0),
+            },
+        ),
+        (
+            AccountId(1),
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash: PubKeyHash::zero(),
+                new_pub_key_hash: first_hash,
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(1),
+            },
+        ),
+    ];
+    let second_block_updates = vec![
+        (
+            AccountId(1),
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash: first_hash,
+                new_pub_key_hash: second_hash,
+                old_nonce: Nonce(1),
+                new_nonce: Nonce(2),
+            },
+        ),
+        (
+            AccountId(2),
+            AccountUpdate::ChangePubKeyHash {
+                old_pub_key_hash: PubKeyHash::zero(),
+                new_pub_key_hash: first_hash,
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(1),
+            },
+        ),
+    ];
+    for (block_number, updates) in [(1, first_block_updates), (2, second_block_updates)] {
+        BlockSchema(&mut storage)
+            .save_full_block(gen_sample_block(
+                BlockNumber(block_number),
+                100,
+                Default::default(),
+            ))
+            .await?;
+        StateSchema(&mut storage)
+            .commit_state_update(BlockNumber(block_number), &updates, 0)
+            .await?;
+    }
+
+    let owners = AccountSchema(&mut storage)
+        .accounts_by_pubkey_hash(first_hash)
+        .await?;
+    assert_eq!(owners.len(), 2);
+    assert_eq!(owners[0].account_id, 1);
+    assert_eq!(owners[0].address, first_address.as_bytes().to_vec());
+    assert!(!owners[0].is_current);
+    assert_eq!(owners[1].account_id, 2);
+    assert_eq!(owners[1].address, second_address.as_bytes().to_vec());
+    assert!(owners[1].is_current);
+
+    let owners = AccountSchema(&mut storage)
+        .accounts_by_pubkey_hash(second_hash)
+        .await?;
+    assert_eq!(owners.len(), 1);
+    assert_eq!(owners[0].account_id, 1);
+    assert!(owners[0].is_current);
+
+    let owners = AccountSchema(&mut storage)
+        .accounts_by_pubkey_hash(unused_hash)
+        .await?;
+    assert!(owners.is_empty());
+
+    Ok(())
+}
+
+/// Checks that the block the account was created in is found.
+#[db_test]
+async fn account_creation_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let _lock = ACCOUNT_MUTEX.lock().await;
+
+    for block_number in 1..=3 {
+        BlockSchema(&mut storage)
+            .save_full_block(gen_sample_block(
+                BlockNumber(block_number),
+                100,
+                Default::default(),
+            ))
+            .await?;
+        let updates = vec![(
+            AccountId(block_number),
+            AccountUpdate::Create {
+                address: Address::random(),
+                nonce: Nonce(0),
+            },
+        )];
+        StateSchema(&mut storage)
+            .commit_state_update(BlockNumber(block_number), &updates, 0)
+            .await?;
+    }
+
+    for account_id in 1..=3 {
+        let block_number = AccountSchema(&mut storage)
+            .account_creation_block(AccountId(account_id))
+            .await?;
+        assert_eq!(block_number, Some(BlockNumber(account_id)));
+    }
+    let block_number = AccountSchema(&mut storage)
+        .account_creation_block(AccountId(4))
+        .await?;
+    assert_eq!(block_number, None);
+
+    Ok(())
+}
+
 /// Checks that stored accounts can be obtained once they're committed.
 #[db_test]
 async fn stored_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -330,25 +561,31 @@ async fn test_get_account_nft_balance(mut storage: StorageProcessor<'_>) -> Quer
 
     storage
         .tokens_schema()
-        .store_or_update_token(Token {
-            id: nft_id,
-            address: Address::random(),
-            symbol: "NFT".to_string(),
-            decimals: 0,
-            kind: TokenKind::NFT,
-            is_nft: true,
-        })
+        .store_or_update_token(
+            Token {
+                id: nft_id,
+                address: Address::random(),
+                symbol: "NFT".to_string(),
+                decimals: 0,
+                kind: TokenKind::NFT,
+                is_nft: true,
+            },
+            &TokenChangeSource::TokenHandler,
+        )
         .await?;
     storage
         .tokens_schema()
-        .store_or_update_token(Token {
-            id: NFT_TOKEN_ID,
-            address: Address::random(),
-            symbol: "SPECIAL".to_string(),
-            decimals: 0,
-            kind: TokenKind::NFT,
-            is_nft: true,
-        })
+        .store_or_update_token(
+            Token {
+                id: NFT_TOKEN_ID,
+                address: Address::random(),
+                symbol: "SPECIAL".to_string(),
+                decimals: 0,
+                kind: TokenKind::NFT,
+                is_nft: true,
+            },
+            &TokenChangeSource::TokenHandler,
+        )
         .await?;
 
     // Checks that nonexistent account has zero nft balance.
@@ -460,14 +697,17 @@ async fn test_get_nft_owner(mut storage: StorageProcessor<'_>) -> QueryResult<()
     ];
     storage
         .tokens_schema()
-        .store_or_update_token(Token {
-            id: nft_id,
-            address: Address::random(),
-            symbol: "NFT".to_string(),
-            decimals: 0,
-            kind: TokenKind::NFT,
-            is_nft: true,
-        })
+        .store_or_update_token(
+            Token {
+                id: nft_id,
+                address: Address::random(),
+                symbol: "NFT".to_string(),
+                decimals: 0,
+                kind: TokenKind::NFT,
+                is_nft: true,
+            },
+            &TokenChangeSource::TokenHandler,
+        )
         .await?;
     storage
         .chain()
@@ -1271,6 +1271,44 @@ async fn test_get_block_transactions_page(mut storage: StorageProcessor<'_>) ->
     Ok(())
 }
 
+/// Checks that `load_block_transactions_api_v02` returns all the operations of the block in order.
+#[db_test]
+async fn test_load_block_transactions(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    setup.add_block(2);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    for (block_idx, block) in setup.blocks.iter().enumerate() {
+        let expected: Vec<TxHash> = (0..block.block_transactions.len())
+            .map(|tx_idx| setup.get_tx_hash(block_idx, tx_idx))
+            .collect();
+        let txs = storage
+            .chain()
+            .block_schema()
+            .load_block_transactions_api_v02(block.block_number)
+            .await?;
+        let actual: Vec<TxHash> = txs.iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(actual, expected);
+
+        // Both L1 and L2 operations report their positions in the block.
+        let block_indices: Vec<_> = txs.iter().map(|tx| tx.block_index).collect();
+        let expected: Vec<_> = (0..block.block_transactions.len() as u32)
+            .map(Some)
+            .collect();
+        assert_eq!(block_indices, expected);
+    }
+
+    let txs = storage
+        .chain()
+        .block_schema()
+        .load_block_transactions_api_v02(BlockNumber(3))
+        .await?;
+    assert!(txs.is_empty());
+
+    Ok(())
+}
+
 /// Check that `get_block_number_by_hash` works correctly
 #[db_test]
 async fn test_get_block_number_by_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
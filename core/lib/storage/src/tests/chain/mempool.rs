@@ -1,6 +1,10 @@
 // External imports
 use chrono::Utc;
 // Workspace imports
+use zksync_api_types::v02::{
+    pagination::PaginationDirection,
+    transaction::{L2Receipt, Receipt, TxInBlockStatus},
+};
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_types::{
     block::{Block, ExecutedOperations},
@@ -8,14 +12,14 @@ use zksync_types::{
     priority_ops::FullExit,
     tx::{ChangePubKey, Transfer, TxHash, Withdraw},
     AccountId, Address, BlockNumber, ExecutedPriorityOp, ExecutedTx, FullExitOp, Nonce, PriorityOp,
-    SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
+    SignedZkSyncTx, TokenId, TokenLike, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
 // Local imports
 use crate::test_data::gen_eth_sign_data;
 use crate::tests::db_test;
 use crate::{
     chain::{
-        mempool::MempoolSchema,
+        mempool::{MempoolSchema, BATCH_MEMBER_REMOVED_REASON, CANCELLED_TX_REASON},
         operations::{
             records::{NewExecutedPriorityOperation, NewExecutedTransaction},
             OperationsSchema,
@@ -179,6 +183,34 @@ async fn store_load(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 }
 
 /// Checks the save&load routine for mempool schema.
+/// Checks that the pending transactions are found by both the initiator and the recipient address.
+#[db_test]
+async fn pending_txs_for_address(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    // `zksync_txs` starts with two transfers.
+    let (sender, recipient) = match &txs[0].tx {
+        ZkSyncTx::Transfer(transfer) => (transfer.from, transfer.to),
+        _ => unreachable!(),
+    };
+
+    for address in [sender, recipient] {
+        let pending = MempoolSchema(&mut storage)
+            .get_pending_txs_for_address(address)
+            .await?;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].hash(), txs[0].hash());
+    }
+    assert!(MempoolSchema(&mut storage)
+        .get_pending_txs_for_address(Address::random())
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
 #[db_test]
 async fn store_load_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     // Insert several txs into the mempool schema.
@@ -322,6 +354,260 @@ async fn collect_garbage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that evicted txs are removed from the mempool and reported along with
+/// the failed executed txs of the same account.
+#[db_test]
+async fn evict_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    let address = txs[0].account();
+
+    // Add one failed transaction of the same account.
+    let failed_tx_hash = H256::repeat_byte(0x11);
+    let executed_tx = NewExecutedTransaction {
+        block_number: 1,
+        tx_hash: failed_tx_hash.as_bytes().to_vec(),
+        tx: Default::default(),
+        operation: Default::default(),
+        from_account: address.as_bytes().to_vec(),
+        to_account: None,
+        success: false,
+        fail_reason: Some("Nonce mismatch".to_string()),
+        block_index: None,
+        primary_account_address: Default::default(),
+        nonce: Default::default(),
+        created_at: Utc::now() - chrono::Duration::minutes(1),
+        eth_sign_data: None,
+        batch_id: None,
+        affected_accounts: Vec::new(),
+        used_tokens: Vec::new(),
+    };
+    OperationsSchema(&mut storage)
+        .store_executed_tx(executed_tx)
+        .await?;
+
+    MempoolSchema(&mut storage)
+        .evict_txs(
+            &[SignedTxVariant::Tx(txs[0].clone())],
+            "Transaction has expired",
+        )
+        .await?;
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(txs[0].hash())
+            .await?
+    );
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), txs.len() - 1);
+
+    // Both rejections are reported, newest first.
+    let rejected = OperationsExtSchema(&mut storage)
+        .get_account_rejected_txs(address, Utc::now() - chrono::Duration::hours(1), 10)
+        .await?;
+    assert_eq!(rejected.len(), 2);
+    assert_eq!(rejected[0].tx_hash, txs[0].hash().as_ref().to_vec());
+    assert!(rejected[0].evicted);
+    assert_eq!(
+        rejected[0].fail_reason.as_deref(),
+        Some("Transaction has expired")
+    );
+    assert_eq!(rejected[1].tx_hash, failed_tx_hash.as_bytes().to_vec());
+    assert!(!rejected[1].evicted);
+
+    // Rejections older than the provided moment are skipped.
+    let rejected = OperationsExtSchema(&mut storage)
+        .get_account_rejected_txs(address, Utc::now() - chrono::Duration::seconds(30), 10)
+        .await?;
+    assert_eq!(rejected.len(), 1);
+    assert!(rejected[0].evicted);
+
+    // Outdated evictions are pruned.
+    let removed = MempoolSchema(&mut storage)
+        .remove_evicted_txs_older_than(Utc::now() + chrono::Duration::minutes(1))
+        .await?;
+    assert_eq!(removed, 1);
+    let rejected = OperationsExtSchema(&mut storage)
+        .get_account_rejected_txs(address, Utc::now() - chrono::Duration::hours(1), 10)
+        .await?;
+    assert_eq!(rejected.len(), 1);
+    assert!(!rejected[0].evicted);
+
+    Ok(())
+}
+
+/// Checks that the executed transactions, priority operations and mempool transactions
+/// are found by the prefix of their hashes.
+#[db_test]
+async fn search_tx_hashes_by_prefix(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    let mempool_hash = txs[0].hash().as_ref().to_vec();
+    let address = txs[0].account();
+
+    // Executed operations having all but the last byte of the hash in common with the mempool tx.
+    let mut executed_hash = mempool_hash.clone();
+    executed_hash[31] = executed_hash[31].wrapping_add(1);
+    let executed_tx = NewExecutedTransaction {
+        block_number: 1,
+        tx_hash: executed_hash.clone(),
+        tx: serde_json::json!({ "type": "Transfer" }),
+        operation: Default::default(),
+        from_account: address.as_bytes().to_vec(),
+        to_account: None,
+        success: true,
+        fail_reason: None,
+        block_index: Some(0),
+        primary_account_address: Default::default(),
+        nonce: Default::default(),
+        created_at: Utc::now(),
+        eth_sign_data: None,
+        batch_id: None,
+        affected_accounts: Vec::new(),
+        used_tokens: Vec::new(),
+    };
+    OperationsSchema(&mut storage)
+        .store_executed_tx(executed_tx)
+        .await?;
+
+    let mut priority_op_hash = mempool_hash.clone();
+    priority_op_hash[31] = priority_op_hash[31].wrapping_add(2);
+    let priority_op = NewExecutedPriorityOperation {
+        block_number: 1,
+        block_index: 1,
+        operation: serde_json::json!({ "type": "Deposit" }),
+        from_account: address.as_bytes().to_vec(),
+        to_account: address.as_bytes().to_vec(),
+        priority_op_serialid: 1,
+        deadline_block: 0,
+        eth_hash: H256::zero().as_bytes().to_vec(),
+        eth_block: 0,
+        created_at: Utc::now(),
+        eth_block_index: None,
+        tx_hash: priority_op_hash.clone(),
+        affected_accounts: Vec::new(),
+        token: 0,
+    };
+    OperationsSchema(&mut storage)
+        .store_executed_priority_op(priority_op)
+        .await?;
+
+    // All three are found by the common prefix, ordered by the hash.
+    let prefix = hex::encode(&mempool_hash[..4]);
+    let found = OperationsExtSchema(&mut storage)
+        .search_tx_hashes_by_prefix(&prefix, 10)
+        .await?;
+    let mut expected_hashes = vec![
+        mempool_hash.clone(),
+        executed_hash.clone(),
+        priority_op_hash.clone(),
+    ];
+    expected_hashes.sort();
+    assert_eq!(
+        found.iter().map(|m| m.tx_hash.clone()).collect::<Vec<_>>(),
+        expected_hashes
+    );
+
+    let mempool_match = found.iter().find(|m| m.tx_hash == mempool_hash).unwrap();
+    assert_eq!(mempool_match.source, "mempool");
+    assert_eq!(mempool_match.tx_type.as_deref(), Some("Transfer"));
+    assert_eq!(mempool_match.block_number, None);
+    assert_eq!(mempool_match.account, Some(address.as_bytes().to_vec()));
+    let executed_match = found.iter().find(|m| m.tx_hash == executed_hash).unwrap();
+    assert_eq!(executed_match.source, "executed");
+    assert_eq!(executed_match.block_number, Some(1));
+    let priority_op_match = found
+        .iter()
+        .find(|m| m.tx_hash == priority_op_hash)
+        .unwrap();
+    assert_eq!(priority_op_match.source, "priority");
+    assert_eq!(priority_op_match.tx_type.as_deref(), Some("Deposit"));
+
+    // An odd number of digits is supported, the number of matches is limited.
+    let found = OperationsExtSchema(&mut storage)
+        .search_tx_hashes_by_prefix(&prefix[..7], 2)
+        .await?;
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].tx_hash, expected_hashes[0]);
+
+    // The full hash matches only the transaction itself.
+    let found = OperationsExtSchema(&mut storage)
+        .search_tx_hashes_by_prefix(&hex::encode(&executed_hash), 10)
+        .await?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].tx_hash, executed_hash);
+
+    Ok(())
+}
+
+/// Checks that removing a single member of the batch removes the whole batch,
+/// so the nonces of the other batch accounts can be used again.
+#[db_test]
+async fn remove_batch_member(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    MempoolSchema(&mut storage)
+        .insert_batch(&txs, Vec::new())
+        .await?;
+    let tx_hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
+    let batch_hash = TxHash::batch_hash(&tx_hashes);
+
+    // Remove the transaction in the middle of the batch.
+    MempoolSchema(&mut storage)
+        .remove_txs(&[tx_hashes[1]])
+        .await?;
+    assert!(MempoolSchema(&mut storage).load_txs(&[]).await?.is_empty());
+    for tx in &txs {
+        assert!(MempoolSchema(&mut storage)
+            .get_pending_txs_for_address(tx.account())
+            .await?
+            .is_empty());
+    }
+
+    // The rest of the batch is recorded as evicted.
+    for tx in [&txs[0], &txs[2]] {
+        let rejected = OperationsExtSchema(&mut storage)
+            .get_account_rejected_txs(tx.account(), Utc::now() - chrono::Duration::hours(1), 10)
+            .await?;
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].tx_hash, tx.hash().as_ref().to_vec());
+        assert_eq!(
+            rejected[0].fail_reason.as_deref(),
+            Some(BATCH_MEMBER_REMOVED_REASON)
+        );
+    }
+
+    // The batch is reported as rejected along with the reason.
+    let batch = OperationsExtSchema(&mut storage)
+        .get_batch_info(batch_hash)
+        .await?
+        .unwrap();
+    assert_eq!(batch.batch_status.last_state, TxInBlockStatus::Rejected);
+    assert_eq!(
+        batch.batch_status.fail_reason.as_deref(),
+        Some(BATCH_MEMBER_REMOVED_REASON)
+    );
+
+    // A new transaction with the same nonce is not bound to the removed batch.
+    let mut transfer = txs[0].tx.clone();
+    if let ZkSyncTx::Transfer(transfer) = &mut transfer {
+        transfer.amount = 200u32.into();
+    }
+    let new_tx = SignedZkSyncTx {
+        tx: transfer,
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    };
+    MempoolSchema(&mut storage).insert_tx(&new_tx).await?;
+    let txs_from_db = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(txs_from_db.len(), 1);
+    assert_eq!(unwrap_tx(txs_from_db[0].clone()).hash(), new_tx.hash());
+
+    Ok(())
+}
+
 /// Checks that memory pool contains previously inserted transaction.
 #[db_test]
 async fn contains_and_get_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -369,6 +655,362 @@ async fn contains_and_get_tx(mut storage: StorageProcessor<'_>) -> QueryResult<(
     Ok(())
 }
 
+/// Checks the lifecycle of the transactions in the submission outbox.
+#[db_test]
+async fn submission_outbox(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        assert!(MempoolSchema(&mut storage).insert_outbox_tx(tx).await?);
+        assert_eq!(
+            MempoolSchema(&mut storage)
+                .get_outbox_tx_receipt(tx.hash())
+                .await?,
+            Some(Receipt::L2(L2Receipt {
+                tx_hash: tx.hash(),
+                rollup_block: None,
+                status: TxInBlockStatus::Queued,
+                fail_reason: None,
+                archived: false,
+            }))
+        );
+    }
+    // Repeated insertion of the same transaction is ignored.
+    assert!(
+        !MempoolSchema(&mut storage)
+            .insert_outbox_tx(&txs[0])
+            .await?
+    );
+
+    let undelivered: Vec<_> = MempoolSchema(&mut storage)
+        .lock_undelivered_outbox_submissions(u32::MAX)
+        .await?
+        .iter()
+        .flat_map(SignedTxVariant::get_transactions)
+        .collect();
+    for tx in &txs {
+        let stored_tx = undelivered
+            .iter()
+            .find(|stored_tx| stored_tx.hash() == tx.hash())
+            .expect("Transaction is not loaded from the outbox");
+        assert_eq!(stored_tx.eth_sign_data, tx.eth_sign_data);
+    }
+
+    // Failed delivery attempts don't change the state of the transaction.
+    MempoolSchema(&mut storage)
+        .record_outbox_tx_failure(txs[0].hash(), "Mempool is unavailable")
+        .await?;
+    MempoolSchema(&mut storage)
+        .mark_outbox_tx_delivered(txs[1].hash(), None)
+        .await?;
+    MempoolSchema(&mut storage)
+        .mark_outbox_tx_delivered(txs[2].hash(), Some("Nonce mismatch"))
+        .await?;
+
+    let undelivered: Vec<_> = MempoolSchema(&mut storage)
+        .lock_undelivered_outbox_submissions(u32::MAX)
+        .await?
+        .iter()
+        .flat_map(SignedTxVariant::hashes)
+        .collect();
+    assert!(undelivered.contains(&txs[0].hash()));
+    assert!(!undelivered.contains(&txs[1].hash()));
+    assert!(!undelivered.contains(&txs[2].hash()));
+
+    // Delivered transactions are reported by the mempool, rejected ones by the outbox.
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_outbox_tx_receipt(txs[1].hash())
+            .await?,
+        None
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_outbox_tx_receipt(txs[2].hash())
+            .await?,
+        Some(Receipt::L2(L2Receipt {
+            tx_hash: txs[2].hash(),
+            rollup_block: None,
+            status: TxInBlockStatus::Rejected,
+            fail_reason: Some("Nonce mismatch".to_string()),
+            archived: false,
+        }))
+    );
+
+    // Only delivered transactions are pruned.
+    MempoolSchema(&mut storage)
+        .remove_delivered_outbox_txs_older_than(Utc::now() + chrono::Duration::minutes(1))
+        .await?;
+    assert!(MempoolSchema(&mut storage)
+        .get_outbox_tx_receipt(txs[0].hash())
+        .await?
+        .is_some());
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_outbox_tx_receipt(txs[2].hash())
+            .await?,
+        None
+    );
+
+    Ok(())
+}
+
+/// Checks that the batches are stored in the submission outbox and forwarded as a whole.
+#[db_test]
+async fn submission_outbox_batches(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(4);
+    let (single_tx, batch) = (&txs[0], &txs[1..]);
+    let batch_hash = TxHash::batch_hash(&batch.iter().map(|tx| tx.hash()).collect::<Vec<_>>());
+    let eth_signatures = vec![gen_eth_sign_data("test message".to_owned()).signature];
+
+    assert!(
+        MempoolSchema(&mut storage)
+            .insert_outbox_tx(single_tx)
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .insert_outbox_batch(batch, &eth_signatures)
+            .await?
+    );
+    // Repeated insertion of the same batch is ignored.
+    assert!(
+        !MempoolSchema(&mut storage)
+            .insert_outbox_batch(batch, &eth_signatures)
+            .await?
+    );
+    // Transactions submitted on their own can't be a part of the batch.
+    assert!(MempoolSchema(&mut storage)
+        .insert_outbox_batch(&txs[..2], &[])
+        .await
+        .is_err());
+
+    // The batch is loaded as a whole even if the limit is exceeded.
+    let undelivered = MempoolSchema(&mut storage)
+        .lock_undelivered_outbox_submissions(2)
+        .await?;
+    assert_eq!(undelivered.len(), 2);
+    assert_eq!(undelivered[0].hashes(), vec![single_tx.hash()]);
+    match &undelivered[1] {
+        SignedTxVariant::Batch(loaded_batch) => {
+            assert_eq!(
+                undelivered[1].hashes(),
+                batch.iter().map(|tx| tx.hash()).collect::<Vec<_>>()
+            );
+            assert_eq!(loaded_batch.eth_signatures, eth_signatures);
+        }
+        SignedTxVariant::Tx(_) => panic!("Batch is loaded as a single transaction"),
+    }
+
+    // Transactions of the batch can't be cancelled.
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_undelivered_outbox_tx(batch[0].hash())
+            .await?
+            .map(|(_, batch_hash)| batch_hash),
+        Some(Some(batch_hash))
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .cancel_outbox_tx(batch[0].hash())
+            .await?
+    );
+
+    let batch_info = OperationsExtSchema(&mut storage)
+        .get_batch_info(batch_hash)
+        .await?
+        .expect("Queued batch is not found");
+    assert_eq!(batch_info.batch_status.last_state, TxInBlockStatus::Queued);
+    assert_eq!(batch_info.transaction_hashes.len(), batch.len());
+
+    // The rejection of the batch is reported by its status.
+    for tx in batch {
+        MempoolSchema(&mut storage)
+            .mark_outbox_tx_delivered(tx.hash(), Some("Nonce mismatch"))
+            .await?;
+    }
+    let batch_info = OperationsExtSchema(&mut storage)
+        .get_batch_info(batch_hash)
+        .await?
+        .expect("Rejected batch is not found");
+    assert_eq!(
+        batch_info.batch_status.last_state,
+        TxInBlockStatus::Rejected
+    );
+    assert_eq!(
+        batch_info.batch_status.fail_reason.as_deref(),
+        Some("Nonce mismatch")
+    );
+
+    // Delivered batches are pruned along with their transactions.
+    MempoolSchema(&mut storage)
+        .remove_delivered_outbox_txs_older_than(Utc::now() + chrono::Duration::minutes(1))
+        .await?;
+    assert!(OperationsExtSchema(&mut storage)
+        .get_batch_info(batch_hash)
+        .await?
+        .is_none());
+    assert!(
+        MempoolSchema(&mut storage)
+            .insert_outbox_batch(batch, &eth_signatures)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the submitters can cancel only the transactions that are submitted on their own,
+/// either before or after being forwarded to the mempool.
+#[db_test]
+async fn cancel_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(4);
+    let (outbox_tx, mempool_tx, batch) = (&txs[0], &txs[1], &txs[2..]);
+    MempoolSchema(&mut storage)
+        .insert_outbox_tx(outbox_tx)
+        .await?;
+    MempoolSchema(&mut storage).insert_tx(mempool_tx).await?;
+    MempoolSchema(&mut storage)
+        .insert_batch(batch, Vec::new())
+        .await?;
+
+    // The undelivered outbox transaction is cancelled once and reported as rejected.
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_undelivered_outbox_tx(outbox_tx.hash())
+            .await?
+            .map(|(tx, batch_hash)| (tx.hash(), batch_hash)),
+        Some((outbox_tx.hash(), None))
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .cancel_outbox_tx(outbox_tx.hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .cancel_outbox_tx(outbox_tx.hash())
+            .await?
+    );
+    assert!(MempoolSchema(&mut storage)
+        .get_undelivered_outbox_tx(outbox_tx.hash())
+        .await?
+        .is_none());
+    assert!(MempoolSchema(&mut storage)
+        .lock_undelivered_outbox_submissions(u32::MAX)
+        .await?
+        .iter()
+        .all(|submission| !submission.hashes().contains(&outbox_tx.hash())));
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_outbox_tx_receipt(outbox_tx.hash())
+            .await?,
+        Some(Receipt::L2(L2Receipt {
+            tx_hash: outbox_tx.hash(),
+            rollup_block: None,
+            status: TxInBlockStatus::Rejected,
+            fail_reason: Some(CANCELLED_TX_REASON.to_string()),
+            archived: false,
+        }))
+    );
+
+    // The mempool transaction is evicted along with the reason.
+    let (_, batch_id) = MempoolSchema(&mut storage)
+        .get_tx_with_batch_id(mempool_tx.hash())
+        .await?
+        .unwrap();
+    assert_eq!(batch_id, None);
+    assert!(
+        MempoolSchema(&mut storage)
+            .cancel_tx(mempool_tx.hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .cancel_tx(mempool_tx.hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(mempool_tx.hash())
+            .await?
+    );
+    let rejected = OperationsExtSchema(&mut storage)
+        .get_account_rejected_txs(
+            mempool_tx.account(),
+            Utc::now() - chrono::Duration::hours(1),
+            10,
+        )
+        .await?;
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].tx_hash, mempool_tx.hash().as_ref().to_vec());
+    assert_eq!(
+        rejected[0].fail_reason.as_deref(),
+        Some(CANCELLED_TX_REASON)
+    );
+
+    // Batch members are never cancelled.
+    for tx in batch {
+        let (_, batch_id) = MempoolSchema(&mut storage)
+            .get_tx_with_batch_id(tx.hash())
+            .await?
+            .unwrap();
+        assert!(batch_id.is_some());
+        assert!(!MempoolSchema(&mut storage).cancel_tx(tx.hash()).await?);
+        assert!(MempoolSchema(&mut storage).contains_tx(tx.hash()).await?);
+    }
+
+    Ok(())
+}
+
+/// Checks that transactions are known once they are in the mempool or executed.
+#[db_test]
+async fn is_tx_known(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = zksync_txs();
+    for tx in &txs {
+        assert!(!MempoolSchema(&mut storage).is_tx_known(tx.hash()).await?);
+    }
+
+    MempoolSchema(&mut storage).insert_tx(&txs[0]).await?;
+    let executed_tx = NewExecutedTransaction {
+        block_number: 1,
+        tx_hash: txs[1].hash().as_ref().to_vec(),
+        tx: Default::default(),
+        operation: Default::default(),
+        from_account: Default::default(),
+        to_account: None,
+        success: true,
+        fail_reason: None,
+        block_index: None,
+        primary_account_address: Default::default(),
+        nonce: Default::default(),
+        created_at: chrono::Utc::now(),
+        eth_sign_data: None,
+        batch_id: None,
+        affected_accounts: Vec::new(),
+        used_tokens: Vec::new(),
+    };
+    OperationsSchema(&mut storage)
+        .store_executed_tx(executed_tx)
+        .await?;
+
+    assert!(
+        MempoolSchema(&mut storage)
+            .is_tx_known(txs[0].hash())
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .is_tx_known(txs[1].hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .is_tx_known(txs[2].hash())
+            .await?
+    );
+
+    Ok(())
+}
+
 /// Checks that batch is got from mempool correctly
 #[db_test]
 async fn test_get_batch_info_from_mempool(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -395,6 +1037,55 @@ async fn test_get_batch_info_from_mempool(mut storage: StorageProcessor<'_>) ->
     Ok(())
 }
 
+/// Checks that the pending priority operation keeps the time it was first observed at
+/// after being confirmed.
+#[db_test]
+async fn pending_priority_op_created_at(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let eth_address = Address::random();
+    let op = PriorityOp {
+        serial_id: 1,
+        data: ZkSyncPriorityOp::FullExit(FullExit {
+            account_id: AccountId(0),
+            eth_address,
+            token: TokenId(0),
+            is_legacy: false,
+        }),
+        deadline_block: 0,
+        eth_hash: H256::random(),
+        eth_block: 0,
+        eth_block_index: None,
+    };
+
+    let started_at = Utc::now();
+    MempoolSchema(&mut storage)
+        .insert_priority_ops(&[op.clone()], false)
+        .await?;
+    let pending = MempoolSchema(&mut storage)
+        .get_pending_operation_by_hash(op.eth_hash)
+        .await?
+        .expect("Pending operation must be stored");
+    assert_eq!(pending.op.serial_id, op.serial_id);
+    assert!(pending.created_at >= started_at - chrono::Duration::seconds(1));
+
+    // Confirmation updates the operation, but not its creation time.
+    MempoolSchema(&mut storage)
+        .insert_priority_ops(&[op.clone()], true)
+        .await?;
+    let confirmed = MempoolSchema(&mut storage)
+        .get_pending_operation_by_hash(op.eth_hash)
+        .await?
+        .expect("Pending operation must be stored");
+    assert_eq!(confirmed.created_at, pending.created_at);
+
+    let pending_ops = MempoolSchema(&mut storage)
+        .get_pending_deposits_for(eth_address, 0, 10, PaginationDirection::Newer)
+        .await?;
+    assert_eq!(pending_ops.len(), 1);
+    assert_eq!(pending_ops[0].created_at, pending.created_at);
+
+    Ok(())
+}
+
 /// Checks that returning executed txs to mempool works correctly.
 #[db_test]
 async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -525,3 +1216,83 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
     assert_eq!(block_tx.variance_name(), "FullExit");
     Ok(())
 }
+
+/// Checks that the Ethereum signature data is stored without the message if the message can be
+/// reconstructed, and that the rows in both formats are loaded and reported the same way.
+#[db_test]
+async fn eth_sign_data_formats(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // ETH is stored by the migrations.
+    let eth = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(TokenId(0)))
+        .await?
+        .expect("ETH should be stored");
+    let transfer = Transfer::new(
+        AccountId(1),
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        100u32.into(),
+        10u32.into(),
+        Nonce(1),
+        Default::default(),
+        None,
+    );
+    let tx = ZkSyncTx::Transfer(Box::new(transfer));
+    let message = tx.get_ethereum_sign_message(eth).unwrap();
+    let signed_tx = SignedZkSyncTx {
+        tx: tx.clone(),
+        eth_sign_data: Some(gen_eth_sign_data(message)),
+        created_at: Utc::now(),
+    };
+    MempoolSchema(&mut storage).insert_tx(&signed_tx).await?;
+
+    let tx_hash = hex::encode(tx.hash().as_ref());
+    let (stored,): (serde_json::Value,) =
+        sqlx::query_as("SELECT eth_sign_data FROM mempool_txs WHERE tx_hash = $1")
+            .bind(&tx_hash)
+            .fetch_one(storage.conn())
+            .await?;
+    assert!(stored.get("message").is_none());
+
+    let compact_tx = unwrap_tx(
+        MempoolSchema(&mut storage)
+            .load_txs(&[])
+            .await?
+            .remove(0)
+            .unwrap(),
+    );
+    assert_eq!(compact_tx.eth_sign_data, signed_tx.eth_sign_data);
+    let compact_tx_data = OperationsExtSchema(&mut storage)
+        .tx_data_api_v02(tx.hash().as_ref())
+        .await?
+        .expect("Transaction should be found");
+    assert!(compact_tx_data.eth_signature.is_some());
+
+    // The rows written before the compact format contain the message.
+    let legacy = serde_json::to_value(signed_tx.eth_sign_data.as_ref().unwrap())?;
+    sqlx::query("UPDATE mempool_txs SET eth_sign_data = $1 WHERE tx_hash = $2")
+        .bind(legacy)
+        .bind(&tx_hash)
+        .execute(storage.conn())
+        .await?;
+
+    let legacy_tx = unwrap_tx(
+        MempoolSchema(&mut storage)
+            .load_txs(&[])
+            .await?
+            .remove(0)
+            .unwrap(),
+    );
+    assert_eq!(legacy_tx.eth_sign_data, signed_tx.eth_sign_data);
+    let legacy_tx_data = OperationsExtSchema(&mut storage)
+        .tx_data_api_v02(tx.hash().as_ref())
+        .await?
+        .expect("Transaction should be found");
+    assert_eq!(
+        serde_json::to_string(&legacy_tx_data)?,
+        serde_json::to_string(&compact_tx_data)?
+    );
+
+    Ok(())
+}
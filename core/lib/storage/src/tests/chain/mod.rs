@@ -4,6 +4,7 @@ mod mempool;
 mod operations;
 mod operations_ext;
 mod state;
+mod stats;
 mod tree_cache;
 
 pub use block::apply_random_updates;
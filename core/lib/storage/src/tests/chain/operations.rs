@@ -1,9 +1,10 @@
 // External imports
 use chrono::{Duration, Utc};
 // Workspace imports
+use zksync_api_types::v02::transaction::Receipt;
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, Address, BlockNumber, Deposit, SequentialTxId,
-    ZkSyncPriorityOp, H256,
+    aggregated_operations::AggregatedActionType, Address, BlockNumber, Deposit, PriorityOp,
+    SequentialTxId, ZkSyncPriorityOp, H256,
 };
 // Local imports
 use crate::chain::mempool::MempoolSchema;
@@ -139,6 +140,75 @@ async fn executed_priority_operations(mut storage: StorageProcessor<'_>) -> Quer
     Ok(())
 }
 
+/// Checks that the missing zkSync hashes of the executed priority operations are restored.
+#[db_test]
+async fn priority_op_tx_hashes_restoring(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let executed_tx = NewExecutedPriorityOperation {
+        block_number: 1,
+        block_index: 1,
+        operation: Default::default(),
+        from_account: Default::default(),
+        to_account: Default::default(),
+        priority_op_serialid: 5,
+        deadline_block: 100,
+        eth_hash: H256::repeat_byte(0xDE).as_bytes().to_vec(),
+        eth_block: 10,
+        created_at: chrono::Utc::now(),
+        tx_hash: Default::default(),
+        eth_block_index: Some(3),
+        affected_accounts: Default::default(),
+        token: Default::default(),
+    };
+    let expected_hash = PriorityOp::compute_tx_hash(&executed_tx.eth_hash, 10, Some(3), 5);
+
+    // The hash is computed if the operation is stored without it.
+    OperationsSchema(&mut storage)
+        .store_executed_priority_op(executed_tx.clone())
+        .await?;
+    let stored_operation = OperationsSchema(&mut storage)
+        .get_executed_priority_operation(5)
+        .await?
+        .expect("Operation must be stored");
+    assert_eq!(stored_operation.tx_hash, expected_hash.as_ref().to_vec());
+
+    // Operations stored by the older versions of the server have the empty hash.
+    sqlx::query(
+        "UPDATE executed_priority_operations SET tx_hash = ''::bytea
+        WHERE priority_op_serialid = $1",
+    )
+    .bind(executed_tx.priority_op_serialid)
+    .execute(storage.conn())
+    .await?;
+    assert!(storage
+        .chain()
+        .operations_ext_schema()
+        .tx_receipt_api_v02(expected_hash.as_ref())
+        .await?
+        .is_none());
+
+    let restored = OperationsSchema(&mut storage)
+        .restore_priority_op_tx_hashes()
+        .await?;
+    assert_eq!(restored, 1);
+    for hash in [expected_hash.as_ref(), executed_tx.eth_hash.as_slice()] {
+        let receipt = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(hash)
+            .await?
+            .expect("Operation must be found by both hashes");
+        assert!(matches!(receipt, Receipt::L1(receipt) if receipt.id == 5));
+    }
+
+    // Nothing is left to restore.
+    let restored = OperationsSchema(&mut storage)
+        .restore_priority_op_tx_hashes()
+        .await?;
+    assert_eq!(restored, 0);
+
+    Ok(())
+}
+
 /// Checks that attempt to save the duplicate txs is ignored by the DB.
 #[db_test]
 async fn duplicated_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -3,15 +3,18 @@ use std::collections::HashMap;
 // External imports
 // Workspace imports
 use zksync_api_types::v02::{
+    account::ActivityKind,
     pagination::{AccountTxsRequest, ApiEither, PaginationDirection, PaginationQuery},
-    transaction::{Receipt, TxInBlockStatus},
+    transaction::{L2Transaction, Receipt, TransactionData, TxInBlockStatus},
 };
 use zksync_crypto::{franklin_crypto::bellman::pairing::ff::Field, Fr};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
     tx::TxHash,
-    AccountId, AccountUpdate, BlockNumber, ExecutedOperations, Nonce, ZkSyncOp, H256,
+    withdrawals::{WithdrawalPendingEvent, WithdrawalType},
+    AccountId, AccountUpdate, Address, BlockNumber, ExecutedOperations, Nonce, TokenId, ZkSyncOp,
+    H256,
 };
 // Local imports
 use self::setup::TransactionsHistoryTestSetup;
@@ -255,6 +258,11 @@ async fn get_account_transactions_history(mut storage: StorageProcessor<'_>) ->
     assert_eq!(from_history.len(), 10);
     assert_eq!(to_history.len(), 7);
 
+    // History is listed newest first, the block indices match the execution order.
+    let block_indices: Vec<_> = from_history.iter().map(|tx| tx.block_index).collect();
+    let expected: Vec<_> = (0..10).rev().map(Some).collect();
+    assert_eq!(block_indices, expected);
+
     Ok(())
 }
 
@@ -349,6 +357,151 @@ async fn get_account_transactions_history_from(
     Ok(())
 }
 
+/// Checks that iterating over the account history with `get_account_transactions_history_page`
+/// yields the same pages as `get_account_transactions_history` and that the new transactions
+/// don't shift the pages following the cursor.
+#[db_test]
+async fn get_account_transactions_history_page(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    setup.add_block(2);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let address = setup.from_zksync_account.address;
+    let limit = 3;
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    let mut offset = 0;
+    loop {
+        let expected_page = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history(&address, offset, limit)
+            .await?;
+        let (page, next_cursor) = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history_page(&address, cursor, limit)
+            .await?;
+        assert_eq!(page, expected_page, "Pages differ for offset {}", offset);
+        pages.push((cursor, page));
+
+        match next_cursor {
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => break,
+        }
+        offset += limit;
+    }
+    assert!(pages.len() > 1);
+
+    // New transactions only appear on the first page.
+    setup.add_block(3);
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(setup.blocks[2].clone())
+        .await?;
+    for (cursor, expected_page) in pages.into_iter().skip(1) {
+        let (page, _) = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history_page(&address, cursor, limit)
+            .await?;
+        assert_eq!(page, expected_page, "Pages differ for cursor {:?}", cursor);
+    }
+
+    Ok(())
+}
+
+/// Inserts the synthetic history of `count` transfers made by `address`.
+async fn insert_synthetic_history(
+    storage: &mut StorageProcessor<'_>,
+    address: Address,
+    count: i64,
+) -> QueryResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO executed_transactions (
+            block_number, block_index, tx, operation, tx_hash, from_account, success,
+            primary_account_address, nonce, created_at, sequence_number
+        )
+        SELECT
+            i / 100 + 1, i % 100, jsonb_build_object('type', 'Transfer', 'token', 0), '{}'::jsonb,
+            decode(lpad(to_hex(i), 64, '0'), 'hex'), $1, true, $1, i, now(), i
+        FROM generate_series(1, $2) AS i
+        "#,
+    )
+    .bind(address.as_bytes())
+    .bind(count)
+    .execute(storage.conn())
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO tx_filters (address, token, tx_hash, sequence_number, is_priority)
+        SELECT $1, 0, decode(lpad(to_hex(i), 64, '0'), 'hex'), i, false
+        FROM generate_series(1, $2) AS i
+        "#,
+    )
+    .bind(address.as_bytes())
+    .bind(count)
+    .execute(storage.conn())
+    .await?;
+
+    Ok(())
+}
+
+/// Loads the deep pages of the long account history with both offset and keyset pagination
+/// and checks that the pages match.
+#[db_test]
+async fn account_transactions_history_deep_pages(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    const HISTORY_SIZE: i64 = 50_000;
+    const LIMIT: u64 = 100;
+    const CHECKED_PAGES: [u64; 5] = [0, 1, 100, 250, 499];
+
+    let address = Address::random();
+    insert_synthetic_history(&mut storage, address, HISTORY_SIZE).await?;
+
+    let mut cursor = None;
+    let mut page_idx = 0;
+    loop {
+        let (page, next_cursor) = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_history_page(&address, cursor, LIMIT)
+            .await?;
+
+        if CHECKED_PAGES.contains(&page_idx) {
+            let expected_page = storage
+                .chain()
+                .operations_ext_schema()
+                .get_account_transactions_history(&address, page_idx * LIMIT, LIMIT)
+                .await?;
+            assert_eq!(page, expected_page, "Pages differ for page {}", page_idx);
+        }
+
+        // The history size is a multiple of the limit, so only the page after the last one is empty.
+        match next_cursor {
+            Some(next_cursor) => {
+                assert_eq!(page.len() as u64, LIMIT);
+                cursor = Some(next_cursor);
+            }
+            None => {
+                assert!(page.is_empty());
+                break;
+            }
+        }
+        page_idx += 1;
+    }
+    assert_eq!(page_idx * LIMIT, HISTORY_SIZE as u64);
+
+    Ok(())
+}
+
 pub struct ReceiptRequest {
     tx_hash: TxHash,
     direction: PaginationDirection,
@@ -376,6 +529,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 0)),
                 token: None,
                 second_address: None,
+                counterparty: None,
             },
             limit: 1,
             direction: PaginationDirection::Newer,
@@ -504,6 +658,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                     tx_hash: ApiEither::from(request.tx_hash),
                     token: None,
                     second_address: None,
+                    counterparty: None,
                 },
                 limit: request.limit,
                 direction: request.direction,
@@ -523,6 +678,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 tx_hash: ApiEither::from(setup.get_tx_hash(1, 2)),
                 token: None,
                 second_address: None,
+                counterparty: None,
             },
             limit: 1,
             direction: PaginationDirection::Newer,
@@ -541,6 +697,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 9)),
                 token: None,
                 second_address: None,
+                counterparty: None,
             },
             limit: 2,
             direction: PaginationDirection::Newer,
@@ -560,6 +717,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 2)),
                 token: None,
                 second_address: None,
+                counterparty: None,
             },
             limit: 1,
             direction: PaginationDirection::Newer,
@@ -575,6 +733,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 2)),
                 token: None,
                 second_address: None,
+                counterparty: None,
             },
             limit: 1,
             direction: PaginationDirection::Newer,
@@ -588,6 +747,158 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
     Ok(())
 }
 
+/// Checks that `get_account_transactions` filtered by the counterparty returns only the operations
+/// between the two accounts in both directions, including the deposit from the L1 sender.
+#[db_test]
+async fn get_account_transactions_with_counterparty(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    let from = setup.from_zksync_account.address;
+    let to = setup.to_zksync_account.address;
+    setup.add_block(1);
+    setup.add_block_with_transfer_back(2);
+
+    commit_schema_data(&mut storage, &setup).await?;
+    commit_block(&mut storage, BlockNumber(1)).await?;
+    commit_block(&mut storage, BlockNumber(2)).await?;
+
+    // Deposit, transfer to new, transfer, withdraw, mint NFT and withdraw NFT are sent to `to`,
+    // the last transfer is received from it. Close, change pubkey, swap and full exit don't have
+    // a counterparty.
+    let all_between = vec![
+        setup.get_tx_hash(1, 0),
+        setup.get_tx_hash(0, 7),
+        setup.get_tx_hash(0, 6),
+        setup.get_tx_hash(0, 5),
+        setup.get_tx_hash(0, 2),
+        setup.get_tx_hash(0, 1),
+        setup.get_tx_hash(0, 0),
+    ];
+    let test_data = vec![
+        (
+            "Operations with the recipient",
+            from,
+            to,
+            None,
+            ReceiptRequest {
+                tx_hash: setup.get_tx_hash(1, 0),
+                direction: PaginationDirection::Older,
+                limit: 10,
+            },
+            all_between.clone(),
+        ),
+        (
+            "Operations with the sender, the deposit is received from the L1 sender",
+            to,
+            from,
+            None,
+            ReceiptRequest {
+                tx_hash: setup.get_tx_hash(1, 0),
+                direction: PaginationDirection::Older,
+                limit: 10,
+            },
+            all_between,
+        ),
+        (
+            "Page of the older operations",
+            from,
+            to,
+            None,
+            ReceiptRequest {
+                tx_hash: setup.get_tx_hash(0, 6),
+                direction: PaginationDirection::Older,
+                limit: 3,
+            },
+            vec![
+                setup.get_tx_hash(0, 6),
+                setup.get_tx_hash(0, 5),
+                setup.get_tx_hash(0, 2),
+            ],
+        ),
+        (
+            "Page of the newer operations",
+            to,
+            from,
+            None,
+            ReceiptRequest {
+                tx_hash: setup.get_tx_hash(0, 3),
+                direction: PaginationDirection::Newer,
+                limit: 3,
+            },
+            vec![
+                setup.get_tx_hash(0, 5),
+                setup.get_tx_hash(0, 6),
+                setup.get_tx_hash(0, 7),
+            ],
+        ),
+        (
+            "Transfers in both directions",
+            from,
+            to,
+            Some(setup.tokens[1].id),
+            ReceiptRequest {
+                tx_hash: setup.get_tx_hash(1, 0),
+                direction: PaginationDirection::Older,
+                limit: 10,
+            },
+            vec![
+                setup.get_tx_hash(1, 0),
+                setup.get_tx_hash(0, 2),
+                setup.get_tx_hash(0, 1),
+            ],
+        ),
+        (
+            "Unrelated counterparty",
+            from,
+            Address::random(),
+            None,
+            ReceiptRequest {
+                tx_hash: setup.get_tx_hash(1, 0),
+                direction: PaginationDirection::Older,
+                limit: 10,
+            },
+            vec![],
+        ),
+    ];
+
+    for (test_name, address, counterparty, token, request, expected_resp) in test_data {
+        let items = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions(&PaginationQuery {
+                from: AccountTxsRequest {
+                    address,
+                    tx_hash: ApiEither::from(request.tx_hash),
+                    token,
+                    second_address: None,
+                    counterparty: Some(counterparty),
+                },
+                limit: request.limit,
+                direction: request.direction,
+            })
+            .await?;
+        let actual_resp: Vec<TxHash> = items.unwrap().into_iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(actual_resp, expected_resp, "\"{}\", failed", test_name);
+    }
+
+    let counts = vec![
+        (from, to, None, 7),
+        (to, from, None, 7),
+        (from, to, Some(TokenId(1)), 3),
+    ];
+    for (address, counterparty, token, expected_count) in counts {
+        let count = storage
+            .chain()
+            .operations_ext_schema()
+            .get_counterparty_transactions_count(address, counterparty, token)
+            .await?;
+        assert_eq!(count, expected_count);
+    }
+
+    Ok(())
+}
+
 /// Test `get_tx_created_at_and_block_number` method
 #[db_test]
 async fn get_tx_sequnecner_id(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -772,6 +1083,103 @@ async fn get_batch_info(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Returns the number of scans of the `table` performed in the current database transaction.
+async fn table_scans(storage: &mut StorageProcessor<'_>, table: &str) -> QueryResult<i64> {
+    let scans: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(seq_scan, 0) + COALESCE(idx_scan, 0)
+        FROM pg_stat_xact_user_tables
+        WHERE relname = $1
+        "#,
+    )
+    .bind(table)
+    .fetch_optional(storage.conn())
+    .await?;
+    Ok(scans.unwrap_or_default())
+}
+
+/// Checks that `get_batch_info` loads the batch with the same number of table scans
+/// regardless of its size, and doesn't resolve the statuses of the batch members.
+#[db_test]
+async fn get_batch_info_scans_count(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const LARGE_BATCH_SIZE: usize = 50;
+    const TABLES: [&str; 3] = [
+        "tx_batch_summaries",
+        "executed_transactions",
+        "aggregate_operations",
+    ];
+
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block_with_batch_of_size(1, 1, true);
+    setup.add_block_with_batch_of_size(2, LARGE_BATCH_SIZE as u32, true);
+
+    let mut batch_hashes = Vec::new();
+    for block in setup.blocks.iter_mut() {
+        let txs: Vec<_> = block
+            .block_transactions
+            .iter()
+            .map(|tx| tx.get_executed_tx().unwrap().signed_tx.clone())
+            .collect();
+        let batch_id = storage
+            .chain()
+            .mempool_schema()
+            .insert_batch(&txs, Vec::new())
+            .await?;
+        block.block_transactions.iter_mut().for_each(|tx| match tx {
+            ExecutedOperations::Tx(tx) => {
+                tx.batch_id = Some(batch_id);
+            }
+            _ => unreachable!(),
+        });
+        let tx_hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
+        batch_hashes.push((TxHash::batch_hash(&tx_hashes), tx_hashes));
+    }
+    commit_schema_data(&mut storage, &setup).await?;
+    for block_number in 1..=2 {
+        commit_block(&mut storage, BlockNumber(block_number)).await?;
+        verify_block(&mut storage, BlockNumber(block_number)).await?;
+    }
+
+    let mut scans = Vec::new();
+    for (batch_hash, tx_hashes) in batch_hashes {
+        let mut scans_before = Vec::new();
+        for table in TABLES.iter() {
+            scans_before.push(table_scans(&mut storage, table).await?);
+        }
+
+        let batch_info = storage
+            .chain()
+            .operations_ext_schema()
+            .get_batch_info(batch_hash)
+            .await?
+            .unwrap();
+        let actual_tx_hashes: Vec<TxHash> = batch_info
+            .transaction_hashes
+            .into_iter()
+            .map(|tx_hash| tx_hash.0)
+            .collect();
+        assert_eq!(actual_tx_hashes, tx_hashes);
+        assert_eq!(
+            batch_info.batch_status.last_state,
+            TxInBlockStatus::Finalized
+        );
+
+        let mut batch_scans = Vec::new();
+        for (table, before) in TABLES.iter().zip(scans_before) {
+            batch_scans.push(table_scans(&mut storage, table).await? - before);
+        }
+        scans.push(batch_scans);
+    }
+
+    // The large batch is loaded exactly like the single transaction one: the members are
+    // read by a single query and the aggregated operations are not queried at all.
+    assert_eq!(scans[0], scans[1]);
+    assert!(scans[1][1] <= 1);
+    assert_eq!(scans[1][2], 0);
+
+    Ok(())
+}
+
 /// Test `get_account_transactions_count` method
 #[db_test]
 async fn account_transactions_count(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -815,6 +1223,83 @@ async fn account_last_tx_hash(mut storage: StorageProcessor<'_>) -> QueryResult<
     Ok(())
 }
 
+/// Checks that the account activity merges priority operations and transactions, and that
+/// paging through it with the sequence number cursor is not affected by the new entries.
+#[db_test]
+async fn account_activity(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    let address = setup.from_zksync_account.address;
+    setup.add_block(1);
+    setup.add_block_with_rejected_op(2);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let activity = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_activity(address, None, 100)
+        .await?;
+    let expected: Vec<_> = activity
+        .iter()
+        .map(|entry| (entry.sequence_number, entry.tx.tx_hash))
+        .collect();
+    assert!(activity
+        .windows(2)
+        .all(|pair| pair[0].sequence_number > pair[1].sequence_number));
+    for entry in &activity {
+        let is_l1 = matches!(entry.tx.op, TransactionData::L1(_));
+        assert_eq!(entry.kind == ActivityKind::L1, is_l1);
+    }
+    assert!(activity.iter().any(|entry| entry.kind == ActivityKind::L1));
+    assert!(activity.iter().any(|entry| entry.kind == ActivityKind::L2));
+    // The rejected transaction is reported as well.
+    assert!(activity
+        .iter()
+        .any(|entry| entry.tx.status == TxInBlockStatus::Rejected));
+
+    for limit in 1..=4 {
+        let mut pages = Vec::new();
+        let mut before = None;
+        loop {
+            let page = storage
+                .chain()
+                .operations_ext_schema()
+                .get_account_activity(address, before, limit)
+                .await?;
+            if page.is_empty() {
+                break;
+            }
+            assert!(page.len() <= limit as usize);
+            before = page.last().map(|entry| entry.sequence_number);
+            pages.extend(
+                page.into_iter()
+                    .map(|entry| (entry.sequence_number, entry.tx.tx_hash)),
+            );
+
+            // New entries appear while paging, but don't shift the pages.
+            if limit == 2 && pages.len() == 2 {
+                setup.add_block(3);
+                storage
+                    .chain()
+                    .block_schema()
+                    .save_full_block(setup.blocks[2].clone())
+                    .await?;
+            }
+        }
+        assert_eq!(pages, expected, "Pages differ for limit {}", limit);
+    }
+
+    // The new entries are returned on the first page.
+    let activity = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_activity(address, None, 1)
+        .await?;
+    assert_eq!(activity[0].tx.block_number, Some(BlockNumber(3)));
+    assert!(activity[0].sequence_number > expected[0].0);
+
+    Ok(())
+}
+
 /// Test `get_block_last_tx_hash` method
 #[db_test]
 async fn block_last_tx_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -872,6 +1357,7 @@ async fn tx_receipt(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     match l1_receipt_by_tx_hash.unwrap() {
         Receipt::L1(receipt) => {
             assert_eq!(receipt.id, expected_id);
+            assert!(receipt.created_at.is_some());
         }
         Receipt::L2(_) => {
             panic!("Should be L1 receipt");
@@ -1004,6 +1490,116 @@ async fn tx_data(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the lost final hashes of the execute operations are restored from the withdrawal
+/// events, so the withdrawals are linked with the Ethereum transaction again.
+#[db_test]
+async fn withdrawal_eth_tx_repair(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    commit_schema_data(&mut storage, &setup).await?;
+    commit_block(&mut storage, BlockNumber(1)).await?;
+    verify_block(&mut storage, BlockNumber(1)).await?;
+    let withdrawal_hash = setup.get_tx_hash(0, 5);
+
+    async fn withdrawal_eth_tx(
+        storage: &mut StorageProcessor<'_>,
+        withdrawal_hash: TxHash,
+    ) -> QueryResult<Option<H256>> {
+        let data = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_data_api_v02(withdrawal_hash.as_ref())
+            .await?
+            .unwrap();
+        match data.tx.op {
+            TransactionData::L2(L2Transaction::Withdraw(withdraw)) => Ok(withdraw.eth_tx_hash),
+            _ => panic!("Should be a withdrawal"),
+        }
+    }
+
+    let (execute_op_id, _) = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::ExecuteBlocks, BlockNumber(1))
+        .await?
+        .unwrap();
+    let eth_tx_hash = dummy_ethereum_tx_hash(execute_op_id);
+    assert_eq!(
+        withdrawal_eth_tx(&mut storage, withdrawal_hash).await?,
+        Some(eth_tx_hash)
+    );
+
+    // Break the link.
+    sqlx::query("UPDATE eth_operations SET final_hash = NULL WHERE final_hash = $1")
+        .bind(eth_tx_hash.as_bytes())
+        .execute(storage.conn())
+        .await?;
+    assert_eq!(
+        withdrawal_eth_tx(&mut storage, withdrawal_hash).await?,
+        None
+    );
+
+    // Without the withdrawal events the transaction can't be found, blocks out of the range
+    // are not considered.
+    let unlinked = storage
+        .ethereum_schema()
+        .restore_withdrawal_eth_txs(BlockNumber(2), BlockNumber(5), false)
+        .await?;
+    assert!(unlinked.is_empty());
+    let unlinked = storage
+        .ethereum_schema()
+        .restore_withdrawal_eth_txs(BlockNumber(1), BlockNumber(1), false)
+        .await?;
+    assert_eq!(unlinked.len(), 1);
+    assert_eq!(unlinked[0].aggregated_op_id, execute_op_id);
+    assert_eq!(unlinked[0].withdrawal_tx_hash, None);
+
+    storage
+        .withdrawals_schema()
+        .save_pending_withdrawals(&[WithdrawalPendingEvent {
+            block_number: 10,
+            tx_hash: eth_tx_hash,
+            token_id: TokenId(0),
+            recipient: setup.from_zksync_account.address,
+            amount: 1u32.into(),
+            withdrawal_type: WithdrawalType::Withdrawal,
+            log_index: 0,
+        }])
+        .await?;
+
+    // The dry run doesn't change anything.
+    let unlinked = storage
+        .ethereum_schema()
+        .restore_withdrawal_eth_txs(BlockNumber(1), BlockNumber(1), true)
+        .await?;
+    assert_eq!(unlinked.len(), 1);
+    assert_eq!(
+        unlinked[0].withdrawal_tx_hash,
+        Some(eth_tx_hash.as_bytes().to_vec())
+    );
+    assert_eq!(
+        withdrawal_eth_tx(&mut storage, withdrawal_hash).await?,
+        None
+    );
+
+    let unlinked = storage
+        .ethereum_schema()
+        .restore_withdrawal_eth_txs(BlockNumber(1), BlockNumber(1), false)
+        .await?;
+    assert_eq!(unlinked.len(), 1);
+    assert_eq!(
+        withdrawal_eth_tx(&mut storage, withdrawal_hash).await?,
+        Some(eth_tx_hash)
+    );
+
+    // The restored operations are not repaired again.
+    let unlinked = storage
+        .ethereum_schema()
+        .restore_withdrawal_eth_txs(BlockNumber(1), BlockNumber(1), false)
+        .await?;
+    assert!(unlinked.is_empty());
+
+    Ok(())
+}
+
 /// Test `tx_data_for_web3` method
 #[db_test]
 async fn tx_data_for_web3(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -1215,6 +1811,7 @@ async fn test_getting_swap_for_acc(mut storage: StorageProcessor<'_>) -> QueryRe
                     tx_hash: ApiEither::from(tx_hash),
                     token: None,
                     second_address: None,
+                    counterparty: None,
                 },
                 limit: 1,
                 direction: PaginationDirection::Older,
@@ -156,16 +156,13 @@ impl TransactionsHistoryTestSetup {
     }
 
     pub fn add_block_with_batch(&mut self, block_id: u32, success: bool) {
-        let block_indexes = if success {
-            vec![Some(0), Some(1), Some(2)]
-        } else {
-            vec![None, None, None]
-        };
-        let transfer_op_0 = self.create_transfer_tx(block_indexes[0]);
-        let transfer_op_1 = self.create_transfer_tx(block_indexes[1]);
-        let transfer_op_2 = self.create_transfer_tx(block_indexes[2]);
+        self.add_block_with_batch_of_size(block_id, 3, success);
+    }
 
-        let operations = vec![transfer_op_0, transfer_op_1, transfer_op_2];
+    pub fn add_block_with_batch_of_size(&mut self, block_id: u32, size: u32, success: bool) {
+        let operations = (0..size)
+            .map(|block_index| self.create_transfer_tx(Some(block_index).filter(|_| success)))
+            .collect();
 
         let block = Block::new(
             BlockNumber(block_id),
@@ -183,6 +180,51 @@ impl TransactionsHistoryTestSetup {
         self.blocks.push(block);
     }
 
+    /// Adds a block with a single transfer back from the recipient of the other operations to their sender.
+    pub fn add_block_with_transfer_back(&mut self, block_id: u32) {
+        let transfer_op = ZkSyncOp::Transfer(Box::new(TransferOp {
+            tx: self
+                .to_zksync_account
+                .sign_transfer(
+                    self.tokens[1].id,
+                    &self.tokens[1].symbol,
+                    self.amount.clone(),
+                    0u32.into(),
+                    &self.from_zksync_account.address,
+                    None,
+                    true,
+                    Default::default(),
+                )
+                .0,
+            from: self.to_zksync_account.get_account_id().unwrap(),
+            to: self.from_zksync_account.get_account_id().unwrap(),
+        }));
+        let executed_transfer_op = ExecutedTx {
+            signed_tx: transfer_op.try_get_tx().unwrap().into(),
+            success: true,
+            op: Some(transfer_op),
+            fail_reason: None,
+            block_index: Some(0),
+            created_at: self.get_tx_time(),
+            batch_id: None,
+        };
+
+        let block = Block::new(
+            BlockNumber(block_id),
+            Fr::zero(),
+            AccountId(0),
+            vec![ExecutedOperations::Tx(Box::new(executed_transfer_op))],
+            (0, 0), // Not important
+            100,
+            1_000_000.into(), // Not important
+            1_500_000.into(), // Not important
+            Default::default(),
+            0,
+        );
+
+        self.blocks.push(block);
+    }
+
     fn create_deposit_op(
         &mut self,
         serial_id: u64,
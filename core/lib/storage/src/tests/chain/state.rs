@@ -1,9 +1,11 @@
 // External imports
+use num::BigUint;
 // Workspace imports
+use zksync_crypto::rand::{Rng, XorShiftRng};
 use zksync_types::aggregated_operations::AggregatedActionType;
 use zksync_types::{
-    helpers::apply_updates, AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Nonce,
-    TokenId, H256, NFT,
+    helpers::apply_updates, AccountId, AccountMap, AccountUpdate, AccountUpdates, Address,
+    BlockNumber, Nonce, PubKeyHash, TokenId, H256, NFT,
 };
 // Local imports
 use super::block::apply_random_updates;
@@ -328,3 +330,148 @@ async fn test_get_mint_nft_update(mut storage: StorageProcessor<'_>) -> QueryRes
 
     Ok(())
 }
+
+/// Generates the history of blocks updating the accounts of a small set, so that every account
+/// is updated many times. Returns the updates of every block.
+fn gen_accounts_history(
+    rng: &mut XorShiftRng,
+    accounts_count: u32,
+    blocks: u32,
+) -> Vec<AccountUpdates> {
+    let mut accounts = AccountMap::default();
+    let mut history = Vec::new();
+    for _ in 0..blocks {
+        let mut updates = AccountUpdates::new();
+        for _ in 0..rng.gen_range(1, 4) {
+            let account_id = AccountId(rng.gen_range(0, accounts_count));
+            let update = match accounts.get(&account_id) {
+                None => AccountUpdate::Create {
+                    address: Address::from(rng.gen::<[u8; 20]>()),
+                    nonce: Nonce(0),
+                },
+                Some(account) if rng.gen_range(0, 5) == 0 => AccountUpdate::ChangePubKeyHash {
+                    old_pub_key_hash: account.pub_key_hash,
+                    new_pub_key_hash: PubKeyHash { data: rng.gen() },
+                    old_nonce: account.nonce,
+                    new_nonce: account.nonce + 1,
+                },
+                Some(account) => {
+                    let token = TokenId(rng.gen_range(0, 3));
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: account.nonce,
+                        new_nonce: account.nonce + 1,
+                        balance_update: (
+                            token,
+                            account.get_balance(token),
+                            BigUint::from(rng.gen::<u64>()),
+                        ),
+                    }
+                }
+            };
+            apply_updates(&mut accounts, vec![(account_id, update.clone())]);
+            updates.push((account_id, update));
+        }
+        history.push(updates);
+    }
+    history
+}
+
+/// Checks that the historical state of the accounts restored from the snapshots matches
+/// the state obtained by replaying all the updates since the genesis.
+#[db_test]
+async fn account_snapshots(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const ACCOUNTS: u32 = 8;
+    const BLOCKS: u32 = 100;
+    const SNAPSHOT_BLOCKS: u32 = 7;
+
+    async fn check_random_blocks(
+        storage: &mut StorageProcessor<'_>,
+        rng: &mut XorShiftRng,
+    ) -> QueryResult<()> {
+        // The last account is never created.
+        let account_ids: Vec<_> = (0..=ACCOUNTS).map(AccountId).collect();
+        for _ in 0..30 {
+            let block = BlockNumber(rng.gen_range(0, BLOCKS));
+            let mut replayed_state = AccountMap::default();
+            if let Some((_, updates)) = StateSchema(storage)
+                .load_state_diff(BlockNumber(0), Some(block))
+                .await?
+            {
+                apply_updates(&mut replayed_state, updates);
+            }
+
+            let state = StateSchema(storage)
+                .load_accounts_state(block, &account_ids)
+                .await?;
+            assert_eq!(state, replayed_state, "State at block {} differs", *block);
+        }
+        Ok(())
+    }
+
+    let mut rng = create_rng();
+    let history = gen_accounts_history(&mut rng, ACCOUNTS, BLOCKS);
+    for (block_number, updates) in (1..=BLOCKS).zip(history) {
+        let block_number = BlockNumber(block_number);
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                AggregatedActionType::CommitBlocks,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+        StateSchema(&mut storage)
+            .commit_state_update(block_number, &updates, 0)
+            .await?;
+        StateSchema(&mut storage)
+            .apply_state_update(block_number)
+            .await?;
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                AggregatedActionType::ExecuteBlocks,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+    }
+    OperationsSchema(&mut storage)
+        .confirm_aggregated_operations(
+            BlockNumber(1),
+            BlockNumber(BLOCKS),
+            AggregatedActionType::ExecuteBlocks,
+        )
+        .await?;
+
+    // Without the snapshots, the updates of the accounts are replayed since the genesis.
+    check_random_blocks(&mut storage, &mut rng).await?;
+
+    for block in (SNAPSHOT_BLOCKS..BLOCKS).step_by(SNAPSHOT_BLOCKS as usize) {
+        StateSchema(&mut storage)
+            .take_account_snapshot(BlockNumber(block))
+            .await?;
+    }
+    assert_eq!(
+        StateSchema(&mut storage)
+            .last_account_snapshot_block()
+            .await?,
+        BlockNumber(98)
+    );
+    check_random_blocks(&mut storage, &mut rng).await?;
+
+    // Snapshots are taken again after the removed ones.
+    StateSchema(&mut storage)
+        .remove_account_snapshots(BlockNumber(50))
+        .await?;
+    assert_eq!(
+        StateSchema(&mut storage)
+            .last_account_snapshot_block()
+            .await?,
+        BlockNumber(49)
+    );
+    check_random_blocks(&mut storage, &mut rng).await?;
+    StateSchema(&mut storage)
+        .take_account_snapshot(BlockNumber(60))
+        .await?;
+    check_random_blocks(&mut storage, &mut rng).await?;
+
+    Ok(())
+}
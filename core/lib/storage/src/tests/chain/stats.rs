@@ -0,0 +1,225 @@
+// External imports
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use num::{rational::Ratio, BigUint};
+use serde_json::json;
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_types::{AccountId, BlockNumber, TokenId, TokenPrice, H256};
+// Local imports
+use crate::{
+    chain::{
+        operations::{records::NewExecutedTransaction, OperationsSchema},
+        stats::{StatsSchema, TokenActivityDelta},
+    },
+    tests::db_test,
+    QueryResult, StorageProcessor,
+};
+
+async fn store_tx(
+    storage: &mut StorageProcessor<'_>,
+    tx: serde_json::Value,
+    success: bool,
+    created_at: DateTime<Utc>,
+) -> QueryResult<()> {
+    let tx_hash = H256::random().as_bytes().to_vec();
+    OperationsSchema(storage)
+        .store_executed_tx(NewExecutedTransaction {
+            block_number: 1,
+            tx_hash,
+            tx,
+            operation: Default::default(),
+            from_account: Default::default(),
+            to_account: None,
+            success,
+            fail_reason: None,
+            block_index: None,
+            primary_account_address: Default::default(),
+            nonce: Default::default(),
+            created_at,
+            eth_sign_data: None,
+            batch_id: None,
+            affected_accounts: Vec::new(),
+            used_tokens: Vec::new(),
+        })
+        .await
+}
+
+/// Checks that the fee statistics are aggregated per bucket and only for the requested
+/// type and token of the successful transactions.
+#[db_test]
+async fn fee_statistics(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let day = |day: u32, hour: u32| Utc.ymd(2023, 3, day).and_hms(hour, 0, 0);
+    let transfer = |fee: &str| json!({ "type": "Transfer", "token": 0, "fee": fee });
+
+    for (fee, created_at) in [("10", day(1, 1)), ("30", day(1, 5)), ("20", day(1, 23))] {
+        store_tx(&mut storage, transfer(fee), true, created_at).await?;
+    }
+    store_tx(&mut storage, transfer("100"), true, day(3, 12)).await?;
+    // Failed transactions, other types and other tokens are ignored.
+    store_tx(&mut storage, transfer("1000"), false, day(1, 2)).await?;
+    store_tx(
+        &mut storage,
+        json!({ "type": "Withdraw", "token": 0, "fee": "1000" }),
+        true,
+        day(1, 3),
+    )
+    .await?;
+    store_tx(
+        &mut storage,
+        json!({ "type": "Transfer", "token": 1, "fee": "1000" }),
+        true,
+        day(1, 4),
+    )
+    .await?;
+    // Transactions outside of the requested range are ignored as well.
+    store_tx(&mut storage, transfer("1000"), true, day(4, 0)).await?;
+
+    let buckets = StatsSchema(&mut storage)
+        .fee_statistics("Transfer", TokenId(0), "day", day(1, 0), day(4, 0))
+        .await?;
+    let fee = |value: u32| Some(BigDecimal::from(value));
+
+    assert_eq!(buckets.len(), 3);
+    assert_eq!(buckets[0].bucket, day(1, 0));
+    assert_eq!(buckets[0].count, 3);
+    assert_eq!(buckets[0].average, fee(20));
+    assert_eq!(buckets[0].median, fee(20));
+    assert_eq!(buckets[0].percentile_90, fee(30));
+
+    // Buckets without transactions have no statistics.
+    assert_eq!(buckets[1].bucket, day(2, 0));
+    assert_eq!(buckets[1].count, 0);
+    assert_eq!(buckets[1].average, None);
+    assert_eq!(buckets[1].median, None);
+    assert_eq!(buckets[1].percentile_90, None);
+
+    assert_eq!(buckets[2].bucket, day(3, 0));
+    assert_eq!(buckets[2].count, 1);
+    assert_eq!(buckets[2].average, fee(100));
+    assert_eq!(buckets[2].median, fee(100));
+
+    Ok(())
+}
+
+/// Checks that the activity of the blocks is added up per day, and the days affected
+/// by the reverted blocks are removed entirely.
+#[db_test]
+async fn token_activity(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let day = |day: u32| NaiveDate::from_ymd(2023, 3, day);
+    let delta = |token_id: u32, transfers: i64, volume: u32, senders: &[u32], withdrawn: u32| {
+        TokenActivityDelta {
+            token_id: TokenId(token_id),
+            transfers,
+            transfer_volume: BigDecimal::from(volume),
+            senders: senders.iter().copied().map(AccountId).collect(),
+            withdrawals: (withdrawn > 0) as i64,
+            withdrawal_volume: BigDecimal::from(withdrawn),
+        }
+    };
+    // Only the price stored during the day is the price of the day.
+    storage
+        .tokens_schema()
+        .update_historical_ticker_price(
+            TokenId(0),
+            TokenPrice {
+                usd_price: Ratio::from_integer(BigUint::from(2u32)),
+                last_updated: Utc.ymd(2023, 3, 1).and_hms(12, 0, 0),
+            },
+        )
+        .await?;
+
+    let blocks = [
+        (1, day(1), vec![delta(0, 2, 30, &[1, 2], 5)]),
+        (
+            2,
+            day(1),
+            vec![delta(0, 1, 10, &[1], 0), delta(1, 1, 100, &[3], 0)],
+        ),
+        (3, day(2), vec![delta(0, 1, 7, &[3], 0)]),
+        (4, day(2), vec![]),
+    ];
+    for (block_number, day, deltas) in &blocks {
+        StatsSchema(&mut storage)
+            .store_token_activity(BlockNumber(*block_number), *day, deltas)
+            .await?;
+    }
+    assert_eq!(
+        StatsSchema(&mut storage)
+            .last_token_activity_block()
+            .await?,
+        BlockNumber(4)
+    );
+
+    let buckets = StatsSchema(&mut storage)
+        .token_activity(TokenId(0), day(1), day(3))
+        .await?;
+    assert_eq!(buckets.len(), 3);
+    assert_eq!(buckets[0].day, day(1));
+    assert_eq!(buckets[0].transfers, 3);
+    assert_eq!(buckets[0].transfer_volume, BigDecimal::from(40));
+    assert_eq!(buckets[0].unique_senders, 2);
+    assert_eq!(buckets[0].withdrawals, 1);
+    assert_eq!(buckets[0].withdrawal_volume, BigDecimal::from(5));
+    assert_eq!(buckets[0].usd_price, Some(BigDecimal::from(2)));
+
+    assert_eq!(buckets[1].day, day(2));
+    assert_eq!(buckets[1].transfers, 1);
+    assert_eq!(buckets[1].transfer_volume, BigDecimal::from(7));
+    assert_eq!(buckets[1].unique_senders, 1);
+    assert_eq!(buckets[1].withdrawals, 0);
+    assert_eq!(buckets[1].usd_price, None);
+
+    // Days without activity are reported with zero counters.
+    assert_eq!(buckets[2].day, day(3));
+    assert_eq!(buckets[2].transfers, 0);
+    assert_eq!(buckets[2].transfer_volume, BigDecimal::from(0));
+    assert_eq!(buckets[2].unique_senders, 0);
+
+    // Other tokens are counted separately.
+    let buckets = StatsSchema(&mut storage)
+        .token_activity(TokenId(1), day(1), day(1))
+        .await?;
+    assert_eq!(buckets[0].transfers, 1);
+    assert_eq!(buckets[0].unique_senders, 1);
+    assert_eq!(buckets[0].usd_price, None);
+
+    // The second day is affected by the revert entirely.
+    StatsSchema(&mut storage)
+        .revert_token_activity(BlockNumber(3))
+        .await?;
+    assert_eq!(
+        StatsSchema(&mut storage)
+            .last_token_activity_block()
+            .await?,
+        BlockNumber(2)
+    );
+    let buckets = StatsSchema(&mut storage)
+        .token_activity(TokenId(0), day(1), day(2))
+        .await?;
+    assert_eq!(buckets[0].transfers, 3);
+    assert_eq!(buckets[1].transfers, 0);
+
+    // The first day is removed along with the block preceding the reverted one,
+    // and recalculated once the block is rolled up again.
+    StatsSchema(&mut storage)
+        .revert_token_activity(BlockNumber(1))
+        .await?;
+    assert_eq!(
+        StatsSchema(&mut storage)
+            .last_token_activity_block()
+            .await?,
+        BlockNumber(0)
+    );
+    let (block_number, day_1, deltas) = &blocks[0];
+    StatsSchema(&mut storage)
+        .store_token_activity(BlockNumber(*block_number), *day_1, deltas)
+        .await?;
+    let buckets = StatsSchema(&mut storage)
+        .token_activity(TokenId(0), day(1), day(1))
+        .await?;
+    assert_eq!(buckets[0].transfers, 2);
+    assert_eq!(buckets[0].transfer_volume, BigDecimal::from(30));
+    assert_eq!(buckets[0].unique_senders, 2);
+
+    Ok(())
+}
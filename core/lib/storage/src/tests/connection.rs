@@ -0,0 +1,112 @@
+//! Tests for the connection utilities.
+//!
+//! Unlike the other tests, these ones require actual pools rather than a single
+//! test transaction, so both the primary and the replica pools point to the test database.
+
+// Built-in imports
+use std::time::Duration;
+// Local imports
+use crate::{get_database_url, AccessIntent, ConnectionPool, QueryResult, ReplicatedPool};
+
+/// Address without a running database, used to emulate an unavailable replica.
+const UNAVAILABLE_DATABASE_URL: &str = "postgres://postgres@127.0.0.1:1/plasma";
+
+fn test_pool() -> ConnectionPool {
+    ConnectionPool::new_with_url(get_database_url(), Some(1))
+}
+
+/// Checks that read-only queries are served by the replica once it's checked.
+#[tokio::test]
+#[cfg_attr(not(feature = "db_test"), ignore)]
+async fn replicated_pool_routing() -> QueryResult<()> {
+    let pool = ReplicatedPool::new(test_pool(), vec![test_pool()], Duration::from_secs(1));
+    // Replicas are not used until the first health check.
+    assert_eq!(pool.healthy_replicas(), 0);
+
+    pool.check_replicas().await;
+    assert_eq!(pool.healthy_replicas(), 1);
+
+    for intent in [AccessIntent::Read, AccessIntent::Write] {
+        let mut storage = pool.access_storage(intent).await?;
+        storage.misc_schema().load_disabled_tx_types().await?;
+    }
+
+    Ok(())
+}
+
+/// Checks that all the queries fall back to the primary database if the replicas are unavailable.
+#[tokio::test]
+#[cfg_attr(not(feature = "db_test"), ignore)]
+async fn replicated_pool_failover() -> QueryResult<()> {
+    let unavailable_replica = ConnectionPool::new_with_url(UNAVAILABLE_DATABASE_URL, Some(1));
+    let pool = ReplicatedPool::new(
+        test_pool(),
+        vec![unavailable_replica, test_pool()],
+        Duration::from_secs(1),
+    );
+
+    pool.check_replicas().await;
+    assert_eq!(pool.healthy_replicas(), 1);
+
+    let mut storage = pool.access_storage(AccessIntent::Read).await?;
+    storage.misc_schema().load_disabled_tx_types().await?;
+    drop(storage);
+
+    let pool = ReplicatedPool::primary_only(test_pool());
+    pool.check_replicas().await;
+    assert_eq!(pool.healthy_replicas(), 0);
+    let mut storage = pool.access_storage(AccessIntent::Read).await?;
+    storage.misc_schema().load_disabled_tx_types().await?;
+
+    Ok(())
+}
+
+/// Checks that the queries made within a snapshot read don't observe the changes committed
+/// concurrently, even if the reader is slow, and that the snapshot read can't modify the data.
+#[tokio::test]
+#[cfg_attr(not(feature = "db_test"), ignore)]
+async fn snapshot_read_consistency() -> QueryResult<()> {
+    const TX_TYPE: &str = "SnapshotReadTest";
+    let pool = ConnectionPool::new_with_url(get_database_url(), Some(2));
+
+    let mut storage = pool.access_storage().await?;
+    let mut snapshot = storage.start_snapshot_read().await?;
+    let disabled_before = snapshot.misc_schema().load_disabled_tx_types().await?;
+    assert!(!disabled_before.contains(&TX_TYPE.to_string()));
+
+    // The data is changed while the reader is processing the first query result.
+    let writer = tokio::spawn({
+        let pool = pool.clone();
+        async move {
+            pool.access_storage()
+                .await?
+                .misc_schema()
+                .set_tx_type_disabled(TX_TYPE, true)
+                .await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    writer.await.expect("Writer task panicked")?;
+
+    let disabled_after = snapshot.misc_schema().load_disabled_tx_types().await?;
+    assert_eq!(disabled_after, disabled_before);
+    snapshot.commit().await?;
+
+    // Queries outside of the snapshot observe the change.
+    let disabled = storage.misc_schema().load_disabled_tx_types().await?;
+    assert!(disabled.contains(&TX_TYPE.to_string()));
+
+    let mut snapshot = storage.start_snapshot_read().await?;
+    assert!(snapshot
+        .misc_schema()
+        .set_tx_type_disabled(TX_TYPE, false)
+        .await
+        .is_err());
+    drop(snapshot);
+
+    storage
+        .misc_schema()
+        .set_tx_type_disabled(TX_TYPE, false)
+        .await?;
+    Ok(())
+}
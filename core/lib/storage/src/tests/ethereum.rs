@@ -1,6 +1,7 @@
 // Built-in deps
 use std::str::FromStr;
 // External imports
+use chrono::{Duration, Utc};
 // Workspace imports
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
@@ -8,10 +9,11 @@ use zksync_types::{
     BlockNumber, H256, U256,
 };
 // Local imports
-use crate::test_data::{gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS};
+use crate::test_data::{gen_sample_block, gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS};
 use crate::tests::db_test;
 use crate::{
-    chain::operations::OperationsSchema, ethereum::EthereumSchema, QueryResult, StorageProcessor,
+    chain::operations::OperationsSchema, ethereum::records::ETHStats, ethereum::EthereumSchema,
+    QueryResult, StorageProcessor,
 };
 use num::BigUint;
 
@@ -196,6 +198,226 @@ async fn ethereum_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that aggregated operations are resolved by the hashes of the Ethereum transactions
+/// sent for them, including the transactions replaced by the ones with a higher gas price.
+#[db_test]
+async fn ethereum_l1_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+    let block_number = BlockNumber(1);
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(gen_sample_block(
+            block_number,
+            BLOCK_SIZE_CHUNKS,
+            Vec::new(),
+        ))
+        .await
+        .unwrap();
+
+    let mut sent_operations = Vec::new();
+    for action_type in [
+        AggregatedActionType::CommitBlocks,
+        AggregatedActionType::PublishProofBlocksOnchain,
+        AggregatedActionType::ExecuteBlocks,
+    ] {
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                action_type,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+        let op = OperationsSchema(&mut storage)
+            .get_aggregated_op_that_affects_block(action_type, block_number)
+            .await?;
+
+        let params = EthereumTxParams::new(action_type.to_string(), op);
+        let response = EthereumSchema(&mut storage)
+            .save_new_eth_tx(
+                action_type,
+                params.op.clone(),
+                params.deadline_block as i64,
+                params.gas_price.clone(),
+                params.raw_tx.clone(),
+            )
+            .await?;
+        EthereumSchema(&mut storage)
+            .add_hash_entry(response.id, &params.hash)
+            .await?;
+        sent_operations.push((action_type, params, response.id));
+    }
+
+    // Replace the commit transaction and confirm the replacement.
+    let (_, commit_params, commit_eth_op_id) = &sent_operations[0];
+    let replacement_hash = H256::repeat_byte(0xaa);
+    EthereumSchema(&mut storage)
+        .add_hash_entry(*commit_eth_op_id, &replacement_hash)
+        .await?;
+    EthereumSchema(&mut storage)
+        .confirm_eth_tx(&replacement_hash)
+        .await?;
+    // The Ethereum block of the commitment is unknown until it is recorded.
+    let commit_l1_block = EthereumSchema(&mut storage)
+        .load_commit_l1_block(block_number)
+        .await?;
+    assert_eq!(commit_l1_block, None);
+    EthereumSchema(&mut storage)
+        .save_gas_used(&replacement_hash, 150_000u64.into())
+        .await?;
+    EthereumSchema(&mut storage)
+        .save_l1_block(&replacement_hash, 1_000)
+        .await?;
+
+    for hash in [commit_params.hash, replacement_hash] {
+        let operation = EthereumSchema(&mut storage)
+            .load_l1_operation(&hash)
+            .await?
+            .expect("Commit operation should be found");
+        let (op_id, _) = commit_params.op.as_ref().unwrap();
+        assert_eq!(operation.aggregated_op_id, *op_id);
+        assert_eq!(operation.eth_op_id, *commit_eth_op_id);
+        assert_eq!(operation.action_type, "CommitBlocks");
+        assert!(operation.confirmed);
+        assert_eq!(
+            operation.final_hash,
+            Some(replacement_hash.as_bytes().to_vec())
+        );
+        assert_eq!(operation.gas_used, Some(150_000));
+        assert_eq!((operation.from_block, operation.to_block), (1, 1));
+    }
+
+    // Other operations are not confirmed yet.
+    for (action_type, params, eth_op_id) in &sent_operations[1..] {
+        let operation = EthereumSchema(&mut storage)
+            .load_l1_operation(&params.hash)
+            .await?
+            .expect("Operation should be found");
+        assert_eq!(operation.eth_op_id, *eth_op_id);
+        assert_eq!(operation.action_type, action_type.to_string());
+        assert!(!operation.confirmed);
+        assert_eq!(operation.final_hash, None);
+        assert_eq!(operation.gas_used, None);
+    }
+
+    // Unknown hash.
+    let operation = EthereumSchema(&mut storage)
+        .load_l1_operation(&H256::repeat_byte(0xbb))
+        .await?;
+    assert_eq!(operation, None);
+
+    // Only the confirmed commit operation processed the block so far.
+    let block_operations = EthereumSchema(&mut storage)
+        .load_block_l1_operations(block_number)
+        .await?;
+    assert_eq!(block_operations.len(), 1);
+    assert_eq!(block_operations[0].action_type, "CommitBlocks");
+    assert_eq!(
+        (block_operations[0].from_block, block_operations[0].to_block),
+        (1, 1)
+    );
+    assert_eq!(block_operations[0].gas_used, Some(150_000));
+    let block_operations = EthereumSchema(&mut storage)
+        .load_block_l1_operations(BlockNumber(2))
+        .await?;
+    assert!(block_operations.is_empty());
+
+    let commit_l1_block = EthereumSchema(&mut storage)
+        .load_commit_l1_block(block_number)
+        .await?;
+    assert_eq!(commit_l1_block, Some(1_000));
+    let commit_l1_block = EthereumSchema(&mut storage)
+        .load_commit_l1_block(BlockNumber(2))
+        .await?;
+    assert_eq!(commit_l1_block, None);
+
+    let summaries = storage
+        .chain()
+        .block_schema()
+        .load_block_summaries(block_number, BlockNumber(2))
+        .await?;
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].block_number, 1);
+    assert_eq!(summaries[0].block_size, BLOCK_SIZE_CHUNKS as i64);
+    assert_eq!(summaries[0].transactions_count, 0);
+    assert_eq!(summaries[0].priority_operations_count, 0);
+
+    Ok(())
+}
+
+/// Checks that the Ethereum operation is loaded by the ID of the aggregated operation along with
+/// the sent transactions, and that the resend requests are deduplicated until they're taken.
+#[db_test]
+async fn ethereum_tx_resend_requests(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+    let block_number = BlockNumber(1);
+    OperationsSchema(&mut storage)
+        .store_aggregated_action(gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        ))
+        .await?;
+    let op = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?;
+    let (op_id, _) = op.clone().unwrap();
+
+    // The operation is not sent yet.
+    let eth_op = EthereumSchema(&mut storage)
+        .load_eth_operation_by_aggregated_op(op_id)
+        .await?;
+    assert_eq!(eth_op, None);
+
+    let params = EthereumTxParams::new("CommitBlocks".into(), op);
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            params.op.clone(),
+            params.deadline_block as i64,
+            params.gas_price.clone(),
+            params.raw_tx.clone(),
+        )
+        .await?;
+    let replacement_hash = H256::repeat_byte(0xaa);
+    for hash in vec![params.hash, replacement_hash] {
+        EthereumSchema(&mut storage)
+            .add_hash_entry(response.id, &hash)
+            .await?;
+    }
+
+    let eth_op = EthereumSchema(&mut storage)
+        .load_eth_operation_by_aggregated_op(op_id)
+        .await?
+        .expect("Ethereum operation should be found");
+    assert_eq!(eth_op.id, response.id);
+    assert!(!eth_op.confirmed);
+    let hashes: Vec<_> = EthereumSchema(&mut storage)
+        .load_eth_tx_hashes(response.id)
+        .await?
+        .into_iter()
+        .map(|entry| H256::from_slice(&entry.tx_hash))
+        .collect();
+    assert_eq!(hashes, vec![params.hash, replacement_hash]);
+
+    // Repeated requests don't create duplicates.
+    let mut schema = EthereumSchema(&mut storage);
+    assert!(!schema.is_eth_tx_resend_requested(response.id).await?);
+    assert!(schema.request_eth_tx_resend(response.id).await?);
+    assert!(!schema.request_eth_tx_resend(response.id).await?);
+    assert!(schema.is_eth_tx_resend_requested(response.id).await?);
+
+    // Requests are removed once taken.
+    assert_eq!(
+        schema.take_eth_tx_resend_requests().await?,
+        vec![response.id]
+    );
+    assert!(schema.take_eth_tx_resend_requests().await?.is_empty());
+    assert!(!schema.is_eth_tx_resend_requested(response.id).await?);
+
+    Ok(())
+}
+
 /// Here we check `unprocessed` and `unconfirmed` operations getting.
 /// If there is no `ETHOperation` for `Operation`, it must be returned by `load_unprocessed_operations`.
 /// It must **not** be returned by `load_unconfirmed_operations`.
@@ -399,3 +621,139 @@ async fn test_update_eth_parameters(mut storage: StorageProcessor<'_>) -> QueryR
 
     Ok(())
 }
+
+/// Checks that the overwritten stats are loaded back and that the new operations
+/// are checked against them.
+#[db_test]
+async fn ethereum_stats_overwrite(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    storage.ethereum_schema().initialize_eth_data().await?;
+
+    let stats = ETHStats {
+        last_committed_block: 5,
+        last_verified_block: 3,
+        last_executed_block: 2,
+    };
+    storage.ethereum_schema().store_stats(&stats).await?;
+
+    let stored_stats = storage.ethereum_schema().load_stats().await?;
+    assert_eq!(stored_stats.last_committed_block, 5);
+    assert_eq!(stored_stats.last_verified_block, 3);
+    assert_eq!(stored_stats.last_executed_block, 2);
+
+    // The next commit operation must follow the overwritten stats.
+    for (block_number, is_ok) in vec![(BlockNumber(1), false), (BlockNumber(6), true)] {
+        let operation = gen_unique_aggregated_operation(
+            block_number,
+            AggregatedActionType::CommitBlocks,
+            BLOCK_SIZE_CHUNKS,
+        );
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(operation)
+            .await?;
+        let operation = OperationsSchema(&mut storage)
+            .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+            .await?
+            .unwrap();
+        let result = storage
+            .ethereum_schema()
+            .save_new_eth_tx(
+                AggregatedActionType::CommitBlocks,
+                Some(operation),
+                100,
+                100u32.into(),
+                Default::default(),
+            )
+            .await;
+        assert_eq!(result.is_ok(), is_ok);
+    }
+
+    Ok(())
+}
+
+/// Checks that the creation times of the recent Ethereum operations are loaded
+/// for the requested operation type only, newest first.
+#[db_test]
+async fn ethereum_recent_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+    let mut schema = EthereumSchema(&mut storage);
+    for op_type in [
+        AggregatedActionType::ExecuteBlocks,
+        AggregatedActionType::CommitBlocks,
+        AggregatedActionType::ExecuteBlocks,
+        AggregatedActionType::ExecuteBlocks,
+    ] {
+        schema
+            .save_new_eth_tx(op_type, None, 100, 100u32.into(), Vec::new())
+            .await?;
+    }
+
+    let created_at = schema
+        .load_recent_operations_created_at(AggregatedActionType::ExecuteBlocks, 2)
+        .await?;
+    assert_eq!(created_at.len(), 2);
+    assert!(created_at[0] >= created_at[1]);
+    let created_at = schema
+        .load_recent_operations_created_at(AggregatedActionType::CommitBlocks, 10)
+        .await?;
+    assert_eq!(created_at.len(), 1);
+    assert!(schema
+        .load_recent_operations_created_at(AggregatedActionType::PublishProofBlocksOnchain, 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Checks that the recent Ethereum operations are loaded along with the gas used by the confirmed
+/// ones, and that only the confirmed operations are taken into account in the spent fees.
+#[db_test]
+async fn ethereum_gas_spend(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    EthereumSchema(&mut storage).initialize_eth_data().await?;
+    let mut schema = EthereumSchema(&mut storage);
+    let sent = [
+        (1_000u32, Some(100_000u64)),
+        (2_000u32, Some(50_000u64)),
+        (3_000u32, None),
+    ];
+    for (i, (gas_price, gas_used)) in sent.iter().enumerate() {
+        let response = schema
+            .save_new_eth_tx(
+                AggregatedActionType::CommitBlocks,
+                None,
+                100,
+                (*gas_price).into(),
+                Vec::new(),
+            )
+            .await?;
+        let hash = H256::from_low_u64_be(i as u64 + 1);
+        schema.add_hash_entry(response.id, &hash).await?;
+        if let Some(gas_used) = gas_used {
+            schema.confirm_eth_tx(&hash).await?;
+            schema.save_gas_used(&hash, (*gas_used).into()).await?;
+        }
+    }
+
+    let operations = schema.load_recent_operations_gas(2).await?;
+    assert_eq!(operations.len(), 2);
+    assert!(!operations[0].confirmed);
+    assert_eq!(operations[0].gas_used, None);
+    assert_eq!(operations[0].last_used_gas_price, 3_000u32.into());
+    assert!(operations[1].confirmed);
+    assert_eq!(operations[1].gas_used, Some(50_000));
+    assert_eq!(
+        operations[1].final_hash,
+        Some(H256::from_low_u64_be(2).as_bytes().to_vec())
+    );
+    assert_eq!(operations[1].op_type, "CommitBlocks");
+
+    let spent = schema
+        .load_spent_fees_since(Utc::now() - Duration::hours(1))
+        .await?;
+    assert_eq!(spent, BigUint::from(200_000_000u64));
+    let spent = schema
+        .load_spent_fees_since(Utc::now() + Duration::hours(1))
+        .await?;
+    assert_eq!(spent, BigUint::from(0u32));
+
+    Ok(())
+}
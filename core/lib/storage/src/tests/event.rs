@@ -1,6 +1,7 @@
 // Built-in uses
 use std::convert::TryFrom;
 // External uses
+use chrono::Utc;
 // Workspace uses
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
@@ -12,6 +13,7 @@ use zksync_types::{
 // Local uses
 use super::{chain::apply_random_updates, create_rng, db_test, ACCOUNT_MUTEX};
 use crate::{
+    event::EventType,
     test_data::{
         dummy_ethereum_tx_hash, gen_sample_block, gen_unique_aggregated_operation,
         BLOCK_SIZE_CHUNKS,
@@ -331,3 +333,95 @@ async fn test_account_events(mut storage: StorageProcessor<'_>) -> QueryResult<(
             && check_account_event(event, AccountStateChangeStatus::Finalized)));
     Ok(())
 }
+
+/// Checks that events can be loaded page by page and filtered by type.
+/// Also checks that repeated events are not stored and that outdated events are pruned.
+#[db_test]
+async fn test_events_journal(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let _lock = ACCOUNT_MUTEX.lock().await;
+    storage.ethereum_schema().initialize_eth_data().await?;
+
+    // Commit a block with state updates, so both block and account events are created.
+    let mut rng = create_rng();
+    let (_, updates) = apply_random_updates(AccountMap::default(), &mut rng);
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(BlockNumber(1), &updates, 0)
+        .await?;
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(gen_sample_block(
+            BlockNumber(1),
+            BLOCK_SIZE_CHUNKS,
+            Vec::new(),
+        ))
+        .await?;
+    store_operation(
+        &mut storage,
+        AggregatedActionType::CommitBlocks,
+        BlockNumber(1),
+    )
+    .await?;
+
+    let events = storage
+        .event_schema()
+        .load_events(EventId(0), 100, None)
+        .await?;
+    assert_eq!(events.len(), updates.len() + 1);
+    // Events are ordered by their ids.
+    assert!(events.windows(2).all(|pair| pair[0].id < pair[1].id));
+
+    // Events can be replayed starting from any id.
+    let page = storage
+        .event_schema()
+        .load_events(EventId(events[0].id as u64), 1, None)
+        .await?;
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, events[1].id);
+
+    // Filter events by type.
+    let block_events = storage
+        .event_schema()
+        .load_events(EventId(0), 100, Some(EventType::Block))
+        .await?;
+    assert_eq!(block_events.len(), 1);
+    let account_events = storage
+        .event_schema()
+        .load_events(EventId(0), 100, Some(EventType::Account))
+        .await?;
+    assert_eq!(account_events.len(), updates.len());
+    assert!(account_events
+        .iter()
+        .all(|event| event.event_type == EventType::Account));
+
+    // Storing the same event once again doesn't create a duplicate.
+    storage
+        .event_schema()
+        .store_block_event(BlockNumber(1), BlockStatus::Committed)
+        .await?;
+    let last_event_id = EventId(events.last().unwrap().id as u64);
+    assert!(fetch_new_events(&mut storage, last_event_id)
+        .await?
+        .is_empty());
+
+    // Events that happened before the given moment are removed.
+    let removed = storage
+        .event_schema()
+        .remove_events_older_than(Utc::now() - chrono::Duration::hours(1))
+        .await?;
+    assert_eq!(removed, 0);
+    let removed = storage
+        .event_schema()
+        .remove_events_older_than(Utc::now())
+        .await?;
+    assert_eq!(removed as usize, events.len());
+    assert!(storage
+        .event_schema()
+        .load_events(EventId(0), 100, None)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
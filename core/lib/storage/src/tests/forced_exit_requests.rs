@@ -201,3 +201,22 @@ async fn delete_old_requests(mut storage: StorageProcessor<'_>) -> QueryResult<(
 
     Ok(())
 }
+
+// Checks that the processing is not paused by default and the pause flag
+// is only reported as changed when it's actually changed
+#[db_test]
+async fn processing_pause(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut schema = ForcedExitRequestsSchema(&mut storage);
+    assert!(!schema.is_processing_paused().await?);
+    assert!(!schema.set_processing_paused(false, "operator").await?);
+    assert!(!schema.is_processing_paused().await?);
+
+    assert!(schema.set_processing_paused(true, "operator").await?);
+    assert!(schema.is_processing_paused().await?);
+    assert!(!schema.set_processing_paused(true, "operator").await?);
+
+    assert!(schema.set_processing_paused(false, "operator").await?);
+    assert!(!schema.is_processing_paused().await?);
+
+    Ok(())
+}
@@ -1,8 +1,14 @@
+use std::time::Duration;
+
+use chrono::NaiveDate;
 use sqlx::types::BigDecimal;
 use zksync_types::TokenId;
 
 use crate::tests::db_test;
-use crate::{misc::records::Subsidy, misc::MiscSchema};
+use crate::{
+    misc::records::{RpcMethodUsage, Subsidy},
+    misc::MiscSchema,
+};
 use crate::{QueryResult, StorageProcessor};
 
 fn get_subsidy(name: String, value: u64) -> Subsidy {
@@ -54,3 +60,92 @@ async fn stored_subsidy(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Checks that transaction types can be disabled and enabled back.
+#[db_test]
+async fn disabled_tx_types(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(MiscSchema(&mut storage)
+        .load_disabled_tx_types()
+        .await?
+        .is_empty());
+
+    MiscSchema(&mut storage)
+        .set_tx_type_disabled("Swap", true)
+        .await?;
+    MiscSchema(&mut storage)
+        .set_tx_type_disabled("WithdrawNFT", true)
+        .await?;
+    // Disabling the same type twice must not fail.
+    MiscSchema(&mut storage)
+        .set_tx_type_disabled("Swap", true)
+        .await?;
+    assert_eq!(
+        MiscSchema(&mut storage).load_disabled_tx_types().await?,
+        vec!["Swap".to_string(), "WithdrawNFT".to_string()]
+    );
+
+    MiscSchema(&mut storage)
+        .set_tx_type_disabled("Swap", false)
+        .await?;
+    assert_eq!(
+        MiscSchema(&mut storage).load_disabled_tx_types().await?,
+        vec!["WithdrawNFT".to_string()]
+    );
+
+    Ok(())
+}
+
+/// Checks that the replication lag of the primary database is zero.
+#[db_test]
+async fn replication_lag(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let lag = MiscSchema(&mut storage).replication_lag().await?;
+    assert_eq!(lag, Duration::default());
+
+    Ok(())
+}
+
+/// Checks that the usage of the JSON-RPC methods is accumulated per method, day and subnet.
+#[db_test]
+async fn rpc_methods_usage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let day_1 = NaiveDate::from_ymd(2023, 6, 19);
+    let day_2 = NaiveDate::from_ymd(2023, 6, 20);
+    let usage = |method: &str, day: NaiveDate, subnet_hash: &str, calls: i64| RpcMethodUsage {
+        method: method.to_string(),
+        day,
+        subnet_hash: subnet_hash.to_string(),
+        calls,
+    };
+
+    MiscSchema(&mut storage)
+        .record_rpc_methods_usage(&[
+            usage("tx_info", day_1, "", 3),
+            usage("tx_info", day_1, "subnet", 1),
+            usage("tokens", day_2, "", 5),
+        ])
+        .await?;
+    MiscSchema(&mut storage)
+        .record_rpc_methods_usage(&[usage("tx_info", day_1, "", 2)])
+        .await?;
+    MiscSchema(&mut storage)
+        .record_rpc_methods_usage(&[])
+        .await?;
+
+    let loaded = MiscSchema(&mut storage)
+        .load_rpc_methods_usage(day_1, day_2)
+        .await?;
+    assert_eq!(
+        loaded,
+        vec![
+            usage("tx_info", day_1, "", 5),
+            usage("tx_info", day_1, "subnet", 1),
+            usage("tokens", day_2, "", 5),
+        ]
+    );
+
+    let loaded = MiscSchema(&mut storage)
+        .load_rpc_methods_usage(day_2, day_2)
+        .await?;
+    assert_eq!(loaded, vec![usage("tokens", day_2, "", 5)]);
+
+    Ok(())
+}
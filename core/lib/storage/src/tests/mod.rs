@@ -23,8 +23,12 @@ use tokio::sync::Mutex;
 // Workspace imports
 use zksync_crypto::rand::{SeedableRng, XorShiftRng};
 
+mod access_tokens;
+mod account_locks;
+mod banned_addresses;
 pub(crate) mod chain;
 mod config;
+mod connection;
 mod data_restore;
 mod ethereum;
 mod event;
@@ -32,6 +36,7 @@ mod forced_exit_requests;
 mod misc;
 mod prover;
 mod tokens;
+mod webhooks;
 mod withdrawals;
 
 pub use db_test_macro::test as db_test;
@@ -1,9 +1,13 @@
 // Built-in imports
 use std::str::FromStr;
 // External imports
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use num::{rational::Ratio, BigUint};
 // Workspace imports
+use zksync_api_types::v02::{
+    pagination::{PaginationDirection, PaginationQuery},
+    token::TokenMetadataField,
+};
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
     tokens::TokenMarketVolume, AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx,
@@ -13,10 +17,13 @@ use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
 use crate::tests::db_test;
 use crate::{
-    chain::account::records::StorageMintNFTUpdate,
+    chain::account::records::{StorageAccountCreation, StorageAccountUpdate, StorageMintNFTUpdate},
     diff::StorageAccountDiff,
-    tokens::{TokensSchema, STORED_USD_PRICE_PRECISION},
-    QueryResult, StorageProcessor,
+    tokens::{
+        records::{TokenChangeSource, TokenMetadata, TokenMetadataChange, TokensFilter},
+        TokensSchema, STORED_USD_PRICE_PRECISION,
+    },
+    BigDecimal, QueryResult, StorageProcessor,
 };
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 
@@ -69,16 +76,16 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     };
 
     TokensSchema(&mut storage)
-        .store_or_update_token(nft.clone())
+        .store_or_update_token(nft.clone(), &TokenChangeSource::TokenHandler)
         .await
         .expect("Store tokens query failed");
 
     TokensSchema(&mut storage)
-        .store_or_update_token(token_a.clone())
+        .store_or_update_token(token_a.clone(), &TokenChangeSource::TokenHandler)
         .await
         .expect("Store tokens query failed");
     TokensSchema(&mut storage)
-        .store_or_update_token(token_b.clone())
+        .store_or_update_token(token_b.clone(), &TokenChangeSource::TokenHandler)
         .await
         .expect("Store tokens query failed");
     // The count is updated.
@@ -132,6 +139,14 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         .expect("Get nft failed")
         .expect("Token not found");
     assert_eq!(db_nft_token, nft);
+
+    // Tokens are loaded by ids regardless of their kind, the unknown ids are omitted.
+    let tokens_by_ids = TokensSchema(&mut storage)
+        .load_tokens_by_ids(&[token_b.id, nft.id, TokenId(3)])
+        .await?;
+    assert_eq!(tokens_by_ids.len(), 2);
+    assert_eq!(tokens_by_ids[&token_b.id], token_b);
+    assert_eq!(tokens_by_ids[&nft.id], nft);
     Ok(())
 }
 
@@ -192,11 +207,14 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
 
     storage
         .tokens_schema()
-        .store_or_update_token(Token {
-            id: WRONG_TOKEN_ID,
-            address: Address::random(),
-            ..Default::default()
-        })
+        .store_or_update_token(
+            Token {
+                id: WRONG_TOKEN_ID,
+                address: Address::random(),
+                ..Default::default()
+            },
+            &TokenChangeSource::TokenHandler,
+        )
         .await
         .unwrap();
     let price = TokenPrice {
@@ -255,7 +273,311 @@ async fn test_market_volume(mut storage: StorageProcessor<'_>) -> QueryResult<()
     Ok(())
 }
 
+/// Checks the tokens listing filters combined with the pagination.
+#[db_test]
+async fn test_filtered_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for (id, symbol) in [(1, "USDC"), (2, "usdt"), (3, "DAI"), (4, "US_D")] {
+        storage
+            .tokens_schema()
+            .store_or_update_token(
+                Token::new(
+                    TokenId(id),
+                    Address::from_low_u64_be(id as u64),
+                    symbol,
+                    6,
+                    TokenKind::ERC20,
+                ),
+                &TokenChangeSource::TokenHandler,
+            )
+            .await?;
+    }
+    storage
+        .tokens_schema()
+        .store_or_update_token(
+            Token::new_nft(TokenId(MIN_NFT_TOKEN_ID), "USD-NFT"),
+            &TokenChangeSource::TokenHandler,
+        )
+        .await?;
+    // Only `USDC` has enough market volume to be used for paying fees.
+    let market_volume = TokenMarketVolume {
+        market_volume: Ratio::from_integer(BigUint::from(100u32)),
+        last_updated: Utc::now(),
+    };
+    let price = TokenPrice {
+        usd_price: Ratio::from_integer(BigUint::from(1u32)),
+        last_updated: Utc::now(),
+    };
+    storage
+        .tokens_schema()
+        .update_token_market_volume(TokenId(1), market_volume)
+        .await?;
+    storage
+        .tokens_schema()
+        .update_historical_ticker_price(TokenId(1), price)
+        .await?;
+
+    async fn load_ids(
+        storage: &mut StorageProcessor<'_>,
+        from: u32,
+        limit: u32,
+        direction: PaginationDirection,
+        filter: &TokensFilter,
+    ) -> QueryResult<Vec<u32>> {
+        let query = PaginationQuery {
+            from: TokenId(from),
+            limit,
+            direction,
+        };
+        let tokens = storage
+            .tokens_schema()
+            .load_token_page(&query, filter)
+            .await?;
+        Ok(tokens.into_iter().map(|token| *token.id).collect())
+    }
+
+    // Symbol prefix is case-insensitive.
+    let filter = TokensFilter {
+        symbol_prefix: Some("uS".to_owned()),
+        ..TokensFilter::new(TokenKind::ERC20)
+    };
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_filtered_count_and_max_id(&filter)
+            .await?,
+        (3, Some(TokenId(4)))
+    );
+    let first_page = load_ids(&mut storage, 0, 2, PaginationDirection::Newer, &filter).await?;
+    assert_eq!(first_page, vec![1, 2]);
+    let second_page = load_ids(&mut storage, 3, 2, PaginationDirection::Newer, &filter).await?;
+    assert_eq!(second_page, vec![4]);
+    let latest = load_ids(&mut storage, 4, 2, PaginationDirection::Older, &filter).await?;
+    assert_eq!(latest, vec![4, 2]);
+
+    // Wildcards in the prefix are matched literally.
+    let filter = TokensFilter {
+        symbol_prefix: Some("us_".to_owned()),
+        ..TokensFilter::new(TokenKind::ERC20)
+    };
+    let ids = load_ids(&mut storage, 0, 10, PaginationDirection::Newer, &filter).await?;
+    assert_eq!(ids, vec![4]);
+
+    // ETH is always enabled for paying fees.
+    let filter = TokensFilter {
+        min_market_volume: Some(Ratio::from_integer(BigUint::from(50u32))),
+        ..TokensFilter::new(TokenKind::ERC20)
+    };
+    let ids = load_ids(&mut storage, 0, 10, PaginationDirection::Newer, &filter).await?;
+    assert_eq!(ids, vec![0, 1]);
+    let filter = TokensFilter {
+        symbol_prefix: Some("us".to_owned()),
+        ..filter
+    };
+    let ids = load_ids(&mut storage, 0, 10, PaginationDirection::Newer, &filter).await?;
+    assert_eq!(ids, vec![1]);
+
+    // NFTs are listed only on request.
+    let filter = TokensFilter::new(TokenKind::ERC20);
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_filtered_count_and_max_id(&filter)
+            .await?,
+        (5, Some(TokenId(4)))
+    );
+    let filter = TokensFilter {
+        symbol_prefix: Some("usd".to_owned()),
+        ..TokensFilter::new(TokenKind::NFT)
+    };
+    let ids = load_ids(&mut storage, 0, 10, PaginationDirection::Newer, &filter).await?;
+    assert_eq!(ids, vec![MIN_NFT_TOKEN_ID]);
+    let filter = TokensFilter {
+        min_market_volume: Some(Ratio::from_integer(BigUint::from(50u32))),
+        ..TokensFilter::new(TokenKind::NFT)
+    };
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_filtered_count_and_max_id(&filter)
+            .await?,
+        (0, None)
+    );
+
+    Ok(())
+}
+
+/// Renames the token on behalf of the token handler.
+async fn rename_token(
+    storage: &mut StorageProcessor<'_>,
+    token_id: TokenId,
+    symbol: &str,
+    alias_valid_until: DateTime<Utc>,
+) -> QueryResult<bool> {
+    storage
+        .tokens_schema()
+        .update_token_metadata(
+            token_id,
+            TokenMetadataChange::Symbol {
+                symbol: symbol.to_string(),
+                alias_valid_until,
+            },
+            &TokenChangeSource::TokenHandler,
+        )
+        .await
+}
+
 /// Checks the store/load factories for nft
+/// Checks that the renamed token can be found by both the current and the previous symbols
+/// until the alias expires.
+#[db_test]
+async fn test_token_rename(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(TokenId(1), Address::random(), "OLD", 18, TokenKind::ERC20);
+    storage
+        .tokens_schema()
+        .store_or_update_token(token.clone(), &TokenChangeSource::TokenHandler)
+        .await?;
+
+    let valid_until = Utc::now() + chrono::Duration::days(1);
+    assert!(rename_token(&mut storage, token.id, "NEW", valid_until).await?);
+    // Renaming to the same symbol does nothing.
+    assert!(!rename_token(&mut storage, token.id, "NEW", valid_until).await?);
+
+    let renamed = Token::new(token.id, token.address, "NEW", 18, TokenKind::ERC20);
+    for symbol in &["NEW", "new", "OLD", "old"] {
+        let loaded = storage
+            .tokens_schema()
+            .get_token(TokenLike::Symbol(symbol.to_string()))
+            .await?;
+        assert_eq!(loaded, Some(renamed.clone()), "symbol: {}", symbol);
+    }
+    let aliases = storage
+        .tokens_schema()
+        .load_token_aliases(&[TokenId(0), token.id])
+        .await?;
+    assert_eq!(aliases.len(), 1);
+    assert_eq!(aliases[&token.id], vec!["OLD".to_string()]);
+
+    // Rename the token back, the current symbol must not be listed as an alias.
+    rename_token(&mut storage, token.id, "OLD", valid_until).await?;
+    let aliases = storage
+        .tokens_schema()
+        .load_token_aliases(&[token.id])
+        .await?;
+    assert_eq!(aliases[&token.id], vec!["NEW".to_string()]);
+
+    // Expired aliases are not resolved.
+    rename_token(
+        &mut storage,
+        token.id,
+        "NEWEST",
+        Utc::now() - chrono::Duration::seconds(1),
+    )
+    .await?;
+    let loaded = storage
+        .tokens_schema()
+        .get_token(TokenLike::Symbol("OLD".to_string()))
+        .await?;
+    assert!(loaded.is_none());
+    let loaded = storage
+        .tokens_schema()
+        .get_token(TokenLike::Symbol("NEW".to_string()))
+        .await?;
+    assert_eq!(loaded.unwrap().symbol, "NEWEST");
+    let aliases = storage
+        .tokens_schema()
+        .load_token_aliases(&[token.id])
+        .await?;
+    assert_eq!(aliases[&token.id], vec!["NEW".to_string()]);
+
+    Ok(())
+}
+
+/// Checks that the token metadata changes are recorded in the changelog in the order they were made.
+#[db_test]
+async fn test_token_changes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(TokenId(1), Address::random(), "OLD", 18, TokenKind::ERC20);
+    storage
+        .tokens_schema()
+        .store_or_update_token(token.clone(), &TokenChangeSource::TokenHandler)
+        .await?;
+    assert!(storage
+        .tokens_schema()
+        .load_token_changes(0, 10)
+        .await?
+        .is_empty());
+
+    let valid_until = Utc::now() + chrono::Duration::days(1);
+    rename_token(&mut storage, token.id, "NEW", valid_until).await?;
+    let admin = TokenChangeSource::Admin("operator".to_string());
+    assert!(
+        storage
+            .tokens_schema()
+            .update_token_metadata(token.id, TokenMetadataChange::Decimals(6), &admin)
+            .await?
+    );
+    // Unchanged values and unknown tokens are not recorded.
+    assert!(!rename_token(&mut storage, token.id, "NEW", valid_until).await?);
+    assert!(
+        !storage
+            .tokens_schema()
+            .update_token_metadata(TokenId(2), TokenMetadataChange::Decimals(6), &admin)
+            .await?
+    );
+
+    let changes = storage.tokens_schema().load_token_changes(0, 10).await?;
+    assert_eq!(changes.len(), 2);
+    assert!(changes[0].id < changes[1].id);
+    assert_eq!(changes[0].token_id, token.id);
+    assert_eq!(changes[0].field, TokenMetadataField::Symbol);
+    assert_eq!(
+        (changes[0].old_value.as_str(), changes[0].new_value.as_str()),
+        ("OLD", "NEW")
+    );
+    assert_eq!(changes[0].source, "tokenHandler");
+    assert_eq!(changes[1].field, TokenMetadataField::Decimals);
+    assert_eq!(
+        (changes[1].old_value.as_str(), changes[1].new_value.as_str()),
+        ("18", "6")
+    );
+    assert_eq!(changes[1].source, "admin:operator");
+
+    // Only the changes made after the given one are returned.
+    let delta = storage
+        .tokens_schema()
+        .load_token_changes(changes[0].id, 10)
+        .await?;
+    assert_eq!(delta, changes[1..]);
+    let limited = storage.tokens_schema().load_token_changes(0, 1).await?;
+    assert_eq!(limited, changes[..1]);
+
+    // Replacing the token records the changes of its metadata as well.
+    storage
+        .tokens_schema()
+        .store_or_update_token(
+            Token::new(token.id, token.address, "NEWEST", 8, TokenKind::ERC20),
+            &admin,
+        )
+        .await?;
+    let delta = storage
+        .tokens_schema()
+        .load_token_changes(changes[1].id, 10)
+        .await?;
+    assert_eq!(delta.len(), 2);
+    assert_eq!(delta[0].field, TokenMetadataField::Symbol);
+    assert_eq!(
+        (delta[0].old_value.as_str(), delta[0].new_value.as_str()),
+        ("NEW", "NEWEST")
+    );
+    assert_eq!(delta[1].field, TokenMetadataField::Decimals);
+    assert_eq!(
+        (delta[1].old_value.as_str(), delta[1].new_value.as_str()),
+        ("6", "8")
+    );
+    assert!(delta.iter().all(|change| change.source == "admin:operator"));
+
+    Ok(())
+}
+
 #[db_test]
 async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     let token_id = TokenId(2u32.pow(16) + 10);
@@ -375,3 +697,328 @@ async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryRes
 
     Ok(())
 }
+
+/// Checks that the listing progress of the token is recorded.
+#[db_test]
+async fn test_token_listing(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(0x12);
+    let token_id = TokenId(7);
+    assert!(storage
+        .tokens_schema()
+        .get_token_listing(address)
+        .await?
+        .is_none());
+
+    storage
+        .tokens_schema()
+        .record_token_listing(address, token_id, 100)
+        .await?;
+    let listing = storage
+        .tokens_schema()
+        .get_token_listing(address)
+        .await?
+        .unwrap();
+    assert_eq!(listing.address, address);
+    assert_eq!(listing.token_id, token_id);
+    assert_eq!(listing.eth_block_number, 100);
+    assert!(listing.token_stored_at.is_none());
+    assert!(listing.store_error.is_none());
+
+    // The repeated event doesn't change the record.
+    storage
+        .tokens_schema()
+        .record_token_listing(address, token_id, 101)
+        .await?;
+    storage
+        .tokens_schema()
+        .record_token_listing_result(address, Some("symbol is already used"))
+        .await?;
+    let listing = storage
+        .tokens_schema()
+        .get_token_listing(address)
+        .await?
+        .unwrap();
+    assert_eq!(listing.eth_block_number, 100);
+    assert!(listing.token_stored_at.is_none());
+    assert_eq!(
+        listing.store_error.as_deref(),
+        Some("symbol is already used")
+    );
+
+    storage
+        .tokens_schema()
+        .record_token_listing_result(address, None)
+        .await?;
+    let listing = storage
+        .tokens_schema()
+        .get_token_listing(address)
+        .await?
+        .unwrap();
+    assert!(listing.token_stored_at.is_some());
+    assert!(listing.store_error.is_none());
+
+    Ok(())
+}
+
+#[db_test]
+async fn test_token_metadata(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let placeholder = Token::new(
+        TokenId(1),
+        Address::random(),
+        "ERC20-1",
+        18,
+        TokenKind::ERC20,
+    );
+    let listed = Token::new(TokenId(2), Address::random(), "LST", 6, TokenKind::ERC20);
+    let not_erc20 = Token::new(
+        TokenId(3),
+        Address::random(),
+        "ERC20-3",
+        18,
+        TokenKind::None,
+    );
+    for token in &[&placeholder, &listed, &not_erc20] {
+        storage
+            .tokens_schema()
+            .store_or_update_token((*token).clone(), &TokenChangeSource::TokenHandler)
+            .await?;
+    }
+
+    // Only the ERC20 tokens with the placeholder symbol are reconciled.
+    let tokens = storage.tokens_schema().load_placeholder_tokens(10).await?;
+    assert_eq!(tokens, vec![placeholder.clone()]);
+    assert!(storage
+        .tokens_schema()
+        .load_token_metadata(placeholder.id)
+        .await?
+        .is_none());
+
+    // Values the contract failed to return are stored as unknown.
+    let metadata = TokenMetadata {
+        symbol: None,
+        decimals: Some(8),
+    };
+    storage
+        .tokens_schema()
+        .store_token_metadata(placeholder.id, &metadata)
+        .await?;
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .load_token_metadata(placeholder.id)
+            .await?,
+        Some(metadata)
+    );
+    assert!(storage
+        .tokens_schema()
+        .load_placeholder_tokens(10)
+        .await?
+        .is_empty());
+
+    let metadata = TokenMetadata {
+        symbol: Some("PLC".to_string()),
+        decimals: Some(8),
+    };
+    storage
+        .tokens_schema()
+        .store_token_metadata(placeholder.id, &metadata)
+        .await?;
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .load_token_metadata(placeholder.id)
+            .await?,
+        Some(metadata)
+    );
+
+    storage
+        .tokens_schema()
+        .update_token_metadata(
+            placeholder.id,
+            TokenMetadataChange::Decimals(8),
+            &TokenChangeSource::TokenHandler,
+        )
+        .await?;
+    let token = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(placeholder.id))
+        .await?
+        .unwrap();
+    assert_eq!(token.decimals, 8);
+
+    Ok(())
+}
+
+/// Creates the account with the given balances.
+async fn store_account_balances(
+    storage: &mut StorageProcessor<'_>,
+    account_id: i64,
+    balances: &[(TokenId, u32)],
+) -> QueryResult<()> {
+    storage
+        .chain()
+        .state_schema()
+        .apply_storage_account_diff(StorageAccountDiff::Create(StorageAccountCreation {
+            account_id,
+            is_create: true,
+            block_number: 0,
+            address: Address::from_low_u64_be(account_id as u64)
+                .as_bytes()
+                .to_vec(),
+            nonce: 0,
+            update_order_id: 0,
+        }))
+        .await?;
+    for (token_id, balance) in balances {
+        storage
+            .chain()
+            .state_schema()
+            .apply_storage_account_diff(StorageAccountDiff::BalanceUpdate(StorageAccountUpdate {
+                balance_update_id: 0,
+                account_id,
+                block_number: 0,
+                coin_id: **token_id as i32,
+                old_balance: 0u32.into(),
+                new_balance: (*balance).into(),
+                old_nonce: 0,
+                new_nonce: 0,
+                update_order_id: 0,
+            }))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Checks that the largest holders of the ERC20 tokens are recalculated from the balances.
+#[db_test]
+async fn test_token_top_holders(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let eth = TokenId(0);
+    let nft = TokenId(MIN_NFT_TOKEN_ID + 1);
+    storage
+        .tokens_schema()
+        .store_token(Token::new(
+            nft,
+            Address::repeat_byte(0x11),
+            "NFT-65537",
+            0,
+            TokenKind::NFT,
+        ))
+        .await?;
+
+    // Holders are not known until the first recalculation.
+    let (holders, refreshed_at) = storage.tokens_schema().load_top_holders(eth, 10).await?;
+    assert!(holders.is_empty());
+    assert!(refreshed_at.is_none());
+
+    store_account_balances(&mut storage, 1, &[(eth, 100), (nft, 1)]).await?;
+    store_account_balances(&mut storage, 2, &[(eth, 300)]).await?;
+    store_account_balances(&mut storage, 3, &[(eth, 300)]).await?;
+    store_account_balances(&mut storage, 4, &[(eth, 0)]).await?;
+    let refreshed_at = storage.tokens_schema().refresh_top_holders(2).await?;
+
+    // Only the top holders with non-zero balances are stored, the ties are ordered by the account id.
+    let (holders, holders_refreshed_at) = storage.tokens_schema().load_top_holders(eth, 10).await?;
+    assert_eq!(holders_refreshed_at, Some(refreshed_at));
+    let holders: Vec<_> = holders
+        .into_iter()
+        .map(|holder| (holder.rank, holder.account_id, holder.balance))
+        .collect();
+    assert_eq!(
+        holders,
+        vec![(1, 2, BigDecimal::from(300)), (2, 3, BigDecimal::from(300))]
+    );
+
+    let (holders, _) = storage.tokens_schema().load_top_holders(eth, 1).await?;
+    assert_eq!(holders.len(), 1);
+    assert_eq!(holders[0].address, Address::from_low_u64_be(2).as_bytes());
+
+    // NFTs are not tracked, but the time of the recalculation is still reported.
+    let (holders, holders_refreshed_at) = storage.tokens_schema().load_top_holders(nft, 10).await?;
+    assert!(holders.is_empty());
+    assert_eq!(holders_refreshed_at, Some(refreshed_at));
+
+    // The next recalculation replaces the previous holders.
+    store_account_balances(&mut storage, 5, &[(eth, 500)]).await?;
+    let refreshed_at = storage.tokens_schema().refresh_top_holders(2).await?;
+    let (holders, holders_refreshed_at) = storage.tokens_schema().load_top_holders(eth, 10).await?;
+    assert_eq!(holders_refreshed_at, Some(refreshed_at));
+    let accounts: Vec<_> = holders.iter().map(|holder| holder.account_id).collect();
+    assert_eq!(accounts, vec![5, 2]);
+
+    Ok(())
+}
+
+/// Checks that the maximum age of the token price set by the operator is stored and reset.
+#[db_test]
+async fn test_token_price_max_age(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let token = Token::new(TokenId(1), Address::random(), "EXT", 18, TokenKind::ERC20);
+    storage
+        .tokens_schema()
+        .store_or_update_token(token, &TokenChangeSource::TokenHandler)
+        .await?;
+
+    // The default of the liquidity tier is used until the value is set.
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_price_max_age(TokenId(1))
+            .await?,
+        None
+    );
+    assert!(
+        !storage
+            .tokens_schema()
+            .set_price_max_age(TokenId(1), None, "operator")
+            .await?
+    );
+
+    assert!(
+        storage
+            .tokens_schema()
+            .set_price_max_age(TokenId(1), Some(300), "operator")
+            .await?
+    );
+    assert!(
+        !storage
+            .tokens_schema()
+            .set_price_max_age(TokenId(1), Some(300), "operator")
+            .await?
+    );
+    assert!(
+        storage
+            .tokens_schema()
+            .set_price_max_age(TokenId(1), Some(60), "operator")
+            .await?
+    );
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_price_max_age(TokenId(1))
+            .await?,
+        Some(60)
+    );
+    // Other tokens are not affected.
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_price_max_age(TokenId(0))
+            .await?,
+        None
+    );
+
+    assert!(
+        storage
+            .tokens_schema()
+            .set_price_max_age(TokenId(1), None, "operator")
+            .await?
+    );
+    assert_eq!(
+        storage
+            .tokens_schema()
+            .get_price_max_age(TokenId(1))
+            .await?,
+        None
+    );
+
+    Ok(())
+}
@@ -0,0 +1,202 @@
+// External imports
+use chrono::{Duration, Utc};
+use serde_json::json;
+// Workspace imports
+use zksync_types::{event::EventId, Address};
+// Local imports
+use crate::tests::db_test;
+use crate::{
+    webhooks::{records::NewWebhookDelivery, WebhooksSchema},
+    QueryResult, StorageProcessor,
+};
+
+/// Stores an event into the journal, returns its ID.
+async fn insert_event(storage: &mut StorageProcessor<'_>) -> QueryResult<EventId> {
+    let id = sqlx::query!(
+        r#"
+        INSERT INTO events (block_number, event_type, event_data)
+        VALUES (1, 'Transaction', '{}')
+        RETURNING id
+        "#
+    )
+    .fetch_one(storage.conn())
+    .await?
+    .id;
+    Ok(EventId(id as u64))
+}
+
+/// Checks that the subscriptions start at the end of the events journal, and that
+/// the claimed subscription is skipped by other dispatchers until it's released.
+#[db_test]
+async fn webhook_subscriptions(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let last_event_id = insert_event(&mut storage).await?;
+    let address = Address::repeat_byte(0x01);
+    let subscription = WebhooksSchema(&mut storage)
+        .create_subscription(address, "https://example.com", "secret", &json!({}))
+        .await?;
+    assert_eq!(subscription.address, address);
+    assert_eq!(subscription.last_event_id, last_event_id);
+    assert_eq!(subscription.failed_attempts, 0);
+    assert_eq!(
+        WebhooksSchema(&mut storage)
+            .load_subscription(subscription.id)
+            .await?,
+        Some(subscription.clone())
+    );
+
+    let preferences = json!({ "txTypes": ["Withdraw"] });
+    assert!(
+        WebhooksSchema(&mut storage)
+            .update_preferences(subscription.id, &preferences)
+            .await?
+    );
+    assert!(
+        !WebhooksSchema(&mut storage)
+            .update_preferences(subscription.id + 1, &preferences)
+            .await?
+    );
+
+    // The subscription is claimed only once until it's released.
+    let claimed = WebhooksSchema(&mut storage)
+        .claim_due_subscription(Utc::now() + Duration::minutes(1))
+        .await?
+        .expect("Subscription should be due");
+    assert_eq!(claimed.id, subscription.id);
+    assert_eq!(claimed.preferences, preferences);
+    assert_eq!(
+        WebhooksSchema(&mut storage)
+            .claim_due_subscription(Utc::now() + Duration::minutes(1))
+            .await?,
+        None
+    );
+
+    let next_event_id = insert_event(&mut storage).await?;
+    WebhooksSchema(&mut storage)
+        .release_subscription(
+            subscription.id,
+            next_event_id,
+            2,
+            Utc::now() - Duration::seconds(1),
+        )
+        .await?;
+    let claimed = WebhooksSchema(&mut storage)
+        .claim_due_subscription(Utc::now() + Duration::minutes(1))
+        .await?
+        .expect("Subscription should be due");
+    assert_eq!(claimed.last_event_id, next_event_id);
+    assert_eq!(claimed.failed_attempts, 2);
+
+    assert!(
+        WebhooksSchema(&mut storage)
+            .remove_subscription(subscription.id)
+            .await?
+    );
+    assert_eq!(
+        WebhooksSchema(&mut storage)
+            .load_subscription(subscription.id)
+            .await?,
+        None
+    );
+    Ok(())
+}
+
+/// Checks that the delivery attempts are listed in the order of the events, that the body of
+/// the first attempt is kept for the replays, and that the outdated attempts are removed.
+#[db_test]
+async fn webhook_deliveries(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let subscription = WebhooksSchema(&mut storage)
+        .create_subscription(
+            Address::repeat_byte(0x01),
+            "https://example.com",
+            "secret",
+            &json!({}),
+        )
+        .await?;
+    let first_event_id = insert_event(&mut storage).await?;
+    let second_event_id = insert_event(&mut storage).await?;
+    let delivery = |event_id, payload: &'static [u8], response_code, replay| NewWebhookDelivery {
+        subscription_id: subscription.id,
+        event_id,
+        payload,
+        body_hash: &[0xaa; 32],
+        response_code,
+        latency: std::time::Duration::from_millis(120),
+        replay,
+    };
+
+    for new_delivery in &[
+        delivery(second_event_id, b"second", Some(200), false),
+        delivery(first_event_id, b"first", None, false),
+        delivery(first_event_id, b"first, retried", Some(500), false),
+        delivery(first_event_id, b"first, replayed", Some(200), true),
+    ] {
+        let stored = WebhooksSchema(&mut storage)
+            .store_delivery(*new_delivery)
+            .await?;
+        assert_eq!(stored.payload, new_delivery.payload);
+        assert_eq!(stored.response_code, new_delivery.response_code);
+        assert_eq!(stored.latency, new_delivery.latency);
+    }
+
+    let deliveries = WebhooksSchema(&mut storage)
+        .load_deliveries(subscription.id, first_event_id, 10)
+        .await?;
+    let listed: Vec<_> = deliveries
+        .iter()
+        .map(|delivery| {
+            (
+                delivery.event_id,
+                delivery.payload.as_slice(),
+                delivery.replay,
+            )
+        })
+        .collect();
+    assert_eq!(
+        listed,
+        vec![
+            (first_event_id, &b"first"[..], false),
+            (first_event_id, &b"first, retried"[..], false),
+            (first_event_id, &b"first, replayed"[..], true),
+            (second_event_id, &b"second"[..], false),
+        ]
+    );
+    let deliveries = WebhooksSchema(&mut storage)
+        .load_deliveries(subscription.id, second_event_id, 10)
+        .await?;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(
+        WebhooksSchema(&mut storage)
+            .load_deliveries(subscription.id + 1, first_event_id, 10)
+            .await?,
+        vec![]
+    );
+
+    assert_eq!(
+        WebhooksSchema(&mut storage)
+            .load_delivered_payload(subscription.id, first_event_id)
+            .await?,
+        Some(b"first".to_vec())
+    );
+    assert_eq!(
+        WebhooksSchema(&mut storage)
+            .load_delivered_payload(subscription.id, EventId(*second_event_id + 1))
+            .await?,
+        None
+    );
+
+    let removed = WebhooksSchema(&mut storage)
+        .remove_deliveries_older_than(Utc::now() - Duration::hours(1))
+        .await?;
+    assert_eq!(removed, 0);
+    let removed = WebhooksSchema(&mut storage)
+        .remove_deliveries_older_than(Utc::now() + Duration::seconds(1))
+        .await?;
+    assert_eq!(removed, 4);
+    assert_eq!(
+        WebhooksSchema(&mut storage)
+            .load_delivered_payload(subscription.id, first_event_id)
+            .await?,
+        None
+    );
+    Ok(())
+}
@@ -2,18 +2,24 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 // External imports
+use chrono::{DateTime, SubsecRound, Utc};
 use num::{rational::Ratio, BigUint};
 
 use thiserror::Error;
 // Workspace imports
 use zksync_api_types::v02::{
     pagination::{PaginationDirection, PaginationQuery},
-    token::ApiNFT,
+    token::{ApiNFT, TokenChange},
 };
+use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_types::{AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT};
 use zksync_utils::ratio_to_big_decimal;
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind};
+use self::records::{
+    DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, StorageTokenChange,
+    StorageTokenHolder, StorageTokenListing, StorageTokenMetadata, TokenChangeSource, TokenKind,
+    TokenListing, TokenMetadata, TokenMetadataChange, TokensFilter,
+};
 
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
@@ -100,17 +106,21 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     }
 
     /// If a token with a given ID exists, then it replaces the information about the
-    /// token with a new one, otherwise, saves the token.
-    pub async fn store_or_update_token(&mut self, token: Token) -> QueryResult<()> {
+    /// token with a new one, otherwise, saves the token. Changes of the symbol and decimals
+    /// are applied via `update_token_metadata`, so they're recorded in the changelog.
+    pub async fn store_or_update_token(
+        &mut self,
+        token: Token,
+        source: &TokenChangeSource,
+    ) -> QueryResult<()> {
         let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
         let kind: TokenKind = token.kind.into();
-        sqlx::query!(
+        let inserted = sqlx::query!(
             r#"
             INSERT INTO tokens ( id, address, symbol, decimals, kind )
             VALUES ( $1, $2, $3, $4, $5 )
-            ON CONFLICT (id)
-            DO
-              UPDATE SET address = $2, symbol = $3, decimals = $4, kind = $5
+            ON CONFLICT (id) DO NOTHING
             "#,
             *token.id as i32,
             address_to_stored_string(&token.address),
@@ -118,13 +128,340 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
             i16::from(token.decimals),
             kind as TokenKind
         )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected()
+            == 1;
+
+        if !inserted {
+            sqlx::query!(
+                "UPDATE tokens SET address = $2, kind = $3 WHERE id = $1",
+                *token.id as i32,
+                address_to_stored_string(&token.address),
+                kind as TokenKind
+            )
+            .execute(transaction.conn())
+            .await?;
+
+            let mut tokens_schema = transaction.tokens_schema();
+            // The token is replaced, so its previous symbol is not kept as an alias.
+            let symbol_change = TokenMetadataChange::Symbol {
+                symbol: token.symbol,
+                alias_valid_until: Utc::now(),
+            };
+            tokens_schema
+                .update_token_metadata(token.id, symbol_change, source)
+                .await?;
+            tokens_schema
+                .update_token_metadata(
+                    token.id,
+                    TokenMetadataChange::Decimals(token.decimals),
+                    source,
+                )
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.store_token", start.elapsed());
+        Ok(())
+    }
+
+    /// Applies the change of the token metadata and records it in the `token_changes` changelog
+    /// within the same transaction, so every update of the token metadata must go through here.
+    /// Returns `false` if the token doesn't exist or already has the given value.
+    pub async fn update_token_metadata(
+        &mut self,
+        token_id: TokenId,
+        change: TokenMetadataChange,
+        source: &TokenChangeSource,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let token = sqlx::query!(
+            "SELECT symbol, decimals FROM tokens WHERE id = $1 FOR UPDATE",
+            *token_id as i32
+        )
+        .fetch_optional(transaction.conn())
+        .await?;
+        let token = match token {
+            Some(token) => token,
+            None => return Ok(false),
+        };
+
+        let (field, old_value, new_value) = match change {
+            TokenMetadataChange::Symbol {
+                symbol,
+                alias_valid_until,
+            } => {
+                if token.symbol == symbol {
+                    return Ok(false);
+                }
+                sqlx::query!(
+                    r#"
+                    INSERT INTO token_symbol_aliases ( token_id, symbol, valid_until )
+                    VALUES ( $1, $2, $3 )
+                    ON CONFLICT (token_id, symbol)
+                    DO
+                      UPDATE SET valid_until = $3
+                    "#,
+                    *token_id as i32,
+                    token.symbol,
+                    alias_valid_until
+                )
+                .execute(transaction.conn())
+                .await?;
+                // The token may be renamed back, the current symbol must not be an alias.
+                sqlx::query!(
+                    "DELETE FROM token_symbol_aliases WHERE token_id = $1 AND symbol = $2",
+                    *token_id as i32,
+                    symbol
+                )
+                .execute(transaction.conn())
+                .await?;
+                sqlx::query!(
+                    "UPDATE tokens SET symbol = $2 WHERE id = $1",
+                    *token_id as i32,
+                    symbol
+                )
+                .execute(transaction.conn())
+                .await?;
+                ("symbol", token.symbol, symbol)
+            }
+            TokenMetadataChange::Decimals(decimals) => {
+                if token.decimals == i16::from(decimals) {
+                    return Ok(false);
+                }
+                sqlx::query!(
+                    "UPDATE tokens SET decimals = $2 WHERE id = $1",
+                    *token_id as i32,
+                    i16::from(decimals)
+                )
+                .execute(transaction.conn())
+                .await?;
+                ("decimals", token.decimals.to_string(), decimals.to_string())
+            }
+        };
+
+        // Ids of the sequence are taken before the commit, so the concurrent changes are
+        // serialized to make the ids visible to the readers in the increasing order only.
+        sqlx::query!("LOCK TABLE token_changes IN EXCLUSIVE MODE")
+            .execute(transaction.conn())
+            .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO token_changes ( token_id, field, old_value, new_value, source )
+            VALUES ( $1, $2, $3, $4, $5 )
+            "#,
+            *token_id as i32,
+            field,
+            old_value,
+            new_value,
+            source.to_string()
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.update_token_metadata", start.elapsed());
+        Ok(true)
+    }
+
+    /// Loads the changes of the token metadata with the ids greater than `since_id`,
+    /// in the order they were made.
+    pub async fn load_token_changes(
+        &mut self,
+        since_id: u64,
+        limit: u32,
+    ) -> QueryResult<Vec<TokenChange>> {
+        let start = Instant::now();
+        let changes = sqlx::query_as!(
+            StorageTokenChange,
+            "SELECT * FROM token_changes WHERE id > $1 ORDER BY id ASC LIMIT $2",
+            since_id as i64,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(TokenChange::from)
+        .collect();
+
+        metrics::histogram!("sql.token.load_token_changes", start.elapsed());
+        Ok(changes)
+    }
+
+    /// Loads the previous symbols of the given tokens that are still accepted in lookups,
+    /// the most recent ones go first. Tokens that were never renamed are omitted.
+    pub async fn load_token_aliases(
+        &mut self,
+        token_ids: &[TokenId],
+    ) -> QueryResult<HashMap<TokenId, Vec<String>>> {
+        let start = Instant::now();
+        let token_ids: Vec<i32> = token_ids.iter().map(|id| **id as i32).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT token_id, symbol FROM token_symbol_aliases
+            WHERE token_id = ANY($1) AND valid_until > now()
+            ORDER BY token_id, valid_until DESC, symbol
+            "#,
+            &token_ids
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let mut aliases: HashMap<TokenId, Vec<String>> = HashMap::new();
+        for row in rows {
+            aliases
+                .entry(TokenId(row.token_id as u32))
+                .or_default()
+                .push(row.symbol);
+        }
+
+        metrics::histogram!("sql.token.load_token_aliases", start.elapsed());
+        Ok(aliases)
+    }
+
+    /// Records that the confirmed governance event adding the token was received.
+    /// Repeated events for the same token are ignored.
+    pub async fn record_token_listing(
+        &mut self,
+        address: Address,
+        token_id: TokenId,
+        eth_block_number: u64,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_listings (address, token_id, eth_block_number)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (address) DO NOTHING
+            "#,
+            address.as_bytes(),
+            *token_id as i32,
+            eth_block_number as i64
+        )
         .execute(self.0.conn())
         .await?;
 
-        metrics::histogram!("sql.token.store_token", start.elapsed());
+        metrics::histogram!("sql.token.record_token_listing", start.elapsed());
+        Ok(())
+    }
+
+    /// Records the result of storing the listed token: `None` if the token was stored,
+    /// otherwise the reason of the failure.
+    pub async fn record_token_listing_result(
+        &mut self,
+        address: Address,
+        store_error: Option<&str>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE token_listings
+            SET token_stored_at = CASE WHEN $2::text IS NULL THEN now() ELSE token_stored_at END,
+                store_error = $2
+            WHERE address = $1
+            "#,
+            address.as_bytes(),
+            store_error
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.record_token_listing_result", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the listing progress of the token with the given address.
+    pub async fn get_token_listing(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Option<TokenListing>> {
+        let start = Instant::now();
+        let listing = sqlx::query_as!(
+            StorageTokenListing,
+            "SELECT * FROM token_listings WHERE address = $1",
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(TokenListing::from);
+
+        metrics::histogram!("sql.token.get_token_listing", start.elapsed());
+        Ok(listing)
+    }
+
+    /// Stores the metadata queried from the token contract, replacing the previous one.
+    pub async fn store_token_metadata(
+        &mut self,
+        token_id: TokenId,
+        metadata: &TokenMetadata,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO token_metadata ( token_id, symbol, decimals )
+            VALUES ( $1, $2, $3 )
+            ON CONFLICT (token_id)
+            DO
+              UPDATE SET symbol = $2, decimals = $3, fetched_at = now()
+            "#,
+            *token_id as i32,
+            metadata.symbol,
+            metadata.decimals.map(i16::from)
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.store_token_metadata", start.elapsed());
         Ok(())
     }
 
+    /// Loads the metadata queried from the token contract, `None` if it was never queried.
+    pub async fn load_token_metadata(
+        &mut self,
+        token_id: TokenId,
+    ) -> QueryResult<Option<TokenMetadata>> {
+        let start = Instant::now();
+        let metadata = sqlx::query_as!(
+            StorageTokenMetadata,
+            "SELECT * FROM token_metadata WHERE token_id = $1",
+            *token_id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(TokenMetadata::from);
+
+        metrics::histogram!("sql.token.load_token_metadata", start.elapsed());
+        Ok(metadata)
+    }
+
+    /// Loads the ERC20 tokens stored with the placeholder symbol (`ERC20-{id}`)
+    /// whose metadata was never queried, in the ascending order of ids.
+    pub async fn load_placeholder_tokens(&mut self, limit: u32) -> QueryResult<Vec<Token>> {
+        let start = Instant::now();
+        let tokens = sqlx::query_as!(
+            DbToken,
+            r#"
+            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            WHERE kind = 'ERC20'::token_kind AND symbol = 'ERC20-' || id
+                AND NOT EXISTS (SELECT 1 FROM token_metadata WHERE token_metadata.token_id = tokens.id)
+            ORDER BY id ASC
+            LIMIT $1
+            "#,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(Token::from)
+        .collect();
+
+        metrics::histogram!("sql.token.load_placeholder_tokens", start.elapsed());
+        Ok(tokens)
+    }
+
     /// Loads tokens from the database starting from the given id with the given limit in the ascending order.
     pub async fn load_tokens_asc(
         &mut self,
@@ -179,6 +516,33 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(result)
     }
 
+    /// Loads the tokens with the given ids in a single query, the unknown ids are omitted.
+    pub async fn load_tokens_by_ids(
+        &mut self,
+        ids: &[TokenId],
+    ) -> QueryResult<HashMap<TokenId, Token>> {
+        let start = Instant::now();
+        let ids: Vec<i32> = ids.iter().map(|id| id.0 as i32).collect();
+        let tokens = sqlx::query_as!(
+            DbToken,
+            r#"
+            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            WHERE id = ANY($1)
+            "#,
+            &ids
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let result = tokens
+            .into_iter()
+            .map(Token::from)
+            .map(|token| (token.id, token))
+            .collect();
+        metrics::histogram!("sql.token.load_tokens_by_ids", start.elapsed());
+        Ok(result)
+    }
+
     /// Loads all the stored tokens from the database.
     /// Alongside with the tokens added via `store_token` method, the default `ETH` token
     /// is returned.
@@ -187,22 +551,147 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(tokens.into_iter().map(|token| (token.id, token)).collect())
     }
 
-    /// Loads tokens for the given pagination query
+    /// Loads tokens matching the filter for the given pagination query.
     pub async fn load_token_page(
         &mut self,
         query: &PaginationQuery<TokenId>,
+        filter: &TokensFilter,
     ) -> QueryResult<Vec<Token>> {
         let tokens = match query.direction {
             PaginationDirection::Newer => {
-                self.load_tokens_asc(query.from, Some(query.limit)).await?
+                self.load_filtered_tokens_asc(query.from, Some(query.limit), filter)
+                    .await?
             }
             PaginationDirection::Older => {
-                self.load_tokens_desc(query.from, Some(query.limit)).await?
+                self.load_filtered_tokens_desc(query.from, Some(query.limit), filter)
+                    .await?
             }
         };
         Ok(tokens)
     }
 
+    /// Loads tokens matching the filter starting from the given id with the given limit in the ascending order.
+    /// ETH is always considered enabled for paying fees.
+    pub async fn load_filtered_tokens_asc(
+        &mut self,
+        from: TokenId,
+        limit: Option<u32>,
+        filter: &TokensFilter,
+    ) -> QueryResult<Vec<Token>> {
+        let start = Instant::now();
+        let tokens = sqlx::query_as!(
+            DbToken,
+            r#"
+            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            WHERE id >= $1 AND kind = $2
+            AND ($3::text IS NULL OR lower(symbol) LIKE $3)
+            AND ($4::numeric IS NULL OR id = 0 OR EXISTS (
+                SELECT 1 FROM ticker_market_volume
+                INNER JOIN ticker_price
+                ON ticker_market_volume.token_id = ticker_price.token_id
+                WHERE ticker_market_volume.token_id = tokens.id
+                AND ticker_market_volume.market_volume >= $4
+                AND ticker_price.usd_price > 0
+            ))
+            ORDER BY id ASC
+            LIMIT $5
+            "#,
+            *from as i32,
+            TokenKind::from(filter.kind) as TokenKind,
+            filter.symbol_pattern(),
+            filter
+                .min_market_volume
+                .as_ref()
+                .map(|volume| ratio_to_big_decimal(volume, STORED_USD_PRICE_PRECISION)),
+            limit.map(i64::from)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let result = tokens.into_iter().map(Token::from).collect();
+        metrics::histogram!("sql.token.load_filtered_tokens_asc", start.elapsed());
+        Ok(result)
+    }
+
+    /// Loads tokens matching the filter starting from the given id with the given limit in the descending order.
+    pub async fn load_filtered_tokens_desc(
+        &mut self,
+        from: TokenId,
+        limit: Option<u32>,
+        filter: &TokensFilter,
+    ) -> QueryResult<Vec<Token>> {
+        let start = Instant::now();
+        let tokens = sqlx::query_as!(
+            DbToken,
+            r#"
+            SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
+            WHERE id <= $1 AND kind = $2
+            AND ($3::text IS NULL OR lower(symbol) LIKE $3)
+            AND ($4::numeric IS NULL OR id = 0 OR EXISTS (
+                SELECT 1 FROM ticker_market_volume
+                INNER JOIN ticker_price
+                ON ticker_market_volume.token_id = ticker_price.token_id
+                WHERE ticker_market_volume.token_id = tokens.id
+                AND ticker_market_volume.market_volume >= $4
+                AND ticker_price.usd_price > 0
+            ))
+            ORDER BY id DESC
+            LIMIT $5
+            "#,
+            *from as i32,
+            TokenKind::from(filter.kind) as TokenKind,
+            filter.symbol_pattern(),
+            filter
+                .min_market_volume
+                .as_ref()
+                .map(|volume| ratio_to_big_decimal(volume, STORED_USD_PRICE_PRECISION)),
+            limit.map(i64::from)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let result = tokens.into_iter().map(Token::from).collect();
+        metrics::histogram!("sql.token.load_filtered_tokens_desc", start.elapsed());
+        Ok(result)
+    }
+
+    /// Returns the number of tokens matching the filter and the largest id among them.
+    pub async fn get_filtered_count_and_max_id(
+        &mut self,
+        filter: &TokensFilter,
+    ) -> QueryResult<(u32, Option<TokenId>)> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!", MAX(id) as "max_id" FROM tokens
+            WHERE kind = $1
+            AND ($2::text IS NULL OR lower(symbol) LIKE $2)
+            AND ($3::numeric IS NULL OR id = 0 OR EXISTS (
+                SELECT 1 FROM ticker_market_volume
+                INNER JOIN ticker_price
+                ON ticker_market_volume.token_id = ticker_price.token_id
+                WHERE ticker_market_volume.token_id = tokens.id
+                AND ticker_market_volume.market_volume >= $3
+                AND ticker_price.usd_price > 0
+            ))
+            "#,
+            TokenKind::from(filter.kind) as TokenKind,
+            filter.symbol_pattern(),
+            filter
+                .min_market_volume
+                .as_ref()
+                .map(|volume| ratio_to_big_decimal(volume, STORED_USD_PRICE_PRECISION))
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.get_filtered_count_and_max_id", start.elapsed());
+        Ok((
+            record.count as u32,
+            record.max_id.map(|id| TokenId(id as u32)),
+        ))
+    }
+
     /// Loads all finalized NFTs.
     pub async fn load_nfts(&mut self) -> QueryResult<HashMap<TokenId, NFT>> {
         let start = Instant::now();
@@ -409,6 +898,7 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     }
 
     /// Given the numeric token ID, symbol or address, returns token.
+    #[tracing::instrument(name = "sql.token.get_token", skip(self))]
     pub async fn get_token(&mut self, token_like: TokenLike) -> QueryResult<Option<Token>> {
         let start = Instant::now();
 
@@ -446,17 +936,39 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
                 // 1) don't have too much tokens.
                 // 2) most tokens requests will be handled by `TokenDbCache` anyway,
                 // so it shouldn't be a problem.
-                sqlx::query_as!(
+                let db_token = sqlx::query_as!(
                     DbToken,
                     r#"
                     SELECT id, address, decimals, kind as "kind: _", symbol FROM tokens
                     WHERE lower(symbol) = lower($1)
                     LIMIT 1
                     "#,
-                    token_symbol
+                    &token_symbol
                 )
                 .fetch_optional(self.0.conn())
-                .await?
+                .await?;
+                // The token may have been renamed, so previous symbols are checked
+                // if no token currently has the requested one.
+                match db_token {
+                    Some(db_token) => Some(db_token),
+                    None => {
+                        sqlx::query_as!(
+                            DbToken,
+                            r#"
+                            SELECT tokens.id, tokens.address, tokens.decimals, tokens.kind as "kind: _", tokens.symbol
+                            FROM token_symbol_aliases
+                            INNER JOIN tokens ON tokens.id = token_symbol_aliases.token_id
+                            WHERE lower(token_symbol_aliases.symbol) = lower($1)
+                                AND token_symbol_aliases.valid_until > now()
+                            ORDER BY token_symbol_aliases.valid_until DESC
+                            LIMIT 1
+                            "#,
+                            token_symbol
+                        )
+                        .fetch_optional(self.0.conn())
+                        .await?
+                    }
+                }
             }
         };
 
@@ -515,6 +1027,70 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Returns the maximum age of the token price set by the operator, `None` if the default
+    /// of the token liquidity tier is used.
+    pub async fn get_price_max_age(&mut self, token_id: TokenId) -> QueryResult<Option<u64>> {
+        let start = Instant::now();
+        let max_age_secs = sqlx::query!(
+            "SELECT max_age_secs FROM token_price_max_age WHERE token_id = $1",
+            *token_id as i32
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|record| record.max_age_secs as u64);
+
+        metrics::histogram!("sql.token.get_price_max_age", start.elapsed());
+        Ok(max_age_secs)
+    }
+
+    /// Sets the maximum age of the token price, `None` restores the default of the token
+    /// liquidity tier. Returns `false` if the value was already set.
+    pub async fn set_price_max_age(
+        &mut self,
+        token_id: TokenId,
+        max_age_secs: Option<u64>,
+        actor: &str,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let changed = TokensSchema(&mut transaction)
+            .get_price_max_age(token_id)
+            .await?
+            != max_age_secs;
+        if changed {
+            match max_age_secs {
+                Some(max_age_secs) => {
+                    sqlx::query!(
+                        r#"
+                        INSERT INTO token_price_max_age (token_id, max_age_secs, changed_by)
+                        VALUES ($1, $2, $3)
+                        ON CONFLICT (token_id) DO UPDATE
+                        SET max_age_secs = $2, changed_by = $3, changed_at = now()
+                        "#,
+                        *token_id as i32,
+                        max_age_secs as i64,
+                        actor
+                    )
+                    .execute(transaction.conn())
+                    .await?;
+                }
+                None => {
+                    sqlx::query!(
+                        "DELETE FROM token_price_max_age WHERE token_id = $1",
+                        *token_id as i32
+                    )
+                    .execute(transaction.conn())
+                    .await?;
+                }
+            }
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.set_price_max_age", start.elapsed());
+        Ok(changed)
+    }
+
     /// Given token id, returns its price in USD and a timestamp of the last update.
     pub async fn get_historical_ticker_price(
         &mut self,
@@ -537,6 +1113,34 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         Ok(db_price.map(|p| p.into()))
     }
 
+    /// Given token ids, returns their prices in USD and timestamps of the last updates.
+    /// Tokens without a stored price are omitted.
+    pub async fn get_historical_ticker_prices(
+        &mut self,
+        token_ids: &[TokenId],
+    ) -> QueryResult<HashMap<TokenId, TokenPrice>> {
+        let start = Instant::now();
+        let token_ids: Vec<i32> = token_ids.iter().map(|id| **id as i32).collect();
+        let db_prices = sqlx::query_as!(
+            DbTickerPrice,
+            r#"
+            SELECT * FROM ticker_price
+            WHERE token_id = ANY($1)
+            "#,
+            &token_ids
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        let prices = db_prices
+            .into_iter()
+            .map(|price| (TokenId(price.token_id as u32), price.into()))
+            .collect();
+
+        metrics::histogram!("sql.token.get_historical_ticker_prices", start.elapsed());
+        Ok(prices)
+    }
+
     /// Updates price in USD for the given token.
     ///
     /// Note, that the price precision cannot be greater than `STORED_USD_PRICE_PRECISION`,
@@ -592,4 +1196,75 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         metrics::histogram!("sql.token.store_nft_factory", start.elapsed());
         Ok(())
     }
+
+    /// Recalculates the `limit` largest holders of every ERC20 token from the current balances.
+    /// NFTs are skipped, since every NFT has a single holder.
+    pub async fn refresh_top_holders(&mut self, limit: u32) -> QueryResult<DateTime<Utc>> {
+        let start = Instant::now();
+        // Truncated to the precision of the stored timestamps, so the returned time matches the stored one.
+        let refreshed_at = Utc::now().trunc_subsecs(6);
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!("DELETE FROM token_top_holders")
+            .execute(transaction.conn())
+            .await?;
+        sqlx::query!(
+            r#"
+            INSERT INTO token_top_holders (token_id, rank, account_id, address, balance, refreshed_at)
+            SELECT ranked.coin_id, ranked.rank, ranked.account_id, accounts.address, ranked.balance, $3
+            FROM (
+                SELECT
+                    coin_id, account_id, balance,
+                    row_number() OVER (PARTITION BY coin_id ORDER BY balance DESC, account_id ASC) AS rank
+                FROM balances
+                WHERE coin_id < $1 AND balance > 0
+            ) AS ranked
+            INNER JOIN accounts ON accounts.id = ranked.account_id
+            WHERE ranked.rank <= $2
+            "#,
+            MIN_NFT_TOKEN_ID as i32,
+            i64::from(limit),
+            refreshed_at
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.token.refresh_top_holders", start.elapsed());
+        Ok(refreshed_at)
+    }
+
+    /// Loads up to `limit` largest holders of the token along with the time of their recalculation,
+    /// which is `None` if the holders were never calculated.
+    pub async fn load_top_holders(
+        &mut self,
+        token_id: TokenId,
+        limit: u32,
+    ) -> QueryResult<(Vec<StorageTokenHolder>, Option<DateTime<Utc>>)> {
+        let start = Instant::now();
+        let holders = sqlx::query_as!(
+            StorageTokenHolder,
+            r#"
+            SELECT * FROM token_top_holders
+            WHERE token_id = $1 AND rank <= $2
+            ORDER BY rank ASC
+            "#,
+            *token_id as i32,
+            limit as i32
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+        // All the holders are recalculated at once, so the time is shared by all the tokens.
+        let refreshed_at = match holders.first() {
+            Some(holder) => Some(holder.refreshed_at),
+            None => {
+                sqlx::query_scalar!("SELECT refreshed_at FROM token_top_holders LIMIT 1")
+                    .fetch_optional(self.0.conn())
+                    .await?
+            }
+        };
+
+        metrics::histogram!("sql.token.load_top_holders", start.elapsed());
+        Ok((holders, refreshed_at))
+    }
 }
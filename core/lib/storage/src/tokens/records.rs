@@ -1,13 +1,15 @@
 // Built-in imports
+use std::fmt;
 use std::str::FromStr;
 // External imports
+use num::{bigint::ToBigInt, rational::Ratio, BigUint};
 use serde::{Deserialize, Serialize};
 use sqlx::{types::BigDecimal, FromRow};
 // Workspace imports
 // Local imports
 use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
-use zksync_api_types::v02::token::ApiNFT;
+use zksync_api_types::v02::token::{ApiNFT, TokenChange, TokenHolder, TokenMetadataField};
 use zksync_types::{
     tokens::{TokenMarketVolume, TokenPrice},
     AccountId, Address, Token, TokenId, H256, NFT,
@@ -168,3 +170,183 @@ impl From<DBMarketVolume> for TokenMarketVolume {
         }
     }
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageTokenListing {
+    pub address: Vec<u8>,
+    pub token_id: i32,
+    pub eth_block_number: i64,
+    pub event_processed_at: DateTime<Utc>,
+    pub token_stored_at: Option<DateTime<Utc>>,
+    pub store_error: Option<String>,
+}
+
+/// Progress of adding the token to the network recorded by the token handler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenListing {
+    pub address: Address,
+    /// Id assigned to the token by the governance contract.
+    pub token_id: TokenId,
+    /// Number of the Ethereum block containing the event that added the token.
+    pub eth_block_number: u64,
+    /// Time the confirmed governance event was processed by the token handler.
+    pub event_processed_at: DateTime<Utc>,
+    /// Time the token was stored to the `tokens` table.
+    pub token_stored_at: Option<DateTime<Utc>>,
+    /// Reason the token could not be stored, if any.
+    pub store_error: Option<String>,
+}
+
+impl From<StorageTokenListing> for TokenListing {
+    fn from(value: StorageTokenListing) -> Self {
+        Self {
+            address: Address::from_slice(&value.address),
+            token_id: TokenId(value.token_id as u32),
+            eth_block_number: value.eth_block_number as u64,
+            event_processed_at: value.event_processed_at,
+            token_stored_at: value.token_stored_at,
+            store_error: value.store_error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageTokenMetadata {
+    pub token_id: i32,
+    pub symbol: Option<String>,
+    pub decimals: Option<i16>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Metadata of the token queried from its contract.
+/// `None` means that the contract failed to return the value.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TokenMetadata {
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+impl From<StorageTokenMetadata> for TokenMetadata {
+    fn from(value: StorageTokenMetadata) -> Self {
+        Self {
+            symbol: value.symbol,
+            decimals: value.decimals.map(|decimals| decimals as u8),
+        }
+    }
+}
+
+/// Change of the token metadata, see `TokensSchema::update_token_metadata`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenMetadataChange {
+    /// The previous symbol is kept as an alias of the token until `alias_valid_until`.
+    Symbol {
+        symbol: String,
+        alias_valid_until: DateTime<Utc>,
+    },
+    Decimals(u8),
+}
+
+/// Origin of the token metadata change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenChangeSource {
+    /// The metadata was queried from the token contract by the token handler.
+    TokenHandler,
+    /// The change was made via the admin API, the author is the subject of the auth token.
+    Admin(String),
+}
+
+impl fmt::Display for TokenChangeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TokenHandler => write!(f, "tokenHandler"),
+            Self::Admin(author) => write!(f, "admin:{}", author),
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageTokenChange {
+    pub id: i64,
+    pub token_id: i32,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub source: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl From<StorageTokenChange> for TokenChange {
+    fn from(val: StorageTokenChange) -> Self {
+        let field = match val.field.as_str() {
+            "symbol" => TokenMetadataField::Symbol,
+            "decimals" => TokenMetadataField::Decimals,
+            field => panic!("Unknown token metadata field: {}", field),
+        };
+        Self {
+            id: val.id as u64,
+            token_id: TokenId(val.token_id as u32),
+            field,
+            old_value: val.old_value,
+            new_value: val.new_value,
+            source: val.source,
+            changed_at: val.changed_at,
+        }
+    }
+}
+
+/// Filters of the tokens listing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokensFilter {
+    pub kind: zksync_types::TokenKind,
+    /// Only the tokens enabled for paying fees, i.e. having at least this market volume, are listed.
+    pub min_market_volume: Option<Ratio<BigUint>>,
+    /// Case-insensitive prefix of the token symbol.
+    pub symbol_prefix: Option<String>,
+}
+
+impl TokensFilter {
+    pub fn new(kind: zksync_types::TokenKind) -> Self {
+        Self {
+            kind,
+            min_market_volume: None,
+            symbol_prefix: None,
+        }
+    }
+
+    /// Returns the `LIKE` pattern matching the symbols with the prefix, wildcards in the prefix are escaped.
+    pub(crate) fn symbol_pattern(&self) -> Option<String> {
+        self.symbol_prefix.as_ref().map(|prefix| {
+            let mut pattern = String::with_capacity(prefix.len() + 1);
+            for ch in prefix.to_lowercase().chars() {
+                if matches!(ch, '\\' | '%' | '_') {
+                    pattern.push('\\');
+                }
+                pattern.push(ch);
+            }
+            pattern.push('%');
+            pattern
+        })
+    }
+}
+
+/// One of the largest holders of the token, recalculated periodically.
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageTokenHolder {
+    pub token_id: i32,
+    pub rank: i32,
+    pub account_id: i64,
+    pub address: Vec<u8>,
+    pub balance: BigDecimal,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+impl From<StorageTokenHolder> for TokenHolder {
+    fn from(val: StorageTokenHolder) -> Self {
+        Self {
+            rank: val.rank as u32,
+            account_id: AccountId(val.account_id as u32),
+            address: Address::from_slice(&val.address),
+            balance: val.balance.to_bigint().unwrap().to_biguint().unwrap(),
+        }
+    }
+}
@@ -1,5 +1,12 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{de::Error as _, Deserialize, Serialize};
+
 use crate::{QueryResult, StorageProcessor};
-use zksync_types::{Address, ZkSyncTx};
+use zksync_types::{
+    tx::{EthSignData, TxEthSignature},
+    Address, SignedZkSyncTx, Token, TokenId, TokenKind, ZkSyncTx,
+};
 
 pub(crate) fn address_to_stored_string(address: &Address) -> String {
     format!("0x{:x}", address)
@@ -58,9 +65,186 @@ pub(crate) async fn affected_accounts(
     Ok(accounts)
 }
 
+/// Ethereum signature data in the compact format the new rows are stored in.
+///
+/// The signed message takes most of the space and is derivable from the transaction, so it's
+/// reconstructed when the transaction is loaded. The token symbol and decimals the message was
+/// constructed with are kept, since the token metadata may be refetched after the transaction is signed.
+/// The rows in the legacy format contain the message and are read as is.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompactEthSignData {
+    signature: TxEthSignature,
+    token_symbol: String,
+    token_decimals: u8,
+}
+
+/// Encodes the Ethereum signature data of the transaction to be stored. The compact format is used
+/// if the message is the one constructed from the transaction and its token, otherwise the data
+/// is stored in the legacy format.
+pub(crate) fn encode_eth_sign_data(
+    tx: &ZkSyncTx,
+    sign_data: &EthSignData,
+    token: Option<&Token>,
+) -> serde_json::Value {
+    if let Some(token) = token {
+        let message = tx.get_ethereum_sign_message(token.clone());
+        if message.as_ref().map(String::as_bytes) == Some(sign_data.message.as_slice()) {
+            let compact = CompactEthSignData {
+                signature: sign_data.signature.clone(),
+                token_symbol: token.symbol.clone(),
+                token_decimals: token.decimals,
+            };
+            return serde_json::to_value(compact).expect("failed to encode EthSignData");
+        }
+    }
+    serde_json::to_value(sign_data).expect("failed to encode EthSignData")
+}
+
+/// Decodes the stored Ethereum signature data of the transaction in either format.
+pub(crate) fn decode_eth_sign_data(
+    tx: &ZkSyncTx,
+    value: serde_json::Value,
+) -> serde_json::Result<EthSignData> {
+    if value.get("message").is_some() {
+        return serde_json::from_value(value);
+    }
+
+    let compact: CompactEthSignData = serde_json::from_value(value)?;
+    // Only the symbol and decimals of the token are used to construct the message.
+    let token = Token::new(
+        tx.token_id(),
+        Address::zero(),
+        &compact.token_symbol,
+        compact.token_decimals,
+        TokenKind::ERC20,
+    );
+    let message = tx.get_ethereum_sign_message(token).ok_or_else(|| {
+        serde_json::Error::custom(format!(
+            "transaction {} has no Ethereum message to sign",
+            tx.hash()
+        ))
+    })?;
+    Ok(EthSignData {
+        signature: compact.signature,
+        message: message.into_bytes(),
+    })
+}
+
+/// Decodes the signature of the stored Ethereum signature data, the signature is stored the same way
+/// in both formats.
+pub(crate) fn decode_eth_signature(value: serde_json::Value) -> serde_json::Result<TxEthSignature> {
+    #[derive(Deserialize)]
+    struct StoredSignature {
+        signature: TxEthSignature,
+    }
+
+    serde_json::from_value::<StoredSignature>(value).map(|stored| stored.signature)
+}
+
+/// Tokens the Ethereum messages of the transactions stored together (e.g. the ones of a block
+/// or a batch) are constructed with, so the Ethereum signature data of the transactions is
+/// encoded without querying the token of every transaction.
+#[derive(Debug, Default)]
+pub(crate) struct EthSignTokens(HashMap<TokenId, Token>);
+
+impl EthSignTokens {
+    /// Loads the tokens of the transactions with the Ethereum signature data in a single query.
+    pub async fn load<'a>(
+        storage: &mut StorageProcessor<'_>,
+        txs: impl IntoIterator<Item = &'a SignedZkSyncTx>,
+    ) -> QueryResult<Self> {
+        let ids: HashSet<TokenId> = txs
+            .into_iter()
+            .filter(|tx| tx.eth_sign_data.is_some())
+            .map(|tx| tx.tx.token_id())
+            .collect();
+        if ids.is_empty() {
+            return Ok(Self::default());
+        }
+        let ids: Vec<TokenId> = ids.into_iter().collect();
+        let tokens = storage.tokens_schema().load_tokens_by_ids(&ids).await?;
+        Ok(Self(tokens))
+    }
+
+    /// Encodes the Ethereum signature data of the transaction to be stored, the token of
+    /// the transaction is expected to be loaded.
+    pub fn eth_sign_data_to_store(&self, tx: &SignedZkSyncTx) -> Option<serde_json::Value> {
+        let sign_data = tx.eth_sign_data.as_ref()?;
+        let token = self.0.get(&tx.tx.token_id());
+        Some(encode_eth_sign_data(&tx.tx, sign_data, token))
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use super::*;
+    use zksync_types::{
+        tx::{PackedEthSignature, Transfer},
+        AccountId, Nonce, TokenId, H256,
+    };
+
+    fn signed_transfer() -> (ZkSyncTx, EthSignData, Token) {
+        let transfer = Transfer::new(
+            AccountId(42),
+            Address::repeat_byte(0x11),
+            Address::repeat_byte(0x22),
+            TokenId(1),
+            100u32.into(),
+            10u32.into(),
+            Nonce(10),
+            Default::default(),
+            None,
+        );
+        let tx = ZkSyncTx::Transfer(Box::new(transfer));
+        let token = Token::new(
+            TokenId(1),
+            Address::repeat_byte(0x33),
+            "DAI",
+            18,
+            TokenKind::ERC20,
+        );
+        let message = tx.get_ethereum_sign_message(token.clone()).unwrap();
+        let signature =
+            PackedEthSignature::sign(&H256::repeat_byte(0x44), message.as_bytes()).unwrap();
+        let sign_data = EthSignData {
+            signature: TxEthSignature::EthereumSignature(signature),
+            message: message.into_bytes(),
+        };
+        (tx, sign_data, token)
+    }
+
+    #[test]
+    fn eth_sign_data_formats() {
+        let (tx, sign_data, token) = signed_transfer();
+
+        // The message is omitted and reconstructed from the transaction.
+        let compact = encode_eth_sign_data(&tx, &sign_data, Some(&token));
+        assert!(compact.get("message").is_none());
+        assert_eq!(compact["tokenSymbol"], "DAI");
+        assert_eq!(
+            decode_eth_sign_data(&tx, compact.clone()).unwrap(),
+            sign_data
+        );
+        assert_eq!(decode_eth_signature(compact).unwrap(), sign_data.signature);
+
+        // The legacy rows and the messages that can't be reconstructed are stored as is.
+        let legacy = serde_json::to_value(&sign_data).unwrap();
+        assert_eq!(encode_eth_sign_data(&tx, &sign_data, None), legacy);
+        let other_message = EthSignData {
+            signature: sign_data.signature.clone(),
+            message: b"other message".to_vec(),
+        };
+        assert_eq!(
+            encode_eth_sign_data(&tx, &other_message, Some(&token)),
+            serde_json::to_value(&other_message).unwrap()
+        );
+        assert_eq!(
+            decode_eth_sign_data(&tx, legacy.clone()).unwrap(),
+            sign_data
+        );
+        assert_eq!(decode_eth_signature(legacy).unwrap(), sign_data.signature);
+    }
 
     #[test]
     fn address_store_roundtrip() {
@@ -0,0 +1,266 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+// Workspace imports
+use zksync_types::{event::EventId, Address};
+// Local imports
+use self::records::{
+    NewWebhookDelivery, StorageWebhookDelivery, StorageWebhookSubscription, WebhookDelivery,
+    WebhookSubscription,
+};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Schema for the webhook subscriptions delivering the transaction events of the accounts.
+///
+/// Every subscription keeps its own cursor in the events journal. Dispatchers claim the due
+/// subscriptions for a limited time, so several API servers may dispatch the events at once,
+/// while the subscription abandoned by a failed dispatcher is picked up once its claim expires.
+/// Every delivery attempt is logged along with the sent body, so the subscriber can inspect
+/// the deliveries and have the event sent again.
+#[derive(Debug)]
+pub struct WebhooksSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> WebhooksSchema<'a, 'c> {
+    /// Stores the subscription, only the events stored afterwards are delivered to it.
+    pub async fn create_subscription(
+        &mut self,
+        address: Address,
+        url: &str,
+        secret: &str,
+        preferences: &Value,
+    ) -> QueryResult<WebhookSubscription> {
+        let start = Instant::now();
+        let subscription = sqlx::query_as!(
+            StorageWebhookSubscription,
+            r#"
+            INSERT INTO webhook_subscriptions (address, url, secret, preferences, last_event_id)
+            VALUES ($1, $2, $3, $4, (SELECT COALESCE(MAX(id), 0) FROM events))
+            RETURNING *
+            "#,
+            address.as_bytes(),
+            url,
+            secret,
+            preferences
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .into();
+
+        metrics::histogram!("sql.webhooks.create_subscription", start.elapsed());
+        Ok(subscription)
+    }
+
+    pub async fn load_subscription(&mut self, id: i64) -> QueryResult<Option<WebhookSubscription>> {
+        let start = Instant::now();
+        let subscription = sqlx::query_as!(
+            StorageWebhookSubscription,
+            "SELECT * FROM webhook_subscriptions WHERE id = $1",
+            id
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(WebhookSubscription::from);
+
+        metrics::histogram!("sql.webhooks.load_subscription", start.elapsed());
+        Ok(subscription)
+    }
+
+    /// Replaces the preferences of the subscription, they apply to the events not dispatched yet.
+    /// Returns `false` if the subscription doesn't exist.
+    pub async fn update_preferences(&mut self, id: i64, preferences: &Value) -> QueryResult<bool> {
+        let start = Instant::now();
+        let updated = sqlx::query!(
+            "UPDATE webhook_subscriptions SET preferences = $2 WHERE id = $1",
+            id,
+            preferences
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.webhooks.update_preferences", start.elapsed());
+        Ok(updated)
+    }
+
+    /// Removes the subscription. Returns `false` if it doesn't exist.
+    pub async fn remove_subscription(&mut self, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let removed = sqlx::query!("DELETE FROM webhook_subscriptions WHERE id = $1", id)
+            .execute(self.0.conn())
+            .await?
+            .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.webhooks.remove_subscription", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Claims the subscription due for the dispatch until `claimed_until`, so other dispatchers
+    /// skip it meanwhile. The subscription waiting for the dispatch the longest is claimed first.
+    pub async fn claim_due_subscription(
+        &mut self,
+        claimed_until: DateTime<Utc>,
+    ) -> QueryResult<Option<WebhookSubscription>> {
+        let start = Instant::now();
+        let subscription = sqlx::query_as!(
+            StorageWebhookSubscription,
+            r#"
+            UPDATE webhook_subscriptions SET next_attempt_at = $1
+            WHERE id = (
+                SELECT id FROM webhook_subscriptions
+                WHERE next_attempt_at <= now()
+                ORDER BY next_attempt_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+            claimed_until
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(WebhookSubscription::from);
+
+        metrics::histogram!("sql.webhooks.claim_due_subscription", start.elapsed());
+        Ok(subscription)
+    }
+
+    /// Releases the claimed subscription: moves its cursor to `last_event_id` and schedules
+    /// the next dispatch at `next_attempt_at`.
+    pub async fn release_subscription(
+        &mut self,
+        id: i64,
+        last_event_id: EventId,
+        failed_attempts: u32,
+        next_attempt_at: DateTime<Utc>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            UPDATE webhook_subscriptions
+            SET last_event_id = $2, failed_attempts = $3, next_attempt_at = $4
+            WHERE id = $1
+            "#,
+            id,
+            *last_event_id as i64,
+            failed_attempts as i32,
+            next_attempt_at
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.release_subscription", start.elapsed());
+        Ok(())
+    }
+
+    /// Logs the attempt to deliver the event.
+    pub async fn store_delivery(
+        &mut self,
+        delivery: NewWebhookDelivery<'_>,
+    ) -> QueryResult<WebhookDelivery> {
+        let start = Instant::now();
+        let delivery = sqlx::query_as!(
+            StorageWebhookDelivery,
+            r#"
+            INSERT INTO webhook_deliveries
+                (subscription_id, event_id, payload, body_hash, response_code, latency_ms, replay)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+            delivery.subscription_id,
+            *delivery.event_id as i64,
+            delivery.payload,
+            delivery.body_hash,
+            delivery.response_code.map(i32::from),
+            delivery.latency.as_millis() as i64,
+            delivery.replay
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .into();
+
+        metrics::histogram!("sql.webhooks.store_delivery", start.elapsed());
+        Ok(delivery)
+    }
+
+    /// Loads the delivery attempts of the events starting with `from`, in the order of the events
+    /// and the attempts.
+    pub async fn load_deliveries(
+        &mut self,
+        subscription_id: i64,
+        from: EventId,
+        limit: u32,
+    ) -> QueryResult<Vec<WebhookDelivery>> {
+        let start = Instant::now();
+        let deliveries = sqlx::query_as!(
+            StorageWebhookDelivery,
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE subscription_id = $1 AND event_id >= $2
+            ORDER BY event_id, id
+            LIMIT $3
+            "#,
+            subscription_id,
+            *from as i64,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(WebhookDelivery::from)
+        .collect();
+
+        metrics::histogram!("sql.webhooks.load_deliveries", start.elapsed());
+        Ok(deliveries)
+    }
+
+    /// Loads the body of the first attempt to deliver the event, `None` if the event was never
+    /// delivered to the subscription or its attempts are already removed.
+    pub async fn load_delivered_payload(
+        &mut self,
+        subscription_id: i64,
+        event_id: EventId,
+    ) -> QueryResult<Option<Vec<u8>>> {
+        let start = Instant::now();
+        let payload = sqlx::query!(
+            r#"
+            SELECT payload FROM webhook_deliveries
+            WHERE subscription_id = $1 AND event_id = $2 AND NOT replay
+            ORDER BY id
+            LIMIT 1
+            "#,
+            subscription_id,
+            *event_id as i64
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.payload);
+
+        metrics::histogram!("sql.webhooks.load_delivered_payload", start.elapsed());
+        Ok(payload)
+    }
+
+    /// Removes the delivery attempts made before the given moment.
+    /// Returns the number of removed attempts.
+    pub async fn remove_deliveries_older_than(
+        &mut self,
+        timestamp: DateTime<Utc>,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM webhook_deliveries WHERE created_at < $1",
+            timestamp
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected();
+
+        metrics::histogram!("sql.webhooks.remove_deliveries_older_than", start.elapsed());
+        Ok(removed)
+    }
+}
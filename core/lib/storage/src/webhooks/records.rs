@@ -0,0 +1,114 @@
+// Built-in imports
+use std::time::Duration;
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+use zksync_types::{event::EventId, Address};
+// Local imports
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageWebhookSubscription {
+    pub id: i64,
+    pub address: Vec<u8>,
+    pub url: String,
+    pub secret: String,
+    pub preferences: Value,
+    pub last_event_id: i64,
+    pub failed_attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Subscription delivering the transaction events of a single account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookSubscription {
+    pub id: i64,
+    /// Account the events are delivered for.
+    pub address: Address,
+    pub url: String,
+    pub secret: String,
+    /// Serialized preferences, the storage doesn't interpret them.
+    pub preferences: Value,
+    /// Events up to this one are either delivered, filtered out or given up on.
+    pub last_event_id: EventId,
+    /// Failed attempts to deliver the event following `last_event_id`.
+    pub failed_attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StorageWebhookSubscription> for WebhookSubscription {
+    fn from(value: StorageWebhookSubscription) -> Self {
+        Self {
+            id: value.id,
+            address: Address::from_slice(&value.address),
+            url: value.url,
+            secret: value.secret,
+            preferences: value.preferences,
+            last_event_id: EventId(value.last_event_id as u64),
+            failed_attempts: value.failed_attempts as u32,
+            next_attempt_at: value.next_attempt_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow, PartialEq)]
+pub struct StorageWebhookDelivery {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub event_id: i64,
+    pub payload: Vec<u8>,
+    pub body_hash: Vec<u8>,
+    pub response_code: Option<i32>,
+    pub latency_ms: i64,
+    pub replay: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Attempt to deliver the event to the subscription.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub event_id: EventId,
+    /// Exact body of the request.
+    pub payload: Vec<u8>,
+    pub body_hash: Vec<u8>,
+    /// HTTP status of the response, `None` if there was no response.
+    pub response_code: Option<u16>,
+    pub latency: Duration,
+    /// Whether the event was sent again on the subscriber's request.
+    pub replay: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StorageWebhookDelivery> for WebhookDelivery {
+    fn from(value: StorageWebhookDelivery) -> Self {
+        Self {
+            id: value.id,
+            subscription_id: value.subscription_id,
+            event_id: EventId(value.event_id as u64),
+            payload: value.payload,
+            body_hash: value.body_hash,
+            response_code: value.response_code.map(|code| code as u16),
+            latency: Duration::from_millis(value.latency_ms as u64),
+            replay: value.replay,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// Delivery attempt to be stored.
+#[derive(Debug, Clone, Copy)]
+pub struct NewWebhookDelivery<'a> {
+    pub subscription_id: i64,
+    pub event_id: EventId,
+    pub payload: &'a [u8],
+    pub body_hash: &'a [u8],
+    pub response_code: Option<u16>,
+    pub latency: Duration,
+    pub replay: bool,
+}
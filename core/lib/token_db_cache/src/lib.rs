@@ -112,7 +112,21 @@ impl TokenDBCache {
 
     pub async fn fill_token_cache(&mut self, storage: &mut StorageProcessor<'_>) {
         let tokens = Self::get_all_tokens(storage).await.unwrap();
+        let token_ids: Vec<TokenId> = tokens.iter().map(|token| token.id).collect();
+        let aliases = storage
+            .tokens_schema()
+            .load_token_aliases(&token_ids)
+            .await
+            .unwrap();
         let mut cache = self.cache.write().await;
+        // Renamed tokens are accessible by their previous symbols as well. Aliases are
+        // inserted first, so they can't shadow the current symbol of another token.
+        for token in &tokens {
+            for alias in aliases.get(&token.id).into_iter().flatten() {
+                let alias = TokenLike::Symbol(alias.clone());
+                cache.insert(alias.to_lowercase(), (token.clone(), Instant::now()));
+            }
+        }
         for token in tokens {
             let symbol = TokenLike::Symbol(token.symbol.clone());
             let token_id = TokenLike::Id(token.id);
@@ -29,7 +29,7 @@ pub enum EventData {
 pub struct ZkSyncEvent {
     // Id of the event. This value is equal to
     // the id of the corresponding row in the database.
-    #[serde(skip)]
+    // Clients may use it to replay the events missed after reconnecting.
     pub id: EventId,
     pub block_number: BlockNumber,
     #[serde(flatten)]
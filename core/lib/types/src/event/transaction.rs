@@ -1,4 +1,5 @@
 // Built-in uses
+use std::{fmt, str::FromStr};
 // External uses
 use chrono::{DateTime, Utc};
 use once_cell::sync::OnceCell;
@@ -6,7 +7,7 @@ use serde::{Deserialize, Serialize};
 // Workspace uses
 // Local uses
 use super::account::AccountStateChangeStatus;
-use crate::{block::ExecutedOperations, AccountId, BlockNumber, TokenId};
+use crate::{block::ExecutedOperations, AccountId, BlockNumber, TokenId, ZkSyncTx};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -19,7 +20,7 @@ pub enum TransactionStatus {
 
 /// All possible types of operations in the zkSync network.
 /// Deserialized from the `tx` field of the [TransactionEvent].
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TransactionType {
     Transfer,
     Withdraw,
@@ -32,6 +33,50 @@ pub enum TransactionType {
     Deposit,
 }
 
+impl TransactionType {
+    /// Types of the transactions that can be submitted to the zkSync network via API.
+    pub const L2_TYPES: [TransactionType; 7] = [
+        Self::Transfer,
+        Self::Withdraw,
+        Self::WithdrawNFT,
+        Self::MintNFT,
+        Self::Swap,
+        Self::ChangePubKey,
+        Self::ForcedExit,
+    ];
+
+    /// Returns the type of the L2 transaction.
+    ///
+    /// Returns [`None`] for [close](crate::tx::Close) operation.
+    pub fn from_l2_tx(tx: &ZkSyncTx) -> Option<Self> {
+        match tx {
+            ZkSyncTx::Transfer(_) => Some(Self::Transfer),
+            ZkSyncTx::Withdraw(_) => Some(Self::Withdraw),
+            ZkSyncTx::WithdrawNFT(_) => Some(Self::WithdrawNFT),
+            ZkSyncTx::MintNFT(_) => Some(Self::MintNFT),
+            ZkSyncTx::Swap(_) => Some(Self::Swap),
+            ZkSyncTx::ChangePubKey(_) => Some(Self::ChangePubKey),
+            ZkSyncTx::ForcedExit(_) => Some(Self::ForcedExit),
+            ZkSyncTx::Close(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl FromStr for TransactionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_string()))
+            .map_err(|_| format!("Unknown transaction type: {}", s))
+    }
+}
+
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionEvent {
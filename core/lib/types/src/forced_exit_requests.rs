@@ -47,6 +47,8 @@ pub struct FundsReceivedEvent {
 #[derive(Serialize, Deserialize)]
 pub struct ForcedExitEligibilityResponse {
     pub eligible: bool,
+    /// Address of the checked account, useful if it was requested by the account id.
+    pub target: Address,
 }
 
 impl TryFrom<Log> for FundsReceivedEvent {
@@ -5,7 +5,7 @@
 // Workspace deps
 use zksync_basic_types::*;
 // Local deps
-use crate::{Block, ZkSyncOp};
+use crate::{aggregated_operations::AggregatedOperation, Block, ZkSyncOp};
 
 // HACK: hardcode some configuration options for now.
 const MAX_WITHDRAWALS_TO_COMPLETE_IN_A_CALL: u64 = 20;
@@ -268,6 +268,23 @@ impl GasCounter {
         Self::scale_up(approx_limit)
     }
 
+    /// Returns the gas limit for the transaction sending the aggregated operation to L1,
+    /// or `None` if the operation is not sent to L1 on its own.
+    pub fn aggregated_op_gas_limit(op: &AggregatedOperation) -> Option<U256> {
+        match op {
+            AggregatedOperation::CommitBlocks(commit) => {
+                Some(Self::commit_gas_limit_aggregated(&commit.blocks))
+            }
+            AggregatedOperation::ExecuteBlocks(execute) => {
+                Some(Self::execute_gas_limit_aggregated(&execute.blocks))
+            }
+            AggregatedOperation::PublishProofBlocksOnchain(_) => {
+                Some(U256::from(Self::BASE_PROOF_BLOCKS_TX_COST))
+            }
+            AggregatedOperation::CreateProofBlocks(_) => None,
+        }
+    }
+
     /// Increases the value by 30%.
     fn scale_up(value: U256) -> U256 {
         value * U256::from(130) / U256::from(100)
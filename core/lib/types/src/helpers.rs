@@ -131,6 +131,76 @@ pub fn closest_greater_or_eq_packable_token_amount(amount: &BigUint) -> BigUint
     unpack_token_amount(&fee_packed).expect("token amount repacking")
 }
 
+/// Returns the maximum amount that can be packed with the given bit widths. Amounts are limited to `u128`,
+/// so the result is the largest packable amount fitting into it.
+fn max_packable_amount(exponent_bit_width: usize, mantissa_bit_width: usize) -> BigUint {
+    let bound = BigUint::from(u128::MAX);
+    let max_mantissa = BigUint::from((1u128 << mantissa_bit_width) - 1);
+    (0..(1u32 << exponent_bit_width))
+        .map(|exponent| {
+            let base = BigUint::from(10u32).pow(exponent);
+            (&bound / &base).min(max_mantissa.clone()) * base
+        })
+        .max()
+        .expect("exponent range is not empty")
+}
+
+/// Returns the maximum token amount that can be packed.
+pub fn max_packable_token_amount() -> BigUint {
+    max_packable_amount(
+        params::AMOUNT_EXPONENT_BIT_WIDTH,
+        params::AMOUNT_MANTISSA_BIT_WIDTH,
+    )
+}
+
+/// Returns the maximum fee amount that can be packed.
+pub fn max_packable_fee_amount() -> BigUint {
+    max_packable_amount(
+        params::FEE_EXPONENT_BIT_WIDTH,
+        params::FEE_MANTISSA_BIT_WIDTH,
+    )
+}
+
+fn nearest_packable_amount(
+    amount: &BigUint,
+    max_packable: BigUint,
+    round_down: fn(&BigUint) -> BigUint,
+    round_up: fn(&BigUint) -> BigUint,
+) -> BigUint {
+    if *amount >= max_packable {
+        return max_packable;
+    }
+    let lower = round_down(amount);
+    let upper = round_up(amount);
+    if &upper - amount < amount - &lower {
+        upper
+    } else {
+        lower
+    }
+}
+
+/// Returns the packable token amount nearest to the provided one. If both neighbours are equally close,
+/// the lower one is returned. Amounts above the maximum packable one are mapped to the maximum.
+pub fn nearest_packable_token_amount(amount: &BigUint) -> BigUint {
+    nearest_packable_amount(
+        amount,
+        max_packable_token_amount(),
+        closest_packable_token_amount,
+        closest_greater_or_eq_packable_token_amount,
+    )
+}
+
+/// Returns the packable fee amount nearest to the provided one. If both neighbours are equally close,
+/// the lower one is returned. Amounts above the maximum packable one are mapped to the maximum.
+pub fn nearest_packable_fee_amount(amount: &BigUint) -> BigUint {
+    nearest_packable_amount(
+        amount,
+        max_packable_fee_amount(),
+        closest_packable_fee_amount,
+        closest_greater_or_eq_packable_fee_amount,
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -223,6 +293,81 @@ mod test {
         );
     }
 
+    #[test]
+    fn max_packable_amounts() {
+        let max_fee = BigUint::from(2047u32) * BigUint::from(10u32).pow(31);
+        assert_eq!(max_packable_fee_amount(), max_fee);
+        assert!(is_fee_amount_packable(&max_fee));
+
+        // The amount exponent allows values above `u128`, so the maximum is truncated.
+        let max_token = BigUint::from(34_028_236_692u64) * BigUint::from(10u32).pow(28);
+        assert_eq!(max_packable_token_amount(), max_token);
+        assert!(is_token_amount_packable(&max_token));
+        assert!(max_token <= BigUint::from(u128::MAX));
+    }
+
+    #[test]
+    fn nearest_packable_amounts() {
+        let fee_cases: &[(u64, u64)] = &[
+            (0, 0),
+            (2047, 2047),
+            // The first unpackable fee.
+            (2048, 2047),
+            (2049, 2050),
+            (2051, 2050),
+            (20475, 20470),
+            // Both neighbours are equally close, the lower one is chosen.
+            (20485, 20470),
+        ];
+        for &(fee, nearest) in fee_cases {
+            let nearest = BigUint::from(nearest);
+            assert_eq!(nearest_packable_fee_amount(&BigUint::from(fee)), nearest);
+            assert!(is_fee_amount_packable(&nearest));
+        }
+
+        let max_fee = max_packable_fee_amount();
+        assert_eq!(
+            nearest_packable_fee_amount(&(&max_fee - BigUint::from(1u32))),
+            max_fee
+        );
+        assert_eq!(
+            nearest_packable_fee_amount(&(&max_fee + BigUint::from(1u32))),
+            max_fee
+        );
+        assert_eq!(
+            nearest_packable_fee_amount(&BigUint::from(u128::MAX)),
+            max_fee
+        );
+
+        let max_mantissa = (1u64 << params::AMOUNT_MANTISSA_BIT_WIDTH) - 1;
+        assert_eq!(
+            nearest_packable_token_amount(&BigUint::from(max_mantissa)),
+            BigUint::from(max_mantissa)
+        );
+        assert_eq!(
+            nearest_packable_token_amount(&BigUint::from(max_mantissa + 1)),
+            BigUint::from(max_mantissa)
+        );
+        assert_eq!(
+            nearest_packable_token_amount(&BigUint::from(max_mantissa + 3)),
+            BigUint::from(max_mantissa + 3)
+        );
+
+        let max_token = max_packable_token_amount();
+        assert_eq!(
+            nearest_packable_token_amount(&(&max_token - BigUint::from(1u32))),
+            max_token
+        );
+        assert_eq!(
+            nearest_packable_token_amount(&BigUint::from(u128::MAX)),
+            max_token
+        );
+        assert_eq!(
+            nearest_packable_token_amount(&(BigUint::from(u128::MAX) * BigUint::from(2u32))),
+            max_token
+        );
+    }
+
     #[test]
     fn token_like_serialization() {
         #[derive(Debug, Serialize, Deserialize, PartialEq)]
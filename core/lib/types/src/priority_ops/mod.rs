@@ -485,11 +485,27 @@ impl PriorityOp {
     }
 
     pub fn tx_hash(&self) -> TxHash {
+        Self::compute_tx_hash(
+            self.eth_hash.as_bytes(),
+            self.eth_block,
+            self.eth_block_index,
+            self.serial_id,
+        )
+    }
+
+    /// Computes the zkSync hash of the priority operation from the Ethereum transaction
+    /// it was emitted by, e.g. to restore the hash of the stored operation.
+    pub fn compute_tx_hash(
+        eth_hash: &[u8],
+        eth_block: u64,
+        eth_block_index: Option<u64>,
+        serial_id: SerialId,
+    ) -> TxHash {
         let mut bytes = Vec::with_capacity(56);
-        bytes.extend_from_slice(self.eth_hash.as_bytes());
-        bytes.extend_from_slice(&self.eth_block.to_be_bytes());
-        bytes.extend_from_slice(&self.eth_block_index.unwrap_or(0).to_be_bytes());
-        bytes.extend_from_slice(&self.serial_id.to_be_bytes());
+        bytes.extend_from_slice(eth_hash);
+        bytes.extend_from_slice(&eth_block.to_be_bytes());
+        bytes.extend_from_slice(&eth_block_index.unwrap_or(0).to_be_bytes());
+        bytes.extend_from_slice(&serial_id.to_be_bytes());
 
         let hash = sha256(&bytes);
         let mut out = [0u8; 32];
@@ -14,8 +14,9 @@ use zksync_utils::{format_units, BigUintSerdeAsRadix10Str};
 
 use super::{PackedEthSignature, TimeRange, TxSignature, VerifiedSignatureCache};
 use crate::tx::error::{
-    ChangePubkeySignedDataError, FEE_AMOUNT_IS_NOT_PACKABLE, INVALID_AUTH_DATA, WRONG_ACCOUNT_ID,
-    WRONG_FEE_ERROR, WRONG_SIGNATURE, WRONG_TIME_RANGE, WRONG_TOKEN_FOR_PAYING_FEE,
+    ChangePubkeySignedDataError, Create2AddressMismatch, FEE_AMOUNT_IS_NOT_PACKABLE,
+    INVALID_AUTH_DATA, WRONG_ACCOUNT_ID, WRONG_FEE_ERROR, WRONG_SIGNATURE, WRONG_TIME_RANGE,
+    WRONG_TOKEN_FOR_PAYING_FEE,
 };
 
 use crate::{
@@ -76,6 +77,25 @@ impl ChangePubKeyCREATE2Data {
         bytes.extend_from_slice(self.code_hash.as_bytes());
         Address::from_slice(&bytes.keccak256()[12..])
     }
+
+    /// Checks that `account` is the address the factory deploys the account to for the given
+    /// public key hash: the salt is derived from `salt_arg` and the public key hash, so the data
+    /// can't be reused to set another key.
+    pub fn check_address(
+        &self,
+        pubkey_hash: &PubKeyHash,
+        account: Address,
+    ) -> Result<(), Create2AddressMismatch> {
+        let derived = self.get_address(pubkey_hash);
+        if derived != account {
+            return Err(Create2AddressMismatch {
+                factory: self.creator_address,
+                derived,
+                account,
+            });
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -404,10 +424,9 @@ impl ChangePubKey {
                     });
                     recovered_address == Some(self.account)
                 }
-                ChangePubKeyEthAuthData::CREATE2(create2_data) => {
-                    let create2_address = create2_data.get_address(&self.new_pk_hash);
-                    create2_address == self.account
-                }
+                ChangePubKeyEthAuthData::CREATE2(create2_data) => create2_data
+                    .check_address(&self.new_pk_hash, self.account)
+                    .is_ok(),
                 ChangePubKeyEthAuthData::EIP712(ChangePubKeyEIP712Data {
                     eth_signature, ..
                 }) => {
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use zksync_basic_types::Address;
 
 use crate::tx::{
     change_pubkey, close, forced_exit, mint_nft, swap, transfer, withdraw, withdraw_nft,
@@ -10,6 +11,16 @@ pub enum ChangePubkeySignedDataError {
     SignedMessageLengthMismatch { actual: usize, expected: usize },
 }
 
+/// The account address of the `ChangePubKey` is not the one the CREATE2 factory would deploy
+/// the account to for the new public key hash.
+#[derive(Debug, Error, PartialEq)]
+#[error("Account address {account:?} does not match the address {derived:?} derived for the CREATE2 factory {factory:?}")]
+pub struct Create2AddressMismatch {
+    pub factory: Address,
+    pub derived: Address,
+    pub account: Address,
+}
+
 #[derive(Error, Debug, PartialEq)]
 #[error("Close operations are disabled")]
 pub struct CloseOperationsDisabled();
@@ -13,6 +13,7 @@ mod withdraw_nft;
 mod zksync_tx;
 
 pub mod error;
+pub mod preconditions;
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,152 @@
+//! Conditions the current state must satisfy for a transaction to be executed.
+//!
+//! Unlike `check_correctness` methods of the transactions, these checks depend on
+//! the state of the initiator account and on the current time, so the transaction
+//! that fails them now may become executable later (or never).
+
+use std::collections::BTreeMap;
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zksync_utils::BigUintSerdeAsRadix10Str;
+
+use super::{TimeRange, ZkSyncTx};
+use crate::{Account, AccountId, Nonce, PubKeyHash, TokenId};
+
+/// Precondition of the transaction execution that doesn't hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Error)]
+#[serde(tag = "condition", rename_all = "camelCase")]
+pub enum TxPreconditionFailure {
+    #[error("Account {account_id} does not exist")]
+    #[serde(rename_all = "camelCase")]
+    AccountNotFound { account_id: AccountId },
+    #[error("Nonce is too low: expected {expected}, got {actual}")]
+    NonceTooLow { expected: Nonce, actual: Nonce },
+    #[error("Nonce is too high: expected {expected}, got {actual}")]
+    NonceTooHigh { expected: Nonce, actual: Nonce },
+    #[error("Not enough balance of token {token}: needed {needed}, available {available}")]
+    InsufficientBalance {
+        token: TokenId,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        needed: BigUint,
+        #[serde(with = "BigUintSerdeAsRadix10Str")]
+        available: BigUint,
+    },
+    #[error("Transaction is not valid until {valid_from}, current time is {now}")]
+    #[serde(rename_all = "camelCase")]
+    NotYetValid { valid_from: u64, now: u64 },
+    #[error("Transaction has expired at {valid_until}, current time is {now}")]
+    #[serde(rename_all = "camelCase")]
+    Expired { valid_until: u64, now: u64 },
+    #[error("Signing key of the account is not set")]
+    SigningKeyNotSet,
+    #[error("Transaction is signed with a key different from the account signing key")]
+    SigningKeyMismatch,
+}
+
+/// Checks whether the transaction with the given time range can be executed at `timestamp`.
+pub fn check_time_range(time_range: &TimeRange, timestamp: u64) -> Option<TxPreconditionFailure> {
+    if timestamp < time_range.valid_from {
+        Some(TxPreconditionFailure::NotYetValid {
+            valid_from: time_range.valid_from,
+            now: timestamp,
+        })
+    } else if timestamp > time_range.valid_until {
+        Some(TxPreconditionFailure::Expired {
+            valid_until: time_range.valid_until,
+            now: timestamp,
+        })
+    } else {
+        None
+    }
+}
+
+/// Returns the balances the initiator account must have to execute the transaction,
+/// i.e. the amounts the transaction takes from the initiator account.
+pub fn required_balances(tx: &ZkSyncTx) -> BTreeMap<TokenId, BigUint> {
+    let mut balances = BTreeMap::new();
+    let mut require = |token: TokenId, amount: &BigUint| {
+        *balances.entry(token).or_insert_with(BigUint::default) += amount;
+    };
+
+    match tx {
+        ZkSyncTx::Transfer(tx) => {
+            require(tx.token, &tx.amount);
+            require(tx.token, &tx.fee);
+        }
+        ZkSyncTx::Withdraw(tx) => {
+            require(tx.token, &tx.amount);
+            require(tx.token, &tx.fee);
+        }
+        ZkSyncTx::ForcedExit(tx) => require(tx.token, &tx.fee),
+        ZkSyncTx::ChangePubKey(tx) => require(tx.fee_token, &tx.fee),
+        ZkSyncTx::MintNFT(tx) => require(tx.fee_token, &tx.fee),
+        ZkSyncTx::WithdrawNFT(tx) => {
+            require(tx.token, &BigUint::from(1u32));
+            require(tx.fee_token, &tx.fee);
+        }
+        // Amounts of the orders are paid by the order accounts.
+        ZkSyncTx::Swap(tx) => require(tx.fee_token, &tx.fee),
+        ZkSyncTx::Close(_) => {}
+    }
+    balances.retain(|_, amount| *amount != BigUint::default());
+    balances
+}
+
+/// Checks the transaction against the state of its initiator account at `timestamp`.
+///
+/// All the failed conditions are returned, so the result explains every reason that
+/// currently prevents the transaction from being executed.
+pub fn check_tx_preconditions(
+    tx: &ZkSyncTx,
+    account_id: AccountId,
+    account: Option<&Account>,
+    timestamp: u64,
+) -> Vec<TxPreconditionFailure> {
+    let mut failures = Vec::new();
+    failures.extend(check_time_range(&tx.time_range(), timestamp));
+
+    let account = match account {
+        Some(account) => account,
+        None => {
+            failures.push(TxPreconditionFailure::AccountNotFound { account_id });
+            return failures;
+        }
+    };
+
+    let nonce = tx.nonce();
+    if nonce < account.nonce {
+        failures.push(TxPreconditionFailure::NonceTooLow {
+            expected: account.nonce,
+            actual: nonce,
+        });
+    } else if nonce > account.nonce {
+        failures.push(TxPreconditionFailure::NonceTooHigh {
+            expected: account.nonce,
+            actual: nonce,
+        });
+    }
+
+    for (token, needed) in required_balances(tx) {
+        let available = account.get_balance(token);
+        if available < needed {
+            failures.push(TxPreconditionFailure::InsufficientBalance {
+                token,
+                needed,
+                available,
+            });
+        }
+    }
+
+    // `ChangePubKey` is the transaction that sets the signing key.
+    if !matches!(tx, ZkSyncTx::ChangePubKey(_)) {
+        if account.pub_key_hash == PubKeyHash::default() {
+            failures.push(TxPreconditionFailure::SigningKeyNotSet);
+        } else if PubKeyHash::from_pubkey(&tx.signature().pub_key.0) != account.pub_key_hash {
+            failures.push(TxPreconditionFailure::SigningKeyMismatch);
+        }
+    }
+
+    failures
+}
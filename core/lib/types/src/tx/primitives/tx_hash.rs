@@ -32,9 +32,15 @@ impl TxHash {
         }
     }
 
+    /// Returns the hash of the batch: SHA-256 of the [batch hash preimage](TxHash::batch_hash_preimage).
     pub fn batch_hash(tx_hashes: &[TxHash]) -> TxHash {
-        let bytes: Vec<u8> = tx_hashes.iter().flat_map(AsRef::as_ref).cloned().collect();
-        TxHash::from_slice(&sha256(&bytes)).unwrap()
+        TxHash::from_slice(&sha256(&Self::batch_hash_preimage(tx_hashes))).unwrap()
+    }
+
+    /// Returns the data hashed to get the batch hash: concatenation of the 32-byte hashes
+    /// of the batch transactions in the order of the batch.
+    pub fn batch_hash_preimage(tx_hashes: &[TxHash]) -> Vec<u8> {
+        tx_hashes.iter().flat_map(AsRef::as_ref).cloned().collect()
     }
 
     pub fn to_string_without_prefix(&self) -> String {
@@ -586,3 +586,245 @@ fn test_check_signature() {
 
     assert_eq!(hex::encode(signature), "4e3298ac8cc13868dbbc94ad6fb41085ffe05b3c2eee22f88b05e69b7a5126aea723d7a3e7282ef5a32d9479c9c8dde52b3e3c462dd445dcd8158ebb6edb6000");
 }
+
+/// Checks that the addresses the funds are moved from or to are reported for every transaction type.
+#[test]
+fn test_tx_addresses() {
+    let from = Address::repeat_byte(0x01);
+    let to = Address::repeat_byte(0x02);
+    let second_recipient = Address::repeat_byte(0x03);
+    let order = |recipient_address| Order {
+        account_id: AccountId(1),
+        recipient_address,
+        nonce: Nonce(0),
+        token_buy: TokenId(1),
+        token_sell: TokenId(2),
+        price: (BigUint::from(1u32), BigUint::from(1u32)),
+        amount: BigUint::from(1u32),
+        time_range: Default::default(),
+        signature: Default::default(),
+    };
+
+    let cases = vec![
+        (
+            ZkSyncTx::from(Transfer::new(
+                AccountId(1),
+                from,
+                to,
+                TokenId(0),
+                BigUint::from(1u32),
+                BigUint::from(1u32),
+                Nonce(0),
+                Default::default(),
+                None,
+            )),
+            vec![from, to],
+        ),
+        (
+            ZkSyncTx::from(Withdraw::new(
+                AccountId(1),
+                from,
+                to,
+                TokenId(0),
+                BigUint::from(1u32),
+                BigUint::from(1u32),
+                Nonce(0),
+                Default::default(),
+                None,
+            )),
+            vec![from, to],
+        ),
+        (
+            ZkSyncTx::from(Close {
+                account: from,
+                nonce: Nonce(0),
+                signature: Default::default(),
+                time_range: Default::default(),
+            }),
+            vec![from],
+        ),
+        (
+            ZkSyncTx::from(ChangePubKey::new(
+                AccountId(1),
+                from,
+                Default::default(),
+                TokenId(0),
+                BigUint::from(1u32),
+                Nonce(0),
+                Default::default(),
+                None,
+                None,
+                None,
+            )),
+            vec![from],
+        ),
+        (
+            ZkSyncTx::from(ForcedExit::new(
+                AccountId(1),
+                to,
+                TokenId(0),
+                BigUint::from(1u32),
+                Nonce(0),
+                Default::default(),
+                None,
+            )),
+            vec![to],
+        ),
+        (
+            ZkSyncTx::from(Swap::new(
+                AccountId(1),
+                from,
+                Nonce(0),
+                (order(to), order(second_recipient)),
+                (BigUint::from(1u32), BigUint::from(1u32)),
+                BigUint::from(1u32),
+                TokenId(0),
+                None,
+            )),
+            vec![from, to, second_recipient],
+        ),
+        (
+            ZkSyncTx::from(MintNFT::new(
+                AccountId(1),
+                from,
+                H256::zero(),
+                to,
+                BigUint::from(1u32),
+                TokenId(0),
+                Nonce(0),
+                None,
+            )),
+            vec![from, to],
+        ),
+        (
+            ZkSyncTx::from(WithdrawNFT::new(
+                AccountId(1),
+                from,
+                to,
+                TokenId(70000),
+                TokenId(0),
+                BigUint::from(1u32),
+                Nonce(0),
+                Default::default(),
+                None,
+            )),
+            vec![from, to],
+        ),
+    ];
+
+    for (tx, expected) in cases {
+        assert_eq!(tx.addresses(), expected, "{}", tx.variance_name());
+    }
+}
+
+#[test]
+fn test_tx_preconditions() {
+    use super::preconditions::{check_tx_preconditions, TxPreconditionFailure};
+    use crate::Account;
+
+    let key = gen_pk_and_msg().0;
+    let from = Address::repeat_byte(1);
+    let transfer = ZkSyncTx::from(
+        Transfer::new_signed(
+            AccountId(1),
+            from,
+            Address::repeat_byte(2),
+            TokenId(0),
+            BigUint::from(100u32),
+            BigUint::from(10u32),
+            Nonce(5),
+            TimeRange::new(100, 200),
+            &key,
+        )
+        .expect("failed to sign transfer"),
+    );
+
+    assert_eq!(
+        check_tx_preconditions(&transfer, AccountId(1), None, 150),
+        vec![TxPreconditionFailure::AccountNotFound {
+            account_id: AccountId(1)
+        }]
+    );
+
+    let mut account = Account::default_with_address(&from);
+    account.nonce = Nonce(3);
+    account.set_balance(TokenId(0), BigUint::from(50u32));
+    assert_eq!(
+        check_tx_preconditions(&transfer, AccountId(1), Some(&account), 250),
+        vec![
+            TxPreconditionFailure::Expired {
+                valid_until: 200,
+                now: 250
+            },
+            TxPreconditionFailure::NonceTooHigh {
+                expected: Nonce(3),
+                actual: Nonce(5)
+            },
+            TxPreconditionFailure::InsufficientBalance {
+                token: TokenId(0),
+                needed: BigUint::from(110u32),
+                available: BigUint::from(50u32)
+            },
+            TxPreconditionFailure::SigningKeyNotSet,
+        ]
+    );
+
+    account.pub_key_hash = PubKeyHash::from_privkey(&gen_pk_and_msg().0);
+    account.nonce = Nonce(5);
+    account.set_balance(TokenId(0), BigUint::from(110u32));
+    assert!(check_tx_preconditions(&transfer, AccountId(1), Some(&account), 150).is_empty());
+
+    account.pub_key_hash = PubKeyHash::default();
+    account.pub_key_hash.data[0] = 1;
+    assert_eq!(
+        check_tx_preconditions(&transfer, AccountId(1), Some(&account), 50),
+        vec![
+            TxPreconditionFailure::NotYetValid {
+                valid_from: 100,
+                now: 50
+            },
+            TxPreconditionFailure::SigningKeyMismatch,
+        ]
+    );
+}
+
+#[test]
+fn test_create2_address_derivation() {
+    let create2_data = ChangePubKeyCREATE2Data {
+        creator_address: Address::repeat_byte(0x11),
+        salt_arg: H256::repeat_byte(0x22),
+        code_hash: H256::repeat_byte(0x33),
+    };
+    let pubkey_hash = PubKeyHash { data: [0x44; 20] };
+    let account: Address = "32ab0551fee069c7d5c361f5a9965e2aa24bb0cd".parse().unwrap();
+
+    assert_eq!(create2_data.get_address(&pubkey_hash), account);
+    assert_eq!(create2_data.check_address(&pubkey_hash, account), Ok(()));
+
+    // The salt incorporates the public key hash, so the data can't be used to set another key.
+    let other_pubkey_hash = PubKeyHash { data: [0x45; 20] };
+    let error = create2_data
+        .check_address(&other_pubkey_hash, account)
+        .unwrap_err();
+    assert_eq!(error.factory, create2_data.creator_address);
+    assert_eq!(error.account, account);
+    assert_eq!(error.derived, create2_data.get_address(&other_pubkey_hash));
+
+    // Any change of the factory, the salt or the code hash changes the address.
+    for create2_data in [
+        ChangePubKeyCREATE2Data {
+            creator_address: Address::repeat_byte(0x12),
+            ..create2_data.clone()
+        },
+        ChangePubKeyCREATE2Data {
+            salt_arg: H256::repeat_byte(0x23),
+            ..create2_data.clone()
+        },
+        ChangePubKeyCREATE2Data {
+            code_hash: H256::repeat_byte(0x34),
+            ..create2_data.clone()
+        },
+    ] {
+        assert!(create2_data.check_address(&pubkey_hash, account).is_err());
+    }
+}
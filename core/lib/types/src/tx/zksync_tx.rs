@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 use zksync_basic_types::{AccountId, Address};
-use zksync_crypto::params::ETH_TOKEN_ID;
+use zksync_crypto::params::{ETH_TOKEN_ID, MIN_NFT_TOKEN_ID};
 
 use crate::{
     operations::{ChangePubKeyOp, MintNFTOp},
@@ -168,6 +168,39 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns the L2 account that receives the fungible tokens moved by the transaction.
+    ///
+    /// Only transfers credit another L2 account: withdrawals send the funds to L1,
+    /// and the NFTs and the swapped tokens are not taken into account.
+    pub fn l2_recipient(&self) -> Option<Address> {
+        match self {
+            ZkSyncTx::Transfer(tx) => Some(tx.to),
+            _ => None,
+        }
+    }
+
+    /// Returns all the addresses the transaction moves funds from or to,
+    /// including the recipients and the withdrawal targets.
+    ///
+    /// Accounts that are referenced only by their IDs (`ForcedExit` initiator
+    /// and the `Swap` order accounts) are not included.
+    pub fn addresses(&self) -> Vec<Address> {
+        match self {
+            ZkSyncTx::Transfer(tx) => vec![tx.from, tx.to],
+            ZkSyncTx::Withdraw(tx) => vec![tx.from, tx.to],
+            ZkSyncTx::Close(tx) => vec![tx.account],
+            ZkSyncTx::ChangePubKey(tx) => vec![tx.account],
+            ZkSyncTx::ForcedExit(tx) => vec![tx.target],
+            ZkSyncTx::Swap(tx) => vec![
+                tx.submitter_address,
+                tx.orders.0.recipient_address,
+                tx.orders.1.recipient_address,
+            ],
+            ZkSyncTx::MintNFT(tx) => vec![tx.creator_address, tx.recipient],
+            ZkSyncTx::WithdrawNFT(tx) => vec![tx.from, tx.to],
+        }
+    }
+
     pub fn tokens(&self) -> Vec<TokenId> {
         let mut tokens = match self {
             ZkSyncTx::Transfer(tx) => vec![tx.token],
@@ -366,6 +399,33 @@ impl ZkSyncTx {
         }
     }
 
+    /// Returns the message the zkSync signature of the transaction was created for,
+    /// or `None` if the signature is not valid.
+    ///
+    /// Legacy transactions are signed without the transaction version, so the message
+    /// is chosen the same way as in the `verify_signature` methods of the transactions.
+    pub fn signed_message(&self) -> Option<Vec<u8>> {
+        let signature = self.signature();
+        let legacy_message = match self {
+            ZkSyncTx::Transfer(tx) if tx.token.0 < MIN_NFT_TOKEN_ID => Some(tx.get_old_bytes()),
+            ZkSyncTx::Withdraw(tx) if tx.token.0 < MIN_NFT_TOKEN_ID => Some(tx.get_old_bytes()),
+            ZkSyncTx::ChangePubKey(tx) => Some(tx.get_old_bytes()),
+            ZkSyncTx::ForcedExit(tx) => Some(tx.get_old_bytes()),
+            _ => None,
+        };
+        if let Some(message) = legacy_message {
+            if signature.verify_musig(&message).is_some() {
+                return Some(message);
+            }
+        }
+
+        let message = match self {
+            ZkSyncTx::Swap(tx) => tx.get_sign_bytes(),
+            _ => self.get_bytes(),
+        };
+        signature.verify_musig(&message).map(|_| message)
+    }
+
     /// Returns the minimum amount of block chunks required for this operation.
     /// Maximum amount of chunks in block is a part of  the server and provers configuration,
     /// and this value determines the block capacity.
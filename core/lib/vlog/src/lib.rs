@@ -11,17 +11,29 @@
 //! Integration with sentry for catching errors and react on them immediately
 //! https://docs.sentry.io/platforms/rust/
 //!
+//! Spans can be exported to an OpenTelemetry collector via OTLP, see `init_with_otlp_exporter`.
+//!
 
 use chrono::Duration;
 use std::{borrow::Cow, str::FromStr};
 
+use opentelemetry::{
+    sdk::{
+        trace::{self, Tracer},
+        Resource,
+    },
+    KeyValue,
+};
 pub use sentry;
 use sentry::protocol::Event;
 use sentry::{types::Dsn, ClientInitGuard, ClientOptions};
 
 pub use tracing as __tracing;
+use tracing::Subscriber;
 pub use tracing::{debug, info, log, trace};
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::{layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt};
 
 #[macro_export]
 macro_rules! warn {
@@ -51,6 +63,16 @@ macro_rules! error {
 pub struct VlogGuard {
     _sentry_guard: Option<ClientInitGuard>,
     _logger_guard: WorkerGuard,
+    _otlp_guard: Option<OtlpGuard>,
+}
+
+/// Flushes the spans that are not exported yet when dropped.
+struct OtlpGuard;
+
+impl Drop for OtlpGuard {
+    fn drop(&mut self) {
+        opentelemetry::global::shutdown_tracer_provider();
+    }
 }
 
 fn get_sentry_url() -> Option<Dsn> {
@@ -71,6 +93,14 @@ fn get_sentry_url() -> Option<Dsn> {
 /// https://docs.sentry.io/platforms/rust/#configure
 /// https://docs.rs/tracing-appender/0.2.2/tracing_appender/non_blocking/index.html
 pub fn init() -> VlogGuard {
+    init_with_otlp_exporter(None, "")
+}
+
+/// Same as `init`, but if the OTLP collector endpoint is provided, the tracing spans are also
+/// exported to it under the given service name.
+///
+/// Must be called from within the Tokio runtime, since the spans are exported in the background.
+pub fn init_with_otlp_exporter(otlp_endpoint: Option<&str>, service_name: &str) -> VlogGuard {
     let log_format = std::env::var("MISC_LOG_FORMAT").unwrap_or_else(|_| "plain".to_string());
     let (non_blocking, _logger_guard) = tracing_appender::non_blocking(std::io::stdout());
     match log_format.as_str() {
@@ -78,6 +108,8 @@ pub fn init() -> VlogGuard {
             tracing_subscriber::fmt::Subscriber::builder()
                 .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
                 .with_writer(non_blocking)
+                .finish()
+                .with(otlp_endpoint.map(|endpoint| otlp_layer(endpoint, service_name)))
                 .init();
         }
         "json" => {
@@ -87,6 +119,8 @@ pub fn init() -> VlogGuard {
                 .with_writer(non_blocking)
                 .with_timer(timer)
                 .json()
+                .finish()
+                .with(otlp_endpoint.map(|endpoint| otlp_layer(endpoint, service_name)))
                 .init();
         }
         _ => panic!("MISC_LOG_FORMAT has an unexpected value {}", log_format),
@@ -110,9 +144,32 @@ pub fn init() -> VlogGuard {
     VlogGuard {
         _sentry_guard,
         _logger_guard,
+        _otlp_guard: otlp_endpoint.map(|_| OtlpGuard),
     }
 }
 
+fn otlp_layer<S>(endpoint: &str, service_name: &str) -> OpenTelemetryLayer<S, Tracer>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint),
+        )
+        .with_trace_config(
+            trace::config().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                service_name.to_owned(),
+            )])),
+        )
+        .install_batch(opentelemetry::runtime::Tokio)
+        .expect("Failed to install the OTLP exporter");
+    tracing_opentelemetry::layer().with_tracer(tracer)
+}
+
 struct AddIntervalToFingerprintIntegration {
     panic_interval: Duration,
     error_interval: Duration,
@@ -32,6 +32,9 @@ export async function getDefaultRestProvider(
     }
 }
 
+// Code of the error reporting the resource requested by its identifier as missing.
+const NOT_FOUND_ERROR_CODE = 223;
+
 export interface Request {
     network: types.Network;
     apiVersion: 'v02';
@@ -85,6 +88,9 @@ export class RestProvider extends SyncProvider {
     parseResponse<T>(response: Response<T>): T {
         if (response.status === 'success') {
             return response.result;
+        } else if (response.error.code === NOT_FOUND_ERROR_CODE) {
+            // Resources requested by their identifiers and not found are returned as `null`.
+            return null;
         } else {
             throw new RESTError(
                 `zkSync API response error: errorType: ${response.error.errorType};` +
@@ -67,7 +67,7 @@ export interface NFTInfo {
     withdrawnFactory?: Address;
 }
 
-export type EthAccountType = 'Owned' | 'CREATE2' | 'No2FA';
+export type EthAccountType = 'Owned' | 'CREATE2' | 'No2FA' | 'NoKey';
 
 export interface Depositing {
     balances: {
@@ -558,6 +558,22 @@ export interface WithdrawNFTData {
     ethTxHash?: string;
 }
 
+export interface SwapOrderData {
+    accountId: number;
+    recipient: Address;
+    tokenSell: number;
+    tokenBuy: number;
+    ratio: Ratio;
+    amount: BigNumberish;
+    soldAmount: BigNumberish;
+    boughtAmount: BigNumberish;
+    isFullFill: boolean;
+}
+
+export interface SwapData extends Swap {
+    decodedOrders: [SwapOrderData, SwapOrderData];
+}
+
 export interface ApiDeposit {
     type: 'Deposit';
     from: Address;
@@ -589,7 +605,7 @@ export type L2TxData =
     | CloseAccount
     | MintNFT
     | WithdrawNFTData
-    | Swap;
+    | SwapData;
 
 export type TransactionData = L2TxData | ApiDeposit | ApiFullExit;
 
@@ -611,6 +627,7 @@ export interface ApiSignedTx {
 export interface ApiBatchStatus {
     updatedAt: string;
     lastState: L2TxStatus;
+    failReason?: string;
 }
 
 export interface ApiBatchData {